@@ -24,9 +24,7 @@ use vulkano::buffer::CpuAccessibleBuffer;
 use vulkano::command_buffer::AutoCommandBufferBuilder;
 use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
 use vulkano::device::Device;
-use vulkano::device::DeviceExtensions;
 use vulkano::instance::Instance;
-use vulkano::instance::InstanceExtensions;
 use vulkano::pipeline::ComputePipeline;
 use vulkano::sync::now;
 use vulkano::sync::GpuFuture;
@@ -34,30 +32,20 @@ use vulkano::sync::GpuFuture;
 use std::sync::Arc;
 
 fn main() {
-    // As with other examples, the first step is to create an instance.
-    let instance = Instance::new(None, &InstanceExtensions::none(), None)
-        .expect("failed to create Vulkan instance");
+    // As with other examples, the first step is to create an instance. Since we don't draw
+    // anything, `new_compute` doesn't enable any surface extension.
+    let instance = Instance::new_compute(None).expect("failed to create Vulkan instance");
 
     // Choose which physical device to use.
     let physical = vulkano::instance::PhysicalDevice::enumerate(&instance)
         .next().expect("no device available");
 
-    // Choose the queue of the physical device which is going to run our compute operation.
+    // Now initializing the device. `new_compute` creates a single queue from a queue family that
+    // supports compute operations, which is going to run our compute operation.
     //
     // The Vulkan specs guarantee that a compliant implementation must provide at least one queue
     // that supports compute operations.
-    let queue = physical.queue_families().find(|&q| q.supports_compute()).unwrap();
-
-    // Now initializing the device.
-    let (device, mut queues) = {
-        Device::new(physical, physical.supported_features(), &DeviceExtensions::none(),
-                    [(queue, 0.5)].iter().cloned()).expect("failed to create device")
-    };
-
-    // Since we can request multiple queues, the `queues` variable is in fact an iterator. In this
-    // example we use only one queue, so we just retreive the first and only element of the
-    // iterator and throw it away.
-    let queue = queues.next().unwrap();
+    let (device, queue) = Device::new_compute(physical).expect("failed to create device");
 
     println!("Device initialized");
 
@@ -202,6 +202,7 @@ fn main() {
                           depth_range: 0.0 .. 1.0,
                       }]),
                       scissors: None,
+                      .. vulkano::command_buffer::DynamicState::none()
                   },
                   vertex_buffer.clone(),
                   set.clone(), ()).unwrap()
@@ -425,6 +425,7 @@ void main() {
                           depth_range: 0.0 .. 1.0,
                       }]),
                       scissors: None,
+                      .. DynamicState::none()
                   },
                   vertex_buffer.clone(), (), ())
             .unwrap()
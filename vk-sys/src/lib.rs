@@ -15,6 +15,7 @@ use std::mem;
 use std::ffi::CStr;
 use std::fmt;
 use std::os::raw::c_char;
+use std::os::raw::c_int;
 use std::os::raw::c_void;
 use std::os::raw::c_ulong;
 use std::os::raw::c_double;
@@ -55,7 +56,11 @@ pub type SwapchainKHR = u64;
 pub type DisplayKHR = u64;
 pub type DisplayModeKHR = u64;
 pub type DebugReportCallbackEXT = u64;
+pub type DebugUtilsMessengerEXT = u64;
 pub type DescriptorUpdateTemplateKHR = u64;
+pub type AccelerationStructureKHR = u64;
+pub type DeferredOperationKHR = u64;
+pub type DeviceAddress = u64;
 
 pub const LOD_CLAMP_NONE: f32 = 1000.0;
 pub const REMAINING_MIP_LEVELS: u32 = 0xffffffff;
@@ -68,10 +73,14 @@ pub const QUEUE_FAMILY_IGNORED: u32 = 0xffffffff;
 pub const SUBPASS_EXTERNAL: u32 = 0xffffffff;
 pub const MAX_PHYSICAL_DEVICE_NAME_SIZE: u32 = 256;
 pub const UUID_SIZE: u32 = 16;
+pub const LUID_SIZE: u32 = 8;
 pub const MAX_MEMORY_TYPES: u32 = 32;
 pub const MAX_MEMORY_HEAPS: u32 = 16;
 pub const MAX_EXTENSION_NAME_SIZE: u32 = 256;
 pub const MAX_DESCRIPTION_SIZE: u32 = 256;
+pub const MAX_DRIVER_NAME_SIZE_KHR: u32 = 256;
+pub const MAX_DRIVER_INFO_SIZE_KHR: u32 = 256;
+pub const MAX_DEVICE_GROUP_SIZE_KHR: usize = 32;
 
 pub type PipelineCacheHeaderVersion = u32;
 pub const PIPELINE_CACHE_HEADER_VERSION_ONE: u32 = 1;
@@ -102,6 +111,7 @@ pub const ERROR_INCOMPATIBLE_DISPLAY_KHR: u32 = -1000003001i32 as u32;
 pub const ERROR_VALIDATION_FAILED_EXT: u32 = -1000011001i32 as u32;
 pub const ERROR_INVALID_SHADER_NV: u32 = -1000012000i32 as u32;
 pub const ERROR_OUT_OF_POOL_MEMORY_KHR: u32 = -1000069000i32 as u32;
+pub const ERROR_INVALID_EXTERNAL_HANDLE_KHR: u32 = -1000072003i32 as u32;
 
 pub type StructureType = u32;
 pub const STRUCTURE_TYPE_APPLICATION_INFO: u32 = 0;
@@ -167,6 +177,7 @@ pub const STRUCTURE_TYPE_WIN32_SURFACE_CREATE_INFO_KHR: u32 = 1000009000;
 #[deprecated(note = "Use STRUCTURE_TYPE_DEBUG_REPORT_CALLBACK_CREATE_INFO_EXT instead")]
 pub const STRUCTURE_TYPE_DEBUG_REPORT_CREATE_INFO_EXT: u32 = 1000011000;
 pub const STRUCTURE_TYPE_DEBUG_REPORT_CALLBACK_CREATE_INFO_EXT: u32 = 1000011000;
+pub const STRUCTURE_TYPE_VALIDATION_FEATURES_EXT: u32 = 1000247000;
 pub const STRUCTURE_TYPE_IOS_SURFACE_CREATE_INFO_MVK: u32 = 1000000000 + (52 * 1000);
 pub const STRUCTURE_TYPE_MACOS_SURFACE_CREATE_INFO_MVK: u32 = 1000000000 + (53 * 1000);
 pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_FEATURES_2_KHR: u32 = 1000059000;
@@ -192,6 +203,100 @@ pub const STRUCTURE_TYPE_SPARSE_IMAGE_MEMORY_REQUIREMENTS_2_KHR: u32 = 100014600
 pub const STRUCTURE_TYPE_DEBUG_MARKER_OBJECT_NAME_INFO_EXT: u32 = 1000022000;
 pub const STRUCTURE_TYPE_DEBUG_MARKER_OBJECT_TAG_INFO_EXT: u32 = 1000022001;
 pub const STRUCTURE_TYPE_DEBUG_MARKER_MARKER_INFO_EXT: u32 = 1000022002;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTENDED_DYNAMIC_STATE_FEATURES_EXT: u32 = 1000267000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_ROBUSTNESS_2_FEATURES_EXT: u32 = 1000286000;
+pub const STRUCTURE_TYPE_CHECKPOINT_DATA_NV: u32 = 1000206000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_TOOL_PROPERTIES_EXT: u32 = 1000245000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_PORTABILITY_SUBSET_FEATURES_KHR: u32 = 1000163000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_PORTABILITY_SUBSET_PROPERTIES_KHR: u32 = 1000163001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_CONSERVATIVE_RASTERIZATION_PROPERTIES_EXT: u32 = 1000101000;
+pub const STRUCTURE_TYPE_PIPELINE_RASTERIZATION_CONSERVATIVE_STATE_CREATE_INFO_EXT: u32 = 1000101001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_LINE_RASTERIZATION_FEATURES_EXT: u32 = 1000259000;
+pub const STRUCTURE_TYPE_PIPELINE_RASTERIZATION_LINE_STATE_CREATE_INFO_EXT: u32 = 1000259001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_LINE_RASTERIZATION_PROPERTIES_EXT: u32 = 1000259002;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_PROPERTIES_EXT: u32 = 1000190000;
+pub const STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_DIVISOR_STATE_CREATE_INFO_EXT: u32 = 1000190001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_FEATURES_EXT: u32 = 1000190002;
+pub const STRUCTURE_TYPE_PIPELINE_CREATION_FEEDBACK_CREATE_INFO_EXT: u32 = 1000192000;
+pub const STRUCTURE_TYPE_PIPELINE_LIBRARY_CREATE_INFO_KHR: u32 = 1000290000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_GRAPHICS_PIPELINE_LIBRARY_FEATURES_EXT: u32 = 1000320000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_GRAPHICS_PIPELINE_LIBRARY_PROPERTIES_EXT: u32 = 1000320001;
+pub const STRUCTURE_TYPE_GRAPHICS_PIPELINE_LIBRARY_CREATE_INFO_EXT: u32 = 1000320002;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_FEATURES_EXT: u32 = 1000328000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_PROPERTIES_EXT: u32 = 1000328001;
+pub const STRUCTURE_TYPE_MEMORY_ALLOCATE_FLAGS_INFO_KHR: u32 = 1000060000;
+pub const STRUCTURE_TYPE_DEVICE_GROUP_RENDER_PASS_BEGIN_INFO_KHR: u32 = 1000060003;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_GROUP_PROPERTIES_KHR: u32 = 1000070000;
+pub const STRUCTURE_TYPE_DEVICE_GROUP_DEVICE_CREATE_INFO_KHR: u32 = 1000070001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTERNAL_IMAGE_FORMAT_INFO: u32 = 1000071000;
+pub const STRUCTURE_TYPE_EXTERNAL_IMAGE_FORMAT_PROPERTIES: u32 = 1000071001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_ID_PROPERTIES: u32 = 1000071004;
+pub const STRUCTURE_TYPE_EXTERNAL_MEMORY_BUFFER_CREATE_INFO: u32 = 1000072000;
+pub const STRUCTURE_TYPE_EXTERNAL_MEMORY_IMAGE_CREATE_INFO: u32 = 1000072001;
+pub const STRUCTURE_TYPE_EXPORT_MEMORY_ALLOCATE_INFO: u32 = 1000072002;
+pub const STRUCTURE_TYPE_MEMORY_GET_WIN32_HANDLE_INFO_KHR: u32 = 1000073003;
+pub const STRUCTURE_TYPE_MEMORY_GET_FD_INFO_KHR: u32 = 1000074002;
+pub const STRUCTURE_TYPE_SAMPLER_YCBCR_CONVERSION_IMAGE_FORMAT_PROPERTIES: u32 = 1000156005;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_BUFFER_DEVICE_ADDRESS_FEATURES_KHR: u32 = 1000257000;
+pub const STRUCTURE_TYPE_BUFFER_DEVICE_ADDRESS_INFO_KHR: u32 = 1000244001;
+pub const STRUCTURE_TYPE_WRITE_DESCRIPTOR_SET_ACCELERATION_STRUCTURE_KHR: u32 = 1000150007;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_BUILD_GEOMETRY_INFO_KHR: u32 = 1000150000;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_DEVICE_ADDRESS_INFO_KHR: u32 = 1000150002;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_AABBS_DATA_KHR: u32 = 1000150003;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_INSTANCES_DATA_KHR: u32 = 1000150004;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_TRIANGLES_DATA_KHR: u32 = 1000150005;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_KHR: u32 = 1000150006;
+pub const STRUCTURE_TYPE_COPY_ACCELERATION_STRUCTURE_INFO_KHR: u32 = 1000150010;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_ACCELERATION_STRUCTURE_FEATURES_KHR: u32 = 1000150013;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_ACCELERATION_STRUCTURE_PROPERTIES_KHR: u32 = 1000150014;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_CREATE_INFO_KHR: u32 = 1000150017;
+pub const STRUCTURE_TYPE_ACCELERATION_STRUCTURE_BUILD_SIZES_INFO_KHR: u32 = 1000150020;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_RAY_TRACING_PIPELINE_FEATURES_KHR: u32 = 1000347000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_RAY_TRACING_PIPELINE_PROPERTIES_KHR: u32 = 1000347001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SUBGROUP_PROPERTIES: u32 = 1000094000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_PROTECTED_MEMORY_FEATURES: u32 = 1000145000;
+pub const STRUCTURE_TYPE_DEVICE_QUEUE_INFO_2: u32 = 1000145003;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_PROPERTIES_EXT: u32 = 1000225000;
+pub const STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_REQUIRED_SUBGROUP_SIZE_CREATE_INFO_EXT: u32 = 1000225001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_FEATURES_EXT: u32 = 1000225002;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_16BIT_STORAGE_FEATURES_KHR: u32 = 1000083000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_8BIT_STORAGE_FEATURES_KHR: u32 = 1000177000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SHADER_FLOAT16_INT8_FEATURES_KHR: u32 = 1000082000;
+pub const STRUCTURE_TYPE_RAY_TRACING_PIPELINE_CREATE_INFO_KHR: u32 = 1000150015;
+pub const STRUCTURE_TYPE_RAY_TRACING_SHADER_GROUP_CREATE_INFO_KHR: u32 = 1000150016;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTI_DRAW_FEATURES_EXT: u32 = 1000392000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTI_DRAW_PROPERTIES_EXT: u32 = 1000392001;
+pub const STRUCTURE_TYPE_COMMAND_BUFFER_INHERITANCE_CONDITIONAL_RENDERING_INFO_EXT: u32 = 1000081000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_CONDITIONAL_RENDERING_FEATURES_EXT: u32 = 1000081001;
+pub const STRUCTURE_TYPE_CONDITIONAL_RENDERING_BEGIN_INFO_EXT: u32 = 1000081002;
+pub const STRUCTURE_TYPE_DEBUG_UTILS_OBJECT_NAME_INFO_EXT: u32 = 1000128000;
+pub const STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT: u32 = 1000128002;
+pub const STRUCTURE_TYPE_DEBUG_UTILS_MESSENGER_CALLBACK_DATA_EXT: u32 = 1000128003;
+pub const STRUCTURE_TYPE_DEBUG_UTILS_MESSENGER_CREATE_INFO_EXT: u32 = 1000128004;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_TRANSFORM_FEEDBACK_FEATURES_EXT: u32 = 1000028000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_TRANSFORM_FEEDBACK_PROPERTIES_EXT: u32 = 1000028001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_TIMELINE_SEMAPHORE_FEATURES_KHR: u32 = 1000207000;
+pub const STRUCTURE_TYPE_SEMAPHORE_TYPE_CREATE_INFO_KHR: u32 = 1000207002;
+pub const STRUCTURE_TYPE_TIMELINE_SEMAPHORE_SUBMIT_INFO_KHR: u32 = 1000207003;
+pub const STRUCTURE_TYPE_SEMAPHORE_WAIT_INFO_KHR: u32 = 1000207004;
+pub const STRUCTURE_TYPE_SEMAPHORE_SIGNAL_INFO_KHR: u32 = 1000207005;
+pub const STRUCTURE_TYPE_EXPORT_SEMAPHORE_CREATE_INFO_KHR: u32 = 1000077000;
+pub const STRUCTURE_TYPE_IMPORT_SEMAPHORE_WIN32_HANDLE_INFO_KHR: u32 = 1000078000;
+pub const STRUCTURE_TYPE_SEMAPHORE_GET_WIN32_HANDLE_INFO_KHR: u32 = 1000078003;
+pub const STRUCTURE_TYPE_IMPORT_SEMAPHORE_FD_INFO_KHR: u32 = 1000079000;
+pub const STRUCTURE_TYPE_SEMAPHORE_GET_FD_INFO_KHR: u32 = 1000079001;
+pub const STRUCTURE_TYPE_MEMORY_BARRIER_2_KHR: u32 = 1000314000;
+pub const STRUCTURE_TYPE_BUFFER_MEMORY_BARRIER_2_KHR: u32 = 1000314001;
+pub const STRUCTURE_TYPE_IMAGE_MEMORY_BARRIER_2_KHR: u32 = 1000314002;
+pub const STRUCTURE_TYPE_DEPENDENCY_INFO_KHR: u32 = 1000314003;
+pub const STRUCTURE_TYPE_SUBMIT_INFO_2_KHR: u32 = 1000314004;
+pub const STRUCTURE_TYPE_SEMAPHORE_SUBMIT_INFO_KHR: u32 = 1000314005;
+pub const STRUCTURE_TYPE_COMMAND_BUFFER_SUBMIT_INFO_KHR: u32 = 1000314006;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_SYNCHRONIZATION_2_FEATURES_KHR: u32 = 1000314007;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_DESCRIPTOR_INDEXING_FEATURES_EXT: u32 = 1000161001;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_DESCRIPTOR_INDEXING_PROPERTIES_EXT: u32 = 1000161002;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_MAINTENANCE_3_PROPERTIES_KHR: u32 = 1000168000;
+pub const STRUCTURE_TYPE_PHYSICAL_DEVICE_DRIVER_PROPERTIES_KHR: u32 = 1000196000;
 
 pub type SystemAllocationScope = u32;
 pub const SYSTEM_ALLOCATION_SCOPE_COMMAND: u32 = 0;
@@ -410,6 +515,7 @@ pub type QueryType = u32;
 pub const QUERY_TYPE_OCCLUSION: u32 = 0;
 pub const QUERY_TYPE_PIPELINE_STATISTICS: u32 = 1;
 pub const QUERY_TYPE_TIMESTAMP: u32 = 2;
+pub const QUERY_TYPE_ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR: u32 = 1000150000;
 
 pub type SharingMode = u32;
 pub const SHARING_MODE_EXCLUSIVE: u32 = 0;
@@ -547,6 +653,43 @@ pub const DYNAMIC_STATE_DEPTH_BOUNDS: u32 = 5;
 pub const DYNAMIC_STATE_STENCIL_COMPARE_MASK: u32 = 6;
 pub const DYNAMIC_STATE_STENCIL_WRITE_MASK: u32 = 7;
 pub const DYNAMIC_STATE_STENCIL_REFERENCE: u32 = 8;
+pub const DYNAMIC_STATE_CULL_MODE_EXT: u32 = 1000267000;
+pub const DYNAMIC_STATE_FRONT_FACE_EXT: u32 = 1000267001;
+pub const DYNAMIC_STATE_PRIMITIVE_TOPOLOGY_EXT: u32 = 1000267002;
+pub const DYNAMIC_STATE_VIEWPORT_WITH_COUNT_EXT: u32 = 1000267003;
+pub const DYNAMIC_STATE_SCISSOR_WITH_COUNT_EXT: u32 = 1000267004;
+pub const DYNAMIC_STATE_VERTEX_INPUT_BINDING_STRIDE_EXT: u32 = 1000267005;
+pub const DYNAMIC_STATE_DEPTH_TEST_ENABLE_EXT: u32 = 1000267006;
+pub const DYNAMIC_STATE_DEPTH_WRITE_ENABLE_EXT: u32 = 1000267007;
+pub const DYNAMIC_STATE_DEPTH_COMPARE_OP_EXT: u32 = 1000267008;
+pub const DYNAMIC_STATE_DEPTH_BOUNDS_TEST_ENABLE_EXT: u32 = 1000267009;
+pub const DYNAMIC_STATE_STENCIL_TEST_ENABLE_EXT: u32 = 1000267010;
+pub const DYNAMIC_STATE_STENCIL_OP_EXT: u32 = 1000267011;
+pub const DYNAMIC_STATE_LINE_STIPPLE_EXT: u32 = 1000259000;
+
+pub type ConservativeRasterizationModeEXT = u32;
+pub const CONSERVATIVE_RASTERIZATION_MODE_DISABLED_EXT: u32 = 0;
+pub const CONSERVATIVE_RASTERIZATION_MODE_OVERESTIMATE_EXT: u32 = 1;
+pub const CONSERVATIVE_RASTERIZATION_MODE_UNDERESTIMATE_EXT: u32 = 2;
+
+pub type LineRasterizationModeEXT = u32;
+pub const LINE_RASTERIZATION_MODE_DEFAULT_EXT: u32 = 0;
+pub const LINE_RASTERIZATION_MODE_RECTANGULAR_EXT: u32 = 1;
+pub const LINE_RASTERIZATION_MODE_BRESENHAM_EXT: u32 = 2;
+pub const LINE_RASTERIZATION_MODE_RECTANGULAR_SMOOTH_EXT: u32 = 3;
+
+pub type PipelineCreationFeedbackFlagBitsEXT = u32;
+pub const PIPELINE_CREATION_FEEDBACK_VALID_BIT_EXT: u32 = 0x00000001;
+pub const PIPELINE_CREATION_FEEDBACK_APPLICATION_PIPELINE_CACHE_HIT_BIT_EXT: u32 = 0x00000002;
+pub const PIPELINE_CREATION_FEEDBACK_BASE_PIPELINE_ACCELERATION_BIT_EXT: u32 = 0x00000004;
+pub type PipelineCreationFeedbackFlagsEXT = u32;
+
+pub type GraphicsPipelineLibraryFlagBitsEXT = u32;
+pub const GRAPHICS_PIPELINE_LIBRARY_VERTEX_INPUT_INTERFACE_BIT_EXT: u32 = 0x00000001;
+pub const GRAPHICS_PIPELINE_LIBRARY_PRE_RASTERIZATION_SHADERS_BIT_EXT: u32 = 0x00000002;
+pub const GRAPHICS_PIPELINE_LIBRARY_FRAGMENT_SHADER_BIT_EXT: u32 = 0x00000004;
+pub const GRAPHICS_PIPELINE_LIBRARY_FRAGMENT_OUTPUT_INTERFACE_BIT_EXT: u32 = 0x00000008;
+pub type GraphicsPipelineLibraryFlagsEXT = u32;
 
 pub type Filter = u32;
 pub const FILTER_NEAREST: u32 = 0;
@@ -583,6 +726,7 @@ pub const DESCRIPTOR_TYPE_STORAGE_BUFFER: u32 = 7;
 pub const DESCRIPTOR_TYPE_UNIFORM_BUFFER_DYNAMIC: u32 = 8;
 pub const DESCRIPTOR_TYPE_STORAGE_BUFFER_DYNAMIC: u32 = 9;
 pub const DESCRIPTOR_TYPE_INPUT_ATTACHMENT: u32 = 10;
+pub const DESCRIPTOR_TYPE_ACCELERATION_STRUCTURE_KHR: u32 = 1000150000;
 
 pub type AttachmentLoadOp = u32;
 pub const ATTACHMENT_LOAD_OP_LOAD: u32 = 0;
@@ -596,6 +740,7 @@ pub const ATTACHMENT_STORE_OP_DONT_CARE: u32 = 1;
 pub type PipelineBindPoint = u32;
 pub const PIPELINE_BIND_POINT_GRAPHICS: u32 = 0;
 pub const PIPELINE_BIND_POINT_COMPUTE: u32 = 1;
+pub const PIPELINE_BIND_POINT_RAY_TRACING_KHR: u32 = 1000165000;
 
 pub type CommandBufferLevel = u32;
 pub const COMMAND_BUFFER_LEVEL_PRIMARY: u32 = 0;
@@ -604,12 +749,14 @@ pub const COMMAND_BUFFER_LEVEL_SECONDARY: u32 = 1;
 pub type IndexType = u32;
 pub const INDEX_TYPE_UINT16: u32 = 0;
 pub const INDEX_TYPE_UINT32: u32 = 1;
+pub const INDEX_TYPE_NONE_KHR: u32 = 1000165000;
 
 pub type SubpassContents = u32;
 pub const SUBPASS_CONTENTS_INLINE: u32 = 0;
 pub const SUBPASS_CONTENTS_SECONDARY_COMMAND_BUFFERS: u32 = 1;
 
 pub type InstanceCreateFlags = Flags;
+pub const INSTANCE_CREATE_ENUMERATE_PORTABILITY_BIT_KHR: u32 = 0x00000001;
 
 pub type FormatFeatureFlagBits = u32;
 pub const FORMAT_FEATURE_SAMPLED_IMAGE_BIT: u32 = 0x00000001;
@@ -649,6 +796,7 @@ pub const IMAGE_CREATE_SPARSE_ALIASED_BIT: u32 = 0x00000004;
 pub const IMAGE_CREATE_MUTABLE_FORMAT_BIT: u32 = 0x00000008;
 pub const IMAGE_CREATE_CUBE_COMPATIBLE_BIT: u32 = 0x00000010;
 pub const IMAGE_CREATE_2D_ARRAY_COMPATIBLE_BIT_KHR: u32 = 0x00000020;
+pub const IMAGE_CREATE_DISJOINT_BIT: u32 = 0x00000200;
 pub type ImageCreateFlags = Flags;
 
 
@@ -668,6 +816,7 @@ pub const QUEUE_GRAPHICS_BIT: u32 = 0x00000001;
 pub const QUEUE_COMPUTE_BIT: u32 = 0x00000002;
 pub const QUEUE_TRANSFER_BIT: u32 = 0x00000004;
 pub const QUEUE_SPARSE_BINDING_BIT: u32 = 0x00000008;
+pub const QUEUE_PROTECTED_BIT: u32 = 0x00000010;
 pub type QueueFlags = Flags;
 
 
@@ -684,6 +833,8 @@ pub type MemoryHeapFlagBits = u32;
 pub const MEMORY_HEAP_DEVICE_LOCAL_BIT: u32 = 0x00000001;
 pub type MemoryHeapFlags = Flags;
 pub type DeviceCreateFlags = Flags;
+pub type DeviceQueueCreateFlagBits = u32;
+pub const DEVICE_QUEUE_CREATE_PROTECTED_BIT: u32 = 0x00000001;
 pub type DeviceQueueCreateFlags = Flags;
 
 
@@ -705,6 +856,12 @@ pub const PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT: u32 = 0x00002000;
 pub const PIPELINE_STAGE_HOST_BIT: u32 = 0x00004000;
 pub const PIPELINE_STAGE_ALL_GRAPHICS_BIT: u32 = 0x00008000;
 pub const PIPELINE_STAGE_ALL_COMMANDS_BIT: u32 = 0x00010000;
+pub const PIPELINE_STAGE_TRANSFORM_FEEDBACK_BIT_EXT: u32 = 0x01000000;
+pub const PIPELINE_STAGE_CONDITIONAL_RENDERING_BIT_EXT: u32 = 0x00040000;
+pub const PIPELINE_STAGE_TASK_SHADER_BIT_EXT: u32 = 0x00080000;
+pub const PIPELINE_STAGE_MESH_SHADER_BIT_EXT: u32 = 0x00100000;
+pub const PIPELINE_STAGE_RAY_TRACING_SHADER_BIT_KHR: u32 = 0x00200000;
+pub const PIPELINE_STAGE_ACCELERATION_STRUCTURE_BUILD_BIT_KHR: u32 = 0x02000000;
 pub type PipelineStageFlags = Flags;
 pub type MemoryMapFlags = Flags;
 
@@ -777,6 +934,13 @@ pub const BUFFER_USAGE_STORAGE_BUFFER_BIT: u32 = 0x00000020;
 pub const BUFFER_USAGE_INDEX_BUFFER_BIT: u32 = 0x00000040;
 pub const BUFFER_USAGE_VERTEX_BUFFER_BIT: u32 = 0x00000080;
 pub const BUFFER_USAGE_INDIRECT_BUFFER_BIT: u32 = 0x00000100;
+pub const BUFFER_USAGE_CONDITIONAL_RENDERING_BIT_EXT: u32 = 0x00000200;
+pub const BUFFER_USAGE_SHADER_BINDING_TABLE_BIT_KHR: u32 = 0x00000400;
+pub const BUFFER_USAGE_TRANSFORM_FEEDBACK_BUFFER_BIT_EXT: u32 = 0x00000800;
+pub const BUFFER_USAGE_TRANSFORM_FEEDBACK_COUNTER_BUFFER_BIT_EXT: u32 = 0x00001000;
+pub const BUFFER_USAGE_SHADER_DEVICE_ADDRESS_BIT_KHR: u32 = 0x00020000;
+pub const BUFFER_USAGE_ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_BIT_KHR: u32 = 0x00080000;
+pub const BUFFER_USAGE_ACCELERATION_STRUCTURE_STORAGE_BIT_KHR: u32 = 0x00100000;
 pub type BufferUsageFlags = Flags;
 pub type BufferViewCreateFlags = Flags;
 pub type ImageViewCreateFlags = Flags;
@@ -788,9 +952,27 @@ pub type PipelineCreateFlagBits = u32;
 pub const PIPELINE_CREATE_DISABLE_OPTIMIZATION_BIT: u32 = 0x00000001;
 pub const PIPELINE_CREATE_ALLOW_DERIVATIVES_BIT: u32 = 0x00000002;
 pub const PIPELINE_CREATE_DERIVATIVE_BIT: u32 = 0x00000004;
+pub const PIPELINE_CREATE_DISPATCH_BASE_KHR: u32 = 0x00000010;
+pub const PIPELINE_CREATE_LINK_TIME_OPTIMIZATION_BIT_EXT: u32 = 0x00000400;
+pub const PIPELINE_CREATE_LIBRARY_BIT_KHR: u32 = 0x00000800;
+pub const PIPELINE_CREATE_RETAIN_LINK_TIME_OPTIMIZATION_INFO_BIT_EXT: u32 = 0x00800000;
 pub type PipelineCreateFlags = Flags;
+pub type PipelineShaderStageCreateFlagBits = u32;
+pub const PIPELINE_SHADER_STAGE_CREATE_ALLOW_VARYING_SUBGROUP_SIZE_BIT_EXT: u32 = 0x00000001;
+pub const PIPELINE_SHADER_STAGE_CREATE_REQUIRE_FULL_SUBGROUPS_BIT_EXT: u32 = 0x00000002;
 pub type PipelineShaderStageCreateFlags = Flags;
 
+pub type SubgroupFeatureFlagBits = u32;
+pub const SUBGROUP_FEATURE_BASIC_BIT: u32 = 0x00000001;
+pub const SUBGROUP_FEATURE_VOTE_BIT: u32 = 0x00000002;
+pub const SUBGROUP_FEATURE_ARITHMETIC_BIT: u32 = 0x00000004;
+pub const SUBGROUP_FEATURE_BALLOT_BIT: u32 = 0x00000008;
+pub const SUBGROUP_FEATURE_SHUFFLE_BIT: u32 = 0x00000010;
+pub const SUBGROUP_FEATURE_SHUFFLE_RELATIVE_BIT: u32 = 0x00000020;
+pub const SUBGROUP_FEATURE_CLUSTERED_BIT: u32 = 0x00000040;
+pub const SUBGROUP_FEATURE_QUAD_BIT: u32 = 0x00000080;
+pub type SubgroupFeatureFlags = Flags;
+
 
 pub type ShaderStageFlagBits = u32;
 pub const SHADER_STAGE_VERTEX_BIT: u32 = 0x00000001;
@@ -799,6 +981,14 @@ pub const SHADER_STAGE_TESSELLATION_EVALUATION_BIT: u32 = 0x00000004;
 pub const SHADER_STAGE_GEOMETRY_BIT: u32 = 0x00000008;
 pub const SHADER_STAGE_FRAGMENT_BIT: u32 = 0x00000010;
 pub const SHADER_STAGE_COMPUTE_BIT: u32 = 0x00000020;
+pub const SHADER_STAGE_TASK_BIT_EXT: u32 = 0x00000040;
+pub const SHADER_STAGE_MESH_BIT_EXT: u32 = 0x00000080;
+pub const SHADER_STAGE_RAYGEN_BIT_KHR: u32 = 0x00000100;
+pub const SHADER_STAGE_ANY_HIT_BIT_KHR: u32 = 0x00000200;
+pub const SHADER_STAGE_CLOSEST_HIT_BIT_KHR: u32 = 0x00000400;
+pub const SHADER_STAGE_MISS_BIT_KHR: u32 = 0x00000800;
+pub const SHADER_STAGE_INTERSECTION_BIT_KHR: u32 = 0x00001000;
+pub const SHADER_STAGE_CALLABLE_BIT_KHR: u32 = 0x00002000;
 pub const SHADER_STAGE_ALL_GRAPHICS: u32 = 0x1F;
 pub const SHADER_STAGE_ALL: u32 = 0x7FFFFFFF;
 pub type PipelineVertexInputStateCreateFlags = Flags;
@@ -864,6 +1054,12 @@ pub const ACCESS_HOST_READ_BIT: u32 = 0x00002000;
 pub const ACCESS_HOST_WRITE_BIT: u32 = 0x00004000;
 pub const ACCESS_MEMORY_READ_BIT: u32 = 0x00008000;
 pub const ACCESS_MEMORY_WRITE_BIT: u32 = 0x00010000;
+pub const ACCESS_CONDITIONAL_RENDERING_READ_BIT_EXT: u32 = 0x00100000;
+pub const ACCESS_ACCELERATION_STRUCTURE_READ_BIT_KHR: u32 = 0x00200000;
+pub const ACCESS_ACCELERATION_STRUCTURE_WRITE_BIT_KHR: u32 = 0x00400000;
+pub const ACCESS_TRANSFORM_FEEDBACK_WRITE_BIT_EXT: u32 = 0x02000000;
+pub const ACCESS_TRANSFORM_FEEDBACK_COUNTER_READ_BIT_EXT: u32 = 0x04000000;
+pub const ACCESS_TRANSFORM_FEEDBACK_COUNTER_WRITE_BIT_EXT: u32 = 0x08000000;
 pub type AccessFlags = Flags;
 
 
@@ -1008,6 +1204,44 @@ pub const DEBUG_REPORT_OBJECT_TYPE_OBJECT_TABLE_NVX_EXT: u32 = 31;
 pub const DEBUG_REPORT_OBJECT_TYPE_INDIRECT_COMMANDS_LAYOUT_NVX_EXT: u32 = 32;
 pub const DEBUG_REPORT_OBJECT_TYPE_VALIDATION_CACHE_EXT: u32 = 33;
 pub const DEBUG_REPORT_OBJECT_TYPE_DESCRIPTOR_UPDATE_TEMPLATE_KHR_EXT: u32 = 1000085000;
+pub const DEBUG_REPORT_OBJECT_TYPE_ACCELERATION_STRUCTURE_KHR_EXT: u32 = 1000150000;
+
+pub type ObjectType = u32;
+pub const OBJECT_TYPE_UNKNOWN: u32 = 0;
+pub const OBJECT_TYPE_INSTANCE: u32 = 1;
+pub const OBJECT_TYPE_PHYSICAL_DEVICE: u32 = 2;
+pub const OBJECT_TYPE_DEVICE: u32 = 3;
+pub const OBJECT_TYPE_QUEUE: u32 = 4;
+pub const OBJECT_TYPE_SEMAPHORE: u32 = 5;
+pub const OBJECT_TYPE_COMMAND_BUFFER: u32 = 6;
+pub const OBJECT_TYPE_FENCE: u32 = 7;
+pub const OBJECT_TYPE_DEVICE_MEMORY: u32 = 8;
+pub const OBJECT_TYPE_BUFFER: u32 = 9;
+pub const OBJECT_TYPE_IMAGE: u32 = 10;
+pub const OBJECT_TYPE_EVENT: u32 = 11;
+pub const OBJECT_TYPE_QUERY_POOL: u32 = 12;
+pub const OBJECT_TYPE_BUFFER_VIEW: u32 = 13;
+pub const OBJECT_TYPE_IMAGE_VIEW: u32 = 14;
+pub const OBJECT_TYPE_SHADER_MODULE: u32 = 15;
+pub const OBJECT_TYPE_PIPELINE_CACHE: u32 = 16;
+pub const OBJECT_TYPE_PIPELINE_LAYOUT: u32 = 17;
+pub const OBJECT_TYPE_RENDER_PASS: u32 = 18;
+pub const OBJECT_TYPE_PIPELINE: u32 = 19;
+pub const OBJECT_TYPE_DESCRIPTOR_SET_LAYOUT: u32 = 20;
+pub const OBJECT_TYPE_SAMPLER: u32 = 21;
+pub const OBJECT_TYPE_DESCRIPTOR_POOL: u32 = 22;
+pub const OBJECT_TYPE_DESCRIPTOR_SET: u32 = 23;
+pub const OBJECT_TYPE_FRAMEBUFFER: u32 = 24;
+pub const OBJECT_TYPE_COMMAND_POOL: u32 = 25;
+pub const OBJECT_TYPE_SURFACE_KHR: u32 = 1000000000;
+pub const OBJECT_TYPE_SWAPCHAIN_KHR: u32 = 1000001000;
+pub const OBJECT_TYPE_DISPLAY_KHR: u32 = 1000002000;
+pub const OBJECT_TYPE_DISPLAY_MODE_KHR: u32 = 1000002001;
+pub const OBJECT_TYPE_DEBUG_REPORT_CALLBACK_EXT: u32 = 1000011000;
+pub const OBJECT_TYPE_DESCRIPTOR_UPDATE_TEMPLATE_KHR: u32 = 1000085000;
+pub const OBJECT_TYPE_DEBUG_UTILS_MESSENGER_EXT: u32 = 1000128000;
+pub const OBJECT_TYPE_ACCELERATION_STRUCTURE_KHR: u32 = 1000150000;
+pub const OBJECT_TYPE_VALIDATION_CACHE_EXT: u32 = 1000160000;
 
 pub type DebugReportErrorEXT = u32;
 pub const DEBUG_REPORT_ERROR_NONE_EXT: u32 = 0;
@@ -1021,6 +1255,32 @@ pub const DEBUG_REPORT_ERROR_BIT_EXT: u32 = 0x00000008;
 pub const DEBUG_REPORT_DEBUG_BIT_EXT: u32 = 0x00000010;
 pub type DebugReportFlagsEXT = Flags;
 
+pub type DebugUtilsMessageSeverityFlagBitsEXT = u32;
+pub const DEBUG_UTILS_MESSAGE_SEVERITY_VERBOSE_BIT_EXT: u32 = 0x00000001;
+pub const DEBUG_UTILS_MESSAGE_SEVERITY_INFO_BIT_EXT: u32 = 0x00000010;
+pub const DEBUG_UTILS_MESSAGE_SEVERITY_WARNING_BIT_EXT: u32 = 0x00000100;
+pub const DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT: u32 = 0x00001000;
+pub type DebugUtilsMessageSeverityFlagsEXT = Flags;
+
+pub type DebugUtilsMessageTypeFlagBitsEXT = u32;
+pub const DEBUG_UTILS_MESSAGE_TYPE_GENERAL_BIT_EXT: u32 = 0x00000001;
+pub const DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT: u32 = 0x00000002;
+pub const DEBUG_UTILS_MESSAGE_TYPE_PERFORMANCE_BIT_EXT: u32 = 0x00000004;
+pub type DebugUtilsMessageTypeFlagsEXT = Flags;
+
+pub type DebugUtilsMessengerCreateFlagsEXT = Flags;
+pub type DebugUtilsMessengerCallbackDataFlagsEXT = Flags;
+
+pub type ToolPurposeFlagBitsEXT = u32;
+pub const TOOL_PURPOSE_VALIDATION_BIT_EXT: u32 = 0x00000001;
+pub const TOOL_PURPOSE_PROFILING_BIT_EXT: u32 = 0x00000002;
+pub const TOOL_PURPOSE_TRACING_BIT_EXT: u32 = 0x00000004;
+pub const TOOL_PURPOSE_ADDITIONAL_FEATURES_BIT_EXT: u32 = 0x00000008;
+pub const TOOL_PURPOSE_MODIFYING_FEATURES_BIT_EXT: u32 = 0x00000010;
+pub const TOOL_PURPOSE_DEBUG_REPORTING_BIT_EXT: u32 = 0x00000020;
+pub const TOOL_PURPOSE_DEBUG_MARKERS_BIT_EXT: u32 = 0x00000040;
+pub type ToolPurposeFlagsEXT = Flags;
+
 pub type MacOSSurfaceCreateFlagsMVK = u32;
 
 pub type IOSSurfaceCreateFlagsMVK = u32;
@@ -1042,6 +1302,7 @@ pub type PFN_vkFreeFunction = extern "system" fn(*mut c_void, *mut c_void);
 pub type PFN_vkInternalAllocationNotification = extern "system" fn(*mut c_void, usize, InternalAllocationType, SystemAllocationScope) -> *mut c_void;
 pub type PFN_vkInternalFreeNotification = extern "system" fn(*mut c_void, usize, InternalAllocationType, SystemAllocationScope) -> *mut c_void;
 pub type PFN_vkDebugReportCallbackEXT = extern "system" fn(DebugReportFlagsEXT, DebugReportObjectTypeEXT, u64, usize, i32, *const c_char, *const c_char, *mut c_void) -> Bool32;
+pub type PFN_vkDebugUtilsMessengerCallbackEXT = extern "system" fn(DebugUtilsMessageSeverityFlagBitsEXT, DebugUtilsMessageTypeFlagsEXT, *const DebugUtilsMessengerCallbackDataEXT, *mut c_void) -> Bool32;
 
 pub type PFN_vkVoidFunction = extern "system" fn() -> ();
 
@@ -2438,6 +2699,33 @@ pub struct DebugReportCallbackCreateInfoEXT {
     pub pUserData: *mut c_void,
 }
 
+pub type ValidationFeatureEnableEXT = u32;
+pub const VALIDATION_FEATURE_ENABLE_GPU_ASSISTED_EXT: u32 = 0;
+pub const VALIDATION_FEATURE_ENABLE_GPU_ASSISTED_RESERVE_BINDING_SLOT_EXT: u32 = 1;
+pub const VALIDATION_FEATURE_ENABLE_BEST_PRACTICES_EXT: u32 = 2;
+pub const VALIDATION_FEATURE_ENABLE_DEBUG_PRINTF_EXT: u32 = 3;
+pub const VALIDATION_FEATURE_ENABLE_SYNCHRONIZATION_VALIDATION_EXT: u32 = 4;
+
+pub type ValidationFeatureDisableEXT = u32;
+pub const VALIDATION_FEATURE_DISABLE_ALL_EXT: u32 = 0;
+pub const VALIDATION_FEATURE_DISABLE_SHADERS_EXT: u32 = 1;
+pub const VALIDATION_FEATURE_DISABLE_THREAD_SAFETY_EXT: u32 = 2;
+pub const VALIDATION_FEATURE_DISABLE_API_PARAMETERS_EXT: u32 = 3;
+pub const VALIDATION_FEATURE_DISABLE_OBJECT_LIFETIMES_EXT: u32 = 4;
+pub const VALIDATION_FEATURE_DISABLE_CORE_CHECKS_EXT: u32 = 5;
+pub const VALIDATION_FEATURE_DISABLE_UNIQUE_HANDLES_EXT: u32 = 6;
+pub const VALIDATION_FEATURE_DISABLE_SHADER_VALIDATION_CACHE_EXT: u32 = 7;
+
+#[repr(C)]
+pub struct ValidationFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub enabledValidationFeatureCount: u32,
+    pub pEnabledValidationFeatures: *const ValidationFeatureEnableEXT,
+    pub disabledValidationFeatureCount: u32,
+    pub pDisabledValidationFeatures: *const ValidationFeatureDisableEXT,
+}
+
 #[repr(C)]
 pub struct IOSSurfaceCreateInfoMVK {
 	pub sType: StructureType,
@@ -2681,6 +2969,1262 @@ pub struct DebugMarkerMarkerInfoEXT {
     pub color: [f32; 4],
 }
 
+#[repr(C)]
+pub struct DebugUtilsObjectNameInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub objectType: ObjectType,
+    pub objectHandle: u64,
+    pub pObjectName: *const c_char,
+}
+
+#[repr(C)]
+pub struct DebugUtilsLabelEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub pLabelName: *const c_char,
+    pub color: [f32; 4],
+}
+
+#[repr(C)]
+pub struct CheckpointDataNV {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub stage: PipelineStageFlagBits,
+    pub pCheckpointMarker: *mut c_void,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceToolPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub name: [c_char; MAX_EXTENSION_NAME_SIZE as usize],
+    pub version: [c_char; MAX_EXTENSION_NAME_SIZE as usize],
+    pub purposes: ToolPurposeFlagsEXT,
+    pub description: [c_char; MAX_DESCRIPTION_SIZE as usize],
+    pub layer: [c_char; MAX_EXTENSION_NAME_SIZE as usize],
+}
+
+#[repr(C)]
+pub struct DebugUtilsMessengerCallbackDataEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: DebugUtilsMessengerCallbackDataFlagsEXT,
+    pub pMessageIdName: *const c_char,
+    pub messageIdNumber: i32,
+    pub pMessage: *const c_char,
+    pub queueLabelCount: u32,
+    pub pQueueLabels: *const DebugUtilsLabelEXT,
+    pub cmdBufLabelCount: u32,
+    pub pCmdBufLabels: *const DebugUtilsLabelEXT,
+    pub objectCount: u32,
+    pub pObjects: *const DebugUtilsObjectNameInfoEXT,
+}
+
+#[repr(C)]
+pub struct DebugUtilsMessengerCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: DebugUtilsMessengerCreateFlagsEXT,
+    pub messageSeverity: DebugUtilsMessageSeverityFlagsEXT,
+    pub messageType: DebugUtilsMessageTypeFlagsEXT,
+    pub pfnUserCallback: PFN_vkDebugUtilsMessengerCallbackEXT,
+    pub pUserData: *mut c_void,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceExtendedDynamicStateFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub extendedDynamicState: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceRobustness2FeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub robustBufferAccess2: Bool32,
+    pub robustImageAccess2: Bool32,
+    pub nullDescriptor: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDevicePortabilitySubsetFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub constantAlphaColorBlendFactors: Bool32,
+    pub events: Bool32,
+    pub imageViewFormatReinterpretation: Bool32,
+    pub imageViewFormatSwizzle: Bool32,
+    pub imageView2DOn3DImage: Bool32,
+    pub multisampleArrayImage: Bool32,
+    pub mutableComparisonSamplers: Bool32,
+    pub pointPolygons: Bool32,
+    pub samplerMipLodBias: Bool32,
+    pub separateStencilMaskRef: Bool32,
+    pub shaderSampleRateInterpolationFunctions: Bool32,
+    pub tessellationIsolines: Bool32,
+    pub tessellationPointMode: Bool32,
+    pub triangleFans: Bool32,
+    pub vertexAttributeAccessBeyondStride: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDevicePortabilitySubsetPropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub minVertexInputBindingStrideAlignment: u32,
+}
+
+pub type PipelineRasterizationConservativeStateCreateFlagsEXT = Flags;
+
+#[repr(C)]
+pub struct PipelineRasterizationConservativeStateCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: PipelineRasterizationConservativeStateCreateFlagsEXT,
+    pub conservativeRasterizationMode: ConservativeRasterizationModeEXT,
+    pub extraPrimitiveOverestimationSize: f32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceConservativeRasterizationPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub primitiveOverestimationSize: f32,
+    pub maxExtraPrimitiveOverestimationSize: f32,
+    pub extraPrimitiveOverestimationSizeGranularity: f32,
+    pub primitiveUnderestimation: Bool32,
+    pub conservativePointAndLineRasterization: Bool32,
+    pub degenerateTrianglesRasterized: Bool32,
+    pub degenerateLinesRasterized: Bool32,
+    pub fullyCoveredFragmentShaderInputVariable: Bool32,
+    pub conservativeRasterizationPostDepthCoverage: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceLineRasterizationFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub rectangularLines: Bool32,
+    pub bresenhamLines: Bool32,
+    pub smoothLines: Bool32,
+    pub stippledRectangularLines: Bool32,
+    pub stippledBresenhamLines: Bool32,
+    pub stippledSmoothLines: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceLineRasterizationPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub lineSubPixelPrecisionBits: u32,
+}
+
+#[repr(C)]
+pub struct PipelineRasterizationLineStateCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub lineRasterizationMode: LineRasterizationModeEXT,
+    pub stippledLineEnable: Bool32,
+    pub lineStippleFactor: u32,
+    pub lineStipplePattern: u16,
+}
+
+#[repr(C)]
+pub struct VertexInputBindingDivisorDescriptionEXT {
+    pub binding: u32,
+    pub divisor: u32,
+}
+
+#[repr(C)]
+pub struct PipelineVertexInputDivisorStateCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub vertexBindingDivisorCount: u32,
+    pub pVertexBindingDivisors: *const VertexInputBindingDivisorDescriptionEXT,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceVertexAttributeDivisorFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub vertexAttributeInstanceRateDivisor: Bool32,
+    pub vertexAttributeInstanceRateZeroDivisor: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceVertexAttributeDivisorPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxVertexAttribDivisor: u32,
+}
+
+#[repr(C)]
+pub struct PipelineCreationFeedbackEXT {
+    pub flags: PipelineCreationFeedbackFlagsEXT,
+    pub duration: u64,
+}
+
+#[repr(C)]
+pub struct PipelineCreationFeedbackCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub pPipelineCreationFeedback: *mut PipelineCreationFeedbackEXT,
+    pub pipelineStageCreationFeedbackCount: u32,
+    pub pPipelineStageCreationFeedbacks: *mut PipelineCreationFeedbackEXT,
+}
+
+#[repr(C)]
+pub struct PipelineLibraryCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub libraryCount: u32,
+    pub pLibraries: *const Pipeline,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceGraphicsPipelineLibraryFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub graphicsPipelineLibrary: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceGraphicsPipelineLibraryPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub graphicsPipelineLibraryFastLinking: Bool32,
+    pub graphicsPipelineLibraryIndependentInterpolationDecoration: Bool32,
+}
+
+#[repr(C)]
+pub struct GraphicsPipelineLibraryCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub flags: GraphicsPipelineLibraryFlagsEXT,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceMeshShaderFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub taskShader: Bool32,
+    pub meshShader: Bool32,
+    pub multiviewMeshShader: Bool32,
+    pub primitiveFragmentShadingRateMeshShader: Bool32,
+    pub meshShaderQueries: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceMeshShaderPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxTaskWorkGroupTotalCount: u32,
+    pub maxTaskWorkGroupCount: [u32; 3],
+    pub maxTaskWorkGroupInvocations: u32,
+    pub maxTaskWorkGroupSize: [u32; 3],
+    pub maxTaskPayloadSize: u32,
+    pub maxTaskSharedMemorySize: u32,
+    pub maxTaskPayloadAndSharedMemorySize: u32,
+    pub maxMeshWorkGroupTotalCount: u32,
+    pub maxMeshWorkGroupCount: [u32; 3],
+    pub maxMeshWorkGroupInvocations: u32,
+    pub maxMeshWorkGroupSize: [u32; 3],
+    pub maxMeshSharedMemorySize: u32,
+    pub maxMeshPayloadAndSharedMemorySize: u32,
+    pub maxMeshOutputMemorySize: u32,
+    pub maxMeshPayloadAndOutputMemorySize: u32,
+    pub maxMeshOutputComponents: u32,
+    pub maxMeshOutputVertices: u32,
+    pub maxMeshOutputPrimitives: u32,
+    pub maxMeshOutputLayers: u32,
+    pub maxMeshMultiviewViewCount: u32,
+    pub meshOutputPerVertexGranularity: u32,
+    pub meshOutputPerPrimitiveGranularity: u32,
+    pub maxPreferredTaskWorkGroupInvocations: u32,
+    pub maxPreferredMeshWorkGroupInvocations: u32,
+    pub prefersLocalInvocationVertexOutput: Bool32,
+    pub prefersLocalInvocationPrimitiveOutput: Bool32,
+    pub prefersCompactVertexOutput: Bool32,
+    pub prefersCompactPrimitiveOutput: Bool32,
+}
+
+#[repr(C)]
+pub struct DrawMeshTasksIndirectCommandEXT {
+    pub groupCountX: u32,
+    pub groupCountY: u32,
+    pub groupCountZ: u32,
+}
+
+pub type MemoryAllocateFlagBitsKHR = u32;
+pub const MEMORY_ALLOCATE_DEVICE_MASK_BIT_KHR: u32 = 0x00000001;
+pub const MEMORY_ALLOCATE_DEVICE_ADDRESS_BIT_KHR: u32 = 0x00000002;
+pub type MemoryAllocateFlagsKHR = Flags;
+
+#[repr(C)]
+pub struct MemoryAllocateFlagsInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: MemoryAllocateFlagsKHR,
+    pub deviceMask: u32,
+}
+
+pub type PeerMemoryFeatureFlagBitsKHR = u32;
+pub const PEER_MEMORY_FEATURE_COPY_SRC_BIT_KHR: u32 = 0x00000001;
+pub const PEER_MEMORY_FEATURE_COPY_DST_BIT_KHR: u32 = 0x00000002;
+pub const PEER_MEMORY_FEATURE_GENERIC_SRC_BIT_KHR: u32 = 0x00000004;
+pub const PEER_MEMORY_FEATURE_GENERIC_DST_BIT_KHR: u32 = 0x00000008;
+pub type PeerMemoryFeatureFlagsKHR = Flags;
+
+#[repr(C)]
+pub struct PhysicalDeviceGroupPropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub physicalDeviceCount: u32,
+    pub physicalDevices: [PhysicalDevice; MAX_DEVICE_GROUP_SIZE_KHR],
+    pub subsetAllocation: Bool32,
+}
+
+#[repr(C)]
+pub struct DeviceGroupDeviceCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub physicalDeviceCount: u32,
+    pub pPhysicalDevices: *const PhysicalDevice,
+}
+
+#[repr(C)]
+pub struct DeviceGroupRenderPassBeginInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub deviceMask: u32,
+    pub deviceRenderAreaCount: u32,
+    pub pDeviceRenderAreas: *const Rect2D,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceBufferDeviceAddressFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub bufferDeviceAddress: Bool32,
+    pub bufferDeviceAddressCaptureReplay: Bool32,
+    pub bufferDeviceAddressMultiDevice: Bool32,
+}
+
+#[repr(C)]
+pub struct BufferDeviceAddressInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub buffer: Buffer,
+}
+
+pub type AccelerationStructureTypeKHR = u32;
+pub const ACCELERATION_STRUCTURE_TYPE_TOP_LEVEL_KHR: u32 = 0;
+pub const ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL_KHR: u32 = 1;
+pub const ACCELERATION_STRUCTURE_TYPE_GENERIC_KHR: u32 = 2;
+
+pub type AccelerationStructureBuildTypeKHR = u32;
+pub const ACCELERATION_STRUCTURE_BUILD_TYPE_HOST_KHR: u32 = 0;
+pub const ACCELERATION_STRUCTURE_BUILD_TYPE_DEVICE_KHR: u32 = 1;
+pub const ACCELERATION_STRUCTURE_BUILD_TYPE_HOST_OR_DEVICE_KHR: u32 = 2;
+
+pub type BuildAccelerationStructureModeKHR = u32;
+pub const BUILD_ACCELERATION_STRUCTURE_MODE_BUILD_KHR: u32 = 0;
+pub const BUILD_ACCELERATION_STRUCTURE_MODE_UPDATE_KHR: u32 = 1;
+
+pub type BuildAccelerationStructureFlagBitsKHR = u32;
+pub const BUILD_ACCELERATION_STRUCTURE_ALLOW_UPDATE_BIT_KHR: u32 = 0x00000001;
+pub const BUILD_ACCELERATION_STRUCTURE_ALLOW_COMPACTION_BIT_KHR: u32 = 0x00000002;
+pub const BUILD_ACCELERATION_STRUCTURE_PREFER_FAST_TRACE_BIT_KHR: u32 = 0x00000004;
+pub const BUILD_ACCELERATION_STRUCTURE_PREFER_FAST_BUILD_BIT_KHR: u32 = 0x00000008;
+pub const BUILD_ACCELERATION_STRUCTURE_LOW_MEMORY_BIT_KHR: u32 = 0x00000010;
+pub type BuildAccelerationStructureFlagsKHR = Flags;
+
+pub type AccelerationStructureCreateFlagsKHR = Flags;
+
+pub type GeometryTypeKHR = u32;
+pub const GEOMETRY_TYPE_TRIANGLES_KHR: u32 = 0;
+pub const GEOMETRY_TYPE_AABBS_KHR: u32 = 1;
+pub const GEOMETRY_TYPE_INSTANCES_KHR: u32 = 2;
+
+pub type GeometryFlagBitsKHR = u32;
+pub const GEOMETRY_OPAQUE_BIT_KHR: u32 = 0x00000001;
+pub const GEOMETRY_NO_DUPLICATE_ANY_HIT_INVOCATION_BIT_KHR: u32 = 0x00000002;
+pub type GeometryFlagsKHR = Flags;
+
+pub type GeometryInstanceFlagBitsKHR = u32;
+pub const GEOMETRY_INSTANCE_TRIANGLE_FACING_CULL_DISABLE_BIT_KHR: u32 = 0x00000001;
+pub const GEOMETRY_INSTANCE_TRIANGLE_FLIP_FACING_BIT_KHR: u32 = 0x00000002;
+pub const GEOMETRY_INSTANCE_FORCE_OPAQUE_BIT_KHR: u32 = 0x00000004;
+pub const GEOMETRY_INSTANCE_FORCE_NO_OPAQUE_BIT_KHR: u32 = 0x00000008;
+pub type GeometryInstanceFlagsKHR = Flags;
+
+pub type CopyAccelerationStructureModeKHR = u32;
+pub const COPY_ACCELERATION_STRUCTURE_MODE_CLONE_KHR: u32 = 0;
+pub const COPY_ACCELERATION_STRUCTURE_MODE_COMPACT_KHR: u32 = 1;
+
+#[repr(C)]
+pub struct PhysicalDeviceAccelerationStructureFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub accelerationStructure: Bool32,
+    pub accelerationStructureCaptureReplay: Bool32,
+    pub accelerationStructureIndirectBuild: Bool32,
+    pub accelerationStructureHostCommands: Bool32,
+    pub descriptorBindingAccelerationStructureUpdateAfterBind: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceAccelerationStructurePropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxGeometryCount: u64,
+    pub maxInstanceCount: u64,
+    pub maxPrimitiveCount: u64,
+    pub maxPerStageDescriptorAccelerationStructures: u32,
+    pub maxPerStageDescriptorUpdateAfterBindAccelerationStructures: u32,
+    pub maxDescriptorSetAccelerationStructures: u32,
+    pub maxDescriptorSetUpdateAfterBindAccelerationStructures: u32,
+    pub minAccelerationStructureScratchOffsetAlignment: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union DeviceOrHostAddressKHR {
+    pub deviceAddress: DeviceAddress,
+    pub hostAddress: *mut c_void,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union DeviceOrHostAddressConstKHR {
+    pub deviceAddress: DeviceAddress,
+    pub hostAddress: *const c_void,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AccelerationStructureGeometryTrianglesDataKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub vertexFormat: Format,
+    pub vertexData: DeviceOrHostAddressConstKHR,
+    pub vertexStride: DeviceSize,
+    pub maxVertex: u32,
+    pub indexType: IndexType,
+    pub indexData: DeviceOrHostAddressConstKHR,
+    pub transformData: DeviceOrHostAddressConstKHR,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AccelerationStructureGeometryAabbsDataKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub data: DeviceOrHostAddressConstKHR,
+    pub stride: DeviceSize,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AccelerationStructureGeometryInstancesDataKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub arrayOfPointers: Bool32,
+    pub data: DeviceOrHostAddressConstKHR,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub union AccelerationStructureGeometryDataKHR {
+    pub triangles: AccelerationStructureGeometryTrianglesDataKHR,
+    pub aabbs: AccelerationStructureGeometryAabbsDataKHR,
+    pub instances: AccelerationStructureGeometryInstancesDataKHR,
+}
+
+#[repr(C)]
+pub struct AccelerationStructureGeometryKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub geometryType: GeometryTypeKHR,
+    pub geometry: AccelerationStructureGeometryDataKHR,
+    pub flags: GeometryFlagsKHR,
+}
+
+#[repr(C)]
+pub struct AccelerationStructureBuildGeometryInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub ty: AccelerationStructureTypeKHR,
+    pub flags: BuildAccelerationStructureFlagsKHR,
+    pub mode: BuildAccelerationStructureModeKHR,
+    pub srcAccelerationStructure: AccelerationStructureKHR,
+    pub dstAccelerationStructure: AccelerationStructureKHR,
+    pub geometryCount: u32,
+    pub pGeometries: *const AccelerationStructureGeometryKHR,
+    pub ppGeometries: *const *const AccelerationStructureGeometryKHR,
+    pub scratchData: DeviceOrHostAddressKHR,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AccelerationStructureBuildRangeInfoKHR {
+    pub primitiveCount: u32,
+    pub primitiveOffset: u32,
+    pub firstVertex: u32,
+    pub transformOffset: u32,
+}
+
+#[repr(C)]
+pub struct AccelerationStructureBuildSizesInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub accelerationStructureSize: DeviceSize,
+    pub updateScratchSize: DeviceSize,
+    pub buildScratchSize: DeviceSize,
+}
+
+#[repr(C)]
+pub struct AccelerationStructureCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub createFlags: AccelerationStructureCreateFlagsKHR,
+    pub buffer: Buffer,
+    pub offset: DeviceSize,
+    pub size: DeviceSize,
+    pub ty: AccelerationStructureTypeKHR,
+    pub deviceAddress: DeviceAddress,
+}
+
+#[repr(C)]
+pub struct AccelerationStructureDeviceAddressInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub accelerationStructure: AccelerationStructureKHR,
+}
+
+#[repr(C)]
+pub struct CopyAccelerationStructureInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub src: AccelerationStructureKHR,
+    pub dst: AccelerationStructureKHR,
+    pub mode: CopyAccelerationStructureModeKHR,
+}
+
+#[repr(C)]
+pub struct WriteDescriptorSetAccelerationStructureKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub accelerationStructureCount: u32,
+    pub pAccelerationStructures: *const AccelerationStructureKHR,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct TransformMatrixKHR {
+    pub matrix: [[f32; 4]; 3],
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AabbPositionsKHR {
+    pub minX: f32,
+    pub minY: f32,
+    pub minZ: f32,
+    pub maxX: f32,
+    pub maxY: f32,
+    pub maxZ: f32,
+}
+
+// The C definition uses bitfields: the custom index and the shader binding table record offset
+// are in the lower 24 bits of their field, and the mask and the flags in the upper 8 bits.
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct AccelerationStructureInstanceKHR {
+    pub transform: TransformMatrixKHR,
+    pub instanceCustomIndexAndMask: u32,
+    pub instanceShaderBindingTableRecordOffsetAndFlags: u32,
+    pub accelerationStructureReference: u64,
+}
+
+pub type RayTracingShaderGroupTypeKHR = u32;
+pub const RAY_TRACING_SHADER_GROUP_TYPE_GENERAL_KHR: u32 = 0;
+pub const RAY_TRACING_SHADER_GROUP_TYPE_TRIANGLES_HIT_GROUP_KHR: u32 = 1;
+pub const RAY_TRACING_SHADER_GROUP_TYPE_PROCEDURAL_HIT_GROUP_KHR: u32 = 2;
+
+pub const SHADER_UNUSED_KHR: u32 = !0;
+
+#[repr(C)]
+pub struct PhysicalDeviceRayTracingPipelineFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub rayTracingPipeline: Bool32,
+    pub rayTracingPipelineShaderGroupHandleCaptureReplay: Bool32,
+    pub rayTracingPipelineShaderGroupHandleCaptureReplayMixed: Bool32,
+    pub rayTracingPipelineTraceRaysIndirect: Bool32,
+    pub rayTraversalPrimitiveCulling: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceRayTracingPipelinePropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub shaderGroupHandleSize: u32,
+    pub maxRayRecursionDepth: u32,
+    pub maxShaderGroupStride: u32,
+    pub shaderGroupBaseAlignment: u32,
+    pub shaderGroupHandleCaptureReplaySize: u32,
+    pub maxRayDispatchInvocationCount: u32,
+    pub shaderGroupHandleAlignment: u32,
+    pub maxRayHitAttributeSize: u32,
+}
+
+#[repr(C)]
+pub struct RayTracingShaderGroupCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub ty: RayTracingShaderGroupTypeKHR,
+    pub generalShader: u32,
+    pub closestHitShader: u32,
+    pub anyHitShader: u32,
+    pub intersectionShader: u32,
+    pub pShaderGroupCaptureReplayHandle: *const c_void,
+}
+
+#[repr(C)]
+pub struct RayTracingPipelineCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: PipelineCreateFlags,
+    pub stageCount: u32,
+    pub pStages: *const PipelineShaderStageCreateInfo,
+    pub groupCount: u32,
+    pub pGroups: *const RayTracingShaderGroupCreateInfoKHR,
+    pub maxPipelineRayRecursionDepth: u32,
+    pub pLibraryInfo: *const c_void,
+    pub pLibraryInterface: *const c_void,
+    pub pDynamicState: *const PipelineDynamicStateCreateInfo,
+    pub layout: PipelineLayout,
+    pub basePipelineHandle: Pipeline,
+    pub basePipelineIndex: i32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct StridedDeviceAddressRegionKHR {
+    pub deviceAddress: DeviceAddress,
+    pub stride: DeviceSize,
+    pub size: DeviceSize,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceIDProperties {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub deviceUUID: [u8; UUID_SIZE as usize],
+    pub driverUUID: [u8; UUID_SIZE as usize],
+    pub deviceLUID: [u8; LUID_SIZE as usize],
+    pub deviceNodeMask: u32,
+    pub deviceLUIDValid: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceSubgroupProperties {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub subgroupSize: u32,
+    pub supportedStages: ShaderStageFlags,
+    pub supportedOperations: SubgroupFeatureFlags,
+    pub quadOperationsInAllStages: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceProtectedMemoryFeatures {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub protectedMemory: Bool32,
+}
+
+#[repr(C)]
+pub struct DeviceQueueInfo2 {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: DeviceQueueCreateFlags,
+    pub queueFamilyIndex: u32,
+    pub queueIndex: u32,
+}
+
+pub type DriverIdKHR = u32;
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct ConformanceVersionKHR {
+    pub major: u8,
+    pub minor: u8,
+    pub subminor: u8,
+    pub patch: u8,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceDriverPropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub driverID: DriverIdKHR,
+    pub driverName: [c_char; MAX_DRIVER_NAME_SIZE_KHR as usize],
+    pub driverInfo: [c_char; MAX_DRIVER_INFO_SIZE_KHR as usize],
+    pub conformanceVersion: ConformanceVersionKHR,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceMaintenance3PropertiesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxPerSetDescriptors: u32,
+    pub maxMemoryAllocationSize: DeviceSize,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceDescriptorIndexingPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxUpdateAfterBindDescriptorsInAllPools: u32,
+    pub shaderUniformBufferArrayNonUniformIndexingNative: Bool32,
+    pub shaderSampledImageArrayNonUniformIndexingNative: Bool32,
+    pub shaderStorageBufferArrayNonUniformIndexingNative: Bool32,
+    pub shaderStorageImageArrayNonUniformIndexingNative: Bool32,
+    pub shaderInputAttachmentArrayNonUniformIndexingNative: Bool32,
+    pub robustBufferAccessUpdateAfterBind: Bool32,
+    pub quadDivergentImplicitLod: Bool32,
+    pub maxPerStageDescriptorUpdateAfterBindSamplers: u32,
+    pub maxPerStageDescriptorUpdateAfterBindUniformBuffers: u32,
+    pub maxPerStageDescriptorUpdateAfterBindStorageBuffers: u32,
+    pub maxPerStageDescriptorUpdateAfterBindSampledImages: u32,
+    pub maxPerStageDescriptorUpdateAfterBindStorageImages: u32,
+    pub maxPerStageDescriptorUpdateAfterBindInputAttachments: u32,
+    pub maxPerStageUpdateAfterBindResources: u32,
+    pub maxDescriptorSetUpdateAfterBindSamplers: u32,
+    pub maxDescriptorSetUpdateAfterBindUniformBuffers: u32,
+    pub maxDescriptorSetUpdateAfterBindUniformBuffersDynamic: u32,
+    pub maxDescriptorSetUpdateAfterBindStorageBuffers: u32,
+    pub maxDescriptorSetUpdateAfterBindStorageBuffersDynamic: u32,
+    pub maxDescriptorSetUpdateAfterBindSampledImages: u32,
+    pub maxDescriptorSetUpdateAfterBindStorageImages: u32,
+    pub maxDescriptorSetUpdateAfterBindInputAttachments: u32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceSubgroupSizeControlFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub subgroupSizeControl: Bool32,
+    pub computeFullSubgroups: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceSubgroupSizeControlPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub minSubgroupSize: u32,
+    pub maxSubgroupSize: u32,
+    pub maxComputeWorkgroupSubgroups: u32,
+    pub requiredSubgroupSizeStages: ShaderStageFlags,
+}
+
+#[repr(C)]
+pub struct PipelineShaderStageRequiredSubgroupSizeCreateInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub requiredSubgroupSize: u32,
+}
+
+#[repr(C)]
+pub struct PhysicalDevice16BitStorageFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub storageBuffer16BitAccess: Bool32,
+    pub uniformAndStorageBuffer16BitAccess: Bool32,
+    pub storagePushConstant16: Bool32,
+    pub storageInputOutput16: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDevice8BitStorageFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub storageBuffer8BitAccess: Bool32,
+    pub uniformAndStorageBuffer8BitAccess: Bool32,
+    pub storagePushConstant8: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceShaderFloat16Int8FeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub shaderFloat16: Bool32,
+    pub shaderInt8: Bool32,
+}
+
+pub type SemaphoreTypeKHR = u32;
+pub const SEMAPHORE_TYPE_BINARY_KHR: u32 = 0;
+pub const SEMAPHORE_TYPE_TIMELINE_KHR: u32 = 1;
+
+pub type SemaphoreWaitFlagBitsKHR = u32;
+pub const SEMAPHORE_WAIT_ANY_BIT_KHR: u32 = 0x00000001;
+pub type SemaphoreWaitFlagsKHR = Flags;
+
+#[repr(C)]
+pub struct PhysicalDeviceTimelineSemaphoreFeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub timelineSemaphore: Bool32,
+}
+
+#[repr(C)]
+pub struct SemaphoreTypeCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphoreType: SemaphoreTypeKHR,
+    pub initialValue: u64,
+}
+
+#[repr(C)]
+pub struct TimelineSemaphoreSubmitInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub waitSemaphoreValueCount: u32,
+    pub pWaitSemaphoreValues: *const u64,
+    pub signalSemaphoreValueCount: u32,
+    pub pSignalSemaphoreValues: *const u64,
+}
+
+#[repr(C)]
+pub struct SemaphoreWaitInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: SemaphoreWaitFlagsKHR,
+    pub semaphoreCount: u32,
+    pub pSemaphores: *const Semaphore,
+    pub pValues: *const u64,
+}
+
+#[repr(C)]
+pub struct SemaphoreSignalInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub value: u64,
+}
+
+pub type ExternalSemaphoreHandleTypeFlagBitsKHR = u32;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD_BIT_KHR: u32 = 0x00000001;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR: u32 = 0x00000002;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR: u32 = 0x00000004;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_D3D12_FENCE_BIT_KHR: u32 = 0x00000008;
+pub const EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD_BIT_KHR: u32 = 0x00000010;
+pub type ExternalSemaphoreHandleTypeFlagsKHR = Flags;
+
+pub type ExternalMemoryHandleTypeFlagBits = u32;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT: u32 = 0x00000001;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_BIT: u32 = 0x00000002;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT: u32 = 0x00000004;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_BIT: u32 = 0x00000008;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_KMT_BIT: u32 = 0x00000010;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_HEAP_BIT: u32 = 0x00000020;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_RESOURCE_BIT: u32 = 0x00000040;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_HOST_ALLOCATION_BIT_EXT: u32 = 0x00000080;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_HOST_MAPPED_FOREIGN_MEMORY_BIT_EXT: u32 = 0x00000100;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT: u32 = 0x00000200;
+pub const EXTERNAL_MEMORY_HANDLE_TYPE_ANDROID_HARDWARE_BUFFER_BIT_ANDROID: u32 = 0x00000400;
+pub type ExternalMemoryHandleTypeFlags = Flags;
+
+pub type ExternalMemoryFeatureFlagBits = u32;
+pub const EXTERNAL_MEMORY_FEATURE_DEDICATED_ONLY_BIT: u32 = 0x00000001;
+pub const EXTERNAL_MEMORY_FEATURE_EXPORTABLE_BIT: u32 = 0x00000002;
+pub const EXTERNAL_MEMORY_FEATURE_IMPORTABLE_BIT: u32 = 0x00000004;
+pub type ExternalMemoryFeatureFlags = Flags;
+
+#[repr(C)]
+pub struct ExternalMemoryProperties {
+    pub externalMemoryFeatures: ExternalMemoryFeatureFlags,
+    pub exportFromImportedHandleTypes: ExternalMemoryHandleTypeFlags,
+    pub compatibleHandleTypes: ExternalMemoryHandleTypeFlags,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceExternalImageFormatInfo {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleType: ExternalMemoryHandleTypeFlagBits,
+}
+
+#[repr(C)]
+pub struct ExternalImageFormatProperties {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub externalMemoryProperties: ExternalMemoryProperties,
+}
+
+#[repr(C)]
+pub struct ExternalMemoryBufferCreateInfo {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalMemoryHandleTypeFlags,
+}
+
+#[repr(C)]
+pub struct ExternalMemoryImageCreateInfo {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalMemoryHandleTypeFlags,
+}
+
+#[repr(C)]
+pub struct ExportMemoryAllocateInfo {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalMemoryHandleTypeFlags,
+}
+
+#[repr(C)]
+pub struct MemoryGetFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub memory: DeviceMemory,
+    pub handleType: ExternalMemoryHandleTypeFlagBits,
+}
+
+#[repr(C)]
+pub struct MemoryGetWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub memory: DeviceMemory,
+    pub handleType: ExternalMemoryHandleTypeFlagBits,
+}
+
+#[repr(C)]
+pub struct SamplerYcbcrConversionImageFormatProperties {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub combinedImageSamplerDescriptorCount: u32,
+}
+
+pub type SemaphoreImportFlagBitsKHR = u32;
+pub const SEMAPHORE_IMPORT_TEMPORARY_BIT_KHR: u32 = 0x00000001;
+pub type SemaphoreImportFlagsKHR = Flags;
+
+pub type PipelineStageFlagBits2KHR = u64;
+pub const PIPELINE_STAGE_2_NONE_KHR: u64 = 0;
+pub const PIPELINE_STAGE_2_TOP_OF_PIPE_BIT_KHR: u64 = 0x00000001;
+pub const PIPELINE_STAGE_2_DRAW_INDIRECT_BIT_KHR: u64 = 0x00000002;
+pub const PIPELINE_STAGE_2_VERTEX_INPUT_BIT_KHR: u64 = 0x00000004;
+pub const PIPELINE_STAGE_2_VERTEX_SHADER_BIT_KHR: u64 = 0x00000008;
+pub const PIPELINE_STAGE_2_TESSELLATION_CONTROL_SHADER_BIT_KHR: u64 = 0x00000010;
+pub const PIPELINE_STAGE_2_TESSELLATION_EVALUATION_SHADER_BIT_KHR: u64 = 0x00000020;
+pub const PIPELINE_STAGE_2_GEOMETRY_SHADER_BIT_KHR: u64 = 0x00000040;
+pub const PIPELINE_STAGE_2_FRAGMENT_SHADER_BIT_KHR: u64 = 0x00000080;
+pub const PIPELINE_STAGE_2_EARLY_FRAGMENT_TESTS_BIT_KHR: u64 = 0x00000100;
+pub const PIPELINE_STAGE_2_LATE_FRAGMENT_TESTS_BIT_KHR: u64 = 0x00000200;
+pub const PIPELINE_STAGE_2_COLOR_ATTACHMENT_OUTPUT_BIT_KHR: u64 = 0x00000400;
+pub const PIPELINE_STAGE_2_COMPUTE_SHADER_BIT_KHR: u64 = 0x00000800;
+pub const PIPELINE_STAGE_2_ALL_TRANSFER_BIT_KHR: u64 = 0x00001000;
+pub const PIPELINE_STAGE_2_BOTTOM_OF_PIPE_BIT_KHR: u64 = 0x00002000;
+pub const PIPELINE_STAGE_2_HOST_BIT_KHR: u64 = 0x00004000;
+pub const PIPELINE_STAGE_2_ALL_GRAPHICS_BIT_KHR: u64 = 0x00008000;
+pub const PIPELINE_STAGE_2_ALL_COMMANDS_BIT_KHR: u64 = 0x00010000;
+pub const PIPELINE_STAGE_2_TRANSFORM_FEEDBACK_BIT_EXT: u64 = 0x01000000;
+pub const PIPELINE_STAGE_2_CONDITIONAL_RENDERING_BIT_EXT: u64 = 0x00040000;
+pub const PIPELINE_STAGE_2_TASK_SHADER_BIT_EXT: u64 = 0x00080000;
+pub const PIPELINE_STAGE_2_MESH_SHADER_BIT_EXT: u64 = 0x00100000;
+pub const PIPELINE_STAGE_2_RAY_TRACING_SHADER_BIT_KHR: u64 = 0x00200000;
+pub const PIPELINE_STAGE_2_ACCELERATION_STRUCTURE_BUILD_BIT_KHR: u64 = 0x02000000;
+pub const PIPELINE_STAGE_2_COPY_BIT_KHR: u64 = 0x100000000;
+pub const PIPELINE_STAGE_2_RESOLVE_BIT_KHR: u64 = 0x200000000;
+pub const PIPELINE_STAGE_2_BLIT_BIT_KHR: u64 = 0x400000000;
+pub const PIPELINE_STAGE_2_CLEAR_BIT_KHR: u64 = 0x800000000;
+pub const PIPELINE_STAGE_2_INDEX_INPUT_BIT_KHR: u64 = 0x1000000000;
+pub const PIPELINE_STAGE_2_VERTEX_ATTRIBUTE_INPUT_BIT_KHR: u64 = 0x2000000000;
+pub const PIPELINE_STAGE_2_PRE_RASTERIZATION_SHADERS_BIT_KHR: u64 = 0x4000000000;
+pub type PipelineStageFlags2KHR = u64;
+
+pub type AccessFlagBits2KHR = u64;
+pub const ACCESS_2_NONE_KHR: u64 = 0;
+pub const ACCESS_2_INDIRECT_COMMAND_READ_BIT_KHR: u64 = 0x00000001;
+pub const ACCESS_2_INDEX_READ_BIT_KHR: u64 = 0x00000002;
+pub const ACCESS_2_VERTEX_ATTRIBUTE_READ_BIT_KHR: u64 = 0x00000004;
+pub const ACCESS_2_UNIFORM_READ_BIT_KHR: u64 = 0x00000008;
+pub const ACCESS_2_INPUT_ATTACHMENT_READ_BIT_KHR: u64 = 0x00000010;
+pub const ACCESS_2_SHADER_READ_BIT_KHR: u64 = 0x00000020;
+pub const ACCESS_2_SHADER_WRITE_BIT_KHR: u64 = 0x00000040;
+pub const ACCESS_2_COLOR_ATTACHMENT_READ_BIT_KHR: u64 = 0x00000080;
+pub const ACCESS_2_COLOR_ATTACHMENT_WRITE_BIT_KHR: u64 = 0x00000100;
+pub const ACCESS_2_DEPTH_STENCIL_ATTACHMENT_READ_BIT_KHR: u64 = 0x00000200;
+pub const ACCESS_2_DEPTH_STENCIL_ATTACHMENT_WRITE_BIT_KHR: u64 = 0x00000400;
+pub const ACCESS_2_TRANSFER_READ_BIT_KHR: u64 = 0x00000800;
+pub const ACCESS_2_TRANSFER_WRITE_BIT_KHR: u64 = 0x00001000;
+pub const ACCESS_2_HOST_READ_BIT_KHR: u64 = 0x00002000;
+pub const ACCESS_2_HOST_WRITE_BIT_KHR: u64 = 0x00004000;
+pub const ACCESS_2_MEMORY_READ_BIT_KHR: u64 = 0x00008000;
+pub const ACCESS_2_MEMORY_WRITE_BIT_KHR: u64 = 0x00010000;
+pub const ACCESS_2_CONDITIONAL_RENDERING_READ_BIT_EXT: u64 = 0x00100000;
+pub const ACCESS_2_ACCELERATION_STRUCTURE_READ_BIT_KHR: u64 = 0x00200000;
+pub const ACCESS_2_ACCELERATION_STRUCTURE_WRITE_BIT_KHR: u64 = 0x00400000;
+pub const ACCESS_2_TRANSFORM_FEEDBACK_WRITE_BIT_EXT: u64 = 0x02000000;
+pub const ACCESS_2_TRANSFORM_FEEDBACK_COUNTER_READ_BIT_EXT: u64 = 0x04000000;
+pub const ACCESS_2_TRANSFORM_FEEDBACK_COUNTER_WRITE_BIT_EXT: u64 = 0x08000000;
+pub const ACCESS_2_SHADER_SAMPLED_READ_BIT_KHR: u64 = 0x100000000;
+pub const ACCESS_2_SHADER_STORAGE_READ_BIT_KHR: u64 = 0x200000000;
+pub const ACCESS_2_SHADER_STORAGE_WRITE_BIT_KHR: u64 = 0x400000000;
+pub type AccessFlags2KHR = u64;
+
+pub type SubmitFlagBitsKHR = u32;
+pub const SUBMIT_PROTECTED_BIT_KHR: u32 = 0x00000001;
+pub type SubmitFlagsKHR = Flags;
+
+#[repr(C)]
+pub struct PhysicalDeviceSynchronization2FeaturesKHR {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub synchronization2: Bool32,
+}
+
+#[repr(C)]
+pub struct MemoryBarrier2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub srcStageMask: PipelineStageFlags2KHR,
+    pub srcAccessMask: AccessFlags2KHR,
+    pub dstStageMask: PipelineStageFlags2KHR,
+    pub dstAccessMask: AccessFlags2KHR,
+}
+
+#[repr(C)]
+pub struct BufferMemoryBarrier2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub srcStageMask: PipelineStageFlags2KHR,
+    pub srcAccessMask: AccessFlags2KHR,
+    pub dstStageMask: PipelineStageFlags2KHR,
+    pub dstAccessMask: AccessFlags2KHR,
+    pub srcQueueFamilyIndex: u32,
+    pub dstQueueFamilyIndex: u32,
+    pub buffer: Buffer,
+    pub offset: DeviceSize,
+    pub size: DeviceSize,
+}
+
+#[repr(C)]
+pub struct ImageMemoryBarrier2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub srcStageMask: PipelineStageFlags2KHR,
+    pub srcAccessMask: AccessFlags2KHR,
+    pub dstStageMask: PipelineStageFlags2KHR,
+    pub dstAccessMask: AccessFlags2KHR,
+    pub oldLayout: ImageLayout,
+    pub newLayout: ImageLayout,
+    pub srcQueueFamilyIndex: u32,
+    pub dstQueueFamilyIndex: u32,
+    pub image: Image,
+    pub subresourceRange: ImageSubresourceRange,
+}
+
+#[repr(C)]
+pub struct DependencyInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub dependencyFlags: DependencyFlags,
+    pub memoryBarrierCount: u32,
+    pub pMemoryBarriers: *const MemoryBarrier2KHR,
+    pub bufferMemoryBarrierCount: u32,
+    pub pBufferMemoryBarriers: *const BufferMemoryBarrier2KHR,
+    pub imageMemoryBarrierCount: u32,
+    pub pImageMemoryBarriers: *const ImageMemoryBarrier2KHR,
+}
+
+#[repr(C)]
+pub struct SemaphoreSubmitInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub value: u64,
+    pub stageMask: PipelineStageFlags2KHR,
+    pub deviceIndex: u32,
+}
+
+#[repr(C)]
+pub struct CommandBufferSubmitInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub commandBuffer: CommandBuffer,
+    pub deviceMask: u32,
+}
+
+#[repr(C)]
+pub struct SubmitInfo2KHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub flags: SubmitFlagsKHR,
+    pub waitSemaphoreInfoCount: u32,
+    pub pWaitSemaphoreInfos: *const SemaphoreSubmitInfoKHR,
+    pub commandBufferInfoCount: u32,
+    pub pCommandBufferInfos: *const CommandBufferSubmitInfoKHR,
+    pub signalSemaphoreInfoCount: u32,
+    pub pSignalSemaphoreInfos: *const SemaphoreSubmitInfoKHR,
+}
+
+#[repr(C)]
+pub struct ExportSemaphoreCreateInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub handleTypes: ExternalSemaphoreHandleTypeFlagsKHR,
+}
+
+#[repr(C)]
+pub struct ImportSemaphoreFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub flags: SemaphoreImportFlagsKHR,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+    pub fd: c_int,
+}
+
+#[repr(C)]
+pub struct SemaphoreGetFdInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+}
+
+#[repr(C)]
+pub struct ImportSemaphoreWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub flags: SemaphoreImportFlagsKHR,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+    pub handle: *mut c_void,
+    pub name: *const u16,
+}
+
+#[repr(C)]
+pub struct SemaphoreGetWin32HandleInfoKHR {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub semaphore: Semaphore,
+    pub handleType: ExternalSemaphoreHandleTypeFlagBitsKHR,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceMultiDrawFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub multiDraw: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceMultiDrawPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxMultiDrawCount: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct MultiDrawInfoEXT {
+    pub firstVertex: u32,
+    pub vertexCount: u32,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone)]
+pub struct MultiDrawIndexedInfoEXT {
+    pub firstIndex: u32,
+    pub indexCount: u32,
+    pub vertexOffset: i32,
+}
+
+pub type ConditionalRenderingFlagBitsEXT = u32;
+pub const CONDITIONAL_RENDERING_INVERTED_BIT_EXT: u32 = 0x00000001;
+pub type ConditionalRenderingFlagsEXT = Flags;
+
+#[repr(C)]
+pub struct ConditionalRenderingBeginInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub buffer: Buffer,
+    pub offset: DeviceSize,
+    pub flags: ConditionalRenderingFlagsEXT,
+}
+
+#[repr(C)]
+pub struct CommandBufferInheritanceConditionalRenderingInfoEXT {
+    pub sType: StructureType,
+    pub pNext: *const c_void,
+    pub conditionalRenderingEnable: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceConditionalRenderingFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub conditionalRendering: Bool32,
+    pub inheritedConditionalRendering: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceTransformFeedbackFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub transformFeedback: Bool32,
+    pub geometryStreams: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceDescriptorIndexingFeaturesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub shaderInputAttachmentArrayDynamicIndexing: Bool32,
+    pub shaderUniformTexelBufferArrayDynamicIndexing: Bool32,
+    pub shaderStorageTexelBufferArrayDynamicIndexing: Bool32,
+    pub shaderUniformBufferArrayNonUniformIndexing: Bool32,
+    pub shaderSampledImageArrayNonUniformIndexing: Bool32,
+    pub shaderStorageBufferArrayNonUniformIndexing: Bool32,
+    pub shaderStorageImageArrayNonUniformIndexing: Bool32,
+    pub shaderInputAttachmentArrayNonUniformIndexing: Bool32,
+    pub shaderUniformTexelBufferArrayNonUniformIndexing: Bool32,
+    pub shaderStorageTexelBufferArrayNonUniformIndexing: Bool32,
+    pub descriptorBindingUniformBufferUpdateAfterBind: Bool32,
+    pub descriptorBindingSampledImageUpdateAfterBind: Bool32,
+    pub descriptorBindingStorageImageUpdateAfterBind: Bool32,
+    pub descriptorBindingStorageBufferUpdateAfterBind: Bool32,
+    pub descriptorBindingUniformTexelBufferUpdateAfterBind: Bool32,
+    pub descriptorBindingStorageTexelBufferUpdateAfterBind: Bool32,
+    pub descriptorBindingUpdateUnusedWhilePending: Bool32,
+    pub descriptorBindingPartiallyBound: Bool32,
+    pub descriptorBindingVariableDescriptorCount: Bool32,
+    pub runtimeDescriptorArray: Bool32,
+}
+
+#[repr(C)]
+pub struct PhysicalDeviceTransformFeedbackPropertiesEXT {
+    pub sType: StructureType,
+    pub pNext: *mut c_void,
+    pub maxTransformFeedbackStreams: u32,
+    pub maxTransformFeedbackBuffers: u32,
+    pub maxTransformFeedbackBufferSize: DeviceSize,
+    pub maxTransformFeedbackStreamDataSize: u32,
+    pub maxTransformFeedbackBufferDataSize: u32,
+    pub maxTransformFeedbackBufferDataStride: u32,
+    pub transformFeedbackQueries: Bool32,
+    pub transformFeedbackStreamsLinesTriangles: Bool32,
+    pub transformFeedbackRasterizationStreamSelect: Bool32,
+    pub transformFeedbackDraw: Bool32,
+}
+
 macro_rules! ptrs {
     ($struct_name:ident, { $($name:ident => ($($param_n:ident: $param_ty:ty),*) -> $ret:ty,)+ }) => (
         pub struct $struct_name {
@@ -2707,8 +4251,15 @@ macro_rules! ptrs {
                     $(
                         $name: unsafe {
                             extern "system" fn $name($(_: $param_ty),*) { panic!("function pointer `{}` not loaded", stringify!($name)) }
-                            let name = CStr::from_bytes_with_nul_unchecked(concat!("vk", stringify!($name), "\0").as_bytes());
-                            let val = f(name);
+                            let name = concat!("vk", stringify!($name), "\0").as_bytes();
+                            let mut val = f(CStr::from_bytes_with_nul_unchecked(name));
+                            // Functions of extensions that were promoted to the core API are
+                            // also available without the `KHR` suffix.
+                            if val.is_null() && name.ends_with(b"KHR\0") {
+                                let mut core_name = name[.. name.len() - 4].to_vec();
+                                core_name.push(0);
+                                val = f(CStr::from_bytes_with_nul_unchecked(&core_name));
+                            }
                             if val.is_null() { mem::transmute($name as *const ()) } else { mem::transmute(val) }
                         },
                     )+
@@ -2734,6 +4285,7 @@ ptrs!(EntryPoints, {
     CreateInstance => (pCreateInfo: *const InstanceCreateInfo, pAllocator: *const AllocationCallbacks, pInstance: *mut Instance) -> Result,
     EnumerateInstanceExtensionProperties => (pLayerName: *const c_char, pPropertyCount: *mut u32, pProperties: *mut ExtensionProperties) -> Result,
     EnumerateInstanceLayerProperties => (pPropertyCount: *mut u32, pProperties: *mut LayerProperties) -> Result,
+    EnumerateInstanceVersion => (pApiVersion: *mut u32) -> Result,
 });
 
 ptrs!(InstancePointers, {
@@ -2776,6 +4328,9 @@ ptrs!(InstancePointers, {
     CreateDebugReportCallbackEXT => (instance: Instance, pCreateInfo: *const DebugReportCallbackCreateInfoEXT, pAllocator: *const AllocationCallbacks, pCallback: *mut DebugReportCallbackEXT) -> Result,
     DestroyDebugReportCallbackEXT => (instance: Instance, callback: DebugReportCallbackEXT, pAllocator: *const AllocationCallbacks) -> (),
     DebugReportMessageEXT => (instance: Instance, flags: DebugReportFlagsEXT, objectType: DebugReportObjectTypeEXT, object: u64, location: usize, messageCode: i32, pLayerPrefix: *const c_char, pMessage: *const c_char) -> (),
+    CreateDebugUtilsMessengerEXT => (instance: Instance, pCreateInfo: *const DebugUtilsMessengerCreateInfoEXT, pAllocator: *const AllocationCallbacks, pMessenger: *mut DebugUtilsMessengerEXT) -> Result,
+    DestroyDebugUtilsMessengerEXT => (instance: Instance, messenger: DebugUtilsMessengerEXT, pAllocator: *const AllocationCallbacks) -> (),
+    SubmitDebugUtilsMessageEXT => (instance: Instance, messageSeverity: DebugUtilsMessageSeverityFlagBitsEXT, messageTypes: DebugUtilsMessageTypeFlagsEXT, pCallbackData: *const DebugUtilsMessengerCallbackDataEXT) -> (),
     CreateIOSSurfaceMVK => (instance: Instance, pCreateInfo: *const IOSSurfaceCreateInfoMVK, pAllocator: *const AllocationCallbacks, pSurface: *mut SurfaceKHR) -> Result,
     CreateMacOSSurfaceMVK => (instance: Instance, pCreateInfo: *const MacOSSurfaceCreateInfoMVK, pAllocator: *const AllocationCallbacks, pSurface: *mut SurfaceKHR) -> Result,
     ActivateMoltenVKLicenseMVK => (licenseID: *const c_char, licenseKey: *const c_char, acceptLicenseTermsAndConditions: Bool32) -> Result,
@@ -2792,17 +4347,23 @@ ptrs!(InstancePointers, {
     GetPhysicalDeviceQueueFamilyProperties2KHR => (physicalDevice: PhysicalDevice, pQueueFamilyPropertiesCount: *mut u32, pQueueFamilyProperties: *mut QueueFamilyProperties2KHR) -> (),
     GetPhysicalDeviceMemoryProperties2KHR => (physicalDevice: PhysicalDevice, pMemoryProperties: *mut PhysicalDeviceMemoryProperties2KHR) -> (),
     GetPhysicalDeviceSparseImageFormatProperties2KHR => (physicalDevice: PhysicalDevice, pFormatInfo: *const PhysicalDeviceSparseImageFormatInfo2KHR, pPropertyCount: *mut u32, pProperties: *mut SparseImageFormatProperties2KHR) -> (),
+    EnumeratePhysicalDeviceGroupsKHR => (instance: Instance, pPhysicalDeviceGroupCount: *mut u32, pPhysicalDeviceGroupProperties: *mut PhysicalDeviceGroupPropertiesKHR) -> Result,
+    GetPhysicalDeviceToolPropertiesEXT => (physicalDevice: PhysicalDevice, pToolCount: *mut u32, pToolProperties: *mut PhysicalDeviceToolPropertiesEXT) -> Result,
 });
 
 ptrs!(DevicePointers, {
     DestroyDevice => (device: Device, pAllocator: *const AllocationCallbacks) -> (),
     GetDeviceQueue => (device: Device, queueFamilyIndex: u32, queueIndex: u32, pQueue: *mut Queue) -> (),
+    GetDeviceQueue2 => (device: Device, pQueueInfo: *const DeviceQueueInfo2, pQueue: *mut Queue) -> (),
     QueueSubmit => (queue: Queue, submitCount: u32, pSubmits: *const SubmitInfo, fence: Fence) -> Result,
+    QueueSubmit2KHR => (queue: Queue, submitCount: u32, pSubmits: *const SubmitInfo2KHR, fence: Fence) -> Result,
     QueueWaitIdle => (queue: Queue) -> Result,
     DeviceWaitIdle => (device: Device) -> Result,
     AllocateMemory => (device: Device, pAllocateInfo: *const MemoryAllocateInfo, pAllocator: *const AllocationCallbacks, pMemory: *mut DeviceMemory) -> Result,
     FreeMemory => (device: Device, memory: DeviceMemory, pAllocator: *const AllocationCallbacks) -> (),
     MapMemory => (device: Device, memory: DeviceMemory, offset: DeviceSize, size: DeviceSize, flags: MemoryMapFlags, ppData: *mut *mut c_void) -> Result,
+    GetMemoryFdKHR => (device: Device, pGetFdInfo: *const MemoryGetFdInfoKHR, pFd: *mut c_int) -> Result,
+    GetMemoryWin32HandleKHR => (device: Device, pGetWin32HandleInfo: *const MemoryGetWin32HandleInfoKHR, pHandle: *mut *mut c_void) -> Result,
     UnmapMemory => (device: Device, memory: DeviceMemory) -> (),
     FlushMappedMemoryRanges => (device: Device, memoryRangeCount: u32, pMemoryRanges: *const MappedMemoryRange) -> Result,
     InvalidateMappedMemoryRanges => (device: Device, memoryRangeCount: u32, pMemoryRanges: *const MappedMemoryRange) -> Result,
@@ -2820,6 +4381,13 @@ ptrs!(DevicePointers, {
     WaitForFences => (device: Device, fenceCount: u32, pFences: *const Fence, waitAll: Bool32, timeout: u64) -> Result,
     CreateSemaphore => (device: Device, pCreateInfo: *const SemaphoreCreateInfo, pAllocator: *const AllocationCallbacks, pSemaphore: *mut Semaphore) -> Result,
     DestroySemaphore => (device: Device, semaphore: Semaphore, pAllocator: *const AllocationCallbacks) -> (),
+    GetSemaphoreCounterValueKHR => (device: Device, semaphore: Semaphore, pValue: *mut u64) -> Result,
+    WaitSemaphoresKHR => (device: Device, pWaitInfo: *const SemaphoreWaitInfoKHR, timeout: u64) -> Result,
+    SignalSemaphoreKHR => (device: Device, pSignalInfo: *const SemaphoreSignalInfoKHR) -> Result,
+    ImportSemaphoreFdKHR => (device: Device, pImportSemaphoreFdInfo: *const ImportSemaphoreFdInfoKHR) -> Result,
+    GetSemaphoreFdKHR => (device: Device, pGetFdInfo: *const SemaphoreGetFdInfoKHR, pFd: *mut c_int) -> Result,
+    ImportSemaphoreWin32HandleKHR => (device: Device, pImportSemaphoreWin32HandleInfo: *const ImportSemaphoreWin32HandleInfoKHR) -> Result,
+    GetSemaphoreWin32HandleKHR => (device: Device, pGetWin32HandleInfo: *const SemaphoreGetWin32HandleInfoKHR, pHandle: *mut *mut c_void) -> Result,
     CreateEvent => (device: Device, pCreateInfo: *const EventCreateInfo, pAllocator: *const AllocationCallbacks, pEvent: *mut Event) -> Result,
     DestroyEvent => (device: Device, event: Event, pAllocator: *const AllocationCallbacks) -> (),
     GetEventStatus => (device: Device, event: Event) -> Result,
@@ -2890,6 +4458,9 @@ ptrs!(DevicePointers, {
     CmdDrawIndirect => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, drawCount: u32, stride: u32) -> (),
     CmdDrawIndexedIndirect => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, drawCount: u32, stride: u32) -> (),
     CmdDispatch => (commandBuffer: CommandBuffer, x: u32, y: u32, z: u32) -> (),
+    CmdDispatchBaseKHR => (commandBuffer: CommandBuffer, baseGroupX: u32, baseGroupY: u32, baseGroupZ: u32, groupCountX: u32, groupCountY: u32, groupCountZ: u32) -> (),
+    CmdSetDeviceMaskKHR => (commandBuffer: CommandBuffer, deviceMask: u32) -> (),
+    GetDeviceGroupPeerMemoryFeaturesKHR => (device: Device, heapIndex: u32, localDeviceIndex: u32, remoteDeviceIndex: u32, pPeerMemoryFeatures: *mut PeerMemoryFeatureFlagsKHR) -> (),
     CmdDispatchIndirect => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize) -> (),
     CmdCopyBuffer => (commandBuffer: CommandBuffer, srcBuffer: Buffer, dstBuffer: Buffer, regionCount: u32, pRegions: *const BufferCopy) -> (),
     CmdCopyImage => (commandBuffer: CommandBuffer, srcImage: Image, srcImageLayout: ImageLayout, dstImage: Image, dstImageLayout: ImageLayout, regionCount: u32, pRegions: *const ImageCopy) -> (),
@@ -2906,6 +4477,7 @@ ptrs!(DevicePointers, {
     CmdResetEvent => (commandBuffer: CommandBuffer, event: Event, stageMask: PipelineStageFlags) -> (),
     CmdWaitEvents => (commandBuffer: CommandBuffer, eventCount: u32, pEvents: *const Event, srcStageMask: PipelineStageFlags, dstStageMask: PipelineStageFlags, memoryBarrierCount: u32, pMemoryBarriers: *const MemoryBarrier, bufferMemoryBarrierCount: u32, pBufferMemoryBarriers: *const BufferMemoryBarrier, imageMemoryBarrierCount: u32, pImageMemoryBarriers: *const ImageMemoryBarrier) -> (),
     CmdPipelineBarrier => (commandBuffer: CommandBuffer, srcStageMask: PipelineStageFlags, dstStageMask: PipelineStageFlags, dependencyFlags: DependencyFlags, memoryBarrierCount: u32, pMemoryBarriers: *const MemoryBarrier, bufferMemoryBarrierCount: u32, pBufferMemoryBarriers: *const BufferMemoryBarrier, imageMemoryBarrierCount: u32, pImageMemoryBarriers: *const ImageMemoryBarrier) -> (),
+    CmdPipelineBarrier2KHR => (commandBuffer: CommandBuffer, pDependencyInfo: *const DependencyInfoKHR) -> (),
     CmdBeginQuery => (commandBuffer: CommandBuffer, queryPool: QueryPool, query: u32, flags: QueryControlFlags) -> (),
     CmdEndQuery => (commandBuffer: CommandBuffer, queryPool: QueryPool, query: u32) -> (),
     CmdResetQueryPool => (commandBuffer: CommandBuffer, queryPool: QueryPool, firstQuery: u32, queryCount: u32) -> (),
@@ -2934,4 +4506,50 @@ ptrs!(DevicePointers, {
     CmdDebugMarkerBeginEXT => (commandBuffer: CommandBuffer, pMarkerInfo: *const DebugMarkerMarkerInfoEXT) -> (),
     CmdDebugMarkerEndEXT => (commandBuffer: CommandBuffer) -> (),
     CmdDebugMarkerInsertEXT => (commandBuffer: CommandBuffer, pMarkerInfo: *const DebugMarkerMarkerInfoEXT) -> (),
+    SetDebugUtilsObjectNameEXT => (device: Device, pNameInfo: *const DebugUtilsObjectNameInfoEXT) -> Result,
+    QueueBeginDebugUtilsLabelEXT => (queue: Queue, pLabelInfo: *const DebugUtilsLabelEXT) -> (),
+    QueueEndDebugUtilsLabelEXT => (queue: Queue) -> (),
+    QueueInsertDebugUtilsLabelEXT => (queue: Queue, pLabelInfo: *const DebugUtilsLabelEXT) -> (),
+    CmdBeginDebugUtilsLabelEXT => (commandBuffer: CommandBuffer, pLabelInfo: *const DebugUtilsLabelEXT) -> (),
+    CmdEndDebugUtilsLabelEXT => (commandBuffer: CommandBuffer) -> (),
+    CmdInsertDebugUtilsLabelEXT => (commandBuffer: CommandBuffer, pLabelInfo: *const DebugUtilsLabelEXT) -> (),
+    CmdSetCheckpointNV => (commandBuffer: CommandBuffer, pCheckpointMarker: *const c_void) -> (),
+    GetQueueCheckpointDataNV => (queue: Queue, pCheckpointDataCount: *mut u32, pCheckpointData: *mut CheckpointDataNV) -> (),
+    CmdWriteBufferMarkerAMD => (commandBuffer: CommandBuffer, pipelineStage: PipelineStageFlagBits, dstBuffer: Buffer, dstOffset: DeviceSize, marker: u32) -> (),
+    CmdSetCullModeEXT => (commandBuffer: CommandBuffer, cullMode: CullModeFlags) -> (),
+    CmdSetFrontFaceEXT => (commandBuffer: CommandBuffer, frontFace: FrontFace) -> (),
+    CmdSetPrimitiveTopologyEXT => (commandBuffer: CommandBuffer, primitiveTopology: PrimitiveTopology) -> (),
+    CmdSetViewportWithCountEXT => (commandBuffer: CommandBuffer, viewportCount: u32, pViewports: *const Viewport) -> (),
+    CmdSetScissorWithCountEXT => (commandBuffer: CommandBuffer, scissorCount: u32, pScissors: *const Rect2D) -> (),
+    CmdBindVertexBuffers2EXT => (commandBuffer: CommandBuffer, firstBinding: u32, bindingCount: u32, pBuffers: *const Buffer, pOffsets: *const DeviceSize, pSizes: *const DeviceSize, pStrides: *const DeviceSize) -> (),
+    CmdSetDepthTestEnableEXT => (commandBuffer: CommandBuffer, depthTestEnable: Bool32) -> (),
+    CmdSetDepthWriteEnableEXT => (commandBuffer: CommandBuffer, depthWriteEnable: Bool32) -> (),
+    CmdSetDepthCompareOpEXT => (commandBuffer: CommandBuffer, depthCompareOp: CompareOp) -> (),
+    CmdSetDepthBoundsTestEnableEXT => (commandBuffer: CommandBuffer, depthBoundsTestEnable: Bool32) -> (),
+    CmdSetStencilTestEnableEXT => (commandBuffer: CommandBuffer, stencilTestEnable: Bool32) -> (),
+    CmdSetStencilOpEXT => (commandBuffer: CommandBuffer, faceMask: StencilFaceFlags, failOp: StencilOp, passOp: StencilOp, depthFailOp: StencilOp, compareOp: CompareOp) -> (),
+    CmdSetLineStippleEXT => (commandBuffer: CommandBuffer, lineStippleFactor: u32, lineStipplePattern: u16) -> (),
+    CmdDrawMeshTasksEXT => (commandBuffer: CommandBuffer, groupCountX: u32, groupCountY: u32, groupCountZ: u32) -> (),
+    CmdDrawMeshTasksIndirectEXT => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, drawCount: u32, stride: u32) -> (),
+    CmdDrawIndirectCountKHR => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, countBuffer: Buffer, countBufferOffset: DeviceSize, maxDrawCount: u32, stride: u32) -> (),
+    CmdDrawIndexedIndirectCountKHR => (commandBuffer: CommandBuffer, buffer: Buffer, offset: DeviceSize, countBuffer: Buffer, countBufferOffset: DeviceSize, maxDrawCount: u32, stride: u32) -> (),
+    CmdDrawMultiEXT => (commandBuffer: CommandBuffer, drawCount: u32, pVertexInfo: *const MultiDrawInfoEXT, instanceCount: u32, firstInstance: u32, stride: u32) -> (),
+    CmdDrawMultiIndexedEXT => (commandBuffer: CommandBuffer, drawCount: u32, pIndexInfo: *const MultiDrawIndexedInfoEXT, instanceCount: u32, firstInstance: u32, stride: u32, pVertexOffset: *const i32) -> (),
+    CmdBeginConditionalRenderingEXT => (commandBuffer: CommandBuffer, pConditionalRenderingBegin: *const ConditionalRenderingBeginInfoEXT) -> (),
+    CmdEndConditionalRenderingEXT => (commandBuffer: CommandBuffer) -> (),
+    CmdBindTransformFeedbackBuffersEXT => (commandBuffer: CommandBuffer, firstBinding: u32, bindingCount: u32, pBuffers: *const Buffer, pOffsets: *const DeviceSize, pSizes: *const DeviceSize) -> (),
+    CmdBeginTransformFeedbackEXT => (commandBuffer: CommandBuffer, firstCounterBuffer: u32, counterBufferCount: u32, pCounterBuffers: *const Buffer, pCounterBufferOffsets: *const DeviceSize) -> (),
+    CmdEndTransformFeedbackEXT => (commandBuffer: CommandBuffer, firstCounterBuffer: u32, counterBufferCount: u32, pCounterBuffers: *const Buffer, pCounterBufferOffsets: *const DeviceSize) -> (),
+    CmdDrawIndirectByteCountEXT => (commandBuffer: CommandBuffer, instanceCount: u32, firstInstance: u32, counterBuffer: Buffer, counterBufferOffset: DeviceSize, counterOffset: u32, vertexStride: u32) -> (),
+    GetBufferDeviceAddressKHR => (device: Device, pInfo: *const BufferDeviceAddressInfoKHR) -> DeviceAddress,
+    CreateAccelerationStructureKHR => (device: Device, pCreateInfo: *const AccelerationStructureCreateInfoKHR, pAllocator: *const AllocationCallbacks, pAccelerationStructure: *mut AccelerationStructureKHR) -> Result,
+    DestroyAccelerationStructureKHR => (device: Device, accelerationStructure: AccelerationStructureKHR, pAllocator: *const AllocationCallbacks) -> (),
+    GetAccelerationStructureBuildSizesKHR => (device: Device, buildType: AccelerationStructureBuildTypeKHR, pBuildInfo: *const AccelerationStructureBuildGeometryInfoKHR, pMaxPrimitiveCounts: *const u32, pSizeInfo: *mut AccelerationStructureBuildSizesInfoKHR) -> (),
+    GetAccelerationStructureDeviceAddressKHR => (device: Device, pInfo: *const AccelerationStructureDeviceAddressInfoKHR) -> DeviceAddress,
+    CmdBuildAccelerationStructuresKHR => (commandBuffer: CommandBuffer, infoCount: u32, pInfos: *const AccelerationStructureBuildGeometryInfoKHR, ppBuildRangeInfos: *const *const AccelerationStructureBuildRangeInfoKHR) -> (),
+    CmdCopyAccelerationStructureKHR => (commandBuffer: CommandBuffer, pInfo: *const CopyAccelerationStructureInfoKHR) -> (),
+    CmdWriteAccelerationStructuresPropertiesKHR => (commandBuffer: CommandBuffer, accelerationStructureCount: u32, pAccelerationStructures: *const AccelerationStructureKHR, queryType: QueryType, queryPool: QueryPool, firstQuery: u32) -> (),
+    CreateRayTracingPipelinesKHR => (device: Device, deferredOperation: DeferredOperationKHR, pipelineCache: PipelineCache, createInfoCount: u32, pCreateInfos: *const RayTracingPipelineCreateInfoKHR, pAllocator: *const AllocationCallbacks, pPipelines: *mut Pipeline) -> Result,
+    GetRayTracingShaderGroupHandlesKHR => (device: Device, pipeline: Pipeline, firstGroup: u32, groupCount: u32, dataSize: usize, pData: *mut c_void) -> Result,
+    CmdTraceRaysKHR => (commandBuffer: CommandBuffer, pRaygenShaderBindingTable: *const StridedDeviceAddressRegionKHR, pMissShaderBindingTable: *const StridedDeviceAddressRegionKHR, pHitShaderBindingTable: *const StridedDeviceAddressRegionKHR, pCallableShaderBindingTable: *const StridedDeviceAddressRegionKHR, width: u32, height: u32, depth: u32) -> (),
 });
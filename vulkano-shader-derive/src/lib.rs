@@ -51,7 +51,8 @@
 //! `Arc<ShaderModule>`.
 //! * The `Shader::load` constructor. This method takes an `Arc<Device>`, calls
 //! [`ShaderModule::new`][ShaderModule::new] with the passed-in device and the
-//! shader data provided via the macro, and returns `Result<Shader, OomError>`.
+//! shader data provided via the macro, and returns
+//! `Result<Shader, ShaderModuleCreationError>`.
 //! Before doing so, it loops through every capability instruction in the shader
 //! data, verifying that the passed-in `Device` has the appropriate features
 //! enabled. **This function currently panics if a feature required by the shader
@@ -82,8 +83,8 @@
 //! # extern crate vulkano;
 //! # fn main() {}
 //! # use std::sync::Arc;
-//! # use vulkano::OomError;
 //! # use vulkano::device::Device;
+//! # use vulkano::pipeline::shader::ShaderModuleCreationError;
 //! #
 //! # #[allow(unused)]
 //! # mod vertex_shader {
@@ -108,7 +109,7 @@
 //! }
 //! 
 //! impl Shaders {
-//!     pub fn load(device: Arc<Device>) -> Result<Self, OomError> {
+//!     pub fn load(device: Arc<Device>) -> Result<Self, ShaderModuleCreationError> {
 //!         Ok(Self {
 //!             vertex_shader: vertex_shader::Shader::load(device)?,
 //!         })
@@ -144,10 +145,43 @@
 //! Provides the path to the GLSL source to be compiled, relative to `Cargo.toml`.
 //! Cannot be used in conjunction with the `#[src]` attribute.
 //! 
+//! # Specialization constants
+//!
+//! This crate also provides a `SpecializationConstants` derive, which implements the
+//! [`SpecializationConstants`][SpecializationConstants] trait on a struct of your own. This is
+//! useful when a shader is loaded at runtime, or when the same specialization constants are shared
+//! between multiple shaders. Each field must have a `#[constant_id = N]` attribute, where `N` is
+//! the value of `constant_id` in the shader.
+//!
+//! ```
+//! #[macro_use]
+//! extern crate vulkano_shader_derive;
+//! extern crate vulkano;
+//! # fn main() {
+//! use vulkano::pipeline::shader::SpecializationConstants;
+//!
+//! #[derive(SpecializationConstants)]
+//! #[repr(C)]
+//! struct MySpecConstants {
+//!     #[constant_id = 0]
+//!     workgroup_size: u32,
+//!     #[constant_id = 3]
+//!     scale: f32,
+//! }
+//!
+//! let descriptors = MySpecConstants::descriptors();
+//! assert_eq!((descriptors[1].constant_id, descriptors[1].offset), (3, 4));
+//! # }
+//! ```
+//!
+//! The layout of the struct is checked at compile time: it must be `#[repr(C)]`, its fields must
+//! be integers or floating-point numbers, and each `constant_id` can only be used once. Booleans
+//! must be stored as a `u32`, as the Rust `bool` type doesn't have the same size as a SPIR-V
+//! boolean.
+//!
 //! [reflect]: https://github.com/vulkano-rs/vulkano/blob/master/vulkano-shaders/src/lib.rs#L67
 //! [cargo-expand]: https://github.com/dtolnay/cargo-expand
 //! [ShaderModule::new]: https://docs.rs/vulkano/*/vulkano/pipeline/shader/struct.ShaderModule.html#method.new
-//! [OomError]: https://docs.rs/vulkano/*/vulkano/enum.OomError.html
 //! [pipeline::shader]: https://docs.rs/vulkano/*/vulkano/pipeline/shader/index.html
 //! [descriptor]: https://docs.rs/vulkano/*/vulkano/descriptor/index.html
 //! [ShaderStages]: https://docs.rs/vulkano/*/vulkano/descriptor/descriptor/struct.ShaderStages.html
@@ -251,3 +285,183 @@ pub fn derive(input: TokenStream) -> TokenStream {
 
     vulkano_shaders::reflect("Shader", spirv_data).unwrap().parse().unwrap()
 }
+
+/// Implements `SpecializationConstants` on a struct. See the crate documentation for details.
+///
+/// The layout of the struct is checked at compile time. A struct that isn't `#[repr(C)]` is
+/// rejected:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate vulkano_shader_derive;
+/// # extern crate vulkano;
+/// # fn main() {}
+/// #[derive(SpecializationConstants)]
+/// struct NotReprC {
+///     #[constant_id = 0]
+///     a: u32,
+/// }
+/// ```
+///
+/// So is a field that isn't a scalar:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate vulkano_shader_derive;
+/// # extern crate vulkano;
+/// # fn main() {}
+/// #[derive(SpecializationConstants)]
+/// #[repr(C)]
+/// struct NotScalar {
+///     #[constant_id = 0]
+///     a: [u32; 2],
+/// }
+/// ```
+///
+/// A `bool`, which must be stored as a `u32` instead:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate vulkano_shader_derive;
+/// # extern crate vulkano;
+/// # fn main() {}
+/// #[derive(SpecializationConstants)]
+/// #[repr(C)]
+/// struct Bool {
+///     #[constant_id = 0]
+///     a: bool,
+/// }
+/// ```
+///
+/// A `constant_id` that is used twice:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate vulkano_shader_derive;
+/// # extern crate vulkano;
+/// # fn main() {}
+/// #[derive(SpecializationConstants)]
+/// #[repr(C)]
+/// struct Duplicate {
+///     #[constant_id = 1]
+///     a: u32,
+///     #[constant_id = 1]
+///     b: f32,
+/// }
+/// ```
+///
+/// And a field without a `constant_id`:
+///
+/// ```compile_fail
+/// # #[macro_use] extern crate vulkano_shader_derive;
+/// # extern crate vulkano;
+/// # fn main() {}
+/// #[derive(SpecializationConstants)]
+/// #[repr(C)]
+/// struct Missing {
+///     #[constant_id = 0]
+///     a: u32,
+///     b: f32,
+/// }
+/// ```
+#[proc_macro_derive(SpecializationConstants, attributes(constant_id))]
+pub fn derive_specialization_constants(input: TokenStream) -> TokenStream {
+    let syn_item: syn::DeriveInput = syn::parse(input).unwrap();
+    let name = syn_item.ident.to_string();
+
+    if !syn_item.generics.params.is_empty() {
+        panic!("Can't derive `SpecializationConstants` on the generic struct `{}`", name);
+    }
+
+    let is_repr_c = syn_item.attrs.iter().filter_map(|attr| attr.interpret_meta()).any(|meta| {
+        match meta {
+            syn::Meta::List(syn::MetaList { ident, nested, .. }) => {
+                ident == "repr" && nested.iter().any(|nested| match *nested {
+                    syn::NestedMeta::Meta(syn::Meta::Word(ref word)) => word == "C",
+                    _ => false,
+                })
+            },
+            _ => false,
+        }
+    });
+
+    if !is_repr_c {
+        panic!("`{}` must be `#[repr(C)]` in order to derive `SpecializationConstants`", name);
+    }
+
+    let fields = match syn_item.data {
+        syn::Data::Struct(syn::DataStruct { fields: syn::Fields::Named(ref fields), .. }) => {
+            &fields.named
+        },
+        _ => panic!("`SpecializationConstants` can only be derived on a struct with named fields"),
+    };
+
+    let mut entries = Vec::new();
+
+    for field in fields.iter() {
+        let field_name = field.ident.as_ref().unwrap().to_string();
+
+        let constant_id = field.attrs.iter().filter_map(|attr| {
+            attr.interpret_meta().and_then(|meta| {
+                match meta {
+                    syn::Meta::NameValue(syn::MetaNameValue {
+                        ident, lit: syn::Lit::Int(lit), ..
+                    }) if ident == "constant_id" => Some(lit.value()),
+                    _ => None,
+                }
+            })
+        }).next().unwrap_or_else(|| {
+            panic!("The field `{}` has no `constant_id` attribute ; put #[constant_id = 0] for \
+                    example", field_name)
+        });
+
+        if constant_id > u32::max_value() as u64 {
+            panic!("The `constant_id` of the field `{}` doesn't fit in 32 bits", field_name);
+        }
+
+        // SPIR-V specialization constants can only be scalars.
+        let ty = match field.ty {
+            syn::Type::Path(syn::TypePath { ref path, .. }) => {
+                path.segments.iter().last().map(|segment| segment.ident.to_string())
+            },
+            _ => None,
+        };
+        match ty.as_ref().map(|ty| &ty[..]) {
+            Some("u8") | Some("i8") | Some("u16") | Some("i16") | Some("u32") | Some("i32") |
+            Some("u64") | Some("i64") | Some("f16") | Some("f32") | Some("f64") => (),
+            Some("bool") => {
+                panic!("The field `{}` is a `bool` ; use a `u32` instead, as SPIR-V booleans are \
+                        32 bits wide", field_name)
+            },
+            _ => panic!("The field `{}` must be an integer or a floating-point number", field_name),
+        }
+
+        if entries.iter().any(|&(_, id)| id == constant_id) {
+            panic!("The `constant_id` {} is used by multiple fields", constant_id);
+        }
+
+        entries.push((field_name, constant_id));
+    }
+
+    let entries = entries.iter().map(|&(ref field_name, constant_id)| {
+        format!(r#"
+                    SpecializationMapEntry {{
+                        constant_id: {constant_id},
+                        offset: ::std::mem::offset_of!({name}, {field}) as u32,
+                        size: ::vulkano::pipeline::shader::specialization_constant_size(
+                            |c: &{name}| &c.{field}),
+                    }},"#,
+                name = name, field = field_name, constant_id = constant_id)
+    }).collect::<Vec<_>>().concat();
+
+    format!(r#"
+        #[allow(unsafe_code)]
+        unsafe impl ::vulkano::pipeline::shader::SpecializationConstants for {name} {{
+            #[inline]
+            fn descriptors() -> &'static [::vulkano::pipeline::shader::SpecializationMapEntry] {{
+                use ::vulkano::pipeline::shader::SpecializationMapEntry;
+
+                static DESCRIPTORS: &'static [SpecializationMapEntry] = &[{entries}
+                ];
+
+                DESCRIPTORS
+            }}
+        }}
+    "#, name = name, entries = entries).parse().unwrap()
+}
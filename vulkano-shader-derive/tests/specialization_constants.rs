@@ -0,0 +1,68 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+#[macro_use]
+extern crate vulkano_shader_derive;
+extern crate vulkano;
+
+use vulkano::pipeline::shader::SpecializationConstants;
+
+#[derive(SpecializationConstants)]
+#[repr(C)]
+#[allow(dead_code)]
+struct Scalars {
+    #[constant_id = 5]
+    a: u8,
+    #[constant_id = 1]
+    b: i16,
+    #[constant_id = 0]
+    c: u32,
+    #[constant_id = 2]
+    d: f64,
+    #[constant_id = 7]
+    e: i8,
+}
+
+#[derive(SpecializationConstants)]
+#[repr(C)]
+#[allow(dead_code)]
+struct WithBool {
+    // A SPIR-V boolean, stored as a `u32`.
+    #[constant_id = 0]
+    enabled: u32,
+    #[constant_id = 1]
+    scale: f32,
+}
+
+#[derive(SpecializationConstants)]
+#[repr(C)]
+struct Empty {}
+
+#[test]
+fn scalars() {
+    let descriptors = Scalars::descriptors();
+    let descriptors = descriptors.iter()
+        .map(|d| (d.constant_id, d.offset, d.size))
+        .collect::<Vec<_>>();
+    assert_eq!(descriptors, [(5, 0, 1), (1, 2, 2), (0, 4, 4), (2, 8, 8), (7, 16, 1)]);
+}
+
+#[test]
+fn bool_as_u32() {
+    let descriptors = WithBool::descriptors();
+    let descriptors = descriptors.iter()
+        .map(|d| (d.constant_id, d.offset, d.size))
+        .collect::<Vec<_>>();
+    assert_eq!(descriptors, [(0, 0, 4), (1, 4, 4)]);
+}
+
+#[test]
+fn empty() {
+    assert!(Empty::descriptors().is_empty());
+}
@@ -340,6 +340,11 @@ fn descriptor_infos(doc: &parse::Spirv, pointed_ty: u32, force_combined_image_sa
                 Some((desc, true, 1))
             },
 
+            &parse::Instruction::TypeAccelerationStructure { result_id } if result_id == pointed_ty => {
+                let desc = format!("DescriptorDescTy::AccelerationStructure");
+                Some((desc, true, 1))
+            },
+
             &parse::Instruction::TypeArray { result_id, type_id, length_id } if result_id == pointed_ty => {
                 let (desc, readonly, arr) = match descriptor_infos(doc, type_id, false) {
                     None => return None,
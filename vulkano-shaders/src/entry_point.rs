@@ -51,6 +51,7 @@ pub fn write_entry_point(doc: &parse::Spirv, instruction: &parse::Instruction) -
                                 match *execution {
                                     enums::ExecutionModel::ExecutionModelTessellationControl =>
                                         true,
+                                    enums::ExecutionModel::ExecutionModelMeshEXT => true,
                                     _ => false,
                                 });
 
@@ -60,12 +61,25 @@ pub fn write_entry_point(doc: &parse::Spirv, instruction: &parse::Instruction) -
         "()"
     };
 
+    // Compute and ray tracing shaders have no interface, so they share the same entry point type.
+    let compute_like_stage = match *execution {
+        enums::ExecutionModel::ExecutionModelGLCompute => Some("compute"),
+        enums::ExecutionModel::ExecutionModelRayGenerationKHR => Some("raygen"),
+        enums::ExecutionModel::ExecutionModelIntersectionKHR => Some("intersection"),
+        enums::ExecutionModel::ExecutionModelAnyHitKHR => Some("any_hit"),
+        enums::ExecutionModel::ExecutionModelClosestHitKHR => Some("closest_hit"),
+        enums::ExecutionModel::ExecutionModelMissKHR => Some("miss"),
+        enums::ExecutionModel::ExecutionModelCallableKHR => Some("callable"),
+        _ => None,
+    };
+
     let (ty, f_call) = {
-        if let enums::ExecutionModel::ExecutionModelGLCompute = *execution {
+        if let Some(stage) = compute_like_stage {
             (format!("::vulkano::pipeline::shader::ComputeEntryPoint<{}, Layout>",
                      spec_consts_struct),
              format!("compute_entry_point(::std::ffi::CStr::from_ptr(NAME.as_ptr() as *const _), \
-                      Layout(ShaderStages {{ compute: true, .. ShaderStages::none() }}))"))
+                      Layout(ShaderStages {{ {}: true, .. ShaderStages::none() }}))",
+                     stage))
 
         } else {
             let ty = match *execution {
@@ -124,11 +138,17 @@ pub fn write_entry_point(doc: &parse::Spirv, instruction: &parse::Instruction) -
                     "::vulkano::pipeline::shader::GraphicsShaderType::Fragment".to_owned()
                 },
 
-                enums::ExecutionModel::ExecutionModelGLCompute => {
-                    unreachable!()
+                enums::ExecutionModel::ExecutionModelTaskEXT => {
+                    "::vulkano::pipeline::shader::GraphicsShaderType::Task".to_owned()
+                },
+
+                enums::ExecutionModel::ExecutionModelMeshEXT => {
+                    "::vulkano::pipeline::shader::GraphicsShaderType::Mesh".to_owned()
                 },
 
                 enums::ExecutionModel::ExecutionModelKernel => panic!("Kernels are not supported"),
+
+                _ => unreachable!(),
             };
 
             let stage = match *execution {
@@ -147,8 +167,13 @@ pub fn write_entry_point(doc: &parse::Spirv, instruction: &parse::Instruction) -
                 enums::ExecutionModel::ExecutionModelFragment => {
                     "ShaderStages { fragment: true, .. ShaderStages::none() }"
                 },
-                enums::ExecutionModel::ExecutionModelGLCompute => unreachable!(),
-                enums::ExecutionModel::ExecutionModelKernel => unreachable!(),
+                enums::ExecutionModel::ExecutionModelTaskEXT => {
+                    "ShaderStages { task: true, .. ShaderStages::none() }"
+                },
+                enums::ExecutionModel::ExecutionModelMeshEXT => {
+                    "ShaderStages { mesh: true, .. ShaderStages::none() }"
+                },
+                _ => unreachable!(),
             };
 
             let t = format!("::vulkano::pipeline::shader::GraphicsEntryPoint<{0}, {1}Input, \
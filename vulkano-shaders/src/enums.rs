@@ -53,6 +53,14 @@ enumeration! {
         ExecutionModelFragment = 4,
         ExecutionModelGLCompute = 5,
         ExecutionModelKernel = 6,
+        ExecutionModelRayGenerationKHR = 5313,
+        ExecutionModelIntersectionKHR = 5314,
+        ExecutionModelAnyHitKHR = 5315,
+        ExecutionModelClosestHitKHR = 5316,
+        ExecutionModelMissKHR = 5317,
+        ExecutionModelCallableKHR = 5318,
+        ExecutionModelTaskEXT = 5364,
+        ExecutionModelMeshEXT = 5365,
     } ExecutionModel;
 
     typedef enum AddressingModel_ {
@@ -114,6 +122,14 @@ enumeration! {
         StorageClassPushConstant = 9,
         StorageClassAtomicCounter = 10,
         StorageClassImage = 11,
+        StorageClassStorageBuffer = 12,
+        StorageClassCallableDataKHR = 5328,
+        StorageClassIncomingCallableDataKHR = 5329,
+        StorageClassRayPayloadKHR = 5338,
+        StorageClassHitAttributeKHR = 5339,
+        StorageClassIncomingRayPayloadKHR = 5342,
+        StorageClassShaderRecordBufferKHR = 5343,
+        StorageClassPhysicalStorageBuffer = 5349,
     } StorageClass;
 
     typedef enum Dim_ {
@@ -546,5 +562,13 @@ enumeration! {
         CapabilityStorageImageReadWithoutFormat = 55,
         CapabilityStorageImageWriteWithoutFormat = 56,
         CapabilityMultiViewport = 57,
+        CapabilityStorageBuffer16BitAccess = 4433,
+        CapabilityUniformAndStorageBuffer16BitAccess = 4434,
+        CapabilityStoragePushConstant16 = 4435,
+        CapabilityStorageInputOutput16 = 4436,
+        CapabilityStorageBuffer8BitAccess = 4448,
+        CapabilityUniformAndStorageBuffer8BitAccess = 4449,
+        CapabilityStoragePushConstant8 = 4450,
+        CapabilityRayTracingKHR = 4479,
     } Capability;
 }
@@ -135,7 +135,7 @@ impl {name} {{
     #[inline]
     #[allow(unsafe_code)]
     pub fn load(device: ::std::sync::Arc<::vulkano::device::Device>)
-                -> Result<{name}, ::vulkano::OomError>
+                -> Result<{name}, ::vulkano::pipeline::shader::ShaderModuleCreationError>
     {{
 
         "#,
@@ -265,6 +265,7 @@ fn format_from_id(doc: &parse::Spirv, searched: u32, ignore_first_array: bool) -
             &parse::Instruction::TypeFloat { result_id, width } if result_id == searched => {
                 assert!(!ignore_first_array);
                 return (match width {
+                    16 => "R16Sfloat",
                     32 => "R32Sfloat",
                     64 => "R64Sfloat",
                     _ => panic!(),
@@ -278,16 +279,19 @@ fn format_from_id(doc: &parse::Spirv, searched: u32, ignore_first_array: bool) -
             } if result_id == searched => {
                 assert!(!ignore_first_array);
                 let (format, sz) = format_from_id(doc, component_id, false);
-                assert!(format.starts_with("R32"));
+                assert!(!format.starts_with("R64"));
                 assert_eq!(sz, 1);
+                // Splits `R16Sfloat` into `16` and `Sfloat`.
+                let split = format[1 ..].find(|c: char| !c.is_digit(10)).unwrap() + 1;
+                let (bits, ty) = (&format[1 .. split], &format[split ..]);
                 let format = if count == 1 {
-                    format
+                    format.clone()
                 } else if count == 2 {
-                    format!("R32G32{}", &format[3 ..])
+                    format!("R{0}G{0}{1}", bits, ty)
                 } else if count == 3 {
-                    format!("R32G32B32{}", &format[3 ..])
+                    format!("R{0}G{0}B{0}{1}", bits, ty)
                 } else if count == 4 {
-                    format!("R32G32B32A32{}", &format[3 ..])
+                    format!("R{0}G{0}B{0}A{0}{1}", bits, ty)
                 } else {
                     panic!("Found vector type with more than 4 elements")
                 };
@@ -473,7 +477,7 @@ fn capability_name(cap: &enums::Capability) -> Option<&'static str> {
         enums::Capability::CapabilityKernel => panic!(),    // not supported
         enums::Capability::CapabilityVector16 => panic!(),  // not supported
         enums::Capability::CapabilityFloat16Buffer => panic!(), // not supported
-        enums::Capability::CapabilityFloat16 => panic!(),   // not supported
+        enums::Capability::CapabilityFloat16 => None,   // checked when the module is created
         enums::Capability::CapabilityFloat64 => Some("shader_f3264"),
         enums::Capability::CapabilityInt64 => Some("shader_int64"),
         enums::Capability::CapabilityInt64Atomics => panic!(),  // not supported
@@ -508,7 +512,7 @@ fn capability_name(cap: &enums::Capability) -> Option<&'static str> {
         enums::Capability::CapabilityImageRect => panic!(), // not supported
         enums::Capability::CapabilitySampledRect => panic!(),   // not supported
         enums::Capability::CapabilityGenericPointer => panic!(),    // not supported
-        enums::Capability::CapabilityInt8 => panic!(),  // not supported
+        enums::Capability::CapabilityInt8 => None,   // checked when the module is created
         enums::Capability::CapabilityInputAttachment => None,       // always supported
         enums::Capability::CapabilitySparseResidency => Some("shader_resource_residency"),
         enums::Capability::CapabilityMinLod => Some("shader_resource_min_lod"),
@@ -530,5 +534,15 @@ fn capability_name(cap: &enums::Capability) -> Option<&'static str> {
         enums::Capability::CapabilityStorageImageWriteWithoutFormat =>
             Some("shader_storage_image_write_without_format"),
         enums::Capability::CapabilityMultiViewport => Some("multi_viewport"),
+        // Checked when the shader module is created.
+        enums::Capability::CapabilityStorageBuffer16BitAccess => None,
+        enums::Capability::CapabilityUniformAndStorageBuffer16BitAccess => None,
+        enums::Capability::CapabilityStoragePushConstant16 => None,
+        enums::Capability::CapabilityStorageInputOutput16 => None,
+        enums::Capability::CapabilityStorageBuffer8BitAccess => None,
+        enums::Capability::CapabilityUniformAndStorageBuffer8BitAccess => None,
+        enums::Capability::CapabilityStoragePushConstant8 => None,
+        // Checked when the ray tracing pipeline is created.
+        enums::Capability::CapabilityRayTracingKHR => None,
     }
 }
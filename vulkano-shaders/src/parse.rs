@@ -156,6 +156,7 @@ pub enum Instruction {
         member_types: Vec<u32>,
     },
     TypeOpaque { result_id: u32, name: String },
+    TypeAccelerationStructure { result_id: u32 },
     TypePointer {
         result_id: u32,
         storage_class: StorageClass,
@@ -325,6 +326,7 @@ fn decode_instruction(opcode: u16, operands: &[u32]) -> Result<Instruction, Pars
                storage_class: StorageClass::from_num(operands[1])?,
                type_id: operands[2],
            },
+           5341 => Instruction::TypeAccelerationStructure { result_id: operands[0] },
            43 => Instruction::Constant {
                result_type_id: operands[0],
                result_id: operands[1],
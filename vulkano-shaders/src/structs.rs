@@ -337,6 +337,10 @@ pub fn type_from_id(doc: &parse::Spirv, searched: u32) -> (String, Option<usize>
             },
             &parse::Instruction::TypeFloat { result_id, width } if result_id == searched => {
                 match width {
+                    16 => {
+                        // `half::f16` is a `u16` internally.
+                        return ("::vulkano::half::f16".to_owned(), Some(2), 2);
+                    },
                     32 => {
                         #[repr(C)]
                         struct Foo {
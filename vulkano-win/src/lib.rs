@@ -1,57 +1,22 @@
+//! Link between vulkano and winit.
+//!
+//! Creating a surface from a winit window is implemented by vulkano itself behind its `winit`
+//! feature. This crate only adds the `VkSurfaceBuild` trait on top of it.
+
 extern crate vulkano;
 extern crate winit;
 
-#[cfg(target_os = "macos")]
-extern crate objc;
-#[cfg(target_os = "macos")]
-extern crate cocoa;
-#[cfg(target_os = "macos")]
-extern crate metal_rs as metal;
-
 use std::error;
 use std::fmt;
-#[cfg(target_os = "windows")]
-use std::ptr;
 use std::sync::Arc;
 
 use vulkano::instance::Instance;
-use vulkano::instance::InstanceExtensions;
 use vulkano::swapchain::Surface;
 use vulkano::swapchain::SurfaceCreationError;
 use winit::{EventsLoop, WindowBuilder};
 use winit::CreationError as WindowCreationError;
 
-#[cfg(target_os = "macos")]
-use cocoa::appkit::{NSView, NSWindow};
-#[cfg(target_os = "macos")]
-use cocoa::base::id as cocoa_id;
-#[cfg(target_os = "macos")]
-use metal::CoreAnimationLayer;
-#[cfg(target_os = "macos")]
-use objc::runtime::YES;
-
-#[cfg(target_os = "macos")]
-use std::mem;
-
-pub fn required_extensions() -> InstanceExtensions {
-    let ideal = InstanceExtensions {
-        khr_surface: true,
-        khr_xlib_surface: true,
-        khr_xcb_surface: true,
-        khr_wayland_surface: true,
-        khr_mir_surface: true,
-        khr_android_surface: true,
-        khr_win32_surface: true,
-        mvk_ios_surface: true,
-        mvk_macos_surface: true,
-        ..InstanceExtensions::none()
-    };
-
-    match InstanceExtensions::supported_by_core() {
-        Ok(supported) => supported.intersection(&ideal),
-        Err(_) => InstanceExtensions::none(),
-    }
-}
+pub use vulkano::swapchain::winit::required_extensions;
 
 pub trait VkSurfaceBuild {
     fn build_vk_surface(self, events_loop: &EventsLoop, instance: Arc<Instance>)
@@ -62,7 +27,7 @@ impl VkSurfaceBuild for WindowBuilder {
     fn build_vk_surface(self, events_loop: &EventsLoop, instance: Arc<Instance>)
                         -> Result<Arc<Surface<winit::Window>>, CreationError> {
         let window = self.build(events_loop)?;
-        Ok(unsafe { winit_to_surface(instance, window) }?)
+        Ok(Surface::from_winit(instance, window)?)
     }
 }
 
@@ -113,69 +78,3 @@ impl From<WindowCreationError> for CreationError {
         CreationError::WindowCreationError(err)
     }
 }
-
-#[cfg(target_os = "android")]
-unsafe fn winit_to_surface(instance: Arc<Instance>, win: winit::Window)
-                           -> Result<Arc<Surface<winit::Window>>, SurfaceCreationError> {
-    use winit::os::android::WindowExt;
-    Surface::from_anativewindow(instance, win.get_native_window(), win)
-}
-
-#[cfg(all(unix, not(target_os = "android"), not(target_os = "macos")))]
-unsafe fn winit_to_surface(instance: Arc<Instance>, win: winit::Window)
-                           -> Result<Arc<Surface<winit::Window>>, SurfaceCreationError> {
-    use winit::os::unix::WindowExt;
-    match (win.get_wayland_display(), win.get_wayland_surface()) {
-        (Some(display), Some(surface)) => Surface::from_wayland(instance,
-                                                                display,
-                                                                surface,
-                                                                win),
-        _ => {
-            // No wayland display found, check if we can use xlib.
-            // If not, we use xcb.
-            if instance.loaded_extensions().khr_xlib_surface {
-                Surface::from_xlib(instance,
-                                   win.get_xlib_display().unwrap(),
-                                   win.get_xlib_window().unwrap() as _,
-                                   win)
-            } else {
-                Surface::from_xcb(instance,
-                                  win.get_xcb_connection().unwrap(),
-                                  win.get_xlib_window().unwrap() as _,
-                                  win)
-            }
-        },
-    }
-}
-
-#[cfg(target_os = "windows")]
-unsafe fn winit_to_surface(instance: Arc<Instance>, win: winit::Window)
-                           -> Result<Arc<Surface<winit::Window>>, SurfaceCreationError> {
-    use winit::os::windows::WindowExt;
-    Surface::from_hwnd(instance,
-                       ptr::null() as *const (), // FIXME
-                       win.get_hwnd(),
-                       win)
-}
-
-#[cfg(target_os = "macos")]
-unsafe fn winit_to_surface(instance: Arc<Instance>, win: winit::Window)
-                           -> Result<Arc<Surface<winit::Window>>, SurfaceCreationError> {
-    use winit::os::macos::WindowExt;
-
-    let wnd: cocoa_id = mem::transmute(win.get_nswindow());
-
-    let layer = CoreAnimationLayer::new();
-
-    layer.set_edge_antialiasing_mask(0);
-    layer.set_presents_with_transaction(false);
-    layer.remove_all_animations();
-
-    let view = wnd.contentView();
-
-    layer.set_contents_scale(view.backingScaleFactor());
-    view.setLayer(mem::transmute(layer.as_ref())); // Bombs here with out of memory
-    view.setWantsLayer(YES);
-
-    Surface::from_macos_moltenvk(instance, win.get_nsview() as *const (), win)
-}
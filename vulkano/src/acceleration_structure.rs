@@ -0,0 +1,904 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Acceleration structures, used by ray tracing pipelines to find the geometry hit by rays.
+//!
+//! There are two kinds of acceleration structures:
+//!
+//! - *Bottom-level* acceleration structures (BLAS) contain the actual geometry, either as
+//!   triangles or as axis-aligned bounding boxes for procedural geometry.
+//! - *Top-level* acceleration structures (TLAS) contain instances of bottom-level acceleration
+//!   structures, each with its own transformation. This is what the shaders trace rays against.
+//!
+//! An acceleration structure is created empty with `AccelerationStructure::new`, by passing a
+//! size that can be obtained with `AccelerationStructure::build_sizes`. It is then built on the
+//! device with the `build_acceleration_structure` command of `AutoCommandBufferBuilder`, which
+//! also takes care of allocating the scratch memory needed by the build. An acceleration
+//! structure that was built with the `allow_update` flag can later be updated with
+//! `BuildMode::Update`, and one that was built with the `allow_compaction` flag can be copied
+//! into a smaller one with `CopyMode::Compact`.
+//!
+//! A top-level acceleration structure is built out of an `AccelerationStructureInstances`, which
+//! holds the bottom-level acceleration structures of its instances. The top-level acceleration
+//! structure keeps them alive for as long as it references them.
+//!
+//! All of this requires the `VK_KHR_acceleration_structure` extension.
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use buffer::BufferAccess;
+use buffer::BufferUsage;
+use buffer::CpuAccessibleBuffer;
+use buffer::DeviceLocalBuffer;
+use device::Device;
+use device::DeviceOwned;
+use format::Format;
+use memory::DeviceMemoryAllocError;
+use pipeline::input_assembly::IndexType;
+
+use Error;
+use OomError;
+use VulkanObject;
+use check_errors;
+use vk;
+
+/// An acceleration structure, stored in a buffer that it owns.
+pub struct AccelerationStructure {
+    handle: vk::AccelerationStructureKHR,
+    device: Arc<Device>,
+    buffer: Arc<DeviceLocalBuffer<[u8]>>,
+    ty: AccelerationStructureType,
+    device_address: u64,
+    build_state: Mutex<BuildState>,
+}
+
+// What the last build or copy recorded with an acceleration structure as destination did.
+#[derive(Default)]
+struct BuildState {
+    allow_update: bool,
+    // The bottom-level acceleration structures referenced by a top-level acceleration structure.
+    instances: Vec<Arc<AccelerationStructure>>,
+}
+
+impl AccelerationStructure {
+    /// Creates a new acceleration structure of the given type, and allocates a buffer of `size`
+    /// bytes to store it.
+    ///
+    /// The content of the acceleration structure is undefined until it is built with a command.
+    /// The size needed can be obtained with `build_sizes`.
+    pub fn new(device: Arc<Device>, ty: AccelerationStructureType, size: usize)
+               -> Result<Arc<AccelerationStructure>, AccelerationStructureCreationError> {
+        if !device.enabled_features().acceleration_structure {
+            return Err(AccelerationStructureCreationError::AccelerationStructureFeatureNotEnabled);
+        }
+        if !device.buffer_device_address_enabled() {
+            return Err(AccelerationStructureCreationError::BufferDeviceAddressFeatureNotEnabled);
+        }
+
+        let usage = BufferUsage {
+            acceleration_structure_storage: true,
+            shader_device_address: true,
+            ..BufferUsage::none()
+        };
+        let buffer = DeviceLocalBuffer::array(device.clone(),
+                                              size,
+                                              usage,
+                                              device.active_queue_families())?;
+
+        let handle = unsafe {
+            let vk = device.pointers();
+
+            let infos = vk::AccelerationStructureCreateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_CREATE_INFO_KHR,
+                pNext: ptr::null(),
+                createFlags: 0,
+                buffer: buffer.inner().buffer.internal_object(),
+                offset: 0,
+                size: size as vk::DeviceSize,
+                ty: ty as u32,
+                deviceAddress: 0,
+            };
+
+            let mut output = mem::uninitialized();
+            check_errors(vk.CreateAccelerationStructureKHR(device.internal_object(),
+                                                           &infos,
+                                                           device.allocator_ptr(),
+                                                           &mut output))?;
+            output
+        };
+
+        let device_address = unsafe {
+            let vk = device.pointers();
+            let infos = vk::AccelerationStructureDeviceAddressInfoKHR {
+                sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_DEVICE_ADDRESS_INFO_KHR,
+                pNext: ptr::null(),
+                accelerationStructure: handle,
+            };
+            vk.GetAccelerationStructureDeviceAddressKHR(device.internal_object(), &infos)
+        };
+
+        Ok(Arc::new(AccelerationStructure {
+                        handle: handle,
+                        device: device,
+                        buffer: buffer,
+                        ty: ty,
+                        device_address: device_address,
+                        build_state: Mutex::new(BuildState::default()),
+                    }))
+    }
+
+    /// Returns the sizes of the acceleration structure and of the scratch memory that are
+    /// needed to build an acceleration structure out of `geometries`.
+    ///
+    /// Only the types, formats and number of primitives of the geometries are taken into
+    /// account. The content of their buffers doesn't matter.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the `accelerationStructure` feature isn't enabled on the device.
+    ///
+    pub fn build_sizes(device: &Device, ty: AccelerationStructureType,
+                       flags: BuildAccelerationStructureFlags,
+                       geometries: &[AccelerationStructureGeometry])
+                       -> AccelerationStructureBuildSizes {
+        assert!(device.enabled_features().acceleration_structure);
+
+        let vk_geometries = geometries
+            .iter()
+            .map(|g| g.to_vulkan())
+            .collect::<Vec<_>>();
+        let max_primitive_counts = geometries
+            .iter()
+            .map(|g| g.primitive_count())
+            .collect::<Vec<_>>();
+
+        unsafe {
+            let vk = device.pointers();
+
+            let infos = vk::AccelerationStructureBuildGeometryInfoKHR {
+                sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_BUILD_GEOMETRY_INFO_KHR,
+                pNext: ptr::null(),
+                ty: ty as u32,
+                flags: flags.into_vulkan_bits(),
+                mode: vk::BUILD_ACCELERATION_STRUCTURE_MODE_BUILD_KHR,
+                srcAccelerationStructure: 0,
+                dstAccelerationStructure: 0,
+                geometryCount: vk_geometries.len() as u32,
+                pGeometries: vk_geometries.as_ptr(),
+                ppGeometries: ptr::null(),
+                scratchData: vk::DeviceOrHostAddressKHR { deviceAddress: 0 },
+            };
+
+            let mut output = vk::AccelerationStructureBuildSizesInfoKHR {
+                sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_BUILD_SIZES_INFO_KHR,
+                pNext: ptr::null(),
+                accelerationStructureSize: 0,
+                updateScratchSize: 0,
+                buildScratchSize: 0,
+            };
+
+            vk.GetAccelerationStructureBuildSizesKHR(device.internal_object(),
+                                                     vk::ACCELERATION_STRUCTURE_BUILD_TYPE_DEVICE_KHR,
+                                                     &infos,
+                                                     max_primitive_counts.as_ptr(),
+                                                     &mut output);
+
+            AccelerationStructureBuildSizes {
+                acceleration_structure_size: output.accelerationStructureSize as usize,
+                update_scratch_size: output.updateScratchSize as usize,
+                build_scratch_size: output.buildScratchSize as usize,
+            }
+        }
+    }
+
+    /// Returns the buffer that stores the acceleration structure.
+    ///
+    /// This is the resource that is tracked when the acceleration structure is used in a
+    /// command buffer.
+    #[inline]
+    pub fn buffer(&self) -> &Arc<DeviceLocalBuffer<[u8]>> {
+        &self.buffer
+    }
+
+    /// Returns the type of the acceleration structure.
+    #[inline]
+    pub fn ty(&self) -> AccelerationStructureType {
+        self.ty
+    }
+
+    /// Returns the address of the acceleration structure in the device's address space.
+    #[inline]
+    pub fn device_address(&self) -> u64 {
+        self.device_address
+    }
+
+    /// Returns true if the last build of the acceleration structure that was recorded in a
+    /// command buffer used the `allow_update` flag, in which case it can be the source of a
+    /// `BuildMode::Update`.
+    #[inline]
+    pub fn allows_update(&self) -> bool {
+        self.build_state.lock().unwrap().allow_update
+    }
+
+    // Records that the acceleration structure is built with the given flag and instances.
+    // Returns the instances of the previous build, which must be kept alive until the build is
+    // over.
+    pub(crate) fn set_build_state(&self, allow_update: bool,
+                                  instances: Vec<Arc<AccelerationStructure>>)
+                                  -> Vec<Arc<AccelerationStructure>> {
+        let mut state = self.build_state.lock().unwrap();
+        state.allow_update = allow_update;
+        mem::replace(&mut state.instances, instances)
+    }
+
+    // Records that `source` is copied into the acceleration structure. Returns the instances of
+    // the previous build, like `set_build_state`.
+    pub(crate) fn copy_build_state(&self, source: &AccelerationStructure)
+                                   -> Vec<Arc<AccelerationStructure>> {
+        let (allow_update, instances) = {
+            let source = source.build_state.lock().unwrap();
+            (source.allow_update, source.instances.clone())
+        };
+        self.set_build_state(allow_update, instances)
+    }
+}
+
+unsafe impl VulkanObject for AccelerationStructure {
+    type Object = vk::AccelerationStructureKHR;
+
+    const TYPE: vk::DebugReportObjectTypeEXT =
+        vk::DEBUG_REPORT_OBJECT_TYPE_ACCELERATION_STRUCTURE_KHR_EXT;
+
+    #[inline]
+    fn internal_object(&self) -> vk::AccelerationStructureKHR {
+        self.handle
+    }
+}
+
+unsafe impl DeviceOwned for AccelerationStructure {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+impl fmt::Debug for AccelerationStructure {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "<Vulkan acceleration structure {:?}>", self.handle)
+    }
+}
+
+impl Drop for AccelerationStructure {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let vk = self.device.pointers();
+            vk.DestroyAccelerationStructureKHR(self.device.internal_object(),
+                                               self.handle,
+                                               self.device.allocator_ptr());
+        }
+    }
+}
+
+/// Type of an acceleration structure.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum AccelerationStructureType {
+    /// Contains instances of bottom-level acceleration structures.
+    TopLevel = vk::ACCELERATION_STRUCTURE_TYPE_TOP_LEVEL_KHR,
+    /// Contains triangles or bounding boxes.
+    BottomLevel = vk::ACCELERATION_STRUCTURE_TYPE_BOTTOM_LEVEL_KHR,
+}
+
+/// Sizes needed to build an acceleration structure, as returned by `build_sizes`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct AccelerationStructureBuildSizes {
+    /// Size of the acceleration structure itself.
+    pub acceleration_structure_size: usize,
+    /// Size of the scratch memory needed to update the acceleration structure.
+    pub update_scratch_size: usize,
+    /// Size of the scratch memory needed to build the acceleration structure.
+    pub build_scratch_size: usize,
+}
+
+/// Whether to build an acceleration structure from scratch or to update an existing one.
+#[derive(Debug, Clone)]
+pub enum BuildMode {
+    /// Builds the acceleration structure from scratch.
+    Build,
+    /// Updates the given acceleration structure, which must have been built with the
+    /// `allow_update` flag, and writes the result in the destination. The source and the
+    /// destination can be the same.
+    ///
+    /// The geometries must be the same as the ones of the previous build, except for the
+    /// content of their buffers.
+    Update(Arc<AccelerationStructure>),
+}
+
+/// How to copy an acceleration structure into another one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum CopyMode {
+    /// Makes an exact copy. The destination must be at least as large as the source.
+    Clone = vk::COPY_ACCELERATION_STRUCTURE_MODE_CLONE_KHR,
+    /// Makes a compact copy. The source must have been built with the `allow_compaction` flag,
+    /// and the destination must be at least as large as the compacted size of the source, which
+    /// can be retrieved with the `write_acceleration_structure_compacted_size` command.
+    Compact = vk::COPY_ACCELERATION_STRUCTURE_MODE_COMPACT_KHR,
+}
+
+/// Flags to pass when building an acceleration structure.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct BuildAccelerationStructureFlags {
+    /// The acceleration structure can be updated later with `BuildMode::Update`.
+    pub allow_update: bool,
+    /// The acceleration structure can be compacted later with `CopyMode::Compact`.
+    pub allow_compaction: bool,
+    /// Prioritizes the speed of tracing rays over the speed of the build.
+    pub prefer_fast_trace: bool,
+    /// Prioritizes the speed of the build over the speed of tracing rays.
+    pub prefer_fast_build: bool,
+    /// Minimizes the memory used by the acceleration structure and the scratch memory, at the
+    /// cost of speed.
+    pub low_memory: bool,
+}
+
+impl BuildAccelerationStructureFlags {
+    /// Builds a `BuildAccelerationStructureFlags` with all the flags set to false.
+    #[inline]
+    pub fn none() -> BuildAccelerationStructureFlags {
+        BuildAccelerationStructureFlags::default()
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan_bits(self) -> vk::BuildAccelerationStructureFlagsKHR {
+        let mut result = 0;
+        if self.allow_update {
+            result |= vk::BUILD_ACCELERATION_STRUCTURE_ALLOW_UPDATE_BIT_KHR;
+        }
+        if self.allow_compaction {
+            result |= vk::BUILD_ACCELERATION_STRUCTURE_ALLOW_COMPACTION_BIT_KHR;
+        }
+        if self.prefer_fast_trace {
+            result |= vk::BUILD_ACCELERATION_STRUCTURE_PREFER_FAST_TRACE_BIT_KHR;
+        }
+        if self.prefer_fast_build {
+            result |= vk::BUILD_ACCELERATION_STRUCTURE_PREFER_FAST_BUILD_BIT_KHR;
+        }
+        if self.low_memory {
+            result |= vk::BUILD_ACCELERATION_STRUCTURE_LOW_MEMORY_BIT_KHR;
+        }
+        result
+    }
+}
+
+/// Flags of a geometry of a bottom-level acceleration structure.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GeometryFlags {
+    /// The any hit shaders are never invoked for this geometry.
+    pub opaque: bool,
+    /// The any hit shader is invoked at most once per primitive during a single trace.
+    pub no_duplicate_any_hit_invocation: bool,
+}
+
+impl GeometryFlags {
+    /// Builds a `GeometryFlags` with all the flags set to false.
+    #[inline]
+    pub fn none() -> GeometryFlags {
+        GeometryFlags::default()
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan_bits(self) -> vk::GeometryFlagsKHR {
+        let mut result = 0;
+        if self.opaque {
+            result |= vk::GEOMETRY_OPAQUE_BIT_KHR;
+        }
+        if self.no_duplicate_any_hit_invocation {
+            result |= vk::GEOMETRY_NO_DUPLICATE_ANY_HIT_INVOCATION_BIT_KHR;
+        }
+        result
+    }
+}
+
+/// Flags of an instance of a top-level acceleration structure.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct GeometryInstanceFlags {
+    /// Disables face culling for the triangles of this instance.
+    pub triangle_facing_cull_disable: bool,
+    /// Inverts which faces of the triangles are considered to be front faces.
+    pub triangle_flip_facing: bool,
+    /// Considers all the geometries of this instance as opaque.
+    pub force_opaque: bool,
+    /// Considers all the geometries of this instance as non-opaque.
+    pub force_no_opaque: bool,
+}
+
+impl GeometryInstanceFlags {
+    /// Builds a `GeometryInstanceFlags` with all the flags set to false.
+    #[inline]
+    pub fn none() -> GeometryInstanceFlags {
+        GeometryInstanceFlags::default()
+    }
+
+    #[inline]
+    fn into_vulkan_bits(self) -> u32 {
+        let mut result = 0;
+        if self.triangle_facing_cull_disable {
+            result |= vk::GEOMETRY_INSTANCE_TRIANGLE_FACING_CULL_DISABLE_BIT_KHR;
+        }
+        if self.triangle_flip_facing {
+            result |= vk::GEOMETRY_INSTANCE_TRIANGLE_FLIP_FACING_BIT_KHR;
+        }
+        if self.force_opaque {
+            result |= vk::GEOMETRY_INSTANCE_FORCE_OPAQUE_BIT_KHR;
+        }
+        if self.force_no_opaque {
+            result |= vk::GEOMETRY_INSTANCE_FORCE_NO_OPAQUE_BIT_KHR;
+        }
+        result
+    }
+}
+
+/// A geometry to build an acceleration structure from.
+///
+/// Bottom-level acceleration structures are built from triangles or bounding boxes, and
+/// top-level acceleration structures are built from a single `Instances` geometry.
+///
+/// All the buffers must have been created with the `acceleration_structure_build_input` and
+/// `shader_device_address` usages.
+#[derive(Clone)]
+pub enum AccelerationStructureGeometry {
+    /// A list of triangles.
+    Triangles {
+        /// Buffer containing the vertices.
+        vertex_buffer: Arc<BufferAccess + Send + Sync>,
+        /// Format of the position of each vertex.
+        vertex_format: Format,
+        /// Number of bytes between the start of a vertex and the start of the next one.
+        vertex_stride: u32,
+        /// Highest index of a vertex that is used by the triangles.
+        max_vertex: u32,
+        /// Buffer containing the indices of the vertices of each triangle, if any. If `None`,
+        /// each group of three consecutive vertices is a triangle.
+        index_buffer: Option<(Arc<BufferAccess + Send + Sync>, IndexType)>,
+        /// Number of triangles.
+        primitive_count: u32,
+        /// Flags of the geometry.
+        flags: GeometryFlags,
+    },
+
+    /// A list of axis-aligned bounding boxes, for procedural geometry. Each element of the
+    /// buffer starts with six `f32`s: the minimum then the maximum coordinates of the box.
+    Aabbs {
+        /// Buffer containing the bounding boxes.
+        buffer: Arc<BufferAccess + Send + Sync>,
+        /// Number of bytes between the start of a box and the start of the next one.
+        stride: u32,
+        /// Number of boxes.
+        primitive_count: u32,
+        /// Flags of the geometry.
+        flags: GeometryFlags,
+    },
+
+    /// A list of instances of bottom-level acceleration structures.
+    Instances {
+        /// The instances.
+        instances: AccelerationStructureInstances,
+        /// Flags of the geometry.
+        flags: GeometryFlags,
+    },
+}
+
+impl AccelerationStructureGeometry {
+    /// Returns the number of primitives of the geometry.
+    #[inline]
+    pub fn primitive_count(&self) -> u32 {
+        match *self {
+            AccelerationStructureGeometry::Triangles { primitive_count, .. } |
+            AccelerationStructureGeometry::Aabbs { primitive_count, .. } => primitive_count,
+            AccelerationStructureGeometry::Instances { ref instances, .. } => {
+                instances.len() as u32
+            },
+        }
+    }
+
+    /// Returns the buffers that are read when building an acceleration structure out of this
+    /// geometry.
+    pub fn buffers(&self) -> Vec<&Arc<BufferAccess + Send + Sync>> {
+        match *self {
+            AccelerationStructureGeometry::Triangles {
+                ref vertex_buffer,
+                ref index_buffer,
+                ..
+            } => {
+                let mut buffers = vec![vertex_buffer];
+                if let Some((ref index_buffer, _)) = *index_buffer {
+                    buffers.push(index_buffer);
+                }
+                buffers
+            },
+            AccelerationStructureGeometry::Aabbs { ref buffer, .. } => vec![buffer],
+            AccelerationStructureGeometry::Instances { ref instances, .. } => {
+                vec![&instances.buffer]
+            },
+        }
+    }
+
+    // Builds the Vulkan description of the geometry. The addresses of the buffers that don't
+    // have the `shader_device_address` usage are replaced with 0.
+    pub(crate) fn to_vulkan(&self) -> vk::AccelerationStructureGeometryKHR {
+        fn address(buffer: &Arc<BufferAccess + Send + Sync>) -> vk::DeviceOrHostAddressConstKHR {
+            vk::DeviceOrHostAddressConstKHR { deviceAddress: buffer.device_address().unwrap_or(0) }
+        }
+
+        let (ty, geometry, flags) = match *self {
+            AccelerationStructureGeometry::Triangles {
+                ref vertex_buffer,
+                vertex_format,
+                vertex_stride,
+                max_vertex,
+                ref index_buffer,
+                flags,
+                ..
+            } => {
+                let (index_type, index_data) = match *index_buffer {
+                    Some((ref buffer, ty)) => (ty as u32, address(buffer)),
+                    None => (vk::INDEX_TYPE_NONE_KHR,
+                             vk::DeviceOrHostAddressConstKHR { deviceAddress: 0 }),
+                };
+
+                let triangles = vk::AccelerationStructureGeometryTrianglesDataKHR {
+                    sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_TRIANGLES_DATA_KHR,
+                    pNext: ptr::null(),
+                    vertexFormat: vertex_format as u32,
+                    vertexData: address(vertex_buffer),
+                    vertexStride: vertex_stride as vk::DeviceSize,
+                    maxVertex: max_vertex,
+                    indexType: index_type,
+                    indexData: index_data,
+                    transformData: vk::DeviceOrHostAddressConstKHR { deviceAddress: 0 },
+                };
+
+                (vk::GEOMETRY_TYPE_TRIANGLES_KHR,
+                 vk::AccelerationStructureGeometryDataKHR { triangles: triangles },
+                 flags)
+            },
+
+            AccelerationStructureGeometry::Aabbs {
+                ref buffer,
+                stride,
+                flags,
+                ..
+            } => {
+                let aabbs = vk::AccelerationStructureGeometryAabbsDataKHR {
+                    sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_AABBS_DATA_KHR,
+                    pNext: ptr::null(),
+                    data: address(buffer),
+                    stride: stride as vk::DeviceSize,
+                };
+
+                (vk::GEOMETRY_TYPE_AABBS_KHR,
+                 vk::AccelerationStructureGeometryDataKHR { aabbs: aabbs },
+                 flags)
+            },
+
+            AccelerationStructureGeometry::Instances {
+                ref instances,
+                flags,
+            } => {
+                let vk_instances = vk::AccelerationStructureGeometryInstancesDataKHR {
+                    sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_INSTANCES_DATA_KHR,
+                    pNext: ptr::null(),
+                    arrayOfPointers: vk::FALSE,
+                    data: address(&instances.buffer),
+                };
+
+                (vk::GEOMETRY_TYPE_INSTANCES_KHR,
+                 vk::AccelerationStructureGeometryDataKHR { instances: vk_instances },
+                 flags)
+            },
+        };
+
+        vk::AccelerationStructureGeometryKHR {
+            sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_GEOMETRY_KHR,
+            pNext: ptr::null(),
+            geometryType: ty,
+            geometry: geometry,
+            flags: flags.into_vulkan_bits(),
+        }
+    }
+}
+
+/// A list of instances of bottom-level acceleration structures, stored in a buffer that a
+/// top-level acceleration structure can be built from with
+/// `AccelerationStructureGeometry::Instances`.
+#[derive(Clone)]
+pub struct AccelerationStructureInstances {
+    buffer: Arc<BufferAccess + Send + Sync>,
+    acceleration_structures: Vec<Arc<AccelerationStructure>>,
+}
+
+impl AccelerationStructureInstances {
+    /// Writes `instances` in a new buffer.
+    pub fn new<I>(device: Arc<Device>, instances: I)
+                  -> Result<AccelerationStructureInstances, DeviceMemoryAllocError>
+        where I: IntoIterator<Item = AccelerationStructureInstance>
+    {
+        let instances = instances.into_iter().collect::<Vec<_>>();
+
+        let usage = BufferUsage {
+            acceleration_structure_build_input: true,
+            shader_device_address: true,
+            ..BufferUsage::none()
+        };
+        let buffer = CpuAccessibleBuffer::from_iter(device,
+                                                    usage,
+                                                    instances.iter().map(|i| i.data))?;
+
+        Ok(AccelerationStructureInstances {
+               buffer: buffer,
+               acceleration_structures: instances
+                   .into_iter()
+                   .map(|i| i.acceleration_structure)
+                   .collect(),
+           })
+    }
+
+    /// Returns the number of instances.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.acceleration_structures.len()
+    }
+
+    /// Returns the acceleration structure of each instance.
+    #[inline]
+    pub fn acceleration_structures(&self) -> &[Arc<AccelerationStructure>] {
+        &self.acceleration_structures
+    }
+}
+
+/// An instance of a bottom-level acceleration structure, to put in an
+/// `AccelerationStructureInstances`.
+#[derive(Debug, Clone)]
+pub struct AccelerationStructureInstance {
+    acceleration_structure: Arc<AccelerationStructure>,
+    data: InstanceData,
+}
+
+// An instance, with the layout that the Vulkan implementation expects.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[repr(C)]
+struct InstanceData {
+    transform: [[f32; 4]; 3],
+    // Custom index in the lower 24 bits, mask in the upper 8 bits.
+    custom_index_and_mask: u32,
+    // Shader binding table record offset in the lower 24 bits, flags in the upper 8 bits.
+    sbt_record_offset_and_flags: u32,
+    acceleration_structure_reference: u64,
+}
+
+impl AccelerationStructureInstance {
+    /// Builds an instance of the given bottom-level acceleration structure, with an identity
+    /// transformation, a mask of `0xff` and all the other values set to 0.
+    #[inline]
+    pub fn new(acceleration_structure: Arc<AccelerationStructure>)
+               -> AccelerationStructureInstance {
+        let data = InstanceData::new(acceleration_structure.device_address());
+
+        AccelerationStructureInstance {
+            acceleration_structure: acceleration_structure,
+            data: data,
+        }
+    }
+
+    /// Returns the acceleration structure of the instance.
+    #[inline]
+    pub fn acceleration_structure(&self) -> &Arc<AccelerationStructure> {
+        &self.acceleration_structure
+    }
+
+    /// Sets the transformation of the instance, as a row-major 3x4 matrix.
+    #[inline]
+    pub fn transform(mut self, transform: [[f32; 4]; 3]) -> AccelerationStructureInstance {
+        self.data.transform = transform;
+        self
+    }
+
+    /// Sets the value returned by `gl_InstanceCustomIndexEXT` in the shaders.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `index` doesn't fit in 24 bits.
+    ///
+    #[inline]
+    pub fn custom_index(mut self, index: u32) -> AccelerationStructureInstance {
+        assert!(index < (1 << 24));
+        self.data = self.data.custom_index(index);
+        self
+    }
+
+    /// Sets the visibility mask of the instance. The instance is only hit by rays whose cull
+    /// mask has at least one bit in common with this mask.
+    #[inline]
+    pub fn mask(mut self, mask: u8) -> AccelerationStructureInstance {
+        self.data = self.data.mask(mask);
+        self
+    }
+
+    /// Sets the offset of the hit groups of the instance within the shader binding table.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `offset` doesn't fit in 24 bits.
+    ///
+    #[inline]
+    pub fn shader_binding_table_record_offset(mut self, offset: u32)
+                                              -> AccelerationStructureInstance {
+        assert!(offset < (1 << 24));
+        self.data = self.data.shader_binding_table_record_offset(offset);
+        self
+    }
+
+    /// Sets the flags of the instance.
+    #[inline]
+    pub fn flags(mut self, flags: GeometryInstanceFlags) -> AccelerationStructureInstance {
+        self.data = self.data.flags(flags);
+        self
+    }
+}
+
+impl InstanceData {
+    #[inline]
+    fn new(address: u64) -> InstanceData {
+        InstanceData {
+            transform: [[1.0, 0.0, 0.0, 0.0], [0.0, 1.0, 0.0, 0.0], [0.0, 0.0, 1.0, 0.0]],
+            custom_index_and_mask: 0xff << 24,
+            sbt_record_offset_and_flags: 0,
+            acceleration_structure_reference: address,
+        }
+    }
+
+    #[inline]
+    fn custom_index(mut self, index: u32) -> InstanceData {
+        self.custom_index_and_mask = (self.custom_index_and_mask & 0xff000000) | index;
+        self
+    }
+
+    #[inline]
+    fn mask(mut self, mask: u8) -> InstanceData {
+        self.custom_index_and_mask = (self.custom_index_and_mask & 0xffffff) |
+            ((mask as u32) << 24);
+        self
+    }
+
+    #[inline]
+    fn shader_binding_table_record_offset(mut self, offset: u32) -> InstanceData {
+        self.sbt_record_offset_and_flags = (self.sbt_record_offset_and_flags & 0xff000000) |
+            offset;
+        self
+    }
+
+    #[inline]
+    fn flags(mut self, flags: GeometryInstanceFlags) -> InstanceData {
+        self.sbt_record_offset_and_flags = (self.sbt_record_offset_and_flags & 0xffffff) |
+            (flags.into_vulkan_bits() << 24);
+        self
+    }
+}
+
+/// Error that can happen when creating an acceleration structure.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AccelerationStructureCreationError {
+    /// Allocating the buffer of the acceleration structure failed.
+    AllocError(DeviceMemoryAllocError),
+    /// Not enough memory.
+    OomError(OomError),
+    /// The `accelerationStructure` feature of the `VK_KHR_acceleration_structure` extension
+    /// wasn't enabled.
+    AccelerationStructureFeatureNotEnabled,
+    /// The `bufferDeviceAddress` feature of the `VK_KHR_buffer_device_address` extension wasn't
+    /// enabled.
+    BufferDeviceAddressFeatureNotEnabled,
+}
+
+impl error::Error for AccelerationStructureCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            AccelerationStructureCreationError::AllocError(_) => {
+                "allocating the buffer of the acceleration structure failed"
+            },
+            AccelerationStructureCreationError::OomError(_) => "not enough memory available",
+            AccelerationStructureCreationError::AccelerationStructureFeatureNotEnabled => {
+                "the `accelerationStructure` feature wasn't enabled"
+            },
+            AccelerationStructureCreationError::BufferDeviceAddressFeatureNotEnabled => {
+                "the `bufferDeviceAddress` feature wasn't enabled"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            AccelerationStructureCreationError::AllocError(ref err) => Some(err),
+            AccelerationStructureCreationError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for AccelerationStructureCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<DeviceMemoryAllocError> for AccelerationStructureCreationError {
+    #[inline]
+    fn from(err: DeviceMemoryAllocError) -> AccelerationStructureCreationError {
+        AccelerationStructureCreationError::AllocError(err)
+    }
+}
+
+impl From<OomError> for AccelerationStructureCreationError {
+    #[inline]
+    fn from(err: OomError) -> AccelerationStructureCreationError {
+        AccelerationStructureCreationError::OomError(err)
+    }
+}
+
+impl From<Error> for AccelerationStructureCreationError {
+    #[inline]
+    fn from(err: Error) -> AccelerationStructureCreationError {
+        match err {
+            err @ Error::OutOfHostMemory => {
+                AccelerationStructureCreationError::OomError(OomError::from(err))
+            },
+            err @ Error::OutOfDeviceMemory => {
+                AccelerationStructureCreationError::OomError(OomError::from(err))
+            },
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use acceleration_structure::GeometryInstanceFlags;
+    use acceleration_structure::InstanceData;
+    use std::mem;
+
+    #[test]
+    fn instance_layout() {
+        assert_eq!(mem::size_of::<InstanceData>(), 64);
+    }
+
+    #[test]
+    fn instance_packing() {
+        let flags = GeometryInstanceFlags { force_opaque: true, ..GeometryInstanceFlags::none() };
+        let instance = InstanceData::new(0x1234)
+            .custom_index(0x00abcdef)
+            .mask(0x0f)
+            .shader_binding_table_record_offset(3)
+            .flags(flags);
+
+        assert_eq!(instance.custom_index_and_mask, 0x0fabcdef);
+        assert_eq!(instance.sbt_record_offset_and_flags, 0x04000003);
+        assert_eq!(instance.acceleration_structure_reference, 0x1234);
+    }
+}
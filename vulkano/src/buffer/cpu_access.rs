@@ -33,7 +33,6 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 use buffer::BufferUsage;
-use buffer::sys::BufferCreationError;
 use buffer::sys::SparseLevel;
 use buffer::sys::UnsafeBuffer;
 use buffer::traits::BufferAccess;
@@ -184,12 +183,7 @@ impl<T: ?Sized> CpuAccessibleBuffer<T> {
                 Sharing::Exclusive
             };
 
-            match UnsafeBuffer::new(device.clone(), size, usage, sharing, SparseLevel::none()) {
-                Ok(b) => b,
-                Err(BufferCreationError::AllocError(err)) => return Err(err),
-                Err(_) => unreachable!(),        // We don't use sparse binding, therefore the other
-                // errors can't happen
-            }
+            UnsafeBuffer::new(device.clone(), size, usage, sharing, SparseLevel::none())?
         };
 
         let mem = MemoryPool::alloc_from_requirements(&Device::standard_pool(&device),
@@ -572,6 +566,7 @@ impl fmt::Display for WriteLockError {
 #[cfg(test)]
 mod tests {
     use buffer::{BufferUsage, CpuAccessibleBuffer};
+    use memory::DeviceMemoryAllocError;
 
     #[test]
     fn create_empty_buffer() {
@@ -581,4 +576,19 @@ mod tests {
 
         let _ = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), EMPTY.iter());
     }
+
+    #[test]
+    fn usage_not_supported() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = BufferUsage {
+            shader_device_address: true,
+            ..BufferUsage::none()
+        };
+
+        match CpuAccessibleBuffer::from_data(device, usage, 0u32) {
+            Err(DeviceMemoryAllocError::UsageNotSupported) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -19,7 +19,6 @@ use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 use buffer::BufferUsage;
-use buffer::sys::BufferCreationError;
 use buffer::sys::SparseLevel;
 use buffer::sys::UnsafeBuffer;
 use buffer::traits::BufferAccess;
@@ -358,16 +357,11 @@ impl<T, A> CpuBufferPool<T, A>
                         return Err(DeviceMemoryAllocError::OomError(OomError::OutOfDeviceMemory)),
                 };
 
-                match UnsafeBuffer::new(self.device.clone(),
-                                          size_bytes,
-                                          self.usage,
-                                          Sharing::Exclusive::<iter::Empty<_>>,
-                                          SparseLevel::none()) {
-                    Ok(b) => b,
-                    Err(BufferCreationError::AllocError(err)) => return Err(err),
-                    Err(_) => unreachable!(),        // We don't use sparse binding, therefore the other
-                    // errors can't happen
-                }
+                UnsafeBuffer::new(self.device.clone(),
+                                  size_bytes,
+                                  self.usage,
+                                  Sharing::Exclusive::<iter::Empty<_>>,
+                                  SparseLevel::none())?
             };
 
             let mem = MemoryPool::alloc_from_requirements(&self.pool,
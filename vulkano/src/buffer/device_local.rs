@@ -20,7 +20,6 @@ use std::sync::Arc;
 use std::sync::Mutex;
 
 use buffer::BufferUsage;
-use buffer::sys::BufferCreationError;
 use buffer::sys::SparseLevel;
 use buffer::sys::UnsafeBuffer;
 use buffer::traits::BufferAccess;
@@ -124,12 +123,7 @@ impl<T: ?Sized> DeviceLocalBuffer<T> {
                 Sharing::Exclusive
             };
 
-            match UnsafeBuffer::new(device.clone(), size, usage, sharing, SparseLevel::none()) {
-                Ok(b) => b,
-                Err(BufferCreationError::AllocError(err)) => return Err(err),
-                Err(_) => unreachable!(),        // We don't use sparse binding, therefore the other
-                // errors can't happen
-            }
+            UnsafeBuffer::new(device.clone(), size, usage, sharing, SparseLevel::none())?
         };
 
         let mem = MemoryPool::alloc_from_requirements(&Device::standard_pool(&device),
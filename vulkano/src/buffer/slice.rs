@@ -177,6 +177,26 @@ impl<T, B> BufferSlice<[T], B> {
     }
 }
 
+impl<B> BufferSlice<[u8], B> {
+    /// Builds a slice of bytes that covers the whole buffer, whatever its content.
+    ///
+    /// This is useful to select byte ranges of buffers whose content isn't known at compile
+    /// time, like an `Arc<BufferAccess>`.
+    #[inline]
+    pub fn from_buffer_access(r: B) -> BufferSlice<[u8], B>
+        where B: BufferAccess
+    {
+        let size = r.size();
+
+        BufferSlice {
+            marker: PhantomData,
+            resource: r,
+            offset: 0,
+            size: size,
+        }
+    }
+}
+
 unsafe impl<T: ?Sized, B> BufferAccess for BufferSlice<T, B>
     where B: BufferAccess
 {
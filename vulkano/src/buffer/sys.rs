@@ -36,6 +36,7 @@ use device::Device;
 use device::DeviceOwned;
 use memory::DeviceMemory;
 use memory::DeviceMemoryAllocError;
+use memory::ExternalMemoryHandleTypes;
 use memory::MemoryRequirements;
 use sync::Sharing;
 
@@ -63,10 +64,51 @@ impl UnsafeBuffer {
     /// Panics if `sparse.sparse` is false and `sparse.sparse_residency` or
     /// `sparse.sparse_aliased` is true.
     ///
+    #[inline]
     pub unsafe fn new<'a, I>(device: Arc<Device>, size: usize, usage: BufferUsage,
                              sharing: Sharing<I>, sparse: SparseLevel)
                              -> Result<(UnsafeBuffer, MemoryRequirements), BufferCreationError>
         where I: Iterator<Item = u32>
+    {
+        UnsafeBuffer::new_impl(device,
+                               size,
+                               usage,
+                               sharing,
+                               sparse,
+                               ExternalMemoryHandleTypes::none())
+    }
+
+    /// Same as `new`, but the memory of the buffer can be exported as the handle types of
+    /// `handle_types`. The memory must be allocated with `DeviceMemory::alloc_exportable` with
+    /// the same handle types.
+    ///
+    /// Requires the `VK_KHR_external_memory` extension.
+    ///
+    /// # Panic
+    ///
+    /// Panics if `sparse.sparse` is false and `sparse.sparse_residency` or
+    /// `sparse.sparse_aliased` is true.
+    ///
+    #[inline]
+    pub unsafe fn new_exportable<'a, I>(device: Arc<Device>, size: usize, usage: BufferUsage,
+                                        sharing: Sharing<I>, sparse: SparseLevel,
+                                        handle_types: ExternalMemoryHandleTypes)
+                                        -> Result<(UnsafeBuffer, MemoryRequirements),
+                                                  BufferCreationError>
+        where I: Iterator<Item = u32>
+    {
+        if !device.loaded_extensions().khr_external_memory {
+            return Err(BufferCreationError::ExternalMemoryExtensionNotEnabled);
+        }
+
+        UnsafeBuffer::new_impl(device, size, usage, sharing, sparse, handle_types)
+    }
+
+    unsafe fn new_impl<'a, I>(device: Arc<Device>, size: usize, usage: BufferUsage,
+                              sharing: Sharing<I>, sparse: SparseLevel,
+                              external_handle_types: ExternalMemoryHandleTypes)
+                              -> Result<(UnsafeBuffer, MemoryRequirements), BufferCreationError>
+        where I: Iterator<Item = u32>
     {
         let vk = device.pointers();
 
@@ -95,15 +137,49 @@ impl UnsafeBuffer {
             return Err(BufferCreationError::SparseResidencyAliasedFeatureNotEnabled);
         }
 
+        // Checking the usages that require an extension.
+        if usage.shader_device_address && !device.buffer_device_address_enabled() {
+            return Err(BufferCreationError::BufferDeviceAddressFeatureNotEnabled);
+        }
+        if (usage.acceleration_structure_build_input || usage.acceleration_structure_storage) &&
+            !device.enabled_features().acceleration_structure
+        {
+            return Err(BufferCreationError::AccelerationStructureFeatureNotEnabled);
+        }
+        if usage.shader_binding_table && !device.enabled_features().ray_tracing_pipeline {
+            return Err(BufferCreationError::RayTracingPipelineFeatureNotEnabled);
+        }
+        if usage.conditional_rendering && !device.loaded_extensions().ext_conditional_rendering {
+            return Err(BufferCreationError::ConditionalRenderingExtensionNotEnabled);
+        }
+        if (usage.transform_feedback_buffer || usage.transform_feedback_counter_buffer) &&
+            !device.enabled_features().transform_feedback
+        {
+            return Err(BufferCreationError::TransformFeedbackFeatureNotEnabled);
+        }
+
         let buffer = {
             let (sh_mode, sh_indices) = match sharing {
                 Sharing::Exclusive => (vk::SHARING_MODE_EXCLUSIVE, SmallVec::<[u32; 8]>::new()),
                 Sharing::Concurrent(ids) => (vk::SHARING_MODE_CONCURRENT, ids.collect()),
             };
 
+            let external_infos = if external_handle_types != ExternalMemoryHandleTypes::none() {
+                Some(vk::ExternalMemoryBufferCreateInfo {
+                         sType: vk::STRUCTURE_TYPE_EXTERNAL_MEMORY_BUFFER_CREATE_INFO,
+                         pNext: ptr::null(),
+                         handleTypes: external_handle_types.into_vulkan_bits(),
+                     })
+            } else {
+                None
+            };
+
             let infos = vk::BufferCreateInfo {
                 sType: vk::STRUCTURE_TYPE_BUFFER_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: external_infos
+                    .as_ref()
+                    .map(|i| i as *const vk::ExternalMemoryBufferCreateInfo as *const _)
+                    .unwrap_or(ptr::null()),
                 flags: sparse.to_flags(),
                 size: size as u64,
                 usage: usage_bits,
@@ -115,7 +191,7 @@ impl UnsafeBuffer {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateBuffer(device.internal_object(),
                                          &infos,
-                                         ptr::null(),
+                                         device.allocator_ptr(),
                                          &mut output))?;
             output
         };
@@ -159,8 +235,12 @@ impl UnsafeBuffer {
 
                 let mut out = MemoryRequirements::from_vulkan_reqs(output.memoryRequirements);
                 if let Some(output2) = output2 {
-                    debug_assert_eq!(output2.requiresDedicatedAllocation, 0);
-                    out.prefer_dedicated = output2.prefersDedicatedAllocation != 0;
+                    // Only resources whose memory is shared with other APIs may require a
+                    // dedicated allocation.
+                    debug_assert!(output2.requiresDedicatedAllocation == 0 ||
+                                      external_handle_types != ExternalMemoryHandleTypes::none());
+                    out.prefer_dedicated = output2.prefersDedicatedAllocation != 0 ||
+                        output2.requiresDedicatedAllocation != 0;
                 }
                 out
 
@@ -202,6 +282,26 @@ impl UnsafeBuffer {
         Ok((obj, mem_reqs))
     }
 
+    /// Builds an `UnsafeBuffer` from a raw buffer handle, for example one that was created by
+    /// another library.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid buffer that was created from `device`, with the allocation
+    ///   callbacks of `device` if it has any.
+    /// - `size` and `usage` must match the parameters that the buffer was created with.
+    /// - The `UnsafeBuffer` takes ownership of `handle` and destroys it when it is dropped.
+    ///
+    pub unsafe fn from_raw(device: Arc<Device>, handle: u64, size: usize, usage: BufferUsage)
+                           -> UnsafeBuffer {
+        UnsafeBuffer {
+            buffer: handle,
+            device: device,
+            size: size,
+            usage: usage.to_vulkan_bits(),
+        }
+    }
+
     pub unsafe fn bind_memory(&self, memory: &DeviceMemory, offset: usize) -> Result<(), OomError> {
         let vk = self.device.pointers();
 
@@ -288,6 +388,65 @@ impl UnsafeBuffer {
         (self.usage & vk::BUFFER_USAGE_INDIRECT_BUFFER_BIT) != 0
     }
 
+    #[inline]
+    pub fn usage_shader_device_address(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_SHADER_DEVICE_ADDRESS_BIT_KHR) != 0
+    }
+
+    #[inline]
+    pub fn usage_acceleration_structure_build_input(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_ACCELERATION_STRUCTURE_BUILD_INPUT_READ_ONLY_BIT_KHR) != 0
+    }
+
+    #[inline]
+    pub fn usage_acceleration_structure_storage(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_ACCELERATION_STRUCTURE_STORAGE_BIT_KHR) != 0
+    }
+
+    #[inline]
+    pub fn usage_shader_binding_table(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_SHADER_BINDING_TABLE_BIT_KHR) != 0
+    }
+
+    #[inline]
+    pub fn usage_conditional_rendering(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_CONDITIONAL_RENDERING_BIT_EXT) != 0
+    }
+
+    #[inline]
+    pub fn usage_transform_feedback_buffer(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_TRANSFORM_FEEDBACK_BUFFER_BIT_EXT) != 0
+    }
+
+    #[inline]
+    pub fn usage_transform_feedback_counter_buffer(&self) -> bool {
+        (self.usage & vk::BUFFER_USAGE_TRANSFORM_FEEDBACK_COUNTER_BUFFER_BIT_EXT) != 0
+    }
+
+    /// Returns the address of the buffer in the device's address space.
+    ///
+    /// Returns `None` if the buffer wasn't created with the `shader_device_address` usage, or if
+    /// the `bufferDeviceAddress` feature of the `VK_KHR_buffer_device_address` extension isn't
+    /// enabled.
+    ///
+    /// The buffer must be bound to memory before the address can be queried.
+    #[inline]
+    pub fn device_address(&self) -> Option<u64> {
+        if !self.usage_shader_device_address() || !self.device.buffer_device_address_enabled() {
+            return None;
+        }
+
+        unsafe {
+            let vk = self.device.pointers();
+            let infos = vk::BufferDeviceAddressInfoKHR {
+                sType: vk::STRUCTURE_TYPE_BUFFER_DEVICE_ADDRESS_INFO_KHR,
+                pNext: ptr::null(),
+                buffer: self.buffer,
+            };
+            Some(vk.GetBufferDeviceAddressKHR(self.device.internal_object(), &infos))
+        }
+    }
+
     /// Returns a key unique to each `UnsafeBuffer`. Can be used for the `conflicts_key` method.
     #[inline]
     pub fn key(&self) -> u64 {
@@ -325,7 +484,9 @@ impl Drop for UnsafeBuffer {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyBuffer(self.device.internal_object(), self.buffer, ptr::null());
+            vk.DestroyBuffer(self.device.internal_object(),
+                             self.buffer,
+                             self.device.allocator_ptr());
         }
     }
 }
@@ -374,6 +535,24 @@ pub enum BufferCreationError {
     SparseResidencyBufferFeatureNotEnabled,
     /// Sparse aliasing was requested but the corresponding feature wasn't enabled.
     SparseResidencyAliasedFeatureNotEnabled,
+    /// The `shader_device_address` usage was requested but the `bufferDeviceAddress` feature
+    /// wasn't enabled.
+    BufferDeviceAddressFeatureNotEnabled,
+    /// An acceleration structure usage was requested but the `accelerationStructure` feature
+    /// wasn't enabled.
+    AccelerationStructureFeatureNotEnabled,
+    /// The `shader_binding_table` usage was requested but the `rayTracingPipeline` feature wasn't
+    /// enabled.
+    RayTracingPipelineFeatureNotEnabled,
+    /// The `conditional_rendering` usage was requested but the `VK_EXT_conditional_rendering`
+    /// extension wasn't enabled.
+    ConditionalRenderingExtensionNotEnabled,
+    /// A transform feedback usage was requested but the `transform_feedback` feature of the
+    /// `VK_EXT_transform_feedback` extension wasn't enabled.
+    TransformFeedbackFeatureNotEnabled,
+    /// An exportable buffer was requested but the `VK_KHR_external_memory` extension isn't
+    /// enabled.
+    ExternalMemoryExtensionNotEnabled,
 }
 
 impl error::Error for BufferCreationError {
@@ -390,6 +569,30 @@ impl error::Error for BufferCreationError {
             BufferCreationError::SparseResidencyAliasedFeatureNotEnabled => {
                 "sparse aliasing was requested but the corresponding feature wasn't enabled"
             },
+            BufferCreationError::BufferDeviceAddressFeatureNotEnabled => {
+                "the `shader_device_address` usage was requested but the `bufferDeviceAddress` \
+                 feature wasn't enabled"
+            },
+            BufferCreationError::AccelerationStructureFeatureNotEnabled => {
+                "an acceleration structure usage was requested but the `accelerationStructure` \
+                 feature wasn't enabled"
+            },
+            BufferCreationError::RayTracingPipelineFeatureNotEnabled => {
+                "the `shader_binding_table` usage was requested but the `rayTracingPipeline` \
+                 feature wasn't enabled"
+            },
+            BufferCreationError::ConditionalRenderingExtensionNotEnabled => {
+                "the `conditional_rendering` usage was requested but the \
+                 `VK_EXT_conditional_rendering` extension wasn't enabled"
+            },
+            BufferCreationError::TransformFeedbackFeatureNotEnabled => {
+                "a transform feedback usage was requested but the `transform_feedback` feature \
+                 wasn't enabled"
+            },
+            BufferCreationError::ExternalMemoryExtensionNotEnabled => {
+                "an exportable buffer was requested but the `VK_KHR_external_memory` extension \
+                 isn't enabled"
+            },
         }
     }
 
@@ -416,6 +619,19 @@ impl From<OomError> for BufferCreationError {
     }
 }
 
+impl From<BufferCreationError> for DeviceMemoryAllocError {
+    #[inline]
+    fn from(err: BufferCreationError) -> DeviceMemoryAllocError {
+        match err {
+            BufferCreationError::AllocError(err) => err,
+            BufferCreationError::ExternalMemoryExtensionNotEnabled => {
+                DeviceMemoryAllocError::ExternalMemoryExtensionNotEnabled
+            },
+            _ => DeviceMemoryAllocError::UsageNotSupported,
+        }
+    }
+}
+
 impl From<Error> for BufferCreationError {
     #[inline]
     fn from(err: Error) -> BufferCreationError {
@@ -432,6 +648,7 @@ impl From<Error> for BufferCreationError {
 #[cfg(test)]
 mod tests {
     use std::iter::Empty;
+    use std::mem;
 
     use super::BufferCreationError;
     use super::BufferUsage;
@@ -439,6 +656,7 @@ mod tests {
     use super::UnsafeBuffer;
 
     use device::Device;
+    use VulkanObject;
     use device::DeviceOwned;
     use sync::Sharing;
 
@@ -458,6 +676,28 @@ mod tests {
         assert_eq!(&**buf.device() as *const Device, &*device as *const Device);
     }
 
+    #[test]
+    fn raw_round_trip() {
+        let (device, _) = gfx_dev_and_queue!();
+        let (buf, _) = unsafe {
+            UnsafeBuffer::new(device.clone(),
+                              128,
+                              BufferUsage::all(),
+                              Sharing::Exclusive::<Empty<_>>,
+                              SparseLevel::none())
+        }.unwrap();
+
+        let handle = buf.as_raw();
+        mem::forget(buf);
+
+        let buf = unsafe {
+            UnsafeBuffer::from_raw(device.clone(), handle, 128, BufferUsage::all())
+        };
+        assert_eq!(buf.as_raw(), handle);
+        assert_eq!(buf.size(), 128);
+        assert!(buf.usage_transfer_source());
+    }
+
     #[test]
     fn panic_wrong_sparse_residency() {
         let (device, _) = gfx_dev_and_queue!();
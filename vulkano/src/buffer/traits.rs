@@ -30,6 +30,15 @@ pub unsafe trait BufferAccess: DeviceOwned {
     /// Returns the size of the buffer in bytes.
     fn size(&self) -> usize;
 
+    /// Returns the address of the start of the buffer in the device's address space.
+    ///
+    /// See `UnsafeBuffer::device_address` for the conditions under which `None` is returned.
+    #[inline]
+    fn device_address(&self) -> Option<u64> {
+        let inner = self.inner();
+        inner.buffer.device_address().map(|addr| addr + inner.offset as u64)
+    }
+
     /// Builds a `BufferSlice` object holding the buffer by reference.
     #[inline]
     fn as_buffer_slice(&self) -> BufferSlice<Self::Content, &Self>
@@ -156,7 +156,7 @@ impl<F, B> BufferView<F, B>
             let mut output = mem::uninitialized();
             check_errors(vk.CreateBufferView(device.internal_object(),
                                              &infos,
-                                             ptr::null(),
+                                             device.allocator_ptr(),
                                              &mut output))?;
             (output, format_props)
         };
@@ -238,7 +238,7 @@ impl<F, B> Drop for BufferView<F, B>
             let vk = self.buffer.inner().buffer.device().pointers();
             vk.DestroyBufferView(self.buffer.inner().buffer.device().internal_object(),
                                  self.view,
-                                 ptr::null());
+                                 self.buffer.inner().buffer.device().allocator_ptr());
         }
     }
 }
@@ -8,24 +8,50 @@
 // according to those terms.
 
 use std::error;
+use std::ffi::CStr;
 use std::fmt;
 use std::iter;
 use std::mem;
 use std::slice;
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
 
 use OomError;
+use VulkanObject;
+use acceleration_structure::AccelerationStructure;
+use acceleration_structure::AccelerationStructureGeometry;
+use acceleration_structure::BuildAccelerationStructureFlags;
+use acceleration_structure::BuildMode;
+use acceleration_structure::CopyMode;
 use buffer::BufferAccess;
+use buffer::BufferSlice;
+use buffer::BufferUsage;
+use buffer::DeviceLocalBuffer;
 use buffer::TypedBufferAccess;
+use command_buffer::BufferMemoryBarrier;
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
 use command_buffer::CommandBuffer;
 use command_buffer::CommandBufferExecError;
+use command_buffer::DrawIndexedIndirectCommand;
 use command_buffer::DrawIndirectCommand;
+use command_buffer::DrawMeshTasksIndirectCommand;
 use command_buffer::DynamicState;
+use command_buffer::DynamicStencilOp;
+use command_buffer::DynamicStencilValue;
+use command_buffer::ImageMemoryBarrier;
+use command_buffer::MultiDrawIndexedInfo;
+use command_buffer::MultiDrawInfo;
+use command_buffer::PipelineBarrier;
+use command_buffer::RawCommandsResources;
 use command_buffer::StateCacher;
 use command_buffer::StateCacherOutcome;
+use command_buffer::pool::CommandBufferAllocator;
+use command_buffer::pool::CommandPool;
 use command_buffer::pool::CommandPoolBuilderAlloc;
+use command_buffer::pool::StandardCommandPool;
 use command_buffer::pool::standard::StandardCommandPoolAlloc;
 use command_buffer::pool::standard::StandardCommandPoolBuilder;
 use command_buffer::synced::SyncCommandBuffer;
@@ -36,12 +62,14 @@ use command_buffer::sys::Kind;
 use command_buffer::sys::KindOcclusionQuery;
 use command_buffer::sys::KindSecondaryRenderPass;
 use command_buffer::sys::UnsafeCommandBuffer;
+use command_buffer::sys::UnsafeCommandBufferBuilder;
 use command_buffer::sys::UnsafeCommandBufferBuilderBufferImageCopy;
 use command_buffer::sys::UnsafeCommandBufferBuilderColorImageClear;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageAspect;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageBlit;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageCopy;
 use command_buffer::validity::*;
+use descriptor::descriptor::ShaderStages;
 use descriptor::descriptor_set::DescriptorSetsCollection;
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
 use device::Device;
@@ -66,14 +94,27 @@ use image::ImageLayout;
 use instance::QueueFamily;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
+use pipeline::ShaderBindingTableRegions;
+use pipeline::depth_stencil::StencilFaces;
 use pipeline::input_assembly::Index;
 use pipeline::vertex::VertexSource;
+use pipeline::viewport::Scissor;
+use pipeline::viewport::Viewport;
+use memory::DeviceMemoryAllocError;
 use query::QueryPipelineStatisticFlags;
+use query::QueryResultElement;
+use query::QueryResultFlags;
+use query::QueryType;
+use query::UnsafeQueryPool;
 use sampler::Filter;
 use sync::AccessCheckError;
 use sync::AccessFlagBits;
+use sync::Event;
 use sync::GpuFuture;
 use sync::PipelineStages;
+use vk;
 
 ///
 ///
@@ -86,6 +127,9 @@ pub struct AutoCommandBufferBuilder<P = StandardCommandPoolBuilder> {
     inner: SyncCommandBufferBuilder<P>,
     state_cacher: StateCacher,
 
+    // Id of the queue family the command buffer is allocated for.
+    queue_family_id: u32,
+
     // True if the queue family supports graphics operations.
     graphics_allowed: bool,
 
@@ -95,6 +139,28 @@ pub struct AutoCommandBufferBuilder<P = StandardCommandPoolBuilder> {
     // If we're inside a render pass, contains the render pass and the subpass index.
     render_pass: Option<(Box<RenderPassAbstract>, u32)>,
 
+    // Dimensions of the framebuffer of the current render pass, if known. Always `None` for
+    // secondary command buffers that don't know their framebuffer.
+    framebuffer_dimensions: Option<[u32; 3]>,
+
+    // Device mask that the current render pass was begun with, if any. `None` if the render pass
+    // runs on all the physical devices of the device.
+    render_pass_device_mask: Option<u32>,
+
+    // Queries that have been begun and not ended yet, with their index in their pool.
+    active_queries: Vec<(Arc<UnsafeQueryPool>, u32)>,
+
+    // Number of debug label regions that have been begun and not ended yet.
+    debug_label_depth: u32,
+
+    // If transform feedback is active, contains the counter buffers that are written when it
+    // ends.
+    transform_feedback: Option<Vec<Arc<BufferAccess + Send + Sync>>>,
+
+    // False if `set_pipeline_checks` has been called to skip the pipeline checks in release
+    // builds.
+    pipeline_checks: bool,
+
     // True if we are a secondary command buffer.
     secondary_cb: bool,
 
@@ -141,7 +207,12 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
     /// Starts building a primary command buffer.
     ///
     /// Contrary to `primary`, the final command buffer can be executed multiple times in parallel
-    /// in multiple different queues.
+    /// in multiple different queues. This is typically used to pre-record one command buffer per
+    /// swapchain image and submit it again while the previous frame is still in flight.
+    ///
+    /// The secondary command buffers executed by this command buffer must have been created with
+    /// the `SimultaneousUse` flag as well, otherwise submitting the command buffer while it is
+    /// already pending returns `CommandBufferExecError::ExclusiveAlreadyInUse`.
     #[inline]
     pub fn primary_simultaneous_use(
         device: Arc<Device>, queue_family: QueueFamily)
@@ -241,6 +312,7 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
                               }),
             occlusion_query: KindOcclusionQuery::Forbidden,
             query_statistics_flags: QueryPipelineStatisticFlags::none(),
+            conditional_rendering: false,
         };
 
         AutoCommandBufferBuilder::with_flags(device, queue_family, kind, Flags::None)
@@ -266,6 +338,7 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
                               }),
             occlusion_query: KindOcclusionQuery::Forbidden,
             query_statistics_flags: QueryPipelineStatisticFlags::none(),
+            conditional_rendering: false,
         };
 
         AutoCommandBufferBuilder::with_flags(device, queue_family, kind, Flags::OneTimeSubmit)
@@ -290,6 +363,7 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
                               }),
             occlusion_query: KindOcclusionQuery::Forbidden,
             query_statistics_flags: QueryPipelineStatisticFlags::none(),
+            conditional_rendering: false,
         };
 
         AutoCommandBufferBuilder::with_flags(device, queue_family, kind, Flags::SimultaneousUse)
@@ -312,6 +386,7 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
                               }),
             occlusion_query,
             query_statistics_flags,
+            conditional_rendering: false,
         };
 
         AutoCommandBufferBuilder::with_flags(device, queue_family, kind, Flags::None)
@@ -334,6 +409,7 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
                               }),
             occlusion_query,
             query_statistics_flags,
+            conditional_rendering: false,
         };
 
         AutoCommandBufferBuilder::with_flags(device, queue_family, kind, Flags::OneTimeSubmit)
@@ -356,42 +432,101 @@ impl AutoCommandBufferBuilder<StandardCommandPoolBuilder> {
                               }),
             occlusion_query,
             query_statistics_flags,
+            conditional_rendering: false,
         };
 
         AutoCommandBufferBuilder::with_flags(device, queue_family, kind, Flags::SimultaneousUse)
     }
 
+    /// Starts building a command buffer allocated from a `CommandBufferAllocator`.
+    ///
+    /// The command buffer is allocated from the pool of the current thread, which makes it
+    /// possible to build command buffers from multiple threads without locking.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the allocator and the queue family don't belong to the same physical device.
+    ///
+    #[inline]
+    pub fn with_allocator<R, F>(allocator: &CommandBufferAllocator, queue_family: QueueFamily,
+                                kind: Kind<R, F>, flags: Flags)
+                                -> Result<AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+                                          OomError>
+        where R: RenderPassAbstract + Clone + Send + Sync + 'static,
+              F: FramebufferAbstract
+    {
+        AutoCommandBufferBuilder::with_pool(allocator.pool(queue_family), kind, flags)
+    }
+
     // Actual constructor. Private.
     fn with_flags<R, F>(device: Arc<Device>, queue_family: QueueFamily, kind: Kind<R, F>,
                         flags: Flags)
                         -> Result<AutoCommandBufferBuilder<StandardCommandPoolBuilder>, OomError>
         where R: RenderPassAbstract + Clone + Send + Sync + 'static,
               F: FramebufferAbstract
+    {
+        let pool = Device::standard_command_pool(&device, queue_family);
+        AutoCommandBufferBuilder::with_pool(&pool, kind, flags)
+    }
+
+    fn with_pool<R, F>(pool: &Arc<StandardCommandPool>, kind: Kind<R, F>, flags: Flags)
+                       -> Result<AutoCommandBufferBuilder<StandardCommandPoolBuilder>, OomError>
+        where R: RenderPassAbstract + Clone + Send + Sync + 'static,
+              F: FramebufferAbstract
+    {
+        let secondary = match kind {
+            Kind::Primary => false,
+            Kind::Secondary { .. } => true,
+        };
+
+        let alloc = pool.alloc(secondary, 1)?
+            .next()
+            .expect("Requested one command buffer from the command pool, but got zero.");
+        AutoCommandBufferBuilder::from_alloc(alloc, kind, flags)
+    }
+
+    // Starts recording a command buffer that is already allocated. The kind must match how the
+    // command buffer was allocated.
+    fn from_alloc<R, F>(alloc: StandardCommandPoolBuilder, kind: Kind<R, F>, flags: Flags)
+                        -> Result<AutoCommandBufferBuilder<StandardCommandPoolBuilder>, OomError>
+        where R: RenderPassAbstract + Clone + Send + Sync + 'static,
+              F: FramebufferAbstract
     {
         unsafe {
-            let (secondary_cb, render_pass) = match kind {
-                Kind::Primary => (false, None),
+            let (secondary_cb, render_pass, framebuffer_dimensions) = match kind {
+                Kind::Primary => (false, None, None),
                 Kind::Secondary { render_pass: Some(ref sec), .. } => {
                     let render_pass = sec.subpass.render_pass().clone();
                     let index = sec.subpass.index();
-                    (true, Some((Box::new(render_pass) as Box<_>, index)))
+                    let dimensions = sec.framebuffer.as_ref().map(|f| f.dimensions());
+                    (true, Some((Box::new(render_pass) as Box<_>, index)), dimensions)
                 },
-                Kind::Secondary { render_pass: None, .. } => (true, None),
+                Kind::Secondary { render_pass: None, .. } => (true, None, None),
             };
 
-            let pool = Device::standard_command_pool(&device, queue_family);
-            let inner = SyncCommandBufferBuilder::new(&pool, kind, flags);
-            let state_cacher = StateCacher::new();
+            let queue_family_id = alloc.queue_family().id();
+            let graphics_allowed = alloc.queue_family().supports_graphics();
+            let compute_allowed = alloc.queue_family().supports_compute();
 
-            let graphics_allowed = queue_family.supports_graphics();
-            let compute_allowed = queue_family.supports_compute();
+            let inside_render_pass = render_pass.is_some();
+            let cmd = UnsafeCommandBufferBuilder::already_allocated(alloc, kind, flags)?;
+            let inner =
+                SyncCommandBufferBuilder::from_unsafe_cmd(cmd, secondary_cb, inside_render_pass);
+            let state_cacher = StateCacher::new();
 
             Ok(AutoCommandBufferBuilder {
-                   inner: inner?,
+                   inner: inner,
                    state_cacher,
+                   queue_family_id,
                    graphics_allowed,
                    compute_allowed,
                    render_pass,
+                   framebuffer_dimensions,
+                   render_pass_device_mask: None,
+                   active_queries: Vec::new(),
+                   debug_label_depth: 0,
+                   transform_feedback: None,
+                   pipeline_checks: true,
                    secondary_cb,
                    subpass_secondary: false,
                    flags,
@@ -443,13 +578,46 @@ impl<P> AutoCommandBufferBuilder<P> {
             return Err(AutoCommandBufferBuilderContextError::WrongSubpassIndex);
         }
 
-        if !RenderPassCompatible::is_compatible_with(pipeline, &local_render_pass.0) {
+        if self.pipeline_checks_enabled() &&
+            !RenderPassCompatible::is_compatible_with(pipeline, &local_render_pass.0)
+        {
             return Err(AutoCommandBufferBuilderContextError::IncompatibleRenderPass);
         }
 
         Ok(())
     }
 
+    // Returns true if the checks of the compatibility between the pipeline and the other
+    // parameters of the draw, dispatch and trace rays commands must be performed.
+    #[inline]
+    fn pipeline_checks_enabled(&self) -> bool {
+        self.pipeline_checks || cfg!(debug_assertions)
+    }
+
+    /// Enables or disables the checks of the compatibility between the pipeline and the dynamic
+    /// state, push constants, descriptor sets and render pass passed to the draw, dispatch and
+    /// trace rays commands that are added afterwards. These checks are enabled by default.
+    ///
+    /// With tens of thousands of draw commands, the CPU cost of these checks can become
+    /// noticeable. Disabling them is meant for release builds of command buffers whose pattern has
+    /// already been validated: the checks are always performed when debug assertions are enabled,
+    /// whatever the value passed here.
+    ///
+    /// The other checks of the commands, and the synchronization of the resources, are not
+    /// affected.
+    ///
+    /// # Safety
+    ///
+    /// If the checks are disabled, the pipeline of every draw, dispatch and trace rays command
+    /// must be compatible with the other parameters of the command and with the current render
+    /// pass and subpass.
+    ///
+    #[inline]
+    pub unsafe fn set_pipeline_checks(mut self, enabled: bool) -> Self {
+        self.pipeline_checks = enabled;
+        self
+    }
+
     /// Builds the command buffer.
     #[inline]
     pub fn build(self) -> Result<AutoCommandBuffer<P::Alloc>, BuildError>
@@ -459,6 +627,18 @@ impl<P> AutoCommandBufferBuilder<P> {
             return Err(AutoCommandBufferBuilderContextError::ForbiddenInsideRenderPass.into());
         }
 
+        if !self.active_queries.is_empty() {
+            return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
+        }
+
+        if self.debug_label_depth != 0 {
+            return Err(AutoCommandBufferBuilderContextError::DebugLabelIsActive.into());
+        }
+
+        if self.transform_feedback.is_some() {
+            return Err(AutoCommandBufferBuilderContextError::TransformFeedbackIsActive.into());
+        }
+
         let submit_state = match self.flags {
             Flags::None => {
                 SubmitState::ExclusiveUse { in_use: AtomicBool::new(false) }
@@ -474,9 +654,74 @@ impl<P> AutoCommandBufferBuilder<P> {
         Ok(AutoCommandBuffer {
                inner: self.inner.build()?,
                submit_state,
+               pending_submissions: AtomicUsize::new(0),
            })
     }
 
+    /// Adds a command that opens a debug label region named `name`.
+    ///
+    /// Debugging tools such as RenderDoc group all the commands recorded until the matching
+    /// `end_debug_label` under this label. Regions can be nested, and must all be closed before the
+    /// command buffer is built.
+    ///
+    /// Requires the `VK_EXT_debug_utils` instance extension.
+    pub fn begin_debug_label(mut self, name: &CStr, color: [f32; 4])
+                             -> Result<Self, DebugLabelError> {
+        unsafe {
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_debug_label(self.device())?;
+
+            self.debug_label_depth += 1;
+            self.inner.begin_debug_utils_label(name.to_owned(), color);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that begins the query `query` of `query_pool`.
+    ///
+    /// The query pool must be an occlusion or a pipeline statistics query pool, and only one
+    /// query of each type can be active at a time. The query must have been reset beforehand, for
+    /// example with `reset_query_pool`, and must be ended with `end_query` before the command
+    /// buffer is built.
+    ///
+    /// If `precise` is true, the result of an occlusion query is the exact number of samples
+    /// that passed the tests, instead of only being non-zero if any sample passed. This requires
+    /// the `occlusion_query_precise` feature.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the query pool doesn't belong to the same device as the builder.
+    ///
+    pub fn begin_query(mut self, query_pool: Arc<UnsafeQueryPool>, query: u32, precise: bool)
+                       -> Result<Self, BeginQueryError> {
+        unsafe {
+            let allowed = match query_pool.ty() {
+                QueryType::Occlusion => self.graphics_allowed,
+                _ => self.graphics_allowed || self.compute_allowed,
+            };
+            if !allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_begin_query(self.device(), &query_pool, query, precise)?;
+
+            let ty = mem::discriminant(&query_pool.ty());
+            if self.active_queries
+                .iter()
+                .any(|&(ref pool, _)| mem::discriminant(&pool.ty()) == ty)
+            {
+                return Err(AutoCommandBufferBuilderContextError::QueryIsActive.into());
+            }
+
+            self.active_queries.push((query_pool.clone(), query));
+            self.inner.begin_query(query_pool, query, precise);
+            Ok(self)
+        }
+    }
+
     /// Adds a command that enters a render pass.
     ///
     /// If `secondary` is true, then you will only be able to add secondary command buffers while
@@ -487,9 +732,41 @@ impl<P> AutoCommandBufferBuilder<P> {
     ///
     /// You must call this before you can add draw commands.
     #[inline]
-    pub fn begin_render_pass<F, C>(mut self, framebuffer: F, secondary: bool, clear_values: C)
+    pub fn begin_render_pass<F, C>(self, framebuffer: F, secondary: bool, clear_values: C)
                                    -> Result<Self, BeginRenderPassError>
         where F: FramebufferAbstract + RenderPassDescClearValues<C> + Clone + Send + Sync + 'static
+    {
+        self.begin_render_pass_impl(framebuffer, secondary, clear_values, None)
+    }
+
+    /// Same as `begin_render_pass`, but the render pass only runs on the physical devices whose
+    /// bit is set in `device_mask`.
+    ///
+    /// `render_areas` is either empty, in which case all the physical devices render to the
+    /// whole framebuffer, or contains one render area per physical device of the device. This
+    /// makes it possible to split a frame between multiple GPUs.
+    ///
+    /// The draw commands of the render pass can be restricted further with `set_device_mask`.
+    ///
+    /// Requires the `VK_KHR_device_group` device extension.
+    #[inline]
+    pub fn begin_render_pass_device_group<F, C>(self, framebuffer: F, secondary: bool,
+                                                clear_values: C, device_mask: u32,
+                                                render_areas: Vec<Scissor>)
+                                                -> Result<Self, BeginRenderPassError>
+        where F: FramebufferAbstract + RenderPassDescClearValues<C> + Clone + Send + Sync + 'static
+    {
+        check_device_group_render_pass(self.device(), device_mask, &render_areas)?;
+        self.begin_render_pass_impl(framebuffer,
+                                    secondary,
+                                    clear_values,
+                                    Some((device_mask, render_areas)))
+    }
+
+    fn begin_render_pass_impl<F, C>(mut self, framebuffer: F, secondary: bool, clear_values: C,
+                                    device_group: Option<(u32, Vec<Scissor>)>)
+                                    -> Result<Self, BeginRenderPassError>
+        where F: FramebufferAbstract + RenderPassDescClearValues<C> + Clone + Send + Sync + 'static
     {
         unsafe {
             if self.secondary_cb {
@@ -559,14 +836,120 @@ impl<P> AutoCommandBufferBuilder<P> {
             } else {
                 SubpassContents::Inline
             };
-            self.inner
-                .begin_render_pass(framebuffer.clone(), contents, clear_values)?;
+            self.render_pass_device_mask = match device_group {
+                Some((device_mask, render_areas)) => {
+                    self.inner.begin_render_pass_device_group(framebuffer.clone(),
+                                                              contents,
+                                                              clear_values,
+                                                              device_mask,
+                                                              render_areas)?;
+                    Some(device_mask)
+                },
+                None => {
+                    self.inner
+                        .begin_render_pass(framebuffer.clone(), contents, clear_values)?;
+                    None
+                },
+            };
+            self.framebuffer_dimensions = Some(framebuffer.dimensions());
             self.render_pass = Some((Box::new(framebuffer) as Box<_>, 0));
             self.subpass_secondary = secondary;
             Ok(self)
         }
     }
 
+    /// Adds a command that begins writing the vertices output by the following draw commands to
+    /// the bound transform feedback buffers.
+    ///
+    /// Transform feedback must be begun and ended in the same subpass. When it ends, the number
+    /// of bytes written to each transform feedback buffer is stored in the corresponding element
+    /// of `counter_buffers`, which can then be passed to `draw_indirect_byte_count` in order to
+    /// draw the captured vertices. If `resume` is true, the writes start at the byte counts that
+    /// are currently stored in the counter buffers instead of the start of the transform feedback
+    /// buffers, which appends the vertices to the ones of a previous capture.
+    ///
+    /// The counter buffers must have been created with the `transform_feedback_counter_buffer`
+    /// usage. Requires the `transform_feedback` feature of the `VK_EXT_transform_feedback`
+    /// extension.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the counter buffers doesn't belong to the same device as the builder.
+    ///
+    pub fn begin_transform_feedback(mut self,
+                                    counter_buffers: Vec<Arc<BufferAccess + Send + Sync>>,
+                                    resume: bool)
+                                    -> Result<Self, BeginTransformFeedbackError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            if self.render_pass.is_none() {
+                return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass
+                               .into());
+            }
+
+            if self.subpass_secondary {
+                return Err(AutoCommandBufferBuilderContextError::WrongSubpassType.into());
+            }
+
+            if self.transform_feedback.is_some() {
+                return Err(AutoCommandBufferBuilderContextError::TransformFeedbackIsActive.into());
+            }
+
+            check_transform_feedback_counters(self.device(),
+                                              counter_buffers
+                                                  .iter()
+                                                  .map(|b| &**b as &BufferAccess))?;
+
+            let boxed = counter_buffers
+                .iter()
+                .map(|b| Box::new(b.clone()) as Box<_>)
+                .collect();
+            self.inner.begin_transform_feedback(boxed, resume)?;
+            self.transform_feedback = Some(counter_buffers);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that binds `buffers` as the transform feedback buffers, starting at
+    /// binding `first_binding`.
+    ///
+    /// The buffers must have been created with the `transform_feedback_buffer` usage, and can't
+    /// be changed while transform feedback is active. Requires the `transform_feedback` feature of
+    /// the `VK_EXT_transform_feedback` extension.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the buffers doesn't belong to the same device as the builder.
+    ///
+    pub fn bind_transform_feedback_buffers(mut self, first_binding: u32,
+                                           buffers: Vec<Arc<BufferAccess + Send + Sync>>)
+                                           -> Result<Self, BindTransformFeedbackBuffersError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            if self.transform_feedback.is_some() {
+                return Err(AutoCommandBufferBuilderContextError::TransformFeedbackIsActive.into());
+            }
+
+            check_transform_feedback_buffers(self.device(),
+                                             first_binding,
+                                             buffers.iter().map(|b| &**b as &BufferAccess))?;
+
+            let buffers = buffers
+                .into_iter()
+                .map(|b| Box::new(b) as Box<_>)
+                .collect();
+            self.inner
+                .bind_transform_feedback_buffers(first_binding, buffers)?;
+            Ok(self)
+        }
+    }
+
     /// Adds a command that copies an image to another.
     ///
     /// Copy operations have several restrictions:
@@ -737,6 +1120,112 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that builds an acceleration structure out of `geometries`.
+    ///
+    /// With `BuildMode::Update`, the acceleration structure is updated from an acceleration
+    /// structure that was previously built with the same geometries, which is faster than a
+    /// full build. The scratch memory needed by the build is allocated automatically.
+    ///
+    /// Requires the `VK_KHR_acceleration_structure` extension.
+    pub fn build_acceleration_structure(mut self, mode: BuildMode,
+                                        destination: Arc<AccelerationStructure>,
+                                        flags: BuildAccelerationStructureFlags,
+                                        geometries: Vec<AccelerationStructureGeometry>)
+                                        -> Result<Self, BuildAccelerationStructureError> {
+        unsafe {
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+
+            let source = match mode {
+                BuildMode::Build => None,
+                BuildMode::Update(source) => Some(source),
+            };
+
+            let sizes = check_build_acceleration_structure(self.device(),
+                                                           source.as_ref().map(|s| &**s),
+                                                           &destination,
+                                                           flags,
+                                                           &geometries)?;
+
+            let device = self.device().clone();
+            let scratch_size = if source.is_some() {
+                sizes.update_scratch_size
+            } else {
+                sizes.build_scratch_size
+            };
+
+            // The scratch memory must be aligned, so we allocate a bit more than needed and use
+            // the part that starts at the right address.
+            let alignment = device
+                .ray_tracing_properties()
+                .min_acceleration_structure_scratch_offset_alignment
+                .max(1) as usize;
+            let usage = BufferUsage {
+                storage_buffer: true,
+                shader_device_address: true,
+                ..BufferUsage::none()
+            };
+            let scratch = DeviceLocalBuffer::<[u8]>::array(device.clone(),
+                                                           scratch_size.max(1) + alignment,
+                                                           usage,
+                                                           device.active_queue_families())?;
+            let address = scratch.device_address().unwrap() as usize;
+            let offset = (alignment - address % alignment) % alignment;
+            let scratch = BufferSlice::from_typed_buffer_access(scratch)
+                .slice(offset .. offset + scratch_size.max(1))
+                .unwrap();
+            check_build_acceleration_structure_scratch(&sizes, source.is_some(), &scratch)?;
+
+            self.inner
+                .build_acceleration_structure(source,
+                                              destination,
+                                              flags,
+                                              geometries,
+                                              Arc::new(scratch))?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that clears regions of the attachments of the current subpass.
+    ///
+    /// Must be called inside of a render pass. Contrary to `clear_color_image`, only the
+    /// rectangles in `rects` are cleared, which makes it possible to clear part of the
+    /// framebuffer in the middle of a render pass. If the dimensions of the framebuffer are known,
+    /// the rectangles are checked against them.
+    #[inline]
+    pub fn clear_attachments(mut self, attachments: Vec<ClearAttachment>, rects: Vec<ClearRect>)
+                             -> Result<Self, ClearAttachmentsError> {
+        unsafe {
+            if !self.graphics_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            match self.render_pass {
+                Some((ref rp, index)) => {
+                    if self.subpass_secondary {
+                        return Err(AutoCommandBufferBuilderContextError::WrongSubpassType.into());
+                    }
+
+                    check_clear_attachments(&**rp,
+                                            index,
+                                            self.framebuffer_dimensions,
+                                            &attachments,
+                                            &rects)?;
+                },
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass
+                                   .into());
+                },
+            }
+
+            self.inner.clear_attachments(attachments, rects);
+            Ok(self)
+        }
+    }
+
     /// Adds a command that clears all the layers and mipmap levels of a color image with a
     /// specific value.
     ///
@@ -802,6 +1291,28 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that copies an acceleration structure into another one.
+    ///
+    /// With `CopyMode::Compact`, `destination` is usually created with the size written by
+    /// `write_acceleration_structure_compacted_size` in a previous submission.
+    ///
+    /// Requires the `VK_KHR_acceleration_structure` extension.
+    pub fn copy_acceleration_structure(mut self, source: Arc<AccelerationStructure>,
+                                       destination: Arc<AccelerationStructure>, mode: CopyMode)
+                                       -> Result<Self, CopyAccelerationStructureError> {
+        unsafe {
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+            check_copy_acceleration_structure(self.device(), &source, &destination, mode)?;
+            self.inner
+                .copy_acceleration_structure(source, destination, mode)?;
+            Ok(self)
+        }
+    }
+
     /// Adds a command that copies from a buffer to another.
     ///
     /// This command will copy from the source to the destination. If their size is not equal, then
@@ -946,6 +1457,52 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Adds a command that copies the results of `count` queries of `query_pool`, starting at
+    /// `first_query`, to `destination`.
+    ///
+    /// The results are written as 32-bits or 64-bits values depending on the content of
+    /// `destination`. Each query writes `num_results()` values of its query type, so pipeline
+    /// statistics queries write one value per enabled statistic, followed by an availability
+    /// value if `flags.with_availability` is true. The availability value is non-zero if the
+    /// results of the query are available.
+    ///
+    /// If `flags.wait` is true, the GPU waits until the results are available before copying
+    /// them. Otherwise the results of queries that aren't available yet are left untouched,
+    /// unless `flags.partial` is true.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the query pool or the buffer doesn't belong to the same device as the builder.
+    ///
+    pub fn copy_query_pool_results<D, T>(mut self, query_pool: Arc<UnsafeQueryPool>,
+                                         first_query: u32, count: u32, destination: D,
+                                         flags: QueryResultFlags)
+                                         -> Result<Self, CopyQueryPoolResultsError>
+        where D: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+              T: QueryResultElement
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_copy_query_pool_results(self.device(),
+                                          &query_pool,
+                                          first_query,
+                                          count,
+                                          &destination,
+                                          flags)?;
+
+            let num_values = query_pool.ty().num_results() +
+                if flags.with_availability { 1 } else { 0 };
+            let stride = num_values as usize * mem::size_of::<T>();
+            self.inner.copy_query_pool_results(query_pool,
+                                               first_query,
+                                               count,
+                                               destination,
+                                               stride,
+                                               flags)?;
+            Ok(self)
+        }
+    }
+
     #[inline]
     pub fn dispatch<Cp, S, Pc>(mut self, dimensions: [u32; 3], pipeline: Cp, sets: S, constants: Pc)
                                -> Result<Self, DispatchError>
@@ -958,8 +1515,10 @@ impl<P> AutoCommandBufferBuilder<P> {
             }
 
             self.ensure_outside_render_pass()?;
-            check_push_constants_validity(&pipeline, &constants)?;
-            check_descriptor_sets_validity(&pipeline, &sets)?;
+            if self.pipeline_checks_enabled() {
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
             check_dispatch(pipeline.device(), dimensions)?;
 
             if let StateCacherOutcome::NeedChange =
@@ -971,7 +1530,7 @@ impl<P> AutoCommandBufferBuilder<P> {
             push_constants(&mut self.inner, pipeline.clone(), constants);
             descriptor_sets(&mut self.inner,
                             &mut self.state_cacher,
-                            false,
+                            PipelineBindPoint::Compute,
                             pipeline.clone(),
                             sets)?;
 
@@ -980,6 +1539,50 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
+    /// Same as `dispatch`, but the workgroups start at `base` instead of `[0, 0, 0]`.
+    ///
+    /// The value of `gl_WorkGroupID` in the shader goes from `base` to `base + dimensions`, which
+    /// makes it possible to process a sub-region of a larger grid without passing the offset
+    /// through push constants.
+    ///
+    /// Requires the `VK_KHR_device_group` device extension. Compute pipelines created by vulkano
+    /// on a device with this extension can be used with this command.
+    pub fn dispatch_base<Cp, S, Pc>(mut self, base: [u32; 3], dimensions: [u32; 3], pipeline: Cp,
+                                    sets: S, constants: Pc)
+                                    -> Result<Self, DispatchBaseError>
+        where Cp: ComputePipelineAbstract + Send + Sync + 'static + Clone,
+              S: DescriptorSetsCollection
+    {
+        unsafe {
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+            if self.pipeline_checks_enabled() {
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            check_dispatch_base(pipeline.device(), base, dimensions)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_compute_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_compute(pipeline.clone());
+            }
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Compute,
+                            pipeline.clone(),
+                            sets)?;
+
+            self.inner.dispatch_base(base, dimensions);
+            Ok(self)
+        }
+    }
+
     #[inline]
     pub fn draw<V, Gp, S, Pc>(mut self, pipeline: Gp, dynamic: DynamicState, vertices: V, sets: S,
                               constants: Pc)
@@ -991,10 +1594,17 @@ impl<P> AutoCommandBufferBuilder<P> {
             // TODO: must check that pipeline is compatible with render pass
 
             self.ensure_inside_render_pass_inline(&pipeline)?;
-            check_dynamic_state_validity(&pipeline, &dynamic)?;
-            check_push_constants_validity(&pipeline, &constants)?;
-            check_descriptor_sets_validity(&pipeline, &sets)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
             let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
 
             if let StateCacherOutcome::NeedChange =
                 self.state_cacher.bind_graphics_pipeline(&pipeline)
@@ -1002,18 +1612,20 @@ impl<P> AutoCommandBufferBuilder<P> {
                 self.inner.bind_pipeline_graphics(pipeline.clone());
             }
 
+            let vertex_strides = dynamic.vertex_strides.clone();
             let dynamic = self.state_cacher.dynamic_state(dynamic);
 
             push_constants(&mut self.inner, pipeline.clone(), constants);
             set_state(&mut self.inner, dynamic);
             descriptor_sets(&mut self.inner,
                             &mut self.state_cacher,
-                            true,
+                            PipelineBindPoint::Graphics,
                             pipeline.clone(),
                             sets)?;
             vertex_buffers(&mut self.inner,
                            &mut self.state_cacher,
-                           vb_infos.vertex_buffers)?;
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
 
             debug_assert!(self.graphics_allowed);
 
@@ -1038,11 +1650,18 @@ impl<P> AutoCommandBufferBuilder<P> {
             // TODO: must check that pipeline is compatible with render pass
 
             self.ensure_inside_render_pass_inline(&pipeline)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
             let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
-            check_dynamic_state_validity(&pipeline, &dynamic)?;
-            check_push_constants_validity(&pipeline, &constants)?;
-            check_descriptor_sets_validity(&pipeline, &sets)?;
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
             let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
 
             if let StateCacherOutcome::NeedChange =
                 self.state_cacher.bind_graphics_pipeline(&pipeline)
@@ -1056,18 +1675,20 @@ impl<P> AutoCommandBufferBuilder<P> {
                 self.inner.bind_index_buffer(index_buffer, I::ty())?;
             }
 
+            let vertex_strides = dynamic.vertex_strides.clone();
             let dynamic = self.state_cacher.dynamic_state(dynamic);
 
             push_constants(&mut self.inner, pipeline.clone(), constants);
             set_state(&mut self.inner, dynamic);
             descriptor_sets(&mut self.inner,
                             &mut self.state_cacher,
-                            true,
+                            PipelineBindPoint::Graphics,
                             pipeline.clone(),
                             sets)?;
             vertex_buffers(&mut self.inner,
                            &mut self.state_cacher,
-                           vb_infos.vertex_buffers)?;
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
             // TODO: how to handle an index out of range of the vertex buffers?
 
             debug_assert!(self.graphics_allowed);
@@ -1094,10 +1715,17 @@ impl<P> AutoCommandBufferBuilder<P> {
             // TODO: must check that pipeline is compatible with render pass
 
             self.ensure_inside_render_pass_inline(&pipeline)?;
-            check_dynamic_state_validity(&pipeline, &dynamic)?;
-            check_push_constants_validity(&pipeline, &constants)?;
-            check_descriptor_sets_validity(&pipeline, &sets)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
             let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
 
             let draw_count = indirect_buffer.len() as u32;
 
@@ -1107,18 +1735,20 @@ impl<P> AutoCommandBufferBuilder<P> {
                 self.inner.bind_pipeline_graphics(pipeline.clone());
             }
 
+            let vertex_strides = dynamic.vertex_strides.clone();
             let dynamic = self.state_cacher.dynamic_state(dynamic);
 
             push_constants(&mut self.inner, pipeline.clone(), constants);
             set_state(&mut self.inner, dynamic);
             descriptor_sets(&mut self.inner,
                             &mut self.state_cacher,
-                            true,
+                            PipelineBindPoint::Graphics,
                             pipeline.clone(),
                             sets)?;
             vertex_buffers(&mut self.inner,
                            &mut self.state_cacher,
-                           vb_infos.vertex_buffers)?;
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
 
             debug_assert!(self.graphics_allowed);
 
@@ -1130,14 +1760,515 @@ impl<P> AutoCommandBufferBuilder<P> {
         }
     }
 
-    /// Adds a command that ends the current render pass.
+    /// Adds a command that draws the vertices captured by a previous transform feedback.
+    ///
+    /// The number of vertices is read by the device from `counter_buffer` when the command
+    /// executes: it is the byte count written by `end_transform_feedback`, minus
+    /// `counter_offset`, divided by `vertex_stride`. The captured buffer is usually passed as
+    /// one of the `vertices`.
+    ///
+    /// The counter buffer must have been created with the `transform_feedback_counter_buffer`
+    /// usage. Requires the `transform_feedback` feature of the `VK_EXT_transform_feedback`
+    /// extension, and a physical device that supports transform feedback draws.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the counter buffer doesn't belong to the same device as the builder.
     ///
-    /// This must be called after you went through all the subpasses and before you can build
-    /// the command buffer or add further commands.
     #[inline]
-    pub fn end_render_pass(mut self) -> Result<Self, AutoCommandBufferBuilderContextError> {
+    pub fn draw_indirect_byte_count<V, Gp, S, Pc, Cb>(mut self, pipeline: Gp,
+                                                      dynamic: DynamicState, vertices: V,
+                                                      counter_buffer: Cb, counter_offset: u32,
+                                                      vertex_stride: u32, sets: S, constants: Pc)
+                                                      -> Result<Self, DrawIndirectByteCountError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static
+    {
         unsafe {
-            if self.secondary_cb {
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            check_draw_indirect_byte_count(self.device(), &counter_buffer, vertex_stride)?;
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            }
+
+            let vertex_strides = dynamic.vertex_strides.clone();
+            let dynamic = self.state_cacher.dynamic_state(dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Graphics,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_indirect_byte_count(vb_infos.instance_count as u32,
+                                          0,
+                                          counter_buffer,
+                                          counter_offset,
+                                          vertex_stride)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that performs several draws, each with its own range of vertices, with the
+    /// same pipeline and state. This is equivalent to calling `draw` once for each element of
+    /// `draws`, but records a single command.
+    ///
+    /// Requires the `multiDraw` feature of the `VK_EXT_multi_draw` extension.
+    #[inline]
+    pub fn draw_multi<V, Gp, S, Pc>(mut self, pipeline: Gp, dynamic: DynamicState, vertices: V,
+                                    draws: Vec<MultiDrawInfo>, sets: S, constants: Pc)
+                                    -> Result<Self, DrawMultiError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
+            check_multi_draw(self.device(),
+                             draws.iter().map(|d| (d.first_vertex, d.vertex_count)),
+                             vb_infos.vertex_count as usize)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            }
+
+            let vertex_strides = dynamic.vertex_strides.clone();
+            let dynamic = self.state_cacher.dynamic_state(dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Graphics,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_multi(draws, vb_infos.instance_count as u32, 0);
+            Ok(self)
+        }
+    }
+
+    /// Same as `draw_multi`, but each draw uses its own range of `index_buffer`.
+    ///
+    /// Requires the `multiDraw` feature of the `VK_EXT_multi_draw` extension.
+    #[inline]
+    pub fn draw_multi_indexed<V, Gp, S, Pc, Ib, I>(mut self, pipeline: Gp, dynamic: DynamicState,
+                                                   vertices: V, index_buffer: Ib,
+                                                   draws: Vec<MultiDrawIndexedInfo>, sets: S,
+                                                   constants: Pc)
+                                                   -> Result<Self, DrawMultiIndexedError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+              I: Index + 'static
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            let ib_infos = check_index_buffer(self.device(), &index_buffer)?;
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
+            check_multi_draw(self.device(),
+                             draws.iter().map(|d| (d.first_index, d.index_count)),
+                             ib_infos.num_indices)?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            }
+
+            let vertex_strides = dynamic.vertex_strides.clone();
+            let dynamic = self.state_cacher.dynamic_state(dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Graphics,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
+            // TODO: how to handle an index out of range of the vertex buffers?
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_multi_indexed(draws, vb_infos.instance_count as u32, 0);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that draws using the commands of `indirect_buffer`, where the number of
+    /// draws is read by the device from `count_buffer` when the command executes. At most
+    /// `max_draw_count` draws are performed.
+    ///
+    /// This lets a compute shader choose the number of draws, for example after culling, without
+    /// reading anything back on the CPU.
+    ///
+    /// Requires the `VK_KHR_draw_indirect_count` extension.
+    #[inline]
+    pub fn draw_indirect_count<V, Gp, S, Pc, Ib, Cb>(mut self, pipeline: Gp, dynamic: DynamicState,
+                                                     vertices: V, indirect_buffer: Ib,
+                                                     count_buffer: Cb, max_draw_count: u32,
+                                                     sets: S, constants: Pc)
+                                                     -> Result<Self, DrawIndirectCountError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Ib: BufferAccess
+                      + TypedBufferAccess<Content = [DrawIndirectCommand]>
+                      + Send
+                      + Sync
+                      + 'static,
+              Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            check_indirect_count(self.device(), &indirect_buffer, &count_buffer, max_draw_count)?;
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            }
+
+            let vertex_strides = dynamic.vertex_strides.clone();
+            let dynamic = self.state_cacher.dynamic_state(dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Graphics,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_indirect_count(indirect_buffer,
+                                     count_buffer,
+                                     max_draw_count,
+                                     mem::size_of::<DrawIndirectCommand>() as u32)?;
+            Ok(self)
+        }
+    }
+
+    /// Same as `draw_indirect_count`, but draws indexed vertices using `index_buffer`.
+    ///
+    /// Requires the `VK_KHR_draw_indirect_count` extension.
+    #[inline]
+    pub fn draw_indexed_indirect_count<V, Gp, S, Pc, Ib, I, Db, Cb>(
+        mut self, pipeline: Gp, dynamic: DynamicState, vertices: V, index_buffer: Ib,
+        indirect_buffer: Db, count_buffer: Cb, max_draw_count: u32, sets: S, constants: Pc)
+        -> Result<Self, DrawIndexedIndirectCountError>
+        where Gp: GraphicsPipelineAbstract + VertexSource<V> + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Ib: BufferAccess + TypedBufferAccess<Content = [I]> + Send + Sync + 'static,
+              I: Index + 'static,
+              Db: BufferAccess
+                      + TypedBufferAccess<Content = [DrawIndexedIndirectCommand]>
+                      + Send
+                      + Sync
+                      + 'static,
+              Cb: BufferAccess + TypedBufferAccess<Content = u32> + Send + Sync + 'static
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            if pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            check_index_buffer(self.device(), &index_buffer)?;
+            check_indirect_count(self.device(), &indirect_buffer, &count_buffer, max_draw_count)?;
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            let vb_infos = check_vertex_buffers(&pipeline, vertices)?;
+            check_vertex_strides(&dynamic, vb_infos.vertex_buffers.len())?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            }
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_index_buffer(&index_buffer, I::ty())
+            {
+                self.inner.bind_index_buffer(index_buffer, I::ty())?;
+            }
+
+            let vertex_strides = dynamic.vertex_strides.clone();
+            let dynamic = self.state_cacher.dynamic_state(dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Graphics,
+                            pipeline.clone(),
+                            sets)?;
+            vertex_buffers(&mut self.inner,
+                           &mut self.state_cacher,
+                           vb_infos.vertex_buffers,
+                           vertex_strides)?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_indexed_indirect_count(indirect_buffer,
+                                             count_buffer,
+                                             max_draw_count,
+                                             mem::size_of::<DrawIndexedIndirectCommand>() as u32)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that draws with a pipeline that uses a mesh shader, by launching
+    /// `group_counts` workgroups of the task shader, or of the mesh shader if there is no task
+    /// shader.
+    ///
+    /// Requires the `VK_EXT_mesh_shader` extension.
+    #[inline]
+    pub fn draw_mesh_tasks<Gp, S, Pc>(mut self, pipeline: Gp, dynamic: DynamicState,
+                                      group_counts: [u32; 3], sets: S, constants: Pc)
+                                      -> Result<Self, DrawMeshTasksError>
+        where Gp: GraphicsPipelineAbstract + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            if !pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            check_draw_mesh_tasks(pipeline.device(), group_counts, pipeline.has_task_shader())?;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Graphics,
+                            pipeline.clone(),
+                            sets)?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.draw_mesh_tasks(group_counts);
+            Ok(self)
+        }
+    }
+
+    /// Same as `draw_mesh_tasks`, except that the numbers of workgroups are read from
+    /// `indirect_buffer`. One draw is performed for each element of the buffer.
+    ///
+    /// Requires the `VK_EXT_mesh_shader` extension.
+    #[inline]
+    pub fn draw_mesh_tasks_indirect<Gp, S, Pc, Ib>(mut self, pipeline: Gp, dynamic: DynamicState,
+                                                   indirect_buffer: Ib, sets: S, constants: Pc)
+                                                   -> Result<Self, DrawMeshTasksIndirectError>
+        where Gp: GraphicsPipelineAbstract + Send + Sync + 'static + Clone, // TODO: meh for Clone
+              S: DescriptorSetsCollection,
+              Ib: BufferAccess
+                      + TypedBufferAccess<Content = [DrawMeshTasksIndirectCommand]>
+                      + Send
+                      + Sync
+                      + 'static
+    {
+        unsafe {
+            // TODO: must check that pipeline is compatible with render pass
+
+            self.ensure_inside_render_pass_inline(&pipeline)?;
+            if !pipeline.has_mesh_shader() {
+                return Err(AutoCommandBufferBuilderContextError::MeshShaderMismatch.into());
+            }
+            let dynamic = self.state_cacher.fill_viewports_scissors(&pipeline, dynamic);
+            if self.pipeline_checks_enabled() {
+                check_dynamic_state_validity(&pipeline, &dynamic)?;
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+
+            let draw_count = indirect_buffer.len() as u32;
+
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_graphics_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_graphics(pipeline.clone());
+            }
+
+            let dynamic = self.state_cacher.dynamic_state(dynamic);
+
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            set_state(&mut self.inner, dynamic);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::Graphics,
+                            pipeline.clone(),
+                            sets)?;
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner
+                .draw_mesh_tasks_indirect(indirect_buffer,
+                                          draw_count,
+                                          mem::size_of::<DrawMeshTasksIndirectCommand>() as u32)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that closes the debug label region that was last opened with
+    /// `begin_debug_label` in this command buffer.
+    pub fn end_debug_label(mut self) -> Result<Self, DebugLabelError> {
+        unsafe {
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_debug_label(self.device())?;
+
+            if self.debug_label_depth == 0 {
+                return Err(AutoCommandBufferBuilderContextError::DebugLabelIsNotActive.into());
+            }
+
+            self.debug_label_depth -= 1;
+            self.inner.end_debug_utils_label();
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that ends the query `query` of `query_pool`, which must have been begun
+    /// with `begin_query` in this command buffer.
+    pub fn end_query(mut self, query_pool: Arc<UnsafeQueryPool>, query: u32)
+                     -> Result<Self, AutoCommandBufferBuilderContextError> {
+        unsafe {
+            let position = self.active_queries
+                .iter()
+                .position(|&(ref pool, q)| Arc::ptr_eq(pool, &query_pool) && q == query);
+
+            match position {
+                Some(position) => {
+                    self.active_queries.remove(position);
+                },
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::QueryIsNotActive);
+                },
+            }
+
+            self.inner.end_query(query_pool, query);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that ends the current render pass.
+    ///
+    /// This must be called after you went through all the subpasses and before you can build
+    /// the command buffer or add further commands.
+    #[inline]
+    pub fn end_render_pass(mut self) -> Result<Self, AutoCommandBufferBuilderContextError> {
+        unsafe {
+            if self.secondary_cb {
                 return Err(AutoCommandBufferBuilderContextError::ForbiddenInSecondary);
             }
 
@@ -1152,93 +2283,659 @@ impl<P> AutoCommandBufferBuilder<P> {
                                    current: index,
                                });
                 },
-            }
+            }
+
+            if self.transform_feedback.is_some() {
+                return Err(AutoCommandBufferBuilderContextError::TransformFeedbackIsActive);
+            }
+
+            debug_assert!(self.graphics_allowed);
+
+            self.inner.end_render_pass();
+            self.render_pass = None;
+            self.render_pass_device_mask = None;
+            self.framebuffer_dimensions = None;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that ends the transform feedback begun with `begin_transform_feedback`, and
+    /// writes the number of bytes written to each transform feedback buffer to the counter buffers
+    /// that were passed to it.
+    #[inline]
+    pub fn end_transform_feedback(mut self)
+                                  -> Result<Self, AutoCommandBufferBuilderContextError> {
+        unsafe {
+            let counter_buffers = match self.transform_feedback.take() {
+                Some(buffers) => buffers,
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::TransformFeedbackIsNotActive);
+                },
+            };
+
+            let boxed = counter_buffers
+                .into_iter()
+                .map(|b| Box::new(b) as Box<_>)
+                .collect();
+            self.inner.end_transform_feedback(boxed);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that executes a secondary command buffer.
+    ///
+    /// The buffers and images used by the secondary command buffer are synchronized with the
+    /// other commands of this builder, provided that the secondary command buffer reports them
+    /// through `CommandBuffer::buffer` and `CommandBuffer::image`, which is the case of the
+    /// command buffers created with vulkano.
+    ///
+    /// **This function is unsafe for now because safety checks are not implemented.**
+    // TODO: implement correctly
+    pub unsafe fn execute_commands<C>(mut self, command_buffer: C)
+                                      -> Result<Self, ExecuteCommandsError>
+        where C: CommandBuffer + Send + Sync + 'static
+    {
+        {
+            let mut builder = self.inner.execute_commands();
+            builder.add(command_buffer);
+            builder.submit()?;
+        }
+
+        self.state_cacher.invalidate();
+
+        Ok(self)
+    }
+
+    /// Adds a command that executes a secondary command buffer only if the 32-bits value of
+    /// `predicate` is non-zero, or zero if `inverted` is true.
+    ///
+    /// The predicate is read by the GPU when the command is executed, which makes it possible to
+    /// skip pre-recorded work depending on the results of previous commands. This requires the
+    /// `inherited_conditional_rendering` feature of the `VK_EXT_conditional_rendering` extension,
+    /// and the predicate buffer must have been created with the `conditional_rendering` usage.
+    ///
+    /// **This function is unsafe for the same reasons as `execute_commands`.** In addition, the
+    /// secondary command buffer must have been created with the `conditional_rendering` field of
+    /// `Kind::Secondary` set to true.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the predicate buffer doesn't belong to the same device as the builder.
+    ///
+    pub unsafe fn execute_commands_conditional<C, B>(
+        mut self, command_buffer: C, predicate: B, inverted: bool)
+        -> Result<Self, ExecuteCommandsConditionalError>
+        where C: CommandBuffer + Send + Sync + 'static,
+              B: TypedBufferAccess<Content = u32> + Send + Sync + 'static
+    {
+        if self.secondary_cb {
+            return Err(AutoCommandBufferBuilderContextError::ForbiddenInSecondary.into());
+        }
+
+        check_conditional_rendering(self.device(), &predicate)?;
+
+        self.inner.begin_conditional_rendering(predicate, inverted)?;
+        {
+            let mut builder = self.inner.execute_commands();
+            builder.add(command_buffer);
+            builder.submit()?;
+        }
+        self.inner.end_conditional_rendering();
+
+        self.state_cacher.invalidate();
+
+        Ok(self)
+    }
+
+    /// Adds a command that executes multiple secondary command buffers, in order.
+    ///
+    /// This is equivalent to calling `execute_commands` once per command buffer, except that they
+    /// are all executed by a single `vkCmdExecuteCommands`. Does nothing if the list is empty.
+    ///
+    /// **This function is unsafe for the same reasons as `execute_commands`.**
+    pub unsafe fn execute_commands_from_vec<C>(mut self, command_buffers: Vec<C>)
+                                               -> Result<Self, ExecuteCommandsError>
+        where C: CommandBuffer + Send + Sync + 'static
+    {
+        if command_buffers.is_empty() {
+            return Ok(self);
+        }
+
+        {
+            let mut builder = self.inner.execute_commands();
+            for command_buffer in command_buffers {
+                builder.add(command_buffer);
+            }
+            builder.submit()?;
+        }
+
+        self.state_cacher.invalidate();
+
+        Ok(self)
+    }
+
+    /// Adds a command that writes the content of a buffer.
+    ///
+    /// This function is similar to the `memset` function in C. The `data` parameter is a number
+    /// that will be repeatidely written through the entire buffer.
+    ///
+    /// > **Note**: This function is technically safe because buffers can only contain integers or
+    /// > floating point numbers, which are always valid whatever their memory representation is.
+    /// > But unless your buffer actually contains only 32-bits integers, you are encouraged to use
+    /// > this function only for zeroing the content of a buffer by passing `0` for the data.
+    // TODO: not safe because of signalling NaNs
+    #[inline]
+    pub fn fill_buffer<B>(mut self, buffer: B, data: u32) -> Result<Self, FillBufferError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_fill_buffer(self.device(), &buffer)?;
+            self.inner.fill_buffer(buffer, data);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that inserts a single debug label named `name`.
+    ///
+    /// Requires the `VK_EXT_debug_utils` instance extension.
+    pub fn insert_debug_label(mut self, name: &CStr, color: [f32; 4])
+                              -> Result<Self, DebugLabelError> {
+        unsafe {
+            if !self.graphics_allowed && !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            check_debug_label(self.device())?;
+
+            self.inner.insert_debug_utils_label(name.to_owned(), color);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that jumps to the next subpass of the current render pass.
+    #[inline]
+    pub fn next_subpass(mut self, secondary: bool)
+                        -> Result<Self, AutoCommandBufferBuilderContextError> {
+        unsafe {
+            if self.secondary_cb {
+                return Err(AutoCommandBufferBuilderContextError::ForbiddenInSecondary);
+            }
+
+            if self.transform_feedback.is_some() {
+                return Err(AutoCommandBufferBuilderContextError::TransformFeedbackIsActive);
+            }
+
+            match self.render_pass {
+                None => {
+                    return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass);
+                },
+                Some((ref rp, ref mut index)) => {
+                    if *index + 1 >= rp.num_subpasses() as u32 {
+                        return Err(AutoCommandBufferBuilderContextError::NumSubpassesMismatch {
+                                       actual: rp.num_subpasses() as u32,
+                                       current: *index,
+                                   });
+                    } else {
+                        *index += 1;
+                    }
+                },
+            };
+
+            self.subpass_secondary = secondary;
+
+            debug_assert!(self.graphics_allowed);
+
+            let contents = if secondary {
+                SubpassContents::SecondaryCommandBuffers
+            } else {
+                SubpassContents::Inline
+            };
+            self.inner.next_subpass(contents);
+            Ok(self)
+        }
+    }
+
+    /// Adds an explicit pipeline barrier.
+    ///
+    /// The builder already synchronizes the commands it records with each other. This is only
+    /// needed for accesses that vulkano doesn't know about, such as accesses performed by another
+    /// API on a shared resource, or to manage the layout of an image manually.
+    ///
+    /// The buffers and images of the barrier are considered as accessed by the destination stages
+    /// and accesses of the barrier by the following commands, and the images are considered to
+    /// be in their new layout.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the buffers or images of the barrier doesn't belong to the same device
+    ///   as the builder.
+    ///
+    #[inline]
+    pub fn pipeline_barrier(mut self, barrier: PipelineBarrier)
+                            -> Result<Self, PipelineBarrierError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_pipeline_barrier(self.device(), &barrier)?;
+            self.inner.pipeline_barrier(barrier)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a barrier that releases the ownership of `buffer` from the queue family of this
+    /// command buffer to `destination_family`, once `source_stages` and `source_access` have
+    /// completed.
+    ///
+    /// This is needed when a buffer that isn't shared between queue families is used on one
+    /// queue family and then on another, for example when uploading data on a transfer queue
+    /// and reading it on a graphics queue. The release must be paired with a call to
+    /// `acquire_buffer_ownership` in a command buffer of `destination_family`, which must be
+    /// executed after this one, usually by signaling a semaphore between the two submissions.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the buffer doesn't belong to the same device as the builder.
+    ///
+    pub fn release_buffer_ownership<B>(self, buffer: B, source_stages: PipelineStages,
+                                       source_access: AccessFlagBits,
+                                       destination_family: QueueFamily)
+                                       -> Result<Self, PipelineBarrierError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        let size = buffer.size();
+        let barrier = PipelineBarrier {
+            buffer_barriers: vec![
+                BufferMemoryBarrier {
+                    buffer: Arc::new(buffer),
+                    source_stages,
+                    source_access,
+                    destination_stages: PipelineStages {
+                        bottom_of_pipe: true,
+                        ..PipelineStages::none()
+                    },
+                    destination_access: AccessFlagBits::none(),
+                    range: 0 .. size,
+                    queue_family_transfer: Some((self.queue_family_id, destination_family.id())),
+                },
+            ],
+            ..PipelineBarrier::new()
+        };
+
+        self.pipeline_barrier(barrier)
+    }
+
+    /// Adds a barrier that acquires the ownership of `buffer` from `source_family` for the queue
+    /// family of this command buffer, before `destination_stages` and `destination_access`.
+    ///
+    /// This is the counterpart of `release_buffer_ownership`, which must have been recorded in a
+    /// command buffer of `source_family` that is executed before this one.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the buffer doesn't belong to the same device as the builder.
+    ///
+    pub fn acquire_buffer_ownership<B>(self, buffer: B, source_family: QueueFamily,
+                                       destination_stages: PipelineStages,
+                                       destination_access: AccessFlagBits)
+                                       -> Result<Self, PipelineBarrierError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        let size = buffer.size();
+        let barrier = PipelineBarrier {
+            buffer_barriers: vec![
+                BufferMemoryBarrier {
+                    buffer: Arc::new(buffer),
+                    source_stages: PipelineStages {
+                        top_of_pipe: true,
+                        ..PipelineStages::none()
+                    },
+                    source_access: AccessFlagBits::none(),
+                    destination_stages,
+                    destination_access,
+                    range: 0 .. size,
+                    queue_family_transfer: Some((source_family.id(), self.queue_family_id)),
+                },
+            ],
+            ..PipelineBarrier::new()
+        };
+
+        self.pipeline_barrier(barrier)
+    }
+
+    /// Adds a barrier that releases the ownership of all the subresources of `image` from the
+    /// queue family of this command buffer to `destination_family`, once `source_stages` and
+    /// `source_access` have completed.
+    ///
+    /// The image is transitioned from `old_layout` to `new_layout` as part of the transfer. The
+    /// paired call to `acquire_image_ownership` must use the same layouts.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the image doesn't belong to the same device as the builder.
+    ///
+    pub fn release_image_ownership<I>(self, image: I, source_stages: PipelineStages,
+                                      source_access: AccessFlagBits,
+                                      destination_family: QueueFamily, old_layout: ImageLayout,
+                                      new_layout: ImageLayout)
+                                      -> Result<Self, PipelineBarrierError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        let mipmap_levels = image.mipmap_levels();
+        let array_layers = image.dimensions().array_layers();
+        let barrier = PipelineBarrier {
+            image_barriers: vec![
+                ImageMemoryBarrier {
+                    image: Arc::new(image),
+                    source_stages,
+                    source_access,
+                    destination_stages: PipelineStages {
+                        bottom_of_pipe: true,
+                        ..PipelineStages::none()
+                    },
+                    destination_access: AccessFlagBits::none(),
+                    mipmap_levels: 0 .. mipmap_levels,
+                    array_layers: 0 .. array_layers,
+                    old_layout,
+                    new_layout,
+                    queue_family_transfer: Some((self.queue_family_id, destination_family.id())),
+                },
+            ],
+            ..PipelineBarrier::new()
+        };
+
+        self.pipeline_barrier(barrier)
+    }
+
+    /// Adds a barrier that acquires the ownership of all the subresources of `image` from
+    /// `source_family` for the queue family of this command buffer, before `destination_stages`
+    /// and `destination_access`.
+    ///
+    /// This is the counterpart of `release_image_ownership`, and must use the same layouts.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the image doesn't belong to the same device as the builder.
+    ///
+    pub fn acquire_image_ownership<I>(self, image: I, source_family: QueueFamily,
+                                      destination_stages: PipelineStages,
+                                      destination_access: AccessFlagBits,
+                                      old_layout: ImageLayout, new_layout: ImageLayout)
+                                      -> Result<Self, PipelineBarrierError>
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        let mipmap_levels = image.mipmap_levels();
+        let array_layers = image.dimensions().array_layers();
+        let barrier = PipelineBarrier {
+            image_barriers: vec![
+                ImageMemoryBarrier {
+                    image: Arc::new(image),
+                    source_stages: PipelineStages {
+                        top_of_pipe: true,
+                        ..PipelineStages::none()
+                    },
+                    source_access: AccessFlagBits::none(),
+                    destination_stages,
+                    destination_access,
+                    mipmap_levels: 0 .. mipmap_levels,
+                    array_layers: 0 .. array_layers,
+                    old_layout,
+                    new_layout,
+                    queue_family_transfer: Some((source_family.id(), self.queue_family_id)),
+                },
+            ],
+            ..PipelineBarrier::new()
+        };
 
-            debug_assert!(self.graphics_allowed);
+        self.pipeline_barrier(barrier)
+    }
 
-            self.inner.end_render_pass();
-            self.render_pass = None;
-            Ok(self)
+    /// Adds a command that writes `data` to the push constants of `pipeline_layout`, starting at
+    /// `offset` bytes, for the shader stages `stages`.
+    ///
+    /// The range of bytes that is written and `stages` are checked against the push constants
+    /// ranges of the layout, which are usually obtained by reflection of the shaders.
+    ///
+    /// > **Note**: The draw, dispatch and trace rays commands of this builder write the push
+    /// > constants they receive to all the ranges of their pipeline's layout, which overwrites
+    /// > the values written by this command.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `pipeline_layout` doesn't belong to the same device as the builder.
+    ///
+    #[inline]
+    pub fn push_constants<Pl, D>(mut self, pipeline_layout: Pl, stages: ShaderStages, offset: u32,
+                                 data: &D)
+                                 -> Result<Self, PushConstantsError>
+        where Pl: PipelineLayoutAbstract + Send + Sync + 'static,
+              D: ?Sized + Send + Sync + 'static
+    {
+        assert_eq!(pipeline_layout.device().internal_object(),
+                   self.device().internal_object());
+
+        let size = mem::size_of_val(data) as u32;
+        check_push_constants_range(&pipeline_layout, stages, offset, size)?;
+
+        unsafe {
+            self.inner
+                .push_constants(pipeline_layout, stages, offset, size, data);
         }
+
+        Ok(self)
     }
 
-    /// Adds a command that executes a secondary command buffer.
+    /// Records commands that vulkano doesn't wrap, for example commands of an extension that
+    /// isn't supported yet.
     ///
-    /// **This function is unsafe for now because safety checks and synchronization are not
-    /// implemented.**
-    // TODO: implement correctly
-    pub unsafe fn execute_commands<C>(mut self, command_buffer: C)
-                                      -> Result<Self, ExecuteCommandsError>
-        where C: CommandBuffer + Send + Sync + 'static
+    /// `f` is called with the function pointers of the device and the raw command buffer. Naming
+    /// these types requires adding the `vk-sys` crate as a dependency. Depending on the state
+    /// of the builder, `f` isn't necessarily called immediately but only when the command buffer
+    /// is built.
+    ///
+    /// The buffers and images of `resources` are kept alive by the command buffer and are
+    /// synchronized with the other commands of the builder.
+    ///
+    /// # Safety
+    ///
+    /// - The commands must only access the buffers and images declared in `resources`, in the
+    ///   stages, with the accesses and in the layouts that are declared.
+    /// - The commands must leave the command buffer in a state where the other commands of the
+    ///   builder are still valid. In particular, the state set by the builder (bound pipelines,
+    ///   descriptor sets, dynamic state, ...) must not be modified.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the buffers or images of `resources` doesn't belong to the same device
+    ///   as the builder.
+    ///
+    pub unsafe fn raw_commands<F>(mut self, resources: RawCommandsResources, f: F)
+                                  -> Result<Self, RawCommandsError>
+        where F: FnOnce(&vk::DevicePointers, vk::CommandBuffer) + Send + Sync + 'static
     {
-        {
-            let mut builder = self.inner.execute_commands();
-            builder.add(command_buffer);
-            builder.submit()?;
+        for b in resources.buffers.iter() {
+            assert_eq!(b.buffer.inner().buffer.device().internal_object(),
+                       self.device().internal_object());
         }
 
-        self.state_cacher.invalidate();
+        for i in resources.images.iter() {
+            assert_eq!(i.image.inner().image.device().internal_object(),
+                       self.device().internal_object());
+        }
 
+        self.inner.raw_commands(resources, f)?;
         Ok(self)
     }
 
-    /// Adds a command that writes the content of a buffer.
+    /// Adds a command that changes `event` to the unsignaled state once all the previous commands
+    /// have reached `stages`.
     ///
-    /// This function is similar to the `memset` function in C. The `data` parameter is a number
-    /// that will be repeatidely written through the entire buffer.
+    /// # Panic
     ///
-    /// > **Note**: This function is technically safe because buffers can only contain integers or
-    /// > floating point numbers, which are always valid whatever their memory representation is.
-    /// > But unless your buffer actually contains only 32-bits integers, you are encouraged to use
-    /// > this function only for zeroing the content of a buffer by passing `0` for the data.
-    // TODO: not safe because of signalling NaNs
-    #[inline]
-    pub fn fill_buffer<B>(mut self, buffer: B, data: u32) -> Result<Self, FillBufferError>
-        where B: BufferAccess + Send + Sync + 'static
-    {
+    /// - Panics if the event doesn't belong to the same device as the builder.
+    ///
+    pub fn reset_event(mut self, event: Arc<Event>, stages: PipelineStages)
+                       -> Result<Self, ResetEventError> {
         unsafe {
             self.ensure_outside_render_pass()?;
-            check_fill_buffer(self.device(), &buffer)?;
-            self.inner.fill_buffer(buffer, data);
+            check_event_stages(self.device(), &event, stages)?;
+            self.inner.reset_event(event, stages);
             Ok(self)
         }
     }
 
-    /// Adds a command that jumps to the next subpass of the current render pass.
+    /// Adds a command that resets `count` queries of `query_pool`, starting at `first_query`.
+    ///
+    /// Queries must be reset before they are used by `begin_query` or `write_timestamp`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the query pool doesn't belong to the same device as the builder.
+    ///
+    pub fn reset_query_pool(mut self, query_pool: Arc<UnsafeQueryPool>, first_query: u32,
+                            count: u32)
+                            -> Result<Self, ResetQueryPoolError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_reset_query_pool(self.device(), &query_pool, first_query, count)?;
+            self.inner.reset_query_pool(query_pool, first_query, count);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the device mask of the command buffer.
+    ///
+    /// The commands that follow, including draw and dispatch commands, only execute on the
+    /// physical devices whose bit is set in `device_mask`. Inside of a render pass begun with
+    /// `begin_render_pass_device_group`, the mask must be a subset of the mask of the render pass.
+    ///
+    /// Requires the `VK_KHR_device_group` device extension.
+    pub fn set_device_mask(mut self, device_mask: u32) -> Result<Self, SetDeviceMaskError> {
+        unsafe {
+            check_device_mask(self.device(), device_mask)?;
+
+            if self.render_pass.is_some() {
+                if let Some(render_pass_mask) = self.render_pass_device_mask {
+                    if device_mask & !render_pass_mask != 0 {
+                        let err = AutoCommandBufferBuilderContextError::DeviceMaskOutsideRenderPass;
+                        return Err(err.into());
+                    }
+                }
+            }
+
+            self.inner.set_device_mask(device_mask);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets a checkpoint with the given marker.
+    ///
+    /// If the device is lost, `Queue::checkpoints` can be used to find the markers of the last
+    /// checkpoints that the GPU reached, in order to determine which commands caused the loss.
+    /// The marker is an arbitrary value chosen by the application.
+    ///
+    /// Requires the `VK_NV_device_diagnostic_checkpoints` device extension.
     #[inline]
-    pub fn next_subpass(mut self, secondary: bool)
-                        -> Result<Self, AutoCommandBufferBuilderContextError> {
+    pub fn set_checkpoint(mut self, marker: usize) -> Result<Self, SetCheckpointError> {
         unsafe {
-            if self.secondary_cb {
-                return Err(AutoCommandBufferBuilderContextError::ForbiddenInSecondary);
+            check_checkpoint(self.device())?;
+            self.inner.set_checkpoint(marker);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that changes `event` to the signaled state once all the previous commands
+    /// have reached `stages`.
+    ///
+    /// Commands recorded after a `wait_events` command that waits for the event, in this command
+    /// buffer or in another one submitted afterwards to the same queue, can then start.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the event doesn't belong to the same device as the builder.
+    ///
+    pub fn set_event(mut self, event: Arc<Event>, stages: PipelineStages)
+                     -> Result<Self, SetEventError> {
+        unsafe {
+            self.ensure_outside_render_pass()?;
+            check_event_stages(self.device(), &event, stages)?;
+            self.inner.set_event(event, stages);
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the scissor boxes of indices `first_scissor` and above.
+    ///
+    /// The scissor boxes remain set for the draw commands that follow, as long as they use a
+    /// pipeline with dynamic scissors and pass `None` as the scissors of their `DynamicState`.
+    /// Anything else than the first scissor box requires the `multi_viewport` feature.
+    pub fn set_scissor(mut self, first_scissor: u32, scissors: &[Scissor])
+                       -> Result<Self, SetScissorError> {
+        unsafe {
+            check_set_scissor(self.device(), first_scissor, scissors)?;
+            self.state_cacher.set_scissors(first_scissor, scissors);
+            self.inner
+                .set_scissor(first_scissor, scissors.to_vec().into_iter());
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that sets the viewports of indices `first_viewport` and above.
+    ///
+    /// The viewports remain set for the draw commands that follow, as long as they use a
+    /// pipeline with dynamic viewports and pass `None` as the viewports of their `DynamicState`.
+    /// Anything else than the first viewport requires the `multi_viewport` feature.
+    pub fn set_viewport(mut self, first_viewport: u32, viewports: &[Viewport])
+                        -> Result<Self, SetViewportError> {
+        unsafe {
+            check_set_viewport(self.device(), first_viewport, viewports)?;
+            self.state_cacher.set_viewports(first_viewport, viewports);
+            self.inner
+                .set_viewport(first_viewport, viewports.to_vec().into_iter());
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that traces rays with a ray tracing pipeline, by invoking the ray
+    /// generation shader `dimensions[0] * dimensions[1] * dimensions[2]` times.
+    ///
+    /// `regions` describes where the shader groups are located in `shader_binding_table`.
+    ///
+    /// Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    pub fn trace_rays<Rp, B, S, Pc>(mut self, dimensions: [u32; 3], pipeline: Rp,
+                                    shader_binding_table: B, regions: ShaderBindingTableRegions,
+                                    sets: S, constants: Pc)
+                                    -> Result<Self, TraceRaysError>
+        where Rp: RayTracingPipelineAbstract + Send + Sync + 'static + Clone,
+              B: BufferAccess + Send + Sync + 'static,
+              S: DescriptorSetsCollection
+    {
+        unsafe {
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
             }
 
-            match self.render_pass {
-                None => {
-                    return Err(AutoCommandBufferBuilderContextError::ForbiddenOutsideRenderPass);
-                },
-                Some((ref rp, ref mut index)) => {
-                    if *index + 1 >= rp.num_subpasses() as u32 {
-                        return Err(AutoCommandBufferBuilderContextError::NumSubpassesMismatch {
-                                       actual: rp.num_subpasses() as u32,
-                                       current: *index,
-                                   });
-                    } else {
-                        *index += 1;
-                    }
-                },
-            };
+            self.ensure_outside_render_pass()?;
+            if self.pipeline_checks_enabled() {
+                check_push_constants_validity(&pipeline, &constants)?;
+                check_descriptor_sets_validity(&pipeline, &sets)?;
+            }
+            check_trace_rays(pipeline.device(), &shader_binding_table, &regions)?;
 
-            self.subpass_secondary = secondary;
+            if let StateCacherOutcome::NeedChange =
+                self.state_cacher.bind_ray_tracing_pipeline(&pipeline)
+            {
+                self.inner.bind_pipeline_ray_tracing(pipeline.clone());
+            }
 
-            debug_assert!(self.graphics_allowed);
+            push_constants(&mut self.inner, pipeline.clone(), constants);
+            descriptor_sets(&mut self.inner,
+                            &mut self.state_cacher,
+                            PipelineBindPoint::RayTracing,
+                            pipeline.clone(),
+                            sets)?;
 
-            let contents = if secondary {
-                SubpassContents::SecondaryCommandBuffers
-            } else {
-                SubpassContents::Inline
-            };
-            self.inner.next_subpass(contents);
+            self.inner
+                .trace_rays(shader_binding_table, regions, dimensions)?;
             Ok(self)
         }
     }
@@ -1268,6 +2965,119 @@ impl<P> AutoCommandBufferBuilder<P> {
             Ok(self)
         }
     }
+
+    /// Adds a command that waits until all of `events` are signaled, then applies the memory
+    /// barriers of `barrier`.
+    ///
+    /// The stages of the following commands in `destination_stages` don't start before the
+    /// events are signaled. `source_stages` must be the union of the stages that were passed to
+    /// `set_event`, plus the host stage if an event is signaled from the CPU. The stages of the
+    /// barriers of `barrier` must be included in `source_stages` and `destination_stages`, and
+    /// `barrier` can't be by region.
+    ///
+    /// Like with `pipeline_barrier`, the buffers and images of the barrier are considered as
+    /// accessed by the destination stages and accesses of the barrier by the following commands.
+    ///
+    /// # Safety
+    ///
+    /// - Each event must be signaled by a `set_event` command that was recorded earlier in the
+    ///   same queue or by the CPU, otherwise the GPU hangs or the device is lost.
+    /// - `source_stages` must match the stages that were used to signal the events.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the events or one of the buffers or images of the barrier doesn't
+    ///   belong to the same device as the builder.
+    ///
+    pub unsafe fn wait_events(mut self, events: Vec<Arc<Event>>, source_stages: PipelineStages,
+                              destination_stages: PipelineStages, barrier: PipelineBarrier)
+                              -> Result<Self, WaitEventsError> {
+        check_wait_events(self.device(), &events, source_stages, destination_stages, &barrier)?;
+        if !barrier.is_empty() {
+            check_pipeline_barrier(self.device(), &barrier)?;
+        }
+
+        self.inner
+            .wait_events(events, source_stages, destination_stages, barrier)?;
+        Ok(self)
+    }
+
+    /// Adds a command that writes the size that `acceleration_structure` would have after a
+    /// compaction in the query `query` of `query_pool`. The query is reset beforehand.
+    ///
+    /// The acceleration structure must have been built with the `allow_compaction` flag, and
+    /// the query pool must have the `AccelerationStructureCompactedSize` type. Once the command
+    /// buffer has finished executing, the size can be read with `get_results_u64`.
+    pub fn write_acceleration_structure_compacted_size(
+        mut self, acceleration_structure: Arc<AccelerationStructure>,
+        query_pool: Arc<UnsafeQueryPool>, query: u32)
+        -> Result<Self, WriteAccelerationStructureCompactedSizeError> {
+        unsafe {
+            if !self.compute_allowed {
+                return Err(AutoCommandBufferBuilderContextError::NotSupportedByQueueFamily.into());
+            }
+
+            self.ensure_outside_render_pass()?;
+            check_write_acceleration_structure_compacted_size(self.device(),
+                                                              &acceleration_structure,
+                                                              &query_pool,
+                                                              query)?;
+            self.inner
+                .write_acceleration_structure_compacted_size(acceleration_structure,
+                                                             query_pool,
+                                                             query)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that writes `marker` to `buffer` once all the previous commands have
+    /// reached `stage`.
+    ///
+    /// After a device loss, reading the buffer tells which commands have completed, since the
+    /// markers written by the commands that didn't reach `stage` are missing. The buffer should
+    /// be allocated from host-visible memory for that purpose.
+    ///
+    /// Requires the `VK_AMD_buffer_marker` device extension.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the buffer doesn't belong to the same device as the builder.
+    ///
+    pub fn write_buffer_marker<B>(mut self, buffer: B, stage: PipelineStages, marker: u32)
+                                  -> Result<Self, WriteBufferMarkerError>
+        where B: TypedBufferAccess<Content = u32> + Send + Sync + 'static
+    {
+        unsafe {
+            check_write_buffer_marker(self.device(), &buffer, stage)?;
+            self.inner.write_buffer_marker(buffer, stage, marker)?;
+            Ok(self)
+        }
+    }
+
+    /// Adds a command that writes a timestamp in the query `query` of `query_pool` once all the
+    /// previous commands have reached `stage`.
+    ///
+    /// The query pool must be a timestamp query pool, and `stage` must contain exactly one
+    /// stage. The query must have been reset beforehand, for example with `reset_query_pool`.
+    /// The queue family of the command buffer must support timestamps.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the query pool doesn't belong to the same device as the builder.
+    ///
+    pub fn write_timestamp(mut self, query_pool: Arc<UnsafeQueryPool>, query: u32,
+                           stage: PipelineStages)
+                           -> Result<Self, WriteTimestampError> {
+        unsafe {
+            let queue_family = self.device()
+                .physical_device()
+                .queue_family_by_id(self.queue_family_id)
+                .unwrap();
+            check_write_timestamp(self.device(), queue_family, &query_pool, query, stage)?;
+            self.inner.write_timestamp(query_pool, query, stage);
+            Ok(self)
+        }
+    }
 }
 
 unsafe impl<P> DeviceOwned for AutoCommandBufferBuilder<P> {
@@ -1313,24 +3123,131 @@ unsafe fn set_state<P>(destination: &mut SyncCommandBufferBuilder<P>, dynamic: D
         destination.set_viewport(0, viewports.iter().cloned().collect::<Vec<_>>().into_iter()); // TODO: don't collect
     }
 
-    if let Some(ref scissors) = dynamic.scissors {
-        destination.set_scissor(0, scissors.iter().cloned().collect::<Vec<_>>().into_iter()); // TODO: don't collect
+    if let Some(ref scissors) = dynamic.scissors {
+        destination.set_scissor(0, scissors.iter().cloned().collect::<Vec<_>>().into_iter()); // TODO: don't collect
+    }
+
+    if let Some(bias) = dynamic.depth_bias {
+        destination.set_depth_bias(bias.constant_factor, bias.clamp, bias.slope_factor);
+    }
+
+    if let Some((min, max)) = dynamic.depth_bounds {
+        destination.set_depth_bounds(min, max);
+    }
+
+    if let Some(constants) = dynamic.blend_constants {
+        destination.set_blend_constants(constants);
+    }
+
+    if let Some(compare_mask) = dynamic.compare_mask {
+        set_stencil_value(compare_mask, |faces, value| destination.set_stencil_compare_mask(faces, value));
+    }
+
+    if let Some(write_mask) = dynamic.write_mask {
+        set_stencil_value(write_mask, |faces, value| destination.set_stencil_write_mask(faces, value));
+    }
+
+    if let Some(reference) = dynamic.reference {
+        set_stencil_value(reference, |faces, value| destination.set_stencil_reference(faces, value));
+    }
+
+    if let Some(topology) = dynamic.primitive_topology {
+        destination.set_primitive_topology(topology);
+    }
+
+    if let Some(cull_mode) = dynamic.cull_mode {
+        destination.set_cull_mode(cull_mode);
+    }
+
+    if let Some(front_face) = dynamic.front_face {
+        destination.set_front_face(front_face);
+    }
+
+    if let Some(enable) = dynamic.depth_test_enable {
+        destination.set_depth_test_enable(enable);
+    }
+
+    if let Some(enable) = dynamic.depth_write_enable {
+        destination.set_depth_write_enable(enable);
+    }
+
+    if let Some(compare_op) = dynamic.depth_compare_op {
+        destination.set_depth_compare_op(compare_op);
+    }
+
+    if let Some(enable) = dynamic.depth_bounds_test_enable {
+        destination.set_depth_bounds_test_enable(enable);
+    }
+
+    if let Some(enable) = dynamic.stencil_test_enable {
+        destination.set_stencil_test_enable(enable);
+    }
+
+    if let Some(ops) = dynamic.stencil_op {
+        let mut set = |faces, op: DynamicStencilOp| {
+            destination.set_stencil_op(faces, op.fail_op, op.pass_op, op.depth_fail_op, op.compare)
+        };
+
+        if ops.front == ops.back {
+            set(StencilFaces::FrontAndBack, ops.front);
+        } else {
+            set(StencilFaces::Front, ops.front);
+            set(StencilFaces::Back, ops.back);
+        }
+    }
+
+    if let Some(stipple) = dynamic.line_stipple {
+        destination.set_line_stipple(stipple.factor, stipple.pattern);
+    }
+
+    // The vertex strides are handled by `vertex_buffers`.
+}
+
+// Calls `set` once if the front and back values are the same, or once per face otherwise.
+unsafe fn set_stencil_value<F>(value: DynamicStencilValue, mut set: F)
+    where F: FnMut(StencilFaces, u32)
+{
+    if value.front == value.back {
+        set(StencilFaces::FrontAndBack, value.front);
+    } else {
+        set(StencilFaces::Front, value.front);
+        set(StencilFaces::Back, value.back);
+    }
+}
+
+// Checks that the dynamic vertex strides, if any, contain one stride per vertex buffer.
+fn check_vertex_strides(dynamic: &DynamicState, num_vertex_buffers: usize)
+                        -> Result<(), AutoCommandBufferBuilderContextError> {
+    match dynamic.vertex_strides {
+        Some(ref strides) if strides.len() != num_vertex_buffers => {
+            Err(AutoCommandBufferBuilderContextError::VertexStridesCountMismatch {
+                    expected: num_vertex_buffers,
+                    obtained: strides.len(),
+                })
+        },
+        _ => Ok(()),
     }
 }
 
 // Shortcut function to bind vertex buffers.
+//
+// If `strides` is `Some`, all the vertex buffers are bound again with these strides, as the state
+// cacher doesn't keep track of them. Their number must have been checked with
+// `check_vertex_strides`.
 unsafe fn vertex_buffers<P>(destination: &mut SyncCommandBufferBuilder<P>,
                             state_cacher: &mut StateCacher,
-                            vertex_buffers: Vec<Box<BufferAccess + Send + Sync>>)
+                            vertex_buffers: Vec<Box<BufferAccess + Send + Sync>>,
+                            strides: Option<Vec<u32>>)
                             -> Result<(), SyncCommandBufferBuilderError> {
     let binding_range = {
         let mut compare = state_cacher.bind_vertex_buffers();
         for vb in vertex_buffers.iter() {
             compare.add(vb);
         }
-        match compare.compare() {
-            Some(r) => r,
-            None => return Ok(()),
+        match (compare.compare(), strides.is_some()) {
+            (_, true) => 0 .. vertex_buffers.len() as u32,
+            (Some(r), false) => r,
+            (None, false) => return Ok(()),
         }
     };
 
@@ -1338,20 +3255,27 @@ unsafe fn vertex_buffers<P>(destination: &mut SyncCommandBufferBuilder<P>,
     let num_bindings = binding_range.end - binding_range.start;
 
     let mut binder = destination.bind_vertex_buffers();
-    for vb in vertex_buffers
-        .into_iter()
-        .skip(first_binding as usize)
-        .take(num_bindings as usize)
-    {
-        binder.add(vb);
+    if let Some(strides) = strides {
+        debug_assert_eq!(strides.len(), vertex_buffers.len());
+        for (vb, stride) in vertex_buffers.into_iter().zip(strides) {
+            binder.add_with_stride(vb, stride);
+        }
+    } else {
+        for vb in vertex_buffers
+            .into_iter()
+            .skip(first_binding as usize)
+            .take(num_bindings as usize)
+        {
+            binder.add(vb);
+        }
     }
     binder.submit(first_binding)?;
     Ok(())
 }
 
 unsafe fn descriptor_sets<P, Pl, S>(destination: &mut SyncCommandBufferBuilder<P>,
-                                    state_cacher: &mut StateCacher, gfx: bool, pipeline: Pl,
-                                    sets: S)
+                                    state_cacher: &mut StateCacher,
+                                    bind_point: PipelineBindPoint, pipeline: Pl, sets: S)
                                     -> Result<(), SyncCommandBufferBuilderError>
     where Pl: PipelineLayoutAbstract + Send + Sync + Clone + 'static,
           S: DescriptorSetsCollection
@@ -1359,7 +3283,7 @@ unsafe fn descriptor_sets<P, Pl, S>(destination: &mut SyncCommandBufferBuilder<P
     let sets = sets.into_vec();
 
     let first_binding = {
-        let mut compare = state_cacher.bind_descriptor_sets(gfx);
+        let mut compare = state_cacher.bind_descriptor_sets(bind_point);
         for set in sets.iter() {
             compare.add(set);
         }
@@ -1376,7 +3300,7 @@ unsafe fn descriptor_sets<P, Pl, S>(destination: &mut SyncCommandBufferBuilder<P
         sets_binder.add(set);
     }
     sets_binder
-        .submit(gfx, pipeline.clone(), first_binding, iter::empty())?;
+        .submit(bind_point, pipeline.clone(), first_binding, iter::empty())?;
     Ok(())
 }
 
@@ -1385,6 +3309,51 @@ pub struct AutoCommandBuffer<P = StandardCommandPoolAlloc> {
 
     // Tracks usage of the command buffer on the GPU.
     submit_state: SubmitState,
+
+    // Number of submissions of the command buffer that haven't been unlocked yet. The command
+    // buffer can only be reset when it is zero.
+    pending_submissions: AtomicUsize,
+}
+
+impl AutoCommandBuffer<StandardCommandPoolAlloc> {
+    /// Resets the command buffer and starts recording it again.
+    ///
+    /// This reuses the Vulkan command buffer instead of allocating a new one. The resources used
+    /// by the previous recording are released.
+    ///
+    /// Returns an error if the command buffer is still in use by the GPU, if `kind` is primary
+    /// and the command buffer was secondary or vice versa, or if the command buffer was allocated
+    /// by another thread. In that case the command buffer is destroyed, and you have to allocate
+    /// a new one.
+    ///
+    /// > **Note**: A command buffer that doesn't need to be recorded again can simply be
+    /// > submitted multiple times, unless it was built with the `OneTimeSubmit` flag.
+    pub fn reset<R, F>(self, kind: Kind<R, F>, flags: Flags)
+                       -> Result<AutoCommandBufferBuilder<StandardCommandPoolBuilder>,
+                                 ResetCommandBufferError>
+        where R: RenderPassAbstract + Clone + Send + Sync + 'static,
+              F: FramebufferAbstract
+    {
+        // Since we own the command buffer, no other thread can submit it in the meantime.
+        if self.pending_submissions.load(Ordering::SeqCst) != 0 {
+            return Err(ResetCommandBufferError::InUse);
+        }
+
+        let alloc = self.inner.into_unsafe().into_alloc();
+
+        let secondary = match kind {
+            Kind::Primary => false,
+            Kind::Secondary { .. } => true,
+        };
+        if alloc.is_secondary() != secondary {
+            return Err(ResetCommandBufferError::KindMismatch);
+        }
+
+        let alloc = alloc
+            .into_builder()
+            .map_err(|_| ResetCommandBufferError::WrongThread)?;
+        Ok(AutoCommandBufferBuilder::from_alloc(alloc, kind, flags)?)
+    }
 }
 
 // Whether the command buffer can be submitted.
@@ -1435,7 +3404,10 @@ unsafe impl<P> CommandBuffer for AutoCommandBuffer<P> {
         };
 
         let err = match self.inner.lock_submit(future, queue) {
-            Ok(()) => return Ok(()),
+            Ok(()) => {
+                self.pending_submissions.fetch_add(1, Ordering::SeqCst);
+                return Ok(());
+            },
             Err(err) => err,
         };
 
@@ -1457,6 +3429,7 @@ unsafe impl<P> CommandBuffer for AutoCommandBuffer<P> {
     unsafe fn unlock(&self) {
         // Because of panic safety, we unlock the inner command buffer first.
         self.inner.unlock();
+        self.pending_submissions.fetch_sub(1, Ordering::SeqCst);
 
         match self.submit_state {
             SubmitState::OneTime { ref already_submitted } => {
@@ -1484,6 +3457,29 @@ unsafe impl<P> CommandBuffer for AutoCommandBuffer<P> {
         self.inner
             .check_image_access(image, layout, exclusive, queue)
     }
+
+    #[inline]
+    fn num_buffers(&self) -> usize {
+        self.inner.num_buffers()
+    }
+
+    #[inline]
+    fn buffer(&self, index: usize)
+              -> Option<(&BufferAccess, PipelineStages, AccessFlagBits, bool)> {
+        self.inner.buffer(index)
+    }
+
+    #[inline]
+    fn num_images(&self) -> usize {
+        self.inner.num_images()
+    }
+
+    #[inline]
+    fn image(&self, index: usize)
+             -> Option<(&ImageAccess, PipelineStages, AccessFlagBits, bool, ImageLayout,
+                        ImageLayout)> {
+        self.inner.image(index)
+    }
 }
 
 unsafe impl<P> DeviceOwned for AutoCommandBuffer<P> {
@@ -1493,6 +3489,57 @@ unsafe impl<P> DeviceOwned for AutoCommandBuffer<P> {
     }
 }
 
+/// Error that can happen when resetting a command buffer.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResetCommandBufferError {
+    /// Not enough memory to start recording the command buffer.
+    OomError(OomError),
+    /// The command buffer is still in use by the GPU.
+    InUse,
+    /// The command buffer can't be turned from primary to secondary or vice versa.
+    KindMismatch,
+    /// The command buffer was allocated by another thread, and can only be recorded by it.
+    WrongThread,
+}
+
+impl error::Error for ResetCommandBufferError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ResetCommandBufferError::OomError(_) => "not enough memory",
+            ResetCommandBufferError::InUse => "the command buffer is still in use by the GPU",
+            ResetCommandBufferError::KindMismatch => {
+                "the command buffer can't be turned from primary to secondary or vice versa"
+            },
+            ResetCommandBufferError::WrongThread => {
+                "the command buffer was allocated by another thread"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ResetCommandBufferError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ResetCommandBufferError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for ResetCommandBufferError {
+    #[inline]
+    fn from(err: OomError) -> ResetCommandBufferError {
+        ResetCommandBufferError::OomError(err)
+    }
+}
+
 macro_rules! err_gen {
     ($name:ident { $($err:ident,)+ }) => (
         #[derive(Debug, Clone)]
@@ -1547,8 +3594,39 @@ err_gen!(BuildError {
              OomError,
          });
 
+err_gen!(BeginQueryError {
+             AutoCommandBufferBuilderContextError,
+             CheckBeginQueryError,
+         });
+
 err_gen!(BeginRenderPassError {
              AutoCommandBufferBuilderContextError,
+             CheckDeviceGroupError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(BeginTransformFeedbackError {
+             AutoCommandBufferBuilderContextError,
+             CheckTransformFeedbackCountersError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(BindTransformFeedbackBuffersError {
+             AutoCommandBufferBuilderContextError,
+             CheckTransformFeedbackBuffersError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(BuildAccelerationStructureError {
+             AutoCommandBufferBuilderContextError,
+             CheckBuildAccelerationStructureError,
+             DeviceMemoryAllocError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(CopyAccelerationStructureError {
+             AutoCommandBufferBuilderContextError,
+             CheckCopyAccelerationStructureError,
              SyncCommandBufferBuilderError,
          });
 
@@ -1564,6 +3642,11 @@ err_gen!(BlitImageError {
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(ClearAttachmentsError {
+             AutoCommandBufferBuilderContextError,
+             CheckClearAttachmentsError,
+         });
+
 err_gen!(ClearColorImageError {
              AutoCommandBufferBuilderContextError,
              CheckClearColorImageError,
@@ -1582,11 +3665,22 @@ err_gen!(CopyBufferImageError {
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(CopyQueryPoolResultsError {
+             AutoCommandBufferBuilderContextError,
+             CheckCopyQueryPoolResultsError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(FillBufferError {
              AutoCommandBufferBuilderContextError,
              CheckFillBufferError,
          });
 
+err_gen!(DebugLabelError {
+             AutoCommandBufferBuilderContextError,
+             CheckDebugLabelError,
+         });
+
 err_gen!(DispatchError {
              AutoCommandBufferBuilderContextError,
              CheckPushConstantsValidityError,
@@ -1595,6 +3689,14 @@ err_gen!(DispatchError {
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(DispatchBaseError {
+             AutoCommandBufferBuilderContextError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckDispatchBaseError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(DrawError {
              AutoCommandBufferBuilderContextError,
              CheckDynamicStateValidityError,
@@ -1614,6 +3716,37 @@ err_gen!(DrawIndexedError {
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(DrawIndexedIndirectCountError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckVertexBufferError,
+             CheckIndexBufferError,
+             CheckIndirectCountError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(DrawIndirectByteCountError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckVertexBufferError,
+             CheckDrawIndirectByteCountError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(DrawIndirectCountError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckVertexBufferError,
+             CheckIndirectCountError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(DrawIndirectError {
              AutoCommandBufferBuilderContextError,
              CheckDynamicStateValidityError,
@@ -1623,16 +3756,135 @@ err_gen!(DrawIndirectError {
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(DrawMeshTasksError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckDrawMeshTasksError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(DrawMeshTasksIndirectError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(DrawMultiError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckVertexBufferError,
+             CheckMultiDrawError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(DrawMultiIndexedError {
+             AutoCommandBufferBuilderContextError,
+             CheckDynamicStateValidityError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckVertexBufferError,
+             CheckIndexBufferError,
+             CheckMultiDrawError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(ExecuteCommandsError {
              AutoCommandBufferBuilderContextError,
              SyncCommandBufferBuilderError,
          });
 
+err_gen!(ExecuteCommandsConditionalError {
+             AutoCommandBufferBuilderContextError,
+             CheckConditionalRenderingError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(PipelineBarrierError {
+             AutoCommandBufferBuilderContextError,
+             CheckPipelineBarrierError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(PushConstantsError {
+             CheckPushConstantsRangeError,
+         });
+
+err_gen!(RawCommandsError {
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(ResetEventError {
+             AutoCommandBufferBuilderContextError,
+             CheckEventError,
+         });
+
+err_gen!(ResetQueryPoolError {
+             AutoCommandBufferBuilderContextError,
+             CheckResetQueryPoolError,
+         });
+
+err_gen!(SetCheckpointError {
+             CheckCheckpointError,
+         });
+
+err_gen!(SetDeviceMaskError {
+             AutoCommandBufferBuilderContextError,
+             CheckDeviceGroupError,
+         });
+
+err_gen!(SetEventError {
+             AutoCommandBufferBuilderContextError,
+             CheckEventError,
+         });
+
+err_gen!(SetScissorError {
+             CheckSetViewportError,
+         });
+
+err_gen!(SetViewportError {
+             CheckSetViewportError,
+         });
+
+err_gen!(TraceRaysError {
+             AutoCommandBufferBuilderContextError,
+             CheckPushConstantsValidityError,
+             CheckDescriptorSetsValidityError,
+             CheckTraceRaysError,
+             SyncCommandBufferBuilderError,
+         });
+
 err_gen!(UpdateBufferError {
              AutoCommandBufferBuilderContextError,
              CheckUpdateBufferError,
          });
 
+err_gen!(WaitEventsError {
+             CheckEventError,
+             CheckPipelineBarrierError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(WriteAccelerationStructureCompactedSizeError {
+             AutoCommandBufferBuilderContextError,
+             CheckWriteAccelerationStructureCompactedSizeError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(WriteBufferMarkerError {
+             CheckWriteBufferMarkerError,
+             SyncCommandBufferBuilderError,
+         });
+
+err_gen!(WriteTimestampError {
+             CheckWriteTimestampError,
+         });
+
 #[derive(Debug, Copy, Clone)]
 pub enum AutoCommandBufferBuilderContextError {
     /// Operation forbidden in a secondary command buffer.
@@ -1660,6 +3912,33 @@ pub enum AutoCommandBufferBuilderContextError {
     /// Tried to use a graphics pipeline whose render pass is incompatible with the current render
     /// pass.
     IncompatibleRenderPass,
+    /// Tried to use a pipeline with a mesh shader with a regular draw command, or a pipeline
+    /// without a mesh shader with a `draw_mesh_tasks` command.
+    MeshShaderMismatch,
+    /// Tried to begin a query while a query of the same type is active, or to build the command
+    /// buffer while a query is active.
+    QueryIsActive,
+    /// Tried to end a query that isn't active.
+    QueryIsNotActive,
+    /// Tried to build the command buffer while a debug label region is open.
+    DebugLabelIsActive,
+    /// Tried to end a debug label region while none is open.
+    DebugLabelIsNotActive,
+    /// Tried to begin transform feedback or to bind transform feedback buffers while transform
+    /// feedback is active, or to leave the subpass or build the command buffer before it ends.
+    TransformFeedbackIsActive,
+    /// Tried to end transform feedback while it isn't active.
+    TransformFeedbackIsNotActive,
+    /// Tried to set a device mask that contains physical devices that the current render pass
+    /// doesn't run on.
+    DeviceMaskOutsideRenderPass,
+    /// The number of dynamic vertex strides doesn't match the number of vertex buffers.
+    VertexStridesCountMismatch {
+        /// Number of vertex buffers.
+        expected: usize,
+        /// Number of vertex strides that were passed.
+        obtained: usize,
+    },
 }
 
 impl error::Error for AutoCommandBufferBuilderContextError {
@@ -1695,6 +3974,38 @@ impl error::Error for AutoCommandBufferBuilderContextError {
                 "tried to use a graphics pipeline whose render pass is incompatible with the \
                  current render pass"
             },
+            AutoCommandBufferBuilderContextError::MeshShaderMismatch => {
+                "tried to use a pipeline with a mesh shader with a regular draw command, or a \
+                 pipeline without a mesh shader with a `draw_mesh_tasks` command"
+            },
+            AutoCommandBufferBuilderContextError::QueryIsActive => {
+                "tried to begin a query while a query of the same type is active, or to build \
+                 the command buffer while a query is active"
+            },
+            AutoCommandBufferBuilderContextError::QueryIsNotActive => {
+                "tried to end a query that isn't active"
+            },
+            AutoCommandBufferBuilderContextError::DebugLabelIsActive => {
+                "tried to build the command buffer while a debug label region is open"
+            },
+            AutoCommandBufferBuilderContextError::DebugLabelIsNotActive => {
+                "tried to end a debug label region while none is open"
+            },
+            AutoCommandBufferBuilderContextError::TransformFeedbackIsActive => {
+                "tried to begin transform feedback or to bind transform feedback buffers while \
+                 transform feedback is active, or to leave the subpass or build the command \
+                 buffer before it ends"
+            },
+            AutoCommandBufferBuilderContextError::TransformFeedbackIsNotActive => {
+                "tried to end transform feedback while it isn't active"
+            },
+            AutoCommandBufferBuilderContextError::DeviceMaskOutsideRenderPass => {
+                "tried to set a device mask that contains physical devices that the current \
+                 render pass doesn't run on"
+            },
+            AutoCommandBufferBuilderContextError::VertexStridesCountMismatch { .. } => {
+                "the number of dynamic vertex strides doesn't match the number of vertex buffers"
+            },
         }
     }
 }
@@ -1705,3 +4016,250 @@ impl fmt::Display for AutoCommandBufferBuilderContextError {
         write!(fmt, "{}", error::Error::description(self))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use VulkanObject;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::AutoCommandBufferBuilderContextError;
+    use command_buffer::CommandBuffer;
+    use command_buffer::CommandBufferExecError;
+    use command_buffer::ResetCommandBufferError;
+    use command_buffer::PipelineBarrier;
+    use command_buffer::SetDeviceMaskError;
+    use command_buffer::sys::Flags;
+    use command_buffer::sys::Kind;
+    use command_buffer::sys::KindOcclusionQuery;
+    use command_buffer::validity::CheckDeviceGroupError;
+    use query::QueryPipelineStatisticFlags;
+    use sync::AccessFlagBits;
+    use sync::Event;
+    use sync::GpuFuture;
+    use sync::PipelineStages;
+
+    #[test]
+    fn reset_reuses_command_buffer() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb = AutoCommandBufferBuilder::primary(device.clone(), queue.family())
+            .unwrap()
+            .build()
+            .unwrap();
+        let raw = cb.inner().internal_object();
+
+        let cb = cb.reset(Kind::primary(), Flags::OneTimeSubmit).unwrap().build().unwrap();
+        assert_eq!(cb.inner().internal_object(), raw);
+
+        let secondary = Kind::secondary(KindOcclusionQuery::Forbidden,
+                                        QueryPipelineStatisticFlags::none());
+        match cb.reset(secondary, Flags::None) {
+            Err(ResetCommandBufferError::KindMismatch) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn simultaneous_use_multiple_submissions() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb = AutoCommandBufferBuilder::primary_simultaneous_use(device.clone(), queue.family())
+            .unwrap()
+            .build()
+            .unwrap();
+        let cb = Arc::new(cb);
+
+        let _first = cb.clone().execute(queue.clone()).unwrap();
+        let _second = cb.clone().execute(queue.clone()).unwrap();
+    }
+
+    #[test]
+    fn secondary_exclusive_use_locked_with_primary() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let secondary = AutoCommandBufferBuilder::secondary_compute(device.clone(), queue.family())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        let primary = unsafe {
+            AutoCommandBufferBuilder::primary_simultaneous_use(device.clone(), queue.family())
+                .unwrap()
+                .execute_commands(secondary)
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+        let primary = Arc::new(primary);
+
+        let _first = primary.clone().execute(queue.clone()).unwrap();
+        match primary.clone().execute(queue.clone()) {
+            Err(CommandBufferExecError::ExclusiveAlreadyInUse) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn execute_multiple_secondaries() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let secondaries = (0 .. 2)
+            .map(|_| {
+                AutoCommandBufferBuilder::secondary_compute(device.clone(), queue.family())
+                    .unwrap()
+                    .build()
+                    .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        let primary = unsafe {
+            AutoCommandBufferBuilder::primary_simultaneous_use(device.clone(), queue.family())
+                .unwrap()
+                .execute_commands_from_vec(secondaries)
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+        let primary = Arc::new(primary);
+
+        // Both secondaries are locked by the first submission.
+        let _first = primary.clone().execute(queue.clone()).unwrap();
+        match primary.clone().execute(queue.clone()) {
+            Err(CommandBufferExecError::ExclusiveAlreadyInUse) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn secondary_resources_synchronized_with_primary() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let source = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let destination = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+
+        let secondary = AutoCommandBufferBuilder::secondary_compute(device.clone(), queue.family())
+            .unwrap()
+            .fill_buffer(source.clone(), 5)
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(secondary.num_buffers(), 1);
+
+        let primary = unsafe {
+            AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family())
+                .unwrap()
+                .execute_commands(secondary)
+                .unwrap()
+                .copy_buffer(source.clone(), destination.clone())
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+
+        // The copy is the last command to access the source buffer.
+        let (stages, access) = primary.check_buffer_access(&source, false, &queue)
+            .unwrap()
+            .unwrap();
+        assert!(stages.transfer);
+        assert!(access.transfer_read);
+        assert!(!access.transfer_write);
+
+        primary.execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert_eq!(*destination.read().unwrap(), 5);
+    }
+
+    #[test]
+    fn set_device_mask_extension_not_enabled() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let builder = AutoCommandBufferBuilder::primary(device, queue.family()).unwrap();
+        match builder.set_device_mask(1) {
+            Err(SetDeviceMaskError::CheckDeviceGroupError(
+                    CheckDeviceGroupError::ExtensionNotEnabled)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn set_then_wait_event() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let event = Arc::new(Event::alloc(device.clone()).unwrap());
+        let stages = PipelineStages {
+            transfer: true,
+            ..PipelineStages::none()
+        };
+
+        let cb = unsafe {
+            AutoCommandBufferBuilder::primary_one_time_submit(device, queue.family())
+                .unwrap()
+                .set_event(event.clone(), stages)
+                .unwrap()
+                .wait_events(vec![event.clone()], stages, stages, PipelineBarrier::new())
+                .unwrap()
+                .build()
+                .unwrap()
+        };
+
+        cb.execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert!(event.signaled().unwrap());
+    }
+
+    #[test]
+    fn release_then_acquire_buffer() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let stages = PipelineStages {
+            transfer: true,
+            ..PipelineStages::none()
+        };
+        let access = AccessFlagBits {
+            transfer_write: true,
+            ..AccessFlagBits::none()
+        };
+
+        let cb = AutoCommandBufferBuilder::primary_one_time_submit(device, queue.family())
+            .unwrap()
+            .release_buffer_ownership(buffer.clone(), stages, access, queue.family())
+            .unwrap()
+            .acquire_buffer_ownership(buffer, queue.family(), stages, access)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        cb.execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+    }
+
+    #[test]
+    fn end_transform_feedback_not_active() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let builder = AutoCommandBufferBuilder::primary(device.clone(), queue.family()).unwrap();
+        match builder.end_transform_feedback() {
+            Err(AutoCommandBufferBuilderContextError::TransformFeedbackIsNotActive) => (),
+            _ => panic!(),
+        }
+    }
+}
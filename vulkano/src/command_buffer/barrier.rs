@@ -0,0 +1,108 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::ops::Range;
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use image::ImageAccess;
+use image::ImageLayout;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// Description of an explicit pipeline barrier, passed to
+/// `AutoCommandBufferBuilder::pipeline_barrier`.
+///
+/// The command buffer builder already inserts the barriers that are needed between the commands
+/// it knows about. An explicit barrier is only needed when the GPU accesses a resource in a way
+/// that vulkano can't see, for example when the resource is shared with another API.
+///
+/// The buffers and images of the barrier are kept alive by the command buffer, and their state
+/// is updated as if they were accessed by the destination stages and accesses of the barrier.
+///
+/// Buffer and image barriers can also transfer the ownership of a resource that isn't shared
+/// between queue families. The transfer is made of a release barrier recorded for the source
+/// queue family and an identical acquire barrier recorded for the destination queue family.
+#[derive(Clone)]
+pub struct PipelineBarrier {
+    /// If true, the dependency is framebuffer-local.
+    pub by_region: bool,
+    /// Global memory barriers.
+    pub memory_barriers: Vec<MemoryBarrier>,
+    /// Barriers that apply to a range of a buffer.
+    pub buffer_barriers: Vec<BufferMemoryBarrier>,
+    /// Barriers that apply to a range of an image and that can change its layout.
+    pub image_barriers: Vec<ImageMemoryBarrier>,
+}
+
+impl PipelineBarrier {
+    /// Builds a barrier that contains nothing.
+    #[inline]
+    pub fn new() -> PipelineBarrier {
+        PipelineBarrier {
+            by_region: false,
+            memory_barriers: Vec::new(),
+            buffer_barriers: Vec::new(),
+            image_barriers: Vec::new(),
+        }
+    }
+
+    /// Returns true if the barrier contains no memory, buffer or image barrier.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.memory_barriers.is_empty() && self.buffer_barriers.is_empty() &&
+            self.image_barriers.is_empty()
+    }
+}
+
+/// A global memory barrier. The writes performed by `source_access` in `source_stages` are made
+/// visible to `destination_access` in `destination_stages`.
+#[derive(Debug, Copy, Clone)]
+pub struct MemoryBarrier {
+    pub source_stages: PipelineStages,
+    pub source_access: AccessFlagBits,
+    pub destination_stages: PipelineStages,
+    pub destination_access: AccessFlagBits,
+}
+
+/// A memory barrier that only applies to a range of a buffer.
+#[derive(Clone)]
+pub struct BufferMemoryBarrier {
+    pub buffer: Arc<BufferAccess + Send + Sync>,
+    pub source_stages: PipelineStages,
+    pub source_access: AccessFlagBits,
+    pub destination_stages: PipelineStages,
+    pub destination_access: AccessFlagBits,
+    /// Range of bytes of the buffer.
+    pub range: Range<usize>,
+    /// If `Some`, the ids of the source and destination queue families of a transfer of
+    /// ownership of the buffer.
+    pub queue_family_transfer: Option<(u32, u32)>,
+}
+
+/// A memory barrier that only applies to some mipmap levels and array layers of an image, and
+/// that can transition them from `old_layout` to `new_layout`.
+#[derive(Clone)]
+pub struct ImageMemoryBarrier {
+    pub image: Arc<ImageAccess + Send + Sync>,
+    pub source_stages: PipelineStages,
+    pub source_access: AccessFlagBits,
+    pub destination_stages: PipelineStages,
+    pub destination_access: AccessFlagBits,
+    pub mipmap_levels: Range<u32>,
+    pub array_layers: Range<u32>,
+    /// Layout the image is in before the barrier. `Undefined` means that the content of the
+    /// image can be discarded.
+    pub old_layout: ImageLayout,
+    /// Layout the image is in after the barrier. Can't be `Undefined` or `Preinitialized`.
+    pub new_layout: ImageLayout,
+    /// If `Some`, the ids of the source and destination queue families of a transfer of
+    /// ownership of the image.
+    pub queue_family_transfer: Option<(u32, u32)>,
+}
@@ -76,35 +76,86 @@
 pub use self::auto::AutoCommandBuffer;
 pub use self::auto::AutoCommandBufferBuilder;
 pub use self::auto::AutoCommandBufferBuilderContextError;
+pub use self::auto::BeginQueryError;
 pub use self::auto::BeginRenderPassError;
+pub use self::auto::BeginTransformFeedbackError;
+pub use self::auto::BindTransformFeedbackBuffersError;
 pub use self::auto::BlitImageError;
+pub use self::auto::BuildAccelerationStructureError;
 pub use self::auto::BuildError;
+pub use self::auto::ClearAttachmentsError;
 pub use self::auto::ClearColorImageError;
+pub use self::auto::CopyAccelerationStructureError;
 pub use self::auto::CopyBufferError;
 pub use self::auto::CopyBufferImageError;
+pub use self::auto::CopyQueryPoolResultsError;
+pub use self::auto::DebugLabelError;
+pub use self::auto::DispatchBaseError;
 pub use self::auto::DispatchError;
 pub use self::auto::DrawError;
 pub use self::auto::DrawIndexedError;
+pub use self::auto::DrawIndexedIndirectCountError;
+pub use self::auto::DrawIndirectByteCountError;
+pub use self::auto::DrawIndirectCountError;
 pub use self::auto::DrawIndirectError;
+pub use self::auto::DrawMeshTasksError;
+pub use self::auto::DrawMeshTasksIndirectError;
+pub use self::auto::DrawMultiError;
+pub use self::auto::DrawMultiIndexedError;
+pub use self::auto::ExecuteCommandsConditionalError;
 pub use self::auto::ExecuteCommandsError;
 pub use self::auto::FillBufferError;
+pub use self::auto::PipelineBarrierError;
+pub use self::auto::PushConstantsError;
+pub use self::auto::RawCommandsError;
+pub use self::auto::ResetCommandBufferError;
+pub use self::auto::ResetEventError;
+pub use self::auto::ResetQueryPoolError;
+pub use self::auto::SetCheckpointError;
+pub use self::auto::SetDeviceMaskError;
+pub use self::auto::SetEventError;
+pub use self::auto::SetScissorError;
+pub use self::auto::SetViewportError;
+pub use self::auto::TraceRaysError;
 pub use self::auto::UpdateBufferError;
+pub use self::auto::WaitEventsError;
+pub use self::auto::WriteAccelerationStructureCompactedSizeError;
+pub use self::auto::WriteBufferMarkerError;
+pub use self::auto::WriteTimestampError;
+pub use self::barrier::BufferMemoryBarrier;
+pub use self::barrier::ImageMemoryBarrier;
+pub use self::barrier::MemoryBarrier;
+pub use self::barrier::PipelineBarrier;
+pub use self::raw_commands::RawCommandsBuffer;
+pub use self::raw_commands::RawCommandsImage;
+pub use self::raw_commands::RawCommandsResources;
 pub use self::state_cacher::StateCacher;
 pub use self::state_cacher::StateCacherOutcome;
 pub use self::traits::CommandBuffer;
 pub use self::traits::CommandBufferExecError;
 pub use self::traits::CommandBufferExecFuture;
 
+use format::ClearValue;
+use pipeline::depth_stencil::Compare;
+use pipeline::depth_stencil::StencilOp;
+use pipeline::input_assembly::PrimitiveTopology;
+use pipeline::raster::CullMode;
+use pipeline::raster::DepthBias;
+use pipeline::raster::LineStipple;
+use pipeline::raster::FrontFace;
 use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 
 pub mod pool;
+pub mod render_graph;
 pub mod submit;
 pub mod synced;
 pub mod sys;
 pub mod validity;
 
 mod auto;
+mod barrier;
+mod raw_commands;
 mod state_cacher;
 mod traits;
 
@@ -127,6 +178,61 @@ pub struct DrawIndexedIndirectCommand {
     pub first_instance: u32,
 }
 
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DrawMeshTasksIndirectCommand {
+    pub group_count_x: u32,
+    pub group_count_y: u32,
+    pub group_count_z: u32,
+}
+
+/// An attachment of the current subpass to clear with
+/// `AutoCommandBufferBuilder::clear_attachments`.
+#[derive(Debug, Copy, Clone)]
+pub enum ClearAttachment {
+    /// Clears the color attachment of the subpass with the given index, which is an index in the
+    /// list of color attachments of the subpass and not in the list of attachments of the render
+    /// pass. The value must be `Float`, `Int` or `Uint` depending on the format of the attachment.
+    Color(u32, ClearValue),
+    /// Clears the depth aspect of the depth-stencil attachment.
+    Depth(f32),
+    /// Clears the stencil aspect of the depth-stencil attachment.
+    Stencil(u32),
+    /// Clears both aspects of the depth-stencil attachment.
+    DepthStencil((f32, u32)),
+}
+
+/// A region of the framebuffer to clear with `AutoCommandBufferBuilder::clear_attachments`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ClearRect {
+    /// Coordinates in pixels of the top-left corner of the rectangle.
+    pub offset: [u32; 2],
+    /// Width and height in pixels of the rectangle.
+    pub extent: [u32; 2],
+    /// First layer of the framebuffer to clear.
+    pub base_array_layer: u32,
+    /// Number of layers to clear.
+    pub layer_count: u32,
+}
+
+/// Range of vertices of one of the draws of `AutoCommandBufferBuilder::draw_multi`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MultiDrawInfo {
+    pub first_vertex: u32,
+    pub vertex_count: u32,
+}
+
+/// Range of indices of one of the draws of `AutoCommandBufferBuilder::draw_multi_indexed`.
+#[repr(C)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct MultiDrawIndexedInfo {
+    pub first_index: u32,
+    pub index_count: u32,
+    /// Value added to the vertex index before indexing into the vertex buffers.
+    pub vertex_offset: i32,
+}
+
 #[repr(C)]
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct DispatchIndirectCommand {
@@ -135,14 +241,76 @@ pub struct DispatchIndirectCommand {
     pub z: u32,
 }
 
+/// Limits of the physical device related to transform feedback.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct TransformFeedbackProperties {
+    /// Maximum number of vertex streams that can be output by a geometry shader.
+    pub max_transform_feedback_streams: u32,
+    /// Maximum number of transform feedback buffers that can be bound at the same time.
+    pub max_transform_feedback_buffers: u32,
+    /// Maximum size in bytes of the range of a transform feedback buffer binding.
+    pub max_transform_feedback_buffer_size: u64,
+    /// Maximum number of bytes that can be captured to the buffers of a single vertex stream.
+    pub max_transform_feedback_stream_data_size: u32,
+    /// Maximum number of bytes that can be captured to a single transform feedback buffer.
+    pub max_transform_feedback_buffer_data_size: u32,
+    /// Maximum stride in bytes between two captured vertices, and maximum vertex stride of
+    /// `draw_indirect_byte_count`.
+    pub max_transform_feedback_buffer_data_stride: u32,
+    /// Transform feedback queries are supported.
+    pub transform_feedback_queries: bool,
+    /// Geometry shaders can output lines and triangles to vertex streams other than the first
+    /// one.
+    pub transform_feedback_streams_lines_triangles: bool,
+    /// A vertex stream other than the first one can be rasterized.
+    pub transform_feedback_rasterization_stream_select: bool,
+    /// The `draw_indirect_byte_count` command is supported.
+    pub transform_feedback_draw: bool,
+}
+
 /// The dynamic state to use for a draw command.
+///
+/// Each field must be `Some` if and only if the corresponding state was declared as dynamic when
+/// creating the pipeline.
 // TODO: probably not the right location
 #[derive(Debug, Clone)]
 pub struct DynamicState {
     pub line_width: Option<f32>,
+    /// If `None` while the pipeline has dynamic viewports, the viewports previously set with
+    /// `AutoCommandBufferBuilder::set_viewport` are used.
     pub viewports: Option<Vec<Viewport>>,
+    /// Same as `viewports`, but for `AutoCommandBufferBuilder::set_scissor`.
     pub scissors: Option<Vec<Scissor>>,
-    // TODO: missing fields
+    pub depth_bias: Option<DepthBias>,
+    /// Minimum and maximum depth values of the depth bounds test.
+    pub depth_bounds: Option<(f32, f32)>,
+    pub blend_constants: Option<[f32; 4]>,
+    pub compare_mask: Option<DynamicStencilValue>,
+    pub write_mask: Option<DynamicStencilValue>,
+    pub reference: Option<DynamicStencilValue>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub primitive_topology: Option<PrimitiveTopology>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub cull_mode: Option<CullMode>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub front_face: Option<FrontFace>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub depth_test_enable: Option<bool>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub depth_write_enable: Option<bool>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub depth_compare_op: Option<Compare>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub depth_bounds_test_enable: Option<bool>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub stencil_test_enable: Option<bool>,
+    /// Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub stencil_op: Option<DynamicStencilOps>,
+    /// Stride of each vertex buffer passed to the draw command, in the same order as the vertex
+    /// buffers. Requires the `VK_EXT_extended_dynamic_state` extension.
+    pub vertex_strides: Option<Vec<u32>>,
+    /// Requires the `VK_EXT_line_rasterization` extension.
+    pub line_stipple: Option<LineStipple>,
 }
 
 impl DynamicState {
@@ -152,6 +320,23 @@ impl DynamicState {
             line_width: None,
             viewports: None,
             scissors: None,
+            depth_bias: None,
+            depth_bounds: None,
+            blend_constants: None,
+            compare_mask: None,
+            write_mask: None,
+            reference: None,
+            primitive_topology: None,
+            cull_mode: None,
+            front_face: None,
+            depth_test_enable: None,
+            depth_write_enable: None,
+            depth_compare_op: None,
+            depth_bounds_test_enable: None,
+            stencil_test_enable: None,
+            stencil_op: None,
+            vertex_strides: None,
+            line_stipple: None,
         }
     }
 }
@@ -162,3 +347,30 @@ impl Default for DynamicState {
         DynamicState::none()
     }
 }
+
+/// Value of a dynamic stencil state, for the front and the back faces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DynamicStencilValue {
+    pub front: u32,
+    pub back: u32,
+}
+
+/// Dynamic stencil operations, for the front and the back faces.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DynamicStencilOps {
+    pub front: DynamicStencilOp,
+    pub back: DynamicStencilOp,
+}
+
+/// Stencil operations of one face, when they are set as dynamic.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DynamicStencilOp {
+    /// The operation to perform when the stencil test failed.
+    pub fail_op: StencilOp,
+    /// The operation to perform when both the depth test and the stencil test passed.
+    pub pass_op: StencilOp,
+    /// The operation to perform when the stencil test passed but the depth test failed.
+    pub depth_fail_op: StencilOp,
+    /// The comparison to perform between the existing stencil value and the reference value.
+    pub compare: Compare,
+}
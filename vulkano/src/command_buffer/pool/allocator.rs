@@ -0,0 +1,181 @@
+// Copyright (c) 2018 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+
+use command_buffer::pool::CommandPoolStatistics;
+use command_buffer::pool::CommandPoolTrimError;
+use command_buffer::pool::StandardCommandPool;
+use instance::QueueFamily;
+
+use OomError;
+use VulkanObject;
+use device::Device;
+use device::DeviceOwned;
+
+/// Allocates command buffers from one Vulkan command pool per thread and per queue family.
+///
+/// Each thread that allocates a command buffer gets its own `UnsafeCommandPool` for the queue
+/// family, so multiple threads can build `AutoCommandBufferBuilder`s at the same time without
+/// waiting for each other. Unlike `Device::standard_command_pool`, getting the pool of a queue
+/// family doesn't lock anything.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use std::thread;
+/// use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// use vulkano::command_buffer::pool::CommandBufferAllocator;
+/// use vulkano::command_buffer::sys::Flags;
+/// use vulkano::command_buffer::sys::Kind;
+/// use vulkano::device::DeviceOwned;
+///
+/// # let device: Arc<vulkano::device::Device> = return;
+/// # let queue: Arc<vulkano::device::Queue> = return;
+/// let allocator = Arc::new(CommandBufferAllocator::new(device.clone()));
+///
+/// let threads = (0 .. 4).map(|_| {
+///     let allocator = allocator.clone();
+///     let queue_family_id = queue.family().id();
+///     thread::spawn(move || {
+///         let queue_family = allocator.device().physical_device()
+///             .queue_family_by_id(queue_family_id).unwrap();
+///         AutoCommandBufferBuilder::with_allocator(&allocator, queue_family, Kind::primary(),
+///                                                  Flags::OneTimeSubmit)
+///             .unwrap()
+///             // record commands here
+///             .build()
+///             .unwrap()
+///     })
+/// }).collect::<Vec<_>>();
+///
+/// let command_buffers = threads.into_iter().map(|t| t.join().unwrap()).collect::<Vec<_>>();
+/// ```
+pub struct CommandBufferAllocator {
+    device: Arc<Device>,
+    // One pool for each queue family of the physical device, indexed by the id of the family.
+    pools: Vec<Arc<StandardCommandPool>>,
+}
+
+impl CommandBufferAllocator {
+    /// Builds a new allocator for the queue families of the device.
+    ///
+    /// The Vulkan pools are only created when a thread allocates a command buffer for the first
+    /// time.
+    pub fn new(device: Arc<Device>) -> CommandBufferAllocator {
+        let pools = device
+            .physical_device()
+            .queue_families()
+            .map(|family| Arc::new(StandardCommandPool::new(device.clone(), family)))
+            .collect();
+
+        CommandBufferAllocator {
+            device: device,
+            pools: pools,
+        }
+    }
+
+    /// Returns the pool that allocates the command buffers of a queue family.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the device and the queue family don't belong to the same physical device.
+    ///
+    #[inline]
+    pub fn pool(&self, queue_family: QueueFamily) -> &Arc<StandardCommandPool> {
+        assert_eq!(self.device.physical_device().internal_object(),
+                   queue_family.physical_device().internal_object());
+
+        &self.pools[queue_family.id() as usize]
+    }
+
+    /// Trims the pools of the current thread for all the queue families. See
+    /// `StandardCommandPool::trim`.
+    pub fn trim(&self) -> Result<(), CommandPoolTrimError> {
+        for pool in self.pools.iter() {
+            pool.trim()?;
+        }
+
+        Ok(())
+    }
+
+    /// Resets the pools of the current thread for all the queue families. See
+    /// `StandardCommandPool::reset`.
+    ///
+    /// Returns `Ok(false)` if one of the pools couldn't be reset because some of its command
+    /// buffers are still in use. The other pools are reset anyway.
+    pub fn reset(&self, release_resources: bool) -> Result<bool, OomError> {
+        let mut all_reset = true;
+        for pool in self.pools.iter() {
+            all_reset &= pool.reset(release_resources)?;
+        }
+
+        Ok(all_reset)
+    }
+
+    /// Returns the sum of the statistics of the pools of all the queue families.
+    pub fn statistics(&self) -> CommandPoolStatistics {
+        self.pools
+            .iter()
+            .map(|pool| pool.statistics())
+            .fold(CommandPoolStatistics::default(), |total, stats| {
+                CommandPoolStatistics {
+                    num_pools: total.num_pools + stats.num_pools,
+                    num_allocated: total.num_allocated + stats.num_allocated,
+                    num_in_use: total.num_in_use + stats.num_in_use,
+                }
+            })
+    }
+}
+
+unsafe impl DeviceOwned for CommandBufferAllocator {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::pool::CommandBufferAllocator;
+    use command_buffer::pool::CommandPool;
+    use device::DeviceOwned;
+    use std::sync::Arc;
+    use std::thread;
+
+    #[test]
+    fn alloc_from_multiple_threads() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let allocator = Arc::new(CommandBufferAllocator::new(device));
+        assert!(Arc::ptr_eq(allocator.pool(queue.family()), allocator.pool(queue.family())));
+
+        let family_id = queue.family().id();
+        let threads = (0 .. 4)
+            .map(|_| {
+                let allocator = allocator.clone();
+                thread::spawn(move || {
+                    let device = allocator.device().clone();
+                    let family = device.physical_device().queue_family_by_id(family_id).unwrap();
+                    let pool = allocator.pool(family);
+                    assert_eq!(pool.alloc(false, 2).unwrap().count(), 2);
+                })
+            })
+            .collect::<Vec<_>>();
+
+        for thread in threads {
+            thread.join().unwrap();
+        }
+
+        let stats = allocator.statistics();
+        assert_eq!(stats.num_pools, 4);
+        assert_eq!(stats.num_allocated, 8);
+        assert_eq!(stats.num_in_use, 0);
+    }
+}
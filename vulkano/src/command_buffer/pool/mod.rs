@@ -15,12 +15,18 @@
 //! In vulkano, creating a command buffer requires passing an implementation of the `CommandPool`
 //! trait. By default vulkano will use the `StandardCommandPool` struct, but you can implement
 //! this trait yourself by wrapping around the `UnsafeCommandPool` type.
+//!
+//! When command buffers are recorded from multiple threads, a `CommandBufferAllocator` can be
+//! shared between the threads and passed to `AutoCommandBufferBuilder::with_allocator`. It
+//! allocates from one Vulkan pool per thread and per queue family.
 
 use instance::QueueFamily;
 
 use OomError;
 use device::DeviceOwned;
 
+pub use self::allocator::CommandBufferAllocator;
+pub use self::standard::CommandPoolStatistics;
 pub use self::standard::StandardCommandPool;
 pub use self::sys::CommandPoolTrimError;
 pub use self::sys::UnsafeCommandPool;
@@ -28,6 +34,7 @@ pub use self::sys::UnsafeCommandPoolAlloc;
 pub use self::sys::UnsafeCommandPoolAllocIter;
 
 pub mod standard;
+mod allocator;
 mod sys;
 
 /// Types that manage the memory of command buffers.
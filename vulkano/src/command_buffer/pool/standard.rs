@@ -9,6 +9,7 @@
 
 use crossbeam::sync::MsQueue;
 use fnv::FnvHashMap;
+use std::cell::RefCell;
 use std::collections::hash_map::Entry;
 use std::marker::PhantomData;
 use std::mem::ManuallyDrop;
@@ -16,12 +17,14 @@ use std::ptr;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::Weak;
-use std::thread;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
 use std::vec::IntoIter as VecIntoIter;
 
 use command_buffer::pool::CommandPool;
 use command_buffer::pool::CommandPoolAlloc;
 use command_buffer::pool::CommandPoolBuilderAlloc;
+use command_buffer::pool::CommandPoolTrimError;
 use command_buffer::pool::UnsafeCommandPool;
 use command_buffer::pool::UnsafeCommandPoolAlloc;
 use instance::QueueFamily;
@@ -36,9 +39,13 @@ use device::DeviceOwned;
 /// It is guaranteed that the allocated command buffers keep the `Arc<StandardCommandPool>` alive.
 /// This is desirable so that we can store a `Weak<StandardCommandPool>`.
 ///
-/// Will use one Vulkan pool per thread in order to avoid locking. Will try to reuse command
-/// buffers. Command buffers can't be moved between threads during the building process, but
-/// finished command buffers can.
+/// Will use one Vulkan pool per thread in order to avoid locking. The pool of each thread is
+/// found in a thread-local storage, so threads that allocate command buffers at the same time
+/// don't wait for each other. Will try to reuse command buffers. Command buffers can't be moved
+/// between threads during the building process, but finished command buffers can.
+///
+/// The Vulkan pools and the command buffers kept for reuse are only destroyed along with the
+/// `StandardCommandPool`. Use `trim` or `reset` to return their memory to the driver earlier.
 pub struct StandardCommandPool {
     // The device.
     device: Arc<Device>,
@@ -46,8 +53,21 @@ pub struct StandardCommandPool {
     // Identifier of the queue family.
     queue_family: u32,
 
-    // For each thread, we store thread-specific info.
-    per_thread: Mutex<FnvHashMap<thread::ThreadId, Weak<StandardCommandPoolPerThread>>>,
+    // Identifier of this pool in the thread-local storage of the threads that use it.
+    id: usize,
+
+    // The pools of all the threads that use this pool. Keeps them alive, so that the command
+    // buffers available for reuse aren't destroyed when a thread has no command buffer in use.
+    per_thread_pools: Mutex<Vec<Arc<StandardCommandPoolPerThread>>>,
+}
+
+// Source of the identifiers of the `StandardCommandPool`s.
+static NEXT_POOL_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // For each `StandardCommandPool` used by the current thread, the pool of the thread.
+    static PER_THREAD: RefCell<FnvHashMap<usize, Weak<StandardCommandPoolPerThread>>> =
+        RefCell::new(Default::default());
 }
 
 unsafe impl Send for StandardCommandPool {
@@ -62,6 +82,11 @@ struct StandardCommandPoolPerThread {
     available_primary_command_buffers: MsQueue<UnsafeCommandPoolAlloc>,
     // List of existing secondary command buffers that are available for reuse.
     available_secondary_command_buffers: MsQueue<UnsafeCommandPoolAlloc>,
+    // Number of command buffers allocated from the Vulkan pool.
+    num_allocated: AtomicUsize,
+    // Number of command buffers in the two lists above. Incremented after a command buffer is
+    // pushed and decremented after it is popped, so that it never overestimates.
+    num_available: AtomicUsize,
 }
 
 impl StandardCommandPool {
@@ -78,41 +103,136 @@ impl StandardCommandPool {
         StandardCommandPool {
             device: device,
             queue_family: queue_family.id(),
-            per_thread: Mutex::new(Default::default()),
+            id: NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed),
+            per_thread_pools: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// Trims the Vulkan pool of the current thread, which returns its unused memory to the
+    /// driver. This is useful after a spike of command buffer allocations.
+    ///
+    /// The command buffers allocated from the pool are not affected. Does nothing if the current
+    /// thread hasn't allocated any command buffer from this pool.
+    ///
+    /// Requires the `VK_KHR_maintenance1` extension.
+    pub fn trim(&self) -> Result<(), CommandPoolTrimError> {
+        if !self.device.loaded_extensions().khr_maintenance1 {
+            return Err(CommandPoolTrimError::Maintenance1ExtensionNotEnabled);
+        }
+
+        if let Some(per_thread) = self.current_thread_pool() {
+            per_thread.pool.lock().unwrap().trim()?;
+        }
+
+        Ok(())
+    }
+
+    /// Resets the Vulkan pool of the current thread, which resets all of its command buffers at
+    /// once.
+    ///
+    /// If `release_resources` is true, the memory of the command buffers is returned to the
+    /// driver.
+    ///
+    /// The pool can only be reset if none of its command buffers are in use, in other words if
+    /// all the command buffers and builders allocated by the current thread have been destroyed.
+    /// Returns `Ok(false)` without doing anything if that's not the case.
+    pub fn reset(&self, release_resources: bool) -> Result<bool, OomError> {
+        let per_thread = match self.current_thread_pool() {
+            Some(pt) => pt,
+            None => return Ok(true),
+        };
+
+        // Only the current thread can allocate from this Vulkan pool, therefore the number of
+        // command buffers in use can't increase in the meantime.
+        let pool = per_thread.pool.lock().unwrap();
+        let num_available = per_thread.num_available.load(Ordering::Acquire);
+        if num_available != per_thread.num_allocated.load(Ordering::Relaxed) {
+            return Ok(false);
+        }
+
+        unsafe {
+            pool.reset(release_resources)?;
+        }
+        Ok(true)
+    }
+
+    /// Returns statistics about the command buffers allocated by all the threads that use this
+    /// pool.
+    pub fn statistics(&self) -> CommandPoolStatistics {
+        let per_thread_pools = self.per_thread_pools.lock().unwrap();
+
+        let mut stats = CommandPoolStatistics::default();
+        for per_thread in per_thread_pools.iter() {
+            let num_available = per_thread.num_available.load(Ordering::Acquire);
+            let num_allocated = per_thread.num_allocated.load(Ordering::Acquire);
+            stats.num_pools += 1;
+            stats.num_allocated += num_allocated;
+            stats.num_in_use += num_allocated.saturating_sub(num_available);
         }
+        stats
+    }
+
+    // Returns the pool of the current thread, if the current thread has allocated from this pool.
+    fn current_thread_pool(&self) -> Option<Arc<StandardCommandPoolPerThread>> {
+        PER_THREAD.with(|per_thread| {
+                            per_thread
+                                .borrow()
+                                .get(&self.id)
+                                .and_then(|pt| pt.upgrade())
+                        })
     }
 }
 
+/// Statistics about the command buffers of a `StandardCommandPool`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct CommandPoolStatistics {
+    /// Number of Vulkan pools, which is the number of threads that allocate from the pool.
+    pub num_pools: usize,
+    /// Number of command buffers allocated from the Vulkan pools, including the ones that are
+    /// kept for reuse.
+    pub num_allocated: usize,
+    /// Number of command buffers that are being recorded, pending execution or simply not
+    /// destroyed yet.
+    pub num_in_use: usize,
+}
+
 unsafe impl CommandPool for Arc<StandardCommandPool> {
     type Iter = VecIntoIter<StandardCommandPoolBuilder>;
     type Builder = StandardCommandPoolBuilder;
     type Alloc = StandardCommandPoolAlloc;
 
     fn alloc(&self, secondary: bool, count: u32) -> Result<Self::Iter, OomError> {
-        // Find the correct `StandardCommandPoolPerThread` structure.
-        let mut hashmap = self.per_thread.lock().unwrap();
-        // TODO: meh for iterating everything every time
-        hashmap.retain(|_, w| w.upgrade().is_some());
-
-        // Get an appropriate `Arc<StandardCommandPoolPerThread>`.
-        let per_thread = match hashmap.entry(thread::current().id()) {
-            Entry::Occupied(entry) => {
-                // The `unwrap()` can't fail, since we retained only valid members earlier.
-                entry.get().upgrade().unwrap()
-            },
-            Entry::Vacant(entry) => {
-                let new_pool =
-                    UnsafeCommandPool::new(self.device.clone(), self.queue_family(), false, true)?;
-                let pt = Arc::new(StandardCommandPoolPerThread {
-                                      pool: Mutex::new(new_pool),
-                                      available_primary_command_buffers: MsQueue::new(),
-                                      available_secondary_command_buffers: MsQueue::new(),
-                                  });
-
-                entry.insert(Arc::downgrade(&pt));
-                pt
-            },
-        };
+        // Get the `StandardCommandPoolPerThread` of the current thread, or create it.
+        let per_thread = PER_THREAD.with(|per_thread| -> Result<_, OomError> {
+            let mut hashmap = per_thread.borrow_mut();
+            // Also removes the entries of the pools that have been destroyed.
+            // TODO: meh for iterating everything every time
+            hashmap.retain(|_, w| w.upgrade().is_some());
+
+            match hashmap.entry(self.id) {
+                Entry::Occupied(entry) => {
+                    // The `unwrap()` can't fail, since we retained only valid members earlier.
+                    Ok(entry.get().upgrade().unwrap())
+                },
+                Entry::Vacant(entry) => {
+                    let new_pool = UnsafeCommandPool::new(self.device.clone(),
+                                                          self.queue_family(),
+                                                          false,
+                                                          true)?;
+                    let pt = Arc::new(StandardCommandPoolPerThread {
+                                          pool: Mutex::new(new_pool),
+                                          available_primary_command_buffers: MsQueue::new(),
+                                          available_secondary_command_buffers: MsQueue::new(),
+                                          num_allocated: AtomicUsize::new(0),
+                                          num_available: AtomicUsize::new(0),
+                                      });
+
+                    entry.insert(Arc::downgrade(&pt));
+                    self.per_thread_pools.lock().unwrap().push(pt.clone());
+                    Ok(pt)
+                },
+            }
+        })?;
 
         // The final output.
         let mut output = Vec::with_capacity(count as usize);
@@ -127,6 +247,7 @@ unsafe impl CommandPool for Arc<StandardCommandPool> {
 
             for _ in 0 .. count as usize {
                 if let Some(cmd) = existing.try_pop() {
+                    per_thread.num_available.fetch_sub(1, Ordering::AcqRel);
                     output.push(StandardCommandPoolBuilder {
                                     inner: StandardCommandPoolAlloc {
                                         cmd: ManuallyDrop::new(cmd),
@@ -148,7 +269,12 @@ unsafe impl CommandPool for Arc<StandardCommandPool> {
             let pool_lock = per_thread.pool.lock().unwrap();
             let num_new = count as usize - output.len();
 
-            for cmd in pool_lock.alloc_command_buffers(secondary, num_new)? {
+            let new_command_buffers = pool_lock.alloc_command_buffers(secondary, num_new)?;
+            per_thread
+                .num_allocated
+                .fetch_add(new_command_buffers.len(), Ordering::AcqRel);
+
+            for cmd in new_command_buffers {
                 output.push(StandardCommandPoolBuilder {
                                 inner: StandardCommandPoolAlloc {
                                     cmd: ManuallyDrop::new(cmd),
@@ -236,6 +362,38 @@ unsafe impl Send for StandardCommandPoolAlloc {
 unsafe impl Sync for StandardCommandPoolAlloc {
 }
 
+impl StandardCommandPoolAlloc {
+    // Turns the command buffer back into a builder, so that it can be recorded again. Returns
+    // `Err` if the command buffer wasn't allocated from the pool of the current thread, as the
+    // pool of another thread could be in use.
+    pub(crate) fn into_builder(self)
+                               -> Result<StandardCommandPoolBuilder, StandardCommandPoolAlloc> {
+        let same_thread = PER_THREAD.with(|per_thread| {
+            per_thread
+                .borrow()
+                .get(&self.pool_parent.id)
+                .and_then(|pt| pt.upgrade())
+                .map(|pt| Arc::ptr_eq(&pt, &self.pool))
+                .unwrap_or(false)
+        });
+
+        if !same_thread {
+            return Err(self);
+        }
+
+        Ok(StandardCommandPoolBuilder {
+               inner: self,
+               dummy_avoid_send_sync: PhantomData,
+           })
+    }
+
+    // True if the command buffer was allocated as a secondary command buffer.
+    #[inline]
+    pub(crate) fn is_secondary(&self) -> bool {
+        self.secondary
+    }
+}
+
 unsafe impl CommandPoolAlloc for StandardCommandPoolAlloc {
     #[inline]
     fn inner(&self) -> &UnsafeCommandPoolAlloc {
@@ -272,6 +430,7 @@ impl Drop for StandardCommandPoolAlloc {
         } else {
             self.pool.available_primary_command_buffers.push(cmd);
         }
+        self.pool.num_available.fetch_add(1, Ordering::AcqRel);
     }
 }
 
@@ -280,6 +439,7 @@ mod tests {
     use VulkanObject;
     use command_buffer::pool::CommandPool;
     use command_buffer::pool::CommandPoolBuilderAlloc;
+    use command_buffer::pool::CommandPoolStatistics;
     use command_buffer::pool::StandardCommandPool;
     use device::Device;
     use std::sync::Arc;
@@ -313,4 +473,29 @@ mod tests {
         drop(cb);
         assert!(pool_weak.upgrade().is_none());
     }
+
+    #[test]
+    fn statistics_and_reset() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Arc::new(StandardCommandPool::new(device, queue.family()));
+        assert_eq!(pool.statistics(), CommandPoolStatistics::default());
+
+        let cbs = pool.alloc(false, 3).unwrap().collect::<Vec<_>>();
+        assert_eq!(pool.statistics(),
+                   CommandPoolStatistics {
+                       num_pools: 1,
+                       num_allocated: 3,
+                       num_in_use: 3,
+                   });
+        assert_eq!(pool.reset(false), Ok(false));
+
+        drop(cbs);
+        assert_eq!(pool.statistics().num_in_use, 0);
+        assert_eq!(pool.reset(true), Ok(true));
+
+        // The command buffers are reused after the reset.
+        let _cb = pool.alloc(false, 1).unwrap().next().unwrap();
+        assert_eq!(pool.statistics().num_allocated, 3);
+    }
 }
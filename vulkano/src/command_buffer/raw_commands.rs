@@ -0,0 +1,73 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use image::ImageAccess;
+use image::ImageLayout;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// List of the resources accessed by commands recorded with
+/// `AutoCommandBufferBuilder::raw_commands`.
+///
+/// Vulkano can't know what raw commands do. The resources declared here are kept alive by the
+/// command buffer and synchronized with the other commands of the builder exactly as if they were
+/// accessed by a regular command.
+#[derive(Clone)]
+pub struct RawCommandsResources {
+    /// Buffers accessed by the raw commands.
+    pub buffers: Vec<RawCommandsBuffer>,
+    /// Images accessed by the raw commands.
+    pub images: Vec<RawCommandsImage>,
+}
+
+impl RawCommandsResources {
+    /// Builds a list that contains no resource.
+    #[inline]
+    pub fn new() -> RawCommandsResources {
+        RawCommandsResources {
+            buffers: Vec::new(),
+            images: Vec::new(),
+        }
+    }
+}
+
+/// A buffer accessed by raw commands.
+#[derive(Clone)]
+pub struct RawCommandsBuffer {
+    /// The buffer. The whole buffer is synchronized, even if the raw commands only access a part
+    /// of it.
+    pub buffer: Arc<BufferAccess + Send + Sync>,
+    /// Stages in which the buffer is accessed.
+    pub stages: PipelineStages,
+    /// How the buffer is accessed.
+    pub access: AccessFlagBits,
+    /// True if the buffer is written.
+    pub exclusive: bool,
+}
+
+/// An image accessed by raw commands.
+#[derive(Clone)]
+pub struct RawCommandsImage {
+    /// The image. All its mipmap levels and array layers are synchronized, even if the raw
+    /// commands only access some of them.
+    pub image: Arc<ImageAccess + Send + Sync>,
+    /// Stages in which the image is accessed.
+    pub stages: PipelineStages,
+    /// How the image is accessed.
+    pub access: AccessFlagBits,
+    /// True if the image is written.
+    pub exclusive: bool,
+    /// Layout the image must be in when the raw commands start executing.
+    pub start_layout: ImageLayout,
+    /// Layout the image is in after the raw commands have executed.
+    pub end_layout: ImageLayout,
+}
@@ -0,0 +1,971 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Describing a frame as a graph of passes.
+//!
+//! A `RenderGraph` contains a list of *passes*. Each pass declares the resources it reads and
+//! writes, and how it accesses them, and provides a closure that records its commands into an
+//! `AutoCommandBufferBuilder`. When the graph is recorded:
+//!
+//! - The passes whose results are never used are removed. A pass is kept if it writes a buffer
+//!   or an image that was imported in the graph, or a transient image that a kept pass reads.
+//! - The transient images are created. Two transient images with the same dimensions, format
+//!   and usage share the same memory if their lifetimes don't overlap.
+//! - Before each pass, a pipeline barrier is added that makes the previous writes visible to the
+//!   pass and transitions the images to the layouts required by the pass.
+//!
+//! The passes are executed in the order in which they were added, which must be an order in
+//! which every read happens after the matching write.
+//!
+//! ```
+//! use vulkano::command_buffer::AutoCommandBufferBuilder;
+//! use vulkano::command_buffer::render_graph::RenderGraph;
+//! use vulkano::command_buffer::render_graph::ResourceAccess;
+//! use vulkano::format::ClearValue;
+//! use vulkano::format::Format;
+//!
+//! # let device: std::sync::Arc<vulkano::device::Device> = return;
+//! # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+//! # let output: std::sync::Arc<vulkano::buffer::CpuAccessibleBuffer<[u8]>> = return;
+//! let mut graph = RenderGraph::new();
+//! let image = graph.transient_image([16, 16], Format::R8G8B8A8Unorm);
+//! let buffer = graph.import_buffer(output.clone());
+//!
+//! graph.add_pass("clear", vec![(image, ResourceAccess::TransferDestination)], |cb, res| {
+//!     Ok(cb.clear_color_image(res.image(image), ClearValue::Float([0.0; 4]))?)
+//! });
+//!
+//! graph.add_pass("readback",
+//!                vec![(image, ResourceAccess::TransferSource),
+//!                     (buffer, ResourceAccess::TransferDestination)],
+//!                |cb, res| Ok(cb.copy_image_to_buffer(res.image(image), output.clone())?));
+//!
+//! let builder = AutoCommandBufferBuilder::primary_one_time_submit(device, queue.family())
+//!     .unwrap();
+//! let command_buffer = graph.record(builder).unwrap().build().unwrap();
+//! ```
+
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::BufferMemoryBarrier;
+use command_buffer::ImageMemoryBarrier;
+use command_buffer::PipelineBarrier;
+use command_buffer::PipelineBarrierError;
+use command_buffer::pool::standard::StandardCommandPoolBuilder;
+use device::DeviceOwned;
+use format::Format;
+use image::AttachmentImage;
+use image::ImageAccess;
+use image::ImageCreationError;
+use image::ImageLayout;
+use image::ImageUsage;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// A frame described as a list of passes that access resources.
+///
+/// See the documentation of the `render_graph` module.
+pub struct RenderGraph<'a, P = StandardCommandPoolBuilder> {
+    resources: Vec<Resource>,
+    passes: Vec<Pass<'a, P>>,
+}
+
+/// Identifier of a resource of a `RenderGraph`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceId(usize);
+
+/// How a pass accesses a resource.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ResourceAccess {
+    /// The image is a color attachment of a render pass.
+    ColorAttachment,
+    /// The image is a depth, stencil or depth-stencil attachment of a render pass.
+    DepthStencilAttachment,
+    /// The image is sampled, or the buffer is read as a uniform or storage buffer, by shaders.
+    ShaderRead,
+    /// The image or buffer is written as a storage image or buffer by shaders.
+    ShaderWrite,
+    /// The resource is the source of a transfer command.
+    TransferSource,
+    /// The resource is the destination of a transfer command.
+    TransferDestination,
+    /// The buffer is used as a vertex buffer.
+    VertexBuffer,
+    /// The buffer is used as an index buffer.
+    IndexBuffer,
+    /// The buffer contains the parameters of an indirect draw or dispatch.
+    IndirectBuffer,
+}
+
+impl ResourceAccess {
+    /// Returns true if the access writes the resource.
+    #[inline]
+    pub fn is_write(&self) -> bool {
+        match *self {
+            ResourceAccess::ColorAttachment |
+            ResourceAccess::DepthStencilAttachment |
+            ResourceAccess::ShaderWrite |
+            ResourceAccess::TransferDestination => true,
+            _ => false,
+        }
+    }
+
+    // Returns true if the access can be performed on an image, or on a buffer if `image` is
+    // false.
+    fn allowed(&self, image: bool) -> bool {
+        match *self {
+            ResourceAccess::ColorAttachment |
+            ResourceAccess::DepthStencilAttachment => image,
+            ResourceAccess::VertexBuffer |
+            ResourceAccess::IndexBuffer |
+            ResourceAccess::IndirectBuffer => !image,
+            _ => true,
+        }
+    }
+
+    // Returns the stages, the accesses and the image layout that correspond to the access.
+    fn properties(&self, image: bool) -> (PipelineStages, AccessFlagBits, ImageLayout) {
+        let shaders = PipelineStages {
+            vertex_shader: true,
+            tessellation_control_shader: true,
+            tessellation_evaluation_shader: true,
+            geometry_shader: true,
+            fragment_shader: true,
+            compute_shader: true,
+            ..PipelineStages::none()
+        };
+
+        let (stages, access, layout) = match *self {
+            ResourceAccess::ColorAttachment => {
+                (PipelineStages {
+                     color_attachment_output: true,
+                     ..PipelineStages::none()
+                 },
+                 AccessFlagBits {
+                     color_attachment_read: true,
+                     color_attachment_write: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::ColorAttachmentOptimal)
+            },
+            ResourceAccess::DepthStencilAttachment => {
+                (PipelineStages {
+                     early_fragment_tests: true,
+                     late_fragment_tests: true,
+                     ..PipelineStages::none()
+                 },
+                 AccessFlagBits {
+                     depth_stencil_attachment_read: true,
+                     depth_stencil_attachment_write: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::DepthStencilAttachmentOptimal)
+            },
+            ResourceAccess::ShaderRead => {
+                (shaders,
+                 AccessFlagBits {
+                     shader_read: true,
+                     uniform_read: !image,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::ShaderReadOnlyOptimal)
+            },
+            ResourceAccess::ShaderWrite => {
+                (shaders,
+                 AccessFlagBits {
+                     shader_read: true,
+                     shader_write: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::General)
+            },
+            ResourceAccess::TransferSource => {
+                (PipelineStages {
+                     transfer: true,
+                     ..PipelineStages::none()
+                 },
+                 AccessFlagBits {
+                     transfer_read: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::TransferSrcOptimal)
+            },
+            ResourceAccess::TransferDestination => {
+                (PipelineStages {
+                     transfer: true,
+                     ..PipelineStages::none()
+                 },
+                 AccessFlagBits {
+                     transfer_write: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::TransferDstOptimal)
+            },
+            ResourceAccess::VertexBuffer => {
+                (PipelineStages {
+                     vertex_input: true,
+                     ..PipelineStages::none()
+                 },
+                 AccessFlagBits {
+                     vertex_attribute_read: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::Undefined)
+            },
+            ResourceAccess::IndexBuffer => {
+                (PipelineStages {
+                     vertex_input: true,
+                     ..PipelineStages::none()
+                 },
+                 AccessFlagBits {
+                     index_read: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::Undefined)
+            },
+            ResourceAccess::IndirectBuffer => {
+                (PipelineStages {
+                     draw_indirect: true,
+                     ..PipelineStages::none()
+                 },
+                 AccessFlagBits {
+                     indirect_command_read: true,
+                     ..AccessFlagBits::none()
+                 },
+                 ImageLayout::Undefined)
+            },
+        };
+
+        (stages, access, if image { layout } else { ImageLayout::Undefined })
+    }
+
+    // Returns the usage that a transient image needs for this access, in addition to the
+    // attachment usages.
+    fn image_usage(&self) -> ImageUsage {
+        ImageUsage {
+            sampled: *self == ResourceAccess::ShaderRead,
+            storage: *self == ResourceAccess::ShaderWrite,
+            transfer_source: *self == ResourceAccess::TransferSource,
+            transfer_destination: *self == ResourceAccess::TransferDestination,
+            ..ImageUsage::none()
+        }
+    }
+}
+
+enum Resource {
+    Buffer(Arc<BufferAccess + Send + Sync>),
+    Image(Arc<ImageAccess + Send + Sync>),
+    Transient { dimensions: [u32; 2], format: Format },
+}
+
+struct Pass<'a, P> {
+    name: &'static str,
+    accesses: Vec<(ResourceId, ResourceAccess)>,
+    record: Box<FnMut(AutoCommandBufferBuilder<P>, &PassResources)
+                      -> Result<AutoCommandBufferBuilder<P>, Box<error::Error + Send + Sync>>
+                      + 'a>,
+}
+
+impl<'a, P> RenderGraph<'a, P> {
+    /// Builds a new empty graph.
+    #[inline]
+    pub fn new() -> RenderGraph<'a, P> {
+        RenderGraph {
+            resources: Vec::new(),
+            passes: Vec::new(),
+        }
+    }
+
+    /// Adds a buffer that exists outside of the graph.
+    ///
+    /// The passes that write an imported resource are never removed from the graph.
+    pub fn import_buffer<B>(&mut self, buffer: B) -> ResourceId
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        self.resources.push(Resource::Buffer(Arc::new(buffer)));
+        ResourceId(self.resources.len() - 1)
+    }
+
+    /// Adds an image that exists outside of the graph.
+    ///
+    /// The passes that write an imported resource are never removed from the graph. The image
+    /// is expected to be in the layout it has at the end of the previous commands of the
+    /// builder, or in its initial layout requirement.
+    pub fn import_image<I>(&mut self, image: I) -> ResourceId
+        where I: ImageAccess + Send + Sync + 'static
+    {
+        self.resources.push(Resource::Image(Arc::new(image)));
+        ResourceId(self.resources.len() - 1)
+    }
+
+    /// Adds an image that only exists during the execution of the graph.
+    ///
+    /// The image is created when the graph is recorded, with the usages required by the passes
+    /// that access it. Its content is undefined before the first pass that writes it.
+    pub fn transient_image(&mut self, dimensions: [u32; 2], format: Format) -> ResourceId {
+        self.resources.push(Resource::Transient { dimensions, format });
+        ResourceId(self.resources.len() - 1)
+    }
+
+    /// Adds a pass at the end of the graph.
+    ///
+    /// `accesses` is the list of resources that the commands recorded by `record` access, with
+    /// the way they access them. `record` receives the builder and the resources of the graph,
+    /// and must return the builder.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the resources doesn't belong to this graph.
+    ///
+    pub fn add_pass<I, F>(&mut self, name: &'static str, accesses: I, record: F)
+        where I: IntoIterator<Item = (ResourceId, ResourceAccess)>,
+              F: FnMut(AutoCommandBufferBuilder<P>, &PassResources)
+                       -> Result<AutoCommandBufferBuilder<P>, Box<error::Error + Send + Sync>>
+                       + 'a
+    {
+        let accesses = accesses.into_iter().collect::<Vec<_>>();
+        for &(ResourceId(id), _) in accesses.iter() {
+            assert!(id < self.resources.len(), "the resource doesn't belong to this graph");
+        }
+
+        self.passes.push(Pass {
+                             name,
+                             accesses,
+                             record: Box::new(record),
+                         });
+    }
+
+    /// Checks the graph, and returns the passes that will be recorded and how the transient
+    /// images are allocated.
+    pub fn compile(&self) -> Result<RenderGraphSchedule, RenderGraphError> {
+        for pass in self.passes.iter() {
+            for (num, &(id, access)) in pass.accesses.iter().enumerate() {
+                if pass.accesses[.. num].iter().any(|&(other, _)| other == id) {
+                    return Err(RenderGraphError::ResourceUsedTwice { pass: pass.name });
+                }
+
+                let image = match self.resources[id.0] {
+                    Resource::Buffer(_) => false,
+                    _ => true,
+                };
+
+                if !access.allowed(image) {
+                    return Err(RenderGraphError::InvalidAccess { pass: pass.name });
+                }
+            }
+        }
+
+        // Walking the passes backwards in order to find the ones whose results are used.
+        let mut needed = self.resources
+            .iter()
+            .map(|r| match *r {
+                     Resource::Transient { .. } => false,
+                     _ => true,
+                 })
+            .collect::<Vec<_>>();
+        let mut kept = vec![false; self.passes.len()];
+        for (num, pass) in self.passes.iter().enumerate().rev() {
+            kept[num] = pass.accesses
+                .iter()
+                .any(|&(id, access)| access.is_write() && needed[id.0]);
+
+            if kept[num] {
+                for &(id, access) in pass.accesses.iter() {
+                    if !access.is_write() {
+                        needed[id.0] = true;
+                    }
+                }
+            }
+        }
+
+        let order = (0 .. self.passes.len()).filter(|&n| kept[n]).collect::<Vec<_>>();
+
+        // Checking that the transient images are written before being read, and computing their
+        // lifetimes and usages.
+        let mut lifetimes: Vec<Option<(usize, usize)>> = vec![None; self.resources.len()];
+        let mut usages = vec![ImageUsage::none(); self.resources.len()];
+        for (position, &num) in order.iter().enumerate() {
+            let pass = &self.passes[num];
+            for &(id, access) in pass.accesses.iter() {
+                if let Resource::Transient { .. } = self.resources[id.0] {
+                    match lifetimes[id.0] {
+                        Some((_, ref mut last)) => *last = position,
+                        None if access.is_write() => lifetimes[id.0] = Some((position, position)),
+                        None => return Err(RenderGraphError::ReadBeforeWrite { pass: pass.name }),
+                    }
+
+                    usages[id.0] = usages[id.0] | access.image_usage();
+                }
+            }
+        }
+
+        // Assigning the transient images to the images that will be created, in the order of
+        // their first use. An image is reused if its previous user is no longer alive.
+        let mut by_first_use = (0 .. self.resources.len())
+            .filter(|&id| lifetimes[id].is_some())
+            .collect::<Vec<_>>();
+        by_first_use.sort_by_key(|&id| lifetimes[id].unwrap().0);
+
+        let mut images: Vec<TransientImage> = Vec::new();
+        let mut transient_images = vec![None; self.resources.len()];
+        for id in by_first_use {
+            let (first, last) = lifetimes[id].unwrap();
+            let (dimensions, format) = match self.resources[id] {
+                Resource::Transient { dimensions, format } => (dimensions, format),
+                _ => unreachable!(),
+            };
+
+            let existing = images.iter().position(|img| {
+                img.dimensions == dimensions && img.format == format &&
+                    img.usage == usages[id] && img.last_use < first
+            });
+
+            let slot = match existing {
+                Some(slot) => slot,
+                None => {
+                    images.push(TransientImage {
+                                    dimensions,
+                                    format,
+                                    usage: usages[id],
+                                    last_use: 0,
+                                });
+                    images.len() - 1
+                },
+            };
+
+            images[slot].last_use = last;
+            transient_images[id] = Some(slot);
+        }
+
+        Ok(RenderGraphSchedule {
+               order,
+               names: self.passes.iter().map(|p| p.name).collect(),
+               transient_images,
+               images,
+           })
+    }
+
+    /// Compiles the graph, then records its passes into `builder` with the barriers between
+    /// them.
+    pub fn record(mut self, mut builder: AutoCommandBufferBuilder<P>)
+                  -> Result<AutoCommandBufferBuilder<P>, RenderGraphError> {
+        let schedule = self.compile()?;
+
+        let created = schedule
+            .images
+            .iter()
+            .map(|img| {
+                     AttachmentImage::with_usage(builder.device().clone(),
+                                                 img.dimensions,
+                                                 img.format,
+                                                 img.usage)
+                 })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let resources = PassResources {
+            resources: self.resources
+                .iter()
+                .enumerate()
+                .map(|(id, r)| match *r {
+                         Resource::Buffer(ref buf) => Some(PhysicalResource::Buffer(buf.clone())),
+                         Resource::Image(ref img) => Some(PhysicalResource::Image(img.clone())),
+                         Resource::Transient { .. } => {
+                             schedule.transient_images[id]
+                                 .map(|slot| PhysicalResource::Transient(created[slot].clone()))
+                         },
+                     })
+                .collect(),
+        };
+
+        // State of each buffer, imported image and created image. The created images come
+        // after the resources of the graph.
+        let mut states: Vec<Option<AccessState>> =
+            vec![None; self.resources.len() + created.len()];
+
+        for &num in schedule.order.iter() {
+            let pass = &mut self.passes[num];
+            let mut barrier = PipelineBarrier::new();
+
+            for &(id, access) in pass.accesses.iter() {
+                let (image, physical) = match self.resources[id.0] {
+                    Resource::Buffer(_) => (false, id.0),
+                    Resource::Image(_) => (true, id.0),
+                    Resource::Transient { .. } => {
+                        (true, self.resources.len() + schedule.transient_images[id.0].unwrap())
+                    },
+                };
+
+                let (stages, flags, layout) = access.properties(image);
+                let write = access.is_write();
+
+                let transient = match self.resources[id.0] {
+                    Resource::Transient { .. } => true,
+                    _ => false,
+                };
+
+                let source = next_access(&mut states[physical],
+                                         id,
+                                         transient,
+                                         stages,
+                                         flags,
+                                         layout,
+                                         write);
+
+                let (source_stages, source_access, old_layout) = match source {
+                    Some(s) => s,
+                    None => continue,
+                };
+
+                match resources.resources[id.0] {
+                    Some(PhysicalResource::Buffer(ref buffer)) => {
+                        barrier.buffer_barriers.push(BufferMemoryBarrier {
+                                                         buffer: buffer.clone(),
+                                                         source_stages,
+                                                         source_access,
+                                                         destination_stages: stages,
+                                                         destination_access: flags,
+                                                         range: 0 .. buffer.size(),
+                                                         queue_family_transfer: None,
+                                                     });
+                    },
+                    Some(PhysicalResource::Image(_)) |
+                    Some(PhysicalResource::Transient(_)) => {
+                        let image = resources.image(id);
+                        barrier.image_barriers.push(ImageMemoryBarrier {
+                            mipmap_levels: 0 .. image.mipmap_levels(),
+                            array_layers: 0 .. image.dimensions().array_layers(),
+                            image,
+                            source_stages,
+                            source_access,
+                            destination_stages: stages,
+                            destination_access: flags,
+                            old_layout,
+                            new_layout: layout,
+                            queue_family_transfer: None,
+                        });
+                    },
+                    None => unreachable!(),
+                }
+            }
+
+            if !barrier.is_empty() {
+                builder = builder.pipeline_barrier(barrier)?;
+            }
+
+            builder = (pass.record)(builder, &resources).map_err(|error| {
+                RenderGraphError::PassError {
+                    pass: pass.name,
+                    error,
+                }
+            })?;
+        }
+
+        Ok(builder)
+    }
+}
+
+// Description of an image that is created to hold transient images.
+struct TransientImage {
+    dimensions: [u32; 2],
+    format: Format,
+    usage: ImageUsage,
+    // Position in the execution order of the last pass that uses the image.
+    last_use: usize,
+}
+
+// How a buffer or image was last accessed by the passes of the graph.
+#[derive(Copy, Clone)]
+struct AccessState {
+    // Resource of the graph that last accessed the buffer or image.
+    owner: ResourceId,
+    // Stages and accesses of the last write, or of all the reads since the last write.
+    stages: PipelineStages,
+    access: AccessFlagBits,
+    layout: ImageLayout,
+    write: bool,
+    // Stages and accesses that a read in new stages must wait for, in other words the last
+    // write or the first read after the last layout transition. `None` if the content of the
+    // resource hasn't been modified by the graph.
+    last_write: Option<(PipelineStages, AccessFlagBits)>,
+}
+
+// Updates the state of a buffer or image for a new access by the resource `id`, and returns
+// the source stages, the source accesses and the old layout of the barrier that must precede
+// the access, or `None` if no barrier is needed.
+fn next_access(state: &mut Option<AccessState>, id: ResourceId, transient: bool,
+               stages: PipelineStages, access: AccessFlagBits, layout: ImageLayout, write: bool)
+               -> Option<(PipelineStages, AccessFlagBits, ImageLayout)> {
+    let source = match *state {
+        Some(ref mut prev) => {
+            // The previous content of an aliased image is discarded.
+            let old_layout = if prev.owner == id {
+                prev.layout
+            } else {
+                ImageLayout::Undefined
+            };
+
+            if !prev.write && !write && prev.owner == id && old_layout == layout {
+                // A read only needs a barrier if the previous reads didn't already make the
+                // last write visible to its stages and accesses.
+                let covered = (prev.stages | stages) == prev.stages &&
+                    (prev.access | access).into_vulkan_bits2() == prev.access.into_vulkan_bits2();
+                prev.stages |= stages;
+                prev.access |= access;
+
+                if covered {
+                    return None;
+                }
+
+                return prev.last_write.map(|(stages, access)| (stages, access, layout));
+            }
+
+            Some((prev.stages, prev.access, old_layout))
+        },
+        None if transient => {
+            Some((PipelineStages {
+                      top_of_pipe: true,
+                      ..PipelineStages::none()
+                  },
+                  AccessFlagBits::none(),
+                  ImageLayout::Undefined))
+        },
+        // The builder already knows the state of the imported resources before their first use
+        // in the graph.
+        None => None,
+    };
+
+    let last_write = if write {
+        Some((stages, access))
+    } else {
+        match (*state, source) {
+            (Some(prev), _) if prev.write => Some((prev.stages, prev.access)),
+            // The layout transition happens before the stages of this read, so the next reads
+            // can wait for them.
+            (_, Some(_)) => Some((stages, AccessFlagBits::none())),
+            _ => None,
+        }
+    };
+
+    *state = Some(AccessState {
+                      owner: id,
+                      stages,
+                      access,
+                      layout,
+                      write,
+                      last_write,
+                  });
+
+    source
+}
+
+/// Result of compiling a `RenderGraph`.
+pub struct RenderGraphSchedule {
+    order: Vec<usize>,
+    names: Vec<&'static str>,
+    transient_images: Vec<Option<usize>>,
+    images: Vec<TransientImage>,
+}
+
+impl RenderGraphSchedule {
+    /// Returns the names of the passes that are recorded, in the order in which they are
+    /// recorded.
+    #[inline]
+    pub fn passes(&self) -> Vec<&'static str> {
+        self.order.iter().map(|&n| self.names[n]).collect()
+    }
+
+    /// Returns the number of images that are created to hold the transient images of the graph.
+    #[inline]
+    pub fn transient_images_count(&self) -> usize {
+        self.images.len()
+    }
+}
+
+enum PhysicalResource {
+    Buffer(Arc<BufferAccess + Send + Sync>),
+    Image(Arc<ImageAccess + Send + Sync>),
+    Transient(Arc<AttachmentImage>),
+}
+
+/// Gives access to the buffers and images of a `RenderGraph` while its passes are recorded.
+pub struct PassResources {
+    resources: Vec<Option<PhysicalResource>>,
+}
+
+impl PassResources {
+    /// Returns the buffer that corresponds to `id`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `id` isn't a buffer of the graph.
+    ///
+    pub fn buffer(&self, id: ResourceId) -> Arc<BufferAccess + Send + Sync> {
+        match self.resources[id.0] {
+            Some(PhysicalResource::Buffer(ref buffer)) => buffer.clone(),
+            _ => panic!("the resource is not a buffer"),
+        }
+    }
+
+    /// Returns the image that corresponds to `id`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `id` isn't an image of the graph, or is a transient image that isn't used
+    ///   by any recorded pass.
+    ///
+    pub fn image(&self, id: ResourceId) -> Arc<ImageAccess + Send + Sync> {
+        match self.resources[id.0] {
+            Some(PhysicalResource::Image(ref image)) => image.clone(),
+            Some(PhysicalResource::Transient(ref image)) => image.clone() as Arc<_>,
+            _ => panic!("the resource is not an image"),
+        }
+    }
+
+    /// Returns the image that was created for the transient image `id`, which can be used as
+    /// an attachment of a framebuffer.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `id` isn't a transient image of the graph that is used by a recorded pass.
+    ///
+    pub fn transient_image(&self, id: ResourceId) -> Arc<AttachmentImage> {
+        match self.resources[id.0] {
+            Some(PhysicalResource::Transient(ref image)) => image.clone(),
+            _ => panic!("the resource is not a transient image"),
+        }
+    }
+}
+
+/// Error that can happen when compiling or recording a `RenderGraph`.
+#[derive(Debug)]
+pub enum RenderGraphError {
+    /// A pass accesses the same resource twice.
+    ResourceUsedTwice {
+        /// Name of the faulty pass.
+        pass: &'static str,
+    },
+    /// A pass accesses a buffer as an attachment, or an image as a vertex, index or indirect
+    /// buffer.
+    InvalidAccess {
+        /// Name of the faulty pass.
+        pass: &'static str,
+    },
+    /// A pass reads a transient image that no previous pass has written.
+    ReadBeforeWrite {
+        /// Name of the faulty pass.
+        pass: &'static str,
+    },
+    /// Error while creating a transient image.
+    ImageCreationError(ImageCreationError),
+    /// Error while adding the barrier before a pass.
+    PipelineBarrierError(PipelineBarrierError),
+    /// A pass returned an error while recording its commands.
+    PassError {
+        /// Name of the faulty pass.
+        pass: &'static str,
+        /// The error returned by the pass.
+        error: Box<error::Error + Send + Sync>,
+    },
+}
+
+impl error::Error for RenderGraphError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            RenderGraphError::ResourceUsedTwice { .. } => {
+                "a pass accesses the same resource twice"
+            },
+            RenderGraphError::InvalidAccess { .. } => {
+                "a pass accesses a buffer as an attachment, or an image as a vertex, index or \
+                 indirect buffer"
+            },
+            RenderGraphError::ReadBeforeWrite { .. } => {
+                "a pass reads a transient image that no previous pass has written"
+            },
+            RenderGraphError::ImageCreationError(_) => {
+                "error while creating a transient image"
+            },
+            RenderGraphError::PipelineBarrierError(_) => {
+                "error while adding the barrier before a pass"
+            },
+            RenderGraphError::PassError { .. } => {
+                "a pass returned an error while recording its commands"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            RenderGraphError::ImageCreationError(ref err) => Some(err),
+            RenderGraphError::PipelineBarrierError(ref err) => Some(err),
+            RenderGraphError::PassError { ref error, .. } => Some(&**error),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RenderGraphError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ImageCreationError> for RenderGraphError {
+    #[inline]
+    fn from(err: ImageCreationError) -> RenderGraphError {
+        RenderGraphError::ImageCreationError(err)
+    }
+}
+
+impl From<PipelineBarrierError> for RenderGraphError {
+    #[inline]
+    fn from(err: PipelineBarrierError) -> RenderGraphError {
+        RenderGraphError::PipelineBarrierError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::CommandBuffer;
+    use format::ClearValue;
+    use sync::GpuFuture;
+
+    #[test]
+    fn culls_unused_passes() {
+        let (device, _) = gfx_dev_and_queue!();
+        let output = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        let mut graph: RenderGraph = RenderGraph::new();
+        let used = graph.transient_image([4, 4], Format::R8G8B8A8Unorm);
+        let unused = graph.transient_image([4, 4], Format::R8G8B8A8Unorm);
+        let output = graph.import_buffer(output);
+
+        graph.add_pass("a", vec![(used, ResourceAccess::ColorAttachment)], |cb, _| Ok(cb));
+        graph.add_pass("b", vec![(unused, ResourceAccess::ColorAttachment)], |cb, _| Ok(cb));
+        graph.add_pass("c",
+                       vec![(used, ResourceAccess::TransferSource),
+                            (output, ResourceAccess::TransferDestination)],
+                       |cb, _| Ok(cb));
+
+        let schedule = graph.compile().unwrap();
+        assert_eq!(schedule.passes(), vec!["a", "c"]);
+        assert_eq!(schedule.transient_images_count(), 1);
+    }
+
+    #[test]
+    fn aliases_transient_images() {
+        let (device, _) = gfx_dev_and_queue!();
+        let output = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        let mut graph: RenderGraph = RenderGraph::new();
+        let first = graph.transient_image([4, 4], Format::R8G8B8A8Unorm);
+        let second = graph.transient_image([4, 4], Format::R8G8B8A8Unorm);
+        let output = graph.import_buffer(output);
+
+        for &image in [first, second].iter() {
+            graph.add_pass("write", vec![(image, ResourceAccess::ColorAttachment)], |cb, _| {
+                Ok(cb)
+            });
+            graph.add_pass("read",
+                           vec![(image, ResourceAccess::ShaderRead),
+                                (output, ResourceAccess::ShaderWrite)],
+                           |cb, _| Ok(cb));
+        }
+
+        assert_eq!(graph.compile().unwrap().transient_images_count(), 1);
+    }
+
+    #[test]
+    fn read_before_write() {
+        let (device, _) = gfx_dev_and_queue!();
+        let output = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        let mut graph: RenderGraph = RenderGraph::new();
+        let image = graph.transient_image([4, 4], Format::R8G8B8A8Unorm);
+        let output = graph.import_buffer(output);
+
+        graph.add_pass("read",
+                       vec![(image, ResourceAccess::TransferSource),
+                            (output, ResourceAccess::TransferDestination)],
+                       |cb, _| Ok(cb));
+
+        match graph.compile() {
+            Err(RenderGraphError::ReadBeforeWrite { pass: "read" }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn reads_in_new_stages_wait_for_last_write() {
+        let id = ResourceId(0);
+        let mut state = None;
+
+        let write = ResourceAccess::TransferDestination.properties(false);
+        let vertex = ResourceAccess::VertexBuffer.properties(false);
+        let indirect = ResourceAccess::IndirectBuffer.properties(false);
+
+        assert!(next_access(&mut state, id, false, write.0, write.1, write.2, true).is_none());
+
+        let (stages, access, _) =
+            next_access(&mut state, id, false, vertex.0, vertex.1, vertex.2, false).unwrap();
+        assert!(stages == write.0 && access.transfer_write);
+
+        // The second read happens in a different stage and must also wait for the write.
+        let (stages, access, _) =
+            next_access(&mut state, id, false, indirect.0, indirect.1, indirect.2, false)
+                .unwrap();
+        assert!(stages == write.0 && access.transfer_write);
+
+        // Reading again in a stage that already waited doesn't need another barrier.
+        assert!(next_access(&mut state, id, false, vertex.0, vertex.1, vertex.2, false)
+                    .is_none());
+    }
+
+    #[test]
+    fn record_and_execute() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let output = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                    BufferUsage::all(),
+                                                    (0 .. 64).map(|_| 0u8))
+            .unwrap();
+
+        let mut graph = RenderGraph::new();
+        let image = graph.transient_image([4, 4], Format::R8G8B8A8Unorm);
+        let buffer = graph.import_buffer(output.clone());
+
+        graph.add_pass("clear", vec![(image, ResourceAccess::TransferDestination)], |cb, res| {
+            Ok(cb.clear_color_image(res.image(image), ClearValue::Float([1.0; 4]))?)
+        });
+        graph.add_pass("readback",
+                       vec![(image, ResourceAccess::TransferSource),
+                            (buffer, ResourceAccess::TransferDestination)],
+                       |cb, res| Ok(cb.copy_image_to_buffer(res.image(image), output.clone())?));
+
+        let builder = AutoCommandBufferBuilder::primary_one_time_submit(device, queue.family())
+            .unwrap();
+        let cb = graph.record(builder).unwrap().build().unwrap();
+
+        cb.execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+        assert!(output.read().unwrap().iter().all(|&b| b == 255));
+    }
+}
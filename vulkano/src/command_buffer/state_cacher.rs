@@ -13,7 +13,11 @@ use command_buffer::DynamicState;
 use descriptor::DescriptorSet;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
 use pipeline::input_assembly::IndexType;
+use pipeline::viewport::Scissor;
+use pipeline::viewport::Viewport;
 use smallvec::SmallVec;
 use std::ops::Range;
 use vk;
@@ -34,6 +38,10 @@ pub struct StateCacher {
     compute_descriptor_sets: SmallVec<[vk::DescriptorSet; 12]>,
     // The descriptor sets for the graphics pipeline.
     graphics_descriptor_sets: SmallVec<[vk::DescriptorSet; 12]>,
+    // The ray tracing pipeline currently bound. 0 if nothing bound.
+    ray_tracing_pipeline: vk::Pipeline,
+    // The descriptor sets for the ray tracing pipeline.
+    ray_tracing_descriptor_sets: SmallVec<[vk::DescriptorSet; 12]>,
     // If the user starts comparing descriptor sets, but drops the helper struct in the middle of
     // the processing then we will end up in a weird state. This bool is true when we start
     // comparing sets, and is set to false when we end up comparing. If it was true when we start
@@ -66,6 +74,8 @@ impl StateCacher {
             graphics_pipeline: 0,
             compute_descriptor_sets: SmallVec::new(),
             graphics_descriptor_sets: SmallVec::new(),
+            ray_tracing_pipeline: 0,
+            ray_tracing_descriptor_sets: SmallVec::new(),
             poisonned_descriptor_sets: false,
             vertex_buffers: SmallVec::new(),
             poisonned_vertex_buffers: false,
@@ -82,6 +92,8 @@ impl StateCacher {
         self.graphics_pipeline = 0;
         self.compute_descriptor_sets = SmallVec::new();
         self.graphics_descriptor_sets = SmallVec::new();
+        self.ray_tracing_pipeline = 0;
+        self.ray_tracing_descriptor_sets = SmallVec::new();
         self.vertex_buffers = SmallVec::new();
         self.index_buffer = None;
     }
@@ -105,10 +117,69 @@ impl StateCacher {
         cmp!(line_width);
         cmp!(viewports);
         cmp!(scissors);
+        cmp!(depth_bias);
+        cmp!(depth_bounds);
+        cmp!(blend_constants);
+        cmp!(compare_mask);
+        cmp!(write_mask);
+        cmp!(reference);
+        cmp!(primitive_topology);
+        cmp!(cull_mode);
+        cmp!(front_face);
+        cmp!(depth_test_enable);
+        cmp!(depth_write_enable);
+        cmp!(depth_compare_op);
+        cmp!(depth_bounds_test_enable);
+        cmp!(stencil_test_enable);
+        cmp!(stencil_op);
+        cmp!(line_stipple);
+        // The vertex strides are bound alongside the vertex buffers, and thus aren't cached here.
 
         incoming
     }
 
+    /// Updates the cache after `viewports` have been set in the command buffer builder, starting
+    /// at index `first_viewport`.
+    #[inline]
+    pub fn set_viewports(&mut self, first_viewport: u32, viewports: &[Viewport]) {
+        splice_state(&mut self.dynamic_state.viewports, first_viewport, viewports);
+    }
+
+    /// Updates the cache after `scissors` have been set in the command buffer builder, starting
+    /// at index `first_scissor`.
+    #[inline]
+    pub fn set_scissors(&mut self, first_scissor: u32, scissors: &[Scissor]) {
+        splice_state(&mut self.dynamic_state.scissors, first_scissor, scissors);
+    }
+
+    /// Fills the viewports and the scissor boxes that `pipeline` expects as dynamic state but
+    /// that are missing from `state`, with the ones that were previously set with
+    /// `set_viewports` and `set_scissors`.
+    ///
+    /// The values that haven't all been set are left to `None`.
+    pub fn fill_viewports_scissors<P>(&self, pipeline: &P, mut state: DynamicState)
+                                      -> DynamicState
+        where P: GraphicsPipelineAbstract
+    {
+        let num = pipeline.num_viewports() as usize;
+
+        if pipeline.has_dynamic_viewports() && state.viewports.is_none() {
+            state.viewports = match self.dynamic_state.viewports {
+                Some(ref v) if v.len() >= num => Some(v[.. num].to_vec()),
+                _ => None,
+            };
+        }
+
+        if pipeline.has_dynamic_scissors() && state.scissors.is_none() {
+            state.scissors = match self.dynamic_state.scissors {
+                Some(ref s) if s.len() >= num => Some(s[.. num].to_vec()),
+                _ => None,
+            };
+        }
+
+        state
+    }
+
     /// Starts the process of comparing a list of descriptor sets to the descriptor sets currently
     /// in cache.
     ///
@@ -119,20 +190,22 @@ impl StateCacher {
     /// This process also updates the state cacher. The state cacher assumes that the state
     /// changes are going to be performed after the `compare` function returns.
     #[inline]
-    pub fn bind_descriptor_sets(&mut self, graphics: bool) -> StateCacherDescriptorSets {
+    pub fn bind_descriptor_sets(&mut self, bind_point: PipelineBindPoint)
+                                -> StateCacherDescriptorSets {
         if self.poisonned_descriptor_sets {
             self.compute_descriptor_sets = SmallVec::new();
             self.graphics_descriptor_sets = SmallVec::new();
+            self.ray_tracing_descriptor_sets = SmallVec::new();
         }
 
         self.poisonned_descriptor_sets = true;
 
         StateCacherDescriptorSets {
             poisonned: &mut self.poisonned_descriptor_sets,
-            state: if graphics {
-                &mut self.graphics_descriptor_sets
-            } else {
-                &mut self.compute_descriptor_sets
+            state: match bind_point {
+                PipelineBindPoint::Graphics => &mut self.graphics_descriptor_sets,
+                PipelineBindPoint::Compute => &mut self.compute_descriptor_sets,
+                PipelineBindPoint::RayTracing => &mut self.ray_tracing_descriptor_sets,
             },
             offset: 0,
             found_diff: None,
@@ -175,6 +248,24 @@ impl StateCacher {
         }
     }
 
+    /// Checks whether we need to bind a ray tracing pipeline. Returns
+    /// `StateCacherOutcome::AlreadyOk` if the pipeline was already bound earlier, and
+    /// `StateCacherOutcome::NeedChange` if you need to actually bind the pipeline.
+    ///
+    /// This function also updates the state cacher. The state cacher assumes that the state
+    /// changes are going to be performed after this function returns.
+    pub fn bind_ray_tracing_pipeline<P>(&mut self, pipeline: &P) -> StateCacherOutcome
+        where P: RayTracingPipelineAbstract
+    {
+        let inner = pipeline.inner().internal_object();
+        if inner == self.ray_tracing_pipeline {
+            StateCacherOutcome::AlreadyOk
+        } else {
+            self.ray_tracing_pipeline = inner;
+            StateCacherOutcome::NeedChange
+        }
+    }
+
     /// Starts the process of comparing a list of vertex buffers to the vertex buffers currently
     /// in cache.
     ///
@@ -224,6 +315,28 @@ impl StateCacher {
     }
 }
 
+// Replaces the elements of `state` starting at `first` with `values`. If this would leave
+// elements with an unknown value, the state becomes `None`.
+fn splice_state<T>(state: &mut Option<Vec<T>>, first: u32, values: &[T])
+    where T: Clone
+{
+    let first = first as usize;
+
+    let mut current = match state.take() {
+        Some(v) => v,
+        None if first == 0 => Vec::new(),
+        None => return,
+    };
+
+    if first > current.len() {
+        return;
+    }
+
+    let end = (first + values.len()).min(current.len());
+    current.splice(first .. end, values.iter().cloned());
+    *state = Some(current);
+}
+
 /// Helper struct for comparing descriptor sets.
 ///
 /// > **Note**: For reliability reasons, if you drop/leak this struct before calling `compare` then
@@ -352,7 +465,9 @@ impl<'s> StateCacherVertexBuffers<'s> {
 mod tests {
     use buffer::BufferUsage;
     use buffer::CpuAccessibleBuffer;
+    use command_buffer::DynamicState;
     use command_buffer::state_cacher::StateCacher;
+    use pipeline::viewport::Scissor;
 
     #[test]
     fn vb_caching_single() {
@@ -464,4 +579,26 @@ mod tests {
             assert_eq!(bind_vb.compare(), Some(0 .. 1));
         }
     }
+
+    #[test]
+    fn viewports_partial_set() {
+        let scissor = |x| Scissor { origin: [x, 0], dimensions: [16, 16] };
+        let mut cacher = StateCacher::new();
+
+        // Setting a scissor box with an index beyond the known ones leaves the state unknown.
+        cacher.set_scissors(1, &[scissor(1)]);
+        assert_eq!(cacher.dynamic_state.scissors, None);
+
+        cacher.set_scissors(0, &[scissor(0), scissor(1)]);
+        cacher.set_scissors(1, &[scissor(2), scissor(3)]);
+        assert_eq!(cacher.dynamic_state.scissors,
+                   Some(vec![scissor(0), scissor(2), scissor(3)]));
+
+        // Setting the same values again through a draw is a no-op.
+        let incoming = DynamicState {
+            scissors: Some(vec![scissor(0), scissor(2), scissor(3)]),
+            ..DynamicState::none()
+        };
+        assert_eq!(cacher.dynamic_state(incoming).scissors, None);
+    }
 }
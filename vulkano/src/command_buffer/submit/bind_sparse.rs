@@ -141,7 +141,8 @@ impl<'a> SubmitBindSparseBuilder<'a> {
         unsafe {
             debug_assert!(queue.family().supports_sparse_binding());
 
-            let vk = queue.device().pointers();
+            let device = queue.device();
+            let vk = device.pointers();
             let queue = queue.internal_object_guard();
 
             // We start by storing all the `VkSparseBufferMemoryBindInfo`s of the whole command
@@ -241,10 +242,14 @@ impl<'a> SubmitBindSparseBuilder<'a> {
             };
 
             // Finally executing the command.
-            check_errors(vk.QueueBindSparse(*queue,
+            let _span = instrument_span!("vkQueueBindSparse",
+                                         queue = ::instrument::queue_name(device, *queue),
+                                         batches = bs_infos.len());
+            let result = vk.QueueBindSparse(*queue,
                                             bs_infos.len() as u32,
                                             bs_infos.as_ptr(),
-                                            self.fence))?;
+                                            self.fence);
+            device.check_lost(check_errors(result))?;
             Ok(())
         }
     }
@@ -21,6 +21,7 @@ pub use self::bind_sparse::SubmitBindSparseImageBindBuilder;
 pub use self::bind_sparse::SubmitBindSparseImageOpaqueBindBuilder;
 pub use self::queue_present::SubmitPresentBuilder;
 pub use self::queue_present::SubmitPresentError;
+pub use self::queue_submit::SubmitBatchBuilder;
 pub use self::queue_submit::SubmitCommandBufferBuilder;
 pub use self::queue_submit::SubmitCommandBufferError;
 pub use self::semaphores_wait::SubmitSemaphoresWaitBuilder;
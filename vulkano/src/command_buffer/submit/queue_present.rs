@@ -160,7 +160,8 @@ impl<'a> SubmitPresentBuilder<'a> {
 
             let mut results = vec![mem::uninitialized(); self.swapchains.len()]; // TODO: alloca
 
-            let vk = queue.device().pointers();
+            let device = queue.device();
+            let vk = device.pointers();
             let queue = queue.internal_object_guard();
 
             let infos = vk::PresentInfoKHR {
@@ -177,7 +178,10 @@ impl<'a> SubmitPresentBuilder<'a> {
                 pResults: results.as_mut_ptr(),
             };
 
-            check_errors(vk.QueuePresentKHR(*queue, &infos))?;
+            let _span = instrument_span!("vkQueuePresentKHR",
+                                         queue = ::instrument::queue_name(device, *queue),
+                                         swapchains = self.swapchains.len());
+            device.check_lost(check_errors(vk.QueuePresentKHR(*queue, &infos)))?;
 
             // TODO: AMD driver initially didn't write the results ; check that it's been fixed
             //for result in results {
@@ -18,6 +18,7 @@ use device::Queue;
 use sync::Fence;
 use sync::PipelineStages;
 use sync::Semaphore;
+use sync::TimelineSemaphore;
 
 use Error;
 use OomError;
@@ -32,9 +33,17 @@ use vk;
 pub struct SubmitCommandBufferBuilder<'a> {
     wait_semaphores: SmallVec<[vk::Semaphore; 16]>,
     destination_stages: SmallVec<[vk::PipelineStageFlags; 8]>,
+    // Same as `destination_stages`, but in the format of `VK_KHR_synchronization2`.
+    destination_stages2: SmallVec<[vk::PipelineStageFlags2KHR; 8]>,
     signal_semaphores: SmallVec<[vk::Semaphore; 16]>,
     command_buffers: SmallVec<[vk::CommandBuffer; 4]>,
     fence: vk::Fence,
+    // Values of the timeline semaphores, in parallel with `wait_semaphores` and
+    // `signal_semaphores`. The values of binary semaphores are ignored.
+    wait_values: SmallVec<[u64; 16]>,
+    signal_values: SmallVec<[u64; 16]>,
+    // True if one of the semaphores is a timeline semaphore.
+    has_timeline_semaphores: bool,
     marker: PhantomData<&'a ()>,
 }
 
@@ -45,9 +54,13 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
         SubmitCommandBufferBuilder {
             wait_semaphores: SmallVec::new(),
             destination_stages: SmallVec::new(),
+            destination_stages2: SmallVec::new(),
             signal_semaphores: SmallVec::new(),
             command_buffers: SmallVec::new(),
             fence: 0,
+            wait_values: SmallVec::new(),
+            signal_values: SmallVec::new(),
+            has_timeline_semaphores: false,
             marker: PhantomData,
         }
     }
@@ -145,6 +158,38 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
         // TODO: debug assert that the device supports the stages
         self.wait_semaphores.push(semaphore.internal_object());
         self.destination_stages.push(stages.into_vulkan_bits());
+        self.destination_stages2.push(stages.into_vulkan_bits2());
+        self.wait_values.push(0);
+    }
+
+    /// Adds a timeline semaphore to be waited upon before the command buffers are executed. The
+    /// wait ends when the counter of the semaphore is greater than or equal to `value`.
+    ///
+    /// Only the given `stages` of the command buffers added afterwards will wait upon
+    /// the semaphore. Other stages not included in `stages` can execute before waiting.
+    ///
+    /// # Safety
+    ///
+    /// - The stages must be supported by the device.
+    ///
+    /// - If you submit this builder, the semaphore must be kept alive until you are guaranteed
+    ///   that the GPU has at least started executing the command buffers.
+    ///
+    /// - If you submit this builder, the counter must eventually reach `value`, either because of
+    ///   a signal from the CPU or because of another submission. In other words, you must not
+    ///   block the queue forever.
+    ///
+    /// - The fence, command buffers, and semaphores must all belong to the same device.
+    ///
+    #[inline]
+    pub unsafe fn add_wait_timeline_semaphore(&mut self, semaphore: &'a TimelineSemaphore,
+                                              value: u64, stages: PipelineStages) {
+        debug_assert!(stages.into_vulkan_bits() != 0);
+        self.wait_semaphores.push(semaphore.internal_object());
+        self.destination_stages.push(stages.into_vulkan_bits());
+        self.destination_stages2.push(stages.into_vulkan_bits2());
+        self.wait_values.push(value);
+        self.has_timeline_semaphores = true;
     }
 
     /// Adds a command buffer that is executed as part of this command.
@@ -193,51 +238,175 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
     #[inline]
     pub unsafe fn add_signal_semaphore(&mut self, semaphore: &'a Semaphore) {
         self.signal_semaphores.push(semaphore.internal_object());
+        self.signal_values.push(0);
+    }
+
+    /// Adds a timeline semaphore whose counter is set to `value` at the end of the submission.
+    ///
+    /// # Safety
+    ///
+    /// - If you submit this builder, the semaphore must be kept alive until you are guaranteed
+    ///   that the GPU has finished executing this submission.
+    ///
+    /// - `value` must be greater than the value of the counter when queue execution reaches this
+    ///   submission, and than the values of the other signal operations that are pending.
+    ///
+    /// - The fence, command buffers, and semaphores must all belong to the same device.
+    ///
+    #[inline]
+    pub unsafe fn add_signal_timeline_semaphore(&mut self, semaphore: &'a TimelineSemaphore,
+                                                value: u64) {
+        self.signal_semaphores.push(semaphore.internal_object());
+        self.signal_values.push(value);
+        self.has_timeline_semaphores = true;
     }
 
     /// Submits the command buffer to the given queue.
     ///
+    /// Uses `vkQueueSubmit2KHR` if the `synchronization2` feature is enabled on the device, and
+    /// `vkQueueSubmit` otherwise.
+    ///
     /// > **Note**: This is an expensive operation, so you may want to merge as many builders as
     /// > possible together and avoid submitting them one by one.
     ///
     pub fn submit(self, queue: &Queue) -> Result<(), SubmitCommandBufferError> {
         unsafe {
-            let vk = queue.device().pointers();
+            let device = queue.device();
+            let vk = device.pointers();
             let queue = queue.internal_object_guard();
-
-            debug_assert_eq!(self.wait_semaphores.len(), self.destination_stages.len());
-
-            let batch = vk::SubmitInfo {
-                sType: vk::STRUCTURE_TYPE_SUBMIT_INFO,
-                pNext: ptr::null(),
-                waitSemaphoreCount: self.wait_semaphores.len() as u32,
-                pWaitSemaphores: self.wait_semaphores.as_ptr(),
-                pWaitDstStageMask: self.destination_stages.as_ptr(),
-                commandBufferCount: self.command_buffers.len() as u32,
-                pCommandBuffers: self.command_buffers.as_ptr(),
-                signalSemaphoreCount: self.signal_semaphores.len() as u32,
-                pSignalSemaphores: self.signal_semaphores.as_ptr(),
-            };
-
-            check_errors(vk.QueueSubmit(*queue, 1, &batch, self.fence))?;
+            let _span = instrument_span!("vkQueueSubmit",
+                                         queue = ::instrument::queue_name(device, *queue),
+                                         command_buffers = self.command_buffers.len());
+
+            if device.synchronization2_enabled() {
+                let infos2 = self.submit_infos2();
+                let batch = infos2.submit_info();
+                let result = vk.QueueSubmit2KHR(*queue, 1, &batch, self.fence);
+                device.check_lost(check_errors(result))?;
+                return Ok(());
+            }
+
+            let timeline_infos = self.timeline_infos();
+            let batch = self.submit_info(&timeline_infos);
+
+            device.check_lost(check_errors(vk.QueueSubmit(*queue, 1, &batch, self.fence)))?;
             Ok(())
         }
     }
 
+    // Builds the structs that describe this builder with `VK_KHR_synchronization2`.
+    fn submit_infos2(&self) -> SubmitInfos2 {
+        debug_assert_eq!(self.wait_semaphores.len(), self.destination_stages2.len());
+
+        let wait_semaphores = self.wait_semaphores
+            .iter()
+            .zip(self.wait_values.iter())
+            .zip(self.destination_stages2.iter())
+            .map(|((&semaphore, &value), &stages)| {
+                     vk::SemaphoreSubmitInfoKHR {
+                         sType: vk::STRUCTURE_TYPE_SEMAPHORE_SUBMIT_INFO_KHR,
+                         pNext: ptr::null(),
+                         semaphore: semaphore,
+                         value: value,
+                         stageMask: stages,
+                         deviceIndex: 0,
+                     }
+                 })
+            .collect();
+
+        let command_buffers = self.command_buffers
+            .iter()
+            .map(|&command_buffer| {
+                     vk::CommandBufferSubmitInfoKHR {
+                         sType: vk::STRUCTURE_TYPE_COMMAND_BUFFER_SUBMIT_INFO_KHR,
+                         pNext: ptr::null(),
+                         commandBuffer: command_buffer,
+                         deviceMask: 0,
+                     }
+                 })
+            .collect();
+
+        let signal_semaphores = self.signal_semaphores
+            .iter()
+            .zip(self.signal_values.iter())
+            .map(|(&semaphore, &value)| {
+                     vk::SemaphoreSubmitInfoKHR {
+                         sType: vk::STRUCTURE_TYPE_SEMAPHORE_SUBMIT_INFO_KHR,
+                         pNext: ptr::null(),
+                         semaphore: semaphore,
+                         value: value,
+                         stageMask: vk::PIPELINE_STAGE_2_ALL_COMMANDS_BIT_KHR,
+                         deviceIndex: 0,
+                     }
+                 })
+            .collect();
+
+        SubmitInfos2 {
+            wait_semaphores,
+            command_buffers,
+            signal_semaphores,
+        }
+    }
+
+    // Builds the `VkTimelineSemaphoreSubmitInfoKHR` that holds the values of the semaphores.
+    fn timeline_infos(&self) -> vk::TimelineSemaphoreSubmitInfoKHR {
+        debug_assert_eq!(self.wait_semaphores.len(), self.wait_values.len());
+        debug_assert_eq!(self.signal_semaphores.len(), self.signal_values.len());
+
+        vk::TimelineSemaphoreSubmitInfoKHR {
+            sType: vk::STRUCTURE_TYPE_TIMELINE_SEMAPHORE_SUBMIT_INFO_KHR,
+            pNext: ptr::null(),
+            waitSemaphoreValueCount: self.wait_values.len() as u32,
+            pWaitSemaphoreValues: self.wait_values.as_ptr(),
+            signalSemaphoreValueCount: self.signal_values.len() as u32,
+            pSignalSemaphoreValues: self.signal_values.as_ptr(),
+        }
+    }
+
+    // Builds the `VkSubmitInfo` of this builder. `timeline_infos` must have been returned by
+    // `self.timeline_infos()` and must outlive the submission.
+    fn submit_info(&self, timeline_infos: &vk::TimelineSemaphoreSubmitInfoKHR)
+                   -> vk::SubmitInfo {
+        debug_assert_eq!(self.wait_semaphores.len(), self.destination_stages.len());
+
+        vk::SubmitInfo {
+            sType: vk::STRUCTURE_TYPE_SUBMIT_INFO,
+            pNext: if self.has_timeline_semaphores {
+                timeline_infos as *const _ as *const _
+            } else {
+                ptr::null()
+            },
+            waitSemaphoreCount: self.wait_semaphores.len() as u32,
+            pWaitSemaphores: self.wait_semaphores.as_ptr(),
+            pWaitDstStageMask: self.destination_stages.as_ptr(),
+            commandBufferCount: self.command_buffers.len() as u32,
+            pCommandBuffers: self.command_buffers.as_ptr(),
+            signalSemaphoreCount: self.signal_semaphores.len() as u32,
+            pSignalSemaphores: self.signal_semaphores.as_ptr(),
+        }
+    }
+
     /// Merges this builder with another builder.
     ///
+    /// The semaphores and command buffers of `other` are appended to the ones of `self`, which
+    /// means that all the command buffers wait upon all the semaphores. Use a
+    /// `SubmitBatchBuilder` in order to keep them in separate batches.
+    ///
     /// # Panic
     ///
     /// Panics if both builders have a fence already set.
-    // TODO: create multiple batches instead
     pub fn merge(mut self, other: Self) -> Self {
         assert!(self.fence == 0 || other.fence == 0,
                 "Can't merge two queue submits that both have a fence");
 
         self.wait_semaphores.extend(other.wait_semaphores);
         self.destination_stages.extend(other.destination_stages); // TODO: meh? will be solved if we submit multiple batches
+        self.destination_stages2.extend(other.destination_stages2);
         self.signal_semaphores.extend(other.signal_semaphores);
         self.command_buffers.extend(other.command_buffers);
+        self.wait_values.extend(other.wait_values);
+        self.signal_values.extend(other.signal_values);
+        self.has_timeline_semaphores |= other.has_timeline_semaphores;
 
         if self.fence == 0 {
             self.fence = other.fence;
@@ -247,6 +416,159 @@ impl<'a> SubmitCommandBufferBuilder<'a> {
     }
 }
 
+// Storage for the structs of a submission with `VK_KHR_synchronization2`. The values of the
+// timeline semaphores are part of the semaphore infos.
+struct SubmitInfos2 {
+    wait_semaphores: SmallVec<[vk::SemaphoreSubmitInfoKHR; 16]>,
+    command_buffers: SmallVec<[vk::CommandBufferSubmitInfoKHR; 4]>,
+    signal_semaphores: SmallVec<[vk::SemaphoreSubmitInfoKHR; 16]>,
+}
+
+impl SubmitInfos2 {
+    // Builds the `VkSubmitInfo2KHR`, which points to the content of `self`.
+    fn submit_info(&self) -> vk::SubmitInfo2KHR {
+        vk::SubmitInfo2KHR {
+            sType: vk::STRUCTURE_TYPE_SUBMIT_INFO_2_KHR,
+            pNext: ptr::null(),
+            flags: 0,
+            waitSemaphoreInfoCount: self.wait_semaphores.len() as u32,
+            pWaitSemaphoreInfos: self.wait_semaphores.as_ptr(),
+            commandBufferInfoCount: self.command_buffers.len() as u32,
+            pCommandBufferInfos: self.command_buffers.as_ptr(),
+            signalSemaphoreInfoCount: self.signal_semaphores.len() as u32,
+            pSignalSemaphoreInfos: self.signal_semaphores.as_ptr(),
+        }
+    }
+}
+
+/// Prototype for a submission that executes multiple batches of command buffers with a single
+/// call to `vkQueueSubmit`.
+///
+/// Each batch is a `SubmitCommandBufferBuilder` and keeps its own wait and signal semaphores.
+/// Submitting the batches together is cheaper for the CPU than submitting each builder
+/// individually.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::command_buffer::submit::SubmitBatchBuilder;
+/// use vulkano::command_buffer::submit::SubmitCommandBufferBuilder;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+///
+/// let mut batches = SubmitBatchBuilder::new();
+/// batches.add(SubmitCommandBufferBuilder::new());
+/// batches.add(SubmitCommandBufferBuilder::new());
+/// assert_eq!(batches.len(), 2);
+/// batches.submit(&queue).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct SubmitBatchBuilder<'a> {
+    batches: SmallVec<[SubmitCommandBufferBuilder<'a>; 4]>,
+    fence: vk::Fence,
+}
+
+impl<'a> SubmitBatchBuilder<'a> {
+    /// Builds a new empty `SubmitBatchBuilder`.
+    #[inline]
+    pub fn new() -> SubmitBatchBuilder<'a> {
+        SubmitBatchBuilder {
+            batches: SmallVec::new(),
+            fence: 0,
+        }
+    }
+
+    /// Adds a batch to the submission.
+    ///
+    /// Batches start execution in order, but can finish in a different order. The wait
+    /// semaphores of a batch only apply to the command buffers of this batch.
+    ///
+    /// If `builder` has a fence, it becomes the fence of the whole submission and is signaled
+    /// once all the batches have finished executing.
+    ///
+    /// # Panic
+    ///
+    /// Panics if both `builder` and `self` have a fence already set.
+    pub fn add(&mut self, mut builder: SubmitCommandBufferBuilder<'a>) {
+        if builder.fence != 0 {
+            assert!(self.fence == 0, "Can't batch two queue submits that both have a fence");
+            self.fence = builder.fence;
+            builder.fence = 0;
+        }
+
+        self.batches.push(builder);
+    }
+
+    /// Returns the number of batches that have been added.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.batches.len()
+    }
+
+    /// Returns true if no batch has been added.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// Returns true if this builder will signal a fence when submitted.
+    #[inline]
+    pub fn has_fence(&self) -> bool {
+        self.fence != 0
+    }
+
+    /// Adds an operation that signals a fence after all the batches have finished executing.
+    ///
+    /// # Safety
+    ///
+    /// Same as `SubmitCommandBufferBuilder::set_fence_signal`.
+    #[inline]
+    pub unsafe fn set_fence_signal(&mut self, fence: &'a Fence) {
+        self.fence = fence.internal_object();
+    }
+
+    /// Submits all the batches to the given queue with a single call to `vkQueueSubmit`.
+    pub fn submit(self, queue: &Queue) -> Result<(), SubmitCommandBufferError> {
+        unsafe {
+            let device = queue.device();
+            let vk = device.pointers();
+            let queue = queue.internal_object_guard();
+            let _span = instrument_span!("vkQueueSubmit",
+                                         queue = ::instrument::queue_name(device, *queue),
+                                         batches = self.batches.len());
+
+            if device.synchronization2_enabled() {
+                let infos2: SmallVec<[_; 4]> = self.batches
+                    .iter()
+                    .map(|batch| batch.submit_infos2())
+                    .collect();
+                let infos: SmallVec<[_; 4]> = infos2.iter().map(|i| i.submit_info()).collect();
+
+                let result =
+                    vk.QueueSubmit2KHR(*queue, infos.len() as u32, infos.as_ptr(), self.fence);
+                device.check_lost(check_errors(result))?;
+                return Ok(());
+            }
+
+            // The `VkSubmitInfo`s point to the timeline infos, so we must collect all of them
+            // before building the submit infos.
+            let timeline_infos: SmallVec<[_; 4]> = self.batches
+                .iter()
+                .map(|batch| batch.timeline_infos())
+                .collect();
+
+            let infos: SmallVec<[_; 4]> = self.batches
+                .iter()
+                .zip(timeline_infos.iter())
+                .map(|(batch, timeline_infos)| batch.submit_info(timeline_infos))
+                .collect();
+
+            let result = vk.QueueSubmit(*queue, infos.len() as u32, infos.as_ptr(), self.fence);
+            device.check_lost(check_errors(result))?;
+            Ok(())
+        }
+    }
+}
+
 /// Error that can happen when submitting the prototype.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 #[repr(u32)]
@@ -340,6 +662,47 @@ mod tests {
         }
     }
 
+    #[test]
+    fn batch_signal_fence() {
+        unsafe {
+            let (device, queue) = gfx_dev_and_queue!();
+
+            let fence = Fence::alloc(device.clone()).unwrap();
+
+            let mut first = SubmitCommandBufferBuilder::new();
+            first.set_fence_signal(&fence);
+
+            let mut batches = SubmitBatchBuilder::new();
+            batches.add(first);
+            batches.add(SubmitCommandBufferBuilder::new());
+            assert!(batches.has_fence());
+            assert_eq!(batches.len(), 2);
+
+            batches.submit(&queue).unwrap();
+            fence.wait(Some(Duration::from_secs(5))).unwrap();
+        }
+    }
+
+    #[test]
+    fn batch_both_have_fences() {
+        unsafe {
+            let (device, _) = gfx_dev_and_queue!();
+
+            let fence1 = Fence::alloc(device.clone()).unwrap();
+            let fence2 = Fence::alloc(device.clone()).unwrap();
+
+            let mut batches = SubmitBatchBuilder::new();
+            batches.set_fence_signal(&fence1);
+            let mut builder = SubmitCommandBufferBuilder::new();
+            builder.set_fence_signal(&fence2);
+
+            assert_should_panic!("Can't batch two queue submits that both have a fence", {
+                let mut batches = batches;
+                batches.add(builder);
+            });
+        }
+    }
+
     #[test]
     fn merge_both_have_fences() {
         unsafe {
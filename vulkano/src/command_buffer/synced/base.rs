@@ -13,6 +13,7 @@ use std::collections::hash_map::Entry;
 use std::error;
 use std::fmt;
 use std::hash::{Hash, Hasher};
+use std::mem;
 use std::sync::Arc;
 use std::sync::Mutex;
 
@@ -336,6 +337,11 @@ struct ResourceState {
     // Access for the command that last used this resource.
     access: AccessFlagBits,
 
+    // Union of the stages of all the commands that used this resource.
+    all_stages: PipelineStages,
+    // Union of the accesses of all the commands that used this resource.
+    all_access: AccessFlagBits,
+
     // True if the resource was used in exclusive mode at any point during the building of the
     // command buffer. Also true if an image layout transition or queue transfer has been performed.
     exclusive_any: bool,
@@ -359,6 +365,8 @@ impl ResourceState {
         ResourceFinalState {
             final_stages: self.stages,
             final_access: self.access,
+            all_stages: self.all_stages,
+            all_access: self.all_access,
             exclusive: self.exclusive_any,
             initial_layout: self.initial_layout,
             final_layout: self.current_layout,
@@ -581,6 +589,14 @@ impl<P> SyncCommandBufferBuilder<P> {
                                 let img = commands_lock.commands[latest_command_id]
                                     .image(resource_index);
 
+                                // A command that starts from `Undefined` doesn't care about the
+                                // content of the image, so the image is left in its layout.
+                                let new_layout = if start_layout == ImageLayout::Undefined {
+                                    entry.current_layout
+                                } else {
+                                    start_layout
+                                };
+
                                 let b = &mut self.pending_barrier;
                                 b.add_image_memory_barrier(img,
                                                            0 .. img.mipmap_levels(),
@@ -592,7 +608,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                                                            true,
                                                            None,
                                                            entry.current_layout,
-                                                           start_layout);
+                                                           new_layout);
                             },
                         };
                     }
@@ -600,6 +616,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                     // Update state.
                     entry.stages = stages;
                     entry.access = access;
+                    entry.all_stages = entry.all_stages | stages;
+                    entry.all_access = entry.all_access | access;
                     entry.exclusive_any = true;
                     entry.exclusive = exclusive;
                     if exclusive || end_layout != ImageLayout::Undefined {
@@ -615,6 +633,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                     //       this would simplify the job of the driver, but is it worth it?
                     entry.stages = entry.stages | stages;
                     entry.access = entry.access | access;
+                    entry.all_stages = entry.all_stages | stages;
+                    entry.all_access = entry.all_access | access;
                 }
             },
 
@@ -670,6 +690,8 @@ impl<P> SyncCommandBufferBuilder<P> {
                 entry.insert(ResourceState {
                     stages: stages,
                     access: access,
+                    all_stages: stages,
+                    all_access: access,
                     exclusive_any: actually_exclusive,
                     exclusive: actually_exclusive,
                     initial_layout: actual_start_layout,
@@ -750,11 +772,19 @@ impl<P> SyncCommandBufferBuilder<P> {
         };
 
         // Build the final resources states.
+        let mut buffers = Vec::new();
+        let mut images = Vec::new();
         let final_resources_states: FnvHashMap<_, _> = {
             self.resources
                 .into_iter()
                 .map(|(resource, state)| {
-                         (resource.into_cb_key(final_commands.clone()), state.finalize())
+                         let state = state.finalize();
+                         let list = match resource.resource_ty {
+                             KeyTy::Buffer => &mut buffers,
+                             KeyTy::Image => &mut images,
+                         };
+                         list.push((resource.command_id, resource.resource_index, state.clone()));
+                         (resource.into_cb_key(final_commands.clone()), state)
                      })
                 .collect()
         };
@@ -762,6 +792,8 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(SyncCommandBuffer {
                inner: self.inner.build()?,
                resources: final_resources_states,
+               buffers: buffers,
+               images: images,
                commands: final_commands,
            })
     }
@@ -783,6 +815,11 @@ pub struct SyncCommandBuffer<P> {
     // State of all the resources used by this command buffer.
     resources: FnvHashMap<CbKey<'static>, ResourceFinalState>,
 
+    // The buffers and the images of `resources`, as the index of the command that holds them,
+    // their index within the command and their state. Used to enumerate them in a fixed order.
+    buffers: Vec<(usize, usize, ResourceFinalState)>,
+    images: Vec<(usize, usize, ResourceFinalState)>,
+
     // List of commands used by the command buffer. Used to hold the various resources that are
     // being used. Each element of `resources` has a copy of this `Arc`, but we need to keep one
     // here in case `resources` is empty.
@@ -797,6 +834,11 @@ struct ResourceFinalState {
     // Access for the last command that uses the resource.
     final_access: AccessFlagBits,
 
+    // Union of the stages of all the commands that use the resource.
+    all_stages: PipelineStages,
+    // Union of the accesses of all the commands that use the resource.
+    all_access: AccessFlagBits,
+
     // True if the resource is used in exclusive mode.
     exclusive: bool,
 
@@ -835,6 +877,32 @@ pub trait FinalCommand {
     fn image_name(&self, _num: usize) -> Cow<'static, str> {
         panic!()
     }
+
+    // Called when the command buffer is submitted. Commands that execute secondary command
+    // buffers must lock them here, so that their own resources and submission state are
+    // tracked as well.
+    fn lock_submit(&self, _future: &GpuFuture, _queue: &Queue)
+                   -> Result<(), CommandBufferExecError> {
+        Ok(())
+    }
+
+    // Unlocks what `lock_submit` has locked. Only called after a successful `lock_submit`.
+    unsafe fn unlock(&self) {
+    }
+
+    // Checks whether the secondary command buffers executed by the command have access to a
+    // buffer, in which case they have locked it in `lock_submit`.
+    fn check_buffer_access(&self, _buffer: &BufferAccess, _exclusive: bool, _queue: &Queue)
+                           -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    // Checks whether the secondary command buffers executed by the command have access to an
+    // image, in which case they have locked it in `lock_submit`.
+    fn check_image_access(&self, _image: &ImageAccess, _exclusive: bool, _queue: &Queue)
+                          -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
 }
 
 impl FinalCommand for &'static str {
@@ -1031,6 +1099,13 @@ impl<P> AsRef<UnsafeCommandBuffer<P>> for SyncCommandBuffer<P> {
 }
 
 impl<P> SyncCommandBuffer<P> {
+    // Destroys the tracking of the resources, which releases them, and returns the inner command
+    // buffer. The command buffer must not be in use by the GPU.
+    #[inline]
+    pub(crate) fn into_unsafe(self) -> UnsafeCommandBuffer<P> {
+        self.inner
+    }
+
     /// Tries to lock the resources used by the command buffer.
     ///
     /// > **Note**: You should call this in the implementation of the `CommandBuffer` trait.
@@ -1039,6 +1114,20 @@ impl<P> SyncCommandBuffer<P> {
 
         let commands_lock = self.commands.lock().unwrap();
 
+        // Lock the secondary command buffers executed by the commands first, and revert if one of
+        // them can't be submitted. They lock their own resources, which are also part of
+        // `self.resources`.
+        for (num, cmd) in commands_lock.iter().enumerate() {
+            if let Err(err) = cmd.lock_submit(future, queue) {
+                for cmd in commands_lock.iter().take(num) {
+                    unsafe {
+                        cmd.unlock();
+                    }
+                }
+                return Err(err);
+            }
+        }
+
         // Number of resources in `self.resources` that have been successfully locked.
         let mut locked_resources = 0;
         // Final return value of this function.
@@ -1064,7 +1153,16 @@ impl<P> SyncCommandBuffer<P> {
                     let cmd = &commands_lock[command_id];
                     let buf = cmd.buffer(resource_index);
 
-                    let prev_err = match future.check_buffer_access(&buf, entry.exclusive, queue) {
+                    // The resources that the future or a secondary command buffer have already
+                    // locked only need their lock count to be increased.
+                    let prev_err = match future.check_buffer_access(&buf, entry.exclusive, queue)
+                        .or_else(|err| {
+                            commands_lock
+                                .iter()
+                                .map(|c| c.check_buffer_access(buf, entry.exclusive, queue))
+                                .find(|r| r.is_ok())
+                                .unwrap_or(Err(err))
+                        }) {
                         Ok(_) => {
                             unsafe {
                                 buf.increase_gpu_lock();
@@ -1098,6 +1196,13 @@ impl<P> SyncCommandBuffer<P> {
 
                     let prev_err = match future.check_image_access(img, entry.initial_layout,
                                                                    entry.exclusive, queue)
+                        .or_else(|err| {
+                            commands_lock
+                                .iter()
+                                .map(|c| c.check_image_access(img, entry.exclusive, queue))
+                                .find(|r| r.is_ok())
+                                .unwrap_or(Err(err))
+                        })
                     {
                         Ok(_) => {
                             unsafe { img.increase_gpu_lock(); }
@@ -1126,8 +1231,15 @@ impl<P> SyncCommandBuffer<P> {
             }
         }
 
-        // If we are going to return an error, we have to unlock all the resources we locked above.
+        // If we are going to return an error, we have to unlock all the resources and the
+        // secondary command buffers we locked above.
         if let Err(_) = ret_value {
+            for cmd in commands_lock.iter() {
+                unsafe {
+                    cmd.unlock();
+                }
+            }
+
             for key in self.resources.keys().take(locked_resources) {
                 let (command_id, resource_ty, resource_index) = match *key {
                     CbKey::Command {
@@ -1177,6 +1289,10 @@ impl<P> SyncCommandBuffer<P> {
     pub unsafe fn unlock(&self) {
         let commands_lock = self.commands.lock().unwrap();
 
+        for cmd in commands_lock.iter() {
+            cmd.unlock();
+        }
+
         for (key, val) in self.resources.iter() {
             let (command_id, resource_ty, resource_index) = match *key {
                 CbKey::Command {
@@ -1256,6 +1372,58 @@ impl<P> SyncCommandBuffer<P> {
 
         Err(AccessCheckError::Unknown)
     }
+
+    /// Returns the number of buffers used by this command buffer.
+    ///
+    /// > **Note**: Suitable when implementing the `CommandBuffer` trait.
+    #[inline]
+    pub fn num_buffers(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Returns the `index`th buffer used by this command buffer, the union of the stages and of
+    /// the accesses of the commands that use it, and true if it is used in exclusive mode.
+    ///
+    /// > **Note**: Suitable when implementing the `CommandBuffer` trait.
+    pub fn buffer<'a>(&'a self, index: usize)
+                      -> Option<(&'a BufferAccess, PipelineStages, AccessFlagBits, bool)> {
+        let &(command_id, resource_index, ref state) = self.buffers.get(index)?;
+        let commands_lock = self.commands.lock().unwrap();
+        let buffer = commands_lock[command_id].buffer(resource_index);
+        // The final commands are never modified after the command buffer has been built, and they
+        // are kept alive by `self`, so the buffer can outlive the lock.
+        let buffer: &'a BufferAccess = unsafe { mem::transmute(buffer) };
+        Some((buffer, state.all_stages, state.all_access, state.exclusive))
+    }
+
+    /// Returns the number of images used by this command buffer.
+    ///
+    /// > **Note**: Suitable when implementing the `CommandBuffer` trait.
+    #[inline]
+    pub fn num_images(&self) -> usize {
+        self.images.len()
+    }
+
+    /// Returns the `index`th image used by this command buffer, the union of the stages and of
+    /// the accesses of the commands that use it, true if it is used in exclusive mode, and the
+    /// layouts it must be in at the start of the command buffer and it is in at the end.
+    ///
+    /// > **Note**: Suitable when implementing the `CommandBuffer` trait.
+    pub fn image<'a>(&'a self, index: usize)
+                     -> Option<(&'a ImageAccess, PipelineStages, AccessFlagBits, bool, ImageLayout,
+                                ImageLayout)> {
+        let &(command_id, resource_index, ref state) = self.images.get(index)?;
+        let commands_lock = self.commands.lock().unwrap();
+        let image = commands_lock[command_id].image(resource_index);
+        // Same as in `buffer`.
+        let image: &'a ImageAccess = unsafe { mem::transmute(image) };
+        Some((image,
+              state.all_stages,
+              state.all_access,
+              state.exclusive,
+              state.initial_layout,
+              state.final_layout))
+    }
 }
 
 unsafe impl<P> DeviceOwned for SyncCommandBuffer<P> {
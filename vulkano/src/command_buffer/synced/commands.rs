@@ -8,14 +8,26 @@
 // according to those terms.
 
 use smallvec::SmallVec;
-use std::any::Any;
 use std::borrow::Cow;
+use std::ffi::CString;
 use std::mem;
 use std::ptr;
 use std::sync::Arc;
 
+use acceleration_structure::AccelerationStructure;
+use acceleration_structure::AccelerationStructureGeometry;
+use acceleration_structure::BuildAccelerationStructureFlags;
+use acceleration_structure::CopyMode;
 use buffer::BufferAccess;
+use buffer::TypedBufferAccess;
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
 use command_buffer::CommandBuffer;
+use command_buffer::CommandBufferExecError;
+use command_buffer::MultiDrawIndexedInfo;
+use command_buffer::MultiDrawInfo;
+use command_buffer::PipelineBarrier;
+use command_buffer::RawCommandsResources;
 use command_buffer::synced::base::Command;
 use command_buffer::synced::base::FinalCommand;
 use command_buffer::synced::base::KeyTy;
@@ -28,10 +40,13 @@ use command_buffer::sys::UnsafeCommandBufferBuilderColorImageClear;
 use command_buffer::sys::UnsafeCommandBufferBuilderExecuteCommands;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageCopy;
 use command_buffer::sys::UnsafeCommandBufferBuilderImageBlit;
+use command_buffer::sys::UnsafeCommandBufferBuilderPipelineBarrier;
+use command_buffer::sys::UnsafeCommandBufferBuilderTransformFeedbackBuffers;
 use descriptor::descriptor::DescriptorDescTy;
 use descriptor::descriptor::ShaderStages;
 use descriptor::descriptor_set::DescriptorSet;
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
+use device::Queue;
 use format::ClearValue;
 use framebuffer::FramebufferAbstract;
 use framebuffer::SubpassContents;
@@ -39,15 +54,242 @@ use image::ImageAccess;
 use image::ImageLayout;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
+use pipeline::ShaderBindingTableRegions;
+use pipeline::depth_stencil::Compare;
+use pipeline::depth_stencil::StencilFaces;
+use pipeline::depth_stencil::StencilOp;
 use pipeline::input_assembly::IndexType;
+use pipeline::input_assembly::PrimitiveTopology;
+use pipeline::raster::CullMode;
+use pipeline::raster::FrontFace;
 use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
+use query::QueryResultElement;
+use query::QueryResultFlags;
+use query::UnsafeQueryPool;
 use sampler::Filter;
+use sync::AccessCheckError;
 use sync::AccessFlagBits;
 use sync::Event;
+use sync::GpuFuture;
 use sync::PipelineStages;
+use vk;
 
 impl<P> SyncCommandBufferBuilder<P> {
+    /// Calls `vkCmdBeginConditionalRenderingEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_conditional_rendering<B>(&mut self, buffer: B, inverted: bool)
+                                                 -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B> {
+            buffer: B,
+            inverted: bool,
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdBeginConditionalRenderingEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.begin_conditional_rendering(&self.buffer, self.inverted);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBeginConditionalRenderingEXT"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "predicate".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "predicate".into()
+            }
+        }
+
+        self.append_command(Cmd { buffer, inverted });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               false,
+                               PipelineStages {
+                                   conditional_rendering: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   conditional_rendering_read: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdBeginDebugUtilsLabelEXT` on the builder.
+    #[inline]
+    pub unsafe fn begin_debug_utils_label(&mut self, name: CString, color: [f32; 4]) {
+        struct Cmd {
+            name: CString,
+            color: [f32; 4],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdBeginDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.begin_debug_utils_label(&self.name, self.color);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdBeginDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd { name, color });
+    }
+
+    /// Calls `vkCmdBeginQuery` on the builder.
+    #[inline]
+    pub unsafe fn begin_query(&mut self, query_pool: Arc<UnsafeQueryPool>, query: u32,
+                              precise: bool) {
+        struct Cmd {
+            query_pool: Arc<UnsafeQueryPool>,
+            query: u32,
+            precise: bool,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdBeginQuery"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.begin_query(self.query_pool.query(self.query).unwrap(), self.precise);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Arc<UnsafeQueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBeginQuery"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+                                query_pool,
+                                query,
+                                precise,
+                            });
+    }
+
+    /// Calls `vkCmdBeginTransformFeedbackEXT` on the builder.
+    ///
+    /// The counters are read by this command only if `resume` is true. They are then written by
+    /// the matching `end_transform_feedback`, which must be passed the same counter buffers. The
+    /// accesses of both commands are registered here.
+    #[inline]
+    pub unsafe fn begin_transform_feedback(&mut self,
+                                           counter_buffers: Vec<Box<BufferAccess + Send + Sync>>,
+                                           resume: bool)
+                                           -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            counter_buffers: Vec<Box<BufferAccess + Send + Sync>>,
+            resume: bool,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdBeginTransformFeedbackEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let mut counters = UnsafeCommandBufferBuilderTransformFeedbackBuffers::new();
+                if self.resume {
+                    for buffer in self.counter_buffers.iter() {
+                        counters.add(buffer);
+                    }
+                }
+                out.begin_transform_feedback(&counters);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Vec<Box<BufferAccess + Send + Sync>>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBeginTransformFeedbackEXT"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        &self.0[num]
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("Counter buffer #{}", num).into()
+                    }
+                }
+                Box::new(Fin(self.counter_buffers))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                &self.counter_buffers[num]
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                format!("Counter buffer #{}", num).into()
+            }
+        }
+
+        let num_buffers = counter_buffers.len();
+
+        self.append_command(Cmd {
+                                counter_buffers,
+                                resume,
+                            });
+
+        for n in 0 .. num_buffers {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   n,
+                                   true,
+                                   PipelineStages {
+                                       transform_feedback: true,
+                                       ..PipelineStages::none()
+                                   },
+                                   AccessFlagBits {
+                                       transform_feedback_counter_read: resume,
+                                       transform_feedback_counter_write: true,
+                                       ..AccessFlagBits::none()
+                                   },
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        Ok(())
+    }
+
     /// Calls `vkBeginRenderPass` on the builder.
     // TODO: it shouldn't be possible to get an error if the framebuffer checked conflicts already
     // TODO: after begin_render_pass has been called, flushing should be forbidden and an error
@@ -58,11 +300,39 @@ impl<P> SyncCommandBufferBuilder<P> {
                                           -> Result<(), SyncCommandBufferBuilderError>
         where F: FramebufferAbstract + Send + Sync + 'static,
               I: Iterator<Item = ClearValue> + Send + Sync + 'static
+    {
+        self.begin_render_pass_impl(framebuffer, subpass_contents, clear_values, None)
+    }
+
+    /// Calls `vkCmdBeginRenderPass` on the builder, restricted to the physical devices of
+    /// `device_mask`.
+    #[inline]
+    pub unsafe fn begin_render_pass_device_group<F, I>(&mut self, framebuffer: F,
+                                                       subpass_contents: SubpassContents,
+                                                       clear_values: I, device_mask: u32,
+                                                       render_areas: Vec<Scissor>)
+                                                       -> Result<(), SyncCommandBufferBuilderError>
+        where F: FramebufferAbstract + Send + Sync + 'static,
+              I: Iterator<Item = ClearValue> + Send + Sync + 'static
+    {
+        self.begin_render_pass_impl(framebuffer,
+                                    subpass_contents,
+                                    clear_values,
+                                    Some((device_mask, render_areas)))
+    }
+
+    unsafe fn begin_render_pass_impl<F, I>(&mut self, framebuffer: F,
+                                           subpass_contents: SubpassContents, clear_values: I,
+                                           device_group: Option<(u32, Vec<Scissor>)>)
+                                           -> Result<(), SyncCommandBufferBuilderError>
+        where F: FramebufferAbstract + Send + Sync + 'static,
+              I: Iterator<Item = ClearValue> + Send + Sync + 'static
     {
         struct Cmd<F, I> {
             framebuffer: F,
             subpass_contents: SubpassContents,
             clear_values: Option<I>,
+            device_group: Option<(u32, Vec<Scissor>)>,
         }
 
         impl<P, F, I> Command<P> for Cmd<F, I>
@@ -74,9 +344,21 @@ impl<P> SyncCommandBufferBuilder<P> {
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.begin_render_pass(&self.framebuffer,
-                                      self.subpass_contents,
-                                      self.clear_values.take().unwrap());
+                let clear_values = self.clear_values.take().unwrap();
+                match self.device_group {
+                    Some((device_mask, ref render_areas)) => {
+                        out.begin_render_pass_device_group(&self.framebuffer,
+                                                           self.subpass_contents,
+                                                           clear_values,
+                                                           device_mask,
+                                                           render_areas);
+                    },
+                    None => {
+                        out.begin_render_pass(&self.framebuffer,
+                                              self.subpass_contents,
+                                              clear_values);
+                    },
+                }
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
@@ -114,6 +396,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                                 framebuffer,
                                 subpass_contents,
                                 clear_values: Some(clear_values),
+                                device_group,
                             });
 
         for (atch, desc) in atch_desc.into_iter().enumerate() {
@@ -279,6 +562,116 @@ impl<P> SyncCommandBufferBuilder<P> {
         self.append_command(Cmd { pipeline });
     }
 
+    /// Calls `vkCmdBindTransformFeedbackBuffersEXT` on the builder.
+    #[inline]
+    pub unsafe fn bind_transform_feedback_buffers(&mut self, first_binding: u32,
+                                                  buffers: Vec<Box<BufferAccess + Send + Sync>>)
+                                                  -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            first_binding: u32,
+            buffers: Vec<Box<BufferAccess + Send + Sync>>,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdBindTransformFeedbackBuffersEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let mut params = UnsafeCommandBufferBuilderTransformFeedbackBuffers::new();
+                for buffer in self.buffers.iter() {
+                    params.add(buffer);
+                }
+                out.bind_transform_feedback_buffers(self.first_binding, &params);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Vec<Box<BufferAccess + Send + Sync>>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBindTransformFeedbackBuffersEXT"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        &self.0[num]
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("Buffer #{}", num).into()
+                    }
+                }
+                Box::new(Fin(self.buffers))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                &self.buffers[num]
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                format!("Buffer #{}", num).into()
+            }
+        }
+
+        let num_buffers = buffers.len();
+
+        self.append_command(Cmd {
+                                first_binding,
+                                buffers,
+                            });
+
+        for n in 0 .. num_buffers {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   n,
+                                   true,
+                                   PipelineStages {
+                                       transform_feedback: true,
+                                       ..PipelineStages::none()
+                                   },
+                                   AccessFlagBits {
+                                       transform_feedback_write: true,
+                                       ..AccessFlagBits::none()
+                                   },
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdBindPipeline` on the builder with a ray tracing pipeline.
+    #[inline]
+    pub unsafe fn bind_pipeline_ray_tracing<Rp>(&mut self, pipeline: Rp)
+        where Rp: RayTracingPipelineAbstract + Send + Sync + 'static
+    {
+        struct Cmd<Rp> {
+            pipeline: Rp,
+        }
+
+        impl<P, Rp> Command<P> for Cmd<Rp>
+            where Rp: RayTracingPipelineAbstract + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdBindPipeline"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.bind_pipeline_ray_tracing(&self.pipeline);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<Rp>(Rp);
+                impl<Rp> FinalCommand for Fin<Rp>
+                    where Rp: Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBindPipeline"
+                    }
+                }
+                Box::new(Fin(self.pipeline))
+            }
+        }
+
+        self.append_command(Cmd { pipeline });
+    }
+
     /// Starts the process of binding descriptor sets. Returns an intermediate struct which can be
     /// used to add the sets.
     #[inline]
@@ -562,19 +955,211 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
-    /// Calls `vkCmdClearColorImage` on the builder.
+    /// Calls `vkCmdBuildAccelerationStructuresKHR` on the builder.
     ///
-    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
-    /// usage of the command anyway.
-    pub unsafe fn clear_color_image<I, R>(&mut self, image: I, layout: ImageLayout,
-                                          color: ClearValue, regions: R)
-                                          -> Result<(), SyncCommandBufferBuilderError>
-        where I: ImageAccess + Send + Sync + 'static,
-              R: Iterator<Item = UnsafeCommandBufferBuilderColorImageClear> + Send + Sync + 'static
-    {
-        struct Cmd<I, R> {
-            image: Option<I>,
-            layout: ImageLayout,
+    /// The device address of `scratch` must be aligned to
+    /// `min_acceleration_structure_scratch_offset_alignment`.
+    ///
+    /// The bottom-level acceleration structures of the instances are kept alive by `destination`
+    /// until it is built again.
+    pub unsafe fn build_acceleration_structure(&mut self,
+                                               source: Option<Arc<AccelerationStructure>>,
+                                               destination: Arc<AccelerationStructure>,
+                                               flags: BuildAccelerationStructureFlags,
+                                               geometries: Vec<AccelerationStructureGeometry>,
+                                               scratch: Arc<BufferAccess + Send + Sync>)
+                                               -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            source: Option<Arc<AccelerationStructure>>,
+            destination: Arc<AccelerationStructure>,
+            flags: BuildAccelerationStructureFlags,
+            geometries: Vec<AccelerationStructureGeometry>,
+            // All the buffers accessed by the command, with their name.
+            buffers: Vec<(Arc<BufferAccess + Send + Sync>, Cow<'static, str>)>,
+            // The instances of the previous build of the destination, which may still be in use
+            // until this build happens.
+            previous_instances: Vec<Arc<AccelerationStructure>>,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdBuildAccelerationStructuresKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                // The scratch buffer is always the last one.
+                let scratch_address = self.buffers
+                    .last()
+                    .unwrap()
+                    .0
+                    .device_address()
+                    .unwrap();
+                out.build_acceleration_structure(self.source.as_ref().map(|s| &**s),
+                                                 &self.destination,
+                                                 self.flags,
+                                                 &self.geometries,
+                                                 scratch_address);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Option<Arc<AccelerationStructure>>,
+                           Arc<AccelerationStructure>,
+                           Vec<(Arc<BufferAccess + Send + Sync>, Cow<'static, str>)>,
+                           Vec<Arc<AccelerationStructure>>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdBuildAccelerationStructuresKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        &*self.2[num].0
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        self.2[num].1.clone()
+                    }
+                }
+                Box::new(Fin(self.source, self.destination, self.buffers, self.previous_instances))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                &*self.buffers[num].0
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                self.buffers[num].1.clone()
+            }
+        }
+
+        let stages = PipelineStages {
+            acceleration_structure_build: true,
+            ..PipelineStages::none()
+        };
+
+        // Each buffer is accompanied by whether it's written and by how it is accessed.
+        let mut buffers: Vec<(Arc<BufferAccess + Send + Sync>, Cow<'static, str>)> = Vec::new();
+        let mut accesses = Vec::new();
+
+        buffers.push((destination.buffer().clone(), "destination".into()));
+        accesses.push((true,
+                       AccessFlagBits {
+                           acceleration_structure_write: true,
+                           ..AccessFlagBits::none()
+                       }));
+
+        // When updating in place, the source is the same as the destination and must not be
+        // tracked twice.
+        if let Some(ref source) = source {
+            if !Arc::ptr_eq(source, &destination) {
+                buffers.push((source.buffer().clone(), "source".into()));
+                accesses.push((false,
+                               AccessFlagBits {
+                                   acceleration_structure_read: true,
+                                   ..AccessFlagBits::none()
+                               }));
+            }
+        }
+
+        let mut referenced: Vec<Arc<AccelerationStructure>> = Vec::new();
+        for (geometry_num, geometry) in geometries.iter().enumerate() {
+            for buffer in geometry.buffers() {
+                buffers.push((buffer.clone(), format!("geometry {}", geometry_num).into()));
+                accesses.push((false,
+                               AccessFlagBits {
+                                   shader_read: true,
+                                   ..AccessFlagBits::none()
+                               }));
+            }
+
+            if let AccelerationStructureGeometry::Instances { ref instances, .. } = *geometry {
+                for instance in instances.acceleration_structures() {
+                    // The same acceleration structure can be used by multiple instances, but
+                    // must only be tracked once.
+                    if referenced.iter().any(|i| Arc::ptr_eq(i, instance)) {
+                        continue;
+                    }
+
+                    buffers.push((instance.buffer().clone(),
+                                  format!("instance {}", referenced.len()).into()));
+                    accesses.push((false,
+                                   AccessFlagBits {
+                                       acceleration_structure_read: true,
+                                       ..AccessFlagBits::none()
+                                   }));
+                    referenced.push(instance.clone());
+                }
+            }
+        }
+
+        buffers.push((scratch, "scratch buffer".into()));
+        accesses.push((true,
+                       AccessFlagBits {
+                           acceleration_structure_read: true,
+                           acceleration_structure_write: true,
+                           ..AccessFlagBits::none()
+                       }));
+
+        let previous_instances = destination.set_build_state(flags.allow_update, referenced);
+
+        self.append_command(Cmd {
+                                source,
+                                destination,
+                                flags,
+                                geometries,
+                                buffers,
+                                previous_instances,
+                            });
+
+        for (n, (write, access)) in accesses.into_iter().enumerate() {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   n,
+                                   write,
+                                   stages,
+                                   access,
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdClearAttachments` on the builder.
+    #[inline]
+    pub unsafe fn clear_attachments(&mut self, attachments: Vec<ClearAttachment>,
+                                    rects: Vec<ClearRect>) {
+        struct Cmd {
+            attachments: Vec<ClearAttachment>,
+            rects: Vec<ClearRect>,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdClearAttachments"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.clear_attachments(self.attachments.iter().cloned(), self.rects.iter().cloned());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdClearAttachments")
+            }
+        }
+
+        self.append_command(Cmd { attachments, rects });
+    }
+
+    /// Calls `vkCmdClearColorImage` on the builder.
+    ///
+    /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    pub unsafe fn clear_color_image<I, R>(&mut self, image: I, layout: ImageLayout,
+                                          color: ClearValue, regions: R)
+                                          -> Result<(), SyncCommandBufferBuilderError>
+        where I: ImageAccess + Send + Sync + 'static,
+              R: Iterator<Item = UnsafeCommandBufferBuilderColorImageClear> + Send + Sync + 'static
+    {
+        struct Cmd<I, R> {
+            image: Option<I>,
+            layout: ImageLayout,
             color: ClearValue,
             regions: Option<R>,
         }
@@ -652,6 +1237,108 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
+    /// Calls `vkCmdCopyAccelerationStructureKHR` on the builder.
+    pub unsafe fn copy_acceleration_structure(&mut self, source: Arc<AccelerationStructure>,
+                                              destination: Arc<AccelerationStructure>,
+                                              mode: CopyMode)
+                                              -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            source: Arc<AccelerationStructure>,
+            destination: Arc<AccelerationStructure>,
+            mode: CopyMode,
+            // The instances of the previous build of the destination.
+            previous_instances: Vec<Arc<AccelerationStructure>>,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdCopyAccelerationStructureKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.copy_acceleration_structure(&self.source, &self.destination, self.mode);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Arc<AccelerationStructure>,
+                           Arc<AccelerationStructure>,
+                           Vec<Arc<AccelerationStructure>>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdCopyAccelerationStructureKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        match num {
+                            0 => self.0.buffer(),
+                            1 => self.1.buffer(),
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "source".into(),
+                            1 => "destination".into(),
+                            _ => panic!(),
+                        }
+                    }
+                }
+                Box::new(Fin(self.source, self.destination, self.previous_instances))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                match num {
+                    0 => self.source.buffer(),
+                    1 => self.destination.buffer(),
+                    _ => panic!(),
+                }
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                match num {
+                    0 => "source".into(),
+                    1 => "destination".into(),
+                    _ => panic!(),
+                }
+            }
+        }
+
+        let previous_instances = destination.copy_build_state(&source);
+
+        self.append_command(Cmd {
+                                source,
+                                destination,
+                                mode,
+                                previous_instances,
+                            });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               false,
+                               PipelineStages {
+                                   acceleration_structure_build: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   acceleration_structure_read: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               1,
+                               true,
+                               PipelineStages {
+                                   acceleration_structure_build: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   acceleration_structure_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        Ok(())
+    }
+
     /// Calls `vkCmdCopyBuffer` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
@@ -1013,6 +1700,94 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
+    /// Calls `vkCmdCopyQueryPoolResults` on the builder.
+    #[inline]
+    pub unsafe fn copy_query_pool_results<D, T>(&mut self, query_pool: Arc<UnsafeQueryPool>,
+                                                first_query: u32, count: u32, destination: D,
+                                                stride: usize, flags: QueryResultFlags)
+                                                -> Result<(), SyncCommandBufferBuilderError>
+        where D: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+              T: QueryResultElement
+    {
+        struct Cmd<D> {
+            query_pool: Arc<UnsafeQueryPool>,
+            first_query: u32,
+            count: u32,
+            destination: D,
+            stride: usize,
+            flags: QueryResultFlags,
+        }
+
+        impl<P, D, T> Command<P> for Cmd<D>
+            where D: TypedBufferAccess<Content = [T]> + Send + Sync + 'static,
+                  T: QueryResultElement
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdCopyQueryPoolResults"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.copy_query_pool_results::<T>(self.query_pool
+                                                     .queries_range(self.first_query, self.count)
+                                                     .unwrap(),
+                                                 &self.destination,
+                                                 self.stride,
+                                                 self.flags);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<D>(Arc<UnsafeQueryPool>, D);
+                impl<D> FinalCommand for Fin<D>
+                    where D: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdCopyQueryPoolResults"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.1
+                    }
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "destination".into()
+                    }
+                }
+                Box::new(Fin(self.query_pool, self.destination))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                assert_eq!(num, 0);
+                &self.destination
+            }
+
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "destination".into()
+            }
+        }
+
+        self.append_command(Cmd {
+                                query_pool,
+                                first_query,
+                                count,
+                                destination,
+                                stride,
+                                flags,
+                            });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               true,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        Ok(())
+    }
+
     /// Calls `vkCmdDispatch` on the builder.
     #[inline]
     pub unsafe fn dispatch(&mut self, dimensions: [u32; 3]) {
@@ -1037,6 +1812,31 @@ impl<P> SyncCommandBufferBuilder<P> {
         self.append_command(Cmd { dimensions });
     }
 
+    /// Calls `vkCmdDispatchBaseKHR` on the builder.
+    #[inline]
+    pub unsafe fn dispatch_base(&mut self, base: [u32; 3], dimensions: [u32; 3]) {
+        struct Cmd {
+            base: [u32; 3],
+            dimensions: [u32; 3],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdDispatchBaseKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.dispatch_base(self.base, self.dimensions);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdDispatchBaseKHR")
+            }
+        }
+
+        self.append_command(Cmd { base, dimensions });
+    }
+
     /// Calls `vkCmdDispatchIndirect` on the builder.
     #[inline]
     pub unsafe fn dispatch_indirect<B>(&mut self, buffer: B)
@@ -1257,27 +2057,35 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
-    /// Calls `vkCmdDrawIndexedIndirect` on the builder.
+    /// Calls `vkCmdDrawIndirectByteCountEXT` on the builder.
     #[inline]
-    pub unsafe fn draw_indexed_indirect<B>(&mut self, buffer: B, draw_count: u32, stride: u32)
-                                           -> Result<(), SyncCommandBufferBuilderError>
+    pub unsafe fn draw_indirect_byte_count<B>(&mut self, instance_count: u32,
+                                              first_instance: u32, counter_buffer: B,
+                                              counter_offset: u32, vertex_stride: u32)
+                                              -> Result<(), SyncCommandBufferBuilderError>
         where B: BufferAccess + Send + Sync + 'static
     {
         struct Cmd<B> {
-            buffer: B,
-            draw_count: u32,
-            stride: u32,
+            instance_count: u32,
+            first_instance: u32,
+            counter_buffer: B,
+            counter_offset: u32,
+            vertex_stride: u32,
         }
 
         impl<P, B> Command<P> for Cmd<B>
             where B: BufferAccess + Send + Sync + 'static
         {
             fn name(&self) -> &'static str {
-                "vkCmdDrawIndexedIndirect"
+                "vkCmdDrawIndirectByteCountEXT"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.draw_indexed_indirect(&self.buffer, self.draw_count, self.stride);
+                out.draw_indirect_byte_count(self.instance_count,
+                                             self.first_instance,
+                                             &self.counter_buffer,
+                                             self.counter_offset,
+                                             self.vertex_stride);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
@@ -1286,7 +2094,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                     where B: BufferAccess + Send + Sync + 'static
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdDrawIndexedIndirect"
+                        "vkCmdDrawIndirectByteCountEXT"
                     }
                     fn buffer(&self, num: usize) -> &BufferAccess {
                         assert_eq!(num, 0);
@@ -1294,27 +2102,29 @@ impl<P> SyncCommandBufferBuilder<P> {
                     }
                     fn buffer_name(&self, num: usize) -> Cow<'static, str> {
                         assert_eq!(num, 0);
-                        "indirect buffer".into()
+                        "counter buffer".into()
                     }
                 }
-                Box::new(Fin(self.buffer))
+                Box::new(Fin(self.counter_buffer))
             }
 
             fn buffer(&self, num: usize) -> &BufferAccess {
                 assert_eq!(num, 0);
-                &self.buffer
+                &self.counter_buffer
             }
 
             fn buffer_name(&self, num: usize) -> Cow<'static, str> {
                 assert_eq!(num, 0);
-                "indirect buffer".into()
+                "counter buffer".into()
             }
         }
 
         self.append_command(Cmd {
-                                buffer,
-                                draw_count,
-                                stride,
+                                instance_count,
+                                first_instance,
+                                counter_buffer,
+                                counter_offset,
+                                vertex_stride,
                             });
         self.prev_cmd_resource(KeyTy::Buffer,
                                0,
@@ -1324,7 +2134,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                                    ..PipelineStages::none()
                                },
                                AccessFlagBits {
-                                   indirect_command_read: true,
+                                   transform_feedback_counter_read: true,
                                    ..AccessFlagBits::none()
                                },
                                ImageLayout::Undefined,
@@ -1332,59 +2142,27 @@ impl<P> SyncCommandBufferBuilder<P> {
         Ok(())
     }
 
-    /// Calls `vkCmdEndRenderPass` on the builder.
-    #[inline]
-    pub unsafe fn end_render_pass(&mut self) {
-        struct Cmd;
-
-        impl<P> Command<P> for Cmd {
-            fn name(&self) -> &'static str {
-                "vkCmdEndRenderPass"
-            }
-
-            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.end_render_pass();
-            }
-
-            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdEndRenderPass")
-            }
-        }
-
-        self.append_command(Cmd);
-        self.prev_cmd_left_render_pass();
-    }
-
-    /// Starts the process of executing secondary command buffers. Returns an intermediate struct
-    /// which can be used to add the command buffers.
-    #[inline]
-    pub unsafe fn execute_commands(&mut self) -> SyncCommandBufferBuilderExecuteCommands<P> {
-        SyncCommandBufferBuilderExecuteCommands {
-            builder: self,
-            inner: UnsafeCommandBufferBuilderExecuteCommands::new(),
-            command_buffers: Vec::new(),
-        }
-    }
-
-    /// Calls `vkCmdFillBuffer` on the builder.
+    /// Calls `vkCmdDrawIndexedIndirect` on the builder.
     #[inline]
-    pub unsafe fn fill_buffer<B>(&mut self, buffer: B, data: u32)
+    pub unsafe fn draw_indexed_indirect<B>(&mut self, buffer: B, draw_count: u32, stride: u32)
+                                           -> Result<(), SyncCommandBufferBuilderError>
         where B: BufferAccess + Send + Sync + 'static
     {
         struct Cmd<B> {
             buffer: B,
-            data: u32,
+            draw_count: u32,
+            stride: u32,
         }
 
         impl<P, B> Command<P> for Cmd<B>
             where B: BufferAccess + Send + Sync + 'static
         {
             fn name(&self) -> &'static str {
-                "vkCmdFillBuffer"
+                "vkCmdDrawIndexedIndirect"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.fill_buffer(&self.buffer, self.data);
+                out.draw_indexed_indirect(&self.buffer, self.draw_count, self.stride);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
@@ -1393,14 +2171,15 @@ impl<P> SyncCommandBufferBuilder<P> {
                     where B: BufferAccess + Send + Sync + 'static
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdFillBuffer"
+                        "vkCmdDrawIndexedIndirect"
                     }
                     fn buffer(&self, num: usize) -> &BufferAccess {
                         assert_eq!(num, 0);
                         &self.0
                     }
-                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
-                        "destination".into()
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "indirect buffer".into()
                     }
                 }
                 Box::new(Fin(self.buffer))
@@ -1411,368 +2190,1903 @@ impl<P> SyncCommandBufferBuilder<P> {
                 &self.buffer
             }
 
-            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
-                "destination".into()
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "indirect buffer".into()
             }
         }
 
-        self.append_command(Cmd { buffer, data });
+        self.append_command(Cmd {
+                                buffer,
+                                draw_count,
+                                stride,
+                            });
         self.prev_cmd_resource(KeyTy::Buffer,
                                0,
-                               true,
+                               false,
                                PipelineStages {
-                                   transfer: true,
+                                   draw_indirect: true,
                                    ..PipelineStages::none()
                                },
                                AccessFlagBits {
-                                   transfer_write: true,
+                                   indirect_command_read: true,
                                    ..AccessFlagBits::none()
                                },
                                ImageLayout::Undefined,
-                               ImageLayout::Undefined)
-            .unwrap();
+                               ImageLayout::Undefined)?;
+        Ok(())
     }
 
-    /// Calls `vkCmdNextSubpass` on the builder.
+    /// Calls `vkCmdDrawMultiEXT` on the builder.
     #[inline]
-    pub unsafe fn next_subpass(&mut self, subpass_contents: SubpassContents) {
+    pub unsafe fn draw_multi(&mut self, draws: Vec<MultiDrawInfo>, instance_count: u32,
+                             first_instance: u32) {
         struct Cmd {
-            subpass_contents: SubpassContents,
+            draws: Vec<MultiDrawInfo>,
+            instance_count: u32,
+            first_instance: u32,
         }
 
         impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdNextSubpass"
+                "vkCmdDrawMultiEXT"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.next_subpass(self.subpass_contents);
+                out.draw_multi(&self.draws, self.instance_count, self.first_instance);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdNextSubpass")
+                Box::new("vkCmdDrawMultiEXT")
             }
         }
 
-        self.append_command(Cmd { subpass_contents });
+        self.append_command(Cmd {
+                                draws,
+                                instance_count,
+                                first_instance,
+                            });
     }
 
-    /// Calls `vkCmdPushConstants` on the builder.
+    /// Calls `vkCmdDrawMultiIndexedEXT` on the builder.
     #[inline]
-    pub unsafe fn push_constants<Pl, D>(&mut self, pipeline_layout: Pl, stages: ShaderStages,
-                                        offset: u32, size: u32, data: &D)
-        where Pl: PipelineLayoutAbstract + Send + Sync + 'static,
-              D: ?Sized + Send + Sync + 'static
+    pub unsafe fn draw_multi_indexed(&mut self, draws: Vec<MultiDrawIndexedInfo>,
+                                     instance_count: u32, first_instance: u32) {
+        struct Cmd {
+            draws: Vec<MultiDrawIndexedInfo>,
+            instance_count: u32,
+            first_instance: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawMultiIndexedEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_multi_indexed(&self.draws, self.instance_count, self.first_instance);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdDrawMultiIndexedEXT")
+            }
+        }
+
+        self.append_command(Cmd {
+                                draws,
+                                instance_count,
+                                first_instance,
+                            });
+    }
+
+    /// Calls `vkCmdDrawIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_count<B, C>(&mut self, buffer: B, count_buffer: C,
+                                            max_draw_count: u32, stride: u32)
+                                            -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static,
+              C: BufferAccess + Send + Sync + 'static
     {
-        struct Cmd<Pl> {
-            pipeline_layout: Pl,
-            stages: ShaderStages,
-            offset: u32,
-            size: u32,
-            data: Box<[u8]>,
+        struct Cmd<B, C> {
+            buffer: B,
+            count_buffer: C,
+            max_draw_count: u32,
+            stride: u32,
         }
 
-        impl<P, Pl> Command<P> for Cmd<Pl>
-            where Pl: PipelineLayoutAbstract + Send + Sync + 'static
+        impl<P, B, C> Command<P> for Cmd<B, C>
+            where B: BufferAccess + Send + Sync + 'static,
+                  C: BufferAccess + Send + Sync + 'static
         {
             fn name(&self) -> &'static str {
-                "vkCmdPushConstants"
+                "vkCmdDrawIndirectCountKHR"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.push_constants::<_, [u8]>(&self.pipeline_layout,
-                                              self.stages,
-                                              self.offset,
-                                              self.size,
-                                              &self.data);
+                out.draw_indirect_count(&self.buffer,
+                                        &self.count_buffer,
+                                        self.max_draw_count,
+                                        self.stride);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                struct Fin<Pl>(Pl);
-                impl<Pl> FinalCommand for Fin<Pl>
-                    where Pl: Send + Sync + 'static
+                struct Fin<B, C>(B, C);
+                impl<B, C> FinalCommand for Fin<B, C>
+                    where B: BufferAccess + Send + Sync + 'static,
+                          C: BufferAccess + Send + Sync + 'static
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdPushConstants"
+                        "vkCmdDrawIndirectCountKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        match num {
+                            0 => &self.0,
+                            1 => &self.1,
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "indirect buffer".into(),
+                            1 => "count buffer".into(),
+                            _ => panic!(),
+                        }
                     }
                 }
-                Box::new(Fin(self.pipeline_layout))
+                let cmd = *self;
+                Box::new(Fin(cmd.buffer, cmd.count_buffer))
             }
-        }
 
-        debug_assert!(mem::size_of_val(data) >= size as usize);
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                match num {
+                    0 => &self.buffer,
+                    1 => &self.count_buffer,
+                    _ => panic!(),
+                }
+            }
 
-        let mut out = Vec::with_capacity(size as usize);
-        ptr::copy::<u8>(data as *const D as *const u8,
-                        out.as_mut_ptr(),
-                        size as usize);
-        out.set_len(size as usize);
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                match num {
+                    0 => "indirect buffer".into(),
+                    1 => "count buffer".into(),
+                    _ => panic!(),
+                }
+            }
+        }
 
         self.append_command(Cmd {
-                                pipeline_layout,
-                                stages,
-                                offset,
-                                size,
-                                data: out.into(),
+                                buffer,
+                                count_buffer,
+                                max_draw_count,
+                                stride,
                             });
+        for num in 0 .. 2 {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   false,
+                                   PipelineStages {
+                                       draw_indirect: true,
+                                       ..PipelineStages::none()
+                                   },
+                                   AccessFlagBits {
+                                       indirect_command_read: true,
+                                       ..AccessFlagBits::none()
+                                   },
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+        Ok(())
     }
 
-    /// Calls `vkCmdResetEvent` on the builder.
+    /// Calls `vkCmdDrawIndexedIndirectCountKHR` on the builder.
     #[inline]
-    pub unsafe fn reset_event(&mut self, event: Arc<Event>, stages: PipelineStages) {
-        struct Cmd {
-            event: Arc<Event>,
-            stages: PipelineStages,
+    pub unsafe fn draw_indexed_indirect_count<B, C>(&mut self, buffer: B, count_buffer: C,
+                                                    max_draw_count: u32, stride: u32)
+                                                    -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static,
+              C: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B, C> {
+            buffer: B,
+            count_buffer: C,
+            max_draw_count: u32,
+            stride: u32,
         }
 
-        impl<P> Command<P> for Cmd {
+        impl<P, B, C> Command<P> for Cmd<B, C>
+            where B: BufferAccess + Send + Sync + 'static,
+                  C: BufferAccess + Send + Sync + 'static
+        {
             fn name(&self) -> &'static str {
-                "vkCmdResetEvent"
+                "vkCmdDrawIndexedIndirectCountKHR"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.reset_event(&self.event, self.stages);
+                out.draw_indexed_indirect_count(&self.buffer,
+                                                &self.count_buffer,
+                                                self.max_draw_count,
+                                                self.stride);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                struct Fin(Arc<Event>);
-                impl FinalCommand for Fin {
+                struct Fin<B, C>(B, C);
+                impl<B, C> FinalCommand for Fin<B, C>
+                    where B: BufferAccess + Send + Sync + 'static,
+                          C: BufferAccess + Send + Sync + 'static
+                {
                     fn name(&self) -> &'static str {
-                        "vkCmdResetEvent"
+                        "vkCmdDrawIndexedIndirectCountKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        match num {
+                            0 => &self.0,
+                            1 => &self.1,
+                            _ => panic!(),
+                        }
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        match num {
+                            0 => "indirect buffer".into(),
+                            1 => "count buffer".into(),
+                            _ => panic!(),
+                        }
                     }
                 }
-                Box::new(Fin(self.event))
+                let cmd = *self;
+                Box::new(Fin(cmd.buffer, cmd.count_buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                match num {
+                    0 => &self.buffer,
+                    1 => &self.count_buffer,
+                    _ => panic!(),
+                }
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                match num {
+                    0 => "indirect buffer".into(),
+                    1 => "count buffer".into(),
+                    _ => panic!(),
+                }
             }
         }
 
-        self.append_command(Cmd { event, stages });
+        self.append_command(Cmd {
+                                buffer,
+                                count_buffer,
+                                max_draw_count,
+                                stride,
+                            });
+        for num in 0 .. 2 {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   false,
+                                   PipelineStages {
+                                       draw_indirect: true,
+                                       ..PipelineStages::none()
+                                   },
+                                   AccessFlagBits {
+                                       indirect_command_read: true,
+                                       ..AccessFlagBits::none()
+                                   },
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+        Ok(())
     }
 
-    /// Calls `vkCmdSetBlendConstants` on the builder.
+    /// Calls `vkCmdDrawMeshTasksEXT` on the builder.
     #[inline]
-    pub unsafe fn set_blend_constants(&mut self, constants: [f32; 4]) {
+    pub unsafe fn draw_mesh_tasks(&mut self, group_count: [u32; 3]) {
         struct Cmd {
-            constants: [f32; 4],
+            group_count: [u32; 3],
         }
 
         impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdSetBlendConstants"
+                "vkCmdDrawMeshTasksEXT"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.set_blend_constants(self.constants);
+                out.draw_mesh_tasks(self.group_count);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdSetBlendConstants")
+                Box::new("vkCmdDrawMeshTasksEXT")
             }
         }
 
-        self.append_command(Cmd { constants });
+        self.append_command(Cmd { group_count });
     }
 
-    /// Calls `vkCmdSetDepthBias` on the builder.
+    /// Calls `vkCmdDrawMeshTasksIndirectEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks_indirect<B>(&mut self, buffer: B, draw_count: u32, stride: u32)
+                                              -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B> {
+            buffer: B,
+            draw_count: u32,
+            stride: u32,
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdDrawMeshTasksIndirectEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.draw_mesh_tasks_indirect(&self.buffer, self.draw_count, self.stride);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdDrawMeshTasksIndirectEXT"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "indirect buffer".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "indirect buffer".into()
+            }
+        }
+
+        self.append_command(Cmd {
+                                buffer,
+                                draw_count,
+                                stride,
+                            });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               false,
+                               PipelineStages {
+                                   draw_indirect: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   indirect_command_read: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdEndConditionalRenderingEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_conditional_rendering(&mut self) {
+        struct Cmd;
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndConditionalRenderingEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.end_conditional_rendering();
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdEndConditionalRenderingEXT")
+            }
+        }
+
+        self.append_command(Cmd);
+    }
+
+    /// Calls `vkCmdEndDebugUtilsLabelEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_debug_utils_label(&mut self) {
+        struct Cmd;
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.end_debug_utils_label();
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdEndDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd);
+    }
+
+    /// Calls `vkCmdEndQuery` on the builder.
+    #[inline]
+    pub unsafe fn end_query(&mut self, query_pool: Arc<UnsafeQueryPool>, query: u32) {
+        struct Cmd {
+            query_pool: Arc<UnsafeQueryPool>,
+            query: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndQuery"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.end_query(self.query_pool.query(self.query).unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Arc<UnsafeQueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdEndQuery"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd { query_pool, query });
+    }
+
+    /// Calls `vkCmdEndTransformFeedbackEXT` on the builder.
+    ///
+    /// The accesses to the counter buffers are registered by `begin_transform_feedback`, which
+    /// must have been passed the same buffers.
+    #[inline]
+    pub unsafe fn end_transform_feedback(&mut self,
+                                         counter_buffers: Vec<Box<BufferAccess + Send + Sync>>) {
+        struct Cmd(Vec<Box<BufferAccess + Send + Sync>>);
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndTransformFeedbackEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let mut counters = UnsafeCommandBufferBuilderTransformFeedbackBuffers::new();
+                for buffer in self.0.iter() {
+                    counters.add(buffer);
+                }
+                out.end_transform_feedback(&counters);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdEndTransformFeedbackEXT")
+            }
+        }
+
+        self.append_command(Cmd(counter_buffers));
+    }
+
+    /// Calls `vkCmdEndRenderPass` on the builder.
+    #[inline]
+    pub unsafe fn end_render_pass(&mut self) {
+        struct Cmd;
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdEndRenderPass"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.end_render_pass();
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdEndRenderPass")
+            }
+        }
+
+        self.append_command(Cmd);
+        self.prev_cmd_left_render_pass();
+    }
+
+    /// Starts the process of executing secondary command buffers. Returns an intermediate struct
+    /// which can be used to add the command buffers.
+    #[inline]
+    pub unsafe fn execute_commands(&mut self) -> SyncCommandBufferBuilderExecuteCommands<P> {
+        SyncCommandBufferBuilderExecuteCommands {
+            builder: self,
+            inner: UnsafeCommandBufferBuilderExecuteCommands::new(),
+            command_buffers: Vec::new(),
+        }
+    }
+
+    /// Calls `vkCmdFillBuffer` on the builder.
+    #[inline]
+    pub unsafe fn fill_buffer<B>(&mut self, buffer: B, data: u32)
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B> {
+            buffer: B,
+            data: u32,
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdFillBuffer"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.fill_buffer(&self.buffer, self.data);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdFillBuffer"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "destination".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "destination".into()
+            }
+        }
+
+        self.append_command(Cmd { buffer, data });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               true,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)
+            .unwrap();
+    }
+
+    /// Calls `vkCmdInsertDebugUtilsLabelEXT` on the builder.
+    #[inline]
+    pub unsafe fn insert_debug_utils_label(&mut self, name: CString, color: [f32; 4]) {
+        struct Cmd {
+            name: CString,
+            color: [f32; 4],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdInsertDebugUtilsLabelEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.insert_debug_utils_label(&self.name, self.color);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdInsertDebugUtilsLabelEXT")
+            }
+        }
+
+        self.append_command(Cmd { name, color });
+    }
+
+    /// Calls `vkCmdNextSubpass` on the builder.
+    #[inline]
+    pub unsafe fn next_subpass(&mut self, subpass_contents: SubpassContents) {
+        struct Cmd {
+            subpass_contents: SubpassContents,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdNextSubpass"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.next_subpass(self.subpass_contents);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdNextSubpass")
+            }
+        }
+
+        self.append_command(Cmd { subpass_contents });
+    }
+
+    /// Calls `vkCmdPipelineBarrier` on the builder with an explicit barrier.
+    ///
+    /// The buffers and images of the barrier are registered as if they were accessed by the
+    /// destination stages and accesses of the barrier, so that the following commands are
+    /// synchronized with it and see the new layout of the images.
+    pub unsafe fn pipeline_barrier(&mut self, barrier: PipelineBarrier)
+                                   -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            barrier: PipelineBarrier,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdPipelineBarrier"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.pipeline_barrier(&barrier_command(&self.barrier));
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(PipelineBarrier);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdPipelineBarrier"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        &self.0.buffer_barriers[num].buffer
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("buffer barrier {}", num).into()
+                    }
+                    fn image(&self, num: usize) -> &ImageAccess {
+                        &self.0.image_barriers[num].image
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("image barrier {}", num).into()
+                    }
+                }
+                Box::new(Fin(self.barrier))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                &self.barrier.buffer_barriers[num].buffer
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                format!("buffer barrier {}", num).into()
+            }
+
+            fn image(&self, num: usize) -> &ImageAccess {
+                &self.barrier.image_barriers[num].image
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                format!("image barrier {}", num).into()
+            }
+        }
+
+        let buffers = barrier
+            .buffer_barriers
+            .iter()
+            .map(|b| (b.destination_stages, b.destination_access))
+            .collect::<Vec<_>>();
+        let images = barrier
+            .image_barriers
+            .iter()
+            .map(|b| (b.destination_stages, b.destination_access, b.old_layout, b.new_layout))
+            .collect::<Vec<_>>();
+
+        self.append_command(Cmd { barrier });
+
+        for (num, (stages, access)) in buffers.into_iter().enumerate() {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   true,
+                                   stages,
+                                   access,
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        for (num, (stages, access, old_layout, new_layout)) in images.into_iter().enumerate() {
+            self.prev_cmd_resource(KeyTy::Image,
+                                   num,
+                                   true,
+                                   stages,
+                                   access,
+                                   old_layout,
+                                   new_layout)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` on the raw command buffer. The resources of `resources` are tracked as if they
+    /// were accessed by a regular command.
+    pub unsafe fn raw_commands<F>(&mut self, resources: RawCommandsResources, f: F)
+                                  -> Result<(), SyncCommandBufferBuilderError>
+        where F: FnOnce(&vk::DevicePointers, vk::CommandBuffer) + Send + Sync + 'static
+    {
+        struct Cmd<F> {
+            resources: RawCommandsResources,
+            f: Option<F>,
+        }
+
+        impl<P, F> Command<P> for Cmd<F>
+            where F: FnOnce(&vk::DevicePointers, vk::CommandBuffer) + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "raw commands"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.raw_commands(self.f.take().unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(RawCommandsResources);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "raw commands"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        &self.0.buffers[num].buffer
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("buffer {}", num).into()
+                    }
+                    fn image(&self, num: usize) -> &ImageAccess {
+                        &self.0.images[num].image
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("image {}", num).into()
+                    }
+                }
+                Box::new(Fin(self.resources))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                &self.resources.buffers[num].buffer
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                format!("buffer {}", num).into()
+            }
+
+            fn image(&self, num: usize) -> &ImageAccess {
+                &self.resources.images[num].image
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                format!("image {}", num).into()
+            }
+        }
+
+        let buffers = resources
+            .buffers
+            .iter()
+            .map(|b| (b.exclusive, b.stages, b.access))
+            .collect::<Vec<_>>();
+        let images = resources
+            .images
+            .iter()
+            .map(|i| (i.exclusive, i.stages, i.access, i.start_layout, i.end_layout))
+            .collect::<Vec<_>>();
+
+        self.append_command(Cmd {
+                                resources,
+                                f: Some(f),
+                            });
+
+        for (num, (exclusive, stages, access)) in buffers.into_iter().enumerate() {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   exclusive,
+                                   stages,
+                                   access,
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        for (num, (exclusive, stages, access, start_layout, end_layout)) in
+            images.into_iter().enumerate()
+        {
+            self.prev_cmd_resource(KeyTy::Image,
+                                   num,
+                                   exclusive,
+                                   stages,
+                                   access,
+                                   start_layout,
+                                   end_layout)?;
+        }
+
+        Ok(())
+    }
+
+    /// Calls `vkCmdPushConstants` on the builder.
+    #[inline]
+    pub unsafe fn push_constants<Pl, D>(&mut self, pipeline_layout: Pl, stages: ShaderStages,
+                                        offset: u32, size: u32, data: &D)
+        where Pl: PipelineLayoutAbstract + Send + Sync + 'static,
+              D: ?Sized + Send + Sync + 'static
+    {
+        struct Cmd<Pl> {
+            pipeline_layout: Pl,
+            stages: ShaderStages,
+            offset: u32,
+            size: u32,
+            data: Box<[u8]>,
+        }
+
+        impl<P, Pl> Command<P> for Cmd<Pl>
+            where Pl: PipelineLayoutAbstract + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdPushConstants"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.push_constants::<_, [u8]>(&self.pipeline_layout,
+                                              self.stages,
+                                              self.offset,
+                                              self.size,
+                                              &self.data);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<Pl>(Pl);
+                impl<Pl> FinalCommand for Fin<Pl>
+                    where Pl: Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdPushConstants"
+                    }
+                }
+                Box::new(Fin(self.pipeline_layout))
+            }
+        }
+
+        debug_assert!(mem::size_of_val(data) >= size as usize);
+
+        let mut out = Vec::with_capacity(size as usize);
+        ptr::copy::<u8>(data as *const D as *const u8,
+                        out.as_mut_ptr(),
+                        size as usize);
+        out.set_len(size as usize);
+
+        self.append_command(Cmd {
+                                pipeline_layout,
+                                stages,
+                                offset,
+                                size,
+                                data: out.into(),
+                            });
+    }
+
+    /// Calls `vkCmdResetEvent` on the builder.
+    #[inline]
+    pub unsafe fn reset_event(&mut self, event: Arc<Event>, stages: PipelineStages) {
+        struct Cmd {
+            event: Arc<Event>,
+            stages: PipelineStages,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdResetEvent"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.reset_event(&self.event, self.stages);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Arc<Event>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdResetEvent"
+                    }
+                }
+                Box::new(Fin(self.event))
+            }
+        }
+
+        self.append_command(Cmd { event, stages });
+    }
+
+    /// Calls `vkCmdResetQueryPool` on the builder.
+    #[inline]
+    pub unsafe fn reset_query_pool(&mut self, query_pool: Arc<UnsafeQueryPool>, first_query: u32,
+                                   count: u32) {
+        struct Cmd {
+            query_pool: Arc<UnsafeQueryPool>,
+            first_query: u32,
+            count: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdResetQueryPool"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.reset_query_pool(self.query_pool
+                                         .queries_range(self.first_query, self.count)
+                                         .unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Arc<UnsafeQueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdResetQueryPool"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+                                query_pool,
+                                first_query,
+                                count,
+                            });
+    }
+
+    /// Calls `vkCmdSetBlendConstants` on the builder.
+    #[inline]
+    pub unsafe fn set_blend_constants(&mut self, constants: [f32; 4]) {
+        struct Cmd {
+            constants: [f32; 4],
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetBlendConstants"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_blend_constants(self.constants);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetBlendConstants")
+            }
+        }
+
+        self.append_command(Cmd { constants });
+    }
+
+    /// Calls `vkCmdSetCullModeEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_cull_mode(&mut self, cull_mode: CullMode) {
+        struct Cmd {
+            cull_mode: CullMode,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetCullModeEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_cull_mode(self.cull_mode);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetCullModeEXT")
+            }
+        }
+
+        self.append_command(Cmd { cull_mode });
+    }
+
+    /// Calls `vkCmdSetLineStippleEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_line_stipple(&mut self, factor: u32, pattern: u16) {
+        struct Cmd {
+            factor: u32,
+            pattern: u16,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetLineStippleEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_line_stipple(self.factor, self.pattern);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetLineStippleEXT")
+            }
+        }
+
+        self.append_command(Cmd { factor, pattern });
+    }
+
+    /// Calls `vkCmdSetDepthBias` on the builder.
     #[inline]
     pub unsafe fn set_depth_bias(&mut self, constant_factor: f32, clamp: f32, slope_factor: f32) {
         struct Cmd {
-            constant_factor: f32,
-            clamp: f32,
-            slope_factor: f32,
+            constant_factor: f32,
+            clamp: f32,
+            slope_factor: f32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetDepthBias"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_depth_bias(self.constant_factor, self.clamp, self.slope_factor);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetDepthBias")
+            }
+        }
+
+        self.append_command(Cmd {
+                                constant_factor,
+                                clamp,
+                                slope_factor,
+                            });
+    }
+
+    /// Calls `vkCmdSetDepthBounds` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_bounds(&mut self, min: f32, max: f32) {
+        struct Cmd {
+            min: f32,
+            max: f32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetDepthBounds"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_depth_bounds(self.min, self.max);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetDepthBounds")
+            }
+        }
+
+        self.append_command(Cmd { min, max });
+    }
+
+    /// Calls `vkCmdSetCheckpointNV` on the builder.
+    #[inline]
+    pub unsafe fn set_checkpoint(&mut self, marker: usize) {
+        struct Cmd {
+            marker: usize,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetCheckpointNV"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_checkpoint(self.marker);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetCheckpointNV")
+            }
+        }
+
+        self.append_command(Cmd { marker });
+    }
+
+    /// Calls `vkCmdSetDeviceMaskKHR` on the builder.
+    #[inline]
+    pub unsafe fn set_device_mask(&mut self, device_mask: u32) {
+        struct Cmd {
+            device_mask: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetDeviceMaskKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_device_mask(self.device_mask);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetDeviceMaskKHR")
+            }
+        }
+
+        self.append_command(Cmd { device_mask });
+    }
+
+    /// Calls `vkCmdSetDepthBoundsTestEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_bounds_test_enable(&mut self, enable: bool) {
+        struct Cmd {
+            enable: bool,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetDepthBoundsTestEnableEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_depth_bounds_test_enable(self.enable);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetDepthBoundsTestEnableEXT")
+            }
+        }
+
+        self.append_command(Cmd { enable });
+    }
+
+    /// Calls `vkCmdSetDepthCompareOpEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_compare_op(&mut self, compare_op: Compare) {
+        struct Cmd {
+            compare_op: Compare,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetDepthCompareOpEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_depth_compare_op(self.compare_op);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetDepthCompareOpEXT")
+            }
+        }
+
+        self.append_command(Cmd { compare_op });
+    }
+
+    /// Calls `vkCmdSetDepthTestEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_test_enable(&mut self, enable: bool) {
+        struct Cmd {
+            enable: bool,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetDepthTestEnableEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_depth_test_enable(self.enable);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetDepthTestEnableEXT")
+            }
+        }
+
+        self.append_command(Cmd { enable });
+    }
+
+    /// Calls `vkCmdSetDepthWriteEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_write_enable(&mut self, enable: bool) {
+        struct Cmd {
+            enable: bool,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetDepthWriteEnableEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_depth_write_enable(self.enable);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetDepthWriteEnableEXT")
+            }
+        }
+
+        self.append_command(Cmd { enable });
+    }
+
+    /// Calls `vkCmdSetEvent` on the builder.
+    #[inline]
+    pub unsafe fn set_event(&mut self, event: Arc<Event>, stages: PipelineStages) {
+        struct Cmd {
+            event: Arc<Event>,
+            stages: PipelineStages,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetEvent"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_event(&self.event, self.stages);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Arc<Event>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdSetEvent"
+                    }
+                }
+                Box::new(Fin(self.event))
+            }
+        }
+
+        self.append_command(Cmd { event, stages });
+    }
+
+    /// Calls `vkCmdSetFrontFaceEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_front_face(&mut self, front_face: FrontFace) {
+        struct Cmd {
+            front_face: FrontFace,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetFrontFaceEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_front_face(self.front_face);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetFrontFaceEXT")
+            }
+        }
+
+        self.append_command(Cmd { front_face });
+    }
+
+    /// Calls `vkCmdSetLineWidth` on the builder.
+    #[inline]
+    pub unsafe fn set_line_width(&mut self, line_width: f32) {
+        struct Cmd {
+            line_width: f32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetLineWidth"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_line_width(self.line_width);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetLineWidth")
+            }
+        }
+
+        self.append_command(Cmd { line_width });
+    }
+
+    /// Calls `vkCmdSetPrimitiveTopologyEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_primitive_topology(&mut self, topology: PrimitiveTopology) {
+        struct Cmd {
+            topology: PrimitiveTopology,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetPrimitiveTopologyEXT"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_primitive_topology(self.topology);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetPrimitiveTopologyEXT")
+            }
+        }
+
+        self.append_command(Cmd { topology });
+    }
+
+    /// Calls `vkCmdSetStencilCompareMask` on the builder.
+    #[inline]
+    pub unsafe fn set_stencil_compare_mask(&mut self, face_mask: StencilFaces, compare_mask: u32) {
+        struct Cmd {
+            face_mask: StencilFaces,
+            compare_mask: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetStencilCompareMask"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_stencil_compare_mask(self.face_mask, self.compare_mask);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetStencilCompareMask")
+            }
+        }
+
+        self.append_command(Cmd { face_mask, compare_mask });
+    }
+
+    /// Calls `vkCmdSetStencilReference` on the builder.
+    #[inline]
+    pub unsafe fn set_stencil_reference(&mut self, face_mask: StencilFaces, reference: u32) {
+        struct Cmd {
+            face_mask: StencilFaces,
+            reference: u32,
         }
 
         impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdSetDepthBias"
+                "vkCmdSetStencilReference"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.set_depth_bias(self.constant_factor, self.clamp, self.slope_factor);
+                out.set_stencil_reference(self.face_mask, self.reference);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdSetDepthBias")
+                Box::new("vkCmdSetStencilReference")
             }
         }
 
-        self.append_command(Cmd {
-                                constant_factor,
-                                clamp,
-                                slope_factor,
-                            });
+        self.append_command(Cmd { face_mask, reference });
     }
 
-    /// Calls `vkCmdSetDepthBounds` on the builder.
+    /// Calls `vkCmdSetStencilOpEXT` on the builder.
     #[inline]
-    pub unsafe fn set_depth_bounds(&mut self, min: f32, max: f32) {
+    pub unsafe fn set_stencil_op(&mut self, face_mask: StencilFaces, fail_op: StencilOp,
+                                 pass_op: StencilOp, depth_fail_op: StencilOp,
+                                 compare_op: Compare) {
         struct Cmd {
-            min: f32,
-            max: f32,
+            face_mask: StencilFaces,
+            fail_op: StencilOp,
+            pass_op: StencilOp,
+            depth_fail_op: StencilOp,
+            compare_op: Compare,
         }
 
         impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdSetDepthBounds"
+                "vkCmdSetStencilOpEXT"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.set_depth_bounds(self.min, self.max);
+                out.set_stencil_op(self.face_mask,
+                                   self.fail_op,
+                                   self.pass_op,
+                                   self.depth_fail_op,
+                                   self.compare_op);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdSetDepthBounds")
+                Box::new("vkCmdSetStencilOpEXT")
             }
         }
 
-        self.append_command(Cmd { min, max });
+        self.append_command(Cmd { face_mask, fail_op, pass_op, depth_fail_op, compare_op });
     }
 
-    /// Calls `vkCmdSetEvent` on the builder.
+    /// Calls `vkCmdSetStencilTestEnableEXT` on the builder.
     #[inline]
-    pub unsafe fn set_event(&mut self, event: Arc<Event>, stages: PipelineStages) {
+    pub unsafe fn set_stencil_test_enable(&mut self, enable: bool) {
         struct Cmd {
-            event: Arc<Event>,
-            stages: PipelineStages,
+            enable: bool,
         }
 
         impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdSetEvent"
+                "vkCmdSetStencilTestEnableEXT"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.set_event(&self.event, self.stages);
+                out.set_stencil_test_enable(self.enable);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                struct Fin(Arc<Event>);
-                impl FinalCommand for Fin {
+                Box::new("vkCmdSetStencilTestEnableEXT")
+            }
+        }
+
+        self.append_command(Cmd { enable });
+    }
+
+    /// Calls `vkCmdSetStencilWriteMask` on the builder.
+    #[inline]
+    pub unsafe fn set_stencil_write_mask(&mut self, face_mask: StencilFaces, write_mask: u32) {
+        struct Cmd {
+            face_mask: StencilFaces,
+            write_mask: u32,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdSetStencilWriteMask"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_stencil_write_mask(self.face_mask, self.write_mask);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetStencilWriteMask")
+            }
+        }
+
+        self.append_command(Cmd { face_mask, write_mask });
+    }
+
+    /// Calls `vkCmdSetScissor` on the builder.
+    ///
+    /// If the list is empty then the command is automatically ignored.
+    #[inline]
+    pub unsafe fn set_scissor<I>(&mut self, first_scissor: u32, scissors: I)
+        where I: Iterator<Item = Scissor> + Send + Sync + 'static
+    {
+        struct Cmd<I> {
+            first_scissor: u32,
+            scissors: Option<I>,
+        }
+
+        impl<P, I> Command<P> for Cmd<I>
+            where I: Iterator<Item = Scissor>
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdSetScissor"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_scissor(self.first_scissor, self.scissors.take().unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetScissor")
+            }
+        }
+
+        self.append_command(Cmd {
+                                first_scissor,
+                                scissors: Some(scissors),
+                            });
+    }
+
+    /// Calls `vkCmdSetViewport` on the builder.
+    ///
+    /// If the list is empty then the command is automatically ignored.
+    #[inline]
+    pub unsafe fn set_viewport<I>(&mut self, first_viewport: u32, viewports: I)
+        where I: Iterator<Item = Viewport> + Send + Sync + 'static
+    {
+        struct Cmd<I> {
+            first_viewport: u32,
+            viewports: Option<I>,
+        }
+
+        impl<P, I> Command<P> for Cmd<I>
+            where I: Iterator<Item = Viewport>
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdSetViewport"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.set_viewport(self.first_viewport, self.viewports.take().unwrap());
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                Box::new("vkCmdSetViewport")
+            }
+        }
+
+        self.append_command(Cmd {
+                                first_viewport,
+                                viewports: Some(viewports),
+                            });
+    }
+
+    /// Calls `vkCmdTraceRaysKHR` on the builder.
+    ///
+    /// The offsets of `regions` are relative to the start of `shader_binding_table`.
+    pub unsafe fn trace_rays<B>(&mut self, shader_binding_table: B,
+                                regions: ShaderBindingTableRegions, dimensions: [u32; 3])
+                                -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        struct Cmd<B> {
+            shader_binding_table: B,
+            regions: ShaderBindingTableRegions,
+            dimensions: [u32; 3],
+        }
+
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdTraceRaysKHR"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                let address = self.shader_binding_table.device_address().unwrap();
+                out.trace_rays(address, &self.regions, self.dimensions);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
                     fn name(&self) -> &'static str {
-                        "vkCmdSetEvent"
+                        "vkCmdTraceRaysKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "shader binding table".into()
                     }
                 }
-                Box::new(Fin(self.event))
+                Box::new(Fin(self.shader_binding_table))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                assert_eq!(num, 0);
+                &self.shader_binding_table
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "shader binding table".into()
             }
         }
 
-        self.append_command(Cmd { event, stages });
+        self.append_command(Cmd {
+                                shader_binding_table,
+                                regions,
+                                dimensions,
+                            });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               false,
+                               PipelineStages {
+                                   ray_tracing_shader: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   shader_read: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        Ok(())
     }
 
-    /// Calls `vkCmdSetLineWidth` on the builder.
+    /// Calls `vkCmdUpdateBuffer` on the builder.
     #[inline]
-    pub unsafe fn set_line_width(&mut self, line_width: f32) {
+    pub unsafe fn update_buffer<B, D>(&mut self, buffer: B, data: D)
+        where B: BufferAccess + Send + Sync + 'static,
+              D: Send + Sync + 'static
+    {
+        struct Cmd<B, D> {
+            buffer: B,
+            data: D,
+        }
+
+        impl<P, B, D> Command<P> for Cmd<B, D>
+            where B: BufferAccess + Send + Sync + 'static,
+                  D: Send + Sync + 'static
+        {
+            fn name(&self) -> &'static str {
+                "vkCmdUpdateBuffer"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.update_buffer(&self.buffer, &self.data);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin<B>(B);
+                impl<B> FinalCommand for Fin<B>
+                    where B: BufferAccess + Send + Sync + 'static
+                {
+                    fn name(&self) -> &'static str {
+                        "vkCmdUpdateBuffer"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        assert_eq!(num, 0);
+                        &self.0
+                    }
+                    fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                        "destination".into()
+                    }
+                }
+                Box::new(Fin(self.buffer))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                assert_eq!(num, 0);
+                &self.buffer
+            }
+
+            fn buffer_name(&self, _: usize) -> Cow<'static, str> {
+                "destination".into()
+            }
+        }
+
+        self.append_command(Cmd { buffer, data });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               true,
+                               PipelineStages {
+                                   transfer: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   transfer_write: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)
+            .unwrap();
+    }
+
+    /// Calls `vkCmdWaitEvents` on the builder.
+    ///
+    /// `source_stages` and `destination_stages` are added to the stages of the barrier. Like with
+    /// `pipeline_barrier`, the buffers and images of the barrier are registered as if they were
+    /// accessed by the destination stages and accesses of the barrier.
+    pub unsafe fn wait_events(&mut self, events: Vec<Arc<Event>>, source_stages: PipelineStages,
+                              destination_stages: PipelineStages, barrier: PipelineBarrier)
+                              -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
-            line_width: f32,
+            events: Vec<Arc<Event>>,
+            source_stages: PipelineStages,
+            destination_stages: PipelineStages,
+            barrier: PipelineBarrier,
         }
 
         impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdSetLineWidth"
+                "vkCmdWaitEvents"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.set_line_width(self.line_width);
+                let mut command = barrier_command(&self.barrier);
+                command.add_execution_dependency(self.source_stages,
+                                                 self.destination_stages,
+                                                 false);
+                out.wait_events(self.events.iter().map(|e| &**e), &command);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdSetLineWidth")
+                struct Fin(Vec<Arc<Event>>, PipelineBarrier);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdWaitEvents"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        &self.1.buffer_barriers[num].buffer
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("buffer barrier {}", num).into()
+                    }
+                    fn image(&self, num: usize) -> &ImageAccess {
+                        &self.1.image_barriers[num].image
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("image barrier {}", num).into()
+                    }
+                }
+                Box::new(Fin(self.events, self.barrier))
             }
-        }
-
-        self.append_command(Cmd { line_width });
-    }
-
-    // TODO: stencil states
 
-    /// Calls `vkCmdSetScissor` on the builder.
-    ///
-    /// If the list is empty then the command is automatically ignored.
-    #[inline]
-    pub unsafe fn set_scissor<I>(&mut self, first_scissor: u32, scissors: I)
-        where I: Iterator<Item = Scissor> + Send + Sync + 'static
-    {
-        struct Cmd<I> {
-            first_scissor: u32,
-            scissors: Option<I>,
-        }
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                &self.barrier.buffer_barriers[num].buffer
+            }
 
-        impl<P, I> Command<P> for Cmd<I>
-            where I: Iterator<Item = Scissor>
-        {
-            fn name(&self) -> &'static str {
-                "vkCmdSetScissor"
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                format!("buffer barrier {}", num).into()
             }
 
-            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.set_scissor(self.first_scissor, self.scissors.take().unwrap());
+            fn image(&self, num: usize) -> &ImageAccess {
+                &self.barrier.image_barriers[num].image
             }
 
-            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdSetScissor")
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                format!("image barrier {}", num).into()
             }
         }
 
+        let buffers = barrier
+            .buffer_barriers
+            .iter()
+            .map(|b| (b.destination_stages, b.destination_access))
+            .collect::<Vec<_>>();
+        let images = barrier
+            .image_barriers
+            .iter()
+            .map(|b| (b.destination_stages, b.destination_access, b.old_layout, b.new_layout))
+            .collect::<Vec<_>>();
+
         self.append_command(Cmd {
-                                first_scissor,
-                                scissors: Some(scissors),
+                                events,
+                                source_stages,
+                                destination_stages,
+                                barrier,
                             });
+
+        for (num, (stages, access)) in buffers.into_iter().enumerate() {
+            self.prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   true,
+                                   stages,
+                                   access,
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        for (num, (stages, access, old_layout, new_layout)) in images.into_iter().enumerate() {
+            self.prev_cmd_resource(KeyTy::Image,
+                                   num,
+                                   true,
+                                   stages,
+                                   access,
+                                   old_layout,
+                                   new_layout)?;
+        }
+
+        Ok(())
     }
 
-    /// Calls `vkCmdSetViewport` on the builder.
-    ///
-    /// If the list is empty then the command is automatically ignored.
-    #[inline]
-    pub unsafe fn set_viewport<I>(&mut self, first_viewport: u32, viewports: I)
-        where I: Iterator<Item = Viewport> + Send + Sync + 'static
-    {
-        struct Cmd<I> {
-            first_viewport: u32,
-            viewports: Option<I>,
+    /// Calls `vkCmdResetQueryPool` then `vkCmdWriteAccelerationStructuresPropertiesKHR` on the
+    /// builder, to write the compacted size of an acceleration structure in the query `query` of
+    /// `query_pool`.
+    pub unsafe fn write_acceleration_structure_compacted_size(
+        &mut self, acceleration_structure: Arc<AccelerationStructure>,
+        query_pool: Arc<UnsafeQueryPool>, query: u32)
+        -> Result<(), SyncCommandBufferBuilderError> {
+        struct Cmd {
+            acceleration_structure: Arc<AccelerationStructure>,
+            query_pool: Arc<UnsafeQueryPool>,
+            query: u32,
         }
 
-        impl<P, I> Command<P> for Cmd<I>
-            where I: Iterator<Item = Viewport>
-        {
+        impl<P> Command<P> for Cmd {
             fn name(&self) -> &'static str {
-                "vkCmdSetViewport"
+                "vkCmdWriteAccelerationStructuresPropertiesKHR"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.set_viewport(self.first_viewport, self.viewports.take().unwrap());
+                out.reset_query_pool(self.query_pool.queries_range(self.query, 1).unwrap());
+                out.write_acceleration_structure_compacted_size(&self.acceleration_structure,
+                                                                self.query_pool
+                                                                    .query(self.query)
+                                                                    .unwrap());
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                Box::new("vkCmdSetViewport")
+                struct Fin(Arc<AccelerationStructure>, Arc<UnsafeQueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdWriteAccelerationStructuresPropertiesKHR"
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        assert_eq!(num, 0);
+                        self.0.buffer()
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        assert_eq!(num, 0);
+                        "acceleration structure".into()
+                    }
+                }
+                Box::new(Fin(self.acceleration_structure, self.query_pool))
+            }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                assert_eq!(num, 0);
+                self.acceleration_structure.buffer()
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                assert_eq!(num, 0);
+                "acceleration structure".into()
             }
         }
 
         self.append_command(Cmd {
-                                first_viewport,
-                                viewports: Some(viewports),
+                                acceleration_structure,
+                                query_pool,
+                                query,
                             });
+        self.prev_cmd_resource(KeyTy::Buffer,
+                               0,
+                               false,
+                               PipelineStages {
+                                   acceleration_structure_build: true,
+                                   ..PipelineStages::none()
+                               },
+                               AccessFlagBits {
+                                   acceleration_structure_read: true,
+                                   ..AccessFlagBits::none()
+                               },
+                               ImageLayout::Undefined,
+                               ImageLayout::Undefined)?;
+        Ok(())
     }
 
-    /// Calls `vkCmdUpdateBuffer` on the builder.
+    /// Calls `vkCmdWriteBufferMarkerAMD` on the builder.
     #[inline]
-    pub unsafe fn update_buffer<B, D>(&mut self, buffer: B, data: D)
-        where B: BufferAccess + Send + Sync + 'static,
-              D: Send + Sync + 'static
+    pub unsafe fn write_buffer_marker<B>(&mut self, buffer: B, stage: PipelineStages,
+                                         marker: u32)
+                                         -> Result<(), SyncCommandBufferBuilderError>
+        where B: BufferAccess + Send + Sync + 'static
     {
-        struct Cmd<B, D> {
+        struct Cmd<B> {
             buffer: B,
-            data: D,
+            stage: PipelineStages,
+            marker: u32,
         }
 
-        impl<P, B, D> Command<P> for Cmd<B, D>
-            where B: BufferAccess + Send + Sync + 'static,
-                  D: Send + Sync + 'static
+        impl<P, B> Command<P> for Cmd<B>
+            where B: BufferAccess + Send + Sync + 'static
         {
             fn name(&self) -> &'static str {
-                "vkCmdUpdateBuffer"
+                "vkCmdWriteBufferMarkerAMD"
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.update_buffer(&self.buffer, &self.data);
+                out.write_buffer_marker(&self.buffer, self.stage, self.marker);
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
@@ -1781,7 +4095,7 @@ impl<P> SyncCommandBufferBuilder<P> {
                     where B: BufferAccess + Send + Sync + 'static
                 {
                     fn name(&self) -> &'static str {
-                        "vkCmdUpdateBuffer"
+                        "vkCmdWriteBufferMarkerAMD"
                     }
                     fn buffer(&self, num: usize) -> &BufferAccess {
                         assert_eq!(num, 0);
@@ -1804,7 +4118,12 @@ impl<P> SyncCommandBufferBuilder<P> {
             }
         }
 
-        self.append_command(Cmd { buffer, data });
+        self.append_command(Cmd {
+                                buffer,
+                                stage,
+                                marker,
+                            });
+        // The marker is written as if by a transfer command, after the given stage.
         self.prev_cmd_resource(KeyTy::Buffer,
                                0,
                                true,
@@ -1817,9 +4136,89 @@ impl<P> SyncCommandBufferBuilder<P> {
                                    ..AccessFlagBits::none()
                                },
                                ImageLayout::Undefined,
-                               ImageLayout::Undefined)
-            .unwrap();
+                               ImageLayout::Undefined)?;
+        Ok(())
+    }
+
+    /// Calls `vkCmdWriteTimestamp` on the builder.
+    #[inline]
+    pub unsafe fn write_timestamp(&mut self, query_pool: Arc<UnsafeQueryPool>, query: u32,
+                                  stage: PipelineStages) {
+        struct Cmd {
+            query_pool: Arc<UnsafeQueryPool>,
+            query: u32,
+            stage: PipelineStages,
+        }
+
+        impl<P> Command<P> for Cmd {
+            fn name(&self) -> &'static str {
+                "vkCmdWriteTimestamp"
+            }
+
+            unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
+                out.write_timestamp(self.query_pool.query(self.query).unwrap(), self.stage);
+            }
+
+            fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
+                struct Fin(Arc<UnsafeQueryPool>);
+                impl FinalCommand for Fin {
+                    fn name(&self) -> &'static str {
+                        "vkCmdWriteTimestamp"
+                    }
+                }
+                Box::new(Fin(self.query_pool))
+            }
+        }
+
+        self.append_command(Cmd {
+                                query_pool,
+                                query,
+                                stage,
+                            });
+    }
+}
+
+// Builds the low-level barrier command that corresponds to `barrier`.
+unsafe fn barrier_command(barrier: &PipelineBarrier) -> UnsafeCommandBufferBuilderPipelineBarrier {
+    let by_region = barrier.by_region;
+    let mut command = UnsafeCommandBufferBuilderPipelineBarrier::new();
+
+    for b in barrier.memory_barriers.iter() {
+        command.add_memory_barrier(b.source_stages,
+                                   b.source_access,
+                                   b.destination_stages,
+                                   b.destination_access,
+                                   by_region);
+    }
+
+    for b in barrier.buffer_barriers.iter() {
+        command.add_buffer_memory_barrier(&b.buffer,
+                                          b.source_stages,
+                                          b.source_access,
+                                          b.destination_stages,
+                                          b.destination_access,
+                                          by_region,
+                                          b.queue_family_transfer,
+                                          b.range.start,
+                                          b.range.end - b.range.start);
+    }
+
+    for b in barrier.image_barriers.iter() {
+        command.add_image_memory_barrier(&b.image,
+                                         b.mipmap_levels.clone(),
+                                         b.array_layers.clone(),
+                                         b.source_stages,
+                                         b.source_access,
+                                         b.destination_stages,
+                                         b.destination_access,
+                                         by_region,
+                                         b.queue_family_transfer,
+                                         b.old_layout,
+                                         b.new_layout);
     }
+
+
+    command
 }
 
 pub struct SyncCommandBufferBuilderBindDescriptorSets<'b, P: 'b> {
@@ -1837,8 +4236,8 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
     }
 
     #[inline]
-    pub unsafe fn submit<Pl, I>(self, graphics: bool, pipeline_layout: Pl, first_binding: u32,
-                                dynamic_offsets: I)
+    pub unsafe fn submit<Pl, I>(self, bind_point: PipelineBindPoint, pipeline_layout: Pl,
+                                first_binding: u32, dynamic_offsets: I)
                                 -> Result<(), SyncCommandBufferBuilderError>
         where Pl: PipelineLayoutAbstract + Send + Sync + 'static,
               I: Iterator<Item = u32> + Send + Sync + 'static
@@ -1849,7 +4248,7 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
 
         struct Cmd<Pl, I> {
             inner: SmallVec<[Box<DescriptorSet + Send + Sync>; 12]>,
-            graphics: bool,
+            bind_point: PipelineBindPoint,
             pipeline_layout: Pl,
             first_binding: u32,
             dynamic_offsets: Option<I>,
@@ -1864,7 +4263,7 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
             }
 
             unsafe fn send(&mut self, out: &mut UnsafeCommandBufferBuilder<P>) {
-                out.bind_descriptor_sets(self.graphics,
+                out.bind_descriptor_sets(self.bind_point,
                                          &self.pipeline_layout,
                                          self.first_binding,
                                          self.inner.iter().map(|s| s.inner()),
@@ -2019,7 +4418,7 @@ impl<'b, P> SyncCommandBufferBuilderBindDescriptorSets<'b, P> {
 
         self.builder.append_command(Cmd {
                                         inner: self.inner,
-                                        graphics,
+                                        bind_point,
                                         pipeline_layout,
                                         first_binding,
                                         dynamic_offsets: Some(dynamic_offsets),
@@ -2067,6 +4466,15 @@ impl<'a, P> SyncCommandBufferBuilderBindVertexBuffer<'a, P> {
         self.buffers.push(Box::new(buffer));
     }
 
+    /// Adds a buffer to the list, with a stride that overrides the one of the pipeline.
+    #[inline]
+    pub fn add_with_stride<B>(&mut self, buffer: B, stride: u32)
+        where B: BufferAccess + Send + Sync + 'static
+    {
+        self.inner.add_with_stride(&buffer, stride);
+        self.buffers.push(Box::new(buffer));
+    }
+
     #[inline]
     pub unsafe fn submit(self, first_binding: u32) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
@@ -2139,11 +4547,109 @@ impl<'a, P> SyncCommandBufferBuilderBindVertexBuffer<'a, P> {
 }
 
 /// Prototype for a `vkCmdExecuteCommands`.
-// FIXME: synchronization not implemented yet
+///
+/// The buffers and images that the secondary command buffers report with
+/// `CommandBuffer::buffer` and `CommandBuffer::image` are synchronized with the other commands,
+/// as if the secondary command buffers were a single command that accesses all of them.
 pub struct SyncCommandBufferBuilderExecuteCommands<'a, P: 'a> {
     builder: &'a mut SyncCommandBufferBuilder<P>,
     inner: UnsafeCommandBufferBuilderExecuteCommands,
-    command_buffers: Vec<Box<Any + Send + Sync>>,
+    command_buffers: Vec<Box<SecondaryCommandBuffer + Send + Sync>>,
+}
+
+// Object-safe version of the `CommandBuffer` trait, used to hold the secondary command buffers
+// whatever their pool.
+trait SecondaryCommandBuffer {
+    fn lock_submit(&self, future: &GpuFuture, queue: &Queue)
+                   -> Result<(), CommandBufferExecError>;
+    unsafe fn unlock(&self);
+    fn check_buffer_access(&self, buffer: &BufferAccess, exclusive: bool, queue: &Queue)
+                           -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError>;
+    fn check_image_access(&self, image: &ImageAccess, exclusive: bool, queue: &Queue)
+                          -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError>;
+    fn num_buffers(&self) -> usize;
+    fn buffer(&self, index: usize)
+              -> Option<(&BufferAccess, PipelineStages, AccessFlagBits, bool)>;
+    fn num_images(&self) -> usize;
+    fn image(&self, index: usize)
+             -> Option<(&ImageAccess, PipelineStages, AccessFlagBits, bool, ImageLayout,
+                        ImageLayout)>;
+}
+
+impl<C> SecondaryCommandBuffer for C
+    where C: CommandBuffer
+{
+    #[inline]
+    fn lock_submit(&self, future: &GpuFuture, queue: &Queue)
+                   -> Result<(), CommandBufferExecError> {
+        CommandBuffer::lock_submit(self, future, queue)
+    }
+
+    #[inline]
+    unsafe fn unlock(&self) {
+        CommandBuffer::unlock(self)
+    }
+
+    #[inline]
+    fn check_buffer_access(&self, buffer: &BufferAccess, exclusive: bool, queue: &Queue)
+                           -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        CommandBuffer::check_buffer_access(self, buffer, exclusive, queue)
+    }
+
+    #[inline]
+    fn check_image_access(&self, image: &ImageAccess, exclusive: bool, queue: &Queue)
+                          -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        // Only whether the command buffer uses the image matters, not its final layout.
+        CommandBuffer::check_image_access(self, image, ImageLayout::Undefined, exclusive, queue)
+    }
+
+    #[inline]
+    fn num_buffers(&self) -> usize {
+        CommandBuffer::num_buffers(self)
+    }
+
+    #[inline]
+    fn buffer(&self, index: usize)
+              -> Option<(&BufferAccess, PipelineStages, AccessFlagBits, bool)> {
+        CommandBuffer::buffer(self, index)
+    }
+
+    #[inline]
+    fn num_images(&self) -> usize {
+        CommandBuffer::num_images(self)
+    }
+
+    #[inline]
+    fn image(&self, index: usize)
+             -> Option<(&ImageAccess, PipelineStages, AccessFlagBits, bool, ImageLayout,
+                        ImageLayout)> {
+        CommandBuffer::image(self, index)
+    }
+}
+
+// Returns the `num`th buffer of a list of secondary command buffers, whose buffers are numbered
+// one command buffer after the other.
+fn secondary_buffer(command_buffers: &[Box<SecondaryCommandBuffer + Send + Sync>], mut num: usize)
+                    -> &BufferAccess {
+    for command_buffer in command_buffers {
+        if num < command_buffer.num_buffers() {
+            return command_buffer.buffer(num).unwrap().0;
+        }
+        num -= command_buffer.num_buffers();
+    }
+    panic!()
+}
+
+// Same as `secondary_buffer`, but for images.
+fn secondary_image(command_buffers: &[Box<SecondaryCommandBuffer + Send + Sync>], mut num: usize)
+                   -> &ImageAccess {
+    for command_buffer in command_buffers {
+        if num < command_buffer.num_images() {
+            return command_buffer.image(num).unwrap().0;
+        }
+        num -= command_buffer.num_images();
+    }
+    panic!()
 }
 
 impl<'a, P> SyncCommandBufferBuilderExecuteCommands<'a, P> {
@@ -2161,7 +4667,7 @@ impl<'a, P> SyncCommandBufferBuilderExecuteCommands<'a, P> {
     pub unsafe fn submit(self) -> Result<(), SyncCommandBufferBuilderError> {
         struct Cmd {
             inner: Option<UnsafeCommandBufferBuilderExecuteCommands>,
-            command_buffers: Vec<Box<Any + Send + Sync>>,
+            command_buffers: Vec<Box<SecondaryCommandBuffer + Send + Sync>>,
         }
 
         impl<P> Command<P> for Cmd {
@@ -2174,20 +4680,123 @@ impl<'a, P> SyncCommandBufferBuilderExecuteCommands<'a, P> {
             }
 
             fn into_final_command(self: Box<Self>) -> Box<FinalCommand + Send + Sync> {
-                struct Fin(Vec<Box<Any + Send + Sync>>);
+                struct Fin(Vec<Box<SecondaryCommandBuffer + Send + Sync>>);
                 impl FinalCommand for Fin {
                     fn name(&self) -> &'static str {
                         "vkCmdExecuteCommands"
                     }
+                    fn lock_submit(&self, future: &GpuFuture, queue: &Queue)
+                                   -> Result<(), CommandBufferExecError> {
+                        for (num, command_buffer) in self.0.iter().enumerate() {
+                            if let Err(err) = command_buffer.lock_submit(future, queue) {
+                                for command_buffer in self.0.iter().take(num) {
+                                    unsafe {
+                                        command_buffer.unlock();
+                                    }
+                                }
+                                return Err(err);
+                            }
+                        }
+                        Ok(())
+                    }
+                    unsafe fn unlock(&self) {
+                        for command_buffer in self.0.iter() {
+                            command_buffer.unlock();
+                        }
+                    }
+                    fn check_buffer_access(
+                        &self, buffer: &BufferAccess, exclusive: bool, queue: &Queue)
+                        -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+                        self.0
+                            .iter()
+                            .map(|cb| cb.check_buffer_access(buffer, exclusive, queue))
+                            .find(|r| r.is_ok())
+                            .unwrap_or(Err(AccessCheckError::Unknown))
+                    }
+                    fn check_image_access(
+                        &self, image: &ImageAccess, exclusive: bool, queue: &Queue)
+                        -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+                        self.0
+                            .iter()
+                            .map(|cb| cb.check_image_access(image, exclusive, queue))
+                            .find(|r| r.is_ok())
+                            .unwrap_or(Err(AccessCheckError::Unknown))
+                    }
+                    fn buffer(&self, num: usize) -> &BufferAccess {
+                        secondary_buffer(&self.0, num)
+                    }
+                    fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("buffer {}", num).into()
+                    }
+                    fn image(&self, num: usize) -> &ImageAccess {
+                        secondary_image(&self.0, num)
+                    }
+                    fn image_name(&self, num: usize) -> Cow<'static, str> {
+                        format!("image {}", num).into()
+                    }
                 }
                 Box::new(Fin(self.command_buffers))
             }
+
+            fn buffer(&self, num: usize) -> &BufferAccess {
+                secondary_buffer(&self.command_buffers, num)
+            }
+
+            fn buffer_name(&self, num: usize) -> Cow<'static, str> {
+                format!("buffer {}", num).into()
+            }
+
+            fn image(&self, num: usize) -> &ImageAccess {
+                secondary_image(&self.command_buffers, num)
+            }
+
+            fn image_name(&self, num: usize) -> Cow<'static, str> {
+                format!("image {}", num).into()
+            }
         }
 
+        let buffers = self.command_buffers
+            .iter()
+            .flat_map(|cb| (0 .. cb.num_buffers()).map(move |n| cb.buffer(n).unwrap()))
+            .map(|(_, stages, access, exclusive)| (exclusive, stages, access))
+            .collect::<Vec<_>>();
+        let images = self.command_buffers
+            .iter()
+            .flat_map(|cb| (0 .. cb.num_images()).map(move |n| cb.image(n).unwrap()))
+            .map(|(_, stages, access, exclusive, start_layout, end_layout)| {
+                     (exclusive, stages, access, start_layout, end_layout)
+                 })
+            .collect::<Vec<_>>();
+
         self.builder.append_command(Cmd {
                                         inner: Some(self.inner),
                                         command_buffers: self.command_buffers,
                                     });
+
+        for (num, (exclusive, stages, access)) in buffers.into_iter().enumerate() {
+            self.builder
+                .prev_cmd_resource(KeyTy::Buffer,
+                                   num,
+                                   exclusive,
+                                   stages,
+                                   access,
+                                   ImageLayout::Undefined,
+                                   ImageLayout::Undefined)?;
+        }
+
+        for (num, (exclusive, stages, access, start_layout, end_layout)) in
+            images.into_iter().enumerate()
+        {
+            self.builder
+                .prev_cmd_resource(KeyTy::Image,
+                                   num,
+                                   exclusive,
+                                   stages,
+                                   access,
+                                   start_layout,
+                                   end_layout)?;
+        }
+
         Ok(())
     }
 }
@@ -11,11 +11,15 @@ use std::iter;
 
 use buffer::BufferUsage;
 use buffer::CpuAccessibleBuffer;
+use command_buffer::RawCommandsBuffer;
+use command_buffer::RawCommandsResources;
 use command_buffer::synced::base::SyncCommandBufferBuilder;
 use command_buffer::synced::base::SyncCommandBufferBuilderError;
 use command_buffer::sys::Flags;
 use command_buffer::sys::Kind;
 use device::Device;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
 
 #[test]
 fn basic_creation() {
@@ -42,3 +46,42 @@ fn basic_conflict() {
         };
     }
 }
+
+#[test]
+fn raw_commands_synchronized_with_later_commands() {
+    unsafe {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = Device::standard_command_pool(&device, queue.family());
+        let mut sync = SyncCommandBufferBuilder::new(&pool, Kind::primary(), Flags::None).unwrap();
+
+        let buf = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let dest = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), 0u32).unwrap();
+
+        let mut resources = RawCommandsResources::new();
+        resources.buffers.push(RawCommandsBuffer {
+                                   buffer: buf.clone(),
+                                   stages: PipelineStages {
+                                       transfer: true,
+                                       ..PipelineStages::none()
+                                   },
+                                   access: AccessFlagBits {
+                                       transfer_write: true,
+                                       ..AccessFlagBits::none()
+                                   },
+                                   exclusive: true,
+                               });
+        sync.raw_commands(resources, |_, _| ()).unwrap();
+        sync.copy_buffer(buf.clone(), dest, iter::once((0, 0, 4))).unwrap();
+        let cb = sync.build().unwrap();
+
+        // The read of the copy conflicts with the write of the raw commands, so a barrier is
+        // inserted between them and the buffer is left in the state of the copy, instead of the
+        // accesses of both commands being merged.
+        let (stages, access) = cb.check_buffer_access(&*buf, true, &queue).unwrap().unwrap();
+        assert!(stages.transfer);
+        assert!(access.transfer_read);
+        assert!(!access.transfer_write);
+    }
+}
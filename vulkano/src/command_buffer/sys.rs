@@ -11,16 +11,25 @@ use smallvec::SmallVec;
 use std::fmt;
 use std::mem;
 use std::ops::Range;
+use std::os::raw::c_void;
 use std::ptr;
 use std::sync::Arc;
 use std::ffi::CStr;
 
 use OomError;
 use VulkanObject;
+use acceleration_structure::AccelerationStructure;
+use acceleration_structure::AccelerationStructureGeometry;
+use acceleration_structure::BuildAccelerationStructureFlags;
+use acceleration_structure::CopyMode;
 use buffer::BufferAccess;
 use buffer::BufferInner;
 use check_errors;
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
 use command_buffer::CommandBuffer;
+use command_buffer::MultiDrawIndexedInfo;
+use command_buffer::MultiDrawInfo;
 use command_buffer::pool::CommandPool;
 use command_buffer::pool::CommandPoolAlloc;
 use command_buffer::pool::CommandPoolBuilderAlloc;
@@ -44,16 +53,30 @@ use image::ImageLayout;
 use instance::QueueFamily;
 use pipeline::ComputePipelineAbstract;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::PipelineBindPoint;
+use pipeline::RayTracingPipelineAbstract;
+use pipeline::ShaderBindingTableRegion;
+use pipeline::ShaderBindingTableRegions;
+use pipeline::depth_stencil::Compare;
+use pipeline::depth_stencil::StencilFaces;
+use pipeline::depth_stencil::StencilOp;
 use pipeline::input_assembly::IndexType;
+use pipeline::input_assembly::PrimitiveTopology;
+use pipeline::raster::CullMode;
+use pipeline::raster::FrontFace;
 use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 use query::QueryPipelineStatisticFlags;
+use query::QueryResultElement;
+use query::QueryResultFlags;
 use query::UnsafeQueriesRange;
 use query::UnsafeQuery;
 use sampler::Filter;
 use sync::AccessFlagBits;
 use sync::Event;
 use sync::PipelineStages;
+use sync::access2_to_legacy;
+use sync::stages2_to_legacy;
 use vk;
 
 /// Determines the kind of command buffer that we want to create.
@@ -79,6 +102,13 @@ pub enum Kind<R, F> {
         /// Note that the `pipeline_statistics_query` feature must be enabled if any of the flags
         /// of this value are set.
         query_statistics_flags: QueryPipelineStatisticFlags,
+
+        /// Whether this secondary command buffer can be executed while conditional rendering is
+        /// active in the primary command buffer.
+        ///
+        /// Note that the `inherited_conditional_rendering` feature of the
+        /// `VK_EXT_conditional_rendering` extension must be enabled if this is true.
+        conditional_rendering: bool,
     },
 }
 
@@ -139,6 +169,7 @@ impl
             render_pass: None,
             occlusion_query,
             query_statistics_flags,
+            conditional_rendering: false,
         }
     }
 }
@@ -299,9 +330,32 @@ impl<P> UnsafeCommandBufferBuilder<P> {
             _ => (0, 0, 0),
         };
 
+        let conditional_rendering = match kind {
+            Kind::Secondary { conditional_rendering, .. } => {
+                debug_assert!(!conditional_rendering ||
+                                  alloc
+                                      .device()
+                                      .enabled_features()
+                                      .inherited_conditional_rendering);
+                conditional_rendering
+            },
+            _ => false,
+        };
+
+        let inheritance_conditional_rendering =
+            vk::CommandBufferInheritanceConditionalRenderingInfoEXT {
+                sType: vk::STRUCTURE_TYPE_COMMAND_BUFFER_INHERITANCE_CONDITIONAL_RENDERING_INFO_EXT,
+                pNext: ptr::null(),
+                conditionalRenderingEnable: vk::TRUE,
+            };
+
         let inheritance = vk::CommandBufferInheritanceInfo {
             sType: vk::STRUCTURE_TYPE_COMMAND_BUFFER_INHERITANCE_INFO,
-            pNext: ptr::null(),
+            pNext: if conditional_rendering {
+                &inheritance_conditional_rendering as *const _ as *const _
+            } else {
+                ptr::null()
+            },
             renderPass: rp,
             subpass: sp,
             framebuffer: fb,
@@ -353,6 +407,39 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         }
     }
 
+    /// Calls `vkCmdBeginConditionalRenderingEXT` on the builder.
+    ///
+    /// The commands that follow are discarded if the 32-bits value at the start of `buffer` is
+    /// zero, or non-zero if `inverted` is true.
+    #[inline]
+    pub unsafe fn begin_conditional_rendering<B>(&mut self, buffer: &B, inverted: bool)
+        where B: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        let BufferInner {
+            buffer: buffer_inner,
+            offset,
+        } = buffer.inner();
+        debug_assert!(buffer_inner.usage_conditional_rendering());
+        debug_assert_eq!(offset % 4, 0);
+
+        let infos = vk::ConditionalRenderingBeginInfoEXT {
+            sType: vk::STRUCTURE_TYPE_CONDITIONAL_RENDERING_BEGIN_INFO_EXT,
+            pNext: ptr::null(),
+            buffer: buffer_inner.internal_object(),
+            offset: offset as vk::DeviceSize,
+            flags: if inverted {
+                vk::CONDITIONAL_RENDERING_INVERTED_BIT_EXT
+            } else {
+                0
+            },
+        };
+
+        vk.CmdBeginConditionalRenderingEXT(cmd, &infos);
+    }
+
     /// Calls `vkCmdBeginQuery` on the builder.
     #[inline]
     pub unsafe fn begin_query(&mut self, query: UnsafeQuery, precise: bool) {
@@ -366,12 +453,69 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdBeginQuery(cmd, query.pool().internal_object(), query.index(), flags);
     }
 
+    /// Calls `vkCmdBeginTransformFeedbackEXT` on the builder.
+    ///
+    /// If `counter_buffers` is empty, the vertices are written at the start of the bound
+    /// transform feedback buffers. Otherwise, the writes resume at the byte counts stored in the
+    /// counter buffers.
+    #[inline]
+    pub unsafe fn begin_transform_feedback(
+        &mut self, counter_buffers: &UnsafeCommandBufferBuilderTransformFeedbackBuffers) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        let (buffers, offsets) = if counter_buffers.raw_buffers.is_empty() {
+            (ptr::null(), ptr::null())
+        } else {
+            (counter_buffers.raw_buffers.as_ptr(), counter_buffers.offsets.as_ptr())
+        };
+
+        vk.CmdBeginTransformFeedbackEXT(cmd,
+                                        0,
+                                        counter_buffers.raw_buffers.len() as u32,
+                                        buffers,
+                                        offsets);
+    }
+
     /// Calls `vkCmdBeginRenderPass` on the builder.
     #[inline]
     pub unsafe fn begin_render_pass<F, I>(&mut self, framebuffer: &F,
                                           subpass_contents: SubpassContents, clear_values: I)
         where F: ?Sized + FramebufferAbstract,
               I: Iterator<Item = ClearValue>
+    {
+        self.begin_render_pass_impl(framebuffer, subpass_contents, clear_values, None)
+    }
+
+    /// Calls `vkCmdBeginRenderPass` on the builder, with a `VkDeviceGroupRenderPassBeginInfo`
+    /// that restricts the render pass to the physical devices of `device_mask`.
+    ///
+    /// `render_areas` is either empty or contains the render area of each physical device of the
+    /// device.
+    #[inline]
+    pub unsafe fn begin_render_pass_device_group<F, I>(&mut self, framebuffer: &F,
+                                                       subpass_contents: SubpassContents,
+                                                       clear_values: I, device_mask: u32,
+                                                       render_areas: &[Scissor])
+        where F: ?Sized + FramebufferAbstract,
+              I: Iterator<Item = ClearValue>
+    {
+        debug_assert!(self.device().loaded_extensions().khr_device_group);
+        debug_assert_ne!(device_mask, 0);
+        debug_assert!(render_areas.is_empty() ||
+                          render_areas.len() as u32 == self.device().physical_device_count());
+
+        self.begin_render_pass_impl(framebuffer,
+                                    subpass_contents,
+                                    clear_values,
+                                    Some((device_mask, render_areas)))
+    }
+
+    unsafe fn begin_render_pass_impl<F, I>(&mut self, framebuffer: &F,
+                                           subpass_contents: SubpassContents, clear_values: I,
+                                           device_group: Option<(u32, &[Scissor])>)
+        where F: ?Sized + FramebufferAbstract,
+              I: Iterator<Item = ClearValue>
     {
         let vk = self.device().pointers();
         let cmd = self.internal_object();
@@ -427,9 +571,30 @@ impl<P> UnsafeCommandBufferBuilder<P> {
             0 .. framebuffer.dimensions()[1],
         ];
 
+        let device_group = device_group.map(|(device_mask, render_areas)| {
+            let render_areas = render_areas
+                .iter()
+                .map(|area| area.clone().into_vulkan_rect())
+                .collect::<SmallVec<[_; 4]>>();
+            (device_mask, render_areas)
+        });
+
+        let device_group_info = device_group.as_ref().map(|&(device_mask, ref render_areas)| {
+            vk::DeviceGroupRenderPassBeginInfoKHR {
+                sType: vk::STRUCTURE_TYPE_DEVICE_GROUP_RENDER_PASS_BEGIN_INFO_KHR,
+                pNext: ptr::null(),
+                deviceMask: device_mask,
+                deviceRenderAreaCount: render_areas.len() as u32,
+                pDeviceRenderAreas: render_areas.as_ptr(),
+            }
+        });
+
         let begin = vk::RenderPassBeginInfo {
             sType: vk::STRUCTURE_TYPE_RENDER_PASS_BEGIN_INFO,
-            pNext: ptr::null(),
+            pNext: device_group_info
+                .as_ref()
+                .map(|info| info as *const _ as *const _)
+                .unwrap_or(ptr::null()),
             renderPass: raw_render_pass,
             framebuffer: raw_framebuffer,
             renderArea: vk::Rect2D {
@@ -454,7 +619,7 @@ impl<P> UnsafeCommandBufferBuilder<P> {
     /// Does nothing if the list of descriptor sets is empty, as it would be a no-op and isn't a
     /// valid usage of the command anyway.
     #[inline]
-    pub unsafe fn bind_descriptor_sets<'s, Pl, S, I>(&mut self, graphics: bool,
+    pub unsafe fn bind_descriptor_sets<'s, Pl, S, I>(&mut self, bind_point: PipelineBindPoint,
                                                      pipeline_layout: &Pl, first_binding: u32,
                                                      sets: S, dynamic_offsets: I)
         where Pl: ?Sized + PipelineLayoutAbstract,
@@ -473,14 +638,8 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         let num_bindings = sets.len() as u32;
         debug_assert!(first_binding + num_bindings <= pipeline_layout.num_sets() as u32);
 
-        let bind_point = if graphics {
-            vk::PIPELINE_BIND_POINT_GRAPHICS
-        } else {
-            vk::PIPELINE_BIND_POINT_COMPUTE
-        };
-
         vk.CmdBindDescriptorSets(cmd,
-                                 bind_point,
+                                 bind_point as u32,
                                  pipeline_layout.sys().internal_object(),
                                  first_binding,
                                  num_bindings,
@@ -530,6 +689,47 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdBindPipeline(cmd, vk::PIPELINE_BIND_POINT_GRAPHICS, inner);
     }
 
+    /// Calls `vkCmdBindTransformFeedbackBuffersEXT` on the builder.
+    ///
+    /// Does nothing if the list of buffers is empty, as it would be a no-op and isn't a valid
+    /// usage of the command anyway.
+    #[inline]
+    pub unsafe fn bind_transform_feedback_buffers(
+        &mut self, first_binding: u32,
+        params: &UnsafeCommandBufferBuilderTransformFeedbackBuffers) {
+        if params.raw_buffers.is_empty() {
+            return;
+        }
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!({
+                          let max_bindings = self.device()
+                              .transform_feedback_properties()
+                              .max_transform_feedback_buffers;
+                          first_binding + params.raw_buffers.len() as u32 <= max_bindings
+                      });
+
+        vk.CmdBindTransformFeedbackBuffersEXT(cmd,
+                                              first_binding,
+                                              params.raw_buffers.len() as u32,
+                                              params.raw_buffers.as_ptr(),
+                                              params.offsets.as_ptr(),
+                                              params.sizes.as_ptr());
+    }
+
+    /// Calls `vkCmdBindPipeline` on the builder with a ray tracing pipeline.
+    #[inline]
+    pub unsafe fn bind_pipeline_ray_tracing<Rp>(&mut self, pipeline: &Rp)
+        where Rp: ?Sized + RayTracingPipelineAbstract
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        let inner = RayTracingPipelineAbstract::inner(pipeline).internal_object();
+        vk.CmdBindPipeline(cmd, vk::PIPELINE_BIND_POINT_RAY_TRACING_KHR, inner);
+    }
+
     /// Calls `vkCmdBindVertexBuffers` on the builder.
     ///
     /// Does nothing if the list of buffers is empty, as it would be a no-op and isn't a valid
@@ -556,11 +756,23 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                           first_binding + num_bindings <= max_bindings
                       });
 
-        vk.CmdBindVertexBuffers(cmd,
-                                first_binding,
-                                num_bindings,
-                                params.raw_buffers.as_ptr(),
-                                params.offsets.as_ptr());
+        if params.strides.is_empty() {
+            vk.CmdBindVertexBuffers(cmd,
+                                    first_binding,
+                                    num_bindings,
+                                    params.raw_buffers.as_ptr(),
+                                    params.offsets.as_ptr());
+        } else {
+            debug_assert_eq!(params.raw_buffers.len(), params.strides.len());
+            debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+            vk.CmdBindVertexBuffers2EXT(cmd,
+                                        first_binding,
+                                        num_bindings,
+                                        params.raw_buffers.as_ptr(),
+                                        params.offsets.as_ptr(),
+                                        ptr::null(),
+                                        params.strides.as_ptr());
+        }
     }
 
     /// Calls `vkCmdCopyImage` on the builder.
@@ -763,18 +975,144 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                         filter as u32);
     }
 
-    // TODO: missing structs
-    /*/// Calls `vkCmdClearAttachments` on the builder.
+    /// Calls `vkCmdBuildAccelerationStructuresKHR` on the builder, to build `destination` out
+    /// of `geometries`.
+    ///
+    /// If `source` is `Some`, the acceleration structure is updated from `source` instead of
+    /// being built from scratch. `scratch_address` is the device address of the scratch memory,
+    /// which must be aligned to `min_acceleration_structure_scratch_offset_alignment`.
+    pub unsafe fn build_acceleration_structure(&mut self, source: Option<&AccelerationStructure>,
+                                               destination: &AccelerationStructure,
+                                               flags: BuildAccelerationStructureFlags,
+                                               geometries: &[AccelerationStructureGeometry],
+                                               scratch_address: u64) {
+        debug_assert_eq!(scratch_address %
+                             self.device()
+                                 .ray_tracing_properties()
+                                 .min_acceleration_structure_scratch_offset_alignment
+                                 .max(1) as u64,
+                         0);
+
+        let vk_geometries: SmallVec<[_; 8]> = geometries.iter().map(|g| g.to_vulkan()).collect();
+        let ranges: SmallVec<[_; 8]> = geometries
+            .iter()
+            .map(|g| {
+                     vk::AccelerationStructureBuildRangeInfoKHR {
+                         primitiveCount: g.primitive_count(),
+                         primitiveOffset: 0,
+                         firstVertex: 0,
+                         transformOffset: 0,
+                     }
+                 })
+            .collect();
+
+        let (mode, source) = match source {
+            Some(source) => (vk::BUILD_ACCELERATION_STRUCTURE_MODE_UPDATE_KHR,
+                             source.internal_object()),
+            None => (vk::BUILD_ACCELERATION_STRUCTURE_MODE_BUILD_KHR, 0),
+        };
+
+        let infos = vk::AccelerationStructureBuildGeometryInfoKHR {
+            sType: vk::STRUCTURE_TYPE_ACCELERATION_STRUCTURE_BUILD_GEOMETRY_INFO_KHR,
+            pNext: ptr::null(),
+            ty: destination.ty() as u32,
+            flags: flags.into_vulkan_bits(),
+            mode: mode,
+            srcAccelerationStructure: source,
+            dstAccelerationStructure: destination.internal_object(),
+            geometryCount: vk_geometries.len() as u32,
+            pGeometries: vk_geometries.as_ptr(),
+            ppGeometries: ptr::null(),
+            scratchData: vk::DeviceOrHostAddressKHR { deviceAddress: scratch_address },
+        };
+
+        let ranges_ptr = ranges.as_ptr();
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdBuildAccelerationStructuresKHR(cmd, 1, &infos, &ranges_ptr);
+    }
+
+    /// Calls `vkCmdClearAttachments` on the builder.
     ///
     /// Does nothing if the list of attachments or the list of rects is empty, as it would be a
     /// no-op and isn't a valid usage of the command anyway.
     #[inline]
     pub unsafe fn clear_attachments<A, R>(&mut self, attachments: A, rects: R)
-        where A: Iterator<Item = >,
-              R: Iterator<Item = >
+        where A: Iterator<Item = ClearAttachment>,
+              R: Iterator<Item = ClearRect>
     {
-        let attachments: SmallVec<[_; 16]> = attachments.map().collect();
-        let rects: SmallVec<[_; 4]> = rects.map().collect();
+        let attachments: SmallVec<[_; 16]> = attachments
+            .map(|attachment| match attachment {
+                     ClearAttachment::Color(num, value) => {
+                         let color = match value {
+                             ClearValue::Float(val) => vk::ClearColorValue { float32: val },
+                             ClearValue::Int(val) => vk::ClearColorValue { int32: val },
+                             ClearValue::Uint(val) => vk::ClearColorValue { uint32: val },
+                             _ => vk::ClearColorValue { float32: [0.0; 4] },
+                         };
+
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_COLOR_BIT,
+                             colorAttachment: num,
+                             clearValue: vk::ClearValue { color },
+                         }
+                     },
+                     ClearAttachment::Depth(depth) => {
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_DEPTH_BIT,
+                             colorAttachment: 0,
+                             clearValue: vk::ClearValue {
+                                 depthStencil: vk::ClearDepthStencilValue {
+                                     depth,
+                                     stencil: 0,
+                                 },
+                             },
+                         }
+                     },
+                     ClearAttachment::Stencil(stencil) => {
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_STENCIL_BIT,
+                             colorAttachment: 0,
+                             clearValue: vk::ClearValue {
+                                 depthStencil: vk::ClearDepthStencilValue {
+                                     depth: 0.0,
+                                     stencil,
+                                 },
+                             },
+                         }
+                     },
+                     ClearAttachment::DepthStencil((depth, stencil)) => {
+                         vk::ClearAttachment {
+                             aspectMask: vk::IMAGE_ASPECT_DEPTH_BIT | vk::IMAGE_ASPECT_STENCIL_BIT,
+                             colorAttachment: 0,
+                             clearValue: vk::ClearValue {
+                                 depthStencil: vk::ClearDepthStencilValue { depth, stencil },
+                             },
+                         }
+                     },
+                 })
+            .collect();
+
+        let rects: SmallVec<[_; 4]> = rects
+            .map(|rect| {
+                debug_assert!(rect.layer_count >= 1);
+                vk::ClearRect {
+                    rect: vk::Rect2D {
+                        offset: vk::Offset2D {
+                            x: rect.offset[0] as i32,
+                            y: rect.offset[1] as i32,
+                        },
+                        extent: vk::Extent2D {
+                            width: rect.extent[0],
+                            height: rect.extent[1],
+                        },
+                    },
+                    baseArrayLayer: rect.base_array_layer,
+                    layerCount: rect.layer_count,
+                }
+            })
+            .collect();
 
         if attachments.is_empty() || rects.is_empty() {
             return;
@@ -782,9 +1120,12 @@ impl<P> UnsafeCommandBufferBuilder<P> {
 
         let vk = self.device().pointers();
         let cmd = self.internal_object();
-        vk.CmdClearAttachments(cmd, attachments.len() as u32, attachments.as_ptr(),
-                               rects.len() as u32, rects.as_ptr());
-    }*/
+        vk.CmdClearAttachments(cmd,
+                               attachments.len() as u32,
+                               attachments.as_ptr(),
+                               rects.len() as u32,
+                               rects.as_ptr());
+    }
 
     /// Calls `vkCmdClearColorImage` on the builder.
     ///
@@ -854,6 +1195,24 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                               regions.as_ptr());
     }
 
+    /// Calls `vkCmdCopyAccelerationStructureKHR` on the builder.
+    #[inline]
+    pub unsafe fn copy_acceleration_structure(&mut self, source: &AccelerationStructure,
+                                              destination: &AccelerationStructure,
+                                              mode: CopyMode) {
+        let infos = vk::CopyAccelerationStructureInfoKHR {
+            sType: vk::STRUCTURE_TYPE_COPY_ACCELERATION_STRUCTURE_INFO_KHR,
+            pNext: ptr::null(),
+            src: source.internal_object(),
+            dst: destination.internal_object(),
+            mode: mode as u32,
+        };
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdCopyAccelerationStructureKHR(cmd, &infos);
+    }
+
     /// Calls `vkCmdCopyBuffer` on the builder.
     ///
     /// Does nothing if the list of regions is empty, as it would be a no-op and isn't a valid
@@ -1027,14 +1386,22 @@ impl<P> UnsafeCommandBufferBuilder<P> {
     }
 
     /// Calls `vkCmdCopyQueryPoolResults` on the builder.
+    ///
+    /// The results are written as values of type `T`.
     #[inline]
-    pub unsafe fn copy_query_pool_results(&mut self, queries: UnsafeQueriesRange,
-                                          destination: &BufferAccess, stride: usize) {
+    pub unsafe fn copy_query_pool_results<T>(&mut self, queries: UnsafeQueriesRange,
+                                             destination: &BufferAccess, stride: usize,
+                                             flags: QueryResultFlags)
+        where T: QueryResultElement
+    {
         let destination = destination.inner();
         debug_assert!(destination.offset < destination.buffer.size());
         debug_assert!(destination.buffer.usage_transfer_destination());
 
-        let flags = 0; // FIXME:
+        let mut flags = flags.into_vulkan_bits();
+        if T::is_64_bits() {
+            flags |= vk::QUERY_RESULT_64_BIT;
+        }
 
         let vk = self.device().pointers();
         let cmd = self.internal_object();
@@ -1065,6 +1432,32 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdDispatch(cmd, dimensions[0], dimensions[1], dimensions[2]);
     }
 
+    /// Calls `vkCmdDispatchBaseKHR` on the builder.
+    ///
+    /// # Panics
+    /// Requires the `VK_KHR_device_group` device extension to be loaded.
+    #[inline]
+    pub unsafe fn dispatch_base(&mut self, base: [u32; 3], dimensions: [u32; 3]) {
+        debug_assert!({
+                          let max_dims = self.device()
+                              .physical_device()
+                              .limits()
+                              .max_compute_work_group_count();
+                          (0 .. 3).all(|i| base[i] as u64 + dimensions[i] as u64 <=
+                                           max_dims[i] as u64)
+                      });
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdDispatchBaseKHR(cmd,
+                              base[0],
+                              base[1],
+                              base[2],
+                              dimensions[0],
+                              dimensions[1],
+                              dimensions[2]);
+    }
+
     /// Calls `vkCmdDispatchIndirect` on the builder.
     #[inline]
     pub unsafe fn dispatch_indirect<B>(&mut self, buffer: &B)
@@ -1133,6 +1526,32 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                            stride);
     }
 
+    /// Calls `vkCmdDrawIndirectByteCountEXT` on the builder.
+    ///
+    /// The number of vertices is the 32-bits byte count stored at the start of `counter_buffer`,
+    /// minus `counter_offset`, divided by `vertex_stride`.
+    #[inline]
+    pub unsafe fn draw_indirect_byte_count<B>(&mut self, instance_count: u32, first_instance: u32,
+                                              counter_buffer: &B, counter_offset: u32,
+                                              vertex_stride: u32)
+        where B: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        let inner = counter_buffer.inner();
+        debug_assert!(inner.buffer.usage_transform_feedback_counter_buffer());
+        debug_assert_eq!(inner.offset % 4, 0);
+
+        vk.CmdDrawIndirectByteCountEXT(cmd,
+                                       instance_count,
+                                       first_instance,
+                                       inner.buffer.internal_object(),
+                                       inner.offset as vk::DeviceSize,
+                                       counter_offset,
+                                       vertex_stride);
+    }
+
     /// Calls `vkCmdDrawIndexedIndirect` on the builder.
     #[inline]
     pub unsafe fn draw_indexed_indirect<B>(&mut self, buffer: &B, draw_count: u32, stride: u32)
@@ -1152,6 +1571,151 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                                   stride);
     }
 
+    /// Calls `vkCmdDrawMultiEXT` on the builder.
+    ///
+    /// Does nothing if `draws` is empty.
+    #[inline]
+    pub unsafe fn draw_multi(&mut self, draws: &[MultiDrawInfo], instance_count: u32,
+                             first_instance: u32) {
+        if draws.is_empty() {
+            return;
+        }
+
+        debug_assert!(self.device().loaded_extensions().ext_multi_draw);
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdDrawMultiEXT(cmd,
+                           draws.len() as u32,
+                           draws.as_ptr() as *const vk::MultiDrawInfoEXT,
+                           instance_count,
+                           first_instance,
+                           mem::size_of::<MultiDrawInfo>() as u32);
+    }
+
+    /// Calls `vkCmdDrawMultiIndexedEXT` on the builder. The vertex offset of each draw is used.
+    ///
+    /// Does nothing if `draws` is empty.
+    #[inline]
+    pub unsafe fn draw_multi_indexed(&mut self, draws: &[MultiDrawIndexedInfo],
+                                     instance_count: u32, first_instance: u32) {
+        if draws.is_empty() {
+            return;
+        }
+
+        debug_assert!(self.device().loaded_extensions().ext_multi_draw);
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdDrawMultiIndexedEXT(cmd,
+                                  draws.len() as u32,
+                                  draws.as_ptr() as *const vk::MultiDrawIndexedInfoEXT,
+                                  instance_count,
+                                  first_instance,
+                                  mem::size_of::<MultiDrawIndexedInfo>() as u32,
+                                  ptr::null());
+    }
+
+    /// Calls `vkCmdDrawIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indirect_count<B, C>(&mut self, buffer: &B, count_buffer: &C,
+                                            max_draw_count: u32, stride: u32)
+        where B: ?Sized + BufferAccess,
+              C: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(((stride % 4) == 0) &&
+                          stride as usize >= mem::size_of::<vk::DrawIndirectCommand>());
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+
+        let count_inner = count_buffer.inner();
+        debug_assert!(count_inner.offset % 4 == 0);
+        debug_assert!(count_inner.buffer.usage_indirect_buffer());
+
+        vk.CmdDrawIndirectCountKHR(cmd,
+                                   inner.buffer.internal_object(),
+                                   inner.offset as vk::DeviceSize,
+                                   count_inner.buffer.internal_object(),
+                                   count_inner.offset as vk::DeviceSize,
+                                   max_draw_count,
+                                   stride);
+    }
+
+    /// Calls `vkCmdDrawIndexedIndirectCountKHR` on the builder.
+    #[inline]
+    pub unsafe fn draw_indexed_indirect_count<B, C>(&mut self, buffer: &B, count_buffer: &C,
+                                                    max_draw_count: u32, stride: u32)
+        where B: ?Sized + BufferAccess,
+              C: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(((stride % 4) == 0) &&
+                          stride as usize >= mem::size_of::<vk::DrawIndexedIndirectCommand>());
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+
+        let count_inner = count_buffer.inner();
+        debug_assert!(count_inner.offset % 4 == 0);
+        debug_assert!(count_inner.buffer.usage_indirect_buffer());
+
+        vk.CmdDrawIndexedIndirectCountKHR(cmd,
+                                          inner.buffer.internal_object(),
+                                          inner.offset as vk::DeviceSize,
+                                          count_inner.buffer.internal_object(),
+                                          count_inner.offset as vk::DeviceSize,
+                                          max_draw_count,
+                                          stride);
+    }
+
+    /// Calls `vkCmdDrawMeshTasksEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks(&mut self, group_count: [u32; 3]) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdDrawMeshTasksEXT(cmd, group_count[0], group_count[1], group_count[2]);
+    }
+
+    /// Calls `vkCmdDrawMeshTasksIndirectEXT` on the builder.
+    #[inline]
+    pub unsafe fn draw_mesh_tasks_indirect<B>(&mut self, buffer: &B, draw_count: u32, stride: u32)
+        where B: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert!(draw_count == 0 ||
+                          ((stride % 4) == 0) &&
+                              stride as usize >=
+                                  mem::size_of::<vk::DrawMeshTasksIndirectCommandEXT>());
+
+        let inner = buffer.inner();
+        debug_assert!(inner.offset < inner.buffer.size());
+        debug_assert!(inner.buffer.usage_indirect_buffer());
+
+        vk.CmdDrawMeshTasksIndirectEXT(cmd,
+                                       inner.buffer.internal_object(),
+                                       inner.offset as vk::DeviceSize,
+                                       draw_count,
+                                       stride);
+    }
+
+    /// Calls `vkCmdEndConditionalRenderingEXT` on the builder.
+    #[inline]
+    pub unsafe fn end_conditional_rendering(&mut self) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdEndConditionalRenderingEXT(cmd);
+    }
+
     /// Calls `vkCmdEndQuery` on the builder.
     #[inline]
     pub unsafe fn end_query(&mut self, query: UnsafeQuery) {
@@ -1168,6 +1732,29 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdEndRenderPass(cmd);
     }
 
+    /// Calls `vkCmdEndTransformFeedbackEXT` on the builder.
+    ///
+    /// If `counter_buffers` isn't empty, the number of bytes written to each transform feedback
+    /// buffer is stored in the corresponding counter buffer.
+    #[inline]
+    pub unsafe fn end_transform_feedback(
+        &mut self, counter_buffers: &UnsafeCommandBufferBuilderTransformFeedbackBuffers) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        let (buffers, offsets) = if counter_buffers.raw_buffers.is_empty() {
+            (ptr::null(), ptr::null())
+        } else {
+            (counter_buffers.raw_buffers.as_ptr(), counter_buffers.offsets.as_ptr())
+        };
+
+        vk.CmdEndTransformFeedbackEXT(cmd,
+                                      0,
+                                      counter_buffers.raw_buffers.len() as u32,
+                                      buffers,
+                                      offsets);
+    }
+
     /// Calls `vkCmdExecuteCommands` on the builder.
     ///
     /// Does nothing if the list of command buffers is empty, as it would be a no-op and isn't a
@@ -1218,6 +1805,17 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdNextSubpass(cmd, subpass_contents as u32);
     }
 
+    /// Calls `f` with the function pointers of the device and the raw command buffer, so that it
+    /// can record commands that vulkano doesn't wrap.
+    #[inline]
+    pub unsafe fn raw_commands<F>(&mut self, f: F)
+        where F: FnOnce(&vk::DevicePointers, vk::CommandBuffer)
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        f(vk, cmd);
+    }
+
     #[inline]
     pub unsafe fn pipeline_barrier(&mut self, command: &UnsafeCommandBufferBuilderPipelineBarrier) {
         // If barrier is empty, don't do anything.
@@ -1235,16 +1833,35 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         debug_assert_ne!(command.src_stage_mask, 0);
         debug_assert_ne!(command.dst_stage_mask, 0);
 
+        if self.device().synchronization2_enabled() {
+            let infos = vk::DependencyInfoKHR {
+                sType: vk::STRUCTURE_TYPE_DEPENDENCY_INFO_KHR,
+                pNext: ptr::null(),
+                dependencyFlags: command.dependency_flags,
+                memoryBarrierCount: command.memory_barriers.len() as u32,
+                pMemoryBarriers: command.memory_barriers.as_ptr(),
+                bufferMemoryBarrierCount: command.buffer_barriers.len() as u32,
+                pBufferMemoryBarriers: command.buffer_barriers.as_ptr(),
+                imageMemoryBarrierCount: command.image_barriers.len() as u32,
+                pImageMemoryBarriers: command.image_barriers.as_ptr(),
+            };
+
+            vk.CmdPipelineBarrier2KHR(cmd, &infos);
+            return;
+        }
+
+        let (memory_barriers, buffer_barriers, image_barriers) = command.legacy_barriers();
+
         vk.CmdPipelineBarrier(cmd,
-                              command.src_stage_mask,
-                              command.dst_stage_mask,
+                              stages2_to_legacy(command.src_stage_mask),
+                              stages2_to_legacy(command.dst_stage_mask),
                               command.dependency_flags,
-                              command.memory_barriers.len() as u32,
-                              command.memory_barriers.as_ptr(),
-                              command.buffer_barriers.len() as u32,
-                              command.buffer_barriers.as_ptr(),
-                              command.image_barriers.len() as u32,
-                              command.image_barriers.as_ptr());
+                              memory_barriers.len() as u32,
+                              memory_barriers.as_ptr(),
+                              buffer_barriers.len() as u32,
+                              buffer_barriers.as_ptr(),
+                              image_barriers.len() as u32,
+                              image_barriers.as_ptr());
     }
 
     /// Calls `vkCmdPushConstants` on the builder.
@@ -1302,6 +1919,61 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdSetBlendConstants(cmd, constants); // TODO: correct to pass array?
     }
 
+    /// Calls `vkCmdSetCullModeEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_cull_mode(&mut self, cull_mode: CullMode) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetCullModeEXT(cmd, cull_mode as u32);
+    }
+
+    /// Calls `vkCmdSetDepthBoundsTestEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_bounds_test_enable(&mut self, enable: bool) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetDepthBoundsTestEnableEXT(cmd, if enable { vk::TRUE } else { vk::FALSE });
+    }
+
+    /// Calls `vkCmdSetDepthCompareOpEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_compare_op(&mut self, compare_op: Compare) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetDepthCompareOpEXT(cmd, compare_op as u32);
+    }
+
+    /// Calls `vkCmdSetDepthTestEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_test_enable(&mut self, enable: bool) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetDepthTestEnableEXT(cmd, if enable { vk::TRUE } else { vk::FALSE });
+    }
+
+    /// Calls `vkCmdSetDepthWriteEnableEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_depth_write_enable(&mut self, enable: bool) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetDepthWriteEnableEXT(cmd, if enable { vk::TRUE } else { vk::FALSE });
+    }
+
+    /// Calls `vkCmdSetLineStippleEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_line_stipple(&mut self, factor: u32, pattern: u16) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_line_rasterization);
+        debug_assert!(factor >= 1 && factor <= 256);
+        vk.CmdSetLineStippleEXT(cmd, factor, pattern);
+    }
+
     /// Calls `vkCmdSetDepthBias` on the builder.
     #[inline]
     pub unsafe fn set_depth_bias(&mut self, constant_factor: f32, clamp: f32, slope_factor: f32) {
@@ -1321,6 +1993,25 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdSetDepthBounds(cmd, min, max);
     }
 
+    /// Calls `vkCmdSetCheckpointNV` on the builder.
+    #[inline]
+    pub unsafe fn set_checkpoint(&mut self, marker: usize) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().nv_device_diagnostic_checkpoints);
+        vk.CmdSetCheckpointNV(cmd, marker as *const c_void);
+    }
+
+    /// Calls `vkCmdSetDeviceMaskKHR` on the builder.
+    #[inline]
+    pub unsafe fn set_device_mask(&mut self, device_mask: u32) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().khr_device_group);
+        debug_assert_ne!(device_mask, 0);
+        vk.CmdSetDeviceMaskKHR(cmd, device_mask);
+    }
+
     /// Calls `vkCmdSetEvent` on the builder.
     #[inline]
     pub unsafe fn set_event(&mut self, event: &Event, stages: PipelineStages) {
@@ -1333,6 +2024,15 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdSetEvent(cmd, event.internal_object(), stages.into_vulkan_bits());
     }
 
+    /// Calls `vkCmdSetFrontFaceEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_front_face(&mut self, front_face: FrontFace) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetFrontFaceEXT(cmd, front_face as u32);
+    }
+
     /// Calls `vkCmdSetLineWidth` on the builder.
     #[inline]
     pub unsafe fn set_line_width(&mut self, line_width: f32) {
@@ -1342,32 +2042,63 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         vk.CmdSetLineWidth(cmd, line_width);
     }
 
-    // TODO: missing enum for face_mask
-    /*/// Calls `vkCmdSetStencilCompareMask` on the builder.
+    /// Calls `vkCmdSetPrimitiveTopologyEXT` on the builder.
+    #[inline]
+    pub unsafe fn set_primitive_topology(&mut self, topology: PrimitiveTopology) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetPrimitiveTopologyEXT(cmd, topology.into());
+    }
+
+    /// Calls `vkCmdSetStencilCompareMask` on the builder.
+    #[inline]
+    pub unsafe fn set_stencil_compare_mask(&mut self, face_mask: StencilFaces, compare_mask: u32) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdSetStencilCompareMask(cmd, face_mask.into(), compare_mask);
+    }
+
+    /// Calls `vkCmdSetStencilReference` on the builder.
     #[inline]
-    pub unsafe fn set_stencil_compare_mask(&mut self, face_mask: , compare_mask: u32) {
+    pub unsafe fn set_stencil_reference(&mut self, face_mask: StencilFaces, reference: u32) {
         let vk = self.device().pointers();
         let cmd = self.internal_object();
-        vk.CmdSetStencilCompareMask(cmd, face_mask, compare_mask);
-    }*/
+        vk.CmdSetStencilReference(cmd, face_mask.into(), reference);
+    }
 
-    // TODO: missing enum for face_mask
-    /*/// Calls `vkCmdSetStencilReference` on the builder.
+    /// Calls `vkCmdSetStencilOpEXT` on the builder.
     #[inline]
-    pub unsafe fn set_stencil_reference(&mut self, face_mask: , reference: u32) {
+    pub unsafe fn set_stencil_op(&mut self, face_mask: StencilFaces, fail_op: StencilOp,
+                                 pass_op: StencilOp, depth_fail_op: StencilOp,
+                                 compare_op: Compare) {
         let vk = self.device().pointers();
         let cmd = self.internal_object();
-        vk.CmdSetStencilReference(cmd, face_mask, reference);
-    }*/
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetStencilOpEXT(cmd,
+                              face_mask.into(),
+                              fail_op as u32,
+                              pass_op as u32,
+                              depth_fail_op as u32,
+                              compare_op as u32);
+    }
 
-    // TODO: missing enum for face_mask
-    /*/// Calls `vkCmdSetStencilWriteMask` on the builder.
+    /// Calls `vkCmdSetStencilTestEnableEXT` on the builder.
     #[inline]
-    pub unsafe fn set_stencil_write_mask(&mut self, face_mask: , write_mask: u32) {
+    pub unsafe fn set_stencil_test_enable(&mut self, enable: bool) {
         let vk = self.device().pointers();
         let cmd = self.internal_object();
-        vk.CmdSetStencilWriteMask(cmd, face_mask, write_mask);
-    }*/
+        debug_assert!(self.device().loaded_extensions().ext_extended_dynamic_state);
+        vk.CmdSetStencilTestEnableEXT(cmd, if enable { vk::TRUE } else { vk::FALSE });
+    }
+
+    /// Calls `vkCmdSetStencilWriteMask` on the builder.
+    #[inline]
+    pub unsafe fn set_stencil_write_mask(&mut self, face_mask: StencilFaces, write_mask: u32) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdSetStencilWriteMask(cmd, face_mask.into(), write_mask);
+    }
 
     /// Calls `vkCmdSetScissor` on the builder.
     ///
@@ -1430,6 +2161,43 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                           viewports.as_ptr());
     }
 
+    /// Calls `vkCmdTraceRaysKHR` on the builder.
+    ///
+    /// `shader_binding_table` is the device address of the buffer containing the shader
+    /// binding table, and the offsets of `regions` are relative to it.
+    pub unsafe fn trace_rays(&mut self, shader_binding_table: u64,
+                             regions: &ShaderBindingTableRegions, dimensions: [u32; 3]) {
+        let region = |r: &ShaderBindingTableRegion| if r.size == 0 {
+            vk::StridedDeviceAddressRegionKHR {
+                deviceAddress: 0,
+                stride: 0,
+                size: 0,
+            }
+        } else {
+            vk::StridedDeviceAddressRegionKHR {
+                deviceAddress: shader_binding_table + r.offset as u64,
+                stride: r.stride as vk::DeviceSize,
+                size: r.size as vk::DeviceSize,
+            }
+        };
+
+        let raygen = region(&regions.raygen);
+        let miss = region(&regions.miss);
+        let hit = region(&regions.hit);
+        let callable = region(&regions.callable);
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdTraceRaysKHR(cmd,
+                           &raygen,
+                           &miss,
+                           &hit,
+                           &callable,
+                           dimensions[0],
+                           dimensions[1],
+                           dimensions[2]);
+    }
+
     /// Calls `vkCmdUpdateBuffer` on the builder.
     #[inline]
     pub unsafe fn update_buffer<B, D>(&mut self, buffer: &B, data: &D)
@@ -1461,6 +2229,69 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                            data as *const D as *const _);
     }
 
+    /// Calls `vkCmdWaitEvents` on the builder.
+    ///
+    /// The stage masks and the memory barriers of `command` are used for the wait. Its dependency
+    /// flags are ignored, as waiting for events can't create a dependency by region.
+    #[inline]
+    pub unsafe fn wait_events<'a, I>(&mut self, events: I,
+                                     command: &UnsafeCommandBufferBuilderPipelineBarrier)
+        where I: Iterator<Item = &'a Event>
+    {
+        let events: SmallVec<[_; 8]> = events.map(|e| e.internal_object()).collect();
+        if events.is_empty() {
+            return;
+        }
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+
+        debug_assert_ne!(command.src_stage_mask, 0);
+        debug_assert_ne!(command.dst_stage_mask, 0);
+
+        let (memory_barriers, buffer_barriers, image_barriers) = command.legacy_barriers();
+
+        vk.CmdWaitEvents(cmd,
+                         events.len() as u32,
+                         events.as_ptr(),
+                         stages2_to_legacy(command.src_stage_mask),
+                         stages2_to_legacy(command.dst_stage_mask),
+                         memory_barriers.len() as u32,
+                         memory_barriers.as_ptr(),
+                         buffer_barriers.len() as u32,
+                         buffer_barriers.as_ptr(),
+                         image_barriers.len() as u32,
+                         image_barriers.as_ptr());
+    }
+
+    /// Calls `vkCmdWriteBufferMarkerAMD` on the builder.
+    #[inline]
+    pub unsafe fn write_buffer_marker<B>(&mut self, buffer: &B, stage: PipelineStages,
+                                         marker: u32)
+        where B: ?Sized + BufferAccess
+    {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        debug_assert!(self.device().loaded_extensions().amd_buffer_marker);
+        debug_assert_eq!(stage.into_vulkan_bits().count_ones(), 1);
+
+        let (buffer_handle, offset) = {
+            let BufferInner {
+                buffer: buffer_inner,
+                offset,
+            } = buffer.inner();
+            debug_assert!(buffer_inner.usage_transfer_destination());
+            debug_assert_eq!(offset % 4, 0);
+            (buffer_inner.internal_object(), offset)
+        };
+
+        vk.CmdWriteBufferMarkerAMD(cmd,
+                                   stage.into_vulkan_bits(),
+                                   buffer_handle,
+                                   offset as vk::DeviceSize,
+                                   marker);
+    }
+
     /// Calls `vkCmdWriteTimestamp` on the builder.
     #[inline]
     pub unsafe fn write_timestamp(&mut self, query: UnsafeQuery, stages: PipelineStages) {
@@ -1472,6 +2303,25 @@ impl<P> UnsafeCommandBufferBuilder<P> {
                              query.index());
     }
 
+    /// Calls `vkCmdWriteAccelerationStructuresPropertiesKHR` on the builder, to write the
+    /// compacted size of an acceleration structure in a query.
+    #[inline]
+    pub unsafe fn write_acceleration_structure_compacted_size(&mut self,
+                                                              acceleration_structure: &AccelerationStructure,
+                                                              query: UnsafeQuery) {
+        let handle = acceleration_structure.internal_object();
+
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdWriteAccelerationStructuresPropertiesKHR(
+            cmd,
+            1,
+            &handle,
+            vk::QUERY_TYPE_ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR,
+            query.pool().internal_object(),
+            query.index());
+    }
+
     /// Calls `vkCmdDebugMarkerBeginEXT` on the builder.
     ///
     /// # Panics
@@ -1530,6 +2380,65 @@ impl<P> UnsafeCommandBufferBuilder<P> {
         };
         vk.CmdDebugMarkerInsertEXT(cmd, &info);
     }
+
+    /// Calls `vkCmdBeginDebugUtilsLabelEXT` on the builder.
+    ///
+    /// # Panics
+    /// Requires the `VK_EXT_debug_utils` instance extension to be loaded.
+    ///
+    /// # Safety
+    /// The command pool that this command buffer was allocated from must support graphics or
+    /// compute operations
+    #[inline]
+    pub unsafe fn begin_debug_utils_label(&mut self, name: &CStr, color: [f32; 4]) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        let info = vk::DebugUtilsLabelEXT {
+            sType: vk::STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT,
+            pNext: ptr::null(),
+            pLabelName: name.as_ptr(),
+            color: color,
+        };
+        vk.CmdBeginDebugUtilsLabelEXT(cmd, &info);
+    }
+
+    /// Calls `vkCmdEndDebugUtilsLabelEXT` on the builder.
+    ///
+    /// # Panics
+    /// Requires the `VK_EXT_debug_utils` instance extension to be loaded.
+    ///
+    /// # Safety
+    /// There must be an outstanding `vkCmdBeginDebugUtilsLabelEXT` command prior to the
+    /// `vkCmdEndDebugUtilsLabelEXT` on the queue that this command buffer is submitted to. If the
+    /// command buffer is a secondary command buffer, the matching `vkCmdBeginDebugUtilsLabelEXT`
+    /// must be in the same command buffer.
+    #[inline]
+    pub unsafe fn end_debug_utils_label(&mut self) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        vk.CmdEndDebugUtilsLabelEXT(cmd);
+    }
+
+    /// Calls `vkCmdInsertDebugUtilsLabelEXT` on the builder.
+    ///
+    /// # Panics
+    /// Requires the `VK_EXT_debug_utils` instance extension to be loaded.
+    ///
+    /// # Safety
+    /// The command pool that this command buffer was allocated from must support graphics or
+    /// compute operations
+    #[inline]
+    pub unsafe fn insert_debug_utils_label(&mut self, name: &CStr, color: [f32; 4]) {
+        let vk = self.device().pointers();
+        let cmd = self.internal_object();
+        let info = vk::DebugUtilsLabelEXT {
+            sType: vk::STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT,
+            pNext: ptr::null(),
+            pLabelName: name.as_ptr(),
+            color: color,
+        };
+        vk.CmdInsertDebugUtilsLabelEXT(cmd, &info);
+    }
 }
 
 unsafe impl<P> DeviceOwned for UnsafeCommandBufferBuilder<P> {
@@ -1557,6 +2466,8 @@ pub struct UnsafeCommandBufferBuilderBindVertexBuffer {
     raw_buffers: SmallVec<[vk::Buffer; 4]>,
     // Raw offsets of the buffers to bind.
     offsets: SmallVec<[vk::DeviceSize; 4]>,
+    // Strides of the buffers to bind, or empty if the strides of the pipeline must be used.
+    strides: SmallVec<[vk::DeviceSize; 4]>,
 }
 
 impl UnsafeCommandBufferBuilderBindVertexBuffer {
@@ -1566,6 +2477,7 @@ impl UnsafeCommandBufferBuilderBindVertexBuffer {
         UnsafeCommandBufferBuilderBindVertexBuffer {
             raw_buffers: SmallVec::new(),
             offsets: SmallVec::new(),
+            strides: SmallVec::new(),
         }
     }
 
@@ -1579,6 +2491,52 @@ impl UnsafeCommandBufferBuilderBindVertexBuffer {
         self.raw_buffers.push(inner.buffer.internal_object());
         self.offsets.push(inner.offset as vk::DeviceSize);
     }
+
+    /// Adds a buffer to the list, with a stride that overrides the one of the pipeline.
+    ///
+    /// The pipeline must have the vertex input binding stride set as dynamic. Either all the
+    /// buffers or none of them must have a stride.
+    #[inline]
+    pub fn add_with_stride<B>(&mut self, buffer: &B, stride: u32)
+        where B: ?Sized + BufferAccess
+    {
+        self.add(buffer);
+        self.strides.push(stride as vk::DeviceSize);
+    }
+}
+
+/// Prototype for the buffers of a `vkCmdBindTransformFeedbackBuffersEXT`, or the counter buffers
+/// of a `vkCmdBeginTransformFeedbackEXT` or `vkCmdEndTransformFeedbackEXT`.
+pub struct UnsafeCommandBufferBuilderTransformFeedbackBuffers {
+    // Raw handles of the buffers.
+    raw_buffers: SmallVec<[vk::Buffer; 4]>,
+    // Raw offsets of the buffers.
+    offsets: SmallVec<[vk::DeviceSize; 4]>,
+    // Sizes of the buffers. Ignored for counter buffers.
+    sizes: SmallVec<[vk::DeviceSize; 4]>,
+}
+
+impl UnsafeCommandBufferBuilderTransformFeedbackBuffers {
+    /// Builds a new empty list.
+    #[inline]
+    pub fn new() -> UnsafeCommandBufferBuilderTransformFeedbackBuffers {
+        UnsafeCommandBufferBuilderTransformFeedbackBuffers {
+            raw_buffers: SmallVec::new(),
+            offsets: SmallVec::new(),
+            sizes: SmallVec::new(),
+        }
+    }
+
+    /// Adds a buffer to the list.
+    #[inline]
+    pub fn add<B>(&mut self, buffer: &B)
+        where B: ?Sized + BufferAccess
+    {
+        let inner = buffer.inner();
+        self.raw_buffers.push(inner.buffer.internal_object());
+        self.offsets.push(inner.offset as vk::DeviceSize);
+        self.sizes.push(buffer.size() as vk::DeviceSize);
+    }
 }
 
 /// Prototype for a `vkCmdExecuteCommands`.
@@ -1699,13 +2657,17 @@ pub struct UnsafeCommandBufferBuilderImageBlit {
 /// > **Note**: We use a builder-like API here so that users can pass multiple buffers or images of
 /// > multiple different types. Doing so with a single function would be very tedious in terms of
 /// > API.
+///
+/// The barriers are stored in the format of `VK_KHR_synchronization2`, where each barrier has its
+/// own stages. If the device doesn't support this extension, they are translated when recorded
+/// and all of them use the union of the stages of the command.
 pub struct UnsafeCommandBufferBuilderPipelineBarrier {
-    src_stage_mask: vk::PipelineStageFlags,
-    dst_stage_mask: vk::PipelineStageFlags,
+    src_stage_mask: vk::PipelineStageFlags2KHR,
+    dst_stage_mask: vk::PipelineStageFlags2KHR,
     dependency_flags: vk::DependencyFlags,
-    memory_barriers: SmallVec<[vk::MemoryBarrier; 2]>,
-    buffer_barriers: SmallVec<[vk::BufferMemoryBarrier; 8]>,
-    image_barriers: SmallVec<[vk::ImageMemoryBarrier; 8]>,
+    memory_barriers: SmallVec<[vk::MemoryBarrier2KHR; 2]>,
+    buffer_barriers: SmallVec<[vk::BufferMemoryBarrier2KHR; 8]>,
+    image_barriers: SmallVec<[vk::ImageMemoryBarrier2KHR; 8]>,
 }
 
 impl UnsafeCommandBufferBuilderPipelineBarrier {
@@ -1754,6 +2716,24 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
     #[inline]
     pub unsafe fn add_execution_dependency(&mut self, source: PipelineStages,
                                            destination: PipelineStages, by_region: bool) {
+        self.add_stages(source, destination, by_region);
+
+        // With `VK_KHR_synchronization2`, an execution dependency is a memory barrier without
+        // any access.
+        self.memory_barriers.push(vk::MemoryBarrier2KHR {
+                                      sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER_2_KHR,
+                                      pNext: ptr::null(),
+                                      srcStageMask: source.into_vulkan_bits2(),
+                                      srcAccessMask: 0,
+                                      dstStageMask: destination.into_vulkan_bits2(),
+                                      dstAccessMask: 0,
+                                  });
+    }
+
+    // Adds the stages to the stages of the whole command.
+    #[inline]
+    fn add_stages(&mut self, source: PipelineStages, destination: PipelineStages,
+                  by_region: bool) {
         if !by_region {
             self.dependency_flags = 0;
         }
@@ -1761,8 +2741,70 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert_ne!(source, PipelineStages::none());
         debug_assert_ne!(destination, PipelineStages::none());
 
-        self.src_stage_mask |= source.into_vulkan_bits();
-        self.dst_stage_mask |= destination.into_vulkan_bits();
+        self.src_stage_mask |= source.into_vulkan_bits2();
+        self.dst_stage_mask |= destination.into_vulkan_bits2();
+    }
+
+    // Translates the barriers into the structs of the original API, for devices that don't
+    // support `VK_KHR_synchronization2`.
+    fn legacy_barriers(&self)
+                       -> (SmallVec<[vk::MemoryBarrier; 2]>,
+                           SmallVec<[vk::BufferMemoryBarrier; 8]>,
+                           SmallVec<[vk::ImageMemoryBarrier; 8]>) {
+        let memory_barriers = self.memory_barriers
+            .iter()
+            .map(|b| {
+                     vk::MemoryBarrier {
+                         sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER,
+                         pNext: ptr::null(),
+                         srcAccessMask: access2_to_legacy(b.srcAccessMask),
+                         dstAccessMask: access2_to_legacy(b.dstAccessMask),
+                     }
+                 })
+            .collect();
+
+        let buffer_barriers = self.buffer_barriers
+            .iter()
+            .map(|b| {
+                     vk::BufferMemoryBarrier {
+                         sType: vk::STRUCTURE_TYPE_BUFFER_MEMORY_BARRIER,
+                         pNext: ptr::null(),
+                         srcAccessMask: access2_to_legacy(b.srcAccessMask),
+                         dstAccessMask: access2_to_legacy(b.dstAccessMask),
+                         srcQueueFamilyIndex: b.srcQueueFamilyIndex,
+                         dstQueueFamilyIndex: b.dstQueueFamilyIndex,
+                         buffer: b.buffer,
+                         offset: b.offset,
+                         size: b.size,
+                     }
+                 })
+            .collect();
+
+        let image_barriers = self.image_barriers
+            .iter()
+            .map(|b| {
+                     vk::ImageMemoryBarrier {
+                         sType: vk::STRUCTURE_TYPE_IMAGE_MEMORY_BARRIER,
+                         pNext: ptr::null(),
+                         srcAccessMask: access2_to_legacy(b.srcAccessMask),
+                         dstAccessMask: access2_to_legacy(b.dstAccessMask),
+                         oldLayout: b.oldLayout,
+                         newLayout: b.newLayout,
+                         srcQueueFamilyIndex: b.srcQueueFamilyIndex,
+                         dstQueueFamilyIndex: b.dstQueueFamilyIndex,
+                         image: b.image,
+                         subresourceRange: vk::ImageSubresourceRange {
+                             aspectMask: b.subresourceRange.aspectMask,
+                             baseMipLevel: b.subresourceRange.baseMipLevel,
+                             levelCount: b.subresourceRange.levelCount,
+                             baseArrayLayer: b.subresourceRange.baseArrayLayer,
+                             layerCount: b.subresourceRange.layerCount,
+                         },
+                     }
+                 })
+            .collect();
+
+        (memory_barriers, buffer_barriers, image_barriers)
     }
 
     /// Adds a memory barrier. This means that all the memory writes by the given source stages
@@ -1782,13 +2824,15 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert!(source_access.is_compatible_with(&source_stage));
         debug_assert!(destination_access.is_compatible_with(&destination_stage));
 
-        self.add_execution_dependency(source_stage, destination_stage, by_region);
+        self.add_stages(source_stage, destination_stage, by_region);
 
-        self.memory_barriers.push(vk::MemoryBarrier {
-                                      sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER,
+        self.memory_barriers.push(vk::MemoryBarrier2KHR {
+                                      sType: vk::STRUCTURE_TYPE_MEMORY_BARRIER_2_KHR,
                                       pNext: ptr::null(),
-                                      srcAccessMask: source_access.into_vulkan_bits(),
-                                      dstAccessMask: destination_access.into_vulkan_bits(),
+                                      srcStageMask: source_stage.into_vulkan_bits2(),
+                                      srcAccessMask: source_access.into_vulkan_bits2(),
+                                      dstStageMask: destination_stage.into_vulkan_bits2(),
+                                      dstAccessMask: destination_access.into_vulkan_bits2(),
                                   });
     }
 
@@ -1818,7 +2862,7 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert!(source_access.is_compatible_with(&source_stage));
         debug_assert!(destination_access.is_compatible_with(&destination_stage));
 
-        self.add_execution_dependency(source_stage, destination_stage, by_region);
+        self.add_stages(source_stage, destination_stage, by_region);
 
         debug_assert!(size <= buffer.size());
         let BufferInner {
@@ -1833,11 +2877,13 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
             (vk::QUEUE_FAMILY_IGNORED, vk::QUEUE_FAMILY_IGNORED)
         };
 
-        self.buffer_barriers.push(vk::BufferMemoryBarrier {
-                                      sType: vk::STRUCTURE_TYPE_BUFFER_MEMORY_BARRIER,
+        self.buffer_barriers.push(vk::BufferMemoryBarrier2KHR {
+                                      sType: vk::STRUCTURE_TYPE_BUFFER_MEMORY_BARRIER_2_KHR,
                                       pNext: ptr::null(),
-                                      srcAccessMask: source_access.into_vulkan_bits(),
-                                      dstAccessMask: destination_access.into_vulkan_bits(),
+                                      srcStageMask: source_stage.into_vulkan_bits2(),
+                                      srcAccessMask: source_access.into_vulkan_bits2(),
+                                      dstStageMask: destination_stage.into_vulkan_bits2(),
+                                      dstAccessMask: destination_access.into_vulkan_bits2(),
                                       srcQueueFamilyIndex: src_queue,
                                       dstQueueFamilyIndex: dest_queue,
                                       buffer: buffer.internal_object(),
@@ -1875,7 +2921,7 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
         debug_assert!(source_access.is_compatible_with(&source_stage));
         debug_assert!(destination_access.is_compatible_with(&destination_stage));
 
-        self.add_execution_dependency(source_stage, destination_stage, by_region);
+        self.add_stages(source_stage, destination_stage, by_region);
 
         debug_assert_ne!(new_layout, ImageLayout::Undefined);
         debug_assert_ne!(new_layout, ImageLayout::Preinitialized);
@@ -1905,11 +2951,13 @@ impl UnsafeCommandBufferBuilderPipelineBarrier {
 
         let image = image.inner();
 
-        self.image_barriers.push(vk::ImageMemoryBarrier {
-                                     sType: vk::STRUCTURE_TYPE_IMAGE_MEMORY_BARRIER,
+        self.image_barriers.push(vk::ImageMemoryBarrier2KHR {
+                                     sType: vk::STRUCTURE_TYPE_IMAGE_MEMORY_BARRIER_2_KHR,
                                      pNext: ptr::null(),
-                                     srcAccessMask: source_access.into_vulkan_bits(),
-                                     dstAccessMask: destination_access.into_vulkan_bits(),
+                                     srcStageMask: source_stage.into_vulkan_bits2(),
+                                     srcAccessMask: source_access.into_vulkan_bits2(),
+                                     dstStageMask: destination_stage.into_vulkan_bits2(),
+                                     dstAccessMask: destination_access.into_vulkan_bits2(),
                                      oldLayout: current_layout as u32,
                                      newLayout: new_layout as u32,
                                      srcQueueFamilyIndex: src_queue,
@@ -1942,6 +2990,14 @@ pub struct UnsafeCommandBuffer<P> {
     device: Arc<Device>,
 }
 
+impl<P> UnsafeCommandBuffer<P> {
+    // Returns the command buffer allocated from the pool, so that it can be recorded again.
+    #[inline]
+    pub(crate) fn into_alloc(self) -> P {
+        self.cmd
+    }
+}
+
 unsafe impl<P> DeviceOwned for UnsafeCommandBuffer<P> {
     #[inline]
     fn device(&self) -> &Arc<Device> {
@@ -34,6 +34,7 @@ use sync::GpuFuture;
 use sync::NowFuture;
 use sync::PipelineStages;
 use sync::now;
+use sync::wait_error_on_drop;
 
 pub unsafe trait CommandBuffer: DeviceOwned {
     /// The command pool of the command buffer.
@@ -143,6 +144,40 @@ pub unsafe trait CommandBuffer: DeviceOwned {
                           queue: &Queue)
                           -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError>;
 
+    /// Returns the number of buffers used by this command buffer.
+    ///
+    /// When this command buffer is executed as a secondary command buffer, its buffers and images
+    /// are synchronized with the other commands of the primary command buffer. The default
+    /// implementation returns 0, in which case the synchronization is up to the user.
+    #[inline]
+    fn num_buffers(&self) -> usize {
+        0
+    }
+
+    /// Returns the `index`th buffer used by this command buffer, the union of the stages and of
+    /// the accesses of the commands that use it, and true if it is used in exclusive mode.
+    #[inline]
+    fn buffer(&self, _index: usize)
+              -> Option<(&BufferAccess, PipelineStages, AccessFlagBits, bool)> {
+        None
+    }
+
+    /// Returns the number of images used by this command buffer. See `num_buffers`.
+    #[inline]
+    fn num_images(&self) -> usize {
+        0
+    }
+
+    /// Returns the `index`th image used by this command buffer, the union of the stages and of
+    /// the accesses of the commands that use it, true if it is used in exclusive mode, and the
+    /// layouts it must be in at the start of the command buffer and it is in at the end.
+    #[inline]
+    fn image(&self, _index: usize)
+             -> Option<(&ImageAccess, PipelineStages, AccessFlagBits, bool, ImageLayout,
+                        ImageLayout)> {
+        None
+    }
+
     // FIXME: lots of other methods
 }
 
@@ -180,6 +215,29 @@ unsafe impl<T> CommandBuffer for T
                           -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
         (**self).check_image_access(image, layout, exclusive, queue)
     }
+
+    #[inline]
+    fn num_buffers(&self) -> usize {
+        (**self).num_buffers()
+    }
+
+    #[inline]
+    fn buffer(&self, index: usize)
+              -> Option<(&BufferAccess, PipelineStages, AccessFlagBits, bool)> {
+        (**self).buffer(index)
+    }
+
+    #[inline]
+    fn num_images(&self) -> usize {
+        (**self).num_images()
+    }
+
+    #[inline]
+    fn image(&self, index: usize)
+             -> Option<(&ImageAccess, PipelineStages, AccessFlagBits, bool, ImageLayout,
+                        ImageLayout)> {
+        (**self).image(index)
+    }
 }
 
 /// Represents a command buffer being executed by the GPU and the moment when the execution
@@ -326,10 +384,14 @@ impl<F, Cb> Drop for CommandBufferExecFuture<F, Cb>
     fn drop(&mut self) {
         unsafe {
             if !*self.finished.get_mut() {
-                // TODO: handle errors?
-                self.flush().unwrap();
                 // Block until the queue finished.
-                self.queue.wait().unwrap();
+                let result = self.flush().and_then(|()| self.queue.wait().map_err(From::from));
+                match result {
+                    // If the device is lost, nothing executes anymore and the resources can be
+                    // freed.
+                    Ok(()) | Err(FlushError::DeviceLost) => (),
+                    Err(err) => wait_error_on_drop(err),
+                }
                 self.command_buffer.unlock();
                 self.previous.signal_finished();
             }
@@ -0,0 +1,353 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use acceleration_structure::AccelerationStructure;
+use acceleration_structure::AccelerationStructureBuildSizes;
+use acceleration_structure::AccelerationStructureGeometry;
+use acceleration_structure::AccelerationStructureType;
+use acceleration_structure::BuildAccelerationStructureFlags;
+use acceleration_structure::CopyMode;
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use query::QueryType;
+use query::UnsafeQueryPool;
+
+/// Checks whether a build acceleration structure command is valid.
+///
+/// If `source` is `Some`, the acceleration structure is updated from `source`. On success,
+/// returns the sizes needed by the build, as returned by `AccelerationStructure::build_sizes`.
+///
+/// # Panic
+///
+/// - Panics if the acceleration structures or the buffers of the geometries were not created
+///   with `device`.
+///
+pub fn check_build_acceleration_structure(device: &Device,
+                                          source: Option<&AccelerationStructure>,
+                                          destination: &AccelerationStructure,
+                                          flags: BuildAccelerationStructureFlags,
+                                          geometries: &[AccelerationStructureGeometry])
+                                          -> Result<AccelerationStructureBuildSizes,
+                                                    CheckBuildAccelerationStructureError> {
+    assert_eq!(destination.device().internal_object(),
+               device.internal_object());
+
+    if let Some(source) = source {
+        assert_eq!(source.device().internal_object(), device.internal_object());
+
+        if source.ty() != destination.ty() {
+            return Err(CheckBuildAccelerationStructureError::TypeMismatch);
+        }
+
+        if !source.allows_update() {
+            return Err(CheckBuildAccelerationStructureError::SourceNotUpdatable);
+        }
+    }
+
+    for geometry in geometries {
+        let instances = match *geometry {
+            AccelerationStructureGeometry::Instances { ref instances, .. } => {
+                for instance in instances.acceleration_structures() {
+                    assert_eq!(instance.device().internal_object(), device.internal_object());
+
+                    if instance.ty() != AccelerationStructureType::BottomLevel {
+                        return Err(CheckBuildAccelerationStructureError::InstanceNotBottomLevel);
+                    }
+                }
+
+                true
+            },
+            _ => false,
+        };
+
+        if instances != (destination.ty() == AccelerationStructureType::TopLevel) {
+            return Err(CheckBuildAccelerationStructureError::WrongGeometryType);
+        }
+
+        for buffer in geometry.buffers() {
+            let inner = buffer.inner().buffer;
+            assert_eq!(inner.device().internal_object(), device.internal_object());
+
+            if !inner.usage_acceleration_structure_build_input() ||
+                !inner.usage_shader_device_address()
+            {
+                return Err(CheckBuildAccelerationStructureError::GeometryMissingUsage);
+            }
+        }
+    }
+
+    if destination.ty() == AccelerationStructureType::TopLevel && geometries.len() != 1 {
+        return Err(CheckBuildAccelerationStructureError::WrongGeometryType);
+    }
+
+    let sizes = AccelerationStructure::build_sizes(device, destination.ty(), flags, geometries);
+    if destination.buffer().size() < sizes.acceleration_structure_size {
+        return Err(CheckBuildAccelerationStructureError::DestinationTooSmall {
+                       required: sizes.acceleration_structure_size,
+                       actual: destination.buffer().size(),
+                   });
+    }
+
+    Ok(sizes)
+}
+
+/// Checks whether `scratch` is large enough to be the scratch memory of a build acceleration
+/// structure command, where `sizes` are the sizes returned by
+/// `check_build_acceleration_structure` and `update` is true if the acceleration structure is
+/// updated.
+pub fn check_build_acceleration_structure_scratch(sizes: &AccelerationStructureBuildSizes,
+                                                  update: bool, scratch: &BufferAccess)
+                                                  -> Result<(),
+                                                            CheckBuildAccelerationStructureError> {
+    let required = if update {
+        sizes.update_scratch_size
+    } else {
+        sizes.build_scratch_size
+    };
+
+    if scratch.size() < required {
+        return Err(CheckBuildAccelerationStructureError::ScratchTooSmall {
+                       required: required,
+                       actual: scratch.size(),
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_build_acceleration_structure`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckBuildAccelerationStructureError {
+    /// The source of an update doesn't have the same type as the destination.
+    TypeMismatch,
+    /// The source of an update wasn't built with the `allow_update` flag.
+    SourceNotUpdatable,
+    /// A top-level acceleration structure must be built out of exactly one list of instances,
+    /// and a bottom-level acceleration structure out of triangles or bounding boxes.
+    WrongGeometryType,
+    /// An instance of a top-level acceleration structure isn't a bottom-level acceleration
+    /// structure.
+    InstanceNotBottomLevel,
+    /// A buffer of a geometry is missing the `acceleration_structure_build_input` or the
+    /// `shader_device_address` usage.
+    GeometryMissingUsage,
+    /// The destination is smaller than the size returned by
+    /// `AccelerationStructure::build_sizes`.
+    DestinationTooSmall {
+        /// Size needed by the build.
+        required: usize,
+        /// Size of the destination.
+        actual: usize,
+    },
+    /// The scratch memory is smaller than the size returned by
+    /// `AccelerationStructure::build_sizes`.
+    ScratchTooSmall {
+        /// Size needed by the build.
+        required: usize,
+        /// Size of the scratch memory.
+        actual: usize,
+    },
+}
+
+impl error::Error for CheckBuildAccelerationStructureError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckBuildAccelerationStructureError::TypeMismatch => {
+                "the source of an update doesn't have the same type as the destination"
+            },
+            CheckBuildAccelerationStructureError::SourceNotUpdatable => {
+                "the source of an update wasn't built with the `allow_update` flag"
+            },
+            CheckBuildAccelerationStructureError::WrongGeometryType => {
+                "a top-level acceleration structure must be built out of exactly one list of \
+                 instances, and a bottom-level acceleration structure out of triangles or \
+                 bounding boxes"
+            },
+            CheckBuildAccelerationStructureError::InstanceNotBottomLevel => {
+                "an instance of a top-level acceleration structure isn't a bottom-level \
+                 acceleration structure"
+            },
+            CheckBuildAccelerationStructureError::GeometryMissingUsage => {
+                "a buffer of a geometry is missing the `acceleration_structure_build_input` or \
+                 the `shader_device_address` usage"
+            },
+            CheckBuildAccelerationStructureError::DestinationTooSmall { .. } => {
+                "the destination is smaller than the size needed by the build"
+            },
+            CheckBuildAccelerationStructureError::ScratchTooSmall { .. } => {
+                "the scratch memory is smaller than the size needed by the build"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckBuildAccelerationStructureError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a copy acceleration structure command is valid.
+///
+/// # Panic
+///
+/// - Panics if the source and destination were not created with `device`.
+///
+pub fn check_copy_acceleration_structure(device: &Device, source: &AccelerationStructure,
+                                         destination: &AccelerationStructure, mode: CopyMode)
+                                         -> Result<(), CheckCopyAccelerationStructureError> {
+    assert_eq!(source.device().internal_object(), device.internal_object());
+    assert_eq!(destination.device().internal_object(),
+               device.internal_object());
+
+    if source.internal_object() == destination.internal_object() {
+        return Err(CheckCopyAccelerationStructureError::SameAccelerationStructure);
+    }
+
+    if source.ty() != destination.ty() {
+        return Err(CheckCopyAccelerationStructureError::TypeMismatch);
+    }
+
+    // The compacted size can only be known by the GPU, so it isn't checked.
+    if mode == CopyMode::Clone && destination.buffer().size() < source.buffer().size() {
+        return Err(CheckCopyAccelerationStructureError::DestinationTooSmall);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_copy_acceleration_structure`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckCopyAccelerationStructureError {
+    /// The source and the destination are the same acceleration structure.
+    SameAccelerationStructure,
+    /// The source and the destination don't have the same type.
+    TypeMismatch,
+    /// The destination is smaller than the source.
+    DestinationTooSmall,
+}
+
+impl error::Error for CheckCopyAccelerationStructureError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckCopyAccelerationStructureError::SameAccelerationStructure => {
+                "the source and the destination are the same acceleration structure"
+            },
+            CheckCopyAccelerationStructureError::TypeMismatch => {
+                "the source and the destination don't have the same type"
+            },
+            CheckCopyAccelerationStructureError::DestinationTooSmall => {
+                "the destination is smaller than the source"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckCopyAccelerationStructureError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a command that writes the compacted size of an acceleration structure in a
+/// query is valid.
+///
+/// # Panic
+///
+/// - Panics if the acceleration structure or the query pool were not created with `device`.
+///
+pub fn check_write_acceleration_structure_compacted_size(
+    device: &Device, acceleration_structure: &AccelerationStructure,
+    query_pool: &UnsafeQueryPool, query: u32)
+    -> Result<(), CheckWriteAccelerationStructureCompactedSizeError> {
+    assert_eq!(acceleration_structure.device().internal_object(),
+               device.internal_object());
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    match query_pool.ty() {
+        QueryType::AccelerationStructureCompactedSize => (),
+        _ => return Err(CheckWriteAccelerationStructureCompactedSizeError::WrongQueryType),
+    }
+
+    if query >= query_pool.num_slots() {
+        return Err(CheckWriteAccelerationStructureCompactedSizeError::QueryOutOfRange);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_write_acceleration_structure_compacted_size`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckWriteAccelerationStructureCompactedSizeError {
+    /// The query pool wasn't created with the `AccelerationStructureCompactedSize` type.
+    WrongQueryType,
+    /// The index of the query is out of range of the query pool.
+    QueryOutOfRange,
+}
+
+impl error::Error for CheckWriteAccelerationStructureCompactedSizeError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckWriteAccelerationStructureCompactedSizeError::WrongQueryType => {
+                "the query pool wasn't created with the `AccelerationStructureCompactedSize` type"
+            },
+            CheckWriteAccelerationStructureCompactedSizeError::QueryOutOfRange => {
+                "the index of the query is out of range of the query pool"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckWriteAccelerationStructureCompactedSizeError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+
+    #[test]
+    fn scratch_too_small() {
+        let (device, _) = gfx_dev_and_queue!();
+        let scratch = CpuAccessibleBuffer::from_data(device, BufferUsage::all(), [0u8; 16])
+            .unwrap();
+
+        let sizes = AccelerationStructureBuildSizes {
+            acceleration_structure_size: 0,
+            update_scratch_size: 16,
+            build_scratch_size: 32,
+        };
+
+        assert!(check_build_acceleration_structure_scratch(&sizes, true, &scratch).is_ok());
+
+        match check_build_acceleration_structure_scratch(&sizes, false, &scratch) {
+            Err(CheckBuildAccelerationStructureError::ScratchTooSmall {
+                    required: 32,
+                    actual: 16,
+                }) => (),
+            _ => panic!(),
+        }
+    }
+}
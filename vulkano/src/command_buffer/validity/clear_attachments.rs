@@ -0,0 +1,213 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use command_buffer::ClearAttachment;
+use command_buffer::ClearRect;
+use format::ClearValue;
+use format::FormatTy;
+use framebuffer::RenderPassDesc;
+
+/// Checks whether a clear attachments command is valid.
+///
+/// `subpass` is the index of the current subpass of `render_pass`. `framebuffer_dimensions`
+/// contains the dimensions of the framebuffer if they are known, in which case the rectangles are
+/// checked against them.
+pub fn check_clear_attachments<Rp>(render_pass: &Rp, subpass: u32,
+                                   framebuffer_dimensions: Option<[u32; 3]>,
+                                   attachments: &[ClearAttachment], rects: &[ClearRect])
+                                   -> Result<(), CheckClearAttachmentsError>
+    where Rp: ?Sized + RenderPassDesc
+{
+    let subpass_desc = render_pass
+        .subpass_desc(subpass as usize)
+        .expect("the current subpass doesn't exist in the render pass");
+
+    for attachment in attachments {
+        match *attachment {
+            ClearAttachment::Color(num, value) => {
+                let atch_num = match subpass_desc.color_attachments.get(num as usize) {
+                    Some(&(atch_num, _)) => atch_num,
+                    None => {
+                        return Err(CheckClearAttachmentsError::ColorAttachmentOutOfRange {
+                                       color_attachment: num,
+                                       num_color_attachments: subpass_desc
+                                           .color_attachments
+                                           .len() as u32,
+                                   })
+                    },
+                };
+
+                let format_ty = render_pass.attachment_desc(atch_num).unwrap().format.ty();
+                match (format_ty, value) {
+                    (FormatTy::Float, ClearValue::Float(_)) |
+                    (FormatTy::Compressed, ClearValue::Float(_)) |
+                    (FormatTy::Sint, ClearValue::Int(_)) |
+                    (FormatTy::Uint, ClearValue::Uint(_)) => (),
+                    _ => {
+                        return Err(CheckClearAttachmentsError::WrongClearValue {
+                                       color_attachment: num,
+                                   })
+                    },
+                }
+            },
+            ClearAttachment::Depth(_) => {
+                if !render_pass.has_depth(subpass).unwrap() {
+                    return Err(CheckClearAttachmentsError::MissingDepthStencilAttachment);
+                }
+            },
+            ClearAttachment::Stencil(_) => {
+                if !render_pass.has_stencil(subpass).unwrap() {
+                    return Err(CheckClearAttachmentsError::MissingDepthStencilAttachment);
+                }
+            },
+            ClearAttachment::DepthStencil(_) => {
+                if !render_pass.has_depth(subpass).unwrap() ||
+                    !render_pass.has_stencil(subpass).unwrap()
+                {
+                    return Err(CheckClearAttachmentsError::MissingDepthStencilAttachment);
+                }
+            },
+        }
+    }
+
+    for (num, rect) in rects.iter().enumerate() {
+        if rect.extent[0] == 0 || rect.extent[1] == 0 || rect.layer_count == 0 {
+            return Err(CheckClearAttachmentsError::EmptyRect { rect: num });
+        }
+
+        if let Some(dimensions) = framebuffer_dimensions {
+            if rect.offset[0] as u64 + rect.extent[0] as u64 > dimensions[0] as u64 ||
+                rect.offset[1] as u64 + rect.extent[1] as u64 > dimensions[1] as u64 ||
+                rect.base_array_layer as u64 + rect.layer_count as u64 > dimensions[2] as u64
+            {
+                return Err(CheckClearAttachmentsError::RectOutOfBounds { rect: num });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether a clear attachments command is valid.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckClearAttachmentsError {
+    /// The index of a color attachment is out of range of the color attachments of the subpass.
+    ColorAttachmentOutOfRange {
+        /// The index that was passed.
+        color_attachment: u32,
+        /// The number of color attachments of the subpass.
+        num_color_attachments: u32,
+    },
+    /// The clear value of a color attachment doesn't match the format of the attachment.
+    WrongClearValue {
+        /// The index of the color attachment in the subpass.
+        color_attachment: u32,
+    },
+    /// The depth or stencil aspect is cleared, but the subpass has no such attachment.
+    MissingDepthStencilAttachment,
+    /// A rectangle has no width, no height or no layer.
+    EmptyRect {
+        /// Index of the faulty rectangle.
+        rect: usize,
+    },
+    /// A rectangle goes beyond the dimensions of the framebuffer.
+    RectOutOfBounds {
+        /// Index of the faulty rectangle.
+        rect: usize,
+    },
+}
+
+impl error::Error for CheckClearAttachmentsError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckClearAttachmentsError::ColorAttachmentOutOfRange { .. } => {
+                "the index of a color attachment is out of range of the color attachments of the \
+                 subpass"
+            },
+            CheckClearAttachmentsError::WrongClearValue { .. } => {
+                "the clear value of a color attachment doesn't match the format of the attachment"
+            },
+            CheckClearAttachmentsError::MissingDepthStencilAttachment => {
+                "the depth or stencil aspect is cleared, but the subpass has no such attachment"
+            },
+            CheckClearAttachmentsError::EmptyRect { .. } => {
+                "a rectangle has no width, no height or no layer"
+            },
+            CheckClearAttachmentsError::RectOutOfBounds { .. } => {
+                "a rectangle goes beyond the dimensions of the framebuffer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckClearAttachmentsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use framebuffer::EmptySinglePassRenderPassDesc;
+
+    #[test]
+    fn color_out_of_range() {
+        let result = check_clear_attachments(&EmptySinglePassRenderPassDesc,
+                                             0,
+                                             None,
+                                             &[ClearAttachment::Color(0, [0.0; 4].into())],
+                                             &[]);
+        match result {
+            Err(CheckClearAttachmentsError::ColorAttachmentOutOfRange {
+                    color_attachment: 0,
+                    num_color_attachments: 0,
+                }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn no_depth() {
+        let result = check_clear_attachments(&EmptySinglePassRenderPassDesc,
+                                             0,
+                                             None,
+                                             &[ClearAttachment::Depth(1.0)],
+                                             &[]);
+        match result {
+            Err(CheckClearAttachmentsError::MissingDepthStencilAttachment) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn rect_out_of_bounds() {
+        let rect = ClearRect {
+            offset: [16, 0],
+            extent: [64, 64],
+            base_array_layer: 0,
+            layer_count: 1,
+        };
+
+        let result = check_clear_attachments(&EmptySinglePassRenderPassDesc,
+                                             0,
+                                             Some([64, 64, 1]),
+                                             &[],
+                                             &[rect]);
+        match result {
+            Err(CheckClearAttachmentsError::RectOutOfBounds { rect: 0 }) => (),
+            _ => panic!(),
+        }
+    }
+}
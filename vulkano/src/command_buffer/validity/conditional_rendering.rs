@@ -0,0 +1,105 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use buffer::TypedBufferAccess;
+use device::Device;
+use device::DeviceOwned;
+
+/// Checks whether a predicate buffer can be used to conditionally execute secondary command
+/// buffers.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+///
+pub fn check_conditional_rendering<B>(device: &Device, predicate: &B)
+                                      -> Result<(), CheckConditionalRenderingError>
+    where B: ?Sized + TypedBufferAccess<Content = u32>
+{
+    assert_eq!(predicate.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if !device.enabled_features().inherited_conditional_rendering {
+        return Err(CheckConditionalRenderingError::InheritedConditionalRenderingFeatureNotEnabled);
+    }
+
+    if !predicate.inner().buffer.usage_conditional_rendering() {
+        return Err(CheckConditionalRenderingError::MissingUsage);
+    }
+
+    if predicate.inner().offset % 4 != 0 {
+        return Err(CheckConditionalRenderingError::UnalignedPredicate);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether a predicate buffer is valid.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckConditionalRenderingError {
+    /// The `inherited_conditional_rendering` feature of the `VK_EXT_conditional_rendering`
+    /// extension isn't enabled.
+    InheritedConditionalRenderingFeatureNotEnabled,
+    /// The "conditional rendering" usage must be enabled on the predicate buffer.
+    MissingUsage,
+    /// The offset of the predicate in its buffer must be a multiple of 4.
+    UnalignedPredicate,
+}
+
+impl error::Error for CheckConditionalRenderingError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckConditionalRenderingError::InheritedConditionalRenderingFeatureNotEnabled => {
+                "the `inherited_conditional_rendering` feature of the \
+                 `VK_EXT_conditional_rendering` extension isn't enabled"
+            },
+            CheckConditionalRenderingError::MissingUsage => {
+                "the conditional rendering usage must be enabled on the predicate buffer"
+            },
+            CheckConditionalRenderingError::UnalignedPredicate => {
+                "the offset of the predicate in its buffer must be a multiple of 4"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckConditionalRenderingError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+
+    #[test]
+    fn feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let predicate = CpuAccessibleBuffer::from_data(device.clone(),
+                                                       BufferUsage::all(),
+                                                       1u32)
+            .unwrap();
+
+        match check_conditional_rendering(&device, &predicate) {
+            Err(CheckConditionalRenderingError::InheritedConditionalRenderingFeatureNotEnabled) => {
+            },
+            _ => panic!(),
+        }
+    }
+}
@@ -9,6 +9,7 @@
 
 use std::error;
 use std::fmt;
+use std::mem;
 
 use VulkanObject;
 use buffer::TypedBufferAccess;
@@ -16,6 +17,7 @@ use device::Device;
 use device::DeviceOwned;
 use format::AcceptsPixels;
 use format::Format;
+use format::FormatTy;
 use format::IncompatiblePixelsType;
 use image::ImageAccess;
 
@@ -33,7 +35,6 @@ pub enum CheckCopyBufferImageTy {
 ///
 /// - Panics if the buffer and image were not created with `device`.
 ///
-// TODO: handle compressed image formats
 pub fn check_copy_buffer_image<B, I, P>(device: &Device, buffer: &B, image: &I,
                                         ty: CheckCopyBufferImageTy, image_offset: [u32; 3],
                                         image_size: [u32; 3], image_first_layer: u32,
@@ -95,11 +96,35 @@ pub fn check_copy_buffer_image<B, I, P>(device: &Device, buffer: &B, image: &I,
         return Err(CheckCopyBufferImageError::ImageCoordinatesOutOfRange);
     }
 
-    image.format().ensure_accepts()?;
+    let format = image.format();
+
+    if format.ty() == FormatTy::Compressed {
+        if format.block_size() as usize % mem::size_of::<P>() != 0 {
+            return Err(CheckCopyBufferImageError::WrongPixelType(IncompatiblePixelsType));
+        }
+
+        // The copied region must be made of whole blocks, except at the edges of the image.
+        let image_extent = [
+            image_dimensions.width(),
+            image_dimensions.height(),
+            image_dimensions.depth(),
+        ];
+        let block_extent = [format.block_extent()[0], format.block_extent()[1], 1];
+        for i in 0 .. 3 {
+            if image_offset[i] % block_extent[i] != 0 ||
+                (image_size[i] % block_extent[i] != 0 &&
+                     image_offset[i] + image_size[i] != image_extent[i])
+            {
+                return Err(CheckCopyBufferImageError::ImageNotBlockAligned);
+            }
+        }
+    } else {
+        format.ensure_accepts()?;
+    }
 
     {
-        let num_texels = image_size[0] * image_size[1] * image_size[2] * image_num_layers;
-        let required_len = num_texels as usize * image.format().rate() as usize;
+        let required_bytes = format.copy_buffer_size(image_size, image_num_layers, 0, 0);
+        let required_len = required_bytes as usize / mem::size_of::<P>();
         if required_len > buffer.len() {
             return Err(CheckCopyBufferImageError::BufferTooSmall {
                            required_len: required_len,
@@ -126,6 +151,9 @@ pub enum CheckCopyBufferImageError {
     UnexpectedMultisampled,
     /// The image coordinates are out of range.
     ImageCoordinatesOutOfRange,
+    /// The image has a compressed format, and the offset or size of the copy isn't a multiple
+    /// of the block extent of the format.
+    ImageNotBlockAligned,
     /// The type of pixels in the buffer isn't compatible with the image format.
     WrongPixelType(IncompatiblePixelsType),
     /// The buffer is too small for the copy operation.
@@ -156,6 +184,9 @@ impl error::Error for CheckCopyBufferImageError {
             CheckCopyBufferImageError::ImageCoordinatesOutOfRange => {
                 "the image coordinates are out of range"
             },
+            CheckCopyBufferImageError::ImageNotBlockAligned => {
+                "the offset or size of the copy isn't a multiple of the block extent of the format"
+            },
             CheckCopyBufferImageError::WrongPixelType(_) => {
                 "the type of pixels in the buffer isn't compatible with the image format"
             },
@@ -0,0 +1,62 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+
+/// Checks whether debug label commands can be recorded in a command buffer of `device`.
+pub fn check_debug_label(device: &Device) -> Result<(), CheckDebugLabelError> {
+    if !device.instance().loaded_extensions().ext_debug_utils {
+        return Err(CheckDebugLabelError::ExtensionNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_debug_label`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDebugLabelError {
+    /// The `VK_EXT_debug_utils` instance extension isn't enabled.
+    ExtensionNotEnabled,
+}
+
+impl error::Error for CheckDebugLabelError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDebugLabelError::ExtensionNotEnabled => {
+                "the `VK_EXT_debug_utils` instance extension isn't enabled"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDebugLabelError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match check_debug_label(&device) {
+            Err(CheckDebugLabelError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,130 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+use pipeline::viewport::Scissor;
+
+/// Checks whether `device_mask` can be used as the device mask of a command buffer.
+pub fn check_device_mask(device: &Device, device_mask: u32) -> Result<(), CheckDeviceGroupError> {
+    if !device.loaded_extensions().khr_device_group {
+        return Err(CheckDeviceGroupError::ExtensionNotEnabled);
+    }
+
+    if device_mask == 0 {
+        return Err(CheckDeviceGroupError::EmptyDeviceMask);
+    }
+
+    let physical_device_count = device.physical_device_count();
+    if physical_device_count < 32 && (device_mask >> physical_device_count) != 0 {
+        return Err(CheckDeviceGroupError::InvalidDeviceMask {
+                       device_mask,
+                       physical_device_count,
+                   });
+    }
+
+    Ok(())
+}
+
+/// Checks whether a render pass can be begun with the given device mask and per-device render
+/// areas.
+pub fn check_device_group_render_pass(device: &Device, device_mask: u32,
+                                      render_areas: &[Scissor])
+                                      -> Result<(), CheckDeviceGroupError> {
+    check_device_mask(device, device_mask)?;
+
+    let physical_device_count = device.physical_device_count();
+    if !render_areas.is_empty() && render_areas.len() as u32 != physical_device_count {
+        return Err(CheckDeviceGroupError::RenderAreaCountMismatch {
+                       physical_device_count,
+                       obtained: render_areas.len() as u32,
+                   });
+    }
+
+    for area in render_areas {
+        if area.origin[0] < 0 || area.origin[1] < 0 {
+            return Err(CheckDeviceGroupError::RenderAreaNegativeOrigin);
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_device_mask` or `check_device_group_render_pass`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CheckDeviceGroupError {
+    /// The `VK_KHR_device_group` extension isn't enabled.
+    ExtensionNotEnabled,
+    /// The device mask doesn't contain any physical device.
+    EmptyDeviceMask,
+    /// The device mask contains bits that don't correspond to a physical device of the device.
+    InvalidDeviceMask {
+        /// The device mask that was passed.
+        device_mask: u32,
+        /// Number of physical devices that the device is made of.
+        physical_device_count: u32,
+    },
+    /// The number of render areas is neither zero nor the number of physical devices.
+    RenderAreaCountMismatch {
+        /// Number of physical devices that the device is made of.
+        physical_device_count: u32,
+        /// Number of render areas that were passed.
+        obtained: u32,
+    },
+    /// The origin of a render area is negative.
+    RenderAreaNegativeOrigin,
+}
+
+impl error::Error for CheckDeviceGroupError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDeviceGroupError::ExtensionNotEnabled => {
+                "the `VK_KHR_device_group` extension isn't enabled"
+            },
+            CheckDeviceGroupError::EmptyDeviceMask => {
+                "the device mask doesn't contain any physical device"
+            },
+            CheckDeviceGroupError::InvalidDeviceMask { .. } => {
+                "the device mask contains bits that don't correspond to a physical device of the \
+                 device"
+            },
+            CheckDeviceGroupError::RenderAreaCountMismatch { .. } => {
+                "the number of render areas is neither zero nor the number of physical devices"
+            },
+            CheckDeviceGroupError::RenderAreaNegativeOrigin => {
+                "the origin of a render area is negative"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDeviceGroupError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match check_device_mask(&device, 1) {
+            Err(CheckDeviceGroupError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
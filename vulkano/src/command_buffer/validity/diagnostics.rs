@@ -0,0 +1,156 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use buffer::TypedBufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use sync::PipelineStages;
+
+/// Checks whether a checkpoint can be set in a command buffer of `device`.
+pub fn check_checkpoint(device: &Device) -> Result<(), CheckCheckpointError> {
+    if !device.loaded_extensions().nv_device_diagnostic_checkpoints {
+        return Err(CheckCheckpointError::ExtensionNotEnabled);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_checkpoint`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckCheckpointError {
+    /// The `VK_NV_device_diagnostic_checkpoints` extension isn't enabled.
+    ExtensionNotEnabled,
+}
+
+impl error::Error for CheckCheckpointError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckCheckpointError::ExtensionNotEnabled => {
+                "the `VK_NV_device_diagnostic_checkpoints` extension isn't enabled"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckCheckpointError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a write buffer marker command is valid.
+///
+/// # Panic
+///
+/// - Panics if the buffer not created with `device`.
+///
+pub fn check_write_buffer_marker<B>(device: &Device, buffer: &B, stage: PipelineStages)
+                                    -> Result<(), CheckWriteBufferMarkerError>
+    where B: ?Sized + TypedBufferAccess<Content = u32>
+{
+    assert_eq!(buffer.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if !device.loaded_extensions().amd_buffer_marker {
+        return Err(CheckWriteBufferMarkerError::ExtensionNotEnabled);
+    }
+
+    if !buffer.inner().buffer.usage_transfer_destination() {
+        return Err(CheckWriteBufferMarkerError::BufferMissingUsage);
+    }
+
+    if buffer.inner().offset % 4 != 0 {
+        return Err(CheckWriteBufferMarkerError::WrongAlignment);
+    }
+
+    if stage.into_vulkan_bits().count_ones() != 1 {
+        return Err(CheckWriteBufferMarkerError::NotOneStage);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_write_buffer_marker`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckWriteBufferMarkerError {
+    /// The `VK_AMD_buffer_marker` extension isn't enabled.
+    ExtensionNotEnabled,
+    /// The "transfer destination" usage must be enabled on the buffer.
+    BufferMissingUsage,
+    /// The offset of the buffer must be 4-bytes aligned.
+    WrongAlignment,
+    /// Exactly one pipeline stage must be specified.
+    NotOneStage,
+}
+
+impl error::Error for CheckWriteBufferMarkerError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckWriteBufferMarkerError::ExtensionNotEnabled => {
+                "the `VK_AMD_buffer_marker` extension isn't enabled"
+            },
+            CheckWriteBufferMarkerError::BufferMissingUsage => {
+                "the transfer destination usage must be enabled on the buffer"
+            },
+            CheckWriteBufferMarkerError::WrongAlignment => {
+                "the offset of the buffer is not aligned to 4 bytes"
+            },
+            CheckWriteBufferMarkerError::NotOneStage => {
+                "exactly one pipeline stage must be specified"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckWriteBufferMarkerError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+
+    #[test]
+    fn checkpoint_extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match check_checkpoint(&device) {
+            Err(CheckCheckpointError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn buffer_marker_extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let buffer = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let stage = PipelineStages {
+            bottom_of_pipe: true,
+            ..PipelineStages::none()
+        };
+
+        match check_write_buffer_marker(&device, &buffer, stage) {
+            Err(CheckWriteBufferMarkerError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
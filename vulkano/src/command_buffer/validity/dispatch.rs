@@ -59,6 +59,66 @@ impl fmt::Display for CheckDispatchError {
     }
 }
 
+/// Checks whether a dispatch command that starts at the workgroup `base` is valid.
+pub fn check_dispatch_base(device: &Device, base: [u32; 3], dimensions: [u32; 3])
+                           -> Result<(), CheckDispatchBaseError> {
+    if !device.loaded_extensions().khr_device_group {
+        return Err(CheckDispatchBaseError::ExtensionNotEnabled);
+    }
+
+    let max = device
+        .physical_device()
+        .limits()
+        .max_compute_work_group_count();
+
+    if (0 .. 3).any(|i| base[i] as u64 + dimensions[i] as u64 > max[i] as u64) {
+        return Err(CheckDispatchBaseError::UnsupportedDimensions {
+                       base,
+                       requested: dimensions,
+                       max_supported: max,
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking the validity of a dispatch command with a base workgroup.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDispatchBaseError {
+    /// The `VK_KHR_device_group` extension isn't enabled.
+    ExtensionNotEnabled,
+    /// The base workgroup plus the dimensions are too large for the device's limits.
+    UnsupportedDimensions {
+        /// The requested base workgroup.
+        base: [u32; 3],
+        /// The requested dimensions.
+        requested: [u32; 3],
+        /// The actual supported dimensions.
+        max_supported: [u32; 3],
+    },
+}
+
+impl error::Error for CheckDispatchBaseError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDispatchBaseError::ExtensionNotEnabled => {
+                "the `VK_KHR_device_group` extension isn't enabled"
+            },
+            CheckDispatchBaseError::UnsupportedDimensions { .. } => {
+                "the base workgroup plus the dimensions are too large for the device's limits"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDispatchBaseError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use command_buffer::validity;
@@ -85,4 +145,14 @@ mod tests {
             _ => panic!(),
         }
     }
+
+    #[test]
+    fn base_extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match validity::check_dispatch_base(&device, [1, 0, 0], [1, 1, 1]) {
+            Err(validity::CheckDispatchBaseError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
 }
@@ -12,6 +12,7 @@ use std::fmt;
 
 use command_buffer::DynamicState;
 use pipeline::GraphicsPipelineAbstract;
+use pipeline::input_assembly::PrimitiveTopology;
 
 /// Checks whether states that are about to be set are correct.
 pub fn check_dynamic_state_validity<Pl>(pipeline: &Pl, state: &DynamicState)
@@ -71,7 +72,211 @@ pub fn check_dynamic_state_validity<Pl>(pipeline: &Pl, state: &DynamicState)
         }
     }
 
-    // TODO: don't forget to implement the rest
+    if pipeline.has_dynamic_depth_bias() {
+        if let Some(bias) = state.depth_bias {
+            if bias.clamp != 0.0 && !device.enabled_features().depth_bias_clamp {
+                return Err(CheckDynamicStateValidityError::DepthBiasClampMissingFeature);
+            }
+        } else {
+            return Err(CheckDynamicStateValidityError::DepthBiasMissing);
+        }
+
+    } else {
+        if state.depth_bias.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthBiasNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_depth_bounds() {
+        if let Some((min, max)) = state.depth_bounds {
+            if min < 0.0 || min > 1.0 || max < 0.0 || max > 1.0 {
+                return Err(CheckDynamicStateValidityError::DepthBoundsOutOfRange);
+            }
+        } else {
+            return Err(CheckDynamicStateValidityError::DepthBoundsMissing);
+        }
+
+    } else {
+        if state.depth_bounds.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthBoundsNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_blend_constants() {
+        if state.blend_constants.is_none() {
+            return Err(CheckDynamicStateValidityError::BlendConstantsMissing);
+        }
+
+    } else {
+        if state.blend_constants.is_some() {
+            return Err(CheckDynamicStateValidityError::BlendConstantsNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_stencil_compare_mask() {
+        if state.compare_mask.is_none() {
+            return Err(CheckDynamicStateValidityError::StencilCompareMaskMissing);
+        }
+
+    } else {
+        if state.compare_mask.is_some() {
+            return Err(CheckDynamicStateValidityError::StencilCompareMaskNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_stencil_write_mask() {
+        if state.write_mask.is_none() {
+            return Err(CheckDynamicStateValidityError::StencilWriteMaskMissing);
+        }
+
+    } else {
+        if state.write_mask.is_some() {
+            return Err(CheckDynamicStateValidityError::StencilWriteMaskNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_stencil_reference() {
+        if state.reference.is_none() {
+            return Err(CheckDynamicStateValidityError::StencilReferenceMissing);
+        }
+
+    } else {
+        if state.reference.is_some() {
+            return Err(CheckDynamicStateValidityError::StencilReferenceNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_primitive_topology() {
+        if let Some(topology) = state.primitive_topology {
+            if !topology.same_class(&pipeline.primitive_topology()) {
+                return Err(CheckDynamicStateValidityError::PrimitiveTopologyClassMismatch {
+                               expected: pipeline.primitive_topology(),
+                               obtained: topology,
+                           });
+            }
+        } else {
+            return Err(CheckDynamicStateValidityError::PrimitiveTopologyMissing);
+        }
+
+    } else {
+        if state.primitive_topology.is_some() {
+            return Err(CheckDynamicStateValidityError::PrimitiveTopologyNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_cull_mode() {
+        if state.cull_mode.is_none() {
+            return Err(CheckDynamicStateValidityError::CullModeMissing);
+        }
+
+    } else {
+        if state.cull_mode.is_some() {
+            return Err(CheckDynamicStateValidityError::CullModeNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_front_face() {
+        if state.front_face.is_none() {
+            return Err(CheckDynamicStateValidityError::FrontFaceMissing);
+        }
+
+    } else {
+        if state.front_face.is_some() {
+            return Err(CheckDynamicStateValidityError::FrontFaceNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_depth_test_enable() {
+        if state.depth_test_enable.is_none() {
+            return Err(CheckDynamicStateValidityError::DepthTestEnableMissing);
+        }
+
+    } else {
+        if state.depth_test_enable.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthTestEnableNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_depth_write_enable() {
+        if state.depth_write_enable.is_none() {
+            return Err(CheckDynamicStateValidityError::DepthWriteEnableMissing);
+        }
+
+    } else {
+        if state.depth_write_enable.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthWriteEnableNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_depth_compare_op() {
+        if state.depth_compare_op.is_none() {
+            return Err(CheckDynamicStateValidityError::DepthCompareOpMissing);
+        }
+
+    } else {
+        if state.depth_compare_op.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthCompareOpNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_depth_bounds_test_enable() {
+        if state.depth_bounds_test_enable.is_none() {
+            return Err(CheckDynamicStateValidityError::DepthBoundsTestEnableMissing);
+        }
+
+    } else {
+        if state.depth_bounds_test_enable.is_some() {
+            return Err(CheckDynamicStateValidityError::DepthBoundsTestEnableNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_stencil_test_enable() {
+        if state.stencil_test_enable.is_none() {
+            return Err(CheckDynamicStateValidityError::StencilTestEnableMissing);
+        }
+
+    } else {
+        if state.stencil_test_enable.is_some() {
+            return Err(CheckDynamicStateValidityError::StencilTestEnableNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_stencil_op() {
+        if state.stencil_op.is_none() {
+            return Err(CheckDynamicStateValidityError::StencilOpMissing);
+        }
+
+    } else {
+        if state.stencil_op.is_some() {
+            return Err(CheckDynamicStateValidityError::StencilOpNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_vertex_input_binding_stride() {
+        if state.vertex_strides.is_none() {
+            return Err(CheckDynamicStateValidityError::VertexStridesMissing);
+        }
+
+    } else {
+        if state.vertex_strides.is_some() {
+            return Err(CheckDynamicStateValidityError::VertexStridesNotDynamic);
+        }
+    }
+
+    if pipeline.has_dynamic_line_stipple() {
+        if let Some(stipple) = state.line_stipple {
+            if stipple.factor < 1 || stipple.factor > 256 {
+                return Err(CheckDynamicStateValidityError::LineStippleFactorOutOfRange);
+            }
+        } else {
+            return Err(CheckDynamicStateValidityError::LineStippleMissing);
+        }
+
+    } else {
+        if state.line_stipple.is_some() {
+            return Err(CheckDynamicStateValidityError::LineStippleNotDynamic);
+        }
+    }
 
     Ok(())
 }
@@ -108,6 +313,104 @@ pub enum CheckDynamicStateValidityError {
         /// Number of scissors that were passed.
         obtained: usize,
     },
+    /// Passed a dynamic depth bias, while the pipeline doesn't have depth bias set as dynamic.
+    DepthBiasNotDynamic,
+    /// The pipeline has a dynamic depth bias, but no depth bias value was passed.
+    DepthBiasMissing,
+    /// The `depth_bias_clamp` feature must be enabled in order to use a depth bias clamp
+    /// different from 0.0.
+    DepthBiasClampMissingFeature,
+    /// Passed dynamic depth bounds, while the pipeline doesn't have depth bounds set as dynamic.
+    DepthBoundsNotDynamic,
+    /// The pipeline has dynamic depth bounds, but no depth bounds were passed.
+    DepthBoundsMissing,
+    /// The depth bounds must be between 0.0 and 1.0.
+    DepthBoundsOutOfRange,
+    /// Passed dynamic blend constants, while the pipeline doesn't have blend constants set as
+    /// dynamic.
+    BlendConstantsNotDynamic,
+    /// The pipeline has dynamic blend constants, but no blend constants were passed.
+    BlendConstantsMissing,
+    /// Passed dynamic stencil compare masks, while the pipeline doesn't have stencil compare masks
+    /// set as dynamic.
+    StencilCompareMaskNotDynamic,
+    /// The pipeline has dynamic stencil compare masks, but no stencil compare masks were passed.
+    StencilCompareMaskMissing,
+    /// Passed dynamic stencil write masks, while the pipeline doesn't have stencil write masks set
+    /// as dynamic.
+    StencilWriteMaskNotDynamic,
+    /// The pipeline has dynamic stencil write masks, but no stencil write masks were passed.
+    StencilWriteMaskMissing,
+    /// Passed dynamic stencil references, while the pipeline doesn't have stencil references set as
+    /// dynamic.
+    StencilReferenceNotDynamic,
+    /// The pipeline has dynamic stencil references, but no stencil references were passed.
+    StencilReferenceMissing,
+    /// Passed a dynamic primitive topology, while the pipeline doesn't have the primitive topology
+    /// set as dynamic.
+    PrimitiveTopologyNotDynamic,
+    /// The pipeline has a dynamic primitive topology, but no primitive topology was passed.
+    PrimitiveTopologyMissing,
+    /// The dynamic primitive topology doesn't belong to the same class as the topology the
+    /// pipeline was created with.
+    PrimitiveTopologyClassMismatch {
+        /// Topology the pipeline was created with.
+        expected: PrimitiveTopology,
+        /// Topology that was passed.
+        obtained: PrimitiveTopology,
+    },
+    /// Passed a dynamic cull mode, while the pipeline doesn't have the cull mode set as dynamic.
+    CullModeNotDynamic,
+    /// The pipeline has a dynamic cull mode, but no cull mode was passed.
+    CullModeMissing,
+    /// Passed a dynamic front face, while the pipeline doesn't have the front face set as dynamic.
+    FrontFaceNotDynamic,
+    /// The pipeline has a dynamic front face, but no front face was passed.
+    FrontFaceMissing,
+    /// Passed a dynamic depth test enable, while the pipeline doesn't have the depth test enable
+    /// set as dynamic.
+    DepthTestEnableNotDynamic,
+    /// The pipeline has a dynamic depth test enable, but no depth test enable was passed.
+    DepthTestEnableMissing,
+    /// Passed a dynamic depth write enable, while the pipeline doesn't have the depth write enable
+    /// set as dynamic.
+    DepthWriteEnableNotDynamic,
+    /// The pipeline has a dynamic depth write enable, but no depth write enable was passed.
+    DepthWriteEnableMissing,
+    /// Passed a dynamic depth compare operation, while the pipeline doesn't have the depth compare
+    /// operation set as dynamic.
+    DepthCompareOpNotDynamic,
+    /// The pipeline has a dynamic depth compare operation, but no depth compare operation was
+    /// passed.
+    DepthCompareOpMissing,
+    /// Passed a dynamic depth bounds test enable, while the pipeline doesn't have the depth bounds
+    /// test enable set as dynamic.
+    DepthBoundsTestEnableNotDynamic,
+    /// The pipeline has a dynamic depth bounds test enable, but no depth bounds test enable was
+    /// passed.
+    DepthBoundsTestEnableMissing,
+    /// Passed a dynamic stencil test enable, while the pipeline doesn't have the stencil test
+    /// enable set as dynamic.
+    StencilTestEnableNotDynamic,
+    /// The pipeline has a dynamic stencil test enable, but no stencil test enable was passed.
+    StencilTestEnableMissing,
+    /// Passed dynamic stencil operations, while the pipeline doesn't have the stencil operations
+    /// set as dynamic.
+    StencilOpNotDynamic,
+    /// The pipeline has dynamic stencil operations, but no stencil operations were passed.
+    StencilOpMissing,
+    /// Passed dynamic vertex strides, while the pipeline doesn't have the vertex strides set as
+    /// dynamic.
+    VertexStridesNotDynamic,
+    /// The pipeline has dynamic vertex strides, but no vertex strides were passed.
+    VertexStridesMissing,
+    /// Passed a dynamic line stipple, while the pipeline doesn't have the line stipple set as
+    /// dynamic.
+    LineStippleNotDynamic,
+    /// The pipeline has a dynamic line stipple, but no line stipple was passed.
+    LineStippleMissing,
+    /// The line stipple factor must be between 1 and 256.
+    LineStippleFactorOutOfRange,
 }
 
 impl error::Error for CheckDynamicStateValidityError {
@@ -144,6 +447,148 @@ impl error::Error for CheckDynamicStateValidityError {
             CheckDynamicStateValidityError::ScissorsCountMismatch { .. } => {
                 "the number of dynamic scissors doesn't match the expected number of scissors"
             },
+            CheckDynamicStateValidityError::DepthBiasNotDynamic => {
+                "passed a dynamic depth bias, while the pipeline doesn't have depth bias set as \
+                 dynamic"
+            },
+            CheckDynamicStateValidityError::DepthBiasMissing => {
+                "the pipeline has a dynamic depth bias, but no depth bias value was passed"
+            },
+            CheckDynamicStateValidityError::DepthBiasClampMissingFeature => {
+                "the `depth_bias_clamp` feature must be enabled in order to use a depth bias clamp \
+                 different from 0.0"
+            },
+            CheckDynamicStateValidityError::DepthBoundsNotDynamic => {
+                "passed dynamic depth bounds, while the pipeline doesn't have depth bounds set as \
+                 dynamic"
+            },
+            CheckDynamicStateValidityError::DepthBoundsMissing => {
+                "the pipeline has dynamic depth bounds, but no depth bounds were passed"
+            },
+            CheckDynamicStateValidityError::DepthBoundsOutOfRange => {
+                "the depth bounds must be between 0.0 and 1.0"
+            },
+            CheckDynamicStateValidityError::BlendConstantsNotDynamic => {
+                "passed dynamic blend constants, while the pipeline doesn't have blend constants \
+                 set as dynamic"
+            },
+            CheckDynamicStateValidityError::BlendConstantsMissing => {
+                "the pipeline has dynamic blend constants, but no blend constants were passed"
+            },
+            CheckDynamicStateValidityError::StencilCompareMaskNotDynamic => {
+                "passed dynamic stencil compare masks, while the pipeline doesn't have stencil \
+                 compare masks set as dynamic"
+            },
+            CheckDynamicStateValidityError::StencilCompareMaskMissing => {
+                "the pipeline has dynamic stencil compare masks, but no stencil compare masks were \
+                 passed"
+            },
+            CheckDynamicStateValidityError::StencilWriteMaskNotDynamic => {
+                "passed dynamic stencil write masks, while the pipeline doesn't have stencil write \
+                 masks set as dynamic"
+            },
+            CheckDynamicStateValidityError::StencilWriteMaskMissing => {
+                "the pipeline has dynamic stencil write masks, but no stencil write masks were \
+                 passed"
+            },
+            CheckDynamicStateValidityError::StencilReferenceNotDynamic => {
+                "passed dynamic stencil references, while the pipeline doesn't have stencil \
+                 references set as dynamic"
+            },
+            CheckDynamicStateValidityError::StencilReferenceMissing => {
+                "the pipeline has dynamic stencil references, but no stencil references were passed"
+            },
+            CheckDynamicStateValidityError::PrimitiveTopologyNotDynamic => {
+                "passed a dynamic primitive topology, while the pipeline doesn't have the \
+                 primitive topology set as dynamic"
+            },
+            CheckDynamicStateValidityError::PrimitiveTopologyMissing => {
+                "the pipeline has a dynamic primitive topology, but no primitive topology was \
+                 passed"
+            },
+            CheckDynamicStateValidityError::PrimitiveTopologyClassMismatch { .. } => {
+                "the dynamic primitive topology doesn't belong to the same class as the topology \
+                 the pipeline was created with"
+            },
+            CheckDynamicStateValidityError::CullModeNotDynamic => {
+                "passed a dynamic cull mode, while the pipeline doesn't have the cull mode set as \
+                 dynamic"
+            },
+            CheckDynamicStateValidityError::CullModeMissing => {
+                "the pipeline has a dynamic cull mode, but no cull mode was passed"
+            },
+            CheckDynamicStateValidityError::FrontFaceNotDynamic => {
+                "passed a dynamic front face, while the pipeline doesn't have the front face set \
+                 as dynamic"
+            },
+            CheckDynamicStateValidityError::FrontFaceMissing => {
+                "the pipeline has a dynamic front face, but no front face was passed"
+            },
+            CheckDynamicStateValidityError::DepthTestEnableNotDynamic => {
+                "passed a dynamic depth test enable, while the pipeline doesn't have the depth \
+                 test enable set as dynamic"
+            },
+            CheckDynamicStateValidityError::DepthTestEnableMissing => {
+                "the pipeline has a dynamic depth test enable, but no depth test enable was \
+                 passed"
+            },
+            CheckDynamicStateValidityError::DepthWriteEnableNotDynamic => {
+                "passed a dynamic depth write enable, while the pipeline doesn't have the depth \
+                 write enable set as dynamic"
+            },
+            CheckDynamicStateValidityError::DepthWriteEnableMissing => {
+                "the pipeline has a dynamic depth write enable, but no depth write enable was \
+                 passed"
+            },
+            CheckDynamicStateValidityError::DepthCompareOpNotDynamic => {
+                "passed a dynamic depth compare operation, while the pipeline doesn't have the \
+                 depth compare operation set as dynamic"
+            },
+            CheckDynamicStateValidityError::DepthCompareOpMissing => {
+                "the pipeline has a dynamic depth compare operation, but no depth compare \
+                 operation was passed"
+            },
+            CheckDynamicStateValidityError::DepthBoundsTestEnableNotDynamic => {
+                "passed a dynamic depth bounds test enable, while the pipeline doesn't have the \
+                 depth bounds test enable set as dynamic"
+            },
+            CheckDynamicStateValidityError::DepthBoundsTestEnableMissing => {
+                "the pipeline has a dynamic depth bounds test enable, but no depth bounds test \
+                 enable was passed"
+            },
+            CheckDynamicStateValidityError::StencilTestEnableNotDynamic => {
+                "passed a dynamic stencil test enable, while the pipeline doesn't have the \
+                 stencil test enable set as dynamic"
+            },
+            CheckDynamicStateValidityError::StencilTestEnableMissing => {
+                "the pipeline has a dynamic stencil test enable, but no stencil test enable was \
+                 passed"
+            },
+            CheckDynamicStateValidityError::StencilOpNotDynamic => {
+                "passed dynamic stencil operations, while the pipeline doesn't have the stencil \
+                 operations set as dynamic"
+            },
+            CheckDynamicStateValidityError::StencilOpMissing => {
+                "the pipeline has dynamic stencil operations, but no stencil operations were \
+                 passed"
+            },
+            CheckDynamicStateValidityError::VertexStridesNotDynamic => {
+                "passed dynamic vertex strides, while the pipeline doesn't have the vertex \
+                 strides set as dynamic"
+            },
+            CheckDynamicStateValidityError::VertexStridesMissing => {
+                "the pipeline has dynamic vertex strides, but no vertex strides were passed"
+            },
+            CheckDynamicStateValidityError::LineStippleNotDynamic => {
+                "passed a dynamic line stipple, while the pipeline doesn't have the line stipple \
+                 set as dynamic"
+            },
+            CheckDynamicStateValidityError::LineStippleMissing => {
+                "the pipeline has a dynamic line stipple, but no line stipple was passed"
+            },
+            CheckDynamicStateValidityError::LineStippleFactorOutOfRange => {
+                "the line stipple factor must be between 1 and 256"
+            },
         }
     }
 }
@@ -0,0 +1,193 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use VulkanObject;
+use command_buffer::PipelineBarrier;
+use device::Device;
+use device::DeviceOwned;
+use sync::Event;
+use sync::PipelineStages;
+
+/// Checks whether `event` can be set or reset by a command buffer once `stages` have completed.
+///
+/// # Panic
+///
+/// - Panics if the event was not created with `device`.
+///
+pub fn check_event_stages(device: &Device, event: &Event, stages: PipelineStages)
+                          -> Result<(), CheckEventError> {
+    assert_eq!(event.device().internal_object(), device.internal_object());
+
+    if stages == PipelineStages::none() {
+        return Err(CheckEventError::NoStage);
+    }
+
+    if stages.host {
+        return Err(CheckEventError::HostStage);
+    }
+
+    Ok(())
+}
+
+/// Checks whether a command buffer can wait for `events` with the given stages and barrier.
+///
+/// The barrier itself isn't checked. Use `check_pipeline_barrier` if it isn't empty.
+///
+/// # Panic
+///
+/// - Panics if one of the events was not created with `device`.
+///
+pub fn check_wait_events(device: &Device, events: &[Arc<Event>], source_stages: PipelineStages,
+                         destination_stages: PipelineStages, barrier: &PipelineBarrier)
+                         -> Result<(), CheckEventError> {
+    for event in events {
+        assert_eq!(event.device().internal_object(), device.internal_object());
+    }
+
+    if events.is_empty() {
+        return Err(CheckEventError::NoEvent);
+    }
+
+    if source_stages == PipelineStages::none() || destination_stages == PipelineStages::none() {
+        return Err(CheckEventError::NoStage);
+    }
+
+    if barrier.by_region {
+        return Err(CheckEventError::ByRegion);
+    }
+
+    // The stages of the barriers are merged into the stage masks of the command, which must
+    // stay equal to the stages that were passed when setting the events.
+    let sources = barrier
+        .memory_barriers
+        .iter()
+        .map(|b| (b.source_stages, b.destination_stages))
+        .chain(barrier
+                   .buffer_barriers
+                   .iter()
+                   .map(|b| (b.source_stages, b.destination_stages)))
+        .chain(barrier
+                   .image_barriers
+                   .iter()
+                   .map(|b| (b.source_stages, b.destination_stages)));
+
+    for (source, destination) in sources {
+        if (source_stages | source) != source_stages ||
+            (destination_stages | destination) != destination_stages
+        {
+            return Err(CheckEventError::BarrierStagesNotIncluded);
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether an event command is valid.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckEventError {
+    /// No event was passed.
+    NoEvent,
+    /// The stages are empty.
+    NoStage,
+    /// The stages contain the host stage, which a command buffer can't set or reset an event in.
+    HostStage,
+    /// Waiting for events can't create a dependency by region.
+    ByRegion,
+    /// The stages of a barrier aren't included in the stages of the command.
+    BarrierStagesNotIncluded,
+}
+
+impl error::Error for CheckEventError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckEventError::NoEvent => {
+                "no event was passed"
+            },
+            CheckEventError::NoStage => {
+                "the stages are empty"
+            },
+            CheckEventError::HostStage => {
+                "the stages contain the host stage, which a command buffer can't set or reset an \
+                 event in"
+            },
+            CheckEventError::ByRegion => {
+                "waiting for events can't create a dependency by region"
+            },
+            CheckEventError::BarrierStagesNotIncluded => {
+                "the stages of a barrier aren't included in the stages of the command"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckEventError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use command_buffer::MemoryBarrier;
+    use sync::AccessFlagBits;
+
+    #[test]
+    fn host_stage() {
+        let (device, _) = gfx_dev_and_queue!();
+        let event = Event::alloc(device.clone()).unwrap();
+
+        let stages = PipelineStages {
+            host: true,
+            ..PipelineStages::none()
+        };
+
+        match check_event_stages(&device, &event, stages) {
+            Err(CheckEventError::HostStage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn barrier_stages_not_included() {
+        let (device, _) = gfx_dev_and_queue!();
+        let event = Arc::new(Event::alloc(device.clone()).unwrap());
+
+        let transfer = PipelineStages {
+            transfer: true,
+            ..PipelineStages::none()
+        };
+
+        let barrier = PipelineBarrier {
+            memory_barriers: vec![
+                MemoryBarrier {
+                    source_stages: PipelineStages {
+                        compute_shader: true,
+                        ..PipelineStages::none()
+                    },
+                    source_access: AccessFlagBits::none(),
+                    destination_stages: transfer,
+                    destination_access: AccessFlagBits::none(),
+                },
+            ],
+            ..PipelineBarrier::new()
+        };
+
+        match check_wait_events(&device, &[event], transfer, transfer, &barrier) {
+            Err(CheckEventError::BarrierStagesNotIncluded) => (),
+            _ => panic!(),
+        }
+    }
+}
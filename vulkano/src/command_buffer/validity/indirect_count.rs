@@ -0,0 +1,229 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use buffer::TypedBufferAccess;
+use device::Device;
+use device::DeviceOwned;
+
+/// Checks whether the buffers passed to an indirect draw command whose draw count is read from
+/// a buffer are valid.
+///
+/// # Panic
+///
+/// - Panics if one of the buffers was not created with `device`.
+///
+pub fn check_indirect_count<B, C, T>(device: &Device, indirect_buffer: &B, count_buffer: &C,
+                                     max_draw_count: u32)
+                                     -> Result<(), CheckIndirectCountError>
+    where B: ?Sized + TypedBufferAccess<Content = [T]>,
+          C: ?Sized + TypedBufferAccess<Content = u32>
+{
+    assert_eq!(indirect_buffer.inner().buffer.device().internal_object(),
+               device.internal_object());
+    assert_eq!(count_buffer.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if !device.loaded_extensions().khr_draw_indirect_count {
+        return Err(CheckIndirectCountError::ExtensionNotEnabled);
+    }
+
+    if !indirect_buffer.inner().buffer.usage_indirect_buffer() ||
+        !count_buffer.inner().buffer.usage_indirect_buffer()
+    {
+        return Err(CheckIndirectCountError::MissingUsage);
+    }
+
+    if count_buffer.inner().offset % 4 != 0 {
+        return Err(CheckIndirectCountError::UnalignedCountBuffer);
+    }
+
+    if max_draw_count as usize > indirect_buffer.len() {
+        return Err(CheckIndirectCountError::MaxDrawCountOutOfRange {
+                       max_draw_count: max_draw_count,
+                       buffer_len: indirect_buffer.len(),
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking the validity of an indirect count draw command.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckIndirectCountError {
+    /// The `VK_KHR_draw_indirect_count` extension wasn't enabled on the device.
+    ExtensionNotEnabled,
+    /// The indirect buffer or the count buffer is missing the `indirect_buffer` usage.
+    MissingUsage,
+    /// The offset of the count buffer isn't a multiple of 4.
+    UnalignedCountBuffer,
+    /// The maximum number of draws is larger than the number of commands in the indirect buffer.
+    MaxDrawCountOutOfRange {
+        /// The maximum number of draws that was requested.
+        max_draw_count: u32,
+        /// The number of commands in the indirect buffer.
+        buffer_len: usize,
+    },
+}
+
+impl error::Error for CheckIndirectCountError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckIndirectCountError::ExtensionNotEnabled => {
+                "the `VK_KHR_draw_indirect_count` extension wasn't enabled on the device"
+            },
+            CheckIndirectCountError::MissingUsage => {
+                "the indirect buffer or the count buffer is missing the `indirect_buffer` usage"
+            },
+            CheckIndirectCountError::UnalignedCountBuffer => {
+                "the offset of the count buffer isn't a multiple of 4"
+            },
+            CheckIndirectCountError::MaxDrawCountOutOfRange { .. } => {
+                "the maximum number of draws is larger than the number of commands in the \
+                 indirect buffer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckIndirectCountError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter;
+
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::DrawIndirectCommand;
+
+    // Creates a device with the `VK_KHR_draw_indirect_count` extension, or returns if it isn't
+    // supported.
+    macro_rules! indirect_count_device {
+        () => ({
+            use device::DeviceExtensions;
+            use features::Features;
+            use instance;
+
+            let instance = instance!();
+
+            let physical = match instance::PhysicalDevice::enumerate(&instance).next() {
+                Some(p) => p,
+                None => return,
+            };
+
+            let queue = match physical.queue_families().find(|q| q.supports_graphics()) {
+                Some(q) => q,
+                None => return,
+            };
+
+            let extensions = DeviceExtensions {
+                khr_draw_indirect_count: true,
+                ..DeviceExtensions::none()
+            };
+
+            match Device::new(physical, &Features::none(), &extensions,
+                              [(queue, 0.5)].iter().cloned()) {
+                Ok((device, _)) => device,
+                Err(_) => return,
+            }
+        });
+    }
+
+    fn commands(count: usize) -> iter::Take<iter::Repeat<DrawIndirectCommand>> {
+        let command = DrawIndirectCommand {
+            vertex_count: 3,
+            instance_count: 1,
+            first_vertex: 0,
+            first_instance: 0,
+        };
+        iter::repeat(command).take(count)
+    }
+
+    #[test]
+    fn extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let indirect = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                      BufferUsage::indirect_buffer(),
+                                                      commands(1))
+            .unwrap();
+        let count = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::indirect_buffer(),
+                                                   1u32)
+            .unwrap();
+
+        match check_indirect_count(&device, &indirect, &count, 1) {
+            Err(CheckIndirectCountError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn missing_usage() {
+        let device = indirect_count_device!();
+        let indirect = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                      BufferUsage::indirect_buffer(),
+                                                      commands(1))
+            .unwrap();
+        let count = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::vertex_buffer(),
+                                                   1u32)
+            .unwrap();
+
+        match check_indirect_count(&device, &indirect, &count, 1) {
+            Err(CheckIndirectCountError::MissingUsage) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn max_draw_count_out_of_range() {
+        let device = indirect_count_device!();
+        let indirect = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                      BufferUsage::indirect_buffer(),
+                                                      commands(2))
+            .unwrap();
+        let count = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::indirect_buffer(),
+                                                   1u32)
+            .unwrap();
+
+        check_indirect_count(&device, &indirect, &count, 2).unwrap();
+
+        match check_indirect_count(&device, &indirect, &count, 3) {
+            Err(CheckIndirectCountError::MaxDrawCountOutOfRange {
+                    max_draw_count: 3,
+                    buffer_len: 2,
+                }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn wrong_device() {
+        let (dev1, _) = gfx_dev_and_queue!();
+        let (dev2, _) = gfx_dev_and_queue!();
+        let indirect = CpuAccessibleBuffer::from_iter(dev1.clone(),
+                                                      BufferUsage::indirect_buffer(),
+                                                      commands(1))
+            .unwrap();
+        let count = CpuAccessibleBuffer::from_data(dev1, BufferUsage::indirect_buffer(), 1u32)
+            .unwrap();
+
+        assert_should_panic!({
+                                 let _ = check_indirect_count(&dev2, &indirect, &count, 1);
+                             });
+    }
+}
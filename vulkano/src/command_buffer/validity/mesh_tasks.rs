@@ -0,0 +1,106 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+
+/// Checks whether the numbers of workgroups of a `draw_mesh_tasks` command are supported by the
+/// device.
+///
+/// The workgroups are those of the task shader if `task_shader` is true, and of the mesh shader
+/// otherwise.
+pub fn check_draw_mesh_tasks(device: &Device, group_counts: [u32; 3], task_shader: bool)
+                             -> Result<(), CheckDrawMeshTasksError> {
+    let properties = device.mesh_shader_properties();
+    let (max, max_total) = if task_shader {
+        (properties.max_task_work_group_count, properties.max_task_work_group_total_count)
+    } else {
+        (properties.max_mesh_work_group_count, properties.max_mesh_work_group_total_count)
+    };
+
+    if (0 .. 3).any(|i| group_counts[i] > max[i]) {
+        return Err(CheckDrawMeshTasksError::UnsupportedDimensions {
+                       requested: group_counts,
+                       max_supported: max,
+                   });
+    }
+
+    let total = group_counts.iter().fold(1u64, |total, &count| total * count as u64);
+    if total > max_total as u64 {
+        return Err(CheckDrawMeshTasksError::TooManyWorkgroups {
+                       requested: total,
+                       max_supported: max_total,
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking the validity of a `draw_mesh_tasks` command.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDrawMeshTasksError {
+    /// The numbers of workgroups are larger than the `maxTaskWorkGroupCount` or
+    /// `maxMeshWorkGroupCount` limit of the device.
+    UnsupportedDimensions {
+        /// The requested numbers of workgroups.
+        requested: [u32; 3],
+        /// The actual supported numbers of workgroups.
+        max_supported: [u32; 3],
+    },
+    /// The total number of workgroups is larger than the `maxTaskWorkGroupTotalCount` or
+    /// `maxMeshWorkGroupTotalCount` limit of the device.
+    TooManyWorkgroups {
+        /// The requested total number of workgroups.
+        requested: u64,
+        /// The actual supported total number of workgroups.
+        max_supported: u32,
+    },
+}
+
+impl error::Error for CheckDrawMeshTasksError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDrawMeshTasksError::UnsupportedDimensions { .. } => {
+                "the numbers of workgroups are too large for the device's limits"
+            },
+            CheckDrawMeshTasksError::TooManyWorkgroups { .. } => {
+                "the total number of workgroups is too large for the device's limits"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDrawMeshTasksError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::validity;
+
+    #[test]
+    fn max_checked() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let attempted = [u32::max_value(), 1, 1];
+
+        match validity::check_draw_mesh_tasks(&device, attempted, false) {
+            Err(validity::CheckDrawMeshTasksError::UnsupportedDimensions { requested, .. }) => {
+                assert_eq!(requested, attempted);
+            },
+            _ => panic!(),
+        }
+    }
+}
@@ -9,31 +9,80 @@
 
 //! Functions that check the validity of commands.
 
+pub use self::acceleration_structure::{CheckBuildAccelerationStructureError,
+                                       CheckCopyAccelerationStructureError,
+                                       CheckWriteAccelerationStructureCompactedSizeError,
+                                       check_build_acceleration_structure,
+                                       check_build_acceleration_structure_scratch,
+                                       check_copy_acceleration_structure,
+                                       check_write_acceleration_structure_compacted_size};
 pub use self::blit_image::{CheckBlitImageError, check_blit_image};
+pub use self::clear_attachments::{CheckClearAttachmentsError, check_clear_attachments};
 pub use self::clear_color_image::{CheckClearColorImageError, check_clear_color_image};
+pub use self::conditional_rendering::{CheckConditionalRenderingError,
+                                      check_conditional_rendering};
 pub use self::copy_buffer::{CheckCopyBuffer, CheckCopyBufferError, check_copy_buffer};
 pub use self::copy_image::{CheckCopyImageError, check_copy_image};
 pub use self::copy_image_buffer::{CheckCopyBufferImageError, CheckCopyBufferImageTy,
                                   check_copy_buffer_image};
+pub use self::debug_label::{CheckDebugLabelError, check_debug_label};
 pub use self::descriptor_sets::{CheckDescriptorSetsValidityError, check_descriptor_sets_validity};
-pub use self::dispatch::{CheckDispatchError, check_dispatch};
+pub use self::device_group::{CheckDeviceGroupError, check_device_group_render_pass,
+                             check_device_mask};
+pub use self::diagnostics::{CheckCheckpointError, CheckWriteBufferMarkerError, check_checkpoint,
+                            check_write_buffer_marker};
+pub use self::dispatch::{CheckDispatchBaseError, CheckDispatchError, check_dispatch,
+                         check_dispatch_base};
 pub use self::dynamic_state::{CheckDynamicStateValidityError, check_dynamic_state_validity};
+pub use self::event::{CheckEventError, check_event_stages, check_wait_events};
 pub use self::fill_buffer::{CheckFillBufferError, check_fill_buffer};
+pub use self::indirect_count::{CheckIndirectCountError, check_indirect_count};
 pub use self::index_buffer::{CheckIndexBuffer, CheckIndexBufferError, check_index_buffer};
-pub use self::push_constants::{CheckPushConstantsValidityError, check_push_constants_validity};
+pub use self::mesh_tasks::{CheckDrawMeshTasksError, check_draw_mesh_tasks};
+pub use self::multi_draw::{CheckMultiDrawError, check_multi_draw};
+pub use self::pipeline_barrier::{CheckPipelineBarrierError, check_pipeline_barrier};
+pub use self::push_constants::{CheckPushConstantsRangeError, CheckPushConstantsValidityError,
+                               check_push_constants_range, check_push_constants_validity};
+pub use self::query::{CheckBeginQueryError, CheckCopyQueryPoolResultsError,
+                      CheckResetQueryPoolError, CheckWriteTimestampError, check_begin_query,
+                      check_copy_query_pool_results, check_reset_query_pool,
+                      check_write_timestamp};
+pub use self::trace_rays::{CheckTraceRaysError, check_trace_rays};
+pub use self::transform_feedback::{CheckDrawIndirectByteCountError,
+                                   CheckTransformFeedbackBuffersError,
+                                   CheckTransformFeedbackCountersError,
+                                   check_draw_indirect_byte_count,
+                                   check_transform_feedback_buffers,
+                                   check_transform_feedback_counters};
 pub use self::update_buffer::{CheckUpdateBufferError, check_update_buffer};
 pub use self::vertex_buffers::{CheckVertexBuffer, CheckVertexBufferError, check_vertex_buffers};
+pub use self::viewport::{CheckSetViewportError, check_set_scissor, check_set_viewport};
 
+mod acceleration_structure;
 mod blit_image;
+mod clear_attachments;
 mod clear_color_image;
+mod conditional_rendering;
 mod copy_buffer;
 mod copy_image;
 mod copy_image_buffer;
+mod debug_label;
 mod descriptor_sets;
+mod device_group;
+mod diagnostics;
 mod dispatch;
 mod dynamic_state;
+mod event;
 mod fill_buffer;
 mod index_buffer;
+mod indirect_count;
+mod mesh_tasks;
+mod multi_draw;
+mod pipeline_barrier;
 mod push_constants;
+mod query;
+mod trace_rays;
+mod transform_feedback;
 mod update_buffer;
 mod vertex_buffers;
+mod viewport;
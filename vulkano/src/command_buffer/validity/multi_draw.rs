@@ -0,0 +1,101 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+
+/// Checks whether the draws of a multi-draw command are valid.
+///
+/// Each element of `ranges` is the first vertex or index of a draw and its number of vertices or
+/// indices. `available` is the number of vertices or indices that can be read from the bound
+/// buffers.
+pub fn check_multi_draw<I>(device: &Device, ranges: I, available: usize)
+                           -> Result<(), CheckMultiDrawError>
+    where I: ExactSizeIterator<Item = (u32, u32)>
+{
+    if !device.multi_draw_enabled() {
+        return Err(CheckMultiDrawError::FeatureNotEnabled);
+    }
+
+    let draw_count = ranges.len();
+    if draw_count > device.max_multi_draw_count() as usize {
+        return Err(CheckMultiDrawError::TooManyDraws {
+                       draw_count: draw_count,
+                       max: device.max_multi_draw_count(),
+                   });
+    }
+
+    for (draw, (first, count)) in ranges.enumerate() {
+        if first as u64 + count as u64 > available as u64 {
+            return Err(CheckMultiDrawError::DrawOutOfRange { draw: draw });
+        }
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking the validity of a multi-draw command.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckMultiDrawError {
+    /// The `multiDraw` feature of the `VK_EXT_multi_draw` extension isn't enabled.
+    FeatureNotEnabled,
+    /// The number of draws is larger than the `maxMultiDrawCount` limit of the device.
+    TooManyDraws {
+        /// The number of draws of the command.
+        draw_count: usize,
+        /// The limit of the device.
+        max: u32,
+    },
+    /// A draw reads vertices or indices beyond the end of the bound buffers.
+    DrawOutOfRange {
+        /// The index of the faulty draw.
+        draw: usize,
+    },
+}
+
+impl error::Error for CheckMultiDrawError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckMultiDrawError::FeatureNotEnabled => {
+                "the `multiDraw` feature of the `VK_EXT_multi_draw` extension isn't enabled"
+            },
+            CheckMultiDrawError::TooManyDraws { .. } => {
+                "the number of draws is larger than the `maxMultiDrawCount` limit of the device"
+            },
+            CheckMultiDrawError::DrawOutOfRange { .. } => {
+                "a draw reads vertices or indices beyond the end of the bound buffers"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckMultiDrawError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match check_multi_draw(&device, vec![(0, 3)].into_iter(), 3) {
+            Err(CheckMultiDrawError::FeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use command_buffer::PipelineBarrier;
+use device::Device;
+use device::DeviceOwned;
+use image::ImageLayout;
+use sync::AccessFlagBits;
+use sync::PipelineStages;
+
+/// Checks whether an explicit pipeline barrier is valid.
+///
+/// # Panic
+///
+/// - Panics if one of the buffers or images of the barrier was not created with `device`.
+///
+pub fn check_pipeline_barrier(device: &Device, barrier: &PipelineBarrier)
+                              -> Result<(), CheckPipelineBarrierError> {
+    if barrier.is_empty() {
+        return Err(CheckPipelineBarrierError::Empty);
+    }
+
+    for b in barrier.memory_barriers.iter() {
+        check_stages(b.source_stages, b.source_access)?;
+        check_stages(b.destination_stages, b.destination_access)?;
+    }
+
+    for (num, b) in barrier.buffer_barriers.iter().enumerate() {
+        assert_eq!(b.buffer.inner().buffer.device().internal_object(),
+                   device.internal_object());
+
+        check_stages(b.source_stages, b.source_access)?;
+        check_stages(b.destination_stages, b.destination_access)?;
+
+        if b.range.start >= b.range.end || b.range.end > b.buffer.size() {
+            return Err(CheckPipelineBarrierError::BufferRangeOutOfBounds { barrier: num });
+        }
+
+        if !check_queue_family_transfer(device, b.queue_family_transfer) {
+            return Err(CheckPipelineBarrierError::InvalidBufferQueueFamily { barrier: num });
+        }
+    }
+
+    for (num, b) in barrier.image_barriers.iter().enumerate() {
+        assert_eq!(b.image.inner().image.device().internal_object(),
+                   device.internal_object());
+
+        check_stages(b.source_stages, b.source_access)?;
+        check_stages(b.destination_stages, b.destination_access)?;
+
+        if b.mipmap_levels.start >= b.mipmap_levels.end ||
+            b.mipmap_levels.end > b.image.mipmap_levels() ||
+            b.array_layers.start >= b.array_layers.end ||
+            b.array_layers.end > b.image.dimensions().array_layers()
+        {
+            return Err(CheckPipelineBarrierError::ImageRangeOutOfBounds { barrier: num });
+        }
+
+        if b.new_layout == ImageLayout::Undefined || b.new_layout == ImageLayout::Preinitialized {
+            return Err(CheckPipelineBarrierError::InvalidNewLayout { barrier: num });
+        }
+
+        if !check_queue_family_transfer(device, b.queue_family_transfer) {
+            return Err(CheckPipelineBarrierError::InvalidImageQueueFamily { barrier: num });
+        }
+
+        // The layout of images is tracked for whole images, so a transition must apply to all
+        // the subresources.
+        if b.old_layout != b.new_layout &&
+            (b.mipmap_levels.start != 0 || b.mipmap_levels.end != b.image.mipmap_levels() ||
+                 b.array_layers.start != 0 ||
+                 b.array_layers.end != b.image.dimensions().array_layers())
+        {
+            return Err(CheckPipelineBarrierError::PartialLayoutTransition { barrier: num });
+        }
+    }
+
+    Ok(())
+}
+
+// Returns false if a queue family of the transfer doesn't exist on the physical device.
+fn check_queue_family_transfer(device: &Device, transfer: Option<(u32, u32)>) -> bool {
+    match transfer {
+        Some((source, destination)) => {
+            let physical_device = device.physical_device();
+            physical_device.queue_family_by_id(source).is_some() &&
+                physical_device.queue_family_by_id(destination).is_some()
+        },
+        None => true,
+    }
+}
+
+fn check_stages(stages: PipelineStages, access: AccessFlagBits)
+                -> Result<(), CheckPipelineBarrierError> {
+    if stages == PipelineStages::none() {
+        return Err(CheckPipelineBarrierError::NoStage);
+    }
+
+    if !access.is_compatible_with(&stages) {
+        return Err(CheckPipelineBarrierError::AccessNotSupportedByStages);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking whether a pipeline barrier is valid.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckPipelineBarrierError {
+    /// The barrier contains no memory, buffer or image barrier.
+    Empty,
+    /// The source or destination stages of a barrier are empty.
+    NoStage,
+    /// An access of a barrier can't be performed by its stages.
+    AccessNotSupportedByStages,
+    /// The range of a buffer barrier is empty or goes beyond the end of the buffer.
+    BufferRangeOutOfBounds {
+        /// Index of the faulty buffer barrier.
+        barrier: usize,
+    },
+    /// The mipmap levels or array layers of an image barrier are empty or out of range.
+    ImageRangeOutOfBounds {
+        /// Index of the faulty image barrier.
+        barrier: usize,
+    },
+    /// The new layout of an image barrier is `Undefined` or `Preinitialized`.
+    InvalidNewLayout {
+        /// Index of the faulty image barrier.
+        barrier: usize,
+    },
+    /// An image barrier changes the layout of only some of the subresources of an image.
+    PartialLayoutTransition {
+        /// Index of the faulty image barrier.
+        barrier: usize,
+    },
+    /// A queue family of the ownership transfer of a buffer barrier doesn't exist.
+    InvalidBufferQueueFamily {
+        /// Index of the faulty buffer barrier.
+        barrier: usize,
+    },
+    /// A queue family of the ownership transfer of an image barrier doesn't exist.
+    InvalidImageQueueFamily {
+        /// Index of the faulty image barrier.
+        barrier: usize,
+    },
+}
+
+impl error::Error for CheckPipelineBarrierError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckPipelineBarrierError::Empty => {
+                "the barrier contains no memory, buffer or image barrier"
+            },
+            CheckPipelineBarrierError::NoStage => {
+                "the source or destination stages of a barrier are empty"
+            },
+            CheckPipelineBarrierError::AccessNotSupportedByStages => {
+                "an access of a barrier can't be performed by its stages"
+            },
+            CheckPipelineBarrierError::BufferRangeOutOfBounds { .. } => {
+                "the range of a buffer barrier is empty or goes beyond the end of the buffer"
+            },
+            CheckPipelineBarrierError::ImageRangeOutOfBounds { .. } => {
+                "the mipmap levels or array layers of an image barrier are empty or out of range"
+            },
+            CheckPipelineBarrierError::InvalidNewLayout { .. } => {
+                "the new layout of an image barrier is `Undefined` or `Preinitialized`"
+            },
+            CheckPipelineBarrierError::PartialLayoutTransition { .. } => {
+                "an image barrier changes the layout of only some of the subresources of an image"
+            },
+            CheckPipelineBarrierError::InvalidBufferQueueFamily { .. } => {
+                "a queue family of the ownership transfer of a buffer barrier doesn't exist"
+            },
+            CheckPipelineBarrierError::InvalidImageQueueFamily { .. } => {
+                "a queue family of the ownership transfer of an image barrier doesn't exist"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckPipelineBarrierError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::BufferMemoryBarrier;
+    use command_buffer::MemoryBarrier;
+    use std::sync::Arc;
+
+    #[test]
+    fn empty() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match check_pipeline_barrier(&device, &PipelineBarrier::new()) {
+            Err(CheckPipelineBarrierError::Empty) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn access_not_supported() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let barrier = PipelineBarrier {
+            memory_barriers: vec![
+                MemoryBarrier {
+                    source_stages: PipelineStages {
+                        transfer: true,
+                        ..PipelineStages::none()
+                    },
+                    source_access: AccessFlagBits {
+                        transfer_write: true,
+                        ..AccessFlagBits::none()
+                    },
+                    destination_stages: PipelineStages {
+                        transfer: true,
+                        ..PipelineStages::none()
+                    },
+                    destination_access: AccessFlagBits {
+                        uniform_read: true,
+                        ..AccessFlagBits::none()
+                    },
+                },
+            ],
+            ..PipelineBarrier::new()
+        };
+
+        match check_pipeline_barrier(&device, &barrier) {
+            Err(CheckPipelineBarrierError::AccessNotSupportedByStages) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn invalid_queue_family() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let stages = PipelineStages {
+            transfer: true,
+            ..PipelineStages::none()
+        };
+
+        let barrier = PipelineBarrier {
+            buffer_barriers: vec![
+                BufferMemoryBarrier {
+                    buffer: Arc::new(buffer),
+                    source_stages: stages,
+                    source_access: AccessFlagBits::none(),
+                    destination_stages: stages,
+                    destination_access: AccessFlagBits::none(),
+                    range: 0 .. 4,
+                    queue_family_transfer: Some((0, 9999)),
+                },
+            ],
+            ..PipelineBarrier::new()
+        };
+
+        match check_pipeline_barrier(&device, &barrier) {
+            Err(CheckPipelineBarrierError::InvalidBufferQueueFamily { barrier: 0 }) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -10,7 +10,9 @@
 use std::error;
 use std::fmt;
 
+use descriptor::descriptor::ShaderStages;
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
+use descriptor::pipeline_layout::PipelineLayoutDesc;
 use descriptor::pipeline_layout::PipelineLayoutPushConstantsCompatible;
 
 /// Checks whether push constants are compatible with the pipeline.
@@ -26,6 +28,61 @@ pub fn check_push_constants_validity<Pl, Pc>(pipeline: &Pl, push_constants: &Pc)
     Ok(())
 }
 
+/// Checks whether `size` bytes of push constants can be written at `offset` for `stages`, based
+/// on the push constants ranges declared by the pipeline layout.
+///
+/// Each byte that is written must belong to a range of the layout that is accessible from all the
+/// stages of `stages`, and `stages` must contain all the stages of each range that is written to.
+pub fn check_push_constants_range<Pl>(pipeline_layout: &Pl, stages: ShaderStages, offset: u32,
+                                      size: u32)
+                                      -> Result<(), CheckPushConstantsRangeError>
+    where Pl: ?Sized + PipelineLayoutDesc
+{
+    if stages == ShaderStages::none() {
+        return Err(CheckPushConstantsRangeError::NoStage);
+    }
+
+    if size == 0 {
+        return Err(CheckPushConstantsRangeError::EmptyData);
+    }
+
+    if offset % 4 != 0 || size % 4 != 0 {
+        return Err(CheckPushConstantsRangeError::WrongAlignment);
+    }
+
+    let ranges = (0 .. pipeline_layout.num_push_constants_ranges())
+        .filter_map(|num| pipeline_layout.push_constants_range(num))
+        .collect::<Vec<_>>();
+
+    let start = offset as usize;
+    let end = start + size as usize;
+
+    for range in ranges.iter() {
+        if range.offset < end && start < range.offset + range.size {
+            if stages.is_superset_of(&range.stages).is_err() {
+                return Err(CheckPushConstantsRangeError::MissingStages {
+                               range_offset: range.offset,
+                               range_size: range.size,
+                           });
+            }
+        }
+    }
+
+    // All the offsets and sizes are multiples of 4, so checking one byte out of four is enough.
+    for byte in (start .. end).filter(|b| b % 4 == 0) {
+        let accessible = ranges
+            .iter()
+            .filter(|r| r.offset <= byte && byte < r.offset + r.size)
+            .fold(ShaderStages::none(), |stages, r| stages | r.stages);
+
+        if accessible.is_superset_of(&stages).is_err() {
+            return Err(CheckPushConstantsRangeError::NotInLayout { offset: byte });
+        }
+    }
+
+    Ok(())
+}
+
 /// Error that can happen when checking push constants validity.
 #[derive(Debug, Copy, Clone)]
 pub enum CheckPushConstantsValidityError {
@@ -50,3 +107,140 @@ impl fmt::Display for CheckPushConstantsValidityError {
         write!(fmt, "{}", error::Error::description(self))
     }
 }
+
+/// Error that can happen when checking whether push constants can be written to a range.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckPushConstantsRangeError {
+    /// No shader stage was specified.
+    NoStage,
+    /// The data to write is empty.
+    EmptyData,
+    /// The offset or the size of the data isn't a multiple of 4.
+    WrongAlignment,
+    /// A byte of the data isn't part of a push constants range of the layout that is accessible
+    /// from all the requested stages.
+    NotInLayout {
+        /// Offset of the byte in the push constants.
+        offset: usize,
+    },
+    /// The data overlaps a push constants range of the layout whose stages aren't all part of
+    /// the requested stages.
+    MissingStages {
+        /// Offset of the range in the layout.
+        range_offset: usize,
+        /// Size of the range in the layout.
+        range_size: usize,
+    },
+}
+
+impl error::Error for CheckPushConstantsRangeError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckPushConstantsRangeError::NoStage => {
+                "no shader stage was specified"
+            },
+            CheckPushConstantsRangeError::EmptyData => {
+                "the data to write is empty"
+            },
+            CheckPushConstantsRangeError::WrongAlignment => {
+                "the offset or the size of the data isn't a multiple of 4"
+            },
+            CheckPushConstantsRangeError::NotInLayout { .. } => {
+                "a byte of the data isn't part of a push constants range of the layout that is \
+                 accessible from all the requested stages"
+            },
+            CheckPushConstantsRangeError::MissingStages { .. } => {
+                "the data overlaps a push constants range of the layout whose stages aren't all \
+                 part of the requested stages"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckPushConstantsRangeError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use descriptor::descriptor::DescriptorDesc;
+    use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+    use descriptor::pipeline_layout::RuntimePipelineDesc;
+    use std::iter;
+
+    // Layout with a vertex range in 0..16 and a fragment range in 16..32.
+    fn layout() -> RuntimePipelineDesc {
+        let vertex = PipelineLayoutDescPcRange {
+            offset: 0,
+            size: 16,
+            stages: ShaderStages {
+                vertex: true,
+                ..ShaderStages::none()
+            },
+        };
+
+        let fragment = PipelineLayoutDescPcRange {
+            offset: 16,
+            size: 16,
+            stages: ShaderStages {
+                fragment: true,
+                ..ShaderStages::none()
+            },
+        };
+
+        RuntimePipelineDesc::new::<_, _, iter::Empty<Option<DescriptorDesc>>>
+                                    (iter::empty(), vec![vertex, fragment])
+            .unwrap()
+    }
+
+    #[test]
+    fn range_valid() {
+        let vertex = ShaderStages {
+            vertex: true,
+            ..ShaderStages::none()
+        };
+        check_push_constants_range(&layout(), vertex, 4, 8).unwrap();
+    }
+
+    #[test]
+    fn range_outside_layout() {
+        let fragment = ShaderStages {
+            fragment: true,
+            ..ShaderStages::none()
+        };
+
+        match check_push_constants_range(&layout(), fragment, 24, 16) {
+            Err(CheckPushConstantsRangeError::NotInLayout { offset: 32 }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn range_missing_stages() {
+        let vertex = ShaderStages {
+            vertex: true,
+            ..ShaderStages::none()
+        };
+
+        match check_push_constants_range(&layout(), vertex, 12, 8) {
+            Err(CheckPushConstantsRangeError::MissingStages {
+                    range_offset: 16,
+                    range_size: 16,
+                }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn range_misaligned() {
+        match check_push_constants_range(&layout(), ShaderStages::all_graphics(), 2, 4) {
+            Err(CheckPushConstantsRangeError::WrongAlignment) => (),
+            _ => panic!(),
+        }
+    }
+}
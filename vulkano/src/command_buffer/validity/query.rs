@@ -0,0 +1,439 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::mem;
+
+use VulkanObject;
+use buffer::TypedBufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use instance::QueueFamily;
+use query::QueryResultElement;
+use query::QueryResultFlags;
+use query::QueryType;
+use query::UnsafeQueryPool;
+use sync::PipelineStages;
+
+/// Checks whether a begin query command is valid.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_begin_query(device: &Device, query_pool: &UnsafeQueryPool, query: u32,
+                         precise: bool)
+                         -> Result<(), CheckBeginQueryError> {
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    if query >= query_pool.num_slots() {
+        return Err(CheckBeginQueryError::QueryOutOfRange);
+    }
+
+    match query_pool.ty() {
+        QueryType::Occlusion => {
+            if precise && !device.enabled_features().occlusion_query_precise {
+                return Err(CheckBeginQueryError::OcclusionQueryPreciseFeatureNotEnabled);
+            }
+        },
+        QueryType::PipelineStatistics(_) => {
+            if precise {
+                return Err(CheckBeginQueryError::PreciseNotOcclusion);
+            }
+        },
+        QueryType::Timestamp |
+        QueryType::AccelerationStructureCompactedSize => {
+            return Err(CheckBeginQueryError::WrongQueryType);
+        },
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_begin_query`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckBeginQueryError {
+    /// The index of the query is out of range of the query pool.
+    QueryOutOfRange,
+    /// Only occlusion and pipeline statistics queries can be begun.
+    WrongQueryType,
+    /// A precise query was requested, but the query pool isn't an occlusion query pool.
+    PreciseNotOcclusion,
+    /// A precise occlusion query was requested, but the `occlusion_query_precise` feature isn't
+    /// enabled.
+    OcclusionQueryPreciseFeatureNotEnabled,
+}
+
+impl error::Error for CheckBeginQueryError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckBeginQueryError::QueryOutOfRange => {
+                "the index of the query is out of range of the query pool"
+            },
+            CheckBeginQueryError::WrongQueryType => {
+                "only occlusion and pipeline statistics queries can be begun"
+            },
+            CheckBeginQueryError::PreciseNotOcclusion => {
+                "a precise query was requested, but the query pool isn't an occlusion query pool"
+            },
+            CheckBeginQueryError::OcclusionQueryPreciseFeatureNotEnabled => {
+                "a precise occlusion query was requested, but the `occlusion_query_precise` \
+                 feature isn't enabled"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckBeginQueryError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a write timestamp command is valid.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_write_timestamp(device: &Device, queue_family: QueueFamily,
+                             query_pool: &UnsafeQueryPool, query: u32, stage: PipelineStages)
+                             -> Result<(), CheckWriteTimestampError> {
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    if queue_family.timestamp_valid_bits() == 0 {
+        return Err(CheckWriteTimestampError::TimestampsNotSupported);
+    }
+
+    match query_pool.ty() {
+        QueryType::Timestamp => (),
+        _ => return Err(CheckWriteTimestampError::WrongQueryType),
+    }
+
+    if query >= query_pool.num_slots() {
+        return Err(CheckWriteTimestampError::QueryOutOfRange);
+    }
+
+    if stage.into_vulkan_bits().count_ones() != 1 {
+        return Err(CheckWriteTimestampError::NotOneStage);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_write_timestamp`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckWriteTimestampError {
+    /// The query pool wasn't created with the `Timestamp` type.
+    WrongQueryType,
+    /// The index of the query is out of range of the query pool.
+    QueryOutOfRange,
+    /// Exactly one pipeline stage must be specified.
+    NotOneStage,
+    /// The queue family of the command buffer doesn't support timestamps.
+    TimestampsNotSupported,
+}
+
+impl error::Error for CheckWriteTimestampError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckWriteTimestampError::WrongQueryType => {
+                "the query pool wasn't created with the `Timestamp` type"
+            },
+            CheckWriteTimestampError::QueryOutOfRange => {
+                "the index of the query is out of range of the query pool"
+            },
+            CheckWriteTimestampError::NotOneStage => {
+                "exactly one pipeline stage must be specified"
+            },
+            CheckWriteTimestampError::TimestampsNotSupported => {
+                "the queue family of the command buffer doesn't support timestamps"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckWriteTimestampError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a range of queries is valid for a reset query pool command.
+///
+/// # Panic
+///
+/// - Panics if the query pool was not created with `device`.
+///
+pub fn check_reset_query_pool(device: &Device, query_pool: &UnsafeQueryPool, first_query: u32,
+                              count: u32)
+                              -> Result<(), CheckResetQueryPoolError> {
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+
+    if count == 0 {
+        return Err(CheckResetQueryPoolError::EmptyRange);
+    }
+
+    if first_query as u64 + count as u64 > query_pool.num_slots() as u64 {
+        return Err(CheckResetQueryPoolError::RangeOutOfBounds);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_reset_query_pool`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckResetQueryPoolError {
+    /// The range of queries is empty.
+    EmptyRange,
+    /// The range of queries goes beyond the number of slots of the query pool.
+    RangeOutOfBounds,
+}
+
+impl error::Error for CheckResetQueryPoolError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckResetQueryPoolError::EmptyRange => {
+                "the range of queries is empty"
+            },
+            CheckResetQueryPoolError::RangeOutOfBounds => {
+                "the range of queries goes beyond the number of slots of the query pool"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckResetQueryPoolError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a copy query pool results command is valid.
+///
+/// The results are written as values of type `T`, with `query_pool.ty().num_results()` values
+/// per query, followed by an availability value if `flags.with_availability` is true.
+///
+/// # Panic
+///
+/// - Panics if the query pool or the buffer was not created with `device`.
+///
+pub fn check_copy_query_pool_results<D, T>(device: &Device, query_pool: &UnsafeQueryPool,
+                                           first_query: u32, count: u32, destination: &D,
+                                           flags: QueryResultFlags)
+                                           -> Result<(), CheckCopyQueryPoolResultsError>
+    where D: ?Sized + TypedBufferAccess<Content = [T]>,
+          T: QueryResultElement
+{
+    assert_eq!(query_pool.device().internal_object(),
+               device.internal_object());
+    assert_eq!(destination.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if count == 0 {
+        return Err(CheckCopyQueryPoolResultsError::EmptyRange);
+    }
+
+    if first_query as u64 + count as u64 > query_pool.num_slots() as u64 {
+        return Err(CheckCopyQueryPoolResultsError::RangeOutOfBounds);
+    }
+
+    if !destination.inner().buffer.usage_transfer_destination() {
+        return Err(CheckCopyQueryPoolResultsError::DestinationMissingTransferUsage);
+    }
+
+    if destination.inner().offset % mem::size_of::<T>() != 0 {
+        return Err(CheckCopyQueryPoolResultsError::DestinationNotAligned);
+    }
+
+    if let QueryType::Timestamp = query_pool.ty() {
+        if flags.partial {
+            return Err(CheckCopyQueryPoolResultsError::PartialTimestampResults);
+        }
+    }
+
+    let stride = query_pool.ty().num_results() + if flags.with_availability { 1 } else { 0 };
+    let required_len = count as usize * stride as usize;
+    if destination.len() < required_len {
+        return Err(CheckCopyQueryPoolResultsError::DestinationTooSmall {
+                       required_len,
+                       actual_len: destination.len(),
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_copy_query_pool_results`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckCopyQueryPoolResultsError {
+    /// The range of queries is empty.
+    EmptyRange,
+    /// The range of queries goes beyond the number of slots of the query pool.
+    RangeOutOfBounds,
+    /// The "transfer destination" usage must be enabled on the destination buffer.
+    DestinationMissingTransferUsage,
+    /// The offset of the destination buffer isn't a multiple of the size of the results.
+    DestinationNotAligned,
+    /// Partial results were requested from a timestamp query pool.
+    PartialTimestampResults,
+    /// The destination buffer is too small to hold the results.
+    DestinationTooSmall {
+        /// Number of elements that the buffer must be able to hold.
+        required_len: usize,
+        /// Number of elements in the buffer.
+        actual_len: usize,
+    },
+}
+
+impl error::Error for CheckCopyQueryPoolResultsError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckCopyQueryPoolResultsError::EmptyRange => {
+                "the range of queries is empty"
+            },
+            CheckCopyQueryPoolResultsError::RangeOutOfBounds => {
+                "the range of queries goes beyond the number of slots of the query pool"
+            },
+            CheckCopyQueryPoolResultsError::DestinationMissingTransferUsage => {
+                "the transfer destination usage must be enabled on the destination buffer"
+            },
+            CheckCopyQueryPoolResultsError::DestinationNotAligned => {
+                "the offset of the destination buffer isn't a multiple of the size of the results"
+            },
+            CheckCopyQueryPoolResultsError::PartialTimestampResults => {
+                "partial results were requested from a timestamp query pool"
+            },
+            CheckCopyQueryPoolResultsError::DestinationTooSmall { .. } => {
+                "the destination buffer is too small to hold the results"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckCopyQueryPoolResultsError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+
+    #[test]
+    fn begin_timestamp_query() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Timestamp, 4).unwrap();
+
+        match check_begin_query(&device, &pool, 0, false) {
+            Err(CheckBeginQueryError::WrongQueryType) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn begin_query_out_of_range() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        match check_begin_query(&device, &pool, 4, false) {
+            Err(CheckBeginQueryError::QueryOutOfRange) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn timestamp_multiple_stages() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Timestamp, 4).unwrap();
+
+        let stages = PipelineStages {
+            top_of_pipe: true,
+            bottom_of_pipe: true,
+            ..PipelineStages::none()
+        };
+
+        match check_write_timestamp(&device, queue.family(), &pool, 0, stages) {
+            Err(CheckWriteTimestampError::NotOneStage) |
+            Err(CheckWriteTimestampError::TimestampsNotSupported) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn reset_out_of_bounds() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+
+        match check_reset_query_pool(&device, &pool, 2, 3) {
+            Err(CheckResetQueryPoolError::RangeOutOfBounds) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn copy_results_with_availability() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Occlusion, 4).unwrap();
+        let buffer = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                    BufferUsage::transfer_destination(),
+                                                    [0u64; 4].iter().cloned())
+            .unwrap();
+
+        assert!(check_copy_query_pool_results(&device, &pool, 0, 4, &buffer,
+                                              QueryResultFlags::none())
+                    .is_ok());
+
+        let flags = QueryResultFlags {
+            with_availability: true,
+            ..QueryResultFlags::none()
+        };
+        match check_copy_query_pool_results(&device, &pool, 0, 4, &buffer, flags) {
+            Err(CheckCopyQueryPoolResultsError::DestinationTooSmall {
+                    required_len: 8,
+                    actual_len: 4,
+                }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn copy_partial_timestamps() {
+        let (device, _) = gfx_dev_and_queue!();
+        let pool = UnsafeQueryPool::new(device.clone(), QueryType::Timestamp, 4).unwrap();
+        let buffer = CpuAccessibleBuffer::from_iter(device.clone(),
+                                                    BufferUsage::transfer_destination(),
+                                                    0 .. 4u32)
+            .unwrap();
+
+        let flags = QueryResultFlags {
+            partial: true,
+            ..QueryResultFlags::none()
+        };
+        match check_copy_query_pool_results(&device, &pool, 0, 4, &buffer, flags) {
+            Err(CheckCopyQueryPoolResultsError::PartialTimestampResults) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,164 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use buffer::BufferAccess;
+use device::Device;
+use device::DeviceOwned;
+use pipeline::ShaderBindingTableRegion;
+use pipeline::ShaderBindingTableRegions;
+
+/// Checks whether the shader binding table passed to a trace rays command is valid.
+///
+/// # Panic
+///
+/// - Panics if the buffer was not created with `device`.
+///
+pub fn check_trace_rays<B>(device: &Device, shader_binding_table: &B,
+                           regions: &ShaderBindingTableRegions)
+                           -> Result<(), CheckTraceRaysError>
+    where B: ?Sized + BufferAccess
+{
+    assert_eq!(shader_binding_table.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    {
+        let inner = shader_binding_table.inner().buffer;
+        if !inner.usage_shader_binding_table() || !inner.usage_shader_device_address() {
+            return Err(CheckTraceRaysError::MissingUsage);
+        }
+    }
+
+    if regions.raygen.size == 0 || regions.raygen.size != regions.raygen.stride {
+        return Err(CheckTraceRaysError::InvalidRaygenRegion);
+    }
+
+    let properties = device.ray_tracing_properties();
+    let address = shader_binding_table.device_address().unwrap();
+
+    for region in [regions.raygen, regions.miss, regions.hit, regions.callable].iter() {
+        check_region(region,
+                     address,
+                     shader_binding_table.size(),
+                     properties.shader_group_base_alignment as u64,
+                     properties.shader_group_handle_alignment as usize,
+                     properties.max_shader_group_stride as usize)?;
+    }
+
+    Ok(())
+}
+
+// Checks a single region of a shader binding table that starts at `address` and has `size`
+// bytes.
+fn check_region(region: &ShaderBindingTableRegion, address: u64, size: usize, alignment: u64,
+                stride_alignment: usize, max_stride: usize)
+                -> Result<(), CheckTraceRaysError> {
+    if region.size == 0 {
+        return Ok(());
+    }
+
+    if region.offset + region.size > size {
+        return Err(CheckTraceRaysError::RegionOutOfBounds);
+    }
+
+    if alignment != 0 && (address + region.offset as u64) % alignment != 0 {
+        return Err(CheckTraceRaysError::UnalignedRegion);
+    }
+
+    if stride_alignment != 0 && region.stride % stride_alignment != 0 {
+        return Err(CheckTraceRaysError::UnalignedStride);
+    }
+
+    if region.stride > max_stride {
+        return Err(CheckTraceRaysError::StrideTooLarge);
+    }
+
+    Ok(())
+}
+
+/// Error that can happen when checking trace rays command validity.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckTraceRaysError {
+    /// The shader binding table buffer is missing the `shader_binding_table` or the
+    /// `shader_device_address` usage.
+    MissingUsage,
+    /// The ray generation region must contain exactly one record.
+    InvalidRaygenRegion,
+    /// A region goes beyond the end of the shader binding table buffer.
+    RegionOutOfBounds,
+    /// The start of a region isn't aligned to `shader_group_base_alignment`.
+    UnalignedRegion,
+    /// The stride of a region isn't a multiple of `shader_group_handle_alignment`.
+    UnalignedStride,
+    /// The stride of a region is larger than `max_shader_group_stride`.
+    StrideTooLarge,
+}
+
+impl error::Error for CheckTraceRaysError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckTraceRaysError::MissingUsage => {
+                "the shader binding table buffer is missing the `shader_binding_table` or the \
+                 `shader_device_address` usage"
+            },
+            CheckTraceRaysError::InvalidRaygenRegion => {
+                "the ray generation region must contain exactly one record"
+            },
+            CheckTraceRaysError::RegionOutOfBounds => {
+                "a region goes beyond the end of the shader binding table buffer"
+            },
+            CheckTraceRaysError::UnalignedRegion => {
+                "the start of a region isn't aligned to `shader_group_base_alignment`"
+            },
+            CheckTraceRaysError::UnalignedStride => {
+                "the stride of a region isn't a multiple of `shader_group_handle_alignment`"
+            },
+            CheckTraceRaysError::StrideTooLarge => {
+                "the stride of a region is larger than `max_shader_group_stride`"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckTraceRaysError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn region_stride() {
+        let region = ShaderBindingTableRegion {
+            offset: 0,
+            stride: 48,
+            size: 96,
+        };
+
+        assert!(check_region(&region, 0, 128, 64, 16, 64).is_ok());
+
+        match check_region(&region, 0, 128, 64, 32, 64) {
+            Err(CheckTraceRaysError::UnalignedStride) => (),
+            _ => panic!(),
+        }
+
+        match check_region(&region, 0, 128, 64, 16, 32) {
+            Err(CheckTraceRaysError::StrideTooLarge) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -0,0 +1,355 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use VulkanObject;
+use buffer::BufferAccess;
+use buffer::TypedBufferAccess;
+use device::Device;
+use device::DeviceOwned;
+
+/// Checks whether buffers can be bound as transform feedback buffers, starting at binding
+/// `first_binding`.
+///
+/// # Panic
+///
+/// - Panics if one of the buffers was not created with `device`.
+///
+pub fn check_transform_feedback_buffers<'a, I>(device: &Device, first_binding: u32, buffers: I)
+                                               -> Result<(), CheckTransformFeedbackBuffersError>
+    where I: IntoIterator<Item = &'a BufferAccess>
+{
+    if !device.enabled_features().transform_feedback {
+        return Err(CheckTransformFeedbackBuffersError::FeatureNotEnabled);
+    }
+
+    let properties = device.transform_feedback_properties();
+    let mut num_bindings = 0;
+
+    for buffer in buffers {
+        assert_eq!(buffer.inner().buffer.device().internal_object(),
+                   device.internal_object());
+
+        if !buffer.inner().buffer.usage_transform_feedback_buffer() {
+            return Err(CheckTransformFeedbackBuffersError::MissingUsage);
+        }
+
+        if buffer.inner().offset % 4 != 0 {
+            return Err(CheckTransformFeedbackBuffersError::UnalignedOffset);
+        }
+
+        if buffer.size() as u64 > properties.max_transform_feedback_buffer_size {
+            return Err(CheckTransformFeedbackBuffersError::BufferTooLarge {
+                           size: buffer.size() as u64,
+                           max: properties.max_transform_feedback_buffer_size,
+                       });
+        }
+
+        num_bindings += 1;
+    }
+
+    if first_binding as u64 + num_bindings as u64 >
+        properties.max_transform_feedback_buffers as u64
+    {
+        return Err(CheckTransformFeedbackBuffersError::MaxBindingsExceeded {
+                       first_binding,
+                       num_bindings,
+                       max: properties.max_transform_feedback_buffers,
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_transform_feedback_buffers`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckTransformFeedbackBuffersError {
+    /// The `transform_feedback` feature of the `VK_EXT_transform_feedback` extension isn't
+    /// enabled.
+    FeatureNotEnabled,
+    /// The "transform feedback buffer" usage must be enabled on the buffers.
+    MissingUsage,
+    /// The offset of a buffer in its memory must be a multiple of 4.
+    UnalignedOffset,
+    /// A buffer is larger than the maximum size of a transform feedback binding.
+    BufferTooLarge {
+        /// Size of the buffer in bytes.
+        size: u64,
+        /// Maximum size supported by the physical device.
+        max: u64,
+    },
+    /// The bindings go beyond the maximum number of transform feedback buffers.
+    MaxBindingsExceeded {
+        /// Index of the first binding.
+        first_binding: u32,
+        /// Number of buffers to bind.
+        num_bindings: u32,
+        /// Maximum number of transform feedback buffers supported by the physical device.
+        max: u32,
+    },
+}
+
+impl error::Error for CheckTransformFeedbackBuffersError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckTransformFeedbackBuffersError::FeatureNotEnabled => {
+                "the `transform_feedback` feature of the `VK_EXT_transform_feedback` extension \
+                 isn't enabled"
+            },
+            CheckTransformFeedbackBuffersError::MissingUsage => {
+                "the transform feedback buffer usage must be enabled on the buffers"
+            },
+            CheckTransformFeedbackBuffersError::UnalignedOffset => {
+                "the offset of a buffer in its memory must be a multiple of 4"
+            },
+            CheckTransformFeedbackBuffersError::BufferTooLarge { .. } => {
+                "a buffer is larger than the maximum size of a transform feedback binding"
+            },
+            CheckTransformFeedbackBuffersError::MaxBindingsExceeded { .. } => {
+                "the bindings go beyond the maximum number of transform feedback buffers"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckTransformFeedbackBuffersError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether buffers can be used as the counter buffers of a transform feedback.
+///
+/// # Panic
+///
+/// - Panics if one of the buffers was not created with `device`.
+///
+pub fn check_transform_feedback_counters<'a, I>(device: &Device, counter_buffers: I)
+                                                -> Result<(), CheckTransformFeedbackCountersError>
+    where I: IntoIterator<Item = &'a BufferAccess>
+{
+    if !device.enabled_features().transform_feedback {
+        return Err(CheckTransformFeedbackCountersError::FeatureNotEnabled);
+    }
+
+    let mut num_counters = 0;
+
+    for buffer in counter_buffers {
+        assert_eq!(buffer.inner().buffer.device().internal_object(),
+                   device.internal_object());
+
+        if !buffer.inner().buffer.usage_transform_feedback_counter_buffer() {
+            return Err(CheckTransformFeedbackCountersError::MissingUsage);
+        }
+
+        if buffer.inner().offset % 4 != 0 {
+            return Err(CheckTransformFeedbackCountersError::UnalignedOffset);
+        }
+
+        if buffer.size() < 4 {
+            return Err(CheckTransformFeedbackCountersError::CounterBufferTooSmall);
+        }
+
+        num_counters += 1;
+    }
+
+    let max = device
+        .transform_feedback_properties()
+        .max_transform_feedback_buffers;
+    if num_counters > max {
+        return Err(CheckTransformFeedbackCountersError::TooManyCounterBuffers {
+                       num_counters,
+                       max,
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_transform_feedback_counters`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckTransformFeedbackCountersError {
+    /// The `transform_feedback` feature of the `VK_EXT_transform_feedback` extension isn't
+    /// enabled.
+    FeatureNotEnabled,
+    /// The "transform feedback counter buffer" usage must be enabled on the counter buffers.
+    MissingUsage,
+    /// The offset of a counter buffer in its memory must be a multiple of 4.
+    UnalignedOffset,
+    /// A counter buffer is too small to hold a 32-bits counter.
+    CounterBufferTooSmall,
+    /// There are more counter buffers than the maximum number of transform feedback buffers.
+    TooManyCounterBuffers {
+        /// Number of counter buffers.
+        num_counters: u32,
+        /// Maximum number of transform feedback buffers supported by the physical device.
+        max: u32,
+    },
+}
+
+impl error::Error for CheckTransformFeedbackCountersError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckTransformFeedbackCountersError::FeatureNotEnabled => {
+                "the `transform_feedback` feature of the `VK_EXT_transform_feedback` extension \
+                 isn't enabled"
+            },
+            CheckTransformFeedbackCountersError::MissingUsage => {
+                "the transform feedback counter buffer usage must be enabled on the counter \
+                 buffers"
+            },
+            CheckTransformFeedbackCountersError::UnalignedOffset => {
+                "the offset of a counter buffer in its memory must be a multiple of 4"
+            },
+            CheckTransformFeedbackCountersError::CounterBufferTooSmall => {
+                "a counter buffer is too small to hold a 32-bits counter"
+            },
+            CheckTransformFeedbackCountersError::TooManyCounterBuffers { .. } => {
+                "there are more counter buffers than the maximum number of transform feedback \
+                 buffers"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckTransformFeedbackCountersError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+/// Checks whether a `draw_indirect_byte_count` command is valid.
+///
+/// # Panic
+///
+/// - Panics if the counter buffer was not created with `device`.
+///
+pub fn check_draw_indirect_byte_count<B>(device: &Device, counter_buffer: &B, vertex_stride: u32)
+                                         -> Result<(), CheckDrawIndirectByteCountError>
+    where B: ?Sized + TypedBufferAccess<Content = u32>
+{
+    assert_eq!(counter_buffer.inner().buffer.device().internal_object(),
+               device.internal_object());
+
+    if !device.enabled_features().transform_feedback {
+        return Err(CheckDrawIndirectByteCountError::FeatureNotEnabled);
+    }
+
+    let properties = device.transform_feedback_properties();
+
+    if !properties.transform_feedback_draw {
+        return Err(CheckDrawIndirectByteCountError::TransformFeedbackDrawNotSupported);
+    }
+
+    if !counter_buffer
+        .inner()
+        .buffer
+        .usage_transform_feedback_counter_buffer()
+    {
+        return Err(CheckDrawIndirectByteCountError::MissingUsage);
+    }
+
+    if counter_buffer.inner().offset % 4 != 0 {
+        return Err(CheckDrawIndirectByteCountError::UnalignedCounter);
+    }
+
+    if vertex_stride == 0 || vertex_stride > properties.max_transform_feedback_buffer_data_stride {
+        return Err(CheckDrawIndirectByteCountError::InvalidVertexStride {
+                       stride: vertex_stride,
+                       max: properties.max_transform_feedback_buffer_data_stride,
+                   });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_draw_indirect_byte_count`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckDrawIndirectByteCountError {
+    /// The `transform_feedback` feature of the `VK_EXT_transform_feedback` extension isn't
+    /// enabled.
+    FeatureNotEnabled,
+    /// The physical device doesn't support drawing with a transform feedback counter.
+    TransformFeedbackDrawNotSupported,
+    /// The "transform feedback counter buffer" usage must be enabled on the counter buffer.
+    MissingUsage,
+    /// The offset of the counter in its buffer must be a multiple of 4.
+    UnalignedCounter,
+    /// The vertex stride is 0 or larger than the maximum stride of transform feedback data.
+    InvalidVertexStride {
+        /// The stride that was passed.
+        stride: u32,
+        /// Maximum stride supported by the physical device.
+        max: u32,
+    },
+}
+
+impl error::Error for CheckDrawIndirectByteCountError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckDrawIndirectByteCountError::FeatureNotEnabled => {
+                "the `transform_feedback` feature of the `VK_EXT_transform_feedback` extension \
+                 isn't enabled"
+            },
+            CheckDrawIndirectByteCountError::TransformFeedbackDrawNotSupported => {
+                "the physical device doesn't support drawing with a transform feedback counter"
+            },
+            CheckDrawIndirectByteCountError::MissingUsage => {
+                "the transform feedback counter buffer usage must be enabled on the counter \
+                 buffer"
+            },
+            CheckDrawIndirectByteCountError::UnalignedCounter => {
+                "the offset of the counter in its buffer must be a multiple of 4"
+            },
+            CheckDrawIndirectByteCountError::InvalidVertexStride { .. } => {
+                "the vertex stride is 0 or larger than the maximum stride of transform feedback \
+                 data"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckDrawIndirectByteCountError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+
+    #[test]
+    fn feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+
+        match check_transform_feedback_buffers(&device, 0, Some(&buffer as &BufferAccess)) {
+            Err(CheckTransformFeedbackBuffersError::FeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+
+        match check_draw_indirect_byte_count(&device, &buffer, 16) {
+            Err(CheckDrawIndirectByteCountError::FeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
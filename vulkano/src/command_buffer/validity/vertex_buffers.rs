@@ -13,6 +13,7 @@ use std::fmt;
 use VulkanObject;
 use buffer::BufferAccess;
 use device::DeviceOwned;
+use pipeline::vertex::IncompatibleVertexSourceError;
 use pipeline::vertex::VertexSource;
 
 /// Checks whether vertex buffers can be bound.
@@ -25,7 +26,7 @@ pub fn check_vertex_buffers<P, V>(pipeline: &P, vertex_buffers: V)
                                   -> Result<CheckVertexBuffer, CheckVertexBufferError>
     where P: DeviceOwned + VertexSource<V>
 {
-    let (vertex_buffers, vertex_count, instance_count) = pipeline.decode(vertex_buffers);
+    let (vertex_buffers, vertex_count, instance_count) = pipeline.decode(vertex_buffers)?;
 
     for (num, buf) in vertex_buffers.iter().enumerate() {
         assert_eq!(buf.inner().buffer.device().internal_object(),
@@ -61,6 +62,8 @@ pub enum CheckVertexBufferError {
         /// Index of the buffer that is missing usage.
         num_buffer: usize,
     },
+    /// The vertex buffers don't match the vertex definition of the pipeline.
+    IncompatibleVertexSource(IncompatibleVertexSourceError),
 }
 
 impl error::Error for CheckVertexBufferError {
@@ -70,6 +73,17 @@ impl error::Error for CheckVertexBufferError {
             CheckVertexBufferError::BufferMissingUsage { .. } => {
                 "the vertex buffer usage is missing on a vertex buffer"
             },
+            CheckVertexBufferError::IncompatibleVertexSource(_) => {
+                "the vertex buffers don't match the vertex definition of the pipeline"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            CheckVertexBufferError::IncompatibleVertexSource(ref err) => Some(err),
+            _ => None,
         }
     }
 }
@@ -80,3 +94,10 @@ impl fmt::Display for CheckVertexBufferError {
         write!(fmt, "{}", error::Error::description(self))
     }
 }
+
+impl From<IncompatibleVertexSourceError> for CheckVertexBufferError {
+    #[inline]
+    fn from(err: IncompatibleVertexSourceError) -> CheckVertexBufferError {
+        CheckVertexBufferError::IncompatibleVertexSource(err)
+    }
+}
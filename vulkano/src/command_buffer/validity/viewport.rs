@@ -0,0 +1,159 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+
+use device::Device;
+use pipeline::viewport::Scissor;
+use pipeline::viewport::Viewport;
+
+/// Checks whether viewports can be set dynamically, starting at index `first_viewport`.
+pub fn check_set_viewport(device: &Device, first_viewport: u32, viewports: &[Viewport])
+                          -> Result<(), CheckSetViewportError> {
+    if viewports.is_empty() {
+        return Err(CheckSetViewportError::Empty);
+    }
+
+    check_range(device, first_viewport, viewports.len() as u32)?;
+
+    Ok(())
+}
+
+/// Checks whether scissor boxes can be set dynamically, starting at index `first_scissor`.
+pub fn check_set_scissor(device: &Device, first_scissor: u32, scissors: &[Scissor])
+                         -> Result<(), CheckSetViewportError> {
+    if scissors.is_empty() {
+        return Err(CheckSetViewportError::Empty);
+    }
+
+    check_range(device, first_scissor, scissors.len() as u32)?;
+
+    for scissor in scissors {
+        if scissor.origin[0] < 0 || scissor.origin[1] < 0 {
+            return Err(CheckSetViewportError::ScissorNegativeOrigin);
+        }
+
+        let overflows = |origin: i32, dimension: u32| {
+            dimension > i32::max_value() as u32 || origin.checked_add(dimension as i32).is_none()
+        };
+        if overflows(scissor.origin[0], scissor.dimensions[0]) ||
+            overflows(scissor.origin[1], scissor.dimensions[1])
+        {
+            return Err(CheckSetViewportError::ScissorOverflow);
+        }
+    }
+
+    Ok(())
+}
+
+// Checks the range of viewports or scissors that is about to be set.
+fn check_range(device: &Device, first: u32, count: u32) -> Result<(), CheckSetViewportError> {
+    if (first != 0 || count != 1) && !device.enabled_features().multi_viewport {
+        return Err(CheckSetViewportError::MultiViewportFeatureNotEnabled);
+    }
+
+    let max = device.physical_device().limits().max_viewports();
+    if first as u64 + count as u64 > max as u64 {
+        return Err(CheckSetViewportError::MaxViewportsExceeded { first, count, max });
+    }
+
+    Ok(())
+}
+
+/// Error that can happen from `check_set_viewport` or `check_set_scissor`.
+#[derive(Debug, Copy, Clone)]
+pub enum CheckSetViewportError {
+    /// The list of viewports or scissor boxes is empty.
+    Empty,
+    /// Setting anything else than the first viewport or scissor box requires the
+    /// `multi_viewport` feature.
+    MultiViewportFeatureNotEnabled,
+    /// The range goes beyond the maximum number of viewports of the physical device.
+    MaxViewportsExceeded {
+        /// Index of the first viewport or scissor box.
+        first: u32,
+        /// Number of viewports or scissor boxes.
+        count: u32,
+        /// Maximum number of viewports supported by the physical device.
+        max: u32,
+    },
+    /// The origin of a scissor box is negative.
+    ScissorNegativeOrigin,
+    /// The origin plus the dimensions of a scissor box overflow a signed 32-bits integer.
+    ScissorOverflow,
+}
+
+impl error::Error for CheckSetViewportError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            CheckSetViewportError::Empty => {
+                "the list of viewports or scissor boxes is empty"
+            },
+            CheckSetViewportError::MultiViewportFeatureNotEnabled => {
+                "setting anything else than the first viewport or scissor box requires the \
+                 `multi_viewport` feature"
+            },
+            CheckSetViewportError::MaxViewportsExceeded { .. } => {
+                "the range goes beyond the maximum number of viewports of the physical device"
+            },
+            CheckSetViewportError::ScissorNegativeOrigin => {
+                "the origin of a scissor box is negative"
+            },
+            CheckSetViewportError::ScissorOverflow => {
+                "the origin plus the dimensions of a scissor box overflow a signed 32-bits \
+                 integer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for CheckSetViewportError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn multi_viewport_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let viewport = Viewport {
+            origin: [0.0, 0.0],
+            dimensions: [16.0, 16.0],
+            depth_range: 0.0 .. 1.0,
+        };
+
+        match check_set_viewport(&device, 1, &[viewport]) {
+            Err(CheckSetViewportError::MultiViewportFeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn scissor_negative_origin() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let scissor = Scissor {
+            origin: [-1, 0],
+            dimensions: [16, 16],
+        };
+
+        match check_set_scissor(&device, 0, &[scissor]) {
+            Err(CheckSetViewportError::ScissorNegativeOrigin) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -57,7 +57,7 @@ use vk;
 /// > will be checked when you create a pipeline layout, a descriptor set, or when you try to bind
 /// > a descriptor set.
 // TODO: add example
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DescriptorDesc {
     /// Describes the content and layout of each array element of a descriptor.
     pub ty: DescriptorDescTy,
@@ -164,6 +164,12 @@ impl DescriptorDesc {
                     }
                 }
             },
+            DescriptorDescTy::AccelerationStructure => {
+                AccessFlagBits {
+                    acceleration_structure_read: true,
+                    ..AccessFlagBits::none()
+                }
+            },
         };
 
         (stages, access)
@@ -171,7 +177,7 @@ impl DescriptorDesc {
 }
 
 /// Describes the content and layout of each array element of a descriptor.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum DescriptorDescTy {
     Sampler, // TODO: the sampler has some restrictions as well
     CombinedImageSampler(DescriptorImageDesc), // TODO: the sampler has some restrictions as well
@@ -192,6 +198,9 @@ pub enum DescriptorDescTy {
         array_layers: DescriptorImageDescArray,
     },
     Buffer(DescriptorBufferDesc),
+    /// An acceleration structure that can be traversed by ray queries and ray tracing shaders.
+    /// Requires the `VK_KHR_acceleration_structure` extension.
+    AccelerationStructure,
 }
 
 impl DescriptorDescTy {
@@ -230,6 +239,7 @@ impl DescriptorDescTy {
                          DescriptorType::UniformTexelBuffer
                      }
                  },
+                 DescriptorDescTy::AccelerationStructure => DescriptorType::AccelerationStructure,
              })
     }
 
@@ -241,6 +251,9 @@ impl DescriptorDescTy {
         match (self, other) {
             (&DescriptorDescTy::Sampler, &DescriptorDescTy::Sampler) => Ok(()),
 
+            (&DescriptorDescTy::AccelerationStructure,
+             &DescriptorDescTy::AccelerationStructure) => Ok(()),
+
             (&DescriptorDescTy::CombinedImageSampler(ref me),
              &DescriptorDescTy::CombinedImageSampler(ref other)) => me.is_superset_of(other),
 
@@ -326,7 +339,7 @@ impl DescriptorDescTy {
 }
 
 /// Additional description for descriptors that contain images.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct DescriptorImageDesc {
     /// If `true`, the image can be sampled by the shader. Only images that were created with the
     /// `sampled` usage can be attached to the descriptor.
@@ -419,14 +432,14 @@ impl DescriptorImageDesc {
 }
 
 // TODO: documentation
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum DescriptorImageDescArray {
     NonArrayed,
     Arrayed { max_layers: Option<u32> },
 }
 
 // TODO: documentation
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum DescriptorImageDescDimensions {
     OneDimensional,
     TwoDimensional,
@@ -451,7 +464,7 @@ impl DescriptorImageDescDimensions {
 }
 
 // TODO: documentation
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct DescriptorBufferDesc {
     pub dynamic: Option<bool>,
     pub storage: bool,
@@ -474,6 +487,7 @@ pub enum DescriptorType {
     UniformBufferDynamic = vk::DESCRIPTOR_TYPE_UNIFORM_BUFFER_DYNAMIC,
     StorageBufferDynamic = vk::DESCRIPTOR_TYPE_STORAGE_BUFFER_DYNAMIC,
     InputAttachment = vk::DESCRIPTOR_TYPE_INPUT_ATTACHMENT,
+    AccelerationStructure = vk::DESCRIPTOR_TYPE_ACCELERATION_STRUCTURE_KHR,
 }
 
 /// Error when checking whether a descriptor is a superset of another one.
@@ -561,7 +575,7 @@ impl From<ShaderStagesSupersetError> for DescriptorDescSupersetError {
 
 /// Describes which shader stages have access to a descriptor.
 // TODO: add example with BitOr
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct ShaderStages {
     /// `True` means that the descriptor will be used by the vertex shader.
     pub vertex: bool,
@@ -575,10 +589,28 @@ pub struct ShaderStages {
     pub fragment: bool,
     /// `True` means that the descriptor will be used by the compute shader.
     pub compute: bool,
+    /// `True` means that the descriptor will be used by the task shader.
+    pub task: bool,
+    /// `True` means that the descriptor will be used by the mesh shader.
+    pub mesh: bool,
+    /// `True` means that the descriptor will be used by the ray generation shader.
+    pub raygen: bool,
+    /// `True` means that the descriptor will be used by the any-hit shader.
+    pub any_hit: bool,
+    /// `True` means that the descriptor will be used by the closest-hit shader.
+    pub closest_hit: bool,
+    /// `True` means that the descriptor will be used by the miss shader.
+    pub miss: bool,
+    /// `True` means that the descriptor will be used by the intersection shader.
+    pub intersection: bool,
+    /// `True` means that the descriptor will be used by the callable shader.
+    pub callable: bool,
 }
 
 impl ShaderStages {
-    /// Creates a `ShaderStages` struct will all stages set to `true`.
+    /// Creates a `ShaderStages` struct will all stages set to `true`, except the task and mesh
+    /// stages that require the `VK_EXT_mesh_shader` extension and the ray tracing stages that
+    /// require the `VK_KHR_ray_tracing_pipeline` extension.
     // TODO: add example
     #[inline]
     pub fn all() -> ShaderStages {
@@ -589,6 +621,14 @@ impl ShaderStages {
             geometry: true,
             fragment: true,
             compute: true,
+            task: false,
+            mesh: false,
+            raygen: false,
+            any_hit: false,
+            closest_hit: false,
+            miss: false,
+            intersection: false,
+            callable: false,
         }
     }
 
@@ -603,6 +643,14 @@ impl ShaderStages {
             geometry: false,
             fragment: false,
             compute: false,
+            task: false,
+            mesh: false,
+            raygen: false,
+            any_hit: false,
+            closest_hit: false,
+            miss: false,
+            intersection: false,
+            callable: false,
         }
     }
 
@@ -617,6 +665,28 @@ impl ShaderStages {
             geometry: true,
             fragment: true,
             compute: false,
+            task: false,
+            mesh: false,
+            raygen: false,
+            any_hit: false,
+            closest_hit: false,
+            miss: false,
+            intersection: false,
+            callable: false,
+        }
+    }
+
+    /// Creates a `ShaderStages` struct with all the ray tracing stages set to `true`.
+    #[inline]
+    pub fn all_ray_tracing() -> ShaderStages {
+        ShaderStages {
+            raygen: true,
+            any_hit: true,
+            closest_hit: true,
+            miss: true,
+            intersection: true,
+            callable: true,
+            ..ShaderStages::none()
         }
     }
 
@@ -631,6 +701,14 @@ impl ShaderStages {
             geometry: false,
             fragment: false,
             compute: true,
+            task: false,
+            mesh: false,
+            raygen: false,
+            any_hit: false,
+            closest_hit: false,
+            miss: false,
+            intersection: false,
+            callable: false,
         }
     }
 
@@ -642,7 +720,11 @@ impl ShaderStages {
             (self.tessellation_control || !other.tessellation_control) &&
             (self.tessellation_evaluation || !other.tessellation_evaluation) &&
             (self.geometry || !other.geometry) &&
-            (self.fragment || !other.fragment) && (self.compute || !other.compute)
+            (self.fragment || !other.fragment) && (self.compute || !other.compute) &&
+            (self.task || !other.task) && (self.mesh || !other.mesh) &&
+            (self.raygen || !other.raygen) && (self.any_hit || !other.any_hit) &&
+            (self.closest_hit || !other.closest_hit) && (self.miss || !other.miss) &&
+            (self.intersection || !other.intersection) && (self.callable || !other.callable)
         {
             Ok(())
         } else {
@@ -658,7 +740,31 @@ impl ShaderStages {
             (self.tessellation_control && other.tessellation_control) ||
             (self.tessellation_evaluation && other.tessellation_evaluation) ||
             (self.geometry && other.geometry) || (self.fragment && other.fragment) ||
-            (self.compute && other.compute)
+            (self.compute && other.compute) || (self.task && other.task) ||
+            (self.mesh && other.mesh) || (self.raygen && other.raygen) ||
+            (self.any_hit && other.any_hit) || (self.closest_hit && other.closest_hit) ||
+            (self.miss && other.miss) || (self.intersection && other.intersection) ||
+            (self.callable && other.callable)
+    }
+
+    #[inline]
+    pub(crate) fn from_vulkan_bits(bits: vk::ShaderStageFlags) -> ShaderStages {
+        ShaderStages {
+            vertex: (bits & vk::SHADER_STAGE_VERTEX_BIT) != 0,
+            tessellation_control: (bits & vk::SHADER_STAGE_TESSELLATION_CONTROL_BIT) != 0,
+            tessellation_evaluation: (bits & vk::SHADER_STAGE_TESSELLATION_EVALUATION_BIT) != 0,
+            geometry: (bits & vk::SHADER_STAGE_GEOMETRY_BIT) != 0,
+            fragment: (bits & vk::SHADER_STAGE_FRAGMENT_BIT) != 0,
+            compute: (bits & vk::SHADER_STAGE_COMPUTE_BIT) != 0,
+            task: (bits & vk::SHADER_STAGE_TASK_BIT_EXT) != 0,
+            mesh: (bits & vk::SHADER_STAGE_MESH_BIT_EXT) != 0,
+            raygen: (bits & vk::SHADER_STAGE_RAYGEN_BIT_KHR) != 0,
+            any_hit: (bits & vk::SHADER_STAGE_ANY_HIT_BIT_KHR) != 0,
+            closest_hit: (bits & vk::SHADER_STAGE_CLOSEST_HIT_BIT_KHR) != 0,
+            miss: (bits & vk::SHADER_STAGE_MISS_BIT_KHR) != 0,
+            intersection: (bits & vk::SHADER_STAGE_INTERSECTION_BIT_KHR) != 0,
+            callable: (bits & vk::SHADER_STAGE_CALLABLE_BIT_KHR) != 0,
+        }
     }
 
     #[inline]
@@ -682,6 +788,30 @@ impl ShaderStages {
         if self.compute {
             result |= vk::SHADER_STAGE_COMPUTE_BIT;
         }
+        if self.task {
+            result |= vk::SHADER_STAGE_TASK_BIT_EXT;
+        }
+        if self.mesh {
+            result |= vk::SHADER_STAGE_MESH_BIT_EXT;
+        }
+        if self.raygen {
+            result |= vk::SHADER_STAGE_RAYGEN_BIT_KHR;
+        }
+        if self.any_hit {
+            result |= vk::SHADER_STAGE_ANY_HIT_BIT_KHR;
+        }
+        if self.closest_hit {
+            result |= vk::SHADER_STAGE_CLOSEST_HIT_BIT_KHR;
+        }
+        if self.miss {
+            result |= vk::SHADER_STAGE_MISS_BIT_KHR;
+        }
+        if self.intersection {
+            result |= vk::SHADER_STAGE_INTERSECTION_BIT_KHR;
+        }
+        if self.callable {
+            result |= vk::SHADER_STAGE_CALLABLE_BIT_KHR;
+        }
         result
     }
 }
@@ -698,6 +828,14 @@ impl BitOr for ShaderStages {
             geometry: self.geometry || other.geometry,
             fragment: self.fragment || other.fragment,
             compute: self.compute || other.compute,
+            task: self.task || other.task,
+            mesh: self.mesh || other.mesh,
+            raygen: self.raygen || other.raygen,
+            any_hit: self.any_hit || other.any_hit,
+            closest_hit: self.closest_hit || other.closest_hit,
+            miss: self.miss || other.miss,
+            intersection: self.intersection || other.intersection,
+            callable: self.callable || other.callable,
         }
     }
 }
@@ -712,6 +850,10 @@ impl From<ShaderStages> for PipelineStages {
             geometry_shader: stages.geometry,
             fragment_shader: stages.fragment,
             compute_shader: stages.compute,
+            task_shader: stages.task,
+            mesh_shader: stages.mesh,
+            ray_tracing_shader: stages.raygen || stages.any_hit || stages.closest_hit ||
+                stages.miss || stages.intersection || stages.callable,
             ..PipelineStages::none()
         }
     }
@@ -347,6 +347,20 @@ impl<'a, L, R> FixedSizeDescriptorSetBuilder<'a, L, R>
            })
     }
 
+    /// Puts a null descriptor as the next descriptor.
+    ///
+    /// An error is returned if the `null_descriptor` feature of the `VK_EXT_robustness2`
+    /// extension isn't enabled on the device, or if the descriptor is a sampler.
+    #[inline]
+    pub fn add_null(
+        self)
+        -> Result<FixedSizeDescriptorSetBuilder<'a, L, R>, PersistentDescriptorSetError> {
+        Ok(FixedSizeDescriptorSetBuilder {
+               pool: self.pool,
+               inner: self.inner.add_null()?,
+           })
+    }
+
     /// Binds a buffer as the next descriptor.
     ///
     /// An error is returned if the buffer isn't compatible with the descriptor.
@@ -469,6 +483,19 @@ impl<'a, L, R> FixedSizeDescriptorSetBuilderArray<'a, L, R>
            })
     }
 
+    /// Puts a null descriptor as the next element in the array.
+    ///
+    /// An error is returned if the `null_descriptor` feature of the `VK_EXT_robustness2`
+    /// extension isn't enabled on the device, or if the descriptor is a sampler.
+    pub fn add_null(
+        self)
+        -> Result<FixedSizeDescriptorSetBuilderArray<'a, L, R>, PersistentDescriptorSetError> {
+        Ok(FixedSizeDescriptorSetBuilderArray {
+               pool: self.pool,
+               inner: self.inner.add_null()?,
+           })
+    }
+
     /// Binds a buffer as the next element in the array.
     ///
     /// An error is returned if the buffer isn't compatible with the descriptor.
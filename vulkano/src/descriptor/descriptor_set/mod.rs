@@ -46,6 +46,7 @@ pub use self::fixed_size_pool::FixedSizeDescriptorSetBuilder;
 pub use self::fixed_size_pool::FixedSizeDescriptorSetBuilderArray;
 pub use self::fixed_size_pool::FixedSizeDescriptorSetsPool;
 pub use self::persistent::PersistentDescriptorSet;
+pub use self::persistent::PersistentDescriptorSetAccelerationStructure;
 pub use self::persistent::PersistentDescriptorSetBuf;
 pub use self::persistent::PersistentDescriptorSetBufView;
 pub use self::persistent::PersistentDescriptorSetBuildError;
@@ -13,6 +13,7 @@ use std::sync::Arc;
 
 use OomError;
 use VulkanObject;
+use acceleration_structure::AccelerationStructure;
 use buffer::BufferAccess;
 use buffer::BufferViewRef;
 use descriptor::descriptor::DescriptorDesc;
@@ -261,6 +262,18 @@ impl<L, R> PersistentDescriptorSetBuilder<L, R>
         Ok(self)
     }
 
+    /// Puts a null descriptor as the next descriptor. Reading from it returns zeroes, and writes
+    /// to it are discarded.
+    ///
+    /// An error is returned if the `null_descriptor` feature of the `VK_EXT_robustness2`
+    /// extension isn't enabled on the device, or if the descriptor is a sampler.
+    #[inline]
+    pub fn add_null(
+        self)
+        -> Result<PersistentDescriptorSetBuilder<L, R>, PersistentDescriptorSetError> {
+        self.enter_array()?.add_null()?.leave_array()
+    }
+
     /// Binds a buffer as the next descriptor.
     ///
     /// An error is returned if the buffer isn't compatible with the descriptor.
@@ -348,6 +361,23 @@ impl<L, R> PersistentDescriptorSetBuilder<L, R>
                                  PersistentDescriptorSetError> {
         self.enter_array()?.add_sampler(sampler)?.leave_array()
     }
+
+    /// Binds an acceleration structure as the next descriptor.
+    ///
+    /// An error is returned if the acceleration structure isn't compatible with the descriptor.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the acceleration structure doesn't have the same device as the pipeline layout.
+    ///
+    #[inline]
+    pub fn add_acceleration_structure(self, acceleration_structure: Arc<AccelerationStructure>)
+        -> Result<PersistentDescriptorSetBuilder<L, (R, PersistentDescriptorSetAccelerationStructure)>, PersistentDescriptorSetError>
+    {
+        self.enter_array()?
+            .add_acceleration_structure(acceleration_structure)?
+            .leave_array()
+    }
 }
 
 /// Same as `PersistentDescriptorSetBuilder`, but we're in an array.
@@ -380,6 +410,45 @@ impl<L, R> PersistentDescriptorSetBuilderArray<L, R>
         Ok(self.builder)
     }
 
+    /// Puts a null descriptor as the next element in the array. Reading from it returns zeroes,
+    /// and writes to it are discarded.
+    ///
+    /// An error is returned if the `null_descriptor` feature of the `VK_EXT_robustness2`
+    /// extension isn't enabled on the device, or if the descriptor is a sampler.
+    pub fn add_null(
+        mut self)
+        -> Result<PersistentDescriptorSetBuilderArray<L, R>, PersistentDescriptorSetError> {
+        if !self.builder.layout.device().enabled_features().null_descriptor {
+            return Err(PersistentDescriptorSetError::NullDescriptorNotAllowed);
+        }
+
+        if self.array_element as u32 >= self.desc.array_count {
+            return Err(PersistentDescriptorSetError::ArrayOutOfBounds);
+        }
+
+        let ty = match self.desc.ty {
+            DescriptorDescTy::Sampler |
+            DescriptorDescTy::CombinedImageSampler(_) => {
+                return Err(PersistentDescriptorSetError::NullDescriptorNotAllowed);
+            },
+            // Buffers are always bound as non-dynamic buffers, see `add_buffer`.
+            DescriptorDescTy::Buffer(ref buffer_desc) => {
+                if buffer_desc.storage {
+                    DescriptorType::StorageBuffer
+                } else {
+                    DescriptorType::UniformBuffer
+                }
+            },
+            ref d => d.ty().unwrap(),
+        };
+
+        self.builder.writes.push(DescriptorWrite::null(self.builder.binding_id as u32,
+                                                       self.array_element as u32,
+                                                       ty));
+        self.array_element += 1;
+        Ok(self)
+    }
+
     /// Binds a buffer as the next element in the array.
     ///
     /// An error is returned if the buffer isn't compatible with the descriptor.
@@ -748,6 +817,54 @@ impl<L, R> PersistentDescriptorSetBuilderArray<L, R>
                array_element: self.array_element + 1,
            })
     }
+
+    /// Binds an acceleration structure as the next element in the array.
+    ///
+    /// An error is returned if the acceleration structure isn't compatible with the descriptor.
+    ///
+    /// # Panic
+    ///
+    /// Panics if the acceleration structure doesn't have the same device as the pipeline layout.
+    ///
+    pub fn add_acceleration_structure(mut self, acceleration_structure: Arc<AccelerationStructure>)
+        -> Result<PersistentDescriptorSetBuilderArray<L, (R, PersistentDescriptorSetAccelerationStructure)>, PersistentDescriptorSetError>
+    {
+        assert_eq!(self.builder.layout.device().internal_object(),
+                   acceleration_structure.device().internal_object());
+
+        if self.array_element as u32 >= self.desc.array_count {
+            return Err(PersistentDescriptorSetError::ArrayOutOfBounds);
+        }
+
+        self.builder.writes.push(match self.desc.ty {
+            DescriptorDescTy::AccelerationStructure => {
+                DescriptorWrite::acceleration_structure(self.builder.binding_id as u32,
+                                                        self.array_element as u32,
+                                                        &acceleration_structure)
+            },
+            ref d => {
+                return Err(PersistentDescriptorSetError::WrongDescriptorTy {
+                               expected: d.ty().unwrap(),
+                           });
+            },
+        });
+
+        Ok(PersistentDescriptorSetBuilderArray {
+               builder: PersistentDescriptorSetBuilder {
+                   layout: self.builder.layout,
+                   set_id: self.builder.set_id,
+                   binding_id: self.builder.binding_id,
+                   writes: self.builder.writes,
+                   resources: (self.builder.resources,
+                               PersistentDescriptorSetAccelerationStructure {
+                                   acceleration_structure: acceleration_structure,
+                                   descriptor_num: self.builder.binding_id as u32,
+                               }),
+               },
+               desc: self.desc,
+               array_element: self.array_element + 1,
+           })
+    }
 }
 
 // Checks whether an image view matches the descriptor.
@@ -983,6 +1100,43 @@ unsafe impl<R> PersistentDescriptorSetResources for (R, PersistentDescriptorSetS
     }
 }
 
+/// Internal object related to the `PersistentDescriptorSet` system.
+pub struct PersistentDescriptorSetAccelerationStructure {
+    acceleration_structure: Arc<AccelerationStructure>,
+    descriptor_num: u32,
+}
+
+// The acceleration structure is tracked through the buffer that stores it.
+unsafe impl<R> PersistentDescriptorSetResources for (R, PersistentDescriptorSetAccelerationStructure)
+    where R: PersistentDescriptorSetResources
+{
+    #[inline]
+    fn num_buffers(&self) -> usize {
+        self.0.num_buffers() + 1
+    }
+
+    #[inline]
+    fn buffer(&self, index: usize) -> Option<(&BufferAccess, u32)> {
+        if let Some(buf) = self.0.buffer(index) {
+            Some(buf)
+        } else if index == self.0.num_buffers() {
+            Some((&**self.1.acceleration_structure.buffer(), self.1.descriptor_num))
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn num_images(&self) -> usize {
+        self.0.num_images()
+    }
+
+    #[inline]
+    fn image(&self, index: usize) -> Option<(&ImageViewAccess, u32)> {
+        self.0.image(index)
+    }
+}
+
 /// Error related to the persistent descriptor set.
 #[derive(Debug, Clone)]
 pub enum PersistentDescriptorSetError {
@@ -1041,6 +1195,10 @@ pub enum PersistentDescriptorSetError {
         /// Type of the image view that was passed.
         obtained: DescriptorImageDescDimensions,
     },
+
+    /// A null descriptor was added, but the `null_descriptor` feature isn't enabled on the
+    /// device or the descriptor is a sampler.
+    NullDescriptorNotAllowed,
 }
 
 impl error::Error for PersistentDescriptorSetError {
@@ -1080,6 +1238,10 @@ impl error::Error for PersistentDescriptorSetError {
             PersistentDescriptorSetError::ImageViewTypeMismatch { .. } => {
                 "the type of an image view doesn't match what was expected"
             },
+            PersistentDescriptorSetError::NullDescriptorNotAllowed => {
+                "a null descriptor was added, but the `null_descriptor` feature isn't enabled or \
+                 the descriptor is a sampler"
+            },
         }
     }
 }
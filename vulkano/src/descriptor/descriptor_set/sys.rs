@@ -17,6 +17,7 @@ use std::ptr;
 use std::sync::Arc;
 use std::vec::IntoIter as VecIntoIter;
 
+use acceleration_structure::AccelerationStructure;
 use buffer::BufferAccess;
 use buffer::BufferInner;
 use buffer::BufferView;
@@ -104,6 +105,7 @@ macro_rules! descriptors_count {
                     DescriptorType::UniformBufferDynamic => self.uniform_buffer_dynamic += 1,
                     DescriptorType::StorageBufferDynamic => self.storage_buffer_dynamic += 1,
                     DescriptorType::InputAttachment => self.input_attachment += 1,
+                    DescriptorType::AccelerationStructure => self.acceleration_structure += 1,
                 };
             }
         }
@@ -220,6 +222,7 @@ descriptors_count! {
     sampler,
     combined_image_sampler,
     input_attachment,
+    acceleration_structure,
 }
 
 /// Pool from which descriptor sets are allocated from.
@@ -281,6 +284,8 @@ impl UnsafeDescriptorPool {
         elem!(combined_image_sampler,
               vk::DESCRIPTOR_TYPE_COMBINED_IMAGE_SAMPLER);
         elem!(input_attachment, vk::DESCRIPTOR_TYPE_INPUT_ATTACHMENT);
+        elem!(acceleration_structure,
+              vk::DESCRIPTOR_TYPE_ACCELERATION_STRUCTURE_KHR);
 
         assert!(!pool_sizes.is_empty(),
                 "All the descriptors count of a pool are 0");
@@ -302,7 +307,7 @@ impl UnsafeDescriptorPool {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateDescriptorPool(device.internal_object(),
                                                  &infos,
-                                                 ptr::null(),
+                                                 device.allocator_ptr(),
                                                  &mut output))?;
             output
         };
@@ -466,7 +471,9 @@ impl Drop for UnsafeDescriptorPool {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyDescriptorPool(self.device.internal_object(), self.pool, ptr::null());
+            vk.DestroyDescriptorPool(self.device.internal_object(),
+                                     self.pool,
+                                     self.device.allocator_ptr());
         }
     }
 }
@@ -548,6 +555,16 @@ impl UnsafeDescriptorSet {
     //       add a `copy` method that just takes a copy, and an `update` method that takes both
     //       writes and copies and that actually performs the operation
 
+    /// Builds an `UnsafeDescriptorSet` from a raw descriptor set handle, for example one that was
+    /// allocated by another library.
+    ///
+    /// Like the other `UnsafeDescriptorSet`s, the descriptor set isn't freed when the object is
+    /// dropped. It is freed or reset with its pool.
+    #[inline]
+    pub unsafe fn from_raw(handle: u64) -> UnsafeDescriptorSet {
+        UnsafeDescriptorSet { set: handle }
+    }
+
     /// Modifies a descriptor set. Doesn't check that the writes or copies are correct, and
     /// doesn't check whether the descriptor set is in use.
     ///
@@ -581,15 +598,21 @@ impl UnsafeDescriptorSet {
         // list.
         // Once we have finished iterating all the writes requested by the user, we modify
         // `raw_writes` to point to the correct locations.
+        //
+        // Acceleration structures are passed through a struct chained to the write instead, which
+        // we handle in the same way with `acceleration_structure_descriptors` and
+        // `raw_writes_accel_infos`.
 
         let mut buffer_descriptors: SmallVec<[_; 64]> = SmallVec::new();
         let mut image_descriptors: SmallVec<[_; 64]> = SmallVec::new();
         let mut buffer_views_descriptors: SmallVec<[_; 64]> = SmallVec::new();
+        let mut acceleration_structure_descriptors: SmallVec<[_; 64]> = SmallVec::new();
 
         let mut raw_writes: SmallVec<[_; 64]> = SmallVec::new();
         let mut raw_writes_img_infos: SmallVec<[_; 64]> = SmallVec::new();
         let mut raw_writes_buf_infos: SmallVec<[_; 64]> = SmallVec::new();
         let mut raw_writes_buf_view_infos: SmallVec<[_; 64]> = SmallVec::new();
+        let mut raw_writes_accel_infos: SmallVec<[_; 64]> = SmallVec::new();
 
         for indiv_write in writes {
             // Since the `DescriptorWrite` objects are built only through functions, we know for
@@ -620,6 +643,7 @@ impl UnsafeDescriptorSet {
                     raw_writes_img_infos.push(Some(image_descriptors.len()));
                     raw_writes_buf_infos.push(None);
                     raw_writes_buf_view_infos.push(None);
+                    raw_writes_accel_infos.push(None);
                 },
                 DescriptorWriteInner::UniformBuffer(_, _, _) |
                 DescriptorWriteInner::StorageBuffer(_, _, _) |
@@ -628,12 +652,20 @@ impl UnsafeDescriptorSet {
                     raw_writes_img_infos.push(None);
                     raw_writes_buf_infos.push(Some(buffer_descriptors.len()));
                     raw_writes_buf_view_infos.push(None);
+                    raw_writes_accel_infos.push(None);
                 },
                 DescriptorWriteInner::UniformTexelBuffer(_) |
                 DescriptorWriteInner::StorageTexelBuffer(_) => {
                     raw_writes_img_infos.push(None);
                     raw_writes_buf_infos.push(None);
                     raw_writes_buf_view_infos.push(Some(buffer_views_descriptors.len()));
+                    raw_writes_accel_infos.push(None);
+                },
+                DescriptorWriteInner::AccelerationStructure(_) => {
+                    raw_writes_img_infos.push(None);
+                    raw_writes_buf_infos.push(None);
+                    raw_writes_buf_view_infos.push(None);
+                    raw_writes_accel_infos.push(Some(acceleration_structure_descriptors.len()));
                 },
             }
 
@@ -641,19 +673,11 @@ impl UnsafeDescriptorSet {
                 match *elem {
                     DescriptorWriteInner::UniformBuffer(buffer, offset, size) |
                     DescriptorWriteInner::DynamicUniformBuffer(buffer, offset, size) => {
-                        buffer_descriptors.push(vk::DescriptorBufferInfo {
-                                                    buffer: buffer,
-                                                    offset: offset as u64,
-                                                    range: size as u64,
-                                                });
+                        buffer_descriptors.push(buffer_info(buffer, offset, size));
                     },
                     DescriptorWriteInner::StorageBuffer(buffer, offset, size) |
                     DescriptorWriteInner::DynamicStorageBuffer(buffer, offset, size) => {
-                        buffer_descriptors.push(vk::DescriptorBufferInfo {
-                                                    buffer: buffer,
-                                                    offset: offset as u64,
-                                                    range: size as u64,
-                                                });
+                        buffer_descriptors.push(buffer_info(buffer, offset, size));
                     },
                     DescriptorWriteInner::Sampler(sampler) => {
                         image_descriptors.push(vk::DescriptorImageInfo {
@@ -694,10 +718,30 @@ impl UnsafeDescriptorSet {
                     DescriptorWriteInner::StorageTexelBuffer(view) => {
                         buffer_views_descriptors.push(view);
                     },
+                    DescriptorWriteInner::AccelerationStructure(accel) => {
+                        acceleration_structure_descriptors.push(accel);
+                    },
                 }
             }
         }
 
+        let accel_writes = raw_writes
+            .iter()
+            .zip(raw_writes_accel_infos.iter())
+            .filter_map(|(write, off)| off.map(|off| (write.descriptorCount, off)))
+            .map(|(count, off)| {
+                vk::WriteDescriptorSetAccelerationStructureKHR {
+                    sType: vk::STRUCTURE_TYPE_WRITE_DESCRIPTOR_SET_ACCELERATION_STRUCTURE_KHR,
+                    pNext: ptr::null(),
+                    accelerationStructureCount: count,
+                    pAccelerationStructures: acceleration_structure_descriptors
+                        .as_ptr()
+                        .offset(off as isize),
+                }
+            })
+            .collect::<SmallVec<[_; 64]>>();
+        let mut accel_writes_iter = accel_writes.iter();
+
         // Now that `image_descriptors`, `buffer_descriptors` and `buffer_views_descriptors` are
         // entirely filled and will never move again, we can fill the pointers in `raw_writes`.
         for (i, write) in raw_writes.iter_mut().enumerate() {
@@ -715,6 +759,10 @@ impl UnsafeDescriptorSet {
                 Some(off) => buffer_views_descriptors.as_ptr().offset(off as isize),
                 None => ptr::null(),
             };
+
+            if raw_writes_accel_infos[i].is_some() {
+                write.pNext = accel_writes_iter.next().unwrap() as *const _ as *const _;
+            }
         }
 
         // It is forbidden to call `vkUpdateDescriptorSets` with 0 writes, so we need to perform
@@ -729,6 +777,17 @@ impl UnsafeDescriptorSet {
     }
 }
 
+// Builds the `VkDescriptorBufferInfo` of a buffer descriptor. The range of a null buffer must be
+// `VK_WHOLE_SIZE`.
+#[inline]
+fn buffer_info(buffer: vk::Buffer, offset: usize, size: usize) -> vk::DescriptorBufferInfo {
+    vk::DescriptorBufferInfo {
+        buffer: buffer,
+        offset: offset as u64,
+        range: if buffer == 0 { vk::WHOLE_SIZE } else { size as u64 },
+    }
+}
+
 unsafe impl VulkanObject for UnsafeDescriptorSet {
     type Object = vk::DescriptorSet;
 
@@ -770,6 +829,7 @@ enum DescriptorWriteInner {
     DynamicUniformBuffer(vk::Buffer, usize, usize),
     DynamicStorageBuffer(vk::Buffer, usize, usize),
     InputAttachment(vk::ImageView, vk::ImageLayout),
+    AccelerationStructure(vk::AccelerationStructureKHR),
 }
 
 macro_rules! smallvec {
@@ -1013,6 +1073,54 @@ impl DescriptorWrite {
         }
     }
 
+    #[inline]
+    pub fn acceleration_structure(binding: u32, array_element: u32,
+                                  acceleration_structure: &AccelerationStructure)
+                                  -> DescriptorWrite {
+        DescriptorWrite {
+            binding: binding,
+            first_array_element: array_element,
+            inner: smallvec!(DescriptorWriteInner::AccelerationStructure(acceleration_structure
+                                                                             .internal_object())),
+        }
+    }
+
+    /// Builds a write that puts a null descriptor of type `ty` in the descriptor set. Reading
+    /// from a null descriptor returns zeroes, and writes to it are discarded.
+    ///
+    /// Requires the `null_descriptor` feature of the `VK_EXT_robustness2` extension.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `ty` is `Sampler` or `CombinedImageSampler`, as samplers can't be null.
+    ///
+    #[inline]
+    pub fn null(binding: u32, array_element: u32, ty: DescriptorType) -> DescriptorWrite {
+        let inner = match ty {
+            DescriptorType::Sampler | DescriptorType::CombinedImageSampler => {
+                panic!("samplers can't be null descriptors")
+            },
+            DescriptorType::SampledImage => DescriptorWriteInner::SampledImage(0, 0),
+            DescriptorType::StorageImage => DescriptorWriteInner::StorageImage(0, 0),
+            DescriptorType::UniformTexelBuffer => DescriptorWriteInner::UniformTexelBuffer(0),
+            DescriptorType::StorageTexelBuffer => DescriptorWriteInner::StorageTexelBuffer(0),
+            DescriptorType::UniformBuffer => DescriptorWriteInner::UniformBuffer(0, 0, 0),
+            DescriptorType::StorageBuffer => DescriptorWriteInner::StorageBuffer(0, 0, 0),
+            DescriptorType::UniformBufferDynamic =>
+                DescriptorWriteInner::DynamicUniformBuffer(0, 0, 0),
+            DescriptorType::StorageBufferDynamic =>
+                DescriptorWriteInner::DynamicStorageBuffer(0, 0, 0),
+            DescriptorType::InputAttachment => DescriptorWriteInner::InputAttachment(0, 0),
+            DescriptorType::AccelerationStructure => DescriptorWriteInner::AccelerationStructure(0),
+        };
+
+        DescriptorWrite {
+            binding: binding,
+            first_array_element: array_element,
+            inner: smallvec!(inner),
+        }
+    }
+
     /// Returns the type corresponding to this write.
     #[inline]
     pub fn ty(&self) -> DescriptorType {
@@ -1031,6 +1139,8 @@ impl DescriptorWrite {
             DescriptorWriteInner::DynamicStorageBuffer(_, _, _) =>
                 DescriptorType::StorageBufferDynamic,
             DescriptorWriteInner::InputAttachment(_, _) => DescriptorType::InputAttachment,
+            DescriptorWriteInner::AccelerationStructure(_) =>
+                DescriptorType::AccelerationStructure,
         }
     }
 }
@@ -1040,7 +1150,9 @@ mod tests {
     use descriptor::descriptor::DescriptorBufferDesc;
     use descriptor::descriptor::DescriptorDesc;
     use descriptor::descriptor::DescriptorDescTy;
+    use descriptor::descriptor::DescriptorType;
     use descriptor::descriptor::ShaderStages;
+    use descriptor::descriptor_set::DescriptorWrite;
     use descriptor::descriptor_set::DescriptorsCount;
     use descriptor::descriptor_set::UnsafeDescriptorPool;
     use descriptor::descriptor_set::UnsafeDescriptorSetLayout;
@@ -1157,4 +1269,17 @@ mod tests {
             assert_eq!(sets.count(), 0);
         }
     }
+
+    #[test]
+    fn null_write() {
+        let write = DescriptorWrite::null(2, 1, DescriptorType::StorageBuffer);
+        assert_eq!(write.ty(), DescriptorType::StorageBuffer);
+    }
+
+    #[test]
+    fn null_sampler() {
+        assert_should_panic!("samplers can't be null descriptors", {
+            DescriptorWrite::null(0, 0, DescriptorType::Sampler);
+        });
+    }
 }
@@ -89,7 +89,7 @@ impl UnsafeDescriptorSetLayout {
             let vk = device.pointers();
             check_errors(vk.CreateDescriptorSetLayout(device.internal_object(),
                                                       &infos,
-                                                      ptr::null(),
+                                                      device.allocator_ptr(),
                                                       &mut output))?;
             output
         };
@@ -140,7 +140,9 @@ impl Drop for UnsafeDescriptorSetLayout {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyDescriptorSetLayout(self.device.internal_object(), self.layout, ptr::null());
+            vk.DestroyDescriptorSetLayout(self.device.internal_object(),
+                                          self.layout,
+                                          self.device.allocator_ptr());
         }
     }
 }
@@ -80,6 +80,9 @@ pub fn check_desc_against_limits<D>(desc: &D, limits: Limits)
                 DescriptorType::InputAttachment => {
                     num_input_attachments.increment(descriptor.array_count, &descriptor.stages);
                 },
+                // The limits of acceleration structures are part of the properties of the
+                // `VK_KHR_acceleration_structure` extension, and aren't checked here.
+                DescriptorType::AccelerationStructure => (),
             }
         }
     }
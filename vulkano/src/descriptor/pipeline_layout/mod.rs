@@ -48,6 +48,12 @@
 //! pipeline layout object in advance and pass it when you create the pipelines.
 //!
 //! TODO: write this section
+//!
+//! # Layouts from shader modules
+//!
+//! When shaders are loaded at runtime, their layout isn't known at compile time. In that
+//! situation `RuntimePipelineDesc::from_shader_modules` reflects the SPIR-V code of the modules
+//! and builds a description containing the union of their descriptors and push constants.
 
 pub use self::empty::EmptyPipelineDesc;
 pub use self::limits_check::PipelineLayoutLimitsError;
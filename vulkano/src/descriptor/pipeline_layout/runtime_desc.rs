@@ -7,6 +7,7 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use std::cmp;
 use std::error;
 use std::fmt;
 
@@ -15,6 +16,9 @@ use smallvec::SmallVec;
 use descriptor::descriptor::DescriptorDesc;
 use descriptor::pipeline_layout::PipelineLayoutDesc;
 use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+use pipeline::shader::ShaderModule;
+use pipeline::shader::ShaderReflectionError;
+use spirv::Spirv;
 
 /// Runtime description of a pipeline layout.
 #[derive(Debug, Clone)]
@@ -61,6 +65,104 @@ impl RuntimePipelineDesc {
                push_constants,
            })
     }
+
+    /// Builds a new `RuntimePipelineDesc` by reflecting the SPIR-V code of shader modules.
+    ///
+    /// The returned description is the union of the descriptors and push constants used by all
+    /// the modules. It can then be turned into a `PipelineLayout` with `build()`, or adjusted
+    /// before that.
+    ///
+    /// The shader stages of the descriptors and push constants of a module are determined from
+    /// the entry points it contains. Push constants ranges that overlap or that share a stage are
+    /// merged together.
+    pub fn from_shader_modules<'a, I>(modules: I)
+                                      -> Result<RuntimePipelineDesc, RuntimePipelineDescError>
+        where I: IntoIterator<Item = &'a ShaderModule>
+    {
+        let modules = modules
+            .into_iter()
+            .map(|m| Spirv::parse(m.spirv()))
+            .collect::<Result<Vec<_>, _>>()?;
+        RuntimePipelineDesc::from_spirv(&modules)
+    }
+
+    // Actual implementation of `from_shader_modules`.
+    pub(crate) fn from_spirv(modules: &[Spirv])
+                             -> Result<RuntimePipelineDesc, RuntimePipelineDescError> {
+        let mut descriptors = Vec::new();
+        let mut push_constants = Vec::new();
+
+        for spirv in modules {
+            descriptors.extend(spirv.descriptors()?);
+
+            if let Some((offset, size)) = spirv.push_constants_range()? {
+                push_constants.push(PipelineLayoutDescPcRange {
+                                        offset: offset as usize,
+                                        size: size as usize,
+                                        stages: spirv.stages(),
+                                    });
+            }
+        }
+
+        RuntimePipelineDesc::union_of(descriptors, push_constants)
+    }
+
+    // Builds the union of descriptors and push constants ranges that were used by multiple
+    // shader stages. Descriptors that share a binding are merged if they are compatible.
+    pub(crate) fn union_of<D, P>(descs: D, ranges: P)
+                                 -> Result<RuntimePipelineDesc, RuntimePipelineDescError>
+        where D: IntoIterator<Item = (u32, u32, DescriptorDesc)>,
+              P: IntoIterator<Item = PipelineLayoutDescPcRange>
+    {
+        let mut descriptors: SmallVec<[SmallVec<[Option<DescriptorDesc>; 5]>; 3]> =
+            SmallVec::new();
+        let mut push_constants: SmallVec<[PipelineLayoutDescPcRange; 6]> = SmallVec::new();
+
+        for (set, binding, desc) in descs {
+            while descriptors.len() <= set as usize {
+                descriptors.push(SmallVec::new());
+            }
+            let set_descs = &mut descriptors[set as usize];
+            while set_descs.len() <= binding as usize {
+                set_descs.push(None);
+            }
+
+            let merged = match set_descs[binding as usize].take() {
+                Some(existing) => {
+                    match existing.union(&desc) {
+                        Some(d) => d,
+                        None => {
+                            return Err(RuntimePipelineDescError::IncompatibleDescriptors {
+                                           set: set,
+                                           binding: binding,
+                                       });
+                        },
+                    }
+                },
+                None => desc,
+            };
+            set_descs[binding as usize] = Some(merged);
+        }
+
+        for mut range in ranges {
+            // Vulkan forbids a stage from being in multiple ranges, and we forbid ranges
+            // from overlapping. Merge the new range with the ones it conflicts with.
+            while let Some(pos) = push_constants.iter().position(|r| {
+                r.stages.intersects(&range.stages) ||
+                    (r.offset < range.offset + range.size && range.offset < r.offset + r.size)
+            }) {
+                let other = push_constants.remove(pos);
+                let end = cmp::max(other.offset + other.size, range.offset + range.size);
+                range.offset = cmp::min(other.offset, range.offset);
+                range.size = end - range.offset;
+                range.stages = range.stages | other.stages;
+            }
+
+            push_constants.push(range);
+        }
+
+        RuntimePipelineDesc::new(descriptors, push_constants)
+    }
 }
 
 unsafe impl PipelineLayoutDesc for RuntimePipelineDesc {
@@ -101,6 +203,12 @@ pub enum RuntimePipelineDescError {
         first_size: usize,
         second_offset: usize,
     },
+
+    /// Error while reflecting the SPIR-V code of a shader module.
+    ShaderReflectionError(ShaderReflectionError),
+
+    /// Two shader modules use the same binding with incompatible descriptor types.
+    IncompatibleDescriptors { set: u32, binding: u32 },
 }
 
 impl error::Error for RuntimePipelineDescError {
@@ -110,10 +218,31 @@ impl error::Error for RuntimePipelineDescError {
             RuntimePipelineDescError::PushConstantsConflict { .. } => {
                 "conflict between different push constants ranges"
             },
+            RuntimePipelineDescError::ShaderReflectionError(_) => {
+                "error while reflecting the SPIR-V code of a shader module"
+            },
+            RuntimePipelineDescError::IncompatibleDescriptors { .. } => {
+                "two shader modules use the same binding with incompatible descriptor types"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            RuntimePipelineDescError::ShaderReflectionError(ref err) => Some(err),
+            _ => None,
         }
     }
 }
 
+impl From<ShaderReflectionError> for RuntimePipelineDescError {
+    #[inline]
+    fn from(err: ShaderReflectionError) -> RuntimePipelineDescError {
+        RuntimePipelineDescError::ShaderReflectionError(err)
+    }
+}
+
 impl fmt::Display for RuntimePipelineDescError {
     #[inline]
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
@@ -125,9 +254,13 @@ impl fmt::Display for RuntimePipelineDescError {
 mod tests {
     use descriptor::descriptor::DescriptorDesc;
     use descriptor::descriptor::ShaderStages;
+    use descriptor::pipeline_layout::PipelineLayoutDesc;
     use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
     use descriptor::pipeline_layout::RuntimePipelineDesc;
     use descriptor::pipeline_layout::RuntimePipelineDescError;
+    use pipeline::shader::ShaderReflectionError;
+    use spirv::*;
+    use spirv::tests::module;
     use std::iter;
 
     #[test]
@@ -156,4 +289,80 @@ mod tests {
             _ => panic!(),   // test failed
         }
     }
+
+    #[test]
+    fn reflection_union() {
+        // Vertex shader with a uniform buffer at (0, 0) and push constants in 0..16.
+        let vs = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x6e69616d, 0]),
+                          (OP_MEMBER_DECORATE, &[3, 0, DECORATION_OFFSET, 0]),
+                          (OP_DECORATE, &[3, DECORATION_BLOCK]),
+                          (OP_DECORATE, &[5, DECORATION_DESCRIPTOR_SET, 0]),
+                          (OP_DECORATE, &[5, DECORATION_BINDING, 0]),
+                          (OP_TYPE_FLOAT, &[10, 32]),
+                          (OP_TYPE_VECTOR, &[2, 10, 4]),
+                          (OP_TYPE_STRUCT, &[3, 2]),
+                          (OP_TYPE_POINTER, &[4, STORAGE_CLASS_UNIFORM, 3]),
+                          (OP_VARIABLE, &[4, 5, STORAGE_CLASS_UNIFORM]),
+                          (OP_TYPE_POINTER, &[6, STORAGE_CLASS_PUSH_CONSTANT, 3]),
+                          (OP_VARIABLE, &[6, 7, STORAGE_CLASS_PUSH_CONSTANT])]);
+
+        // Fragment shader with the same uniform buffer, a sampler at (1, 2) and push constants
+        // in 8..24.
+        let fs = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_FRAGMENT, 1, 0x6e69616d, 0]),
+                          (OP_MEMBER_DECORATE, &[3, 0, DECORATION_OFFSET, 0]),
+                          (OP_DECORATE, &[3, DECORATION_BLOCK]),
+                          (OP_DECORATE, &[5, DECORATION_DESCRIPTOR_SET, 0]),
+                          (OP_DECORATE, &[5, DECORATION_BINDING, 0]),
+                          (OP_DECORATE, &[9, DECORATION_DESCRIPTOR_SET, 1]),
+                          (OP_DECORATE, &[9, DECORATION_BINDING, 2]),
+                          (OP_MEMBER_DECORATE, &[11, 0, DECORATION_OFFSET, 8]),
+                          (OP_DECORATE, &[11, DECORATION_BLOCK]),
+                          (OP_TYPE_FLOAT, &[10, 32]),
+                          (OP_TYPE_VECTOR, &[2, 10, 4]),
+                          (OP_TYPE_STRUCT, &[3, 2]),
+                          (OP_TYPE_POINTER, &[4, STORAGE_CLASS_UNIFORM, 3]),
+                          (OP_VARIABLE, &[4, 5, STORAGE_CLASS_UNIFORM]),
+                          (OP_TYPE_SAMPLER, &[6]),
+                          (OP_TYPE_POINTER, &[8, STORAGE_CLASS_UNIFORM_CONSTANT, 6]),
+                          (OP_VARIABLE, &[8, 9, STORAGE_CLASS_UNIFORM_CONSTANT]),
+                          (OP_TYPE_STRUCT, &[11, 2]),
+                          (OP_TYPE_POINTER, &[12, STORAGE_CLASS_PUSH_CONSTANT, 11]),
+                          (OP_VARIABLE, &[12, 13, STORAGE_CLASS_PUSH_CONSTANT])]);
+
+        let modules = [Spirv::parse(&vs).unwrap(), Spirv::parse(&fs).unwrap()];
+        let desc = RuntimePipelineDesc::from_spirv(&modules).unwrap();
+
+        assert_eq!(desc.num_sets(), 2);
+        assert_eq!(desc.num_bindings_in_set(1), Some(3));
+        let ubo = desc.descriptor(0, 0).unwrap();
+        assert!(ubo.stages.vertex && ubo.stages.fragment);
+        assert!(desc.descriptor(1, 0).is_none());
+        assert!(desc.descriptor(1, 2).unwrap().stages.fragment);
+
+        assert_eq!(desc.num_push_constants_ranges(), 1);
+        let pc = desc.push_constants_range(0).unwrap();
+        assert_eq!((pc.offset, pc.size), (0, 24));
+        assert!(pc.stages.vertex && pc.stages.fragment);
+    }
+
+    #[test]
+    fn reflection_huge_binding() {
+        // A sampler whose set and binding numbers would need huge layouts.
+        for &(set, binding) in [(0xffffffff, 0), (0, 0xffffffff)].iter() {
+            let fs = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_FRAGMENT, 1, 0x6e69616d, 0]),
+                              (OP_DECORATE, &[9, DECORATION_DESCRIPTOR_SET, set]),
+                              (OP_DECORATE, &[9, DECORATION_BINDING, binding]),
+                              (OP_TYPE_SAMPLER, &[6]),
+                              (OP_TYPE_POINTER, &[8, STORAGE_CLASS_UNIFORM_CONSTANT, 6]),
+                              (OP_VARIABLE, &[8, 9, STORAGE_CLASS_UNIFORM_CONSTANT])]);
+
+            let modules = [Spirv::parse(&fs).unwrap()];
+            match RuntimePipelineDesc::from_spirv(&modules) {
+                Err(RuntimePipelineDescError::ShaderReflectionError(err)) => {
+                    assert_eq!(err, ShaderReflectionError::BindingOutOfRange { set, binding });
+                },
+                _ => panic!(),
+            }
+        }
+    }
 }
@@ -147,7 +147,7 @@ impl<L> PipelineLayout<L>
             let mut output = mem::uninitialized();
             check_errors(vk.CreatePipelineLayout(device.internal_object(),
                                                  &infos,
-                                                 ptr::null(),
+                                                 device.allocator_ptr(),
                                                  &mut output))?;
             output
         };
@@ -238,7 +238,9 @@ impl<L> Drop for PipelineLayout<L> {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyPipelineLayout(self.device.internal_object(), self.layout, ptr::null());
+            vk.DestroyPipelineLayout(self.device.internal_object(),
+                                     self.layout,
+                                     self.device.allocator_ptr());
         }
     }
 }
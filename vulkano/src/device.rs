@@ -85,33 +85,65 @@
 //!
 //! The `Device::new` function returns the newly-created device, but also the list of queues.
 //!
+//! # Device loss
+//!
+//! A device can be *lost* at any time, for example after a driver crash, a GPU reset or a
+//! command that took too long to execute. Once this happens, submitting work and waiting on the
+//! device return a `DeviceLost` error, such as `FlushError::DeviceLost` or
+//! `WaitIdleError::DeviceLost`, and `Device::is_lost` returns true.
+//!
+//! A lost device can't be used anymore, but nothing runs on it either. The futures and the
+//! other objects that belong to it can be dropped without blocking or panicking. To recover,
+//! drop everything that was created from the lost device, then create a new device from the
+//! same physical device with `Device::new` and recreate your resources.
+//!
 //! # Extended example
 //!
 //! TODO: write
 
 use fnv::FnvHasher;
 use smallvec::SmallVec;
+use std::any::Any;
 use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 use std::error;
 use std::fmt;
 use std::hash::BuildHasherDefault;
+use std::iter::FromIterator;
 use std::mem;
 use std::ops::Deref;
+use std::os::raw::c_void;
 use std::ptr;
+use std::slice;
 use std::sync::Arc;
 use std::sync::Mutex;
 use std::sync::MutexGuard;
 use std::sync::Weak;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::ffi::CStr;
 
+use command_buffer::TransformFeedbackProperties;
 use command_buffer::pool::StandardCommandPool;
 use descriptor::descriptor_set::StdDescriptorPool;
+use features::FeaturesFfi;
+use instance::ExtendedProperties;
 use instance::Features;
 use instance::Instance;
+use instance::MemoryHeap;
 use instance::PhysicalDevice;
+use instance::PhysicalDeviceGroup;
 use instance::QueueFamily;
+use memory::HostAllocationCallbacks;
+use memory::host::HostAllocator;
 use memory::pool::StdMemoryPool;
+use pipeline::ComputePipelineKey;
+use pipeline::RayTracingProperties;
+use pipeline::shader::MeshShaderProperties;
+use pipeline::shader::ShaderModule;
+use pipeline::shader::SubgroupProperties;
+use pipeline::shader::SubgroupSizeControlProperties;
+use sync::PipelineStages;
 
 use Error;
 use OomError;
@@ -119,6 +151,7 @@ use SynchronizedVulkanObject;
 use VulkanObject;
 use VulkanHandle;
 use check_errors;
+use version::Version;
 use vk;
 
 pub use instance::{DeviceExtensions, RawDeviceExtensions};
@@ -127,19 +160,35 @@ pub use instance::{DeviceExtensions, RawDeviceExtensions};
 pub struct Device {
     instance: Arc<Instance>,
     physical_device: usize,
+    device_group: SmallVec<[usize; 8]>,
+    subset_allocation: bool,
+    protected_memory: bool,
     device: vk::Device,
+    // Callbacks that the implementation uses to allocate host memory. Must outlive the Vulkan
+    // device and its objects.
+    allocator: Option<HostAllocator>,
     vk: vk::DevicePointers,
     standard_pool: Mutex<Weak<StdMemoryPool>>,
     standard_descriptor_pool: Mutex<Weak<StdDescriptorPool>>,
     standard_command_pools:
         Mutex<HashMap<u32, Weak<StandardCommandPool>, BuildHasherDefault<FnvHasher>>>,
+    shader_modules: Mutex<HashMap<u64, Vec<Weak<ShaderModule>>, BuildHasherDefault<FnvHasher>>>,
+    compute_pipelines: Mutex<HashMap<ComputePipelineKey, Weak<Any + Send + Sync>,
+                                     BuildHasherDefault<FnvHasher>>>,
     features: Features,
     extensions: DeviceExtensions,
+    api_version: Version,
+    extended_properties: ExtendedProperties,
     active_queue_families: SmallVec<[u32; 8]>,
     allocation_count: Mutex<u32>,
     fence_pool: Mutex<Vec<vk::Fence>>,
     semaphore_pool: Mutex<Vec<vk::Semaphore>>,
     event_pool: Mutex<Vec<vk::Event>>,
+    lost: AtomicBool,
+    collected: Mutex<Vec<Weak<CollectFinished>>>,
+    // Names given to the objects of the device, attached to the spans of the `tracing` feature.
+    #[cfg(feature = "tracing")]
+    debug_names: Mutex<HashMap<(vk::ObjectType, u64), String, BuildHasherDefault<FnvHasher>>>,
 }
 
 // The `StandardCommandPool` type doesn't implement Send/Sync, so we have to manually reimplement
@@ -158,25 +207,185 @@ impl Device {
     ///   feature is not enabled at device creation, you can't use it later even it it's supported
     ///   by the physical device.
     ///
+    ///   The features of device extensions are only enabled if their extension is in
+    ///   `extensions` or was promoted to the core API of the device's version. The other ones are
+    ///   ignored. `enabled_features()` returns the features that have actually been enabled.
+    ///
     /// - An iterator to a list of queues to create. Each element of the iterator must indicate
     ///   the family whose queue belongs to and a priority between 0.0 and 1.0 to assign to it.
     ///   A queue with a higher value indicates that the commands will execute faster than on a
     ///   queue with a lower value. Note however that no guarantee can be made on the way the
     ///   priority value is handled by the implementation.
     ///
+    ///   The elements are either `(QueueFamily, f32)` tuples or `QueueCreateInfo` structs. The
+    ///   latter can also request protected queues.
+    ///
+    /// The queues are returned in the same order as they were requested. They can be collected
+    /// into a `Queues` to look them up by family and index.
+    ///
     /// # Panic
     ///
     /// - Panics if one of the queue families doesn't belong to the given device.
     ///
     // TODO: return Arc<Queue> and handle synchronization in the Queue
     // TODO: should take the PhysicalDevice by value
-    pub fn new<'a, I, Ext>(phys: PhysicalDevice, requested_features: &Features, extensions: Ext,
-                           queue_families: I)
-                           -> Result<(Arc<Device>, QueuesIter), DeviceCreationError>
-        where I: IntoIterator<Item = (QueueFamily<'a>, f32)>,
+    #[inline]
+    pub fn new<'a, I, Q, Ext>(phys: PhysicalDevice, requested_features: &Features, extensions: Ext,
+                              queue_families: I)
+                              -> Result<(Arc<Device>, QueuesIter), DeviceCreationError>
+        where I: IntoIterator<Item = Q>,
+              Q: Into<QueueCreateInfo<'a>>,
+              Ext: Into<RawDeviceExtensions>
+    {
+        Device::with_device_group(&PhysicalDeviceGroup::from_physical_device(phys),
+                                  requested_features,
+                                  extensions,
+                                  queue_families)
+    }
+
+    /// Builds a new Vulkan device for headless compute, with a single queue that supports compute
+    /// operations.
+    ///
+    /// The queue is created from a family that doesn't support graphics operations whenever
+    /// possible. No feature and no extension are enabled.
+    ///
+    /// Returns `ComputeNotSupported` if the physical device has no queue family that supports
+    /// compute operations.
+    pub fn new_compute(phys: PhysicalDevice)
+                       -> Result<(Arc<Device>, Arc<Queue>), DeviceCreationError> {
+        let family = phys.queue_families()
+            .filter(|q| q.supports_compute())
+            .find(|q| !q.supports_graphics())
+            .or_else(|| phys.queue_families().find(|q| q.supports_compute()));
+        let family = match family {
+            Some(f) => f,
+            None => return Err(DeviceCreationError::ComputeNotSupported),
+        };
+
+        let (device, mut queues) = Device::new(phys,
+                                               &Features::none(),
+                                               &DeviceExtensions::none(),
+                                               Some((family, 1.0)))?;
+        Ok((device, queues.next().unwrap()))
+    }
+
+    /// Same as `new`, but builds a device that spans all the physical devices of `group`.
+    ///
+    /// The features, the extensions and the queue families are those of the first physical
+    /// device of the group, which is returned by `physical_device()`. The physical devices of a
+    /// group all have the same properties.
+    ///
+    /// Commands and memory allocations are then executed on all the physical devices of the
+    /// group, unless they are restricted with a device mask. This requires the
+    /// `khr_device_group` extension to be enabled on the device.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the queue families doesn't belong to the first physical device of the
+    ///   group.
+    ///
+    pub fn with_device_group<'a, I, Q, Ext>(
+        group: &PhysicalDeviceGroup, requested_features: &Features, extensions: Ext,
+        queue_families: I)
+        -> Result<(Arc<Device>, QueuesIter), DeviceCreationError>
+        where I: IntoIterator<Item = Q>,
+              Q: Into<QueueCreateInfo<'a>>,
+              Ext: Into<RawDeviceExtensions>
+    {
+        Device::new_inner(group, requested_features, extensions.into(), queue_families, None)
+    }
+
+    /// Same as `new`, but the host memory that the Vulkan implementation needs for the device and
+    /// its objects is allocated through `callbacks`, instead of through the callbacks of the
+    /// instance.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the queue families doesn't belong to the given device.
+    ///
+    pub fn with_allocation_callbacks<'a, I, Q, Ext>(
+        phys: PhysicalDevice, requested_features: &Features, extensions: Ext, queue_families: I,
+        callbacks: Arc<HostAllocationCallbacks>)
+        -> Result<(Arc<Device>, QueuesIter), DeviceCreationError>
+        where I: IntoIterator<Item = Q>,
+              Q: Into<QueueCreateInfo<'a>>,
               Ext: Into<RawDeviceExtensions>
     {
-        let queue_families = queue_families.into_iter();
+        Device::new_inner(&PhysicalDeviceGroup::from_physical_device(phys),
+                          requested_features,
+                          extensions.into(),
+                          queue_families,
+                          Some(callbacks))
+    }
+
+    /// Builds a `Device` from a raw device handle, for example one that was created by an OpenXR
+    /// runtime with `xrCreateVulkanDeviceKHR`.
+    ///
+    /// `enabled_features`, `extensions` and `queue_families` must be the features, the extensions
+    /// and the queues that the device was created with. The extensions that were promoted to the
+    /// core API of the device are considered loaded, like with `new`, and the queues are returned
+    /// in the same order as with `new`.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid device that was created from `phys`, with the allocation
+    ///   callbacks of the instance if it has any.
+    /// - The `Device` takes ownership of `handle` and destroys it when it is dropped.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the queue families doesn't belong to the given device.
+    ///
+    pub unsafe fn from_raw<'a, I, Q, Ext>(phys: PhysicalDevice, handle: u64,
+                                          enabled_features: &Features, extensions: Ext,
+                                          queue_families: I)
+                                          -> (Arc<Device>, QueuesIter)
+        where I: IntoIterator<Item = Q>,
+              Q: Into<QueueCreateInfo<'a>>,
+              Ext: Into<RawDeviceExtensions>
+    {
+        let api_version = Device::api_version_of(phys);
+        let loaded_extensions: DeviceExtensions = (&extensions.into()).into();
+        let loaded_extensions = loaded_extensions.union(&DeviceExtensions::core_in(api_version));
+
+        // The queues of each family get their index in the order in which they are listed,
+        // separately for the protected and the other queues.
+        let mut output_queues: SmallVec<[(u32, u32, bool); 8]> = SmallVec::new();
+        for info in queue_families {
+            let QueueCreateInfo { family, protected, .. } = info.into();
+            assert_eq!(family.physical_device().internal_object(), phys.internal_object());
+            let id = output_queues
+                .iter()
+                .filter(|&&(f, _, p)| f == family.id() && p == protected)
+                .count();
+            output_queues.push((family.id(), id as u32, protected));
+        }
+        let protected_memory = output_queues.iter().any(|&(_, _, protected)| protected);
+
+        let allocator = phys.instance()
+            .allocation_callbacks()
+            .cloned()
+            .map(HostAllocator::new);
+
+        Device::from_handle(&PhysicalDeviceGroup::from_physical_device(phys),
+                            handle as vk::Device,
+                            enabled_features.clone(),
+                            loaded_extensions,
+                            api_version,
+                            protected_memory,
+                            allocator,
+                            output_queues)
+    }
+
+    fn new_inner<'a, I, Q>(group: &PhysicalDeviceGroup, requested_features: &Features,
+                           extensions: RawDeviceExtensions, queue_families: I,
+                           callbacks: Option<Arc<HostAllocationCallbacks>>)
+                           -> Result<(Arc<Device>, QueuesIter), DeviceCreationError>
+        where I: IntoIterator<Item = Q>,
+              Q: Into<QueueCreateInfo<'a>>
+    {
+        let phys = group.physical_device(0).unwrap();
+        let queue_families = queue_families.into_iter().map(|q| q.into());
 
         if !phys.supported_features().superset_of(&requested_features) {
             return Err(DeviceCreationError::FeatureNotPresent);
@@ -184,8 +393,9 @@ impl Device {
 
         let vk_i = phys.instance().pointers();
 
-        // this variable will contain the queue family ID and queue ID of each requested queue
-        let mut output_queues: SmallVec<[(u32, u32); 8]> = SmallVec::new();
+        // this variable will contain the queue family ID, queue ID and protected flag of each
+        // requested queue
+        let mut output_queues: SmallVec<[(u32, u32, bool); 8]> = SmallVec::new();
 
         // Device layers were deprecated in Vulkan 1.0.13, and device layer requests should be
         // ignored by the driver. For backwards compatibility, the spec recommends passing the
@@ -201,47 +411,119 @@ impl Device {
             .map(|layer| layer.as_ptr())
             .collect::<SmallVec<[_; 16]>>();
 
-        let extensions = extensions.into();
+        // The extensions that were promoted to the core API of the version used by the device
+        // are loaded even if they weren't requested. The promoted extensions that the physical
+        // device doesn't advertise anymore aren't passed to the implementation.
+        let api_version = Device::api_version_of(phys);
+        let core_extensions = DeviceExtensions::core_in(api_version);
+        let unsupported_core_extensions: RawDeviceExtensions =
+            (&core_extensions.difference(&DeviceExtensions::supported_by_device(phys))).into();
+
+        // The specifications require `VK_KHR_portability_subset` to be enabled on the devices
+        // that support it, so it is always enabled on them.
+        let portability_subset: RawDeviceExtensions = (&DeviceExtensions {
+            khr_portability_subset: DeviceExtensions::supported_by_device(phys)
+                .khr_portability_subset,
+            ..DeviceExtensions::none()
+        }).into();
+        let extensions = extensions.union(&portability_subset);
+        let loaded_extensions: DeviceExtensions = (&extensions).into();
+        let loaded_extensions = loaded_extensions.union(&core_extensions);
         let extensions_list = extensions
             .iter()
+            .filter(|extension| !unsupported_core_extensions.contains(extension))
             .map(|extension| extension.as_ptr())
             .collect::<SmallVec<[_; 16]>>();
 
+        // The requested features of the extensions that aren't loaded are ignored.
+        //
+        // TODO: The plan regarding `robustBufferAccess` is to check the shaders' code to see
+        //       if they can possibly perform out-of-bounds reads and writes. If the user tries
+        //       to use a shader that can perform out-of-bounds operations without having
+        //       `robustBufferAccess` enabled, an error is returned.
+        //
+        //       However for the moment this verification isn't performed. In order to be safe,
+        //       we always enable the `robustBufferAccess` feature as it is guaranteed to be
+        //       supported everywhere.
+        //
+        //       The only alternative (while waiting for shaders introspection to work) is to
+        //       make all shaders depend on `robustBufferAccess`. But since usually the
+        //       majority of shaders don't need this feature, it would be very annoying to have
+        //       to enable it manually when you don't need it.
+        let enabled_features = Features {
+            robust_buffer_access: true,
+            ..requested_features.restricted_to(&loaded_extensions)
+        };
+
+        // set to true if protected queues are requested
+        let mut protected_memory = false;
+
+        // The device and its objects use the callbacks of the instance if none are specified.
+        let allocator = callbacks
+            .or_else(|| phys.instance().allocation_callbacks().cloned())
+            .map(HostAllocator::new);
+
         // device creation
         let device = unsafe {
-            // each element of `queues` is a `(queue_family, priorities)`
-            // each queue family must only have one entry in `queues`
-            let mut queues: Vec<(u32, Vec<f32>)> = Vec::with_capacity(phys.queue_families().len());
+            // each element of `queues` is a `(queue_family, protected, priorities)`
+            // each queue family must only have one entry in `queues` for protected queues, and
+            // one for the other queues
+            let mut queues: Vec<(u32, bool, Vec<f32>)> =
+                Vec::with_capacity(phys.queue_families().len());
+
+            for info in queue_families {
+                let QueueCreateInfo { family: queue_family, priority, protected } = info;
 
-            for (queue_family, priority) in queue_families {
                 // checking the parameters
                 assert_eq!(queue_family.physical_device().internal_object(),
                            phys.internal_object());
                 if priority < 0.0 || priority > 1.0 {
                     return Err(DeviceCreationError::PriorityOutOfRange);
                 }
+                if protected {
+                    if !queue_family.supports_protected() ||
+                        !Device::protected_memory_supported(phys)
+                    {
+                        return Err(DeviceCreationError::ProtectedMemoryNotSupported);
+                    }
+                    protected_memory = true;
+                }
+
+                // the protected and the other queues of a family share the queues of the family
+                let family_count = queues
+                    .iter()
+                    .filter(|q| q.0 == queue_family.id())
+                    .map(|q| q.2.len())
+                    .sum::<usize>();
+                if family_count >= queue_family.queues_count() {
+                    return Err(DeviceCreationError::TooManyQueuesForFamily);
+                }
 
                 // adding to `queues` and `output_queues`
-                if let Some(q) = queues.iter_mut().find(|q| q.0 == queue_family.id()) {
-                    output_queues.push((queue_family.id(), q.1.len() as u32));
-                    q.1.push(priority);
-                    if q.1.len() > queue_family.queues_count() {
-                        return Err(DeviceCreationError::TooManyQueuesForFamily);
-                    }
+                if let Some(q) = queues
+                    .iter_mut()
+                    .find(|q| q.0 == queue_family.id() && q.1 == protected)
+                {
+                    output_queues.push((queue_family.id(), q.2.len() as u32, protected));
+                    q.2.push(priority);
                     continue;
                 }
-                queues.push((queue_family.id(), vec![priority]));
-                output_queues.push((queue_family.id(), 0));
+                queues.push((queue_family.id(), protected, vec![priority]));
+                output_queues.push((queue_family.id(), 0, protected));
             }
 
             // turning `queues` into an array of `vkDeviceQueueCreateInfo` suitable for Vulkan
             let queues = queues
                 .iter()
-                .map(|&(queue_id, ref priorities)| {
+                .map(|&(queue_id, protected, ref priorities)| {
                     vk::DeviceQueueCreateInfo {
                         sType: vk::STRUCTURE_TYPE_DEVICE_QUEUE_CREATE_INFO,
                         pNext: ptr::null(),
-                        flags: 0, // reserved
+                        flags: if protected {
+                            vk::DEVICE_QUEUE_CREATE_PROTECTED_BIT
+                        } else {
+                            0
+                        },
                         queueFamilyIndex: queue_id,
                         queueCount: priorities.len() as u32,
                         pQueuePriorities: priorities.as_ptr(),
@@ -249,31 +531,53 @@ impl Device {
                 })
                 .collect::<SmallVec<[_; 16]>>();
 
-            // TODO: The plan regarding `robustBufferAccess` is to check the shaders' code to see
-            //       if they can possibly perform out-of-bounds reads and writes. If the user tries
-            //       to use a shader that can perform out-of-bounds operations without having
-            //       `robustBufferAccess` enabled, an error is returned.
-            //
-            //       However for the moment this verification isn't performed. In order to be safe,
-            //       we always enable the `robustBufferAccess` feature as it is guaranteed to be
-            //       supported everywhere.
-            //
-            //       The only alternative (while waiting for shaders introspection to work) is to
-            //       make all shaders depend on `robustBufferAccess`. But since usually the
-            //       majority of shaders don't need this feature, it would be very annoying to have
-            //       to enable it manually when you don't need it.
-            //
-            //       Note that if we ever remove this, don't forget to adjust the change in
-            //       `Device`'s construction below.
-            let features = {
-                let mut features = requested_features.clone().into_vulkan_features();
-                features.robustBufferAccess = vk::TRUE;
-                features
+            // The extension features structs are chained after `VkPhysicalDeviceFeatures2KHR`,
+            // but only the rest of the chain is passed, as `VkPhysicalDeviceFeatures2KHR` isn't
+            // available when the instance doesn't support `VK_KHR_get_physical_device_properties2`.
+            let mut features_ffi = FeaturesFfi::new();
+            enabled_features.write_ffi(&mut features_ffi);
+            features_ffi.make_chain(&loaded_extensions);
+            let features = features_ffi.head_as_ref();
+            let mut next = features.pNext as *const c_void;
+
+            // The `protectedMemory` feature of Vulkan 1.1 is enabled if protected queues were
+            // requested.
+            let protected_memory_features = if protected_memory {
+                Some(vk::PhysicalDeviceProtectedMemoryFeatures {
+                         sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROTECTED_MEMORY_FEATURES,
+                         pNext: next as *mut _,
+                         protectedMemory: vk::TRUE,
+                     })
+            } else {
+                None
+            };
+            if let Some(ref f) = protected_memory_features {
+                next = f as *const vk::PhysicalDeviceProtectedMemoryFeatures as *const _;
+            }
+
+            // A device made of several physical devices is created by putting a
+            // `VkDeviceGroupDeviceCreateInfoKHR` in front of the chain.
+            let group_handles = group
+                .physical_devices()
+                .map(|device| device.internal_object())
+                .collect::<SmallVec<[_; 8]>>();
+            let group_infos = if group_handles.len() > 1 {
+                Some(vk::DeviceGroupDeviceCreateInfoKHR {
+                         sType: vk::STRUCTURE_TYPE_DEVICE_GROUP_DEVICE_CREATE_INFO_KHR,
+                         pNext: next,
+                         physicalDeviceCount: group_handles.len() as u32,
+                         pPhysicalDevices: group_handles.as_ptr(),
+                     })
+            } else {
+                None
             };
+            if let Some(ref i) = group_infos {
+                next = i as *const vk::DeviceGroupDeviceCreateInfoKHR as *const _;
+            }
 
             let infos = vk::DeviceCreateInfo {
                 sType: vk::STRUCTURE_TYPE_DEVICE_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: next,
                 flags: 0, // reserved
                 queueCreateInfoCount: queues.len() as u32,
                 pQueueCreateInfos: queues.as_ptr(),
@@ -281,19 +585,58 @@ impl Device {
                 ppEnabledLayerNames: layers_ptr.as_ptr(),
                 enabledExtensionCount: extensions_list.len() as u32,
                 ppEnabledExtensionNames: extensions_list.as_ptr(),
-                pEnabledFeatures: &features,
+                pEnabledFeatures: &features.features,
             };
 
             let mut output = mem::uninitialized();
             check_errors(vk_i.CreateDevice(phys.internal_object(),
                                            &infos,
-                                           ptr::null(),
+                                           HostAllocator::as_ptr(&allocator),
                                            &mut output))?;
             output
         };
 
+        unsafe {
+            Ok(Device::from_handle(group,
+                                   device,
+                                   enabled_features,
+                                   loaded_extensions,
+                                   api_version,
+                                   protected_memory,
+                                   allocator,
+                                   output_queues))
+        }
+    }
+
+    // Loads the functions of `device` and builds the `Device` and the iterator to its queues.
+    // Shared by `new_inner` and `from_raw`.
+    unsafe fn from_handle(group: &PhysicalDeviceGroup, device: vk::Device,
+                          enabled_features: Features, loaded_extensions: DeviceExtensions,
+                          api_version: Version, protected_memory: bool,
+                          allocator: Option<HostAllocator>,
+                          output_queues: SmallVec<[(u32, u32, bool); 8]>)
+                          -> (Arc<Device>, QueuesIter) {
+        let phys = group.physical_device(0).unwrap();
+        let vk_i = phys.instance().pointers();
+
+        // The properties of the ray tracing extensions are needed to build acceleration
+        // structures and shader binding tables, the one of `VK_EXT_multi_draw` to check the
+        // number of draws of a command, the ones of `VK_EXT_transform_feedback` to check the
+        // transform feedback bindings, the one of `VK_EXT_conservative_rasterization` to check
+        // the extra overestimation size of graphics pipelines, the ones of `VK_EXT_mesh_shader`
+        // to check the number of workgroups of mesh shading draws, and the one of
+        // `VK_EXT_vertex_attribute_divisor` to check the instance divisors of graphics pipelines.
+        // They are queried again with only the loaded extensions, so that the properties of the
+        // extensions that aren't loaded are all 0.
+        let extended_properties =
+            if phys.supports_properties2() {
+                ExtendedProperties::query(vk_i, phys.internal_object(), &loaded_extensions).1
+            } else {
+                ExtendedProperties::none()
+            };
+
         // loading the function pointers of the newly-created device
-        let vk = vk::DevicePointers::load(|name| unsafe {
+        let vk = vk::DevicePointers::load(|name| {
                                               vk_i.GetDeviceProcAddr(device, name.as_ptr()) as
                                                   *const _
                                           });
@@ -302,22 +645,33 @@ impl Device {
             Arc::new(Device {
                          instance: phys.instance().clone(),
                          physical_device: phys.index(),
+                         device_group: group.device_indices().iter().cloned().collect(),
+                         subset_allocation: group.subset_allocation(),
+                         protected_memory: protected_memory,
                          device: device,
+                         allocator: allocator,
                          vk: vk,
                          standard_pool: Mutex::new(Weak::new()),
                          standard_descriptor_pool: Mutex::new(Weak::new()),
                          standard_command_pools: Mutex::new(Default::default()),
-                         features: Features {
-                             // Always enabled ; see above
-                             robust_buffer_access: true,
-                             ..requested_features.clone()
-                         },
-                         extensions: (&extensions).into(),
-                         active_queue_families: output_queues.iter().map(|&(q, _)| q).collect(),
+                         shader_modules: Mutex::new(Default::default()),
+                         compute_pipelines: Mutex::new(Default::default()),
+                         features: enabled_features,
+                         extensions: loaded_extensions,
+                         api_version: api_version,
+                         extended_properties: extended_properties,
+                         active_queue_families: output_queues
+                             .iter()
+                             .map(|&(q, _, _)| q)
+                             .collect(),
                          allocation_count: Mutex::new(0),
                          fence_pool: Mutex::new(Vec::new()),
                          semaphore_pool: Mutex::new(Vec::new()),
                          event_pool: Mutex::new(Vec::new()),
+                         lost: AtomicBool::new(false),
+                         collected: Mutex::new(Vec::new()),
+                         #[cfg(feature = "tracing")]
+                         debug_names: Mutex::new(Default::default()),
                      });
 
         // Iterator for the produced queues.
@@ -327,7 +681,7 @@ impl Device {
             families_and_ids: output_queues,
         };
 
-        Ok((device, output_queues))
+        (device, output_queues)
     }
 
     /// Grants access to the pointers to the Vulkan functions of the device.
@@ -347,23 +701,158 @@ impl Device {
     /// of the device (either explicitely or implicitely, for example with a future's destructor)
     /// while this function is waiting.
     ///
-    pub unsafe fn wait(&self) -> Result<(), OomError> {
-        check_errors(self.vk.DeviceWaitIdle(self.device))?;
+    pub unsafe fn wait(&self) -> Result<(), WaitIdleError> {
+        let _span = instrument_span!("vkDeviceWaitIdle");
+        self.check_lost(check_errors(self.vk.DeviceWaitIdle(self.device)))?;
         Ok(())
     }
 
+    /// Returns true if an operation on the device has reported that the device is lost.
+    ///
+    /// A lost device can't execute anything anymore. See the documentation of the `device`
+    /// module for how to recover from it.
+    #[inline]
+    pub fn is_lost(&self) -> bool {
+        self.lost.load(Ordering::Relaxed)
+    }
+
+    /// Marks the device as lost if `result` is a `DeviceLost` error, then returns `result`.
+    #[inline]
+    pub(crate) fn check_lost<T>(&self, result: Result<T, Error>) -> Result<T, Error> {
+        if let Err(Error::DeviceLost) = result {
+            self.lost.store(true, Ordering::Relaxed);
+        }
+
+        result
+    }
+
     /// Returns the instance used to create this device.
     #[inline]
     pub fn instance(&self) -> &Arc<Instance> {
         &self.instance
     }
 
+    /// Returns the callbacks that the Vulkan implementation uses to allocate host memory for this
+    /// device, if any.
+    #[inline]
+    pub fn allocation_callbacks(&self) -> Option<&Arc<HostAllocationCallbacks>> {
+        self.allocator.as_ref().map(|a| a.callbacks())
+    }
+
+    /// Returns the `pAllocator` parameter to pass when creating or destroying the device or one of
+    /// its objects.
+    #[inline]
+    pub(crate) fn allocator_ptr(&self) -> *const vk::AllocationCallbacks {
+        HostAllocator::as_ptr(&self.allocator)
+    }
+
     /// Returns the physical device that was used to create this device.
+    ///
+    /// If the device was created from a group of physical devices, this is the first one of the
+    /// group.
     #[inline]
     pub fn physical_device(&self) -> PhysicalDevice {
         PhysicalDevice::from_index(&self.instance, self.physical_device).unwrap()
     }
 
+    /// Returns the number of physical devices that this device is made of.
+    ///
+    /// Bit `n` of a device mask designates the `n`th of these physical devices. This is 1 unless
+    /// the device was created with `with_device_group`.
+    #[inline]
+    pub fn physical_device_count(&self) -> u32 {
+        self.device_group.len() as u32
+    }
+
+    /// Returns the physical devices that this device is made of, in the order of their bit in
+    /// device masks.
+    #[inline]
+    pub fn physical_devices<'a>(&'a self)
+                                -> Box<ExactSizeIterator<Item = PhysicalDevice<'a>> + 'a> {
+        let instance = &self.instance;
+        Box::new(self.device_group
+                     .iter()
+                     .map(move |&id| PhysicalDevice::from_index(instance, id).unwrap()))
+    }
+
+    /// Returns true if the `protectedMemory` feature of Vulkan 1.1 is enabled on the device,
+    /// which happens if protected queues were requested when creating it.
+    #[inline]
+    pub fn protected_memory_enabled(&self) -> bool {
+        self.protected_memory
+    }
+
+    // Returns true if `phys` supports the `protectedMemory` feature of Vulkan 1.1.
+    fn protected_memory_supported(phys: PhysicalDevice) -> bool {
+        let v1_1 = Version { major: 1, minor: 1, patch: 0 };
+        if !phys.supports_properties2() || Device::api_version_of(phys) < v1_1 {
+            return false;
+        }
+
+        unsafe {
+            let mut protected_memory = vk::PhysicalDeviceProtectedMemoryFeatures {
+                sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROTECTED_MEMORY_FEATURES,
+                pNext: ptr::null_mut(),
+                protectedMemory: vk::FALSE,
+            };
+            let mut features = vk::PhysicalDeviceFeatures2KHR {
+                sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_FEATURES_2_KHR,
+                pNext: &mut protected_memory as *mut _ as *const _,
+                features: mem::zeroed(),
+            };
+            phys.instance()
+                .pointers()
+                .GetPhysicalDeviceFeatures2KHR(phys.internal_object(), &mut features);
+            protected_memory.protectedMemory != vk::FALSE
+        }
+    }
+
+    /// Returns true if memory can be allocated on a subset of the physical devices of the device
+    /// with `DeviceMemory::alloc_with_device_mask`.
+    #[inline]
+    pub fn subset_allocation(&self) -> bool {
+        self.subset_allocation
+    }
+
+    /// Returns how the physical device `local_device_index` of the device can access memory
+    /// allocated on `heap` for the physical device `remote_device_index`.
+    ///
+    /// The indices are the positions of the physical devices in the device, as in device masks.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the `khr_device_group` extension isn't loaded.
+    /// - Panics if `heap` doesn't belong to the physical device of the device.
+    /// - Panics if one of the indices is out of range, or if they are equal.
+    ///
+    pub fn peer_memory_features(&self, heap: MemoryHeap, local_device_index: u32,
+                                remote_device_index: u32)
+                                -> PeerMemoryFeatures {
+        assert!(self.extensions.khr_device_group);
+        assert_eq!(heap.physical_device().internal_object(),
+                   self.physical_device().internal_object());
+        assert!(local_device_index < self.physical_device_count());
+        assert!(remote_device_index < self.physical_device_count());
+        assert_ne!(local_device_index, remote_device_index);
+
+        let flags = unsafe {
+            let mut output = 0;
+            self.vk.GetDeviceGroupPeerMemoryFeaturesKHR(self.device,
+                                                        heap.id(),
+                                                        local_device_index,
+                                                        remote_device_index,
+                                                        &mut output);
+            output
+        };
+
+        PeerMemoryFeatures {
+            copy_src: (flags & vk::PEER_MEMORY_FEATURE_COPY_SRC_BIT_KHR) != 0,
+            copy_dst: (flags & vk::PEER_MEMORY_FEATURE_COPY_DST_BIT_KHR) != 0,
+            generic_src: (flags & vk::PEER_MEMORY_FEATURE_GENERIC_SRC_BIT_KHR) != 0,
+            generic_dst: (flags & vk::PEER_MEMORY_FEATURE_GENERIC_DST_BIT_KHR) != 0,
+        }
+    }
+
     /// Returns an iterator to the list of queues families that this device uses.
     ///
     /// > **Note**: Will return `-> impl ExactSizeIterator<Item = QueueFamily>` in the future.
@@ -384,11 +873,133 @@ impl Device {
     }
 
     /// Returns the list of extensions that have been loaded.
+    ///
+    /// This includes the extensions that were promoted to the core API of the version returned
+    /// by `api_version()`, even if they weren't passed to `Device::new()`.
     #[inline]
     pub fn loaded_extensions(&self) -> &DeviceExtensions {
         &self.extensions
     }
 
+    /// Returns the version of Vulkan used by the device.
+    ///
+    /// This is the lowest of the version used by the instance and the version supported by the
+    /// physical device.
+    #[inline]
+    pub fn api_version(&self) -> Version {
+        self.api_version
+    }
+
+    // Returns the version of Vulkan that a device created from `phys` uses.
+    fn api_version_of(phys: PhysicalDevice) -> Version {
+        let instance_version = phys.instance().api_version();
+        let device_version = phys.api_version();
+        if device_version < instance_version {
+            device_version
+        } else {
+            instance_version
+        }
+    }
+
+    /// Returns the maximum instance divisor of the vertex input bindings of graphics pipelines.
+    /// Always 0 if the `VK_EXT_vertex_attribute_divisor` extension isn't loaded.
+    #[inline]
+    pub fn max_vertex_attrib_divisor(&self) -> u32 {
+        self.extended_properties.max_vertex_attrib_divisor
+    }
+
+    /// Returns true if the `graphicsPipelineLibrary` feature of the
+    /// `VK_EXT_graphics_pipeline_library` extension is enabled in the device. Always false if the
+    /// extension isn't loaded.
+    #[inline]
+    pub fn graphics_pipeline_library_enabled(&self) -> bool {
+        self.features.graphics_pipeline_library
+    }
+
+    /// Returns the limits of the physical device related to the `VK_EXT_mesh_shader` extension.
+    /// They are all 0 if the extension isn't loaded.
+    #[inline]
+    pub fn mesh_shader_properties(&self) -> &MeshShaderProperties {
+        &self.extended_properties.mesh_shader
+    }
+
+    /// Returns true if the `multiDraw` feature of the `VK_EXT_multi_draw` extension is enabled in
+    /// the device. Always false if the extension isn't loaded.
+    #[inline]
+    pub fn multi_draw_enabled(&self) -> bool {
+        self.features.multi_draw
+    }
+
+    /// Returns the maximum number of draws of a single multi-draw command. Always 0 if the
+    /// `VK_EXT_multi_draw` extension isn't loaded.
+    #[inline]
+    pub fn max_multi_draw_count(&self) -> u32 {
+        self.extended_properties.max_multi_draw_count
+    }
+
+    /// Returns the maximum extra size by which primitives can be overestimated by conservative
+    /// rasterization. Always 0.0 if the `VK_EXT_conservative_rasterization` extension isn't
+    /// loaded.
+    #[inline]
+    pub fn max_extra_primitive_overestimation_size(&self) -> f32 {
+        self.extended_properties.max_extra_primitive_overestimation_size
+    }
+
+    /// Returns the properties of the physical device related to the `VK_EXT_transform_feedback`
+    /// extension. They are all 0 or false if the extension isn't loaded.
+    #[inline]
+    pub fn transform_feedback_properties(&self) -> &TransformFeedbackProperties {
+        &self.extended_properties.transform_feedback
+    }
+
+    /// Returns true if the `bufferDeviceAddress` feature of the `VK_KHR_buffer_device_address`
+    /// extension is enabled in the device. Always false if the extension isn't loaded.
+    #[inline]
+    pub fn buffer_device_address_enabled(&self) -> bool {
+        self.features.buffer_device_address
+    }
+
+    /// Returns the properties of the physical device related to ray tracing. They are all 0 if
+    /// the `VK_KHR_acceleration_structure` and `VK_KHR_ray_tracing_pipeline` extensions aren't
+    /// loaded.
+    #[inline]
+    pub fn ray_tracing_properties(&self) -> &RayTracingProperties {
+        &self.extended_properties.ray_tracing
+    }
+
+    /// Returns the subgroup properties of the physical device. The subgroup size is 0 and no
+    /// operation is supported if the physical device doesn't support Vulkan 1.1, or if the
+    /// `VK_KHR_get_physical_device_properties2` extension isn't loaded on the instance.
+    #[inline]
+    pub fn subgroup_properties(&self) -> &SubgroupProperties {
+        &self.extended_properties.subgroup
+    }
+
+    /// Returns the properties of the physical device related to the
+    /// `VK_EXT_subgroup_size_control` extension. They are all 0 if the extension isn't loaded.
+    #[inline]
+    pub fn subgroup_size_control_properties(&self) -> &SubgroupSizeControlProperties {
+        &self.extended_properties.subgroup_size_control
+    }
+
+    /// Returns true if the `timelineSemaphore` feature of the `VK_KHR_timeline_semaphore`
+    /// extension is enabled in the device. Always false if the extension isn't loaded.
+    #[inline]
+    pub fn timeline_semaphore_enabled(&self) -> bool {
+        self.features.timeline_semaphore
+    }
+
+    /// Returns true if the `synchronization2` feature of the `VK_KHR_synchronization2` extension
+    /// is enabled in the device. Always false if the extension isn't loaded.
+    ///
+    /// If true, vulkano records pipeline barriers with `vkCmdPipelineBarrier2KHR` and submits
+    /// command buffers with `vkQueueSubmit2KHR`. Otherwise the 64-bit pipeline stages and access
+    /// flags are translated to their closest equivalent in the original API.
+    #[inline]
+    pub fn synchronization2_enabled(&self) -> bool {
+        self.features.synchronization2
+    }
+
     /// Returns the standard memory pool used by default if you don't provide any other pool.
     pub fn standard_pool(me: &Arc<Self>) -> Arc<StdMemoryPool> {
         let mut pool = me.standard_pool.lock().unwrap();
@@ -456,6 +1067,19 @@ impl Device {
         &self.allocation_count
     }
 
+    // Shader modules created with `ShaderModule::from_words_deduplicated`, by hash of their code.
+    pub(crate) fn shader_modules(&self)
+        -> &Mutex<HashMap<u64, Vec<Weak<ShaderModule>>, BuildHasherDefault<FnvHasher>>> {
+        &self.shader_modules
+    }
+
+    // Compute pipelines created with `ComputePipeline::new_deduplicated`.
+    pub(crate) fn compute_pipelines(&self)
+        -> &Mutex<HashMap<ComputePipelineKey, Weak<Any + Send + Sync>,
+                          BuildHasherDefault<FnvHasher>>> {
+        &self.compute_pipelines
+    }
+
     pub(crate) fn fence_pool(&self) -> &Mutex<Vec<vk::Fence>> {
         &self.fence_pool
     }
@@ -468,6 +1092,86 @@ impl Device {
         &self.event_pool
     }
 
+    /// Returns the number of fences that are kept in the fence pool of the device, waiting to be
+    /// reused.
+    ///
+    /// The futures of vulkano take their fences from this pool with `Fence::from_pool`, and so
+    /// can code that submits its own command buffers. A pooled fence goes back to the pool when
+    /// it is dropped, so the pool grows to the largest number of fences that were alive at the
+    /// same time.
+    #[inline]
+    pub fn fence_pool_len(&self) -> usize {
+        self.fence_pool.lock().unwrap().len()
+    }
+
+    /// Destroys the fences of the fence pool of the device, except for `keep` of them.
+    ///
+    /// Call this after a burst of submissions in order to release the fences that aren't
+    /// needed anymore.
+    pub fn trim_fence_pool(&self, keep: usize) {
+        let mut pool = self.fence_pool.lock().unwrap();
+        if pool.len() <= keep {
+            return;
+        }
+
+        for raw_fence in pool.drain(keep ..) {
+            unsafe {
+                self.vk.DestroyFence(self.device, raw_fence, self.allocator_ptr());
+            }
+        }
+    }
+
+    /// Releases the resources held by the futures that were registered with
+    /// `FenceSignalFuture::enable_collection` and whose fence has been signaled.
+    ///
+    /// This is the equivalent of calling `cleanup_finished()` on all these futures. It never
+    /// blocks, and can be called regularly, for example once per frame or from a background
+    /// thread, so that the resources of the finished submissions are released even if the
+    /// futures themselves are kept alive.
+    pub fn collect_finished(&self) {
+        let futures = {
+            let mut collected = self.collected.lock().unwrap();
+            collected.retain(|future| future.upgrade().is_some());
+            collected.clone()
+        };
+
+        for future in futures {
+            if let Some(future) = future.upgrade() {
+                future.collect_finished();
+            }
+        }
+    }
+
+    /// Registers an object that `collect_finished` must clean up, until it is dropped.
+    #[inline]
+    pub(crate) fn register_collection(&self, object: Weak<CollectFinished>) {
+        self.collected.lock().unwrap().push(object);
+    }
+
+    /// Returns the number of binary semaphores that are kept in the semaphore pool of the
+    /// device, waiting to be reused.
+    ///
+    /// The futures of vulkano take their semaphores from this pool with `Semaphore::from_pool`.
+    /// A pooled semaphore goes back to the pool when it is dropped, unless it was left signaled.
+    #[inline]
+    pub fn semaphore_pool_len(&self) -> usize {
+        self.semaphore_pool.lock().unwrap().len()
+    }
+
+    /// Destroys the semaphores of the semaphore pool of the device, except for `keep` of them.
+    pub fn trim_semaphore_pool(&self, keep: usize) {
+        let mut pool = self.semaphore_pool.lock().unwrap();
+        if pool.len() <= keep {
+            return;
+        }
+
+        for raw_sem in pool.drain(keep ..) {
+            unsafe {
+                self.vk.DestroySemaphore(self.device, raw_sem, self.allocator_ptr());
+            }
+        }
+    }
+
     /// Assigns a human-readable name to `object` for debugging purposes.
     ///
     /// # Panics
@@ -494,8 +1198,104 @@ impl Device {
             name: name.as_ptr(),
         };
         check_errors(self.vk.DebugMarkerSetObjectNameEXT(self.device, &info))?;
+        self.record_debug_name(object_type_from_debug_report(ty), object, name);
         Ok(())
     }
+
+    /// Assigns a human-readable name to `object` with the `VK_EXT_debug_utils` extension.
+    ///
+    /// The name is displayed by graphics debuggers and is used in the messages of the validation
+    /// layers. See also the `DebugName` trait.
+    ///
+    /// # Panics
+    /// * If the `VK_EXT_debug_utils` instance extension is not loaded.
+    /// * If `object` is not owned by this device.
+    pub fn set_debug_utils_object_name<T>(&self, object: &T, name: &CStr) -> Result<(), OomError>
+        where T: ?Sized + VulkanObject + DeviceOwned
+    {
+        assert!(object.device().internal_object() == self.internal_object());
+        unsafe {
+            self.set_debug_utils_object_name_raw(object_type_from_debug_report(T::TYPE),
+                                                 object.internal_object().value(),
+                                                 name)
+        }
+    }
+
+    /// Assigns a human-readable name to `object` with the `VK_EXT_debug_utils` extension.
+    ///
+    /// # Panics
+    /// * If the `VK_EXT_debug_utils` instance extension is not loaded.
+    ///
+    /// # Safety
+    /// `object` must be a Vulkan handle owned by this device, and its type must be accurately
+    /// described by `ty`.
+    pub unsafe fn set_debug_utils_object_name_raw(&self, ty: vk::ObjectType, object: u64,
+                                                  name: &CStr)
+                                                  -> Result<(), OomError> {
+        assert!(self.instance.loaded_extensions().ext_debug_utils,
+                "the VK_EXT_debug_utils extension is not loaded");
+
+        let info = vk::DebugUtilsObjectNameInfoEXT {
+            sType: vk::STRUCTURE_TYPE_DEBUG_UTILS_OBJECT_NAME_INFO_EXT,
+            pNext: ptr::null(),
+            objectType: ty,
+            objectHandle: object,
+            pObjectName: name.as_ptr(),
+        };
+        check_errors(self.vk.SetDebugUtilsObjectNameEXT(self.device, &info))?;
+        self.record_debug_name(ty, object, name);
+        Ok(())
+    }
+
+    // Remembers the name of an object so that it can be attached to the spans of the `tracing`
+    // feature. Does nothing if the feature is disabled.
+    #[inline]
+    fn record_debug_name(&self, ty: vk::ObjectType, object: u64, name: &CStr) {
+        #[cfg(feature = "tracing")]
+        {
+            let name = name.to_string_lossy().into_owned();
+            self.debug_names.lock().unwrap().insert((ty, object), name);
+        }
+    }
+
+    // Forgets the name of an object that is being destroyed, as its handle can be reused by
+    // another object. Does nothing if the `tracing` feature is disabled.
+    #[inline]
+    pub(crate) fn forget_debug_name(&self, ty: vk::ObjectType, object: u64) {
+        #[cfg(feature = "tracing")]
+        {
+            self.debug_names.lock().unwrap().remove(&(ty, object));
+        }
+    }
+
+    // Returns the name that was given to an object, if any.
+    #[cfg(feature = "tracing")]
+    pub(crate) fn debug_name(&self, ty: vk::ObjectType, object: u64) -> Option<String> {
+        self.debug_names.lock().unwrap().get(&(ty, object)).cloned()
+    }
+}
+
+// Converts the value of `VulkanObject::TYPE` to the object type used by `VK_EXT_debug_utils`.
+// Both enumerations only share their values for the object types of the core specification.
+fn object_type_from_debug_report(ty: vk::DebugReportObjectTypeEXT) -> vk::ObjectType {
+    match ty {
+        vk::DEBUG_REPORT_OBJECT_TYPE_SURFACE_KHR_EXT => vk::OBJECT_TYPE_SURFACE_KHR,
+        vk::DEBUG_REPORT_OBJECT_TYPE_SWAPCHAIN_KHR_EXT => vk::OBJECT_TYPE_SWAPCHAIN_KHR,
+        vk::DEBUG_REPORT_OBJECT_TYPE_DEBUG_REPORT_CALLBACK_EXT_EXT => {
+            vk::OBJECT_TYPE_DEBUG_REPORT_CALLBACK_EXT
+        },
+        vk::DEBUG_REPORT_OBJECT_TYPE_DISPLAY_KHR_EXT => vk::OBJECT_TYPE_DISPLAY_KHR,
+        vk::DEBUG_REPORT_OBJECT_TYPE_DISPLAY_MODE_KHR_EXT => vk::OBJECT_TYPE_DISPLAY_MODE_KHR,
+        vk::DEBUG_REPORT_OBJECT_TYPE_VALIDATION_CACHE_EXT => vk::OBJECT_TYPE_VALIDATION_CACHE_EXT,
+        vk::DEBUG_REPORT_OBJECT_TYPE_DESCRIPTOR_UPDATE_TEMPLATE_KHR_EXT => {
+            vk::OBJECT_TYPE_DESCRIPTOR_UPDATE_TEMPLATE_KHR
+        },
+        vk::DEBUG_REPORT_OBJECT_TYPE_ACCELERATION_STRUCTURE_KHR_EXT => {
+            vk::OBJECT_TYPE_ACCELERATION_STRUCTURE_KHR
+        },
+        ty if ty <= vk::DEBUG_REPORT_OBJECT_TYPE_COMMAND_POOL_EXT => ty,
+        _ => vk::OBJECT_TYPE_UNKNOWN,
+    }
 }
 
 impl fmt::Debug for Device {
@@ -520,20 +1320,28 @@ impl Drop for Device {
     #[inline]
     fn drop(&mut self) {
         unsafe {
+            let allocator = self.allocator_ptr();
             for &raw_fence in self.fence_pool.lock().unwrap().iter() {
-                self.vk.DestroyFence(self.device, raw_fence, ptr::null());
+                self.vk.DestroyFence(self.device, raw_fence, allocator);
             }
             for &raw_sem in self.semaphore_pool.lock().unwrap().iter() {
-                self.vk.DestroySemaphore(self.device, raw_sem, ptr::null());
+                self.vk.DestroySemaphore(self.device, raw_sem, allocator);
             }
             for &raw_event in self.event_pool.lock().unwrap().iter() {
-                self.vk.DestroyEvent(self.device, raw_event, ptr::null());
+                self.vk.DestroyEvent(self.device, raw_event, allocator);
             }
-            self.vk.DestroyDevice(self.device, ptr::null());
+            self.vk.DestroyDevice(self.device, allocator);
         }
     }
 }
 
+/// Implemented on the objects that `Device::collect_finished` can clean up.
+pub(crate) trait CollectFinished: Send + Sync {
+    /// Releases the resources held by the object if the GPU has finished using them. Must not
+    /// block.
+    fn collect_finished(&self);
+}
+
 /// Implemented on objects that belong to a Vulkan device.
 ///
 /// # Safety
@@ -555,11 +1363,109 @@ unsafe impl<T> DeviceOwned for T
     }
 }
 
+/// Objects that can be given a debug name.
+///
+/// This trait is implemented on all the objects that own a Vulkan handle created from a device,
+/// such as buffers, images, pipelines, samplers, etc.
+///
+/// # Example
+///
+/// ```no_run
+/// # use std::ffi::CString;
+/// # use std::sync::Arc;
+/// # use vulkano::buffer::sys::UnsafeBuffer;
+/// use vulkano::device::DebugName;
+///
+/// # let buffer: Arc<UnsafeBuffer> = return;
+/// buffer.set_debug_name(&CString::new("vertex buffer").unwrap()).unwrap();
+/// ```
+pub trait DebugName {
+    /// Assigns a human-readable name to the object with the `VK_EXT_debug_utils` extension.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the `VK_EXT_debug_utils` instance extension is not loaded.
+    ///
+    fn set_debug_name(&self, name: &CStr) -> Result<(), OomError>;
+}
+
+impl<T> DebugName for T
+    where T: ?Sized + VulkanObject + DeviceOwned
+{
+    #[inline]
+    fn set_debug_name(&self, name: &CStr) -> Result<(), OomError> {
+        self.device().set_debug_utils_object_name(self, name)
+    }
+}
+
+/// How a physical device of a device group can access memory allocated for another physical
+/// device of the group.
+///
+/// See `Device::peer_memory_features`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct PeerMemoryFeatures {
+    /// The memory can be the source of copy commands.
+    pub copy_src: bool,
+    /// The memory can be the destination of copy commands.
+    pub copy_dst: bool,
+    /// The memory can be read by any kind of access.
+    pub generic_src: bool,
+    /// The memory can be written by any kind of access.
+    pub generic_dst: bool,
+}
+
+/// A queue to create alongside a device.
+///
+/// See `Device::new`. A `(QueueFamily, f32)` tuple can be used instead for a queue that isn't
+/// protected.
+#[derive(Debug, Copy, Clone)]
+pub struct QueueCreateInfo<'a> {
+    /// The family the queue belongs to.
+    pub family: QueueFamily<'a>,
+    /// The priority of the queue, between 0.0 and 1.0.
+    pub priority: f32,
+    /// If true, the queue is a protected queue. Requires the family to support protected queues
+    /// and the physical device to support the `protectedMemory` feature of Vulkan 1.1.
+    pub protected: bool,
+}
+
+impl<'a> QueueCreateInfo<'a> {
+    /// Builds a `QueueCreateInfo` for a queue that isn't protected.
+    #[inline]
+    pub fn new(family: QueueFamily<'a>, priority: f32) -> QueueCreateInfo<'a> {
+        QueueCreateInfo {
+            family: family,
+            priority: priority,
+            protected: false,
+        }
+    }
+
+    /// Builds a `QueueCreateInfo` for a protected queue.
+    #[inline]
+    pub fn protected(family: QueueFamily<'a>, priority: f32) -> QueueCreateInfo<'a> {
+        QueueCreateInfo {
+            family: family,
+            priority: priority,
+            protected: true,
+        }
+    }
+}
+
+impl<'a> From<(QueueFamily<'a>, f32)> for QueueCreateInfo<'a> {
+    #[inline]
+    fn from((family, priority): (QueueFamily<'a>, f32)) -> QueueCreateInfo<'a> {
+        QueueCreateInfo::new(family, priority)
+    }
+}
+
 /// Iterator that returns the queues produced when creating a device.
+///
+/// The queues are returned in the order they were requested. The iterator can be collected into
+/// a `Queues` to look them up by family and index instead.
 pub struct QueuesIter {
     next_queue: usize,
     device: Arc<Device>,
-    families_and_ids: SmallVec<[(u32, u32); 8]>,
+    families_and_ids: SmallVec<[(u32, u32, bool); 8]>,
 }
 
 unsafe impl DeviceOwned for QueuesIter {
@@ -573,23 +1479,38 @@ impl Iterator for QueuesIter {
 
     fn next(&mut self) -> Option<Arc<Queue>> {
         unsafe {
-            let &(family, id) = match self.families_and_ids.get(self.next_queue) {
+            let &(family, id, protected) = match self.families_and_ids.get(self.next_queue) {
                 Some(a) => a,
                 None => return None,
             };
 
             self.next_queue += 1;
 
+            // Protected queues can only be retrieved with `vkGetDeviceQueue2`.
             let mut output = mem::uninitialized();
-            self.device
-                .vk
-                .GetDeviceQueue(self.device.device, family, id, &mut output);
+            if protected {
+                let infos = vk::DeviceQueueInfo2 {
+                    sType: vk::STRUCTURE_TYPE_DEVICE_QUEUE_INFO_2,
+                    pNext: ptr::null(),
+                    flags: vk::DEVICE_QUEUE_CREATE_PROTECTED_BIT,
+                    queueFamilyIndex: family,
+                    queueIndex: id,
+                };
+                self.device
+                    .vk
+                    .GetDeviceQueue2(self.device.device, &infos, &mut output);
+            } else {
+                self.device
+                    .vk
+                    .GetDeviceQueue(self.device.device, family, id, &mut output);
+            }
 
             Some(Arc::new(Queue {
                               queue: Mutex::new(output),
                               device: self.device.clone(),
                               family: family,
                               id: id,
+                              protected: protected,
                           }))
         }
     }
@@ -604,6 +1525,74 @@ impl Iterator for QueuesIter {
 impl ExactSizeIterator for QueuesIter {
 }
 
+/// The queues produced when creating a device, indexed by family and by index within the family.
+///
+/// Built by collecting a `QueuesIter`.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::device::Queues;
+///
+/// # let physical_device: vulkano::instance::PhysicalDevice = return;
+/// # let (device, queues): (std::sync::Arc<vulkano::device::Device>,
+/// #                        vulkano::device::QueuesIter) = return;
+/// let family = physical_device.queue_families().next().unwrap();
+/// let queues = queues.collect::<Queues>();
+/// let queue = queues.get(family, 0).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Queues {
+    queues: Vec<Arc<Queue>>,
+}
+
+impl Queues {
+    /// Returns the queue of `family` with the given index within the family, or `None` if no
+    /// such queue was created. Protected queues aren't returned.
+    #[inline]
+    pub fn get(&self, family: QueueFamily, id_within_family: u32) -> Option<&Arc<Queue>> {
+        self.find(family, id_within_family, false)
+    }
+
+    /// Same as `get`, but only returns protected queues. The protected queues of a family are
+    /// indexed separately from its other queues.
+    #[inline]
+    pub fn get_protected(&self, family: QueueFamily, id_within_family: u32)
+                         -> Option<&Arc<Queue>> {
+        self.find(family, id_within_family, true)
+    }
+
+    fn find(&self, family: QueueFamily, id_within_family: u32, protected: bool)
+            -> Option<&Arc<Queue>> {
+        self.queues.iter().find(|q| {
+            q.family == family.id() && q.id == id_within_family && q.protected == protected &&
+                q.device.physical_device().internal_object() ==
+                    family.physical_device().internal_object()
+        })
+    }
+
+    /// Returns the number of queues.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.queues.len()
+    }
+
+    /// Returns an iterator to all the queues, in the order they were requested.
+    #[inline]
+    pub fn iter(&self) -> slice::Iter<Arc<Queue>> {
+        self.queues.iter()
+    }
+}
+
+impl FromIterator<Arc<Queue>> for Queues {
+    #[inline]
+    fn from_iter<I>(iter: I) -> Queues
+        where I: IntoIterator<Item = Arc<Queue>>
+    {
+        Queues { queues: iter.into_iter().collect() }
+    }
+}
+
 /// Error that can be returned when creating a device.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DeviceCreationError {
@@ -622,6 +1611,11 @@ pub enum DeviceCreationError {
     TooManyQueuesForFamily,
     /// The priority of one of the queues is out of the [0.0; 1.0] range.
     PriorityOutOfRange,
+    /// A protected queue was requested, but its family doesn't support protected queues or the
+    /// physical device doesn't support the `protectedMemory` feature.
+    ProtectedMemoryNotSupported,
+    /// None of the queue families of the physical device supports compute operations.
+    ComputeNotSupported,
     /// There is no memory available on the host (ie. the CPU, RAM, etc.).
     OutOfHostMemory,
     /// There is no memory available on the device (ie. video memory).
@@ -651,6 +1645,12 @@ impl error::Error for DeviceCreationError {
             DeviceCreationError::PriorityOutOfRange => {
                 "the priority of one of the queues is out of the [0.0; 1.0] range"
             },
+            DeviceCreationError::ProtectedMemoryNotSupported => {
+                "a protected queue was requested, but the physical device doesn't support it"
+            },
+            DeviceCreationError::ComputeNotSupported => {
+                "none of the queue families of the physical device supports compute operations"
+            },
             DeviceCreationError::ExtensionNotPresent => {
                 "some of the requested device extensions are not supported by the physical device"
             },
@@ -685,6 +1685,62 @@ impl From<Error> for DeviceCreationError {
     }
 }
 
+/// Error that can be returned when waiting for a device or a queue to be idle.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WaitIdleError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The device has been lost.
+    DeviceLost,
+}
+
+impl error::Error for WaitIdleError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            WaitIdleError::OomError(_) => "not enough memory",
+            WaitIdleError::DeviceLost => "the device was lost",
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            WaitIdleError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for WaitIdleError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<Error> for WaitIdleError {
+    #[inline]
+    fn from(err: Error) -> WaitIdleError {
+        match err {
+            err @ Error::OutOfHostMemory => WaitIdleError::OomError(err.into()),
+            err @ Error::OutOfDeviceMemory => WaitIdleError::OomError(err.into()),
+            Error::DeviceLost => WaitIdleError::DeviceLost,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+/// Last checkpoint that a queue reached in a pipeline stage, as returned by
+/// `Queue::checkpoints`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// The pipeline stage.
+    pub stage: PipelineStages,
+    /// The marker that was passed to `set_checkpoint`.
+    pub marker: usize,
+}
+
 /// Represents a queue where commands can be submitted.
 // TODO: should use internal synchronization?
 #[derive(Debug)]
@@ -693,9 +1749,38 @@ pub struct Queue {
     device: Arc<Device>,
     family: u32,
     id: u32, // id within family
+    protected: bool,
 }
 
 impl Queue {
+    /// Builds a `Queue` from a raw queue handle, for example the queue that an OpenXR session
+    /// was created with.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be the queue with index `id` of `family` in `device`, and must not be a
+    ///   protected queue.
+    /// - No other `Queue` must exist for the same handle, since vulkano synchronizes the accesses
+    ///   to a queue through its `Queue` object.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `family` doesn't belong to the physical device of `device`.
+    ///
+    pub unsafe fn from_raw(device: Arc<Device>, handle: u64, family: QueueFamily, id: u32)
+                           -> Arc<Queue> {
+        assert_eq!(family.physical_device().internal_object(),
+                   device.physical_device().internal_object());
+
+        Arc::new(Queue {
+                     queue: Mutex::new(handle as vk::Queue),
+                     family: family.id(),
+                     device: device,
+                     id: id,
+                     protected: false,
+                 })
+    }
+
     /// Returns the device this queue belongs to.
     #[inline]
     pub fn device(&self) -> &Arc<Device> {
@@ -705,7 +1790,7 @@ impl Queue {
     /// Returns true if this is the same queue as another one.
     #[inline]
     pub fn is_same(&self, other: &Queue) -> bool {
-        self.id == other.id && self.family == other.family &&
+        self.id == other.id && self.family == other.family && self.protected == other.protected &&
             self.device.internal_object() == other.device.internal_object()
     }
 
@@ -719,23 +1804,147 @@ impl Queue {
     }
 
     /// Returns the index of this queue within its family.
+    ///
+    /// The protected queues of a family are indexed separately from its other queues.
     #[inline]
     pub fn id_within_family(&self) -> u32 {
         self.id
     }
 
+    /// Returns true if this is a protected queue.
+    #[inline]
+    pub fn is_protected(&self) -> bool {
+        self.protected
+    }
+
     /// Waits until all work on this queue has finished.
     ///
     /// Just like `Device::wait()`, you shouldn't have to call this function in a typical program.
     #[inline]
-    pub fn wait(&self) -> Result<(), OomError> {
+    pub fn wait(&self) -> Result<(), WaitIdleError> {
         unsafe {
             let vk = self.device.pointers();
             let queue = self.queue.lock().unwrap();
-            check_errors(vk.QueueWaitIdle(*queue))?;
+            let _span = instrument_span!("vkQueueWaitIdle",
+                                         queue = ::instrument::queue_name(&self.device, *queue));
+            self.device.check_lost(check_errors(vk.QueueWaitIdle(*queue)))?;
             Ok(())
         }
     }
+
+    /// Returns the last checkpoint that the queue reached in each pipeline stage.
+    ///
+    /// Checkpoints are set in command buffers with `AutoCommandBufferBuilder::set_checkpoint`.
+    /// After a device loss, the returned checkpoints tell which commands were being executed.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the `VK_NV_device_diagnostic_checkpoints` extension is not loaded.
+    ///
+    pub fn checkpoints(&self) -> Vec<Checkpoint> {
+        assert!(self.device.loaded_extensions().nv_device_diagnostic_checkpoints,
+                "the VK_NV_device_diagnostic_checkpoints extension is not loaded");
+
+        unsafe {
+            let vk = self.device.pointers();
+            let queue = self.queue.lock().unwrap();
+
+            let mut num = 0;
+            vk.GetQueueCheckpointDataNV(*queue, &mut num, ptr::null_mut());
+
+            let mut data = (0 .. num)
+                .map(|_| {
+                         vk::CheckpointDataNV {
+                             sType: vk::STRUCTURE_TYPE_CHECKPOINT_DATA_NV,
+                             pNext: ptr::null_mut(),
+                             stage: 0,
+                             pCheckpointMarker: ptr::null_mut(),
+                         }
+                     })
+                .collect::<Vec<_>>();
+            vk.GetQueueCheckpointDataNV(*queue, &mut num, data.as_mut_ptr());
+            data.truncate(num as usize);
+
+            data.into_iter()
+                .map(|d| {
+                         Checkpoint {
+                             stage: PipelineStages::from_vulkan_bits(d.stage),
+                             marker: d.pCheckpointMarker as usize,
+                         }
+                     })
+                .collect()
+        }
+    }
+
+    /// Opens a debug label region on this queue. All the work submitted to the queue until the
+    /// matching call to `end_debug_label` is grouped under this label by debugging tools.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the `VK_EXT_debug_utils` instance extension is not loaded.
+    ///
+    #[inline]
+    pub fn begin_debug_label(&self, name: &CStr, color: [f32; 4]) {
+        assert!(self.device.instance().loaded_extensions().ext_debug_utils,
+                "the VK_EXT_debug_utils extension is not loaded");
+
+        unsafe {
+            let vk = self.device.pointers();
+            let queue = self.queue.lock().unwrap();
+            let info = vk::DebugUtilsLabelEXT {
+                sType: vk::STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT,
+                pNext: ptr::null(),
+                pLabelName: name.as_ptr(),
+                color,
+            };
+            vk.QueueBeginDebugUtilsLabelEXT(*queue, &info);
+        }
+    }
+
+    /// Closes the debug label region that was last opened with `begin_debug_label`.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the `VK_EXT_debug_utils` instance extension is not loaded.
+    ///
+    /// # Safety
+    ///
+    /// A debug label region must have been opened on this queue with `begin_debug_label` and not
+    /// closed yet.
+    ///
+    #[inline]
+    pub unsafe fn end_debug_label(&self) {
+        assert!(self.device.instance().loaded_extensions().ext_debug_utils,
+                "the VK_EXT_debug_utils extension is not loaded");
+
+        let vk = self.device.pointers();
+        let queue = self.queue.lock().unwrap();
+        vk.QueueEndDebugUtilsLabelEXT(*queue);
+    }
+
+    /// Inserts a single debug label on this queue.
+    ///
+    /// # Panics
+    ///
+    /// - Panics if the `VK_EXT_debug_utils` instance extension is not loaded.
+    ///
+    #[inline]
+    pub fn insert_debug_label(&self, name: &CStr, color: [f32; 4]) {
+        assert!(self.device.instance().loaded_extensions().ext_debug_utils,
+                "the VK_EXT_debug_utils extension is not loaded");
+
+        unsafe {
+            let vk = self.device.pointers();
+            let queue = self.queue.lock().unwrap();
+            let info = vk::DebugUtilsLabelEXT {
+                sType: vk::STRUCTURE_TYPE_DEBUG_UTILS_LABEL_EXT,
+                pNext: ptr::null(),
+                pLabelName: name.as_ptr(),
+                color,
+            };
+            vk.QueueInsertDebugUtilsLabelEXT(*queue, &info);
+        }
+    }
 }
 
 
@@ -759,8 +1968,14 @@ mod tests {
     use device::Device;
     use device::DeviceCreationError;
     use device::DeviceExtensions;
+    use device::QueueCreateInfo;
+    use device::Queue;
+    use device::Queues;
+    use SynchronizedVulkanObject;
+    use VulkanObject;
     use features::Features;
     use instance;
+    use std::mem;
     use std::sync::Arc;
 
     #[test]
@@ -769,6 +1984,79 @@ mod tests {
         assert!(Arc::get_mut(&mut device).is_some());
     }
 
+    #[test]
+    fn portability_subset_always_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let supported = DeviceExtensions::supported_by_device(device.physical_device());
+        assert_eq!(device.loaded_extensions().khr_portability_subset,
+                   supported.khr_portability_subset);
+    }
+
+    #[test]
+    fn single_physical_device() {
+        let (device, _) = gfx_dev_and_queue!();
+        assert_eq!(device.physical_device_count(), 1);
+        assert_eq!(device.physical_devices().next().unwrap().index(),
+                   device.physical_device().index());
+    }
+
+    #[test]
+    fn new_compute() {
+        let instance = instance!();
+        let physical = match instance::PhysicalDevice::enumerate(&instance).next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let (device, queue) = match Device::new_compute(physical) {
+            Ok(r) => r,
+            Err(DeviceCreationError::ComputeNotSupported) => return,
+            Err(err) => panic!("{:?}", err),
+        };
+        assert!(queue.family().supports_compute());
+        assert_eq!(queue.device().internal_object(), device.internal_object());
+    }
+
+    #[test]
+    fn from_raw() {
+        let instance = instance!();
+        let physical = match instance::PhysicalDevice::enumerate(&instance).next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let family = physical.queue_families().next().unwrap();
+        let (device, _) = match Device::new(physical,
+                                            &Features::none(),
+                                            &DeviceExtensions::none(),
+                                            Some((family, 1.0))) {
+            Ok(r) => r,
+            Err(_) => return,
+        };
+
+        // The original device must not destroy the handle.
+        let handle = device.as_raw();
+        let features = device.enabled_features().clone();
+        mem::forget(device);
+
+        let (device, mut queues) = unsafe {
+            Device::from_raw(physical,
+                             handle,
+                             &features,
+                             &DeviceExtensions::none(),
+                             Some((family, 1.0)))
+        };
+        assert_eq!(device.as_raw(), handle);
+        assert_eq!(device.enabled_features(), &features);
+
+        let queue = queues.next().unwrap();
+        assert_eq!(queue.family().id(), family.id());
+        assert!(queues.next().is_none());
+
+        let same_queue = unsafe { Queue::from_raw(device.clone(), queue.as_raw(), family, 0) };
+        assert!(same_queue.is_same(&queue));
+    }
+
     #[test]
     fn too_many_queues() {
         let instance = instance!();
@@ -789,6 +2077,32 @@ mod tests {
         };
     }
 
+    #[test]
+    fn queues_by_family() {
+        let instance = instance!();
+        let physical = match instance::PhysicalDevice::enumerate(&instance).next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let family = physical.queue_families().next().unwrap();
+        let (_, queues) = match Device::new(physical,
+                                            &Features::none(),
+                                            &DeviceExtensions::none(),
+                                            Some(QueueCreateInfo::new(family, 0.5))) {
+            Ok(d) => d,
+            Err(_) => return,
+        };
+
+        let queues = queues.collect::<Queues>();
+        assert_eq!(queues.len(), 1);
+        let queue = queues.get(family, 0).unwrap();
+        assert_eq!(queue.family().id(), family.id());
+        assert!(!queue.is_protected());
+        assert!(queues.get(family, 1).is_none());
+        assert!(queues.get_protected(family, 0).is_none());
+    }
+
     #[test]
     fn unsupposed_features() {
         let instance = instance!();
@@ -814,6 +2128,93 @@ mod tests {
         };
     }
 
+    #[test]
+    fn extension_feature_without_extension() {
+        let extensions = instance::InstanceExtensions {
+            khr_get_physical_device_properties2: true,
+            ..instance::InstanceExtensions::none()
+        };
+        let instance = match instance::Instance::new(None, &extensions, None) {
+            Ok(i) => i,
+            Err(_) => return,
+        };
+        let physical = match instance::PhysicalDevice::enumerate(&instance).next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let family = physical.queue_families().next().unwrap();
+
+        let features = Features {
+            timeline_semaphore: true,
+            ..Features::none()
+        };
+        if !physical.supported_features().superset_of(&features) {
+            return;
+        }
+
+        // The feature is ignored, as `VK_KHR_timeline_semaphore` isn't loaded.
+        let (device, _) = match Device::new(physical,
+                                            &features,
+                                            &DeviceExtensions::none(),
+                                            Some((family, 1.0))) {
+            Ok(r) => r,
+            Err(_) => return,
+        };
+        if !device.loaded_extensions().khr_timeline_semaphore {
+            assert!(!device.enabled_features().timeline_semaphore);
+        }
+    }
+
+    #[test]
+    fn all_supported_features() {
+        let extensions = instance::InstanceExtensions {
+            khr_get_physical_device_properties2: true,
+            ..instance::InstanceExtensions::none()
+        };
+        let instance = match instance::Instance::new(None, &extensions, None) {
+            Ok(i) => i,
+            Err(_) => return,
+        };
+        let physical = match instance::PhysicalDevice::enumerate(&instance).next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let family = physical.queue_families().next().unwrap();
+
+        // Requesting everything that is supported works even though the features of the device
+        // extensions are included.
+        let (device, _) = Device::new(physical,
+                                      physical.supported_features(),
+                                      &DeviceExtensions::none(),
+                                      Some((family, 1.0)))
+            .unwrap();
+
+        let loaded = device.loaded_extensions();
+        let features = device.enabled_features();
+        assert_eq!(features.geometry_shader, physical.supported_features().geometry_shader);
+        assert_eq!(features.timeline_semaphore,
+                   loaded.khr_timeline_semaphore &&
+                       physical.supported_features().timeline_semaphore);
+        assert!(!features.mesh_shader);
+
+        // The features of an extension are only enabled if they are requested.
+        if !DeviceExtensions::supported_by_device(physical).khr_timeline_semaphore {
+            return;
+        }
+        let extensions = DeviceExtensions {
+            khr_timeline_semaphore: true,
+            ..DeviceExtensions::none()
+        };
+        let (device, _) = Device::new(physical,
+                                      &Features::none(),
+                                      &extensions,
+                                      Some((family, 1.0)))
+            .unwrap();
+        assert!(!device.enabled_features().timeline_semaphore);
+    }
+
     #[test]
     fn priority_out_of_range() {
         let instance = instance!();
@@ -840,4 +2241,29 @@ mod tests {
             _ => panic!(),
         };
     }
+
+    #[test]
+    fn not_lost_after_wait() {
+        let (device, queue) = gfx_dev_and_queue!();
+        queue.wait().unwrap();
+        assert!(!device.is_lost());
+    }
+
+    #[test]
+    #[cfg(feature = "tracing")]
+    fn debug_names() {
+        use std::ffi::CString;
+        use vk;
+
+        let (device, _) = gfx_dev_and_queue!();
+        let name = CString::new("shadow pass fence").unwrap();
+
+        device.record_debug_name(vk::OBJECT_TYPE_FENCE, 1, &name);
+        assert_eq!(device.debug_name(vk::OBJECT_TYPE_FENCE, 1),
+                   Some("shadow pass fence".to_owned()));
+        assert_eq!(device.debug_name(vk::OBJECT_TYPE_SEMAPHORE, 1), None);
+
+        device.forget_debug_name(vk::OBJECT_TYPE_FENCE, 1);
+        assert_eq!(device.debug_name(vk::OBJECT_TYPE_FENCE, 1), None);
+    }
 }
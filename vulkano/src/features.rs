@@ -7,16 +7,38 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+
+use instance::DeviceExtensions;
 use vk;
 
 macro_rules! features {
-    ($($name:ident => $vk:ident,)+) => (
+    (
+        core {
+            $($name:ident => $vk:ident,)+
+        }
+        $(
+            $ext_struct:ident: $ext_ty:ident, $ext_sty:ident, $ext:ident {
+                $($ext_name:ident => $ext_vk:ident,)+
+            }
+        )+
+    ) => (
         /// Represents all the features that are available on a physical device or enabled on
         /// a logical device.
         ///
         /// Note that the `robust_buffer_access` is guaranteed to be supported by all Vulkan
         /// implementations.
         ///
+        /// Besides the core features of Vulkan, this struct contains the features of the device
+        /// extensions that vulkano supports, such as `timeline_semaphore` or
+        /// `runtime_descriptor_array`. These can only be queried if the instance was created with
+        /// the `khr_get_physical_device_properties2` extension or with Vulkan 1.1 or later, and
+        /// are only enabled if their extension is enabled on the device or was promoted to the
+        /// core API of the device's version. The requested features of the other extensions are
+        /// ignored, so that the whole `supported_features()` of a physical device can be requested.
+        ///
         /// # Example
         ///
         /// ```
@@ -46,6 +68,9 @@ macro_rules! features {
             $(
                 pub $name: bool,
             )+
+            $($(
+                pub $ext_name: bool,
+            )+)+
         }
 
         impl Features {
@@ -55,6 +80,9 @@ macro_rules! features {
                     $(
                         $name: false,
                     )+
+                    $($(
+                        $ext_name: false,
+                    )+)+
                 }
             }
 
@@ -67,6 +95,9 @@ macro_rules! features {
                     $(
                         $name: true,
                     )+
+                    $($(
+                        $ext_name: true,
+                    )+)+
                 }
             }
 
@@ -75,7 +106,8 @@ macro_rules! features {
             /// That is, for each feature of the parameter that is true, the corresponding value
             /// in self is true as well.
             pub fn superset_of(&self, other: &Features) -> bool {
-                $((self.$name == true || other.$name == false))&&+
+                $((self.$name == true || other.$name == false))&&+ &&
+                $($((self.$ext_name == true || other.$ext_name == false))&&+)&&+
             }
 
             /// Builds a `Features` that is the intersection of `self` and another `Features`
@@ -87,6 +119,9 @@ macro_rules! features {
                     $(
                         $name: self.$name && other.$name,
                     )+
+                    $($(
+                        $ext_name: self.$ext_name && other.$ext_name,
+                    )+)+
                 }
             }
 
@@ -98,6 +133,36 @@ macro_rules! features {
                     $(
                         $name: self.$name && !other.$name,
                     )+
+                    $($(
+                        $ext_name: self.$ext_name && !other.$ext_name,
+                    )+)+
+                }
+            }
+
+            /// Builds a `Features` that is the union of `self` and another `Features` object.
+            ///
+            /// The result's field will be true if it is true in `self` or in `other`.
+            pub fn union(&self, other: &Features) -> Features {
+                Features {
+                    $(
+                        $name: self.$name || other.$name,
+                    )+
+                    $($(
+                        $ext_name: self.$ext_name || other.$ext_name,
+                    )+)+
+                }
+            }
+
+            /// Builds a copy of `self` without the features of the device extensions that aren't
+            /// in `extensions`.
+            pub(crate) fn restricted_to(&self, extensions: &DeviceExtensions) -> Features {
+                Features {
+                    $(
+                        $name: self.$name,
+                    )+
+                    $($(
+                        $ext_name: self.$ext_name && extensions.$ext,
+                    )+)+
                 }
             }
 
@@ -106,6 +171,9 @@ macro_rules! features {
                     $(
                         $name: features.$vk != 0,
                     )+
+                    $($(
+                        $ext_name: false,
+                    )+)+
                 }
             }
 
@@ -116,64 +184,315 @@ macro_rules! features {
                     )+
                 }
             }
+
+            pub(crate) fn from_ffi(ffi: &FeaturesFfi) -> Features {
+                Features {
+                    $(
+                        $name: ffi.main.features.$vk != 0,
+                    )+
+                    $($(
+                        $ext_name: ffi.$ext_struct.$ext_vk != 0,
+                    )+)+
+                }
+            }
+
+            /// Writes the features into `ffi`. The fields of `ffi` that don't correspond to a
+            /// feature of `self` are left untouched.
+            pub(crate) fn write_ffi(&self, ffi: &mut FeaturesFfi) {
+                $(
+                    ffi.main.features.$vk = if self.$name { vk::TRUE } else { vk::FALSE };
+                )+
+                $($(
+                    ffi.$ext_struct.$ext_vk = if self.$ext_name { vk::TRUE } else { vk::FALSE };
+                )+)+
+            }
+        }
+
+        /// The `VkPhysicalDeviceFeatures2KHR` struct and the features structs of the extensions,
+        /// to be chained together.
+        ///
+        /// Always boxed, so that the pointers of the chain stay valid.
+        pub(crate) struct FeaturesFfi {
+            main: vk::PhysicalDeviceFeatures2KHR,
+            $(
+                $ext_struct: vk::$ext_ty,
+            )+
+        }
+
+        impl FeaturesFfi {
+            /// Builds a `FeaturesFfi` with all the features set to false and an empty chain.
+            pub(crate) fn new() -> Box<FeaturesFfi> {
+                let mut ffi: Box<FeaturesFfi> = Box::new(unsafe { mem::zeroed() });
+                ffi.main.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_FEATURES_2_KHR;
+                $(
+                    ffi.$ext_struct.sType = vk::$ext_sty;
+                )+
+                ffi
+            }
+
+            /// Chains the features structs of the extensions of `extensions` after the
+            /// `VkPhysicalDeviceFeatures2KHR` struct.
+            pub(crate) fn make_chain(&mut self, extensions: &DeviceExtensions) {
+                let mut next: *mut c_void = ptr::null_mut();
+                $(
+                    if extensions.$ext {
+                        self.$ext_struct.pNext = next as _;
+                        next = &mut self.$ext_struct as *mut _ as *mut _;
+                    } else {
+                        self.$ext_struct.pNext = ptr::null_mut() as _;
+                    }
+                )+
+                self.main.pNext = next as _;
+            }
+
+            /// Returns the `VkPhysicalDeviceFeatures2KHR` struct at the head of the chain.
+            #[inline]
+            pub(crate) fn head_as_ref(&self) -> &vk::PhysicalDeviceFeatures2KHR {
+                &self.main
+            }
+
+            /// Returns the `VkPhysicalDeviceFeatures2KHR` struct at the head of the chain.
+            #[inline]
+            pub(crate) fn head_as_mut(&mut self) -> &mut vk::PhysicalDeviceFeatures2KHR {
+                &mut self.main
+            }
         }
     )
 }
 
 features!{
-    robust_buffer_access => robustBufferAccess,
-    full_draw_index_uint32 => fullDrawIndexUint32,
-    image_cube_array => imageCubeArray,
-    independent_blend => independentBlend,
-    geometry_shader => geometryShader,
-    tessellation_shader => tessellationShader,
-    sample_rate_shading => sampleRateShading,
-    dual_src_blend => dualSrcBlend,
-    logic_op => logicOp,
-    multi_draw_indirect => multiDrawIndirect,
-    draw_indirect_first_instance => drawIndirectFirstInstance,
-    depth_clamp => depthClamp,
-    depth_bias_clamp => depthBiasClamp,
-    fill_mode_non_solid => fillModeNonSolid,
-    depth_bounds => depthBounds,
-    wide_lines => wideLines,
-    large_points => largePoints,
-    alpha_to_one => alphaToOne,
-    multi_viewport => multiViewport,
-    sampler_anisotropy => samplerAnisotropy,
-    texture_compression_etc2 => textureCompressionETC2,
-    texture_compression_astc_ldr => textureCompressionASTC_LDR,
-    texture_compression_bc => textureCompressionBC,
-    occlusion_query_precise => occlusionQueryPrecise,
-    pipeline_statistics_query => pipelineStatisticsQuery,
-    vertex_pipeline_stores_and_atomics => vertexPipelineStoresAndAtomics,
-    fragment_stores_and_atomics => fragmentStoresAndAtomics,
-    shader_tessellation_and_geometry_point_size => shaderTessellationAndGeometryPointSize,
-    shader_image_gather_extended => shaderImageGatherExtended,
-    shader_storage_image_extended_formats => shaderStorageImageExtendedFormats,
-    shader_storage_image_multisample => shaderStorageImageMultisample,
-    shader_storage_image_read_without_format => shaderStorageImageReadWithoutFormat,
-    shader_storage_image_write_without_format => shaderStorageImageWriteWithoutFormat,
-    shader_uniform_buffer_array_dynamic_indexing => shaderUniformBufferArrayDynamicIndexing,
-    shader_sampled_image_array_dynamic_indexing => shaderSampledImageArrayDynamicIndexing,
-    shader_storage_buffer_array_dynamic_indexing => shaderStorageBufferArrayDynamicIndexing,
-    shader_storage_image_array_dynamic_indexing => shaderStorageImageArrayDynamicIndexing,
-    shader_clip_distance => shaderClipDistance,
-    shader_cull_distance => shaderCullDistance,
-    shader_f3264 => shaderf3264,
-    shader_int64 => shaderInt64,
-    shader_int16 => shaderInt16,
-    shader_resource_residency => shaderResourceResidency,
-    shader_resource_min_lod => shaderResourceMinLod,
-    sparse_binding => sparseBinding,
-    sparse_residency_buffer => sparseResidencyBuffer,
-    sparse_residency_image2d => sparseResidencyImage2D,
-    sparse_residency_image3d => sparseResidencyImage3D,
-    sparse_residency2_samples => sparseResidency2Samples,
-    sparse_residency4_samples => sparseResidency4Samples,
-    sparse_residency8_samples => sparseResidency8Samples,
-    sparse_residency16_samples => sparseResidency16Samples,
-    sparse_residency_aliased => sparseResidencyAliased,
-    variable_multisample_rate => variableMultisampleRate,
-    inherited_queries => inheritedQueries,
+    core {
+        robust_buffer_access => robustBufferAccess,
+        full_draw_index_uint32 => fullDrawIndexUint32,
+        image_cube_array => imageCubeArray,
+        independent_blend => independentBlend,
+        geometry_shader => geometryShader,
+        tessellation_shader => tessellationShader,
+        sample_rate_shading => sampleRateShading,
+        dual_src_blend => dualSrcBlend,
+        logic_op => logicOp,
+        multi_draw_indirect => multiDrawIndirect,
+        draw_indirect_first_instance => drawIndirectFirstInstance,
+        depth_clamp => depthClamp,
+        depth_bias_clamp => depthBiasClamp,
+        fill_mode_non_solid => fillModeNonSolid,
+        depth_bounds => depthBounds,
+        wide_lines => wideLines,
+        large_points => largePoints,
+        alpha_to_one => alphaToOne,
+        multi_viewport => multiViewport,
+        sampler_anisotropy => samplerAnisotropy,
+        texture_compression_etc2 => textureCompressionETC2,
+        texture_compression_astc_ldr => textureCompressionASTC_LDR,
+        texture_compression_bc => textureCompressionBC,
+        occlusion_query_precise => occlusionQueryPrecise,
+        pipeline_statistics_query => pipelineStatisticsQuery,
+        vertex_pipeline_stores_and_atomics => vertexPipelineStoresAndAtomics,
+        fragment_stores_and_atomics => fragmentStoresAndAtomics,
+        shader_tessellation_and_geometry_point_size => shaderTessellationAndGeometryPointSize,
+        shader_image_gather_extended => shaderImageGatherExtended,
+        shader_storage_image_extended_formats => shaderStorageImageExtendedFormats,
+        shader_storage_image_multisample => shaderStorageImageMultisample,
+        shader_storage_image_read_without_format => shaderStorageImageReadWithoutFormat,
+        shader_storage_image_write_without_format => shaderStorageImageWriteWithoutFormat,
+        shader_uniform_buffer_array_dynamic_indexing => shaderUniformBufferArrayDynamicIndexing,
+        shader_sampled_image_array_dynamic_indexing => shaderSampledImageArrayDynamicIndexing,
+        shader_storage_buffer_array_dynamic_indexing => shaderStorageBufferArrayDynamicIndexing,
+        shader_storage_image_array_dynamic_indexing => shaderStorageImageArrayDynamicIndexing,
+        shader_clip_distance => shaderClipDistance,
+        shader_cull_distance => shaderCullDistance,
+        shader_f3264 => shaderf3264,
+        shader_int64 => shaderInt64,
+        shader_int16 => shaderInt16,
+        shader_resource_residency => shaderResourceResidency,
+        shader_resource_min_lod => shaderResourceMinLod,
+        sparse_binding => sparseBinding,
+        sparse_residency_buffer => sparseResidencyBuffer,
+        sparse_residency_image2d => sparseResidencyImage2D,
+        sparse_residency_image3d => sparseResidencyImage3D,
+        sparse_residency2_samples => sparseResidency2Samples,
+        sparse_residency4_samples => sparseResidency4Samples,
+        sparse_residency8_samples => sparseResidency8Samples,
+        sparse_residency16_samples => sparseResidency16Samples,
+        sparse_residency_aliased => sparseResidencyAliased,
+        variable_multisample_rate => variableMultisampleRate,
+        inherited_queries => inheritedQueries,
+    }
+
+    line_rasterization: PhysicalDeviceLineRasterizationFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_LINE_RASTERIZATION_FEATURES_EXT, ext_line_rasterization {
+        rectangular_lines => rectangularLines,
+        bresenham_lines => bresenhamLines,
+        smooth_lines => smoothLines,
+        stippled_rectangular_lines => stippledRectangularLines,
+        stippled_bresenham_lines => stippledBresenhamLines,
+        stippled_smooth_lines => stippledSmoothLines,
+    }
+
+    vertex_attribute_divisor: PhysicalDeviceVertexAttributeDivisorFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_FEATURES_EXT,
+        ext_vertex_attribute_divisor {
+        vertex_attribute_instance_rate_divisor => vertexAttributeInstanceRateDivisor,
+        vertex_attribute_instance_rate_zero_divisor => vertexAttributeInstanceRateZeroDivisor,
+    }
+
+    graphics_pipeline_library: PhysicalDeviceGraphicsPipelineLibraryFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_GRAPHICS_PIPELINE_LIBRARY_FEATURES_EXT,
+        ext_graphics_pipeline_library {
+        graphics_pipeline_library => graphicsPipelineLibrary,
+    }
+
+    mesh_shader: PhysicalDeviceMeshShaderFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_FEATURES_EXT, ext_mesh_shader {
+        task_shader => taskShader,
+        mesh_shader => meshShader,
+    }
+
+    multi_draw: PhysicalDeviceMultiDrawFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTI_DRAW_FEATURES_EXT, ext_multi_draw {
+        multi_draw => multiDraw,
+    }
+
+    conditional_rendering: PhysicalDeviceConditionalRenderingFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_CONDITIONAL_RENDERING_FEATURES_EXT,
+        ext_conditional_rendering {
+        conditional_rendering => conditionalRendering,
+        inherited_conditional_rendering => inheritedConditionalRendering,
+    }
+
+    transform_feedback: PhysicalDeviceTransformFeedbackFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_TRANSFORM_FEEDBACK_FEATURES_EXT, ext_transform_feedback {
+        transform_feedback => transformFeedback,
+        geometry_streams => geometryStreams,
+    }
+
+    buffer_device_address: PhysicalDeviceBufferDeviceAddressFeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_BUFFER_DEVICE_ADDRESS_FEATURES_KHR,
+        khr_buffer_device_address {
+        buffer_device_address => bufferDeviceAddress,
+    }
+
+    acceleration_structure: PhysicalDeviceAccelerationStructureFeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_ACCELERATION_STRUCTURE_FEATURES_KHR,
+        khr_acceleration_structure {
+        acceleration_structure => accelerationStructure,
+    }
+
+    ray_tracing_pipeline: PhysicalDeviceRayTracingPipelineFeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_RAY_TRACING_PIPELINE_FEATURES_KHR,
+        khr_ray_tracing_pipeline {
+        ray_tracing_pipeline => rayTracingPipeline,
+    }
+
+    subgroup_size_control: PhysicalDeviceSubgroupSizeControlFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_FEATURES_EXT,
+        ext_subgroup_size_control {
+        subgroup_size_control => subgroupSizeControl,
+        compute_full_subgroups => computeFullSubgroups,
+    }
+
+    storage_16bit: PhysicalDevice16BitStorageFeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_16BIT_STORAGE_FEATURES_KHR, khr_16bit_storage {
+        storage_buffer_16bit_access => storageBuffer16BitAccess,
+        uniform_and_storage_buffer_16bit_access => uniformAndStorageBuffer16BitAccess,
+        storage_push_constant16 => storagePushConstant16,
+        storage_input_output16 => storageInputOutput16,
+    }
+
+    storage_8bit: PhysicalDevice8BitStorageFeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_8BIT_STORAGE_FEATURES_KHR, khr_8bit_storage {
+        storage_buffer_8bit_access => storageBuffer8BitAccess,
+        uniform_and_storage_buffer_8bit_access => uniformAndStorageBuffer8BitAccess,
+        storage_push_constant8 => storagePushConstant8,
+    }
+
+    shader_float16_int8: PhysicalDeviceShaderFloat16Int8FeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_SHADER_FLOAT16_INT8_FEATURES_KHR,
+        khr_shader_float16_int8 {
+        shader_float16 => shaderFloat16,
+        shader_int8 => shaderInt8,
+    }
+
+    timeline_semaphore: PhysicalDeviceTimelineSemaphoreFeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_TIMELINE_SEMAPHORE_FEATURES_KHR, khr_timeline_semaphore {
+        timeline_semaphore => timelineSemaphore,
+    }
+
+    synchronization2: PhysicalDeviceSynchronization2FeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_SYNCHRONIZATION_2_FEATURES_KHR, khr_synchronization2 {
+        synchronization2 => synchronization2,
+    }
+
+    extended_dynamic_state: PhysicalDeviceExtendedDynamicStateFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTENDED_DYNAMIC_STATE_FEATURES_EXT,
+        ext_extended_dynamic_state {
+        extended_dynamic_state => extendedDynamicState,
+    }
+
+    robustness2: PhysicalDeviceRobustness2FeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_ROBUSTNESS_2_FEATURES_EXT, ext_robustness2 {
+        robust_buffer_access2 => robustBufferAccess2,
+        robust_image_access2 => robustImageAccess2,
+        null_descriptor => nullDescriptor,
+    }
+
+    portability_subset: PhysicalDevicePortabilitySubsetFeaturesKHR,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_PORTABILITY_SUBSET_FEATURES_KHR, khr_portability_subset {
+        constant_alpha_color_blend_factors => constantAlphaColorBlendFactors,
+        events => events,
+        image_view_format_reinterpretation => imageViewFormatReinterpretation,
+        image_view_format_swizzle => imageViewFormatSwizzle,
+        image_view_2d_on_3d_image => imageView2DOn3DImage,
+        multisample_array_image => multisampleArrayImage,
+        mutable_comparison_samplers => mutableComparisonSamplers,
+        point_polygons => pointPolygons,
+        sampler_mip_lod_bias => samplerMipLodBias,
+        separate_stencil_mask_ref => separateStencilMaskRef,
+        shader_sample_rate_interpolation_functions => shaderSampleRateInterpolationFunctions,
+        tessellation_isolines => tessellationIsolines,
+        tessellation_point_mode => tessellationPointMode,
+        triangle_fans => triangleFans,
+        vertex_attribute_access_beyond_stride => vertexAttributeAccessBeyondStride,
+    }
+
+    descriptor_indexing: PhysicalDeviceDescriptorIndexingFeaturesEXT,
+        STRUCTURE_TYPE_PHYSICAL_DEVICE_DESCRIPTOR_INDEXING_FEATURES_EXT,
+        ext_descriptor_indexing {
+        shader_input_attachment_array_dynamic_indexing =>
+            shaderInputAttachmentArrayDynamicIndexing,
+        shader_uniform_texel_buffer_array_dynamic_indexing =>
+            shaderUniformTexelBufferArrayDynamicIndexing,
+        shader_storage_texel_buffer_array_dynamic_indexing =>
+            shaderStorageTexelBufferArrayDynamicIndexing,
+        shader_uniform_buffer_array_non_uniform_indexing =>
+            shaderUniformBufferArrayNonUniformIndexing,
+        shader_sampled_image_array_non_uniform_indexing =>
+            shaderSampledImageArrayNonUniformIndexing,
+        shader_storage_buffer_array_non_uniform_indexing =>
+            shaderStorageBufferArrayNonUniformIndexing,
+        shader_storage_image_array_non_uniform_indexing =>
+            shaderStorageImageArrayNonUniformIndexing,
+        shader_input_attachment_array_non_uniform_indexing =>
+            shaderInputAttachmentArrayNonUniformIndexing,
+        shader_uniform_texel_buffer_array_non_uniform_indexing =>
+            shaderUniformTexelBufferArrayNonUniformIndexing,
+        shader_storage_texel_buffer_array_non_uniform_indexing =>
+            shaderStorageTexelBufferArrayNonUniformIndexing,
+        descriptor_binding_uniform_buffer_update_after_bind =>
+            descriptorBindingUniformBufferUpdateAfterBind,
+        descriptor_binding_sampled_image_update_after_bind =>
+            descriptorBindingSampledImageUpdateAfterBind,
+        descriptor_binding_storage_image_update_after_bind =>
+            descriptorBindingStorageImageUpdateAfterBind,
+        descriptor_binding_storage_buffer_update_after_bind =>
+            descriptorBindingStorageBufferUpdateAfterBind,
+        descriptor_binding_uniform_texel_buffer_update_after_bind =>
+            descriptorBindingUniformTexelBufferUpdateAfterBind,
+        descriptor_binding_storage_texel_buffer_update_after_bind =>
+            descriptorBindingStorageTexelBufferUpdateAfterBind,
+        descriptor_binding_update_unused_while_pending => descriptorBindingUpdateUnusedWhilePending,
+        descriptor_binding_partially_bound => descriptorBindingPartiallyBound,
+        descriptor_binding_variable_descriptor_count => descriptorBindingVariableDescriptorCount,
+        runtime_descriptor_array => runtimeDescriptorArray,
+    }
 }
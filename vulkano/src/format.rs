@@ -97,12 +97,13 @@
 //! - R16G16B16A16_SFLOAT
 //!
 //! For depth images, only `D16Unorm` is guaranteed to be supported. For depth-stencil images,
-//! it is guaranteed that either `D24Unorm_S8Uint` or `D32Sfloat_S8Uint` are supported.
+//! it is guaranteed that either `D24Unorm_S8Uint` or `D32Sfloat_S8Uint` are supported. Use
+//! `PhysicalDevice::best_depth_format` to choose the most precise supported format.
 //!
 //! // TODO: storage formats
 //!
 
-use std::{error, fmt, mem};
+use std::{cmp, error, fmt, mem};
 use std::vec::IntoIter as VecIntoIter;
 
 use half::f16;
@@ -176,7 +177,8 @@ pub unsafe trait AcceptsPixels<T> {
 macro_rules! formats {
     ($($name:ident => $vk:ident [$sz:expr] [$($f_ty:tt)*] {$($d_ty:tt)*},)+) => (
         /// An enumeration of all the possible formats.
-        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[repr(u32)]
         #[allow(missing_docs)]
         #[allow(non_camel_case_types)]
@@ -606,6 +608,421 @@ formats! {
     ASTC_12x12SrgbBlock => FORMAT_ASTC_12x12_SRGB_BLOCK [None] [compressed=texture_compression_astc_ldr] {},
 }
 
+macro_rules! format_metadata {
+    ($($name:ident => $num:ident $bits:tt [$bw:expr, $bh:expr] $block_size:expr,)+) => (
+        impl Format {
+            /// Returns the numeric type of the components of this format.
+            ///
+            /// For depth-stencil formats, this is the numeric type of the depth component. The
+            /// stencil component is always `Uint`.
+            #[inline]
+            pub fn numeric_type(&self) -> NumericType {
+                match *self {
+                    $(
+                        Format::$name => NumericType::$num,
+                    )+
+                }
+            }
+
+            /// Returns the number of bits of each component of this format. Returns `None` for
+            /// compressed formats, whose components don't have a fixed number of bits.
+            #[inline]
+            pub fn components(&self) -> Option<FormatComponents> {
+                match *self {
+                    $(
+                        Format::$name => format_metadata!(__components__ $bits),
+                    )+
+                }
+            }
+
+            /// Returns the number of components of this format, including for compressed
+            /// formats.
+            #[inline]
+            pub fn component_count(&self) -> u32 {
+                match *self {
+                    $(
+                        Format::$name => format_metadata!(__count__ $bits),
+                    )+
+                }
+            }
+
+            /// Returns the width and height in texels of a block of this format. This is
+            /// `[1, 1]` for uncompressed formats.
+            #[inline]
+            pub fn block_extent(&self) -> [u32; 2] {
+                match *self {
+                    $(
+                        Format::$name => [$bw, $bh],
+                    )+
+                }
+            }
+
+            /// Returns the size in bytes of a block of this format, as defined by the Vulkan
+            /// specifications. For uncompressed formats a block is one texel.
+            ///
+            /// Contrary to `size()`, this is also defined for depth-stencil formats. Keep in mind
+            /// that the way depth-stencil formats are laid out in memory is
+            /// implementation-dependent, and that copies only access one aspect at a time.
+            #[inline]
+            pub fn block_size(&self) -> u32 {
+                match *self {
+                    $(
+                        Format::$name => $block_size,
+                    )+
+                }
+            }
+
+            /// Returns true if this format has a depth component.
+            #[inline]
+            pub fn has_depth(&self) -> bool {
+                self.components().map_or(false, |c| c.depth != 0)
+            }
+
+            /// Returns true if this format has a stencil component.
+            #[inline]
+            pub fn has_stencil(&self) -> bool {
+                self.components().map_or(false, |c| c.stencil != 0)
+            }
+        }
+    );
+
+    (__components__ [$r:expr, $g:expr, $b:expr, $a:expr, $d:expr, $s:expr]) => (
+        Some(FormatComponents {
+            red: $r,
+            green: $g,
+            blue: $b,
+            alpha: $a,
+            depth: $d,
+            stencil: $s,
+        })
+    );
+
+    (__components__ ($num:expr)) => (
+        None
+    );
+
+    (__count__ [$r:expr, $g:expr, $b:expr, $a:expr, $d:expr, $s:expr]) => (
+        [$r, $g, $b, $a, $d, $s].iter().filter(|&&bits| bits != 0).count() as u32
+    );
+
+    (__count__ ($num:expr)) => (
+        $num
+    );
+}
+
+// Mirrors the `<formats>` section of the Vulkan registry (`vk.xml`). Each entry contains the
+// numeric type, the number of bits of the red, green, blue, alpha, depth and stencil components
+// (or the number of components of a compressed format), the block extent and the block size.
+format_metadata! {
+    R4G4UnormPack8 => Unorm [4, 4, 0, 0, 0, 0] [1, 1] 1,
+    R4G4B4A4UnormPack16 => Unorm [4, 4, 4, 4, 0, 0] [1, 1] 2,
+    B4G4R4A4UnormPack16 => Unorm [4, 4, 4, 4, 0, 0] [1, 1] 2,
+    R5G6B5UnormPack16 => Unorm [5, 6, 5, 0, 0, 0] [1, 1] 2,
+    B5G6R5UnormPack16 => Unorm [5, 6, 5, 0, 0, 0] [1, 1] 2,
+    R5G5B5A1UnormPack16 => Unorm [5, 5, 5, 1, 0, 0] [1, 1] 2,
+    B5G5R5A1UnormPack16 => Unorm [5, 5, 5, 1, 0, 0] [1, 1] 2,
+    A1R5G5B5UnormPack16 => Unorm [5, 5, 5, 1, 0, 0] [1, 1] 2,
+    R8Unorm => Unorm [8, 0, 0, 0, 0, 0] [1, 1] 1,
+    R8Snorm => Snorm [8, 0, 0, 0, 0, 0] [1, 1] 1,
+    R8Uscaled => Uscaled [8, 0, 0, 0, 0, 0] [1, 1] 1,
+    R8Sscaled => Sscaled [8, 0, 0, 0, 0, 0] [1, 1] 1,
+    R8Uint => Uint [8, 0, 0, 0, 0, 0] [1, 1] 1,
+    R8Sint => Sint [8, 0, 0, 0, 0, 0] [1, 1] 1,
+    R8Srgb => Srgb [8, 0, 0, 0, 0, 0] [1, 1] 1,
+    R8G8Unorm => Unorm [8, 8, 0, 0, 0, 0] [1, 1] 2,
+    R8G8Snorm => Snorm [8, 8, 0, 0, 0, 0] [1, 1] 2,
+    R8G8Uscaled => Uscaled [8, 8, 0, 0, 0, 0] [1, 1] 2,
+    R8G8Sscaled => Sscaled [8, 8, 0, 0, 0, 0] [1, 1] 2,
+    R8G8Uint => Uint [8, 8, 0, 0, 0, 0] [1, 1] 2,
+    R8G8Sint => Sint [8, 8, 0, 0, 0, 0] [1, 1] 2,
+    R8G8Srgb => Srgb [8, 8, 0, 0, 0, 0] [1, 1] 2,
+    R8G8B8Unorm => Unorm [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    R8G8B8Snorm => Snorm [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    R8G8B8Uscaled => Uscaled [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    R8G8B8Sscaled => Sscaled [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    R8G8B8Uint => Uint [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    R8G8B8Sint => Sint [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    R8G8B8Srgb => Srgb [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    B8G8R8Unorm => Unorm [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    B8G8R8Snorm => Snorm [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    B8G8R8Uscaled => Uscaled [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    B8G8R8Sscaled => Sscaled [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    B8G8R8Uint => Uint [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    B8G8R8Sint => Sint [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    B8G8R8Srgb => Srgb [8, 8, 8, 0, 0, 0] [1, 1] 3,
+    R8G8B8A8Unorm => Unorm [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    R8G8B8A8Snorm => Snorm [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    R8G8B8A8Uscaled => Uscaled [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    R8G8B8A8Sscaled => Sscaled [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    R8G8B8A8Uint => Uint [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    R8G8B8A8Sint => Sint [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    R8G8B8A8Srgb => Srgb [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    B8G8R8A8Unorm => Unorm [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    B8G8R8A8Snorm => Snorm [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    B8G8R8A8Uscaled => Uscaled [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    B8G8R8A8Sscaled => Sscaled [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    B8G8R8A8Uint => Uint [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    B8G8R8A8Sint => Sint [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    B8G8R8A8Srgb => Srgb [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A8B8G8R8UnormPack32 => Unorm [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A8B8G8R8SnormPack32 => Snorm [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A8B8G8R8UscaledPack32 => Uscaled [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A8B8G8R8SscaledPack32 => Sscaled [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A8B8G8R8UintPack32 => Uint [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A8B8G8R8SintPack32 => Sint [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A8B8G8R8SrgbPack32 => Srgb [8, 8, 8, 8, 0, 0] [1, 1] 4,
+    A2R10G10B10UnormPack32 => Unorm [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2R10G10B10SnormPack32 => Snorm [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2R10G10B10UscaledPack32 => Uscaled [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2R10G10B10SscaledPack32 => Sscaled [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2R10G10B10UintPack32 => Uint [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2R10G10B10SintPack32 => Sint [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2B10G10R10UnormPack32 => Unorm [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2B10G10R10SnormPack32 => Snorm [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2B10G10R10UscaledPack32 => Uscaled [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2B10G10R10SscaledPack32 => Sscaled [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2B10G10R10UintPack32 => Uint [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    A2B10G10R10SintPack32 => Sint [10, 10, 10, 2, 0, 0] [1, 1] 4,
+    R16Unorm => Unorm [16, 0, 0, 0, 0, 0] [1, 1] 2,
+    R16Snorm => Snorm [16, 0, 0, 0, 0, 0] [1, 1] 2,
+    R16Uscaled => Uscaled [16, 0, 0, 0, 0, 0] [1, 1] 2,
+    R16Sscaled => Sscaled [16, 0, 0, 0, 0, 0] [1, 1] 2,
+    R16Uint => Uint [16, 0, 0, 0, 0, 0] [1, 1] 2,
+    R16Sint => Sint [16, 0, 0, 0, 0, 0] [1, 1] 2,
+    R16Sfloat => Sfloat [16, 0, 0, 0, 0, 0] [1, 1] 2,
+    R16G16Unorm => Unorm [16, 16, 0, 0, 0, 0] [1, 1] 4,
+    R16G16Snorm => Snorm [16, 16, 0, 0, 0, 0] [1, 1] 4,
+    R16G16Uscaled => Uscaled [16, 16, 0, 0, 0, 0] [1, 1] 4,
+    R16G16Sscaled => Sscaled [16, 16, 0, 0, 0, 0] [1, 1] 4,
+    R16G16Uint => Uint [16, 16, 0, 0, 0, 0] [1, 1] 4,
+    R16G16Sint => Sint [16, 16, 0, 0, 0, 0] [1, 1] 4,
+    R16G16Sfloat => Sfloat [16, 16, 0, 0, 0, 0] [1, 1] 4,
+    R16G16B16Unorm => Unorm [16, 16, 16, 0, 0, 0] [1, 1] 6,
+    R16G16B16Snorm => Snorm [16, 16, 16, 0, 0, 0] [1, 1] 6,
+    R16G16B16Uscaled => Uscaled [16, 16, 16, 0, 0, 0] [1, 1] 6,
+    R16G16B16Sscaled => Sscaled [16, 16, 16, 0, 0, 0] [1, 1] 6,
+    R16G16B16Uint => Uint [16, 16, 16, 0, 0, 0] [1, 1] 6,
+    R16G16B16Sint => Sint [16, 16, 16, 0, 0, 0] [1, 1] 6,
+    R16G16B16Sfloat => Sfloat [16, 16, 16, 0, 0, 0] [1, 1] 6,
+    R16G16B16A16Unorm => Unorm [16, 16, 16, 16, 0, 0] [1, 1] 8,
+    R16G16B16A16Snorm => Snorm [16, 16, 16, 16, 0, 0] [1, 1] 8,
+    R16G16B16A16Uscaled => Uscaled [16, 16, 16, 16, 0, 0] [1, 1] 8,
+    R16G16B16A16Sscaled => Sscaled [16, 16, 16, 16, 0, 0] [1, 1] 8,
+    R16G16B16A16Uint => Uint [16, 16, 16, 16, 0, 0] [1, 1] 8,
+    R16G16B16A16Sint => Sint [16, 16, 16, 16, 0, 0] [1, 1] 8,
+    R16G16B16A16Sfloat => Sfloat [16, 16, 16, 16, 0, 0] [1, 1] 8,
+    R32Uint => Uint [32, 0, 0, 0, 0, 0] [1, 1] 4,
+    R32Sint => Sint [32, 0, 0, 0, 0, 0] [1, 1] 4,
+    R32Sfloat => Sfloat [32, 0, 0, 0, 0, 0] [1, 1] 4,
+    R32G32Uint => Uint [32, 32, 0, 0, 0, 0] [1, 1] 8,
+    R32G32Sint => Sint [32, 32, 0, 0, 0, 0] [1, 1] 8,
+    R32G32Sfloat => Sfloat [32, 32, 0, 0, 0, 0] [1, 1] 8,
+    R32G32B32Uint => Uint [32, 32, 32, 0, 0, 0] [1, 1] 12,
+    R32G32B32Sint => Sint [32, 32, 32, 0, 0, 0] [1, 1] 12,
+    R32G32B32Sfloat => Sfloat [32, 32, 32, 0, 0, 0] [1, 1] 12,
+    R32G32B32A32Uint => Uint [32, 32, 32, 32, 0, 0] [1, 1] 16,
+    R32G32B32A32Sint => Sint [32, 32, 32, 32, 0, 0] [1, 1] 16,
+    R32G32B32A32Sfloat => Sfloat [32, 32, 32, 32, 0, 0] [1, 1] 16,
+    R64Uint => Uint [64, 0, 0, 0, 0, 0] [1, 1] 8,
+    R64Sint => Sint [64, 0, 0, 0, 0, 0] [1, 1] 8,
+    R64Sfloat => Sfloat [64, 0, 0, 0, 0, 0] [1, 1] 8,
+    R64G64Uint => Uint [64, 64, 0, 0, 0, 0] [1, 1] 16,
+    R64G64Sint => Sint [64, 64, 0, 0, 0, 0] [1, 1] 16,
+    R64G64Sfloat => Sfloat [64, 64, 0, 0, 0, 0] [1, 1] 16,
+    R64G64B64Uint => Uint [64, 64, 64, 0, 0, 0] [1, 1] 24,
+    R64G64B64Sint => Sint [64, 64, 64, 0, 0, 0] [1, 1] 24,
+    R64G64B64Sfloat => Sfloat [64, 64, 64, 0, 0, 0] [1, 1] 24,
+    R64G64B64A64Uint => Uint [64, 64, 64, 64, 0, 0] [1, 1] 32,
+    R64G64B64A64Sint => Sint [64, 64, 64, 64, 0, 0] [1, 1] 32,
+    R64G64B64A64Sfloat => Sfloat [64, 64, 64, 64, 0, 0] [1, 1] 32,
+    B10G11R11UfloatPack32 => Ufloat [11, 11, 10, 0, 0, 0] [1, 1] 4,
+    E5B9G9R9UfloatPack32 => Ufloat [9, 9, 9, 0, 0, 0] [1, 1] 4,
+    D16Unorm => Unorm [0, 0, 0, 0, 16, 0] [1, 1] 2,
+    X8_D24UnormPack32 => Unorm [0, 0, 0, 0, 24, 0] [1, 1] 4,
+    D32Sfloat => Sfloat [0, 0, 0, 0, 32, 0] [1, 1] 4,
+    S8Uint => Uint [0, 0, 0, 0, 0, 8] [1, 1] 1,
+    D16Unorm_S8Uint => Unorm [0, 0, 0, 0, 16, 8] [1, 1] 3,
+    D24Unorm_S8Uint => Unorm [0, 0, 0, 0, 24, 8] [1, 1] 4,
+    D32Sfloat_S8Uint => Sfloat [0, 0, 0, 0, 32, 8] [1, 1] 5,
+    BC1_RGBUnormBlock => Unorm (3) [4, 4] 8,
+    BC1_RGBSrgbBlock => Srgb (3) [4, 4] 8,
+    BC1_RGBAUnormBlock => Unorm (4) [4, 4] 8,
+    BC1_RGBASrgbBlock => Srgb (4) [4, 4] 8,
+    BC2UnormBlock => Unorm (4) [4, 4] 16,
+    BC2SrgbBlock => Srgb (4) [4, 4] 16,
+    BC3UnormBlock => Unorm (4) [4, 4] 16,
+    BC3SrgbBlock => Srgb (4) [4, 4] 16,
+    BC4UnormBlock => Unorm (1) [4, 4] 8,
+    BC4SnormBlock => Snorm (1) [4, 4] 8,
+    BC5UnormBlock => Unorm (2) [4, 4] 16,
+    BC5SnormBlock => Snorm (2) [4, 4] 16,
+    BC6HUfloatBlock => Ufloat (3) [4, 4] 16,
+    BC6HSfloatBlock => Sfloat (3) [4, 4] 16,
+    BC7UnormBlock => Unorm (4) [4, 4] 16,
+    BC7SrgbBlock => Srgb (4) [4, 4] 16,
+    ETC2_R8G8B8UnormBlock => Unorm (3) [4, 4] 8,
+    ETC2_R8G8B8SrgbBlock => Srgb (3) [4, 4] 8,
+    ETC2_R8G8B8A1UnormBlock => Unorm (4) [4, 4] 8,
+    ETC2_R8G8B8A1SrgbBlock => Srgb (4) [4, 4] 8,
+    ETC2_R8G8B8A8UnormBlock => Unorm (4) [4, 4] 16,
+    ETC2_R8G8B8A8SrgbBlock => Srgb (4) [4, 4] 16,
+    EAC_R11UnormBlock => Unorm (1) [4, 4] 8,
+    EAC_R11SnormBlock => Snorm (1) [4, 4] 8,
+    EAC_R11G11UnormBlock => Unorm (2) [4, 4] 16,
+    EAC_R11G11SnormBlock => Snorm (2) [4, 4] 16,
+    ASTC_4x4UnormBlock => Unorm (4) [4, 4] 16,
+    ASTC_4x4SrgbBlock => Srgb (4) [4, 4] 16,
+    ASTC_5x4UnormBlock => Unorm (4) [5, 4] 16,
+    ASTC_5x4SrgbBlock => Srgb (4) [5, 4] 16,
+    ASTC_5x5UnormBlock => Unorm (4) [5, 5] 16,
+    ASTC_5x5SrgbBlock => Srgb (4) [5, 5] 16,
+    ASTC_6x5UnormBlock => Unorm (4) [6, 5] 16,
+    ASTC_6x5SrgbBlock => Srgb (4) [6, 5] 16,
+    ASTC_6x6UnormBlock => Unorm (4) [6, 6] 16,
+    ASTC_6x6SrgbBlock => Srgb (4) [6, 6] 16,
+    ASTC_8x5UnormBlock => Unorm (4) [8, 5] 16,
+    ASTC_8x5SrgbBlock => Srgb (4) [8, 5] 16,
+    ASTC_8x6UnormBlock => Unorm (4) [8, 6] 16,
+    ASTC_8x6SrgbBlock => Srgb (4) [8, 6] 16,
+    ASTC_8x8UnormBlock => Unorm (4) [8, 8] 16,
+    ASTC_8x8SrgbBlock => Srgb (4) [8, 8] 16,
+    ASTC_10x5UnormBlock => Unorm (4) [10, 5] 16,
+    ASTC_10x5SrgbBlock => Srgb (4) [10, 5] 16,
+    ASTC_10x6UnormBlock => Unorm (4) [10, 6] 16,
+    ASTC_10x6SrgbBlock => Srgb (4) [10, 6] 16,
+    ASTC_10x8UnormBlock => Unorm (4) [10, 8] 16,
+    ASTC_10x8SrgbBlock => Srgb (4) [10, 8] 16,
+    ASTC_10x10UnormBlock => Unorm (4) [10, 10] 16,
+    ASTC_10x10SrgbBlock => Srgb (4) [10, 10] 16,
+    ASTC_12x10UnormBlock => Unorm (4) [12, 10] 16,
+    ASTC_12x10SrgbBlock => Srgb (4) [12, 10] 16,
+    ASTC_12x12UnormBlock => Unorm (4) [12, 12] 16,
+    ASTC_12x12SrgbBlock => Srgb (4) [12, 12] 16,
+}
+
+impl Format {
+    /// Returns the number of blocks of this format that are needed to cover `extent` texels in
+    /// each dimension.
+    #[inline]
+    pub fn block_count(&self, extent: [u32; 3]) -> [u32; 3] {
+        let block_extent = self.block_extent();
+        [
+            (extent[0] + block_extent[0] - 1) / block_extent[0],
+            (extent[1] + block_extent[1] - 1) / block_extent[1],
+            extent[2],
+        ]
+    }
+
+    /// Returns the number of bytes of a tightly packed row of `width` texels of this format. For
+    /// compressed formats, a row is a row of blocks.
+    #[inline]
+    pub fn row_pitch(&self, width: u32) -> u64 {
+        self.block_count([width, 1, 1])[0] as u64 * self.block_size() as u64
+    }
+
+    /// Returns the number of bytes of a tightly packed 2D slice of `width` by `height` texels of
+    /// this format.
+    #[inline]
+    pub fn slice_pitch(&self, width: u32, height: u32) -> u64 {
+        self.block_count([width, height, 1])[1] as u64 * self.row_pitch(width)
+    }
+
+    /// Returns the number of bytes that a buffer must contain after its offset in order to copy
+    /// `extent` texels of `array_layers` array layers from or to an image of this format.
+    ///
+    /// `buffer_row_length` and `buffer_image_height` have the same meaning as in a Vulkan
+    /// buffer-image copy: they are the number of texels between two rows and two slices in the
+    /// buffer, or `0` if the data is tightly packed.
+    ///
+    /// For depth-stencil formats, the result is only meaningful when copying a single aspect of
+    /// the image with the format of this aspect, for example `D32Sfloat` or `S8Uint`.
+    pub fn copy_buffer_size(&self, extent: [u32; 3], array_layers: u32, buffer_row_length: u32,
+                            buffer_image_height: u32)
+                            -> u64 {
+        if extent[0] == 0 || extent[1] == 0 || extent[2] == 0 || array_layers == 0 {
+            return 0;
+        }
+
+        let blocks = self.block_count(extent);
+        let row_blocks =
+            self.block_count([cmp::max(buffer_row_length, extent[0]), 1, 1])[0] as u64;
+        let slice_blocks =
+            self.block_count([1, cmp::max(buffer_image_height, extent[1]), 1])[1] as u64 *
+                row_blocks;
+
+        // Array layers are laid out in the buffer like the slices of a 3D image.
+        let last_slice = blocks[2] as u64 * array_layers as u64 - 1;
+        let last_block = last_slice * slice_blocks + (blocks[1] as u64 - 1) * row_blocks +
+            blocks[0] as u64 - 1;
+        (last_block + 1) * self.block_size() as u64
+    }
+}
+
+macro_rules! srgb_pairs {
+    ($($unorm:ident => $srgb:ident,)+) => (
+        impl Format {
+            /// Returns the sRGB format with the same layout as this format, or `None` if there
+            /// isn't one. Returns the format itself if it is already an sRGB format.
+            ///
+            /// An image created with `mutable_format` can have views of both formats, for
+            /// example to write to it in linear space and sample it with sRGB conversion.
+            #[inline]
+            pub fn srgb_equivalent(&self) -> Option<Format> {
+                match *self {
+                    $(
+                        Format::$unorm | Format::$srgb => Some(Format::$srgb),
+                    )+
+                    _ => None,
+                }
+            }
+
+            /// Returns the `Unorm` format with the same layout as this format, or `None` if
+            /// there isn't one. This is the opposite of `srgb_equivalent`.
+            #[inline]
+            pub fn unorm_equivalent(&self) -> Option<Format> {
+                match *self {
+                    $(
+                        Format::$unorm | Format::$srgb => Some(Format::$unorm),
+                    )+
+                    _ => None,
+                }
+            }
+        }
+    );
+}
+
+srgb_pairs! {
+    R8Unorm => R8Srgb,
+    R8G8Unorm => R8G8Srgb,
+    R8G8B8Unorm => R8G8B8Srgb,
+    B8G8R8Unorm => B8G8R8Srgb,
+    R8G8B8A8Unorm => R8G8B8A8Srgb,
+    B8G8R8A8Unorm => B8G8R8A8Srgb,
+    A8B8G8R8UnormPack32 => A8B8G8R8SrgbPack32,
+    BC1_RGBUnormBlock => BC1_RGBSrgbBlock,
+    BC1_RGBAUnormBlock => BC1_RGBASrgbBlock,
+    BC2UnormBlock => BC2SrgbBlock,
+    BC3UnormBlock => BC3SrgbBlock,
+    BC7UnormBlock => BC7SrgbBlock,
+    ETC2_R8G8B8UnormBlock => ETC2_R8G8B8SrgbBlock,
+    ETC2_R8G8B8A1UnormBlock => ETC2_R8G8B8A1SrgbBlock,
+    ETC2_R8G8B8A8UnormBlock => ETC2_R8G8B8A8SrgbBlock,
+    ASTC_4x4UnormBlock => ASTC_4x4SrgbBlock,
+    ASTC_5x4UnormBlock => ASTC_5x4SrgbBlock,
+    ASTC_5x5UnormBlock => ASTC_5x5SrgbBlock,
+    ASTC_6x5UnormBlock => ASTC_6x5SrgbBlock,
+    ASTC_6x6UnormBlock => ASTC_6x6SrgbBlock,
+    ASTC_8x5UnormBlock => ASTC_8x5SrgbBlock,
+    ASTC_8x6UnormBlock => ASTC_8x6SrgbBlock,
+    ASTC_8x8UnormBlock => ASTC_8x8SrgbBlock,
+    ASTC_10x5UnormBlock => ASTC_10x5SrgbBlock,
+    ASTC_10x6UnormBlock => ASTC_10x6SrgbBlock,
+    ASTC_10x8UnormBlock => ASTC_10x8SrgbBlock,
+    ASTC_10x10UnormBlock => ASTC_10x10SrgbBlock,
+    ASTC_12x10UnormBlock => ASTC_12x10SrgbBlock,
+    ASTC_12x12UnormBlock => ASTC_12x12SrgbBlock,
+}
+
 pub unsafe trait FormatDesc {
     type ClearValue;
 
@@ -789,6 +1206,34 @@ impl FormatTy {
     }
 }
 
+/// Numeric type of the components of a format, as returned by `Format::numeric_type`.
+///
+/// See the documentation of the `format` module for the meaning of each type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum NumericType {
+    Unorm,
+    Snorm,
+    Uscaled,
+    Sscaled,
+    Uint,
+    Sint,
+    Ufloat,
+    Sfloat,
+    Srgb,
+}
+
+/// Number of bits of each component of a format, as returned by `Format::components`. A
+/// component that the format doesn't have is `0`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FormatComponents {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+    pub depth: u8,
+    pub stencil: u8,
+}
+
 /// Describes a uniform value that will be used to fill an image.
 // TODO: should have the same layout as `vk::ClearValue` for performances
 #[derive(Debug, Copy, Clone, PartialEq)]
@@ -809,6 +1254,236 @@ pub enum ClearValue {
     DepthStencil((f32, u32)),
 }
 
+impl ClearValue {
+    /// Returns the clear value that is used by default for attachments of the given format.
+    ///
+    /// This is transparent black for color formats, `1.0` (the far plane) for depth and `0` for
+    /// stencil.
+    #[inline]
+    pub fn default_for(format: Format) -> ClearValue {
+        match format.ty() {
+            FormatTy::Float | FormatTy::Compressed => ClearValue::Float([0.0; 4]),
+            FormatTy::Uint => ClearValue::Uint([0; 4]),
+            FormatTy::Sint => ClearValue::Int([0; 4]),
+            FormatTy::Depth => ClearValue::Depth(1.0),
+            FormatTy::Stencil => ClearValue::Stencil(0),
+            FormatTy::DepthStencil => ClearValue::DepthStencil((1.0, 0)),
+        }
+    }
+
+    /// Builds a clear value for `format` from a floating-point color.
+    ///
+    /// Returns an error if `format` isn't a floating-point or normalized color format.
+    pub fn from_f32(format: Format, color: [f32; 4]) -> Result<ClearValue, ClearValueError> {
+        match format.ty() {
+            FormatTy::Float | FormatTy::Compressed => Ok(ClearValue::Float(color)),
+            _ => Err(ClearValueError::WrongNumericType { format: format }),
+        }
+    }
+
+    /// Builds a clear value for `format` from an unsigned integer color.
+    ///
+    /// The color is used as-is for `Uint` formats. It is converted for the other color formats:
+    /// `Unorm` and `Srgb` components are divided by their maximum value, `Uscaled` components
+    /// are converted to floating-point, and `Sint` components are converted to signed integers.
+    /// Returns an error if a component doesn't fit in the format.
+    pub fn from_u32(format: Format, color: [u32; 4]) -> Result<ClearValue, ClearValueError> {
+        match format.numeric_type() {
+            _ if format.ty().is_depth_and_or_stencil() => {
+                Err(ClearValueError::WrongNumericType { format: format })
+            },
+            NumericType::Uint => Ok(ClearValue::Uint(color)),
+            NumericType::Sint => {
+                let mut out = [0; 4];
+                for (o, &c) in out.iter_mut().zip(color.iter()) {
+                    if c > i32::max_value() as u32 {
+                        return Err(ClearValueError::ValueOutOfRange { format: format });
+                    }
+                    *o = c as i32;
+                }
+                Ok(ClearValue::Int(out))
+            },
+            NumericType::Uscaled => {
+                Ok(ClearValue::Float([color[0] as f32,
+                                      color[1] as f32,
+                                      color[2] as f32,
+                                      color[3] as f32]))
+            },
+            NumericType::Unorm | NumericType::Srgb => {
+                let max = normalized_max(format, false)?;
+                let mut out = [0.0; 4];
+                for ((o, &c), &max) in out.iter_mut().zip(color.iter()).zip(max.iter()) {
+                    if u64::from(c) > max {
+                        return Err(ClearValueError::ValueOutOfRange { format: format });
+                    }
+                    *o = if max == 0 { 0.0 } else { c as f32 / max as f32 };
+                }
+                Ok(ClearValue::Float(out))
+            },
+            _ => Err(ClearValueError::WrongNumericType { format: format }),
+        }
+    }
+
+    /// Builds a clear value for `format` from a signed integer color.
+    ///
+    /// The color is used as-is for `Sint` formats. It is converted for the other color formats:
+    /// `Snorm` components are divided by their maximum value, `Sscaled` components are converted
+    /// to floating-point, and `Uint` components are converted to unsigned integers. Returns an
+    /// error if a component doesn't fit in the format.
+    pub fn from_i32(format: Format, color: [i32; 4]) -> Result<ClearValue, ClearValueError> {
+        match format.numeric_type() {
+            _ if format.ty().is_depth_and_or_stencil() => {
+                Err(ClearValueError::WrongNumericType { format: format })
+            },
+            NumericType::Sint => Ok(ClearValue::Int(color)),
+            NumericType::Uint => {
+                let mut out = [0; 4];
+                for (o, &c) in out.iter_mut().zip(color.iter()) {
+                    if c < 0 {
+                        return Err(ClearValueError::ValueOutOfRange { format: format });
+                    }
+                    *o = c as u32;
+                }
+                Ok(ClearValue::Uint(out))
+            },
+            NumericType::Sscaled => {
+                Ok(ClearValue::Float([color[0] as f32,
+                                      color[1] as f32,
+                                      color[2] as f32,
+                                      color[3] as f32]))
+            },
+            NumericType::Snorm => {
+                let max = normalized_max(format, true)?;
+                let mut out = [0.0; 4];
+                for ((o, &c), &max) in out.iter_mut().zip(color.iter()).zip(max.iter()) {
+                    if i64::from(c).abs() > max as i64 {
+                        return Err(ClearValueError::ValueOutOfRange { format: format });
+                    }
+                    *o = if max == 0 { 0.0 } else { c as f32 / max as f32 };
+                }
+                Ok(ClearValue::Float(out))
+            },
+            _ => Err(ClearValueError::WrongNumericType { format: format }),
+        }
+    }
+
+    /// Builds a clear value for `format` from a color packed as `0xRRGGBBAA`, with 8 bits per
+    /// component.
+    ///
+    /// The components are interpreted as normalized values for floating-point and normalized
+    /// formats, and as integers for integer formats.
+    pub fn from_packed_rgba8(format: Format, rgba: u32) -> Result<ClearValue, ClearValueError> {
+        let color = [rgba >> 24, (rgba >> 16) & 0xff, (rgba >> 8) & 0xff, rgba & 0xff];
+
+        match format.ty() {
+            FormatTy::Float | FormatTy::Compressed => {
+                Ok(ClearValue::Float([color[0] as f32 / 255.0,
+                                      color[1] as f32 / 255.0,
+                                      color[2] as f32 / 255.0,
+                                      color[3] as f32 / 255.0]))
+            },
+            FormatTy::Uint => Ok(ClearValue::Uint(color)),
+            FormatTy::Sint => {
+                Ok(ClearValue::Int([color[0] as i32,
+                                    color[1] as i32,
+                                    color[2] as i32,
+                                    color[3] as i32]))
+            },
+            _ => Err(ClearValueError::WrongNumericType { format: format }),
+        }
+    }
+
+    /// Builds a clear value for a depth, stencil or depth-stencil `format`. The component that
+    /// the format doesn't have is ignored.
+    ///
+    /// Returns an error if `format` isn't a depth or stencil format, or if `depth` isn't between
+    /// `0.0` and `1.0`.
+    pub fn from_depth_stencil(format: Format, depth: f32, stencil: u32)
+                              -> Result<ClearValue, ClearValueError> {
+        if format.has_depth() && !(depth >= 0.0 && depth <= 1.0) {
+            return Err(ClearValueError::ValueOutOfRange { format: format });
+        }
+
+        match format.ty() {
+            FormatTy::Depth => Ok(ClearValue::Depth(depth)),
+            FormatTy::Stencil => Ok(ClearValue::Stencil(stencil)),
+            FormatTy::DepthStencil => Ok(ClearValue::DepthStencil((depth, stencil))),
+            _ => Err(ClearValueError::WrongNumericType { format: format }),
+        }
+    }
+
+    /// Returns true if this clear value can be used to clear an attachment of the given format.
+    ///
+    /// `ClearValue::None` is compatible with every format.
+    #[inline]
+    pub fn is_compatible_with(&self, format: Format) -> bool {
+        match (*self, format.ty()) {
+            (ClearValue::None, _) => true,
+            (ClearValue::Float(_), FormatTy::Float) => true,
+            (ClearValue::Float(_), FormatTy::Compressed) => true,
+            (ClearValue::Int(_), FormatTy::Sint) => true,
+            (ClearValue::Uint(_), FormatTy::Uint) => true,
+            (ClearValue::Depth(_), FormatTy::Depth) => true,
+            (ClearValue::Stencil(_), FormatTy::Stencil) => true,
+            (ClearValue::DepthStencil(_), FormatTy::DepthStencil) => true,
+            _ => false,
+        }
+    }
+}
+
+// Returns the maximum value of each color component of a normalized format.
+fn normalized_max(format: Format, signed: bool) -> Result<[u64; 4], ClearValueError> {
+    let components = match format.components() {
+        Some(c) => c,
+        None => return Err(ClearValueError::WrongNumericType { format: format }),
+    };
+
+    let max = |bits: u8| if bits == 0 {
+        0
+    } else if signed {
+        (1u64 << (bits - 1)) - 1
+    } else {
+        (1u64 << bits) - 1
+    };
+
+    Ok([
+        max(components.red),
+        max(components.green),
+        max(components.blue),
+        max(components.alpha),
+    ])
+}
+
+/// Error that can happen when building a `ClearValue` for a format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ClearValueError {
+    /// The color or depth-stencil value can't be used or converted for this format.
+    WrongNumericType { format: Format },
+    /// A component doesn't fit in the range of values of this format.
+    ValueOutOfRange { format: Format },
+}
+
+impl error::Error for ClearValueError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ClearValueError::WrongNumericType { .. } => {
+                "the value can't be used or converted for this format"
+            },
+            ClearValueError::ValueOutOfRange { .. } => {
+                "a component doesn't fit in the range of values of this format"
+            },
+        }
+    }
+}
+
+impl fmt::Display for ClearValueError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
 // TODO: remove all these From implementations once they are no longer needed
 
 impl From<[f32; 1]> for ClearValue {
@@ -956,3 +1631,105 @@ macro_rules! impl_clear_values_tuple {
 }
 
 impl_clear_values_tuple!(A B C D E F G H I J K L M N O P Q R S T U V W X Y Z);
+
+#[cfg(test)]
+mod tests {
+    use format::ClearValue;
+    use format::ClearValueError;
+    use format::Format;
+    use format::FormatComponents;
+    use format::NumericType;
+
+    #[test]
+    fn metadata() {
+        let format = Format::B10G11R11UfloatPack32;
+        assert_eq!(format.numeric_type(), NumericType::Ufloat);
+        assert_eq!(format.component_count(), 3);
+        assert_eq!(format.components().unwrap().red, 11);
+        assert_eq!(format.block_size(), 4);
+
+        let format = Format::D24Unorm_S8Uint;
+        assert!(format.has_depth() && format.has_stencil());
+        assert_eq!(format.components(),
+                   Some(FormatComponents {
+                            red: 0,
+                            green: 0,
+                            blue: 0,
+                            alpha: 0,
+                            depth: 24,
+                            stencil: 8,
+                        }));
+
+        let format = Format::ASTC_10x6SrgbBlock;
+        assert_eq!(format.numeric_type(), NumericType::Srgb);
+        assert_eq!(format.components(), None);
+        assert_eq!(format.component_count(), 4);
+        assert_eq!(format.block_extent(), [10, 6]);
+        assert_eq!(format.block_size(), 16);
+        assert!(!format.has_depth());
+    }
+
+    #[test]
+    fn block_size_matches_size() {
+        let formats = [Format::R8Unorm, Format::R16G16B16Sfloat, Format::R64G64B64A64Uint,
+                       Format::A2B10G10R10UnormPack32, Format::D32Sfloat];
+        for format in formats.iter() {
+            assert_eq!(format.size(), Some(format.block_size() as usize));
+        }
+    }
+
+    #[test]
+    fn clear_value_conversions() {
+        assert_eq!(ClearValue::from_u32(Format::R8G8B8A8Unorm, [255, 0, 51, 255]),
+                   Ok(ClearValue::Float([1.0, 0.0, 0.2, 1.0])));
+        assert_eq!(ClearValue::from_u32(Format::R8G8B8A8Unorm, [256, 0, 0, 0]),
+                   Err(ClearValueError::ValueOutOfRange { format: Format::R8G8B8A8Unorm }));
+        assert_eq!(ClearValue::from_i32(Format::R8G8Snorm, [-127, 127, 0, 0]),
+                   Ok(ClearValue::Float([-1.0, 1.0, 0.0, 0.0])));
+        assert_eq!(ClearValue::from_i32(Format::R32Uint, [-1, 0, 0, 0]),
+                   Err(ClearValueError::ValueOutOfRange { format: Format::R32Uint }));
+        assert_eq!(ClearValue::from_f32(Format::R32Sint, [0.0; 4]),
+                   Err(ClearValueError::WrongNumericType { format: Format::R32Sint }));
+        assert_eq!(ClearValue::from_packed_rgba8(Format::B8G8R8A8Srgb, 0xff0000ff),
+                   Ok(ClearValue::Float([1.0, 0.0, 0.0, 1.0])));
+        assert_eq!(ClearValue::from_depth_stencil(Format::D24Unorm_S8Uint, 0.5, 3),
+                   Ok(ClearValue::DepthStencil((0.5, 3))));
+        assert!(ClearValue::from_depth_stencil(Format::D32Sfloat, 2.0, 0).is_err());
+    }
+
+    #[test]
+    fn copy_pitches() {
+        let format = Format::R16G16B16A16Sfloat;
+        assert_eq!(format.row_pitch(10), 80);
+        assert_eq!(format.slice_pitch(10, 3), 240);
+        assert_eq!(format.copy_buffer_size([10, 3, 1], 2, 0, 0), 480);
+        // The last row of the last layer doesn't need to be padded.
+        assert_eq!(format.copy_buffer_size([10, 3, 1], 2, 16, 4), ((16 * 4) + (16 * 2) + 10) * 8);
+
+        let format = Format::BC1_RGBUnormBlock;
+        assert_eq!(format.block_count([10, 3, 1]), [3, 1, 1]);
+        assert_eq!(format.row_pitch(10), 24);
+        assert_eq!(format.slice_pitch(10, 3), 24);
+        assert_eq!(format.copy_buffer_size([16, 16, 1], 1, 0, 0), 128);
+        assert_eq!(format.copy_buffer_size([0, 16, 1], 1, 0, 0), 0);
+    }
+
+    #[test]
+    fn srgb_unorm_equivalents() {
+        assert_eq!(Format::B8G8R8A8Unorm.srgb_equivalent(), Some(Format::B8G8R8A8Srgb));
+        assert_eq!(Format::B8G8R8A8Srgb.srgb_equivalent(), Some(Format::B8G8R8A8Srgb));
+        assert_eq!(Format::ASTC_8x8SrgbBlock.unorm_equivalent(),
+                   Some(Format::ASTC_8x8UnormBlock));
+        assert_eq!(Format::R16Unorm.srgb_equivalent(), None);
+        assert_eq!(Format::R8G8B8A8Snorm.unorm_equivalent(), None);
+    }
+
+    #[test]
+    fn clear_value_default() {
+        for &format in [Format::R8G8B8A8Unorm, Format::R16Uint, Format::R32G32Sint,
+                        Format::D16Unorm, Format::S8Uint, Format::D32Sfloat_S8Uint].iter() {
+            assert!(ClearValue::default_for(format).is_compatible_with(format));
+        }
+        assert!(!ClearValue::Depth(1.0).is_compatible_with(Format::R8Unorm));
+    }
+}
@@ -422,6 +422,7 @@ impl<'a, R: ?Sized + 'a> Iterator for RenderPassDescDependencies<'a, R>
 
 /// Describes an attachment that will be used in a render pass.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LayoutAttachmentDescription {
     /// Format of the image that is going to be binded.
     pub format: Format,
@@ -483,6 +484,7 @@ impl LayoutAttachmentDescription {
 // TODO: add tests for all these restrictions
 // TODO: allow unused attachments (for example attachment 0 and 2 are used, 1 is unused)
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LayoutPassDescription {
     /// Indices and layouts of attachments to use as color attachments.
     pub color_attachments: Vec<(usize, ImageLayout)>, // TODO: Vec is slow
@@ -509,6 +511,7 @@ pub struct LayoutPassDescription {
 /// you specify that there exists a dependency between two passes (ie. the result of one will be
 /// used as the input of another one).
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LayoutPassDependencyDescription {
     /// Index of the subpass that writes the data that `destination_subpass` is going to use.
     pub source_subpass: usize,
@@ -546,6 +549,7 @@ pub struct LayoutPassDependencyDescription {
 /// Describes what the implementation should do with an attachment after all the subpasses have
 /// completed.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum StoreOp {
     /// The attachment will be stored. This is what you usually want.
@@ -567,6 +571,7 @@ pub enum StoreOp {
 
 /// Describes what the implementation should do with an attachment at the start of the subpass.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum LoadOp {
     /// The content of the attachment will be loaded from memory. This is what you want if you want
@@ -593,3 +598,63 @@ pub enum LoadOp {
     /// instead.
     DontCare = vk::ATTACHMENT_LOAD_OP_DONT_CARE,
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use format::Format;
+    use framebuffer::LayoutAttachmentDescription;
+    use framebuffer::LayoutPassDependencyDescription;
+    use framebuffer::LayoutPassDescription;
+    use framebuffer::LoadOp;
+    use framebuffer::StoreOp;
+    use image::ImageLayout;
+    use sync::AccessFlagBits;
+    use sync::PipelineStages;
+
+    #[test]
+    fn serde_round_trip() {
+        let attachment = LayoutAttachmentDescription {
+            format: Format::R8G8B8A8Srgb,
+            samples: 4,
+            load: LoadOp::Clear,
+            store: StoreOp::Store,
+            stencil_load: LoadOp::DontCare,
+            stencil_store: StoreOp::DontCare,
+            initial_layout: ImageLayout::Undefined,
+            final_layout: ImageLayout::PresentSrc,
+        };
+        let pass = LayoutPassDescription {
+            color_attachments: vec![(0, ImageLayout::ColorAttachmentOptimal)],
+            depth_stencil: None,
+            input_attachments: vec![],
+            resolve_attachments: vec![],
+            preserve_attachments: vec![1],
+        };
+        let dependency = LayoutPassDependencyDescription {
+            source_subpass: 0,
+            destination_subpass: 1,
+            source_stages: PipelineStages {
+                color_attachment_output: true,
+                .. PipelineStages::none()
+            },
+            destination_stages: PipelineStages {
+                fragment_shader: true,
+                .. PipelineStages::none()
+            },
+            source_access: AccessFlagBits {
+                color_attachment_write: true,
+                .. AccessFlagBits::none()
+            },
+            destination_access: AccessFlagBits {
+                input_attachment_read: true,
+                .. AccessFlagBits::none()
+            },
+            by_region: true,
+        };
+
+        let json = ::serde_json::to_string(&(&attachment, &pass, &dependency)).unwrap();
+        let decoded: (LayoutAttachmentDescription, LayoutPassDescription,
+                      LayoutPassDependencyDescription) = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", (attachment, pass, dependency)));
+    }
+}
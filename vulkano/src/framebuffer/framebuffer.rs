@@ -308,7 +308,7 @@ impl<Rp, A> FramebufferBuilder<Rp, A>
             let mut output = mem::uninitialized();
             check_errors(vk.CreateFramebuffer(device.internal_object(),
                                               &infos,
-                                              ptr::null(),
+                                              device.allocator_ptr(),
                                               &mut output))?;
             output
         };
@@ -445,7 +445,9 @@ impl<Rp, A> Drop for Framebuffer<Rp, A> {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyFramebuffer(self.device.internal_object(), self.framebuffer, ptr::null());
+            vk.DestroyFramebuffer(self.device.internal_object(),
+                                  self.framebuffer,
+                                  self.device.allocator_ptr());
         }
     }
 }
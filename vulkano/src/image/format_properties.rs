@@ -0,0 +1,592 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Querying the limits of a physical device for a combination of image parameters.
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+
+use Error;
+use OomError;
+use VulkanObject;
+use check_errors;
+use format::Format;
+use image::ImageUsage;
+use instance::DeviceExtensions;
+use instance::PhysicalDevice;
+use instance::Version;
+use memory::ExternalMemoryHandleType;
+use memory::ExternalMemoryProperties;
+use vk;
+
+/// Number of dimensions of an image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ImageType {
+    Dim1d = vk::IMAGE_TYPE_1D,
+    Dim2d = vk::IMAGE_TYPE_2D,
+    Dim3d = vk::IMAGE_TYPE_3D,
+}
+
+/// Arrangement of the texels of an image in memory.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum ImageTiling {
+    /// Texels are laid out in an implementation-dependent arrangement, for more efficient memory
+    /// access.
+    Optimal = vk::IMAGE_TILING_OPTIMAL,
+    /// Texels are laid out row by row, and can be accessed directly from the host.
+    Linear = vk::IMAGE_TILING_LINEAR,
+}
+
+/// Additional parameters of an image.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ImageCreateFlags {
+    /// The image is backed by sparse memory binding.
+    pub sparse_binding: bool,
+    /// The image can be partially backed by memory. Requires `sparse_binding`.
+    pub sparse_residency: bool,
+    /// The image can be backed by memory that is also bound to another resource. Requires
+    /// `sparse_binding`.
+    pub sparse_aliased: bool,
+    /// Image views can use a different format than the image.
+    pub mutable_format: bool,
+    /// Cube image views can be created from the image.
+    pub cube_compatible: bool,
+    /// 2D array image views can be created from the image, which must be a 3D image.
+    pub array_2d_compatible: bool,
+    /// Each plane of a multi-planar format can be bound to separate memory.
+    pub disjoint: bool,
+}
+
+impl ImageCreateFlags {
+    /// Builds an `ImageCreateFlags` with all fields set to `false`.
+    #[inline]
+    pub fn none() -> ImageCreateFlags {
+        ImageCreateFlags {
+            sparse_binding: false,
+            sparse_residency: false,
+            sparse_aliased: false,
+            mutable_format: false,
+            cube_compatible: false,
+            array_2d_compatible: false,
+            disjoint: false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan_bits(self) -> vk::ImageCreateFlags {
+        let mut result = 0;
+        if self.sparse_binding {
+            result |= vk::IMAGE_CREATE_SPARSE_BINDING_BIT;
+        }
+        if self.sparse_residency {
+            result |= vk::IMAGE_CREATE_SPARSE_RESIDENCY_BIT;
+        }
+        if self.sparse_aliased {
+            result |= vk::IMAGE_CREATE_SPARSE_ALIASED_BIT;
+        }
+        if self.mutable_format {
+            result |= vk::IMAGE_CREATE_MUTABLE_FORMAT_BIT;
+        }
+        if self.cube_compatible {
+            result |= vk::IMAGE_CREATE_CUBE_COMPATIBLE_BIT;
+        }
+        if self.array_2d_compatible {
+            result |= vk::IMAGE_CREATE_2D_ARRAY_COMPATIBLE_BIT_KHR;
+        }
+        if self.disjoint {
+            result |= vk::IMAGE_CREATE_DISJOINT_BIT;
+        }
+        result
+    }
+}
+
+/// Combination of image parameters to pass to `PhysicalDevice::image_format_properties`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageFormatInfo {
+    /// Format of the image.
+    pub format: Format,
+    /// Number of dimensions of the image.
+    pub ty: ImageType,
+    /// Tiling of the image.
+    pub tiling: ImageTiling,
+    /// How the image is going to be used.
+    pub usage: ImageUsage,
+    /// Additional parameters of the image.
+    pub flags: ImageCreateFlags,
+    /// If `Some`, also queries whether the memory of the image can be shared with this handle
+    /// type.
+    pub external_memory_handle_type: Option<ExternalMemoryHandleType>,
+}
+
+/// Limits of a physical device for a combination of image parameters, as returned by
+/// `PhysicalDevice::image_format_properties`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ImageFormatProperties {
+    /// Maximum width, height and depth of the image.
+    pub max_extent: [u32; 3],
+    /// Maximum number of mipmap levels of the image.
+    pub max_mip_levels: u32,
+    /// Maximum number of array layers of the image.
+    pub max_array_layers: u32,
+    /// Bitmask of the supported numbers of samples per pixel. Use `supports_samples` to check
+    /// for a specific number.
+    pub sample_counts: u32,
+    /// Upper bound of the total size of the image in bytes, including all of its subresources.
+    pub max_resource_size: u64,
+    /// How the memory of the image can be shared, if `external_memory_handle_type` was
+    /// specified.
+    pub external_memory: Option<ExternalMemoryProperties>,
+    /// Number of combined image sampler descriptors that a YCbCr image of this format uses.
+    /// `None` if neither Vulkan 1.1 nor the `VK_KHR_sampler_ycbcr_conversion` extension is
+    /// supported by the physical device.
+    pub combined_image_sampler_descriptor_count: Option<u32>,
+}
+
+impl ImageFormatProperties {
+    /// Returns true if images can be created with `num_samples` samples per pixel.
+    #[inline]
+    pub fn supports_samples(&self, num_samples: u32) -> bool {
+        num_samples.is_power_of_two() && (self.sample_counts & num_samples) != 0
+    }
+
+    #[inline]
+    fn from_vulkan(props: &vk::ImageFormatProperties,
+                   external_memory: Option<ExternalMemoryProperties>,
+                   combined_image_sampler_descriptor_count: Option<u32>)
+                   -> ImageFormatProperties {
+        ImageFormatProperties {
+            max_extent: [
+                props.maxExtent.width,
+                props.maxExtent.height,
+                props.maxExtent.depth,
+            ],
+            max_mip_levels: props.maxMipLevels,
+            max_array_layers: props.maxArrayLayers,
+            sample_counts: props.sampleCounts,
+            max_resource_size: props.maxResourceSize,
+            external_memory: external_memory,
+            combined_image_sampler_descriptor_count: combined_image_sampler_descriptor_count,
+        }
+    }
+}
+
+/// Requirements of a depth image, to pass to `PhysicalDevice::best_depth_format`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct DepthFormatRequirements {
+    /// The format must have a stencil component.
+    pub stencil: bool,
+    /// The image must be sampled from a shader, for example for shadow mapping.
+    pub sampled: bool,
+    /// Number of samples per pixel of the image.
+    pub samples: u32,
+}
+
+impl DepthFormatRequirements {
+    /// Builds a `DepthFormatRequirements` for a depth attachment without stencil, that isn't
+    /// sampled and has one sample per pixel.
+    #[inline]
+    pub fn none() -> DepthFormatRequirements {
+        DepthFormatRequirements {
+            stencil: false,
+            sampled: false,
+            samples: 1,
+        }
+    }
+}
+
+/// Depth formats in the order of preference that `PhysicalDevice::best_depth_format` uses, from
+/// the most precise to the least precise.
+pub const DEFAULT_DEPTH_FORMATS: [Format; 6] = [
+    Format::D32Sfloat,
+    Format::D32Sfloat_S8Uint,
+    Format::D24Unorm_S8Uint,
+    Format::X8_D24UnormPack32,
+    Format::D16Unorm,
+    Format::D16Unorm_S8Uint,
+];
+
+impl<'a> PhysicalDevice<'a> {
+    /// Returns the first format of `DEFAULT_DEPTH_FORMATS` that the physical device supports as
+    /// an optimally-tiled depth attachment with the given requirements, or `None` if there isn't
+    /// any.
+    ///
+    /// Only `D16Unorm` is guaranteed to be supported for depth attachments, and either
+    /// `D24Unorm_S8Uint` or `D32Sfloat_S8Uint` for depth-stencil attachments. Always using one of
+    /// these formats wastes precision on devices that support better ones.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vulkano::image::DepthFormatRequirements;
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::InstanceExtensions;
+    /// use vulkano::instance::PhysicalDevice;
+    ///
+    /// let instance = Instance::new(None, &InstanceExtensions::none(), None).unwrap();
+    /// let physical = PhysicalDevice::enumerate(&instance).next().unwrap();
+    ///
+    /// let requirements = DepthFormatRequirements {
+    ///     stencil: true,
+    ///     .. DepthFormatRequirements::none()
+    /// };
+    /// let format = physical.best_depth_format(requirements).unwrap()
+    ///     .expect("no depth-stencil format supported");
+    /// ```
+    #[inline]
+    pub fn best_depth_format(&self, requirements: DepthFormatRequirements)
+                             -> Result<Option<Format>, OomError> {
+        self.best_depth_format_among(requirements, &DEFAULT_DEPTH_FORMATS)
+    }
+
+    /// Same as `best_depth_format`, but chooses among `candidates`, in that order of preference.
+    ///
+    /// Candidates that don't have a depth component, or that don't have a stencil component
+    /// while `requirements.stencil` is true, are ignored.
+    pub fn best_depth_format_among(&self, requirements: DepthFormatRequirements,
+                                   candidates: &[Format])
+                                   -> Result<Option<Format>, OomError> {
+        let usage = ImageUsage {
+            depth_stencil_attachment: true,
+            sampled: requirements.sampled,
+            ..ImageUsage::none()
+        };
+
+        for &format in candidates {
+            if !format.has_depth() || (requirements.stencil && !format.has_stencil()) {
+                continue;
+            }
+
+            let info = ImageFormatInfo {
+                format: format,
+                ty: ImageType::Dim2d,
+                tiling: ImageTiling::Optimal,
+                usage: usage,
+                flags: ImageCreateFlags::none(),
+                external_memory_handle_type: None,
+            };
+
+            match self.image_format_properties(&info) {
+                Ok(props) => {
+                    if props.supports_samples(requirements.samples) {
+                        return Ok(Some(format));
+                    }
+                },
+                Err(ImageFormatPropertiesError::FormatNotSupported) => (),
+                Err(ImageFormatPropertiesError::OomError(err)) => return Err(err),
+                Err(ImageFormatPropertiesError::ExtensionNotEnabled) => unreachable!(),
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the limits of the physical device for images created with the parameters of
+    /// `info`.
+    ///
+    /// This can be used to check whether an unusual combination of parameters is supported
+    /// before creating an image. Returns `FormatNotSupported` if the combination isn't supported
+    /// at all.
+    ///
+    /// Querying external memory requires the `VK_KHR_external_memory_capabilities` extension, or
+    /// Vulkan 1.1, on the instance.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vulkano::format::Format;
+    /// use vulkano::image::ImageCreateFlags;
+    /// use vulkano::image::ImageFormatInfo;
+    /// use vulkano::image::ImageTiling;
+    /// use vulkano::image::ImageType;
+    /// use vulkano::image::ImageUsage;
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::InstanceExtensions;
+    /// use vulkano::instance::PhysicalDevice;
+    ///
+    /// let instance = Instance::new(None, &InstanceExtensions::none(), None).unwrap();
+    /// let physical = PhysicalDevice::enumerate(&instance).next().unwrap();
+    ///
+    /// let props = physical.image_format_properties(&ImageFormatInfo {
+    ///     format: Format::R16G16B16A16Sfloat,
+    ///     ty: ImageType::Dim2d,
+    ///     tiling: ImageTiling::Optimal,
+    ///     usage: ImageUsage { storage: true, .. ImageUsage::none() },
+    ///     flags: ImageCreateFlags::none(),
+    ///     external_memory_handle_type: None,
+    /// }).unwrap();
+    /// println!("Up to {}x{} storage images", props.max_extent[0], props.max_extent[1]);
+    /// ```
+    pub fn image_format_properties(&self, info: &ImageFormatInfo)
+                                   -> Result<ImageFormatProperties, ImageFormatPropertiesError> {
+        let vk = self.instance().pointers();
+        let usage = info.usage.to_usage_bits();
+        let flags = info.flags.into_vulkan_bits();
+
+        if !self.supports_properties2() {
+            if info.external_memory_handle_type.is_some() {
+                return Err(ImageFormatPropertiesError::ExtensionNotEnabled);
+            }
+
+            let props = unsafe {
+                let mut output = mem::uninitialized();
+                let r = vk.GetPhysicalDeviceImageFormatProperties(self.internal_object(),
+                                                                  info.format as u32,
+                                                                  info.ty as u32,
+                                                                  info.tiling as u32,
+                                                                  usage,
+                                                                  flags,
+                                                                  &mut output);
+                check_errors(r)?;
+                output
+            };
+
+            return Ok(ImageFormatProperties::from_vulkan(&props, None, None));
+        }
+
+        if info.external_memory_handle_type.is_some() &&
+            !self.instance().loaded_extensions().khr_external_memory_capabilities
+        {
+            return Err(ImageFormatPropertiesError::ExtensionNotEnabled);
+        }
+
+        let v1_1 = Version { major: 1, minor: 1, patch: 0 };
+        let query_ycbcr = self.api_version() >= v1_1 ||
+            DeviceExtensions::supported_by_device(*self).khr_sampler_ycbcr_conversion;
+
+        unsafe {
+            let external_info = info.external_memory_handle_type.map(|handle_type| {
+                vk::PhysicalDeviceExternalImageFormatInfo {
+                    sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_EXTERNAL_IMAGE_FORMAT_INFO,
+                    pNext: ptr::null(),
+                    handleType: handle_type as u32,
+                }
+            });
+
+            let format_info = vk::PhysicalDeviceImageFormatInfo2KHR {
+                sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_IMAGE_FORMAT_INFO_2_KHR,
+                pNext: external_info
+                    .as_ref()
+                    .map(|i| i as *const _ as *const _)
+                    .unwrap_or(ptr::null()),
+                format: info.format as u32,
+                imageType: info.ty as u32,
+                tiling: info.tiling as u32,
+                usage: usage,
+                flags: flags,
+            };
+
+            let mut ycbcr_props = vk::SamplerYcbcrConversionImageFormatProperties {
+                sType: vk::STRUCTURE_TYPE_SAMPLER_YCBCR_CONVERSION_IMAGE_FORMAT_PROPERTIES,
+                pNext: ptr::null_mut(),
+                combinedImageSamplerDescriptorCount: 0,
+            };
+
+            let mut external_props = vk::ExternalImageFormatProperties {
+                sType: vk::STRUCTURE_TYPE_EXTERNAL_IMAGE_FORMAT_PROPERTIES,
+                pNext: ptr::null_mut(),
+                externalMemoryProperties: mem::zeroed(),
+            };
+
+            // Chaining the output structures that the physical device knows about.
+            let mut next: *mut c_void = ptr::null_mut();
+            if query_ycbcr {
+                ycbcr_props.pNext = next;
+                next = &mut ycbcr_props as *mut _ as *mut _;
+            }
+            if external_info.is_some() {
+                external_props.pNext = next;
+                next = &mut external_props as *mut _ as *mut _;
+            }
+
+            let mut output = vk::ImageFormatProperties2KHR {
+                sType: vk::STRUCTURE_TYPE_IMAGE_FORMAT_PROPERTIES_2_KHR,
+                pNext: next as *const _,
+                imageFormatProperties: mem::uninitialized(),
+            };
+
+            let r = vk.GetPhysicalDeviceImageFormatProperties2KHR(self.internal_object(),
+                                                                  &format_info,
+                                                                  &mut output);
+            check_errors(r)?;
+
+            let external_memory = if external_info.is_some() {
+                Some(ExternalMemoryProperties::from_vulkan(&external_props
+                                                               .externalMemoryProperties))
+            } else {
+                None
+            };
+
+            let descriptor_count = if query_ycbcr {
+                Some(ycbcr_props.combinedImageSamplerDescriptorCount)
+            } else {
+                None
+            };
+
+            Ok(ImageFormatProperties::from_vulkan(&output.imageFormatProperties,
+                                                  external_memory,
+                                                  descriptor_count))
+        }
+    }
+}
+
+/// Error that can happen when querying the properties of an image format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ImageFormatPropertiesError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The combination of parameters isn't supported by the physical device.
+    FormatNotSupported,
+    /// External memory was queried, but the `VK_KHR_external_memory_capabilities` extension
+    /// isn't enabled on the instance.
+    ExtensionNotEnabled,
+}
+
+impl error::Error for ImageFormatPropertiesError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ImageFormatPropertiesError::OomError(_) => "not enough memory",
+            ImageFormatPropertiesError::FormatNotSupported => {
+                "the combination of parameters isn't supported by the physical device"
+            },
+            ImageFormatPropertiesError::ExtensionNotEnabled => {
+                "the `VK_KHR_external_memory_capabilities` extension isn't enabled"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ImageFormatPropertiesError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ImageFormatPropertiesError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for ImageFormatPropertiesError {
+    #[inline]
+    fn from(err: OomError) -> ImageFormatPropertiesError {
+        ImageFormatPropertiesError::OomError(err)
+    }
+}
+
+impl From<Error> for ImageFormatPropertiesError {
+    #[inline]
+    fn from(err: Error) -> ImageFormatPropertiesError {
+        match err {
+            err @ Error::OutOfHostMemory => ImageFormatPropertiesError::OomError(err.into()),
+            err @ Error::OutOfDeviceMemory => ImageFormatPropertiesError::OomError(err.into()),
+            Error::FormatNotSupported => ImageFormatPropertiesError::FormatNotSupported,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use image::DepthFormatRequirements;
+    use image::ImageCreateFlags;
+    use image::ImageFormatInfo;
+    use image::ImageFormatPropertiesError;
+    use image::ImageTiling;
+    use image::ImageType;
+    use image::ImageUsage;
+    use instance::PhysicalDevice;
+    use memory::ExternalMemoryHandleType;
+
+    fn color_info() -> ImageFormatInfo {
+        ImageFormatInfo {
+            format: Format::R8G8B8A8Unorm,
+            ty: ImageType::Dim2d,
+            tiling: ImageTiling::Optimal,
+            usage: ImageUsage {
+                sampled: true,
+                transfer_destination: true,
+                ..ImageUsage::none()
+            },
+            flags: ImageCreateFlags::none(),
+            external_memory_handle_type: None,
+        }
+    }
+
+    #[test]
+    fn basic_query() {
+        let instance = instance!();
+
+        for physical in PhysicalDevice::enumerate(&instance) {
+            // `R8G8B8A8Unorm` is required to support sampling with optimal tiling.
+            let props = physical.image_format_properties(&color_info()).unwrap();
+            assert!(props.max_extent[0] >= 1);
+            assert!(props.max_mip_levels >= 1);
+            assert!(props.max_array_layers >= 1);
+            assert!(props.supports_samples(1));
+            assert!(props.external_memory.is_none());
+        }
+    }
+
+    #[test]
+    fn best_depth_format() {
+        let instance = instance!();
+
+        for physical in PhysicalDevice::enumerate(&instance) {
+            // `D16Unorm` is required to be supported as a depth attachment.
+            let format = physical
+                .best_depth_format(DepthFormatRequirements::none())
+                .unwrap()
+                .unwrap();
+            assert!(format.has_depth());
+
+            let requirements = DepthFormatRequirements {
+                stencil: true,
+                ..DepthFormatRequirements::none()
+            };
+            let format = physical.best_depth_format(requirements).unwrap().unwrap();
+            assert!(format.has_stencil());
+
+            let only_d16 = physical
+                .best_depth_format_among(DepthFormatRequirements::none(),
+                                         &[Format::S8Uint, Format::D16Unorm])
+                .unwrap();
+            assert_eq!(only_d16, Some(Format::D16Unorm));
+        }
+    }
+
+    #[test]
+    fn external_memory_query() {
+        let instance = instance!();
+
+        for physical in PhysicalDevice::enumerate(&instance) {
+            let info = ImageFormatInfo {
+                external_memory_handle_type: Some(ExternalMemoryHandleType::OpaqueFd),
+                ..color_info()
+            };
+
+            match physical.image_format_properties(&info) {
+                Ok(props) => assert!(props.external_memory.is_some()),
+                Err(ImageFormatPropertiesError::ExtensionNotEnabled) |
+                Err(ImageFormatPropertiesError::FormatNotSupported) => (),
+                Err(err) => panic!("{:?}", err),
+            }
+        }
+    }
+}
@@ -27,6 +27,7 @@ use vk;
 /// Transitionning between layouts can only be done through a GPU-side operation that is part of
 /// a command buffer.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum ImageLayout {
     Undefined = vk::IMAGE_LAYOUT_UNDEFINED,
@@ -49,6 +49,14 @@
 use std::cmp;
 
 pub use self::attachment::AttachmentImage;
+pub use self::format_properties::DEFAULT_DEPTH_FORMATS;
+pub use self::format_properties::DepthFormatRequirements;
+pub use self::format_properties::ImageCreateFlags;
+pub use self::format_properties::ImageFormatInfo;
+pub use self::format_properties::ImageFormatProperties;
+pub use self::format_properties::ImageFormatPropertiesError;
+pub use self::format_properties::ImageTiling;
+pub use self::format_properties::ImageType;
 pub use self::immutable::ImmutableImage;
 pub use self::layout::ImageLayout;
 pub use self::storage::StorageImage;
@@ -60,6 +68,7 @@ pub use self::traits::ImageViewAccess;
 pub use self::usage::ImageUsage;
 
 pub mod attachment; // TODO: make private
+mod format_properties;
 pub mod immutable; // TODO: make private
 mod layout;
 mod storage;
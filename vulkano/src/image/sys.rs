@@ -30,6 +30,7 @@ use image::MipmapsCount;
 use image::ViewType;
 use memory::DeviceMemory;
 use memory::DeviceMemoryAllocError;
+use memory::ExternalMemoryHandleTypes;
 use memory::MemoryRequirements;
 use sync::Sharing;
 
@@ -65,6 +66,9 @@ pub struct UnsafeImage {
     // Features that are supported for this particular format.
     format_features: vk::FormatFeatureFlagBits,
 
+    // True if views of the image can have a different format than the image.
+    mutable_format: bool,
+
     // `vkDestroyImage` is called only if `needs_destruction` is true.
     needs_destruction: bool,
 }
@@ -100,14 +104,100 @@ impl UnsafeImage {
                               mipmaps.into(),
                               sharing,
                               linear_tiling,
-                              preinitialized_layout)
+                              preinitialized_layout,
+                              false,
+                              ExternalMemoryHandleTypes::none())
+    }
+
+    /// Same as `new`, but views of the image can have a different format than the image, as long
+    /// as it has the same block size. See `UnsafeImageView::raw_with_format`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the dimensions is 0.
+    /// - Panics if the number of mipmaps is 0.
+    /// - Panics if the number of samples is 0.
+    ///
+    #[inline]
+    pub unsafe fn new_mutable_format<'a, Mi, I>(device: Arc<Device>, usage: ImageUsage,
+                                                format: Format, dimensions: ImageDimensions,
+                                                num_samples: u32, mipmaps: Mi,
+                                                sharing: Sharing<I>, linear_tiling: bool,
+                                                preinitialized_layout: bool)
+                                                -> Result<(UnsafeImage, MemoryRequirements),
+                                                          ImageCreationError>
+        where Mi: Into<MipmapsCount>,
+              I: Iterator<Item = u32>
+    {
+        let sharing = match sharing {
+            Sharing::Exclusive => (vk::SHARING_MODE_EXCLUSIVE, SmallVec::<[u32; 8]>::new()),
+            Sharing::Concurrent(ids) => (vk::SHARING_MODE_CONCURRENT, ids.collect()),
+        };
+
+        UnsafeImage::new_impl(device,
+                              usage,
+                              format,
+                              dimensions,
+                              num_samples,
+                              mipmaps.into(),
+                              sharing,
+                              linear_tiling,
+                              preinitialized_layout,
+                              true,
+                              ExternalMemoryHandleTypes::none())
+    }
+
+    /// Same as `new`, but the memory of the image can be exported as the handle types of
+    /// `handle_types`. The memory must be allocated with `DeviceMemory::alloc_exportable` with
+    /// the same handle types.
+    ///
+    /// Requires the `VK_KHR_external_memory` extension.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if one of the dimensions is 0.
+    /// - Panics if the number of mipmaps is 0.
+    /// - Panics if the number of samples is 0.
+    ///
+    #[inline]
+    pub unsafe fn new_exportable<'a, Mi, I>(device: Arc<Device>, usage: ImageUsage,
+                                            format: Format, dimensions: ImageDimensions,
+                                            num_samples: u32, mipmaps: Mi, sharing: Sharing<I>,
+                                            linear_tiling: bool, preinitialized_layout: bool,
+                                            handle_types: ExternalMemoryHandleTypes)
+                                            -> Result<(UnsafeImage, MemoryRequirements),
+                                                      ImageCreationError>
+        where Mi: Into<MipmapsCount>,
+              I: Iterator<Item = u32>
+    {
+        if !device.loaded_extensions().khr_external_memory {
+            return Err(ImageCreationError::ExternalMemoryExtensionNotEnabled);
+        }
+
+        let sharing = match sharing {
+            Sharing::Exclusive => (vk::SHARING_MODE_EXCLUSIVE, SmallVec::<[u32; 8]>::new()),
+            Sharing::Concurrent(ids) => (vk::SHARING_MODE_CONCURRENT, ids.collect()),
+        };
+
+        UnsafeImage::new_impl(device,
+                              usage,
+                              format,
+                              dimensions,
+                              num_samples,
+                              mipmaps.into(),
+                              sharing,
+                              linear_tiling,
+                              preinitialized_layout,
+                              false,
+                              handle_types)
     }
 
     // Non-templated version to avoid inlining and improve compile times.
     unsafe fn new_impl(device: Arc<Device>, usage: ImageUsage, format: Format,
                        dimensions: ImageDimensions, num_samples: u32, mipmaps: MipmapsCount,
                        (sh_mode, sh_indices): (vk::SharingMode, SmallVec<[u32; 8]>),
-                       linear_tiling: bool, preinitialized_layout: bool)
+                       linear_tiling: bool, preinitialized_layout: bool, mutable_format: bool,
+                       external_handle_types: ExternalMemoryHandleTypes)
                        -> Result<(UnsafeImage, MemoryRequirements), ImageCreationError> {
         // TODO: doesn't check that the proper features are enabled
 
@@ -384,6 +474,12 @@ impl UnsafeImage {
             },
         };
 
+        let flags = if mutable_format {
+            flags | vk::IMAGE_CREATE_MUTABLE_FORMAT_BIT
+        } else {
+            flags
+        };
+
         // Checking the dimensions against the limits.
         if array_layers > device.physical_device().limits().max_image_array_layers() {
             let err = ImageCreationError::UnsupportedDimensions { dimensions: dimensions };
@@ -441,7 +537,7 @@ impl UnsafeImage {
                                                                 ty,
                                                                 tiling,
                                                                 usage,
-                                                                0, /* TODO */
+                                                                flags,
                                                                 &mut output);
 
             match check_errors(r) {
@@ -463,9 +559,22 @@ impl UnsafeImage {
 
         // Everything now ok. Creating the image.
         let image = {
+            let external_infos = if external_handle_types != ExternalMemoryHandleTypes::none() {
+                Some(vk::ExternalMemoryImageCreateInfo {
+                         sType: vk::STRUCTURE_TYPE_EXTERNAL_MEMORY_IMAGE_CREATE_INFO,
+                         pNext: ptr::null(),
+                         handleTypes: external_handle_types.into_vulkan_bits(),
+                     })
+            } else {
+                None
+            };
+
             let infos = vk::ImageCreateInfo {
                 sType: vk::STRUCTURE_TYPE_IMAGE_CREATE_INFO,
-                pNext: ptr::null(),
+                pNext: external_infos
+                    .as_ref()
+                    .map(|i| i as *const vk::ExternalMemoryImageCreateInfo as *const _)
+                    .unwrap_or(ptr::null()),
                 flags: flags,
                 imageType: ty,
                 format: format as u32,
@@ -492,7 +601,7 @@ impl UnsafeImage {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateImage(device.internal_object(),
                                         &infos,
-                                        ptr::null(),
+                                        device.allocator_ptr(),
                                         &mut output))?;
             output
         };
@@ -529,8 +638,12 @@ impl UnsafeImage {
 
             let mut out = MemoryRequirements::from_vulkan_reqs(output.memoryRequirements);
             if let Some(output2) = output2 {
-                debug_assert_eq!(output2.requiresDedicatedAllocation, 0);
-                out.prefer_dedicated = output2.prefersDedicatedAllocation != 0;
+                // Only resources whose memory is shared with other APIs may require a dedicated
+                // allocation.
+                debug_assert!(output2.requiresDedicatedAllocation == 0 ||
+                                  external_handle_types != ExternalMemoryHandleTypes::none());
+                out.prefer_dedicated = output2.prefersDedicatedAllocation != 0 ||
+                    output2.requiresDedicatedAllocation != 0;
             }
             out
 
@@ -550,6 +663,7 @@ impl UnsafeImage {
             samples: num_samples,
             mipmaps: mipmaps,
             format_features: format_features,
+            mutable_format: mutable_format,
             needs_destruction: true,
         };
 
@@ -579,6 +693,7 @@ impl UnsafeImage {
             samples: samples,
             mipmaps: mipmaps,
             format_features: output.optimalTilingFeatures,
+            mutable_format: false,
             needs_destruction: false, // TODO: pass as parameter
         }
     }
@@ -629,6 +744,13 @@ impl UnsafeImage {
         self.samples
     }
 
+    /// Returns true if the image was created with `new_mutable_format`, in which case its views
+    /// can have a different format than the image.
+    #[inline]
+    pub fn mutable_format(&self) -> bool {
+        self.mutable_format
+    }
+
     /// Returns a key unique to each `UnsafeImage`. Can be used for the `conflicts_key` method.
     #[inline]
     pub fn key(&self) -> u64 {
@@ -807,7 +929,7 @@ impl Drop for UnsafeImage {
 
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyImage(self.device.internal_object(), self.image, ptr::null());
+            vk.DestroyImage(self.device.internal_object(), self.image, self.device.allocator_ptr());
         }
     }
 }
@@ -832,6 +954,9 @@ pub enum ImageCreationError {
     UnsupportedUsage,
     /// The `shader_storage_image_multisample` feature must be enabled to create such an image.
     ShaderStorageImageMultisampleFeatureNotEnabled,
+    /// An exportable image was requested but the `VK_KHR_external_memory` extension isn't
+    /// enabled.
+    ExternalMemoryExtensionNotEnabled,
 }
 
 impl error::Error for ImageCreationError {
@@ -854,6 +979,10 @@ impl error::Error for ImageCreationError {
                 "the `shader_storage_image_multisample` feature must be enabled to create such \
                  an image"
             },
+            ImageCreationError::ExternalMemoryExtensionNotEnabled => {
+                "an exportable image was requested but the `VK_KHR_external_memory` extension \
+                 isn't enabled"
+            },
         }
     }
 
@@ -932,11 +1061,36 @@ pub struct UnsafeImageView {
 
 impl UnsafeImageView {
     /// See the docs of new().
+    #[inline]
     pub unsafe fn raw(image: &UnsafeImage, ty: ViewType, mipmap_levels: Range<u32>,
                       array_layers: Range<u32>)
                       -> Result<UnsafeImageView, OomError> {
+        UnsafeImageView::raw_with_format(image, ty, mipmap_levels, array_layers, image.format)
+    }
+
+    /// Same as `raw`, but the view uses `format` instead of the format of the image.
+    ///
+    /// # Panic
+    ///
+    /// - Panics for the same reasons as `new`.
+    /// - Panics if `format` is different from the format of the image and the image wasn't
+    ///   created with `UnsafeImage::new_mutable_format`.
+    /// - Panics if `format` doesn't have the same block size and block extent as the format of
+    ///   the image, or if either of them is a depth or stencil format.
+    ///
+    pub unsafe fn raw_with_format(image: &UnsafeImage, ty: ViewType, mipmap_levels: Range<u32>,
+                                  array_layers: Range<u32>, format: Format)
+                                  -> Result<UnsafeImageView, OomError> {
         let vk = image.device.pointers();
 
+        if format != image.format {
+            assert!(image.mutable_format);
+            assert!(!format.ty().is_depth_and_or_stencil());
+            assert!(!image.format.ty().is_depth_and_or_stencil());
+            assert_eq!(format.block_size(), image.format.block_size());
+            assert_eq!(format.block_extent(), image.format.block_extent());
+        }
+
         assert!(mipmap_levels.end > mipmap_levels.start);
         assert!(mipmap_levels.end <= image.mipmaps);
         assert!(array_layers.end > array_layers.start);
@@ -979,7 +1133,7 @@ impl UnsafeImageView {
                 flags: 0, // reserved
                 image: image.internal_object(),
                 viewType: view_type,
-                format: image.format as u32,
+                format: format as u32,
                 components: vk::ComponentMapping {
                     r: 0,
                     g: 0,
@@ -998,7 +1152,7 @@ impl UnsafeImageView {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateImageView(image.device.internal_object(),
                                             &infos,
-                                            ptr::null(),
+                                            image.device.allocator_ptr(),
                                             &mut output))?;
             output
         };
@@ -1008,10 +1162,43 @@ impl UnsafeImageView {
                device: image.device.clone(),
                usage: image.usage,
                identity_swizzle: true, // FIXME:
-               format: image.format,
+               format: format,
            })
     }
 
+    /// Creates an sRGB view and a `Unorm` view of the same image, in that order.
+    ///
+    /// This is useful for user interfaces, which often need to blend in sRGB space while sampling
+    /// in linear space, or the other way around.
+    ///
+    /// # Panic
+    ///
+    /// - Panics for the same reasons as `new`.
+    /// - Panics if the format of the image doesn't have both an sRGB and a `Unorm` variant.
+    /// - Panics if the image wasn't created with `UnsafeImage::new_mutable_format`.
+    ///
+    pub unsafe fn srgb_and_unorm(image: &UnsafeImage, ty: ViewType, mipmap_levels: Range<u32>,
+                                 array_layers: Range<u32>)
+                                 -> Result<(UnsafeImageView, UnsafeImageView), OomError> {
+        let srgb = image
+            .format
+            .srgb_equivalent()
+            .expect("the format of the image doesn't have an sRGB variant");
+        let unorm = image
+            .format
+            .unorm_equivalent()
+            .expect("the format of the image doesn't have a Unorm variant");
+
+        let srgb_view = UnsafeImageView::raw_with_format(image,
+                                                         ty,
+                                                         mipmap_levels.clone(),
+                                                         array_layers.clone(),
+                                                         srgb)?;
+        let unorm_view =
+            UnsafeImageView::raw_with_format(image, ty, mipmap_levels, array_layers, unorm)?;
+        Ok((srgb_view, unorm_view))
+    }
+
     /// Creates a new view from an image.
     ///
     /// Note that you must create the view with identity swizzling if you want to use this view
@@ -1034,6 +1221,28 @@ impl UnsafeImageView {
         UnsafeImageView::raw(image, ty, mipmap_levels, array_layers).unwrap()
     }
 
+    /// Builds an `UnsafeImageView` from a raw image view handle, for example one that was created
+    /// by another library.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid image view that was created from `device`, with the allocation
+    ///   callbacks of `device` if it has any.
+    /// - `format` must be the format of the view, and `usage` the usage of its image.
+    /// - The view must use identity swizzling.
+    /// - The `UnsafeImageView` takes ownership of `handle` and destroys it when it is dropped.
+    ///
+    pub unsafe fn from_raw(device: Arc<Device>, handle: u64, format: Format, usage: ImageUsage)
+                           -> UnsafeImageView {
+        UnsafeImageView {
+            view: handle,
+            device: device,
+            usage: usage.to_usage_bits(),
+            identity_swizzle: true,
+            format: format,
+        }
+    }
+
     #[inline]
     pub fn format(&self) -> Format {
         self.format
@@ -1103,7 +1312,9 @@ impl Drop for UnsafeImageView {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyImageView(self.device.internal_object(), self.view, ptr::null());
+            vk.DestroyImageView(self.device.internal_object(),
+                                self.view,
+                                self.device.allocator_ptr());
         }
     }
 }
@@ -1116,9 +1327,12 @@ mod tests {
     use super::ImageCreationError;
     use super::ImageUsage;
     use super::UnsafeImage;
+    use super::UnsafeImageView;
 
     use format::Format;
     use image::ImageDimensions;
+    use image::ViewType;
+    use memory::DeviceMemory;
     use sync::Sharing;
 
     #[test]
@@ -1441,4 +1655,47 @@ mod tests {
             _ => panic!(),
         };
     }
+
+    #[test]
+    fn srgb_and_unorm_views() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let usage = ImageUsage {
+            sampled: true,
+            ..ImageUsage::none()
+        };
+
+        let (img, mem_reqs) = unsafe {
+            UnsafeImage::new_mutable_format(device.clone(),
+                                            usage,
+                                            Format::R8G8B8A8Unorm,
+                                            ImageDimensions::Dim2d {
+                                                width: 32,
+                                                height: 32,
+                                                array_layers: 1,
+                                                cubemap_compatible: false,
+                                            },
+                                            1,
+                                            1,
+                                            Sharing::Exclusive::<Empty<_>>,
+                                            false,
+                                            false)
+        }.unwrap();
+        assert!(img.mutable_format());
+
+        let memory_type = device
+            .physical_device()
+            .memory_types()
+            .find(|t| (mem_reqs.memory_type_bits & (1 << t.id())) != 0)
+            .unwrap();
+        let memory = DeviceMemory::alloc(device.clone(), memory_type, mem_reqs.size).unwrap();
+
+        unsafe {
+            img.bind_memory(&memory, 0).unwrap();
+            let (srgb, unorm) =
+                UnsafeImageView::srgb_and_unorm(&img, ViewType::Dim2d, 0 .. 1, 0 .. 1).unwrap();
+            assert_eq!(srgb.format(), Format::R8G8B8A8Srgb);
+            assert_eq!(unorm.format(), Format::R8G8B8A8Unorm);
+        }
+    }
 }
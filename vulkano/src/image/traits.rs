@@ -10,6 +10,7 @@
 use buffer::BufferAccess;
 use format::ClearValue;
 use format::Format;
+use format::PossibleCompressedFormatDesc;
 use format::PossibleDepthFormatDesc;
 use format::PossibleDepthStencilFormatDesc;
 use format::PossibleFloatFormatDesc;
@@ -37,11 +38,11 @@ pub unsafe trait ImageAccess {
         self.inner().image.format()
     }
 
-    /// Returns true if the image is a color image.
+    /// Returns true if the image is a color image. Compressed images are color images.
     #[inline]
     fn has_color(&self) -> bool {
         let format = self.format();
-        format.is_float() || format.is_uint() || format.is_sint()
+        format.is_float() || format.is_uint() || format.is_sint() || format.is_compressed()
     }
 
     /// Returns true if the image has a depth component. In other words, if it is a depth or a
@@ -36,6 +36,55 @@
 //! be callable. If you don't store the return value of `DebugCallback`'s constructor in a
 //! variable, it will be immediately destroyed and your callback will not work.
 //!
+//! # Debug utils messenger
+//!
+//! If the `VK_EXT_debug_utils` extension is enabled, a `DebugUtilsMessenger` can be used instead
+//! of a `DebugCallback`. The messages are filtered by severity and by type, and contain the
+//! names of the objects and the labels of the queue and of the command buffer that they refer
+//! to.
+//!
+//! ```
+//! # use vulkano::instance::Instance;
+//! # use std::sync::Arc;
+//! # let instance: Arc<Instance> = return;
+//! use vulkano::instance::debug::DebugUtilsMessenger;
+//! use vulkano::instance::debug::MessageSeverity;
+//! use vulkano::instance::debug::MessageType;
+//!
+//! let severity = MessageSeverity { information: true, .. MessageSeverity::errors_and_warnings() };
+//! let _messenger = DebugUtilsMessenger::new(&instance, severity, MessageType::all(), |msg| {
+//!     println!("{:?} {}: {}", msg.severity, msg.message_id_name.unwrap_or(""), msg.description);
+//! }).ok();
+//! ```
+//!
+//! To also receive the messages emitted while the instance is created and destroyed, register
+//! the messenger with `Instance::with_debug_messenger` instead.
+//!
+//! # Printing from shaders
+//!
+//! If the instance was created with the `debug_printf` validation feature, the calls to
+//! `debugPrintfEXT` in shaders are reported to the debug callbacks once the commands that
+//! executed them have completed. `Message::is_debug_printf` identifies these messages, and a
+//! `DebugPrintfCapture` collects them so that they can be retrieved once per frame.
+//!
+//! ```
+//! # use vulkano::instance::Instance;
+//! # use std::sync::Arc;
+//! # let instance: Arc<Instance> = return;
+//! use vulkano::instance::debug::DebugPrintfCapture;
+//!
+//! let capture = DebugPrintfCapture::new(&instance).unwrap();
+//!
+//! loop {
+//!     // ... submit the frame and wait for its fence ...
+//!
+//!     for line in capture.take() {
+//!         println!("shader: {}", line);
+//!     }
+//! #   break;
+//! }
+//! ```
+//!
 
 use std::error;
 use std::ffi::CStr;
@@ -44,7 +93,9 @@ use std::mem;
 use std::os::raw::{c_char, c_void};
 use std::panic;
 use std::ptr;
+use std::slice;
 use std::sync::Arc;
+use std::sync::Mutex;
 
 use instance::Instance;
 
@@ -152,7 +203,7 @@ impl DebugCallback {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateDebugReportCallbackEXT(instance.internal_object(),
                                                          &infos,
-                                                         ptr::null(),
+                                                         instance.allocator_ptr(),
                                                          &mut output))?;
             output
         };
@@ -183,7 +234,7 @@ impl Drop for DebugCallback {
             let vk = self.instance.pointers();
             vk.DestroyDebugReportCallbackEXT(self.instance.internal_object(),
                                              self.debug_report_callback,
-                                             ptr::null());
+                                             self.instance.allocator_ptr());
         }
     }
 }
@@ -198,6 +249,68 @@ pub struct Message<'a> {
     pub description: &'a str,
 }
 
+impl<'a> Message<'a> {
+    /// Returns true if the message contains the output of a call to `debugPrintfEXT` in a
+    /// shader.
+    #[inline]
+    pub fn is_debug_printf(&self) -> bool {
+        self.layer_prefix.contains("DEBUG-PRINTF")
+    }
+}
+
+/// Collects the output of `debugPrintfEXT` in shaders.
+///
+/// The instance must have been created with the `debug_printf` validation feature. Each call to
+/// `debugPrintfEXT` produces one message, which is available once the commands that executed the
+/// shader have completed, for example after waiting for the fence of a frame.
+///
+/// The messages are collected as long as this object is alive.
+pub struct DebugPrintfCapture {
+    messages: Arc<Mutex<Vec<String>>>,
+    _callback: DebugCallback,
+}
+
+impl DebugPrintfCapture {
+    /// Starts collecting the output of `debugPrintfEXT`.
+    pub fn new(instance: &Arc<Instance>) -> Result<DebugPrintfCapture, DebugCallbackCreationError> {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+
+        // Depending on their version, the validation layers report the messages as informational
+        // messages or as warnings.
+        let types = MessageTypes {
+            information: true,
+            warning: true,
+            ..MessageTypes::none()
+        };
+
+        let callback = {
+            let messages = messages.clone();
+            DebugCallback::new(instance, types, move |msg| if msg.is_debug_printf() {
+                messages.lock().unwrap().push(msg.description.to_owned());
+            })?
+        };
+
+        Ok(DebugPrintfCapture {
+               messages: messages,
+               _callback: callback,
+           })
+    }
+
+    /// Returns the messages collected since the last call to `take`, in the order in which they
+    /// were reported.
+    #[inline]
+    pub fn take(&self) -> Vec<String> {
+        mem::replace(&mut *self.messages.lock().unwrap(), Vec::new())
+    }
+}
+
+impl fmt::Debug for DebugPrintfCapture {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "<Debug printf capture>")
+    }
+}
+
 /// Type of message.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub struct MessageTypes {
@@ -278,3 +391,413 @@ impl From<Error> for DebugCallbackCreationError {
         panic!("unexpected error: {:?}", err)
     }
 }
+
+/// Boxed user callback of a debug utils messenger.
+///
+/// Note that we need to double-box the callback, because a `*const Fn()` is a fat pointer that
+/// can't be casted to a `*const c_void`.
+pub(crate) type DebugUtilsUserCallback = Box<Box<Fn(&DebugUtilsMessage) + Send + Sync>>;
+
+/// Registration of a callback called by validation layers, using the `VK_EXT_debug_utils`
+/// extension.
+///
+/// Contrary to `DebugCallback`, the messages are filtered by severity and by type, and they
+/// contain the labels of the queue and of the command buffer and the names of the objects that
+/// they refer to.
+///
+/// The callback can be called as long as this object is alive. To also receive the messages
+/// emitted while the instance is created or destroyed, use `Instance::with_debug_messenger`
+/// instead.
+#[must_use = "The DebugUtilsMessenger object must be kept alive for as long as you want your \
+              callback to be called"]
+pub struct DebugUtilsMessenger {
+    instance: Arc<Instance>,
+    messenger: vk::DebugUtilsMessengerEXT,
+    user_callback: DebugUtilsUserCallback,
+}
+
+impl DebugUtilsMessenger {
+    /// Initializes a debug utils messenger.
+    ///
+    /// Panics generated by calling `user_callback` are ignored.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `severity` or `ty` is empty.
+    ///
+    pub fn new<F>(instance: &Arc<Instance>, severity: MessageSeverity, ty: MessageType,
+                  user_callback: F)
+                  -> Result<DebugUtilsMessenger, DebugUtilsMessengerCreationError>
+        where F: Fn(&DebugUtilsMessage) + 'static + Send + Sync + panic::RefUnwindSafe
+    {
+        if !instance.loaded_extensions().ext_debug_utils {
+            return Err(DebugUtilsMessengerCreationError::MissingExtension);
+        }
+
+        let user_callback: DebugUtilsUserCallback = Box::new(Box::new(user_callback));
+        let infos = messenger_create_info(severity, ty, &user_callback);
+
+        let vk = instance.pointers();
+
+        let messenger = unsafe {
+            let mut output = mem::uninitialized();
+            check_errors(vk.CreateDebugUtilsMessengerEXT(instance.internal_object(),
+                                                         &infos,
+                                                         instance.allocator_ptr(),
+                                                         &mut output))?;
+            output
+        };
+
+        Ok(DebugUtilsMessenger {
+               instance: instance.clone(),
+               messenger: messenger,
+               user_callback: user_callback,
+           })
+    }
+
+    /// Initializes a debug utils messenger with errors and warnings of all types.
+    ///
+    /// Shortcut for `new(instance, MessageSeverity::errors_and_warnings(), MessageType::all(),
+    /// user_callback)`.
+    #[inline]
+    pub fn errors_and_warnings<F>(instance: &Arc<Instance>, user_callback: F)
+                                  -> Result<DebugUtilsMessenger, DebugUtilsMessengerCreationError>
+        where F: Fn(&DebugUtilsMessage) + 'static + Send + Sync + panic::RefUnwindSafe
+    {
+        DebugUtilsMessenger::new(instance,
+                                 MessageSeverity::errors_and_warnings(),
+                                 MessageType::all(),
+                                 user_callback)
+    }
+}
+
+impl Drop for DebugUtilsMessenger {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let vk = self.instance.pointers();
+            vk.DestroyDebugUtilsMessengerEXT(self.instance.internal_object(),
+                                             self.messenger,
+                                             self.instance.allocator_ptr());
+        }
+    }
+}
+
+impl fmt::Debug for DebugUtilsMessenger {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "<Vulkan debug utils messenger {:?}>", self.messenger)
+    }
+}
+
+/// Builds the `VkDebugUtilsMessengerCreateInfoEXT` that calls `user_callback`.
+///
+/// The returned struct points to `user_callback`, which must stay alive for as long as the
+/// messenger exists.
+///
+/// # Panic
+///
+/// - Panics if `severity` or `ty` is empty.
+///
+pub(crate) fn messenger_create_info(severity: MessageSeverity, ty: MessageType,
+                                    user_callback: &DebugUtilsUserCallback)
+                                    -> vk::DebugUtilsMessengerCreateInfoEXT {
+    assert!(severity != MessageSeverity::none());
+    assert!(ty != MessageType::none());
+
+    extern "system" fn callback(severity: vk::DebugUtilsMessageSeverityFlagBitsEXT,
+                                ty: vk::DebugUtilsMessageTypeFlagsEXT,
+                                data: *const vk::DebugUtilsMessengerCallbackDataEXT,
+                                user_data: *mut c_void)
+                                -> u32 {
+        // Since we box the closure, the type system doesn't detect that the `UnwindSafe`
+        // bound is enforced. Therefore we enforce it manually.
+        let _ = panic::catch_unwind(panic::AssertUnwindSafe(move || unsafe {
+            let user_callback = user_data as *const Box<Fn(&DebugUtilsMessage) + Send + Sync>;
+            let user_callback = &*user_callback;
+            let data = &*data;
+
+            let message = DebugUtilsMessage {
+                severity: MessageSeverity::from_vulkan_bits(severity),
+                ty: MessageType::from_vulkan_bits(ty),
+                message_id_name: optional_str(data.pMessageIdName),
+                message_id_number: data.messageIdNumber,
+                description: optional_str(data.pMessage).unwrap_or(""),
+                queue_labels: labels(data.pQueueLabels, data.queueLabelCount),
+                command_buffer_labels: labels(data.pCmdBufLabels, data.cmdBufLabelCount),
+                objects: raw_slice(data.pObjects, data.objectCount)
+                    .iter()
+                    .map(|object| {
+                             DebugUtilsObject {
+                                 object_type: object.objectType,
+                                 object_handle: object.objectHandle,
+                                 object_name: optional_str(object.pObjectName),
+                             }
+                         })
+                    .collect(),
+            };
+
+            user_callback(&message);
+        }));
+
+        vk::FALSE
+    }
+
+    vk::DebugUtilsMessengerCreateInfoEXT {
+        sType: vk::STRUCTURE_TYPE_DEBUG_UTILS_MESSENGER_CREATE_INFO_EXT,
+        pNext: ptr::null(),
+        flags: 0,
+        messageSeverity: severity.into_vulkan_bits(),
+        messageType: ty.into_vulkan_bits(),
+        pfnUserCallback: callback,
+        pUserData: &**user_callback as *const Box<_> as *const c_void as *mut _,
+    }
+}
+
+// Builds a slice from a pointer and a length that are allowed to be null and 0.
+unsafe fn raw_slice<'a, T>(ptr: *const T, len: u32) -> &'a [T] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len as usize)
+    }
+}
+
+// Converts a string that is allowed to be null.
+unsafe fn optional_str<'a>(ptr: *const c_char) -> Option<&'a str> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(CStr::from_ptr(ptr)
+                 .to_str()
+                 .expect("debug callback message not utf-8"))
+    }
+}
+
+unsafe fn labels<'a>(ptr: *const vk::DebugUtilsLabelEXT, len: u32) -> Vec<DebugUtilsLabel<'a>> {
+    raw_slice(ptr, len)
+        .iter()
+        .map(|label| {
+                 DebugUtilsLabel {
+                     name: optional_str(label.pLabelName).unwrap_or(""),
+                     color: label.color,
+                 }
+             })
+        .collect()
+}
+
+/// A message received by the callback of a `DebugUtilsMessenger`.
+#[derive(Debug, Clone)]
+pub struct DebugUtilsMessage<'a> {
+    /// Severity of the message. Exactly one field is true.
+    pub severity: MessageSeverity,
+    /// Type of the message.
+    pub ty: MessageType,
+    /// Name that identifies the kind of message, for example the identifier of the rule of the
+    /// specification that was violated.
+    pub message_id_name: Option<&'a str>,
+    /// Number that identifies the kind of message.
+    pub message_id_number: i32,
+    /// Description of the message.
+    pub description: &'a str,
+    /// Labels that were active on the queue when the message was emitted, the most recent one
+    /// first.
+    pub queue_labels: Vec<DebugUtilsLabel<'a>>,
+    /// Labels that were active on the command buffer when the message was emitted, the most
+    /// recent one first.
+    pub command_buffer_labels: Vec<DebugUtilsLabel<'a>>,
+    /// Objects that the message refers to.
+    pub objects: Vec<DebugUtilsObject<'a>>,
+}
+
+/// A queue or command buffer label of a `DebugUtilsMessage`.
+#[derive(Debug, Copy, Clone)]
+pub struct DebugUtilsLabel<'a> {
+    /// Name of the label.
+    pub name: &'a str,
+    /// Color of the label.
+    pub color: [f32; 4],
+}
+
+/// An object that a `DebugUtilsMessage` refers to.
+#[derive(Debug, Copy, Clone)]
+pub struct DebugUtilsObject<'a> {
+    /// Type of the object, as one of the values of the `VkObjectType` enumeration.
+    pub object_type: u32,
+    /// Vulkan handle of the object.
+    pub object_handle: u64,
+    /// Name that was given to the object, if any.
+    pub object_name: Option<&'a str>,
+}
+
+/// Severities of the messages that a `DebugUtilsMessenger` receives.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MessageSeverity {
+    /// An error that may cause undefined results, including an application crash.
+    pub error: bool,
+    /// An unexpected use, or a potential non-optimal use.
+    pub warning: bool,
+    /// An informational message that may be handy when debugging an application.
+    pub information: bool,
+    /// Diagnostic information from the loader, layers and drivers.
+    pub verbose: bool,
+}
+
+impl MessageSeverity {
+    /// Builds a `MessageSeverity` with all fields set to `false` expect `error`.
+    #[inline]
+    pub fn errors() -> MessageSeverity {
+        MessageSeverity {
+            error: true,
+            ..MessageSeverity::none()
+        }
+    }
+
+    /// Builds a `MessageSeverity` with all fields set to `false` expect `error` and `warning`.
+    #[inline]
+    pub fn errors_and_warnings() -> MessageSeverity {
+        MessageSeverity {
+            error: true,
+            warning: true,
+            ..MessageSeverity::none()
+        }
+    }
+
+    /// Builds a `MessageSeverity` with all fields set to `true`.
+    #[inline]
+    pub fn all() -> MessageSeverity {
+        MessageSeverity {
+            error: true,
+            warning: true,
+            information: true,
+            verbose: true,
+        }
+    }
+
+    /// Builds a `MessageSeverity` with all fields set to `false`.
+    #[inline]
+    pub fn none() -> MessageSeverity {
+        MessageSeverity {
+            error: false,
+            warning: false,
+            information: false,
+            verbose: false,
+        }
+    }
+
+    #[inline]
+    fn from_vulkan_bits(bits: vk::DebugUtilsMessageSeverityFlagsEXT) -> MessageSeverity {
+        MessageSeverity {
+            error: (bits & vk::DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT) != 0,
+            warning: (bits & vk::DEBUG_UTILS_MESSAGE_SEVERITY_WARNING_BIT_EXT) != 0,
+            information: (bits & vk::DEBUG_UTILS_MESSAGE_SEVERITY_INFO_BIT_EXT) != 0,
+            verbose: (bits & vk::DEBUG_UTILS_MESSAGE_SEVERITY_VERBOSE_BIT_EXT) != 0,
+        }
+    }
+
+    #[inline]
+    fn into_vulkan_bits(self) -> vk::DebugUtilsMessageSeverityFlagsEXT {
+        let mut bits = 0;
+        if self.error {
+            bits |= vk::DEBUG_UTILS_MESSAGE_SEVERITY_ERROR_BIT_EXT;
+        }
+        if self.warning {
+            bits |= vk::DEBUG_UTILS_MESSAGE_SEVERITY_WARNING_BIT_EXT;
+        }
+        if self.information {
+            bits |= vk::DEBUG_UTILS_MESSAGE_SEVERITY_INFO_BIT_EXT;
+        }
+        if self.verbose {
+            bits |= vk::DEBUG_UTILS_MESSAGE_SEVERITY_VERBOSE_BIT_EXT;
+        }
+        bits
+    }
+}
+
+/// Types of the messages that a `DebugUtilsMessenger` receives.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct MessageType {
+    /// An event that is unrelated to the specification or to performance.
+    pub general: bool,
+    /// A violation of the specification, or a possible mistake.
+    pub validation: bool,
+    /// A potential non-optimal use.
+    pub performance: bool,
+}
+
+impl MessageType {
+    /// Builds a `MessageType` with all fields set to `true`.
+    #[inline]
+    pub fn all() -> MessageType {
+        MessageType {
+            general: true,
+            validation: true,
+            performance: true,
+        }
+    }
+
+    /// Builds a `MessageType` with all fields set to `false`.
+    #[inline]
+    pub fn none() -> MessageType {
+        MessageType {
+            general: false,
+            validation: false,
+            performance: false,
+        }
+    }
+
+    #[inline]
+    fn from_vulkan_bits(bits: vk::DebugUtilsMessageTypeFlagsEXT) -> MessageType {
+        MessageType {
+            general: (bits & vk::DEBUG_UTILS_MESSAGE_TYPE_GENERAL_BIT_EXT) != 0,
+            validation: (bits & vk::DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT) != 0,
+            performance: (bits & vk::DEBUG_UTILS_MESSAGE_TYPE_PERFORMANCE_BIT_EXT) != 0,
+        }
+    }
+
+    #[inline]
+    fn into_vulkan_bits(self) -> vk::DebugUtilsMessageTypeFlagsEXT {
+        let mut bits = 0;
+        if self.general {
+            bits |= vk::DEBUG_UTILS_MESSAGE_TYPE_GENERAL_BIT_EXT;
+        }
+        if self.validation {
+            bits |= vk::DEBUG_UTILS_MESSAGE_TYPE_VALIDATION_BIT_EXT;
+        }
+        if self.performance {
+            bits |= vk::DEBUG_UTILS_MESSAGE_TYPE_PERFORMANCE_BIT_EXT;
+        }
+        bits
+    }
+}
+
+/// Error that can happen when creating a debug utils messenger.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DebugUtilsMessengerCreationError {
+    /// The `VK_EXT_debug_utils` extension was not enabled.
+    MissingExtension,
+}
+
+impl error::Error for DebugUtilsMessengerCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            DebugUtilsMessengerCreationError::MissingExtension =>
+                "the `VK_EXT_debug_utils` extension was not enabled",
+        }
+    }
+}
+
+impl fmt::Display for DebugUtilsMessengerCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<Error> for DebugUtilsMessengerCreationError {
+    #[inline]
+    fn from(err: Error) -> DebugUtilsMessengerCreationError {
+        panic!("unexpected error: {:?}", err)
+    }
+}
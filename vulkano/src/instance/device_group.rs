@@ -0,0 +1,192 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Groups of physical devices that can be used together by a single logical device.
+
+use smallvec::SmallVec;
+use std::mem;
+use std::ptr;
+use std::sync::Arc;
+
+use OomError;
+use VulkanObject;
+use check_errors;
+use instance::Instance;
+use instance::PhysicalDevice;
+use vk;
+
+/// A set of physical devices that can be used together to create a single logical device.
+///
+/// Physical devices that belong to the same group usually are identical GPUs linked together,
+/// such as with SLI or Crossfire. A logical device can be created from a whole group with
+/// `Device::with_device_group`, in which case the device masks of command buffers and memory
+/// allocations designate the physical devices of the group by their index in the group.
+///
+/// # Example
+///
+/// ```no_run
+/// use vulkano::instance::Instance;
+/// use vulkano::instance::InstanceExtensions;
+/// use vulkano::instance::PhysicalDeviceGroup;
+///
+/// let extensions = InstanceExtensions {
+///     khr_device_group_creation: true,
+///     .. InstanceExtensions::none()
+/// };
+/// let instance = Instance::new(None, &extensions, None).unwrap();
+///
+/// for group in PhysicalDeviceGroup::enumerate(&instance).unwrap() {
+///     println!("Group of {} physical devices", group.len());
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct PhysicalDeviceGroup<'a> {
+    instance: &'a Arc<Instance>,
+    devices: SmallVec<[usize; 8]>,
+    subset_allocation: bool,
+}
+
+impl<'a> PhysicalDeviceGroup<'a> {
+    /// Returns the groups of physical devices of the instance.
+    ///
+    /// If the `khr_device_group_creation` extension isn't loaded on the instance, each physical
+    /// device forms a group of its own.
+    pub fn enumerate(instance: &'a Arc<Instance>)
+                     -> Result<Vec<PhysicalDeviceGroup<'a>>, OomError> {
+        if !instance.loaded_extensions().khr_device_group_creation {
+            return Ok(PhysicalDevice::enumerate(instance)
+                          .map(PhysicalDeviceGroup::from_physical_device)
+                          .collect());
+        }
+
+        let vk = instance.pointers();
+
+        let properties = unsafe {
+            let mut num = 0;
+            check_errors(vk.EnumeratePhysicalDeviceGroupsKHR(instance.internal_object(),
+                                                             &mut num,
+                                                             ptr::null_mut()))?;
+
+            let mut properties = (0 .. num)
+                .map(|_| {
+                         vk::PhysicalDeviceGroupPropertiesKHR {
+                             sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_GROUP_PROPERTIES_KHR,
+                             pNext: ptr::null_mut(),
+                             physicalDeviceCount: 0,
+                             physicalDevices: mem::zeroed(),
+                             subsetAllocation: vk::FALSE,
+                         }
+                     })
+                .collect::<Vec<_>>();
+
+            check_errors(vk.EnumeratePhysicalDeviceGroupsKHR(instance.internal_object(),
+                                                             &mut num,
+                                                             properties.as_mut_ptr()))?;
+            properties.truncate(num as usize);
+            properties
+        };
+
+        Ok(properties
+               .iter()
+               .map(|group| {
+                   let devices = group.physicalDevices[.. group.physicalDeviceCount as usize]
+                       .iter()
+                       .map(|&handle| {
+                                PhysicalDevice::enumerate(instance)
+                                    .find(|device| device.internal_object() == handle)
+                                    .expect("physical device of a group wasn't enumerated")
+                                    .index()
+                            })
+                       .collect();
+
+                   PhysicalDeviceGroup {
+                       instance: instance,
+                       devices: devices,
+                       subset_allocation: group.subsetAllocation != vk::FALSE,
+                   }
+               })
+               .collect())
+    }
+
+    /// Builds a group that only contains `physical_device`.
+    #[inline]
+    pub fn from_physical_device(physical_device: PhysicalDevice<'a>) -> PhysicalDeviceGroup<'a> {
+        PhysicalDeviceGroup {
+            instance: physical_device.instance(),
+            devices: SmallVec::from_slice(&[physical_device.index()]),
+            subset_allocation: false,
+        }
+    }
+
+    /// Returns the instance corresponding to this group.
+    #[inline]
+    pub fn instance(&self) -> &'a Arc<Instance> {
+        self.instance
+    }
+
+    /// Returns the number of physical devices in the group.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.devices.len()
+    }
+
+    /// Returns the physical device of the group with the given index, or `None` if the index is
+    /// out of range.
+    ///
+    /// Bit `n` of a device mask designates the physical device with index `n`.
+    #[inline]
+    pub fn physical_device(&self, index: usize) -> Option<PhysicalDevice<'a>> {
+        self.devices
+            .get(index)
+            .map(|&id| PhysicalDevice::from_index(self.instance, id).unwrap())
+    }
+
+    /// Returns the physical devices of the group, in the order of their index in the group.
+    #[inline]
+    pub fn physical_devices<'b>(&'b self)
+                                -> Box<ExactSizeIterator<Item = PhysicalDevice<'a>> + 'b> {
+        let instance = self.instance;
+        Box::new(self.devices
+                     .iter()
+                     .map(move |&id| PhysicalDevice::from_index(instance, id).unwrap()))
+    }
+
+    /// Returns true if memory can be allocated on a subset of the physical devices of the group
+    /// with a device mask. Otherwise, allocations are always made on all of them.
+    #[inline]
+    pub fn subset_allocation(&self) -> bool {
+        self.subset_allocation
+    }
+
+    /// Returns the indices in the instance of the physical devices of the group.
+    #[inline]
+    pub(crate) fn device_indices(&self) -> &[usize] {
+        &self.devices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use instance::PhysicalDevice;
+    use instance::PhysicalDeviceGroup;
+
+    #[test]
+    fn every_device_in_one_group() {
+        let instance = instance!();
+
+        let groups = PhysicalDeviceGroup::enumerate(&instance).unwrap();
+        for device in PhysicalDevice::enumerate(&instance) {
+            let count = groups
+                .iter()
+                .filter(|g| g.physical_devices().any(|d| d.index() == device.index()))
+                .count();
+            assert_eq!(count, 1);
+        }
+    }
+}
@@ -14,6 +14,7 @@ use std::fmt;
 use std::iter::FromIterator;
 use std::ptr;
 use std::str;
+use std::vec::IntoIter;
 
 use Error;
 use OomError;
@@ -22,6 +23,7 @@ use check_errors;
 use instance::PhysicalDevice;
 use instance::loader;
 use instance::loader::LoadingError;
+use version::Version;
 use vk;
 
 macro_rules! extensions {
@@ -72,6 +74,17 @@ macro_rules! extensions {
                     _unbuildable: Unbuildable(())
                 }
             }
+
+            /// Returns the union of this list and another list.
+            #[inline]
+            pub fn union(&self, other: &$sname) -> $sname {
+                $sname {
+                    $(
+                        $ext: self.$ext || other.$ext,
+                    )*
+                    _unbuildable: Unbuildable(())
+                }
+            }
         }
 
         impl fmt::Debug for $sname {
@@ -96,7 +109,8 @@ macro_rules! extensions {
         /// Set of extensions, not restricted to those vulkano knows about.
         ///
         /// This is useful when interacting with external code that has statically-unknown extension
-        /// requirements.
+        /// requirements, or to enable extensions that vulkano doesn't know about yet. Their names
+        /// can be listed with `instance_extensions_list()` and `device_extensions_list()`.
         #[derive(Clone, Eq, PartialEq)]
         pub struct $rawname(HashSet<CString>);
 
@@ -116,6 +130,11 @@ macro_rules! extensions {
                 self.0.insert(extension);
             }
 
+            /// Returns true if the set contains the extension.
+            pub fn contains(&self, extension: &CStr) -> bool {
+                self.0.contains(extension)
+            }
+
             /// Returns the intersection of this set and another.
             pub fn intersection(&self, other: &Self) -> Self {
                 $rawname(self.0.intersection(&other.0).cloned().collect())
@@ -381,12 +400,18 @@ instance_extensions! {
     khr_android_surface => b"VK_KHR_android_surface",
     khr_win32_surface => b"VK_KHR_win32_surface",
     ext_debug_report => b"VK_EXT_debug_report",
+    ext_debug_utils => b"VK_EXT_debug_utils",
     mvk_ios_surface => b"VK_MVK_ios_surface",
     mvk_macos_surface => b"VK_MVK_macos_surface",
     mvk_moltenvk => b"VK_MVK_moltenvk",     // TODO: confirm that it's an instance extension
     nn_vi_surface => b"VK_NN_vi_surface",
     ext_swapchain_colorspace => b"VK_EXT_swapchain_colorspace",
     khr_get_physical_device_properties2 => b"VK_KHR_get_physical_device_properties2",
+    khr_device_group_creation => b"VK_KHR_device_group_creation",
+    khr_external_semaphore_capabilities => b"VK_KHR_external_semaphore_capabilities",
+    ext_validation_features => b"VK_EXT_validation_features",
+    khr_portability_enumeration => b"VK_KHR_portability_enumeration",
+    khr_external_memory_capabilities => b"VK_KHR_external_memory_capabilities",
 }
 
 device_extensions! {
@@ -400,6 +425,232 @@ device_extensions! {
     khr_dedicated_allocation => b"VK_KHR_dedicated_allocation",
     khr_incremental_present => b"VK_KHR_incremental_present",
     ext_debug_marker => b"VK_EXT_debug_marker",
+    ext_extended_dynamic_state => b"VK_EXT_extended_dynamic_state",
+    ext_robustness2 => b"VK_EXT_robustness2",
+    ext_conservative_rasterization => b"VK_EXT_conservative_rasterization",
+    ext_line_rasterization => b"VK_EXT_line_rasterization",
+    ext_vertex_attribute_divisor => b"VK_EXT_vertex_attribute_divisor",
+    ext_pipeline_creation_feedback => b"VK_EXT_pipeline_creation_feedback",
+    khr_pipeline_library => b"VK_KHR_pipeline_library",
+    ext_graphics_pipeline_library => b"VK_EXT_graphics_pipeline_library",
+    ext_mesh_shader => b"VK_EXT_mesh_shader",
+    khr_device_group => b"VK_KHR_device_group",
+    khr_buffer_device_address => b"VK_KHR_buffer_device_address",
+    khr_deferred_host_operations => b"VK_KHR_deferred_host_operations",
+    ext_descriptor_indexing => b"VK_EXT_descriptor_indexing",
+    khr_shader_float_controls => b"VK_KHR_shader_float_controls",
+    khr_spirv_1_4 => b"VK_KHR_spirv_1_4",
+    khr_acceleration_structure => b"VK_KHR_acceleration_structure",
+    khr_ray_tracing_pipeline => b"VK_KHR_ray_tracing_pipeline",
+    ext_subgroup_size_control => b"VK_EXT_subgroup_size_control",
+    khr_storage_buffer_storage_class => b"VK_KHR_storage_buffer_storage_class",
+    khr_16bit_storage => b"VK_KHR_16bit_storage",
+    khr_8bit_storage => b"VK_KHR_8bit_storage",
+    khr_shader_float16_int8 => b"VK_KHR_shader_float16_int8",
+    khr_shader_non_semantic_info => b"VK_KHR_shader_non_semantic_info",
+    khr_draw_indirect_count => b"VK_KHR_draw_indirect_count",
+    ext_multi_draw => b"VK_EXT_multi_draw",
+    ext_conditional_rendering => b"VK_EXT_conditional_rendering",
+    ext_transform_feedback => b"VK_EXT_transform_feedback",
+    khr_timeline_semaphore => b"VK_KHR_timeline_semaphore",
+    khr_external_semaphore => b"VK_KHR_external_semaphore",
+    khr_external_semaphore_fd => b"VK_KHR_external_semaphore_fd",
+    khr_external_semaphore_win32 => b"VK_KHR_external_semaphore_win32",
+    khr_external_memory => b"VK_KHR_external_memory",
+    khr_external_memory_fd => b"VK_KHR_external_memory_fd",
+    khr_external_memory_win32 => b"VK_KHR_external_memory_win32",
+    khr_synchronization2 => b"VK_KHR_synchronization2",
+    khr_maintenance3 => b"VK_KHR_maintenance3",
+    khr_driver_properties => b"VK_KHR_driver_properties",
+    nv_device_diagnostic_checkpoints => b"VK_NV_device_diagnostic_checkpoints",
+    amd_buffer_marker => b"VK_AMD_buffer_marker",
+    ext_tooling_info => b"VK_EXT_tooling_info",
+    khr_portability_subset => b"VK_KHR_portability_subset",
+    khr_sampler_ycbcr_conversion => b"VK_KHR_sampler_ycbcr_conversion",
+}
+
+impl InstanceExtensions {
+    /// Returns the instance extensions whose functionalities are part of the core API of
+    /// `api_version`.
+    ///
+    /// When an instance is created with this version of the API, these extensions are
+    /// considered to be loaded even if they weren't requested.
+    pub fn core_in(api_version: Version) -> InstanceExtensions {
+        let mut extensions = InstanceExtensions::none();
+        if api_version >= (Version { major: 1, minor: 1, patch: 0 }) {
+            extensions.khr_get_physical_device_properties2 = true;
+            extensions.khr_device_group_creation = true;
+            extensions.khr_external_semaphore_capabilities = true;
+            extensions.khr_external_memory_capabilities = true;
+        }
+        extensions
+    }
+}
+
+impl DeviceExtensions {
+    /// Returns the device extensions whose functionalities are part of the core API of
+    /// `api_version`.
+    ///
+    /// When a device is created with this version of the API, these extensions are considered to
+    /// be loaded even if they weren't requested. Extensions whose commands require a feature in
+    /// the core API, such as `khr_draw_indirect_count`, aren't part of the list.
+    pub fn core_in(api_version: Version) -> DeviceExtensions {
+        let mut extensions = DeviceExtensions::none();
+        if api_version >= (Version { major: 1, minor: 1, patch: 0 }) {
+            extensions.khr_maintenance1 = true;
+            extensions.khr_get_memory_requirements2 = true;
+            extensions.khr_dedicated_allocation = true;
+            extensions.khr_device_group = true;
+            extensions.khr_storage_buffer_storage_class = true;
+            extensions.khr_16bit_storage = true;
+            extensions.khr_external_semaphore = true;
+            extensions.khr_external_memory = true;
+            extensions.khr_maintenance3 = true;
+        }
+        if api_version >= (Version { major: 1, minor: 2, patch: 0 }) {
+            extensions.khr_buffer_device_address = true;
+            extensions.ext_descriptor_indexing = true;
+            extensions.khr_shader_float_controls = true;
+            extensions.khr_spirv_1_4 = true;
+            extensions.khr_8bit_storage = true;
+            extensions.khr_shader_float16_int8 = true;
+            extensions.khr_timeline_semaphore = true;
+            extensions.khr_driver_properties = true;
+        }
+        extensions
+    }
+}
+
+/// Queries the list of instance extensions that are available, including the ones that vulkano
+/// doesn't know about, with their versions.
+///
+/// In order to enable an extension that isn't part of `InstanceExtensions`, add its name to a
+/// `RawInstanceExtensions` and pass it when creating the [`Instance`](struct.Instance.html).
+///
+/// # Example
+///
+/// ```no_run
+/// use std::ffi::CString;
+/// use vulkano::instance;
+/// use vulkano::instance::Instance;
+/// use vulkano::instance::RawInstanceExtensions;
+///
+/// let mut extensions = RawInstanceExtensions::none();
+/// for extension in instance::instance_extensions_list().unwrap() {
+///     println!("Available extension: {} (version {})", extension.name(),
+///              extension.spec_version());
+///     if extension.name() == "VK_EXT_debug_utils" {
+///         extensions.insert(CString::new(extension.name()).unwrap());
+///     }
+/// }
+///
+/// let instance = Instance::new(None, extensions, None).unwrap();
+/// ```
+pub fn instance_extensions_list() -> Result<ExtensionsIterator, SupportedExtensionsError> {
+    instance_extensions_list_from_loader(loader::auto_loader()?)
+}
+
+/// Same as `instance_extensions_list()`, but allows specifying a loader.
+pub fn instance_extensions_list_from_loader<L>(
+    ptrs: &loader::FunctionPointers<L>)
+    -> Result<ExtensionsIterator, SupportedExtensionsError>
+    where L: loader::Loader
+{
+    unsafe {
+        let entry_points = ptrs.entry_points();
+
+        let mut num = 0;
+        check_errors(entry_points.EnumerateInstanceExtensionProperties(ptr::null(),
+                                                                       &mut num,
+                                                                       ptr::null_mut()))?;
+
+        let mut properties: Vec<vk::ExtensionProperties> = Vec::with_capacity(num as usize);
+        check_errors(entry_points.EnumerateInstanceExtensionProperties(ptr::null(),
+                                                                       &mut num,
+                                                                       properties.as_mut_ptr()))?;
+        properties.set_len(num as usize);
+
+        Ok(ExtensionsIterator { iter: properties.into_iter() })
+    }
+}
+
+/// Queries the list of device extensions that a physical device supports, including the ones
+/// that vulkano doesn't know about, with their versions.
+///
+/// In order to enable an extension that isn't part of `DeviceExtensions`, add its name to a
+/// `RawDeviceExtensions` and pass it when creating the `Device`.
+pub fn device_extensions_list(physical_device: PhysicalDevice)
+                              -> Result<ExtensionsIterator, SupportedExtensionsError> {
+    unsafe {
+        let vk = physical_device.instance().pointers();
+
+        let mut num = 0;
+        check_errors(vk.EnumerateDeviceExtensionProperties(physical_device.internal_object(),
+                                                           ptr::null(),
+                                                           &mut num,
+                                                           ptr::null_mut()))?;
+
+        let mut properties: Vec<vk::ExtensionProperties> = Vec::with_capacity(num as usize);
+        check_errors(vk.EnumerateDeviceExtensionProperties(physical_device.internal_object(),
+                                                           ptr::null(),
+                                                           &mut num,
+                                                           properties.as_mut_ptr()))?;
+        properties.set_len(num as usize);
+
+        Ok(ExtensionsIterator { iter: properties.into_iter() })
+    }
+}
+
+/// Properties of an extension.
+pub struct ExtensionProperties {
+    props: vk::ExtensionProperties,
+}
+
+impl ExtensionProperties {
+    /// Returns the name of the extension.
+    #[inline]
+    pub fn name(&self) -> &str {
+        unsafe {
+            CStr::from_ptr(self.props.extensionName.as_ptr())
+                .to_str()
+                .unwrap()
+        }
+    }
+
+    /// Returns the version of the specification of the extension that is implemented.
+    #[inline]
+    pub fn spec_version(&self) -> u32 {
+        self.props.specVersion
+    }
+}
+
+impl fmt::Debug for ExtensionProperties {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{} (version {})", self.name(), self.spec_version())
+    }
+}
+
+/// Iterator that produces the list of extensions that are available.
+pub struct ExtensionsIterator {
+    iter: IntoIter<vk::ExtensionProperties>,
+}
+
+impl Iterator for ExtensionsIterator {
+    type Item = ExtensionProperties;
+
+    #[inline]
+    fn next(&mut self) -> Option<ExtensionProperties> {
+        self.iter.next().map(|p| ExtensionProperties { props: p })
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ExtensionsIterator {
 }
 
 /// Error that can happen when loading the list of layers.
@@ -473,6 +724,9 @@ pub struct Unbuildable(());
 
 #[cfg(test)]
 mod tests {
+    use std::ffi::CString;
+
+    use instance;
     use instance::{DeviceExtensions, RawDeviceExtensions};
     use instance::{InstanceExtensions, RawInstanceExtensions};
 
@@ -484,4 +738,34 @@ mod tests {
         let d: RawDeviceExtensions = (&DeviceExtensions::none()).into();
         assert!(d.iter().next().is_none());
     }
+
+    #[test]
+    fn instance_extensions_list() {
+        let list = match instance::instance_extensions_list() {
+            Ok(l) => l,
+            Err(_) => return,
+        };
+
+        let raw = RawInstanceExtensions::supported_by_core().unwrap();
+        for extension in list {
+            assert!(raw.contains(&CString::new(extension.name()).unwrap()));
+        }
+    }
+
+    #[test]
+    fn core_in() {
+        let v1_0 = instance::Version { major: 1, minor: 0, patch: 0 };
+        let v1_1 = instance::Version { major: 1, minor: 1, patch: 0 };
+        let v1_2 = instance::Version { major: 1, minor: 2, patch: 0 };
+
+        assert_eq!(InstanceExtensions::core_in(v1_0), InstanceExtensions::none());
+        assert!(InstanceExtensions::core_in(v1_1).khr_get_physical_device_properties2);
+
+        assert_eq!(DeviceExtensions::core_in(v1_0), DeviceExtensions::none());
+        assert!(DeviceExtensions::core_in(v1_1).khr_maintenance1);
+        assert!(!DeviceExtensions::core_in(v1_1).khr_timeline_semaphore);
+        assert!(DeviceExtensions::core_in(v1_2).khr_maintenance1);
+        assert!(DeviceExtensions::core_in(v1_2).khr_timeline_semaphore);
+        assert!(!DeviceExtensions::core_in(v1_2).khr_swapchain);
+    }
 }
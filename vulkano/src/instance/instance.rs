@@ -15,6 +15,8 @@ use std::ffi::CString;
 use std::fmt;
 use std::mem;
 use std::ops::Deref;
+use std::os::raw::c_void;
+use std::panic;
 use std::ptr;
 use std::slice;
 use std::sync::Arc;
@@ -23,14 +25,36 @@ use Error;
 use OomError;
 use VulkanObject;
 use check_errors;
+use instance::debug::DebugUtilsMessage;
+use instance::debug::DebugUtilsUserCallback;
+use instance::debug::MessageSeverity;
+use instance::debug::MessageType;
+use instance::debug::messenger_create_info;
 use instance::limits::Limits;
+use instance::properties::DescriptorIndexingProperties;
+use instance::properties::DriverProperties;
+use instance::properties::ExtendedProperties;
+use instance::properties::IdProperties;
+use instance::properties::Maintenance3Properties;
+use instance::properties::PortabilitySubsetProperties;
+use instance::vendor::DriverVersion;
+use instance::vendor::Vendor;
+use command_buffer::TransformFeedbackProperties;
+use pipeline::RayTracingProperties;
+use pipeline::shader::MeshShaderProperties;
+use pipeline::shader::SubgroupProperties;
+use pipeline::shader::SubgroupSizeControlProperties;
 use instance::loader;
 use instance::loader::FunctionPointers;
 use instance::loader::Loader;
 use instance::loader::LoadingError;
+use memory::HostAllocationCallbacks;
+use memory::host::HostAllocator;
 use vk;
 
 use features::Features;
+use features::FeaturesFfi;
+use instance::{DeviceExtensions, RawDeviceExtensions};
 use instance::{InstanceExtensions, RawInstanceExtensions};
 use version::Version;
 
@@ -85,12 +109,19 @@ use version::Version;
 // TODO: mention that extensions must be supported by layers as well
 pub struct Instance {
     instance: vk::Instance,
-    //alloc: Option<Box<Alloc + Send + Sync>>,
+    // Callbacks that the implementation uses to allocate host memory. Must outlive the Vulkan
+    // instance.
+    allocator: Option<HostAllocator>,
     physical_devices: Vec<PhysicalDeviceInfos>,
     vk: vk::InstancePointers,
     extensions: InstanceExtensions,
+    api_version: Version,
     layers: SmallVec<[CString; 16]>,
+    validation_features: ValidationFeatures,
     function_pointers: OwnedOrRef<FunctionPointers<Box<Loader + Send + Sync>>>,
+    // Callback of the messenger registered with `with_debug_messenger`. Must outlive the
+    // Vulkan instance.
+    debug_messenger_callback: Option<DebugUtilsUserCallback>,
 }
 
 // TODO: fix the underlying cause instead
@@ -117,6 +148,17 @@ impl Instance {
     /// };
     /// ```
     ///
+    /// # Versions and portability
+    ///
+    /// The instance uses the version of Vulkan requested in `ApplicationInfo`, or the highest
+    /// version supported by the loader if it's lower. The version that was chosen is returned by
+    /// `api_version()`. An error is returned if the requested version is lower than Vulkan 1.0.
+    ///
+    /// Implementations that only support a subset of Vulkan, such as MoltenVK on macOS and iOS,
+    /// are only enumerated by recent loaders if the `VK_KHR_portability_enumeration` extension is
+    /// enabled. For this reason, this extension is always enabled if the loader supports it,
+    /// along with `VK_KHR_get_physical_device_properties2`.
+    ///
     /// # Panic
     ///
     /// - Panics if the version numbers passed in `ApplicationInfo` are too large can't be
@@ -138,6 +180,149 @@ impl Instance {
         Instance::new_inner(app_infos,
                             extensions.into(),
                             layers,
+                            &ValidationFeatures::none(),
+                            None,
+                            None,
+                            OwnedOrRef::Ref(loader::auto_loader()?))
+    }
+
+    /// Initializes a new instance of Vulkan for headless compute.
+    ///
+    /// No surface extension and no layer are enabled. `VK_KHR_get_physical_device_properties2`
+    /// is enabled if it's supported, so that the properties that matter for compute, such as
+    /// `PhysicalDevice::subgroup_properties`, can be queried.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vulkano::device::Device;
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::PhysicalDevice;
+    ///
+    /// let instance = Instance::new_compute(None).unwrap();
+    /// let physical = PhysicalDevice::enumerate(&instance).next().unwrap();
+    /// let (device, queue) = Device::new_compute(physical).unwrap();
+    /// ```
+    pub fn new_compute(app_infos: Option<&ApplicationInfo>)
+                       -> Result<Arc<Instance>, InstanceCreationError> {
+        let supported = InstanceExtensions::supported_by_core()?;
+        let extensions = InstanceExtensions {
+            khr_get_physical_device_properties2: supported.khr_get_physical_device_properties2,
+            ..InstanceExtensions::none()
+        };
+
+        Instance::new(app_infos, &extensions, None)
+    }
+
+    /// Same as `new`, but enables or disables some features of the validation layer, instead of
+    /// relying on the environment variables or the configuration file of the layer.
+    ///
+    /// The `VK_EXT_validation_features` extension must be enabled, and the validation layer
+    /// (`VK_LAYER_KHRONOS_validation`) must be part of `layers`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vulkano::instance::DisabledValidationChecks;
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::InstanceExtensions;
+    /// use vulkano::instance::ValidationFeatures;
+    ///
+    /// let extensions = InstanceExtensions {
+    ///     ext_debug_report: true,
+    ///     ext_validation_features: true,
+    ///     .. InstanceExtensions::none()
+    /// };
+    /// let validation_features = ValidationFeatures {
+    ///     best_practices: true,
+    ///     synchronization_validation: true,
+    ///     disabled: DisabledValidationChecks {
+    ///         thread_safety: true,
+    ///         .. DisabledValidationChecks::none()
+    ///     },
+    ///     .. ValidationFeatures::none()
+    /// };
+    ///
+    /// let instance = Instance::with_validation_features(None, &extensions,
+    ///                                                   &["VK_LAYER_KHRONOS_validation"],
+    ///                                                   &validation_features).unwrap();
+    /// ```
+    pub fn with_validation_features<'a, L, Ext>(app_infos: Option<&ApplicationInfo>,
+                                                extensions: Ext, layers: L,
+                                                validation_features: &ValidationFeatures)
+                                                -> Result<Arc<Instance>, InstanceCreationError>
+        where L: IntoIterator<Item = &'a &'a str>,
+              Ext: Into<RawInstanceExtensions>
+    {
+        let layers = layers
+            .into_iter()
+            .map(|&layer| CString::new(layer).unwrap())
+            .collect::<SmallVec<[_; 16]>>();
+
+        Instance::new_inner(app_infos,
+                            extensions.into(),
+                            layers,
+                            validation_features,
+                            None,
+                            None,
+                            OwnedOrRef::Ref(loader::auto_loader()?))
+    }
+
+    /// Same as `new`, but registers a debug utils messenger while the instance is created.
+    ///
+    /// Contrary to a `DebugUtilsMessenger` created afterwards, `user_callback` also receives the
+    /// messages emitted while the instance is created and destroyed. It is called until the
+    /// instance is destroyed. The `VK_EXT_debug_utils` extension must be enabled.
+    ///
+    /// Panics generated by calling `user_callback` are ignored.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::InstanceExtensions;
+    /// use vulkano::instance::debug::MessageSeverity;
+    /// use vulkano::instance::debug::MessageType;
+    ///
+    /// let extensions = InstanceExtensions {
+    ///     ext_debug_utils: true,
+    ///     .. InstanceExtensions::none()
+    /// };
+    ///
+    /// let instance = Instance::with_debug_messenger(None, &extensions,
+    ///                                               &["VK_LAYER_KHRONOS_validation"],
+    ///                                               MessageSeverity::errors_and_warnings(),
+    ///                                               MessageType::all(),
+    ///                                               |msg| println!("{}", msg.description))
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `severity` or `ty` is empty.
+    ///
+    pub fn with_debug_messenger<'a, L, Ext, F>(app_infos: Option<&ApplicationInfo>,
+                                               extensions: Ext, layers: L,
+                                               severity: MessageSeverity, ty: MessageType,
+                                               user_callback: F)
+                                               -> Result<Arc<Instance>, InstanceCreationError>
+        where L: IntoIterator<Item = &'a &'a str>,
+              Ext: Into<RawInstanceExtensions>,
+              F: Fn(&DebugUtilsMessage) + 'static + Send + Sync + panic::RefUnwindSafe
+    {
+        let layers = layers
+            .into_iter()
+            .map(|&layer| CString::new(layer).unwrap())
+            .collect::<SmallVec<[_; 16]>>();
+
+        let user_callback: DebugUtilsUserCallback = Box::new(Box::new(user_callback));
+
+        Instance::new_inner(app_infos,
+                            extensions.into(),
+                            layers,
+                            &ValidationFeatures::none(),
+                            Some((severity, ty, user_callback)),
+                            None,
                             OwnedOrRef::Ref(loader::auto_loader()?))
     }
 
@@ -156,11 +341,16 @@ impl Instance {
         Instance::new_inner(app_infos,
                             extensions.into(),
                             layers,
+                            &ValidationFeatures::none(),
+                            None,
+                            None,
                             OwnedOrRef::Owned(loader))
     }
 
     fn new_inner(app_infos: Option<&ApplicationInfo>, extensions: RawInstanceExtensions,
-                 layers: SmallVec<[CString; 16]>,
+                 layers: SmallVec<[CString; 16]>, validation_features: &ValidationFeatures,
+                 debug_messenger: Option<(MessageSeverity, MessageType, DebugUtilsUserCallback)>,
+                 allocator: Option<HostAllocator>,
                  function_pointers: OwnedOrRef<FunctionPointers<Box<Loader + Send + Sync>>>)
                  -> Result<Arc<Instance>, InstanceCreationError> {
         // TODO: For now there are still buggy drivers that will segfault if you don't pass any
@@ -171,6 +361,35 @@ impl Instance {
             None => Some(&def),
         };
 
+        // The version of Vulkan that the instance uses is the one requested by the user, unless
+        // the implementation doesn't support it. Implementations of Vulkan 1.0 refuse to create
+        // an instance if a higher version is requested.
+        let api_version = {
+            let requested = app_infos
+                .and_then(|a| a.api_version)
+                .unwrap_or(Version { major: 1, minor: 0, patch: 0 });
+            if requested < (Version { major: 1, minor: 0, patch: 0 }) {
+                return Err(InstanceCreationError::InvalidApiVersion { requested });
+            }
+            let supported = function_pointers.api_version()?;
+            if requested > supported { supported } else { requested }
+        };
+
+        // Recent loaders only enumerate the implementations that don't fully conform to the
+        // specifications, such as MoltenVK, if `VK_KHR_portability_enumeration` is enabled.
+        // `VK_KHR_get_physical_device_properties2` is needed by the `VK_KHR_portability_subset`
+        // extension of their devices.
+        let extensions = {
+            let supported = InstanceExtensions::supported_by_core_with_loader(&function_pointers)?;
+            let portability: RawInstanceExtensions = (&InstanceExtensions {
+                khr_portability_enumeration: supported.khr_portability_enumeration,
+                khr_get_physical_device_properties2: supported.khr_portability_enumeration &&
+                    supported.khr_get_physical_device_properties2,
+                ..InstanceExtensions::none()
+            }).into();
+            extensions.union(&portability)
+        };
+
         // Building the CStrings from the `str`s within `app_infos`.
         // They need to be created ahead of time, since we pass pointers to them.
         let app_infos_strings = if let Some(app_infos) = app_infos {
@@ -213,11 +432,7 @@ impl Instance {
                     .engine_version
                     .map(|v| v.into_vulkan_version())
                     .unwrap_or(0),
-                apiVersion: Version {
-                    major: 1,
-                    minor: 0,
-                    patch: 0,
-                }.into_vulkan_version(), // TODO:
+                apiVersion: api_version.into_vulkan_version(),
             })
 
         } else {
@@ -235,13 +450,65 @@ impl Instance {
             .map(|extension| extension.as_ptr())
             .collect::<SmallVec<[_; 32]>>();
 
+        // The extensions that were promoted to the core API of the instance's version are
+        // available even if they weren't explicitly requested.
+        // TODO: should be Into
+        let loaded_extensions: InstanceExtensions = (&extensions).into();
+        let requested_properties2 = loaded_extensions.khr_get_physical_device_properties2;
+        let loaded_extensions = loaded_extensions.union(&InstanceExtensions::core_in(api_version));
+
+        let enabled_validation_features = validation_features.into_vulkan_list();
+        let disabled_validation_features = validation_features.disabled.into_vulkan_list();
+        let has_validation_features = !enabled_validation_features.is_empty() ||
+            !disabled_validation_features.is_empty();
+        if has_validation_features && !loaded_extensions.ext_validation_features {
+            return Err(InstanceCreationError::ValidationFeaturesExtensionNotEnabled);
+        }
+        if !validation_features.is_valid() {
+            return Err(InstanceCreationError::IncompatibleValidationFeatures);
+        }
+
+        if debug_messenger.is_some() && !loaded_extensions.ext_debug_utils {
+            return Err(InstanceCreationError::DebugUtilsExtensionNotEnabled);
+        }
+
+        // The messenger is chained to the create infos of the instance, so that it receives the
+        // messages emitted by `vkCreateInstance` and `vkDestroyInstance`.
+        let debug_messenger_infos = debug_messenger
+            .as_ref()
+            .map(|&(severity, ty, ref user_callback)| {
+                     messenger_create_info(severity, ty, user_callback)
+                 });
+        let debug_messenger_callback = debug_messenger.map(|(_, _, user_callback)| user_callback);
+
+        let mut next: *const c_void = match debug_messenger_infos {
+            Some(ref infos) => infos as *const _ as *const _,
+            None => ptr::null(),
+        };
+
+        let validation_features_infos = vk::ValidationFeaturesEXT {
+            sType: vk::STRUCTURE_TYPE_VALIDATION_FEATURES_EXT,
+            pNext: next,
+            enabledValidationFeatureCount: enabled_validation_features.len() as u32,
+            pEnabledValidationFeatures: enabled_validation_features.as_ptr(),
+            disabledValidationFeatureCount: disabled_validation_features.len() as u32,
+            pDisabledValidationFeatures: disabled_validation_features.as_ptr(),
+        };
+        if has_validation_features {
+            next = &validation_features_infos as *const _ as *const _;
+        }
+
         // Creating the Vulkan instance.
         let instance = unsafe {
             let mut output = mem::uninitialized();
             let infos = vk::InstanceCreateInfo {
                 sType: vk::STRUCTURE_TYPE_INSTANCE_CREATE_INFO,
-                pNext: ptr::null(),
-                flags: 0,
+                pNext: next,
+                flags: if loaded_extensions.khr_portability_enumeration {
+                    vk::INSTANCE_CREATE_ENUMERATE_PORTABILITY_BIT_KHR
+                } else {
+                    0
+                },
                 pApplicationInfo: if let Some(app) = app_infos.as_ref() {
                     app as *const _
                 } else {
@@ -254,19 +521,85 @@ impl Instance {
             };
 
             let entry_points = function_pointers.entry_points();
-            check_errors(entry_points.CreateInstance(&infos, ptr::null(), &mut output))?;
+            check_errors(entry_points.CreateInstance(&infos,
+                                                     HostAllocator::as_ptr(&allocator),
+                                                     &mut output))?;
             output
         };
 
+        unsafe {
+            Instance::from_handle(instance,
+                                  api_version,
+                                  loaded_extensions,
+                                  requested_properties2,
+                                  layers,
+                                  *validation_features,
+                                  allocator,
+                                  function_pointers,
+                                  debug_messenger_callback)
+        }
+    }
+
+    /// Builds an `Instance` from a raw instance handle, for example one that was created by an
+    /// OpenXR runtime with `xrCreateVulkanInstanceKHR`.
+    ///
+    /// `api_version`, `extensions` and `layers` must be the version of the API, the extensions
+    /// and the layers that the instance was created with. The extensions that were promoted to
+    /// the core API of `api_version` are considered loaded, like with `new`. The functions of the
+    /// instance are loaded with the default loader.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid instance that was created through the default loader, without
+    ///   allocation callbacks.
+    /// - The `Instance` takes ownership of `handle` and destroys it when it is dropped.
+    ///
+    pub unsafe fn from_raw<'a, L, Ext>(handle: u64, api_version: Version, extensions: Ext,
+                                       layers: L)
+                                       -> Result<Arc<Instance>, InstanceCreationError>
+        where L: IntoIterator<Item = &'a &'a str>,
+              Ext: Into<RawInstanceExtensions>
+    {
+        let layers = layers
+            .into_iter()
+            .map(|&layer| CString::new(layer).unwrap())
+            .collect::<SmallVec<[_; 16]>>();
+
+        let loaded_extensions: InstanceExtensions = (&extensions.into()).into();
+        let requested_properties2 = loaded_extensions.khr_get_physical_device_properties2;
+        let loaded_extensions = loaded_extensions.union(&InstanceExtensions::core_in(api_version));
+
+        Instance::from_handle(handle as vk::Instance,
+                              api_version,
+                              loaded_extensions,
+                              requested_properties2,
+                              layers,
+                              ValidationFeatures::none(),
+                              None,
+                              OwnedOrRef::Ref(loader::auto_loader()?),
+                              None)
+    }
+
+    // Loads the functions of `instance` and queries its physical devices. Shared by `new_inner`
+    // and `from_raw`.
+    unsafe fn from_handle(instance: vk::Instance, api_version: Version,
+                          loaded_extensions: InstanceExtensions, requested_properties2: bool,
+                          layers: SmallVec<[CString; 16]>,
+                          validation_features: ValidationFeatures,
+                          allocator: Option<HostAllocator>,
+                          function_pointers: OwnedOrRef<FunctionPointers<Box<Loader + Send +
+                                                                              Sync>>>,
+                          debug_messenger_callback: Option<DebugUtilsUserCallback>)
+                          -> Result<Arc<Instance>, InstanceCreationError> {
         // Loading the function pointers of the newly-created instance.
         let vk = {
-            vk::InstancePointers::load(|name| unsafe {
+            vk::InstancePointers::load(|name| {
                 mem::transmute(function_pointers.get_instance_proc_addr(instance, name.as_ptr()))
             })
         };
 
         // Enumerating all physical devices.
-        let physical_devices: Vec<vk::PhysicalDevice> = unsafe {
+        let physical_devices: Vec<vk::PhysicalDevice> = {
             let mut num = 0;
             check_errors(vk.EnumeratePhysicalDevices(instance, &mut num, ptr::null_mut()))?;
 
@@ -276,154 +609,240 @@ impl Instance {
             devices
         };
 
-        // TODO: should be Into
-        let extensions: InstanceExtensions = (&extensions).into();
+        let extensions = loaded_extensions;
 
         // Getting the properties of all physical devices.
-        // If possible, we use VK_KHR_get_physical_device_properties2.
-        let physical_devices = if extensions.khr_get_physical_device_properties2 {
-            Instance::init_physical_devices2(&vk, physical_devices, &extensions)
-        } else {
-            Instance::init_physical_devices(&vk, physical_devices)
-        };
+        // If possible, we use VK_KHR_get_physical_device_properties2 or its core equivalent.
+        // The core version can only be used with physical devices that support Vulkan 1.1.
+        let v1_1 = Version { major: 1, minor: 1, patch: 0 };
+        let physical_devices = physical_devices
+            .into_iter()
+            .map(|device| {
+                let properties2 = requested_properties2 ||
+                    (api_version >= v1_1 && Instance::device_api_version(&vk, device) >= v1_1);
+                if properties2 {
+                    Instance::init_physical_device2(&vk, device)
+                } else {
+                    Instance::init_physical_device(&vk, device)
+                }
+            })
+            .collect();
 
         Ok(Arc::new(Instance {
                         instance: instance,
-                        //alloc: None,
+                        allocator: allocator,
                         physical_devices: physical_devices,
                         vk: vk,
                         extensions: extensions,
+                        api_version: api_version,
                         layers: layers,
+                        validation_features: validation_features,
                         function_pointers: function_pointers,
+                        debug_messenger_callback: debug_messenger_callback,
                     }))
     }
 
-    /// Initialize all physical devices
-    fn init_physical_devices(vk: &vk::InstancePointers, physical_devices: Vec<vk::PhysicalDevice>)
-                             -> Vec<PhysicalDeviceInfos> {
-        let mut output = Vec::with_capacity(physical_devices.len());
+    /// Returns the version of Vulkan supported by a physical device.
+    fn device_api_version(vk: &vk::InstancePointers, device: vk::PhysicalDevice) -> Version {
+        let properties: vk::PhysicalDeviceProperties = unsafe {
+            let mut output = mem::uninitialized();
+            vk.GetPhysicalDeviceProperties(device, &mut output);
+            output
+        };
+        Version::from_vulkan_version(properties.apiVersion)
+    }
 
-        for device in physical_devices.into_iter() {
-            let properties: vk::PhysicalDeviceProperties = unsafe {
-                let mut output = mem::uninitialized();
-                vk.GetPhysicalDeviceProperties(device, &mut output);
-                output
-            };
+    /// Initialize a physical device
+    fn init_physical_device(vk: &vk::InstancePointers, device: vk::PhysicalDevice)
+                            -> PhysicalDeviceInfos {
+        let properties: vk::PhysicalDeviceProperties = unsafe {
+            let mut output = mem::uninitialized();
+            vk.GetPhysicalDeviceProperties(device, &mut output);
+            output
+        };
 
-            let queue_families = unsafe {
-                let mut num = 0;
-                vk.GetPhysicalDeviceQueueFamilyProperties(device, &mut num, ptr::null_mut());
+        let queue_families = unsafe {
+            let mut num = 0;
+            vk.GetPhysicalDeviceQueueFamilyProperties(device, &mut num, ptr::null_mut());
 
-                let mut families = Vec::with_capacity(num as usize);
-                vk.GetPhysicalDeviceQueueFamilyProperties(device, &mut num, families.as_mut_ptr());
-                families.set_len(num as usize);
-                families
-            };
+            let mut families = Vec::with_capacity(num as usize);
+            vk.GetPhysicalDeviceQueueFamilyProperties(device, &mut num, families.as_mut_ptr());
+            families.set_len(num as usize);
+            families
+        };
 
-            let memory: vk::PhysicalDeviceMemoryProperties = unsafe {
-                let mut output = mem::uninitialized();
-                vk.GetPhysicalDeviceMemoryProperties(device, &mut output);
-                output
-            };
+        let memory: vk::PhysicalDeviceMemoryProperties = unsafe {
+            let mut output = mem::uninitialized();
+            vk.GetPhysicalDeviceMemoryProperties(device, &mut output);
+            output
+        };
 
-            let available_features: vk::PhysicalDeviceFeatures = unsafe {
-                let mut output = mem::uninitialized();
-                vk.GetPhysicalDeviceFeatures(device, &mut output);
-                output
-            };
+        let available_features: vk::PhysicalDeviceFeatures = unsafe {
+            let mut output = mem::uninitialized();
+            vk.GetPhysicalDeviceFeatures(device, &mut output);
+            output
+        };
 
-            output.push(PhysicalDeviceInfos {
-                            device: device,
-                            properties: properties,
-                            memory: memory,
-                            queue_families: queue_families,
-                            available_features: Features::from_vulkan_features(available_features),
-                        });
+        PhysicalDeviceInfos {
+            device: device,
+            properties: properties,
+            memory: memory,
+            queue_families: queue_families,
+            available_features: Features::from_vulkan_features(available_features),
+            extended_properties: ExtendedProperties::none(),
+            properties2: false,
         }
-        output
-    }
-
-    /// Initialize all physical devices, but use VK_KHR_get_physical_device_properties2
-    /// TODO: Query extension-specific physical device properties, once a new instance extension is supported.
-    fn init_physical_devices2(vk: &vk::InstancePointers,
-                              physical_devices: Vec<vk::PhysicalDevice>,
-                              extensions: &InstanceExtensions)
-                              -> Vec<PhysicalDeviceInfos> {
-        let mut output = Vec::with_capacity(physical_devices.len());
-
-        for device in physical_devices.into_iter() {
-            let properties: vk::PhysicalDeviceProperties = unsafe {
-                let mut output = vk::PhysicalDeviceProperties2KHR {
-                    sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR,
-                    pNext: ptr::null_mut(),
-                    properties: mem::uninitialized(),
-                };
-
-                vk.GetPhysicalDeviceProperties2KHR(device, &mut output);
-                output.properties
-            };
+    }
 
-            let queue_families = unsafe {
-                let mut num = 0;
-                vk.GetPhysicalDeviceQueueFamilyProperties2KHR(device, &mut num, ptr::null_mut());
-
-                let mut families = (0 .. num)
-                    .map(|_| {
-                             vk::QueueFamilyProperties2KHR {
-                                 sType: vk::STRUCTURE_TYPE_QUEUE_FAMILY_PROPERTIES_2_KHR,
-                                 pNext: ptr::null_mut(),
-                                 queueFamilyProperties: mem::uninitialized(),
-                             }
-                         })
-                    .collect::<Vec<_>>();
-
-                vk.GetPhysicalDeviceQueueFamilyProperties2KHR(device,
-                                                              &mut num,
-                                                              families.as_mut_ptr());
-                families
-                    .into_iter()
-                    .map(|family| family.queueFamilyProperties)
-                    .collect()
-            };
+    /// Initialize a physical device, but use VK_KHR_get_physical_device_properties2
+    fn init_physical_device2(vk: &vk::InstancePointers, device: vk::PhysicalDevice)
+                             -> PhysicalDeviceInfos {
+        // The properties and the features of the device extensions are queried alongside the
+        // core ones, but only for the extensions that the physical device supports.
+        let supported_extensions = Instance::supported_device_extensions(vk, device);
 
-            let memory: vk::PhysicalDeviceMemoryProperties = unsafe {
-                let mut output = vk::PhysicalDeviceMemoryProperties2KHR {
-                    sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_MEMORY_PROPERTIES_2_KHR,
-                    pNext: ptr::null_mut(),
-                    memoryProperties: mem::uninitialized(),
-                };
-                vk.GetPhysicalDeviceMemoryProperties2KHR(device, &mut output);
-                output.memoryProperties
-            };
+        let (properties, extended_properties) = unsafe {
+            ExtendedProperties::query(vk, device, &supported_extensions)
+        };
 
-            let available_features: vk::PhysicalDeviceFeatures = unsafe {
-                let mut output = vk::PhysicalDeviceFeatures2KHR {
-                    sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_FEATURES_2_KHR,
-                    pNext: ptr::null_mut(),
-                    features: mem::uninitialized(),
-                };
-                vk.GetPhysicalDeviceFeatures2KHR(device, &mut output);
-                output.features
+        let queue_families = unsafe {
+            let mut num = 0;
+            vk.GetPhysicalDeviceQueueFamilyProperties2KHR(device, &mut num, ptr::null_mut());
+
+            let mut families = (0 .. num)
+                .map(|_| {
+                         vk::QueueFamilyProperties2KHR {
+                             sType: vk::STRUCTURE_TYPE_QUEUE_FAMILY_PROPERTIES_2_KHR,
+                             pNext: ptr::null_mut(),
+                             queueFamilyProperties: mem::uninitialized(),
+                         }
+                     })
+                .collect::<Vec<_>>();
+
+            vk.GetPhysicalDeviceQueueFamilyProperties2KHR(device,
+                                                          &mut num,
+                                                          families.as_mut_ptr());
+            families
+                .into_iter()
+                .map(|family| family.queueFamilyProperties)
+                .collect()
+        };
+
+        let memory: vk::PhysicalDeviceMemoryProperties = unsafe {
+            let mut output = vk::PhysicalDeviceMemoryProperties2KHR {
+                sType: vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_MEMORY_PROPERTIES_2_KHR,
+                pNext: ptr::null_mut(),
+                memoryProperties: mem::uninitialized(),
             };
+            vk.GetPhysicalDeviceMemoryProperties2KHR(device, &mut output);
+            output.memoryProperties
+        };
+
+        let available_features = unsafe {
+            let mut output = FeaturesFfi::new();
+            output.make_chain(&supported_extensions);
+            vk.GetPhysicalDeviceFeatures2KHR(device, output.head_as_mut());
+            Features::from_ffi(&output)
+        };
+
+        PhysicalDeviceInfos {
+            device: device,
+            properties: properties,
+            memory: memory,
+            queue_families: queue_families,
+            available_features: available_features,
+            extended_properties: extended_properties,
+            properties2: true,
+        }
+    }
 
-            output.push(PhysicalDeviceInfos {
-                            device: device,
-                            properties: properties,
-                            memory: memory,
-                            queue_families: queue_families,
-                            available_features: Features::from_vulkan_features(available_features),
-                        });
+    /// Returns the device extensions supported by a physical device, or none if they can't be
+    /// enumerated.
+    fn supported_device_extensions(vk: &vk::InstancePointers, device: vk::PhysicalDevice)
+                                   -> DeviceExtensions {
+        unsafe {
+            let mut num = 0;
+            if check_errors(vk.EnumerateDeviceExtensionProperties(device,
+                                                                  ptr::null(),
+                                                                  &mut num,
+                                                                  ptr::null_mut()))
+                .is_err()
+            {
+                return DeviceExtensions::none();
+            }
+
+            let mut properties: Vec<vk::ExtensionProperties> = Vec::with_capacity(num as usize);
+            if check_errors(vk.EnumerateDeviceExtensionProperties(device,
+                                                                  ptr::null(),
+                                                                  &mut num,
+                                                                  properties.as_mut_ptr()))
+                .is_err()
+            {
+                return DeviceExtensions::none();
+            }
+            properties.set_len(num as usize);
+
+            let names = properties
+                .iter()
+                .map(|p| CStr::from_ptr(p.extensionName.as_ptr()).to_owned());
+            (&RawDeviceExtensions::new(names)).into()
         }
-        output
     }
 
-    /*/// Same as `new`, but provides an allocator that will be used by the Vulkan library whenever
-    /// it needs to allocate memory on the host.
+    /// Same as `new`, but the host memory that the Vulkan implementation needs is allocated
+    /// through `callbacks`.
+    ///
+    /// The callbacks are used for the instance and for the objects that belong to it, such as
+    /// surfaces and debug messengers. They are also used by the devices created from this
+    /// instance, unless other callbacks are passed to `Device::with_allocation_callbacks`.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use std::sync::Arc;
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::InstanceExtensions;
+    /// use vulkano::memory::HostAllocationCallbacks;
     ///
-    /// Note that this allocator can be overriden when you create a `Device`, a `MemoryPool`, etc.
-    pub fn with_alloc(app_infos: Option<&ApplicationInfo>, alloc: Box<Alloc + Send + Sync>) -> Arc<Instance> {
-        unimplemented!()
-    }*/
+    /// struct Tracker;
+    /// impl HostAllocationCallbacks for Tracker {}
+    ///
+    /// let instance = Instance::with_allocation_callbacks(None, &InstanceExtensions::none(), None,
+    ///                                                    Arc::new(Tracker)).unwrap();
+    /// ```
+    pub fn with_allocation_callbacks<'a, L, Ext>(app_infos: Option<&ApplicationInfo>,
+                                                 extensions: Ext, layers: L,
+                                                 callbacks: Arc<HostAllocationCallbacks>)
+                                                 -> Result<Arc<Instance>, InstanceCreationError>
+        where L: IntoIterator<Item = &'a &'a str>,
+              Ext: Into<RawInstanceExtensions>
+    {
+        let layers = layers
+            .into_iter()
+            .map(|&layer| CString::new(layer).unwrap())
+            .collect::<SmallVec<[_; 16]>>();
+
+        Instance::new_inner(app_infos,
+                            extensions.into(),
+                            layers,
+                            &ValidationFeatures::none(),
+                            None,
+                            Some(HostAllocator::new(callbacks)),
+                            OwnedOrRef::Ref(loader::auto_loader()?))
+    }
+
+    /// Returns the callbacks that were passed to `with_allocation_callbacks`, if any.
+    #[inline]
+    pub fn allocation_callbacks(&self) -> Option<&Arc<HostAllocationCallbacks>> {
+        self.allocator.as_ref().map(|a| a.callbacks())
+    }
+
+    /// Returns the `pAllocator` parameter to pass when creating or destroying the instance or
+    /// one of its objects.
+    #[inline]
+    pub(crate) fn allocator_ptr(&self) -> *const vk::AllocationCallbacks {
+        HostAllocator::as_ptr(&self.allocator)
+    }
 
     /// Grants access to the Vulkan functions of the instance.
     #[inline]
@@ -433,7 +852,8 @@ impl Instance {
 
     /// Returns the list of extensions that have been loaded.
     ///
-    /// This list is equal to what was passed to `Instance::new()`.
+    /// This list is equal to what was passed to `Instance::new()`, plus the extensions that were
+    /// promoted to the core API of the version returned by `api_version()`.
     ///
     /// # Example
     ///
@@ -450,6 +870,23 @@ impl Instance {
         &self.extensions
     }
 
+    /// Returns the version of Vulkan used by this instance.
+    ///
+    /// This is the version requested in the `ApplicationInfo` passed to `Instance::new()`, or
+    /// Vulkan 1.0 if none was requested. It is lowered to the version supported by the Vulkan
+    /// implementation if that one is older.
+    #[inline]
+    pub fn api_version(&self) -> Version {
+        self.api_version
+    }
+
+    /// Returns the features of the validation layer that were enabled or disabled when creating
+    /// this instance.
+    #[inline]
+    pub fn enabled_validation_features(&self) -> &ValidationFeatures {
+        &self.validation_features
+    }
+
     /// Returns the list of layers requested when creating this instance.
     #[doc(hidden)]
     #[inline]
@@ -480,7 +917,7 @@ impl Drop for Instance {
     #[inline]
     fn drop(&mut self) {
         unsafe {
-            self.vk.DestroyInstance(self.instance, ptr::null());
+            self.vk.DestroyInstance(self.instance, self.allocator_ptr());
         }
     }
 }
@@ -514,6 +951,12 @@ pub struct ApplicationInfo<'a> {
     pub engine_name: Option<Cow<'a, str>>,
     /// An opaque number that contains the version number of the engine.
     pub engine_version: Option<Version>,
+    /// Highest version of Vulkan that the application is designed to use. `None` means Vulkan
+    /// 1.0.
+    ///
+    /// The extensions that were promoted to the core API of this version are treated as loaded
+    /// by the instance and by the devices that support this version.
+    pub api_version: Option<Version>,
 }
 
 impl<'a> ApplicationInfo<'a> {
@@ -539,6 +982,7 @@ impl<'a> ApplicationInfo<'a> {
             application_version: Some(version),
             engine_name: None,
             engine_version: None,
+            api_version: None,
         }
     }
 }
@@ -566,6 +1010,7 @@ macro_rules! app_info_from_cargo_toml {
             application_version: Some(version),
             engine_name: None,
             engine_version: None,
+            api_version: None,
         }
     }}
 }
@@ -577,7 +1022,150 @@ impl<'a> Default for ApplicationInfo<'a> {
             application_version: None,
             engine_name: None,
             engine_version: None,
+            api_version: None,
+        }
+    }
+}
+
+/// Features of the validation layer that can be enabled or disabled when creating an instance.
+///
+/// See `Instance::with_validation_features`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ValidationFeatures {
+    /// Shaders are instrumented to detect out-of-bounds accesses and uses of uninitialized
+    /// descriptors at runtime. Can't be enabled together with `debug_printf`.
+    pub gpu_assisted: bool,
+    /// The validation layer reserves a descriptor set binding slot for its own use when
+    /// `gpu_assisted` is enabled, and reduces the maximum number of bound descriptor sets
+    /// reported to the application by one. Requires `gpu_assisted`.
+    pub gpu_assisted_reserve_binding_slot: bool,
+    /// Warnings are emitted about API uses that are valid but potentially non-optimal.
+    pub best_practices: bool,
+    /// Calls to `debugPrintfEXT` in shaders are reported to the debug callbacks, as
+    /// informational messages whose layer prefix contains `DEBUG-PRINTF`. The shaders must be
+    /// used with a device that has the `VK_KHR_shader_non_semantic_info` extension enabled.
+    /// Can't be enabled together with `gpu_assisted`.
+    ///
+    /// See also `debug::DebugPrintfCapture`.
+    pub debug_printf: bool,
+    /// Missing or incorrect synchronization between commands and between queue submissions is
+    /// reported.
+    pub synchronization_validation: bool,
+    /// Checks that the validation layer performs by default and that are disabled.
+    pub disabled: DisabledValidationChecks,
+}
+
+impl ValidationFeatures {
+    /// Builds a `ValidationFeatures` with all fields set to `false`.
+    #[inline]
+    pub fn none() -> ValidationFeatures {
+        ValidationFeatures {
+            gpu_assisted: false,
+            gpu_assisted_reserve_binding_slot: false,
+            best_practices: false,
+            debug_printf: false,
+            synchronization_validation: false,
+            disabled: DisabledValidationChecks::none(),
+        }
+    }
+
+    // Returns true if the features can be requested together.
+    #[inline]
+    fn is_valid(&self) -> bool {
+        !(self.gpu_assisted && self.debug_printf) &&
+            (self.gpu_assisted || !self.gpu_assisted_reserve_binding_slot)
+    }
+
+    #[inline]
+    fn into_vulkan_list(&self) -> SmallVec<[vk::ValidationFeatureEnableEXT; 4]> {
+        let mut list = SmallVec::new();
+        if self.gpu_assisted {
+            list.push(vk::VALIDATION_FEATURE_ENABLE_GPU_ASSISTED_EXT);
+        }
+        if self.gpu_assisted_reserve_binding_slot {
+            list.push(vk::VALIDATION_FEATURE_ENABLE_GPU_ASSISTED_RESERVE_BINDING_SLOT_EXT);
+        }
+        if self.best_practices {
+            list.push(vk::VALIDATION_FEATURE_ENABLE_BEST_PRACTICES_EXT);
+        }
+        if self.debug_printf {
+            list.push(vk::VALIDATION_FEATURE_ENABLE_DEBUG_PRINTF_EXT);
+        }
+        if self.synchronization_validation {
+            list.push(vk::VALIDATION_FEATURE_ENABLE_SYNCHRONIZATION_VALIDATION_EXT);
+        }
+        list
+    }
+}
+
+/// Checks of the validation layer that can be disabled when creating an instance.
+///
+/// See `ValidationFeatures::disabled`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct DisabledValidationChecks {
+    /// All the checks are disabled.
+    pub all: bool,
+    /// The validation of shader modules is disabled.
+    pub shaders: bool,
+    /// The detection of objects that are used concurrently from multiple threads is disabled.
+    pub thread_safety: bool,
+    /// The validation of the parameters of the API functions is disabled.
+    pub api_parameters: bool,
+    /// The tracking of the lifetime of objects is disabled.
+    pub object_lifetimes: bool,
+    /// The core checks of the validation layer, such as the checks of the state of command
+    /// buffers and of the resources that they use, are disabled.
+    pub core_checks: bool,
+    /// The wrapping of handles into unique identifiers is disabled.
+    pub unique_handles: bool,
+    /// The cache of the results of the validation of shader modules is disabled.
+    pub shader_validation_cache: bool,
+}
+
+impl DisabledValidationChecks {
+    /// Builds a `DisabledValidationChecks` with all fields set to `false`.
+    #[inline]
+    pub fn none() -> DisabledValidationChecks {
+        DisabledValidationChecks {
+            all: false,
+            shaders: false,
+            thread_safety: false,
+            api_parameters: false,
+            object_lifetimes: false,
+            core_checks: false,
+            unique_handles: false,
+            shader_validation_cache: false,
+        }
+    }
+
+    #[inline]
+    fn into_vulkan_list(&self) -> SmallVec<[vk::ValidationFeatureDisableEXT; 8]> {
+        let mut list = SmallVec::new();
+        if self.all {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_ALL_EXT);
+        }
+        if self.shaders {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_SHADERS_EXT);
+        }
+        if self.thread_safety {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_THREAD_SAFETY_EXT);
         }
+        if self.api_parameters {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_API_PARAMETERS_EXT);
+        }
+        if self.object_lifetimes {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_OBJECT_LIFETIMES_EXT);
+        }
+        if self.core_checks {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_CORE_CHECKS_EXT);
+        }
+        if self.unique_handles {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_UNIQUE_HANDLES_EXT);
+        }
+        if self.shader_validation_cache {
+            list.push(vk::VALIDATION_FEATURE_DISABLE_SHADER_VALIDATION_CACHE_EXT);
+        }
+        list
     }
 }
 
@@ -594,9 +1182,26 @@ pub enum InstanceCreationError {
     LayerNotPresent,
     /// One of the requested extensions is missing.
     ExtensionNotPresent,
-    /// The version requested is not supported by the implementation.
-    // TODO: more info about this once the question of the version has been resolved
+    /// No installed driver supports the version of Vulkan that was requested, or no driver is
+    /// installed at all.
+    ///
+    /// The requested version is already lowered to the version supported by the loader, so this
+    /// usually means that Vulkan is not usable on the system.
     IncompatibleDriver,
+    /// The version of Vulkan requested in `ApplicationInfo` is lower than Vulkan 1.0.
+    InvalidApiVersion {
+        /// The version that was requested.
+        requested: Version,
+    },
+    /// Validation features were requested, but the `VK_EXT_validation_features` extension isn't
+    /// enabled.
+    ValidationFeaturesExtensionNotEnabled,
+    /// Both `gpu_assisted` and `debug_printf` validation features were requested, or
+    /// `gpu_assisted_reserve_binding_slot` was requested without `gpu_assisted`.
+    IncompatibleValidationFeatures,
+    /// A debug utils messenger was requested, but the `VK_EXT_debug_utils` extension isn't
+    /// enabled.
+    DebugUtilsExtensionNotEnabled,
 }
 
 impl error::Error for InstanceCreationError {
@@ -608,7 +1213,21 @@ impl error::Error for InstanceCreationError {
             InstanceCreationError::InitializationFailed => "initialization failed",
             InstanceCreationError::LayerNotPresent => "layer not present",
             InstanceCreationError::ExtensionNotPresent => "extension not present",
-            InstanceCreationError::IncompatibleDriver => "incompatible driver",
+            InstanceCreationError::IncompatibleDriver => {
+                "no installed driver supports the requested version of Vulkan"
+            },
+            InstanceCreationError::InvalidApiVersion { .. } => {
+                "the requested version of Vulkan is lower than Vulkan 1.0"
+            },
+            InstanceCreationError::ValidationFeaturesExtensionNotEnabled => {
+                "the `VK_EXT_validation_features` extension isn't enabled"
+            },
+            InstanceCreationError::IncompatibleValidationFeatures => {
+                "the requested validation features can't be enabled together"
+            },
+            InstanceCreationError::DebugUtilsExtensionNotEnabled => {
+                "the `VK_EXT_debug_utils` extension isn't enabled"
+            },
         }
     }
 
@@ -664,6 +1283,10 @@ struct PhysicalDeviceInfos {
     queue_families: Vec<vk::QueueFamilyProperties>,
     memory: vk::PhysicalDeviceMemoryProperties,
     available_features: Features,
+    extended_properties: ExtendedProperties,
+    // True if the properties and the features were queried with the `*2` functions, which can
+    // then be used with this physical device.
+    properties2: bool,
 }
 
 /// Represents one of the available devices on this machine.
@@ -742,6 +1365,24 @@ impl<'a> PhysicalDevice<'a> {
         }
     }
 
+    /// Returns the physical device of `instance` whose raw handle is `handle`, for example the
+    /// one that an OpenXR runtime returns from `xrGetVulkanGraphicsDevice2KHR`.
+    ///
+    /// Returns `None` if `handle` isn't one of the physical devices of the instance.
+    #[inline]
+    pub fn from_raw(instance: &'a Arc<Instance>, handle: u64) -> Option<PhysicalDevice<'a>> {
+        instance
+            .physical_devices
+            .iter()
+            .position(|infos| infos.device as u64 == handle)
+            .map(|index| {
+                     PhysicalDevice {
+                         instance: instance,
+                         device: index,
+                     }
+                 })
+    }
+
     /// Returns the instance corresponding to this physical device.
     ///
     /// # Example
@@ -817,6 +1458,14 @@ impl<'a> PhysicalDevice<'a> {
         Version::from_vulkan_version(val)
     }
 
+    /// Returns true if `vkGetPhysicalDeviceProperties2` and the other functions of the
+    /// `VK_KHR_get_physical_device_properties2` extension can be used with this physical device,
+    /// either through the extension or through Vulkan 1.1.
+    #[inline]
+    pub(crate) fn supports_properties2(&self) -> bool {
+        self.infos().properties2
+    }
+
     /// Returns the Vulkan features that are supported by this physical device.
     #[inline]
     pub fn supported_features(&self) -> &'a Features {
@@ -910,6 +1559,28 @@ impl<'a> PhysicalDevice<'a> {
         self.infos().properties.driverVersion
     }
 
+    /// Returns the version of the driver of this device, decoded with the scheme of the vendor.
+    ///
+    /// See the documentation of `DriverVersion` for the encodings that are known.
+    #[inline]
+    pub fn decoded_driver_version(&self) -> DriverVersion {
+        DriverVersion::decode(self.vendor(), self.driver_version())
+    }
+
+    /// Returns the name of the driver of this device, or `None` if the physical device doesn't
+    /// support the `VK_KHR_driver_properties` extension.
+    #[inline]
+    pub fn driver_name(&self) -> Option<&'a str> {
+        self.driver_properties().map(|p| p.driver_name.as_str())
+    }
+
+    /// Returns additional information about the driver of this device, or `None` if the physical
+    /// device doesn't support the `VK_KHR_driver_properties` extension.
+    #[inline]
+    pub fn driver_info(&self) -> Option<&'a str> {
+        self.driver_properties().map(|p| p.driver_info.as_str())
+    }
+
     /// Returns the PCI ID of the device.
     #[inline]
     pub fn pci_device_id(&self) -> u32 {
@@ -922,6 +1593,49 @@ impl<'a> PhysicalDevice<'a> {
         self.infos().properties.vendorID
     }
 
+    /// Returns the identifiers of the device and of its driver, or `None` if the physical device
+    /// doesn't support Vulkan 1.1 or if Vulkan 1.1 isn't enabled on the instance.
+    #[inline]
+    pub fn id_properties(&self) -> Option<&'a IdProperties> {
+        self.infos().extended_properties.id.as_ref()
+    }
+
+    /// Returns the universally unique identifier of the device, or `None` if the ID properties
+    /// are unavailable. See `id_properties`.
+    ///
+    /// Contrary to `uuid`, this identifier is the same across driver versions.
+    #[inline]
+    pub fn device_uuid(&self) -> Option<&'a [u8; 16]> {
+        self.id_properties().map(|p| &p.device_uuid)
+    }
+
+    /// Returns the universally unique identifier of the driver of the device, or `None` if the
+    /// ID properties are unavailable. See `id_properties`.
+    #[inline]
+    pub fn driver_uuid(&self) -> Option<&'a [u8; 16]> {
+        self.id_properties().map(|p| &p.driver_uuid)
+    }
+
+    /// Returns the locally unique identifier of the device, or `None` if it's unavailable. It is
+    /// only available on Windows.
+    #[inline]
+    pub fn device_luid(&self) -> Option<&'a [u8; 8]> {
+        self.id_properties().and_then(|p| p.device_luid.as_ref())
+    }
+
+    /// Returns the node mask of the device, or `None` if the LUID of the device is unavailable.
+    #[inline]
+    pub fn device_node_mask(&self) -> Option<u32> {
+        self.id_properties()
+            .and_then(|p| p.device_luid.map(|_| p.device_node_mask))
+    }
+
+    /// Returns the vendor of the device.
+    #[inline]
+    pub fn vendor(&self) -> Vendor {
+        Vendor::from_id(self.pci_vendor_id())
+    }
+
     /// Returns a unique identifier for the device.
     ///
     /// Can be stored in a configuration file, so that you can retrieve the device again the next
@@ -932,6 +1646,102 @@ impl<'a> PhysicalDevice<'a> {
         &self.infos().properties.pipelineCacheUUID
     }
 
+    /// Returns the subgroup properties of the physical device. The subgroup size is 0 and no
+    /// operation is supported if the physical device doesn't support Vulkan 1.1, or if the
+    /// `VK_KHR_get_physical_device_properties2` extension isn't loaded on the instance.
+    #[inline]
+    pub fn subgroup_properties(&self) -> &'a SubgroupProperties {
+        &self.infos().extended_properties.subgroup
+    }
+
+    /// Returns the properties of the physical device related to the
+    /// `VK_EXT_subgroup_size_control` extension. They are all 0 if the physical device doesn't
+    /// support the extension.
+    #[inline]
+    pub fn subgroup_size_control_properties(&self) -> &'a SubgroupSizeControlProperties {
+        &self.infos().extended_properties.subgroup_size_control
+    }
+
+    /// Returns the properties of the physical device related to ray tracing. They are all 0 if
+    /// the physical device doesn't support the `VK_KHR_acceleration_structure` and
+    /// `VK_KHR_ray_tracing_pipeline` extensions.
+    #[inline]
+    pub fn ray_tracing_properties(&self) -> &'a RayTracingProperties {
+        &self.infos().extended_properties.ray_tracing
+    }
+
+    /// Returns the maximum number of draws of a single multi-draw command. Always 0 if the
+    /// physical device doesn't support the `VK_EXT_multi_draw` extension.
+    #[inline]
+    pub fn max_multi_draw_count(&self) -> u32 {
+        self.infos().extended_properties.max_multi_draw_count
+    }
+
+    /// Returns the maximum extra size by which primitives can be overestimated by conservative
+    /// rasterization. Always 0.0 if the physical device doesn't support the
+    /// `VK_EXT_conservative_rasterization` extension.
+    #[inline]
+    pub fn max_extra_primitive_overestimation_size(&self) -> f32 {
+        self.infos().extended_properties.max_extra_primitive_overestimation_size
+    }
+
+    /// Returns the limits of the physical device related to the `VK_EXT_mesh_shader` extension.
+    /// They are all 0 if the physical device doesn't support the extension.
+    #[inline]
+    pub fn mesh_shader_properties(&self) -> &'a MeshShaderProperties {
+        &self.infos().extended_properties.mesh_shader
+    }
+
+    /// Returns the maximum instance divisor of the vertex input bindings of graphics pipelines.
+    /// Always 0 if the physical device doesn't support the `VK_EXT_vertex_attribute_divisor`
+    /// extension.
+    #[inline]
+    pub fn max_vertex_attrib_divisor(&self) -> u32 {
+        self.infos().extended_properties.max_vertex_attrib_divisor
+    }
+
+    /// Returns the properties of the physical device related to the `VK_EXT_transform_feedback`
+    /// extension. They are all 0 or false if the physical device doesn't support the extension.
+    #[inline]
+    pub fn transform_feedback_properties(&self) -> &'a TransformFeedbackProperties {
+        &self.infos().extended_properties.transform_feedback
+    }
+
+    /// Returns the properties of the driver of the physical device, or `None` if the physical
+    /// device doesn't support the `VK_KHR_driver_properties` extension.
+    #[inline]
+    pub fn driver_properties(&self) -> Option<&'a DriverProperties> {
+        self.infos().extended_properties.driver.as_ref()
+    }
+
+    /// Returns the limits of the physical device related to the `VK_EXT_descriptor_indexing`
+    /// extension. They are all 0 or false if the physical device doesn't support the extension.
+    #[inline]
+    pub fn descriptor_indexing_properties(&self) -> &'a DescriptorIndexingProperties {
+        &self.infos().extended_properties.descriptor_indexing
+    }
+
+    /// Returns the limits of the physical device related to the `VK_KHR_maintenance3` extension,
+    /// which is core in Vulkan 1.1. They are all 0 if the physical device supports neither.
+    #[inline]
+    pub fn maintenance3_properties(&self) -> &'a Maintenance3Properties {
+        &self.infos().extended_properties.maintenance3
+    }
+
+    /// Returns the limits of the physical device related to the `VK_KHR_portability_subset`
+    /// extension, or `None` if the physical device fully conforms to the Vulkan specifications.
+    ///
+    /// Devices that only implement a subset of the API, such as MoltenVK on macOS and iOS, support
+    /// this extension. The functionalities that they lack are listed in the `Features` struct,
+    /// from `constant_alpha_color_blend_factors` to `vertex_attribute_access_beyond_stride`.
+    ///
+    /// Always returns `None` if the `VK_KHR_get_physical_device_properties2` extension isn't
+    /// loaded on the instance.
+    #[inline]
+    pub fn portability_subset_properties(&self) -> Option<&'a PortabilitySubsetProperties> {
+        self.infos().extended_properties.portability_subset.as_ref()
+    }
+
     // Internal function to make it easier to get the infos of this device.
     #[inline]
     fn infos(&self) -> &'a PhysicalDeviceInfos {
@@ -1062,6 +1872,19 @@ impl<'a> QueueFamily<'a> {
         (self.flags() & vk::QUEUE_SPARSE_BINDING_BIT) != 0
     }
 
+    /// Returns true if protected queues can be created from this family.
+    #[inline]
+    pub fn supports_protected(&self) -> bool {
+        (self.flags() & vk::QUEUE_PROTECTED_BIT) != 0
+    }
+
+    /// Returns the number of meaningful bits in the timestamps written by queues of this family,
+    /// or 0 if they don't support timestamps.
+    #[inline]
+    pub fn timestamp_valid_bits(&self) -> u32 {
+        self.physical_device.infos().queue_families[self.id as usize].timestampValidBits
+    }
+
     /// Internal utility function that returns the flags of this queue family.
     #[inline]
     fn flags(&self) -> u32 {
@@ -1292,6 +2115,7 @@ impl<'a> ExactSizeIterator for MemoryHeapsIter<'a> {
 
 #[cfg(test)]
 mod tests {
+    use VulkanObject;
     use instance;
 
     #[test]
@@ -1299,6 +2123,18 @@ mod tests {
         let _ = instance!();
     }
 
+    #[test]
+    fn physical_device_from_raw() {
+        let instance = instance!();
+
+        for phys in instance::PhysicalDevice::enumerate(&instance) {
+            let same = instance::PhysicalDevice::from_raw(&instance, phys.as_raw()).unwrap();
+            assert_eq!(same.index(), phys.index());
+        }
+
+        assert!(instance::PhysicalDevice::from_raw(&instance, 0).is_none());
+    }
+
     #[test]
     fn queue_family_by_id() {
         let instance = instance!();
@@ -1316,4 +2152,103 @@ mod tests {
         let by_id = phys.queue_family_by_id(queue_family.id()).unwrap();
         assert_eq!(by_id.id(), queue_family.id());
     }
+
+    #[test]
+    fn extended_properties_without_properties2() {
+        // `instance!()` doesn't load `VK_KHR_get_physical_device_properties2`.
+        let instance = instance!();
+
+        for phys in instance::PhysicalDevice::enumerate(&instance) {
+            assert_eq!(phys.subgroup_properties().subgroup_size, 0);
+            assert_eq!(phys.max_multi_draw_count(), 0);
+            assert!(phys.driver_properties().is_none());
+            assert!(phys.id_properties().is_none());
+            assert_eq!(*phys.maintenance3_properties(), Default::default());
+            assert_eq!(*phys.descriptor_indexing_properties(), Default::default());
+        }
+    }
+
+    #[test]
+    fn validation_features_without_extension() {
+        let features = instance::ValidationFeatures {
+            debug_printf: true,
+            ..instance::ValidationFeatures::none()
+        };
+
+        match instance::Instance::with_validation_features(None,
+                                                           &instance::InstanceExtensions::none(),
+                                                           None,
+                                                           &features) {
+            Err(instance::InstanceCreationError::ValidationFeaturesExtensionNotEnabled) => (),
+            // Vulkan isn't available.
+            Err(instance::InstanceCreationError::LoadingError(_)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn incompatible_validation_features() {
+        let features = instance::ValidationFeatures {
+            gpu_assisted: true,
+            debug_printf: true,
+            ..instance::ValidationFeatures::none()
+        };
+        let extensions = instance::InstanceExtensions {
+            ext_validation_features: true,
+            ..instance::InstanceExtensions::none()
+        };
+
+        match instance::Instance::with_validation_features(None, &extensions, None, &features) {
+            Err(instance::InstanceCreationError::IncompatibleValidationFeatures) => (),
+            // Vulkan isn't available.
+            Err(instance::InstanceCreationError::LoadingError(_)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn debug_messenger_without_extension() {
+        match instance::Instance::with_debug_messenger(None,
+                                                       &instance::InstanceExtensions::none(),
+                                                       None,
+                                                       instance::debug::MessageSeverity::all(),
+                                                       instance::debug::MessageType::all(),
+                                                       |_| {}) {
+            Err(instance::InstanceCreationError::DebugUtilsExtensionNotEnabled) => (),
+            // Vulkan isn't available.
+            Err(instance::InstanceCreationError::LoadingError(_)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn portability_enumeration() {
+        let instance = instance!();
+        let supported = instance::InstanceExtensions::supported_by_core().unwrap();
+        assert_eq!(instance.loaded_extensions().khr_portability_enumeration,
+                   supported.khr_portability_enumeration);
+    }
+
+    #[test]
+    fn invalid_api_version() {
+        let app_infos = instance::ApplicationInfo {
+            api_version: Some(instance::Version { major: 0, minor: 9, patch: 0 }),
+            ..Default::default()
+        };
+
+        match instance::Instance::new(Some(&app_infos), &instance::InstanceExtensions::none(),
+                                      None) {
+            Err(instance::InstanceCreationError::InvalidApiVersion { .. }) => (),
+            // Vulkan isn't available.
+            Err(instance::InstanceCreationError::LoadingError(_)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn default_api_version() {
+        let instance = instance!();
+        assert_eq!(instance.api_version(),
+                   instance::Version { major: 1, minor: 0, patch: 0 });
+    }
 }
@@ -20,6 +20,24 @@
 //!
 //! By default vulkano will use the `auto_loader()` function, which tries to automatically load
 //! a Vulkan implementation from the system.
+//!
+//! # Running without Vulkan
+//!
+//! Except on iOS, vulkano doesn't link to the Vulkan library. It is opened at runtime the first
+//! time it is needed, which means that a program using vulkano can start on a machine where
+//! Vulkan isn't installed. In that situation, `Instance::new` returns
+//! `InstanceCreationError::LoadingError`, and the `is_available()` function returns false, so
+//! that the program can fall back to another renderer:
+//!
+//! ```
+//! use vulkano::instance::loader;
+//!
+//! if loader::is_available() {
+//!     // Use vulkano.
+//! } else {
+//!     // Fall back to another renderer.
+//! }
+//! ```
 
 use shared_library;
 use std::error;
@@ -30,7 +48,10 @@ use std::os::raw::c_char;
 use std::os::raw::c_void;
 use std::path::Path;
 
+use OomError;
 use SafeDeref;
+use check_errors;
+use version::Version;
 use vk;
 
 /// Implemented on objects that grant access to a Vulkan implementation.
@@ -132,6 +153,26 @@ impl<L> FunctionPointers<L> {
     {
         self.loader.get_instance_proc_addr(instance, name)
     }
+
+    /// Returns the highest Vulkan version supported by the loader for instances.
+    ///
+    /// Returns 1.0.0 if the loader doesn't support `vkEnumerateInstanceVersion`, which was added
+    /// in Vulkan 1.1.
+    pub fn api_version(&self) -> Result<Version, OomError>
+        where L: Loader
+    {
+        unsafe {
+            let name = b"vkEnumerateInstanceVersion\0";
+            let ptr = self.loader.get_instance_proc_addr(0, name.as_ptr() as *const c_char);
+            if ptr as usize == 0 {
+                return Ok(Version { major: 1, minor: 0, patch: 0 });
+            }
+
+            let mut version = 0;
+            check_errors(self.entry_points.EnumerateInstanceVersion(&mut version))?;
+            Ok(Version::from_vulkan_version(version))
+        }
+    }
 }
 
 /// Expression that returns a loader that assumes that Vulkan is linked to the executable you're
@@ -180,25 +221,27 @@ pub fn auto_loader()
 
     #[cfg(not(target_os = "ios"))]
     fn def_loader_impl() -> Result<Box<Loader + Send + Sync>, LoadingError> {
+        // The names of the library to try, in order. The first one is the name of the library
+        // installed by the official Vulkan loader, and the others are fallbacks for systems
+        // where only a development symlink or MoltenVK are available.
         #[cfg(windows)]
-        fn get_path() -> &'static Path {
-            Path::new("vulkan-1.dll")
+        fn get_paths() -> &'static [&'static str] {
+            &["vulkan-1.dll"]
         }
         #[cfg(all(unix, not(target_os = "android"), not(target_os = "macos")))]
-        fn get_path() -> &'static Path {
-            Path::new("libvulkan.so.1")
+        fn get_paths() -> &'static [&'static str] {
+            &["libvulkan.so.1", "libvulkan.so"]
         }
         #[cfg(target_os = "macos")]
-        fn get_path() -> &'static Path {
-            Path::new("libvulkan.1.dylib")
+        fn get_paths() -> &'static [&'static str] {
+            &["libvulkan.1.dylib", "libvulkan.dylib", "libMoltenVK.dylib"]
         }
         #[cfg(target_os = "android")]
-        fn get_path() -> &'static Path {
-            Path::new("libvulkan.so")
+        fn get_paths() -> &'static [&'static str] {
+            &["libvulkan.so"]
         }
 
-        let loader = unsafe { DynamicLibraryLoader::new(get_path())? };
-
+        let loader = unsafe { load_first_library(get_paths())? };
         Ok(Box::new(loader))
     }
 
@@ -214,6 +257,35 @@ pub fn auto_loader()
     }
 }
 
+// Loads the first library of `paths` that can be loaded. If none of them can be loaded, returns
+// the error of the first one.
+//
+// Same safety requirements as `DynamicLibraryLoader::new`.
+#[cfg(not(target_os = "ios"))]
+unsafe fn load_first_library(paths: &[&str]) -> Result<DynamicLibraryLoader, LoadingError> {
+    let mut first_error = None;
+    for path in paths {
+        match DynamicLibraryLoader::new(Path::new(path)) {
+            Ok(loader) => return Ok(loader),
+            Err(err) => if first_error.is_none() {
+                first_error = Some(err);
+            },
+        }
+    }
+
+    Err(first_error.unwrap())
+}
+
+/// Returns true if a Vulkan implementation can be loaded with `auto_loader()`.
+///
+/// If this returns false, creating an `Instance` with the default loader will fail with
+/// `InstanceCreationError::LoadingError`. Note that a return value of true doesn't guarantee
+/// that the system has a physical device that supports Vulkan.
+#[inline]
+pub fn is_available() -> bool {
+    auto_loader().is_ok()
+}
+
 /// Error that can happen when loading the Vulkan loader.
 #[derive(Debug, Clone)]
 pub enum LoadingError {
@@ -256,6 +328,10 @@ impl fmt::Display for LoadingError {
 
 #[cfg(test)]
 mod tests {
+    use instance::Instance;
+    use instance::InstanceCreationError;
+    use instance::InstanceExtensions;
+    use instance::loader;
     use instance::loader::DynamicLibraryLoader;
     use instance::loader::LoadingError;
 
@@ -268,4 +344,32 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn fallback_reports_first_error() {
+        unsafe {
+            let first = match DynamicLibraryLoader::new("_non_existing_library.void") {
+                Err(LoadingError::LibraryLoadFailure(msg)) => msg,
+                _ => panic!(),
+            };
+
+            let paths = ["_non_existing_library.void", "_other_non_existing_library.void"];
+            match loader::load_first_library(&paths) {
+                Err(LoadingError::LibraryLoadFailure(msg)) => assert_eq!(msg, first),
+                _ => panic!(),
+            }
+        }
+    }
+
+    #[test]
+    fn unavailable_instance_creation() {
+        if loader::is_available() {
+            return;
+        }
+
+        match Instance::new(None, &InstanceExtensions::none(), None) {
+            Err(InstanceCreationError::LoadingError(_)) => (),
+            _ => panic!(),
+        }
+    }
 }
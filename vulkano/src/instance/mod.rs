@@ -46,6 +46,11 @@
 //! Contrary to OpenGL, it is not possible to use the features of an extension if it was not
 //! explicitly enabled.
 //!
+//! Extensions that vulkano doesn't know about can be enabled by passing their names in a
+//! `RawInstanceExtensions` or a `RawDeviceExtensions` instead. The names and versions of all the
+//! available extensions are returned by `instance_extensions_list()` and
+//! `device_extensions_list()`.
+//!
 //! Extensions are especially important to take into account if you want to render images on the
 //! screen, as the only way to do so is to use the `VK_KHR_surface` extension. More information
 //! about this in the `swapchain` module.
@@ -102,15 +107,28 @@
 //! be able to draw to a certain surface (ie. to a window or a monitor), or may even not be able
 //! to draw at all. See the `swapchain` module for more information about surfaces.
 //!
+//! Instead of enumerating the physical devices yourself, you can describe the extensions,
+//! features and queue operations that your application needs with a `DeviceRequirements` and let
+//! `PhysicalDevice::select()` or `PhysicalDevice::select_for_surface()` choose the best physical
+//! device and the queue families to use.
+//!
 //! Once you have chosen a physical device, you can create a `Device` object from it. See the
 //! `device` module for more info.
 //!
 
+pub use self::device_group::PhysicalDeviceGroup;
 pub use self::extensions::DeviceExtensions;
+pub use self::extensions::ExtensionProperties;
+pub use self::extensions::ExtensionsIterator;
 pub use self::extensions::InstanceExtensions;
 pub use self::extensions::RawDeviceExtensions;
 pub use self::extensions::RawInstanceExtensions;
+pub use self::extensions::SupportedExtensionsError;
+pub use self::extensions::device_extensions_list;
+pub use self::extensions::instance_extensions_list;
+pub use self::extensions::instance_extensions_list_from_loader;
 pub use self::instance::ApplicationInfo;
+pub use self::instance::DisabledValidationChecks;
 pub use self::instance::Instance;
 pub use self::instance::InstanceCreationError;
 pub use self::instance::MemoryHeap;
@@ -122,19 +140,39 @@ pub use self::instance::PhysicalDeviceType;
 pub use self::instance::PhysicalDevicesIter;
 pub use self::instance::QueueFamiliesIter;
 pub use self::instance::QueueFamily;
+pub use self::instance::ValidationFeatures;
 pub use self::layers::LayerProperties;
 pub use self::layers::LayersIterator;
 pub use self::layers::LayersListError;
 pub use self::layers::layers_list;
 pub use self::limits::Limits;
 pub use self::loader::LoadingError;
+pub use self::properties::ConformanceVersion;
+pub use self::properties::DescriptorIndexingProperties;
+pub use self::properties::DriverProperties;
+pub use self::properties::IdProperties;
+pub use self::properties::Maintenance3Properties;
+pub use self::properties::PortabilitySubsetProperties;
+pub use self::selection::DeviceRequirements;
+pub use self::selection::SelectedPhysicalDevice;
+pub use self::tooling::ToolProperties;
+pub use self::tooling::ToolPurposes;
+pub use self::vendor::DriverVersion;
+pub use self::vendor::Vendor;
 pub use features::Features;
 pub use version::Version;
 
+pub(crate) use self::properties::ExtendedProperties;
+
 pub mod debug;
 pub mod loader;
 
+mod device_group;
 mod extensions;
 mod instance;
 mod layers;
 mod limits;
+mod properties;
+mod selection;
+mod tooling;
+mod vendor;
@@ -0,0 +1,503 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Properties of a physical device that are queried with `vkGetPhysicalDeviceProperties2KHR`.
+
+use std::ffi::CStr;
+use std::mem;
+use std::os::raw::c_char;
+use std::os::raw::c_void;
+use std::ptr;
+
+use command_buffer::TransformFeedbackProperties;
+use descriptor::descriptor::ShaderStages;
+use instance::DeviceExtensions;
+use pipeline::RayTracingProperties;
+use pipeline::shader::MeshShaderProperties;
+use pipeline::shader::SubgroupOperations;
+use pipeline::shader::SubgroupProperties;
+use pipeline::shader::SubgroupSizeControlProperties;
+use version::Version;
+use vk;
+
+/// Properties of the driver of a physical device.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DriverProperties {
+    /// Identifier of the driver, as one of the values of the `VkDriverId` enumeration.
+    pub driver_id: u32,
+    /// Name of the driver.
+    pub driver_name: String,
+    /// Additional information about the driver, such as its version.
+    pub driver_info: String,
+    /// Version of the Vulkan conformance test suite that the driver passed.
+    pub conformance_version: ConformanceVersion,
+}
+
+/// Version of the Vulkan conformance test suite.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ConformanceVersion {
+    pub major: u8,
+    pub minor: u8,
+    pub subminor: u8,
+    pub patch: u8,
+}
+
+/// Identifiers of a physical device and of its driver, as returned by
+/// `PhysicalDevice::id_properties`.
+///
+/// These identifiers can be used to match the physical device with the same device in other
+/// APIs, such as DXGI adapters or OpenXR runtimes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct IdProperties {
+    /// Universally unique identifier of the device.
+    pub device_uuid: [u8; 16],
+    /// Universally unique identifier of the version of the driver. Memory and semaphores shared
+    /// with another instance or process can only be used if the driver UUIDs match.
+    pub driver_uuid: [u8; 16],
+    /// Locally unique identifier of the device. Only available on Windows, where it matches the
+    /// LUID of the corresponding DXGI adapter.
+    pub device_luid: Option<[u8; 8]>,
+    /// Mask with a single bit set, that designates the node of the device in a linked device
+    /// adapter. Only meaningful if `device_luid` is `Some`.
+    pub device_node_mask: u32,
+}
+
+/// Limits of the physical device related to the `VK_KHR_maintenance3` extension.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct Maintenance3Properties {
+    /// Maximum number of descriptors in a single descriptor set.
+    pub max_per_set_descriptors: u32,
+    /// Maximum size of a single memory allocation.
+    pub max_memory_allocation_size: u64,
+}
+
+/// Limits of a physical device that only implements a subset of the Vulkan API, as described
+/// by the `VK_KHR_portability_subset` extension.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct PortabilitySubsetProperties {
+    /// Required alignment of the stride of the vertex input bindings of graphics pipelines.
+    pub min_vertex_input_binding_stride_alignment: u32,
+}
+
+/// Limits of the physical device related to the `VK_EXT_descriptor_indexing` extension.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DescriptorIndexingProperties {
+    /// Maximum number of descriptors that can be created with the update-after-bind flag in all
+    /// the descriptor pools.
+    pub max_update_after_bind_descriptors_in_all_pools: u32,
+    /// True if arrays of uniform buffers can be indexed with non-uniform values without a
+    /// performance penalty.
+    pub shader_uniform_buffer_array_non_uniform_indexing_native: bool,
+    /// True if arrays of sampled images can be indexed with non-uniform values without a
+    /// performance penalty.
+    pub shader_sampled_image_array_non_uniform_indexing_native: bool,
+    /// True if arrays of storage buffers can be indexed with non-uniform values without a
+    /// performance penalty.
+    pub shader_storage_buffer_array_non_uniform_indexing_native: bool,
+    /// True if arrays of storage images can be indexed with non-uniform values without a
+    /// performance penalty.
+    pub shader_storage_image_array_non_uniform_indexing_native: bool,
+    /// True if arrays of input attachments can be indexed with non-uniform values without a
+    /// performance penalty.
+    pub shader_input_attachment_array_non_uniform_indexing_native: bool,
+    /// True if `robust_buffer_access` can be used with update-after-bind descriptors.
+    pub robust_buffer_access_update_after_bind: bool,
+    /// True if implicit level of detail computations in quads with divergent values give
+    /// correct results.
+    pub quad_divergent_implicit_lod: bool,
+    pub max_per_stage_descriptor_update_after_bind_samplers: u32,
+    pub max_per_stage_descriptor_update_after_bind_uniform_buffers: u32,
+    pub max_per_stage_descriptor_update_after_bind_storage_buffers: u32,
+    pub max_per_stage_descriptor_update_after_bind_sampled_images: u32,
+    pub max_per_stage_descriptor_update_after_bind_storage_images: u32,
+    pub max_per_stage_descriptor_update_after_bind_input_attachments: u32,
+    pub max_per_stage_update_after_bind_resources: u32,
+    pub max_descriptor_set_update_after_bind_samplers: u32,
+    pub max_descriptor_set_update_after_bind_uniform_buffers: u32,
+    pub max_descriptor_set_update_after_bind_uniform_buffers_dynamic: u32,
+    pub max_descriptor_set_update_after_bind_storage_buffers: u32,
+    pub max_descriptor_set_update_after_bind_storage_buffers_dynamic: u32,
+    pub max_descriptor_set_update_after_bind_sampled_images: u32,
+    pub max_descriptor_set_update_after_bind_storage_images: u32,
+    pub max_descriptor_set_update_after_bind_input_attachments: u32,
+}
+
+/// Properties of a physical device that aren't part of `VkPhysicalDeviceProperties`.
+///
+/// The properties of an extension are all 0 or false if it wasn't chained when querying them.
+#[derive(Clone, Debug)]
+pub(crate) struct ExtendedProperties {
+    pub subgroup: SubgroupProperties,
+    pub subgroup_size_control: SubgroupSizeControlProperties,
+    pub ray_tracing: RayTracingProperties,
+    pub max_multi_draw_count: u32,
+    pub max_extra_primitive_overestimation_size: f32,
+    pub mesh_shader: MeshShaderProperties,
+    pub max_vertex_attrib_divisor: u32,
+    pub transform_feedback: TransformFeedbackProperties,
+    pub driver: Option<DriverProperties>,
+    pub id: Option<IdProperties>,
+    pub descriptor_indexing: DescriptorIndexingProperties,
+    pub maintenance3: Maintenance3Properties,
+    pub portability_subset: Option<PortabilitySubsetProperties>,
+}
+
+impl ExtendedProperties {
+    /// Returns the properties of a physical device whose extended properties can't be queried.
+    pub(crate) fn none() -> ExtendedProperties {
+        ExtendedProperties::from_ffi(&PropertiesFfi::new())
+    }
+
+    /// Queries the properties of a physical device with `vkGetPhysicalDeviceProperties2KHR`.
+    ///
+    /// The properties of an extension are only queried if it's part of `extensions`.
+    ///
+    /// # Safety
+    ///
+    /// - `vk` must have loaded the `VK_KHR_get_physical_device_properties2` extension.
+    /// - `extensions` must be supported by `device`.
+    ///
+    pub(crate) unsafe fn query(vk: &vk::InstancePointers, device: vk::PhysicalDevice,
+                               extensions: &DeviceExtensions)
+                               -> (vk::PhysicalDeviceProperties, ExtendedProperties) {
+        let mut properties: vk::PhysicalDeviceProperties = mem::zeroed();
+        vk.GetPhysicalDeviceProperties(device, &mut properties);
+        let api_version = Version::from_vulkan_version(properties.apiVersion);
+
+        let mut ffi = PropertiesFfi::new();
+        ffi.make_chain(api_version, extensions);
+        vk.GetPhysicalDeviceProperties2KHR(device, ffi.head_as_mut());
+        let extended = ExtendedProperties::from_ffi(&ffi);
+        (ffi.main.properties, extended)
+    }
+
+    fn from_ffi(ffi: &PropertiesFfi) -> ExtendedProperties {
+        let subgroup = SubgroupProperties {
+            subgroup_size: ffi.subgroup.subgroupSize,
+            supported_stages: ShaderStages::from_vulkan_bits(ffi.subgroup.supportedStages),
+            supported_operations: SubgroupOperations::from_vulkan_bits(ffi.subgroup
+                                                                           .supportedOperations),
+            quad_operations_in_all_stages: ffi.subgroup.quadOperationsInAllStages != 0,
+        };
+
+        let subgroup_size_control = SubgroupSizeControlProperties {
+            min_subgroup_size: ffi.subgroup_size_control.minSubgroupSize,
+            max_subgroup_size: ffi.subgroup_size_control.maxSubgroupSize,
+            max_compute_workgroup_subgroups: ffi.subgroup_size_control
+                .maxComputeWorkgroupSubgroups,
+            required_subgroup_size_stages: ShaderStages::from_vulkan_bits(
+                ffi.subgroup_size_control.requiredSubgroupSizeStages),
+        };
+
+        let ray_tracing = RayTracingProperties {
+            shader_group_handle_size: ffi.ray_tracing_pipeline.shaderGroupHandleSize,
+            max_ray_recursion_depth: ffi.ray_tracing_pipeline.maxRayRecursionDepth,
+            max_shader_group_stride: ffi.ray_tracing_pipeline.maxShaderGroupStride,
+            shader_group_base_alignment: ffi.ray_tracing_pipeline.shaderGroupBaseAlignment,
+            shader_group_handle_alignment: ffi.ray_tracing_pipeline.shaderGroupHandleAlignment,
+            min_acceleration_structure_scratch_offset_alignment:
+                ffi.acceleration_structure.minAccelerationStructureScratchOffsetAlignment,
+        };
+
+        let mesh_shader = MeshShaderProperties {
+            max_task_work_group_total_count: ffi.mesh_shader.maxTaskWorkGroupTotalCount,
+            max_task_work_group_count: ffi.mesh_shader.maxTaskWorkGroupCount,
+            max_mesh_work_group_total_count: ffi.mesh_shader.maxMeshWorkGroupTotalCount,
+            max_mesh_work_group_count: ffi.mesh_shader.maxMeshWorkGroupCount,
+        };
+
+        let tf = &ffi.transform_feedback;
+        let transform_feedback = TransformFeedbackProperties {
+            max_transform_feedback_streams: tf.maxTransformFeedbackStreams,
+            max_transform_feedback_buffers: tf.maxTransformFeedbackBuffers,
+            max_transform_feedback_buffer_size: tf.maxTransformFeedbackBufferSize,
+            max_transform_feedback_stream_data_size: tf.maxTransformFeedbackStreamDataSize,
+            max_transform_feedback_buffer_data_size: tf.maxTransformFeedbackBufferDataSize,
+            max_transform_feedback_buffer_data_stride: tf.maxTransformFeedbackBufferDataStride,
+            transform_feedback_queries: tf.transformFeedbackQueries != 0,
+            transform_feedback_streams_lines_triangles: tf.transformFeedbackStreamsLinesTriangles !=
+                0,
+            transform_feedback_rasterization_stream_select:
+                tf.transformFeedbackRasterizationStreamSelect != 0,
+            transform_feedback_draw: tf.transformFeedbackDraw != 0,
+        };
+
+        // Valid driver identifiers start at 1, so a driver identifier of 0 means that the
+        // properties weren't queried.
+        let driver = if ffi.driver.driverID != 0 {
+            let version = &ffi.driver.conformanceVersion;
+            Some(DriverProperties {
+                     driver_id: ffi.driver.driverID,
+                     driver_name: c_string(&ffi.driver.driverName),
+                     driver_info: c_string(&ffi.driver.driverInfo),
+                     conformance_version: ConformanceVersion {
+                         major: version.major,
+                         minor: version.minor,
+                         subminor: version.subminor,
+                         patch: version.patch,
+                     },
+                 })
+        } else {
+            None
+        };
+
+        // The device UUID is never all zeroes, so a UUID of zeroes means that the properties
+        // weren't queried.
+        let id = if ffi.id.deviceUUID != [0; 16] {
+            Some(IdProperties {
+                     device_uuid: ffi.id.deviceUUID,
+                     driver_uuid: ffi.id.driverUUID,
+                     device_luid: if ffi.id.deviceLUIDValid != 0 {
+                         Some(ffi.id.deviceLUID)
+                     } else {
+                         None
+                     },
+                     device_node_mask: ffi.id.deviceNodeMask,
+                 })
+        } else {
+            None
+        };
+
+        let di = &ffi.descriptor_indexing;
+        let descriptor_indexing = DescriptorIndexingProperties {
+            max_update_after_bind_descriptors_in_all_pools:
+                di.maxUpdateAfterBindDescriptorsInAllPools,
+            shader_uniform_buffer_array_non_uniform_indexing_native:
+                di.shaderUniformBufferArrayNonUniformIndexingNative != 0,
+            shader_sampled_image_array_non_uniform_indexing_native:
+                di.shaderSampledImageArrayNonUniformIndexingNative != 0,
+            shader_storage_buffer_array_non_uniform_indexing_native:
+                di.shaderStorageBufferArrayNonUniformIndexingNative != 0,
+            shader_storage_image_array_non_uniform_indexing_native:
+                di.shaderStorageImageArrayNonUniformIndexingNative != 0,
+            shader_input_attachment_array_non_uniform_indexing_native:
+                di.shaderInputAttachmentArrayNonUniformIndexingNative != 0,
+            robust_buffer_access_update_after_bind: di.robustBufferAccessUpdateAfterBind != 0,
+            quad_divergent_implicit_lod: di.quadDivergentImplicitLod != 0,
+            max_per_stage_descriptor_update_after_bind_samplers:
+                di.maxPerStageDescriptorUpdateAfterBindSamplers,
+            max_per_stage_descriptor_update_after_bind_uniform_buffers:
+                di.maxPerStageDescriptorUpdateAfterBindUniformBuffers,
+            max_per_stage_descriptor_update_after_bind_storage_buffers:
+                di.maxPerStageDescriptorUpdateAfterBindStorageBuffers,
+            max_per_stage_descriptor_update_after_bind_sampled_images:
+                di.maxPerStageDescriptorUpdateAfterBindSampledImages,
+            max_per_stage_descriptor_update_after_bind_storage_images:
+                di.maxPerStageDescriptorUpdateAfterBindStorageImages,
+            max_per_stage_descriptor_update_after_bind_input_attachments:
+                di.maxPerStageDescriptorUpdateAfterBindInputAttachments,
+            max_per_stage_update_after_bind_resources: di.maxPerStageUpdateAfterBindResources,
+            max_descriptor_set_update_after_bind_samplers:
+                di.maxDescriptorSetUpdateAfterBindSamplers,
+            max_descriptor_set_update_after_bind_uniform_buffers:
+                di.maxDescriptorSetUpdateAfterBindUniformBuffers,
+            max_descriptor_set_update_after_bind_uniform_buffers_dynamic:
+                di.maxDescriptorSetUpdateAfterBindUniformBuffersDynamic,
+            max_descriptor_set_update_after_bind_storage_buffers:
+                di.maxDescriptorSetUpdateAfterBindStorageBuffers,
+            max_descriptor_set_update_after_bind_storage_buffers_dynamic:
+                di.maxDescriptorSetUpdateAfterBindStorageBuffersDynamic,
+            max_descriptor_set_update_after_bind_sampled_images:
+                di.maxDescriptorSetUpdateAfterBindSampledImages,
+            max_descriptor_set_update_after_bind_storage_images:
+                di.maxDescriptorSetUpdateAfterBindStorageImages,
+            max_descriptor_set_update_after_bind_input_attachments:
+                di.maxDescriptorSetUpdateAfterBindInputAttachments,
+        };
+
+        let maintenance3 = Maintenance3Properties {
+            max_per_set_descriptors: ffi.maintenance3.maxPerSetDescriptors,
+            max_memory_allocation_size: ffi.maintenance3.maxMemoryAllocationSize,
+        };
+
+        // The alignment is at least 1, so an alignment of 0 means that the properties weren't
+        // queried.
+        let alignment = ffi.portability_subset.minVertexInputBindingStrideAlignment;
+        let portability_subset = if alignment != 0 {
+            Some(PortabilitySubsetProperties {
+                     min_vertex_input_binding_stride_alignment: alignment,
+                 })
+        } else {
+            None
+        };
+
+        ExtendedProperties {
+            subgroup,
+            subgroup_size_control,
+            ray_tracing,
+            max_multi_draw_count: ffi.multi_draw.maxMultiDrawCount,
+            max_extra_primitive_overestimation_size:
+                ffi.conservative_rasterization.maxExtraPrimitiveOverestimationSize,
+            mesh_shader,
+            max_vertex_attrib_divisor: ffi.vertex_attribute_divisor.maxVertexAttribDivisor,
+            transform_feedback,
+            driver,
+            id,
+            descriptor_indexing,
+            maintenance3,
+            portability_subset,
+        }
+    }
+}
+
+/// The `VkPhysicalDeviceProperties2KHR` struct and the properties structs of the extensions, to
+/// be chained together.
+///
+/// Always boxed, so that the pointers of the chain stay valid.
+struct PropertiesFfi {
+    main: vk::PhysicalDeviceProperties2KHR,
+    subgroup: vk::PhysicalDeviceSubgroupProperties,
+    subgroup_size_control: vk::PhysicalDeviceSubgroupSizeControlPropertiesEXT,
+    ray_tracing_pipeline: vk::PhysicalDeviceRayTracingPipelinePropertiesKHR,
+    acceleration_structure: vk::PhysicalDeviceAccelerationStructurePropertiesKHR,
+    multi_draw: vk::PhysicalDeviceMultiDrawPropertiesEXT,
+    conservative_rasterization: vk::PhysicalDeviceConservativeRasterizationPropertiesEXT,
+    mesh_shader: vk::PhysicalDeviceMeshShaderPropertiesEXT,
+    vertex_attribute_divisor: vk::PhysicalDeviceVertexAttributeDivisorPropertiesEXT,
+    transform_feedback: vk::PhysicalDeviceTransformFeedbackPropertiesEXT,
+    driver: vk::PhysicalDeviceDriverPropertiesKHR,
+    id: vk::PhysicalDeviceIDProperties,
+    maintenance3: vk::PhysicalDeviceMaintenance3PropertiesKHR,
+    descriptor_indexing: vk::PhysicalDeviceDescriptorIndexingPropertiesEXT,
+    portability_subset: vk::PhysicalDevicePortabilitySubsetPropertiesKHR,
+}
+
+impl PropertiesFfi {
+    /// Builds a `PropertiesFfi` with all the properties set to 0 and an empty chain.
+    fn new() -> Box<PropertiesFfi> {
+        let mut ffi: Box<PropertiesFfi> = Box::new(unsafe { mem::zeroed() });
+        ffi.main.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PROPERTIES_2_KHR;
+        ffi.subgroup.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_SUBGROUP_PROPERTIES;
+        ffi.subgroup_size_control.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_SUBGROUP_SIZE_CONTROL_PROPERTIES_EXT;
+        ffi.ray_tracing_pipeline.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_RAY_TRACING_PIPELINE_PROPERTIES_KHR;
+        ffi.acceleration_structure.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_ACCELERATION_STRUCTURE_PROPERTIES_KHR;
+        ffi.multi_draw.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_MULTI_DRAW_PROPERTIES_EXT;
+        ffi.conservative_rasterization.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_CONSERVATIVE_RASTERIZATION_PROPERTIES_EXT;
+        ffi.mesh_shader.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_MESH_SHADER_PROPERTIES_EXT;
+        ffi.vertex_attribute_divisor.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_VERTEX_ATTRIBUTE_DIVISOR_PROPERTIES_EXT;
+        ffi.transform_feedback.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_TRANSFORM_FEEDBACK_PROPERTIES_EXT;
+        ffi.driver.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_DRIVER_PROPERTIES_KHR;
+        ffi.id.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_ID_PROPERTIES;
+        ffi.maintenance3.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_MAINTENANCE_3_PROPERTIES_KHR;
+        ffi.descriptor_indexing.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_DESCRIPTOR_INDEXING_PROPERTIES_EXT;
+        ffi.portability_subset.sType =
+            vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_PORTABILITY_SUBSET_PROPERTIES_KHR;
+        ffi
+    }
+
+    /// Chains the properties structs of `extensions` after the `VkPhysicalDeviceProperties2KHR`
+    /// struct. The subgroup and ID properties are core in Vulkan 1.1, and are only chained if
+    /// `api_version` is at least 1.1.
+    fn make_chain(&mut self, api_version: Version, extensions: &DeviceExtensions) {
+        let vulkan_1_1 = api_version >= Version { major: 1, minor: 1, patch: 0 };
+
+        let mut next: *mut c_void = ptr::null_mut();
+        macro_rules! chain {
+            ($cond:expr, $field:ident) => (
+                if $cond {
+                    self.$field.pNext = next as _;
+                    next = &mut self.$field as *mut _ as *mut _;
+                }
+            );
+        }
+        chain!(vulkan_1_1, subgroup);
+        chain!(extensions.ext_subgroup_size_control, subgroup_size_control);
+        chain!(extensions.khr_ray_tracing_pipeline, ray_tracing_pipeline);
+        chain!(extensions.khr_acceleration_structure, acceleration_structure);
+        chain!(extensions.ext_multi_draw, multi_draw);
+        chain!(extensions.ext_conservative_rasterization, conservative_rasterization);
+        chain!(extensions.ext_mesh_shader, mesh_shader);
+        chain!(extensions.ext_vertex_attribute_divisor, vertex_attribute_divisor);
+        chain!(extensions.ext_transform_feedback, transform_feedback);
+        chain!(extensions.khr_driver_properties, driver);
+        chain!(vulkan_1_1, id);
+        chain!(vulkan_1_1 || extensions.khr_maintenance3, maintenance3);
+        chain!(extensions.ext_descriptor_indexing, descriptor_indexing);
+        chain!(extensions.khr_portability_subset, portability_subset);
+        self.main.pNext = next as _;
+    }
+
+    /// Returns the `VkPhysicalDeviceProperties2KHR` struct at the head of the chain.
+    #[inline]
+    fn head_as_mut(&mut self) -> &mut vk::PhysicalDeviceProperties2KHR {
+        &mut self.main
+    }
+}
+
+// Converts a null-terminated array of characters to a `String`.
+fn c_string(chars: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(chars.as_ptr()).to_string_lossy().into_owned() }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::raw::c_void;
+
+    use instance::DeviceExtensions;
+    use instance::properties::ExtendedProperties;
+    use instance::properties::PropertiesFfi;
+    use version::Version;
+    use vk;
+
+    #[test]
+    fn id_properties_not_queried() {
+        assert!(ExtendedProperties::none().id.is_none());
+    }
+
+    #[test]
+    fn id_properties() {
+        let mut ffi = PropertiesFfi::new();
+        ffi.id.deviceUUID = [1; 16];
+        ffi.id.driverUUID = [2; 16];
+        ffi.id.deviceLUID = [3; 8];
+        ffi.id.deviceNodeMask = 1;
+
+        let id = ExtendedProperties::from_ffi(&ffi).id.unwrap();
+        assert_eq!(id.device_uuid, [1; 16]);
+        assert_eq!(id.driver_uuid, [2; 16]);
+        assert_eq!(id.device_luid, None);
+
+        ffi.id.deviceLUIDValid = 1;
+        let id = ExtendedProperties::from_ffi(&ffi).id.unwrap();
+        assert_eq!(id.device_luid, Some([3; 8]));
+        assert_eq!(id.device_node_mask, 1);
+    }
+
+    #[test]
+    fn id_properties_chained_with_vulkan_1_1() {
+        // Returns true if the ID properties are part of the chain.
+        fn id_chained(ffi: &mut PropertiesFfi) -> bool {
+            let id = &mut ffi.id as *mut _ as *mut c_void;
+            let mut next = ffi.head_as_mut().pNext;
+            while !next.is_null() {
+                if next == id {
+                    return true;
+                }
+                // Every struct of the chain starts with `sType` followed by `pNext`.
+                next = unsafe { (*(next as *const vk::PhysicalDeviceIDProperties)).pNext };
+            }
+            false
+        }
+
+        let mut ffi = PropertiesFfi::new();
+        ffi.make_chain(Version { major: 1, minor: 0, patch: 0 }, &DeviceExtensions::none());
+        assert!(!id_chained(&mut ffi));
+
+        let mut ffi = PropertiesFfi::new();
+        ffi.make_chain(Version { major: 1, minor: 1, patch: 0 }, &DeviceExtensions::none());
+        assert!(id_chained(&mut ffi));
+    }
+}
@@ -0,0 +1,319 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+
+use VulkanObject;
+use features::Features;
+use instance::DeviceExtensions;
+use instance::Instance;
+use instance::PhysicalDevice;
+use instance::PhysicalDeviceType;
+use instance::QueueFamily;
+use swapchain::CapabilitiesError;
+use swapchain::Surface;
+
+/// Requirements that a physical device must fulfill to be chosen by `PhysicalDevice::select`.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::instance::DeviceExtensions;
+/// use vulkano::instance::DeviceRequirements;
+///
+/// let requirements = DeviceRequirements {
+///     graphics: true,
+///     extensions: DeviceExtensions {
+///         khr_maintenance1: true,
+///         .. DeviceExtensions::none()
+///     },
+///     .. DeviceRequirements::none()
+/// };
+/// ```
+#[derive(Debug, Clone)]
+pub struct DeviceRequirements {
+    /// Extensions that the physical device must support.
+    pub extensions: DeviceExtensions,
+    /// Features that the physical device must support.
+    pub features: Features,
+    /// The physical device must have a queue family that supports graphics operations.
+    pub graphics: bool,
+    /// The physical device must have a queue family that supports compute operations.
+    pub compute: bool,
+}
+
+impl DeviceRequirements {
+    /// Builds a `DeviceRequirements` that any physical device fulfills.
+    #[inline]
+    pub fn none() -> DeviceRequirements {
+        DeviceRequirements {
+            extensions: DeviceExtensions::none(),
+            features: Features::none(),
+            graphics: false,
+            compute: false,
+        }
+    }
+}
+
+/// Physical device chosen by `PhysicalDevice::select`, and the queue families to create queues
+/// from.
+#[derive(Debug, Copy, Clone)]
+pub struct SelectedPhysicalDevice<'a> {
+    /// The physical device.
+    pub physical_device: PhysicalDevice<'a>,
+    /// Queue family that supports graphics operations, if `graphics` was required. If a surface
+    /// was passed, this family supports presenting to it whenever possible.
+    pub graphics_queue_family: Option<QueueFamily<'a>>,
+    /// Queue family that supports compute operations, if `compute` was required. A family that
+    /// doesn't support graphics operations is chosen whenever possible, so that compute
+    /// operations can run concurrently with graphics operations.
+    pub compute_queue_family: Option<QueueFamily<'a>>,
+    /// Queue family that supports presenting to the surface, if a surface was passed. This is
+    /// the graphics queue family whenever possible.
+    pub present_queue_family: Option<QueueFamily<'a>>,
+}
+
+impl<'a> SelectedPhysicalDevice<'a> {
+    /// Returns the distinct queue families of the selection, to pass to `Device::new`.
+    pub fn queue_families(&self) -> Vec<QueueFamily<'a>> {
+        let mut families: Vec<QueueFamily<'a>> = Vec::with_capacity(3);
+        let chosen = [self.graphics_queue_family,
+                      self.compute_queue_family,
+                      self.present_queue_family];
+        for family in chosen.iter().filter_map(|f| *f) {
+            if families.iter().all(|f| f.id() != family.id()) {
+                families.push(family);
+            }
+        }
+        families
+    }
+}
+
+impl<'a> PhysicalDevice<'a> {
+    /// Chooses the physical device that best fulfills `requirements`.
+    ///
+    /// Physical devices that don't support the required extensions, features or queue
+    /// operations are ignored. The remaining ones are ranked by type, discrete GPUs first, then
+    /// integrated GPUs, virtual GPUs and CPUs, and then by the size of their device-local memory.
+    ///
+    /// Returns `None` if no physical device fulfills the requirements.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vulkano::instance::DeviceRequirements;
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::InstanceExtensions;
+    /// use vulkano::instance::PhysicalDevice;
+    ///
+    /// let instance = Instance::new(None, &InstanceExtensions::none(), None).unwrap();
+    /// let requirements = DeviceRequirements { compute: true, .. DeviceRequirements::none() };
+    ///
+    /// let selected = PhysicalDevice::select(&instance, &requirements)
+    ///     .expect("no device supports compute operations");
+    /// println!("Using {} with queue family {}", selected.physical_device.name(),
+    ///          selected.compute_queue_family.unwrap().id());
+    /// ```
+    pub fn select(instance: &'a Arc<Instance>, requirements: &DeviceRequirements)
+                  -> Option<SelectedPhysicalDevice<'a>> {
+        let no_surface: Option<fn(QueueFamily<'a>) -> Result<bool, CapabilitiesError>> = None;
+        match select_inner(instance, requirements, no_surface) {
+            Ok(selected) => selected,
+            Err(_) => unreachable!(),
+        }
+    }
+
+    /// Same as `select`, but also requires the `khr_swapchain` extension and a queue family that
+    /// can present to `surface`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `surface` doesn't belong to `instance`.
+    ///
+    pub fn select_for_surface<W>(instance: &'a Arc<Instance>, requirements: &DeviceRequirements,
+                                 surface: &Surface<W>)
+                                 -> Result<Option<SelectedPhysicalDevice<'a>>, CapabilitiesError> {
+        assert_eq!(surface.instance().internal_object(),
+                   instance.internal_object());
+
+        let mut requirements = requirements.clone();
+        requirements.extensions.khr_swapchain = true;
+        select_inner(instance, &requirements, Some(|family| surface.is_supported(family)))
+    }
+}
+
+fn select_inner<'a, F>(instance: &'a Arc<Instance>, requirements: &DeviceRequirements,
+                       mut present: Option<F>)
+                       -> Result<Option<SelectedPhysicalDevice<'a>>, CapabilitiesError>
+    where F: FnMut(QueueFamily<'a>) -> Result<bool, CapabilitiesError>
+{
+    let mut best: Option<(SelectedPhysicalDevice<'a>, (u32, usize))> = None;
+
+    for physical_device in PhysicalDevice::enumerate(instance) {
+        let selected = match candidate(physical_device, requirements, present.as_mut())? {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let score = (type_rank(physical_device.ty()), device_local_memory(physical_device));
+        let better = match best {
+            Some((_, best_score)) => score > best_score,
+            None => true,
+        };
+        if better {
+            best = Some((selected, score));
+        }
+    }
+
+    Ok(best.map(|(selected, _)| selected))
+}
+
+// Checks whether `physical_device` fulfills the requirements, and chooses its queue families.
+fn candidate<'a, F>(physical_device: PhysicalDevice<'a>, requirements: &DeviceRequirements,
+                    present: Option<&mut F>)
+                    -> Result<Option<SelectedPhysicalDevice<'a>>, CapabilitiesError>
+    where F: FnMut(QueueFamily<'a>) -> Result<bool, CapabilitiesError>
+{
+    let supported_extensions = DeviceExtensions::supported_by_device(physical_device);
+    if requirements.extensions.difference(&supported_extensions) != DeviceExtensions::none() {
+        return Ok(None);
+    }
+
+    if !physical_device
+        .supported_features()
+        .superset_of(&requirements.features)
+    {
+        return Ok(None);
+    }
+
+    // Identifiers of the queue families that can present to the surface, or `None` if no
+    // surface was passed.
+    let present_families = match present {
+        Some(supports_present) => {
+            let mut families = Vec::new();
+            for family in physical_device.queue_families() {
+                if supports_present(family)? {
+                    families.push(family.id());
+                }
+            }
+            if families.is_empty() {
+                return Ok(None);
+            }
+            Some(families)
+        },
+        None => None,
+    };
+    let can_present = |family: &QueueFamily| match present_families {
+        Some(ref families) => families.contains(&family.id()),
+        None => false,
+    };
+
+    let graphics_queue_family = if requirements.graphics {
+        match preferred(physical_device, |q| q.supports_graphics(), |q| can_present(q)) {
+            Some(q) => Some(q),
+            None => return Ok(None),
+        }
+    } else {
+        None
+    };
+
+    let compute_queue_family = if requirements.compute {
+        match preferred(physical_device, |q| q.supports_compute(), |q| !q.supports_graphics()) {
+            Some(q) => Some(q),
+            None => return Ok(None),
+        }
+    } else {
+        None
+    };
+
+    let present_queue_family = if present_families.is_some() {
+        match graphics_queue_family {
+            Some(q) if can_present(&q) => Some(q),
+            _ => physical_device.queue_families().find(|q| can_present(q)),
+        }
+    } else {
+        None
+    };
+
+    Ok(Some(SelectedPhysicalDevice {
+                physical_device,
+                graphics_queue_family,
+                compute_queue_family,
+                present_queue_family,
+            }))
+}
+
+// Returns the first queue family that matches `filter` and `prefer`, or else the first one that
+// matches `filter`.
+fn preferred<'a, F, P>(physical_device: PhysicalDevice<'a>, filter: F, prefer: P)
+                       -> Option<QueueFamily<'a>>
+    where F: Fn(&QueueFamily<'a>) -> bool,
+          P: Fn(&QueueFamily<'a>) -> bool
+{
+    physical_device
+        .queue_families()
+        .filter(|q| filter(q))
+        .find(|q| prefer(q))
+        .or_else(|| physical_device.queue_families().find(|q| filter(q)))
+}
+
+#[inline]
+fn type_rank(ty: PhysicalDeviceType) -> u32 {
+    match ty {
+        PhysicalDeviceType::DiscreteGpu => 4,
+        PhysicalDeviceType::IntegratedGpu => 3,
+        PhysicalDeviceType::VirtualGpu => 2,
+        PhysicalDeviceType::Cpu => 1,
+        PhysicalDeviceType::Other => 0,
+    }
+}
+
+#[inline]
+fn device_local_memory(physical_device: PhysicalDevice) -> usize {
+    physical_device
+        .memory_heaps()
+        .filter(|h| h.is_device_local())
+        .map(|h| h.size())
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use instance::DeviceRequirements;
+    use instance::PhysicalDevice;
+
+    #[test]
+    fn select_any() {
+        let instance = instance!();
+
+        let selected = PhysicalDevice::select(&instance, &DeviceRequirements::none());
+        assert_eq!(selected.is_some(),
+                   PhysicalDevice::enumerate(&instance).len() != 0);
+        if let Some(selected) = selected {
+            assert!(selected.queue_families().is_empty());
+        }
+    }
+
+    #[test]
+    fn select_graphics() {
+        let instance = instance!();
+
+        let requirements = DeviceRequirements {
+            graphics: true,
+            ..DeviceRequirements::none()
+        };
+        if let Some(selected) = PhysicalDevice::select(&instance, &requirements) {
+            let family = selected.graphics_queue_family.unwrap();
+            assert!(family.supports_graphics());
+            assert_eq!(family.physical_device().index(), selected.physical_device.index());
+            assert!(selected.compute_queue_family.is_none());
+            assert!(selected.present_queue_family.is_none());
+        }
+    }
+}
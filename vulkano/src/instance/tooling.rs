@@ -0,0 +1,176 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Tools, such as validation layers, profilers or capture tools, that are attached to a physical
+//! device.
+
+use std::ffi::CStr;
+use std::mem;
+use std::os::raw::c_char;
+use std::ptr;
+
+use OomError;
+use VulkanObject;
+use check_errors;
+use instance::DeviceExtensions;
+use instance::PhysicalDevice;
+use vk;
+
+/// Description of a tool that is attached to a physical device, as returned by
+/// `PhysicalDevice::tool_properties`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ToolProperties {
+    /// Name of the tool.
+    pub name: String,
+    /// Version of the tool.
+    pub version: String,
+    /// What the tool does.
+    pub purposes: ToolPurposes,
+    /// Description of the tool.
+    pub description: String,
+    /// Name of the layer that implements the tool, or `None` if the tool isn't implemented by a
+    /// layer.
+    pub layer: Option<String>,
+}
+
+/// What a tool attached to a physical device does.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct ToolPurposes {
+    /// The tool validates the usage of the API.
+    pub validation: bool,
+    /// The tool reports profiling information.
+    pub profiling: bool,
+    /// The tool captures the API calls, for example to replay them later.
+    pub tracing: bool,
+    /// The tool provides features that the implementation doesn't support.
+    pub additional_features: bool,
+    /// The tool modifies the features of the implementation, for example by hiding some of them.
+    pub modifying_features: bool,
+    /// The tool reports messages through `VK_EXT_debug_report` or `VK_EXT_debug_utils`.
+    pub debug_reporting: bool,
+    /// The tool consumes debug markers or object names.
+    pub debug_markers: bool,
+}
+
+impl ToolPurposes {
+    /// Builds a `ToolPurposes` with all fields set to `false`.
+    #[inline]
+    pub fn none() -> ToolPurposes {
+        ToolPurposes {
+            validation: false,
+            profiling: false,
+            tracing: false,
+            additional_features: false,
+            modifying_features: false,
+            debug_reporting: false,
+            debug_markers: false,
+        }
+    }
+
+    #[inline]
+    fn from_vulkan_bits(bits: vk::ToolPurposeFlagsEXT) -> ToolPurposes {
+        ToolPurposes {
+            validation: (bits & vk::TOOL_PURPOSE_VALIDATION_BIT_EXT) != 0,
+            profiling: (bits & vk::TOOL_PURPOSE_PROFILING_BIT_EXT) != 0,
+            tracing: (bits & vk::TOOL_PURPOSE_TRACING_BIT_EXT) != 0,
+            additional_features: (bits & vk::TOOL_PURPOSE_ADDITIONAL_FEATURES_BIT_EXT) != 0,
+            modifying_features: (bits & vk::TOOL_PURPOSE_MODIFYING_FEATURES_BIT_EXT) != 0,
+            debug_reporting: (bits & vk::TOOL_PURPOSE_DEBUG_REPORTING_BIT_EXT) != 0,
+            debug_markers: (bits & vk::TOOL_PURPOSE_DEBUG_MARKERS_BIT_EXT) != 0,
+        }
+    }
+}
+
+impl<'a> PhysicalDevice<'a> {
+    /// Returns the tools that are attached to the physical device, such as validation layers,
+    /// profilers or capture tools.
+    ///
+    /// Tools can only be enumerated if the physical device supports the `VK_EXT_tooling_info`
+    /// extension. Otherwise, an empty list is returned. The extension doesn't need to be enabled
+    /// on a device.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use vulkano::instance::Instance;
+    /// use vulkano::instance::InstanceExtensions;
+    /// use vulkano::instance::PhysicalDevice;
+    ///
+    /// let instance = Instance::new(None, &InstanceExtensions::none(), None).unwrap();
+    /// let physical = PhysicalDevice::enumerate(&instance).next().unwrap();
+    ///
+    /// let capturing = physical.tool_properties().unwrap().iter().any(|t| t.purposes.tracing);
+    /// if capturing {
+    ///     println!("A capture tool is attached, disabling timing-sensitive paths");
+    /// }
+    /// ```
+    pub fn tool_properties(&self) -> Result<Vec<ToolProperties>, OomError> {
+        if !DeviceExtensions::supported_by_device(*self).ext_tooling_info {
+            return Ok(Vec::new());
+        }
+
+        let vk = self.instance().pointers();
+
+        let tools = unsafe {
+            let mut num = 0;
+            check_errors(vk.GetPhysicalDeviceToolPropertiesEXT(self.internal_object(),
+                                                               &mut num,
+                                                               ptr::null_mut()))?;
+
+            let mut tools = (0 .. num)
+                .map(|_| {
+                         let mut tool: vk::PhysicalDeviceToolPropertiesEXT = mem::zeroed();
+                         tool.sType = vk::STRUCTURE_TYPE_PHYSICAL_DEVICE_TOOL_PROPERTIES_EXT;
+                         tool
+                     })
+                .collect::<Vec<_>>();
+
+            check_errors(vk.GetPhysicalDeviceToolPropertiesEXT(self.internal_object(),
+                                                               &mut num,
+                                                               tools.as_mut_ptr()))?;
+            tools.truncate(num as usize);
+            tools
+        };
+
+        Ok(tools
+               .iter()
+               .map(|tool| {
+                        let layer = c_string(&tool.layer);
+                        ToolProperties {
+                            name: c_string(&tool.name),
+                            version: c_string(&tool.version),
+                            purposes: ToolPurposes::from_vulkan_bits(tool.purposes),
+                            description: c_string(&tool.description),
+                            layer: if layer.is_empty() { None } else { Some(layer) },
+                        }
+                    })
+               .collect())
+    }
+}
+
+// Converts a null-terminated array of characters to a `String`.
+fn c_string(chars: &[c_char]) -> String {
+    unsafe { CStr::from_ptr(chars.as_ptr()).to_string_lossy().into_owned() }
+}
+
+#[cfg(test)]
+mod tests {
+    use instance::PhysicalDevice;
+
+    #[test]
+    fn tool_properties() {
+        let instance = instance!();
+
+        for physical in PhysicalDevice::enumerate(&instance) {
+            for tool in physical.tool_properties().unwrap() {
+                assert!(!tool.name.is_empty());
+            }
+        }
+    }
+}
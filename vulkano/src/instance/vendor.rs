@@ -0,0 +1,159 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Identification of the vendor and of the driver version of a physical device.
+
+use std::fmt;
+
+/// Vendor of a physical device, as determined from its vendor ID.
+///
+/// The vendor ID is a PCI vendor ID for most vendors, or a Khronos vendor ID for vendors that
+/// don't have one.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Vendor {
+    Amd,
+    Apple,
+    Arm,
+    ImgTec,
+    Intel,
+    Mesa,
+    Nvidia,
+    Qualcomm,
+    /// A vendor that vulkano doesn't know about. Contains the vendor ID.
+    Other(u32),
+}
+
+impl Vendor {
+    /// Returns the vendor corresponding to a vendor ID.
+    #[inline]
+    pub fn from_id(id: u32) -> Vendor {
+        match id {
+            0x1002 => Vendor::Amd,
+            0x106b => Vendor::Apple,
+            0x13b5 => Vendor::Arm,
+            0x1010 => Vendor::ImgTec,
+            0x8086 => Vendor::Intel,
+            0x10005 => Vendor::Mesa,
+            0x10de => Vendor::Nvidia,
+            0x5143 => Vendor::Qualcomm,
+            id => Vendor::Other(id),
+        }
+    }
+
+    /// Returns the vendor ID of the vendor.
+    #[inline]
+    pub fn id(&self) -> u32 {
+        match *self {
+            Vendor::Amd => 0x1002,
+            Vendor::Apple => 0x106b,
+            Vendor::Arm => 0x13b5,
+            Vendor::ImgTec => 0x1010,
+            Vendor::Intel => 0x8086,
+            Vendor::Mesa => 0x10005,
+            Vendor::Nvidia => 0x10de,
+            Vendor::Qualcomm => 0x5143,
+            Vendor::Other(id) => id,
+        }
+    }
+}
+
+/// Version of the driver of a physical device, decoded from the opaque number returned by
+/// `PhysicalDevice::driver_version`.
+///
+/// The Vulkan specifications don't define how the driver version is encoded. Most drivers use
+/// the same encoding as the Vulkan API version, but some vendors use their own scheme:
+///
+/// - NVIDIA drivers encode four numbers on 10, 8, 8 and 6 bits.
+/// - Intel drivers on Windows encode two numbers on 18 and 14 bits.
+///
+/// Components that the encoding doesn't have are 0.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct DriverVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+    pub build: u32,
+}
+
+impl DriverVersion {
+    /// Decodes the driver version of a physical device from the given vendor, using the scheme
+    /// of the current platform.
+    #[inline]
+    pub fn decode(vendor: Vendor, driver_version: u32) -> DriverVersion {
+        decode(vendor, driver_version, cfg!(windows))
+    }
+}
+
+impl fmt::Display for DriverVersion {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if self.build != 0 {
+            write!(formatter, ".{}", self.build)?;
+        }
+        Ok(())
+    }
+}
+
+fn decode(vendor: Vendor, v: u32, windows: bool) -> DriverVersion {
+    match vendor {
+        Vendor::Nvidia => DriverVersion {
+            major: v >> 22,
+            minor: (v >> 14) & 0xff,
+            patch: (v >> 6) & 0xff,
+            build: v & 0x3f,
+        },
+        Vendor::Intel if windows => DriverVersion {
+            major: v >> 14,
+            minor: v & 0x3fff,
+            patch: 0,
+            build: 0,
+        },
+        _ => DriverVersion {
+            major: v >> 22,
+            minor: (v >> 12) & 0x3ff,
+            patch: v & 0xfff,
+            build: 0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::decode;
+    use instance::DriverVersion;
+    use instance::Vendor;
+
+    #[test]
+    fn vendor_ids() {
+        assert_eq!(Vendor::from_id(0x10de), Vendor::Nvidia);
+        assert_eq!(Vendor::from_id(0x1234), Vendor::Other(0x1234));
+        assert_eq!(Vendor::Amd.id(), 0x1002);
+        assert_eq!(Vendor::Other(0x1234).id(), 0x1234);
+    }
+
+    #[test]
+    fn decode_versions() {
+        let nvidia = decode(Vendor::Nvidia, (418 << 22) | (56 << 14) | (3 << 6) | 1, false);
+        assert_eq!(nvidia,
+                   DriverVersion {
+                       major: 418,
+                       minor: 56,
+                       patch: 3,
+                       build: 1,
+                   });
+        assert_eq!(nvidia.to_string(), "418.56.3.1");
+
+        let intel = decode(Vendor::Intel, (100 << 14) | 9466, true);
+        assert_eq!(intel.to_string(), "100.9466.0");
+
+        let mesa = decode(Vendor::Intel, (20 << 22) | (1 << 12) | 5, false);
+        assert_eq!(mesa.to_string(), "20.1.5");
+        assert_eq!(decode(Vendor::Amd, (20 << 22) | (1 << 12) | 5, true), mesa);
+    }
+}
@@ -0,0 +1,70 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Instrumentation of the expensive operations with the `tracing` crate.
+//!
+//! When the `tracing` feature is disabled, the macros of this module expand to nothing and their
+//! arguments are not evaluated.
+
+#[cfg(feature = "tracing")]
+use tracing::field::DisplayValue;
+
+#[cfg(feature = "tracing")]
+use device::Device;
+#[cfg(feature = "tracing")]
+use vk;
+
+/// Enters a `debug` span that lasts until the end of the current scope.
+///
+/// Takes the same arguments as `tracing::debug_span!`. The span is exited when the value
+/// returned by the macro is dropped, so bind it to a named variable such as `_span`.
+#[cfg(feature = "tracing")]
+macro_rules! instrument_span {
+    ($($args:tt)*) => (::tracing::debug_span!($($args)*).entered());
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! instrument_span {
+    ($($args:tt)*) => (());
+}
+
+/// Emits a `debug` event. Takes the same arguments as `tracing::debug!`.
+#[cfg(feature = "tracing")]
+macro_rules! instrument_event {
+    ($($args:tt)*) => (::tracing::debug!($($args)*));
+}
+
+#[cfg(not(feature = "tracing"))]
+macro_rules! instrument_event {
+    ($($args:tt)*) => (());
+}
+
+/// Returns the name that was given to an object with `DebugName` or `Device::set_object_name`,
+/// in a form that can be recorded as a field of a span. The field is left empty if the object
+/// has no name.
+#[cfg(feature = "tracing")]
+pub fn debug_name(device: &Device, ty: vk::ObjectType, object: u64)
+                  -> Option<DisplayValue<String>> {
+    device.debug_name(ty, object).map(::tracing::field::display)
+}
+
+/// Same as `debug_name`, for a queue.
+#[cfg(feature = "tracing")]
+#[inline]
+pub fn queue_name(device: &Device, queue: vk::Queue) -> Option<DisplayValue<String>> {
+    debug_name(device, vk::OBJECT_TYPE_QUEUE, queue as u64)
+}
+
+/// Same as `debug_name`, for a pipeline cache.
+#[cfg(feature = "tracing")]
+#[inline]
+pub fn pipeline_cache_name(device: &Device, cache: vk::PipelineCache)
+                           -> Option<DisplayValue<String>> {
+    debug_name(device, vk::OBJECT_TYPE_PIPELINE_CACHE, cache)
+}
@@ -0,0 +1,182 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Sharing images and buffers with CUDA.
+//!
+//! CUDA can import Vulkan memory with `cudaImportExternalMemory` and Vulkan semaphores with
+//! `cudaImportExternalSemaphore`. This lets a CUDA kernel write its results directly into a
+//! buffer or an image that Vulkan then renders, without copying them.
+//!
+//! The resources are created the same way as for OpenGL, with `required_device_extensions()`,
+//! `memory_handle_types()` and `semaphore_handle_types()`. Their memory is then exported with
+//! `CudaExternalMemory::export`, and the semaphores with `CudaExternalSemaphore::export`. CUDA
+//! must run on the same physical device, which can be checked by comparing the UUID of the CUDA
+//! device with `PhysicalDevice::device_uuid`.
+
+use SafeDeref;
+use device::Device;
+use instance::DeviceExtensions;
+use interop::ExternalHandle;
+use interop::opaque_handle_extensions;
+use interop::opaque_memory_handle_type;
+use interop::opaque_memory_handle_types;
+use interop::opaque_semaphore_handle_type;
+use interop::opaque_semaphore_handle_types;
+use memory::DeviceMemory;
+use memory::ExternalMemoryError;
+use memory::ExternalMemoryHandleType;
+use memory::ExternalMemoryHandleTypes;
+use sync::ExternalSemaphoreError;
+use sync::ExternalSemaphoreHandleType;
+use sync::ExternalSemaphoreHandleTypes;
+use sync::Semaphore;
+
+/// Returns the device extensions that must be enabled to share resources with CUDA on the
+/// current platform.
+#[inline]
+pub fn required_device_extensions() -> DeviceExtensions {
+    opaque_handle_extensions()
+}
+
+/// Returns the handle types to create shared images and buffers and to allocate their memory
+/// with.
+#[inline]
+pub fn memory_handle_types() -> ExternalMemoryHandleTypes {
+    opaque_memory_handle_types()
+}
+
+/// Returns the handle types to create shared semaphores with.
+#[inline]
+pub fn semaphore_handle_types() -> ExternalSemaphoreHandleTypes {
+    opaque_semaphore_handle_types()
+}
+
+/// The content of the `cudaExternalMemoryHandleDesc` that imports memory of Vulkan in CUDA.
+///
+/// `handle_type` corresponds to `cudaExternalMemoryHandleTypeOpaqueFd` or
+/// `cudaExternalMemoryHandleTypeOpaqueWin32`, and `dedicated` to the
+/// `cudaExternalMemoryDedicated` flag. Importing a file descriptor transfers its ownership to
+/// CUDA. Windows handles stay owned by the caller.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CudaExternalMemory {
+    /// The exported memory.
+    pub handle: ExternalHandle,
+    /// The type of `handle`.
+    pub handle_type: ExternalMemoryHandleType,
+    /// The size in bytes of the memory allocation, which is the size to import.
+    pub size: u64,
+    /// True if the memory is a dedicated allocation of a resource.
+    pub dedicated: bool,
+}
+
+impl CudaExternalMemory {
+    /// Exports `memory`, which must have been allocated with the handle types of
+    /// `memory_handle_types()`.
+    ///
+    /// `dedicated` must be true if `memory` was allocated as a dedicated allocation of a
+    /// resource.
+    pub fn export(memory: &DeviceMemory, dedicated: bool)
+                  -> Result<CudaExternalMemory, ExternalMemoryError> {
+        let handle_type = opaque_memory_handle_type();
+        let handle = ExternalHandle::from_memory(memory, handle_type)?;
+
+        Ok(CudaExternalMemory {
+               handle: handle,
+               handle_type: handle_type,
+               size: memory.size() as u64,
+               dedicated: dedicated,
+           })
+    }
+
+    /// Returns the range to pass to `cudaExternalMemoryGetMappedBuffer` in order to access the
+    /// `size` bytes of a resource that is bound at `offset` in the memory.
+    ///
+    /// Returns `None` if the range doesn't fit in the memory.
+    #[inline]
+    pub fn mapped_range(&self, offset: usize, size: usize) -> Option<CudaMappedRange> {
+        let offset = offset as u64;
+        let size = size as u64;
+
+        if size == 0 || offset >= self.size || size > self.size - offset {
+            return None;
+        }
+
+        Some(CudaMappedRange {
+                 offset: offset,
+                 size: size,
+             })
+    }
+}
+
+/// The `offset` and `size` of a `cudaExternalMemoryBufferDesc`. Returned by
+/// `CudaExternalMemory::mapped_range`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CudaMappedRange {
+    /// Offset in bytes of the range within the memory.
+    pub offset: u64,
+    /// Size in bytes of the range.
+    pub size: u64,
+}
+
+/// The content of the `cudaExternalSemaphoreHandleDesc` that imports a semaphore of Vulkan in
+/// CUDA.
+///
+/// `handle_type` corresponds to `cudaExternalSemaphoreHandleTypeOpaqueFd` or
+/// `cudaExternalSemaphoreHandleTypeOpaqueWin32`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CudaExternalSemaphore {
+    /// The exported payload of the semaphore.
+    pub handle: ExternalHandle,
+    /// The type of `handle`.
+    pub handle_type: ExternalSemaphoreHandleType,
+}
+
+impl CudaExternalSemaphore {
+    /// Exports the payload of `semaphore`, which must have been created with the handle types of
+    /// `semaphore_handle_types()`.
+    pub fn export<D>(semaphore: &Semaphore<D>)
+                     -> Result<CudaExternalSemaphore, ExternalSemaphoreError>
+        where D: SafeDeref<Target = Device>
+    {
+        let handle_type = opaque_semaphore_handle_type();
+        let handle = ExternalHandle::from_semaphore(semaphore, handle_type)?;
+
+        Ok(CudaExternalSemaphore {
+               handle: handle,
+               handle_type: handle_type,
+           })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use interop::ExternalHandle;
+    use interop::cuda::CudaExternalMemory;
+    use interop::cuda::CudaMappedRange;
+    use interop::opaque_memory_handle_type;
+
+    #[test]
+    fn mapped_range() {
+        let memory = CudaExternalMemory {
+            handle: ExternalHandle::Fd(-1),
+            handle_type: opaque_memory_handle_type(),
+            size: 1024,
+            dedicated: false,
+        };
+
+        assert_eq!(memory.mapped_range(256, 768),
+                   Some(CudaMappedRange {
+                            offset: 256,
+                            size: 768,
+                        }));
+        assert_eq!(memory.mapped_range(256, 769), None);
+        assert_eq!(memory.mapped_range(1024, 1), None);
+        assert_eq!(memory.mapped_range(0, 0), None);
+    }
+}
@@ -0,0 +1,230 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Sharing images and buffers with OpenGL.
+//!
+//! OpenGL can import Vulkan memory with the `GL_EXT_memory_object` extension, and Vulkan
+//! semaphores with the `GL_EXT_semaphore` extension. This makes it possible to migrate an OpenGL
+//! application to Vulkan gradually, with both APIs rendering to the same resources in the same
+//! process.
+//!
+//! The steps are the following:
+//!
+//! - Check that the OpenGL context runs on the same physical device, by comparing
+//!   `GL_DEVICE_UUID_EXT` and `GL_DRIVER_UUID_EXT` with `PhysicalDevice::device_uuid` and
+//!   `PhysicalDevice::driver_uuid`.
+//! - Create the device with the extensions of `required_device_extensions()`.
+//! - Create the image or buffer with `UnsafeImage::new_exportable` or
+//!   `UnsafeBuffer::new_exportable`, and allocate its memory with
+//!   `DeviceMemory::alloc_exportable`, both with `memory_handle_types()`.
+//! - Create two semaphores with `Semaphore::alloc_exportable` and `semaphore_handle_types()`.
+//!   One is signaled by Vulkan when OpenGL can use the resource, the other one is signaled by
+//!   OpenGL when Vulkan can use it again.
+//! - Export everything with `GlSharedHandles::export`, and import the handles in OpenGL as
+//!   described in the documentation of `GlSharedHandles`.
+//!
+//! The semaphores must stay alive as long as the resource is shared. They are usually signaled
+//! and waited upon with `SubmitCommandBufferBuilder`, since the futures of vulkano take ownership
+//! of their semaphore.
+
+use std::error;
+use std::fmt;
+
+use SafeDeref;
+use device::Device;
+use instance::DeviceExtensions;
+use interop::ExternalHandle;
+use interop::opaque_handle_extensions;
+use interop::opaque_memory_handle_type;
+use interop::opaque_memory_handle_types;
+use interop::opaque_semaphore_handle_type;
+use interop::opaque_semaphore_handle_types;
+use memory::DeviceMemory;
+use memory::ExternalMemoryError;
+use memory::ExternalMemoryHandleTypes;
+use sync::ExternalSemaphoreError;
+use sync::ExternalSemaphoreHandleTypes;
+use sync::Semaphore;
+
+/// Returns the device extensions that must be enabled to share resources with OpenGL on the
+/// current platform.
+#[inline]
+pub fn required_device_extensions() -> DeviceExtensions {
+    opaque_handle_extensions()
+}
+
+/// Returns the handle types to create shared images and buffers and to allocate their memory
+/// with.
+#[inline]
+pub fn memory_handle_types() -> ExternalMemoryHandleTypes {
+    opaque_memory_handle_types()
+}
+
+/// Returns the handle types to create shared semaphores with.
+#[inline]
+pub fn semaphore_handle_types() -> ExternalSemaphoreHandleTypes {
+    opaque_semaphore_handle_types()
+}
+
+/// The handles that OpenGL needs to access an image or a buffer of Vulkan, and to synchronize
+/// with Vulkan.
+///
+/// The handles are imported in OpenGL as follows:
+///
+/// - `memory` with `glCreateMemoryObjectsEXT`, then `glImportMemoryFdEXT` or
+///   `glImportMemoryWin32HandleEXT` with `memory_size` and `GL_HANDLE_TYPE_OPAQUE_FD_EXT` or
+///   `GL_HANDLE_TYPE_OPAQUE_WIN32_EXT`. If `dedicated` is true, `GL_DEDICATED_MEMORY_OBJECT_EXT`
+///   must be set on the memory object before importing it.
+/// - The storage of the texture or buffer is then created with `glTexStorageMem2DEXT` (or
+///   another `glTexStorageMem*EXT` function) or `glBufferStorageMemEXT`, with `offset`. The
+///   parameters of the texture must match the ones of the Vulkan image.
+/// - `gl_ready` and `vulkan_ready` with `glGenSemaphoresEXT`, then `glImportSemaphoreFdEXT` or
+///   `glImportSemaphoreWin32HandleEXT`. OpenGL waits on `gl_ready` with `glWaitSemaphoreEXT`
+///   before using the resource, and signals `vulkan_ready` with `glSignalSemaphoreEXT` once it
+///   is done.
+///
+/// Importing a file descriptor transfers its ownership to OpenGL. Windows handles stay owned by
+/// the caller and must be closed once they have been imported.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GlSharedHandles {
+    /// The memory that the resource is bound to.
+    pub memory: ExternalHandle,
+    /// The size in bytes of the memory allocation.
+    pub memory_size: u64,
+    /// The offset in bytes of the resource within the memory.
+    pub offset: u64,
+    /// True if the memory is a dedicated allocation of the resource.
+    pub dedicated: bool,
+    /// The semaphore that Vulkan signals when OpenGL can use the resource.
+    pub gl_ready: ExternalHandle,
+    /// The semaphore that OpenGL signals when Vulkan can use the resource again.
+    pub vulkan_ready: ExternalHandle,
+}
+
+impl GlSharedHandles {
+    /// Exports the memory of a resource and the two semaphores that synchronize its accesses.
+    ///
+    /// `offset` is the offset that the resource was bound at, and `dedicated` must be true if
+    /// `memory` was allocated as a dedicated allocation of the resource.
+    ///
+    /// The memory and the semaphores must have been created with the handle types of
+    /// `memory_handle_types()` and `semaphore_handle_types()`.
+    pub fn export<D1, D2>(memory: &DeviceMemory, offset: usize, dedicated: bool,
+                          gl_ready: &Semaphore<D1>, vulkan_ready: &Semaphore<D2>)
+                          -> Result<GlSharedHandles, GlInteropError>
+        where D1: SafeDeref<Target = Device>,
+              D2: SafeDeref<Target = Device>
+    {
+        assert!(offset < memory.size());
+
+        let memory_handle = ExternalHandle::from_memory(memory, opaque_memory_handle_type())?;
+        let gl_ready = ExternalHandle::from_semaphore(gl_ready, opaque_semaphore_handle_type())?;
+        let vulkan_ready = ExternalHandle::from_semaphore(vulkan_ready,
+                                                          opaque_semaphore_handle_type())?;
+
+        Ok(GlSharedHandles {
+               memory: memory_handle,
+               memory_size: memory.size() as u64,
+               offset: offset as u64,
+               dedicated: dedicated,
+               gl_ready: gl_ready,
+               vulkan_ready: vulkan_ready,
+           })
+    }
+}
+
+/// Error that can happen when exporting resources to OpenGL.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GlInteropError {
+    /// Exporting the memory failed.
+    ExternalMemoryError(ExternalMemoryError),
+    /// Exporting one of the semaphores failed.
+    ExternalSemaphoreError(ExternalSemaphoreError),
+}
+
+impl error::Error for GlInteropError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            GlInteropError::ExternalMemoryError(_) => "exporting the memory failed",
+            GlInteropError::ExternalSemaphoreError(_) => "exporting one of the semaphores failed",
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            GlInteropError::ExternalMemoryError(ref err) => Some(err),
+            GlInteropError::ExternalSemaphoreError(ref err) => Some(err),
+        }
+    }
+}
+
+impl fmt::Display for GlInteropError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ExternalMemoryError> for GlInteropError {
+    #[inline]
+    fn from(err: ExternalMemoryError) -> GlInteropError {
+        GlInteropError::ExternalMemoryError(err)
+    }
+}
+
+impl From<ExternalSemaphoreError> for GlInteropError {
+    #[inline]
+    fn from(err: ExternalSemaphoreError) -> GlInteropError {
+        GlInteropError::ExternalSemaphoreError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use interop::gl::GlInteropError;
+    use interop::gl::GlSharedHandles;
+    use interop::gl::memory_handle_types;
+    use memory::DedicatedAlloc;
+    use memory::DeviceMemory;
+    use memory::DeviceMemoryAllocError;
+    use memory::ExternalMemoryError;
+    use sync::Semaphore;
+
+    #[test]
+    fn exportable_memory_extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+        let mem_ty = device.physical_device().memory_types().next().unwrap();
+
+        match DeviceMemory::alloc_exportable(device.clone(),
+                                             mem_ty,
+                                             256,
+                                             DedicatedAlloc::None,
+                                             memory_handle_types()) {
+            Err(DeviceMemoryAllocError::ExternalMemoryExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn export_not_exportable() {
+        let (device, _) = gfx_dev_and_queue!();
+        let mem_ty = device.physical_device().memory_types().next().unwrap();
+        let memory = DeviceMemory::alloc(device.clone(), mem_ty, 256).unwrap();
+        let semaphore = Semaphore::alloc(device.clone()).unwrap();
+
+        match GlSharedHandles::export(&memory, 0, false, &semaphore, &semaphore) {
+            Err(GlInteropError::ExternalMemoryError(ExternalMemoryError::ExtensionNotEnabled)) |
+            Err(GlInteropError::ExternalMemoryError(
+                    ExternalMemoryError::HandleTypeNotExportable)) => (),
+            _ => panic!(),
+        }
+    }
+}
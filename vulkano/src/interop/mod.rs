@@ -0,0 +1,127 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Sharing resources with other APIs in the same process.
+//!
+//! Memory and semaphores can be exported as handles that other APIs know how to import, which
+//! lets them access the same images and buffers as Vulkan without copying them. The submodules
+//! package the handles that each API expects.
+
+use std::os::raw::c_int;
+use std::os::raw::c_void;
+
+use SafeDeref;
+use device::Device;
+use instance::DeviceExtensions;
+use memory::DeviceMemory;
+use memory::ExternalMemoryError;
+use memory::ExternalMemoryHandleType;
+use memory::ExternalMemoryHandleTypes;
+use sync::ExternalSemaphoreError;
+use sync::ExternalSemaphoreHandleType;
+use sync::ExternalSemaphoreHandleTypes;
+use sync::Semaphore;
+
+pub mod cuda;
+pub mod gl;
+
+/// A handle to memory or to the payload of a semaphore that was exported to another API.
+///
+/// The receiving API usually takes ownership of the handle when importing it. Otherwise it must
+/// be closed with `close` on POSIX systems or `CloseHandle` on Windows.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExternalHandle {
+    /// A POSIX file descriptor.
+    Fd(c_int),
+    /// A Windows handle.
+    Win32(*mut c_void),
+}
+
+impl ExternalHandle {
+    // Exports `memory` as `handle_type`, with the function that matches the handle type.
+    pub(crate) fn from_memory(memory: &DeviceMemory, handle_type: ExternalMemoryHandleType)
+                              -> Result<ExternalHandle, ExternalMemoryError> {
+        if handle_type.is_fd() {
+            memory.export_fd(handle_type).map(ExternalHandle::Fd)
+        } else {
+            memory
+                .export_win32_handle(handle_type)
+                .map(ExternalHandle::Win32)
+        }
+    }
+
+    // Exports the payload of `semaphore` as `handle_type`, which must be an opaque handle type.
+    pub(crate) fn from_semaphore<D>(semaphore: &Semaphore<D>,
+                                    handle_type: ExternalSemaphoreHandleType)
+                                    -> Result<ExternalHandle, ExternalSemaphoreError>
+        where D: SafeDeref<Target = Device>
+    {
+        debug_assert!(handle_type != ExternalSemaphoreHandleType::SyncFd);
+
+        if handle_type.is_fd() {
+            // Exporting an opaque file descriptor doesn't have any side effect on the semaphore.
+            unsafe { semaphore.export_fd(handle_type).map(ExternalHandle::Fd) }
+        } else {
+            semaphore
+                .export_win32_handle(handle_type)
+                .map(ExternalHandle::Win32)
+        }
+    }
+}
+
+/// Returns the opaque handle type of memory that other APIs import on the current platform.
+#[inline]
+pub fn opaque_memory_handle_type() -> ExternalMemoryHandleType {
+    if cfg!(windows) {
+        ExternalMemoryHandleType::OpaqueWin32
+    } else {
+        ExternalMemoryHandleType::OpaqueFd
+    }
+}
+
+/// Returns the opaque handle type of semaphores that other APIs import on the current platform.
+#[inline]
+pub fn opaque_semaphore_handle_type() -> ExternalSemaphoreHandleType {
+    if cfg!(windows) {
+        ExternalSemaphoreHandleType::OpaqueWin32
+    } else {
+        ExternalSemaphoreHandleType::OpaqueFd
+    }
+}
+
+// The device extensions required to export the opaque handle types of the current platform.
+pub(crate) fn opaque_handle_extensions() -> DeviceExtensions {
+    DeviceExtensions {
+        khr_external_memory: true,
+        khr_external_memory_fd: cfg!(not(windows)),
+        khr_external_memory_win32: cfg!(windows),
+        khr_external_semaphore: true,
+        khr_external_semaphore_fd: cfg!(not(windows)),
+        khr_external_semaphore_win32: cfg!(windows),
+        ..DeviceExtensions::none()
+    }
+}
+
+// The set that only contains `opaque_memory_handle_type()`.
+pub(crate) fn opaque_memory_handle_types() -> ExternalMemoryHandleTypes {
+    ExternalMemoryHandleTypes {
+        opaque_fd: cfg!(not(windows)),
+        opaque_win32: cfg!(windows),
+        ..ExternalMemoryHandleTypes::none()
+    }
+}
+
+// The set that only contains `opaque_semaphore_handle_type()`.
+pub(crate) fn opaque_semaphore_handle_types() -> ExternalSemaphoreHandleTypes {
+    ExternalSemaphoreHandleTypes {
+        opaque_fd: cfg!(not(windows)),
+        opaque_win32: cfg!(windows),
+        ..ExternalSemaphoreHandleTypes::none()
+    }
+}
@@ -61,21 +61,49 @@
 #![allow(dead_code)] // TODO: remove
 #![allow(unused_variables)] // TODO: remove
 
+#[cfg(all(feature = "winit", target_os = "macos"))]
+extern crate cocoa;
 extern crate crossbeam;
 extern crate fnv;
+#[cfg(feature = "hlsl")]
+extern crate hassle_rs;
 #[macro_use]
 extern crate lazy_static;
+#[cfg(all(feature = "winit", target_os = "macos"))]
+extern crate metal_rs as metal;
+#[cfg(all(feature = "winit", target_os = "macos"))]
+extern crate objc;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(feature = "serde")]
+#[macro_use]
+extern crate serde_derive;
+#[cfg(feature = "shaderc")]
+extern crate shaderc;
 extern crate shared_library;
 extern crate smallvec;
+#[cfg(feature = "spirv-val")]
+extern crate spirv_tools;
+#[cfg(feature = "tracing")]
+extern crate tracing;
 extern crate vk_sys as vk;
+#[cfg(feature = "winit")]
+extern crate winit;
 pub extern crate half;
 
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
 #[macro_use]
 mod tests;
+#[macro_use]
+mod instrument;
 
 mod features;
+mod spirv;
 mod version;
 
+pub mod acceleration_structure;
 pub mod buffer;
 pub mod command_buffer;
 pub mod descriptor;
@@ -85,6 +113,7 @@ pub mod format;
 pub mod framebuffer;
 pub mod image;
 pub mod instance;
+pub mod interop;
 pub mod memory;
 pub mod pipeline;
 pub mod query;
@@ -130,6 +159,15 @@ pub unsafe trait VulkanObject {
 
     /// Returns a reference to the object.
     fn internal_object(&self) -> Self::Object;
+
+    /// Returns the raw Vulkan handle of the object as a `u64`.
+    ///
+    /// This is the representation that other Vulkan bindings such as `ash` use to build their
+    /// handles, both for dispatchable handles (which are pointers) and non-dispatchable handles.
+    #[inline]
+    fn as_raw(&self) -> u64 {
+        self.internal_object().value()
+    }
 }
 
 /// Gives access to the internal identifier of an object.
@@ -140,6 +178,14 @@ pub unsafe trait SynchronizedVulkanObject {
 
     /// Returns a reference to the object.
     fn internal_object_guard(&self) -> MutexGuard<Self::Object>;
+
+    /// Returns the raw Vulkan handle of the object as a `u64`. See `VulkanObject::as_raw`.
+    ///
+    /// The object must still be synchronized externally when the handle is used.
+    #[inline]
+    fn as_raw(&self) -> u64 {
+        self.internal_object_guard().value()
+    }
 }
 
 /// Error type returned by most Vulkan functions.
@@ -217,6 +263,7 @@ pub(crate) enum Error {
     IncompatibleDisplay = vk::ERROR_INCOMPATIBLE_DISPLAY_KHR,
     ValidationFailed = vk::ERROR_VALIDATION_FAILED_EXT,
     OutOfPoolMemory = vk::ERROR_OUT_OF_POOL_MEMORY_KHR,
+    InvalidExternalHandle = vk::ERROR_INVALID_EXTERNAL_HANDLE_KHR,
 }
 
 /// Checks whether the result returned correctly.
@@ -246,6 +293,7 @@ fn check_errors(result: vk::Result) -> Result<Success, Error> {
         vk::ERROR_INCOMPATIBLE_DISPLAY_KHR => Err(Error::IncompatibleDisplay),
         vk::ERROR_VALIDATION_FAILED_EXT => Err(Error::ValidationFailed),
         vk::ERROR_OUT_OF_POOL_MEMORY_KHR => Err(Error::OutOfPoolMemory),
+        vk::ERROR_INVALID_EXTERNAL_HANDLE_KHR => Err(Error::InvalidExternalHandle),
         vk::ERROR_INVALID_SHADER_NV => panic!("Vulkan function returned \
                                                VK_ERROR_INVALID_SHADER_NV"),
         c => unreachable!("Unexpected error code returned by Vulkan: {}", c),
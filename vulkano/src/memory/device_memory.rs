@@ -13,6 +13,7 @@ use std::mem;
 use std::ops::Deref;
 use std::ops::DerefMut;
 use std::ops::Range;
+use std::os::raw::c_int;
 use std::os::raw::c_void;
 use std::ptr;
 use std::sync::Arc;
@@ -21,11 +22,16 @@ use Error;
 use OomError;
 use VulkanObject;
 use check_errors;
+use command_buffer::validity::CheckDeviceGroupError;
+use command_buffer::validity::check_device_mask;
 use device::Device;
 use device::DeviceOwned;
 use instance::MemoryType;
 use memory::Content;
 use memory::DedicatedAlloc;
+use memory::ExternalMemoryError;
+use memory::ExternalMemoryHandleType;
+use memory::ExternalMemoryHandleTypes;
 use vk;
 
 /// Represents memory that has been allocated.
@@ -48,6 +54,7 @@ pub struct DeviceMemory {
     device: Arc<Device>,
     size: usize,
     memory_type_index: u32,
+    export_handle_types: ExternalMemoryHandleTypes,
 }
 
 impl DeviceMemory {
@@ -78,6 +85,74 @@ impl DeviceMemory {
     pub fn dedicated_alloc(device: Arc<Device>, memory_type: MemoryType, size: usize,
                            resource: DedicatedAlloc)
                            -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        DeviceMemory::alloc_inner(device,
+                                  memory_type,
+                                  size,
+                                  resource,
+                                  None,
+                                  ExternalMemoryHandleTypes::none())
+    }
+
+    /// Same as `dedicated_alloc`, but only allocates the memory on the physical devices of the
+    /// device group whose bit is set in `device_mask`.
+    ///
+    /// Requires the `VK_KHR_device_group` device extension. Unless `subset_allocation()` returns
+    /// true on the device, `device_mask` must contain all the physical devices of the device.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `size` is 0.
+    /// - Panics if `memory_type` doesn't belong to the same physical device as `device`.
+    ///
+    #[inline]
+    pub fn alloc_with_device_mask(device: Arc<Device>, memory_type: MemoryType, size: usize,
+                                  resource: DedicatedAlloc, device_mask: u32)
+                                  -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        check_device_mask(&device, device_mask)?;
+
+        let all_devices = (!0u32) >> (32 - device.physical_device_count());
+        if device_mask != all_devices && !device.subset_allocation() {
+            return Err(DeviceMemoryAllocError::SubsetAllocationNotSupported);
+        }
+
+        DeviceMemory::alloc_inner(device,
+                                  memory_type,
+                                  size,
+                                  resource,
+                                  Some(device_mask),
+                                  ExternalMemoryHandleTypes::none())
+    }
+
+    /// Same as `dedicated_alloc`, but the memory can be exported as the handle types of
+    /// `handle_types`, in order to share it with another API or another process.
+    ///
+    /// The buffer or image that is bound to the memory must have been created with the same
+    /// handle types, for example with `UnsafeBuffer::new_exportable`. Some implementations
+    /// require the memory of such a resource to be a dedicated allocation, in which case its
+    /// memory requirements have `prefer_dedicated` set and `resource` must be the resource.
+    ///
+    /// Requires the `VK_KHR_external_memory` extension.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `size` is 0.
+    /// - Panics if `memory_type` doesn't belong to the same physical device as `device`.
+    ///
+    #[inline]
+    pub fn alloc_exportable(device: Arc<Device>, memory_type: MemoryType, size: usize,
+                            resource: DedicatedAlloc, handle_types: ExternalMemoryHandleTypes)
+                            -> Result<DeviceMemory, DeviceMemoryAllocError> {
+        if !device.loaded_extensions().khr_external_memory {
+            return Err(DeviceMemoryAllocError::ExternalMemoryExtensionNotEnabled);
+        }
+
+        DeviceMemory::alloc_inner(device, memory_type, size, resource, None, handle_types)
+    }
+
+    fn alloc_inner(device: Arc<Device>, memory_type: MemoryType, size: usize,
+                   resource: DedicatedAlloc, device_mask: Option<u32>,
+                   export_handle_types: ExternalMemoryHandleTypes)
+                   -> Result<DeviceMemory, DeviceMemoryAllocError> {
         assert!(size >= 1);
         assert_eq!(device.physical_device().internal_object(),
                    memory_type.physical_device().internal_object());
@@ -124,20 +199,64 @@ impl DeviceMemory {
                 None
             };
 
+            let dedicated_alloc_info_ptr = dedicated_alloc_info
+                .as_ref()
+                .map(|i| i as *const vk::MemoryDedicatedAllocateInfoKHR)
+                .unwrap_or(ptr::null()) as *const _;
+
+            // Buffers created with the `shader_device_address` usage must be bound to memory that
+            // was allocated with the `DEVICE_ADDRESS` flag. Since we don't know in advance which
+            // buffers will be bound to this memory, we always pass it when the feature is enabled.
+            let mut flags = 0;
+            if device.buffer_device_address_enabled() {
+                flags |= vk::MEMORY_ALLOCATE_DEVICE_ADDRESS_BIT_KHR;
+            }
+            if device_mask.is_some() {
+                flags |= vk::MEMORY_ALLOCATE_DEVICE_MASK_BIT_KHR;
+            }
+            let flags_info = if flags != 0 {
+                Some(vk::MemoryAllocateFlagsInfoKHR {
+                         sType: vk::STRUCTURE_TYPE_MEMORY_ALLOCATE_FLAGS_INFO_KHR,
+                         pNext: dedicated_alloc_info_ptr,
+                         flags: flags,
+                         deviceMask: device_mask.unwrap_or(0),
+                     })
+            } else {
+                None
+            };
+
+            let flags_info_ptr = flags_info
+                .as_ref()
+                .map(|i| i as *const vk::MemoryAllocateFlagsInfoKHR as *const _)
+                .unwrap_or(dedicated_alloc_info_ptr);
+
+            let export_info = if export_handle_types != ExternalMemoryHandleTypes::none() {
+                Some(vk::ExportMemoryAllocateInfo {
+                         sType: vk::STRUCTURE_TYPE_EXPORT_MEMORY_ALLOCATE_INFO,
+                         pNext: flags_info_ptr,
+                         handleTypes: export_handle_types.into_vulkan_bits(),
+                     })
+            } else {
+                None
+            };
+
             let infos = vk::MemoryAllocateInfo {
                 sType: vk::STRUCTURE_TYPE_MEMORY_ALLOCATE_INFO,
-                pNext: dedicated_alloc_info
+                pNext: export_info
                     .as_ref()
-                    .map(|i| i as *const vk::MemoryDedicatedAllocateInfoKHR)
-                    .unwrap_or(ptr::null()) as *const _,
+                    .map(|i| i as *const vk::ExportMemoryAllocateInfo as *const _)
+                    .unwrap_or(flags_info_ptr),
                 allocationSize: size as u64,
                 memoryTypeIndex: memory_type.id(),
             };
 
+            let _span = instrument_span!("vkAllocateMemory",
+                                         size = size,
+                                         memory_type = memory_type.id());
             let mut output = mem::uninitialized();
             check_errors(vk.AllocateMemory(device.internal_object(),
                                            &infos,
-                                           ptr::null(),
+                                           device.allocator_ptr(),
                                            &mut output))?;
             *allocation_count += 1;
             output
@@ -148,6 +267,7 @@ impl DeviceMemory {
                device: device,
                size: size,
                memory_type_index: memory_type.id(),
+               export_handle_types: export_handle_types,
            })
     }
 
@@ -193,6 +313,30 @@ impl DeviceMemory {
            })
     }
 
+    /// Builds a `DeviceMemory` from a raw memory handle, for example memory that was allocated
+    /// by another library.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid memory allocation that was created from `device`, with the
+    ///   allocation callbacks of `device` if it has any.
+    /// - `memory_type` and `size` must match the parameters that the memory was allocated with.
+    /// - The `DeviceMemory` takes ownership of `handle` and destroys it when it is dropped.
+    ///
+    pub unsafe fn from_raw(device: Arc<Device>, handle: u64, memory_type: MemoryType,
+                           size: usize)
+                           -> DeviceMemory {
+        *device.allocation_count().lock().expect("Poisoned mutex") += 1;
+
+        DeviceMemory {
+            memory: handle,
+            device: device,
+            size: size,
+            memory_type_index: memory_type.id(),
+            export_handle_types: ExternalMemoryHandleTypes::none(),
+        }
+    }
+
     /// Returns the memory type this chunk was allocated on.
     #[inline]
     pub fn memory_type(&self) -> MemoryType {
@@ -207,6 +351,88 @@ impl DeviceMemory {
     pub fn size(&self) -> usize {
         self.size
     }
+
+    /// Returns the handle types that the memory can be exported as.
+    #[inline]
+    pub fn export_handle_types(&self) -> ExternalMemoryHandleTypes {
+        self.export_handle_types
+    }
+
+    /// Exports the memory as a POSIX file descriptor. The caller becomes the owner of the file
+    /// descriptor and is responsible for closing it.
+    ///
+    /// `handle_type` must be `OpaqueFd` or `DmaBuf`, and the memory must have been allocated
+    /// with `alloc_exportable` with this handle type.
+    ///
+    /// Requires the `VK_KHR_external_memory_fd` extension.
+    pub fn export_fd(&self, handle_type: ExternalMemoryHandleType)
+                     -> Result<c_int, ExternalMemoryError> {
+        if !self.device.loaded_extensions().khr_external_memory_fd {
+            return Err(ExternalMemoryError::ExtensionNotEnabled);
+        }
+
+        if !handle_type.is_fd() {
+            return Err(ExternalMemoryError::HandleTypeNotSupported);
+        }
+
+        if !self.export_handle_types.contains(handle_type) {
+            return Err(ExternalMemoryError::HandleTypeNotExportable);
+        }
+
+        unsafe {
+            let infos = vk::MemoryGetFdInfoKHR {
+                sType: vk::STRUCTURE_TYPE_MEMORY_GET_FD_INFO_KHR,
+                pNext: ptr::null(),
+                memory: self.memory,
+                handleType: handle_type as u32,
+            };
+
+            let vk = self.device.pointers();
+            let mut output = -1;
+            check_errors(vk.GetMemoryFdKHR(self.device.internal_object(), &infos, &mut output))?;
+            Ok(output)
+        }
+    }
+
+    /// Exports the memory as a Windows handle. If `handle_type` is `OpaqueWin32`, the caller
+    /// becomes the owner of the handle and is responsible for closing it.
+    ///
+    /// `handle_type` must be `OpaqueWin32` or `OpaqueWin32Kmt`, and the memory must have been
+    /// allocated with `alloc_exportable` with this handle type.
+    ///
+    /// Requires the `VK_KHR_external_memory_win32` extension.
+    pub fn export_win32_handle(&self, handle_type: ExternalMemoryHandleType)
+                               -> Result<*mut c_void, ExternalMemoryError> {
+        if !self.device.loaded_extensions().khr_external_memory_win32 {
+            return Err(ExternalMemoryError::ExtensionNotEnabled);
+        }
+
+        match handle_type {
+            ExternalMemoryHandleType::OpaqueWin32 |
+            ExternalMemoryHandleType::OpaqueWin32Kmt => (),
+            _ => return Err(ExternalMemoryError::HandleTypeNotSupported),
+        }
+
+        if !self.export_handle_types.contains(handle_type) {
+            return Err(ExternalMemoryError::HandleTypeNotExportable);
+        }
+
+        unsafe {
+            let infos = vk::MemoryGetWin32HandleInfoKHR {
+                sType: vk::STRUCTURE_TYPE_MEMORY_GET_WIN32_HANDLE_INFO_KHR,
+                pNext: ptr::null(),
+                memory: self.memory,
+                handleType: handle_type as u32,
+            };
+
+            let vk = self.device.pointers();
+            let mut output = ptr::null_mut();
+            check_errors(vk.GetMemoryWin32HandleKHR(self.device.internal_object(),
+                                                    &infos,
+                                                    &mut output))?;
+            Ok(output)
+        }
+    }
 }
 
 unsafe impl DeviceOwned for DeviceMemory {
@@ -242,7 +468,8 @@ impl Drop for DeviceMemory {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.FreeMemory(self.device.internal_object(), self.memory, ptr::null());
+            self.device.forget_debug_name(vk::OBJECT_TYPE_DEVICE_MEMORY, self.memory);
+            vk.FreeMemory(self.device.internal_object(), self.memory, self.device.allocator_ptr());
             let mut allocation_count = self.device
                 .allocation_count()
                 .lock()
@@ -468,6 +695,17 @@ pub enum DeviceMemoryAllocError {
     TooManyObjects,
     /// Memory map failed.
     MemoryMapFailed,
+    /// The device mask is invalid.
+    CheckDeviceGroupError(CheckDeviceGroupError),
+    /// The device mask doesn't contain all the physical devices of the device, and the device
+    /// doesn't support allocating memory on a subset of them.
+    SubsetAllocationNotSupported,
+    /// Exportable memory was requested but the `VK_KHR_external_memory` extension isn't enabled.
+    ExternalMemoryExtensionNotEnabled,
+    /// A usage of the buffer to allocate memory for requires a feature or an extension that
+    /// isn't enabled, for example the `shader_device_address` usage without the
+    /// `bufferDeviceAddress` feature.
+    UsageNotSupported,
 }
 
 impl error::Error for DeviceMemoryAllocError {
@@ -478,6 +716,14 @@ impl error::Error for DeviceMemoryAllocError {
             DeviceMemoryAllocError::TooManyObjects =>
                 "the maximum number of allocations has been exceeded",
             DeviceMemoryAllocError::MemoryMapFailed => "memory map failed",
+            DeviceMemoryAllocError::CheckDeviceGroupError(_) => "the device mask is invalid",
+            DeviceMemoryAllocError::SubsetAllocationNotSupported =>
+                "the device doesn't support allocating memory on a subset of its physical devices",
+            DeviceMemoryAllocError::ExternalMemoryExtensionNotEnabled =>
+                "exportable memory was requested but the `VK_KHR_external_memory` extension isn't \
+                 enabled",
+            DeviceMemoryAllocError::UsageNotSupported =>
+                "a usage of the buffer requires a feature or an extension that isn't enabled",
         }
     }
 
@@ -485,6 +731,7 @@ impl error::Error for DeviceMemoryAllocError {
     fn cause(&self) -> Option<&error::Error> {
         match *self {
             DeviceMemoryAllocError::OomError(ref err) => Some(err),
+            DeviceMemoryAllocError::CheckDeviceGroupError(ref err) => Some(err),
             _ => None,
         }
     }
@@ -517,9 +764,18 @@ impl From<OomError> for DeviceMemoryAllocError {
     }
 }
 
+impl From<CheckDeviceGroupError> for DeviceMemoryAllocError {
+    #[inline]
+    fn from(err: CheckDeviceGroupError) -> DeviceMemoryAllocError {
+        DeviceMemoryAllocError::CheckDeviceGroupError(err)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use OomError;
+    use command_buffer::validity::CheckDeviceGroupError;
+    use memory::DedicatedAlloc;
     use memory::DeviceMemory;
     use memory::DeviceMemoryAllocError;
 
@@ -539,6 +795,18 @@ mod tests {
                              });
     }
 
+    #[test]
+    fn device_mask_without_extension() {
+        let (device, _) = gfx_dev_and_queue!();
+        let mem_ty = device.physical_device().memory_types().next().unwrap();
+        match DeviceMemory::alloc_with_device_mask(device.clone(), mem_ty, 256,
+                                                   DedicatedAlloc::None, 1) {
+            Err(DeviceMemoryAllocError::CheckDeviceGroupError(
+                    CheckDeviceGroupError::ExtensionNotEnabled)) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     #[cfg(target_pointer_width = "64")]
     fn oom_single() {
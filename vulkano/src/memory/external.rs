@@ -0,0 +1,300 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Memory that is shared with other APIs or other processes.
+
+use std::error;
+use std::fmt;
+
+use Error;
+use OomError;
+use vk;
+
+/// A type of handle that memory can be exported as or imported from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ExternalMemoryHandleType {
+    /// A POSIX file descriptor that is only meaningful to a driver of the same physical device.
+    OpaqueFd = vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT,
+    /// An NT handle that is only meaningful to a driver of the same physical device.
+    OpaqueWin32 = vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_BIT,
+    /// A global share handle that is only meaningful to a driver of the same physical device.
+    OpaqueWin32Kmt = vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT,
+    /// An NT handle to a Direct3D 11 texture.
+    D3D11Texture = vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_BIT,
+    /// A global share handle to a Direct3D 11 texture.
+    D3D11TextureKmt = vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_KMT_BIT,
+    /// An NT handle to a Direct3D 12 heap.
+    D3D12Heap = vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_HEAP_BIT,
+    /// An NT handle to a Direct3D 12 committed resource.
+    D3D12Resource = vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_RESOURCE_BIT,
+    /// A pointer to host memory allocated by the application.
+    HostAllocation = vk::EXTERNAL_MEMORY_HANDLE_TYPE_HOST_ALLOCATION_BIT_EXT,
+    /// A pointer to host memory that maps foreign memory, for example device memory.
+    HostMappedForeignMemory = vk::EXTERNAL_MEMORY_HANDLE_TYPE_HOST_MAPPED_FOREIGN_MEMORY_BIT_EXT,
+    /// A Linux dma-buf file descriptor, that can be passed to other graphics APIs and to the
+    /// kernel.
+    DmaBuf = vk::EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT,
+    /// An Android `AHardwareBuffer`.
+    AndroidHardwareBuffer = vk::EXTERNAL_MEMORY_HANDLE_TYPE_ANDROID_HARDWARE_BUFFER_BIT_ANDROID,
+}
+
+impl ExternalMemoryHandleType {
+    /// Returns true if the handle is a file descriptor.
+    #[inline]
+    pub fn is_fd(&self) -> bool {
+        match *self {
+            ExternalMemoryHandleType::OpaqueFd |
+            ExternalMemoryHandleType::DmaBuf => true,
+            _ => false,
+        }
+    }
+}
+
+/// A set of handle types that memory can be exported as or imported from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExternalMemoryHandleTypes {
+    /// `True` means that the set contains `ExternalMemoryHandleType::OpaqueFd`.
+    pub opaque_fd: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::OpaqueWin32`.
+    pub opaque_win32: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::OpaqueWin32Kmt`.
+    pub opaque_win32_kmt: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::D3D11Texture`.
+    pub d3d11_texture: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::D3D11TextureKmt`.
+    pub d3d11_texture_kmt: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::D3D12Heap`.
+    pub d3d12_heap: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::D3D12Resource`.
+    pub d3d12_resource: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::HostAllocation`.
+    pub host_allocation: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::HostMappedForeignMemory`.
+    pub host_mapped_foreign_memory: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::DmaBuf`.
+    pub dma_buf: bool,
+    /// `True` means that the set contains `ExternalMemoryHandleType::AndroidHardwareBuffer`.
+    pub android_hardware_buffer: bool,
+}
+
+impl ExternalMemoryHandleTypes {
+    /// Builds an `ExternalMemoryHandleTypes` with none of the handle types set.
+    #[inline]
+    pub fn none() -> ExternalMemoryHandleTypes {
+        ExternalMemoryHandleTypes {
+            opaque_fd: false,
+            opaque_win32: false,
+            opaque_win32_kmt: false,
+            d3d11_texture: false,
+            d3d11_texture_kmt: false,
+            d3d12_heap: false,
+            d3d12_resource: false,
+            host_allocation: false,
+            host_mapped_foreign_memory: false,
+            dma_buf: false,
+            android_hardware_buffer: false,
+        }
+    }
+
+    /// Returns true if `handle_type` is part of the set.
+    #[inline]
+    pub fn contains(&self, handle_type: ExternalMemoryHandleType) -> bool {
+        match handle_type {
+            ExternalMemoryHandleType::OpaqueFd => self.opaque_fd,
+            ExternalMemoryHandleType::OpaqueWin32 => self.opaque_win32,
+            ExternalMemoryHandleType::OpaqueWin32Kmt => self.opaque_win32_kmt,
+            ExternalMemoryHandleType::D3D11Texture => self.d3d11_texture,
+            ExternalMemoryHandleType::D3D11TextureKmt => self.d3d11_texture_kmt,
+            ExternalMemoryHandleType::D3D12Heap => self.d3d12_heap,
+            ExternalMemoryHandleType::D3D12Resource => self.d3d12_resource,
+            ExternalMemoryHandleType::HostAllocation => self.host_allocation,
+            ExternalMemoryHandleType::HostMappedForeignMemory => self.host_mapped_foreign_memory,
+            ExternalMemoryHandleType::DmaBuf => self.dma_buf,
+            ExternalMemoryHandleType::AndroidHardwareBuffer => self.android_hardware_buffer,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan_bits(self) -> vk::ExternalMemoryHandleTypeFlags {
+        let mut result = 0;
+        if self.opaque_fd {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT;
+        }
+        if self.opaque_win32 {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_BIT;
+        }
+        if self.opaque_win32_kmt {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT;
+        }
+        if self.d3d11_texture {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_BIT;
+        }
+        if self.d3d11_texture_kmt {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_KMT_BIT;
+        }
+        if self.d3d12_heap {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_HEAP_BIT;
+        }
+        if self.d3d12_resource {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_RESOURCE_BIT;
+        }
+        if self.host_allocation {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_HOST_ALLOCATION_BIT_EXT;
+        }
+        if self.host_mapped_foreign_memory {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_HOST_MAPPED_FOREIGN_MEMORY_BIT_EXT;
+        }
+        if self.dma_buf {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT;
+        }
+        if self.android_hardware_buffer {
+            result |= vk::EXTERNAL_MEMORY_HANDLE_TYPE_ANDROID_HARDWARE_BUFFER_BIT_ANDROID;
+        }
+        result
+    }
+
+    #[inline]
+    pub(crate) fn from_vulkan_bits(bits: vk::ExternalMemoryHandleTypeFlags)
+                                   -> ExternalMemoryHandleTypes {
+        ExternalMemoryHandleTypes {
+            opaque_fd: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_FD_BIT) != 0,
+            opaque_win32: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_BIT) != 0,
+            opaque_win32_kmt: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT) != 0,
+            d3d11_texture: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_BIT) != 0,
+            d3d11_texture_kmt: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D11_TEXTURE_KMT_BIT) != 0,
+            d3d12_heap: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_HEAP_BIT) != 0,
+            d3d12_resource: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_D3D12_RESOURCE_BIT) != 0,
+            host_allocation: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_HOST_ALLOCATION_BIT_EXT) != 0,
+            host_mapped_foreign_memory:
+                (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_HOST_MAPPED_FOREIGN_MEMORY_BIT_EXT) != 0,
+            dma_buf: (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_DMA_BUF_BIT_EXT) != 0,
+            android_hardware_buffer:
+                (bits & vk::EXTERNAL_MEMORY_HANDLE_TYPE_ANDROID_HARDWARE_BUFFER_BIT_ANDROID) != 0,
+        }
+    }
+}
+
+/// What the implementation supports when sharing the memory of a resource with a given handle
+/// type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExternalMemoryProperties {
+    /// The memory must be allocated as a dedicated allocation of the resource in order to be
+    /// exported or imported.
+    pub dedicated_only: bool,
+    /// The memory can be exported as the handle type.
+    pub exportable: bool,
+    /// The memory can be imported from the handle type.
+    pub importable: bool,
+    /// The handle types that memory imported from the handle type can be exported as.
+    pub export_from_imported_handle_types: ExternalMemoryHandleTypes,
+    /// The handle types that must be specified alongside the handle type when creating the
+    /// resource.
+    pub compatible_handle_types: ExternalMemoryHandleTypes,
+}
+
+impl ExternalMemoryProperties {
+    #[inline]
+    pub(crate) fn from_vulkan(props: &vk::ExternalMemoryProperties) -> ExternalMemoryProperties {
+        let features = props.externalMemoryFeatures;
+        ExternalMemoryProperties {
+            dedicated_only: (features & vk::EXTERNAL_MEMORY_FEATURE_DEDICATED_ONLY_BIT) != 0,
+            exportable: (features & vk::EXTERNAL_MEMORY_FEATURE_EXPORTABLE_BIT) != 0,
+            importable: (features & vk::EXTERNAL_MEMORY_FEATURE_IMPORTABLE_BIT) != 0,
+            export_from_imported_handle_types:
+                ExternalMemoryHandleTypes::from_vulkan_bits(props.exportFromImportedHandleTypes),
+            compatible_handle_types:
+                ExternalMemoryHandleTypes::from_vulkan_bits(props.compatibleHandleTypes),
+        }
+    }
+}
+
+/// Error that can happen when exporting memory.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExternalMemoryError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// Too many handles are opened in the process.
+    TooManyObjects,
+    /// The extension required by the operation isn't enabled.
+    ExtensionNotEnabled,
+    /// The handle type can't be used with this function.
+    HandleTypeNotSupported,
+    /// The memory wasn't allocated with the handle type in its exportable handle types.
+    HandleTypeNotExportable,
+}
+
+impl error::Error for ExternalMemoryError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ExternalMemoryError::OomError(_) => "not enough memory",
+            ExternalMemoryError::TooManyObjects => "too many handles are opened in the process",
+            ExternalMemoryError::ExtensionNotEnabled => {
+                "the extension required by the operation isn't enabled"
+            },
+            ExternalMemoryError::HandleTypeNotSupported => {
+                "the handle type can't be used with this function"
+            },
+            ExternalMemoryError::HandleTypeNotExportable => {
+                "the memory wasn't allocated with the handle type in its exportable handle types"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ExternalMemoryError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExternalMemoryError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<Error> for ExternalMemoryError {
+    #[inline]
+    fn from(err: Error) -> ExternalMemoryError {
+        match err {
+            err @ Error::OutOfHostMemory => ExternalMemoryError::OomError(err.into()),
+            err @ Error::OutOfDeviceMemory => ExternalMemoryError::OomError(err.into()),
+            Error::TooManyObjects => ExternalMemoryError::TooManyObjects,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use memory::ExternalMemoryHandleType;
+    use memory::ExternalMemoryHandleTypes;
+
+    #[test]
+    fn handle_types_bits() {
+        let types = ExternalMemoryHandleTypes {
+            opaque_fd: true,
+            dma_buf: true,
+            ..ExternalMemoryHandleTypes::none()
+        };
+
+        let bits = types.into_vulkan_bits();
+        assert_eq!(bits,
+                   ExternalMemoryHandleType::OpaqueFd as u32 |
+                       ExternalMemoryHandleType::DmaBuf as u32);
+        assert_eq!(ExternalMemoryHandleTypes::from_vulkan_bits(bits), types);
+        assert!(types.contains(ExternalMemoryHandleType::DmaBuf));
+        assert!(!types.contains(ExternalMemoryHandleType::OpaqueWin32));
+    }
+}
@@ -0,0 +1,400 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Host memory allocated by the Vulkan implementation.
+//!
+//! The implementation allocates host memory for its own needs when Vulkan objects are created.
+//! By passing a `HostAllocationCallbacks` when creating an instance or a device, this memory is
+//! allocated by vulkano on behalf of the implementation, and the callbacks are notified of each
+//! allocation. This can be used to track the host memory used by the driver, or to enforce a
+//! memory budget by refusing allocations.
+//!
+//! The memory is allocated with the global allocator of Rust, so it can also be routed through a
+//! custom allocator with the `#[global_allocator]` attribute.
+
+use std::alloc;
+use std::alloc::Layout;
+use std::cmp;
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+use std::sync::Arc;
+
+use vk;
+
+/// Callbacks that are notified of the host memory allocations of the Vulkan implementation.
+///
+/// All the methods have a default implementation that does nothing.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::atomic::AtomicUsize;
+/// use std::sync::atomic::Ordering;
+/// use vulkano::memory::AllocationScope;
+/// use vulkano::memory::HostAllocationCallbacks;
+///
+/// struct Budget {
+///     used: AtomicUsize,
+///     max: usize,
+/// }
+///
+/// impl HostAllocationCallbacks for Budget {
+///     fn allocation(&self, size: usize, _: usize, _: AllocationScope) -> bool {
+///         if self.used.fetch_add(size, Ordering::SeqCst) + size > self.max {
+///             self.used.fetch_sub(size, Ordering::SeqCst);
+///             return false;
+///         }
+///         true
+///     }
+///
+///     fn free(&self, size: usize, _: usize) {
+///         self.used.fetch_sub(size, Ordering::SeqCst);
+///     }
+/// }
+/// ```
+#[allow(unused_variables)]
+pub trait HostAllocationCallbacks: Send + Sync {
+    /// Called before the implementation allocates `size` bytes with the given alignment.
+    ///
+    /// If `false` is returned, the allocation fails and the Vulkan function that needed the
+    /// memory returns an out of host memory error.
+    #[inline]
+    fn allocation(&self, size: usize, alignment: usize, scope: AllocationScope) -> bool {
+        true
+    }
+
+    /// Called when the implementation frees memory that was previously allocated.
+    #[inline]
+    fn free(&self, size: usize, alignment: usize) {
+    }
+
+    /// Called when the implementation allocates memory itself, without going through the
+    /// callbacks. This happens for example for executable memory.
+    #[inline]
+    fn internal_allocation(&self, size: usize, ty: InternalAllocationType,
+                           scope: AllocationScope) {
+    }
+
+    /// Called when the implementation frees memory that was allocated without going through the
+    /// callbacks.
+    #[inline]
+    fn internal_free(&self, size: usize, ty: InternalAllocationType, scope: AllocationScope) {
+    }
+}
+
+/// Lifetime of a host memory allocation of the Vulkan implementation.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AllocationScope {
+    /// The memory is only used during a Vulkan command.
+    Command,
+    /// The memory is used as long as a Vulkan object exists.
+    Object,
+    /// The memory is used by a pipeline cache.
+    Cache,
+    /// The memory is used as long as the device exists.
+    Device,
+    /// The memory is used as long as the instance exists.
+    Instance,
+}
+
+impl AllocationScope {
+    #[inline]
+    fn from_vulkan(scope: vk::SystemAllocationScope) -> AllocationScope {
+        match scope {
+            vk::SYSTEM_ALLOCATION_SCOPE_COMMAND => AllocationScope::Command,
+            vk::SYSTEM_ALLOCATION_SCOPE_OBJECT => AllocationScope::Object,
+            vk::SYSTEM_ALLOCATION_SCOPE_CACHE => AllocationScope::Cache,
+            vk::SYSTEM_ALLOCATION_SCOPE_DEVICE => AllocationScope::Device,
+            _ => AllocationScope::Instance,
+        }
+    }
+}
+
+/// Kind of memory that the Vulkan implementation allocated without going through the callbacks.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InternalAllocationType {
+    /// Memory that contains executable code.
+    Executable,
+    /// A kind of memory that vulkano doesn't know about.
+    Other(u32),
+}
+
+impl InternalAllocationType {
+    #[inline]
+    fn from_vulkan(ty: vk::InternalAllocationType) -> InternalAllocationType {
+        match ty {
+            vk::INTERNAL_ALLOCATION_TYPE_EXECUTABLE => InternalAllocationType::Executable,
+            ty => InternalAllocationType::Other(ty),
+        }
+    }
+}
+
+/// The `VkAllocationCallbacks` struct that corresponds to a `HostAllocationCallbacks`, to be
+/// passed to the functions that create and destroy Vulkan objects.
+///
+/// Objects must be destroyed with the same callbacks as the ones they were created with, so the
+/// `HostAllocator` must be kept alive as long as the objects.
+pub(crate) struct HostAllocator {
+    raw: vk::AllocationCallbacks,
+    // Boxed so that `pUserData` stays valid when the `HostAllocator` is moved.
+    callbacks: Box<Arc<HostAllocationCallbacks>>,
+}
+
+unsafe impl Send for HostAllocator {
+}
+unsafe impl Sync for HostAllocator {
+}
+// TODO: fix the underlying cause instead
+impl ::std::panic::UnwindSafe for HostAllocator {
+}
+impl ::std::panic::RefUnwindSafe for HostAllocator {
+}
+
+impl HostAllocator {
+    /// Builds the `VkAllocationCallbacks` struct that calls `callbacks`.
+    pub(crate) fn new(callbacks: Arc<HostAllocationCallbacks>) -> HostAllocator {
+        let callbacks = Box::new(callbacks);
+
+        HostAllocator {
+            raw: vk::AllocationCallbacks {
+                pUserData: &*callbacks as *const Arc<HostAllocationCallbacks> as *mut c_void,
+                pfnAllocation: allocation,
+                pfnReallocation: reallocation,
+                pfnFree: free,
+                pfnInternalAllocation: internal_allocation,
+                pfnInternalFree: internal_free,
+            },
+            callbacks,
+        }
+    }
+
+    /// Returns the callbacks that were passed to `new`.
+    #[inline]
+    pub(crate) fn callbacks(&self) -> &Arc<HostAllocationCallbacks> {
+        &self.callbacks
+    }
+
+    /// Returns a pointer to pass as the `pAllocator` parameter of Vulkan functions, or null if
+    /// `allocator` is `None`.
+    #[inline]
+    pub(crate) fn as_ptr(allocator: &Option<HostAllocator>) -> *const vk::AllocationCallbacks {
+        match *allocator {
+            Some(ref allocator) => &allocator.raw,
+            None => ptr::null(),
+        }
+    }
+}
+
+// Each allocation is preceded by a header that contains its size and its alignment. The header is
+// at least as large as the alignment, so that the memory that follows it is correctly aligned.
+const HEADER_MIN_SIZE: usize = 2 * mem::size_of::<usize>();
+
+#[inline]
+fn header_size(alignment: usize) -> usize {
+    cmp::max(alignment, HEADER_MIN_SIZE)
+}
+
+#[inline]
+unsafe fn callbacks<'a>(user_data: *mut c_void) -> &'a HostAllocationCallbacks {
+    &**(user_data as *const Arc<HostAllocationCallbacks>)
+}
+
+unsafe fn allocate(size: usize, alignment: usize) -> *mut c_void {
+    let header = header_size(alignment);
+    let layout = match Layout::from_size_align(header + size, header) {
+        Ok(l) => l,
+        Err(_) => return ptr::null_mut(),
+    };
+
+    let base = alloc::alloc(layout);
+    if base.is_null() {
+        return ptr::null_mut();
+    }
+
+    let memory = base.offset(header as isize);
+    *(memory as *mut usize).offset(-2) = size;
+    *(memory as *mut usize).offset(-1) = alignment;
+    memory as *mut c_void
+}
+
+// Returns the size and the alignment that were passed to `allocate`.
+#[inline]
+unsafe fn size_and_alignment(memory: *mut c_void) -> (usize, usize) {
+    let memory = memory as *const usize;
+    (*memory.offset(-2), *memory.offset(-1))
+}
+
+unsafe fn deallocate(memory: *mut c_void) {
+    let (size, alignment) = size_and_alignment(memory);
+    let header = header_size(alignment);
+    let base = (memory as *mut u8).offset(-(header as isize));
+    alloc::dealloc(base, Layout::from_size_align_unchecked(header + size, header));
+}
+
+extern "system" fn allocation(user_data: *mut c_void, size: usize, alignment: usize,
+                              scope: vk::SystemAllocationScope)
+                              -> *mut c_void {
+    unsafe {
+        let scope = AllocationScope::from_vulkan(scope);
+        if !callbacks(user_data).allocation(size, alignment, scope) {
+            return ptr::null_mut();
+        }
+
+        let memory = allocate(size, alignment);
+        if memory.is_null() {
+            callbacks(user_data).free(size, alignment);
+        }
+        memory
+    }
+}
+
+extern "system" fn reallocation(user_data: *mut c_void, original: *mut c_void, size: usize,
+                                alignment: usize, scope: vk::SystemAllocationScope)
+                                -> *mut c_void {
+    unsafe {
+        if original.is_null() {
+            return allocation(user_data, size, alignment, scope);
+        }
+
+        if size == 0 {
+            free(user_data, original);
+            return ptr::null_mut();
+        }
+
+        let memory = allocation(user_data, size, alignment, scope);
+        if memory.is_null() {
+            return ptr::null_mut();
+        }
+
+        let (original_size, _) = size_and_alignment(original);
+        ptr::copy_nonoverlapping(original as *const u8,
+                                 memory as *mut u8,
+                                 cmp::min(size, original_size));
+        free(user_data, original);
+        memory
+    }
+}
+
+extern "system" fn free(user_data: *mut c_void, memory: *mut c_void) {
+    if memory.is_null() {
+        return;
+    }
+
+    unsafe {
+        let (size, alignment) = size_and_alignment(memory);
+        deallocate(memory);
+        callbacks(user_data).free(size, alignment);
+    }
+}
+
+extern "system" fn internal_allocation(user_data: *mut c_void, size: usize,
+                                       ty: vk::InternalAllocationType,
+                                       scope: vk::SystemAllocationScope)
+                                       -> *mut c_void {
+    unsafe {
+        callbacks(user_data).internal_allocation(size,
+                                                 InternalAllocationType::from_vulkan(ty),
+                                                 AllocationScope::from_vulkan(scope));
+    }
+    ptr::null_mut()
+}
+
+extern "system" fn internal_free(user_data: *mut c_void, size: usize,
+                                 ty: vk::InternalAllocationType,
+                                 scope: vk::SystemAllocationScope)
+                                 -> *mut c_void {
+    unsafe {
+        callbacks(user_data).internal_free(size,
+                                           InternalAllocationType::from_vulkan(ty),
+                                           AllocationScope::from_vulkan(scope));
+    }
+    ptr::null_mut()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::raw::c_void;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use device::Device;
+    use device::DeviceExtensions;
+    use features::Features;
+    use instance::Instance;
+    use instance::InstanceExtensions;
+    use instance::PhysicalDevice;
+    use memory::AllocationScope;
+    use memory::HostAllocationCallbacks;
+    use memory::host::HostAllocator;
+    use vk;
+
+    struct Counter(AtomicUsize);
+
+    impl HostAllocationCallbacks for Counter {
+        fn allocation(&self, size: usize, _: usize, _: AllocationScope) -> bool {
+            self.0.fetch_add(size, Ordering::SeqCst);
+            true
+        }
+
+        fn free(&self, size: usize, _: usize) {
+            self.0.fetch_sub(size, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn allocate_reallocate_free() {
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let allocator = Some(HostAllocator::new(counter.clone()));
+        let raw = unsafe { &*HostAllocator::as_ptr(&allocator) };
+        let scope = vk::SYSTEM_ALLOCATION_SCOPE_OBJECT;
+
+        let memory = (raw.pfnAllocation)(raw.pUserData, 24, 64, scope);
+        assert!(!memory.is_null());
+        assert_eq!(memory as usize % 64, 0);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 24);
+
+        unsafe {
+            *(memory as *mut u8) = 42;
+        }
+        let memory = (raw.pfnReallocation)(raw.pUserData, memory, 100, 64, scope);
+        assert_eq!(memory as usize % 64, 0);
+        assert_eq!(unsafe { *(memory as *const u8) }, 42);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 100);
+
+        (raw.pfnFree)(raw.pUserData, memory);
+        (raw.pfnFree)(raw.pUserData, 0 as *mut c_void);
+        assert_eq!(counter.0.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn device_inherits_instance_callbacks() {
+        let counter = Arc::new(Counter(AtomicUsize::new(0)));
+        let instance = match Instance::with_allocation_callbacks(None,
+                                                                 &InstanceExtensions::none(),
+                                                                 None,
+                                                                 counter.clone()) {
+            Ok(i) => i,
+            Err(_) => return,
+        };
+        let physical = match PhysicalDevice::enumerate(&instance).next() {
+            Some(p) => p,
+            None => return,
+        };
+
+        let family = physical.queue_families().next().unwrap();
+        let (device, _) = Device::new(physical,
+                                      &Features::none(),
+                                      &DeviceExtensions::none(),
+                                      Some((family, 1.0)))
+            .unwrap();
+        assert!(device.allocation_callbacks().is_some());
+    }
+}
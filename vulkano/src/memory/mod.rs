@@ -97,9 +97,18 @@ pub use self::device_memory::CpuAccess;
 pub use self::device_memory::DeviceMemory;
 pub use self::device_memory::DeviceMemoryAllocError;
 pub use self::device_memory::MappedDeviceMemory;
+pub use self::external::ExternalMemoryError;
+pub use self::external::ExternalMemoryHandleType;
+pub use self::external::ExternalMemoryHandleTypes;
+pub use self::external::ExternalMemoryProperties;
+pub use self::host::AllocationScope;
+pub use self::host::HostAllocationCallbacks;
+pub use self::host::InternalAllocationType;
 pub use self::pool::MemoryPool;
 
 mod device_memory;
+mod external;
+pub(crate) mod host;
 pub mod pool;
 
 /// Represents requirements expressed by the Vulkan implementation when it comes to binding memory
@@ -121,6 +130,9 @@ pub struct MemoryRequirements {
     /// a whole block of memory dedicated to this resource alone). If the
     /// `khr_get_memory_requirements2` extension isn't enabled, then this will be false.
     ///
+    /// This is also true if the memory of the resource is exportable and the implementation
+    /// requires it to be a dedicated allocation.
+    ///
     /// > **Note**: As its name says, using a dedicated allocation is an optimization and not a
     /// > requirement.
     pub prefer_dedicated: bool,
@@ -27,6 +27,7 @@ use vk;
 /// Describes how the color output of the fragment shader is written to the attachment. See the
 /// documentation of the `blend` module for more info.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Blend {
     pub logic_op: Option<LogicOp>,
 
@@ -63,17 +64,20 @@ impl Blend {
 
 /// Describes how the blending system should behave.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AttachmentsBlend {
     /// All the framebuffer attachments will use the same blending.
     Collective(AttachmentBlend),
 
-    /// Each attachment will behave differently. Note that this requires enabling the
-    /// `independent_blend` feature.
+    /// Each attachment will behave differently. There must be one element per color attachment
+    /// of the subpass. Note that this requires enabling the `independent_blend` feature, unless
+    /// all the elements are identical.
     Individual(Vec<AttachmentBlend>),
 }
 
 /// Describes how the blending system should behave for an individual attachment.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AttachmentBlend {
     // TODO: could be automatically determined from the other params
     /// If false, blending is ignored and the output is directly written to the attachment.
@@ -94,6 +98,27 @@ pub struct AttachmentBlend {
 }
 
 impl AttachmentBlend {
+    // Returns true if one of the blend factors uses the second output of the fragment shader.
+    #[inline]
+    pub(crate) fn uses_dual_source(&self) -> bool {
+        self.enabled &&
+            (self.color_source.is_dual_source() || self.color_destination.is_dual_source() ||
+                 self.alpha_source.is_dual_source() ||
+                 self.alpha_destination.is_dual_source())
+    }
+
+    // Returns true if one of the color blend factors uses the alpha component of the blend
+    // constants.
+    #[inline]
+    pub(crate) fn uses_constant_alpha_color(&self) -> bool {
+        let constant_alpha = |factor| {
+            factor == BlendFactor::ConstantAlpha || factor == BlendFactor::OneMinusConstantAlpha
+        };
+
+        self.enabled &&
+            (constant_alpha(self.color_source) || constant_alpha(self.color_destination))
+    }
+
     /// Builds an `AttachmentBlend` where blending is disabled.
     #[inline]
     pub fn pass_through() -> AttachmentBlend {
@@ -188,6 +213,7 @@ impl AttachmentBlend {
 ///
 /// Also note that some implementations don't support logic operations.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum LogicOp {
     /// Returns `0`.
@@ -232,6 +258,7 @@ impl Default for LogicOp {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum BlendOp {
     Add = vk::BLEND_OP_ADD,
@@ -242,6 +269,7 @@ pub enum BlendOp {
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum BlendFactor {
     Zero = vk::BLEND_FACTOR_ZERO,
@@ -264,3 +292,18 @@ pub enum BlendFactor {
     Src1Alpha = vk::BLEND_FACTOR_SRC1_ALPHA,
     OneMinusSrc1Alpha = vk::BLEND_FACTOR_ONE_MINUS_SRC1_ALPHA,
 }
+
+impl BlendFactor {
+    /// Returns true if the factor uses the second output of the fragment shader. Using such a
+    /// factor requires the `dual_src_blend` feature to be enabled on the device.
+    #[inline]
+    pub fn is_dual_source(&self) -> bool {
+        match *self {
+            BlendFactor::Src1Color |
+            BlendFactor::OneMinusSrc1Color |
+            BlendFactor::Src1Alpha |
+            BlendFactor::OneMinusSrc1Alpha => true,
+            _ => false,
+        }
+    }
+}
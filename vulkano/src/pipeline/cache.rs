@@ -13,8 +13,8 @@
 //! pipelines on the disk.
 //!
 //! You can create either an empty cache or a cache from some initial data. Whenever you create a
-//! graphics or compute pipeline, you have the possibility to pass a reference to that cache.
-//! TODO: ^ that's not the case yet
+//! graphics or compute pipeline, you have the possibility to pass a reference to that cache
+//! (see `GraphicsPipelineBuilder::pipeline_cache` and `ComputePipeline::with_cache`).
 //! The Vulkan implementation will then look in the cache for an existing entry, or add one if it
 //! doesn't exist.
 //!
@@ -23,12 +23,16 @@
 //! on the disk, and [`with_data`](struct.PipelineCache.html#method.with_data) for how to reload it.
 //!
 
+use std::error;
+use std::fmt;
 use std::mem;
 use std::ptr;
 use std::sync::Arc;
 
 use device::Device;
+use device::DeviceOwned;
 
+use Error;
 use OomError;
 use VulkanObject;
 use check_errors;
@@ -46,15 +50,18 @@ impl PipelineCache {
     /// Builds a new pipeline cache from existing data. The data must have been previously obtained
     /// with [`get_data`](#method.get_data).
     ///
-    /// The data passed to this function will most likely be blindly trusted by the Vulkan
-    /// implementation. Therefore you can easily crash your application or the system by passing
-    /// wrong data. Hence why this function is unsafe.
+    /// The header of the data is checked against the physical device of `device`. If the data
+    /// was produced by a different driver or device, an error is returned and you should create
+    /// an empty cache instead.
+    ///
+    /// Apart from its header, the data passed to this function will most likely be blindly
+    /// trusted by the Vulkan implementation. Therefore you can easily crash your application or
+    /// the system by passing wrong data. Hence why this function is unsafe.
     ///
     /// # Example
     ///
     /// This example loads a cache from a file, if it exists.
     /// See [`get_data`](#method.get_data) for how to store the data in a file.
-    /// TODO: there's a header in the cached data that must be checked ; talk about this
     ///
     /// ```
     /// # use std::sync::Arc;
@@ -74,17 +81,55 @@ impl PipelineCache {
     ///     } else { None }
     /// };
     ///
-    /// let cache = if let Some(data) = data {
-    ///     // This is unsafe because there is no way to be sure that the file contains valid data.
-    ///     unsafe { PipelineCache::with_data(device.clone(), &data).unwrap() }
-    /// } else {
-    ///     PipelineCache::empty(device.clone()).unwrap()
+    /// // This is unsafe because there is no way to be sure that the file contains valid data.
+    /// // If the data comes from another device or driver version, we start from scratch.
+    /// let cache = match data.map(|data| unsafe { PipelineCache::with_data(device.clone(), &data) }) {
+    ///     Some(Ok(cache)) => cache,
+    ///     _ => PipelineCache::empty(device.clone()).unwrap(),
     /// };
     /// ```
     #[inline]
     pub unsafe fn with_data(device: Arc<Device>, initial_data: &[u8])
-                            -> Result<Arc<PipelineCache>, OomError> {
-        PipelineCache::new_impl(device, Some(initial_data))
+                            -> Result<Arc<PipelineCache>, PipelineCacheCreationError> {
+        PipelineCache::check_data(&device, initial_data)?;
+        Ok(PipelineCache::new_impl(device, Some(initial_data))?)
+    }
+
+    /// Checks whether the header of some data previously obtained with
+    /// [`get_data`](#method.get_data) is compatible with `device`.
+    ///
+    /// This doesn't check the rest of the data.
+    pub fn check_data(device: &Arc<Device>, data: &[u8]) -> Result<(), PipelineCacheCreationError> {
+        // The header is made of the header length, the header version, the vendor ID, the device
+        // ID and the pipeline cache UUID.
+        const HEADER_SIZE: usize = 16 + vk::UUID_SIZE as usize;
+
+        if data.len() < HEADER_SIZE {
+            return Err(PipelineCacheCreationError::InvalidHeader);
+        }
+
+        let read_u32 = |offset: usize| {
+            (data[offset] as u32) | ((data[offset + 1] as u32) << 8) |
+                ((data[offset + 2] as u32) << 16) | ((data[offset + 3] as u32) << 24)
+        };
+
+        let header_len = read_u32(0) as usize;
+        if header_len < HEADER_SIZE || header_len > data.len() {
+            return Err(PipelineCacheCreationError::InvalidHeader);
+        }
+        if read_u32(4) != vk::PIPELINE_CACHE_HEADER_VERSION_ONE {
+            return Err(PipelineCacheCreationError::InvalidHeader);
+        }
+
+        let physical_device = device.physical_device();
+        if read_u32(8) != physical_device.pci_vendor_id() ||
+            read_u32(12) != physical_device.pci_device_id() ||
+            &data[16 .. HEADER_SIZE] != &physical_device.uuid()[..]
+        {
+            return Err(PipelineCacheCreationError::IncompatibleDevice);
+        }
+
+        Ok(())
     }
 
     /// Builds a new empty pipeline cache.
@@ -122,7 +167,7 @@ impl PipelineCache {
             let mut output = mem::uninitialized();
             check_errors(vk.CreatePipelineCache(device.internal_object(),
                                                 &infos,
-                                                ptr::null(),
+                                                device.allocator_ptr(),
                                                 &mut output))?;
             output
         };
@@ -227,12 +272,84 @@ unsafe impl VulkanObject for PipelineCache {
     }
 }
 
+unsafe impl DeviceOwned for PipelineCache {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
 impl Drop for PipelineCache {
     #[inline]
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyPipelineCache(self.device.internal_object(), self.cache, ptr::null());
+            vk.DestroyPipelineCache(self.device.internal_object(),
+                                    self.cache,
+                                    self.device.allocator_ptr());
+        }
+    }
+}
+
+/// Error that can happen when creating a pipeline cache from existing data.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PipelineCacheCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+
+    /// The data doesn't start with a valid pipeline cache header.
+    InvalidHeader,
+
+    /// The data was created by a different device or a different version of the driver.
+    IncompatibleDevice,
+}
+
+impl error::Error for PipelineCacheCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            PipelineCacheCreationError::OomError(_) => "not enough memory available",
+            PipelineCacheCreationError::InvalidHeader => {
+                "the data doesn't start with a valid pipeline cache header"
+            },
+            PipelineCacheCreationError::IncompatibleDevice => {
+                "the data was created by a different device or driver version"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            PipelineCacheCreationError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for PipelineCacheCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for PipelineCacheCreationError {
+    #[inline]
+    fn from(err: OomError) -> PipelineCacheCreationError {
+        PipelineCacheCreationError::OomError(err)
+    }
+}
+
+impl From<Error> for PipelineCacheCreationError {
+    #[inline]
+    fn from(err: Error) -> PipelineCacheCreationError {
+        match err {
+            err @ Error::OutOfHostMemory => PipelineCacheCreationError::OomError(OomError::from(err)),
+            err @ Error::OutOfDeviceMemory => {
+                PipelineCacheCreationError::OomError(OomError::from(err))
+            },
+            _ => panic!("unexpected error: {:?}", err),
         }
     }
 }
@@ -240,6 +357,7 @@ impl Drop for PipelineCache {
 #[cfg(test)]
 mod tests {
     use pipeline::cache::PipelineCache;
+    use pipeline::cache::PipelineCacheCreationError;
 
     #[test]
     fn merge_self_forbidden() {
@@ -249,4 +367,30 @@ mod tests {
                                  pipeline.merge(&[&pipeline]).unwrap();
                              });
     }
+
+    #[test]
+    fn reload_data() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let cache = PipelineCache::empty(device.clone()).unwrap();
+        let data = cache.get_data().unwrap();
+        unsafe {
+            PipelineCache::with_data(device, &data).unwrap();
+        }
+    }
+
+    #[test]
+    fn invalid_header() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let cache = PipelineCache::empty(device.clone()).unwrap();
+        let mut data = cache.get_data().unwrap();
+
+        assert_eq!(PipelineCache::check_data(&device, &data[.. 8]),
+                   Err(PipelineCacheCreationError::InvalidHeader));
+
+        data[16] = data[16].wrapping_add(1);
+        match unsafe { PipelineCache::with_data(device, &data) } {
+            Err(PipelineCacheCreationError::IncompatibleDevice) => (),
+            _ => panic!(),
+        }
+    }
 }
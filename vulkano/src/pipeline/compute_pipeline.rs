@@ -7,11 +7,16 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use std::any::Any;
+use std::any::TypeId;
 use std::error;
+use std::ffi::CStr;
+use std::ffi::CString;
 use std::fmt;
 use std::marker::PhantomData;
 use std::mem;
 use std::ptr;
+use std::slice;
 use std::sync::Arc;
 
 use descriptor::descriptor::DescriptorDesc;
@@ -24,8 +29,12 @@ use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
 use descriptor::pipeline_layout::PipelineLayoutNotSupersetError;
 use descriptor::pipeline_layout::PipelineLayoutSuperset;
 use descriptor::pipeline_layout::PipelineLayoutSys;
+use pipeline::cache::PipelineCache;
+use pipeline::creation_feedback::FeedbackStorage;
+use pipeline::creation_feedback::PipelineCreationFeedback;
 use pipeline::shader::EntryPointAbstract;
 use pipeline::shader::SpecializationConstants;
+use spirv::Spirv;
 
 use Error;
 use OomError;
@@ -46,6 +55,7 @@ use vk;
 pub struct ComputePipeline<Pl> {
     inner: Inner,
     pipeline_layout: Pl,
+    creation_feedback: Option<PipelineCreationFeedback>,
 }
 
 struct Inner {
@@ -60,13 +70,104 @@ impl ComputePipeline<()> {
         -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
         where Cs::PipelineLayout: Clone,
               Cs: EntryPointAbstract
+    {
+        ComputePipeline::with_cache(device, shader, specialization, None)
+    }
+
+    /// Same as `new`, but uses a pipeline cache.
+    ///
+    /// The Vulkan implementation will look in the cache for an existing pipeline, or add one if
+    /// it doesn't exist. See [the `cache` module](cache/index.html) for more info.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the cache wasn't created with the same device.
+    ///
+    pub fn with_cache<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        cache: Option<&Arc<PipelineCache>>)
+        -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract
     {
         unsafe {
             let pipeline_layout = shader.layout().clone().build(device.clone())?;
-            ComputePipeline::with_unchecked_pipeline_layout(device,
+            ComputePipeline::new_inner(device,
+                                       shader,
+                                       specialization,
+                                       pipeline_layout,
+                                       &SubgroupSizeControl::default(),
+                                       cache)
+        }
+    }
+
+    /// Same as `with_cache`, but returns an existing pipeline if one was created by this function
+    /// with the same shader module, entry point, values of specialization constants and pipeline
+    /// layout description, and is still alive.
+    ///
+    /// This avoids compiling the same pipeline multiple times, for example when an asset system
+    /// requests the same shader repeatedly. Combine it with `ShaderModule::from_words_deduplicated`
+    /// so that loading the same code twice gives the same module.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the cache wasn't created with the same device.
+    ///
+    pub fn new_deduplicated<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        cache: Option<&Arc<PipelineCache>>)
+        -> Result<Arc<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>>,
+                  ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone + Send + Sync + 'static,
+              Cs: EntryPointAbstract
+    {
+        let key = ComputePipelineKey {
+            module: shader.module().internal_object(),
+            entry_point: shader.name().to_owned(),
+            specialization: specialization_values(specialization),
+            pipeline_layout: TypeId::of::<Cs::PipelineLayout>(),
+            descriptors: layout_descriptors(shader.layout()),
+            push_constants: layout_push_constants(shader.layout()),
+        };
+
+        let mut pipelines = device.compute_pipelines().lock().unwrap();
+        pipelines.retain(|_, pipeline| pipeline.upgrade().is_some());
+
+        if let Some(pipeline) = pipelines.get(&key).and_then(|pipeline| pipeline.upgrade()) {
+            // The type of the pipeline layout is part of the key, so the downcast can't fail.
+            return Ok(pipeline.downcast().unwrap());
+        }
+
+        let pipeline = Arc::new(ComputePipeline::with_cache(device.clone(),
                                                             shader,
                                                             specialization,
-                                                            pipeline_layout)
+                                                            cache)?);
+        let erased: Arc<Any + Send + Sync> = pipeline.clone();
+        pipelines.insert(key, Arc::downgrade(&erased));
+        Ok(pipeline)
+    }
+
+    /// Same as `with_cache`, but controls the size of the subgroups the shader is executed with.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the cache wasn't created with the same device.
+    ///
+    pub fn with_subgroup_size_control<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        subgroup_size_control: SubgroupSizeControl, cache: Option<&Arc<PipelineCache>>)
+        -> Result<ComputePipeline<PipelineLayout<Cs::PipelineLayout>>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract
+    {
+        unsafe {
+            let pipeline_layout = shader.layout().clone().build(device.clone())?;
+            ComputePipeline::new_inner(device,
+                                       shader,
+                                       specialization,
+                                       pipeline_layout,
+                                       &subgroup_size_control,
+                                       cache)
         }
     }
 }
@@ -83,13 +184,36 @@ impl<Pl> ComputePipeline<Pl> {
         where Cs::PipelineLayout: Clone,
               Cs: EntryPointAbstract,
               Pl: PipelineLayoutAbstract
+    {
+        ComputePipeline::with_pipeline_layout_and_cache(device,
+                                                        shader,
+                                                        specialization,
+                                                        pipeline_layout,
+                                                        None)
+    }
+
+    /// Same as `with_pipeline_layout`, but uses a pipeline cache.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the cache wasn't created with the same device.
+    ///
+    pub fn with_pipeline_layout_and_cache<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        pipeline_layout: Pl, cache: Option<&Arc<PipelineCache>>)
+        -> Result<ComputePipeline<Pl>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract,
+              Pl: PipelineLayoutAbstract
     {
         unsafe {
             PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout, shader.layout())?;
-            ComputePipeline::with_unchecked_pipeline_layout(device,
-                                                            shader,
-                                                            specialization,
-                                                            pipeline_layout)
+            ComputePipeline::new_inner(device,
+                                       shader,
+                                       specialization,
+                                       pipeline_layout,
+                                       &SubgroupSizeControl::default(),
+                                       cache)
         }
     }
 
@@ -102,10 +226,47 @@ impl<Pl> ComputePipeline<Pl> {
         where Cs::PipelineLayout: Clone,
               Cs: EntryPointAbstract,
               Pl: PipelineLayoutAbstract
+    {
+        ComputePipeline::new_inner(device,
+                                   shader,
+                                   specialization,
+                                   pipeline_layout,
+                                   &SubgroupSizeControl::default(),
+                                   None)
+    }
+
+    // Actual implementation of the constructors.
+    unsafe fn new_inner<Cs>(
+        device: Arc<Device>, shader: &Cs, specialization: &Cs::SpecializationConstants,
+        pipeline_layout: Pl, subgroup_size_control: &SubgroupSizeControl,
+        cache: Option<&Arc<PipelineCache>>)
+        -> Result<ComputePipeline<Pl>, ComputePipelineCreationError>
+        where Cs::PipelineLayout: Clone,
+              Cs: EntryPointAbstract,
+              Pl: PipelineLayoutAbstract
     {
         let vk = device.pointers();
 
+        let stage_flags = subgroup_size_control.check(&device)?;
+        subgroup_size_control.check_local_size(&device, shader.module().spirv(), shader.name())?;
+
+        let cache = match cache {
+            Some(cache) => {
+                assert_eq!(cache.device().internal_object(), device.internal_object());
+                cache.internal_object()
+            },
+            None => 0,
+        };
+
+        let mut feedback = if device.loaded_extensions().ext_pipeline_creation_feedback {
+            Some(FeedbackStorage::new(Some(vk::SHADER_STAGE_COMPUTE_BIT)))
+        } else {
+            None
+        };
+
         let pipeline = {
+            let feedback_info = feedback.as_mut().map(|f| f.create_info());
+
             let spec_descriptors = Cs::SpecializationConstants::descriptors();
             let specialization = vk::SpecializationInfo {
                 mapEntryCount: spec_descriptors.len() as u32,
@@ -114,10 +275,21 @@ impl<Pl> ComputePipeline<Pl> {
                 pData: specialization as *const Cs::SpecializationConstants as *const _,
             };
 
+            let required_subgroup_size = subgroup_size_control.required_subgroup_size.map(|size| {
+                vk::PipelineShaderStageRequiredSubgroupSizeCreateInfoEXT {
+                    sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_REQUIRED_SUBGROUP_SIZE_CREATE_INFO_EXT,
+                    pNext: ptr::null_mut(),
+                    requiredSubgroupSize: size,
+                }
+            });
+
             let stage = vk::PipelineShaderStageCreateInfo {
                 sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
-                pNext: ptr::null(),
-                flags: 0,
+                pNext: required_subgroup_size
+                    .as_ref()
+                    .map(|s| s as *const _ as *const _)
+                    .unwrap_or(ptr::null()),
+                flags: stage_flags,
                 stage: vk::SHADER_STAGE_COMPUTE_BIT,
                 module: shader.module().internal_object(),
                 pName: shader.name().as_ptr(),
@@ -130,20 +302,29 @@ impl<Pl> ComputePipeline<Pl> {
 
             let infos = vk::ComputePipelineCreateInfo {
                 sType: vk::STRUCTURE_TYPE_COMPUTE_PIPELINE_CREATE_INFO,
-                pNext: ptr::null(),
-                flags: 0,
+                pNext: feedback_info
+                    .as_ref()
+                    .map(|f| f as *const _ as *const _)
+                    .unwrap_or(ptr::null()),
+                flags: if device.loaded_extensions().khr_device_group {
+                    vk::PIPELINE_CREATE_DISPATCH_BASE_KHR
+                } else {
+                    0
+                },
                 stage: stage,
                 layout: PipelineLayoutAbstract::sys(&pipeline_layout).internal_object(),
                 basePipelineHandle: 0,
                 basePipelineIndex: 0,
             };
 
+            let _span = instrument_span!("vkCreateComputePipelines",
+                                         cache = ::instrument::pipeline_cache_name(&device, cache));
             let mut output = mem::uninitialized();
             check_errors(vk.CreateComputePipelines(device.internal_object(),
-                                                   0,
+                                                   cache,
                                                    1,
                                                    &infos,
-                                                   ptr::null(),
+                                                   device.allocator_ptr(),
                                                    &mut output))?;
             output
         };
@@ -154,10 +335,67 @@ impl<Pl> ComputePipeline<Pl> {
                    pipeline: pipeline,
                },
                pipeline_layout: pipeline_layout,
+               creation_feedback: feedback.map(|f| f.into_feedback()),
            })
     }
 }
 
+// Identifies a pipeline created with `ComputePipeline::new_deduplicated`. The entries of a shader
+// module are removed from the cache of the device when the module is destroyed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct ComputePipelineKey {
+    pub(crate) module: vk::ShaderModule,
+    entry_point: CString,
+    // The `constant_id` and the bytes of each specialization constant.
+    specialization: Vec<(u32, Vec<u8>)>,
+    pipeline_layout: TypeId,
+    // Layout descriptions of the same type can be different, for example when they are built at
+    // runtime, so the type alone isn't enough.
+    descriptors: Vec<Vec<Option<DescriptorDesc>>>,
+    push_constants: Vec<PipelineLayoutDescPcRange>,
+}
+
+// Returns the descriptors of each set of a pipeline layout description.
+fn layout_descriptors<Pl>(desc: &Pl) -> Vec<Vec<Option<DescriptorDesc>>>
+    where Pl: PipelineLayoutDesc
+{
+    (0 .. desc.num_sets())
+        .map(|set| {
+                 let num_bindings = desc.num_bindings_in_set(set).unwrap_or(0);
+                 (0 .. num_bindings)
+                     .map(|binding| desc.descriptor(set, binding))
+                     .collect()
+             })
+        .collect()
+}
+
+// Returns the push constants ranges of a pipeline layout description.
+fn layout_push_constants<Pl>(desc: &Pl) -> Vec<PipelineLayoutDescPcRange>
+    where Pl: PipelineLayoutDesc
+{
+    (0 .. desc.num_push_constants_ranges())
+        .filter_map(|num| desc.push_constants_range(num))
+        .collect()
+}
+
+// Returns the value of each specialization constant. The padding between the members of the
+// struct is ignored.
+fn specialization_values<S>(specialization: &S) -> Vec<(u32, Vec<u8>)>
+    where S: SpecializationConstants
+{
+    let data = unsafe {
+        slice::from_raw_parts(specialization as *const S as *const u8, mem::size_of::<S>())
+    };
+
+    S::descriptors()
+        .iter()
+        .map(|entry| {
+                 let offset = entry.offset as usize;
+                 (entry.constant_id, data[offset .. offset + entry.size].to_owned())
+             })
+        .collect()
+}
+
 impl<Pl> fmt::Debug for ComputePipeline<Pl> {
     #[inline]
     fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
@@ -177,6 +415,114 @@ impl<Pl> ComputePipeline<Pl> {
     pub fn layout(&self) -> &Pl {
         &self.pipeline_layout
     }
+
+    /// Returns the feedback about the creation of this pipeline, or `None` if the
+    /// `VK_EXT_pipeline_creation_feedback` extension isn't enabled on the device.
+    #[inline]
+    pub fn creation_feedback(&self) -> Option<&PipelineCreationFeedback> {
+        self.creation_feedback.as_ref()
+    }
+}
+
+/// Controls the size of the subgroups that a compute shader is executed with.
+///
+/// The default value doesn't control anything, and can be used without the
+/// `VK_EXT_subgroup_size_control` extension. Any other value requires the extension and the
+/// matching feature in `Device::enabled_features()`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubgroupSizeControl {
+    /// If `Some`, the shader is executed with subgroups of exactly this size. Must be a power of
+    /// two between the `min_subgroup_size` and the `max_subgroup_size` of the device, and compute
+    /// shaders must be part of its `required_subgroup_size_stages`.
+    pub required_subgroup_size: Option<u32>,
+    /// If true, the implementation is allowed to use any supported subgroup size, and the
+    /// `SubgroupSize` built-in can be different from the subgroup size of the device.
+    pub allow_varying_subgroup_size: bool,
+    /// If true, all the subgroups of a workgroup are full. The `x` dimension of the local size of
+    /// the shader must then be a multiple of the subgroup size. Requires the
+    /// `compute_full_subgroups` feature.
+    pub require_full_subgroups: bool,
+}
+
+impl SubgroupSizeControl {
+    // Checks the values against the device and returns the flags of the shader stage.
+    fn check(&self, device: &Device)
+             -> Result<vk::PipelineShaderStageCreateFlags, ComputePipelineCreationError> {
+        let features = device.enabled_features();
+        let properties = device.subgroup_size_control_properties();
+        let mut flags = 0;
+
+        if let Some(size) = self.required_subgroup_size {
+            if !features.subgroup_size_control {
+                return Err(ComputePipelineCreationError::SubgroupSizeControlNotEnabled);
+            }
+            if !size.is_power_of_two() || size < properties.min_subgroup_size ||
+                size > properties.max_subgroup_size ||
+                !properties.required_subgroup_size_stages.compute
+            {
+                return Err(ComputePipelineCreationError::InvalidRequiredSubgroupSize {
+                               size: size,
+                               min: properties.min_subgroup_size,
+                               max: properties.max_subgroup_size,
+                           });
+            }
+        }
+
+        if self.allow_varying_subgroup_size {
+            if !features.subgroup_size_control {
+                return Err(ComputePipelineCreationError::SubgroupSizeControlNotEnabled);
+            }
+            flags |= vk::PIPELINE_SHADER_STAGE_CREATE_ALLOW_VARYING_SUBGROUP_SIZE_BIT_EXT;
+        }
+
+        if self.require_full_subgroups {
+            if !features.compute_full_subgroups {
+                return Err(ComputePipelineCreationError::FullSubgroupsNotEnabled);
+            }
+            flags |= vk::PIPELINE_SHADER_STAGE_CREATE_REQUIRE_FULL_SUBGROUPS_BIT_EXT;
+        }
+
+        Ok(flags)
+    }
+
+    // Checks that the `x` dimension of the local size of the entry point `name` is a multiple of
+    // the subgroup size when full subgroups are required. Local sizes that can't be known without
+    // specializing the shader aren't checked.
+    fn check_local_size(&self, device: &Device, spirv: &[u32], name: &CStr)
+                        -> Result<(), ComputePipelineCreationError> {
+        if !self.require_full_subgroups {
+            return Ok(());
+        }
+
+        let local_size = match (Spirv::parse(spirv), name.to_str()) {
+            (Ok(spirv), Ok(name)) => {
+                match spirv.entry_point(name).and_then(|i| i.operands.get(1)) {
+                    Some(&id) => spirv.local_size(id),
+                    None => None,
+                }
+            },
+            _ => None,
+        };
+
+        // With a varying subgroup size, any size up to the maximum can be used.
+        let subgroup_size = match self.required_subgroup_size {
+            Some(size) => size,
+            None if self.allow_varying_subgroup_size => {
+                device.subgroup_size_control_properties().max_subgroup_size
+            },
+            None => device.physical_device().subgroup_properties().subgroup_size,
+        };
+
+        match local_size {
+            Some([x, _, _]) if subgroup_size != 0 && x % subgroup_size != 0 => {
+                Err(ComputePipelineCreationError::LocalSizeNotMultipleOfSubgroupSize {
+                        local_size_x: x,
+                        subgroup_size: subgroup_size,
+                    })
+            },
+            _ => Ok(()),
+        }
+    }
 }
 
 /// Trait implemented on all compute pipelines.
@@ -287,7 +633,9 @@ impl Drop for Inner {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyPipeline(self.device.internal_object(), self.pipeline, ptr::null());
+            vk.DestroyPipeline(self.device.internal_object(),
+                               self.pipeline,
+                               self.device.allocator_ptr());
         }
     }
 }
@@ -301,6 +649,29 @@ pub enum ComputePipelineCreationError {
     PipelineLayoutCreationError(PipelineLayoutCreationError),
     /// The pipeline layout is not compatible with what the shader expects.
     IncompatiblePipelineLayout(PipelineLayoutNotSupersetError),
+    /// A subgroup size was required or allowed to vary, but the `subgroup_size_control` feature
+    /// isn't enabled.
+    SubgroupSizeControlNotEnabled,
+    /// Full subgroups were required, but the `compute_full_subgroups` feature isn't enabled.
+    FullSubgroupsNotEnabled,
+    /// The required subgroup size isn't a power of two within the limits of the device, or
+    /// compute shaders can't require a subgroup size.
+    InvalidRequiredSubgroupSize {
+        /// The required size.
+        size: u32,
+        /// The minimum subgroup size of the device.
+        min: u32,
+        /// The maximum subgroup size of the device.
+        max: u32,
+    },
+    /// Full subgroups were required, but the `x` dimension of the local size of the shader isn't
+    /// a multiple of the subgroup size.
+    LocalSizeNotMultipleOfSubgroupSize {
+        /// The `x` dimension of the local size.
+        local_size_x: u32,
+        /// The subgroup size that the local size must be a multiple of.
+        subgroup_size: u32,
+    },
 }
 
 impl error::Error for ComputePipelineCreationError {
@@ -312,6 +683,14 @@ impl error::Error for ComputePipelineCreationError {
                 "error while creating the pipeline layout object",
             ComputePipelineCreationError::IncompatiblePipelineLayout(_) =>
                 "the pipeline layout is not compatible with what the shader expects",
+            ComputePipelineCreationError::SubgroupSizeControlNotEnabled =>
+                "the `subgroup_size_control` feature isn't enabled",
+            ComputePipelineCreationError::FullSubgroupsNotEnabled =>
+                "the `compute_full_subgroups` feature isn't enabled",
+            ComputePipelineCreationError::InvalidRequiredSubgroupSize { .. } =>
+                "the required subgroup size isn't supported by the device",
+            ComputePipelineCreationError::LocalSizeNotMultipleOfSubgroupSize { .. } =>
+                "the local size of the shader isn't a multiple of the subgroup size",
         }
     }
 
@@ -321,6 +700,7 @@ impl error::Error for ComputePipelineCreationError {
             ComputePipelineCreationError::OomError(ref err) => Some(err),
             ComputePipelineCreationError::PipelineLayoutCreationError(ref err) => Some(err),
             ComputePipelineCreationError::IncompatiblePipelineLayout(ref err) => Some(err),
+            _ => None,
         }
     }
 }
@@ -378,13 +758,19 @@ mod tests {
     use descriptor::descriptor::DescriptorDescTy;
     use descriptor::descriptor::ShaderStages;
     use descriptor::descriptor_set::PersistentDescriptorSet;
+    use descriptor::pipeline_layout::EmptyPipelineDesc;
     use descriptor::pipeline_layout::PipelineLayoutDesc;
     use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+    use descriptor::pipeline_layout::RuntimePipelineDesc;
     use pipeline::ComputePipeline;
+    use pipeline::ComputePipelineCreationError;
+    use pipeline::SubgroupSizeControl;
     use pipeline::shader::ShaderModule;
     use pipeline::shader::SpecializationConstants;
     use pipeline::shader::SpecializationMapEntry;
     use std::ffi::CStr;
+    use spirv::*;
+    use spirv::tests::*;
     use std::sync::Arc;
     use sync::GpuFuture;
     use sync::now;
@@ -993,4 +1379,113 @@ mod tests {
         let data_buffer_content = data_buffer.read().unwrap();
         assert_eq!(*data_buffer_content, 0x12345678);
     }
+
+    #[test]
+    fn subgroup_size_control_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        assert_eq!(SubgroupSizeControl::default().check(&device).unwrap(), 0);
+
+        let control = SubgroupSizeControl {
+            required_subgroup_size: Some(32),
+            ..SubgroupSizeControl::default()
+        };
+        match control.check(&device) {
+            Err(ComputePipelineCreationError::SubgroupSizeControlNotEnabled) => (),
+            _ => panic!(),
+        }
+
+        let control = SubgroupSizeControl {
+            require_full_subgroups: true,
+            ..SubgroupSizeControl::default()
+        };
+        match control.check(&device) {
+            Err(ComputePipelineCreationError::FullSubgroupsNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn local_size_not_multiple_of_subgroup_size() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 4, 0x6e69616d, 0]),
+                             (OP_EXECUTION_MODE, &[4, EXECUTION_MODE_LOCAL_SIZE, 48, 1, 1])]);
+        let name = CStr::from_bytes_with_nul(b"main\0").unwrap();
+
+        let control = SubgroupSizeControl {
+            required_subgroup_size: Some(16),
+            require_full_subgroups: true,
+            ..SubgroupSizeControl::default()
+        };
+        assert!(control.check_local_size(&device, &words, name).is_ok());
+
+        let control = SubgroupSizeControl {
+            required_subgroup_size: Some(32),
+            require_full_subgroups: true,
+            ..SubgroupSizeControl::default()
+        };
+        match control.check_local_size(&device, &words, name) {
+            Err(ComputePipelineCreationError::LocalSizeNotMultipleOfSubgroupSize {
+                    local_size_x: 48,
+                    subgroup_size: 32,
+                }) => (),
+            _ => panic!(),
+        }
+
+        // Full subgroups aren't required.
+        let control = SubgroupSizeControl {
+            required_subgroup_size: Some(32),
+            ..SubgroupSizeControl::default()
+        };
+        assert!(control.check_local_size(&device, &words, name).is_ok());
+    }
+
+    #[test]
+    fn deduplicated() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        // An empty compute shader.
+        let words = module(&[(OP_CAPABILITY, &[CAPABILITY_SHADER]),
+                             (OP_MEMORY_MODEL, &[0, 1]),
+                             (OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 4, 0x6e69616d, 0]),
+                             (OP_EXECUTION_MODE, &[4, EXECUTION_MODE_LOCAL_SIZE, 1, 1, 1]),
+                             (OP_TYPE_VOID, &[2]),
+                             (OP_TYPE_FUNCTION, &[3, 2]),
+                             (OP_FUNCTION, &[2, 4, 0, 3]),
+                             (OP_LABEL, &[5]),
+                             (OP_RETURN, &[]),
+                             (OP_FUNCTION_END, &[])]);
+
+        unsafe {
+            let module1 = ShaderModule::from_words_deduplicated(device.clone(), &words).unwrap();
+            let module2 = ShaderModule::from_words_deduplicated(device.clone(), &words).unwrap();
+            assert!(Arc::ptr_eq(&module1, &module2));
+
+            let name = CStr::from_bytes_with_nul_unchecked(b"main\0");
+            let shader = module1.compute_entry_point::<(), _>(name, EmptyPipelineDesc);
+            let pipeline1 = ComputePipeline::new_deduplicated(device.clone(), &shader, &(), None)
+                .unwrap();
+            let pipeline2 = ComputePipeline::new_deduplicated(device.clone(), &shader, &(), None)
+                .unwrap();
+            assert!(Arc::ptr_eq(&pipeline1, &pipeline2));
+
+            // Layouts of the same type that are different must give different pipelines.
+            let push_constants = PipelineLayoutDescPcRange {
+                offset: 0,
+                size: 16,
+                stages: ShaderStages::compute(),
+            };
+            let layout1 = RuntimePipelineDesc::new(Vec::<Vec<_>>::new(), None).unwrap();
+            let layout2 = RuntimePipelineDesc::new(Vec::<Vec<_>>::new(), Some(push_constants))
+                .unwrap();
+            let shader1 = module1.compute_entry_point::<(), _>(name, layout1);
+            let shader2 = module1.compute_entry_point::<(), _>(name, layout2);
+            let pipeline1 = ComputePipeline::new_deduplicated(device.clone(), &shader1, &(), None)
+                .unwrap();
+            let pipeline2 = ComputePipeline::new_deduplicated(device.clone(), &shader2, &(), None)
+                .unwrap();
+            assert!(!Arc::ptr_eq(&pipeline1, &pipeline2));
+        }
+    }
 }
@@ -0,0 +1,115 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Information about how a pipeline was created.
+//!
+//! If the `VK_EXT_pipeline_creation_feedback` extension is enabled on the device, the Vulkan
+//! implementation reports how long it took to create each pipeline and each of its shader stages,
+//! and whether the pipeline could be found in the pipeline cache. This can be used to profile
+//! the compilation of shaders, or to check that a pipeline cache is effective.
+//!
+//! The feedback can be retrieved with `GraphicsPipeline::creation_feedback` or
+//! `ComputePipeline::creation_feedback`.
+
+use smallvec::SmallVec;
+use std::ptr;
+use std::time::Duration;
+
+use descriptor::descriptor::ShaderStages;
+use vk;
+
+/// Feedback about the creation of a pipeline and of each of its shader stages.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipelineCreationFeedback {
+    /// Feedback about the pipeline as a whole.
+    pub pipeline: CreationFeedback,
+    /// Feedback about each shader stage of the pipeline.
+    pub stages: Vec<(ShaderStages, CreationFeedback)>,
+}
+
+/// Feedback about the creation of a pipeline or of a shader stage.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CreationFeedback {
+    /// True if the implementation has filled the other fields. If false, they must be ignored.
+    pub valid: bool,
+    /// True if the pipeline cache passed when creating the pipeline already contained the
+    /// pipeline or the stage, and compilation could be avoided.
+    pub application_pipeline_cache_hit: bool,
+    /// True if the creation was accelerated by a base pipeline.
+    pub base_pipeline_acceleration: bool,
+    /// Time spent creating the pipeline or the stage.
+    pub duration: Duration,
+}
+
+impl CreationFeedback {
+    fn from_vulkan(feedback: &vk::PipelineCreationFeedbackEXT) -> CreationFeedback {
+        let flags = feedback.flags;
+
+        CreationFeedback {
+            valid: (flags & vk::PIPELINE_CREATION_FEEDBACK_VALID_BIT_EXT) != 0,
+            application_pipeline_cache_hit:
+                (flags & vk::PIPELINE_CREATION_FEEDBACK_APPLICATION_PIPELINE_CACHE_HIT_BIT_EXT) != 0,
+            base_pipeline_acceleration:
+                (flags & vk::PIPELINE_CREATION_FEEDBACK_BASE_PIPELINE_ACCELERATION_BIT_EXT) != 0,
+            duration: Duration::new(feedback.duration / 1_000_000_000,
+                                    (feedback.duration % 1_000_000_000) as u32),
+        }
+    }
+}
+
+// Memory where the implementation writes the feedback during the creation of a pipeline.
+pub(crate) struct FeedbackStorage {
+    pipeline: vk::PipelineCreationFeedbackEXT,
+    stage_flags: SmallVec<[vk::ShaderStageFlags; 5]>,
+    stages: SmallVec<[vk::PipelineCreationFeedbackEXT; 5]>,
+}
+
+impl FeedbackStorage {
+    // Builds the storage for a pipeline whose shader stages are `stages`, in the same order as
+    // in the create info.
+    pub(crate) fn new<I>(stages: I) -> FeedbackStorage
+        where I: IntoIterator<Item = vk::ShaderStageFlags>
+    {
+        let empty = || vk::PipelineCreationFeedbackEXT { flags: 0, duration: 0 };
+        let stage_flags = stages.into_iter().collect::<SmallVec<[_; 5]>>();
+
+        FeedbackStorage {
+            pipeline: empty(),
+            stages: stage_flags.iter().map(|_| empty()).collect(),
+            stage_flags: stage_flags,
+        }
+    }
+
+    // Returns the struct to chain to the create info of the pipeline. The storage must not be
+    // moved while the returned value is in use.
+    pub(crate) fn create_info(&mut self) -> vk::PipelineCreationFeedbackCreateInfoEXT {
+        vk::PipelineCreationFeedbackCreateInfoEXT {
+            sType: vk::STRUCTURE_TYPE_PIPELINE_CREATION_FEEDBACK_CREATE_INFO_EXT,
+            pNext: ptr::null(),
+            pPipelineCreationFeedback: &mut self.pipeline,
+            pipelineStageCreationFeedbackCount: self.stages.len() as u32,
+            pPipelineStageCreationFeedbacks: self.stages.as_mut_ptr(),
+        }
+    }
+
+    // Turns the storage into the public type, once the pipeline has been created.
+    pub(crate) fn into_feedback(self) -> PipelineCreationFeedback {
+        PipelineCreationFeedback {
+            pipeline: CreationFeedback::from_vulkan(&self.pipeline),
+            stages: self.stage_flags
+                .iter()
+                .zip(self.stages.iter())
+                .map(|(&flags, feedback)| {
+                         let stage = ShaderStages::from_vulkan_bits(flags);
+                         (stage, CreationFeedback::from_vulkan(feedback))
+                     })
+                .collect(),
+        }
+    }
+}
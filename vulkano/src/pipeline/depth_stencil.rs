@@ -24,8 +24,28 @@ use std::ops::Range;
 use std::u32;
 use vk;
 
+/// Faces of the primitives that a stencil command applies to.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum StencilFaces {
+    /// Only the faces that are facing the user.
+    Front = vk::STENCIL_FACE_FRONT_BIT,
+    /// Only the faces that are facing away from the user.
+    Back = vk::STENCIL_FACE_BACK_BIT,
+    /// Both faces.
+    FrontAndBack = vk::STENCIL_FRONT_AND_BACK,
+}
+
+impl Into<vk::StencilFaceFlags> for StencilFaces {
+    #[inline]
+    fn into(self) -> vk::StencilFaceFlags {
+        self as u32
+    }
+}
+
 /// Configuration of the depth and stencil tests.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DepthStencil {
     /// Comparison to use between the depth value of each fragment and the depth value currently
     /// in the depth buffer.
@@ -82,6 +102,7 @@ impl Default for DepthStencil {
 
 /// Configuration of a stencil test.
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Stencil {
     /// The comparison to perform between the existing stencil value in the stencil buffer, and
     /// the reference value (given by `reference`).
@@ -160,6 +181,7 @@ impl Default for Stencil {
 
 /// Operation to perform after the depth and stencil tests.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum StencilOp {
     Keep = vk::STENCIL_OP_KEEP,
@@ -174,6 +196,7 @@ pub enum StencilOp {
 
 /// Allows you to ask the GPU to exclude fragments that are outside of a certain range.
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DepthBounds {
     /// The test is disabled. All fragments pass the depth bounds test.
     Disabled,
@@ -202,6 +225,7 @@ impl DepthBounds {
 ///
 /// Used for both depth testing and stencil testing.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum Compare {
     /// The test never passes.
@@ -221,3 +245,24 @@ pub enum Compare {
     /// The test always passes.
     Always = vk::COMPARE_OP_ALWAYS,
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use pipeline::depth_stencil::Compare;
+    use pipeline::depth_stencil::DepthBounds;
+    use pipeline::depth_stencil::DepthStencil;
+    use pipeline::depth_stencil::StencilOp;
+
+    #[test]
+    fn serde_round_trip() {
+        let mut state = DepthStencil::simple_depth_test();
+        state.depth_bounds_test = DepthBounds::Fixed(0.25 .. 0.75);
+        state.stencil_front.compare = Compare::Equal;
+        state.stencil_front.pass_op = StencilOp::IncrementAndWrap;
+        state.stencil_back.reference = None;
+
+        let json = ::serde_json::to_string(&state).unwrap();
+        let decoded: DepthStencil = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", state));
+    }
+}
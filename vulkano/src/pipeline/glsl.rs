@@ -0,0 +1,402 @@
+// Copyright (c) 2018 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Compilation of GLSL shaders at runtime.
+//!
+//! This module is only available if the `shaderc` feature of vulkano is enabled. It compiles GLSL
+//! source code to SPIR-V with [shaderc](https://github.com/google/shaderc), and reflects the
+//! result with the `reflect` module so that it can be passed to the pipeline builders directly.
+//! This makes it possible to reload shaders while the application is running, or to use shaders
+//! written by the user.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::device::Device;
+//! use vulkano::pipeline::glsl::GlslCompileOptions;
+//! use vulkano::pipeline::glsl::GlslShaderStage;
+//! use vulkano::pipeline::shader::ShaderModule;
+//!
+//! # let device: Arc<Device> = return;
+//! let source = "
+//!     #version 450
+//!     #include \"common.glsl\"
+//!     layout(location = 0) in vec2 position;
+//!     void main() { gl_Position = vec4(position * SCALE, 0.0, 1.0); }
+//! ";
+//!
+//! let options = GlslCompileOptions::new()
+//!     .file_name("shader.vert")
+//!     .macro_definition("SCALE", Some("0.5"))
+//!     .include_callback(|name, _, _, _| {
+//!         let content = std::fs::read_to_string(name).map_err(|err| err.to_string())?;
+//!         Ok(vulkano::pipeline::glsl::ResolvedInclude {
+//!             resolved_name: name.to_owned(),
+//!             content: content,
+//!         })
+//!     });
+//!
+//! let (module, entry_point) = unsafe {
+//!     ShaderModule::from_glsl_source(device.clone(), source, GlslShaderStage::Vertex, &options)
+//!         .unwrap()
+//! };
+//! let vertex_shader = unsafe { entry_point.graphics_entry_point::<()>(&module).unwrap() };
+//! ```
+
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use shaderc::CompileOptions;
+use shaderc::Compiler;
+use shaderc::ShaderKind;
+use shaderc::SpirvVersion;
+
+use descriptor::descriptor::ShaderStages;
+use device::Device;
+use pipeline::reflect;
+use pipeline::reflect::EntryPointInfo;
+use pipeline::reflect::EntryPointLookupError;
+use pipeline::shader::ShaderModule;
+use pipeline::shader::ShaderModuleCreationError;
+use pipeline::shader::ShaderReflectionError;
+
+pub use shaderc::IncludeType;
+pub use shaderc::ResolvedInclude;
+
+/// Stage that a GLSL shader is compiled for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GlslShaderStage {
+    Vertex,
+    TessellationControl,
+    TessellationEvaluation,
+    Geometry,
+    Fragment,
+    Compute,
+    /// Requires the `VK_EXT_mesh_shader` extension.
+    Task,
+    /// Requires the `VK_EXT_mesh_shader` extension.
+    Mesh,
+    /// Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    RayGeneration,
+    /// Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    AnyHit,
+    /// Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    ClosestHit,
+    /// Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    Miss,
+    /// Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    Intersection,
+    /// Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    Callable,
+}
+
+impl GlslShaderStage {
+    #[inline]
+    fn kind(&self) -> ShaderKind {
+        match *self {
+            GlslShaderStage::Vertex => ShaderKind::Vertex,
+            GlslShaderStage::TessellationControl => ShaderKind::TessControl,
+            GlslShaderStage::TessellationEvaluation => ShaderKind::TessEvaluation,
+            GlslShaderStage::Geometry => ShaderKind::Geometry,
+            GlslShaderStage::Fragment => ShaderKind::Fragment,
+            GlslShaderStage::Compute => ShaderKind::Compute,
+            GlslShaderStage::Task => ShaderKind::Task,
+            GlslShaderStage::Mesh => ShaderKind::Mesh,
+            GlslShaderStage::RayGeneration => ShaderKind::RayGeneration,
+            GlslShaderStage::AnyHit => ShaderKind::AnyHit,
+            GlslShaderStage::ClosestHit => ShaderKind::ClosestHit,
+            GlslShaderStage::Miss => ShaderKind::Miss,
+            GlslShaderStage::Intersection => ShaderKind::Intersection,
+            GlslShaderStage::Callable => ShaderKind::Callable,
+        }
+    }
+
+    // Returns the stage of the entry point of the compiled code.
+    fn stages(&self) -> ShaderStages {
+        let mut stages = ShaderStages::none();
+        match *self {
+            GlslShaderStage::Vertex => stages.vertex = true,
+            GlslShaderStage::TessellationControl => stages.tessellation_control = true,
+            GlslShaderStage::TessellationEvaluation => stages.tessellation_evaluation = true,
+            GlslShaderStage::Geometry => stages.geometry = true,
+            GlslShaderStage::Fragment => stages.fragment = true,
+            GlslShaderStage::Compute => stages.compute = true,
+            GlslShaderStage::Task => stages.task = true,
+            GlslShaderStage::Mesh => stages.mesh = true,
+            GlslShaderStage::RayGeneration => stages.raygen = true,
+            GlslShaderStage::AnyHit => stages.any_hit = true,
+            GlslShaderStage::ClosestHit => stages.closest_hit = true,
+            GlslShaderStage::Miss => stages.miss = true,
+            GlslShaderStage::Intersection => stages.intersection = true,
+            GlslShaderStage::Callable => stages.callable = true,
+        }
+        stages
+    }
+
+    // The GLSL extensions of the mesh shading and ray tracing stages require SPIR-V 1.4.
+    #[inline]
+    fn requires_spirv_1_4(&self) -> bool {
+        match *self {
+            GlslShaderStage::Vertex |
+            GlslShaderStage::TessellationControl |
+            GlslShaderStage::TessellationEvaluation |
+            GlslShaderStage::Geometry |
+            GlslShaderStage::Fragment |
+            GlslShaderStage::Compute => false,
+            _ => true,
+        }
+    }
+}
+
+/// Options for compiling GLSL source code.
+pub struct GlslCompileOptions<'a> {
+    file_name: String,
+    macros: Vec<(String, Option<String>)>,
+    include_callback: Option<Box<Fn(&str, IncludeType, &str, usize)
+                                    -> Result<ResolvedInclude, String> + 'a>>,
+}
+
+impl<'a> GlslCompileOptions<'a> {
+    /// Builds the default options, with no macro definition and no support for `#include`.
+    #[inline]
+    pub fn new() -> GlslCompileOptions<'a> {
+        GlslCompileOptions {
+            file_name: "shader.glsl".to_owned(),
+            macros: Vec::new(),
+            include_callback: None,
+        }
+    }
+
+    /// Sets the name of the source, used in error messages and passed to the include callback.
+    #[inline]
+    pub fn file_name<S>(mut self, name: S) -> GlslCompileOptions<'a>
+        where S: Into<String>
+    {
+        self.file_name = name.into();
+        self
+    }
+
+    /// Defines a preprocessor macro, as if `#define name value` was at the start of the source.
+    ///
+    /// If a macro with the same name was already defined, its value is replaced.
+    #[inline]
+    pub fn macro_definition(mut self, name: &str, value: Option<&str>) -> GlslCompileOptions<'a> {
+        self.macros.retain(|&(ref n, _)| n != name);
+        self.macros.push((name.to_owned(), value.map(|v| v.to_owned())));
+        self
+    }
+
+    /// Sets the function that resolves `#include` directives.
+    ///
+    /// The function is called with the requested name, the type of include (`"..."` or `<...>`),
+    /// the name of the source that contains the directive and the depth of the include. It
+    /// returns the resolved name and the content of the included source, or an error message.
+    #[inline]
+    pub fn include_callback<F>(mut self, callback: F) -> GlslCompileOptions<'a>
+        where F: Fn(&str, IncludeType, &str, usize) -> Result<ResolvedInclude, String> + 'a
+    {
+        self.include_callback = Some(Box::new(callback));
+        self
+    }
+}
+
+impl<'a> fmt::Debug for GlslCompileOptions<'a> {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        fmt.debug_struct("GlslCompileOptions")
+            .field("file_name", &self.file_name)
+            .field("macros", &self.macros)
+            .field("include_callback", &self.include_callback.is_some())
+            .finish()
+    }
+}
+
+impl ShaderModule {
+    /// Compiles GLSL source code and builds a new shader module from the result.
+    ///
+    /// Also returns the description of the `main` entry point of the module, which can be used
+    /// to pass the shader to a pipeline builder.
+    ///
+    /// Only available if the `shaderc` feature of vulkano is enabled.
+    ///
+    /// # Safety
+    ///
+    /// - The SPIR-V code may require some features that are not enabled. This isn't checked by
+    ///   this function.
+    ///
+    pub unsafe fn from_glsl_source(device: Arc<Device>, source: &str, stage: GlslShaderStage,
+                                   options: &GlslCompileOptions)
+                                   -> Result<(Arc<ShaderModule>, EntryPointInfo),
+                                             GlslCompilationError> {
+        let mut compiler = Compiler::new().ok_or(GlslCompilationError::CompilerUnavailable)?;
+        let mut shaderc_options = CompileOptions::new()
+            .ok_or(GlslCompilationError::CompilerUnavailable)?;
+
+        if stage.requires_spirv_1_4() {
+            shaderc_options.set_target_spirv(SpirvVersion::V1_4);
+        }
+
+        for &(ref name, ref value) in options.macros.iter() {
+            shaderc_options.add_macro_definition(name, value.as_ref().map(|v| &v[..]));
+        }
+
+        if let Some(ref callback) = options.include_callback {
+            shaderc_options.set_include_callback(move |name, ty, source, depth| {
+                                                     callback(name, ty, source, depth)
+                                                 });
+        }
+
+        let artifact = compiler
+            .compile_into_spirv(source,
+                                stage.kind(),
+                                &options.file_name,
+                                "main",
+                                Some(&shaderc_options))
+            .map_err(|err| GlslCompilationError::CompilationFailed(err.to_string()))?;
+
+        // Reflecting before creating the module avoids creating a module that can't be used.
+        let entry_point = main_entry_point(artifact.as_binary(), stage)?;
+        let module = ShaderModule::from_words(device, artifact.as_binary())?;
+        Ok((module, entry_point))
+    }
+}
+
+// Reflects the `main` entry point of code compiled for `stage`.
+fn main_entry_point(spirv: &[u32], stage: GlslShaderStage)
+                    -> Result<EntryPointInfo, GlslCompilationError> {
+    Ok(reflect::entry_point(spirv, "main", stage.stages())?)
+}
+
+/// Error that can happen when compiling GLSL source code.
+#[derive(Debug, Clone)]
+pub enum GlslCompilationError {
+    /// The shaderc compiler couldn't be initialized.
+    CompilerUnavailable,
+    /// The source code couldn't be compiled. Contains the messages of the compiler.
+    CompilationFailed(String),
+    /// The compiled code couldn't be reflected.
+    ReflectionError(ShaderReflectionError),
+    /// The compiled code doesn't contain a `main` entry point for the stage.
+    MissingEntryPoint(EntryPointLookupError),
+    /// The shader module couldn't be created from the compiled code.
+    ModuleCreationError(ShaderModuleCreationError),
+}
+
+impl error::Error for GlslCompilationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            GlslCompilationError::CompilerUnavailable => {
+                "the shaderc compiler couldn't be initialized"
+            },
+            GlslCompilationError::CompilationFailed(_) => "the source code couldn't be compiled",
+            GlslCompilationError::ReflectionError(_) => "the compiled code couldn't be reflected",
+            GlslCompilationError::MissingEntryPoint(_) => {
+                "the compiled code doesn't contain a `main` entry point for the stage"
+            },
+            GlslCompilationError::ModuleCreationError(_) => {
+                "the shader module couldn't be created from the compiled code"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            GlslCompilationError::ReflectionError(ref err) => Some(err),
+            GlslCompilationError::MissingEntryPoint(ref err) => Some(err),
+            GlslCompilationError::ModuleCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GlslCompilationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ShaderReflectionError> for GlslCompilationError {
+    #[inline]
+    fn from(err: ShaderReflectionError) -> GlslCompilationError {
+        GlslCompilationError::ReflectionError(err)
+    }
+}
+
+impl From<EntryPointLookupError> for GlslCompilationError {
+    #[inline]
+    fn from(err: EntryPointLookupError) -> GlslCompilationError {
+        match err {
+            EntryPointLookupError::ReflectionError(err) => {
+                GlslCompilationError::ReflectionError(err)
+            },
+            err => GlslCompilationError::MissingEntryPoint(err),
+        }
+    }
+}
+
+impl From<ShaderModuleCreationError> for GlslCompilationError {
+    #[inline]
+    fn from(err: ShaderModuleCreationError) -> GlslCompilationError {
+        GlslCompilationError::ModuleCreationError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pipeline::glsl::GlslCompilationError;
+    use pipeline::glsl::GlslCompileOptions;
+    use pipeline::glsl::GlslShaderStage;
+    use pipeline::glsl::main_entry_point;
+    use pipeline::reflect::EntryPointLookupError;
+    use spirv::*;
+    use spirv::tests::module;
+
+    #[test]
+    fn macro_definition_replaced() {
+        let options = GlslCompileOptions::new()
+            .macro_definition("SCALE", Some("0.5"))
+            .macro_definition("DEBUG", None)
+            .macro_definition("SCALE", Some("2.0"));
+
+        assert_eq!(options.macros,
+                   vec![("DEBUG".to_owned(), None), ("SCALE".to_owned(), Some("2.0".to_owned()))]);
+    }
+
+    #[test]
+    fn main_entry_point_selected() {
+        // `foo` is declared before `main`.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x006f6f66]),
+                             (OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 2, 0x6e69616d, 0])]);
+
+        let entry_point = main_entry_point(&words, GlslShaderStage::Vertex).unwrap();
+        assert_eq!(entry_point.name().to_str(), Ok("main"));
+        assert!(entry_point.stages().vertex);
+    }
+
+    #[test]
+    fn missing_main_entry_point() {
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x006f6f66])]);
+        match main_entry_point(&words, GlslShaderStage::Vertex) {
+            Err(GlslCompilationError::MissingEntryPoint(EntryPointLookupError::NotFound)) => (),
+            _ => panic!(),
+        }
+
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_FRAGMENT, 1, 0x6e69616d, 0])]);
+        match main_entry_point(&words, GlslShaderStage::Vertex) {
+            Err(GlslCompilationError::MissingEntryPoint(EntryPointLookupError::StageMismatch {
+                                                            ..
+                                                        })) => (),
+            _ => panic!(),
+        }
+    }
+}
@@ -13,41 +13,66 @@
 
 use smallvec::SmallVec;
 use std::mem;
+use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
 use std::u32;
 
+use descriptor::pipeline_layout::EmptyPipelineDesc;
 use descriptor::pipeline_layout::PipelineLayoutAbstract;
 use device::Device;
+use device::DeviceOwned;
 use framebuffer::RenderPassAbstract;
 use framebuffer::Subpass;
 use pipeline::blend::AttachmentBlend;
 use pipeline::blend::AttachmentsBlend;
 use pipeline::blend::Blend;
 use pipeline::blend::LogicOp;
+use pipeline::cache::PipelineCache;
+use pipeline::creation_feedback::FeedbackStorage;
+use pipeline::creation_feedback::PipelineCreationFeedback;
 use pipeline::depth_stencil::Compare;
 use pipeline::depth_stencil::DepthBounds;
 use pipeline::depth_stencil::DepthStencil;
+use pipeline::depth_stencil::Stencil;
+use pipeline::graphics_pipeline::ExtendedDynamicState;
 use pipeline::graphics_pipeline::GraphicsPipeline;
 use pipeline::graphics_pipeline::GraphicsPipelineCreationError;
+use pipeline::graphics_pipeline::GraphicsPipelineLibrary;
+use pipeline::graphics_pipeline::GraphicsPipelineLibraryParts;
+use pipeline::graphics_pipeline::library;
 use pipeline::graphics_pipeline::Inner as GraphicsPipelineInner;
 use pipeline::input_assembly::PrimitiveTopology;
+use pipeline::raster::ConservativeRasterization;
 use pipeline::raster::CullMode;
+use pipeline::raster::DepthBias;
 use pipeline::raster::DepthBiasControl;
 use pipeline::raster::FrontFace;
+use pipeline::raster::LineRasterizationMode;
+use pipeline::raster::LineStipple;
+use pipeline::raster::LineStippleControl;
 use pipeline::raster::PolygonMode;
 use pipeline::raster::Rasterization;
 use pipeline::shader::EmptyEntryPointDummy;
+use pipeline::shader::GeometryShaderExecutionMode;
 use pipeline::shader::GraphicsEntryPointAbstract;
 use pipeline::shader::GraphicsShaderType;
+use pipeline::shader::RuntimeShaderInterfaceDef;
+use pipeline::shader::ShaderInterfaceDef;
 use pipeline::shader::ShaderInterfaceDefMatch;
 use pipeline::shader::SpecializationConstants;
 use pipeline::vertex::BufferlessDefinition;
+use pipeline::vertex::InputRate;
 use pipeline::vertex::SingleBufferDefinition;
 use pipeline::vertex::VertexDefinition;
 use pipeline::viewport::Scissor;
 use pipeline::viewport::Viewport;
 use pipeline::viewport::ViewportsState;
+use spirv::EXECUTION_MODE_ISOLINES;
+use spirv::EXECUTION_MODE_POINT_MODE;
+use spirv::EXECUTION_MODE_QUADS;
+use spirv::EXECUTION_MODE_TRIANGLES;
+use spirv::Spirv;
 
 use VulkanObject;
 use check_errors;
@@ -59,7 +84,8 @@ use vk;
 
 /// Prototype for a `GraphicsPipeline`.
 // TODO: we can optimize this by filling directly the raw vk structs
-pub struct GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> {
+pub struct GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                                   Fs, Fss, Rp> {
     vertex_input: Vdef,
     vertex_shader: Option<(Vs, Vss)>,
     input_assembly: vk::PipelineInputAssemblyStateCreateInfo,
@@ -68,13 +94,18 @@ pub struct GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss,
     input_assembly_topology: PrimitiveTopology,
     tessellation: Option<TessInfo<Tcs, Tcss, Tes, Tess>>,
     geometry_shader: Option<(Gs, Gss)>,
+    task_shader: Option<(Ts, Tss)>,
+    mesh_shader: Option<(Ms, Mss)>,
     viewport: Option<ViewportsState>,
     raster: Rasterization,
     multisample: vk::PipelineMultisampleStateCreateInfo,
+    sample_mask: [u32; 2],
     fragment_shader: Option<(Fs, Fss)>,
     depth_stencil: DepthStencil,
     blend: Blend,
     render_pass: Option<Subpass<Rp>>,
+    cache: Option<Arc<PipelineCache>>,
+    extended_dynamic: ExtendedDynamicState,
 }
 
 // Additional parameters if tessellation is used.
@@ -96,6 +127,10 @@ impl
                             (),
                             EmptyEntryPointDummy,
                             (),
+                            EmptyEntryPointDummy,
+                            (),
+                            EmptyEntryPointDummy,
+                            (),
                             ()> {
     /// Builds a new empty builder.
     pub(super) fn new() -> Self {
@@ -111,46 +146,60 @@ impl
                 input_assembly_topology: PrimitiveTopology::TriangleList,
                 tessellation: None,
                 geometry_shader: None,
+                task_shader: None,
+                mesh_shader: None,
                 viewport: None,
                 raster: Default::default(),
                 multisample: vk::PipelineMultisampleStateCreateInfo {
                     sType: vk::STRUCTURE_TYPE_PIPELINE_MULTISAMPLE_STATE_CREATE_INFO,
                     ..mem::zeroed()
                 },
+                sample_mask: [0xffffffff; 2],
                 fragment_shader: None,
                 depth_stencil: DepthStencil::disabled(),
                 blend: Blend::pass_through(),
                 render_pass: None,
+                cache: None,
+                extended_dynamic: Default::default(),
             }
         }
     }
 }
 
-impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
-    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
+impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss, Fs, Fss, Rp>
+    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                            Fs, Fss, Rp>
     where Vdef: VertexDefinition<Vs::InputDefinition>,
           Vs: GraphicsEntryPointAbstract,
           Fs: GraphicsEntryPointAbstract,
           Gs: GraphicsEntryPointAbstract,
           Tcs: GraphicsEntryPointAbstract,
           Tes: GraphicsEntryPointAbstract,
+          Ts: GraphicsEntryPointAbstract,
+          Ms: GraphicsEntryPointAbstract,
           Vss: SpecializationConstants,
           Tcss: SpecializationConstants,
           Tess: SpecializationConstants,
           Gss: SpecializationConstants,
+          Tss: SpecializationConstants,
+          Mss: SpecializationConstants,
           Fss: SpecializationConstants,
           Vs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
           Fs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
           Tcs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
           Tes::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
           Gs::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
+          Ts::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
+          Ms::PipelineLayout: Clone + 'static + Send + Sync, // TODO: shouldn't be required
           Tcs::InputDefinition: ShaderInterfaceDefMatch<Vs::OutputDefinition>,
           Tes::InputDefinition: ShaderInterfaceDefMatch<Tcs::OutputDefinition>,
           Gs::InputDefinition: ShaderInterfaceDefMatch<Tes::OutputDefinition>
                                    + ShaderInterfaceDefMatch<Vs::OutputDefinition>,
+          Ms::InputDefinition: ShaderInterfaceDefMatch<Ts::OutputDefinition>,
           Fs::InputDefinition: ShaderInterfaceDefMatch<Gs::OutputDefinition>
                                    + ShaderInterfaceDefMatch<Tes::OutputDefinition>
-                                   + ShaderInterfaceDefMatch<Vs::OutputDefinition>,
+                                   + ShaderInterfaceDefMatch<Vs::OutputDefinition>
+                                   + ShaderInterfaceDefMatch<Ms::OutputDefinition>,
           Rp: RenderPassAbstract + RenderPassSubpassInterface<Fs::OutputDefinition>
 {
     /// Builds the graphics pipeline, using an inferred a pipeline layout.
@@ -169,40 +218,70 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                             -> Result<GraphicsPipeline<Vdef, Box<PipelineLayoutAbstract + Send + Sync>, Rp>,
                                       GraphicsPipelineCreationError>
     {
+        let pipeline_layout = self.auto_layout(&device, dynamic_buffers)?;
+        self.with_pipeline_layout(device, pipeline_layout)
+    }
+
+    /// Builds multiple graphics pipelines at once, using an inferred pipeline layout for each of
+    /// them.
+    ///
+    /// All the pipelines are created with a single call to `vkCreateGraphicsPipelines`, which is
+    /// usually faster than creating them one by one. The pipelines are returned in the same order
+    /// as the builders.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the builders don't all use the same pipeline cache.
+    ///
+    pub fn build_many<I>(device: Arc<Device>, builders: I)
+                         -> Result<Vec<GraphicsPipeline<Vdef, Box<PipelineLayoutAbstract + Send + Sync>, Rp>>,
+                                   GraphicsPipelineCreationError>
+        where I: IntoIterator<Item = Self>
+    {
+        let builders = builders
+            .into_iter()
+            .map(|builder| {
+                let layout = builder.auto_layout(&device, &[])?;
+                Ok((builder, layout))
+            })
+            .collect::<Result<Vec<_>, GraphicsPipelineCreationError>>()?;
+
+        GraphicsPipelineBuilder::with_pipeline_layout_many(device, builders)
+    }
+
+    // Builds the pipeline layout corresponding to the union of the layouts of the shaders.
+    fn auto_layout(&self, device: &Arc<Device>, dynamic_buffers: &[(usize, usize)])
+                   -> Result<Box<PipelineLayoutAbstract + Send + Sync>,
+                             GraphicsPipelineCreationError>
+    {
+        Self::check_stage_interfaces(self.vertex_shader.as_ref(),
+                                     self.tessellation.as_ref(),
+                                     self.geometry_shader.as_ref(),
+                                     self.task_shader.as_ref(),
+                                     self.mesh_shader.as_ref(),
+                                     self.fragment_shader.as_ref())?;
+
         let pipeline_layout;
 
-        if let Some(ref tess) = self.tessellation {
-            if let Some(ref gs) = self.geometry_shader {
-                if let Err(err) = tess.tessellation_control_shader
-                    .0
-                    .input()
-                    .matches(self.vertex_shader.as_ref().unwrap().0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::VertexTessControlStagesMismatch(err));
-                }
-                if let Err(err) = tess.tessellation_evaluation_shader
-                    .0
-                    .input()
-                    .matches(tess.tessellation_control_shader.0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::TessControlTessEvalStagesMismatch(err));
-                }
-                if let Err(err) = gs.0
-                    .input()
-                    .matches(tess.tessellation_evaluation_shader.0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::TessEvalGeometryStagesMismatch(err));
-                }
-                if let Err(err) = self.fragment_shader
-                    .as_ref()
-                    .unwrap()
-                    .0
-                    .input()
-                    .matches(gs.0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::GeometryFragmentStagesMismatch(err));
-                }
+        if let Some(ref ms) = self.mesh_shader {
+            if let Some(ref ts) = self.task_shader {
+                pipeline_layout = Box::new(
+                    PipelineLayoutDescTweaks::new(ms.0.layout().clone()
+                          .union(self.fragment_shader.as_ref().unwrap().0.layout().clone())
+                          .union(ts.0.layout().clone()),
+                          dynamic_buffers.into_iter().cloned()
+                    ).build(device.clone()).unwrap()) as Box<_>; // TODO: error
+
+            } else {
+                pipeline_layout = Box::new(
+                    PipelineLayoutDescTweaks::new(ms.0.layout().clone()
+                          .union(self.fragment_shader.as_ref().unwrap().0.layout().clone()),
+                          dynamic_buffers.into_iter().cloned()
+                    ).build(device.clone()).unwrap()) as Box<_>; // TODO: error
+            }
 
+        } else if self.tessellation.is_some() {
+            if self.geometry_shader.is_some() {
                 pipeline_layout = Box::new(
                     PipelineLayoutDescTweaks::new(self.vertex_shader.as_ref().unwrap().0.layout().clone()
                           .union(self.fragment_shader.as_ref().unwrap().0.layout().clone())
@@ -213,30 +292,6 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                     ).build(device.clone()).unwrap()) as Box<_>; // TODO: error
 
             } else {
-                if let Err(err) = tess.tessellation_control_shader
-                    .0
-                    .input()
-                    .matches(self.vertex_shader.as_ref().unwrap().0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::VertexTessControlStagesMismatch(err));
-                }
-                if let Err(err) = tess.tessellation_evaluation_shader
-                    .0
-                    .input()
-                    .matches(tess.tessellation_control_shader.0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::TessControlTessEvalStagesMismatch(err));
-                }
-                if let Err(err) = self.fragment_shader
-                    .as_ref()
-                    .unwrap()
-                    .0
-                    .input()
-                    .matches(tess.tessellation_evaluation_shader.0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::TessEvalFragmentStagesMismatch(err));
-                }
-
                 pipeline_layout = Box::new(
                     PipelineLayoutDescTweaks::new(self.vertex_shader.as_ref().unwrap().0.layout().clone()
                           .union(self.fragment_shader.as_ref().unwrap().0.layout().clone())
@@ -247,24 +302,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             }
 
         } else {
-            if let Some(ref geometry_shader) = self.geometry_shader {
-                if let Err(err) = geometry_shader
-                    .0
-                    .input()
-                    .matches(self.vertex_shader.as_ref().unwrap().0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::VertexGeometryStagesMismatch(err));
-                }
-                if let Err(err) = self.fragment_shader
-                    .as_ref()
-                    .unwrap()
-                    .0
-                    .input()
-                    .matches(geometry_shader.0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::GeometryFragmentStagesMismatch(err));
-                }
-
+            if self.geometry_shader.is_some() {
                 pipeline_layout = Box::new(
                     PipelineLayoutDescTweaks::new(self.vertex_shader.as_ref().unwrap().0.layout().clone()
                           .union(self.fragment_shader.as_ref().unwrap().0.layout().clone())
@@ -273,16 +311,6 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                     ).build(device.clone()).unwrap()) as Box<_>; // TODO: error
 
             } else {
-                if let Err(err) = self.fragment_shader
-                    .as_ref()
-                    .unwrap()
-                    .0
-                    .input()
-                    .matches(self.vertex_shader.as_ref().unwrap().0.output())
-                {
-                    return Err(GraphicsPipelineCreationError::VertexFragmentStagesMismatch(err));
-                }
-
                 pipeline_layout =
                     Box::new(
                         PipelineLayoutDescTweaks::new(self.vertex_shader
@@ -297,7 +325,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             }
         }
 
-        self.with_pipeline_layout(device, pipeline_layout)
+        Ok(pipeline_layout)
     }
 
     /// Builds the graphics pipeline.
@@ -310,72 +338,396 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                               GraphicsPipelineCreationError>
         where Pl: PipelineLayoutAbstract
     {
-        // TODO: return errors instead of panicking if missing param
+        let vk = device.pointers();
+        let cache = self.raw_cache(&device);
+
+        let mut pipeline = 0;
+        let feedback = unsafe {
+            let (infos, storage) = self.create_info(&device, &pipeline_layout, None)?;
+            let _span = instrument_span!("vkCreateGraphicsPipelines",
+                                         cache = ::instrument::pipeline_cache_name(&device, cache));
+            check_errors(vk.CreateGraphicsPipelines(device.internal_object(),
+                                                    cache,
+                                                    1,
+                                                    &infos,
+                                                    device.allocator_ptr(),
+                                                    &mut pipeline))?;
+            storage.into_feedback()
+        };
+
+        Ok(self.into_pipeline(device, pipeline_layout, pipeline, feedback))
+    }
+
+    /// Builds a graphics pipeline library that only contains the given parts of the pipeline,
+    /// using an inferred pipeline layout.
+    ///
+    /// The shaders and the states of the builder that don't belong to `parts` are ignored, and
+    /// don't need to be set. The vertex input interface is built from the input of the vertex
+    /// shader, and the subpass is needed by all the parts except the vertex input interface. The
+    /// libraries can then be linked into a complete pipeline with `GraphicsPipeline::link`.
+    ///
+    /// The inferred pipeline layout is the union of the layouts of all the shaders that are set,
+    /// including the ones that don't belong to `parts`. Libraries built from builders with the
+    /// same shaders therefore have the same layout.
+    ///
+    /// Requires the `VK_EXT_graphics_pipeline_library` extension and its
+    /// `graphics_pipeline_library` feature.
+    pub fn build_library(self, device: Arc<Device>, parts: GraphicsPipelineLibraryParts)
+                         -> Result<GraphicsPipelineLibrary<Vdef, Box<PipelineLayoutAbstract + Send + Sync>, Rp>,
+                                   GraphicsPipelineCreationError> {
+        let pipeline_layout = self.library_auto_layout(&device);
+        self.library_with_pipeline_layout(device, pipeline_layout, parts)
+    }
+
+    // Builds the pipeline layout corresponding to the union of the layouts of the shaders that
+    // are set.
+    fn library_auto_layout(&self, device: &Arc<Device>)
+                           -> Box<PipelineLayoutAbstract + Send + Sync> {
+        let mut layout: Box<PipelineLayoutDesc + Send + Sync> = Box::new(EmptyPipelineDesc);
+        if let Some(ref vs) = self.vertex_shader {
+            layout = Box::new(layout.union(vs.0.layout().clone()));
+        }
+        if let Some(ref tess) = self.tessellation {
+            layout = Box::new(layout
+                                  .union(tess.tessellation_control_shader.0.layout().clone())
+                                  .union(tess.tessellation_evaluation_shader.0.layout().clone()));
+        }
+        if let Some(ref gs) = self.geometry_shader {
+            layout = Box::new(layout.union(gs.0.layout().clone()));
+        }
+        if let Some(ref ts) = self.task_shader {
+            layout = Box::new(layout.union(ts.0.layout().clone()));
+        }
+        if let Some(ref ms) = self.mesh_shader {
+            layout = Box::new(layout.union(ms.0.layout().clone()));
+        }
+        if let Some(ref fs) = self.fragment_shader {
+            layout = Box::new(layout.union(fs.0.layout().clone()));
+        }
+
+        Box::new(layout.build(device.clone()).unwrap()) // TODO: error
+    }
 
+    /// Builds a graphics pipeline library that only contains the given parts of the pipeline.
+    ///
+    /// Does the same as `build_library`, except that this function allows you to specify the
+    /// pipeline layout.
+    pub fn library_with_pipeline_layout<Pl>(mut self, device: Arc<Device>, pipeline_layout: Pl,
+                                            parts: GraphicsPipelineLibraryParts)
+                                            -> Result<GraphicsPipelineLibrary<Vdef, Pl, Rp>,
+                                                      GraphicsPipelineCreationError>
+        where Pl: PipelineLayoutAbstract
+    {
         let vk = device.pointers();
+        let cache = self.raw_cache(&device);
+
+        let mut pipeline = 0;
+        let feedback = unsafe {
+            let (infos, storage) = self.create_info(&device, &pipeline_layout, Some(parts))?;
+            let _span = instrument_span!("vkCreateGraphicsPipelines",
+                                         cache = ::instrument::pipeline_cache_name(&device, cache));
+            check_errors(vk.CreateGraphicsPipelines(device.internal_object(),
+                                                    cache,
+                                                    1,
+                                                    &infos,
+                                                    device.allocator_ptr(),
+                                                    &mut pipeline))?;
+            storage.into_feedback()
+        };
+
+        Ok(self.into_library(device, pipeline_layout, pipeline, feedback, parts))
+    }
+
+    /// Builds multiple graphics pipelines at once, each with its own pipeline layout.
+    ///
+    /// Does the same as `build_many`, except that `build_many` automatically builds the pipeline
+    /// layouts while this function allows you to specify them.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the builders don't all use the same pipeline cache.
+    ///
+    pub fn with_pipeline_layout_many<Pl, I>(device: Arc<Device>, builders: I)
+                                            -> Result<Vec<GraphicsPipeline<Vdef, Pl, Rp>>,
+                                                      GraphicsPipelineCreationError>
+        where Pl: PipelineLayoutAbstract,
+              I: IntoIterator<Item = (Self, Pl)>
+    {
+        let mut builders = builders.into_iter().collect::<Vec<_>>();
+        if builders.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let cache = builders[0].0.raw_cache(&device);
+        for &(ref builder, _) in builders.iter() {
+            assert_eq!(builder.raw_cache(&device), cache,
+                       "all the pipelines of a batch must use the same pipeline cache");
+        }
+
+        // The create infos point to the builders, which therefore must not be moved until the
+        // pipelines are created.
+        let mut infos = Vec::with_capacity(builders.len());
+        let mut storages = Vec::with_capacity(builders.len());
+        for &mut (ref mut builder, ref layout) in builders.iter_mut() {
+            let (info, storage) = unsafe { builder.create_info(&device, layout, None)? };
+            infos.push(info);
+            storages.push(storage);
+        }
+
+        let mut pipelines = vec![0; infos.len()];
+        unsafe {
+            let vk = device.pointers();
+            let _span = instrument_span!("vkCreateGraphicsPipelines",
+                                         cache = ::instrument::pipeline_cache_name(&device, cache),
+                                         count = infos.len());
+            let result = check_errors(vk.CreateGraphicsPipelines(device.internal_object(),
+                                                                 cache,
+                                                                 infos.len() as u32,
+                                                                 infos.as_ptr(),
+                                                                 device.allocator_ptr(),
+                                                                 pipelines.as_mut_ptr()));
+
+            if let Err(err) = result {
+                // Some pipelines may have been successfully created.
+                for &pipeline in pipelines.iter() {
+                    if pipeline != 0 {
+                        vk.DestroyPipeline(device.internal_object(),
+                                           pipeline,
+                                           device.allocator_ptr());
+                    }
+                }
+                return Err(err.into());
+            }
+        }
+
+        Ok(builders
+               .into_iter()
+               .zip(pipelines)
+               .zip(storages)
+               .map(|(((builder, layout), pipeline), storage)| {
+                        builder.into_pipeline(device.clone(), layout, pipeline,
+                                              storage.into_feedback())
+                    })
+               .collect())
+    }
+
+    // Checks the interfaces between the given shader stages with `check_stage_interfaces`.
+    fn check_stage_interfaces(vertex_shader: Option<&(Vs, Vss)>,
+                              tessellation: Option<&TessInfo<Tcs, Tcss, Tes, Tess>>,
+                              geometry_shader: Option<&(Gs, Gss)>,
+                              task_shader: Option<&(Ts, Tss)>,
+                              mesh_shader: Option<&(Ms, Mss)>,
+                              fragment_shader: Option<&(Fs, Fss)>)
+                              -> Result<(), GraphicsPipelineCreationError> {
+        let tessellation_control =
+            tessellation.map(|tess| StageInterfaces::of(&tess.tessellation_control_shader.0));
+        let tessellation_evaluation =
+            tessellation.map(|tess| StageInterfaces::of(&tess.tessellation_evaluation_shader.0));
+
+        check_stage_interfaces(vertex_shader.map(|vs| StageInterfaces::of(&vs.0)).as_ref(),
+                               tessellation_control.as_ref(),
+                               tessellation_evaluation.as_ref(),
+                               geometry_shader.map(|gs| StageInterfaces::of(&gs.0)).as_ref(),
+                               task_shader.map(|ts| StageInterfaces::of(&ts.0)).as_ref(),
+                               mesh_shader.map(|ms| StageInterfaces::of(&ms.0)).as_ref(),
+                               fragment_shader.map(|fs| StageInterfaces::of(&fs.0)).as_ref())
+    }
+
+    // Returns the raw pipeline cache to use when creating the pipeline.
+    fn raw_cache(&self, device: &Arc<Device>) -> vk::PipelineCache {
+        match self.cache {
+            Some(ref cache) => {
+                assert_eq!(cache.device().internal_object(), device.internal_object());
+                cache.internal_object()
+            },
+            None => 0,
+        }
+    }
+
+    // Checks the builder and returns the `vk::GraphicsPipelineCreateInfo` that describes the
+    // pipeline, along with the data it points to. The create info is only valid as long as the
+    // storage and the builder are alive and the builder isn't moved.
+    //
+    // If `library` is `Some`, the create info describes a graphics pipeline library that only
+    // contains the given parts of the pipeline.
+    unsafe fn create_info<Pl>(&mut self, device: &Arc<Device>, pipeline_layout: &Pl,
+                              library: Option<GraphicsPipelineLibraryParts>)
+                              -> Result<(vk::GraphicsPipelineCreateInfo, Box<CreateInfoStorage>),
+                                        GraphicsPipelineCreationError>
+        where Pl: PipelineLayoutAbstract
+    {
+        // TODO: return errors instead of panicking if missing param
+
+        if let Some(parts) = library {
+            library::check_support(device)?;
+            if parts == GraphicsPipelineLibraryParts::none() {
+                return Err(GraphicsPipelineCreationError::EmptyGraphicsPipelineLibrary);
+            }
+        }
+
+        // The shaders and the states that don't belong to the parts of the pipeline described by
+        // the create info are ignored, and don't need to be set.
+        let parts = library.unwrap_or(GraphicsPipelineLibraryParts::all());
+        let (vertex_shader, tessellation_shaders, geometry_shader, task_shader, mesh_shader) =
+            if parts.pre_rasterization_shaders {
+                (self.vertex_shader.as_ref(),
+                 self.tessellation.as_ref(),
+                 self.geometry_shader.as_ref(),
+                 self.task_shader.as_ref(),
+                 self.mesh_shader.as_ref())
+            } else {
+                (None, None, None, None, None)
+            };
+        let fragment_shader = if parts.fragment_shader {
+            Some(self.fragment_shader.as_ref().unwrap())
+        } else {
+            None
+        };
+        let viewport = if parts.pre_rasterization_shaders {
+            Some(self.viewport.as_ref().unwrap())
+        } else {
+            None
+        };
+        // All the parts except the vertex input interface depend on the subpass.
+        let subpass = if parts.pre_rasterization_shaders || parts.fragment_shader ||
+            parts.fragment_output_interface
+        {
+            Some(self.render_pass.as_ref().unwrap())
+        } else {
+            None
+        };
+
+        // Mesh shading replaces the vertex, tessellation and geometry stages.
+        if let Some(ms) = mesh_shader {
+            if !device.loaded_extensions().ext_mesh_shader {
+                return Err(GraphicsPipelineCreationError::MeshShaderExtensionNotEnabled);
+            }
+            if !device.enabled_features().mesh_shader {
+                return Err(GraphicsPipelineCreationError::MeshShaderFeatureNotEnabled);
+            }
+            if vertex_shader.is_some() || tessellation_shaders.is_some() ||
+                geometry_shader.is_some()
+            {
+                return Err(GraphicsPipelineCreationError::MeshShaderWithVertexStages);
+            }
+
+            match ms.0.ty() {
+                GraphicsShaderType::Mesh => {},
+                _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
+            };
+
+            if let Some(ts) = task_shader {
+                if !device.enabled_features().task_shader {
+                    return Err(GraphicsPipelineCreationError::TaskShaderFeatureNotEnabled);
+                }
+
+                match ts.0.ty() {
+                    GraphicsShaderType::Task => {},
+                    _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
+                };
+            }
+
+        } else if task_shader.is_some() {
+            return Err(GraphicsPipelineCreationError::TaskShaderWithoutMeshShader);
+        }
 
         // Checking that the pipeline layout matches the shader stages.
         // TODO: more details in the errors
-        PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout,
-                                                   self.vertex_shader
-                                                       .as_ref()
-                                                       .unwrap()
-                                                       .0
-                                                       .layout())?;
-        PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout,
-                                                   self.fragment_shader
-                                                       .as_ref()
-                                                       .unwrap()
-                                                       .0
-                                                       .layout())?;
-        if let Some(ref geometry_shader) = self.geometry_shader {
-            PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout,
-                                                       geometry_shader.0.layout())?;
+        if let Some(vs) = vertex_shader {
+            PipelineLayoutSuperset::ensure_superset_of(pipeline_layout, vs.0.layout())?;
         }
-        if let Some(ref tess) = self.tessellation {
-            PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout,
+        if let Some(fs) = fragment_shader {
+            PipelineLayoutSuperset::ensure_superset_of(pipeline_layout, fs.0.layout())?;
+        }
+        if let Some(gs) = geometry_shader {
+            PipelineLayoutSuperset::ensure_superset_of(pipeline_layout, gs.0.layout())?;
+        }
+        if let Some(tess) = tessellation_shaders {
+            PipelineLayoutSuperset::ensure_superset_of(pipeline_layout,
                                                        tess.tessellation_control_shader
                                                            .0
                                                            .layout())?;
-            PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout,
+            PipelineLayoutSuperset::ensure_superset_of(pipeline_layout,
                                                        tess.tessellation_evaluation_shader
                                                            .0
                                                            .layout())?;
         }
+        if let Some(ts) = task_shader {
+            PipelineLayoutSuperset::ensure_superset_of(pipeline_layout, ts.0.layout())?;
+        }
+        if let Some(ms) = mesh_shader {
+            PipelineLayoutSuperset::ensure_superset_of(pipeline_layout, ms.0.layout())?;
+        }
 
         // Check that the subpass can accept the output of the fragment shader.
-        if !RenderPassSubpassInterface::is_compatible_with(&self.render_pass
-                                                               .as_ref()
-                                                               .unwrap()
-                                                               .render_pass(),
-                                                           self.render_pass
-                                                               .as_ref()
-                                                               .unwrap()
-                                                               .index(),
-                                                           self.fragment_shader
-                                                               .as_ref()
-                                                               .unwrap()
-                                                               .0
-                                                               .output())
-        {
-            return Err(GraphicsPipelineCreationError::FragmentShaderRenderPassIncompatible);
+        if let (Some(fs), Some(subpass)) = (fragment_shader, subpass) {
+            if !RenderPassSubpassInterface::is_compatible_with(&subpass.render_pass(),
+                                                               subpass.index(),
+                                                               fs.0.output())
+            {
+                return Err(GraphicsPipelineCreationError::FragmentShaderRenderPassIncompatible);
+            }
         }
 
         // Will contain the list of dynamic states. Filled throughout this function.
         let mut dynamic_states: SmallVec<[vk::DynamicState; 8]> = SmallVec::new();
 
+        if self.extended_dynamic.any() {
+            if !device.loaded_extensions().ext_extended_dynamic_state {
+                return Err(GraphicsPipelineCreationError::ExtendedDynamicStateExtensionNotEnabled);
+            }
+            if !device.enabled_features().extended_dynamic_state {
+                return Err(GraphicsPipelineCreationError::ExtendedDynamicStateFeatureNotEnabled);
+            }
+
+            if self.extended_dynamic.primitive_topology {
+                dynamic_states.push(vk::DYNAMIC_STATE_PRIMITIVE_TOPOLOGY_EXT);
+            }
+            if self.extended_dynamic.cull_mode {
+                dynamic_states.push(vk::DYNAMIC_STATE_CULL_MODE_EXT);
+            }
+            if self.extended_dynamic.front_face {
+                dynamic_states.push(vk::DYNAMIC_STATE_FRONT_FACE_EXT);
+            }
+            if self.extended_dynamic.depth_test_enable {
+                dynamic_states.push(vk::DYNAMIC_STATE_DEPTH_TEST_ENABLE_EXT);
+            }
+            if self.extended_dynamic.depth_write_enable {
+                dynamic_states.push(vk::DYNAMIC_STATE_DEPTH_WRITE_ENABLE_EXT);
+            }
+            if self.extended_dynamic.depth_compare_op {
+                dynamic_states.push(vk::DYNAMIC_STATE_DEPTH_COMPARE_OP_EXT);
+            }
+            if self.extended_dynamic.depth_bounds_test_enable {
+                dynamic_states.push(vk::DYNAMIC_STATE_DEPTH_BOUNDS_TEST_ENABLE_EXT);
+            }
+            if self.extended_dynamic.stencil_test_enable {
+                dynamic_states.push(vk::DYNAMIC_STATE_STENCIL_TEST_ENABLE_EXT);
+            }
+            if self.extended_dynamic.stencil_op {
+                dynamic_states.push(vk::DYNAMIC_STATE_STENCIL_OP_EXT);
+            }
+            if self.extended_dynamic.vertex_input_binding_stride {
+                dynamic_states.push(vk::DYNAMIC_STATE_VERTEX_INPUT_BINDING_STRIDE_EXT);
+            }
+        }
+
+        let mut storage = Box::new(CreateInfoStorage::default());
+
         // Creating the specialization constants of the various stages.
-        let vertex_shader_specialization = {
+        storage.vertex_shader_specialization = if let Some(vs) = vertex_shader {
             let spec_descriptors = Vss::descriptors();
-            let constants = &self.vertex_shader.as_ref().unwrap().1;
-            vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(constants),
-                pData: constants as *const Vss as *const _,
-            }
+            let constants = &vs.1;
+            Some(vk::SpecializationInfo {
+                     mapEntryCount: spec_descriptors.len() as u32,
+                     pMapEntries: spec_descriptors.as_ptr() as *const _,
+                     dataSize: mem::size_of_val(constants),
+                     pData: constants as *const Vss as *const _,
+                 })
+        } else {
+            None
         };
-        let tess_shader_specialization = if let Some(ref tess) = self.tessellation {
+        storage.tess_shader_specialization = if let Some(tess) = tessellation_shaders {
             let tcs_spec = {
                 let spec_descriptors = Tcss::descriptors();
                 let constants = &tess.tessellation_control_shader.1;
@@ -400,7 +752,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         } else {
             None
         };
-        let geometry_shader_specialization = if let Some(ref gs) = self.geometry_shader {
+        storage.geometry_shader_specialization = if let Some(gs) = geometry_shader {
             let spec_descriptors = Gss::descriptors();
             let constants = &gs.1;
             Some(vk::SpecializationInfo {
@@ -412,62 +764,118 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         } else {
             None
         };
-        let fragment_shader_specialization = {
+        storage.task_shader_specialization = if let Some(ts) = task_shader {
+            let spec_descriptors = Tss::descriptors();
+            let constants = &ts.1;
+            Some(vk::SpecializationInfo {
+                     mapEntryCount: spec_descriptors.len() as u32,
+                     pMapEntries: spec_descriptors.as_ptr() as *const _,
+                     dataSize: mem::size_of_val(constants),
+                     pData: constants as *const Tss as *const _,
+                 })
+        } else {
+            None
+        };
+        storage.mesh_shader_specialization = if let Some(ms) = mesh_shader {
+            let spec_descriptors = Mss::descriptors();
+            let constants = &ms.1;
+            Some(vk::SpecializationInfo {
+                     mapEntryCount: spec_descriptors.len() as u32,
+                     pMapEntries: spec_descriptors.as_ptr() as *const _,
+                     dataSize: mem::size_of_val(constants),
+                     pData: constants as *const Mss as *const _,
+                 })
+        } else {
+            None
+        };
+        storage.fragment_shader_specialization = if let Some(fs) = fragment_shader {
             let spec_descriptors = Fss::descriptors();
-            let constants = &self.fragment_shader.as_ref().unwrap().1;
-            vk::SpecializationInfo {
-                mapEntryCount: spec_descriptors.len() as u32,
-                pMapEntries: spec_descriptors.as_ptr() as *const _,
-                dataSize: mem::size_of_val(constants),
-                pData: constants as *const Fss as *const _,
-            }
+            let constants = &fs.1;
+            Some(vk::SpecializationInfo {
+                     mapEntryCount: spec_descriptors.len() as u32,
+                     pMapEntries: spec_descriptors.as_ptr() as *const _,
+                     dataSize: mem::size_of_val(constants),
+                     pData: constants as *const Fss as *const _,
+                 })
+        } else {
+            None
         };
 
-        // List of shader stages.
-        let stages = {
-            let mut stages = SmallVec::<[_; 5]>::new();
+        // List of shader stages. A library only contains the shader stages of its parts.
+        storage.stages = {
+            let mut stages = SmallVec::new();
+
+            if let Some(ms) = mesh_shader {
+                if let Some(ts) = task_shader {
+                    stages.push(vk::PipelineShaderStageCreateInfo {
+                                    sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+                                    pNext: ptr::null(),
+                                    flags: 0, // reserved
+                                    stage: vk::SHADER_STAGE_TASK_BIT_EXT,
+                                    module: ts.0.module().internal_object(),
+                                    pName: ts.0.name().as_ptr(),
+                                    pSpecializationInfo: storage.task_shader_specialization
+                                        .as_ref()
+                                        .unwrap() as
+                                        *const _,
+                                });
+                }
 
-            match self.vertex_shader.as_ref().unwrap().0.ty() {
-                GraphicsShaderType::Vertex => {},
-                _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
-            };
+                stages.push(vk::PipelineShaderStageCreateInfo {
+                                sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+                                pNext: ptr::null(),
+                                flags: 0, // reserved
+                                stage: vk::SHADER_STAGE_MESH_BIT_EXT,
+                                module: ms.0.module().internal_object(),
+                                pName: ms.0.name().as_ptr(),
+                                pSpecializationInfo: storage.mesh_shader_specialization
+                                    .as_ref()
+                                    .unwrap() as
+                                    *const _,
+                            });
 
-            stages.push(vk::PipelineShaderStageCreateInfo {
-                            sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
-                            pNext: ptr::null(),
-                            flags: 0, // reserved
-                            stage: vk::SHADER_STAGE_VERTEX_BIT,
-                            module: self.vertex_shader
-                                .as_ref()
-                                .unwrap()
-                                .0
-                                .module()
-                                .internal_object(),
-                            pName: self.vertex_shader.as_ref().unwrap().0.name().as_ptr(),
-                            pSpecializationInfo: &vertex_shader_specialization as *const _,
-                        });
-
-            match self.fragment_shader.as_ref().unwrap().0.ty() {
-                GraphicsShaderType::Fragment => {},
-                _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
-            };
+            } else if parts.pre_rasterization_shaders {
+                let vs = self.vertex_shader.as_ref().unwrap();
+                match vs.0.ty() {
+                    GraphicsShaderType::Vertex => {},
+                    _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
+                };
+
+                stages.push(vk::PipelineShaderStageCreateInfo {
+                                sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+                                pNext: ptr::null(),
+                                flags: 0, // reserved
+                                stage: vk::SHADER_STAGE_VERTEX_BIT,
+                                module: vs.0.module().internal_object(),
+                                pName: vs.0.name().as_ptr(),
+                                pSpecializationInfo: storage.vertex_shader_specialization
+                                    .as_ref()
+                                    .unwrap() as
+                                    *const _,
+                            });
+            }
+
+            if let Some(fs) = fragment_shader {
+                match fs.0.ty() {
+                    GraphicsShaderType::Fragment => {},
+                    _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
+                };
+
+                stages.push(vk::PipelineShaderStageCreateInfo {
+                                sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+                                pNext: ptr::null(),
+                                flags: 0, // reserved
+                                stage: vk::SHADER_STAGE_FRAGMENT_BIT,
+                                module: fs.0.module().internal_object(),
+                                pName: fs.0.name().as_ptr(),
+                                pSpecializationInfo: storage.fragment_shader_specialization
+                                    .as_ref()
+                                    .unwrap() as
+                                    *const _,
+                            });
+            }
 
-            stages.push(vk::PipelineShaderStageCreateInfo {
-                            sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
-                            pNext: ptr::null(),
-                            flags: 0, // reserved
-                            stage: vk::SHADER_STAGE_FRAGMENT_BIT,
-                            module: self.fragment_shader
-                                .as_ref()
-                                .unwrap()
-                                .0
-                                .module()
-                                .internal_object(),
-                            pName: self.fragment_shader.as_ref().unwrap().0.name().as_ptr(),
-                            pSpecializationInfo: &fragment_shader_specialization as *const _,
-                        });
-
-            if let Some(ref gs) = self.geometry_shader {
+            if let Some(gs) = geometry_shader {
                 if !device.enabled_features().geometry_shader {
                     return Err(GraphicsPipelineCreationError::GeometryShaderFeatureNotEnabled);
                 }
@@ -479,16 +887,14 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                 stage: vk::SHADER_STAGE_GEOMETRY_BIT,
                                 module: gs.0.module().internal_object(),
                                 pName: gs.0.name().as_ptr(),
-                                pSpecializationInfo: geometry_shader_specialization
+                                pSpecializationInfo: storage.geometry_shader_specialization
                                     .as_ref()
                                     .unwrap() as
                                     *const _,
                             });
             }
 
-            if let Some(ref tess) = self.tessellation {
-                // FIXME: must check that the control shader and evaluation shader are compatible
-
+            if let Some(tess) = tessellation_shaders {
                 if !device.enabled_features().tessellation_shader {
                     return Err(GraphicsPipelineCreationError::TessellationShaderFeatureNotEnabled);
                 }
@@ -499,7 +905,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 };
 
                 match tess.tessellation_evaluation_shader.0.ty() {
-                    GraphicsShaderType::TessellationControl => {},
+                    GraphicsShaderType::TessellationEvaluation => {},
                     _ => return Err(GraphicsPipelineCreationError::WrongShaderType),
                 };
 
@@ -513,7 +919,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                     .module()
                                     .internal_object(),
                                 pName: tess.tessellation_control_shader.0.name().as_ptr(),
-                                pSpecializationInfo: &tess_shader_specialization
+                                pSpecializationInfo: &storage.tess_shader_specialization
                                     .as_ref()
                                     .unwrap()
                                     .0 as
@@ -530,7 +936,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                     .module()
                                     .internal_object(),
                                 pName: tess.tessellation_evaluation_shader.0.name().as_ptr(),
-                                pSpecializationInfo: &tess_shader_specialization
+                                pSpecializationInfo: &storage.tess_shader_specialization
                                     .as_ref()
                                     .unwrap()
                                     .1 as
@@ -541,13 +947,28 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             stages
         };
 
-        // Vertex bindings.
-        let (binding_descriptions, attribute_descriptions) = {
-            let (buffers_iter, attribs_iter) =
-                self.vertex_input
-                    .definition(self.vertex_shader.as_ref().unwrap().0.input())?;
+        Self::check_stage_interfaces(vertex_shader,
+                                     tessellation_shaders,
+                                     geometry_shader,
+                                     task_shader,
+                                     mesh_shader,
+                                     fragment_shader)?;
+
+        // Vertex bindings. Mesh pipelines don't have any vertex input. The vertex input interface
+        // of a library is built from the input of the vertex shader, even though the vertex
+        // shader itself belongs to the pre-rasterization shaders.
+        let vertex_input_shader = if parts.vertex_input_interface {
+            self.vertex_shader.as_ref()
+        } else {
+            None
+        };
+        let (binding_descriptions, attribute_descriptions, binding_divisors) = if let Some(vs) =
+            vertex_input_shader
+        {
+            let (buffers_iter, attribs_iter) = self.vertex_input.definition(vs.0.input())?;
 
             let mut binding_descriptions = SmallVec::<[_; 8]>::new();
+            let mut binding_divisors = SmallVec::<[_; 8]>::new();
             for (num, stride, rate) in buffers_iter {
                 if stride >
                     device
@@ -562,10 +983,55 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                     });
                 }
 
+                if device.loaded_extensions().khr_portability_subset {
+                    if let Some(properties) =
+                        device.physical_device().portability_subset_properties()
+                    {
+                        let alignment =
+                            properties.min_vertex_input_binding_stride_alignment as usize;
+                        if stride % alignment != 0 {
+                            return Err(GraphicsPipelineCreationError::VertexInputBindingStrideNotAligned {
+                                binding: num as usize,
+                                alignment: alignment,
+                                obtained: stride,
+                            });
+                        }
+                    }
+                }
+
+                match rate {
+                    InputRate::Instance { divisor } if divisor != 1 => {
+                        if !device.loaded_extensions().ext_vertex_attribute_divisor {
+                            return Err(GraphicsPipelineCreationError::VertexAttributeDivisorExtensionNotEnabled);
+                        }
+
+                        let features = device.enabled_features();
+                        if !features.vertex_attribute_instance_rate_divisor {
+                            return Err(GraphicsPipelineCreationError::VertexAttributeInstanceRateDivisorFeatureNotEnabled);
+                        }
+                        if divisor == 0 && !features.vertex_attribute_instance_rate_zero_divisor {
+                            return Err(GraphicsPipelineCreationError::VertexAttributeInstanceRateZeroDivisorFeatureNotEnabled);
+                        }
+                        if divisor > device.max_vertex_attrib_divisor() {
+                            return Err(GraphicsPipelineCreationError::MaxVertexAttribDivisorExceeded {
+                                           binding: num as usize,
+                                           max: device.max_vertex_attrib_divisor(),
+                                           obtained: divisor,
+                                       });
+                        }
+
+                        binding_divisors.push(vk::VertexInputBindingDivisorDescriptionEXT {
+                                                  binding: num as u32,
+                                                  divisor: divisor,
+                                              });
+                    },
+                    _ => (),
+                }
+
                 binding_descriptions.push(vk::VertexInputBindingDescription {
                                               binding: num as u32,
                                               stride: stride as u32,
-                                              inputRate: rate as u32,
+                                              inputRate: rate.into(),
                                           });
             }
 
@@ -598,7 +1064,9 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                             });
             }
 
-            (binding_descriptions, attribute_descriptions)
+            (binding_descriptions, attribute_descriptions, binding_divisors)
+        } else {
+            (SmallVec::new(), SmallVec::new(), SmallVec::new())
         };
 
         if binding_descriptions.len() >
@@ -633,17 +1101,36 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                        });
         }
 
-        let vertex_input_state = vk::PipelineVertexInputStateCreateInfo {
-            sType: vk::STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
-            pNext: ptr::null(),
-            flags: 0, // reserved
-            vertexBindingDescriptionCount: binding_descriptions.len() as u32,
-            pVertexBindingDescriptions: binding_descriptions.as_ptr(),
-            vertexAttributeDescriptionCount: attribute_descriptions.len() as u32,
-            pVertexAttributeDescriptions: attribute_descriptions.as_ptr(),
+        storage.binding_descriptions = binding_descriptions;
+        storage.attribute_descriptions = attribute_descriptions;
+        storage.binding_divisors = binding_divisors;
+
+        storage.divisor_state = if !storage.binding_divisors.is_empty() {
+            Some(vk::PipelineVertexInputDivisorStateCreateInfoEXT {
+                     sType: vk::STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_DIVISOR_STATE_CREATE_INFO_EXT,
+                     pNext: ptr::null(),
+                     vertexBindingDivisorCount: storage.binding_divisors.len() as u32,
+                     pVertexBindingDivisors: storage.binding_divisors.as_ptr(),
+                 })
+        } else {
+            None
         };
 
-        if self.input_assembly.primitiveRestartEnable != vk::FALSE &&
+        storage.vertex_input_state = Some(vk::PipelineVertexInputStateCreateInfo {
+            sType: vk::STRUCTURE_TYPE_PIPELINE_VERTEX_INPUT_STATE_CREATE_INFO,
+            pNext: storage.divisor_state
+                .as_ref()
+                .map(|d| d as *const _ as *const _)
+                .unwrap_or(ptr::null()),
+            flags: 0, // reserved
+            vertexBindingDescriptionCount: storage.binding_descriptions.len() as u32,
+            pVertexBindingDescriptions: storage.binding_descriptions.as_ptr(),
+            vertexAttributeDescriptionCount: storage.attribute_descriptions.len() as u32,
+            pVertexAttributeDescriptions: storage.attribute_descriptions.as_ptr(),
+        });
+
+        if parts.vertex_input_interface &&
+            self.input_assembly.primitiveRestartEnable != vk::FALSE &&
             !self.input_assembly_topology.supports_primitive_restart()
         {
             return Err(GraphicsPipelineCreationError::PrimitiveDoesntSupportPrimitiveRestart {
@@ -651,11 +1138,26 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                        });
         }
 
-        // TODO: should check from the tess eval shader instead of the input assembly
-        if let Some(ref gs) = self.geometry_shader {
+        if let Some(gs) = geometry_shader {
             match gs.0.ty() {
                 GraphicsShaderType::Geometry(primitives) => {
-                    if !primitives.matches(self.input_assembly_topology) {
+                    // When tessellation is enabled, the geometry shader receives the primitives
+                    // generated by the tessellator instead of the ones of the input assembly.
+                    let matches = match tessellation_shaders {
+                        Some(tess) => {
+                            match tessellation_output(&tess.tessellation_control_shader.0,
+                                                      &tess.tessellation_evaluation_shader.0) {
+                                Some(output) => output == primitives,
+                                None => true,
+                            }
+                        },
+                        None => {
+                            !parts.vertex_input_interface ||
+                                primitives.matches(self.input_assembly_topology)
+                        },
+                    };
+
+                    if !matches {
                         return Err(GraphicsPipelineCreationError::TopologyNotMatchingGeometryShader);
                     }
                 },
@@ -663,9 +1165,20 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             }
         }
 
-        let tessellation = match self.input_assembly_topology {
+        if parts.vertex_input_interface &&
+            self.input_assembly_topology == PrimitiveTopology::TriangleFan &&
+            device.loaded_extensions().khr_portability_subset &&
+            !device.enabled_features().triangle_fans
+        {
+            return Err(GraphicsPipelineCreationError::TriangleFansFeatureNotEnabled);
+        }
+
+        // The tessellation state belongs to the pre-rasterization shaders, but the number of
+        // vertices per patch is set with the primitive topology.
+        storage.tessellation = match self.input_assembly_topology {
+            _ if !parts.pre_rasterization_shaders => None,
             PrimitiveTopology::PatchList { vertices_per_patch } => {
-                if self.tessellation.is_none() {
+                if tessellation_shaders.is_none() || vertices_per_patch == 0 {
                     return Err(GraphicsPipelineCreationError::InvalidPrimitiveTopology);
                 }
                 if vertices_per_patch >
@@ -685,7 +1198,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                      })
             },
             _ => {
-                if self.tessellation.is_some() {
+                if tessellation_shaders.is_some() {
                     return Err(GraphicsPipelineCreationError::InvalidPrimitiveTopology);
                 }
 
@@ -693,15 +1206,15 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             },
         };
 
-        let (vp_vp, vp_sc, vp_num) = match *self.viewport.as_ref().unwrap() {
-            ViewportsState::Fixed { ref data } => (data.iter()
+        let (vp_vp, vp_sc, vp_num) = match viewport {
+            Some(&ViewportsState::Fixed { ref data }) => (data.iter()
                                                        .map(|e| e.0.clone().into_vulkan_viewport())
                                                        .collect::<SmallVec<[vk::Viewport; 4]>>(),
                                                    data.iter()
                                                        .map(|e| e.1.clone().into_vulkan_rect())
                                                        .collect::<SmallVec<[vk::Rect2D; 4]>>(),
                                                    data.len() as u32),
-            ViewportsState::DynamicViewports { ref scissors } => {
+            Some(&ViewportsState::DynamicViewports { ref scissors }) => {
                 let num = scissors.len() as u32;
                 let scissors = scissors
                     .iter()
@@ -710,7 +1223,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 dynamic_states.push(vk::DYNAMIC_STATE_VIEWPORT);
                 (SmallVec::new(), scissors, num)
             },
-            ViewportsState::DynamicScissors { ref viewports } => {
+            Some(&ViewportsState::DynamicScissors { ref viewports }) => {
                 let num = viewports.len() as u32;
                 let viewports = viewports
                     .iter()
@@ -719,11 +1232,12 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 dynamic_states.push(vk::DYNAMIC_STATE_SCISSOR);
                 (viewports, SmallVec::new(), num)
             },
-            ViewportsState::Dynamic { num } => {
+            Some(&ViewportsState::Dynamic { num }) => {
                 dynamic_states.push(vk::DYNAMIC_STATE_VIEWPORT);
                 dynamic_states.push(vk::DYNAMIC_STATE_SCISSOR);
                 (SmallVec::new(), SmallVec::new(), num)
             },
+            None => (SmallVec::new(), SmallVec::new(), 0),
         };
 
         if vp_num > 1 && !device.enabled_features().multi_viewport {
@@ -753,23 +1267,25 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             }
         }
 
-        let viewport_info = vk::PipelineViewportStateCreateInfo {
+        storage.viewports = vp_vp;
+        storage.scissors = vp_sc;
+        storage.viewport_info = Some(vk::PipelineViewportStateCreateInfo {
             sType: vk::STRUCTURE_TYPE_PIPELINE_VIEWPORT_STATE_CREATE_INFO,
             pNext: ptr::null(),
             flags: 0, // reserved
             viewportCount: vp_num,
-            pViewports: if vp_vp.is_empty() {
+            pViewports: if storage.viewports.is_empty() {
                 ptr::null()
             } else {
-                vp_vp.as_ptr()
+                storage.viewports.as_ptr()
             }, // validation layer crashes if you just pass the pointer
             scissorCount: vp_num,
-            pScissors: if vp_sc.is_empty() {
+            pScissors: if storage.scissors.is_empty() {
                 ptr::null()
             } else {
-                vp_sc.as_ptr()
+                storage.scissors.as_ptr()
             }, // validation layer crashes if you just pass the pointer
-        };
+        });
 
         if let Some(line_width) = self.raster.line_width {
             if line_width != 1.0 && !device.enabled_features().wide_lines {
@@ -806,9 +1322,115 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             return Err(GraphicsPipelineCreationError::FillModeNonSolidFeatureNotEnabled);
         }
 
-        let rasterization = vk::PipelineRasterizationStateCreateInfo {
+        if self.raster.polygon_mode == PolygonMode::Point &&
+            device.loaded_extensions().khr_portability_subset &&
+            !device.enabled_features().point_polygons
+        {
+            return Err(GraphicsPipelineCreationError::PointPolygonsFeatureNotEnabled);
+        }
+
+        storage.conservative = match self.raster.conservative {
+            ConservativeRasterization::Disabled => None,
+            mode => {
+                if !device.loaded_extensions().ext_conservative_rasterization {
+                    return Err(GraphicsPipelineCreationError::ConservativeRasterizationExtensionNotEnabled);
+                }
+
+                let (mode, extra_size) = match mode {
+                    ConservativeRasterization::Overestimate { extra_size } => {
+                        if !(extra_size >= 0.0 &&
+                             extra_size <= device.max_extra_primitive_overestimation_size())
+                        {
+                            return Err(GraphicsPipelineCreationError::InvalidExtraPrimitiveOverestimationSize);
+                        }
+
+                        (vk::CONSERVATIVE_RASTERIZATION_MODE_OVERESTIMATE_EXT, extra_size)
+                    },
+                    ConservativeRasterization::Underestimate => {
+                        (vk::CONSERVATIVE_RASTERIZATION_MODE_UNDERESTIMATE_EXT, 0.0)
+                    },
+                    ConservativeRasterization::Disabled => unreachable!(),
+                };
+
+                Some(vk::PipelineRasterizationConservativeStateCreateInfoEXT {
+                         sType: vk::STRUCTURE_TYPE_PIPELINE_RASTERIZATION_CONSERVATIVE_STATE_CREATE_INFO_EXT,
+                         pNext: ptr::null(),
+                         flags: 0, // reserved
+                         conservativeRasterizationMode: mode,
+                         extraPrimitiveOverestimationSize: extra_size,
+                     })
+            },
+        };
+
+        let conservative_ptr = storage.conservative
+            .as_ref()
+            .map(|c| c as *const _ as *const _)
+            .unwrap_or(ptr::null());
+
+        storage.line_state = match (self.raster.line_rasterization_mode, self.raster.line_stipple) {
+            (LineRasterizationMode::Default, LineStippleControl::Disabled) => None,
+            (mode, stipple) => {
+                if !device.loaded_extensions().ext_line_rasterization {
+                    return Err(GraphicsPipelineCreationError::LineRasterizationExtensionNotEnabled);
+                }
+
+                let features = device.enabled_features();
+                let (mode_supported, stipple_supported) = match mode {
+                    LineRasterizationMode::Default => {
+                        let strict = device.physical_device().limits().strict_lines() != 0;
+                        (true, features.stippled_rectangular_lines && strict)
+                    },
+                    LineRasterizationMode::Rectangular => {
+                        (features.rectangular_lines, features.stippled_rectangular_lines)
+                    },
+                    LineRasterizationMode::Bresenham => {
+                        (features.bresenham_lines, features.stippled_bresenham_lines)
+                    },
+                    LineRasterizationMode::RectangularSmooth => {
+                        (features.smooth_lines, features.stippled_smooth_lines)
+                    },
+                };
+
+                if !mode_supported {
+                    return Err(GraphicsPipelineCreationError::LineRasterizationModeFeatureNotEnabled);
+                }
+
+                let (stipple_enable, factor, pattern) = match stipple {
+                    LineStippleControl::Disabled => (vk::FALSE, 1, 0),
+                    LineStippleControl::Dynamic => {
+                        dynamic_states.push(vk::DYNAMIC_STATE_LINE_STIPPLE_EXT);
+                        (vk::TRUE, 1, 0)
+                    },
+                    LineStippleControl::Static(stipple) => {
+                        if stipple.factor < 1 || stipple.factor > 256 {
+                            return Err(GraphicsPipelineCreationError::InvalidLineStippleFactor);
+                        }
+
+                        (vk::TRUE, stipple.factor, stipple.pattern)
+                    },
+                };
+
+                if stipple_enable != vk::FALSE && !stipple_supported {
+                    return Err(GraphicsPipelineCreationError::LineStippleFeatureNotEnabled);
+                }
+
+                Some(vk::PipelineRasterizationLineStateCreateInfoEXT {
+                         sType: vk::STRUCTURE_TYPE_PIPELINE_RASTERIZATION_LINE_STATE_CREATE_INFO_EXT,
+                         pNext: conservative_ptr,
+                         lineRasterizationMode: mode as u32,
+                         stippledLineEnable: stipple_enable,
+                         lineStippleFactor: factor,
+                         lineStipplePattern: pattern,
+                     })
+            },
+        };
+
+        storage.rasterization = Some(vk::PipelineRasterizationStateCreateInfo {
             sType: vk::STRUCTURE_TYPE_PIPELINE_RASTERIZATION_STATE_CREATE_INFO,
-            pNext: ptr::null(),
+            pNext: storage.line_state
+                .as_ref()
+                .map(|l| l as *const _ as *const _)
+                .unwrap_or(conservative_ptr),
             flags: 0, // reserved
             depthClampEnable: if self.raster.depth_clamp {
                 vk::TRUE
@@ -828,13 +1450,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             depthBiasClamp: db_clamp,
             depthBiasSlopeFactor: db_slope,
             lineWidth: self.raster.line_width.unwrap_or(1.0),
-        };
+        });
 
-        self.multisample.rasterizationSamples = self.render_pass
-            .as_ref()
-            .unwrap()
-            .num_samples()
+        self.multisample.rasterizationSamples = subpass
+            .and_then(|subpass| subpass.num_samples())
             .unwrap_or(1);
+        self.multisample.pSampleMask = if self.sample_mask == [0xffffffff; 2] {
+            ptr::null()
+        } else {
+            self.sample_mask.as_ptr()
+        };
         if self.multisample.sampleShadingEnable != vk::FALSE {
             debug_assert!(self.multisample.minSampleShading >= 0.0 &&
                               self.multisample.minSampleShading <= 1.0);
@@ -848,7 +1473,7 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             }
         }
 
-        let depth_stencil = {
+        storage.depth_stencil = {
             let db = match self.depth_stencil.depth_bounds_test {
                 DepthBounds::Disabled => (vk::FALSE, 0.0, 0.0),
                 DepthBounds::Fixed(ref range) => {
@@ -856,6 +1481,10 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                         return Err(GraphicsPipelineCreationError::DepthBoundsFeatureNotEnabled);
                     }
 
+                    if range.start < 0.0 || range.end > 1.0 || range.start > range.end {
+                        return Err(GraphicsPipelineCreationError::DepthBoundsOutOfRange);
+                    }
+
                     (vk::TRUE, range.start, range.end)
                 },
                 DepthBounds::Dynamic => {
@@ -896,28 +1525,26 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 _ => return Err(GraphicsPipelineCreationError::WrongStencilState),
             };
 
-            if self.depth_stencil.depth_write &&
-                !self.render_pass.as_ref().unwrap().has_writable_depth()
-            {
-                return Err(GraphicsPipelineCreationError::NoDepthAttachment);
-            }
+            if let (true, Some(subpass)) = (parts.fragment_shader, subpass) {
+                if self.depth_stencil.depth_write && !subpass.has_writable_depth() {
+                    return Err(GraphicsPipelineCreationError::NoDepthAttachment);
+                }
 
-            if self.depth_stencil.depth_compare != Compare::Always &&
-                !self.render_pass.as_ref().unwrap().has_depth()
-            {
-                return Err(GraphicsPipelineCreationError::NoDepthAttachment);
-            }
+                if self.depth_stencil.depth_compare != Compare::Always && !subpass.has_depth() {
+                    return Err(GraphicsPipelineCreationError::NoDepthAttachment);
+                }
 
-            if (!self.depth_stencil.stencil_front.always_keep() ||
-                    !self.depth_stencil.stencil_back.always_keep()) &&
-                !self.render_pass.as_ref().unwrap().has_stencil()
-            {
-                return Err(GraphicsPipelineCreationError::NoStencilAttachment);
+                if (!self.depth_stencil.stencil_front.always_keep() ||
+                        !self.depth_stencil.stencil_back.always_keep()) &&
+                    !subpass.has_stencil()
+                {
+                    return Err(GraphicsPipelineCreationError::NoStencilAttachment);
+                }
             }
 
             // FIXME: stencil writability
 
-            vk::PipelineDepthStencilStateCreateInfo {
+            Some(vk::PipelineDepthStencilStateCreateInfo {
                 sType: vk::STRUCTURE_TYPE_PIPELINE_DEPTH_STENCIL_STATE_CREATE_INFO,
                 pNext: ptr::null(),
                 flags: 0, // reserved
@@ -974,36 +1601,61 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 },
                 minDepthBounds: db.1,
                 maxDepthBounds: db.2,
-            }
+            })
         };
 
-        let blend_atch: SmallVec<[vk::PipelineColorBlendAttachmentState; 8]> = {
-            let num_atch = self.render_pass.as_ref().unwrap().num_color_attachments();
-
-            match self.blend.attachments {
-                AttachmentsBlend::Collective(blend) => {
-                    (0 .. num_atch)
-                        .map(|_| blend.clone().into_vulkan_state())
-                        .collect()
-                },
-                AttachmentsBlend::Individual(blend) => {
-                    if blend.len() != num_atch as usize {
-                        return Err(GraphicsPipelineCreationError::MismatchBlendingAttachmentsCount);
-                    }
+        storage.blend_atch = match subpass {
+            Some(subpass) if parts.fragment_output_interface => {
+                let num_atch = subpass.num_color_attachments();
+
+                match self.blend.attachments {
+                    AttachmentsBlend::Collective(ref blend) => {
+                        (0 .. num_atch)
+                            .map(|_| blend.clone().into_vulkan_state())
+                            .collect()
+                    },
+                    AttachmentsBlend::Individual(ref blend) => {
+                        if blend.len() != num_atch as usize {
+                            return Err(GraphicsPipelineCreationError::MismatchBlendingAttachmentsCount);
+                        }
+
+                        if blend.iter().skip(1).any(|b| *b != blend[0]) &&
+                            !device.enabled_features().independent_blend
+                        {
+                            return Err(GraphicsPipelineCreationError::IndependentBlendFeatureNotEnabled);
+                        }
+
+                        blend
+                            .iter()
+                            .map(|b| b.clone().into_vulkan_state())
+                            .collect()
+                    },
+                }
+            },
+            _ => SmallVec::new(),
+        };
 
-                    if !device.enabled_features().independent_blend {
-                        return Err(GraphicsPipelineCreationError::IndependentBlendFeatureNotEnabled);
-                    }
+        let dual_source = match self.blend.attachments {
+            AttachmentsBlend::Collective(ref blend) => blend.uses_dual_source(),
+            AttachmentsBlend::Individual(ref blend) => blend.iter().any(|b| b.uses_dual_source()),
+        };
+        if dual_source && !device.enabled_features().dual_src_blend {
+            return Err(GraphicsPipelineCreationError::DualSrcBlendFeatureNotEnabled);
+        }
 
-                    blend
-                        .iter()
-                        .map(|b| b.clone().into_vulkan_state())
-                        .collect()
-                },
-            }
+        let constant_alpha_color = match self.blend.attachments {
+            AttachmentsBlend::Collective(ref blend) => blend.uses_constant_alpha_color(),
+            AttachmentsBlend::Individual(ref blend) => {
+                blend.iter().any(|b| b.uses_constant_alpha_color())
+            },
         };
+        if constant_alpha_color && device.loaded_extensions().khr_portability_subset &&
+            !device.enabled_features().constant_alpha_color_blend_factors
+        {
+            return Err(GraphicsPipelineCreationError::ConstantAlphaColorBlendFactorsFeatureNotEnabled);
+        }
 
-        let blend = vk::PipelineColorBlendStateCreateInfo {
+        storage.blend = Some(vk::PipelineColorBlendStateCreateInfo {
             sType: vk::STRUCTURE_TYPE_PIPELINE_COLOR_BLEND_STATE_CREATE_INFO,
             pNext: ptr::null(),
             flags: 0, // reserved
@@ -1016,112 +1668,277 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                 vk::FALSE
             },
             logicOp: self.blend.logic_op.unwrap_or(Default::default()) as u32,
-            attachmentCount: blend_atch.len() as u32,
-            pAttachments: blend_atch.as_ptr(),
+            attachmentCount: storage.blend_atch.len() as u32,
+            pAttachments: storage.blend_atch.as_ptr(),
             blendConstants: if let Some(c) = self.blend.blend_constants {
                 c
             } else {
                 dynamic_states.push(vk::DYNAMIC_STATE_BLEND_CONSTANTS);
                 [0.0, 0.0, 0.0, 0.0]
             },
-        };
+        });
 
-        let dynamic_states = if !dynamic_states.is_empty() {
+        storage.dynamic_states = dynamic_states;
+        storage.dynamic_state_info = if !storage.dynamic_states.is_empty() {
             Some(vk::PipelineDynamicStateCreateInfo {
                      sType: vk::STRUCTURE_TYPE_PIPELINE_DYNAMIC_STATE_CREATE_INFO,
                      pNext: ptr::null(),
                      flags: 0, // reserved
-                     dynamicStateCount: dynamic_states.len() as u32,
-                     pDynamicStates: dynamic_states.as_ptr(),
+                     dynamicStateCount: storage.dynamic_states.len() as u32,
+                     pDynamicStates: storage.dynamic_states.as_ptr(),
                  })
         } else {
             None
         };
 
-        let pipeline = unsafe {
-            let infos = vk::GraphicsPipelineCreateInfo {
-                sType: vk::STRUCTURE_TYPE_GRAPHICS_PIPELINE_CREATE_INFO,
-                pNext: ptr::null(),
-                flags: 0, // TODO: some flags are available but none are critical
-                stageCount: stages.len() as u32,
-                pStages: stages.as_ptr(),
-                pVertexInputState: &vertex_input_state,
-                pInputAssemblyState: &self.input_assembly,
-                pTessellationState: tessellation
-                    .as_ref()
-                    .map(|t| t as *const _)
-                    .unwrap_or(ptr::null()),
-                pViewportState: &viewport_info,
-                pRasterizationState: &rasterization,
-                pMultisampleState: &self.multisample,
-                pDepthStencilState: &depth_stencil,
-                pColorBlendState: &blend,
-                pDynamicState: dynamic_states
-                    .as_ref()
-                    .map(|s| s as *const _)
-                    .unwrap_or(ptr::null()),
-                layout: PipelineLayoutAbstract::sys(&pipeline_layout).internal_object(),
-                renderPass: self.render_pass
-                    .as_ref()
-                    .unwrap()
-                    .render_pass()
-                    .inner()
-                    .internal_object(),
-                subpass: self.render_pass.as_ref().unwrap().index(),
-                basePipelineHandle: 0, // TODO:
-                basePipelineIndex: -1, // TODO:
-            };
+        storage.feedback = if device.loaded_extensions().ext_pipeline_creation_feedback {
+            Some(FeedbackStorage::new(storage.stages.iter().map(|s| s.stage)))
+        } else {
+            None
+        };
+        storage.feedback_info = storage.feedback.as_mut().map(|f| f.create_info());
+        let feedback_ptr = storage.feedback_info
+            .as_ref()
+            .map(|f| f as *const _ as *const _)
+            .unwrap_or(ptr::null());
+
+        storage.library_info = library.map(|parts| {
+            vk::GraphicsPipelineLibraryCreateInfoEXT {
+                sType: vk::STRUCTURE_TYPE_GRAPHICS_PIPELINE_LIBRARY_CREATE_INFO_EXT,
+                pNext: feedback_ptr as *mut _,
+                flags: parts.into_vulkan_bits(),
+            }
+        });
 
-            let mut output = mem::uninitialized();
-            check_errors(vk.CreateGraphicsPipelines(device.internal_object(),
-                                                    0,
-                                                    1,
-                                                    &infos,
-                                                    ptr::null(),
-                                                    &mut output))?;
-            output
+        // TODO: some other flags are available but none are critical
+        let flags = if library.is_some() {
+            vk::PIPELINE_CREATE_LIBRARY_BIT_KHR |
+                vk::PIPELINE_CREATE_RETAIN_LINK_TIME_OPTIMIZATION_INFO_BIT_EXT
+        } else {
+            0
+        };
+
+        let infos = vk::GraphicsPipelineCreateInfo {
+            sType: vk::STRUCTURE_TYPE_GRAPHICS_PIPELINE_CREATE_INFO,
+            pNext: storage.library_info
+                .as_ref()
+                .map(|l| l as *const _ as *const _)
+                .unwrap_or(feedback_ptr),
+            flags: flags,
+            stageCount: storage.stages.len() as u32,
+            pStages: storage.stages.as_ptr(),
+            pVertexInputState: if parts.vertex_input_interface && self.mesh_shader.is_none() {
+                storage.vertex_input_state.as_ref().unwrap()
+            } else {
+                ptr::null()
+            },
+            pInputAssemblyState: if parts.vertex_input_interface && self.mesh_shader.is_none() {
+                &self.input_assembly
+            } else {
+                ptr::null()
+            },
+            pTessellationState: storage.tessellation
+                .as_ref()
+                .map(|t| t as *const _)
+                .unwrap_or(ptr::null()),
+            pViewportState: if parts.pre_rasterization_shaders {
+                storage.viewport_info.as_ref().unwrap()
+            } else {
+                ptr::null()
+            },
+            pRasterizationState: if parts.pre_rasterization_shaders {
+                storage.rasterization.as_ref().unwrap()
+            } else {
+                ptr::null()
+            },
+            pMultisampleState: if parts.fragment_shader || parts.fragment_output_interface {
+                &self.multisample
+            } else {
+                ptr::null()
+            },
+            pDepthStencilState: if parts.fragment_shader {
+                storage.depth_stencil.as_ref().unwrap()
+            } else {
+                ptr::null()
+            },
+            pColorBlendState: if parts.fragment_output_interface {
+                storage.blend.as_ref().unwrap()
+            } else {
+                ptr::null()
+            },
+            pDynamicState: storage.dynamic_state_info
+                .as_ref()
+                .map(|s| s as *const _)
+                .unwrap_or(ptr::null()),
+            layout: PipelineLayoutAbstract::sys(pipeline_layout).internal_object(),
+            renderPass: subpass
+                .map(|subpass| subpass.render_pass().inner().internal_object())
+                .unwrap_or(0),
+            subpass: subpass.map(|subpass| subpass.index()).unwrap_or(0),
+            basePipelineHandle: 0, // TODO:
+            basePipelineIndex: -1, // TODO:
         };
 
+        Ok((infos, storage))
+    }
+
+    // Builds the `GraphicsPipeline` once the Vulkan pipeline has been created from `create_info`.
+    fn into_pipeline<Pl>(mut self, device: Arc<Device>, pipeline_layout: Pl,
+                         pipeline: vk::Pipeline, feedback: Option<PipelineCreationFeedback>)
+                         -> GraphicsPipeline<Vdef, Pl, Rp> {
+        let raw = self.raw_pipeline(device, pipeline, feedback);
         let (render_pass, render_pass_subpass) = self.render_pass.take().unwrap().into();
+        raw.with_types(self.vertex_input, pipeline_layout, render_pass, render_pass_subpass)
+    }
+
+    // Builds the `GraphicsPipelineLibrary` once the Vulkan pipeline has been created from
+    // `create_info`.
+    fn into_library<Pl>(mut self, device: Arc<Device>, pipeline_layout: Pl,
+                        pipeline: vk::Pipeline, feedback: Option<PipelineCreationFeedback>,
+                        parts: GraphicsPipelineLibraryParts)
+                        -> GraphicsPipelineLibrary<Vdef, Pl, Rp> {
+        let raw = self.raw_pipeline(device, pipeline, feedback);
+        let subpass = self.render_pass.take();
+        GraphicsPipelineLibrary::new(raw, pipeline_layout, self.vertex_input, subpass, parts)
+    }
 
-        Ok(GraphicsPipeline {
-               inner: GraphicsPipelineInner {
-                   device: device.clone(),
-                   pipeline: pipeline,
-               },
-               layout: pipeline_layout,
+    // Builds a `GraphicsPipeline` that contains the states of the builder, but not its vertex
+    // definition, pipeline layout and render pass.
+    fn raw_pipeline(&self, device: Arc<Device>, pipeline: vk::Pipeline,
+                    feedback: Option<PipelineCreationFeedback>)
+                    -> GraphicsPipeline<(), (), ()> {
+        GraphicsPipeline {
+            inner: GraphicsPipelineInner {
+                device: device,
+                pipeline: pipeline,
+            },
+            layout: (),
+
+            vertex_definition: (),
+
+            render_pass: (),
+            render_pass_subpass: 0,
+
+            dynamic_line_width: self.raster.line_width.is_none(),
+            dynamic_viewport: self.viewport.as_ref().map_or(false, |v| v.dynamic_viewports()),
+            dynamic_scissor: self.viewport.as_ref().map_or(false, |v| v.dynamic_scissors()),
+            dynamic_depth_bias: self.raster.depth_bias.is_dynamic(),
+            dynamic_line_stipple: self.raster.line_stipple.is_dynamic(),
+            dynamic_depth_bounds: self.depth_stencil.depth_bounds_test.is_dynamic(),
+            dynamic_stencil_compare_mask: self.depth_stencil.stencil_back.compare_mask.is_none(),
+            dynamic_stencil_write_mask: self.depth_stencil.stencil_back.write_mask.is_none(),
+            dynamic_stencil_reference: self.depth_stencil.stencil_back.reference.is_none(),
+            dynamic_blend_constants: self.blend.blend_constants.is_none(),
+            dynamic_primitive_topology: self.extended_dynamic.primitive_topology,
+            dynamic_cull_mode: self.extended_dynamic.cull_mode,
+            dynamic_front_face: self.extended_dynamic.front_face,
+            dynamic_depth_test_enable: self.extended_dynamic.depth_test_enable,
+            dynamic_depth_write_enable: self.extended_dynamic.depth_write_enable,
+            dynamic_depth_compare_op: self.extended_dynamic.depth_compare_op,
+            dynamic_depth_bounds_test_enable: self.extended_dynamic.depth_bounds_test_enable,
+            dynamic_stencil_test_enable: self.extended_dynamic.stencil_test_enable,
+            dynamic_stencil_op: self.extended_dynamic.stencil_op,
+            dynamic_vertex_input_binding_stride: self.extended_dynamic.vertex_input_binding_stride,
+
+            num_viewports: self.viewport.as_ref().map_or(0, |v| v.num_viewports()),
+            task_shader: self.task_shader.is_some(),
+            mesh_shader: self.mesh_shader.is_some(),
+            primitive_topology: self.input_assembly_topology,
+
+            creation_feedback: feedback,
+        }
+    }
+}
 
-               vertex_definition: self.vertex_input,
+// Data that the create info returned by `GraphicsPipelineBuilder::create_info` points to. It is
+// boxed, so that the create infos of several pipelines can be collected before creating them.
+#[derive(Default)]
+struct CreateInfoStorage {
+    vertex_shader_specialization: Option<vk::SpecializationInfo>,
+    tess_shader_specialization: Option<(vk::SpecializationInfo, vk::SpecializationInfo)>,
+    geometry_shader_specialization: Option<vk::SpecializationInfo>,
+    task_shader_specialization: Option<vk::SpecializationInfo>,
+    mesh_shader_specialization: Option<vk::SpecializationInfo>,
+    fragment_shader_specialization: Option<vk::SpecializationInfo>,
+    stages: SmallVec<[vk::PipelineShaderStageCreateInfo; 5]>,
+    binding_descriptions: SmallVec<[vk::VertexInputBindingDescription; 8]>,
+    attribute_descriptions: SmallVec<[vk::VertexInputAttributeDescription; 8]>,
+    binding_divisors: SmallVec<[vk::VertexInputBindingDivisorDescriptionEXT; 8]>,
+    divisor_state: Option<vk::PipelineVertexInputDivisorStateCreateInfoEXT>,
+    vertex_input_state: Option<vk::PipelineVertexInputStateCreateInfo>,
+    tessellation: Option<vk::PipelineTessellationStateCreateInfo>,
+    viewports: SmallVec<[vk::Viewport; 4]>,
+    scissors: SmallVec<[vk::Rect2D; 4]>,
+    viewport_info: Option<vk::PipelineViewportStateCreateInfo>,
+    conservative: Option<vk::PipelineRasterizationConservativeStateCreateInfoEXT>,
+    line_state: Option<vk::PipelineRasterizationLineStateCreateInfoEXT>,
+    rasterization: Option<vk::PipelineRasterizationStateCreateInfo>,
+    depth_stencil: Option<vk::PipelineDepthStencilStateCreateInfo>,
+    blend_atch: SmallVec<[vk::PipelineColorBlendAttachmentState; 8]>,
+    blend: Option<vk::PipelineColorBlendStateCreateInfo>,
+    dynamic_states: SmallVec<[vk::DynamicState; 8]>,
+    dynamic_state_info: Option<vk::PipelineDynamicStateCreateInfo>,
+    feedback: Option<FeedbackStorage>,
+    feedback_info: Option<vk::PipelineCreationFeedbackCreateInfoEXT>,
+    library_info: Option<vk::GraphicsPipelineLibraryCreateInfoEXT>,
+}
 
-               render_pass: render_pass,
-               render_pass_subpass: render_pass_subpass,
+impl CreateInfoStorage {
+    // Returns the creation feedback written by the implementation when the pipeline was created,
+    // if the `VK_EXT_pipeline_creation_feedback` extension is enabled.
+    fn into_feedback(self) -> Option<PipelineCreationFeedback> {
+        self.feedback.map(|f| f.into_feedback())
+    }
+}
 
-               dynamic_line_width: self.raster.line_width.is_none(),
-               dynamic_viewport: self.viewport.as_ref().unwrap().dynamic_viewports(),
-               dynamic_scissor: self.viewport.as_ref().unwrap().dynamic_scissors(),
-               dynamic_depth_bias: self.raster.depth_bias.is_dynamic(),
-               dynamic_depth_bounds: self.depth_stencil.depth_bounds_test.is_dynamic(),
-               dynamic_stencil_compare_mask: self.depth_stencil.stencil_back.compare_mask.is_none(),
-               dynamic_stencil_write_mask: self.depth_stencil.stencil_back.write_mask.is_none(),
-               dynamic_stencil_reference: self.depth_stencil.stencil_back.reference.is_none(),
-               dynamic_blend_constants: self.blend.blend_constants.is_none(),
+// Returns the kind of primitives generated by the tessellator, or `None` if it can't be
+// determined from the SPIR-V code of the shaders. The execution modes that control the output of
+// the tessellator can be declared in either of the two tessellation shaders.
+fn tessellation_output<Tcs, Tes>(tcs: &Tcs, tes: &Tes) -> Option<GeometryShaderExecutionMode>
+    where Tcs: GraphicsEntryPointAbstract,
+          Tes: GraphicsEntryPointAbstract
+{
+    let mut point_mode = false;
+    let mut output = None;
+
+    for &(module, name) in &[(tcs.module(), tcs.name()), (tes.module(), tes.name())] {
+        let spirv = match Spirv::parse(module.spirv()) {
+            Ok(s) => s,
+            Err(_) => return None,
+        };
+        let entry_point = match name.to_str().ok().and_then(|n| spirv.entry_point(n)) {
+            Some(i) => i.operands[1],
+            None => return None,
+        };
 
-               num_viewports: self.viewport.as_ref().unwrap().num_viewports(),
-           })
+        point_mode |= spirv.has_execution_mode(entry_point, EXECUTION_MODE_POINT_MODE);
+        if spirv.has_execution_mode(entry_point, EXECUTION_MODE_ISOLINES) {
+            output = Some(GeometryShaderExecutionMode::Lines);
+        } else if spirv.has_execution_mode(entry_point, EXECUTION_MODE_TRIANGLES) ||
+                   spirv.has_execution_mode(entry_point, EXECUTION_MODE_QUADS)
+        {
+            output = Some(GeometryShaderExecutionMode::Triangles);
+        }
     }
 
-    // TODO: add build_with_cache method
+    if point_mode {
+        Some(GeometryShaderExecutionMode::Points)
+    } else {
+        output
+    }
 }
 
-impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
-    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> {
+impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss, Fs, Fss, Rp>
+    GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                            Fs, Fss, Rp> {
     // TODO: add pipeline derivate system
 
     /// Sets the vertex input.
     #[inline]
     pub fn vertex_input<T>(
         self, vertex_input: T)
-        -> GraphicsPipelineBuilder<T, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> {
+        -> GraphicsPipelineBuilder<T, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                                   Fs, Fss, Rp> {
         GraphicsPipelineBuilder {
             vertex_input: vertex_input,
             vertex_shader: self.vertex_shader,
@@ -1129,13 +1946,18 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
+            mesh_shader: self.mesh_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
         }
     }
 
@@ -1154,18 +1976,34 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
                                                                     Tess,
                                                                     Gs,
                                                                     Gss,
+                                                                    Ts,
+                                                                    Tss,
+                                                                    Ms,
+                                                                    Mss,
                                                                     Fs,
                                                                     Fss,
                                                                     Rp> {
         self.vertex_input(SingleBufferDefinition::<V>::new())
     }
 
+    /// Sets the strides of the vertex buffers as dynamic. They will need to be passed in the
+    /// `vertex_strides` member of the `DynamicState` when drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn vertex_input_binding_stride_dynamic(mut self) -> Self {
+        self.extended_dynamic.vertex_input_binding_stride = true;
+        self
+    }
+
     /// Sets the vertex shader to use.
     // TODO: correct specialization constants
     #[inline]
     pub fn vertex_shader<Vs2, Vss2>(
         self, shader: Vs2, specialization_constants: Vss2)
-        -> GraphicsPipelineBuilder<Vdef, Vs2, Vss2, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
+        -> GraphicsPipelineBuilder<Vdef, Vs2, Vss2, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                                   Fs, Fss, Rp>
         where Vs2: GraphicsEntryPointAbstract<SpecializationConstants = Vss2>,
               Vss2: SpecializationConstants
     {
@@ -1176,13 +2014,18 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
+            mesh_shader: self.mesh_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
         }
     }
 
@@ -1296,68 +2139,177 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self.primitive_topology(PrimitiveTopology::TriangleStripWithAdjacency)
     }
 
-    /// Sets the topology of the primitives to a list of patches. Can only be used and must be used
-    /// with a tessellation shader.
-    ///
-    /// > **Note**: This is equivalent to
-    /// > `self.primitive_topology(PrimitiveTopology::PatchList { vertices_per_patch })`.
+    /// Sets the topology of the primitives to a list of patches. Can only be used and must be used
+    /// with a tessellation shader.
+    ///
+    /// > **Note**: This is equivalent to
+    /// > `self.primitive_topology(PrimitiveTopology::PatchList { vertices_per_patch })`.
+    #[inline]
+    pub fn patch_list(self, vertices_per_patch: u32) -> Self {
+        self.primitive_topology(PrimitiveTopology::PatchList { vertices_per_patch })
+    }
+
+    /// Sets the primitive topology as dynamic. It will need to be set when drawing, and must then
+    /// be of the same kind (points, lines, triangles or patches) as the one of the builder.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn primitive_topology_dynamic(mut self) -> Self {
+        self.extended_dynamic.primitive_topology = true;
+        self
+    }
+
+    /// Sets the tessellation shaders to use.
+    ///
+    /// The `tessellation_shader` feature must be enabled on the device, and the primitive
+    /// topology must be set to a list of patches with `patch_list`.
+    #[inline]
+    pub fn tessellation_shaders<Tcs2, Tcss2, Tes2, Tess2>(
+        self, tessellation_control_shader: Tcs2,
+        tessellation_control_shader_spec_constants: Tcss2, tessellation_evaluation_shader: Tes2,
+        tessellation_evaluation_shader_spec_constants: Tess2)
+        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs2, Tcss2, Tes2, Tess2, Gs, Gss, Ts, Tss, Ms, Mss,
+                                   Fs, Fss, Rp>
+        where Tcs2: GraphicsEntryPointAbstract<SpecializationConstants = Tcss2>,
+              Tes2: GraphicsEntryPointAbstract<SpecializationConstants = Tess2>,
+              Tcss2: SpecializationConstants,
+              Tess2: SpecializationConstants
+    {
+        GraphicsPipelineBuilder {
+            vertex_input: self.vertex_input,
+            vertex_shader: self.vertex_shader,
+            input_assembly: self.input_assembly,
+            input_assembly_topology: self.input_assembly_topology,
+            tessellation: Some(TessInfo {
+                                   tessellation_control_shader:
+                                       (tessellation_control_shader,
+                                        tessellation_control_shader_spec_constants),
+                                   tessellation_evaluation_shader:
+                                       (tessellation_evaluation_shader,
+                                        tessellation_evaluation_shader_spec_constants),
+                               }),
+            geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
+            mesh_shader: self.mesh_shader,
+            viewport: self.viewport,
+            raster: self.raster,
+            multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            fragment_shader: self.fragment_shader,
+            depth_stencil: self.depth_stencil,
+            blend: self.blend,
+            render_pass: self.render_pass,
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
+        }
+    }
+
+    /// Sets the tessellation shaders stage as disabled. This is the default.
+    #[inline]
+    pub fn tessellation_shaders_disabled(mut self) -> Self {
+        self.tessellation = None;
+        self
+    }
+
+    /// Sets the geometry shader to use.
+    ///
+    /// The `geometry_shader` feature must be enabled on the device. The type of primitives
+    /// expected by the shader must match the primitive topology, or the output of the
+    /// tessellation shaders if they are enabled.
+    #[inline]
+    pub fn geometry_shader<Gs2, Gss2>(
+        self, shader: Gs2, specialization_constants: Gss2)
+        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs2, Gss2, Ts, Tss, Ms, Mss,
+                                   Fs, Fss, Rp>
+        where Gs2: GraphicsEntryPointAbstract<SpecializationConstants = Gss2>,
+              Gss2: SpecializationConstants
+    {
+        GraphicsPipelineBuilder {
+            vertex_input: self.vertex_input,
+            vertex_shader: self.vertex_shader,
+            input_assembly: self.input_assembly,
+            input_assembly_topology: self.input_assembly_topology,
+            tessellation: self.tessellation,
+            geometry_shader: Some((shader, specialization_constants)),
+            task_shader: self.task_shader,
+            mesh_shader: self.mesh_shader,
+            viewport: self.viewport,
+            raster: self.raster,
+            multisample: self.multisample,
+            sample_mask: self.sample_mask,
+            fragment_shader: self.fragment_shader,
+            depth_stencil: self.depth_stencil,
+            blend: self.blend,
+            render_pass: self.render_pass,
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
+        }
+    }
+
+    /// Sets the geometry shader stage as disabled. This is the default.
     #[inline]
-    pub fn patch_list(self, vertices_per_patch: u32) -> Self {
-        self.primitive_topology(PrimitiveTopology::PatchList { vertices_per_patch })
+    pub fn geometry_shader_disabled(mut self) -> Self {
+        self.geometry_shader = None;
+        self
     }
 
-    /// Sets the tessellation shaders to use.
-    // TODO: correct specialization constants
+    /// Sets the task shader to use.
+    ///
+    /// The task shader runs before the mesh shader and decides how many mesh shader workgroups
+    /// are launched. It can only be used together with a mesh shader, and requires the
+    /// `task_shader` feature of the `VK_EXT_mesh_shader` extension.
     #[inline]
-    pub fn tessellation_shaders<Tcs2, Tcss2, Tes2, Tess2>(
-        self, tessellation_control_shader: Tcs2,
-        tessellation_control_shader_spec_constants: Tcss2, tessellation_evaluation_shader: Tes2,
-        tessellation_evaluation_shader_spec_constants: Tess2)
-        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs2, Tcss2, Tes2, Tess2, Gs, Gss, Fs, Fss, Rp>
-        where Tcs2: GraphicsEntryPointAbstract<SpecializationConstants = Tcss2>,
-              Tes2: GraphicsEntryPointAbstract<SpecializationConstants = Tess2>,
-              Tcss2: SpecializationConstants,
-              Tess2: SpecializationConstants
+    pub fn task_shader<Ts2, Tss2>(
+        self, shader: Ts2, specialization_constants: Tss2)
+        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts2, Tss2, Ms, Mss,
+                                   Fs, Fss, Rp>
+        where Ts2: GraphicsEntryPointAbstract<SpecializationConstants = Tss2>,
+              Tss2: SpecializationConstants
     {
         GraphicsPipelineBuilder {
             vertex_input: self.vertex_input,
             vertex_shader: self.vertex_shader,
             input_assembly: self.input_assembly,
             input_assembly_topology: self.input_assembly_topology,
-            tessellation: Some(TessInfo {
-                                   tessellation_control_shader:
-                                       (tessellation_control_shader,
-                                        tessellation_control_shader_spec_constants),
-                                   tessellation_evaluation_shader:
-                                       (tessellation_evaluation_shader,
-                                        tessellation_evaluation_shader_spec_constants),
-                               }),
+            tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: Some((shader, specialization_constants)),
+            mesh_shader: self.mesh_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
         }
     }
 
-    /// Sets the tessellation shaders stage as disabled. This is the default.
+    /// Sets the task shader stage as disabled. This is the default.
     #[inline]
-    pub fn tessellation_shaders_disabled(mut self) -> Self {
-        self.tessellation = None;
+    pub fn task_shader_disabled(mut self) -> Self {
+        self.task_shader = None;
         self
     }
 
-    /// Sets the geometry shader to use.
-    // TODO: correct specialization constants
+    /// Sets the mesh shader to use.
+    ///
+    /// The mesh shader replaces the vertex input, the vertex shader, the tessellation shaders and
+    /// the geometry shader, which must not be set. The pipeline must then be used with the
+    /// `draw_mesh_tasks` commands instead of the regular draw commands.
+    ///
+    /// Requires the `VK_EXT_mesh_shader` extension and its `mesh_shader` feature.
     #[inline]
-    pub fn geometry_shader<Gs2, Gss2>(
-        self, shader: Gs2, specialization_constants: Gss2)
-        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs2, Gss2, Fs, Fss, Rp>
-        where Gs2: GraphicsEntryPointAbstract<SpecializationConstants = Gss2>,
-              Gss2: SpecializationConstants
+    pub fn mesh_shader<Ms2, Mss2>(
+        self, shader: Ms2, specialization_constants: Mss2)
+        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms2, Mss2,
+                                   Fs, Fss, Rp>
+        where Ms2: GraphicsEntryPointAbstract<SpecializationConstants = Mss2>,
+              Mss2: SpecializationConstants
     {
         GraphicsPipelineBuilder {
             vertex_input: self.vertex_input,
@@ -1365,21 +2317,26 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly: self.input_assembly,
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
-            geometry_shader: Some((shader, specialization_constants)),
+            geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
+            mesh_shader: Some((shader, specialization_constants)),
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
         }
     }
 
-    /// Sets the geometry shader stage as disabled. This is the default.
+    /// Sets the mesh shader stage as disabled. This is the default.
     #[inline]
-    pub fn geometry_shader_disabled(mut self) -> Self {
-        self.geometry_shader = None;
+    pub fn mesh_shader_disabled(mut self) -> Self {
+        self.mesh_shader = None;
         self
     }
 
@@ -1478,6 +2435,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
+    /// Sets the front face as dynamic. It will need to be set when drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn front_face_dynamic(mut self) -> Self {
+        self.extended_dynamic.front_face = true;
+        self
+    }
+
     /// Sets backface culling as disabled. This is the default.
     #[inline]
     pub fn cull_mode_disabled(mut self) -> Self {
@@ -1511,6 +2478,16 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
+    /// Sets the cull mode as dynamic. It will need to be set when drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn cull_mode_dynamic(mut self) -> Self {
+        self.extended_dynamic.cull_mode = true;
+        self
+    }
+
     /// Sets the polygon mode to "fill". This is the default.
     #[inline]
     pub fn polygon_mode_fill(mut self) -> Self {
@@ -1547,7 +2524,98 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: missing DepthBiasControl
+    /// Disables the depth bias. This is the default.
+    #[inline]
+    pub fn depth_bias_disabled(mut self) -> Self {
+        self.raster.depth_bias = DepthBiasControl::Disabled;
+        self
+    }
+
+    /// Enables a fixed depth bias.
+    ///
+    /// A non-zero `clamp` requires the `depth_bias_clamp` feature to be enabled on the device.
+    #[inline]
+    pub fn depth_bias(mut self, bias: DepthBias) -> Self {
+        self.raster.depth_bias = DepthBiasControl::Static(bias);
+        self
+    }
+
+    /// Enables the depth bias and sets it as dynamic, which means that you will need to set its
+    /// value when drawing.
+    #[inline]
+    pub fn depth_bias_dynamic(mut self) -> Self {
+        self.raster.depth_bias = DepthBiasControl::Dynamic;
+        self
+    }
+
+    /// Enables overestimating conservative rasterization. A fragment will be generated for each
+    /// pixel that is at least partially covered by a primitive, after the primitive has been
+    /// enlarged by `extra_size` pixels in addition to an implementation-defined size.
+    /// `extra_size` must not be greater than `Device::max_extra_primitive_overestimation_size`.
+    ///
+    /// Requires the `VK_EXT_conservative_rasterization` extension to be enabled on the device.
+    #[inline]
+    pub fn conservative_rasterization_overestimate(mut self, extra_size: f32) -> Self {
+        self.raster.conservative = ConservativeRasterization::Overestimate { extra_size };
+        self
+    }
+
+    /// Enables underestimating conservative rasterization. A fragment will be generated only for
+    /// the pixels that are fully covered by a primitive.
+    ///
+    /// Requires the `VK_EXT_conservative_rasterization` extension to be enabled on the device.
+    #[inline]
+    pub fn conservative_rasterization_underestimate(mut self) -> Self {
+        self.raster.conservative = ConservativeRasterization::Underestimate;
+        self
+    }
+
+    /// Disables conservative rasterization. This is the default.
+    #[inline]
+    pub fn conservative_rasterization_disabled(mut self) -> Self {
+        self.raster.conservative = ConservativeRasterization::Disabled;
+        self
+    }
+
+    /// Sets the algorithm used to rasterize lines. The default is `LineRasterizationMode::Default`.
+    ///
+    /// Anything else than the default requires the `VK_EXT_line_rasterization` extension to be
+    /// enabled on the device, and the corresponding line rasterization feature to be supported.
+    #[inline]
+    pub fn line_rasterization_mode(mut self, mode: LineRasterizationMode) -> Self {
+        self.raster.line_rasterization_mode = mode;
+        self
+    }
+
+    /// Enables line stippling with a fixed pattern. Each bit of `pattern` covers `factor`
+    /// consecutive pixels of the line, and the pixels whose bit is 0 are discarded. `factor` must
+    /// be between 1 and 256.
+    ///
+    /// Requires the `VK_EXT_line_rasterization` extension to be enabled on the device, and the
+    /// stippled line feature of the line rasterization mode to be supported.
+    #[inline]
+    pub fn line_stipple(mut self, factor: u32, pattern: u16) -> Self {
+        self.raster.line_stipple = LineStippleControl::Static(LineStipple { factor, pattern });
+        self
+    }
+
+    /// Enables line stippling and sets the pattern as dynamic, which means that you will need to
+    /// set its value when drawing.
+    ///
+    /// Requires the `VK_EXT_line_rasterization` extension to be enabled on the device, and the
+    /// stippled line feature of the line rasterization mode to be supported.
+    #[inline]
+    pub fn line_stipple_dynamic(mut self) -> Self {
+        self.raster.line_stipple = LineStippleControl::Dynamic;
+        self
+    }
+
+    /// Disables line stippling. This is the default.
+    #[inline]
+    pub fn line_stipple_disabled(mut self) -> Self {
+        self.raster.line_stipple = LineStippleControl::Disabled;
+        self
+    }
 
     /// Disables sample shading. The fragment shader will only be run once per fragment (ie. per
     /// pixel) and not once by sample. The output will then be copied in all of the covered
@@ -1584,13 +2652,21 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: doc
+    /// Disables alpha-to-coverage.
+    ///
+    /// Alpha-to-coverage is disabled by default.
+    #[inline]
     pub fn alpha_to_coverage_disabled(mut self) -> Self {
         self.multisample.alphaToCoverageEnable = vk::FALSE;
         self
     }
 
-    // TODO: doc
+    /// Enables alpha-to-coverage. The alpha component of the first color output of the fragment
+    /// shader will be used to determine which samples of the pixel are covered. This is typically
+    /// used to render alpha-tested geometry such as foliage with multisampling.
+    ///
+    /// Alpha-to-coverage is disabled by default.
+    #[inline]
     pub fn alpha_to_coverage_enabled(mut self) -> Self {
         self.multisample.alphaToCoverageEnable = vk::TRUE;
         self
@@ -1617,7 +2693,18 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: rasterizationSamples and pSampleMask
+    /// Sets the sample mask. Bit `n % 32` of `mask[n / 32]` corresponds to sample `n`, and the
+    /// samples whose bit is 0 are never written to. Bits that don't correspond to any sample of
+    /// the subpass are ignored.
+    ///
+    /// The default value has all of its bits set.
+    #[inline]
+    pub fn sample_mask(mut self, mask: [u32; 2]) -> Self {
+        self.sample_mask = mask;
+        self
+    }
+
+    // Note: the number of rasterization samples is determined by the subpass.
 
     /// Sets the fragment shader to use.
     ///
@@ -1626,7 +2713,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     #[inline]
     pub fn fragment_shader<Fs2, Fss2>(
         self, shader: Fs2, specialization_constants: Fss2)
-        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs2, Fss2, Rp>
+        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                                   Fs2, Fss2, Rp>
         where Fs2: GraphicsEntryPointAbstract<SpecializationConstants = Fss2>,
               Fss2: SpecializationConstants
     {
@@ -1637,13 +2725,18 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
+            mesh_shader: self.mesh_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
             fragment_shader: Some((shader, specialization_constants)),
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: self.render_pass,
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
         }
     }
 
@@ -1681,15 +2774,156 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self
     }
 
-    // TODO: missing tons of depth-stencil stuff
+    /// Sets whether the depth test is enabled as dynamic. It will need to be set when drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn depth_test_enable_dynamic(mut self) -> Self {
+        self.extended_dynamic.depth_test_enable = true;
+        self
+    }
+
+    /// Sets whether the depth buffer will be written as dynamic. It will need to be set when
+    /// drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn depth_write_enable_dynamic(mut self) -> Self {
+        self.extended_dynamic.depth_write_enable = true;
+        self
+    }
+
+    /// Sets the comparison of the depth test as dynamic. It will need to be set when drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn depth_compare_op_dynamic(mut self) -> Self {
+        self.extended_dynamic.depth_compare_op = true;
+        self
+    }
+
+    /// Sets whether the depth bounds test is enabled as dynamic. It will need to be set when
+    /// drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn depth_bounds_test_enable_dynamic(mut self) -> Self {
+        self.extended_dynamic.depth_bounds_test_enable = true;
+        self
+    }
+
+    /// Sets whether the stencil test is enabled as dynamic. It will need to be set when drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn stencil_test_enable_dynamic(mut self) -> Self {
+        self.extended_dynamic.stencil_test_enable = true;
+        self
+    }
+
+    /// Sets the stencil operations of both faces as dynamic. They will need to be set when
+    /// drawing.
+    ///
+    /// Requires the `extended_dynamic_state` feature of the `VK_EXT_extended_dynamic_state`
+    /// extension to be enabled on the device.
+    #[inline]
+    pub fn stencil_op_dynamic(mut self) -> Self {
+        self.extended_dynamic.stencil_op = true;
+        self
+    }
+
+    /// Sets the comparison to use between the depth value of each fragment and the depth value
+    /// currently in the depth buffer. Use `Compare::Always` to disable the depth test.
+    #[inline]
+    pub fn depth_compare(mut self, compare: Compare) -> Self {
+        self.depth_stencil.depth_compare = compare;
+        self
+    }
+
+    /// Enables the depth bounds test. Fragments whose depth value in the depth buffer is outside
+    /// of `range` are discarded. The bounds must be between 0.0 and 1.0.
+    ///
+    /// The `depth_bounds` feature must be enabled on the device.
+    #[inline]
+    pub fn depth_bounds(mut self, range: Range<f32>) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Fixed(range);
+        self
+    }
+
+    /// Enables the depth bounds test and sets the bounds as dynamic, which means that you will
+    /// need to set them when drawing.
+    ///
+    /// The `depth_bounds` feature must be enabled on the device.
+    #[inline]
+    pub fn depth_bounds_dynamic(mut self) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Dynamic;
+        self
+    }
+
+    /// Disables the depth bounds test. This is the default.
+    #[inline]
+    pub fn depth_bounds_disabled(mut self) -> Self {
+        self.depth_stencil.depth_bounds_test = DepthBounds::Disabled;
+        self
+    }
+
+    /// Sets the stencil test of both the front and the back faces.
+    ///
+    /// The compare masks, write masks and references that are `None` are dynamic and will need to
+    /// be set when drawing.
+    #[inline]
+    pub fn stencil(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_front = stencil;
+        self.depth_stencil.stencil_back = stencil;
+        self
+    }
+
+    /// Sets the stencil test of the faces that are facing the user. Points and lines always use
+    /// this stencil test.
+    ///
+    /// If a compare mask, write mask or reference is dynamic (ie. `None`), it must also be dynamic
+    /// for the back faces.
+    #[inline]
+    pub fn stencil_front(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_front = stencil;
+        self
+    }
+
+    /// Sets the stencil test of the faces that are facing away from the user.
+    ///
+    /// If a compare mask, write mask or reference is dynamic (ie. `None`), it must also be dynamic
+    /// for the front faces.
+    #[inline]
+    pub fn stencil_back(mut self, stencil: Stencil) -> Self {
+        self.depth_stencil.stencil_back = stencil;
+        self
+    }
+
+    /// Disables the stencil test. This is the default.
+    #[inline]
+    pub fn stencil_disabled(mut self) -> Self {
+        self.depth_stencil.stencil_front = Default::default();
+        self.depth_stencil.stencil_back = Default::default();
+        self
+    }
 
 
+    /// Sets the blending of all the color attachments of the subpass to the same value.
     #[inline]
     pub fn blend_collective(mut self, blend: AttachmentBlend) -> Self {
         self.blend.attachments = AttachmentsBlend::Collective(blend);
         self
     }
 
+    /// Sets the blending of each color attachment of the subpass individually.
+    ///
+    /// The iterator must produce one element per color attachment of the subpass, in order. If
+    /// they are not all identical, the `independent_blend` feature must be enabled on the device.
     #[inline]
     pub fn blend_individual<I>(mut self, blend: I) -> Self
         where I: IntoIterator<Item = AttachmentBlend>
@@ -1705,11 +2939,17 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
         self.blend_collective(AttachmentBlend::pass_through())
     }
 
+    /// Each fragment shader output will be merged with the existing value of the framebuffer
+    /// attachment based on its alpha.
     #[inline]
     pub fn blend_alpha_blending(self) -> Self {
         self.blend_collective(AttachmentBlend::alpha_blending())
     }
 
+    /// Sets the logic operation to apply to the attachments with an integer format, instead of
+    /// blending.
+    ///
+    /// The `logic_op` feature must be enabled on the device.
     #[inline]
     pub fn blend_logic_op(mut self, logic_op: LogicOp) -> Self {
         self.blend.logic_op = Some(logic_op);
@@ -1745,7 +2985,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
     #[inline]
     pub fn render_pass<Rp2>(
         self, subpass: Subpass<Rp2>)
-        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp2> {
+        -> GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                                   Fs, Fss, Rp2> {
         GraphicsPipelineBuilder {
             vertex_input: self.vertex_input,
             vertex_shader: self.vertex_shader,
@@ -1753,19 +2994,36 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation,
             geometry_shader: self.geometry_shader,
+            task_shader: self.task_shader,
+            mesh_shader: self.mesh_shader,
             viewport: self.viewport,
             raster: self.raster,
             multisample: self.multisample,
+            sample_mask: self.sample_mask,
             fragment_shader: self.fragment_shader,
             depth_stencil: self.depth_stencil,
             blend: self.blend,
             render_pass: Some(subpass),
+            cache: self.cache,
+            extended_dynamic: self.extended_dynamic,
         }
     }
+
+    /// Sets the pipeline cache to use when building the pipeline.
+    ///
+    /// The Vulkan implementation will look in the cache for an existing pipeline, or add one if
+    /// it doesn't exist. The cache must have been created with the same device as the one
+    /// passed to `build`, otherwise building panics.
+    #[inline]
+    pub fn pipeline_cache(mut self, cache: Arc<PipelineCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
 }
 
-impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> Clone
-    for GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp>
+impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss, Fs, Fss, Rp> Clone
+    for GraphicsPipelineBuilder<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Ts, Tss, Ms, Mss,
+                                Fs, Fss, Rp>
     where Vdef: Clone,
           Vs: Clone,
           Vss: Clone,
@@ -1775,6 +3033,10 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> Clone
           Tess: Clone,
           Gs: Clone,
           Gss: Clone,
+          Ts: Clone,
+          Tss: Clone,
+          Ms: Clone,
+          Mss: Clone,
           Fs: Clone,
           Fss: Clone,
           Rp: Clone
@@ -1787,6 +3049,8 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> Clone
             input_assembly_topology: self.input_assembly_topology,
             tessellation: self.tessellation.clone(),
             geometry_shader: self.geometry_shader.clone(),
+            task_shader: self.task_shader.clone(),
+            mesh_shader: self.mesh_shader.clone(),
             viewport: self.viewport.clone(),
             raster: self.raster.clone(),
             multisample: vk::PipelineMultisampleStateCreateInfo {
@@ -1800,10 +3064,230 @@ impl<Vdef, Vs, Vss, Tcs, Tcss, Tes, Tess, Gs, Gss, Fs, Fss, Rp> Clone
                 alphaToCoverageEnable: self.multisample.alphaToCoverageEnable,
                 alphaToOneEnable: self.multisample.alphaToOneEnable,
             },
+            sample_mask: self.sample_mask,
             fragment_shader: self.fragment_shader.clone(),
             depth_stencil: self.depth_stencil.clone(),
             blend: self.blend.clone(),
             render_pass: self.render_pass.clone(),
+            cache: self.cache.clone(),
+            extended_dynamic: self.extended_dynamic,
+        }
+    }
+}
+
+// The input and output interfaces of a shader stage.
+struct StageInterfaces {
+    input: RuntimeShaderInterfaceDef,
+    output: RuntimeShaderInterfaceDef,
+}
+
+impl StageInterfaces {
+    fn of<S>(shader: &S) -> StageInterfaces
+        where S: GraphicsEntryPointAbstract
+    {
+        // The elements come from definitions that already uphold the requirements of
+        // `RuntimeShaderInterfaceDef`.
+        unsafe {
+            StageInterfaces {
+                input: RuntimeShaderInterfaceDef::new(shader.input().elements().collect()),
+                output: RuntimeShaderInterfaceDef::new(shader.output().elements().collect()),
+            }
+        }
+    }
+}
+
+// Checks that the input interface of each shader stage matches the output interface of the
+// previous stage. The stages that are `None` are skipped, which is the case of the optional
+// stages and of the stages that don't belong to the parts of a pipeline library.
+fn check_stage_interfaces(vertex: Option<&StageInterfaces>,
+                          tessellation_control: Option<&StageInterfaces>,
+                          tessellation_evaluation: Option<&StageInterfaces>,
+                          geometry: Option<&StageInterfaces>, task: Option<&StageInterfaces>,
+                          mesh: Option<&StageInterfaces>, fragment: Option<&StageInterfaces>)
+                          -> Result<(), GraphicsPipelineCreationError> {
+    // Mesh shading replaces the vertex, tessellation and geometry stages.
+    if let Some(ms) = mesh {
+        if let Some(ts) = task {
+            if let Err(err) = ms.input.matches(&ts.output) {
+                return Err(GraphicsPipelineCreationError::TaskMeshStagesMismatch(err));
+            }
+        }
+        if let Some(fs) = fragment {
+            if let Err(err) = fs.input.matches(&ms.output) {
+                return Err(GraphicsPipelineCreationError::MeshFragmentStagesMismatch(err));
+            }
+        }
+        return Ok(());
+    }
+
+    if let (Some(vs), Some(tcs)) = (vertex, tessellation_control) {
+        if let Err(err) = tcs.input.matches(&vs.output) {
+            return Err(GraphicsPipelineCreationError::VertexTessControlStagesMismatch(err));
+        }
+    }
+    if let (Some(tcs), Some(tes)) = (tessellation_control, tessellation_evaluation) {
+        if let Err(err) = tes.input.matches(&tcs.output) {
+            return Err(GraphicsPipelineCreationError::TessControlTessEvalStagesMismatch(err));
+        }
+    }
+
+    if let Some(gs) = geometry {
+        if let Some(tes) = tessellation_evaluation {
+            if let Err(err) = gs.input.matches(&tes.output) {
+                return Err(GraphicsPipelineCreationError::TessEvalGeometryStagesMismatch(err));
+            }
+        } else if let Some(vs) = vertex {
+            if let Err(err) = gs.input.matches(&vs.output) {
+                return Err(GraphicsPipelineCreationError::VertexGeometryStagesMismatch(err));
+            }
+        }
+        if let Some(fs) = fragment {
+            if let Err(err) = fs.input.matches(&gs.output) {
+                return Err(GraphicsPipelineCreationError::GeometryFragmentStagesMismatch(err));
+            }
+        }
+    } else if let Some(fs) = fragment {
+        if let Some(tes) = tessellation_evaluation {
+            if let Err(err) = fs.input.matches(&tes.output) {
+                return Err(GraphicsPipelineCreationError::TessEvalFragmentStagesMismatch(err));
+            }
+        } else if let Some(vs) = vertex {
+            if let Err(err) = fs.input.matches(&vs.output) {
+                return Err(GraphicsPipelineCreationError::VertexFragmentStagesMismatch(err));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use pipeline::graphics_pipeline::GraphicsPipelineCreationError;
+    use pipeline::shader::RuntimeShaderInterfaceDef;
+    use pipeline::shader::ShaderInterfaceDefEntry;
+    use pipeline::shader::ShaderInterfaceMismatchError;
+
+    use super::StageInterfaces;
+    use super::check_stage_interfaces;
+
+    // Builds an interface with one element per location, in order.
+    fn interface(formats: &[Format]) -> RuntimeShaderInterfaceDef {
+        let elements = formats
+            .iter()
+            .enumerate()
+            .map(|(loc, &format)| {
+                ShaderInterfaceDefEntry {
+                    location: loc as u32 .. loc as u32 + 1,
+                    format: format,
+                    name: None,
+                }
+            })
+            .collect();
+        unsafe { RuntimeShaderInterfaceDef::new(elements) }
+    }
+
+    fn stage(input: &[Format], output: &[Format]) -> StageInterfaces {
+        StageInterfaces {
+            input: interface(input),
+            output: interface(output),
+        }
+    }
+
+    const VEC2: Format = Format::R32G32Sfloat;
+    const VEC4: Format = Format::R32G32B32A32Sfloat;
+
+    #[test]
+    fn matching_stages() {
+        let vs = stage(&[VEC4], &[VEC2]);
+        let tcs = stage(&[VEC2], &[VEC4]);
+        let tes = stage(&[VEC4], &[VEC4, VEC2]);
+        let gs = stage(&[VEC4, VEC2], &[VEC2]);
+        let fs = stage(&[VEC2], &[VEC4]);
+
+        check_stage_interfaces(Some(&vs), Some(&tcs), Some(&tes), Some(&gs), None, None, Some(&fs))
+            .unwrap();
+        check_stage_interfaces(Some(&vs), None, None, None, None, None, Some(&tcs)).unwrap();
+    }
+
+    #[test]
+    fn vertex_fragment_mismatch() {
+        let vs = stage(&[], &[VEC4]);
+        let fs = stage(&[VEC2], &[VEC4]);
+
+        match check_stage_interfaces(Some(&vs), None, None, None, None, None, Some(&fs)) {
+            Err(GraphicsPipelineCreationError::VertexFragmentStagesMismatch(
+                ShaderInterfaceMismatchError::FormatMismatch { location: 0, .. })) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn tessellation_mismatch() {
+        let vs = stage(&[], &[VEC4]);
+        let tcs = stage(&[VEC4], &[VEC4]);
+        let tes = stage(&[VEC4, VEC4], &[VEC2]);
+        let fs = stage(&[VEC4], &[VEC4]);
+
+        match check_stage_interfaces(Some(&vs), Some(&tcs), Some(&tes), None, None, None, Some(&fs))
+        {
+            Err(GraphicsPipelineCreationError::TessControlTessEvalStagesMismatch(
+                ShaderInterfaceMismatchError::ElementsCountMismatch {
+                    self_elements: 2,
+                    other_elements: 1,
+                })) => (),
+            _ => panic!(),
+        }
+
+        let tes = stage(&[VEC4], &[VEC2]);
+        match check_stage_interfaces(Some(&vs), Some(&tcs), Some(&tes), None, None, None, Some(&fs))
+        {
+            Err(GraphicsPipelineCreationError::TessEvalFragmentStagesMismatch(_)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn geometry_mismatch() {
+        let vs = stage(&[], &[VEC4]);
+        let gs = stage(&[VEC2], &[VEC4]);
+        let fs = stage(&[VEC4], &[VEC4]);
+
+        match check_stage_interfaces(Some(&vs), None, None, Some(&gs), None, None, Some(&fs)) {
+            Err(GraphicsPipelineCreationError::VertexGeometryStagesMismatch(_)) => (),
+            _ => panic!(),
+        }
+
+        let gs = stage(&[VEC4], &[VEC2]);
+        match check_stage_interfaces(Some(&vs), None, None, Some(&gs), None, None, Some(&fs)) {
+            Err(GraphicsPipelineCreationError::GeometryFragmentStagesMismatch(_)) => (),
+            _ => panic!(),
         }
     }
+
+    #[test]
+    fn mesh_mismatch() {
+        let ts = stage(&[], &[VEC4]);
+        let ms = stage(&[VEC2], &[VEC4]);
+        let fs = stage(&[VEC4], &[VEC4]);
+
+        match check_stage_interfaces(None, None, None, None, Some(&ts), Some(&ms), Some(&fs)) {
+            Err(GraphicsPipelineCreationError::TaskMeshStagesMismatch(_)) => (),
+            _ => panic!(),
+        }
+
+        let fs = stage(&[VEC2], &[VEC4]);
+        match check_stage_interfaces(None, None, None, None, None, Some(&ms), Some(&fs)) {
+            Err(GraphicsPipelineCreationError::MeshFragmentStagesMismatch(_)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn missing_stages_not_checked() {
+        // A library that only contains the fragment shader doesn't know the previous stage.
+        let fs = stage(&[VEC2], &[VEC4]);
+        check_stage_interfaces(None, None, None, None, None, None, Some(&fs)).unwrap();
+    }
 }
@@ -131,9 +131,98 @@ pub enum GraphicsPipelineCreationError {
     /// from `Fill`.
     FillModeNonSolidFeatureNotEnabled,
 
+    /// The `VK_EXT_conservative_rasterization` extension must be enabled in order to use
+    /// conservative rasterization.
+    ConservativeRasterizationExtensionNotEnabled,
+
+    /// The extra primitive overestimation size of conservative rasterization must be between 0.0
+    /// and the `maxExtraPrimitiveOverestimationSize` limit of the device.
+    InvalidExtraPrimitiveOverestimationSize,
+
+    /// The `VK_EXT_line_rasterization` extension must be enabled in order to use a non-default
+    /// line rasterization mode or line stippling.
+    LineRasterizationExtensionNotEnabled,
+
+    /// The requested line rasterization mode isn't supported by the device.
+    LineRasterizationModeFeatureNotEnabled,
+
+    /// Line stippling isn't supported by the device for the requested line rasterization mode.
+    LineStippleFeatureNotEnabled,
+
+    /// The line stipple factor must be between 1 and 256.
+    InvalidLineStippleFactor,
+
+    /// The `VK_EXT_vertex_attribute_divisor` extension must be enabled in order to use an
+    /// instance divisor different from 1.
+    VertexAttributeDivisorExtensionNotEnabled,
+
+    /// The `instance_rate_divisor` feature of `VK_EXT_vertex_attribute_divisor` must be supported
+    /// in order to use an instance divisor different from 1.
+    VertexAttributeInstanceRateDivisorFeatureNotEnabled,
+
+    /// The `instance_rate_zero_divisor` feature of `VK_EXT_vertex_attribute_divisor` must be
+    /// supported in order to use an instance divisor of 0.
+    VertexAttributeInstanceRateZeroDivisorFeatureNotEnabled,
+
+    /// The instance divisor of a vertex input binding is larger than the `maxVertexAttribDivisor`
+    /// limit of the device.
+    MaxVertexAttribDivisorExceeded {
+        /// Index of the faulty binding.
+        binding: usize,
+        /// Maximum allowed value.
+        max: u32,
+        /// Value that was passed.
+        obtained: u32,
+    },
+
+    /// The `VK_EXT_graphics_pipeline_library` extension must be enabled in order to build or link
+    /// graphics pipeline libraries.
+    GraphicsPipelineLibraryExtensionNotEnabled,
+
+    /// The `graphics_pipeline_library` feature must be supported in order to build or link
+    /// graphics pipeline libraries.
+    GraphicsPipelineLibraryFeatureNotEnabled,
+
+    /// A graphics pipeline library must contain at least one part of the pipeline.
+    EmptyGraphicsPipelineLibrary,
+
+    /// Some of the libraries passed when linking contain the same part of the pipeline.
+    OverlappingGraphicsPipelineLibraries,
+
+    /// The libraries passed when linking don't contain all the parts of the pipeline.
+    IncompleteGraphicsPipelineLibraries,
+
+    /// The libraries passed when linking don't all belong to the same device.
+    GraphicsPipelineLibrariesDeviceMismatch,
+
+    /// The `VK_EXT_mesh_shader` extension must be enabled in order to use a mesh shader.
+    MeshShaderExtensionNotEnabled,
+
+    /// The `mesh_shader` feature must be enabled in order to use a mesh shader.
+    MeshShaderFeatureNotEnabled,
+
+    /// The `task_shader` feature must be enabled in order to use a task shader.
+    TaskShaderFeatureNotEnabled,
+
+    /// A task shader was set without a mesh shader.
+    TaskShaderWithoutMeshShader,
+
+    /// A mesh shader can't be used together with a vertex, tessellation or geometry shader.
+    MeshShaderWithVertexStages,
+
+    /// The interface between the task shader and the mesh shader mismatches.
+    TaskMeshStagesMismatch(ShaderInterfaceMismatchError),
+
+    /// The interface between the mesh shader and the fragment shader mismatches.
+    MeshFragmentStagesMismatch(ShaderInterfaceMismatchError),
+
     /// The `depth_bounds` feature must be enabled in order to use depth bounds testing.
     DepthBoundsFeatureNotEnabled,
 
+    /// The depth bounds must be between 0.0 and 1.0, and the minimum must not be greater than the
+    /// maximum.
+    DepthBoundsOutOfRange,
+
     /// The requested stencil test is invalid.
     WrongStencilState,
 
@@ -146,6 +235,14 @@ pub enum GraphicsPipelineCreationError {
     /// The `tessellation_shader` feature must be enabled in order to use tessellation shaders.
     TessellationShaderFeatureNotEnabled,
 
+    /// The `VK_EXT_extended_dynamic_state` extension must be enabled in order to use the states
+    /// that it makes dynamic.
+    ExtendedDynamicStateExtensionNotEnabled,
+
+    /// The `extended_dynamic_state` feature must be enabled in order to use the states that the
+    /// `VK_EXT_extended_dynamic_state` extension makes dynamic.
+    ExtendedDynamicStateFeatureNotEnabled,
+
     /// The number of attachments specified in the blending does not match the number of
     /// attachments in the subpass.
     MismatchBlendingAttachmentsCount,
@@ -157,6 +254,10 @@ pub enum GraphicsPipelineCreationError {
     /// The `logic_op` feature must be enabled in order to use logic operations.
     LogicOpFeatureNotEnabled,
 
+    /// The `dual_src_blend` feature must be enabled in order to use blend factors that read the
+    /// second output of the fragment shader.
+    DualSrcBlendFeatureNotEnabled,
+
     /// The depth test requires a depth attachment but render pass has no depth attachment, or
     /// depth writing is enabled and the depth attachment is read-only.
     NoDepthAttachment,
@@ -182,6 +283,30 @@ pub enum GraphicsPipelineCreationError {
 
     /// The `alpha_to_one` feature must be enabled in order to use alpha-to-one.
     AlphaToOneFeatureNotEnabled,
+
+    /// The device only implements a subset of the Vulkan API, and the `triangle_fans` feature
+    /// must be enabled in order to use the triangle fan topology.
+    TriangleFansFeatureNotEnabled,
+
+    /// The device only implements a subset of the Vulkan API, and the `point_polygons` feature
+    /// must be enabled in order to rasterize polygons as points.
+    PointPolygonsFeatureNotEnabled,
+
+    /// The device only implements a subset of the Vulkan API, and the
+    /// `constant_alpha_color_blend_factors` feature must be enabled in order to use the alpha
+    /// component of the blend constants as a color blend factor.
+    ConstantAlphaColorBlendFactorsFeatureNotEnabled,
+
+    /// The device only implements a subset of the Vulkan API, and the stride of a vertex input
+    /// binding is not a multiple of the `min_vertex_input_binding_stride_alignment` property.
+    VertexInputBindingStrideNotAligned {
+        /// Index of the faulty binding.
+        binding: usize,
+        /// Required alignment.
+        alignment: usize,
+        /// Value that was passed.
+        obtained: usize,
+    },
 }
 
 impl error::Error for GraphicsPipelineCreationError {
@@ -270,9 +395,92 @@ impl error::Error for GraphicsPipelineCreationError {
                 "the `fill_mode_non_solid` feature must be enabled in order to use a polygon mode \
                  different from `Fill`"
             },
+            GraphicsPipelineCreationError::ConservativeRasterizationExtensionNotEnabled => {
+                "the `VK_EXT_conservative_rasterization` extension must be enabled in order to use \
+                 conservative rasterization"
+            },
+            GraphicsPipelineCreationError::InvalidExtraPrimitiveOverestimationSize => {
+                "the extra primitive overestimation size of conservative rasterization must be \
+                 between 0.0 and the `maxExtraPrimitiveOverestimationSize` limit of the device"
+            },
+            GraphicsPipelineCreationError::LineRasterizationExtensionNotEnabled => {
+                "the `VK_EXT_line_rasterization` extension must be enabled in order to use a \
+                 non-default line rasterization mode or line stippling"
+            },
+            GraphicsPipelineCreationError::LineRasterizationModeFeatureNotEnabled => {
+                "the requested line rasterization mode isn't supported by the device"
+            },
+            GraphicsPipelineCreationError::LineStippleFeatureNotEnabled => {
+                "line stippling isn't supported by the device for the requested line \
+                 rasterization mode"
+            },
+            GraphicsPipelineCreationError::InvalidLineStippleFactor => {
+                "the line stipple factor must be between 1 and 256"
+            },
+            GraphicsPipelineCreationError::VertexAttributeDivisorExtensionNotEnabled => {
+                "the `VK_EXT_vertex_attribute_divisor` extension must be enabled in order to use \
+                 an instance divisor different from 1"
+            },
+            GraphicsPipelineCreationError::VertexAttributeInstanceRateDivisorFeatureNotEnabled => {
+                "the `instance_rate_divisor` feature must be supported in order to use an \
+                 instance divisor different from 1"
+            },
+            GraphicsPipelineCreationError::VertexAttributeInstanceRateZeroDivisorFeatureNotEnabled => {
+                "the `instance_rate_zero_divisor` feature must be supported in order to use an \
+                 instance divisor of 0"
+            },
+            GraphicsPipelineCreationError::MaxVertexAttribDivisorExceeded { .. } => {
+                "the instance divisor of a vertex input binding is larger than the \
+                 `maxVertexAttribDivisor` limit of the device"
+            },
+            GraphicsPipelineCreationError::GraphicsPipelineLibraryExtensionNotEnabled => {
+                "the `VK_EXT_graphics_pipeline_library` extension must be enabled in order to \
+                 build or link graphics pipeline libraries"
+            },
+            GraphicsPipelineCreationError::GraphicsPipelineLibraryFeatureNotEnabled => {
+                "the `graphics_pipeline_library` feature must be supported in order to build or \
+                 link graphics pipeline libraries"
+            },
+            GraphicsPipelineCreationError::EmptyGraphicsPipelineLibrary => {
+                "a graphics pipeline library must contain at least one part of the pipeline"
+            },
+            GraphicsPipelineCreationError::OverlappingGraphicsPipelineLibraries => {
+                "some of the libraries passed when linking contain the same part of the pipeline"
+            },
+            GraphicsPipelineCreationError::IncompleteGraphicsPipelineLibraries => {
+                "the libraries passed when linking don't contain all the parts of the pipeline"
+            },
+            GraphicsPipelineCreationError::GraphicsPipelineLibrariesDeviceMismatch => {
+                "the libraries passed when linking don't all belong to the same device"
+            },
+            GraphicsPipelineCreationError::MeshShaderExtensionNotEnabled => {
+                "the `VK_EXT_mesh_shader` extension must be enabled in order to use a mesh shader"
+            },
+            GraphicsPipelineCreationError::MeshShaderFeatureNotEnabled => {
+                "the `mesh_shader` feature must be enabled in order to use a mesh shader"
+            },
+            GraphicsPipelineCreationError::TaskShaderFeatureNotEnabled => {
+                "the `task_shader` feature must be enabled in order to use a task shader"
+            },
+            GraphicsPipelineCreationError::TaskShaderWithoutMeshShader => {
+                "a task shader was set without a mesh shader"
+            },
+            GraphicsPipelineCreationError::MeshShaderWithVertexStages => {
+                "a mesh shader can't be used together with a vertex, tessellation or geometry shader"
+            },
+            GraphicsPipelineCreationError::TaskMeshStagesMismatch(_) => {
+                "the interface between the task shader and the mesh shader mismatches"
+            },
+            GraphicsPipelineCreationError::MeshFragmentStagesMismatch(_) => {
+                "the interface between the mesh shader and the fragment shader mismatches"
+            },
             GraphicsPipelineCreationError::DepthBoundsFeatureNotEnabled => {
                 "the `depth_bounds` feature must be enabled in order to use depth bounds testing"
             },
+            GraphicsPipelineCreationError::DepthBoundsOutOfRange => {
+                "the depth bounds must be between 0.0 and 1.0, and the minimum must not be \
+                 greater than the maximum"
+            },
             GraphicsPipelineCreationError::WrongStencilState => {
                 "the requested stencil test is invalid"
             },
@@ -286,6 +494,14 @@ impl error::Error for GraphicsPipelineCreationError {
                 "the `tessellation_shader` feature must be enabled in order to use tessellation \
                  shaders"
             },
+            GraphicsPipelineCreationError::ExtendedDynamicStateExtensionNotEnabled => {
+                "the `VK_EXT_extended_dynamic_state` extension must be enabled in order to use the \
+                 states that it makes dynamic"
+            },
+            GraphicsPipelineCreationError::ExtendedDynamicStateFeatureNotEnabled => {
+                "the `extended_dynamic_state` feature must be enabled in order to use the states \
+                 that the `VK_EXT_extended_dynamic_state` extension makes dynamic"
+            },
             GraphicsPipelineCreationError::MismatchBlendingAttachmentsCount => {
                 "the number of attachments specified in the blending does not match the number of \
                  attachments in the subpass"
@@ -297,6 +513,10 @@ impl error::Error for GraphicsPipelineCreationError {
             GraphicsPipelineCreationError::LogicOpFeatureNotEnabled => {
                 "the `logic_op` feature must be enabled in order to use logic operations"
             },
+            GraphicsPipelineCreationError::DualSrcBlendFeatureNotEnabled => {
+                "the `dual_src_blend` feature must be enabled in order to use blend factors that \
+                 read the second output of the fragment shader"
+            },
             GraphicsPipelineCreationError::NoDepthAttachment => {
                 "the depth attachment of the render pass does not match the depth test"
             },
@@ -319,6 +539,21 @@ impl error::Error for GraphicsPipelineCreationError {
             GraphicsPipelineCreationError::AlphaToOneFeatureNotEnabled => {
                 "the `alpha_to_one` feature must be enabled in order to use alpha-to-one"
             },
+            GraphicsPipelineCreationError::TriangleFansFeatureNotEnabled => {
+                "the `triangle_fans` feature must be enabled in order to use the triangle fan \
+                 topology"
+            },
+            GraphicsPipelineCreationError::PointPolygonsFeatureNotEnabled => {
+                "the `point_polygons` feature must be enabled in order to rasterize polygons as \
+                 points"
+            },
+            GraphicsPipelineCreationError::ConstantAlphaColorBlendFactorsFeatureNotEnabled => {
+                "the `constant_alpha_color_blend_factors` feature must be enabled in order to use \
+                 the alpha component of the blend constants as a color blend factor"
+            },
+            GraphicsPipelineCreationError::VertexInputBindingStrideNotAligned { .. } => {
+                "the stride of a vertex input binding is not a multiple of the required alignment"
+            },
         }
     }
 
@@ -334,6 +569,8 @@ impl error::Error for GraphicsPipelineCreationError {
             GraphicsPipelineCreationError::TessEvalGeometryStagesMismatch(ref err) => Some(err),
             GraphicsPipelineCreationError::TessEvalFragmentStagesMismatch(ref err) => Some(err),
             GraphicsPipelineCreationError::GeometryFragmentStagesMismatch(ref err) => Some(err),
+            GraphicsPipelineCreationError::TaskMeshStagesMismatch(ref err) => Some(err),
+            GraphicsPipelineCreationError::MeshFragmentStagesMismatch(ref err) => Some(err),
             GraphicsPipelineCreationError::IncompatibleVertexDefinition(ref err) => Some(err),
             _ => None,
         }
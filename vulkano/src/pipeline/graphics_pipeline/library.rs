@@ -0,0 +1,627 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use smallvec::SmallVec;
+use std::fmt;
+use std::ops::BitOr;
+use std::ptr;
+use std::sync::Arc;
+
+use VulkanObject;
+use check_errors;
+use descriptor::PipelineLayoutAbstract;
+use device::Device;
+use device::DeviceOwned;
+use framebuffer::Subpass;
+use pipeline::cache::PipelineCache;
+use pipeline::creation_feedback::FeedbackStorage;
+use pipeline::graphics_pipeline::GraphicsPipeline;
+use pipeline::graphics_pipeline::GraphicsPipelineCreationError;
+use pipeline::graphics_pipeline::Inner as GraphicsPipelineInner;
+use vk;
+
+/// Parts of a graphics pipeline that a `GraphicsPipelineLibrary` contains.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GraphicsPipelineLibraryParts {
+    /// The vertex definition and the input assembly state.
+    pub vertex_input_interface: bool,
+    /// The vertex, tessellation and geometry shaders, and the viewport and rasterization states.
+    pub pre_rasterization_shaders: bool,
+    /// The fragment shader, and the depth and stencil states.
+    pub fragment_shader: bool,
+    /// The blending and multisample states, and the subpass.
+    pub fragment_output_interface: bool,
+}
+
+impl GraphicsPipelineLibraryParts {
+    /// Creates a `GraphicsPipelineLibraryParts` struct with all the parts set to `true`.
+    #[inline]
+    pub fn all() -> GraphicsPipelineLibraryParts {
+        GraphicsPipelineLibraryParts {
+            vertex_input_interface: true,
+            pre_rasterization_shaders: true,
+            fragment_shader: true,
+            fragment_output_interface: true,
+        }
+    }
+
+    /// Creates a `GraphicsPipelineLibraryParts` struct with all the parts set to `false`.
+    #[inline]
+    pub fn none() -> GraphicsPipelineLibraryParts {
+        GraphicsPipelineLibraryParts {
+            vertex_input_interface: false,
+            pre_rasterization_shaders: false,
+            fragment_shader: false,
+            fragment_output_interface: false,
+        }
+    }
+
+    /// Checks whether any of the parts in `self` are also present in `other`.
+    #[inline]
+    pub fn intersects(&self, other: &GraphicsPipelineLibraryParts) -> bool {
+        (self.vertex_input_interface && other.vertex_input_interface) ||
+            (self.pre_rasterization_shaders && other.pre_rasterization_shaders) ||
+            (self.fragment_shader && other.fragment_shader) ||
+            (self.fragment_output_interface && other.fragment_output_interface)
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan_bits(self) -> vk::GraphicsPipelineLibraryFlagsEXT {
+        let mut result = 0;
+        if self.vertex_input_interface {
+            result |= vk::GRAPHICS_PIPELINE_LIBRARY_VERTEX_INPUT_INTERFACE_BIT_EXT;
+        }
+        if self.pre_rasterization_shaders {
+            result |= vk::GRAPHICS_PIPELINE_LIBRARY_PRE_RASTERIZATION_SHADERS_BIT_EXT;
+        }
+        if self.fragment_shader {
+            result |= vk::GRAPHICS_PIPELINE_LIBRARY_FRAGMENT_SHADER_BIT_EXT;
+        }
+        if self.fragment_output_interface {
+            result |= vk::GRAPHICS_PIPELINE_LIBRARY_FRAGMENT_OUTPUT_INTERFACE_BIT_EXT;
+        }
+        result
+    }
+}
+
+impl BitOr for GraphicsPipelineLibraryParts {
+    type Output = GraphicsPipelineLibraryParts;
+
+    #[inline]
+    fn bitor(self, other: GraphicsPipelineLibraryParts) -> GraphicsPipelineLibraryParts {
+        GraphicsPipelineLibraryParts {
+            vertex_input_interface: self.vertex_input_interface || other.vertex_input_interface,
+            pre_rasterization_shaders: self.pre_rasterization_shaders ||
+                other.pre_rasterization_shaders,
+            fragment_shader: self.fragment_shader || other.fragment_shader,
+            fragment_output_interface: self.fragment_output_interface ||
+                other.fragment_output_interface,
+        }
+    }
+}
+
+/// Part of a graphics pipeline, compiled ahead of time with the
+/// `VK_EXT_graphics_pipeline_library` extension.
+///
+/// A library is created with `GraphicsPipelineBuilder::build_library`. Libraries that together
+/// contain all the parts of a pipeline can then be linked into a `GraphicsPipeline` with
+/// `GraphicsPipeline::link`. Linking is much faster than building the whole pipeline, which
+/// makes it possible to create the permutations of a pipeline right before drawing.
+///
+/// A library can't be used for drawing by itself.
+pub struct GraphicsPipelineLibrary<Mv, L, Rp> {
+    // Handle and states of the library. The other members are kept outside of it so that
+    // libraries of different types can be linked together.
+    pipeline: GraphicsPipeline<(), (), ()>,
+    layout: L,
+    vertex_definition: Mv,
+    // Always `Some` if the library contains another part than the vertex input interface.
+    subpass: Option<Subpass<Rp>>,
+    parts: GraphicsPipelineLibraryParts,
+}
+
+impl<Mv, L, Rp> GraphicsPipelineLibrary<Mv, L, Rp> {
+    #[inline]
+    pub(super) fn new(pipeline: GraphicsPipeline<(), (), ()>, layout: L, vertex_definition: Mv,
+                      subpass: Option<Subpass<Rp>>, parts: GraphicsPipelineLibraryParts)
+                      -> GraphicsPipelineLibrary<Mv, L, Rp> {
+        GraphicsPipelineLibrary {
+            pipeline: pipeline,
+            layout: layout,
+            vertex_definition: vertex_definition,
+            subpass: subpass,
+            parts: parts,
+        }
+    }
+
+    /// Returns the parts of the pipeline that this library contains.
+    #[inline]
+    pub fn parts(&self) -> GraphicsPipelineLibraryParts {
+        self.parts
+    }
+
+    /// Returns the pipeline layout used to create this library.
+    #[inline]
+    pub fn layout(&self) -> &L {
+        &self.layout
+    }
+}
+
+/// Trait implemented on all the graphics pipeline libraries, whatever their vertex definition,
+/// pipeline layout and render pass. Can be made into a trait object.
+pub unsafe trait GraphicsPipelineLibraryAbstract: DeviceOwned {
+    /// Returns the parts of the pipeline that this library contains.
+    fn parts(&self) -> GraphicsPipelineLibraryParts;
+
+    /// Returns the handle and the states of the library.
+    #[doc(hidden)]
+    fn pipeline(&self) -> &GraphicsPipeline<(), (), ()>;
+}
+
+unsafe impl<Mv, L, Rp> GraphicsPipelineLibraryAbstract for GraphicsPipelineLibrary<Mv, L, Rp> {
+    #[inline]
+    fn parts(&self) -> GraphicsPipelineLibraryParts {
+        self.parts
+    }
+
+    #[inline]
+    fn pipeline(&self) -> &GraphicsPipeline<(), (), ()> {
+        &self.pipeline
+    }
+}
+
+impl<Mv, Pl, Rp> GraphicsPipeline<Mv, Pl, Rp>
+    where Mv: Clone,
+          Pl: PipelineLayoutAbstract,
+          Rp: Clone
+{
+    /// Links graphics pipeline libraries into a complete graphics pipeline.
+    ///
+    /// The vertex definition of the pipeline is taken from `vertex_input`, and its subpass from
+    /// `fragment_output`. These can be the same library. `others` contains the libraries with the
+    /// rest of the parts. Each part of the pipeline must be contained in exactly one of the
+    /// libraries, and the states of the pipeline, including which ones are dynamic, are taken
+    /// from the library that contains the corresponding part. `pipeline_layout` must be
+    /// compatible with the layouts of all the libraries.
+    ///
+    /// If `cache` is `Some`, the Vulkan implementation will look in the cache for an existing
+    /// pipeline, or add one if it doesn't exist. If `link_time_optimization` is true, the
+    /// implementation is allowed to spend more time linking in order to produce a faster
+    /// pipeline.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `vertex_input` doesn't contain the vertex input interface, or if
+    ///   `fragment_output` doesn't contain the fragment output interface.
+    /// - Panics if the cache wasn't created with the same device as the libraries.
+    ///
+    pub fn link<Lv, Rv, Mf, Lf>(vertex_input: &GraphicsPipelineLibrary<Mv, Lv, Rv>,
+                                fragment_output: &GraphicsPipelineLibrary<Mf, Lf, Rp>,
+                                others: &[&GraphicsPipelineLibraryAbstract],
+                                pipeline_layout: Pl, cache: Option<&Arc<PipelineCache>>,
+                                link_time_optimization: bool)
+                                -> Result<GraphicsPipeline<Mv, Pl, Rp>,
+                                          GraphicsPipelineCreationError> {
+        assert!(vertex_input.parts.vertex_input_interface,
+                "the vertex input library doesn't contain the vertex input interface");
+        assert!(fragment_output.parts.fragment_output_interface,
+                "the fragment output library doesn't contain the fragment output interface");
+
+        let device = vertex_input.device().clone();
+        check_support(&device)?;
+
+        let mut libraries = SmallVec::<[&GraphicsPipelineLibraryAbstract; 4]>::new();
+        libraries.push(vertex_input);
+        if fragment_output.pipeline.inner.pipeline != vertex_input.pipeline.inner.pipeline {
+            libraries.push(fragment_output);
+        }
+        libraries.extend(others.iter().cloned());
+
+        let mut parts = GraphicsPipelineLibraryParts::none();
+        for library in libraries.iter() {
+            if library.device().internal_object() != device.internal_object() {
+                return Err(GraphicsPipelineCreationError::GraphicsPipelineLibrariesDeviceMismatch);
+            }
+            if parts.intersects(&library.parts()) {
+                return Err(GraphicsPipelineCreationError::OverlappingGraphicsPipelineLibraries);
+            }
+            parts = parts | library.parts();
+        }
+        if parts != GraphicsPipelineLibraryParts::all() {
+            return Err(GraphicsPipelineCreationError::IncompleteGraphicsPipelineLibraries);
+        }
+
+        let find = |f: fn(&GraphicsPipelineLibraryParts) -> bool| {
+            libraries.iter().find(|l| f(&l.parts())).unwrap().pipeline()
+        };
+        let pre_rasterization = find(|p| p.pre_rasterization_shaders);
+        let fragment_shader = find(|p| p.fragment_shader);
+
+        let cache = match cache {
+            Some(cache) => {
+                assert_eq!(cache.device().internal_object(), device.internal_object());
+                cache.internal_object()
+            },
+            None => 0,
+        };
+
+        let mut feedback = if device.loaded_extensions().ext_pipeline_creation_feedback {
+            Some(FeedbackStorage::new(None))
+        } else {
+            None
+        };
+
+        let pipeline = unsafe {
+            let vk = device.pointers();
+
+            let feedback_info = feedback.as_mut().map(|f| f.create_info());
+            let handles = libraries
+                .iter()
+                .map(|l| l.pipeline().inner.pipeline)
+                .collect::<SmallVec<[_; 4]>>();
+
+            let library_info = vk::PipelineLibraryCreateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_PIPELINE_LIBRARY_CREATE_INFO_KHR,
+                pNext: feedback_info
+                    .as_ref()
+                    .map(|f| f as *const _ as *const _)
+                    .unwrap_or(ptr::null()),
+                libraryCount: handles.len() as u32,
+                pLibraries: handles.as_ptr(),
+            };
+
+            // All the states are provided by the libraries.
+            let infos = vk::GraphicsPipelineCreateInfo {
+                sType: vk::STRUCTURE_TYPE_GRAPHICS_PIPELINE_CREATE_INFO,
+                pNext: &library_info as *const _ as *const _,
+                flags: if link_time_optimization {
+                    vk::PIPELINE_CREATE_LINK_TIME_OPTIMIZATION_BIT_EXT
+                } else {
+                    0
+                },
+                stageCount: 0,
+                pStages: ptr::null(),
+                pVertexInputState: ptr::null(),
+                pInputAssemblyState: ptr::null(),
+                pTessellationState: ptr::null(),
+                pViewportState: ptr::null(),
+                pRasterizationState: ptr::null(),
+                pMultisampleState: ptr::null(),
+                pDepthStencilState: ptr::null(),
+                pColorBlendState: ptr::null(),
+                pDynamicState: ptr::null(),
+                layout: PipelineLayoutAbstract::sys(&pipeline_layout).internal_object(),
+                renderPass: 0,
+                subpass: 0,
+                basePipelineHandle: 0,
+                basePipelineIndex: -1,
+            };
+
+            let _span = instrument_span!("vkCreateGraphicsPipelines",
+                                         cache = ::instrument::pipeline_cache_name(&device,
+                                                                                   cache),
+                                         libraries = libraries.len());
+            let mut output = 0;
+            check_errors(vk.CreateGraphicsPipelines(device.internal_object(),
+                                                    cache,
+                                                    1,
+                                                    &infos,
+                                                    device.allocator_ptr(),
+                                                    &mut output))?;
+            output
+        };
+
+        let pipeline = GraphicsPipeline {
+            inner: GraphicsPipelineInner {
+                device: device.clone(),
+                pipeline: pipeline,
+            },
+            layout: (),
+
+            vertex_definition: (),
+
+            render_pass: (),
+            render_pass_subpass: 0,
+
+            dynamic_line_width: pre_rasterization.dynamic_line_width,
+            dynamic_viewport: pre_rasterization.dynamic_viewport,
+            dynamic_scissor: pre_rasterization.dynamic_scissor,
+            dynamic_depth_bias: pre_rasterization.dynamic_depth_bias,
+            dynamic_line_stipple: pre_rasterization.dynamic_line_stipple,
+            dynamic_depth_bounds: fragment_shader.dynamic_depth_bounds,
+            dynamic_stencil_compare_mask: fragment_shader.dynamic_stencil_compare_mask,
+            dynamic_stencil_write_mask: fragment_shader.dynamic_stencil_write_mask,
+            dynamic_stencil_reference: fragment_shader.dynamic_stencil_reference,
+            dynamic_blend_constants: fragment_output.pipeline.dynamic_blend_constants,
+            dynamic_primitive_topology: vertex_input.pipeline.dynamic_primitive_topology,
+            dynamic_cull_mode: pre_rasterization.dynamic_cull_mode,
+            dynamic_front_face: pre_rasterization.dynamic_front_face,
+            dynamic_depth_test_enable: fragment_shader.dynamic_depth_test_enable,
+            dynamic_depth_write_enable: fragment_shader.dynamic_depth_write_enable,
+            dynamic_depth_compare_op: fragment_shader.dynamic_depth_compare_op,
+            dynamic_depth_bounds_test_enable: fragment_shader.dynamic_depth_bounds_test_enable,
+            dynamic_stencil_test_enable: fragment_shader.dynamic_stencil_test_enable,
+            dynamic_stencil_op: fragment_shader.dynamic_stencil_op,
+            dynamic_vertex_input_binding_stride: vertex_input
+                .pipeline
+                .dynamic_vertex_input_binding_stride,
+
+            num_viewports: pre_rasterization.num_viewports,
+            task_shader: pre_rasterization.task_shader,
+            mesh_shader: pre_rasterization.mesh_shader,
+            primitive_topology: vertex_input.pipeline.primitive_topology,
+
+            creation_feedback: feedback.map(|f| f.into_feedback()),
+        };
+
+        let (render_pass, render_pass_subpass) = fragment_output
+            .subpass
+            .clone()
+            .expect("a library with the fragment output interface always has a subpass")
+            .into();
+
+        Ok(pipeline.with_types(vertex_input.vertex_definition.clone(),
+                               pipeline_layout,
+                               render_pass,
+                               render_pass_subpass))
+    }
+}
+
+unsafe impl<Mv, L, Rp> DeviceOwned for GraphicsPipelineLibrary<Mv, L, Rp> {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.pipeline.device()
+    }
+}
+
+impl<Mv, L, Rp> fmt::Debug for GraphicsPipelineLibrary<Mv, L, Rp> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt,
+               "<Vulkan graphics pipeline library {:?}>",
+               self.pipeline.inner.pipeline)
+    }
+}
+
+// Checks that graphics pipeline libraries can be used on the device.
+pub(super) fn check_support(device: &Device) -> Result<(), GraphicsPipelineCreationError> {
+    if !device.loaded_extensions().ext_graphics_pipeline_library {
+        return Err(GraphicsPipelineCreationError::GraphicsPipelineLibraryExtensionNotEnabled);
+    }
+    if !device.graphics_pipeline_library_enabled() {
+        return Err(GraphicsPipelineCreationError::GraphicsPipelineLibraryFeatureNotEnabled);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use descriptor::descriptor::ShaderStages;
+    use descriptor::pipeline_layout::PipelineLayoutAbstract;
+    use device::Device;
+    use framebuffer::EmptySinglePassRenderPassDesc;
+    use framebuffer::RenderPass;
+    use framebuffer::RenderPassDesc;
+    use framebuffer::Subpass;
+    use pipeline::GraphicsPipeline;
+    use pipeline::graphics_pipeline::GraphicsPipelineLibrary;
+    use pipeline::graphics_pipeline::GraphicsPipelineLibraryParts;
+    use pipeline::reflect;
+    use pipeline::shader::ShaderModule;
+    use pipeline::vertex::BufferlessDefinition;
+    use spirv::*;
+    use spirv::tests::*;
+
+    type Library = GraphicsPipelineLibrary<BufferlessDefinition,
+                                           Box<PipelineLayoutAbstract + Send + Sync>,
+                                           Arc<RenderPass<EmptySinglePassRenderPassDesc>>>;
+
+    // Creates a device with the `VK_EXT_graphics_pipeline_library` extension, or returns if it
+    // isn't supported.
+    macro_rules! library_device {
+        () => ({
+            use device::DeviceExtensions;
+            use features::Features;
+            use instance;
+
+            let instance = instance!();
+
+            let physical = match instance::PhysicalDevice::enumerate(&instance).next() {
+                Some(p) => p,
+                None => return,
+            };
+
+            let queue = match physical.queue_families().find(|q| q.supports_graphics()) {
+                Some(q) => q,
+                None => return,
+            };
+
+            let extensions = DeviceExtensions {
+                khr_pipeline_library: true,
+                ext_graphics_pipeline_library: true,
+                ..DeviceExtensions::none()
+            };
+
+            let features = Features {
+                graphics_pipeline_library: true,
+                ..Features::none()
+            };
+
+            match Device::new(physical, &features, &extensions, [(queue, 0.5)].iter().cloned()) {
+                Ok((device, _)) => device,
+                Err(_) => return,
+            }
+        });
+    }
+
+    // Builds a module with an empty `main` entry point.
+    fn empty_shader(execution_model: u32) -> Vec<u32> {
+        let execution_mode = if execution_model == EXECUTION_MODEL_FRAGMENT {
+            (OP_EXECUTION_MODE, &[1, EXECUTION_MODE_ORIGIN_UPPER_LEFT][..])
+        } else {
+            (OP_NAME, &[1, 0x6e69616d, 0][..])
+        };
+
+        module(&[(OP_CAPABILITY, &[CAPABILITY_SHADER]),
+                 (OP_MEMORY_MODEL, &[0, 1]),
+                 (OP_ENTRY_POINT, &[execution_model, 1, 0x6e69616d, 0]),
+                 execution_mode,
+                 (OP_TYPE_VOID, &[2]),
+                 (OP_TYPE_FUNCTION, &[3, 2]),
+                 (OP_FUNCTION, &[2, 1, 0, 3]),
+                 (OP_LABEL, &[4]),
+                 (OP_RETURN, &[]),
+                 (OP_FUNCTION_END, &[])])
+    }
+
+    fn subpass(device: &Arc<Device>) -> Subpass<Arc<RenderPass<EmptySinglePassRenderPassDesc>>> {
+        let render_pass = EmptySinglePassRenderPassDesc
+            .build_render_pass(device.clone())
+            .unwrap();
+        Subpass::from(Arc::new(render_pass), 0).unwrap()
+    }
+
+    // Only sets the vertex shader and the subpass.
+    fn vertex_input_library(device: &Arc<Device>) -> Library {
+        let words = empty_shader(EXECUTION_MODEL_VERTEX);
+        let stages = ShaderStages { vertex: true, ..ShaderStages::none() };
+        let info = reflect::entry_point(&words, "main", stages).unwrap();
+
+        unsafe {
+            let module = ShaderModule::from_words(device.clone(), &words).unwrap();
+            GraphicsPipeline::start()
+                .vertex_shader(info.graphics_entry_point::<()>(&module).unwrap(), ())
+                .render_pass(subpass(device))
+                .build_library(device.clone(),
+                               GraphicsPipelineLibraryParts {
+                                   vertex_input_interface: true,
+                                   ..GraphicsPipelineLibraryParts::none()
+                               })
+                .unwrap()
+        }
+    }
+
+    // Only sets the vertex shader, the viewports and the subpass.
+    fn pre_rasterization_library(device: &Arc<Device>) -> Library {
+        let words = empty_shader(EXECUTION_MODEL_VERTEX);
+        let stages = ShaderStages { vertex: true, ..ShaderStages::none() };
+        let info = reflect::entry_point(&words, "main", stages).unwrap();
+
+        unsafe {
+            let module = ShaderModule::from_words(device.clone(), &words).unwrap();
+            GraphicsPipeline::start()
+                .vertex_shader(info.graphics_entry_point::<()>(&module).unwrap(), ())
+                .viewports_dynamic_scissors_irrelevant(1)
+                .render_pass(subpass(device))
+                .build_library(device.clone(),
+                               GraphicsPipelineLibraryParts {
+                                   pre_rasterization_shaders: true,
+                                   ..GraphicsPipelineLibraryParts::none()
+                               })
+                .unwrap()
+        }
+    }
+
+    // Only sets the fragment shader and the subpass.
+    fn fragment_shader_library(device: &Arc<Device>) -> Library {
+        let words = empty_shader(EXECUTION_MODEL_FRAGMENT);
+        let stages = ShaderStages { fragment: true, ..ShaderStages::none() };
+        let info = reflect::entry_point(&words, "main", stages).unwrap();
+
+        unsafe {
+            let module = ShaderModule::from_words(device.clone(), &words).unwrap();
+            GraphicsPipeline::start()
+                .fragment_shader(info.graphics_entry_point::<()>(&module).unwrap(), ())
+                .render_pass(subpass(device))
+                .build_library(device.clone(),
+                               GraphicsPipelineLibraryParts {
+                                   fragment_shader: true,
+                                   ..GraphicsPipelineLibraryParts::none()
+                               })
+                .unwrap()
+        }
+    }
+
+    // Only sets the subpass.
+    fn fragment_output_library(device: &Arc<Device>) -> Library {
+        GraphicsPipeline::start()
+            .render_pass(subpass(device))
+            .build_library(device.clone(),
+                           GraphicsPipelineLibraryParts {
+                               fragment_output_interface: true,
+                               ..GraphicsPipelineLibraryParts::none()
+                           })
+            .unwrap()
+    }
+
+    #[test]
+    fn build_vertex_input() {
+        let device = library_device!();
+        let library = vertex_input_library(&device);
+        assert!(library.parts().vertex_input_interface);
+    }
+
+    #[test]
+    fn build_pre_rasterization() {
+        let device = library_device!();
+        let library = pre_rasterization_library(&device);
+        assert!(library.parts().pre_rasterization_shaders);
+    }
+
+    #[test]
+    fn build_fragment_shader() {
+        let device = library_device!();
+        let library = fragment_shader_library(&device);
+        assert!(library.parts().fragment_shader);
+    }
+
+    #[test]
+    fn build_fragment_output() {
+        let device = library_device!();
+        let library = fragment_output_library(&device);
+        assert!(library.parts().fragment_output_interface);
+    }
+
+    #[test]
+    fn link() {
+        let device = library_device!();
+        let vertex_input = vertex_input_library(&device);
+        let pre_rasterization = pre_rasterization_library(&device);
+        let fragment_shader = fragment_shader_library(&device);
+        let fragment_output = fragment_output_library(&device);
+
+        let pipeline = GraphicsPipeline::link(&vertex_input,
+                                              &fragment_output,
+                                              &[&pre_rasterization, &fragment_shader],
+                                              pre_rasterization.layout(),
+                                              None,
+                                              false)
+            .unwrap();
+        assert!(pipeline.has_dynamic_viewports());
+        assert_eq!(pipeline.num_viewports(), 1);
+    }
+
+    #[test]
+    fn parts_union() {
+        let vertex = GraphicsPipelineLibraryParts {
+            vertex_input_interface: true,
+            ..GraphicsPipelineLibraryParts::none()
+        };
+        let rest = GraphicsPipelineLibraryParts {
+            vertex_input_interface: false,
+            ..GraphicsPipelineLibraryParts::all()
+        };
+
+        assert!(!vertex.intersects(&rest));
+        assert!(vertex.intersects(&GraphicsPipelineLibraryParts::all()));
+        assert_eq!(vertex | rest, GraphicsPipelineLibraryParts::all());
+    }
+}
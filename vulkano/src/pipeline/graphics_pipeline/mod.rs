@@ -9,7 +9,6 @@
 
 use std::fmt;
 use std::marker::PhantomData;
-use std::ptr;
 use std::sync::Arc;
 use std::u32;
 
@@ -33,18 +32,25 @@ use framebuffer::RenderPassDesc;
 use framebuffer::RenderPassDescClearValues;
 use framebuffer::RenderPassSys;
 use framebuffer::Subpass;
+use pipeline::creation_feedback::PipelineCreationFeedback;
+use pipeline::input_assembly::PrimitiveTopology;
 use pipeline::shader::EmptyEntryPointDummy;
 use pipeline::vertex::BufferlessDefinition;
 use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::IncompatibleVertexSourceError;
 use pipeline::vertex::VertexDefinition;
 use pipeline::vertex::VertexSource;
 use vk;
 
 pub use self::builder::GraphicsPipelineBuilder;
 pub use self::creation_error::GraphicsPipelineCreationError;
+pub use self::library::GraphicsPipelineLibrary;
+pub use self::library::GraphicsPipelineLibraryAbstract;
+pub use self::library::GraphicsPipelineLibraryParts;
 
 mod builder;
 mod creation_error;
+mod library;
 // FIXME: restore
 //mod tests;
 
@@ -65,13 +71,61 @@ pub struct GraphicsPipeline<VertexDefinition, Layout, RenderP> {
     dynamic_viewport: bool,
     dynamic_scissor: bool,
     dynamic_depth_bias: bool,
+    dynamic_line_stipple: bool,
     dynamic_depth_bounds: bool,
     dynamic_stencil_compare_mask: bool,
     dynamic_stencil_write_mask: bool,
     dynamic_stencil_reference: bool,
     dynamic_blend_constants: bool,
+    dynamic_primitive_topology: bool,
+    dynamic_cull_mode: bool,
+    dynamic_front_face: bool,
+    dynamic_depth_test_enable: bool,
+    dynamic_depth_write_enable: bool,
+    dynamic_depth_compare_op: bool,
+    dynamic_depth_bounds_test_enable: bool,
+    dynamic_stencil_test_enable: bool,
+    dynamic_stencil_op: bool,
+    dynamic_vertex_input_binding_stride: bool,
 
     num_viewports: u32,
+    task_shader: bool,
+    mesh_shader: bool,
+    primitive_topology: PrimitiveTopology,
+
+    creation_feedback: Option<PipelineCreationFeedback>,
+}
+
+// States of `VK_EXT_extended_dynamic_state` that the builder sets as dynamic.
+#[derive(Debug, Copy, Clone, Default)]
+struct ExtendedDynamicState {
+    primitive_topology: bool,
+    cull_mode: bool,
+    front_face: bool,
+    depth_test_enable: bool,
+    depth_write_enable: bool,
+    depth_compare_op: bool,
+    depth_bounds_test_enable: bool,
+    stencil_test_enable: bool,
+    stencil_op: bool,
+    vertex_input_binding_stride: bool,
+}
+
+impl ExtendedDynamicState {
+    // Returns true if any of the states is dynamic.
+    #[inline]
+    fn any(&self) -> bool {
+        self.primitive_topology ||
+            self.cull_mode ||
+            self.front_face ||
+            self.depth_test_enable ||
+            self.depth_write_enable ||
+            self.depth_compare_op ||
+            self.depth_bounds_test_enable ||
+            self.stencil_test_enable ||
+            self.stencil_op ||
+            self.vertex_input_binding_stride
+    }
 }
 
 struct Inner {
@@ -94,10 +148,58 @@ impl GraphicsPipeline<(), (), ()> {
                                    (),
                                    EmptyEntryPointDummy,
                                    (),
+                                   EmptyEntryPointDummy,
+                                   (),
+                                   EmptyEntryPointDummy,
+                                   (),
                                    ()>
     {
         GraphicsPipelineBuilder::new()
     }
+
+    // Sets the vertex definition, the pipeline layout and the render pass of a pipeline whose
+    // states were filled without them.
+    fn with_types<Mv, L, Rp>(self, vertex_definition: Mv, layout: L, render_pass: Rp,
+                             render_pass_subpass: u32)
+                             -> GraphicsPipeline<Mv, L, Rp> {
+        GraphicsPipeline {
+            inner: self.inner,
+            layout: layout,
+
+            render_pass: render_pass,
+            render_pass_subpass: render_pass_subpass,
+
+            vertex_definition: vertex_definition,
+
+            dynamic_line_width: self.dynamic_line_width,
+            dynamic_viewport: self.dynamic_viewport,
+            dynamic_scissor: self.dynamic_scissor,
+            dynamic_depth_bias: self.dynamic_depth_bias,
+            dynamic_line_stipple: self.dynamic_line_stipple,
+            dynamic_depth_bounds: self.dynamic_depth_bounds,
+            dynamic_stencil_compare_mask: self.dynamic_stencil_compare_mask,
+            dynamic_stencil_write_mask: self.dynamic_stencil_write_mask,
+            dynamic_stencil_reference: self.dynamic_stencil_reference,
+            dynamic_blend_constants: self.dynamic_blend_constants,
+            dynamic_primitive_topology: self.dynamic_primitive_topology,
+            dynamic_cull_mode: self.dynamic_cull_mode,
+            dynamic_front_face: self.dynamic_front_face,
+            dynamic_depth_test_enable: self.dynamic_depth_test_enable,
+            dynamic_depth_write_enable: self.dynamic_depth_write_enable,
+            dynamic_depth_compare_op: self.dynamic_depth_compare_op,
+            dynamic_depth_bounds_test_enable: self.dynamic_depth_bounds_test_enable,
+            dynamic_stencil_test_enable: self.dynamic_stencil_test_enable,
+            dynamic_stencil_op: self.dynamic_stencil_op,
+            dynamic_vertex_input_binding_stride: self.dynamic_vertex_input_binding_stride,
+
+            num_viewports: self.num_viewports,
+            task_shader: self.task_shader,
+            mesh_shader: self.mesh_shader,
+            primitive_topology: self.primitive_topology,
+
+            creation_feedback: self.creation_feedback,
+        }
+    }
 }
 
 impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
@@ -153,6 +255,34 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
         self.num_viewports
     }
 
+    /// Returns true if this pipeline uses a mesh shader, in which case it must be used with the
+    /// `draw_mesh_tasks` commands.
+    #[inline]
+    pub fn has_mesh_shader(&self) -> bool {
+        self.mesh_shader
+    }
+
+    /// Returns true if this pipeline uses a task shader, which launches the workgroups of the mesh
+    /// shader.
+    #[inline]
+    pub fn has_task_shader(&self) -> bool {
+        self.task_shader
+    }
+
+    /// Returns the primitive topology the pipeline was created with. If the primitive topology is
+    /// dynamic, the topologies passed when drawing must belong to the same class.
+    #[inline]
+    pub fn primitive_topology(&self) -> PrimitiveTopology {
+        self.primitive_topology
+    }
+
+    /// Returns the feedback about the creation of this pipeline, or `None` if the
+    /// `VK_EXT_pipeline_creation_feedback` extension isn't enabled on the device.
+    #[inline]
+    pub fn creation_feedback(&self) -> Option<&PipelineCreationFeedback> {
+        self.creation_feedback.as_ref()
+    }
+
     /// Returns true if the viewports used by this pipeline are dynamic.
     #[inline]
     pub fn has_dynamic_viewports(&self) -> bool {
@@ -165,6 +295,18 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
         self.dynamic_scissor
     }
 
+    /// Returns true if the depth bias used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_depth_bias(&self) -> bool {
+        self.dynamic_depth_bias
+    }
+
+    /// Returns true if the line stipple used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_line_stipple(&self) -> bool {
+        self.dynamic_line_stipple
+    }
+
     /// Returns true if the depth bounds used by this pipeline are dynamic.
     #[inline]
     pub fn has_dynamic_depth_bounds(&self) -> bool {
@@ -188,6 +330,72 @@ impl<Mv, L, Rp> GraphicsPipeline<Mv, L, Rp> {
     pub fn has_dynamic_stencil_reference(&self) -> bool {
         self.dynamic_stencil_reference
     }
+
+    /// Returns true if the blend constants used by this pipeline are dynamic.
+    #[inline]
+    pub fn has_dynamic_blend_constants(&self) -> bool {
+        self.dynamic_blend_constants
+    }
+
+    /// Returns true if the primitive topology used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_primitive_topology(&self) -> bool {
+        self.dynamic_primitive_topology
+    }
+
+    /// Returns true if the cull mode used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_cull_mode(&self) -> bool {
+        self.dynamic_cull_mode
+    }
+
+    /// Returns true if the front face used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_front_face(&self) -> bool {
+        self.dynamic_front_face
+    }
+
+    /// Returns true if enabling the depth test in this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_depth_test_enable(&self) -> bool {
+        self.dynamic_depth_test_enable
+    }
+
+    /// Returns true if enabling depth writes in this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_depth_write_enable(&self) -> bool {
+        self.dynamic_depth_write_enable
+    }
+
+    /// Returns true if the depth compare operation used by this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_depth_compare_op(&self) -> bool {
+        self.dynamic_depth_compare_op
+    }
+
+    /// Returns true if enabling the depth bounds test in this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_depth_bounds_test_enable(&self) -> bool {
+        self.dynamic_depth_bounds_test_enable
+    }
+
+    /// Returns true if enabling the stencil test in this pipeline is dynamic.
+    #[inline]
+    pub fn has_dynamic_stencil_test_enable(&self) -> bool {
+        self.dynamic_stencil_test_enable
+    }
+
+    /// Returns true if the stencil operations used by this pipeline are dynamic.
+    #[inline]
+    pub fn has_dynamic_stencil_op(&self) -> bool {
+        self.dynamic_stencil_op
+    }
+
+    /// Returns true if the strides of the vertex buffers used by this pipeline are dynamic.
+    #[inline]
+    pub fn has_dynamic_vertex_input_binding_stride(&self) -> bool {
+        self.dynamic_vertex_input_binding_stride
+    }
 }
 
 unsafe impl<Mv, L, Rp> PipelineLayoutAbstract for GraphicsPipeline<Mv, L, Rp>
@@ -315,7 +523,9 @@ impl Drop for Inner {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyPipeline(self.device.internal_object(), self.pipeline, ptr::null());
+            vk.DestroyPipeline(self.device.internal_object(),
+                               self.pipeline,
+                               self.device.allocator_ptr());
         }
     }
 }
@@ -342,12 +552,27 @@ pub unsafe trait GraphicsPipelineAbstract: PipelineLayoutAbstract + RenderPassAb
 /// Returns the number of viewports and scissors of this pipeline.
     fn num_viewports(&self) -> u32;
 
+/// Returns true if this pipeline uses a mesh shader.
+    fn has_mesh_shader(&self) -> bool;
+
+/// Returns true if this pipeline uses a task shader.
+    fn has_task_shader(&self) -> bool;
+
+/// Returns the primitive topology the pipeline was created with.
+    fn primitive_topology(&self) -> PrimitiveTopology;
+
 /// Returns true if the viewports used by this pipeline are dynamic.
     fn has_dynamic_viewports(&self) -> bool;
 
 /// Returns true if the scissors used by this pipeline are dynamic.
     fn has_dynamic_scissors(&self) -> bool;
 
+/// Returns true if the depth bias used by this pipeline is dynamic.
+    fn has_dynamic_depth_bias(&self) -> bool;
+
+/// Returns true if the line stipple used by this pipeline is dynamic.
+    fn has_dynamic_line_stipple(&self) -> bool;
+
 /// Returns true if the depth bounds used by this pipeline are dynamic.
     fn has_dynamic_depth_bounds(&self) -> bool;
 
@@ -359,6 +584,39 @@ pub unsafe trait GraphicsPipelineAbstract: PipelineLayoutAbstract + RenderPassAb
 
 /// Returns true if the stencil references used by this pipeline are dynamic.
     fn has_dynamic_stencil_reference(&self) -> bool;
+
+/// Returns true if the blend constants used by this pipeline are dynamic.
+    fn has_dynamic_blend_constants(&self) -> bool;
+
+/// Returns true if the primitive topology used by this pipeline is dynamic.
+    fn has_dynamic_primitive_topology(&self) -> bool;
+
+/// Returns true if the cull mode used by this pipeline is dynamic.
+    fn has_dynamic_cull_mode(&self) -> bool;
+
+/// Returns true if the front face used by this pipeline is dynamic.
+    fn has_dynamic_front_face(&self) -> bool;
+
+/// Returns true if enabling the depth test in this pipeline is dynamic.
+    fn has_dynamic_depth_test_enable(&self) -> bool;
+
+/// Returns true if enabling depth writes in this pipeline is dynamic.
+    fn has_dynamic_depth_write_enable(&self) -> bool;
+
+/// Returns true if the depth compare operation used by this pipeline is dynamic.
+    fn has_dynamic_depth_compare_op(&self) -> bool;
+
+/// Returns true if enabling the depth bounds test in this pipeline is dynamic.
+    fn has_dynamic_depth_bounds_test_enable(&self) -> bool;
+
+/// Returns true if enabling the stencil test in this pipeline is dynamic.
+    fn has_dynamic_stencil_test_enable(&self) -> bool;
+
+/// Returns true if the stencil operations used by this pipeline are dynamic.
+    fn has_dynamic_stencil_op(&self) -> bool;
+
+/// Returns true if the strides of the vertex buffers used by this pipeline are dynamic.
+    fn has_dynamic_vertex_input_binding_stride(&self) -> bool;
 }
 
 unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
@@ -386,6 +644,21 @@ unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
         self.num_viewports
     }
 
+    #[inline]
+    fn has_mesh_shader(&self) -> bool {
+        self.mesh_shader
+    }
+
+    #[inline]
+    fn has_task_shader(&self) -> bool {
+        self.task_shader
+    }
+
+    #[inline]
+    fn primitive_topology(&self) -> PrimitiveTopology {
+        self.primitive_topology
+    }
+
     #[inline]
     fn has_dynamic_viewports(&self) -> bool {
         self.dynamic_viewport
@@ -396,6 +669,16 @@ unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
         self.dynamic_scissor
     }
 
+    #[inline]
+    fn has_dynamic_depth_bias(&self) -> bool {
+        self.dynamic_depth_bias
+    }
+
+    #[inline]
+    fn has_dynamic_line_stipple(&self) -> bool {
+        self.dynamic_line_stipple
+    }
+
     #[inline]
     fn has_dynamic_depth_bounds(&self) -> bool {
         self.dynamic_depth_bounds
@@ -415,6 +698,61 @@ unsafe impl<Mv, L, Rp> GraphicsPipelineAbstract for GraphicsPipeline<Mv, L, Rp>
     fn has_dynamic_stencil_reference(&self) -> bool {
         self.dynamic_stencil_reference
     }
+
+    #[inline]
+    fn has_dynamic_blend_constants(&self) -> bool {
+        self.dynamic_blend_constants
+    }
+
+    #[inline]
+    fn has_dynamic_primitive_topology(&self) -> bool {
+        self.dynamic_primitive_topology
+    }
+
+    #[inline]
+    fn has_dynamic_cull_mode(&self) -> bool {
+        self.dynamic_cull_mode
+    }
+
+    #[inline]
+    fn has_dynamic_front_face(&self) -> bool {
+        self.dynamic_front_face
+    }
+
+    #[inline]
+    fn has_dynamic_depth_test_enable(&self) -> bool {
+        self.dynamic_depth_test_enable
+    }
+
+    #[inline]
+    fn has_dynamic_depth_write_enable(&self) -> bool {
+        self.dynamic_depth_write_enable
+    }
+
+    #[inline]
+    fn has_dynamic_depth_compare_op(&self) -> bool {
+        self.dynamic_depth_compare_op
+    }
+
+    #[inline]
+    fn has_dynamic_depth_bounds_test_enable(&self) -> bool {
+        self.dynamic_depth_bounds_test_enable
+    }
+
+    #[inline]
+    fn has_dynamic_stencil_test_enable(&self) -> bool {
+        self.dynamic_stencil_test_enable
+    }
+
+    #[inline]
+    fn has_dynamic_stencil_op(&self) -> bool {
+        self.dynamic_stencil_op
+    }
+
+    #[inline]
+    fn has_dynamic_vertex_input_binding_stride(&self) -> bool {
+        self.dynamic_vertex_input_binding_stride
+    }
 }
 
 unsafe impl<T> GraphicsPipelineAbstract for T
@@ -441,6 +779,21 @@ unsafe impl<T> GraphicsPipelineAbstract for T
         (**self).num_viewports()
     }
 
+    #[inline]
+    fn has_mesh_shader(&self) -> bool {
+        (**self).has_mesh_shader()
+    }
+
+    #[inline]
+    fn has_task_shader(&self) -> bool {
+        (**self).has_task_shader()
+    }
+
+    #[inline]
+    fn primitive_topology(&self) -> PrimitiveTopology {
+        (**self).primitive_topology()
+    }
+
     #[inline]
     fn has_dynamic_viewports(&self) -> bool {
         (**self).has_dynamic_viewports()
@@ -451,6 +804,16 @@ unsafe impl<T> GraphicsPipelineAbstract for T
         (**self).has_dynamic_scissors()
     }
 
+    #[inline]
+    fn has_dynamic_depth_bias(&self) -> bool {
+        (**self).has_dynamic_depth_bias()
+    }
+
+    #[inline]
+    fn has_dynamic_line_stipple(&self) -> bool {
+        (**self).has_dynamic_line_stipple()
+    }
+
     #[inline]
     fn has_dynamic_depth_bounds(&self) -> bool {
         (**self).has_dynamic_depth_bounds()
@@ -470,6 +833,61 @@ unsafe impl<T> GraphicsPipelineAbstract for T
     fn has_dynamic_stencil_reference(&self) -> bool {
         (**self).has_dynamic_stencil_reference()
     }
+
+    #[inline]
+    fn has_dynamic_blend_constants(&self) -> bool {
+        (**self).has_dynamic_blend_constants()
+    }
+
+    #[inline]
+    fn has_dynamic_primitive_topology(&self) -> bool {
+        (**self).has_dynamic_primitive_topology()
+    }
+
+    #[inline]
+    fn has_dynamic_cull_mode(&self) -> bool {
+        (**self).has_dynamic_cull_mode()
+    }
+
+    #[inline]
+    fn has_dynamic_front_face(&self) -> bool {
+        (**self).has_dynamic_front_face()
+    }
+
+    #[inline]
+    fn has_dynamic_depth_test_enable(&self) -> bool {
+        (**self).has_dynamic_depth_test_enable()
+    }
+
+    #[inline]
+    fn has_dynamic_depth_write_enable(&self) -> bool {
+        (**self).has_dynamic_depth_write_enable()
+    }
+
+    #[inline]
+    fn has_dynamic_depth_compare_op(&self) -> bool {
+        (**self).has_dynamic_depth_compare_op()
+    }
+
+    #[inline]
+    fn has_dynamic_depth_bounds_test_enable(&self) -> bool {
+        (**self).has_dynamic_depth_bounds_test_enable()
+    }
+
+    #[inline]
+    fn has_dynamic_stencil_test_enable(&self) -> bool {
+        (**self).has_dynamic_stencil_test_enable()
+    }
+
+    #[inline]
+    fn has_dynamic_stencil_op(&self) -> bool {
+        (**self).has_dynamic_stencil_op()
+    }
+
+    #[inline]
+    fn has_dynamic_vertex_input_binding_stride(&self) -> bool {
+        (**self).has_dynamic_vertex_input_binding_stride()
+    }
 }
 
 /// Opaque object that represents the inside of the graphics pipeline.
@@ -505,7 +923,9 @@ unsafe impl<Mv, L, Rp, S> VertexSource<S> for GraphicsPipeline<Mv, L, Rp>
     where Mv: VertexSource<S>
 {
     #[inline]
-    fn decode(&self, s: S) -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
+    fn decode(&self, s: S)
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
         self.vertex_definition.decode(s)
     }
 }
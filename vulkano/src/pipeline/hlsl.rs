@@ -0,0 +1,384 @@
+// Copyright (c) 2018 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Compilation of HLSL shaders at runtime.
+//!
+//! This module is only available if the `hlsl` feature of vulkano is enabled. It compiles HLSL
+//! source code to SPIR-V with the DirectX Shader Compiler (DXC), and reflects the result with the
+//! `reflect` module so that it can be passed to the pipeline builders directly. The DXC library
+//! (`dxcompiler.dll`, `libdxcompiler.so` or `libdxcompiler.dylib`) is loaded when a shader is
+//! compiled, and must be available in the search paths of the system.
+//!
+//! # Registers and descriptor sets
+//!
+//! DXC puts a resource declared with `register(xN, spaceM)` at the binding `N` of the descriptor
+//! set `M`. Since the `b`, `t`, `s` and `u` registers share the same bindings in Vulkan, a shift
+//! must usually be applied to some of them with `HlslCompileOptions::register_shift`. The
+//! descriptor sets can be rearranged with `HlslCompileOptions::space_to_set`.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::device::Device;
+//! use vulkano::pipeline::hlsl::HlslCompileOptions;
+//! use vulkano::pipeline::hlsl::HlslRegisterKind;
+//! use vulkano::pipeline::hlsl::HlslShaderStage;
+//! use vulkano::pipeline::shader::ShaderModule;
+//!
+//! # let device: Arc<Device> = return;
+//! let source = "
+//!     Texture2D tex : register(t0, space1);
+//!     SamplerState samp : register(s0, space1);
+//!     float4 main(float2 uv : TEXCOORD0) : SV_Target { return tex.Sample(samp, uv); }
+//! ";
+//!
+//! // The sampler ends up at the binding 1 of the descriptor set 0.
+//! let options = HlslCompileOptions::new()
+//!     .register_shift(HlslRegisterKind::Sampler, 1, Some(1))
+//!     .space_to_set(1, 0);
+//!
+//! let (module, entry_points) = unsafe {
+//!     ShaderModule::from_hlsl_source(device.clone(), source, HlslShaderStage::Pixel, &options)
+//!         .unwrap()
+//! };
+//! let fragment_shader = unsafe { entry_points[0].graphics_entry_point::<()>(&module).unwrap() };
+//! ```
+
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use hassle_rs;
+use hassle_rs::HassleError;
+
+use device::Device;
+use pipeline::reflect;
+use pipeline::reflect::EntryPointInfo;
+use pipeline::shader::ShaderModule;
+use pipeline::shader::ShaderModuleCreationError;
+use pipeline::shader::ShaderReflectionError;
+use spirv::remap_descriptor_sets;
+
+/// Stage that a HLSL shader is compiled for.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HlslShaderStage {
+    Vertex,
+    /// Tessellation control shader.
+    Hull,
+    /// Tessellation evaluation shader.
+    Domain,
+    Geometry,
+    /// Fragment shader.
+    Pixel,
+    Compute,
+    /// Task shader. Requires the `VK_EXT_mesh_shader` extension.
+    Amplification,
+    /// Requires the `VK_EXT_mesh_shader` extension.
+    Mesh,
+    /// Library of ray tracing shaders, whose entry points are the functions with a `[shader]`
+    /// attribute. Requires the `VK_KHR_ray_tracing_pipeline` extension.
+    Library,
+}
+
+impl HlslShaderStage {
+    // Returns the prefix of the target profile and the minimum shader model of the stage.
+    #[inline]
+    fn profile(&self) -> (&'static str, (u32, u32)) {
+        match *self {
+            HlslShaderStage::Vertex => ("vs", (6, 0)),
+            HlslShaderStage::Hull => ("hs", (6, 0)),
+            HlslShaderStage::Domain => ("ds", (6, 0)),
+            HlslShaderStage::Geometry => ("gs", (6, 0)),
+            HlslShaderStage::Pixel => ("ps", (6, 0)),
+            HlslShaderStage::Compute => ("cs", (6, 0)),
+            HlslShaderStage::Amplification => ("as", (6, 5)),
+            HlslShaderStage::Mesh => ("ms", (6, 5)),
+            HlslShaderStage::Library => ("lib", (6, 3)),
+        }
+    }
+}
+
+/// Kind of HLSL register.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum HlslRegisterKind {
+    /// `b` registers, used by constant buffers.
+    ConstantBuffer,
+    /// `t` registers, used by textures and read-only buffers.
+    ShaderResource,
+    /// `s` registers, used by samplers.
+    Sampler,
+    /// `u` registers, used by read-write textures and buffers.
+    UnorderedAccess,
+}
+
+impl HlslRegisterKind {
+    #[inline]
+    fn shift_argument(&self) -> &'static str {
+        match *self {
+            HlslRegisterKind::ConstantBuffer => "-fvk-b-shift",
+            HlslRegisterKind::ShaderResource => "-fvk-t-shift",
+            HlslRegisterKind::Sampler => "-fvk-s-shift",
+            HlslRegisterKind::UnorderedAccess => "-fvk-u-shift",
+        }
+    }
+}
+
+/// Options for compiling HLSL source code.
+#[derive(Debug, Clone)]
+pub struct HlslCompileOptions {
+    file_name: String,
+    entry_point: String,
+    shader_model: Option<(u32, u32)>,
+    defines: Vec<(String, Option<String>)>,
+    register_shifts: Vec<(HlslRegisterKind, u32, Option<u32>)>,
+    space_to_set: Vec<(u32, u32)>,
+}
+
+impl HlslCompileOptions {
+    /// Builds the default options. The entry point is `main` and the shader model is the
+    /// minimum one that supports the stage.
+    #[inline]
+    pub fn new() -> HlslCompileOptions {
+        HlslCompileOptions {
+            file_name: "shader.hlsl".to_owned(),
+            entry_point: "main".to_owned(),
+            shader_model: None,
+            defines: Vec::new(),
+            register_shifts: Vec::new(),
+            space_to_set: Vec::new(),
+        }
+    }
+
+    /// Sets the name of the source, used in error messages. `#include` directives are resolved
+    /// by DXC relative to the current directory.
+    #[inline]
+    pub fn file_name<S>(mut self, name: S) -> HlslCompileOptions
+        where S: Into<String>
+    {
+        self.file_name = name.into();
+        self
+    }
+
+    /// Sets the name of the function to compile. Ignored for `HlslShaderStage::Library`.
+    #[inline]
+    pub fn entry_point<S>(mut self, name: S) -> HlslCompileOptions
+        where S: Into<String>
+    {
+        self.entry_point = name.into();
+        self
+    }
+
+    /// Sets the shader model to compile with, for example `(6, 6)`.
+    #[inline]
+    pub fn shader_model(mut self, major: u32, minor: u32) -> HlslCompileOptions {
+        self.shader_model = Some((major, minor));
+        self
+    }
+
+    /// Defines a preprocessor macro, as if `#define name value` was at the start of the source.
+    ///
+    /// If a macro with the same name was already defined, its value is replaced.
+    #[inline]
+    pub fn define(mut self, name: &str, value: Option<&str>) -> HlslCompileOptions {
+        self.defines.retain(|&(ref n, _)| n != name);
+        self.defines.push((name.to_owned(), value.map(|v| v.to_owned())));
+        self
+    }
+
+    /// Adds `shift` to the binding of the registers of kind `kind` in the register space `space`,
+    /// or in all the register spaces if `space` is `None`.
+    #[inline]
+    pub fn register_shift(mut self, kind: HlslRegisterKind, shift: u32, space: Option<u32>)
+                          -> HlslCompileOptions {
+        self.register_shifts.push((kind, shift, space));
+        self
+    }
+
+    /// Puts the resources of the register space `space` in the descriptor set `set`, instead of
+    /// the descriptor set `space`.
+    #[inline]
+    pub fn space_to_set(mut self, space: u32, set: u32) -> HlslCompileOptions {
+        self.space_to_set.retain(|&(s, _)| s != space);
+        self.space_to_set.push((space, set));
+        self
+    }
+
+    // Returns the arguments to pass to DXC.
+    fn arguments(&self, stage: HlslShaderStage) -> Vec<String> {
+        let mut args = vec!["-spirv".to_owned()];
+
+        // The mesh shading and ray tracing stages require SPIR-V 1.4.
+        match stage {
+            HlslShaderStage::Amplification |
+            HlslShaderStage::Mesh |
+            HlslShaderStage::Library => args.push("-fspv-target-env=vulkan1.2".to_owned()),
+            _ => (),
+        }
+
+        for &(kind, shift, space) in self.register_shifts.iter() {
+            args.push(kind.shift_argument().to_owned());
+            args.push(shift.to_string());
+            args.push(space.map(|s| s.to_string()).unwrap_or("all".to_owned()));
+        }
+
+        args
+    }
+
+    // Returns the target profile, for example `ps_6_0`.
+    fn target_profile(&self, stage: HlslShaderStage) -> String {
+        let (prefix, minimum) = stage.profile();
+        let (major, minor) = match self.shader_model {
+            Some(model) if model > minimum => model,
+            _ => minimum,
+        };
+        format!("{}_{}_{}", prefix, major, minor)
+    }
+}
+
+impl ShaderModule {
+    /// Compiles HLSL source code with DXC and builds a new shader module from the result.
+    ///
+    /// Also returns the description of the entry points of the module, which can be used to pass
+    /// the shader to a pipeline builder. There is only one entry point, except for
+    /// `HlslShaderStage::Library`.
+    ///
+    /// Only available if the `hlsl` feature of vulkano is enabled.
+    ///
+    /// # Safety
+    ///
+    /// - The SPIR-V code may require some features that are not enabled through other means than
+    ///   capabilities. This isn't checked by this function.
+    ///
+    pub unsafe fn from_hlsl_source(device: Arc<Device>, source: &str, stage: HlslShaderStage,
+                                   options: &HlslCompileOptions)
+                                   -> Result<(Arc<ShaderModule>, Vec<EntryPointInfo>),
+                                             HlslCompilationError> {
+        let entry_point = match stage {
+            HlslShaderStage::Library => "",
+            _ => &options.entry_point[..],
+        };
+        let args = options.arguments(stage);
+        let args = args.iter().map(|a| &a[..]).collect::<Vec<_>>();
+        let defines = options
+            .defines
+            .iter()
+            .map(|&(ref name, ref value)| (&name[..], value.as_ref().map(|v| &v[..])))
+            .collect::<Vec<_>>();
+
+        let bytes = hassle_rs::compile_hlsl(&options.file_name,
+                                            source,
+                                            entry_point,
+                                            &options.target_profile(stage),
+                                            &args,
+                                            &defines)
+            .map_err(|err| match err {
+                         HassleError::LoadLibraryError { .. } |
+                         HassleError::LibLoadingError(_) => {
+                             HlslCompilationError::CompilerUnavailable(err.to_string())
+                         },
+                         err => HlslCompilationError::CompilationFailed(err.to_string()),
+                     })?;
+
+        // DXC writes the code in the host endianness.
+        let mut words = bytes
+            .chunks(4)
+            .map(|c| {
+                     let mut word = [0; 4];
+                     word[.. c.len()].copy_from_slice(c);
+                     u32::from_ne_bytes(word)
+                 })
+            .collect::<Vec<_>>();
+        remap_descriptor_sets(&mut words, &options.space_to_set)?;
+
+        let entry_points = reflect::entry_points(&words)?;
+        let module = ShaderModule::from_words(device, &words)?;
+        Ok((module, entry_points))
+    }
+}
+
+/// Error that can happen when compiling HLSL source code.
+#[derive(Debug, Clone)]
+pub enum HlslCompilationError {
+    /// The DXC library couldn't be loaded. Contains the reason.
+    CompilerUnavailable(String),
+    /// The source code couldn't be compiled. Contains the messages of the compiler.
+    CompilationFailed(String),
+    /// The compiled code couldn't be reflected.
+    ReflectionError(ShaderReflectionError),
+    /// The shader module couldn't be created from the compiled code.
+    ModuleCreationError(ShaderModuleCreationError),
+}
+
+impl error::Error for HlslCompilationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            HlslCompilationError::CompilerUnavailable(_) => {
+                "the DXC library couldn't be loaded"
+            },
+            HlslCompilationError::CompilationFailed(_) => "the source code couldn't be compiled",
+            HlslCompilationError::ReflectionError(_) => "the compiled code couldn't be reflected",
+            HlslCompilationError::ModuleCreationError(_) => {
+                "the shader module couldn't be created from the compiled code"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            HlslCompilationError::ReflectionError(ref err) => Some(err),
+            HlslCompilationError::ModuleCreationError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for HlslCompilationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ShaderReflectionError> for HlslCompilationError {
+    #[inline]
+    fn from(err: ShaderReflectionError) -> HlslCompilationError {
+        HlslCompilationError::ReflectionError(err)
+    }
+}
+
+impl From<ShaderModuleCreationError> for HlslCompilationError {
+    #[inline]
+    fn from(err: ShaderModuleCreationError) -> HlslCompilationError {
+        HlslCompilationError::ModuleCreationError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pipeline::hlsl::HlslCompileOptions;
+    use pipeline::hlsl::HlslRegisterKind;
+    use pipeline::hlsl::HlslShaderStage;
+
+    #[test]
+    fn arguments() {
+        let options = HlslCompileOptions::new()
+            .register_shift(HlslRegisterKind::ShaderResource, 8, None)
+            .register_shift(HlslRegisterKind::Sampler, 16, Some(2));
+
+        assert_eq!(options.arguments(HlslShaderStage::Pixel),
+                   vec!["-spirv", "-fvk-t-shift", "8", "all", "-fvk-s-shift", "16", "2"]);
+        assert_eq!(options.target_profile(HlslShaderStage::Pixel), "ps_6_0");
+        assert_eq!(options.target_profile(HlslShaderStage::Library), "lib_6_3");
+        assert_eq!(options.clone().shader_model(6, 6).target_profile(HlslShaderStage::Mesh),
+                   "ms_6_6");
+    }
+}
@@ -45,6 +45,7 @@ impl InputAssembly {
 ///
 /// Note that some topologies don't support primitive restart.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PrimitiveTopology {
     PointList,
     LineList,
@@ -95,6 +96,34 @@ impl PrimitiveTopology {
             _ => false,
         }
     }
+
+    /// Returns true if `self` and `other` belong to the same topology class, ie. if they both
+    /// describe points, lines, triangles or patches.
+    ///
+    /// The topology passed to a pipeline whose primitive topology is dynamic must belong to the
+    /// same class as the topology the pipeline was created with.
+    #[inline]
+    pub fn same_class(&self, other: &PrimitiveTopology) -> bool {
+        self.class() == other.class()
+    }
+
+    // Returns 0 for points, 1 for lines, 2 for triangles and 3 for patches.
+    #[inline]
+    fn class(&self) -> u8 {
+        match *self {
+            PrimitiveTopology::PointList => 0,
+            PrimitiveTopology::LineList |
+            PrimitiveTopology::LineStrip |
+            PrimitiveTopology::LineListWithAdjacency |
+            PrimitiveTopology::LineStripWithAdjacency => 1,
+            PrimitiveTopology::TriangleList |
+            PrimitiveTopology::TriangleStrip |
+            PrimitiveTopology::TriangleFan |
+            PrimitiveTopology::TriangleListWithAdjacency |
+            PrimitiveTopology::TriangleStripWithAdjacency => 2,
+            PrimitiveTopology::PatchList { .. } => 3,
+        }
+    }
 }
 
 /// Trait for types that can be used as indices by the GPU.
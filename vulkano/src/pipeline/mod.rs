@@ -17,12 +17,15 @@
 //! the CPU). Consequently it is a CPU-intensive operation that should be performed at
 //! initialization or during a loading screen.
 //!
-//! There are two kinds of pipelines:
+//! There are three kinds of pipelines:
 //!
 //! - `ComputePipeline`s, for compute operations (general-purpose operations that read/write data
 //!   in buffers or raw pixels in images).
 //! - `GraphicsPipeline`s, for graphical operations (operations that take vertices as input and
 //!   write pixels to a framebuffer).
+//! - `RayTracingPipeline`s, for tracing rays against acceleration structures. They require the
+//!   `VK_KHR_ray_tracing_pipeline` extension, and are used together with a shader binding table
+//!   from the `shader_binding_table` module.
 //!
 //! # Creating a compute pipeline.
 //!
@@ -31,6 +34,11 @@
 //! TODO: write the rest
 //! For now vulkano has no "clean" way to create shaders ; everything's a bit hacky
 //!
+//! Shaders that are only known at runtime can be described by reflecting their SPIR-V code with
+//! the `reflect` module instead of using `vulkano-shaders`. If the `shaderc` feature is enabled,
+//! the `glsl` module can also compile GLSL source code at runtime, and the `hlsl` module does the
+//! same for HLSL source code if the `hlsl` feature is enabled.
+//!
 //! # Creating a graphics pipeline
 //!
 //! A graphics operation takes vertices or vertices and indices as input, and writes pixels to a
@@ -75,25 +83,58 @@
 // to avoid duplicating code, so we hide the warnings for now
 #![allow(deprecated)]
 
+use vk;
+
 pub use self::compute_pipeline::ComputePipeline;
 pub use self::compute_pipeline::ComputePipelineAbstract;
 pub use self::compute_pipeline::ComputePipelineCreationError;
 pub use self::compute_pipeline::ComputePipelineSys;
+pub use self::compute_pipeline::SubgroupSizeControl;
+pub(crate) use self::compute_pipeline::ComputePipelineKey;
 pub use self::graphics_pipeline::GraphicsPipeline;
 pub use self::graphics_pipeline::GraphicsPipelineAbstract;
 pub use self::graphics_pipeline::GraphicsPipelineBuilder;
 pub use self::graphics_pipeline::GraphicsPipelineCreationError;
+pub use self::graphics_pipeline::GraphicsPipelineLibrary;
+pub use self::graphics_pipeline::GraphicsPipelineLibraryAbstract;
+pub use self::graphics_pipeline::GraphicsPipelineLibraryParts;
 pub use self::graphics_pipeline::GraphicsPipelineSys;
+pub use self::ray_tracing_pipeline::RayTracingPipeline;
+pub use self::ray_tracing_pipeline::RayTracingPipelineAbstract;
+pub use self::ray_tracing_pipeline::RayTracingPipelineBuilder;
+pub use self::ray_tracing_pipeline::RayTracingPipelineCreationError;
+pub use self::ray_tracing_pipeline::RayTracingPipelineSys;
+pub use self::ray_tracing_pipeline::RayTracingProperties;
+pub use self::ray_tracing_pipeline::ShaderBindingTableRegion;
+pub use self::ray_tracing_pipeline::ShaderBindingTableRegions;
+pub use self::ray_tracing_pipeline::ShaderGroupType;
 
 mod compute_pipeline;
 mod graphics_pipeline;
+mod ray_tracing_pipeline;
 
 pub mod blend;
 pub mod cache;
+pub mod creation_feedback;
 pub mod depth_stencil;
+#[cfg(feature = "shaderc")]
+pub mod glsl;
+#[cfg(feature = "hlsl")]
+pub mod hlsl;
 pub mod input_assembly;
 pub mod multisample;
 pub mod raster;
+pub mod reflect;
 pub mod shader;
+pub mod shader_binding_table;
 pub mod vertex;
 pub mod viewport;
+
+/// The type of pipeline that a command operates on, when binding descriptor sets for example.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum PipelineBindPoint {
+    Graphics = vk::PIPELINE_BIND_POINT_GRAPHICS,
+    Compute = vk::PIPELINE_BIND_POINT_COMPUTE,
+    RayTracing = vk::PIPELINE_BIND_POINT_RAY_TRACING_KHR,
+}
@@ -39,6 +39,7 @@
 /// See the documentation in this module.
 #[deprecated(note = "No longer needed")]
 #[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Multisample {
     pub rasterization_samples: u32,
     pub sample_mask: [u32; 4],
@@ -17,6 +17,7 @@ use vk;
 
 /// State of the rasterizer.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Rasterization {
     /// If true, then the depth value of the vertices will be clamped to [0.0 ; 1.0]. If false,
     /// fragments whose depth is outside of this range will be discarded.
@@ -43,6 +44,18 @@ pub struct Rasterization {
     pub line_width: Option<f32>,
 
     pub depth_bias: DepthBiasControl,
+
+    /// Conservative rasterization mode. Anything else than `Disabled` requires the
+    /// `VK_EXT_conservative_rasterization` extension to be enabled on the device.
+    pub conservative: ConservativeRasterization,
+
+    /// Algorithm used to rasterize lines. Anything else than `Default` requires the
+    /// `VK_EXT_line_rasterization` extension to be enabled on the device.
+    pub line_rasterization_mode: LineRasterizationMode,
+
+    /// Stippling of lines. Anything else than `Disabled` requires the `VK_EXT_line_rasterization`
+    /// extension to be enabled on the device.
+    pub line_stipple: LineStippleControl,
 }
 
 impl Default for Rasterization {
@@ -56,11 +69,15 @@ impl Default for Rasterization {
             front_face: Default::default(),
             line_width: Some(1.0),
             depth_bias: DepthBiasControl::Disabled,
+            conservative: ConservativeRasterization::Disabled,
+            line_rasterization_mode: LineRasterizationMode::Default,
+            line_stipple: LineStippleControl::Disabled,
         }
     }
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DepthBiasControl {
     Disabled,
     Dynamic,
@@ -77,7 +94,8 @@ impl DepthBiasControl {
     }
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DepthBias {
     pub constant_factor: f32,
     /// Requires the `depth_bias_clamp` feature to be enabled.
@@ -85,13 +103,117 @@ pub struct DepthBias {
     pub slope_factor: f32,
 }
 
+/// Conservative rasterization mode.
+///
+/// With regular rasterization, a fragment is generated for each pixel whose center is covered by
+/// the primitive. Conservative rasterization instead generates fragments depending on whether
+/// the primitive covers the pixel at all.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ConservativeRasterization {
+    /// Regular rasterization.
+    Disabled,
+
+    /// A fragment is generated for each pixel that is at least partially covered by the
+    /// primitive.
+    ///
+    /// The primitive is enlarged by an implementation-defined size, plus `extra_size` pixels.
+    /// `extra_size` must be between 0.0 and the `maxExtraPrimitiveOverestimationSize` limit of
+    /// the physical device.
+    Overestimate {
+        extra_size: f32,
+    },
+
+    /// A fragment is generated only for the pixels that are fully covered by the primitive.
+    ///
+    /// Not all implementations support this mode.
+    Underestimate,
+}
+
+impl ConservativeRasterization {
+    /// Returns true if equal to `ConservativeRasterization::Disabled`.
+    #[inline]
+    pub fn is_disabled(&self) -> bool {
+        match *self {
+            ConservativeRasterization::Disabled => true,
+            _ => false,
+        }
+    }
+}
+
+/// Algorithm used to rasterize lines.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[repr(u32)]
+pub enum LineRasterizationMode {
+    /// Implementation-defined algorithm. Equivalent to `Rectangular` if the `strict_lines` limit
+    /// of the physical device is true, and to `Bresenham` or `Rectangular` otherwise.
+    Default = vk::LINE_RASTERIZATION_MODE_DEFAULT_EXT,
+
+    /// Lines are rasterized as parallelograms.
+    ///
+    /// Requires the `rectangular_lines` line rasterization feature.
+    Rectangular = vk::LINE_RASTERIZATION_MODE_RECTANGULAR_EXT,
+
+    /// Lines are rasterized with the Bresenham algorithm, ie. as a sequence of pixels.
+    ///
+    /// Requires the `bresenham_lines` line rasterization feature.
+    Bresenham = vk::LINE_RASTERIZATION_MODE_BRESENHAM_EXT,
+
+    /// Lines are rasterized as antialiased rectangles, and the coverage of each pixel is
+    /// written to its alpha value.
+    ///
+    /// Requires the `smooth_lines` line rasterization feature.
+    RectangularSmooth = vk::LINE_RASTERIZATION_MODE_RECTANGULAR_SMOOTH_EXT,
+}
+
+impl Default for LineRasterizationMode {
+    #[inline]
+    fn default() -> LineRasterizationMode {
+        LineRasterizationMode::Default
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum LineStippleControl {
+    Disabled,
+    Dynamic,
+    Static(LineStipple),
+}
+
+impl LineStippleControl {
+    #[inline]
+    pub fn is_dynamic(&self) -> bool {
+        match *self {
+            LineStippleControl::Dynamic => true,
+            _ => false,
+        }
+    }
+}
+
+/// Stipple pattern of lines.
+///
+/// Each bit of `pattern` corresponds to a group of `factor` consecutive pixels of the line. If the
+/// bit is 0, the pixels are discarded. The pattern is repeated along the line, starting from the
+/// least significant bit.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LineStipple {
+    /// Number of pixels covered by each bit of `pattern`. Must be between 1 and 256.
+    pub factor: u32,
+    /// Bit pattern of the stipple.
+    pub pattern: u16,
+}
+
 /// Specifies the culling mode.
 ///
 /// This setting works in pair with `front_face`. The `front_face` setting tells the GPU whether
 /// clockwise or counter-clockwise correspond to the front and the back of each triangle. Then
 /// `cull_mode` lets you specify whether front faces should be discarded, back faces should be
 /// discarded, or none, or both.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum CullMode {
     /// No culling.
@@ -112,7 +234,8 @@ impl Default for CullMode {
 }
 
 /// Specifies which triangle orientation corresponds to the front or the triangle.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum FrontFace {
     /// Triangles whose vertices are oriented counter-clockwise on the screen will be considered
@@ -132,6 +255,7 @@ impl Default for FrontFace {
 }
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum PolygonMode {
     Fill = vk::POLYGON_MODE_FILL,
@@ -0,0 +1,837 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use smallvec::SmallVec;
+use std::error;
+use std::ffi::CStr;
+use std::fmt;
+use std::marker::PhantomData;
+use std::mem;
+use std::ptr;
+use std::slice;
+use std::sync::Arc;
+
+use descriptor::descriptor::DescriptorDesc;
+use descriptor::descriptor::ShaderStages;
+use descriptor::descriptor_set::UnsafeDescriptorSetLayout;
+use descriptor::pipeline_layout::PipelineLayout;
+use descriptor::pipeline_layout::PipelineLayoutAbstract;
+use descriptor::pipeline_layout::PipelineLayoutCreationError;
+use descriptor::pipeline_layout::PipelineLayoutDesc;
+use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+use descriptor::pipeline_layout::PipelineLayoutNotSupersetError;
+use descriptor::pipeline_layout::PipelineLayoutSuperset;
+use descriptor::pipeline_layout::PipelineLayoutSys;
+use descriptor::pipeline_layout::RuntimePipelineDesc;
+use descriptor::pipeline_layout::RuntimePipelineDescError;
+use pipeline::cache::PipelineCache;
+use pipeline::shader::EntryPointAbstract;
+use pipeline::shader::ShaderModule;
+use pipeline::shader::SpecializationConstants;
+use pipeline::shader::SpecializationMapEntry;
+use spirv::Spirv;
+
+use Error;
+use OomError;
+use SafeDeref;
+use VulkanObject;
+use check_errors;
+use device::Device;
+use device::DeviceOwned;
+use vk;
+
+/// Limits of the physical device related to ray tracing.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct RayTracingProperties {
+    /// Size in bytes of the handle of a shader group.
+    pub shader_group_handle_size: u32,
+    /// Maximum value that can be passed to `max_recursion_depth` when building a pipeline.
+    pub max_ray_recursion_depth: u32,
+    /// Maximum stride in bytes between the records of a shader binding table region.
+    pub max_shader_group_stride: u32,
+    /// Required alignment in bytes of the start of each shader binding table region.
+    pub shader_group_base_alignment: u32,
+    /// Required alignment in bytes of each record of a shader binding table.
+    pub shader_group_handle_alignment: u32,
+    /// Required alignment in bytes of the scratch buffer used when building an acceleration
+    /// structure.
+    pub min_acceleration_structure_scratch_offset_alignment: u32,
+}
+
+/// A pipeline object that describes to the Vulkan implementation how it should trace rays.
+///
+/// A ray tracing pipeline is made of shader stages grouped into *shader groups*. Each group is
+/// either a ray generation shader, a miss shader, a callable shader, or a *hit group* made of
+/// a closest hit, an any hit and an intersection shader. The groups are numbered in the order
+/// in which they were added to the builder, and the handle of each group must be written in a
+/// shader binding table in order to be used by `trace_rays`.
+///
+/// Use `RayTracingPipeline::start()` to build one.
+pub struct RayTracingPipeline<Pl> {
+    inner: Inner,
+    pipeline_layout: Pl,
+    groups: Vec<ShaderGroupType>,
+    group_handles: Vec<u8>,
+    group_handle_size: usize,
+}
+
+struct Inner {
+    pipeline: vk::Pipeline,
+    device: Arc<Device>,
+}
+
+impl RayTracingPipeline<()> {
+    /// Starts the building process of a ray tracing pipeline.
+    #[inline]
+    pub fn start<'a>() -> RayTracingPipelineBuilder<'a> {
+        RayTracingPipelineBuilder {
+            stages: Vec::new(),
+            groups: Vec::new(),
+            descriptors: Vec::new(),
+            push_constants: Vec::new(),
+            max_recursion_depth: 1,
+            cache: None,
+        }
+    }
+}
+
+impl<Pl> RayTracingPipeline<Pl> {
+    /// Returns the `Device` this ray tracing pipeline was created with.
+    #[inline]
+    pub fn device(&self) -> &Arc<Device> {
+        &self.inner.device
+    }
+
+    /// Returns the pipeline layout used in this ray tracing pipeline.
+    #[inline]
+    pub fn layout(&self) -> &Pl {
+        &self.pipeline_layout
+    }
+
+    /// Returns the type of each shader group of the pipeline, in the order in which they were
+    /// added to the builder.
+    #[inline]
+    pub fn shader_groups(&self) -> &[ShaderGroupType] {
+        &self.groups
+    }
+
+    /// Returns the opaque handle of a shader group, to write in a shader binding table.
+    ///
+    /// Returns `None` if `group` is out of range.
+    #[inline]
+    pub fn group_handle(&self, group: usize) -> Option<&[u8]> {
+        if group >= self.groups.len() {
+            return None;
+        }
+
+        let start = group * self.group_handle_size;
+        Some(&self.group_handles[start .. start + self.group_handle_size])
+    }
+}
+
+impl<Pl> fmt::Debug for RayTracingPipeline<Pl> {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "<Vulkan ray tracing pipeline {:?}>", self.inner.pipeline)
+    }
+}
+
+/// Type of a shader group of a ray tracing pipeline.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShaderGroupType {
+    /// A single ray generation shader.
+    RayGeneration,
+    /// A single miss shader.
+    Miss,
+    /// A hit group, made of a closest hit, an any hit and an intersection shader that are all
+    /// optional.
+    Hit,
+    /// A single callable shader.
+    Callable,
+}
+
+/// Region of a buffer that contains the records of a shader binding table.
+///
+/// All the values are in bytes. A region whose `size` is 0 is unused.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ShaderBindingTableRegion {
+    /// Offset of the first record within the buffer.
+    pub offset: usize,
+    /// Number of bytes between the start of a record and the start of the next one.
+    pub stride: usize,
+    /// Total size of the region.
+    pub size: usize,
+}
+
+/// Regions of a shader binding table buffer to pass to `trace_rays`.
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct ShaderBindingTableRegions {
+    /// Region containing the ray generation shader group. Its `size` must be equal to its
+    /// `stride`.
+    pub raygen: ShaderBindingTableRegion,
+    /// Region containing the miss shader groups.
+    pub miss: ShaderBindingTableRegion,
+    /// Region containing the hit groups.
+    pub hit: ShaderBindingTableRegion,
+    /// Region containing the callable shader groups.
+    pub callable: ShaderBindingTableRegion,
+}
+
+/// Prototype for a `RayTracingPipeline`.
+///
+/// Each call to `raygen_shader`, `miss_shader`, `callable_shader` and `hit_group` adds a new
+/// shader group to the pipeline. The closest hit, any hit and intersection shaders are added to
+/// the hit group that was last started with `hit_group`.
+///
+/// Unless you pass a pipeline layout with `build_with_pipeline_layout`, the layout of the
+/// pipeline is the union of the layouts of all the shader stages. When the pipeline is built,
+/// the execution model of each shader is checked against the method it was passed to.
+pub struct RayTracingPipelineBuilder<'a> {
+    stages: Vec<Stage<'a>>,
+    groups: Vec<Group>,
+    descriptors: Vec<(u32, u32, DescriptorDesc)>,
+    push_constants: Vec<PipelineLayoutDescPcRange>,
+    max_recursion_depth: u32,
+    cache: Option<Arc<PipelineCache>>,
+}
+
+// A shader stage of the pipeline, with its specialization constants turned into bytes.
+struct Stage<'a> {
+    module: &'a ShaderModule,
+    name: &'a CStr,
+    stage: vk::ShaderStageFlags,
+    spec_entries: &'static [SpecializationMapEntry],
+    spec_data: Vec<u8>,
+}
+
+// A shader group of the pipeline. The values are indices within the list of stages.
+struct Group {
+    ty: ShaderGroupType,
+    general: Option<u32>,
+    closest_hit: Option<u32>,
+    any_hit: Option<u32>,
+    intersection: Option<u32>,
+}
+
+impl<'a> RayTracingPipelineBuilder<'a> {
+    /// Adds a shader group containing a ray generation shader.
+    #[inline]
+    pub fn raygen_shader<S>(self, shader: &'a S, specialization: &S::SpecializationConstants)
+                            -> Self
+        where S: EntryPointAbstract
+    {
+        self.general_group(ShaderGroupType::RayGeneration,
+                           vk::SHADER_STAGE_RAYGEN_BIT_KHR,
+                           shader,
+                           specialization)
+    }
+
+    /// Adds a shader group containing a miss shader.
+    #[inline]
+    pub fn miss_shader<S>(self, shader: &'a S, specialization: &S::SpecializationConstants)
+                          -> Self
+        where S: EntryPointAbstract
+    {
+        self.general_group(ShaderGroupType::Miss,
+                           vk::SHADER_STAGE_MISS_BIT_KHR,
+                           shader,
+                           specialization)
+    }
+
+    /// Adds a shader group containing a callable shader.
+    #[inline]
+    pub fn callable_shader<S>(self, shader: &'a S, specialization: &S::SpecializationConstants)
+                              -> Self
+        where S: EntryPointAbstract
+    {
+        self.general_group(ShaderGroupType::Callable,
+                           vk::SHADER_STAGE_CALLABLE_BIT_KHR,
+                           shader,
+                           specialization)
+    }
+
+    /// Starts a new hit group. Use `closest_hit_shader`, `any_hit_shader` and
+    /// `intersection_shader` to add shaders to it.
+    ///
+    /// A hit group without an intersection shader is used for triangles, and a hit group with
+    /// an intersection shader is used for procedural geometry.
+    #[inline]
+    pub fn hit_group(mut self) -> Self {
+        self.groups.push(Group {
+                             ty: ShaderGroupType::Hit,
+                             general: None,
+                             closest_hit: None,
+                             any_hit: None,
+                             intersection: None,
+                         });
+        self
+    }
+
+    /// Sets the closest hit shader of the current hit group.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `hit_group` hasn't been called before.
+    ///
+    #[inline]
+    pub fn closest_hit_shader<S>(mut self, shader: &'a S,
+                                 specialization: &S::SpecializationConstants)
+                                 -> Self
+        where S: EntryPointAbstract
+    {
+        let stage = self.add_stage(vk::SHADER_STAGE_CLOSEST_HIT_BIT_KHR, shader, specialization);
+        self.current_hit_group().closest_hit = Some(stage);
+        self
+    }
+
+    /// Sets the any hit shader of the current hit group.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `hit_group` hasn't been called before.
+    ///
+    #[inline]
+    pub fn any_hit_shader<S>(mut self, shader: &'a S, specialization: &S::SpecializationConstants)
+                             -> Self
+        where S: EntryPointAbstract
+    {
+        let stage = self.add_stage(vk::SHADER_STAGE_ANY_HIT_BIT_KHR, shader, specialization);
+        self.current_hit_group().any_hit = Some(stage);
+        self
+    }
+
+    /// Sets the intersection shader of the current hit group.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `hit_group` hasn't been called before.
+    ///
+    #[inline]
+    pub fn intersection_shader<S>(mut self, shader: &'a S,
+                                  specialization: &S::SpecializationConstants)
+                                  -> Self
+        where S: EntryPointAbstract
+    {
+        let stage = self.add_stage(vk::SHADER_STAGE_INTERSECTION_BIT_KHR, shader, specialization);
+        self.current_hit_group().intersection = Some(stage);
+        self
+    }
+
+    /// Sets the maximum number of levels of recursive `traceRayEXT` calls that the shaders are
+    /// allowed to make. The default value is 1, which means that only the ray generation shader
+    /// can trace rays.
+    #[inline]
+    pub fn max_recursion_depth(mut self, depth: u32) -> Self {
+        self.max_recursion_depth = depth;
+        self
+    }
+
+    /// Sets the pipeline cache to use when creating the pipeline.
+    ///
+    /// The Vulkan implementation will look in the cache for an existing pipeline, or add one if
+    /// it doesn't exist. See [the `cache` module](cache/index.html) for more info.
+    #[inline]
+    pub fn pipeline_cache(mut self, cache: Arc<PipelineCache>) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Builds the ray tracing pipeline, with a pipeline layout that is the union of the layouts
+    /// of the shader stages.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the pipeline cache wasn't created with the same device.
+    ///
+    pub fn build(self, device: Arc<Device>)
+                 -> Result<RayTracingPipeline<PipelineLayout<RuntimePipelineDesc>>,
+                           RayTracingPipelineCreationError> {
+        let desc = RuntimePipelineDesc::union_of(self.descriptors.iter().cloned(),
+                                                 self.push_constants.iter().cloned())?;
+        let pipeline_layout = desc.build(device.clone())?;
+        unsafe { self.build_inner(device, pipeline_layout) }
+    }
+
+    /// Builds the ray tracing pipeline with a specific pipeline layout.
+    ///
+    /// An error will be returned if the pipeline layout isn't a superset of what the shaders
+    /// use.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the pipeline cache wasn't created with the same device.
+    ///
+    pub fn build_with_pipeline_layout<Pl>(self, device: Arc<Device>, pipeline_layout: Pl)
+                                          -> Result<RayTracingPipeline<Pl>,
+                                                    RayTracingPipelineCreationError>
+        where Pl: PipelineLayoutAbstract
+    {
+        let desc = RuntimePipelineDesc::union_of(self.descriptors.iter().cloned(),
+                                                 self.push_constants.iter().cloned())?;
+        PipelineLayoutSuperset::ensure_superset_of(&pipeline_layout, &desc)?;
+        unsafe { self.build_inner(device, pipeline_layout) }
+    }
+
+    // Adds a shader group that contains a single shader.
+    fn general_group<S>(mut self, ty: ShaderGroupType, stage: vk::ShaderStageFlags, shader: &'a S,
+                        specialization: &S::SpecializationConstants)
+                        -> Self
+        where S: EntryPointAbstract
+    {
+        let stage = self.add_stage(stage, shader, specialization);
+        self.groups.push(Group {
+                             ty: ty,
+                             general: Some(stage),
+                             closest_hit: None,
+                             any_hit: None,
+                             intersection: None,
+                         });
+        self
+    }
+
+    // Adds a shader stage and its layout, and returns the index of the stage.
+    fn add_stage<S>(&mut self, stage: vk::ShaderStageFlags, shader: &'a S,
+                    specialization: &S::SpecializationConstants)
+                    -> u32
+        where S: EntryPointAbstract
+    {
+        let layout = shader.layout();
+        for set in 0 .. layout.num_sets() {
+            for binding in 0 .. layout.num_bindings_in_set(set).unwrap_or(0) {
+                if let Some(desc) = layout.descriptor(set, binding) {
+                    self.descriptors.push((set as u32, binding as u32, desc));
+                }
+            }
+        }
+        for num in 0 .. layout.num_push_constants_ranges() {
+            if let Some(range) = layout.push_constants_range(num) {
+                self.push_constants.push(range);
+            }
+        }
+
+        let spec_data = unsafe {
+            slice::from_raw_parts(specialization as *const S::SpecializationConstants as *const u8,
+                                  mem::size_of_val(specialization))
+                .to_vec()
+        };
+
+        self.stages.push(Stage {
+                             module: shader.module(),
+                             name: shader.name(),
+                             stage: stage,
+                             spec_entries: S::SpecializationConstants::descriptors(),
+                             spec_data: spec_data,
+                         });
+        (self.stages.len() - 1) as u32
+    }
+
+    fn current_hit_group(&mut self) -> &mut Group {
+        match self.groups.last_mut() {
+            Some(group) if group.ty == ShaderGroupType::Hit => group,
+            _ => panic!("`hit_group` must be called before adding a hit shader"),
+        }
+    }
+
+    // Actual implementation of the build functions.
+    unsafe fn build_inner<Pl>(self, device: Arc<Device>, pipeline_layout: Pl)
+                              -> Result<RayTracingPipeline<Pl>, RayTracingPipelineCreationError>
+        where Pl: PipelineLayoutAbstract
+    {
+        if !device.enabled_features().ray_tracing_pipeline {
+            return Err(RayTracingPipelineCreationError::RayTracingPipelineFeatureNotEnabled);
+        }
+
+        if !self.groups
+            .iter()
+            .any(|g| g.ty == ShaderGroupType::RayGeneration)
+        {
+            return Err(RayTracingPipelineCreationError::NoRayGenerationShader);
+        }
+
+        // The execution model of each shader must match the stage it was added as. An entry point
+        // that can't be found is reported the same way.
+        for stage in self.stages.iter() {
+            let stages = match (Spirv::parse(stage.module.spirv()), stage.name.to_str()) {
+                (Ok(spirv), Ok(name)) => spirv.entry_point_stages(name),
+                _ => ShaderStages::none(),
+            };
+
+            if stages.into_vulkan_bits() & stage.stage == 0 {
+                return Err(RayTracingPipelineCreationError::WrongShaderType);
+            }
+        }
+
+        let max_depth = device.ray_tracing_properties().max_ray_recursion_depth;
+        if self.max_recursion_depth > max_depth {
+            return Err(RayTracingPipelineCreationError::MaxRecursionDepthExceeded {
+                           max: max_depth,
+                           obtained: self.max_recursion_depth,
+                       });
+        }
+
+        let vk = device.pointers();
+
+        let cache = match self.cache {
+            Some(ref cache) => {
+                assert_eq!(cache.device().internal_object(), device.internal_object());
+                cache.internal_object()
+            },
+            None => 0,
+        };
+
+        // The specialization infos must be built before the stages, as the stages point to them.
+        let specializations = self.stages
+            .iter()
+            .map(|stage| {
+                vk::SpecializationInfo {
+                    mapEntryCount: stage.spec_entries.len() as u32,
+                    pMapEntries: stage.spec_entries.as_ptr() as *const _,
+                    dataSize: stage.spec_data.len(),
+                    pData: stage.spec_data.as_ptr() as *const _,
+                }
+            })
+            .collect::<SmallVec<[_; 8]>>();
+
+        let stages = self.stages
+            .iter()
+            .zip(specializations.iter())
+            .map(|(stage, specialization)| {
+                vk::PipelineShaderStageCreateInfo {
+                    sType: vk::STRUCTURE_TYPE_PIPELINE_SHADER_STAGE_CREATE_INFO,
+                    pNext: ptr::null(),
+                    flags: 0,
+                    stage: stage.stage,
+                    module: stage.module.internal_object(),
+                    pName: stage.name.as_ptr(),
+                    pSpecializationInfo: if specialization.dataSize == 0 {
+                        ptr::null()
+                    } else {
+                        specialization
+                    },
+                }
+            })
+            .collect::<SmallVec<[_; 8]>>();
+
+        let groups = self.groups
+            .iter()
+            .map(|group| {
+                let ty = match group.ty {
+                    ShaderGroupType::Hit if group.intersection.is_some() => {
+                        vk::RAY_TRACING_SHADER_GROUP_TYPE_PROCEDURAL_HIT_GROUP_KHR
+                    },
+                    ShaderGroupType::Hit => {
+                        vk::RAY_TRACING_SHADER_GROUP_TYPE_TRIANGLES_HIT_GROUP_KHR
+                    },
+                    _ => vk::RAY_TRACING_SHADER_GROUP_TYPE_GENERAL_KHR,
+                };
+
+                vk::RayTracingShaderGroupCreateInfoKHR {
+                    sType: vk::STRUCTURE_TYPE_RAY_TRACING_SHADER_GROUP_CREATE_INFO_KHR,
+                    pNext: ptr::null(),
+                    ty: ty,
+                    generalShader: group.general.unwrap_or(vk::SHADER_UNUSED_KHR),
+                    closestHitShader: group.closest_hit.unwrap_or(vk::SHADER_UNUSED_KHR),
+                    anyHitShader: group.any_hit.unwrap_or(vk::SHADER_UNUSED_KHR),
+                    intersectionShader: group.intersection.unwrap_or(vk::SHADER_UNUSED_KHR),
+                    pShaderGroupCaptureReplayHandle: ptr::null(),
+                }
+            })
+            .collect::<SmallVec<[_; 8]>>();
+
+        let pipeline = {
+            let infos = vk::RayTracingPipelineCreateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_RAY_TRACING_PIPELINE_CREATE_INFO_KHR,
+                pNext: ptr::null(),
+                flags: 0,
+                stageCount: stages.len() as u32,
+                pStages: stages.as_ptr(),
+                groupCount: groups.len() as u32,
+                pGroups: groups.as_ptr(),
+                maxPipelineRayRecursionDepth: self.max_recursion_depth,
+                pLibraryInfo: ptr::null(),
+                pLibraryInterface: ptr::null(),
+                pDynamicState: ptr::null(),
+                layout: PipelineLayoutAbstract::sys(&pipeline_layout).internal_object(),
+                basePipelineHandle: 0,
+                basePipelineIndex: -1,
+            };
+
+            let _span = instrument_span!("vkCreateRayTracingPipelinesKHR",
+                                         cache = ::instrument::pipeline_cache_name(&device, cache),
+                                         stages = stages.len(),
+                                         groups = groups.len());
+            let mut output = mem::uninitialized();
+            check_errors(vk.CreateRayTracingPipelinesKHR(device.internal_object(),
+                                                         0,
+                                                         cache,
+                                                         1,
+                                                         &infos,
+                                                         device.allocator_ptr(),
+                                                         &mut output))?;
+            output
+        };
+
+        // Build the object now, so that the pipeline gets destroyed if retrieving the handles
+        // fails.
+        let inner = Inner {
+            device: device.clone(),
+            pipeline: pipeline,
+        };
+
+        let group_handle_size = device.ray_tracing_properties().shader_group_handle_size as usize;
+        let mut group_handles = vec![0u8; group_handle_size * groups.len()];
+        check_errors(vk.GetRayTracingShaderGroupHandlesKHR(device.internal_object(),
+                                                           pipeline,
+                                                           0,
+                                                           groups.len() as u32,
+                                                           group_handles.len(),
+                                                           group_handles.as_mut_ptr() as *mut _))?;
+
+        Ok(RayTracingPipeline {
+               inner: inner,
+               pipeline_layout: pipeline_layout,
+               groups: self.groups.iter().map(|g| g.ty).collect(),
+               group_handles: group_handles,
+               group_handle_size: group_handle_size,
+           })
+    }
+}
+
+/// Trait implemented on all ray tracing pipelines.
+pub unsafe trait RayTracingPipelineAbstract: PipelineLayoutAbstract {
+    /// Returns an opaque object that represents the inside of the ray tracing pipeline.
+    fn inner(&self) -> RayTracingPipelineSys;
+}
+
+unsafe impl<Pl> RayTracingPipelineAbstract for RayTracingPipeline<Pl>
+    where Pl: PipelineLayoutAbstract
+{
+    #[inline]
+    fn inner(&self) -> RayTracingPipelineSys {
+        RayTracingPipelineSys(self.inner.pipeline, PhantomData)
+    }
+}
+
+unsafe impl<T> RayTracingPipelineAbstract for T
+    where T: SafeDeref,
+          T::Target: RayTracingPipelineAbstract
+{
+    #[inline]
+    fn inner(&self) -> RayTracingPipelineSys {
+        (**self).inner()
+    }
+}
+
+/// Opaque object that represents the inside of the ray tracing pipeline. Can be made into a
+/// trait object.
+#[derive(Debug, Copy, Clone)]
+pub struct RayTracingPipelineSys<'a>(vk::Pipeline, PhantomData<&'a ()>);
+
+unsafe impl<'a> VulkanObject for RayTracingPipelineSys<'a> {
+    type Object = vk::Pipeline;
+
+    const TYPE: vk::DebugReportObjectTypeEXT = vk::DEBUG_REPORT_OBJECT_TYPE_PIPELINE_EXT;
+
+    #[inline]
+    fn internal_object(&self) -> vk::Pipeline {
+        self.0
+    }
+}
+
+unsafe impl<Pl> PipelineLayoutAbstract for RayTracingPipeline<Pl>
+    where Pl: PipelineLayoutAbstract
+{
+    #[inline]
+    fn sys(&self) -> PipelineLayoutSys {
+        self.layout().sys()
+    }
+
+    #[inline]
+    fn descriptor_set_layout(&self, index: usize) -> Option<&Arc<UnsafeDescriptorSetLayout>> {
+        self.layout().descriptor_set_layout(index)
+    }
+}
+
+unsafe impl<Pl> PipelineLayoutDesc for RayTracingPipeline<Pl>
+    where Pl: PipelineLayoutDesc
+{
+    #[inline]
+    fn num_sets(&self) -> usize {
+        self.pipeline_layout.num_sets()
+    }
+
+    #[inline]
+    fn num_bindings_in_set(&self, set: usize) -> Option<usize> {
+        self.pipeline_layout.num_bindings_in_set(set)
+    }
+
+    #[inline]
+    fn descriptor(&self, set: usize, binding: usize) -> Option<DescriptorDesc> {
+        self.pipeline_layout.descriptor(set, binding)
+    }
+
+    #[inline]
+    fn num_push_constants_ranges(&self) -> usize {
+        self.pipeline_layout.num_push_constants_ranges()
+    }
+
+    #[inline]
+    fn push_constants_range(&self, num: usize) -> Option<PipelineLayoutDescPcRange> {
+        self.pipeline_layout.push_constants_range(num)
+    }
+}
+
+unsafe impl<Pl> DeviceOwned for RayTracingPipeline<Pl> {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.device()
+    }
+}
+
+unsafe impl<Pl> VulkanObject for RayTracingPipeline<Pl> {
+    type Object = vk::Pipeline;
+
+    const TYPE: vk::DebugReportObjectTypeEXT = vk::DEBUG_REPORT_OBJECT_TYPE_PIPELINE_EXT;
+
+    #[inline]
+    fn internal_object(&self) -> vk::Pipeline {
+        self.inner.pipeline
+    }
+}
+
+impl Drop for Inner {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let vk = self.device.pointers();
+            vk.DestroyPipeline(self.device.internal_object(),
+                               self.pipeline,
+                               self.device.allocator_ptr());
+        }
+    }
+}
+
+/// Error that can happen when creating a ray tracing pipeline.
+#[derive(Clone, Debug)]
+pub enum RayTracingPipelineCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// Error while creating the pipeline layout object.
+    PipelineLayoutCreationError(PipelineLayoutCreationError),
+    /// The pipeline layout is not compatible with what the shaders expect.
+    IncompatiblePipelineLayout(PipelineLayoutNotSupersetError),
+    /// The layouts of the shader stages are not compatible with each other.
+    IncompatibleShaderLayouts(RuntimePipelineDescError),
+    /// The `rayTracingPipeline` feature of the `VK_KHR_ray_tracing_pipeline` extension wasn't
+    /// enabled.
+    RayTracingPipelineFeatureNotEnabled,
+    /// The pipeline doesn't contain any ray generation shader.
+    NoRayGenerationShader,
+    /// A shader doesn't have the execution model of the stage it was added as, for example a
+    /// closest hit shader that was passed to `miss_shader`.
+    WrongShaderType,
+    /// The maximum recursion depth is larger than what the device supports.
+    MaxRecursionDepthExceeded {
+        /// Maximum allowed value.
+        max: u32,
+        /// Value that was passed.
+        obtained: u32,
+    },
+}
+
+impl error::Error for RayTracingPipelineCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            RayTracingPipelineCreationError::OomError(_) => "not enough memory available",
+            RayTracingPipelineCreationError::PipelineLayoutCreationError(_) => {
+                "error while creating the pipeline layout object"
+            },
+            RayTracingPipelineCreationError::IncompatiblePipelineLayout(_) => {
+                "the pipeline layout is not compatible with what the shaders expect"
+            },
+            RayTracingPipelineCreationError::IncompatibleShaderLayouts(_) => {
+                "the layouts of the shader stages are not compatible with each other"
+            },
+            RayTracingPipelineCreationError::RayTracingPipelineFeatureNotEnabled => {
+                "the `rayTracingPipeline` feature wasn't enabled"
+            },
+            RayTracingPipelineCreationError::NoRayGenerationShader => {
+                "the pipeline doesn't contain any ray generation shader"
+            },
+            RayTracingPipelineCreationError::WrongShaderType => {
+                "a shader doesn't have the execution model of the stage it was added as"
+            },
+            RayTracingPipelineCreationError::MaxRecursionDepthExceeded { .. } => {
+                "the maximum recursion depth is larger than what the device supports"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            RayTracingPipelineCreationError::OomError(ref err) => Some(err),
+            RayTracingPipelineCreationError::PipelineLayoutCreationError(ref err) => Some(err),
+            RayTracingPipelineCreationError::IncompatiblePipelineLayout(ref err) => Some(err),
+            RayTracingPipelineCreationError::IncompatibleShaderLayouts(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RayTracingPipelineCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: OomError) -> RayTracingPipelineCreationError {
+        RayTracingPipelineCreationError::OomError(err)
+    }
+}
+
+impl From<PipelineLayoutCreationError> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: PipelineLayoutCreationError) -> RayTracingPipelineCreationError {
+        RayTracingPipelineCreationError::PipelineLayoutCreationError(err)
+    }
+}
+
+impl From<PipelineLayoutNotSupersetError> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: PipelineLayoutNotSupersetError) -> RayTracingPipelineCreationError {
+        RayTracingPipelineCreationError::IncompatiblePipelineLayout(err)
+    }
+}
+
+impl From<RuntimePipelineDescError> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: RuntimePipelineDescError) -> RayTracingPipelineCreationError {
+        RayTracingPipelineCreationError::IncompatibleShaderLayouts(err)
+    }
+}
+
+impl From<Error> for RayTracingPipelineCreationError {
+    #[inline]
+    fn from(err: Error) -> RayTracingPipelineCreationError {
+        match err {
+            err @ Error::OutOfHostMemory => {
+                RayTracingPipelineCreationError::OomError(OomError::from(err))
+            },
+            err @ Error::OutOfDeviceMemory => {
+                RayTracingPipelineCreationError::OomError(OomError::from(err))
+            },
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
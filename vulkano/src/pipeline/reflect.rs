@@ -0,0 +1,522 @@
+// Copyright (c) 2018 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Reflection of the SPIR-V code of shaders at runtime.
+//!
+//! The `vulkano-shaders` crate describes the interface of a shader to vulkano by generating Rust
+//! code at compile time. This isn't possible for shaders that are only known at runtime, for
+//! example because they are loaded from a file. Instead, this module parses the SPIR-V code of
+//! such shaders and produces for each entry point the input and output interfaces, the
+//! descriptors and the push constants that the pipeline builders need.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::device::Device;
+//! use vulkano::pipeline::shader::ShaderModule;
+//!
+//! # let device: Arc<Device> = return;
+//! # let words: Vec<u32> = return;
+//! let module = unsafe { ShaderModule::from_words(device.clone(), &words).unwrap() };
+//! let entry_points = module.entry_points().unwrap();
+//!
+//! let main = entry_points.iter().find(|e| e.name().to_str() == Ok("main")).unwrap();
+//! // The entry point can then be passed to `GraphicsPipelineBuilder::vertex_shader`.
+//! let vertex_shader = unsafe { main.graphics_entry_point::<()>(&module).unwrap() };
+//! ```
+//!
+//! A module can also contain multiple entry points, which is common with shaders compiled from
+//! HLSL. `ShaderModule::entry_point` selects one of them by name and stage, and returns an error
+//! if it doesn't exist or if it belongs to a different stage:
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use vulkano::device::Device;
+//! use vulkano::descriptor::descriptor::ShaderStages;
+//! use vulkano::pipeline::shader::ShaderModule;
+//!
+//! # let device: Arc<Device> = return;
+//! # let words: Vec<u32> = return;
+//! let module = unsafe { ShaderModule::from_words(device.clone(), &words).unwrap() };
+//! let fragment_stage = ShaderStages { fragment: true, .. ShaderStages::none() };
+//! let ps_main = module.entry_point("ps_main", fragment_stage).unwrap();
+//! let fragment_shader = unsafe { ps_main.graphics_entry_point::<()>(&module).unwrap() };
+//! ```
+
+use std::error;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::fmt;
+
+use descriptor::descriptor::ShaderStages;
+use descriptor::pipeline_layout::PipelineLayoutDescPcRange;
+use descriptor::pipeline_layout::RuntimePipelineDesc;
+use descriptor::pipeline_layout::RuntimePipelineDescError;
+use pipeline::shader::ComputeEntryPoint;
+use pipeline::shader::GeometryShaderExecutionMode;
+use pipeline::shader::GraphicsEntryPoint;
+use pipeline::shader::GraphicsShaderType;
+use pipeline::shader::RuntimeShaderInterfaceDef;
+use pipeline::shader::ShaderModule;
+use spirv::*;
+
+pub use spirv::ShaderReflectionError;
+
+/// Description of an entry point of a shader module, obtained by reflecting its SPIR-V code.
+#[derive(Debug, Clone)]
+pub struct EntryPointInfo {
+    name: CString,
+    stages: ShaderStages,
+    ty: Option<GraphicsShaderType>,
+    input: RuntimeShaderInterfaceDef,
+    output: RuntimeShaderInterfaceDef,
+    layout: RuntimePipelineDesc,
+}
+
+impl EntryPointInfo {
+    /// Returns the name of the entry point.
+    #[inline]
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// Returns the shader stage of the entry point.
+    #[inline]
+    pub fn stages(&self) -> ShaderStages {
+        self.stages
+    }
+
+    /// Returns the type of the entry point if it belongs to a graphics pipeline, or `None` for
+    /// compute and ray tracing entry points.
+    #[inline]
+    pub fn graphics_ty(&self) -> Option<GraphicsShaderType> {
+        self.ty
+    }
+
+    /// Returns the input attributes of the entry point. Built-in variables are not included.
+    #[inline]
+    pub fn input(&self) -> &RuntimeShaderInterfaceDef {
+        &self.input
+    }
+
+    /// Returns the output attributes of the entry point. Built-in variables are not included.
+    #[inline]
+    pub fn output(&self) -> &RuntimeShaderInterfaceDef {
+        &self.output
+    }
+
+    /// Returns the descriptors and push constants used by the entry point.
+    ///
+    /// The descriptors declared anywhere in the module are included, even if the entry point
+    /// doesn't use them.
+    #[inline]
+    pub fn layout(&self) -> &RuntimePipelineDesc {
+        &self.layout
+    }
+
+    /// Returns an object that can be passed to the graphics pipeline builder, or `None` if this
+    /// isn't a graphics entry point.
+    ///
+    /// # Safety
+    ///
+    /// - `module` must have been created from the SPIR-V code that was reflected.
+    /// - `S` must correctly describe the specialization constants of the module.
+    ///
+    #[inline]
+    pub unsafe fn graphics_entry_point<'a, S>(
+        &'a self, module: &'a ShaderModule)
+        -> Option<GraphicsEntryPoint<'a, S, RuntimeShaderInterfaceDef, RuntimeShaderInterfaceDef,
+                                     RuntimePipelineDesc>> {
+        self.ty.map(|ty| {
+                        module.graphics_entry_point(&self.name,
+                                                    self.input.clone(),
+                                                    self.output.clone(),
+                                                    self.layout.clone(),
+                                                    ty)
+                    })
+    }
+
+    /// Returns an object that can be passed to `ComputePipeline::new` or to the ray tracing
+    /// pipeline builder, or `None` if this is a graphics entry point.
+    ///
+    /// # Safety
+    ///
+    /// - `module` must have been created from the SPIR-V code that was reflected.
+    /// - `S` must correctly describe the specialization constants of the module.
+    ///
+    #[inline]
+    pub unsafe fn compute_entry_point<'a, S>(&'a self, module: &'a ShaderModule)
+                                             -> Option<ComputeEntryPoint<'a, S,
+                                                                         RuntimePipelineDesc>> {
+        if self.ty.is_some() {
+            return None;
+        }
+
+        Some(module.compute_entry_point(&self.name, self.layout.clone()))
+    }
+}
+
+/// Reflects the SPIR-V code of a shader module and returns the description of its entry points.
+///
+/// The words are expected to be in the host endianness, like the ones passed to
+/// `ShaderModule::from_words`. Entry points whose execution model isn't supported by Vulkan are
+/// ignored.
+pub fn entry_points(spirv: &[u32]) -> Result<Vec<EntryPointInfo>, ShaderReflectionError> {
+    let spirv = Spirv::parse(spirv)?;
+
+    let mut entry_points = Vec::new();
+    for instruction in spirv.instructions.iter() {
+        if instruction.opcode != OP_ENTRY_POINT || instruction.operands.len() < 3 {
+            continue;
+        }
+
+        let stages = execution_model_stages(instruction.operands[0]);
+        if stages == ShaderStages::none() {
+            continue;
+        }
+
+        entry_points.push(reflect_entry_point(&spirv, instruction, stages)?);
+    }
+
+    Ok(entry_points)
+}
+
+/// Reflects the SPIR-V code of a shader module and returns the description of the entry point
+/// with the given name and stage.
+///
+/// A module can contain several entry points with the same name, as long as their stages are
+/// different. An entry point matches if its stage is one of the stages in `stages`. The other
+/// entry points of the module are not reflected.
+pub fn entry_point(spirv: &[u32], name: &str, stages: ShaderStages)
+                   -> Result<EntryPointInfo, EntryPointLookupError> {
+    let spirv = Spirv::parse(spirv)?;
+
+    let mut found = ShaderStages::none();
+    for instruction in spirv.instructions.iter() {
+        if instruction.opcode != OP_ENTRY_POINT || instruction.operands.len() < 3 {
+            continue;
+        }
+
+        if parse_string(&instruction.operands[2 ..])?.0 != name {
+            continue;
+        }
+
+        let entry_point_stages = execution_model_stages(instruction.operands[0]);
+        if !entry_point_stages.intersects(&stages) {
+            found = found | entry_point_stages;
+            continue;
+        }
+
+        return Ok(reflect_entry_point(&spirv, instruction, entry_point_stages)?);
+    }
+
+    if found == ShaderStages::none() {
+        Err(EntryPointLookupError::NotFound)
+    } else {
+        Err(EntryPointLookupError::StageMismatch {
+                expected: stages,
+                found: found,
+            })
+    }
+}
+
+/// Error that can happen when looking for an entry point of a shader module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum EntryPointLookupError {
+    /// The code of the module couldn't be reflected.
+    ReflectionError(ShaderReflectionError),
+    /// The module doesn't contain any entry point with this name.
+    NotFound,
+    /// The module contains entry points with this name, but not for the expected stages.
+    StageMismatch {
+        /// The stages that were requested.
+        expected: ShaderStages,
+        /// The stages of the entry points with this name.
+        found: ShaderStages,
+    },
+}
+
+impl error::Error for EntryPointLookupError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            EntryPointLookupError::ReflectionError(_) => "the code couldn't be reflected",
+            EntryPointLookupError::NotFound => "the module doesn't contain this entry point",
+            EntryPointLookupError::StageMismatch { .. } => {
+                "the entry point doesn't belong to the expected stage"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            EntryPointLookupError::ReflectionError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EntryPointLookupError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ShaderReflectionError> for EntryPointLookupError {
+    #[inline]
+    fn from(err: ShaderReflectionError) -> EntryPointLookupError {
+        EntryPointLookupError::ReflectionError(err)
+    }
+}
+
+// Reflects the `OpEntryPoint` instruction `instruction`, whose execution model is `stages`.
+fn reflect_entry_point(spirv: &Spirv, instruction: &Instruction, stages: ShaderStages)
+                       -> Result<EntryPointInfo, ShaderReflectionError> {
+    let (model, id) = (instruction.operands[0], instruction.operands[1]);
+
+    let ty = match model {
+        EXECUTION_MODEL_VERTEX => Some(GraphicsShaderType::Vertex),
+        EXECUTION_MODEL_TESSELLATION_CONTROL => Some(GraphicsShaderType::TessellationControl),
+        EXECUTION_MODEL_TESSELLATION_EVALUATION => {
+            Some(GraphicsShaderType::TessellationEvaluation)
+        },
+        EXECUTION_MODEL_GEOMETRY => {
+            Some(GraphicsShaderType::Geometry(geometry_execution_mode(spirv, id)?))
+        },
+        EXECUTION_MODEL_FRAGMENT => Some(GraphicsShaderType::Fragment),
+        EXECUTION_MODEL_TASK_EXT => Some(GraphicsShaderType::Task),
+        EXECUTION_MODEL_MESH_EXT => Some(GraphicsShaderType::Mesh),
+        _ => None,
+    };
+
+    // The inputs and outputs of some stages have one element per vertex of the primitive.
+    let arrayed_input = match model {
+        EXECUTION_MODEL_TESSELLATION_CONTROL |
+        EXECUTION_MODEL_TESSELLATION_EVALUATION |
+        EXECUTION_MODEL_GEOMETRY => true,
+        _ => false,
+    };
+    let arrayed_output = match model {
+        EXECUTION_MODEL_TESSELLATION_CONTROL | EXECUTION_MODEL_MESH_EXT => true,
+        _ => false,
+    };
+
+    let input = spirv.interface(instruction, STORAGE_CLASS_INPUT, arrayed_input)?;
+    let output = spirv.interface(instruction, STORAGE_CLASS_OUTPUT, arrayed_output)?;
+
+    let push_constants = spirv
+        .push_constants_range()?
+        .map(|(offset, size)| {
+                 PipelineLayoutDescPcRange {
+                     offset: offset as usize,
+                     size: size as usize,
+                     stages: stages,
+                 }
+             });
+    let layout = match RuntimePipelineDesc::union_of(spirv.descriptors_with_stages(stages)?,
+                                                     push_constants) {
+        Ok(layout) => layout,
+        Err(RuntimePipelineDescError::ShaderReflectionError(err)) => return Err(err),
+        Err(RuntimePipelineDescError::IncompatibleDescriptors { set, binding }) => {
+            return Err(ShaderReflectionError::AliasedDescriptors {
+                           set: set,
+                           binding: binding,
+                       });
+        },
+        // There is at most one push constants range.
+        Err(RuntimePipelineDescError::PushConstantsConflict { .. }) => unreachable!(),
+    };
+
+    Ok(EntryPointInfo {
+           name: CString::new(parse_string(&instruction.operands[2 ..])?.0)
+               .map_err(|_| ShaderReflectionError::InvalidString)?,
+           stages: stages,
+           ty: ty,
+           // Safe because the locations of the elements of an interface don't overlap in valid
+           // SPIR-V code.
+           input: unsafe { RuntimeShaderInterfaceDef::new(input) },
+           output: unsafe { RuntimeShaderInterfaceDef::new(output) },
+           layout: layout,
+       })
+}
+
+// Returns the type of primitives expected by the geometry shader entry point `id`.
+fn geometry_execution_mode(spirv: &Spirv, id: u32)
+                           -> Result<GeometryShaderExecutionMode, ShaderReflectionError> {
+    let modes = [
+        (EXECUTION_MODE_INPUT_POINTS, GeometryShaderExecutionMode::Points),
+        (EXECUTION_MODE_INPUT_LINES, GeometryShaderExecutionMode::Lines),
+        (EXECUTION_MODE_INPUT_LINES_ADJACENCY, GeometryShaderExecutionMode::LinesWithAdjacency),
+        (EXECUTION_MODE_TRIANGLES, GeometryShaderExecutionMode::Triangles),
+        (EXECUTION_MODE_INPUT_TRIANGLES_ADJACENCY,
+         GeometryShaderExecutionMode::TrianglesWithAdjacency),
+    ];
+
+    modes
+        .iter()
+        .find(|&&(mode, _)| spirv.has_execution_mode(id, mode))
+        .map(|&(_, mode)| mode)
+        .ok_or(ShaderReflectionError::MissingInputPrimitives(id))
+}
+
+#[cfg(test)]
+mod tests {
+    use descriptor::descriptor::ShaderStages;
+    use descriptor::pipeline_layout::PipelineLayoutDesc;
+    use format::Format;
+    use pipeline::reflect::EntryPointLookupError;
+    use pipeline::reflect::entry_point;
+    use pipeline::reflect::entry_points;
+    use pipeline::shader::GeometryShaderExecutionMode;
+    use pipeline::shader::GraphicsShaderType;
+    use pipeline::shader::ShaderInterfaceDef;
+    use spirv::*;
+    use spirv::tests::module;
+
+    #[test]
+    fn vertex_interface() {
+        // layout(location = 0) in vec3 position;
+        // layout(location = 1) in mat2 transform;
+        // layout(location = 0) out ivec2 id;
+        // plus the `gl_PerVertex` output block.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x6e69616d, 0, 5,
+                                                6, 7, 8]),
+                             (OP_NAME, &[5, 0x69736f70, 0x6e6f6974, 0]),
+                             (OP_DECORATE, &[5, DECORATION_LOCATION, 0]),
+                             (OP_DECORATE, &[6, DECORATION_LOCATION, 1]),
+                             (OP_DECORATE, &[7, DECORATION_LOCATION, 0]),
+                             (OP_MEMBER_DECORATE, &[20, 0, DECORATION_BUILT_IN, 0]),
+                             (OP_TYPE_FLOAT, &[10, 32]),
+                             (OP_TYPE_INT, &[11, 32, 1]),
+                             (OP_TYPE_VECTOR, &[12, 10, 3]),
+                             (OP_TYPE_VECTOR, &[13, 10, 2]),
+                             (OP_TYPE_MATRIX, &[14, 13, 2]),
+                             (OP_TYPE_VECTOR, &[15, 11, 2]),
+                             (OP_TYPE_VECTOR, &[16, 10, 4]),
+                             (OP_TYPE_STRUCT, &[20, 16]),
+                             (OP_TYPE_POINTER, &[30, STORAGE_CLASS_INPUT, 12]),
+                             (OP_TYPE_POINTER, &[31, STORAGE_CLASS_INPUT, 14]),
+                             (OP_TYPE_POINTER, &[32, STORAGE_CLASS_OUTPUT, 15]),
+                             (OP_TYPE_POINTER, &[33, STORAGE_CLASS_OUTPUT, 20]),
+                             (OP_VARIABLE, &[30, 5, STORAGE_CLASS_INPUT]),
+                             (OP_VARIABLE, &[31, 6, STORAGE_CLASS_INPUT]),
+                             (OP_VARIABLE, &[32, 7, STORAGE_CLASS_OUTPUT]),
+                             (OP_VARIABLE, &[33, 8, STORAGE_CLASS_OUTPUT])]);
+
+        let entry_points = entry_points(&words).unwrap();
+        assert_eq!(entry_points.len(), 1);
+        let vs = &entry_points[0];
+        assert_eq!(vs.name().to_str(), Ok("main"));
+        assert!(vs.stages().vertex);
+        assert_eq!(vs.graphics_ty(), Some(GraphicsShaderType::Vertex));
+
+        let input = vs.input().elements().collect::<Vec<_>>();
+        assert_eq!(input.len(), 2);
+        assert_eq!(input[0].location, 0 .. 1);
+        assert_eq!(input[0].format, Format::R32G32B32Sfloat);
+        assert_eq!(input[0].name.as_ref().map(|n| &**n), Some("position"));
+        assert_eq!(input[1].location, 1 .. 3);
+        assert_eq!(input[1].format, Format::R32G32Sfloat);
+
+        let output = vs.output().elements().collect::<Vec<_>>();
+        assert_eq!(output.len(), 1);
+        assert_eq!(output[0].format, Format::R32G32Sint);
+
+        assert_eq!(vs.layout().num_sets(), 0);
+    }
+
+    #[test]
+    fn geometry_arrayed_input() {
+        // layout(triangles) in; layout(location = 0) in vec4 color[3];
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_GEOMETRY, 1, 0x6e69616d, 0, 5]),
+                             (OP_EXECUTION_MODE, &[1, EXECUTION_MODE_TRIANGLES]),
+                             (OP_DECORATE, &[5, DECORATION_LOCATION, 2]),
+                             (OP_TYPE_FLOAT, &[10, 32]),
+                             (OP_TYPE_INT, &[11, 32, 0]),
+                             (OP_CONSTANT, &[11, 12, 3]),
+                             (OP_TYPE_VECTOR, &[13, 10, 4]),
+                             (OP_TYPE_ARRAY, &[14, 13, 12]),
+                             (OP_TYPE_POINTER, &[15, STORAGE_CLASS_INPUT, 14]),
+                             (OP_VARIABLE, &[15, 5, STORAGE_CLASS_INPUT])]);
+
+        let entry_points = entry_points(&words).unwrap();
+        let gs = &entry_points[0];
+        assert_eq!(gs.graphics_ty(),
+                   Some(GraphicsShaderType::Geometry(GeometryShaderExecutionMode::Triangles)));
+
+        let input = gs.input().elements().collect::<Vec<_>>();
+        assert_eq!(input[0].location, 2 .. 3);
+        assert_eq!(input[0].format, Format::R32G32B32A32Sfloat);
+    }
+
+    #[test]
+    fn missing_location() {
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_FRAGMENT, 1, 0x6e69616d, 0, 5]),
+                             (OP_TYPE_FLOAT, &[10, 32]),
+                             (OP_TYPE_POINTER, &[11, STORAGE_CLASS_OUTPUT, 10]),
+                             (OP_VARIABLE, &[11, 5, STORAGE_CLASS_OUTPUT])]);
+
+        match entry_points(&words) {
+            Err(ShaderReflectionError::MissingLocation(5)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn compute_layout() {
+        // layout(set = 0, binding = 1) uniform sampler s;
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 1, 0x6e69616d, 0]),
+                             (OP_DECORATE, &[5, DECORATION_DESCRIPTOR_SET, 0]),
+                             (OP_DECORATE, &[5, DECORATION_BINDING, 1]),
+                             (OP_TYPE_SAMPLER, &[6]),
+                             (OP_TYPE_POINTER, &[7, STORAGE_CLASS_UNIFORM_CONSTANT, 6]),
+                             (OP_VARIABLE, &[7, 5, STORAGE_CLASS_UNIFORM_CONSTANT])]);
+
+        let entry_points = entry_points(&words).unwrap();
+        let cs = &entry_points[0];
+        assert_eq!(cs.graphics_ty(), None);
+        assert_eq!(cs.input().elements().len(), 0);
+
+        let layout = cs.layout();
+        assert_eq!(layout.num_bindings_in_set(0), Some(2));
+        assert!(layout.descriptor(0, 1).unwrap().stages.compute);
+    }
+
+    #[test]
+    fn entry_point_by_name_and_stage() {
+        // Two entry points named `main`, and one named `cs`.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x6e69616d, 0]),
+                             (OP_ENTRY_POINT, &[EXECUTION_MODEL_FRAGMENT, 2, 0x6e69616d, 0]),
+                             (OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 3, 0x7363])]);
+
+        let fragment = ShaderStages { fragment: true, .. ShaderStages::none() };
+        let fs = entry_point(&words, "main", fragment).unwrap();
+        assert_eq!(fs.name().to_str(), Ok("main"));
+        assert_eq!(fs.graphics_ty(), Some(GraphicsShaderType::Fragment));
+
+        let cs = entry_point(&words, "cs", ShaderStages::compute()).unwrap();
+        assert_eq!(cs.graphics_ty(), None);
+
+        match entry_point(&words, "main", ShaderStages::compute()) {
+            Err(EntryPointLookupError::StageMismatch { expected, found }) => {
+                assert_eq!(expected, ShaderStages::compute());
+                assert!(found.vertex && found.fragment && !found.compute);
+            },
+            _ => panic!(),
+        }
+
+        match entry_point(&words, "ps_main", fragment) {
+            Err(EntryPointLookupError::NotFound) => (),
+            _ => panic!(),
+        }
+    }
+}
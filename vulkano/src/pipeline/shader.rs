@@ -13,14 +13,24 @@
 //! code and can contain one or more entry points. Note that for the moment the official
 //! GLSL-to-SPIR-V compiler does not support multiple entry points.
 //!
-//! The vulkano library does not provide any functionnality that checks and introspects the SPIR-V
-//! code, therefore the whole shader-related API is unsafe. You are encouraged to use the
-//! `vulkano-shaders` crate that will generate Rust code that wraps around vulkano's shaders API.
+//! When a shader module is created, vulkano only checks the structure of the SPIR-V code and the
+//! capabilities it declares, therefore the whole shader-related API is unsafe. The complete
+//! validation of the code with `spirv-val` can be enabled with the `spirv-val` feature. You are
+//! encouraged to use the `vulkano-shaders` crate that will generate Rust code that wraps around
+//! vulkano's shaders API.
+//!
+//! The SPIR-V code of a shader module is kept around after its creation, so that the descriptors
+//! and push constants it uses can be reflected at runtime. See
+//! `RuntimePipelineDesc::from_shader_modules`, and the `reflect` module which also produces the
+//! input and output interfaces of each entry point.
 
+use fnv::FnvHasher;
 use std::borrow::Cow;
 use std::error;
 use std::ffi::CStr;
 use std::fmt;
+use std::hash::Hash;
+use std::hash::Hasher;
 use std::iter;
 use std::iter::Empty as EmptyIter;
 use std::marker::PhantomData;
@@ -28,81 +38,141 @@ use std::mem;
 use std::ops::Range;
 use std::ptr;
 use std::sync::Arc;
+use std::vec::IntoIter as VecIntoIter;
 
+use descriptor::descriptor::ShaderStages;
 use descriptor::pipeline_layout::EmptyPipelineDesc;
 use descriptor::pipeline_layout::PipelineLayoutDesc;
 use format::Format;
 use pipeline::input_assembly::PrimitiveTopology;
+use pipeline::reflect;
+use pipeline::reflect::EntryPointInfo;
+use pipeline::reflect::EntryPointLookupError;
+
+pub use spirv::ShaderReflectionError;
 
 use OomError;
 use VulkanObject;
+use Error;
 use check_errors;
 use device::Device;
+use features::Features;
+use spirv::OP_CAPABILITY;
+use spirv::Spirv;
+use version::Version;
 use vk;
 
 /// Contains SPIR-V code with one or more entry points.
 ///
 /// Note that it is advised to wrap around a `ShaderModule` with a struct that is different for
 /// each shader.
-#[derive(Debug)]
 pub struct ShaderModule {
     // The module.
     module: vk::ShaderModule,
     // Pointer to the device.
     device: Arc<Device>,
+    // The SPIR-V code the module was created from, used for reflection.
+    spirv: Vec<u32>,
 }
 
 impl ShaderModule {
     /// Builds a new shader module from SPIR-V bytes.
     ///
+    /// The structure of the code is checked, and an error is returned if it declares a
+    /// capability whose feature isn't enabled on the device. If the `spirv-val` feature of vulkano
+    /// is enabled, the code is also validated with `spirv-val`.
+    ///
     /// # Safety
     ///
-    /// - The SPIR-V code is not validated.
-    /// - The SPIR-V code may require some features that are not enabled. This isn't checked by
-    ///   this function either.
+    /// - Unless the `spirv-val` feature is enabled, the SPIR-V code is not validated beyond its
+    ///   structure.
+    /// - The SPIR-V code may require some features that are not enabled through other means than
+    ///   capabilities, for example by using specific formats. This isn't checked by this
+    ///   function.
     ///
-    pub unsafe fn new(device: Arc<Device>, spirv: &[u8]) -> Result<Arc<ShaderModule>, OomError> {
+    pub unsafe fn new(device: Arc<Device>, spirv: &[u8])
+                      -> Result<Arc<ShaderModule>, ShaderModuleCreationError> {
         debug_assert!((spirv.len() % 4) == 0);
-        Self::from_ptr(device, spirv.as_ptr() as *const _, spirv.len())
+
+        // Copying the code in a `Vec<u32>` ensures that it is correctly aligned.
+        let mut words = Vec::with_capacity(spirv.len() / 4);
+        ptr::copy_nonoverlapping(spirv.as_ptr(), words.as_mut_ptr() as *mut u8,
+                                 (spirv.len() / 4) * 4);
+        words.set_len(spirv.len() / 4);
+
+        Self::from_words_vec(device, words)
     }
 
     /// Builds a new shader module from SPIR-V 32-bit words.
     ///
+    /// Same as `new`, except that the code is already made of words.
+    ///
     /// # Safety
     ///
-    /// - The SPIR-V code is not validated.
-    /// - The SPIR-V code may require some features that are not enabled. This isn't checked by
-    ///   this function either.
+    /// Same as `new`.
     ///
     pub unsafe fn from_words(device: Arc<Device>, spirv: &[u32])
-                             -> Result<Arc<ShaderModule>, OomError> {
-        Self::from_ptr(device, spirv.as_ptr(), spirv.len() * mem::size_of::<u32>())
+                             -> Result<Arc<ShaderModule>, ShaderModuleCreationError> {
+        Self::from_words_vec(device, spirv.to_owned())
     }
 
-    /// Builds a new shader module from SPIR-V.
+    /// Same as `from_words`, but returns an existing module if one was created by this function
+    /// from the same code on this device and is still alive.
+    ///
+    /// This avoids creating the same module multiple times, for example when an asset system
+    /// loads the same shader repeatedly.
     ///
     /// # Safety
     ///
-    /// - The SPIR-V code is not validated.
-    /// - The SPIR-V code may require some features that are not enabled. This isn't checked by
-    ///   this function either.
+    /// Same as `new`.
     ///
-    unsafe fn from_ptr(device: Arc<Device>, spirv: *const u32, spirv_len: usize)
-                       -> Result<Arc<ShaderModule>, OomError> {
+    pub unsafe fn from_words_deduplicated(device: Arc<Device>, spirv: &[u32])
+                                          -> Result<Arc<ShaderModule>, ShaderModuleCreationError> {
+        let hash = {
+            let mut hasher = FnvHasher::default();
+            spirv.hash(&mut hasher);
+            hasher.finish()
+        };
+
+        let mut shader_modules = device.shader_modules().lock().unwrap();
+        let modules = shader_modules.entry(hash).or_insert_with(Vec::new);
+        modules.retain(|module| module.upgrade().is_some());
+
+        // Different codes can have the same hash.
+        if let Some(module) = modules
+            .iter()
+            .filter_map(|module| module.upgrade())
+            .find(|module| module.spirv() == spirv)
+        {
+            return Ok(module);
+        }
+
+        let module = Self::from_words_vec(device.clone(), spirv.to_owned())?;
+        modules.push(Arc::downgrade(&module));
+        Ok(module)
+    }
+
+    // Actual implementation of `new` and `from_words`.
+    unsafe fn from_words_vec(device: Arc<Device>, spirv: Vec<u32>)
+                             -> Result<Arc<ShaderModule>, ShaderModuleCreationError> {
+        let max_minor = max_spirv_minor(device.api_version(),
+                                        device.loaded_extensions().khr_spirv_1_4);
+        check_spirv(&spirv, max_minor, device.enabled_features())?;
+
         let module = {
             let infos = vk::ShaderModuleCreateInfo {
                 sType: vk::STRUCTURE_TYPE_SHADER_MODULE_CREATE_INFO,
                 pNext: ptr::null(),
                 flags: 0, // reserved
-                codeSize: spirv_len,
-                pCode: spirv,
+                codeSize: spirv.len() * mem::size_of::<u32>(),
+                pCode: spirv.as_ptr(),
             };
 
             let vk = device.pointers();
             let mut output = mem::uninitialized();
             check_errors(vk.CreateShaderModule(device.internal_object(),
                                                &infos,
-                                               ptr::null(),
+                                               device.allocator_ptr(),
                                                &mut output))?;
             output
         };
@@ -110,9 +180,36 @@ impl ShaderModule {
         Ok(Arc::new(ShaderModule {
                         module: module,
                         device: device,
+                        spirv: spirv,
                     }))
     }
 
+    /// Returns the SPIR-V code this module was created from.
+    #[inline]
+    pub fn spirv(&self) -> &[u32] {
+        &self.spirv
+    }
+
+    /// Reflects the SPIR-V code of this module and returns the description of its entry points.
+    ///
+    /// See the `reflect` module for more information.
+    #[inline]
+    pub fn entry_points(&self) -> Result<Vec<EntryPointInfo>, ShaderReflectionError> {
+        reflect::entry_points(&self.spirv)
+    }
+
+    /// Reflects the SPIR-V code of this module and returns the description of the entry point
+    /// with the given name and stage.
+    ///
+    /// This is useful for modules that contain multiple entry points, such as the ones compiled
+    /// from HLSL. Returns an error if there is no entry point with this name, or if the ones that
+    /// exist don't belong to one of the `stages`.
+    #[inline]
+    pub fn entry_point(&self, name: &str, stages: ShaderStages)
+                       -> Result<EntryPointInfo, EntryPointLookupError> {
+        reflect::entry_point(&self.spirv, name, stages)
+    }
+
     /// Gets access to an entry point contained in this module.
     ///
     /// This is purely a *logical* operation. It returns a struct that *represents* the entry
@@ -163,6 +260,281 @@ impl ShaderModule {
     }
 }
 
+// Returns the highest minor version of SPIR-V 1.x that a device using the version `api_version` of
+// Vulkan can consume.
+fn max_spirv_minor(api_version: Version, khr_spirv_1_4: bool) -> u8 {
+    if api_version >= (Version { major: 1, minor: 3, patch: 0 }) {
+        6
+    } else if api_version >= (Version { major: 1, minor: 2, patch: 0 }) {
+        5
+    } else if api_version >= (Version { major: 1, minor: 1, patch: 0 }) {
+        if khr_spirv_1_4 { 4 } else { 3 }
+    } else {
+        0
+    }
+}
+
+// Checks the structure, the version and the capabilities of SPIR-V code, and runs `spirv-val` on
+// it if the feature is enabled. `max_minor` is the highest minor version of SPIR-V 1.x that the
+// device can consume.
+fn check_spirv(words: &[u32], max_minor: u8, features: &Features)
+               -> Result<(), ShaderModuleCreationError> {
+    let spirv = Spirv::parse(words)?;
+
+    let (major, minor) = spirv.version;
+    if major != 1 || minor > 6 {
+        return Err(ShaderModuleCreationError::UnsupportedVersion {
+                       major: major,
+                       minor: minor,
+                   });
+    }
+    if minor > max_minor {
+        return Err(ShaderModuleCreationError::VersionNotSupportedByDevice {
+                       minor: minor,
+                       max_minor: max_minor,
+                   });
+    }
+
+    for instruction in spirv.instructions.iter() {
+        if instruction.opcode != OP_CAPABILITY || instruction.operands.is_empty() {
+            continue;
+        }
+
+        let capability = instruction.operands[0];
+        match capability_feature(features, capability) {
+            CapabilitySupport::Supported => (),
+            CapabilitySupport::FeatureNotEnabled(feature) => {
+                return Err(ShaderModuleCreationError::FeatureNotEnabled {
+                               capability: capability,
+                               feature: feature,
+                           });
+            },
+            CapabilitySupport::Unsupported => {
+                return Err(ShaderModuleCreationError::UnsupportedCapability(capability));
+            },
+        }
+    }
+
+    #[cfg(feature = "spirv-val")]
+    {
+        use spirv_tools::TargetEnv;
+        use spirv_tools::val::Validator;
+
+        // `spirv-tools` doesn't have a target environment for SPIR-V 1.6 yet, so these modules
+        // aren't validated.
+        let env = match minor {
+            0 => Some(TargetEnv::Vulkan_1_0),
+            1 | 2 | 3 => Some(TargetEnv::Vulkan_1_1),
+            4 => Some(TargetEnv::Vulkan_1_1_Spirv_1_4),
+            5 => Some(TargetEnv::Vulkan_1_2),
+            _ => None,
+        };
+
+        if let Some(env) = env {
+            spirv_tools::val::create(Some(env))
+                .validate(words, None)
+                .map_err(|err| ShaderModuleCreationError::ValidationFailed(err.to_string()))?;
+        }
+    }
+
+    Ok(())
+}
+
+enum CapabilitySupport {
+    Supported,
+    FeatureNotEnabled(&'static str),
+    Unsupported,
+}
+
+// Returns whether the SPIR-V capability `capability` can be used with the enabled `features`.
+//
+// Capabilities that this function doesn't know about are assumed to be supported, as they are
+// usually enabled by device extensions.
+fn capability_feature(features: &Features, capability: u32) -> CapabilitySupport {
+    let (name, enabled) = match capability {
+        2 => ("geometry_shader", features.geometry_shader),
+        3 => ("tessellation_shader", features.tessellation_shader),
+        // `Addresses`, `Linkage`, `Kernel` and `Float16Buffer` are for OpenCL.
+        4 | 5 | 6 | 8 => return CapabilitySupport::Unsupported,
+        9 => ("shader_float16", features.shader_float16),
+        10 => ("shader_f3264", features.shader_f3264),
+        11 => ("shader_int64", features.shader_int64),
+        22 => ("shader_int16", features.shader_int16),
+        23 | 24 => {
+            ("shader_tessellation_and_geometry_point_size",
+             features.shader_tessellation_and_geometry_point_size)
+        },
+        25 => ("shader_image_gather_extended", features.shader_image_gather_extended),
+        27 | 48 => ("shader_storage_image_multisample", features.shader_storage_image_multisample),
+        28 => {
+            ("shader_uniform_buffer_array_dynamic_indexing",
+             features.shader_uniform_buffer_array_dynamic_indexing)
+        },
+        29 => {
+            ("shader_sampled_image_array_dynamic_indexing",
+             features.shader_sampled_image_array_dynamic_indexing)
+        },
+        30 => {
+            ("shader_storage_buffer_array_dynamic_indexing",
+             features.shader_storage_buffer_array_dynamic_indexing)
+        },
+        31 => {
+            ("shader_storage_image_array_dynamic_indexing",
+             features.shader_storage_image_array_dynamic_indexing)
+        },
+        32 => ("shader_clip_distance", features.shader_clip_distance),
+        33 => ("shader_cull_distance", features.shader_cull_distance),
+        34 | 45 => ("image_cube_array", features.image_cube_array),
+        35 | 52 => ("sample_rate_shading", features.sample_rate_shading),
+        41 => ("shader_resource_residency", features.shader_resource_residency),
+        39 => ("shader_int8", features.shader_int8),
+        42 => ("shader_resource_min_lod", features.shader_resource_min_lod),
+        49 => {
+            ("shader_storage_image_extended_formats",
+             features.shader_storage_image_extended_formats)
+        },
+        55 => {
+            ("shader_storage_image_read_without_format",
+             features.shader_storage_image_read_without_format)
+        },
+        56 => {
+            ("shader_storage_image_write_without_format",
+             features.shader_storage_image_write_without_format)
+        },
+        57 => ("multi_viewport", features.multi_viewport),
+        4433 => ("storage_buffer_16bit_access", features.storage_buffer_16bit_access),
+        4434 => {
+            ("uniform_and_storage_buffer_16bit_access",
+             features.uniform_and_storage_buffer_16bit_access)
+        },
+        4435 => ("storage_push_constant16", features.storage_push_constant16),
+        4436 => ("storage_input_output16", features.storage_input_output16),
+        4448 => ("storage_buffer_8bit_access", features.storage_buffer_8bit_access),
+        4449 => {
+            ("uniform_and_storage_buffer_8bit_access",
+             features.uniform_and_storage_buffer_8bit_access)
+        },
+        4450 => ("storage_push_constant8", features.storage_push_constant8),
+        _ => return CapabilitySupport::Supported,
+    };
+
+    if enabled {
+        CapabilitySupport::Supported
+    } else {
+        CapabilitySupport::FeatureNotEnabled(name)
+    }
+}
+
+/// Error that can happen when creating a shader module.
+#[derive(Debug, Clone)]
+pub enum ShaderModuleCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The code isn't a well-formed SPIR-V module.
+    InvalidSpirv(ShaderReflectionError),
+    /// The version of SPIR-V used by the module isn't supported.
+    UnsupportedVersion { major: u8, minor: u8 },
+    /// The module uses a version of SPIR-V 1.x that the version of Vulkan used by the device, and
+    /// its extensions, can't consume.
+    VersionNotSupportedByDevice {
+        /// The minor version of SPIR-V used by the module.
+        minor: u8,
+        /// The highest minor version of SPIR-V that the device can consume.
+        max_minor: u8,
+    },
+    /// The module declares a capability that can't be used with Vulkan.
+    UnsupportedCapability(u32),
+    /// The module declares a capability that requires a feature that isn't enabled on the
+    /// device.
+    FeatureNotEnabled {
+        /// The SPIR-V capability.
+        capability: u32,
+        /// The name of the feature in the `Features` struct.
+        feature: &'static str,
+    },
+    /// The code was rejected by `spirv-val`. Contains the message of the validator.
+    ///
+    /// Only returned if the `spirv-val` feature is enabled.
+    ValidationFailed(String),
+}
+
+impl error::Error for ShaderModuleCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ShaderModuleCreationError::OomError(_) => "not enough memory available",
+            ShaderModuleCreationError::InvalidSpirv(_) => {
+                "the code isn't a well-formed SPIR-V module"
+            },
+            ShaderModuleCreationError::UnsupportedVersion { .. } => {
+                "the version of SPIR-V used by the module isn't supported"
+            },
+            ShaderModuleCreationError::VersionNotSupportedByDevice { .. } => {
+                "the version of SPIR-V used by the module can't be consumed by the device"
+            },
+            ShaderModuleCreationError::UnsupportedCapability(_) => {
+                "the module declares a capability that can't be used with Vulkan"
+            },
+            ShaderModuleCreationError::FeatureNotEnabled { .. } => {
+                "the module declares a capability that requires a feature that isn't enabled"
+            },
+            ShaderModuleCreationError::ValidationFailed(_) => {
+                "the code was rejected by spirv-val"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ShaderModuleCreationError::OomError(ref err) => Some(err),
+            ShaderModuleCreationError::InvalidSpirv(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ShaderModuleCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for ShaderModuleCreationError {
+    #[inline]
+    fn from(err: OomError) -> ShaderModuleCreationError {
+        ShaderModuleCreationError::OomError(err)
+    }
+}
+
+impl From<ShaderReflectionError> for ShaderModuleCreationError {
+    #[inline]
+    fn from(err: ShaderReflectionError) -> ShaderModuleCreationError {
+        ShaderModuleCreationError::InvalidSpirv(err)
+    }
+}
+
+impl From<Error> for ShaderModuleCreationError {
+    #[inline]
+    fn from(err: Error) -> ShaderModuleCreationError {
+        match err {
+            err @ Error::OutOfHostMemory => ShaderModuleCreationError::OomError(OomError::from(err)),
+            err @ Error::OutOfDeviceMemory => {
+                ShaderModuleCreationError::OomError(OomError::from(err))
+            },
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+impl fmt::Debug for ShaderModule {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "<Vulkan shader module {:?}>", self.module)
+    }
+}
+
 unsafe impl VulkanObject for ShaderModule {
     type Object = vk::ShaderModule;
 
@@ -177,9 +549,18 @@ unsafe impl VulkanObject for ShaderModule {
 impl Drop for ShaderModule {
     #[inline]
     fn drop(&mut self) {
+        // The handle of the module can be reused once it is destroyed.
+        self.device
+            .compute_pipelines()
+            .lock()
+            .unwrap()
+            .retain(|key, _| key.module != self.module);
+
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyShaderModule(self.device.internal_object(), self.module, ptr::null());
+            vk.DestroyShaderModule(self.device.internal_object(),
+                                   self.module,
+                                   self.device.allocator_ptr());
         }
     }
 }
@@ -269,6 +650,10 @@ pub enum GraphicsShaderType {
     TessellationEvaluation,
     Geometry(GeometryShaderExecutionMode),
     Fragment,
+    /// Task shader of a mesh shading pipeline. Requires the `VK_EXT_mesh_shader` extension.
+    Task,
+    /// Mesh shader of a mesh shading pipeline. Requires the `VK_EXT_mesh_shader` extension.
+    Mesh,
 }
 
 /// Declares which type of primitives are expected by the geometry shader.
@@ -305,6 +690,90 @@ impl GeometryShaderExecutionMode {
     }
 }
 
+/// Limits of the physical device related to the `VK_EXT_mesh_shader` extension.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct MeshShaderProperties {
+    /// Maximum total number of workgroups of the task shader that a draw command can launch.
+    pub max_task_work_group_total_count: u32,
+    /// Maximum number of workgroups of the task shader that a draw command can launch in each
+    /// dimension.
+    pub max_task_work_group_count: [u32; 3],
+    /// Maximum total number of workgroups of the mesh shader that a draw command or a task shader
+    /// workgroup can launch.
+    pub max_mesh_work_group_total_count: u32,
+    /// Maximum number of workgroups of the mesh shader that a draw command or a task shader
+    /// workgroup can launch in each dimension.
+    pub max_mesh_work_group_count: [u32; 3],
+}
+
+/// Subgroup operations that a physical device supports.
+///
+/// A subgroup is a set of invocations that execute together and can exchange data with the
+/// `GL_KHR_shader_subgroup_*` GLSL extensions or the wave intrinsics of HLSL.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubgroupOperations {
+    /// `subgroupElect` and `subgroupBarrier`.
+    pub basic: bool,
+    /// `subgroupAll`, `subgroupAny` and `subgroupAllEqual`.
+    pub vote: bool,
+    /// Reductions and scans, like `subgroupAdd` or `subgroupInclusiveMin`.
+    pub arithmetic: bool,
+    /// `subgroupBroadcast` and `subgroupBallot`.
+    pub ballot: bool,
+    /// `subgroupShuffle` and `subgroupShuffleXor`.
+    pub shuffle: bool,
+    /// `subgroupShuffleUp` and `subgroupShuffleDown`.
+    pub shuffle_relative: bool,
+    /// Clustered reductions, like `subgroupClusteredAdd`.
+    pub clustered: bool,
+    /// `subgroupQuadBroadcast` and `subgroupQuadSwapHorizontal`.
+    pub quad: bool,
+}
+
+impl SubgroupOperations {
+    #[inline]
+    pub(crate) fn from_vulkan_bits(bits: vk::SubgroupFeatureFlags) -> SubgroupOperations {
+        SubgroupOperations {
+            basic: (bits & vk::SUBGROUP_FEATURE_BASIC_BIT) != 0,
+            vote: (bits & vk::SUBGROUP_FEATURE_VOTE_BIT) != 0,
+            arithmetic: (bits & vk::SUBGROUP_FEATURE_ARITHMETIC_BIT) != 0,
+            ballot: (bits & vk::SUBGROUP_FEATURE_BALLOT_BIT) != 0,
+            shuffle: (bits & vk::SUBGROUP_FEATURE_SHUFFLE_BIT) != 0,
+            shuffle_relative: (bits & vk::SUBGROUP_FEATURE_SHUFFLE_RELATIVE_BIT) != 0,
+            clustered: (bits & vk::SUBGROUP_FEATURE_CLUSTERED_BIT) != 0,
+            quad: (bits & vk::SUBGROUP_FEATURE_QUAD_BIT) != 0,
+        }
+    }
+}
+
+/// Subgroup properties of a physical device.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SubgroupProperties {
+    /// Default number of invocations in a subgroup. 0 if the physical device doesn't support
+    /// Vulkan 1.1.
+    pub subgroup_size: u32,
+    /// Stages in which subgroup operations can be used.
+    pub supported_stages: ShaderStages,
+    /// Subgroup operations that can be used in `supported_stages`.
+    pub supported_operations: SubgroupOperations,
+    /// True if the quad operations can be used in all the `supported_stages`, and not only in
+    /// fragment and compute shaders.
+    pub quad_operations_in_all_stages: bool,
+}
+
+/// Limits of the physical device related to the `VK_EXT_subgroup_size_control` extension.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SubgroupSizeControlProperties {
+    /// Minimum subgroup size. Always a power of two.
+    pub min_subgroup_size: u32,
+    /// Maximum subgroup size. Always a power of two.
+    pub max_subgroup_size: u32,
+    /// Maximum number of subgroups in a compute workgroup.
+    pub max_compute_workgroup_subgroups: u32,
+    /// Stages that can require a specific subgroup size.
+    pub required_subgroup_size_stages: ShaderStages,
+}
+
 pub unsafe trait EntryPointAbstract {
     type PipelineLayout: PipelineLayoutDesc;
     type SpecializationConstants: SpecializationConstants;
@@ -444,6 +913,36 @@ unsafe impl ShaderInterfaceDef for EmptyShaderInterfaceDef {
     }
 }
 
+/// Description of a shader interface built at runtime, for example by reflecting the SPIR-V code
+/// of a shader with the `reflect` module.
+#[derive(Debug, Clone)]
+pub struct RuntimeShaderInterfaceDef {
+    elements: Vec<ShaderInterfaceDefEntry>,
+}
+
+impl RuntimeShaderInterfaceDef {
+    /// Builds a new `RuntimeShaderInterfaceDef` from a list of elements.
+    ///
+    /// # Safety
+    ///
+    /// - Must only provide one entry per location.
+    /// - The format of each element must not be larger than 128 bits.
+    ///
+    #[inline]
+    pub unsafe fn new(elements: Vec<ShaderInterfaceDefEntry>) -> RuntimeShaderInterfaceDef {
+        RuntimeShaderInterfaceDef { elements: elements }
+    }
+}
+
+unsafe impl ShaderInterfaceDef for RuntimeShaderInterfaceDef {
+    type Iter = VecIntoIter<ShaderInterfaceDefEntry>;
+
+    #[inline]
+    fn elements(&self) -> Self::Iter {
+        self.elements.clone().into_iter()
+    }
+}
+
 /// Extension trait for `ShaderInterfaceDef` that specifies that the interface is potentially
 /// compatible with another one.
 pub unsafe trait ShaderInterfaceDefMatch<I>: ShaderInterfaceDef
@@ -568,13 +1067,38 @@ impl fmt::Display for ShaderInterfaceMismatchError {
 ///
 /// Note that it is the shader module that chooses which type that implements
 /// `SpecializationConstants` it is possible to pass when creating the pipeline, through [the
-/// `EntryPointAbstract` trait](trait.EntryPointAbstract.html). Therefore there is generally no
-/// point to implement this trait yourself, unless you are also writing your own implementation of
-/// `EntryPointAbstract`.
+/// `EntryPointAbstract` trait](trait.EntryPointAbstract.html). The `shader!` macro of
+/// `vulkano-shaders` generates such a type for you. If you load a shader at runtime instead, you
+/// can pass your own type to `ShaderModule::graphics_entry_point` or
+/// `ShaderModule::compute_entry_point`, and implement this trait on it with the
+/// `impl_specialization_constants!` macro or with the `SpecializationConstants` derive of
+/// `vulkano-shader-derive`, which also checks the layout of the struct at compile time.
 ///
 /// # Example
 ///
 /// ```rust
+/// # #[macro_use] extern crate vulkano;
+/// # fn main() {
+/// #[repr(C)]      // `#[repr(C)]` guarantees that the struct has a specific layout
+/// struct MySpecConstants {
+///     my_integer_constant: i32,
+///     a_boolean: u32,
+///     floating_point: f32,
+/// }
+///
+/// // Each member is followed by the `constant_id` of the constant in the shader.
+/// impl_specialization_constants!(MySpecConstants, my_integer_constant => 0, a_boolean => 1,
+///                                floating_point => 2);
+/// # use vulkano::pipeline::shader::SpecializationConstants;
+/// # let descriptors = MySpecConstants::descriptors();
+/// # let last = &descriptors[2];
+/// # assert_eq!((last.constant_id, last.offset, last.size), (2, 8, 4));
+/// # }
+/// ```
+///
+/// The same thing can be written by hand:
+///
+/// ```rust
 /// use vulkano::pipeline::shader::SpecializationConstants;
 /// use vulkano::pipeline::shader::SpecializationMapEntry;
 ///
@@ -628,6 +1152,46 @@ unsafe impl SpecializationConstants for () {
     }
 }
 
+/// Implements the `SpecializationConstants` trait on a struct.
+///
+/// The first parameter is the struct, which should be `#[repr(C)]`. It is followed by the members
+/// of the struct that correspond to specialization constants, each with the `constant_id` of the
+/// constant in the shader. See [the `SpecializationConstants`
+/// trait](pipeline/shader/trait.SpecializationConstants.html) for an example.
+#[macro_export]
+macro_rules! impl_specialization_constants {
+    ($out:ty $(, $member:ident => $constant_id:expr)* $(,)*) => (
+        #[allow(unsafe_code)]
+        unsafe impl $crate::pipeline::shader::SpecializationConstants for $out {
+            #[inline]
+            fn descriptors() -> &'static [$crate::pipeline::shader::SpecializationMapEntry] {
+                use $crate::pipeline::shader::SpecializationMapEntry;
+                use $crate::pipeline::shader::specialization_constant_size;
+
+                static DESCRIPTORS: &'static [SpecializationMapEntry] = &[
+                    $(
+                        SpecializationMapEntry {
+                            constant_id: $constant_id,
+                            offset: ::std::mem::offset_of!($out, $member) as u32,
+                            size: specialization_constant_size(|c: &$out| &c.$member),
+                        },
+                    )*
+                ];
+
+                DESCRIPTORS
+            }
+        }
+    )
+}
+
+// Used by the `impl_specialization_constants!` macro and the `SpecializationConstants` derive
+// in order to get the size of a member.
+#[doc(hidden)]
+#[inline]
+pub const fn specialization_constant_size<T, F>(_: fn(&T) -> &F) -> usize {
+    mem::size_of::<F>()
+}
+
 /// Describes an indiviual constant to set in the shader. Also a field in the struct.
 // Implementation note: has the same memory representation as a `VkSpecializationMapEntry`.
 #[repr(C)]
@@ -646,3 +1210,97 @@ pub struct SpecializationMapEntry {
     /// Size of the data in bytes. Must match the size of the constant (`4` for booleans).
     pub size: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use features::Features;
+    use pipeline::shader::CapabilitySupport;
+    use pipeline::shader::ShaderModuleCreationError;
+    use pipeline::shader::capability_feature;
+    use pipeline::shader::check_spirv;
+    use version::Version;
+    use spirv::*;
+    use spirv::tests::module;
+
+    #[test]
+    fn check_spirv_structure() {
+        let words = [MAGIC, 0x00010000, 0];
+        match check_spirv(&words, 0, &Features::none()) {
+            Err(ShaderModuleCreationError::InvalidSpirv(ShaderReflectionError::MissingHeader)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn check_spirv_version() {
+        let mut words = module(&[]);
+        words[1] = 0x00020000;
+        match check_spirv(&words, 0, &Features::none()) {
+            Err(ShaderModuleCreationError::UnsupportedVersion { major: 2, minor: 0 }) => (),
+            _ => panic!(),
+        }
+
+        words[1] = 0x00010400;
+        match check_spirv(&words, 3, &Features::none()) {
+            Err(ShaderModuleCreationError::VersionNotSupportedByDevice {
+                    minor: 4,
+                    max_minor: 3,
+                }) => (),
+            _ => panic!(),
+        }
+        assert!(check_spirv(&words, 4, &Features::none()).is_ok());
+    }
+
+    #[test]
+    fn max_spirv_minor() {
+        let version = |minor| Version { major: 1, minor: minor, patch: 0 };
+        assert_eq!(super::max_spirv_minor(version(0), false), 0);
+        assert_eq!(super::max_spirv_minor(version(1), false), 3);
+        assert_eq!(super::max_spirv_minor(version(1), true), 4);
+        assert_eq!(super::max_spirv_minor(version(2), true), 5);
+        assert_eq!(super::max_spirv_minor(version(3), true), 6);
+    }
+
+    #[test]
+    fn check_spirv_capabilities() {
+        // `Shader` and `Geometry`.
+        let words = module(&[(OP_CAPABILITY, &[1]), (OP_CAPABILITY, &[2])]);
+        match check_spirv(&words, 0, &Features::none()) {
+            Err(ShaderModuleCreationError::FeatureNotEnabled {
+                    capability: 2,
+                    feature: "geometry_shader",
+                }) => (),
+            _ => panic!(),
+        }
+
+        let features = Features {
+            geometry_shader: true,
+            ..Features::none()
+        };
+        match capability_feature(&features, 2) {
+            CapabilitySupport::Supported => (),
+            _ => panic!(),
+        }
+
+        // `Kernel`.
+        let words = module(&[(OP_CAPABILITY, &[6])]);
+        match check_spirv(&words, 0, &features) {
+            Err(ShaderModuleCreationError::UnsupportedCapability(6)) => (),
+            _ => panic!(),
+        }
+
+        // `StorageBuffer16BitAccess`.
+        let small_types = Features {
+            storage_buffer_16bit_access: true,
+            ..Features::none()
+        };
+        match capability_feature(&features, 4433) {
+            CapabilitySupport::FeatureNotEnabled("storage_buffer_16bit_access") => (),
+            _ => panic!(),
+        }
+        match capability_feature(&small_types, 4433) {
+            CapabilitySupport::Supported => (),
+            _ => panic!(),
+        }
+    }
+}
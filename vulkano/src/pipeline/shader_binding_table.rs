@@ -0,0 +1,480 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Shader binding tables, which tell a ray tracing pipeline which shader groups to invoke.
+//!
+//! When tracing rays, the Vulkan implementation looks for the shaders to invoke in a buffer
+//! called the *shader binding table*. This buffer is split in four regions: one that contains
+//! the ray generation shader group, and three arrays of records for the miss shader groups, the
+//! hit groups and the callable shader groups. Each record starts with the opaque handle of a
+//! shader group, and can be followed by arbitrary data that the shaders can read through the
+//! `shaderRecordEXT` storage class.
+//!
+//! The handles, their alignment and the alignment of the regions depend on the implementation.
+//! The `ShaderBindingTable` struct takes care of all of this:
+//!
+//! ```ignore
+//! let sbt = ShaderBindingTable::start(&pipeline)
+//!     .raygen(0)
+//!     .miss(1)
+//!     .hit_group_with_data(2, &material_data)
+//!     .build()?;
+//!
+//! builder.trace_rays([width, height, 1], pipeline.clone(), sbt.buffer().clone(),
+//!                    sbt.regions(), sets, ())?;
+//! ```
+
+use std::cmp;
+use std::error;
+use std::fmt;
+use std::sync::Arc;
+
+use buffer::BufferAccess;
+use buffer::BufferUsage;
+use buffer::CpuAccessibleBuffer;
+use device::Device;
+use memory::DeviceMemoryAllocError;
+use pipeline::RayTracingPipeline;
+use pipeline::ShaderBindingTableRegion;
+use pipeline::ShaderBindingTableRegions;
+use pipeline::ShaderGroupType;
+
+/// A buffer containing the records of the shader groups of a ray tracing pipeline, and the
+/// location of each region in that buffer.
+#[derive(Debug)]
+pub struct ShaderBindingTable {
+    buffer: Arc<CpuAccessibleBuffer<[u8]>>,
+    regions: ShaderBindingTableRegions,
+}
+
+impl ShaderBindingTable {
+    /// Starts building a shader binding table for the shader groups of `pipeline`.
+    #[inline]
+    pub fn start<Pl>(pipeline: &RayTracingPipeline<Pl>) -> ShaderBindingTableBuilder<Pl> {
+        ShaderBindingTableBuilder {
+            pipeline: pipeline,
+            raygen: None,
+            miss: Vec::new(),
+            hit: Vec::new(),
+            callable: Vec::new(),
+        }
+    }
+
+    /// Returns the buffer that contains the records.
+    #[inline]
+    pub fn buffer(&self) -> &Arc<CpuAccessibleBuffer<[u8]>> {
+        &self.buffer
+    }
+
+    /// Returns the location of each region in the buffer, to pass to `trace_rays`.
+    #[inline]
+    pub fn regions(&self) -> ShaderBindingTableRegions {
+        self.regions
+    }
+}
+
+/// Prototype for a `ShaderBindingTable`.
+///
+/// The records of each region are in the order in which they are added. The index of a record
+/// within its region is what the shaders pass to `traceRayEXT` or `executeCallableEXT` to select
+/// it.
+pub struct ShaderBindingTableBuilder<'a, Pl: 'a> {
+    pipeline: &'a RayTracingPipeline<Pl>,
+    raygen: Option<Record<'a>>,
+    miss: Vec<Record<'a>>,
+    hit: Vec<Record<'a>>,
+    callable: Vec<Record<'a>>,
+}
+
+// A shader group and the data that follows its handle.
+struct Record<'a> {
+    group: usize,
+    data: &'a [u8],
+}
+
+impl<'a, Pl> ShaderBindingTableBuilder<'a, Pl> {
+    /// Sets the ray generation shader group. There can only be one.
+    #[inline]
+    pub fn raygen(self, group: usize) -> Self {
+        self.raygen_with_data(group, &[])
+    }
+
+    /// Sets the ray generation shader group, and the data that follows its handle.
+    #[inline]
+    pub fn raygen_with_data(mut self, group: usize, data: &'a [u8]) -> Self {
+        self.raygen = Some(Record { group, data });
+        self
+    }
+
+    /// Adds a record for a miss shader group.
+    #[inline]
+    pub fn miss(self, group: usize) -> Self {
+        self.miss_with_data(group, &[])
+    }
+
+    /// Adds a record for a miss shader group, with data that follows its handle.
+    #[inline]
+    pub fn miss_with_data(mut self, group: usize, data: &'a [u8]) -> Self {
+        self.miss.push(Record { group, data });
+        self
+    }
+
+    /// Adds a record for a hit group.
+    #[inline]
+    pub fn hit_group(self, group: usize) -> Self {
+        self.hit_group_with_data(group, &[])
+    }
+
+    /// Adds a record for a hit group, with data that follows its handle.
+    #[inline]
+    pub fn hit_group_with_data(mut self, group: usize, data: &'a [u8]) -> Self {
+        self.hit.push(Record { group, data });
+        self
+    }
+
+    /// Adds a record for a callable shader group.
+    #[inline]
+    pub fn callable(self, group: usize) -> Self {
+        self.callable_with_data(group, &[])
+    }
+
+    /// Adds a record for a callable shader group, with data that follows its handle.
+    #[inline]
+    pub fn callable_with_data(mut self, group: usize, data: &'a [u8]) -> Self {
+        self.callable.push(Record { group, data });
+        self
+    }
+
+    /// Allocates the buffer and writes the records in it.
+    pub fn build(self) -> Result<ShaderBindingTable, ShaderBindingTableCreationError> {
+        let device = self.pipeline.device();
+        if !device.buffer_device_address_enabled() {
+            return Err(ShaderBindingTableCreationError::BufferDeviceAddressFeatureNotEnabled);
+        }
+
+        let raygen = match self.raygen {
+            Some(ref r) => r,
+            None => return Err(ShaderBindingTableCreationError::NoRayGenerationGroup),
+        };
+
+        self.check_groups(ShaderGroupType::RayGeneration, Some(raygen))?;
+        self.check_groups(ShaderGroupType::Miss, &self.miss)?;
+        self.check_groups(ShaderGroupType::Hit, &self.hit)?;
+        self.check_groups(ShaderGroupType::Callable, &self.callable)?;
+
+        let properties = device.ray_tracing_properties();
+        let layout = Layout {
+            handle_size: properties.shader_group_handle_size as usize,
+            handle_alignment: properties.shader_group_handle_alignment as usize,
+            base_alignment: properties.shader_group_base_alignment as usize,
+        };
+
+        let (regions, size) = layout.regions([max_data_len(Some(raygen)),
+                                              max_data_len(&self.miss),
+                                              max_data_len(&self.hit),
+                                              max_data_len(&self.callable)],
+                                             [1, self.miss.len(), self.hit.len(),
+                                              self.callable.len()]);
+
+        for region in [regions.raygen, regions.miss, regions.hit, regions.callable].iter() {
+            if region.stride > properties.max_shader_group_stride as usize {
+                return Err(ShaderBindingTableCreationError::MaxStrideExceeded {
+                               max: properties.max_shader_group_stride as usize,
+                               obtained: region.stride,
+                           });
+            }
+        }
+
+        let (buffer, regions) = allocate(device, &layout, regions, size)?;
+
+        {
+            let mut content = buffer.write().unwrap();
+            let records = [(regions.raygen, Some(raygen).into_iter().collect::<Vec<_>>()),
+                           (regions.miss, self.miss.iter().collect()),
+                           (regions.hit, self.hit.iter().collect()),
+                           (regions.callable, self.callable.iter().collect())];
+
+            for &(ref region, ref records) in records.iter() {
+                for (num, record) in records.iter().enumerate() {
+                    let start = region.offset + num * region.stride;
+                    let handle = self.pipeline.group_handle(record.group).unwrap();
+                    content[start .. start + handle.len()].copy_from_slice(handle);
+                    let data_start = start + handle.len();
+                    content[data_start .. data_start + record.data.len()]
+                        .copy_from_slice(record.data);
+                }
+            }
+        }
+
+        Ok(ShaderBindingTable { buffer, regions })
+    }
+
+    // Checks that the groups of `records` exist and have the type `ty`.
+    fn check_groups<'r, I>(&self, ty: ShaderGroupType, records: I)
+                           -> Result<(), ShaderBindingTableCreationError>
+        where I: IntoIterator<Item = &'r Record<'a>>,
+              'a: 'r
+    {
+        let groups = self.pipeline.shader_groups();
+
+        for record in records {
+            match groups.get(record.group) {
+                Some(&obtained) if obtained == ty => (),
+                Some(&obtained) => {
+                    return Err(ShaderBindingTableCreationError::WrongGroupType {
+                                   group: record.group,
+                                   expected: ty,
+                                   obtained: obtained,
+                               });
+                },
+                None => {
+                    return Err(ShaderBindingTableCreationError::GroupOutOfRange {
+                                   group: record.group,
+                                   num_groups: groups.len(),
+                               });
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Returns the length of the largest data of a list of records.
+fn max_data_len<'r, 'a: 'r, I>(records: I) -> usize
+    where I: IntoIterator<Item = &'r Record<'a>>
+{
+    records
+        .into_iter()
+        .map(|r| r.data.len())
+        .max()
+        .unwrap_or(0)
+}
+
+// Allocates a buffer for a table of `size` bytes, and moves the regions so that they start at
+// addresses aligned to the base alignment.
+fn allocate(device: &Arc<Device>, layout: &Layout, mut regions: ShaderBindingTableRegions,
+            size: usize)
+            -> Result<(Arc<CpuAccessibleBuffer<[u8]>>, ShaderBindingTableRegions),
+                      ShaderBindingTableCreationError> {
+    let usage = BufferUsage {
+        shader_binding_table: true,
+        shader_device_address: true,
+        ..BufferUsage::none()
+    };
+
+    let base_alignment = cmp::max(layout.base_alignment, 1);
+    let buffer = unsafe {
+        CpuAccessibleBuffer::uninitialized_array(device.clone(), size + base_alignment, usage)?
+    };
+
+    let address = buffer.device_address().unwrap() as usize;
+    let padding = (base_alignment - address % base_alignment) % base_alignment;
+    for region in [&mut regions.raygen, &mut regions.miss, &mut regions.hit, &mut regions.callable]
+        .iter_mut()
+    {
+        if region.size != 0 {
+            region.offset += padding;
+        }
+    }
+
+    Ok((buffer, regions))
+}
+
+// Alignment rules of the shader binding tables of a device.
+struct Layout {
+    handle_size: usize,
+    handle_alignment: usize,
+    base_alignment: usize,
+}
+
+impl Layout {
+    // Computes the location of each region, given the length of the largest data and the number
+    // of records of each region, in the order raygen, miss, hit and callable. Also returns the
+    // total size of the table.
+    fn regions(&self, max_data_len: [usize; 4], num_records: [usize; 4])
+               -> (ShaderBindingTableRegions, usize) {
+        let mut regions = [ShaderBindingTableRegion::default(); 4];
+        let mut offset = 0;
+
+        for (num, region) in regions.iter_mut().enumerate() {
+            if num_records[num] == 0 {
+                continue;
+            }
+
+            let stride = align(self.handle_size + max_data_len[num], self.handle_alignment);
+            *region = ShaderBindingTableRegion {
+                offset: offset,
+                stride: stride,
+                size: stride * num_records[num],
+            };
+            offset = align(offset + region.size, self.base_alignment);
+        }
+
+        let regions = ShaderBindingTableRegions {
+            raygen: regions[0],
+            miss: regions[1],
+            hit: regions[2],
+            callable: regions[3],
+        };
+
+        (regions, offset)
+    }
+}
+
+// Rounds `value` up to a multiple of `alignment`.
+#[inline]
+fn align(value: usize, alignment: usize) -> usize {
+    if alignment == 0 {
+        return value;
+    }
+
+    (value + alignment - 1) / alignment * alignment
+}
+
+/// Error that can happen when building a shader binding table.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShaderBindingTableCreationError {
+    /// Not enough memory to allocate the buffer.
+    DeviceMemoryAllocError(DeviceMemoryAllocError),
+
+    /// The `bufferDeviceAddress` feature of the `VK_KHR_buffer_device_address` extension must be
+    /// enabled.
+    BufferDeviceAddressFeatureNotEnabled,
+
+    /// No ray generation shader group was set.
+    NoRayGenerationGroup,
+
+    /// A record refers to a shader group that doesn't exist in the pipeline.
+    GroupOutOfRange {
+        /// The index of the group.
+        group: usize,
+        /// The number of groups in the pipeline.
+        num_groups: usize,
+    },
+
+    /// A record was added to a region that doesn't match the type of its shader group.
+    WrongGroupType {
+        /// The index of the group.
+        group: usize,
+        /// The type of group that the region expects.
+        expected: ShaderGroupType,
+        /// The type of the group in the pipeline.
+        obtained: ShaderGroupType,
+    },
+
+    /// The data of the records is too large for the `max_shader_group_stride` limit.
+    MaxStrideExceeded {
+        /// Maximum allowed value.
+        max: usize,
+        /// Value that was required.
+        obtained: usize,
+    },
+}
+
+impl error::Error for ShaderBindingTableCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ShaderBindingTableCreationError::DeviceMemoryAllocError(_) => {
+                "not enough memory to allocate the buffer"
+            },
+            ShaderBindingTableCreationError::BufferDeviceAddressFeatureNotEnabled => {
+                "the `bufferDeviceAddress` feature must be enabled"
+            },
+            ShaderBindingTableCreationError::NoRayGenerationGroup => {
+                "no ray generation shader group was set"
+            },
+            ShaderBindingTableCreationError::GroupOutOfRange { .. } => {
+                "a record refers to a shader group that doesn't exist in the pipeline"
+            },
+            ShaderBindingTableCreationError::WrongGroupType { .. } => {
+                "a record was added to a region that doesn't match the type of its shader group"
+            },
+            ShaderBindingTableCreationError::MaxStrideExceeded { .. } => {
+                "the data of the records is too large for the `max_shader_group_stride` limit"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ShaderBindingTableCreationError::DeviceMemoryAllocError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ShaderBindingTableCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<DeviceMemoryAllocError> for ShaderBindingTableCreationError {
+    #[inline]
+    fn from(err: DeviceMemoryAllocError) -> ShaderBindingTableCreationError {
+        ShaderBindingTableCreationError::DeviceMemoryAllocError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Layout;
+    use pipeline::ShaderBindingTableRegion;
+
+    #[test]
+    fn regions_layout() {
+        let layout = Layout {
+            handle_size: 32,
+            handle_alignment: 32,
+            base_alignment: 64,
+        };
+
+        let (regions, size) = layout.regions([0, 8, 0, 0], [1, 2, 1, 0]);
+
+        assert_eq!(regions.raygen,
+                   ShaderBindingTableRegion {
+                       offset: 0,
+                       stride: 32,
+                       size: 32,
+                   });
+        assert_eq!(regions.miss,
+                   ShaderBindingTableRegion {
+                       offset: 64,
+                       stride: 64,
+                       size: 128,
+                   });
+        assert_eq!(regions.hit,
+                   ShaderBindingTableRegion {
+                       offset: 192,
+                       stride: 32,
+                       size: 32,
+                   });
+        assert_eq!(regions.callable, ShaderBindingTableRegion::default());
+        assert_eq!(size, 256);
+    }
+
+    #[test]
+    fn zero_alignments() {
+        let layout = Layout {
+            handle_size: 16,
+            handle_alignment: 0,
+            base_alignment: 0,
+        };
+
+        let (regions, size) = layout.regions([4, 0, 0, 2], [1, 0, 0, 3]);
+        assert_eq!(regions.raygen.size, 20);
+        assert_eq!(regions.callable.offset, 20);
+        assert_eq!(regions.callable.stride, 18);
+        assert_eq!(size, 74);
+    }
+}
@@ -12,6 +12,7 @@ use std::iter;
 use buffer::BufferAccess;
 use pipeline::vertex::AttributeInfo;
 use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::IncompatibleVertexSourceError;
 use pipeline::vertex::InputRate;
 use pipeline::vertex::VertexDefinition;
 use pipeline::vertex::VertexSource;
@@ -20,6 +21,7 @@ use pipeline::vertex::VertexSource;
 ///
 /// This is only useful if your shaders come up with vertex data on their own, e.g. by inspecting
 /// `gl_VertexIndex`
+#[derive(Debug, Copy, Clone)]
 pub struct BufferlessDefinition;
 
 /// Value to be passed as the vertex source for bufferless draw commands.
@@ -33,14 +35,16 @@ pub struct BufferlessVertices {
 
 unsafe impl VertexSource<BufferlessVertices> for BufferlessDefinition {
     fn decode(&self, n: BufferlessVertices)
-              -> (Vec<Box<BufferAccess + Sync + Send + 'static>>, usize, usize) {
-        (Vec::new(), n.vertices, n.instances)
+              -> Result<(Vec<Box<BufferAccess + Sync + Send + 'static>>, usize, usize),
+                        IncompatibleVertexSourceError> {
+        Ok((Vec::new(), n.vertices, n.instances))
     }
 }
 
 unsafe impl<T> VertexSource<Vec<T>> for BufferlessDefinition {
     fn decode<'l>(&self, _: Vec<T>)
-                  -> (Vec<Box<BufferAccess + Sync + Send + 'static>>, usize, usize) {
+                  -> Result<(Vec<Box<BufferAccess + Sync + Send + 'static>>, usize, usize),
+                            IncompatibleVertexSourceError> {
         panic!("bufferless drawing should not be supplied with buffers")
     }
 }
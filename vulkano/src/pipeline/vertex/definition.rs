@@ -48,13 +48,30 @@ unsafe impl<I, T> VertexDefinition<I> for T
 }
 
 /// How the vertex source should be unrolled.
-#[derive(Copy, Clone, Debug)]
-#[repr(u32)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum InputRate {
     /// Each element of the source corresponds to a vertex.
-    Vertex = vk::VERTEX_INPUT_RATE_VERTEX,
-    /// Each element of the source corresponds to an instance.
-    Instance = vk::VERTEX_INPUT_RATE_INSTANCE,
+    Vertex,
+    /// Each element of the source corresponds to `divisor` consecutive instances.
+    ///
+    /// A `divisor` different from 1 requires the `VK_EXT_vertex_attribute_divisor` extension
+    /// and its `instance_rate_divisor` feature. A `divisor` of 0 means that the same element is
+    /// used for all the instances, and requires the `instance_rate_zero_divisor` feature.
+    /// The `divisor` must not be larger than `Device::max_vertex_attrib_divisor`.
+    Instance {
+        divisor: u32,
+    },
+}
+
+impl Into<vk::VertexInputRate> for InputRate {
+    #[inline]
+    fn into(self) -> vk::VertexInputRate {
+        match self {
+            InputRate::Vertex => vk::VERTEX_INPUT_RATE_VERTEX,
+            InputRate::Instance { .. } => vk::VERTEX_INPUT_RATE_INSTANCE,
+        }
+    }
 }
 
 /// Information about a single attribute within a vertex.
@@ -84,6 +101,24 @@ pub enum IncompatibleVertexDefinitionError {
         /// The format in the vertex definition.
         definition: (VertexMemberTy, usize),
     },
+
+    /// The numeric type of the format of an attribute doesn't match the one of the vertex shader.
+    IncompatibleFormat {
+        /// Location of the attribute.
+        location: u32,
+        /// The format in the vertex shader.
+        shader: Format,
+        /// The format in the vertex definition.
+        definition: Format,
+    },
+
+    /// An attribute refers to a binding that doesn't exist.
+    InvalidBinding {
+        /// Location of the attribute.
+        location: u32,
+        /// The binding that the attribute refers to.
+        binding: u32,
+    },
 }
 
 impl error::Error for IncompatibleVertexDefinitionError {
@@ -94,6 +129,12 @@ impl error::Error for IncompatibleVertexDefinitionError {
             IncompatibleVertexDefinitionError::FormatMismatch { .. } => {
                 "the format of an attribute does not match"
             },
+            IncompatibleVertexDefinitionError::IncompatibleFormat { .. } => {
+                "the numeric type of the format of an attribute doesn't match the vertex shader"
+            },
+            IncompatibleVertexDefinitionError::InvalidBinding { .. } => {
+                "an attribute refers to a binding that doesn't exist"
+            },
         }
     }
 }
@@ -110,10 +151,11 @@ impl fmt::Display for IncompatibleVertexDefinitionError {
 /// vertex definition.
 pub unsafe trait VertexSource<L> {
     /// Checks and returns the list of buffers with offsets, number of vertices and number of instances.
-    // TODO: return error if problem
     // TODO: better than a Vec
     // TODO: return a struct instead
-    fn decode(&self, L) -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize);
+    fn decode(&self, L)
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError>;
 }
 
 unsafe impl<L, T> VertexSource<L> for T
@@ -121,7 +163,65 @@ unsafe impl<L, T> VertexSource<L> for T
           T::Target: VertexSource<L>
 {
     #[inline]
-    fn decode(&self, list: L) -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
+    fn decode(&self, list: L)
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
         (**self).decode(list)
     }
 }
+
+/// Error that can happen when the vertex buffers passed to a draw command don't match the vertex
+/// definition of the pipeline.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IncompatibleVertexSourceError {
+    /// The number of vertex buffers doesn't match the number of bindings of the vertex
+    /// definition.
+    WrongBuffersCount {
+        /// Number of bindings of the vertex definition.
+        expected: usize,
+        /// Number of vertex buffers that were passed.
+        obtained: usize,
+    },
+    /// A vertex buffer is attached to a binding that isn't in the vertex definition.
+    InvalidBinding {
+        /// The binding of the vertex buffer.
+        binding: u32,
+    },
+    /// Multiple vertex buffers are attached to the same binding.
+    DuplicateBinding {
+        /// The binding of the vertex buffers.
+        binding: u32,
+    },
+    /// A binding of the vertex definition doesn't have any vertex buffer.
+    MissingBinding {
+        /// The binding without vertex buffer.
+        binding: u32,
+    },
+}
+
+impl error::Error for IncompatibleVertexSourceError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            IncompatibleVertexSourceError::WrongBuffersCount { .. } => {
+                "the number of vertex buffers doesn't match the vertex definition"
+            },
+            IncompatibleVertexSourceError::InvalidBinding { .. } => {
+                "a vertex buffer is attached to a binding that doesn't exist"
+            },
+            IncompatibleVertexSourceError::DuplicateBinding { .. } => {
+                "multiple vertex buffers are attached to the same binding"
+            },
+            IncompatibleVertexSourceError::MissingBinding { .. } => {
+                "a binding of the vertex definition doesn't have any vertex buffer"
+            },
+        }
+    }
+}
+
+impl fmt::Display for IncompatibleVertexSourceError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
@@ -7,6 +7,8 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use half::f16;
+
 use pipeline::vertex::VertexMemberTy;
 
 /// Implements the `Vertex` trait on a struct.
@@ -101,6 +103,13 @@ unsafe impl VertexMember for u32 {
     }
 }
 
+unsafe impl VertexMember for f16 {
+    #[inline]
+    fn format() -> (VertexMemberTy, usize) {
+        (VertexMemberTy::F16, 1)
+    }
+}
+
 unsafe impl VertexMember for f32 {
     #[inline]
     fn format() -> (VertexMemberTy, usize) {
@@ -21,6 +21,9 @@
 //! The most common situation is a single vertex buffer and no instancing, in which case you can
 //! pass a `SingleBufferDefinition` when you create the pipeline.
 //!
+//! If the layout of your vertices is only known at runtime, you can instead use a
+//! `RuntimeVertexDefinition`, which is built from a list of bindings and attributes.
+//!
 //! # Implementing `Vertex`
 //!
 //! The implementations of the `VertexDefinition` trait that are provided by vulkano (like
@@ -67,11 +70,16 @@ pub use self::bufferless::BufferlessDefinition;
 pub use self::bufferless::BufferlessVertices;
 pub use self::definition::AttributeInfo;
 pub use self::definition::IncompatibleVertexDefinitionError;
+pub use self::definition::IncompatibleVertexSourceError;
 pub use self::definition::InputRate;
 pub use self::definition::VertexDefinition;
 pub use self::definition::VertexSource;
 pub use self::impl_vertex::VertexMember;
 pub use self::one_one::OneVertexOneInstanceDefinition;
+pub use self::runtime::RuntimeVertexAttribute;
+pub use self::runtime::RuntimeVertexBinding;
+pub use self::runtime::RuntimeVertexDefinition;
+pub use self::runtime::VertexBuffers;
 pub use self::single::SingleBufferDefinition;
 pub use self::two::TwoBuffersDefinition;
 pub use self::vertex::Vertex;
@@ -81,6 +89,7 @@ pub use self::vertex::VertexMemberTy;
 mod definition;
 mod impl_vertex;
 mod one_one;
+mod runtime;
 mod single;
 mod two;
 mod vertex;
@@ -17,6 +17,7 @@ use buffer::TypedBufferAccess;
 use pipeline::shader::ShaderInterfaceDef;
 use pipeline::vertex::AttributeInfo;
 use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::IncompatibleVertexSourceError;
 use pipeline::vertex::InputRate;
 use pipeline::vertex::Vertex;
 use pipeline::vertex::VertexDefinition;
@@ -26,6 +27,15 @@ use pipeline::vertex::VertexSource;
 // TODO: bad way to do things
 pub struct OneVertexOneInstanceDefinition<T, U>(pub PhantomData<(T, U)>);
 
+impl<T, U> Clone for OneVertexOneInstanceDefinition<T, U> {
+    #[inline]
+    fn clone(&self) -> Self {
+        OneVertexOneInstanceDefinition(PhantomData)
+    }
+}
+
+impl<T, U> Copy for OneVertexOneInstanceDefinition<T, U> {}
+
 impl<T, U> OneVertexOneInstanceDefinition<T, U> {
     #[inline]
     pub fn new() -> OneVertexOneInstanceDefinition<T, U> {
@@ -86,7 +96,7 @@ unsafe impl<T, U, I> VertexDefinition<I> for OneVertexOneInstanceDefinition<T, U
 
         let buffers = vec![
             (0, mem::size_of::<T>(), InputRate::Vertex),
-            (1, mem::size_of::<U>(), InputRate::Instance),
+            (1, mem::size_of::<U>(), InputRate::Instance { divisor: 1 }),
         ].into_iter();
 
         Ok((buffers, attrib))
@@ -100,14 +110,20 @@ unsafe impl<T, U> VertexSource<Vec<Arc<BufferAccess + Send + Sync>>>
 {
     #[inline]
     fn decode(&self, mut source: Vec<Arc<BufferAccess + Send + Sync>>)
-              -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
-        // FIXME: safety
-        assert_eq!(source.len(), 2);
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
+        if source.len() != 2 {
+            return Err(IncompatibleVertexSourceError::WrongBuffersCount {
+                           expected: 2,
+                           obtained: source.len(),
+                       });
+        }
+
         let len = source[0].size() / mem::size_of::<T>();
         let inst = source[1].size() / mem::size_of::<U>();
         let s0 = source.remove(0);
         let s1 = source.remove(0);
-        (vec![Box::new(s0) as Box<_>, Box::new(s1) as Box<_>], len, inst)
+        Ok((vec![Box::new(s0) as Box<_>, Box::new(s1) as Box<_>], len, inst))
     }
 }
 
@@ -118,9 +134,11 @@ unsafe impl<'a, T, U, Bt, Bu> VertexSource<(Bt, Bu)> for OneVertexOneInstanceDef
           Bu: TypedBufferAccess<Content = [U]> + Send + Sync + 'static
 {
     #[inline]
-    fn decode(&self, source: (Bt, Bu)) -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
+    fn decode(&self, source: (Bt, Bu))
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
         let s1l = source.0.len();
         let s2l = source.1.len();
-        (vec![Box::new(source.0) as Box<_>, Box::new(source.1) as Box<_>], s1l, s2l)
+        Ok((vec![Box::new(source.0) as Box<_>, Box::new(source.1) as Box<_>], s1l, s2l))
     }
 }
@@ -0,0 +1,518 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::ops::Range;
+use std::sync::Arc;
+use std::vec::IntoIter as VecIntoIter;
+
+use buffer::BufferAccess;
+use buffer::BufferSlice;
+use format::Format;
+use pipeline::shader::ShaderInterfaceDef;
+use pipeline::vertex::AttributeInfo;
+use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::IncompatibleVertexSourceError;
+use pipeline::vertex::InputRate;
+use pipeline::vertex::VertexDefinition;
+use pipeline::vertex::VertexSource;
+
+/// Vertex definition whose layout is only known at runtime.
+///
+/// Contrary to the other vertex definitions, the layout isn't determined by implementations of
+/// the `Vertex` trait but by a list of bindings and a list of attributes. This is useful if the
+/// layout of your vertices is loaded from a file, for example.
+///
+/// The vertex buffers that you pass when drawing must be in the same order as the bindings, unless
+/// they are passed as a `VertexBuffers` which attaches each of them to an explicit binding.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::format::Format;
+/// use vulkano::pipeline::vertex::InputRate;
+/// use vulkano::pipeline::vertex::RuntimeVertexAttribute;
+/// use vulkano::pipeline::vertex::RuntimeVertexBinding;
+/// use vulkano::pipeline::vertex::RuntimeVertexDefinition;
+///
+/// // Positions and colors interleaved in one buffer.
+/// let definition = RuntimeVertexDefinition::new(
+///     vec![RuntimeVertexBinding { stride: 28, input_rate: InputRate::Vertex }],
+///     vec![
+///         RuntimeVertexAttribute { location: 0, binding: 0, format: Format::R32G32B32Sfloat, offset: 0 },
+///         RuntimeVertexAttribute { location: 1, binding: 0, format: Format::R32G32B32A32Sfloat, offset: 12 },
+///     ]);
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RuntimeVertexDefinition {
+    bindings: Vec<RuntimeVertexBinding>,
+    attributes: Vec<RuntimeVertexAttribute>,
+}
+
+/// Description of a vertex buffer of a `RuntimeVertexDefinition`.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RuntimeVertexBinding {
+    /// Number of bytes between the start of an element and the start of the next one.
+    pub stride: u32,
+    /// Whether each element corresponds to a vertex or to an instance.
+    pub input_rate: InputRate,
+}
+
+/// Description of an attribute of a `RuntimeVertexDefinition`.
+#[derive(Debug, Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct RuntimeVertexAttribute {
+    /// Location of the attribute in the vertex shader. Attributes that span multiple locations,
+    /// like matrices, need one `RuntimeVertexAttribute` per location.
+    pub location: u32,
+    /// Index of the binding that contains the attribute, within the list of bindings.
+    pub binding: u32,
+    /// Format of the attribute in the vertex buffer.
+    pub format: Format,
+    /// Number of bytes between the start of an element and the location of the attribute.
+    pub offset: u32,
+}
+
+impl RuntimeVertexDefinition {
+    /// Builds a new definition from a list of bindings and a list of attributes.
+    ///
+    /// The definition is only checked against the vertex shader when creating the pipeline.
+    #[inline]
+    pub fn new(bindings: Vec<RuntimeVertexBinding>, attributes: Vec<RuntimeVertexAttribute>)
+               -> RuntimeVertexDefinition {
+        RuntimeVertexDefinition {
+            bindings: bindings,
+            attributes: attributes,
+        }
+    }
+
+    /// Returns the list of bindings.
+    #[inline]
+    pub fn bindings(&self) -> &[RuntimeVertexBinding] {
+        &self.bindings
+    }
+
+    /// Returns the list of attributes.
+    #[inline]
+    pub fn attributes(&self) -> &[RuntimeVertexAttribute] {
+        &self.attributes
+    }
+}
+
+unsafe impl<I> VertexDefinition<I> for RuntimeVertexDefinition
+    where I: ShaderInterfaceDef
+{
+    type BuffersIter = VecIntoIter<(u32, usize, InputRate)>;
+    type AttribsIter = VecIntoIter<(u32, u32, AttributeInfo)>;
+
+    fn definition(
+        &self, interface: &I)
+        -> Result<(Self::BuffersIter, Self::AttribsIter), IncompatibleVertexDefinitionError> {
+        let mut attribs = Vec::with_capacity(interface.elements().len());
+
+        for e in interface.elements() {
+            for loc in e.location.clone() {
+                let attribute = match self.attributes.iter().find(|a| a.location == loc) {
+                    Some(a) => a,
+                    None => {
+                        let name = e.name
+                            .as_ref()
+                            .map(|n| n.clone().into_owned())
+                            .unwrap_or_else(|| format!("location {}", loc));
+                        return Err(IncompatibleVertexDefinitionError::MissingAttribute {
+                                       attribute: name,
+                                   });
+                    },
+                };
+
+                if attribute.binding as usize >= self.bindings.len() {
+                    return Err(IncompatibleVertexDefinitionError::InvalidBinding {
+                                   location: loc,
+                                   binding: attribute.binding,
+                               });
+                }
+
+                // The numeric type of the attribute must match the one of the shader, but not
+                // necessarily the number of components.
+                if attribute.format.ty() != e.format.ty() {
+                    return Err(IncompatibleVertexDefinitionError::IncompatibleFormat {
+                                   location: loc,
+                                   shader: e.format,
+                                   definition: attribute.format,
+                               });
+                }
+
+                attribs.push((loc,
+                              attribute.binding,
+                              AttributeInfo {
+                                  offset: attribute.offset as usize,
+                                  format: attribute.format,
+                              }));
+            }
+        }
+
+        let buffers = self.bindings
+            .iter()
+            .enumerate()
+            .map(|(num, b)| (num as u32, b.stride as usize, b.input_rate))
+            .collect::<Vec<_>>();
+
+        Ok((buffers.into_iter(), attribs.into_iter()))
+    }
+}
+
+impl RuntimeVertexDefinition {
+    // Returns the number of vertices and instances available in buffers of the given sizes, in
+    // the same order as the bindings.
+    fn vertex_instance_counts<I>(&self, sizes: I) -> (usize, usize)
+        where I: IntoIterator<Item = usize>
+    {
+        let mut vertices = None;
+        let mut instances = None;
+
+        for (size, binding) in sizes.into_iter().zip(self.bindings.iter()) {
+            if binding.stride == 0 {
+                continue;
+            }
+
+            let num = size / binding.stride as usize;
+            let (target, num) = match binding.input_rate {
+                InputRate::Vertex => (&mut vertices, num),
+                // The same element is used for all the instances.
+                InputRate::Instance { divisor: 0 } => continue,
+                InputRate::Instance { divisor } => (&mut instances, num * divisor as usize),
+            };
+            *target = Some(target.map_or(num, |t: usize| t.min(num)));
+        }
+
+        (vertices.unwrap_or(0), instances.unwrap_or(1))
+    }
+}
+
+unsafe impl VertexSource<Vec<Arc<BufferAccess + Send + Sync>>> for RuntimeVertexDefinition {
+    fn decode(&self, source: Vec<Arc<BufferAccess + Send + Sync>>)
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
+        if source.len() != self.bindings.len() {
+            return Err(IncompatibleVertexSourceError::WrongBuffersCount {
+                           expected: self.bindings.len(),
+                           obtained: source.len(),
+                       });
+        }
+
+        let (vertices, instances) = self.vertex_instance_counts(source.iter().map(|b| b.size()));
+
+        let buffers = source
+            .into_iter()
+            .map(|b| Box::new(b) as Box<_>)
+            .collect();
+        Ok((buffers, vertices, instances))
+    }
+}
+
+unsafe impl VertexSource<VertexBuffers> for RuntimeVertexDefinition {
+    fn decode(&self, source: VertexBuffers)
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
+        let mut slots = (0 .. self.bindings.len()).map(|_| None).collect::<Vec<_>>();
+
+        for (binding, slice) in source.buffers {
+            let slot = match slots.get_mut(binding as usize) {
+                Some(slot) => slot,
+                None => {
+                    return Err(IncompatibleVertexSourceError::InvalidBinding { binding: binding })
+                },
+            };
+            if slot.is_some() {
+                return Err(IncompatibleVertexSourceError::DuplicateBinding { binding: binding });
+            }
+            *slot = Some(slice);
+        }
+
+        let mut slices = Vec::with_capacity(slots.len());
+        for (num, slot) in slots.into_iter().enumerate() {
+            match slot {
+                Some(slice) => slices.push(slice),
+                None => {
+                    return Err(IncompatibleVertexSourceError::MissingBinding {
+                                   binding: num as u32,
+                               })
+                },
+            }
+        }
+
+        let (vertices, instances) = self.vertex_instance_counts(slices.iter().map(|s| s.size()));
+
+        let buffers = slices
+            .into_iter()
+            .map(|s| Box::new(s) as Box<_>)
+            .collect();
+        Ok((buffers, vertices, instances))
+    }
+}
+
+/// Vertex buffers to draw with, each attached to an explicit binding of a
+/// `RuntimeVertexDefinition` and optionally restricted to a range of bytes.
+///
+/// Contrary to a `Vec<Arc<BufferAccess>>`, the buffers don't need to be passed in the order of
+/// the bindings, and several bindings can source their data from different regions of the same
+/// buffer. Each binding of the definition must receive exactly one buffer, otherwise the draw
+/// command returns an error.
+///
+/// The command buffer builder only binds again the vertex buffers that differ from the ones of the
+/// previous draw command, so changing the buffer of one binding doesn't rebind the others.
+///
+/// # Example
+///
+/// ```
+/// use std::sync::Arc;
+/// use vulkano::buffer::BufferAccess;
+/// use vulkano::pipeline::vertex::VertexBuffers;
+///
+/// # let positions: Arc<BufferAccess + Send + Sync> = return;
+/// # let per_instance: Arc<BufferAccess + Send + Sync> = return;
+/// // The positions start 256 bytes into their buffer.
+/// let buffers = VertexBuffers::new()
+///     .bind(1, per_instance)
+///     .bind_range(0, positions.clone(), 256 .. positions.size());
+/// ```
+#[derive(Clone, Default)]
+pub struct VertexBuffers {
+    buffers: Vec<(u32, BufferSlice<[u8], Arc<BufferAccess + Send + Sync>>)>,
+}
+
+impl VertexBuffers {
+    /// Builds an empty list of vertex buffers.
+    #[inline]
+    pub fn new() -> VertexBuffers {
+        VertexBuffers { buffers: Vec::new() }
+    }
+
+    /// Attaches the whole of `buffer` to `binding`.
+    #[inline]
+    pub fn bind(mut self, binding: u32, buffer: Arc<BufferAccess + Send + Sync>) -> VertexBuffers {
+        self.buffers
+            .push((binding, BufferSlice::from_buffer_access(buffer)));
+        self
+    }
+
+    /// Attaches a range of bytes of `buffer` to `binding`. The vertex data of the binding starts
+    /// at `range.start`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the range is out of the bounds of the buffer.
+    ///
+    #[inline]
+    pub fn bind_range(mut self, binding: u32, buffer: Arc<BufferAccess + Send + Sync>,
+                      range: Range<usize>)
+                      -> VertexBuffers {
+        assert!(range.start <= range.end);
+        let slice = BufferSlice::from_buffer_access(buffer)
+            .slice(range)
+            .expect("range out of the bounds of the vertex buffer");
+        self.buffers.push((binding, slice));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use buffer::BufferAccess;
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use format::Format;
+    use pipeline::shader::ShaderInterfaceDef;
+    use pipeline::shader::ShaderInterfaceDefEntry;
+    use pipeline::vertex::IncompatibleVertexDefinitionError;
+    use pipeline::vertex::IncompatibleVertexSourceError;
+    use pipeline::vertex::InputRate;
+    use pipeline::vertex::RuntimeVertexAttribute;
+    use pipeline::vertex::RuntimeVertexBinding;
+    use pipeline::vertex::RuntimeVertexDefinition;
+    use pipeline::vertex::VertexBuffers;
+    use pipeline::vertex::VertexDefinition;
+    use pipeline::vertex::VertexSource;
+    use std::borrow::Cow;
+    use std::sync::Arc;
+    use std::vec::IntoIter as VecIntoIter;
+
+    struct Interface;
+    unsafe impl ShaderInterfaceDef for Interface {
+        type Iter = VecIntoIter<ShaderInterfaceDefEntry>;
+
+        fn elements(&self) -> Self::Iter {
+            vec![
+                ShaderInterfaceDefEntry {
+                    location: 0 .. 1,
+                    format: Format::R32G32B32Sfloat,
+                    name: Some(Cow::Borrowed("position")),
+                },
+                ShaderInterfaceDefEntry {
+                    location: 1 .. 2,
+                    format: Format::R32Uint,
+                    name: Some(Cow::Borrowed("id")),
+                },
+            ].into_iter()
+        }
+    }
+
+    fn binding() -> Vec<RuntimeVertexBinding> {
+        vec![
+            RuntimeVertexBinding {
+                stride: 16,
+                input_rate: InputRate::Vertex,
+            },
+        ]
+    }
+
+    #[test]
+    fn matching() {
+        let def = RuntimeVertexDefinition::new(binding(), vec![
+            RuntimeVertexAttribute { location: 0, binding: 0, format: Format::R32G32B32Sfloat, offset: 0 },
+            RuntimeVertexAttribute { location: 1, binding: 0, format: Format::R32Uint, offset: 12 },
+        ]);
+
+        let (buffers, attribs) = def.definition(&Interface).unwrap();
+        assert_eq!(buffers.len(), 1);
+        assert_eq!(attribs.len(), 2);
+    }
+
+    #[test]
+    fn missing_attribute() {
+        let def = RuntimeVertexDefinition::new(binding(), vec![
+            RuntimeVertexAttribute { location: 0, binding: 0, format: Format::R32G32B32Sfloat, offset: 0 },
+        ]);
+
+        match def.definition(&Interface) {
+            Err(IncompatibleVertexDefinitionError::MissingAttribute { ref attribute })
+                if attribute == "id" => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn incompatible_format() {
+        let def = RuntimeVertexDefinition::new(binding(), vec![
+            RuntimeVertexAttribute { location: 0, binding: 0, format: Format::R8G8B8A8Unorm, offset: 0 },
+            RuntimeVertexAttribute { location: 1, binding: 0, format: Format::R32Sfloat, offset: 12 },
+        ]);
+
+        match def.definition(&Interface) {
+            Err(IncompatibleVertexDefinitionError::IncompatibleFormat { location: 1, .. }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn invalid_binding() {
+        let def = RuntimeVertexDefinition::new(binding(), vec![
+            RuntimeVertexAttribute { location: 0, binding: 1, format: Format::R32G32B32Sfloat, offset: 0 },
+            RuntimeVertexAttribute { location: 1, binding: 0, format: Format::R32Uint, offset: 12 },
+        ]);
+
+        match def.definition(&Interface) {
+            Err(IncompatibleVertexDefinitionError::InvalidBinding { location: 0, binding: 1 }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn decode_explicit_bindings() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let def = RuntimeVertexDefinition::new(vec![
+            RuntimeVertexBinding { stride: 16, input_rate: InputRate::Vertex },
+            RuntimeVertexBinding { stride: 4, input_rate: InputRate::Instance { divisor: 1 } },
+        ], Vec::new());
+
+        let buffer = CpuAccessibleBuffer::from_iter(device, BufferUsage::all(), 0 .. 64u32)
+            .unwrap();
+        let buffers = VertexBuffers::new()
+            .bind_range(1, buffer.clone(), 0 .. 12)
+            .bind_range(0, buffer.clone(), 64 .. 256);
+
+        let (buffers, vertices, instances) = def.decode(buffers).unwrap();
+        assert_eq!(buffers.len(), 2);
+        assert_eq!(buffers[0].inner().offset, 64);
+        assert_eq!(buffers[1].size(), 12);
+        assert_eq!(vertices, 12);
+        assert_eq!(instances, 3);
+    }
+
+    #[test]
+    fn decode_duplicate_binding() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_iter(device, BufferUsage::all(), 0 .. 64u32)
+            .unwrap();
+        let def = RuntimeVertexDefinition::new(binding(), Vec::new());
+
+        match def.decode(VertexBuffers::new().bind(0, buffer.clone()).bind(0, buffer)) {
+            Err(IncompatibleVertexSourceError::DuplicateBinding { binding: 0 }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn decode_wrong_buffers_count() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_iter(device, BufferUsage::all(), 0 .. 64u32)
+            .unwrap();
+        let def = RuntimeVertexDefinition::new(binding(), Vec::new());
+
+        let buffers: Vec<Arc<BufferAccess + Send + Sync>> = vec![buffer.clone(), buffer];
+        match def.decode(buffers) {
+            Err(IncompatibleVertexSourceError::WrongBuffersCount {
+                    expected: 1,
+                    obtained: 2,
+                }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn decode_missing_binding() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_iter(device, BufferUsage::all(), 0 .. 64u32)
+            .unwrap();
+        let def = RuntimeVertexDefinition::new(vec![
+            RuntimeVertexBinding { stride: 16, input_rate: InputRate::Vertex },
+            RuntimeVertexBinding { stride: 4, input_rate: InputRate::Vertex },
+        ], Vec::new());
+
+        match def.decode(VertexBuffers::new().bind(0, buffer.clone())) {
+            Err(IncompatibleVertexSourceError::MissingBinding { binding: 1 }) => (),
+            _ => panic!(),
+        }
+        match def.decode(VertexBuffers::new().bind(2, buffer)) {
+            Err(IncompatibleVertexSourceError::InvalidBinding { binding: 2 }) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn serde_round_trip() {
+        let def = RuntimeVertexDefinition::new(vec![
+            RuntimeVertexBinding { stride: 16, input_rate: InputRate::Vertex },
+            RuntimeVertexBinding { stride: 4, input_rate: InputRate::Instance { divisor: 2 } },
+        ], vec![
+            RuntimeVertexAttribute { location: 0, binding: 0, format: Format::R32G32B32Sfloat, offset: 0 },
+            RuntimeVertexAttribute { location: 1, binding: 1, format: Format::R32Uint, offset: 0 },
+        ]);
+
+        let json = ::serde_json::to_string(&def).unwrap();
+        let decoded: RuntimeVertexDefinition = ::serde_json::from_str(&json).unwrap();
+        assert_eq!(format!("{:?}", decoded), format!("{:?}", def));
+    }
+}
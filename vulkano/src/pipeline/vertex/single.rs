@@ -18,6 +18,7 @@ use buffer::TypedBufferAccess;
 use pipeline::shader::ShaderInterfaceDef;
 use pipeline::vertex::AttributeInfo;
 use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::IncompatibleVertexSourceError;
 use pipeline::vertex::InputRate;
 use pipeline::vertex::Vertex;
 use pipeline::vertex::VertexDefinition;
@@ -26,6 +27,16 @@ use pipeline::vertex::VertexSource;
 /// Implementation of `VertexDefinition` for a single vertex buffer.
 pub struct SingleBufferDefinition<T>(pub PhantomData<T>);
 
+// Implemented manually, as deriving would require the vertex types to be `Clone`.
+impl<T> Clone for SingleBufferDefinition<T> {
+    #[inline]
+    fn clone(&self) -> Self {
+        SingleBufferDefinition(PhantomData)
+    }
+}
+
+impl<T> Copy for SingleBufferDefinition<T> {}
+
 impl<T> SingleBufferDefinition<T> {
     #[inline]
     pub fn new() -> SingleBufferDefinition<T> {
@@ -90,11 +101,17 @@ unsafe impl<V> VertexSource<Vec<Arc<BufferAccess + Send + Sync>>> for SingleBuff
 {
     #[inline]
     fn decode(&self, mut source: Vec<Arc<BufferAccess + Send + Sync>>)
-              -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
-        // FIXME: safety
-        assert_eq!(source.len(), 1);
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
+        if source.len() != 1 {
+            return Err(IncompatibleVertexSourceError::WrongBuffersCount {
+                           expected: 1,
+                           obtained: source.len(),
+                       });
+        }
+
         let len = source[0].size() / mem::size_of::<V>();
-        (vec![Box::new(source.remove(0))], len, 1)
+        Ok((vec![Box::new(source.remove(0))], len, 1))
     }
 }
 
@@ -103,8 +120,10 @@ unsafe impl<'a, B, V> VertexSource<B> for SingleBufferDefinition<V>
           V: Vertex
 {
     #[inline]
-    fn decode(&self, source: B) -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
+    fn decode(&self, source: B)
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
         let len = source.len();
-        (vec![Box::new(source) as Box<_>], len, 1)
+        Ok((vec![Box::new(source) as Box<_>], len, 1))
     }
 }
@@ -17,6 +17,7 @@ use buffer::TypedBufferAccess;
 use pipeline::shader::ShaderInterfaceDef;
 use pipeline::vertex::AttributeInfo;
 use pipeline::vertex::IncompatibleVertexDefinitionError;
+use pipeline::vertex::IncompatibleVertexSourceError;
 use pipeline::vertex::InputRate;
 use pipeline::vertex::Vertex;
 use pipeline::vertex::VertexDefinition;
@@ -26,6 +27,15 @@ use pipeline::vertex::VertexSource;
 // TODO: shouldn't be just `Two` but `Multi`
 pub struct TwoBuffersDefinition<T, U>(pub PhantomData<(T, U)>);
 
+impl<T, U> Clone for TwoBuffersDefinition<T, U> {
+    #[inline]
+    fn clone(&self) -> Self {
+        TwoBuffersDefinition(PhantomData)
+    }
+}
+
+impl<T, U> Copy for TwoBuffersDefinition<T, U> {}
+
 impl<T, U> TwoBuffersDefinition<T, U> {
     #[inline]
     pub fn new() -> TwoBuffersDefinition<T, U> {
@@ -99,9 +109,15 @@ unsafe impl<T, U> VertexSource<Vec<Arc<BufferAccess + Send + Sync>>> for TwoBuff
 {
     #[inline]
     fn decode(&self, source: Vec<Arc<BufferAccess + Send + Sync>>)
-              -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
-        // FIXME: safety
-        assert_eq!(source.len(), 2);
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
+        if source.len() != 2 {
+            return Err(IncompatibleVertexSourceError::WrongBuffersCount {
+                           expected: 2,
+                           obtained: source.len(),
+                       });
+        }
+
         let vertices = [
             source[0].size() / mem::size_of::<T>(),
             source[1].size() / mem::size_of::<U>(),
@@ -109,7 +125,7 @@ unsafe impl<T, U> VertexSource<Vec<Arc<BufferAccess + Send + Sync>>> for TwoBuff
             .cloned()
             .min()
             .unwrap();
-        (vec![Box::new(source[0].clone()), Box::new(source[1].clone())], vertices, 1)
+        Ok((vec![Box::new(source[0].clone()), Box::new(source[1].clone())], vertices, 1))
     }
 }
 
@@ -120,12 +136,14 @@ unsafe impl<'a, T, U, Bt, Bu> VertexSource<(Bt, Bu)> for TwoBuffersDefinition<T,
           Bu: TypedBufferAccess<Content = [U]> + Send + Sync + 'static
 {
     #[inline]
-    fn decode(&self, source: (Bt, Bu)) -> (Vec<Box<BufferAccess + Send + Sync>>, usize, usize) {
+    fn decode(&self, source: (Bt, Bu))
+              -> Result<(Vec<Box<BufferAccess + Send + Sync>>, usize, usize),
+                        IncompatibleVertexSourceError> {
         let vertices = [source.0.len(), source.1.len()]
             .iter()
             .cloned()
             .min()
             .unwrap();
-        (vec![Box::new(source.0) as Box<_>, Box::new(source.1) as Box<_>], vertices, 1)
+        Ok((vec![Box::new(source.0) as Box<_>, Box::new(source.1) as Box<_>], vertices, 1))
     }
 }
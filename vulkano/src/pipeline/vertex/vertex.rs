@@ -8,6 +8,7 @@
 // according to those terms.
 
 use format::Format;
+use format::FormatTy;
 
 /// Describes an individual `Vertex`. In other words a collection of attributes that can be read
 /// from a vertex shader.
@@ -46,6 +47,8 @@ pub enum VertexMemberTy {
     U16,
     I32,
     U32,
+    /// Half-precision float, from the `half` crate.
+    F16,
     F32,
     F64,
 }
@@ -62,10 +65,22 @@ impl VertexMemberTy {
             VertexMemberTy::U16 => 2,
             VertexMemberTy::I32 => 4,
             VertexMemberTy::U32 => 4,
+            VertexMemberTy::F16 => 2,
             VertexMemberTy::F32 => 4,
             VertexMemberTy::F64 => 8,
         };
 
+        // Floating-point members can't be read through an integer format.
+        match (*self, format.ty()) {
+            (VertexMemberTy::F16, FormatTy::Uint) |
+            (VertexMemberTy::F16, FormatTy::Sint) |
+            (VertexMemberTy::F32, FormatTy::Uint) |
+            (VertexMemberTy::F32, FormatTy::Sint) |
+            (VertexMemberTy::F64, FormatTy::Uint) |
+            (VertexMemberTy::F64, FormatTy::Sint) => return false,
+            _ => (),
+        }
+
         let format_size = match format.size() {
             None => return false,
             Some(s) => s,
@@ -74,3 +89,17 @@ impl VertexMemberTy {
         array_size * my_size == format_size * num_locs as usize
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use format::Format;
+    use pipeline::vertex::VertexMemberTy;
+
+    #[test]
+    fn matches_f16() {
+        assert!(VertexMemberTy::F16.matches(4, Format::R16G16B16A16Sfloat, 1));
+        assert!(!VertexMemberTy::F16.matches(2, Format::R16G16Uint, 1));
+        assert!(!VertexMemberTy::F16.matches(4, Format::R16G16B16A16Sfloat, 2));
+        assert!(VertexMemberTy::U16.matches(2, Format::R16G16Uint, 1));
+    }
+}
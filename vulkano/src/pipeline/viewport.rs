@@ -55,6 +55,7 @@ use vk;
 ///
 /// Note that the number of viewports and scissors must be the same.
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ViewportsState {
     /// The state is known in advance.
     Fixed {
@@ -124,6 +125,7 @@ impl ViewportsState {
 //        x + width must be less than or equal to viewportBoundsRange[0]
 //        y + height must be less than or equal to viewportBoundsRange[1]
 #[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Viewport {
     /// Coordinates in pixels of the top-left hand corner of the viewport.
     pub origin: [f32; 2],
@@ -160,6 +162,7 @@ impl Viewport {
 //      Evaluation of (offset.x + extent.width) must not cause a signed integer addition overflow
 //      Evaluation of (offset.y + extent.height) must not cause a signed integer addition overflow
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Scissor {
     /// Coordinates in pixels of the top-left hand corner of the box.
     pub origin: [i32; 2],
@@ -13,24 +13,39 @@
 //! represent a collection of queries. Whenever you use a query, you have to specify both the query
 //! pool and the slot id within that query pool.
 
+pub use self::profiler::GpuFrameTimings;
+pub use self::profiler::GpuProfiler;
+pub use self::profiler::GpuProfilerCreationError;
+pub use self::profiler::GpuProfilerError;
+pub use self::profiler::GpuProfilerFrame;
+pub use self::profiler::GpuProfilerFrameId;
+pub use self::profiler::GpuProfilerScope;
+pub use self::profiler::GpuScopeTiming;
+
 use std::error;
 use std::fmt;
 use std::mem;
 use std::ptr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use device::Device;
 use device::DeviceOwned;
+use instance::QueueFamily;
 
 use Error;
 use OomError;
+use Success;
 use VulkanObject;
 use check_errors;
 use vk;
 
+mod profiler;
+
 pub struct UnsafeQueryPool {
     pool: vk::QueryPool,
     device: Arc<Device>,
+    ty: QueryType,
     num_slots: u32,
 }
 
@@ -48,6 +63,13 @@ impl UnsafeQueryPool {
 
                 (vk::QUERY_TYPE_PIPELINE_STATISTICS, flags.into())
             },
+            QueryType::AccelerationStructureCompactedSize => {
+                if !device.enabled_features().acceleration_structure {
+                    return Err(QueryPoolCreationError::AccelerationStructureFeatureNotEnabled);
+                }
+
+                (vk::QUERY_TYPE_ACCELERATION_STRUCTURE_COMPACTED_SIZE_KHR, 0)
+            },
         };
 
         let pool = unsafe {
@@ -64,7 +86,7 @@ impl UnsafeQueryPool {
             let vk = device.pointers();
             check_errors(vk.CreateQueryPool(device.internal_object(),
                                             &infos,
-                                            ptr::null(),
+                                            device.allocator_ptr(),
                                             &mut output))?;
             output
         };
@@ -72,10 +94,17 @@ impl UnsafeQueryPool {
         Ok(UnsafeQueryPool {
                pool: pool,
                device: device,
+               ty: ty,
                num_slots: num_slots,
            })
     }
 
+    /// Returns the type of queries of that query pool.
+    #[inline]
+    pub fn ty(&self) -> QueryType {
+        self.ty
+    }
+
     /// Returns the number of slots of that query pool.
     #[inline]
     pub fn num_slots(&self) -> u32 {
@@ -99,7 +128,7 @@ impl UnsafeQueryPool {
     pub fn queries_range(&self, first_index: u32, count: u32) -> Option<UnsafeQueriesRange> {
         assert!(count >= 1);
 
-        if first_index + count < self.num_slots() {
+        if first_index + count <= self.num_slots() {
             Some(UnsafeQueriesRange {
                      pool: self,
                      first: first_index,
@@ -167,6 +196,145 @@ impl<'a> UnsafeQueriesRange<'a> {
     pub fn count(&self) -> u32 {
         self.count
     }
+
+    /// Copies the results of the queries of the range into `destination`, as 64-bits values.
+    ///
+    /// If `wait` is true, blocks until the results of all the queries are available. Otherwise,
+    /// returns `Ok(false)` if some results aren't available yet, in which case the content of
+    /// `destination` is unspecified.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the length of `destination` is not equal to `count()`.
+    ///
+    pub fn get_results_u64(&self, destination: &mut [u64], wait: bool)
+                           -> Result<bool, OomError> {
+        assert_eq!(destination.len(), self.count as usize);
+
+        let flags = if wait {
+            vk::QUERY_RESULT_64_BIT | vk::QUERY_RESULT_WAIT_BIT
+        } else {
+            vk::QUERY_RESULT_64_BIT
+        };
+
+        unsafe {
+            let vk = self.pool.device.pointers();
+            let result = check_errors(vk.GetQueryPoolResults(self.pool.device.internal_object(),
+                                                             self.pool.pool,
+                                                             self.first,
+                                                             self.count,
+                                                             mem::size_of_val(destination),
+                                                             destination.as_mut_ptr() as *mut _,
+                                                             mem::size_of::<u64>() as u64,
+                                                             flags))?;
+
+            match result {
+                Success::Success => Ok(true),
+                Success::NotReady => Ok(false),
+                s => panic!("unexpected success value: {:?}", s),
+            }
+        }
+    }
+
+    /// Copies the results of the queries of the range into `destination`, as 64-bits values,
+    /// according to `flags`.
+    ///
+    /// Each query writes `num_results()` values of the query type of the pool, followed by an
+    /// availability value if `flags.with_availability` is true. The availability value is
+    /// non-zero if the results of the query are available.
+    ///
+    /// Returns `Ok(false)` if some results aren't available yet. This can't happen if
+    /// `flags.wait` is true.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the length of `destination` doesn't match the number of values written for
+    ///   `count()` queries.
+    ///
+    pub fn get_results(&self, destination: &mut [u64], flags: QueryResultFlags)
+                       -> Result<bool, OomError> {
+        let stride = self.pool.ty.num_results() + if flags.with_availability { 1 } else { 0 };
+        assert_eq!(destination.len(), (self.count * stride) as usize);
+
+        let stride_bytes = stride as u64 * mem::size_of::<u64>() as u64;
+        let flags = flags.into_vulkan_bits() | vk::QUERY_RESULT_64_BIT;
+
+        unsafe {
+            let vk = self.pool.device.pointers();
+            let result = check_errors(vk.GetQueryPoolResults(self.pool.device.internal_object(),
+                                                             self.pool.pool,
+                                                             self.first,
+                                                             self.count,
+                                                             mem::size_of_val(destination),
+                                                             destination.as_mut_ptr() as *mut _,
+                                                             stride_bytes,
+                                                             flags))?;
+
+            match result {
+                Success::Success => Ok(true),
+                Success::NotReady => Ok(false),
+                s => panic!("unexpected success value: {:?}", s),
+            }
+        }
+    }
+}
+
+/// Flags that control how the results of queries are retrieved.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QueryResultFlags {
+    /// Wait until the results of all the queries are available.
+    pub wait: bool,
+    /// Write an availability value after the results of each query.
+    pub with_availability: bool,
+    /// Write the intermediate results of the queries that aren't available yet.
+    pub partial: bool,
+}
+
+impl QueryResultFlags {
+    /// Builds a `QueryResultFlags` with none of the flags set.
+    #[inline]
+    pub fn none() -> QueryResultFlags {
+        QueryResultFlags {
+            wait: false,
+            with_availability: false,
+            partial: false,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan_bits(self) -> vk::QueryResultFlags {
+        let mut result = 0;
+        if self.wait {
+            result |= vk::QUERY_RESULT_WAIT_BIT;
+        }
+        if self.with_availability {
+            result |= vk::QUERY_RESULT_WITH_AVAILABILITY_BIT;
+        }
+        if self.partial {
+            result |= vk::QUERY_RESULT_PARTIAL_BIT;
+        }
+        result
+    }
+}
+
+/// Trait for types that the results of queries can be written as by the GPU.
+pub unsafe trait QueryResultElement {
+    /// Returns true if the results are written as 64-bits values.
+    fn is_64_bits() -> bool;
+}
+
+unsafe impl QueryResultElement for u32 {
+    #[inline(always)]
+    fn is_64_bits() -> bool {
+        false
+    }
+}
+
+unsafe impl QueryResultElement for u64 {
+    #[inline(always)]
+    fn is_64_bits() -> bool {
+        true
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -174,6 +342,26 @@ pub enum QueryType {
     Occlusion,
     PipelineStatistics(QueryPipelineStatisticFlags),
     Timestamp,
+    /// Size of an acceleration structure after compaction. Requires the
+    /// `acceleration_structure` feature of the `khr_acceleration_structure` extension.
+    AccelerationStructureCompactedSize,
+}
+
+impl QueryType {
+    /// Returns the number of values that are written for each query of this type when the
+    /// results are retrieved.
+    #[inline]
+    pub fn num_results(&self) -> u32 {
+        match *self {
+            QueryType::PipelineStatistics(flags) => {
+                let flags: vk::QueryPipelineStatisticFlags = flags.into();
+                flags.count_ones()
+            },
+            QueryType::Occlusion |
+            QueryType::Timestamp |
+            QueryType::AccelerationStructureCompactedSize => 1,
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -255,7 +443,9 @@ impl Drop for UnsafeQueryPool {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroyQueryPool(self.device.internal_object(), self.pool, ptr::null());
+            vk.DestroyQueryPool(self.device.internal_object(),
+                                self.pool,
+                                self.device.allocator_ptr());
         }
     }
 }
@@ -267,6 +457,8 @@ pub enum QueryPoolCreationError {
     OomError(OomError),
     /// A pipeline statistics pool was requested but the corresponding feature wasn't enabled.
     PipelineStatisticsQueryFeatureNotEnabled,
+    /// A compacted size pool was requested but the acceleration structure feature wasn't enabled.
+    AccelerationStructureFeatureNotEnabled,
 }
 
 impl error::Error for QueryPoolCreationError {
@@ -278,6 +470,10 @@ impl error::Error for QueryPoolCreationError {
                 "a pipeline statistics pool was requested but the corresponding feature \
                  wasn't enabled"
             },
+            QueryPoolCreationError::AccelerationStructureFeatureNotEnabled => {
+                "a compacted size pool was requested but the acceleration structure feature \
+                 wasn't enabled"
+            },
         }
     }
 
@@ -315,8 +511,41 @@ impl From<Error> for QueryPoolCreationError {
     }
 }
 
+/// Pool of occlusion queries.
+///
+/// An occlusion query counts the samples that pass the depth and stencil tests of the draw
+/// commands recorded between `begin_query` and `end_query`. Reset the queries with
+/// `reset_query_pool`, pass `inner()` to these commands, then read the number of samples with
+/// `results` once the command buffer has been executed.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// use vulkano::query::OcclusionQueriesPool;
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+///
+/// let pool = OcclusionQueriesPool::new(device.clone(), 1);
+///
+/// let builder = AutoCommandBufferBuilder::primary(device.clone(), queue.family()).unwrap()
+///     .reset_query_pool(pool.inner().clone(), 0, 1).unwrap()
+///     .begin_query(pool.inner().clone(), 0, false).unwrap()
+///     // ... draw commands ...
+///     .end_query(pool.inner().clone(), 0).unwrap();
+///
+/// // ... execute the command buffer ...
+///
+/// let mut results = [None];
+/// pool.results(0, &mut results, false).unwrap();
+/// match results[0] {
+///     Some(0) => println!("the object is hidden"),
+///     Some(_) => println!("the object is visible"),
+///     None => println!("the result isn't available yet"),
+/// }
+/// ```
 pub struct OcclusionQueriesPool {
-    inner: UnsafeQueryPool,
+    inner: Arc<UnsafeQueryPool>,
 }
 
 impl OcclusionQueriesPool {
@@ -324,9 +553,10 @@ impl OcclusionQueriesPool {
     pub fn raw(device: Arc<Device>, num_slots: u32) -> Result<OcclusionQueriesPool, OomError> {
         Ok(OcclusionQueriesPool {
                inner: match UnsafeQueryPool::new(device, QueryType::Occlusion, num_slots) {
-                   Ok(q) => q,
+                   Ok(q) => Arc::new(q),
                    Err(QueryPoolCreationError::OomError(err)) => return Err(err),
-                   Err(QueryPoolCreationError::PipelineStatisticsQueryFeatureNotEnabled) => {
+                   Err(QueryPoolCreationError::PipelineStatisticsQueryFeatureNotEnabled) |
+                   Err(QueryPoolCreationError::AccelerationStructureFeatureNotEnabled) => {
                        unreachable!()
                    },
                },
@@ -349,6 +579,47 @@ impl OcclusionQueriesPool {
     pub fn num_slots(&self) -> u32 {
         self.inner.num_slots()
     }
+
+    /// Returns the underlying query pool, to pass to the query commands of a command buffer.
+    #[inline]
+    pub fn inner(&self) -> &Arc<UnsafeQueryPool> {
+        &self.inner
+    }
+
+    /// Writes the number of samples that passed the tests for each query starting at
+    /// `first_query` into `destination`, or `None` if the result of the query isn't available
+    /// yet.
+    ///
+    /// If `wait` is true, blocks until the results of all the queries are available. Returns
+    /// true if all the results are available.
+    ///
+    /// If the query was begun without `precise`, the number of samples is only guaranteed to be
+    /// non-zero if any sample passed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the queries are out of range of the pool.
+    ///
+    pub fn results(&self, first_query: u32, destination: &mut [Option<u64>], wait: bool)
+                   -> Result<bool, OomError> {
+        let range = self.inner
+            .queries_range(first_query, destination.len() as u32)
+            .expect("queries out of range of the pool");
+
+        let mut values = vec![0; destination.len() * 2];
+        let flags = QueryResultFlags {
+            wait,
+            with_availability: true,
+            ..QueryResultFlags::none()
+        };
+        let all_available = range.get_results(&mut values, flags)?;
+
+        for (dest, value) in destination.iter_mut().zip(values.chunks(2)) {
+            *dest = if value[1] != 0 { Some(value[0]) } else { None };
+        }
+
+        Ok(all_available)
+    }
 }
 
 unsafe impl DeviceOwned for OcclusionQueriesPool {
@@ -358,13 +629,244 @@ unsafe impl DeviceOwned for OcclusionQueriesPool {
     }
 }
 
+/// Pool of timestamp queries, with the information required to convert the timestamps written
+/// by a queue family into durations.
+///
+/// Write the timestamps with `write_timestamp` in command buffers executed on queues of the
+/// family passed at construction, then read them with `results` or measure the time between two
+/// of them with `elapsed`.
+///
+/// # Example
+///
+/// ```
+/// use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// use vulkano::query::TimestampQueryPool;
+/// use vulkano::sync::PipelineStages;
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+///
+/// let pool = TimestampQueryPool::new(device.clone(), queue.family(), 2).unwrap();
+/// let stage = PipelineStages { bottom_of_pipe: true, .. PipelineStages::none() };
+///
+/// let builder = AutoCommandBufferBuilder::primary(device.clone(), queue.family()).unwrap()
+///     .reset_query_pool(pool.inner().clone(), 0, 2).unwrap()
+///     .write_timestamp(pool.inner().clone(), 0, stage).unwrap()
+///     // ... commands to measure ...
+///     .write_timestamp(pool.inner().clone(), 1, stage).unwrap();
+///
+/// // ... execute the command buffer and wait for it ...
+///
+/// let duration = pool.elapsed(0, 1, true).unwrap().unwrap();
+/// println!("{:?}", duration);
+/// ```
+pub struct TimestampQueryPool {
+    inner: Arc<UnsafeQueryPool>,
+    // Number of nanoseconds between two increments of a timestamp.
+    period: f32,
+    // Number of meaningful bits of the timestamps.
+    valid_bits: u32,
+}
+
+impl TimestampQueryPool {
+    /// Builds a new pool of timestamps written by queues of `queue_family`.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `queue_family` doesn't belong to the physical device of `device`.
+    ///
+    pub fn new(device: Arc<Device>, queue_family: QueueFamily, num_slots: u32)
+               -> Result<TimestampQueryPool, TimestampQueryPoolCreationError> {
+        assert_eq!(queue_family.physical_device().internal_object(),
+                   device.physical_device().internal_object());
+
+        let valid_bits = queue_family.timestamp_valid_bits();
+        if valid_bits == 0 {
+            return Err(TimestampQueryPoolCreationError::TimestampsNotSupported);
+        }
+
+        let period = device.physical_device().limits().timestamp_period();
+
+        let inner = match UnsafeQueryPool::new(device, QueryType::Timestamp, num_slots) {
+            Ok(q) => q,
+            Err(QueryPoolCreationError::OomError(err)) => return Err(err.into()),
+            Err(QueryPoolCreationError::PipelineStatisticsQueryFeatureNotEnabled) |
+            Err(QueryPoolCreationError::AccelerationStructureFeatureNotEnabled) => {
+                unreachable!()
+            },
+        };
+
+        Ok(TimestampQueryPool {
+               inner: Arc::new(inner),
+               period,
+               valid_bits,
+           })
+    }
+
+    /// Returns the number of slots of that query pool.
+    #[inline]
+    pub fn num_slots(&self) -> u32 {
+        self.inner.num_slots()
+    }
+
+    /// Returns the underlying query pool, to pass to the query commands of a command buffer.
+    #[inline]
+    pub fn inner(&self) -> &Arc<UnsafeQueryPool> {
+        &self.inner
+    }
+
+    /// Returns the number of nanoseconds between two increments of a timestamp.
+    #[inline]
+    pub fn timestamp_period(&self) -> f32 {
+        self.period
+    }
+
+    /// Returns the number of meaningful bits of the timestamps. The other bits of the results
+    /// are cleared.
+    #[inline]
+    pub fn timestamp_valid_bits(&self) -> u32 {
+        self.valid_bits
+    }
+
+    #[inline]
+    fn mask(&self) -> u64 {
+        if self.valid_bits >= 64 {
+            !0
+        } else {
+            (1 << self.valid_bits) - 1
+        }
+    }
+
+    /// Converts a number of timestamp increments into a duration.
+    #[inline]
+    pub fn to_duration(&self, ticks: u64) -> Duration {
+        let ns = (ticks as f64 * self.period as f64) as u64;
+        Duration::new(ns / 1_000_000_000, (ns % 1_000_000_000) as u32)
+    }
+
+    /// Returns the time elapsed between two timestamps written by a queue of the pool's family,
+    /// taking into account that the timestamps wrap around after `timestamp_valid_bits` bits.
+    #[inline]
+    pub fn duration_between(&self, begin: u64, end: u64) -> Duration {
+        self.to_duration(end.wrapping_sub(begin) & self.mask())
+    }
+
+    /// Writes the timestamps of the queries starting at `first_query` into `destination`, or
+    /// `None` if the timestamp of the query isn't available yet.
+    ///
+    /// If `wait` is true, blocks until all the timestamps are available. Returns true if all the
+    /// timestamps are available.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the queries are out of range of the pool.
+    ///
+    pub fn results(&self, first_query: u32, destination: &mut [Option<u64>], wait: bool)
+                   -> Result<bool, OomError> {
+        let range = self.inner
+            .queries_range(first_query, destination.len() as u32)
+            .expect("queries out of range of the pool");
+
+        let mut values = vec![0; destination.len() * 2];
+        let flags = QueryResultFlags {
+            wait,
+            with_availability: true,
+            ..QueryResultFlags::none()
+        };
+        let all_available = range.get_results(&mut values, flags)?;
+
+        let mask = self.mask();
+        for (dest, value) in destination.iter_mut().zip(values.chunks(2)) {
+            *dest = if value[1] != 0 { Some(value[0] & mask) } else { None };
+        }
+
+        Ok(all_available)
+    }
+
+    /// Returns the time elapsed between the timestamps of the queries `begin` and `end`, or
+    /// `None` if one of them isn't available yet.
+    ///
+    /// If `wait` is true, blocks until both timestamps are available.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the queries are out of range of the pool.
+    ///
+    pub fn elapsed(&self, begin: u32, end: u32, wait: bool)
+                   -> Result<Option<Duration>, OomError> {
+        let mut begin_value = [None];
+        let mut end_value = [None];
+        self.results(begin, &mut begin_value, wait)?;
+        self.results(end, &mut end_value, wait)?;
+
+        match (begin_value[0], end_value[0]) {
+            (Some(begin), Some(end)) => Ok(Some(self.duration_between(begin, end))),
+            _ => Ok(None),
+        }
+    }
+}
+
+unsafe impl DeviceOwned for TimestampQueryPool {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.inner.device()
+    }
+}
+
+/// Error that can happen when creating a `TimestampQueryPool`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimestampQueryPoolCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The queue family doesn't support timestamps.
+    TimestampsNotSupported,
+}
+
+impl error::Error for TimestampQueryPoolCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            TimestampQueryPoolCreationError::OomError(_) => "not enough memory available",
+            TimestampQueryPoolCreationError::TimestampsNotSupported => {
+                "the queue family doesn't support timestamps"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            TimestampQueryPoolCreationError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TimestampQueryPoolCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for TimestampQueryPoolCreationError {
+    #[inline]
+    fn from(err: OomError) -> TimestampQueryPoolCreationError {
+        TimestampQueryPoolCreationError::OomError(err)
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
     use query::OcclusionQueriesPool;
     use query::QueryPipelineStatisticFlags;
     use query::QueryPoolCreationError;
     use query::QueryType;
+    use query::TimestampQueryPool;
     use query::UnsafeQueryPool;
+    use std::time::Duration;
+    use sync::GpuFuture;
 
     #[test]
     fn occlusion_create() {
@@ -372,6 +874,53 @@ mod tests {
         let _ = OcclusionQueriesPool::new(device, 256);
     }
 
+    #[test]
+    fn occlusion_results() {
+        let (device, queue) = gfx_dev_and_queue!();
+        let pool = OcclusionQueriesPool::new(device.clone(), 2);
+
+        let command_buffer = AutoCommandBufferBuilder::primary(device.clone(), queue.family())
+            .unwrap()
+            .reset_query_pool(pool.inner().clone(), 0, 2)
+            .unwrap()
+            .begin_query(pool.inner().clone(), 0, false)
+            .unwrap()
+            .end_query(pool.inner().clone(), 0)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        command_buffer
+            .execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
+
+        // The second query has been reset but never used, so its result is never available.
+        let mut results = [None; 2];
+        assert!(!pool.results(0, &mut results, false).unwrap());
+        assert_eq!(results[0], Some(0));
+        assert_eq!(results[1], None);
+    }
+
+    #[test]
+    fn timestamp_to_duration() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let pool = match TimestampQueryPool::new(device, queue.family(), 2) {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+
+        let expected = (1_500_000_000.0 * pool.timestamp_period() as f64) as u64;
+        let duration = pool.to_duration(1_500_000_000);
+        assert_eq!(duration.as_secs() * 1_000_000_000 + duration.subsec_nanos() as u64,
+                   expected);
+        assert_eq!(pool.to_duration(0), Duration::new(0, 0));
+    }
+
     #[test]
     fn pipeline_statistics_feature() {
         let (device, _) = gfx_dev_and_queue!();
@@ -382,4 +931,36 @@ mod tests {
             _ => panic!(),
         };
     }
+
+    #[test]
+    fn acceleration_structure_feature() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let ty = QueryType::AccelerationStructureCompactedSize;
+        match UnsafeQueryPool::new(device, ty, 256) {
+            Err(QueryPoolCreationError::AccelerationStructureFeatureNotEnabled) => (),
+            _ => panic!(),
+        };
+    }
+
+    #[test]
+    fn queries_range_bounds() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let pool = UnsafeQueryPool::new(device, QueryType::Occlusion, 4).unwrap();
+        assert!(pool.queries_range(0, 4).is_some());
+        assert!(pool.queries_range(1, 4).is_none());
+    }
+
+    #[test]
+    fn num_results() {
+        let flags = QueryPipelineStatisticFlags {
+            vertex_shader_invocations: true,
+            fragment_shader_invocations: true,
+            ..QueryPipelineStatisticFlags::none()
+        };
+
+        assert_eq!(QueryType::PipelineStatistics(flags).num_results(), 2);
+        assert_eq!(QueryType::Occlusion.num_results(), 1);
+    }
 }
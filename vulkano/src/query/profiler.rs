@@ -0,0 +1,597 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::borrow::Cow;
+use std::error;
+use std::fmt;
+use std::ops::Deref;
+use std::ops::DerefMut;
+use std::sync::Arc;
+
+use OomError;
+use VulkanObject;
+use command_buffer::AutoCommandBufferBuilder;
+use command_buffer::ResetQueryPoolError;
+use device::Device;
+use device::DeviceOwned;
+use instance::QueueFamily;
+use query::TimestampQueryPool;
+use query::TimestampQueryPoolCreationError;
+use sync::FenceSignalFuture;
+use sync::FlushError;
+use sync::GpuFuture;
+use sync::PipelineStages;
+
+/// Records timestamps around named scopes of command buffers and reports their GPU durations.
+///
+/// A `GpuProfiler` owns one timestamp query pool per frame in flight. When recording the command
+/// buffer of a frame, you open named scopes that write a timestamp when they begin and when they
+/// end. Once the GPU has finished executing the frame, the profiler reads back the timestamps and
+/// returns the duration of each scope in nanoseconds.
+///
+/// # Example
+///
+/// ```no_run
+/// use vulkano::command_buffer::AutoCommandBufferBuilder;
+/// use vulkano::query::GpuProfiler;
+/// use vulkano::sync::GpuFuture;
+///
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+/// let mut profiler = GpuProfiler::new(device.clone(), queue.family(), 2, 64).unwrap();
+///
+/// let builder = AutoCommandBufferBuilder::primary(device.clone(), queue.family()).unwrap();
+/// let mut frame = profiler.begin_frame(builder).unwrap();
+/// {
+///     let mut shadows = frame.scope("shadows").unwrap();
+///     // Record the commands with `shadows.record(|builder| ...)`.
+///     {
+///         let _cascade = shadows.scope("cascade 0").unwrap();
+///     }
+/// }
+/// let (builder, frame_id) = frame.end();
+///
+/// let future = vulkano::sync::now(device.clone())
+///     .then_execute(queue.clone(), builder.build().unwrap()).unwrap()
+///     .then_signal_fence_and_flush().unwrap();
+///
+/// let timings = profiler.resolve_after(frame_id, &future).unwrap();
+/// for scope in timings.scopes() {
+///     println!("{:indent$}{}: {} ns", "", scope.name, scope.duration_ns,
+///              indent = scope.depth as usize * 2);
+/// }
+/// ```
+pub struct GpuProfiler {
+    device: Arc<Device>,
+    frames: Vec<FrameSlot>,
+    max_scopes: u32,
+}
+
+// Storage for the timestamps of one frame in flight.
+struct FrameSlot {
+    pool: TimestampQueryPool,
+    scopes: Vec<ScopeInfo>,
+    // Incremented every time the slot is reused, so that outdated `GpuProfilerFrameId`s can be
+    // detected.
+    generation: u64,
+    // True if the frame has been recorded and its timestamps haven't been read yet.
+    pending: bool,
+}
+
+// Scope recorded in a frame. Scope `n` uses queries `2 * n` and `2 * n + 1`.
+struct ScopeInfo {
+    name: Cow<'static, str>,
+    depth: u32,
+    parent: Option<usize>,
+}
+
+impl GpuProfiler {
+    /// Builds a new profiler for command buffers executed on queues of `queue_family`.
+    ///
+    /// `frames_in_flight` is the number of frames that can be recorded before the timestamps of
+    /// the oldest one are resolved, and `max_scopes` the maximum number of scopes per frame.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `queue_family` doesn't belong to the physical device of `device`.
+    /// - Panics if `frames_in_flight` or `max_scopes` is 0.
+    ///
+    pub fn new(device: Arc<Device>, queue_family: QueueFamily, frames_in_flight: usize,
+               max_scopes: u32)
+               -> Result<GpuProfiler, GpuProfilerCreationError> {
+        assert_eq!(queue_family.physical_device().internal_object(),
+                   device.physical_device().internal_object());
+        assert!(frames_in_flight >= 1);
+        assert!(max_scopes >= 1);
+
+        let mut frames = Vec::with_capacity(frames_in_flight);
+        for _ in 0 .. frames_in_flight {
+            let pool = TimestampQueryPool::new(device.clone(), queue_family, max_scopes * 2)?;
+
+            frames.push(FrameSlot {
+                            pool,
+                            scopes: Vec::new(),
+                            generation: 0,
+                            pending: false,
+                        });
+        }
+
+        Ok(GpuProfiler {
+               device,
+               frames,
+               max_scopes,
+           })
+    }
+
+    /// Returns the maximum number of scopes that can be recorded in a frame.
+    #[inline]
+    pub fn max_scopes(&self) -> u32 {
+        self.max_scopes
+    }
+
+    /// Returns the number of frames whose timestamps haven't been resolved yet.
+    #[inline]
+    pub fn pending_frames(&self) -> usize {
+        self.frames.iter().filter(|f| f.pending).count()
+    }
+
+    /// Starts profiling a frame recorded in `builder`.
+    ///
+    /// This adds a command that resets the query pool of the frame, therefore it must be called
+    /// outside of a render pass.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `builder` doesn't belong to the same device as the profiler.
+    ///
+    pub fn begin_frame<P>(&mut self, builder: AutoCommandBufferBuilder<P>)
+                          -> Result<GpuProfilerFrame<P>, GpuProfilerError> {
+        assert_eq!(builder.device().internal_object(),
+                   self.device.internal_object());
+
+        let slot = match self.frames.iter().position(|f| !f.pending) {
+            Some(s) => s,
+            None => return Err(GpuProfilerError::NoFreeFrame),
+        };
+
+        let builder = {
+            let frame = &mut self.frames[slot];
+            frame.scopes.clear();
+            frame.generation += 1;
+            builder
+                .reset_query_pool(frame.pool.inner().clone(), 0, self.max_scopes * 2)?
+        };
+
+        Ok(GpuProfilerFrame {
+               profiler: self,
+               slot,
+               builder: Some(builder),
+               stack: Vec::new(),
+           })
+    }
+
+    /// Reads the timestamps of a frame without blocking.
+    ///
+    /// Returns `Ok(None)` if the GPU hasn't finished executing the frame yet, in which case you
+    /// should try again later.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the timestamps of the frame have already been resolved.
+    ///
+    pub fn resolve(&mut self, frame: GpuProfilerFrameId)
+                   -> Result<Option<GpuFrameTimings>, OomError> {
+        self.resolve_inner(frame, false)
+    }
+
+    /// Waits until `fence` is signaled, then reads the timestamps of a frame.
+    ///
+    /// `fence` must be the future that signals the end of the submission of the frame's command
+    /// buffer.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the timestamps of the frame have already been resolved.
+    ///
+    pub fn resolve_after<F>(&mut self, frame: GpuProfilerFrameId, fence: &FenceSignalFuture<F>)
+                            -> Result<GpuFrameTimings, FlushError>
+        where F: GpuFuture
+    {
+        fence.wait(None)?;
+        let timings = self.resolve_inner(frame, true)
+            .map_err(FlushError::OomError)?;
+        Ok(timings.expect("the timestamps of the frame are still unavailable after the fence \
+                           has been signaled"))
+    }
+
+    fn resolve_inner(&mut self, frame_id: GpuProfilerFrameId, wait: bool)
+                     -> Result<Option<GpuFrameTimings>, OomError> {
+        let frame = &mut self.frames[frame_id.slot];
+        assert!(frame.pending && frame.generation == frame_id.generation,
+                "the timestamps of this frame have already been resolved");
+
+        if frame.scopes.is_empty() {
+            frame.pending = false;
+            return Ok(Some(GpuFrameTimings { scopes: Vec::new() }));
+        }
+
+        let num_queries = frame.scopes.len() as u32 * 2;
+        let mut timestamps = vec![None; num_queries as usize];
+        let available = frame.pool.results(0, &mut timestamps, wait)?;
+        if !available {
+            return Ok(None);
+        }
+
+        frame.pending = false;
+
+        let pool = &frame.pool;
+        let scopes = frame
+            .scopes
+            .drain(..)
+            .enumerate()
+            .map(|(num, scope)| {
+                let begin = timestamps[num * 2].unwrap();
+                let end = timestamps[num * 2 + 1].unwrap();
+                let duration = pool.duration_between(begin, end);
+                GpuScopeTiming {
+                    name: scope.name,
+                    depth: scope.depth,
+                    parent: scope.parent,
+                    duration_ns: duration.as_secs() * 1_000_000_000 +
+                        duration.subsec_nanos() as u64,
+                }
+            })
+            .collect();
+
+        Ok(Some(GpuFrameTimings { scopes }))
+    }
+}
+
+unsafe impl DeviceOwned for GpuProfiler {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+/// A frame being recorded by a `GpuProfiler`.
+///
+/// Owns the command buffer builder until `end` is called. Commands are added with `record`, and
+/// scopes are opened with `scope`.
+pub struct GpuProfilerFrame<'p, P> {
+    profiler: &'p mut GpuProfiler,
+    slot: usize,
+    // `None` if a closure passed to `record` has returned an error.
+    builder: Option<AutoCommandBufferBuilder<P>>,
+    // Indices of the scopes currently open, from the outermost to the innermost.
+    stack: Vec<usize>,
+}
+
+impl<'p, P> GpuProfilerFrame<'p, P> {
+    /// Opens a scope named `name`. The scope is closed when the returned guard is dropped.
+    ///
+    /// Scopes opened on the guard are children of this scope.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if a closure passed to `record` has previously returned an error.
+    ///
+    pub fn scope<'f, N>(&'f mut self, name: N)
+                        -> Result<GpuProfilerScope<'f, 'p, P>, GpuProfilerError>
+        where N: Into<Cow<'static, str>>
+    {
+        let index = {
+            let frame = &mut self.profiler.frames[self.slot];
+            if frame.scopes.len() as u32 >= self.profiler.max_scopes {
+                return Err(GpuProfilerError::TooManyScopes);
+            }
+
+            frame.scopes.push(ScopeInfo {
+                                  name: name.into(),
+                                  depth: self.stack.len() as u32,
+                                  parent: self.stack.last().cloned(),
+                              });
+            frame.scopes.len() - 1
+        };
+
+        self.write_timestamp(index as u32 * 2,
+                             PipelineStages {
+                                 top_of_pipe: true,
+                                 ..PipelineStages::none()
+                             });
+        self.stack.push(index);
+
+        Ok(GpuProfilerScope { frame: self })
+    }
+
+    /// Adds commands to the command buffer of the frame.
+    ///
+    /// If the closure returns an error, the builder is lost and the frame can no longer be used.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if a closure passed to `record` has previously returned an error.
+    ///
+    pub fn record<F, E>(&mut self, f: F) -> Result<(), E>
+        where F: FnOnce(AutoCommandBufferBuilder<P>) -> Result<AutoCommandBufferBuilder<P>, E>
+    {
+        let builder = self.builder
+            .take()
+            .expect("the command buffer builder of the frame has been lost");
+        self.builder = Some(f(builder)?);
+        Ok(())
+    }
+
+    /// Ends the frame and returns the command buffer builder, and the identifier to pass to
+    /// `resolve` once the command buffer has been executed.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if a closure passed to `record` has previously returned an error.
+    ///
+    pub fn end(mut self) -> (AutoCommandBufferBuilder<P>, GpuProfilerFrameId) {
+        let builder = self.builder
+            .take()
+            .expect("the command buffer builder of the frame has been lost");
+
+        let frame = &mut self.profiler.frames[self.slot];
+        frame.pending = true;
+
+        (builder,
+         GpuProfilerFrameId {
+             slot: self.slot,
+             generation: frame.generation,
+         })
+    }
+
+    fn write_timestamp(&mut self, query: u32, stage: PipelineStages) {
+        let pool = self.profiler.frames[self.slot].pool.inner().clone();
+        let builder = self.builder
+            .take()
+            .expect("the command buffer builder of the frame has been lost");
+        // The pool is a timestamp pool of the same device, the query is in range and there is
+        // one stage, so this can't fail.
+        self.builder = Some(builder.write_timestamp(pool, query, stage).unwrap());
+    }
+}
+
+/// Guard of a scope of a `GpuProfilerFrame`. Writes the end timestamp of the scope when dropped.
+///
+/// Dereferences to the frame, so that commands can be recorded and child scopes opened.
+pub struct GpuProfilerScope<'f, 'p: 'f, P: 'f> {
+    frame: &'f mut GpuProfilerFrame<'p, P>,
+}
+
+impl<'f, 'p, P> Deref for GpuProfilerScope<'f, 'p, P> {
+    type Target = GpuProfilerFrame<'p, P>;
+
+    #[inline]
+    fn deref(&self) -> &GpuProfilerFrame<'p, P> {
+        self.frame
+    }
+}
+
+impl<'f, 'p, P> DerefMut for GpuProfilerScope<'f, 'p, P> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut GpuProfilerFrame<'p, P> {
+        self.frame
+    }
+}
+
+impl<'f, 'p, P> Drop for GpuProfilerScope<'f, 'p, P> {
+    fn drop(&mut self) {
+        let index = self.frame.stack.pop().unwrap();
+
+        // If the builder has been lost, the frame can't be submitted anyway.
+        if self.frame.builder.is_some() {
+            self.frame
+                .write_timestamp(index as u32 * 2 + 1,
+                                 PipelineStages {
+                                     bottom_of_pipe: true,
+                                     ..PipelineStages::none()
+                                 });
+        }
+    }
+}
+
+/// Identifies a frame recorded by a `GpuProfiler` whose timestamps haven't been resolved yet.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct GpuProfilerFrameId {
+    slot: usize,
+    generation: u64,
+}
+
+/// GPU durations of the scopes of a frame.
+#[derive(Debug, Clone)]
+pub struct GpuFrameTimings {
+    scopes: Vec<GpuScopeTiming>,
+}
+
+impl GpuFrameTimings {
+    /// Returns the scopes of the frame, in the order in which they were opened.
+    ///
+    /// A scope is always followed by its children.
+    #[inline]
+    pub fn scopes(&self) -> &[GpuScopeTiming] {
+        &self.scopes
+    }
+
+    /// Returns the sum of the durations of the top-level scopes, in nanoseconds.
+    #[inline]
+    pub fn total_ns(&self) -> u64 {
+        self.scopes
+            .iter()
+            .filter(|s| s.parent.is_none())
+            .map(|s| s.duration_ns)
+            .sum()
+    }
+}
+
+/// GPU duration of a scope.
+#[derive(Debug, Clone)]
+pub struct GpuScopeTiming {
+    /// Name passed when opening the scope.
+    pub name: Cow<'static, str>,
+    /// Number of scopes that enclose this one.
+    pub depth: u32,
+    /// Index of the enclosing scope in `GpuFrameTimings::scopes()`, if any.
+    pub parent: Option<usize>,
+    /// Time between the beginning and the end of the scope, in nanoseconds.
+    pub duration_ns: u64,
+}
+
+/// Error that can happen when creating a `GpuProfiler`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GpuProfilerCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The queue family doesn't support timestamps.
+    TimestampsNotSupported,
+}
+
+impl error::Error for GpuProfilerCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            GpuProfilerCreationError::OomError(_) => "not enough memory available",
+            GpuProfilerCreationError::TimestampsNotSupported => {
+                "the queue family doesn't support timestamps"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            GpuProfilerCreationError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GpuProfilerCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<OomError> for GpuProfilerCreationError {
+    #[inline]
+    fn from(err: OomError) -> GpuProfilerCreationError {
+        GpuProfilerCreationError::OomError(err)
+    }
+}
+
+impl From<TimestampQueryPoolCreationError> for GpuProfilerCreationError {
+    #[inline]
+    fn from(err: TimestampQueryPoolCreationError) -> GpuProfilerCreationError {
+        match err {
+            TimestampQueryPoolCreationError::OomError(err) => {
+                GpuProfilerCreationError::OomError(err)
+            },
+            TimestampQueryPoolCreationError::TimestampsNotSupported => {
+                GpuProfilerCreationError::TimestampsNotSupported
+            },
+        }
+    }
+}
+
+/// Error that can happen when profiling a frame.
+#[derive(Debug, Clone)]
+pub enum GpuProfilerError {
+    /// All the frames in flight are waiting for their timestamps to be resolved.
+    NoFreeFrame,
+    /// The maximum number of scopes of the frame has been reached.
+    TooManyScopes,
+    /// Error while resetting the query pool of the frame.
+    ResetQueryPoolError(ResetQueryPoolError),
+}
+
+impl error::Error for GpuProfilerError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            GpuProfilerError::NoFreeFrame => {
+                "all the frames in flight are waiting for their timestamps to be resolved"
+            },
+            GpuProfilerError::TooManyScopes => {
+                "the maximum number of scopes of the frame has been reached"
+            },
+            GpuProfilerError::ResetQueryPoolError(_) => {
+                "error while resetting the query pool of the frame"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            GpuProfilerError::ResetQueryPoolError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for GpuProfilerError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<ResetQueryPoolError> for GpuProfilerError {
+    #[inline]
+    fn from(err: ResetQueryPoolError) -> GpuProfilerError {
+        GpuProfilerError::ResetQueryPoolError(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::AutoCommandBufferBuilder;
+    use query::GpuProfiler;
+    use query::GpuProfilerError;
+    use sync::GpuFuture;
+    use sync::now;
+
+    #[test]
+    fn nested_scopes() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let mut profiler = match GpuProfiler::new(device.clone(), queue.family(), 1, 2) {
+            Ok(p) => p,
+            Err(_) => return,
+        };
+
+        let builder = AutoCommandBufferBuilder::primary(device.clone(), queue.family()).unwrap();
+        let mut frame = profiler.begin_frame(builder).unwrap();
+        {
+            let mut outer = frame.scope("outer").unwrap();
+            let _inner = outer.scope("inner").unwrap();
+        }
+        match frame.scope("third") {
+            Err(GpuProfilerError::TooManyScopes) => (),
+            _ => panic!(),
+        }
+        let (builder, frame_id) = frame.end();
+
+        let future = now(device.clone())
+            .then_execute(queue.clone(), builder.build().unwrap())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        let timings = profiler.resolve_after(frame_id, &future).unwrap();
+        assert_eq!(timings.scopes().len(), 2);
+        assert_eq!(timings.scopes()[1].parent, Some(0));
+        assert_eq!(timings.scopes()[1].depth, 1);
+        assert_eq!(profiler.pending_frames(), 0);
+    }
+}
@@ -256,6 +256,12 @@ impl Sampler {
             }
         }
 
+        if mip_lod_bias != 0.0 && device.loaded_extensions().khr_portability_subset &&
+            !device.enabled_features().sampler_mip_lod_bias
+        {
+            return Err(SamplerCreationError::SamplerMipLodBiasFeatureNotEnabled);
+        }
+
         // Check MirrorClampToEdge extension support
         if [address_u, address_v, address_w]
             .iter()
@@ -319,7 +325,7 @@ impl Sampler {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateSampler(device.internal_object(),
                                           &infos,
-                                          ptr::null(),
+                                          device.allocator_ptr(),
                                           &mut output))?;
             output
         };
@@ -406,7 +412,7 @@ impl Sampler {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateSampler(device.internal_object(),
                                           &infos,
-                                          ptr::null(),
+                                          device.allocator_ptr(),
                                           &mut output))?;
             output
         };
@@ -502,13 +508,16 @@ impl Drop for Sampler {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroySampler(self.device.internal_object(), self.sampler, ptr::null());
+            vk.DestroySampler(self.device.internal_object(),
+                              self.sampler,
+                              self.device.allocator_ptr());
         }
     }
 }
 
 /// Describes how the color of each pixel should be determined.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum Filter {
     /// The four pixels whose center surround the requested coordinates are taken, then their
@@ -522,6 +531,7 @@ pub enum Filter {
 
 /// Describes which mipmap from the source to use.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum MipmapMode {
     /// Use the mipmap whose dimensions are the nearest to the dimensions of the destination.
@@ -535,6 +545,7 @@ pub enum MipmapMode {
 /// How the sampler should behave when it needs to access a pixel that is out of range of the
 /// texture.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum SamplerAddressMode {
     /// Repeat the texture. In other words, the pixel at coordinate `x + 1.0` is the same as the
     /// one at coordinate `x`.
@@ -584,6 +595,7 @@ impl SamplerAddressMode {
 /// How the sampler should behave when it needs to access a pixel that is out of range of the
 /// texture.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum UnnormalizedSamplerAddressMode {
     /// The coordinates are clamped to the valid range. Coordinates below 0 have the same value
@@ -626,6 +638,7 @@ impl UnnormalizedSamplerAddressMode {
 /// Using a border color restricts the sampler to either floating-point images or integer images.
 /// See the documentation of the `sampler` module for more info.
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[repr(u32)]
 pub enum BorderColor {
     /// The value `(0.0, 0.0, 0.0, 0.0)`. Can only be used with floating-point images.
@@ -681,6 +694,10 @@ pub enum SamplerCreationError {
     /// Using `MirrorClampToEdge` requires enabling the `VK_KHR_sampler_mirror_clamp_to_edge`
     /// extension when creating the device.
     SamplerMirrorClampToEdgeExtensionNotEnabled,
+
+    /// The device only implements a subset of the Vulkan API, and using a mip lod bias other than
+    /// 0.0 requires enabling the `sampler_mip_lod_bias` feature when creating the device.
+    SamplerMipLodBiasFeatureNotEnabled,
 }
 
 impl error::Error for SamplerCreationError {
@@ -695,6 +712,8 @@ impl error::Error for SamplerCreationError {
             SamplerCreationError::MipLodBiasLimitExceeded { .. } => "mip lod bias limit exceeded",
             SamplerCreationError::SamplerMirrorClampToEdgeExtensionNotEnabled =>
                 "the device extension `VK_KHR_sampler_mirror_clamp_to_edge` is not enabled",
+            SamplerCreationError::SamplerMipLodBiasFeatureNotEnabled =>
+                "the `sampler_mip_lod_bias` feature is not enabled",
         }
     }
 
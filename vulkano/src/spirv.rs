@@ -0,0 +1,1274 @@
+// Copyright (c) 2018 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Minimal SPIR-V parser used to reflect shader modules at runtime.
+//!
+//! Contrary to the parser of the `vulkano-shaders` crate, this one doesn't decode every
+//! instruction. It only keeps the raw operands around and provides helpers that look up the
+//! handful of instructions that are needed to describe the interface of a shader to vulkano.
+
+use std::cmp;
+use std::error;
+use std::fmt;
+
+use descriptor::descriptor::DescriptorBufferDesc;
+use descriptor::descriptor::DescriptorDesc;
+use descriptor::descriptor::DescriptorDescTy;
+use descriptor::descriptor::DescriptorImageDesc;
+use descriptor::descriptor::DescriptorImageDescArray;
+use descriptor::descriptor::DescriptorImageDescDimensions;
+use descriptor::descriptor::ShaderStages;
+use format::Format;
+use pipeline::shader::ShaderInterfaceDefEntry;
+
+/// Magic number found at the start of each SPIR-V module.
+pub(crate) const MAGIC: u32 = 0x07230203;
+
+// Opcodes.
+pub(crate) const OP_NAME: u16 = 5;
+pub(crate) const OP_MEMBER_NAME: u16 = 6;
+pub(crate) const OP_ENTRY_POINT: u16 = 15;
+pub(crate) const OP_EXECUTION_MODE: u16 = 16;
+pub(crate) const OP_CAPABILITY: u16 = 17;
+pub(crate) const OP_TYPE_BOOL: u16 = 20;
+pub(crate) const OP_TYPE_INT: u16 = 21;
+pub(crate) const OP_TYPE_FLOAT: u16 = 22;
+pub(crate) const OP_TYPE_VECTOR: u16 = 23;
+pub(crate) const OP_TYPE_MATRIX: u16 = 24;
+pub(crate) const OP_TYPE_IMAGE: u16 = 25;
+pub(crate) const OP_TYPE_SAMPLER: u16 = 26;
+pub(crate) const OP_TYPE_SAMPLED_IMAGE: u16 = 27;
+pub(crate) const OP_TYPE_ARRAY: u16 = 28;
+pub(crate) const OP_TYPE_RUNTIME_ARRAY: u16 = 29;
+pub(crate) const OP_TYPE_STRUCT: u16 = 30;
+pub(crate) const OP_TYPE_POINTER: u16 = 32;
+pub(crate) const OP_CONSTANT: u16 = 43;
+pub(crate) const OP_SPEC_CONSTANT_TRUE: u16 = 48;
+pub(crate) const OP_SPEC_CONSTANT_FALSE: u16 = 49;
+pub(crate) const OP_SPEC_CONSTANT: u16 = 50;
+pub(crate) const OP_VARIABLE: u16 = 59;
+pub(crate) const OP_DECORATE: u16 = 71;
+pub(crate) const OP_MEMBER_DECORATE: u16 = 72;
+
+// Decorations.
+pub(crate) const DECORATION_SPEC_ID: u32 = 1;
+pub(crate) const DECORATION_BLOCK: u32 = 2;
+pub(crate) const DECORATION_BUFFER_BLOCK: u32 = 3;
+pub(crate) const DECORATION_ARRAY_STRIDE: u32 = 6;
+pub(crate) const DECORATION_MATRIX_STRIDE: u32 = 7;
+pub(crate) const DECORATION_BUILT_IN: u32 = 11;
+pub(crate) const DECORATION_NON_WRITABLE: u32 = 24;
+pub(crate) const DECORATION_LOCATION: u32 = 30;
+pub(crate) const DECORATION_BINDING: u32 = 33;
+pub(crate) const DECORATION_DESCRIPTOR_SET: u32 = 34;
+pub(crate) const DECORATION_OFFSET: u32 = 35;
+
+// Built-ins.
+pub(crate) const BUILT_IN_WORKGROUP_SIZE: u32 = 25;
+
+// Storage classes.
+pub(crate) const STORAGE_CLASS_UNIFORM_CONSTANT: u32 = 0;
+pub(crate) const STORAGE_CLASS_INPUT: u32 = 1;
+pub(crate) const STORAGE_CLASS_UNIFORM: u32 = 2;
+pub(crate) const STORAGE_CLASS_OUTPUT: u32 = 3;
+pub(crate) const STORAGE_CLASS_PUSH_CONSTANT: u32 = 9;
+pub(crate) const STORAGE_CLASS_STORAGE_BUFFER: u32 = 12;
+
+// Execution models.
+pub(crate) const EXECUTION_MODEL_VERTEX: u32 = 0;
+pub(crate) const EXECUTION_MODEL_TESSELLATION_CONTROL: u32 = 1;
+pub(crate) const EXECUTION_MODEL_TESSELLATION_EVALUATION: u32 = 2;
+pub(crate) const EXECUTION_MODEL_GEOMETRY: u32 = 3;
+pub(crate) const EXECUTION_MODEL_FRAGMENT: u32 = 4;
+pub(crate) const EXECUTION_MODEL_GL_COMPUTE: u32 = 5;
+pub(crate) const EXECUTION_MODEL_RAY_GENERATION_KHR: u32 = 5313;
+pub(crate) const EXECUTION_MODEL_INTERSECTION_KHR: u32 = 5314;
+pub(crate) const EXECUTION_MODEL_ANY_HIT_KHR: u32 = 5315;
+pub(crate) const EXECUTION_MODEL_CLOSEST_HIT_KHR: u32 = 5316;
+pub(crate) const EXECUTION_MODEL_MISS_KHR: u32 = 5317;
+pub(crate) const EXECUTION_MODEL_CALLABLE_KHR: u32 = 5318;
+pub(crate) const EXECUTION_MODEL_TASK_EXT: u32 = 5364;
+pub(crate) const EXECUTION_MODEL_MESH_EXT: u32 = 5365;
+
+// Execution modes.
+pub(crate) const EXECUTION_MODE_POINT_MODE: u32 = 10;
+pub(crate) const EXECUTION_MODE_LOCAL_SIZE: u32 = 17;
+pub(crate) const EXECUTION_MODE_INPUT_POINTS: u32 = 19;
+pub(crate) const EXECUTION_MODE_INPUT_LINES: u32 = 20;
+pub(crate) const EXECUTION_MODE_INPUT_LINES_ADJACENCY: u32 = 21;
+pub(crate) const EXECUTION_MODE_TRIANGLES: u32 = 22;
+pub(crate) const EXECUTION_MODE_INPUT_TRIANGLES_ADJACENCY: u32 = 23;
+pub(crate) const EXECUTION_MODE_QUADS: u32 = 24;
+pub(crate) const EXECUTION_MODE_ISOLINES: u32 = 25;
+
+// Image dimensions.
+const DIM_1D: u32 = 0;
+const DIM_2D: u32 = 1;
+const DIM_3D: u32 = 2;
+const DIM_CUBE: u32 = 3;
+const DIM_BUFFER: u32 = 5;
+const DIM_SUBPASS_DATA: u32 = 6;
+
+// Maximum number of nested types that are followed when reflecting a type. Valid modules can't
+// define a type in terms of itself, but malformed ones could make the reflection loop forever.
+const MAX_TYPE_DEPTH: u32 = 64;
+
+// Highest descriptor set and binding numbers accepted by the reflection. The layouts store the
+// descriptors of each set in a dense array, so the numbers come with an allocation of the same
+// size. No device supports more than 32 bound descriptor sets.
+const MAX_DESCRIPTOR_SET: u32 = 31;
+const MAX_DESCRIPTOR_BINDING: u32 = 4095;
+
+/// A single instruction of a SPIR-V module.
+#[derive(Debug, Clone)]
+pub(crate) struct Instruction {
+    /// The opcode of the instruction.
+    pub opcode: u16,
+    /// The operands of the instruction, without the word that contains the opcode.
+    pub operands: Vec<u32>,
+}
+
+impl Instruction {
+    /// Returns the operand at index `index`, or an error if the instruction is too short.
+    #[inline]
+    pub fn operand(&self, index: usize) -> Result<u32, ShaderReflectionError> {
+        self.operands
+            .get(index)
+            .cloned()
+            .ok_or(ShaderReflectionError::IncompleteInstruction)
+    }
+}
+
+/// A parsed SPIR-V module.
+#[derive(Debug, Clone)]
+pub(crate) struct Spirv {
+    /// Version of SPIR-V, as `(major, minor)`.
+    pub version: (u8, u8),
+    /// All the instructions of the module, in order.
+    pub instructions: Vec<Instruction>,
+}
+
+impl Spirv {
+    /// Parses a list of SPIR-V words.
+    ///
+    /// The words are expected to be in the host endianness, like the ones passed to Vulkan.
+    pub fn parse(words: &[u32]) -> Result<Spirv, ShaderReflectionError> {
+        if words.len() < 5 {
+            return Err(ShaderReflectionError::MissingHeader);
+        }
+
+        if words[0] != MAGIC {
+            return Err(ShaderReflectionError::WrongMagic);
+        }
+
+        let version = (((words[1] & 0x00ff0000) >> 16) as u8, ((words[1] & 0x0000ff00) >> 8) as u8);
+
+        let mut instructions = Vec::new();
+        let mut rest = &words[5 ..];
+        while !rest.is_empty() {
+            let word_count = (rest[0] >> 16) as usize;
+            if word_count == 0 || rest.len() < word_count {
+                return Err(ShaderReflectionError::IncompleteInstruction);
+            }
+
+            instructions.push(Instruction {
+                                  opcode: (rest[0] & 0xffff) as u16,
+                                  operands: rest[1 .. word_count].to_owned(),
+                              });
+            rest = &rest[word_count ..];
+        }
+
+        Ok(Spirv {
+               version: version,
+               instructions: instructions,
+           })
+    }
+
+    /// Returns the first instruction with the given opcode whose result id (ie. the operand
+    /// at index `id_pos`) is `id`.
+    fn find(&self, opcode: u16, id_pos: usize, id: u32) -> Option<&Instruction> {
+        self.instructions
+            .iter()
+            .find(|i| i.opcode == opcode && i.operands.get(id_pos) == Some(&id))
+    }
+
+    /// Returns the definition of the type whose id is `id`.
+    pub fn type_definition(&self, id: u32) -> Result<&Instruction, ShaderReflectionError> {
+        self.instructions
+            .iter()
+            .find(|i| {
+                      i.opcode >= OP_TYPE_BOOL && i.opcode <= OP_TYPE_POINTER &&
+                          i.operands.get(0) == Some(&id)
+                  })
+            .ok_or(ShaderReflectionError::UnknownId(id))
+    }
+
+    /// Returns the parameters of the decoration `decoration` applied to `target`, if any.
+    pub fn decoration(&self, target: u32, decoration: u32) -> Option<&[u32]> {
+        self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_DECORATE && i.operands.len() >= 2)
+            .find(|i| i.operands[0] == target && i.operands[1] == decoration)
+            .map(|i| &i.operands[2 ..])
+    }
+
+    /// Returns the first parameter of the decoration `decoration` applied to `target`, if any.
+    pub fn decoration_u32(&self, target: u32, decoration: u32)
+                          -> Result<Option<u32>, ShaderReflectionError> {
+        match self.decoration(target, decoration) {
+            Some(params) => {
+                params
+                    .get(0)
+                    .cloned()
+                    .map(Some)
+                    .ok_or(ShaderReflectionError::IncompleteInstruction)
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the parameters of the decoration `decoration` applied to the member `member` of
+    /// the struct `target`, if any.
+    pub fn member_decoration(&self, target: u32, member: u32, decoration: u32)
+                             -> Option<&[u32]> {
+        self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_MEMBER_DECORATE && i.operands.len() >= 3)
+            .find(|i| {
+                      i.operands[0] == target && i.operands[1] == member &&
+                          i.operands[2] == decoration
+                  })
+            .map(|i| &i.operands[3 ..])
+    }
+
+    /// Returns the first parameter of the decoration `decoration` applied to the member `member`
+    /// of the struct `target`, if any.
+    pub fn member_decoration_u32(&self, target: u32, member: u32, decoration: u32)
+                                 -> Result<Option<u32>, ShaderReflectionError> {
+        match self.member_decoration(target, member, decoration) {
+            Some(params) => {
+                params
+                    .get(0)
+                    .cloned()
+                    .map(Some)
+                    .ok_or(ShaderReflectionError::IncompleteInstruction)
+            },
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the name given to `id` with `OpName`, if any.
+    pub fn name(&self, id: u32) -> Result<Option<String>, ShaderReflectionError> {
+        match self.find(OP_NAME, 0, id) {
+            Some(i) => Ok(Some(parse_string(&i.operands[1 ..])?.0)),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the value of the 32-bits integer constant `id`.
+    pub fn constant_u32(&self, id: u32) -> Result<u32, ShaderReflectionError> {
+        self.instructions
+            .iter()
+            .find(|i| {
+                      (i.opcode == OP_CONSTANT || i.opcode == OP_SPEC_CONSTANT) &&
+                          i.operands.get(1) == Some(&id)
+                  })
+            .and_then(|i| i.operands.get(2).cloned())
+            .ok_or(ShaderReflectionError::UnknownId(id))
+    }
+
+    /// Returns the union of the stages of all the entry points of the module.
+    pub fn stages(&self) -> ShaderStages {
+        self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_ENTRY_POINT && !i.operands.is_empty())
+            .fold(ShaderStages::none(),
+                  |stages, i| stages | execution_model_stages(i.operands[0]))
+    }
+
+    /// Returns the union of the stages of the entry points named `name`.
+    ///
+    /// Entry points whose name isn't valid UTF-8 are ignored.
+    pub fn entry_point_stages(&self, name: &str) -> ShaderStages {
+        self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_ENTRY_POINT && i.operands.len() >= 3)
+            .filter(|i| string_eq(&i.operands[2 ..], name))
+            .fold(ShaderStages::none(),
+                  |stages, i| stages | execution_model_stages(i.operands[0]))
+    }
+
+    /// Returns the `OpEntryPoint` instruction of the entry point named `name`, if any.
+    ///
+    /// Entry points whose name isn't valid UTF-8 are ignored.
+    pub fn entry_point(&self, name: &str) -> Option<&Instruction> {
+        self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_ENTRY_POINT && i.operands.len() >= 3)
+            .find(|i| string_eq(&i.operands[2 ..], name))
+    }
+
+    /// Returns true if the execution mode `mode` is declared for the entry point whose function
+    /// id is `entry_point`.
+    pub fn has_execution_mode(&self, entry_point: u32, mode: u32) -> bool {
+        self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_EXECUTION_MODE && i.operands.len() >= 2)
+            .any(|i| i.operands[0] == entry_point && i.operands[1] == mode)
+    }
+
+    /// Returns the local size declared with the `LocalSize` execution mode by the entry point
+    /// whose function id is `entry_point`.
+    ///
+    /// Returns `None` if the local size can't be known without specializing the module, for
+    /// example because it comes from a `WorkgroupSize` built-in or a `LocalSizeId` execution mode.
+    pub fn local_size(&self, entry_point: u32) -> Option<[u32; 3]> {
+        let workgroup_size_builtin = self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_DECORATE && i.operands.len() >= 3)
+            .filter(|i| i.operands[1] == DECORATION_BUILT_IN)
+            .any(|i| i.operands[2] == BUILT_IN_WORKGROUP_SIZE);
+        if workgroup_size_builtin {
+            return None;
+        }
+
+        self.instructions
+            .iter()
+            .filter(|i| i.opcode == OP_EXECUTION_MODE && i.operands.len() >= 5)
+            .find(|i| i.operands[0] == entry_point && i.operands[1] == EXECUTION_MODE_LOCAL_SIZE)
+            .map(|i| [i.operands[2], i.operands[3], i.operands[4]])
+    }
+
+    /// Returns the size in bytes of the type `id`, or `None` if it is runtime-sized.
+    ///
+    /// The sizes of structs and arrays are determined from their `Offset`, `ArrayStride` and
+    /// `MatrixStride` decorations, which are mandatory for types used in buffers or push
+    /// constants.
+    #[inline]
+    pub fn type_size(&self, id: u32) -> Result<Option<u32>, ShaderReflectionError> {
+        self.nested_type_size(id, 0)
+    }
+
+    // Same as `type_size()`, for a type nested `depth` levels deep in the type being measured.
+    fn nested_type_size(&self, id: u32, depth: u32)
+                        -> Result<Option<u32>, ShaderReflectionError> {
+        check_depth(id, depth)?;
+        let ty = self.type_definition(id)?;
+
+        Ok(match ty.opcode {
+               OP_TYPE_BOOL => Some(4),
+               OP_TYPE_INT | OP_TYPE_FLOAT => Some(ty.operand(1)? / 8),
+               OP_TYPE_VECTOR => {
+                   let count = ty.operand(2)?;
+                   match self.nested_type_size(ty.operand(1)?, depth + 1)? {
+                       Some(s) => Some(checked_mul(id, s, count)?),
+                       None => None,
+                   }
+               },
+               OP_TYPE_MATRIX => {
+                   let columns = ty.operand(2)?;
+                   let stride = match self.decoration_u32(id, DECORATION_MATRIX_STRIDE)? {
+                       Some(stride) => Some(stride),
+                       None => self.nested_type_size(ty.operand(1)?, depth + 1)?,
+                   };
+                   match stride {
+                       Some(s) => Some(checked_mul(id, s, columns)?),
+                       None => None,
+                   }
+               },
+               OP_TYPE_ARRAY => {
+                   let len = self.constant_u32(ty.operand(2)?)?;
+                   let stride = match self.decoration_u32(id, DECORATION_ARRAY_STRIDE)? {
+                       Some(stride) => Some(stride),
+                       None => self.nested_type_size(ty.operand(1)?, depth + 1)?,
+                   };
+                   match stride {
+                       Some(s) => Some(checked_mul(id, s, len)?),
+                       None => None,
+                   }
+               },
+               OP_TYPE_RUNTIME_ARRAY => None,
+               OP_TYPE_STRUCT => {
+                   let mut size = 0;
+                   for (member, &member_ty) in ty.operands[1 ..].iter().enumerate() {
+                       let offset = self.member_decoration_u32(id, member as u32,
+                                                               DECORATION_OFFSET)?
+                           .unwrap_or(size);
+                       let member_size = match self.member_size(id, member as u32, member_ty,
+                                                                depth + 1)? {
+                           Some(s) => s,
+                           None => return Ok(None),
+                       };
+                       let end = offset
+                           .checked_add(member_size)
+                           .ok_or(ShaderReflectionError::Overflow(id))?;
+                       size = cmp::max(size, end);
+                   }
+                   Some(size)
+               },
+               _ => return Err(ShaderReflectionError::UnsupportedType(id)),
+           })
+    }
+
+    // Size of a member of a struct. Matrices get their stride from the struct member
+    // decoration instead of the type itself.
+    fn member_size(&self, strukt: u32, member: u32, member_ty: u32, depth: u32)
+                   -> Result<Option<u32>, ShaderReflectionError> {
+        let ty = self.type_definition(member_ty)?;
+        if ty.opcode == OP_TYPE_MATRIX {
+            if let Some(stride) = self.member_decoration_u32(strukt, member,
+                                                             DECORATION_MATRIX_STRIDE)? {
+                return Ok(Some(checked_mul(member_ty, stride, ty.operand(2)?)?));
+            }
+        }
+
+        self.nested_type_size(member_ty, depth)
+    }
+
+    /// Returns the descriptors declared in the module, as `(set, binding, desc)` tuples.
+    ///
+    /// The stages of each descriptor are the stages of all the entry points of the module.
+    #[inline]
+    pub fn descriptors(&self) -> Result<Vec<(u32, u32, DescriptorDesc)>, ShaderReflectionError> {
+        self.descriptors_with_stages(self.stages())
+    }
+
+    /// Same as `descriptors()`, but the descriptors are used by `stages` instead.
+    pub fn descriptors_with_stages(&self, stages: ShaderStages)
+                                   -> Result<Vec<(u32, u32, DescriptorDesc)>,
+                                             ShaderReflectionError> {
+        let mut descriptors = Vec::new();
+
+        for instruction in self.instructions.iter() {
+            if instruction.opcode != OP_VARIABLE {
+                continue;
+            }
+
+            let (pointer_ty, variable, storage_class) =
+                (instruction.operand(0)?, instruction.operand(1)?, instruction.operand(2)?);
+
+            match storage_class {
+                STORAGE_CLASS_UNIFORM_CONSTANT |
+                STORAGE_CLASS_UNIFORM |
+                STORAGE_CLASS_STORAGE_BUFFER => (),
+                _ => continue,
+            }
+
+            let set = match self.decoration_u32(variable, DECORATION_DESCRIPTOR_SET)? {
+                Some(set) => set,
+                None => continue,
+            };
+            let binding = self.decoration_u32(variable, DECORATION_BINDING)?
+                .ok_or(ShaderReflectionError::MissingBinding(variable))?;
+            if set > MAX_DESCRIPTOR_SET || binding > MAX_DESCRIPTOR_BINDING {
+                return Err(ShaderReflectionError::BindingOutOfRange {
+                               set: set,
+                               binding: binding,
+                           });
+            }
+
+            let pointed_ty = self.pointed_type(pointer_ty)?;
+            let var_non_writable = self.decoration(variable, DECORATION_NON_WRITABLE).is_some();
+            let (ty, readonly, array_count) =
+                self.descriptor_infos(pointed_ty, storage_class, false, var_non_writable, 0)?;
+
+            descriptors.push((set,
+                              binding,
+                              DescriptorDesc {
+                                  ty: ty,
+                                  array_count: array_count,
+                                  stages: stages,
+                                  readonly: readonly,
+                              }));
+        }
+
+        Ok(descriptors)
+    }
+
+    /// Returns the range covered by the push constants of the module as `(offset, size)`, or
+    /// `None` if the module doesn't use any push constant.
+    pub fn push_constants_range(&self) -> Result<Option<(u32, u32)>, ShaderReflectionError> {
+        for instruction in self.instructions.iter() {
+            if instruction.opcode != OP_VARIABLE ||
+                instruction.operand(2)? != STORAGE_CLASS_PUSH_CONSTANT
+            {
+                continue;
+            }
+
+            let strukt = self.pointed_type(instruction.operand(0)?)?;
+            let members = &self.type_definition(strukt)?.operands[1 ..];
+
+            let mut start = u32::max_value();
+            for member in 0 .. members.len() as u32 {
+                let offset = self.member_decoration_u32(strukt, member, DECORATION_OFFSET)?
+                    .unwrap_or(0);
+                start = cmp::min(start, offset);
+            }
+
+            let end = self.type_size(strukt)?
+                .ok_or(ShaderReflectionError::UnsupportedType(strukt))?;
+
+            if members.is_empty() || end <= start {
+                return Ok(None);
+            }
+
+            return Ok(Some((start, end - start)));
+        }
+
+        Ok(None)
+    }
+
+    /// Returns the ids of the variables of the interface of an `OpEntryPoint` instruction.
+    pub fn entry_point_interface<'a>(&self, entry_point: &'a Instruction)
+                                     -> Result<&'a [u32], ShaderReflectionError> {
+        if entry_point.operands.len() < 3 {
+            return Err(ShaderReflectionError::IncompleteInstruction);
+        }
+
+        Ok(parse_string(&entry_point.operands[2 ..])?.1)
+    }
+
+    /// Returns the elements of the interface of an entry point whose storage class is
+    /// `storage_class`, sorted by location.
+    ///
+    /// If `arrayed` is true, the variables are arrays with one element per vertex (as for example
+    /// the inputs of a geometry shader) and the outer array is ignored. Built-in variables are
+    /// skipped.
+    pub fn interface(&self, entry_point: &Instruction, storage_class: u32, arrayed: bool)
+                     -> Result<Vec<ShaderInterfaceDefEntry>, ShaderReflectionError> {
+        let mut elements = Vec::new();
+
+        for &variable in self.entry_point_interface(entry_point)? {
+            let instruction = self.find(OP_VARIABLE, 1, variable)
+                .ok_or(ShaderReflectionError::UnknownId(variable))?;
+            if instruction.operand(2)? != storage_class || self.is_builtin(variable)? {
+                continue;
+            }
+
+            let location = self.decoration_u32(variable, DECORATION_LOCATION)?
+                .ok_or(ShaderReflectionError::MissingLocation(variable))?;
+
+            let mut ty = self.pointed_type(instruction.operand(0)?)?;
+            if arrayed {
+                let def = self.type_definition(ty)?;
+                if def.opcode != OP_TYPE_ARRAY && def.opcode != OP_TYPE_RUNTIME_ARRAY {
+                    return Err(ShaderReflectionError::UnsupportedType(ty));
+                }
+                ty = def.operand(1)?;
+            }
+
+            let (format, num_locations) = self.interface_format(ty, 0)?;
+            let end = location
+                .checked_add(num_locations)
+                .ok_or(ShaderReflectionError::Overflow(variable))?;
+            elements.push(ShaderInterfaceDefEntry {
+                              location: location .. end,
+                              format: format,
+                              name: self.name(variable)?.map(Into::into),
+                          });
+        }
+
+        elements.sort_by_key(|e| e.location.start);
+        Ok(elements)
+    }
+
+    // Returns true if `variable` is a built-in, or a block of built-ins like `gl_PerVertex`.
+    fn is_builtin(&self, variable: u32) -> Result<bool, ShaderReflectionError> {
+        if self.decoration(variable, DECORATION_BUILT_IN).is_some() {
+            return Ok(true);
+        }
+
+        let instruction = self.find(OP_VARIABLE, 1, variable)
+            .ok_or(ShaderReflectionError::UnknownId(variable))?;
+        let mut ty = self.pointed_type(instruction.operand(0)?)?;
+        let mut depth = 0;
+        loop {
+            check_depth(ty, depth)?;
+            depth += 1;
+            let def = self.type_definition(ty)?;
+            match def.opcode {
+                OP_TYPE_ARRAY | OP_TYPE_RUNTIME_ARRAY => ty = def.operand(1)?,
+                OP_TYPE_STRUCT => {
+                    return Ok(self.member_decoration(ty, 0, DECORATION_BUILT_IN).is_some());
+                },
+                _ => return Ok(false),
+            }
+        }
+    }
+
+    // Returns the format of each location used by an interface variable of type `id`, and the
+    // number of locations. `depth` is the number of types that enclose `id` in the variable.
+    fn interface_format(&self, id: u32, depth: u32)
+                        -> Result<(Format, u32), ShaderReflectionError> {
+        check_depth(id, depth)?;
+        let ty = self.type_definition(id)?;
+
+        match ty.opcode {
+            OP_TYPE_INT | OP_TYPE_FLOAT | OP_TYPE_VECTOR => {
+                let (scalar, count) = if ty.opcode == OP_TYPE_VECTOR {
+                    (self.type_definition(ty.operand(1)?)?, ty.operand(2)?)
+                } else {
+                    (ty, 1)
+                };
+
+                let kind = match scalar.opcode {
+                    OP_TYPE_FLOAT => 0,
+                    OP_TYPE_INT if scalar.operand(2)? != 0 => 1,
+                    OP_TYPE_INT => 2,
+                    _ => return Err(ShaderReflectionError::UnsupportedType(id)),
+                };
+                let format = interface_format(kind, scalar.operand(1)?, count)
+                    .ok_or(ShaderReflectionError::UnsupportedType(id))?;
+                Ok((format, 1))
+            },
+            OP_TYPE_MATRIX => {
+                let (format, column_locations) =
+                    self.interface_format(ty.operand(1)?, depth + 1)?;
+                Ok((format, checked_mul(id, column_locations, ty.operand(2)?)?))
+            },
+            OP_TYPE_ARRAY => {
+                let (format, element_locations) =
+                    self.interface_format(ty.operand(1)?, depth + 1)?;
+                let len = self.constant_u32(ty.operand(2)?)?;
+                Ok((format, checked_mul(id, element_locations, len)?))
+            },
+            _ => Err(ShaderReflectionError::UnsupportedType(id)),
+        }
+    }
+
+    /// Assumes that `id` is a pointer type and returns the pointed type.
+    pub fn pointed_type(&self, id: u32) -> Result<u32, ShaderReflectionError> {
+        let ty = self.type_definition(id)?;
+        if ty.opcode != OP_TYPE_POINTER {
+            return Err(ShaderReflectionError::UnsupportedType(id));
+        }
+        ty.operand(2)
+    }
+
+    // Returns the descriptor type, whether it is read-only and the number of array elements.
+    // `depth` is the number of types that enclose `pointed_ty` in the variable.
+    //
+    // See also section 14.5.2 of the Vulkan specs: Descriptor Set Interface
+    fn descriptor_infos(&self, pointed_ty: u32, storage_class: u32,
+                        force_combined_image_sampled: bool, non_writable: bool, depth: u32)
+                        -> Result<(DescriptorDescTy, bool, u32), ShaderReflectionError> {
+        check_depth(pointed_ty, depth)?;
+        let ty = self.type_definition(pointed_ty)?;
+
+        match ty.opcode {
+            OP_TYPE_STRUCT => {
+                let is_ssbo = storage_class == STORAGE_CLASS_STORAGE_BUFFER ||
+                    self.decoration(pointed_ty, DECORATION_BUFFER_BLOCK).is_some();
+
+                // A storage buffer is read-only if all of its members are `NonWritable`.
+                let readonly = !is_ssbo || non_writable ||
+                    (0 .. ty.operands.len() as u32 - 1).all(|member| {
+                        self.member_decoration(pointed_ty, member, DECORATION_NON_WRITABLE)
+                            .is_some()
+                    });
+
+                let desc = DescriptorDescTy::Buffer(DescriptorBufferDesc {
+                                                        dynamic: Some(false),
+                                                        storage: is_ssbo,
+                                                    });
+                Ok((desc, readonly, 1))
+            },
+
+            OP_TYPE_IMAGE => {
+                let (dim, arrayed, ms, sampled) =
+                    (ty.operand(2)?, ty.operand(4)? != 0, ty.operand(5)? != 0, ty.operand(6)?);
+                // `Sampled` is 1 for images used with a sampler, and 2 for storage images.
+                let sampled = match sampled {
+                    1 => true,
+                    2 => false,
+                    _ => return Err(ShaderReflectionError::UnsupportedType(pointed_ty)),
+                };
+
+                let array_layers = if arrayed {
+                    DescriptorImageDescArray::Arrayed { max_layers: None }
+                } else {
+                    DescriptorImageDescArray::NonArrayed
+                };
+
+                match dim {
+                    DIM_SUBPASS_DATA => {
+                        Ok((DescriptorDescTy::InputAttachment {
+                                multisampled: ms,
+                                array_layers: array_layers,
+                            },
+                            true,
+                            1))
+                    },
+                    DIM_BUFFER => {
+                        Ok((DescriptorDescTy::TexelBuffer {
+                                storage: !sampled,
+                                format: None,
+                            },
+                            sampled || non_writable,
+                            1))
+                    },
+                    _ => {
+                        let dimensions = match dim {
+                            DIM_1D => DescriptorImageDescDimensions::OneDimensional,
+                            DIM_2D => DescriptorImageDescDimensions::TwoDimensional,
+                            DIM_3D => DescriptorImageDescDimensions::ThreeDimensional,
+                            DIM_CUBE => DescriptorImageDescDimensions::Cube,
+                            _ => return Err(ShaderReflectionError::UnsupportedType(pointed_ty)),
+                        };
+
+                        let desc = DescriptorImageDesc {
+                            sampled: sampled,
+                            dimensions: dimensions,
+                            format: None,
+                            multisampled: ms,
+                            array_layers: array_layers,
+                        };
+
+                        if force_combined_image_sampled {
+                            Ok((DescriptorDescTy::CombinedImageSampler(desc), true, 1))
+                        } else {
+                            Ok((DescriptorDescTy::Image(desc), sampled || non_writable, 1))
+                        }
+                    }
+                }
+            },
+
+            OP_TYPE_SAMPLED_IMAGE => {
+                self.descriptor_infos(ty.operand(1)?, storage_class, true, non_writable, depth + 1)
+            },
+
+            OP_TYPE_SAMPLER => Ok((DescriptorDescTy::Sampler, true, 1)),
+
+            OP_TYPE_ARRAY => {
+                let (desc, readonly, inner_len) =
+                    self.descriptor_infos(ty.operand(1)?,
+                                          storage_class,
+                                          false,
+                                          non_writable,
+                                          depth + 1)?;
+                if inner_len != 1 {
+                    return Err(ShaderReflectionError::UnsupportedType(pointed_ty));
+                }
+                let len = self.constant_u32(ty.operand(2)?)?;
+                Ok((desc, readonly, len))
+            },
+
+            _ => Err(ShaderReflectionError::UnsupportedType(pointed_ty)),
+        }
+    }
+}
+
+/// Changes the `DescriptorSet` decorations of a SPIR-V module in place. Each `(from, to)` pair of
+/// `sets` moves the descriptors of the set `from` to the set `to`.
+///
+/// Returns an error and leaves the words untouched if they aren't a valid module.
+pub(crate) fn remap_descriptor_sets(words: &mut [u32], sets: &[(u32, u32)])
+                                    -> Result<(), ShaderReflectionError> {
+    // Checks the header and that every instruction fits in the module before patching it.
+    Spirv::parse(words)?;
+
+    let mut offset = 5;
+    while offset < words.len() {
+        let word_count = (words[offset] >> 16) as usize;
+        let opcode = (words[offset] & 0xffff) as u16;
+        if opcode == OP_DECORATE && word_count >= 4 &&
+            words[offset + 2] == DECORATION_DESCRIPTOR_SET
+        {
+            let set = &mut words[offset + 3];
+            if let Some(&(_, to)) = sets.iter().find(|&&(from, _)| from == *set) {
+                *set = to;
+            }
+        }
+
+        offset += word_count;
+    }
+
+    Ok(())
+}
+
+/// Returns the shader stages that correspond to a SPIR-V execution model.
+pub(crate) fn execution_model_stages(model: u32) -> ShaderStages {
+    match model {
+        EXECUTION_MODEL_VERTEX => ShaderStages { vertex: true, ..ShaderStages::none() },
+        EXECUTION_MODEL_TESSELLATION_CONTROL => {
+            ShaderStages { tessellation_control: true, ..ShaderStages::none() }
+        },
+        EXECUTION_MODEL_TESSELLATION_EVALUATION => {
+            ShaderStages { tessellation_evaluation: true, ..ShaderStages::none() }
+        },
+        EXECUTION_MODEL_GEOMETRY => ShaderStages { geometry: true, ..ShaderStages::none() },
+        EXECUTION_MODEL_FRAGMENT => ShaderStages { fragment: true, ..ShaderStages::none() },
+        EXECUTION_MODEL_GL_COMPUTE => ShaderStages::compute(),
+        EXECUTION_MODEL_TASK_EXT => ShaderStages { task: true, ..ShaderStages::none() },
+        EXECUTION_MODEL_MESH_EXT => ShaderStages { mesh: true, ..ShaderStages::none() },
+        EXECUTION_MODEL_RAY_GENERATION_KHR => {
+            ShaderStages { raygen: true, ..ShaderStages::none() }
+        },
+        EXECUTION_MODEL_INTERSECTION_KHR => {
+            ShaderStages { intersection: true, ..ShaderStages::none() }
+        },
+        EXECUTION_MODEL_ANY_HIT_KHR => ShaderStages { any_hit: true, ..ShaderStages::none() },
+        EXECUTION_MODEL_CLOSEST_HIT_KHR => {
+            ShaderStages { closest_hit: true, ..ShaderStages::none() }
+        },
+        EXECUTION_MODEL_MISS_KHR => ShaderStages { miss: true, ..ShaderStages::none() },
+        EXECUTION_MODEL_CALLABLE_KHR => ShaderStages { callable: true, ..ShaderStages::none() },
+        _ => ShaderStages::none(),
+    }
+}
+
+// Returns the format of an interface element made of `count` components of `width` bits. `kind`
+// is 0 for floats, 1 for signed integers and 2 for unsigned integers.
+//
+// 64-bits vectors with three or four components span two locations, which `ShaderInterfaceDef`
+// can't describe, so they aren't supported.
+fn interface_format(kind: u32, width: u32, count: u32) -> Option<Format> {
+    Some(match (kind, width, count) {
+             (0, 16, 1) => Format::R16Sfloat,
+             (0, 16, 2) => Format::R16G16Sfloat,
+             (0, 16, 3) => Format::R16G16B16Sfloat,
+             (0, 16, 4) => Format::R16G16B16A16Sfloat,
+             (0, 32, 1) => Format::R32Sfloat,
+             (0, 32, 2) => Format::R32G32Sfloat,
+             (0, 32, 3) => Format::R32G32B32Sfloat,
+             (0, 32, 4) => Format::R32G32B32A32Sfloat,
+             (0, 64, 1) => Format::R64Sfloat,
+             (0, 64, 2) => Format::R64G64Sfloat,
+             (1, 8, 1) => Format::R8Sint,
+             (1, 8, 2) => Format::R8G8Sint,
+             (1, 8, 3) => Format::R8G8B8Sint,
+             (1, 8, 4) => Format::R8G8B8A8Sint,
+             (1, 16, 1) => Format::R16Sint,
+             (1, 16, 2) => Format::R16G16Sint,
+             (1, 16, 3) => Format::R16G16B16Sint,
+             (1, 16, 4) => Format::R16G16B16A16Sint,
+             (1, 32, 1) => Format::R32Sint,
+             (1, 32, 2) => Format::R32G32Sint,
+             (1, 32, 3) => Format::R32G32B32Sint,
+             (1, 32, 4) => Format::R32G32B32A32Sint,
+             (1, 64, 1) => Format::R64Sint,
+             (1, 64, 2) => Format::R64G64Sint,
+             (2, 8, 1) => Format::R8Uint,
+             (2, 8, 2) => Format::R8G8Uint,
+             (2, 8, 3) => Format::R8G8B8Uint,
+             (2, 8, 4) => Format::R8G8B8A8Uint,
+             (2, 16, 1) => Format::R16Uint,
+             (2, 16, 2) => Format::R16G16Uint,
+             (2, 16, 3) => Format::R16G16B16Uint,
+             (2, 16, 4) => Format::R16G16B16A16Uint,
+             (2, 32, 1) => Format::R32Uint,
+             (2, 32, 2) => Format::R32G32Uint,
+             (2, 32, 3) => Format::R32G32B32Uint,
+             (2, 32, 4) => Format::R32G32B32A32Uint,
+             (2, 64, 1) => Format::R64Uint,
+             (2, 64, 2) => Format::R64G64Uint,
+             _ => return None,
+         })
+}
+
+// Returns an error if the type `id` is nested more than `MAX_TYPE_DEPTH` levels deep.
+#[inline]
+fn check_depth(id: u32, depth: u32) -> Result<(), ShaderReflectionError> {
+    if depth > MAX_TYPE_DEPTH {
+        return Err(ShaderReflectionError::RecursiveType(id));
+    }
+
+    Ok(())
+}
+
+// Multiplies a size or a number of locations of the type `id`, or returns an error if the
+// result doesn't fit in 32 bits.
+#[inline]
+fn checked_mul(id: u32, a: u32, b: u32) -> Result<u32, ShaderReflectionError> {
+    a.checked_mul(b).ok_or(ShaderReflectionError::Overflow(id))
+}
+
+/// Parses a nul-terminated literal string. Returns the string and the remaining words.
+pub(crate) fn parse_string(data: &[u32]) -> Result<(String, &[u32]), ShaderReflectionError> {
+    let bytes = data.iter()
+        .flat_map(|&n| {
+                      let b1 = (n & 0xff) as u8;
+                      let b2 = ((n >> 8) & 0xff) as u8;
+                      let b3 = ((n >> 16) & 0xff) as u8;
+                      let b4 = ((n >> 24) & 0xff) as u8;
+                      vec![b1, b2, b3, b4].into_iter()
+                  })
+        .take_while(|&b| b != 0)
+        .collect::<Vec<u8>>();
+
+    let r = 1 + bytes.len() / 4;
+    let s = String::from_utf8(bytes).map_err(|_| ShaderReflectionError::InvalidString)?;
+
+    Ok((s, &data[cmp::min(r, data.len()) ..]))
+}
+
+// Returns true if the nul-terminated literal string `data` is `name`.
+fn string_eq(data: &[u32], name: &str) -> bool {
+    match parse_string(data) {
+        Ok((s, _)) => s == name,
+        Err(_) => false,
+    }
+}
+
+/// Error that can happen when reflecting the SPIR-V code of a shader module.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ShaderReflectionError {
+    /// The code is too short to contain a SPIR-V header.
+    MissingHeader,
+    /// The code doesn't start with the SPIR-V magic number.
+    WrongMagic,
+    /// An instruction is longer than the remaining code, or has fewer operands than its opcode
+    /// requires.
+    IncompleteInstruction,
+    /// An id is used but never defined.
+    UnknownId(u32),
+    /// The type with the given id is not supported by the reflection.
+    UnsupportedType(u32),
+    /// The variable with the given id has a `DescriptorSet` decoration but no `Binding`.
+    MissingBinding(u32),
+    /// The interface variable with the given id has no `Location` decoration.
+    MissingLocation(u32),
+    /// The geometry shader entry point with the given id doesn't declare its input primitives.
+    MissingInputPrimitives(u32),
+    /// Two descriptors of the module use the same binding with incompatible types.
+    AliasedDescriptors { set: u32, binding: u32 },
+    /// A descriptor uses a set or binding number that is too high to be supported.
+    BindingOutOfRange { set: u32, binding: u32 },
+    /// A literal string of the module, like the name of an entry point, isn't valid UTF-8 or
+    /// contains a nul character.
+    InvalidString,
+    /// The type with the given id contains itself, or is nested too deeply in another type.
+    RecursiveType(u32),
+    /// The size or the locations of the type or variable with the given id don't fit in 32 bits.
+    Overflow(u32),
+}
+
+impl error::Error for ShaderReflectionError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ShaderReflectionError::MissingHeader => "the code is too short to contain a header",
+            ShaderReflectionError::WrongMagic => "the code doesn't start with the magic number",
+            ShaderReflectionError::IncompleteInstruction => "an instruction is incomplete",
+            ShaderReflectionError::UnknownId(_) => "an id is used but never defined",
+            ShaderReflectionError::UnsupportedType(_) => "a type is not supported",
+            ShaderReflectionError::MissingBinding(_) => {
+                "a descriptor is missing a binding decoration"
+            },
+            ShaderReflectionError::MissingLocation(_) => {
+                "an interface variable is missing a location decoration"
+            },
+            ShaderReflectionError::MissingInputPrimitives(_) => {
+                "a geometry shader doesn't declare its input primitives"
+            },
+            ShaderReflectionError::AliasedDescriptors { .. } => {
+                "two descriptors use the same binding with incompatible types"
+            },
+            ShaderReflectionError::BindingOutOfRange { .. } => {
+                "a descriptor uses a set or binding number that is too high"
+            },
+            ShaderReflectionError::InvalidString => "a literal string is invalid",
+            ShaderReflectionError::RecursiveType(_) => {
+                "a type contains itself or is nested too deeply"
+            },
+            ShaderReflectionError::Overflow(_) => {
+                "the size or the locations of a type don't fit in 32 bits"
+            },
+        }
+    }
+}
+
+impl fmt::Display for ShaderReflectionError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use descriptor::descriptor::DescriptorDescTy;
+    use spirv::*;
+
+    // Instructions and operands that are only needed to build valid modules in the tests.
+    pub(crate) const OP_MEMORY_MODEL: u16 = 14;
+    pub(crate) const OP_TYPE_VOID: u16 = 19;
+    pub(crate) const OP_TYPE_FUNCTION: u16 = 33;
+    pub(crate) const OP_FUNCTION: u16 = 54;
+    pub(crate) const OP_FUNCTION_END: u16 = 56;
+    pub(crate) const OP_LABEL: u16 = 248;
+    pub(crate) const OP_RETURN: u16 = 253;
+    pub(crate) const CAPABILITY_SHADER: u32 = 1;
+    pub(crate) const EXECUTION_MODE_ORIGIN_UPPER_LEFT: u32 = 7;
+
+    /// Builds a SPIR-V module from a list of `(opcode, operands)`.
+    pub(crate) fn module(instructions: &[(u16, &[u32])]) -> Vec<u32> {
+        let mut words = vec![MAGIC, 0x00010000, 0, 100, 0];
+        for &(opcode, operands) in instructions {
+            words.push(((operands.len() as u32 + 1) << 16) | opcode as u32);
+            words.extend_from_slice(operands);
+        }
+        words
+    }
+
+    #[test]
+    fn wrong_magic() {
+        match Spirv::parse(&[0xdeadbeef, 0, 0, 0, 0]) {
+            Err(ShaderReflectionError::WrongMagic) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn incomplete_instruction() {
+        let mut words = module(&[]);
+        words.push((3 << 16) | OP_CAPABILITY as u32);
+        match Spirv::parse(&words) {
+            Err(ShaderReflectionError::IncompleteInstruction) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn entry_point_stages() {
+        // Two entry points named "main", and one named "miss".
+        let raygen = EXECUTION_MODEL_RAY_GENERATION_KHR;
+        let closest_hit = EXECUTION_MODEL_CLOSEST_HIT_KHR;
+        let words = module(&[(OP_ENTRY_POINT, &[raygen, 1, 0x6e69616d, 0]),
+                             (OP_ENTRY_POINT, &[closest_hit, 2, 0x6e69616d, 0]),
+                             (OP_ENTRY_POINT, &[EXECUTION_MODEL_MISS_KHR, 3, 0x7373696d, 0])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        assert_eq!(spirv.entry_point_stages("main"),
+                   ShaderStages {
+                       raygen: true,
+                       closest_hit: true,
+                       ..ShaderStages::none()
+                   });
+        assert_eq!(spirv.entry_point_stages("miss"),
+                   ShaderStages {
+                       miss: true,
+                       ..ShaderStages::none()
+                   });
+        assert_eq!(spirv.entry_point_stages("other"), ShaderStages::none());
+    }
+
+    #[test]
+    fn storage_buffer_and_push_constants() {
+        // layout(set = 1, binding = 2) buffer Data { float data[4]; };
+        // layout(push_constant) uniform Pc { layout(offset = 16) vec4 a; };
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 1, 0x6e69616d, 0]),
+                             (OP_DECORATE, &[2, DECORATION_ARRAY_STRIDE, 4]),
+                             (OP_MEMBER_DECORATE, &[3, 0, DECORATION_OFFSET, 0]),
+                             (OP_DECORATE, &[3, DECORATION_BUFFER_BLOCK]),
+                             (OP_DECORATE, &[5, DECORATION_DESCRIPTOR_SET, 1]),
+                             (OP_DECORATE, &[5, DECORATION_BINDING, 2]),
+                             (OP_MEMBER_DECORATE, &[8, 0, DECORATION_OFFSET, 16]),
+                             (OP_DECORATE, &[8, DECORATION_BLOCK]),
+                             (OP_TYPE_FLOAT, &[10, 32]),
+                             (OP_TYPE_INT, &[11, 32, 0]),
+                             (OP_CONSTANT, &[11, 12, 4]),
+                             (OP_TYPE_ARRAY, &[2, 10, 12]),
+                             (OP_TYPE_STRUCT, &[3, 2]),
+                             (OP_TYPE_POINTER, &[4, STORAGE_CLASS_UNIFORM, 3]),
+                             (OP_VARIABLE, &[4, 5, STORAGE_CLASS_UNIFORM]),
+                             (OP_TYPE_VECTOR, &[7, 10, 4]),
+                             (OP_TYPE_STRUCT, &[8, 7]),
+                             (OP_TYPE_POINTER, &[9, STORAGE_CLASS_PUSH_CONSTANT, 8]),
+                             (OP_VARIABLE, &[9, 13, STORAGE_CLASS_PUSH_CONSTANT])]);
+
+        let spirv = Spirv::parse(&words).unwrap();
+        assert!(spirv.stages().compute);
+
+        let descriptors = spirv.descriptors().unwrap();
+        assert_eq!(descriptors.len(), 1);
+        let (set, binding, ref desc) = descriptors[0];
+        assert_eq!((set, binding), (1, 2));
+        assert!(!desc.readonly);
+        match desc.ty {
+            DescriptorDescTy::Buffer(ref b) => assert!(b.storage),
+            _ => panic!(),
+        }
+
+        assert_eq!(spirv.push_constants_range().unwrap(), Some((16, 16)));
+    }
+
+    #[test]
+    fn invalid_utf8_name() {
+        // `OpName` of a variable with a lone continuation byte.
+        let words = module(&[(OP_NAME, &[1, 0x80])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        assert_eq!(spirv.name(1), Err(ShaderReflectionError::InvalidString));
+        assert_eq!(spirv.name(2), Ok(None));
+    }
+
+    #[test]
+    fn truncated_descriptor_set_decoration() {
+        // The `DescriptorSet` decoration is missing the set number.
+        let words = module(&[(OP_DECORATE, &[5, DECORATION_DESCRIPTOR_SET]),
+                             (OP_DECORATE, &[5, DECORATION_BINDING, 2]),
+                             (OP_TYPE_SAMPLER, &[3]),
+                             (OP_TYPE_POINTER, &[4, STORAGE_CLASS_UNIFORM_CONSTANT, 3]),
+                             (OP_VARIABLE, &[4, 5, STORAGE_CLASS_UNIFORM_CONSTANT])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        match spirv.descriptors() {
+            Err(ShaderReflectionError::IncompleteInstruction) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn truncated_push_constants_variable() {
+        // `OpVariable` without a storage class.
+        let words = module(&[(OP_TYPE_FLOAT, &[1, 32]),
+                             (OP_TYPE_POINTER, &[2, STORAGE_CLASS_PUSH_CONSTANT, 1]),
+                             (OP_VARIABLE, &[2, 3])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        assert_eq!(spirv.push_constants_range(),
+                   Err(ShaderReflectionError::IncompleteInstruction));
+    }
+
+    #[test]
+    fn remap_sets() {
+        let mut words = module(&[(OP_DECORATE, &[5, DECORATION_DESCRIPTOR_SET, 1]),
+                                 (OP_DECORATE, &[5, DECORATION_BINDING, 1]),
+                                 (OP_DECORATE, &[6, DECORATION_DESCRIPTOR_SET, 0])]);
+        remap_descriptor_sets(&mut words, &[(1, 3), (0, 1)]).unwrap();
+
+        let spirv = Spirv::parse(&words).unwrap();
+        assert_eq!(spirv.decoration(5, DECORATION_DESCRIPTOR_SET), Some(&[3][..]));
+        assert_eq!(spirv.decoration(5, DECORATION_BINDING), Some(&[1][..]));
+        assert_eq!(spirv.decoration(6, DECORATION_DESCRIPTOR_SET), Some(&[1][..]));
+    }
+
+    #[test]
+    fn truncated_interface_variable() {
+        // The `OpVariable` of the interface is missing its storage class.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x6e69616d, 0, 5]),
+                             (OP_TYPE_FLOAT, &[2, 32]),
+                             (OP_TYPE_POINTER, &[3, STORAGE_CLASS_INPUT, 2]),
+                             (OP_VARIABLE, &[3, 5])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        let entry_point = spirv.entry_point("main").unwrap();
+        match spirv.interface(entry_point, STORAGE_CLASS_INPUT, false) {
+            Err(ShaderReflectionError::IncompleteInstruction) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn truncated_interface_type() {
+        // The `OpTypeVector` of the interface variable is missing its component count.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x6e69616d, 0, 5]),
+                             (OP_DECORATE, &[5, DECORATION_LOCATION, 0]),
+                             (OP_TYPE_FLOAT, &[2, 32]),
+                             (OP_TYPE_VECTOR, &[3, 2]),
+                             (OP_TYPE_POINTER, &[4, STORAGE_CLASS_INPUT, 3]),
+                             (OP_VARIABLE, &[4, 5, STORAGE_CLASS_INPUT])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        let entry_point = spirv.entry_point("main").unwrap();
+        match spirv.interface(entry_point, STORAGE_CLASS_INPUT, false) {
+            Err(ShaderReflectionError::IncompleteInstruction) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn self_referential_type() {
+        // An array whose element type is the array itself.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x6e69616d, 0, 5]),
+                             (OP_DECORATE, &[5, DECORATION_LOCATION, 0]),
+                             (OP_TYPE_INT, &[10, 32, 0]),
+                             (OP_CONSTANT, &[10, 3, 1]),
+                             (OP_TYPE_ARRAY, &[2, 2, 3]),
+                             (OP_TYPE_POINTER, &[4, STORAGE_CLASS_INPUT, 2]),
+                             (OP_VARIABLE, &[4, 5, STORAGE_CLASS_INPUT])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        assert_eq!(spirv.type_size(2), Err(ShaderReflectionError::RecursiveType(2)));
+        let entry_point = spirv.entry_point("main").unwrap();
+        match spirv.interface(entry_point, STORAGE_CLASS_INPUT, false) {
+            Err(ShaderReflectionError::RecursiveType(2)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn overflowing_sizes() {
+        // A `float[0xffffffff]` array, and a `float` input at the last location.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_VERTEX, 1, 0x6e69616d, 0, 5]),
+                             (OP_DECORATE, &[5, DECORATION_LOCATION, 0xffffffff]),
+                             (OP_TYPE_FLOAT, &[2, 32]),
+                             (OP_TYPE_INT, &[10, 32, 0]),
+                             (OP_CONSTANT, &[10, 3, 0xffffffff]),
+                             (OP_TYPE_ARRAY, &[6, 2, 3]),
+                             (OP_TYPE_POINTER, &[4, STORAGE_CLASS_INPUT, 2]),
+                             (OP_VARIABLE, &[4, 5, STORAGE_CLASS_INPUT])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        assert_eq!(spirv.type_size(6), Err(ShaderReflectionError::Overflow(6)));
+        let entry_point = spirv.entry_point("main").unwrap();
+        match spirv.interface(entry_point, STORAGE_CLASS_INPUT, false) {
+            Err(ShaderReflectionError::Overflow(5)) => (),
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn remap_sets_truncated() {
+        // The last `OpDecorate` claims four words but only has three.
+        let mut words = module(&[(OP_DECORATE, &[5, DECORATION_DESCRIPTOR_SET, 1])]);
+        words.push((4 << 16) | OP_DECORATE as u32);
+        words.push(6);
+        words.push(DECORATION_DESCRIPTOR_SET);
+        let expected = words.clone();
+
+        assert_eq!(remap_descriptor_sets(&mut words, &[(1, 3)]),
+                   Err(ShaderReflectionError::IncompleteInstruction));
+        assert_eq!(words, expected);
+    }
+
+    #[test]
+    fn local_size() {
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 1, 0x6e69616d, 0]),
+                             (OP_EXECUTION_MODE, &[1, EXECUTION_MODE_LOCAL_SIZE, 48, 2, 1])]);
+        let spirv = Spirv::parse(&words).unwrap();
+        assert_eq!(spirv.local_size(1), Some([48, 2, 1]));
+        assert_eq!(spirv.local_size(2), None);
+
+        // The `WorkgroupSize` built-in overrides the execution mode.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_GL_COMPUTE, 1, 0x6e69616d, 0]),
+                             (OP_EXECUTION_MODE, &[1, EXECUTION_MODE_LOCAL_SIZE, 48, 2, 1]),
+                             (OP_DECORATE, &[3, DECORATION_BUILT_IN, BUILT_IN_WORKGROUP_SIZE])]);
+        let spirv = Spirv::parse(&words).unwrap();
+        assert_eq!(spirv.local_size(1), None);
+    }
+
+    #[test]
+    fn entry_point_execution_modes() {
+        // "main" and "other", as nul-terminated little-endian strings.
+        let words = module(&[(OP_ENTRY_POINT, &[EXECUTION_MODEL_TESSELLATION_EVALUATION, 1,
+                                                0x6e69616d, 0]),
+                             (OP_ENTRY_POINT, &[EXECUTION_MODEL_TESSELLATION_EVALUATION, 2,
+                                                0x6568746f, 0x72]),
+                             (OP_EXECUTION_MODE, &[2, EXECUTION_MODE_ISOLINES])]);
+        let spirv = Spirv::parse(&words).unwrap();
+
+        assert_eq!(spirv.entry_point("main").unwrap().operands[1], 1);
+        assert_eq!(spirv.entry_point("other").unwrap().operands[1], 2);
+        assert!(spirv.entry_point("foo").is_none());
+        assert!(spirv.has_execution_mode(2, EXECUTION_MODE_ISOLINES));
+        assert!(!spirv.has_execution_mode(1, EXECUTION_MODE_ISOLINES));
+    }
+}
@@ -47,6 +47,9 @@
 //! Trying to use one of these functions without enabling the proper extension will result in an
 //! error.
 //!
+//! If the `winit` feature is enabled, `Surface::from_winit` creates a surface from a winit window
+//! with the right function for the platform. See the `winit` module.
+//!
 //! **Note that the `Surface` object is potentially unsafe**. It is your responsibility to
 //! keep the window alive for at least as long as the surface exists. In many cases Surface
 //! may be able to do this for you, if you pass it ownership of your Window (or a
@@ -320,6 +323,8 @@ pub mod display;
 mod present_region;
 mod surface;
 mod swapchain;
+#[cfg(feature = "winit")]
+pub mod winit;
 
 /// Internal trait so that creating/destroying a swapchain can access the surface's "has_swapchain"
 /// flag.
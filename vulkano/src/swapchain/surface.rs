@@ -108,7 +108,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateDisplayPlaneSurfaceKHR(instance.internal_object(),
                                                          &infos,
-                                                         ptr::null(),
+                                                         instance.allocator_ptr(),
                                                          &mut output))?;
             output
         };
@@ -151,7 +151,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateWin32SurfaceKHR(instance.internal_object(),
                                                   &infos,
-                                                  ptr::null(),
+                                                  instance.allocator_ptr(),
                                                   &mut output))?;
             output
         };
@@ -193,7 +193,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateXcbSurfaceKHR(instance.internal_object(),
                                                 &infos,
-                                                ptr::null(),
+                                                instance.allocator_ptr(),
                                                 &mut output))?;
             output
         };
@@ -235,7 +235,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateXlibSurfaceKHR(instance.internal_object(),
                                                  &infos,
-                                                 ptr::null(),
+                                                 instance.allocator_ptr(),
                                                  &mut output))?;
             output
         };
@@ -278,7 +278,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateWaylandSurfaceKHR(instance.internal_object(),
                                                     &infos,
-                                                    ptr::null(),
+                                                    instance.allocator_ptr(),
                                                     &mut output))?;
             output
         };
@@ -322,7 +322,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateMirSurfaceKHR(instance.internal_object(),
                                                 &infos,
-                                                ptr::null(),
+                                                instance.allocator_ptr(),
                                                 &mut output))?;
             output
         };
@@ -361,7 +361,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateAndroidSurfaceKHR(instance.internal_object(),
                                                     &infos,
-                                                    ptr::null(),
+                                                    instance.allocator_ptr(),
                                                     &mut output))?;
             output
         };
@@ -401,7 +401,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateIOSSurfaceMVK(instance.internal_object(),
                                                 &infos,
-                                                ptr::null(),
+                                                instance.allocator_ptr(),
                                                 &mut output))?;
             output
         };
@@ -441,7 +441,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateMacOSSurfaceMVK(instance.internal_object(),
                                                   &infos,
-                                                  ptr::null(),
+                                                  instance.allocator_ptr(),
                                                   &mut output))?;
             output
         };
@@ -480,7 +480,7 @@ impl<W> Surface<W> {
             let mut output = mem::uninitialized();
             check_errors(vk.CreateViSurfaceNN(instance.internal_object(),
                                               &infos,
-                                              ptr::null(),
+                                              instance.allocator_ptr(),
                                               &mut output))?;
             output
         };
@@ -642,7 +642,9 @@ impl <W> Drop for Surface<W> {
     fn drop(&mut self) {
         unsafe {
             let vk = self.instance.pointers();
-            vk.DestroySurfaceKHR(self.instance.internal_object(), self.surface, ptr::null());
+            vk.DestroySurfaceKHR(self.instance.internal_object(),
+                                 self.surface,
+                                 self.instance.allocator_ptr());
         }
     }
 }
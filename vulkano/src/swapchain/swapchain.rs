@@ -24,6 +24,7 @@ use command_buffer::submit::SubmitSemaphoresWaitBuilder;
 use device::Device;
 use device::DeviceOwned;
 use device::Queue;
+use device::WaitIdleError;
 use format::Format;
 use format::FormatDesc;
 use image::ImageAccess;
@@ -45,6 +46,7 @@ use sync::AccessCheckError;
 use sync::AccessError;
 use sync::AccessFlagBits;
 use sync::Fence;
+use sync::FenceWaitError;
 use sync::FlushError;
 use sync::GpuFuture;
 use sync::PipelineStages;
@@ -90,6 +92,7 @@ pub fn acquire_next_image<W>(swapchain: Arc<Swapchain<W>>, timeout: Option<Durat
             semaphore: Some(semaphore),
             fence: Some(fence),
             image_id: id,
+            wait_built: AtomicBool::new(false),
             finished: AtomicBool::new(false),
         }))
 }
@@ -250,6 +253,12 @@ impl <W> Swapchain<W> {
     pub fn recreate_with_dimension(
         &self, dimensions: [u32; 2])
         -> Result<(Arc<Swapchain<W>>, Vec<Arc<SwapchainImage<W>>>), SwapchainCreationError> {
+        instrument_event!(swapchain = ::instrument::debug_name(&self.device,
+                                                               vk::OBJECT_TYPE_SWAPCHAIN_KHR,
+                                                               self.swapchain),
+                          width = dimensions[0],
+                          height = dimensions[1],
+                          "recreating the swapchain");
         Swapchain::new_inner(self.device.clone(),
                              self.surface.clone(),
                              self.num_images,
@@ -399,10 +408,14 @@ impl <W> Swapchain<W> {
                 },
             };
 
+            let _span = instrument_span!("vkCreateSwapchainKHR",
+                                         width = dimensions[0],
+                                         height = dimensions[1],
+                                         images = num_images);
             let mut output = mem::uninitialized();
             check_errors(vk.CreateSwapchainKHR(device.internal_object(),
                                                &infos,
-                                               ptr::null(),
+                                               device.allocator_ptr(),
                                                &mut output))?;
             output
         };
@@ -586,7 +599,10 @@ impl<W> Drop for Swapchain<W> {
     fn drop(&mut self) {
         unsafe {
             let vk = self.device.pointers();
-            vk.DestroySwapchainKHR(self.device.internal_object(), self.swapchain, ptr::null());
+            self.device.forget_debug_name(vk::OBJECT_TYPE_SWAPCHAIN_KHR, self.swapchain);
+            vk.DestroySwapchainKHR(self.device.internal_object(),
+                                   self.swapchain,
+                                   self.device.allocator_ptr());
             self.surface.flag().store(false, Ordering::Release);
         }
     }
@@ -757,6 +773,8 @@ pub struct SwapchainAcquireFuture<W> {
     // Fence that is signalled when the acquire is complete. Empty if the acquire has already
     // happened.
     fence: Option<Fence>,
+    // True if a submission that waits on the semaphore has been built by the next future.
+    wait_built: AtomicBool,
     finished: AtomicBool,
 }
 
@@ -784,6 +802,7 @@ unsafe impl<W> GpuFuture for SwapchainAcquireFuture<W> {
         if let Some(ref semaphore) = self.semaphore {
             let mut sem = SubmitSemaphoresWaitBuilder::new();
             sem.add_wait_semaphore(&semaphore);
+            self.wait_built.store(true, Ordering::SeqCst);
             Ok(SubmitAnyBuilder::SemaphoresWait(sem))
         } else {
             Ok(SubmitAnyBuilder::Empty)
@@ -854,9 +873,21 @@ unsafe impl<W> DeviceOwned for SwapchainAcquireFuture<W> {
 
 impl<W> Drop for SwapchainAcquireFuture<W> {
     fn drop(&mut self) {
+        // The semaphore stays signaled if nothing waited upon it, and can't be reused.
+        if !*self.wait_built.get_mut() {
+            if let Some(ref mut semaphore) = self.semaphore {
+                semaphore.forbid_recycling();
+            }
+        }
+
         if !*self.finished.get_mut() {
             if let Some(ref fence) = self.fence {
-                fence.wait(None).unwrap(); // TODO: handle error?
+                match fence.wait(None) {
+                    // If the device is lost, the image will never be acquired and the semaphore
+                    // can be destroyed.
+                    Ok(()) | Err(FenceWaitError::DeviceLostError) => (),
+                    Err(err) => panic!("unexpected error: {:?}", err), // TODO: handle error?
+                }
                 self.semaphore = None;
             }
 
@@ -1130,8 +1161,12 @@ impl<P, W> Drop for PresentFuture<P, W>
             if !*self.finished.get_mut() {
                 match self.flush() {
                     Ok(()) => {
-                        // Block until the queue finished.
-                        self.queue().unwrap().wait().unwrap();
+                        // Block until the queue finished. If the device is lost, nothing
+                        // executes anymore and the resources can be freed.
+                        match self.queue().unwrap().wait() {
+                            Ok(()) | Err(WaitIdleError::DeviceLost) => (),
+                            Err(err) => panic!("unexpected error: {:?}", err),
+                        }
                         self.previous.signal_finished();
                     },
                     Err(_) => {
@@ -1177,7 +1212,8 @@ pub unsafe fn acquire_next_image_raw<W>(swapchain: &Swapchain<W>, timeout: Optio
                                             timeout_ns,
                                             semaphore.map(|s| s.internal_object()).unwrap_or(0),
                                             fence.map(|f| f.internal_object()).unwrap_or(0),
-                                            &mut out))?;
+                                            &mut out));
+    let r = swapchain.device.check_lost(r)?;
 
     let (id, suboptimal) = match r {
         Success::Success => (out as usize, false),
@@ -0,0 +1,171 @@
+// Copyright (c) 2016 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+//! Creating surfaces from winit windows.
+//!
+//! This module is only available if the `winit` feature is enabled. It replaces the
+//! `vulkano-win` crate for applications that only need to draw on a winit window.
+//!
+//! ```no_run
+//! # extern crate vulkano;
+//! # extern crate winit;
+//! # fn main() {
+//! use vulkano::instance::Instance;
+//! use vulkano::swapchain::Surface;
+//!
+//! let extensions = vulkano::swapchain::winit::required_extensions();
+//! let instance = Instance::new(None, &extensions, None).unwrap();
+//!
+//! let events_loop = winit::EventsLoop::new();
+//! let window = winit::WindowBuilder::new().build(&events_loop).unwrap();
+//! let surface = Surface::from_winit(instance.clone(), window).unwrap();
+//! # }
+//! ```
+
+#[cfg(target_os = "macos")]
+use std::mem;
+#[cfg(target_os = "windows")]
+use std::ptr;
+use std::sync::Arc;
+
+#[cfg(target_os = "macos")]
+use cocoa::appkit::{NSView, NSWindow};
+#[cfg(target_os = "macos")]
+use cocoa::base::id as cocoa_id;
+#[cfg(target_os = "macos")]
+use metal::CoreAnimationLayer;
+#[cfg(target_os = "macos")]
+use objc::runtime::YES;
+use winit::Window;
+
+use instance::Instance;
+use instance::InstanceExtensions;
+use swapchain::Surface;
+use swapchain::SurfaceCreationError;
+
+/// Returns the instance extensions that are needed to create a surface from a winit window,
+/// restricted to the ones that are supported by the Vulkan implementation.
+///
+/// Pass them to `Instance::new` before calling `Surface::from_winit`.
+pub fn required_extensions() -> InstanceExtensions {
+    let ideal = InstanceExtensions {
+        khr_surface: true,
+        khr_xlib_surface: true,
+        khr_xcb_surface: true,
+        khr_wayland_surface: true,
+        khr_mir_surface: true,
+        khr_android_surface: true,
+        khr_win32_surface: true,
+        mvk_ios_surface: true,
+        mvk_macos_surface: true,
+        ..InstanceExtensions::none()
+    };
+
+    match InstanceExtensions::supported_by_core() {
+        Ok(supported) => supported.intersection(&ideal),
+        Err(_) => InstanceExtensions::none(),
+    }
+}
+
+impl Surface<Window> {
+    /// Creates a `Surface` from a winit window.
+    ///
+    /// The surface takes ownership of the window, which stays alive for as long as the surface
+    /// exists and can be accessed with `window()`. The extension matching the platform of the
+    /// window must have been enabled on the instance, which is the case if the instance was
+    /// created with the extensions returned by `required_extensions()`.
+    ///
+    /// On Linux, Wayland is used if the window is a Wayland window. Otherwise Xlib is used if
+    /// `VK_KHR_xlib_surface` is enabled, and XCB if it isn't.
+    pub fn from_winit(instance: Arc<Instance>, window: Window)
+                      -> Result<Arc<Surface<Window>>, SurfaceCreationError> {
+        unsafe { winit_to_surface(instance, window) }
+    }
+}
+
+#[cfg(target_os = "android")]
+unsafe fn winit_to_surface(instance: Arc<Instance>, win: Window)
+                           -> Result<Arc<Surface<Window>>, SurfaceCreationError> {
+    use winit::os::android::WindowExt;
+    Surface::from_anativewindow(instance, win.get_native_window(), win)
+}
+
+#[cfg(all(unix, not(target_os = "android"), not(target_os = "macos")))]
+unsafe fn winit_to_surface(instance: Arc<Instance>, win: Window)
+                           -> Result<Arc<Surface<Window>>, SurfaceCreationError> {
+    use winit::os::unix::WindowExt;
+    match (win.get_wayland_display(), win.get_wayland_surface()) {
+        (Some(display), Some(surface)) => Surface::from_wayland(instance,
+                                                                display,
+                                                                surface,
+                                                                win),
+        _ => {
+            // No wayland display found, check if we can use xlib.
+            // If not, we use xcb.
+            if instance.loaded_extensions().khr_xlib_surface {
+                Surface::from_xlib(instance,
+                                   win.get_xlib_display().unwrap(),
+                                   win.get_xlib_window().unwrap() as _,
+                                   win)
+            } else {
+                Surface::from_xcb(instance,
+                                  win.get_xcb_connection().unwrap(),
+                                  win.get_xlib_window().unwrap() as _,
+                                  win)
+            }
+        },
+    }
+}
+
+#[cfg(target_os = "windows")]
+unsafe fn winit_to_surface(instance: Arc<Instance>, win: Window)
+                           -> Result<Arc<Surface<Window>>, SurfaceCreationError> {
+    use winit::os::windows::WindowExt;
+    Surface::from_hwnd(instance,
+                       ptr::null() as *const (), // FIXME
+                       win.get_hwnd(),
+                       win)
+}
+
+#[cfg(target_os = "macos")]
+unsafe fn winit_to_surface(instance: Arc<Instance>, win: Window)
+                           -> Result<Arc<Surface<Window>>, SurfaceCreationError> {
+    use winit::os::macos::WindowExt;
+
+    let wnd: cocoa_id = mem::transmute(win.get_nswindow());
+
+    let layer = CoreAnimationLayer::new();
+
+    layer.set_edge_antialiasing_mask(0);
+    layer.set_presents_with_transaction(false);
+    layer.remove_all_animations();
+
+    let view = wnd.contentView();
+
+    layer.set_contents_scale(view.backingScaleFactor());
+    view.setLayer(mem::transmute(layer.as_ref())); // Bombs here with out of memory
+    view.setWantsLayer(YES);
+
+    Surface::from_macos_moltenvk(instance, win.get_nsview() as *const (), win)
+}
+
+#[cfg(test)]
+mod tests {
+    use instance::InstanceExtensions;
+
+    #[test]
+    fn required_extensions_supported() {
+        let required = super::required_extensions();
+        let supported = match InstanceExtensions::supported_by_core() {
+            Ok(s) => s,
+            Err(_) => return,
+        };
+        assert_eq!(required.intersection(&supported), required);
+    }
+}
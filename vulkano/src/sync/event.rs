@@ -8,7 +8,6 @@
 // according to those terms.
 
 use std::mem;
-use std::ptr;
 use std::sync::Arc;
 
 use OomError;
@@ -82,7 +81,7 @@ impl Event {
             let vk = device.pointers();
             check_errors(vk.CreateEvent(device.internal_object(),
                                         &INFOS,
-                                        ptr::null(),
+                                        device.allocator_ptr(),
                                         &mut output))?;
             output
         };
@@ -181,7 +180,9 @@ impl Drop for Event {
                 self.device.event_pool().lock().unwrap().push(raw_event);
             } else {
                 let vk = self.device.pointers();
-                vk.DestroyEvent(self.device.internal_object(), self.event, ptr::null());
+                vk.DestroyEvent(self.device.internal_object(),
+                                self.event,
+                                self.device.allocator_ptr());
             }
         }
     }
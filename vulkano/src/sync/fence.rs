@@ -58,7 +58,8 @@ impl<D> Fence<D>
     /// Upon `drop`, the fence is put back into the pool.
     ///
     /// For most applications, using the fence pool should be preferred,
-    /// in order to avoid creating new fences every frame.
+    /// in order to avoid creating new fences every frame. The fences that the pool holds can be
+    /// released with `Device::trim_fence_pool`.
     pub fn from_pool(device: D) -> Result<Fence<D>, OomError> {
         let maybe_raw_fence = device.fence_pool().lock().unwrap().pop();
         match maybe_raw_fence {
@@ -110,7 +111,7 @@ impl<D> Fence<D>
             let mut output = mem::uninitialized();
             check_errors(vk.CreateFence(device.internal_object(),
                                         &infos,
-                                        ptr::null(),
+                                        device.allocator_ptr(),
                                         &mut output))?;
             output
         };
@@ -123,17 +124,39 @@ impl<D> Fence<D>
            })
     }
 
+    /// Builds a `Fence` from a raw fence handle, for example one that was created by another
+    /// library.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid fence that was created from `device`, with the allocation
+    ///   callbacks of `device` if it has any.
+    /// - The `Fence` takes ownership of `handle` and destroys it when it is dropped.
+    ///
+    #[inline]
+    pub unsafe fn from_raw(device: D, handle: u64) -> Fence<D> {
+        Fence {
+            fence: handle,
+            device: device,
+            signaled: AtomicBool::new(false),
+            must_put_in_pool: false,
+        }
+    }
+
     /// Returns true if the fence is signaled.
+    ///
+    /// This function never returns `FenceWaitError::Timeout`.
     #[inline]
-    pub fn ready(&self) -> Result<bool, OomError> {
+    pub fn ready(&self) -> Result<bool, FenceWaitError> {
         unsafe {
             if self.signaled.load(Ordering::Relaxed) {
                 return Ok(true);
             }
 
             let vk = self.device.pointers();
-            let result = check_errors(vk.GetFenceStatus(self.device.internal_object(),
-                                                        self.fence))?;
+            let result = self.device
+                .check_lost(check_errors(vk.GetFenceStatus(self.device.internal_object(),
+                                                           self.fence)))?;
             match result {
                 Success::Success => {
                     self.signaled.store(true, Ordering::Relaxed);
@@ -166,11 +189,17 @@ impl<D> Fence<D>
             };
 
             let vk = self.device.pointers();
-            let r = check_errors(vk.WaitForFences(self.device.internal_object(),
-                                                  1,
-                                                  &self.fence,
-                                                  vk::TRUE,
-                                                  timeout_ns))?;
+            let _span = instrument_span!("vkWaitForFences",
+                                         fence = ::instrument::debug_name(&self.device,
+                                                                          vk::OBJECT_TYPE_FENCE,
+                                                                          self.fence),
+                                         timeout_ns = timeout_ns);
+            let r = self.device
+                .check_lost(check_errors(vk.WaitForFences(self.device.internal_object(),
+                                                          1,
+                                                          &self.fence,
+                                                          vk::TRUE,
+                                                          timeout_ns)))?;
 
             match r {
                 Success::Success => {
@@ -226,11 +255,14 @@ impl<D> Fence<D>
         let r = if let Some(device) = device {
             unsafe {
                 let vk = device.pointers();
-                check_errors(vk.WaitForFences(device.internal_object(),
-                                              fences.len() as u32,
-                                              fences.as_ptr(),
-                                              vk::TRUE,
-                                              timeout_ns))?
+                let _span = instrument_span!("vkWaitForFences",
+                                             count = fences.len(),
+                                             timeout_ns = timeout_ns);
+                device.check_lost(check_errors(vk.WaitForFences(device.internal_object(),
+                                                                fences.len() as u32,
+                                                                fences.as_ptr(),
+                                                                vk::TRUE,
+                                                                timeout_ns)))?
             }
         } else {
             return Ok(());
@@ -326,7 +358,10 @@ impl<D> Drop for Fence<D>
                 self.device.fence_pool().lock().unwrap().push(raw_fence);
             } else {
                 let vk = self.device.pointers();
-                vk.DestroyFence(self.device.internal_object(), self.fence, ptr::null());
+                self.device.forget_debug_name(vk::OBJECT_TYPE_FENCE, self.fence);
+                vk.DestroyFence(self.device.internal_object(),
+                                self.fence,
+                                self.device.allocator_ptr());
             }
         }
     }
@@ -472,4 +507,20 @@ mod tests {
         assert_eq!(device.fence_pool().lock().unwrap().len(), 0);
         assert_eq!(fence2.internal_object(), fence1_internal_obj);
     }
+
+    #[test]
+    fn fence_pool_trim() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        {
+            let _fence1 = Fence::from_pool(device.clone()).unwrap();
+            let _fence2 = Fence::from_pool(device.clone()).unwrap();
+        }
+
+        assert_eq!(device.fence_pool_len(), 2);
+        device.trim_fence_pool(1);
+        assert_eq!(device.fence_pool_len(), 1);
+        device.trim_fence_pool(4);
+        assert_eq!(device.fence_pool_len(), 1);
+    }
 }
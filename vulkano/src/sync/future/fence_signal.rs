@@ -16,6 +16,7 @@ use std::time::Duration;
 use buffer::BufferAccess;
 use command_buffer::submit::SubmitAnyBuilder;
 use command_buffer::submit::SubmitCommandBufferBuilder;
+use device::CollectFinished;
 use device::Device;
 use device::DeviceOwned;
 use device::Queue;
@@ -24,9 +25,11 @@ use image::ImageLayout;
 use sync::AccessCheckError;
 use sync::AccessFlagBits;
 use sync::Fence;
+use sync::FenceWaitError;
 use sync::FlushError;
 use sync::GpuFuture;
 use sync::PipelineStages;
+use sync::future::wait_error_on_drop;
 
 /// Builds a new fence signal future.
 #[inline]
@@ -40,7 +43,7 @@ pub fn then_signal_fence<F>(future: F, behavior: FenceSignalFutureBehavior) -> F
     let fence = Fence::from_pool(device.clone()).unwrap();
     FenceSignalFuture {
         device: device,
-        state: Mutex::new(FenceSignalFutureState::Pending(future, fence)),
+        state: Arc::new(Mutex::new(FenceSignalFutureState::Pending(future, fence))),
         behavior: behavior,
     }
 }
@@ -92,8 +95,9 @@ pub enum FenceSignalFutureBehavior {
 pub struct FenceSignalFuture<F>
     where F: GpuFuture
 {
-    // Current state. See the docs of `FenceSignalFutureState`.
-    state: Mutex<FenceSignalFutureState<F>>,
+    // Current state. See the docs of `FenceSignalFutureState`. Shared with the device if the
+    // future is registered with `enable_collection`.
+    state: Arc<Mutex<FenceSignalFutureState<F>>>,
     // The device of the future.
     device: Arc<Device>,
     behavior: FenceSignalFutureBehavior,
@@ -123,6 +127,49 @@ enum FenceSignalFutureState<F> {
     Poisonned,
 }
 
+// Calls `signal_finished()` on the previous future and drops it if the fence is signaled.
+fn cleanup_state<F>(state: &Mutex<FenceSignalFutureState<F>>)
+    where F: GpuFuture
+{
+    let mut state = state.lock().unwrap();
+
+    match *state {
+        FenceSignalFutureState::Flushed(ref mut prev, ref fence) => {
+            match fence.wait(Some(Duration::from_secs(0))) {
+                Ok(()) => unsafe {
+                    prev.signal_finished()
+                },
+                Err(_) => {
+                    prev.cleanup_finished();
+                    return;
+                },
+            }
+        },
+        FenceSignalFutureState::Pending(ref mut prev, _) => {
+            prev.cleanup_finished();
+            return;
+        },
+        FenceSignalFutureState::PartiallyFlushed(ref mut prev, _) => {
+            prev.cleanup_finished();
+            return;
+        },
+        _ => return,
+    };
+
+    // This code can only be reached if we're already flushed and waiting on the fence
+    // succeeded.
+    *state = FenceSignalFutureState::Cleaned;
+}
+
+impl<F> CollectFinished for Mutex<FenceSignalFutureState<F>>
+    where F: GpuFuture + Send
+{
+    #[inline]
+    fn collect_finished(&self) {
+        cleanup_state(self)
+    }
+}
+
 impl<F> FenceSignalFuture<F>
     where F: GpuFuture
 {
@@ -133,7 +180,8 @@ impl<F> FenceSignalFuture<F>
     /// the specified timeout has elapsed and an error will be returned.
     ///
     /// If the wait is successful, this function also cleans any resource locked by previous
-    /// submissions.
+    /// submissions. If the timeout elapses, the future stays untouched and can be waited upon
+    /// again later.
     pub fn wait(&self, timeout: Option<Duration>) -> Result<(), FlushError> {
         let mut state = self.state.lock().unwrap();
 
@@ -141,53 +189,76 @@ impl<F> FenceSignalFuture<F>
 
         match mem::replace(&mut *state, FenceSignalFutureState::Cleaned) {
             FenceSignalFutureState::Flushed(previous, fence) => {
-                fence.wait(timeout)?;
-                unsafe {
-                    previous.signal_finished();
+                match fence.wait(timeout) {
+                    Ok(()) => {
+                        unsafe {
+                            previous.signal_finished();
+                        }
+                        Ok(())
+                    },
+                    Err(err) => {
+                        *state = FenceSignalFutureState::Flushed(previous, fence);
+                        Err(err.into())
+                    },
                 }
-                Ok(())
             },
             FenceSignalFutureState::Cleaned => Ok(()),
             _ => unreachable!(),
         }
     }
+
+    /// Returns true if the fence has been signaled by the GPU, without blocking the current
+    /// thread. Performs a flush if necessary.
+    ///
+    /// This is typically called once per frame in order to skip the cleanup of resources while
+    /// the GPU is still busy. If the fence is signaled, this function also cleans any resource
+    /// locked by previous submissions, just like a successful `wait`.
+    pub fn is_signaled(&self) -> Result<bool, FlushError> {
+        let mut state = self.state.lock().unwrap();
+
+        self.flush_impl(&mut state)?;
+
+        match mem::replace(&mut *state, FenceSignalFutureState::Cleaned) {
+            FenceSignalFutureState::Flushed(previous, fence) => {
+                match fence.ready() {
+                    Ok(true) => {
+                        unsafe {
+                            previous.signal_finished();
+                        }
+                        Ok(true)
+                    },
+                    result => {
+                        *state = FenceSignalFutureState::Flushed(previous, fence);
+                        result.map_err(From::from)
+                    },
+                }
+            },
+            FenceSignalFutureState::Cleaned => Ok(true),
+            _ => unreachable!(),
+        }
+    }
 }
 
 impl<F> FenceSignalFuture<F>
     where F: GpuFuture
 {
+    /// Registers the future with its device, so that `Device::collect_finished` releases the
+    /// previous futures and the resources they hold once the fence is signaled.
+    ///
+    /// This is useful if the future is kept alive for a long time, for example in order to
+    /// chain the next frame after it, and you don't want to call `cleanup_finished()` yourself.
+    pub fn enable_collection(&self)
+        where F: Send + 'static
+    {
+        let state: Arc<CollectFinished> = self.state.clone();
+        self.device.register_collection(Arc::downgrade(&state));
+    }
+
     // Implementation of `cleanup_finished`, but takes a `&self` instead of a `&mut self`.
     // This is an external function so that we can also call it from an `Arc<FenceSignalFuture>`.
     #[inline]
     fn cleanup_finished_impl(&self) {
-        let mut state = self.state.lock().unwrap();
-
-        match *state {
-            FenceSignalFutureState::Flushed(ref mut prev, ref fence) => {
-                match fence.wait(Some(Duration::from_secs(0))) {
-                    Ok(()) => unsafe {
-                        prev.signal_finished()
-                    },
-                    Err(_) => {
-                        prev.cleanup_finished();
-                        return;
-                    },
-                }
-            },
-            FenceSignalFutureState::Pending(ref mut prev, _) => {
-                prev.cleanup_finished();
-                return;
-            },
-            FenceSignalFutureState::PartiallyFlushed(ref mut prev, _) => {
-                prev.cleanup_finished();
-                return;
-            },
-            _ => return,
-        };
-
-        // This code can only be reached if we're already flushed and waiting on the fence
-        // succeeded.
-        *state = FenceSignalFutureState::Cleaned;
+        cleanup_state(&self.state)
     }
 
     // Implementation of `flush`. You must lock the state and pass the mutex guard here.
@@ -433,8 +504,12 @@ impl<F> Drop for FenceSignalFuture<F>
         match mem::replace(&mut *state, FenceSignalFutureState::Cleaned) {
             FenceSignalFutureState::Flushed(previous, fence) => {
                 // This is a normal situation. Submitting worked.
-                // TODO: handle errors?
-                fence.wait(None).unwrap();
+                match fence.wait(None) {
+                    // If the device is lost, nothing executes anymore and the resources can be
+                    // freed.
+                    Ok(()) | Err(FenceWaitError::DeviceLostError) => (),
+                    Err(err) => wait_error_on_drop(err),
+                }
                 unsafe {
                     previous.signal_finished();
                 }
@@ -503,3 +578,62 @@ unsafe impl<F> GpuFuture for Arc<FenceSignalFuture<F>>
         (**self).check_image_access(image, layout, exclusive, queue)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::Duration;
+
+    use buffer::BufferUsage;
+    use buffer::CpuAccessibleBuffer;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
+    use sync::GpuFuture;
+
+    #[test]
+    fn poll_until_signaled() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb = AutoCommandBufferBuilder::primary_one_time_submit(device, queue.family())
+            .unwrap()
+            .build()
+            .unwrap();
+        let future = cb.execute(queue)
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+
+        while !future.is_signaled().unwrap() {
+            thread::sleep(Duration::from_millis(1));
+        }
+
+        future.wait(Some(Duration::from_secs(0))).unwrap();
+    }
+
+    #[test]
+    fn collect_finished() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let buffer = CpuAccessibleBuffer::from_data(device.clone(), BufferUsage::all(), 0u32)
+            .unwrap();
+        let cb = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family())
+            .unwrap()
+            .fill_buffer(buffer.clone(), 0)
+            .unwrap()
+            .build()
+            .unwrap();
+        let future = cb.execute(queue.clone())
+            .unwrap()
+            .then_signal_fence_and_flush()
+            .unwrap();
+        future.enable_collection();
+
+        queue.wait().unwrap();
+        device.collect_finished();
+
+        // The command buffer and its buffer have been released, even though the future is alive.
+        assert_eq!(Arc::strong_count(&buffer), 1);
+        future.wait(None).unwrap();
+    }
+}
@@ -85,81 +85,9 @@ unsafe impl<A, B> GpuFuture for JoinFuture<A, B>
 
     #[inline]
     unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
-        // TODO: review this function
-
         let first = self.first.build_submission()?;
         let second = self.second.build_submission()?;
-
-        Ok(match (first, second) {
-               (SubmitAnyBuilder::Empty, b) => b,
-               (a, SubmitAnyBuilder::Empty) => a,
-               (SubmitAnyBuilder::SemaphoresWait(mut a), SubmitAnyBuilder::SemaphoresWait(b)) => {
-                   a.merge(b);
-                   SubmitAnyBuilder::SemaphoresWait(a)
-               },
-               (SubmitAnyBuilder::SemaphoresWait(a), SubmitAnyBuilder::CommandBuffer(b)) => {
-                   b.submit(&self.second.queue().clone().unwrap())?;
-                   SubmitAnyBuilder::SemaphoresWait(a)
-               },
-               (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::SemaphoresWait(b)) => {
-                   a.submit(&self.first.queue().clone().unwrap())?;
-                   SubmitAnyBuilder::SemaphoresWait(b)
-               },
-               (SubmitAnyBuilder::SemaphoresWait(a), SubmitAnyBuilder::QueuePresent(b)) => {
-                   b.submit(&self.second.queue().clone().unwrap())?;
-                   SubmitAnyBuilder::SemaphoresWait(a)
-               },
-               (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::SemaphoresWait(b)) => {
-                   a.submit(&self.first.queue().clone().unwrap())?;
-                   SubmitAnyBuilder::SemaphoresWait(b)
-               },
-               (SubmitAnyBuilder::SemaphoresWait(a), SubmitAnyBuilder::BindSparse(b)) => {
-                   b.submit(&self.second.queue().clone().unwrap())?;
-                   SubmitAnyBuilder::SemaphoresWait(a)
-               },
-               (SubmitAnyBuilder::BindSparse(a), SubmitAnyBuilder::SemaphoresWait(b)) => {
-                   a.submit(&self.first.queue().clone().unwrap())?;
-                   SubmitAnyBuilder::SemaphoresWait(b)
-               },
-               (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::CommandBuffer(b)) => {
-                   // TODO: we may want to add debug asserts here
-                   let new = a.merge(b);
-                   SubmitAnyBuilder::CommandBuffer(new)
-               },
-               (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::QueuePresent(b)) => {
-                   a.submit(&self.first.queue().clone().unwrap())?;
-                   b.submit(&self.second.queue().clone().unwrap())?;
-                   SubmitAnyBuilder::Empty
-               },
-               (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::QueuePresent(b)) => {
-                   unimplemented!()
-               },
-               (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::CommandBuffer(b)) => {
-                   unimplemented!()
-               },
-               (SubmitAnyBuilder::BindSparse(a), SubmitAnyBuilder::QueuePresent(b)) => {
-                   unimplemented!()
-               },
-               (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::BindSparse(b)) => {
-                   unimplemented!()
-               },
-               (SubmitAnyBuilder::BindSparse(a), SubmitAnyBuilder::CommandBuffer(b)) => {
-                   unimplemented!()
-               },
-               (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::BindSparse(b)) => {
-                   unimplemented!()
-               },
-               (SubmitAnyBuilder::BindSparse(mut a), SubmitAnyBuilder::BindSparse(b)) => {
-                   match a.merge(b) {
-                       Ok(()) => SubmitAnyBuilder::BindSparse(a),
-                       Err(_) => {
-                           // TODO: this happens if both bind sparse have been given a fence already
-                           //       annoying, but not impossible, to handle
-                           unimplemented!()
-                       },
-                   }
-               },
-           })
+        merge_submissions(first, || self.first.queue(), second, || self.second.queue())
     }
 
     #[inline]
@@ -175,20 +103,10 @@ unsafe impl<A, B> GpuFuture for JoinFuture<A, B>
 
     #[inline]
     fn queue(&self) -> Option<Arc<Queue>> {
-        match (self.first.queue(), self.second.queue()) {
-            (Some(q1), Some(q2)) => if q1.is_same(&q2) {
-                Some(q1)
-            } else if self.first.queue_change_allowed() {
-                Some(q2)
-            } else if self.second.queue_change_allowed() {
-                Some(q1)
-            } else {
-                None
-            },
-            (Some(q), None) => Some(q),
-            (None, Some(q)) => Some(q),
-            (None, None) => None,
-        }
+        merge_queues(self.first.queue(),
+                     self.first.queue_change_allowed(),
+                     self.second.queue(),
+                     self.second.queue_change_allowed())
     }
 
     #[inline]
@@ -197,23 +115,7 @@ unsafe impl<A, B> GpuFuture for JoinFuture<A, B>
         -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
         let first = self.first.check_buffer_access(buffer, exclusive, queue);
         let second = self.second.check_buffer_access(buffer, exclusive, queue);
-        debug_assert!(!exclusive || !(first.is_ok() && second.is_ok()),
-                      "Two futures gave exclusive access to the same resource");
-        match (first, second) {
-            (v, Err(AccessCheckError::Unknown)) => v,
-            (Err(AccessCheckError::Unknown), v) => v,
-            (Err(AccessCheckError::Denied(e1)), Err(AccessCheckError::Denied(e2))) =>
-                Err(AccessCheckError::Denied(e1)),        // TODO: which one?
-            (Ok(_), Err(AccessCheckError::Denied(_))) |
-            (Err(AccessCheckError::Denied(_)), Ok(_)) => panic!("Contradictory information \
-                                                                 between two futures"),
-            (Ok(None), Ok(None)) => Ok(None),
-            (Ok(Some(a)), Ok(None)) |
-            (Ok(None), Ok(Some(a))) => Ok(Some(a)),
-            (Ok(Some((a1, a2))), Ok(Some((b1, b2)))) => {
-                Ok(Some((a1 | b1, a2 | b2)))
-            },
-        }
+        merge_accesses(first, second, exclusive)
     }
 
     #[inline]
@@ -224,22 +126,306 @@ unsafe impl<A, B> GpuFuture for JoinFuture<A, B>
             .check_image_access(image, layout, exclusive, queue);
         let second = self.second
             .check_image_access(image, layout, exclusive, queue);
-        debug_assert!(!exclusive || !(first.is_ok() && second.is_ok()),
-                      "Two futures gave exclusive access to the same resource");
-        match (first, second) {
-            (v, Err(AccessCheckError::Unknown)) => v,
-            (Err(AccessCheckError::Unknown), v) => v,
-            (Err(AccessCheckError::Denied(e1)), Err(AccessCheckError::Denied(e2))) =>
-                Err(AccessCheckError::Denied(e1)),        // TODO: which one?
-            (Ok(_), Err(AccessCheckError::Denied(_))) |
-            (Err(AccessCheckError::Denied(_)), Ok(_)) => panic!("Contradictory information \
-                                                                 between two futures"),
-            (Ok(None), Ok(None)) => Ok(None),
-            (Ok(Some(a)), Ok(None)) |
-            (Ok(None), Ok(Some(a))) => Ok(Some(a)),
-            (Ok(Some((a1, a2))), Ok(Some((b1, b2)))) => {
-                Ok(Some((a1 | b1, a2 | b2)))
-            },
+        merge_accesses(first, second, exclusive)
+    }
+}
+
+/// Joins any number of futures together.
+///
+/// Contrary to calling `join` repeatedly, the returned future has the same type whatever the
+/// number of futures, and its submissions are merged in a loop instead of recursively. The
+/// futures must have the same type, which can be `Box<GpuFuture>` if needed.
+///
+/// # Panic
+///
+/// - Panics if `futures` is empty.
+/// - Panics if the futures don't belong to the same device.
+///
+pub fn join_all<I>(futures: I) -> JoinAllFuture<I::Item>
+    where I: IntoIterator,
+          I::Item: GpuFuture
+{
+    let futures = futures.into_iter().collect::<Vec<_>>();
+    assert!(!futures.is_empty());
+
+    let device = futures[0].device().internal_object();
+    for future in futures.iter() {
+        assert_eq!(future.device().internal_object(), device);
+    }
+
+    let queue_change_allowed = futures.iter().all(|f| f.queue_change_allowed());
+    if !queue_change_allowed {
+        let mut queues = futures.iter().filter(|f| !f.queue_change_allowed());
+        let first = queues.next().unwrap().queue().unwrap();
+        for future in queues {
+            assert!(first.is_same(&future.queue().unwrap()));
+        }
+    }
+
+    JoinAllFuture { futures: futures }
+}
+
+/// Any number of futures joined into one.
+#[must_use]
+pub struct JoinAllFuture<F> {
+    futures: Vec<F>,
+}
+
+impl<F> JoinAllFuture<F> {
+    /// Returns the futures that were joined.
+    #[inline]
+    pub fn futures(&self) -> &[F] {
+        &self.futures
+    }
+}
+
+unsafe impl<F> DeviceOwned for JoinAllFuture<F>
+    where F: DeviceOwned
+{
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.futures[0].device()
+    }
+}
+
+unsafe impl<F> GpuFuture for JoinAllFuture<F>
+    where F: GpuFuture
+{
+    #[inline]
+    fn cleanup_finished(&mut self) {
+        for future in self.futures.iter_mut() {
+            future.cleanup_finished();
+        }
+    }
+
+    #[inline]
+    fn flush(&self) -> Result<(), FlushError> {
+        for future in self.futures.iter() {
+            future.flush()?;
+        }
+        Ok(())
+    }
+
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        let mut submission = SubmitAnyBuilder::Empty;
+        let mut queue = None;
+        let mut queue_change_allowed = true;
+
+        for future in self.futures.iter() {
+            let next = future.build_submission()?;
+            submission = merge_submissions(submission, || queue.clone(), next, || future.queue())?;
+            queue = merge_queues(queue,
+                                 queue_change_allowed,
+                                 future.queue(),
+                                 future.queue_change_allowed());
+            queue_change_allowed = queue_change_allowed && future.queue_change_allowed();
         }
+
+        Ok(submission)
+    }
+
+    #[inline]
+    unsafe fn signal_finished(&self) {
+        for future in self.futures.iter() {
+            future.signal_finished();
+        }
+    }
+
+    #[inline]
+    fn queue_change_allowed(&self) -> bool {
+        self.futures.iter().all(|f| f.queue_change_allowed())
+    }
+
+    fn queue(&self) -> Option<Arc<Queue>> {
+        let mut queue = None;
+        let mut queue_change_allowed = true;
+
+        for future in self.futures.iter() {
+            queue = merge_queues(queue,
+                                 queue_change_allowed,
+                                 future.queue(),
+                                 future.queue_change_allowed());
+            queue_change_allowed = queue_change_allowed && future.queue_change_allowed();
+        }
+
+        queue
+    }
+
+    fn check_buffer_access(
+        &self, buffer: &BufferAccess, exclusive: bool, queue: &Queue)
+        -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        self.futures
+            .iter()
+            .fold(Err(AccessCheckError::Unknown), |result, future| {
+                let next = future.check_buffer_access(buffer, exclusive, queue);
+                merge_accesses(result, next, exclusive)
+            })
+    }
+
+    fn check_image_access(&self, image: &ImageAccess, layout: ImageLayout, exclusive: bool,
+                          queue: &Queue)
+                          -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        self.futures
+            .iter()
+            .fold(Err(AccessCheckError::Unknown), |result, future| {
+                let next = future.check_image_access(image, layout, exclusive, queue);
+                merge_accesses(result, next, exclusive)
+            })
+    }
+}
+
+// Merges the submissions of two futures that are joined together. The queues are only used
+// when the submissions can't be merged and one of them has to be submitted immediately.
+unsafe fn merge_submissions<'a, Q1, Q2>(first: SubmitAnyBuilder<'a>, first_queue: Q1,
+                                        second: SubmitAnyBuilder<'a>, second_queue: Q2)
+                                        -> Result<SubmitAnyBuilder<'a>, FlushError>
+    where Q1: FnOnce() -> Option<Arc<Queue>>,
+          Q2: FnOnce() -> Option<Arc<Queue>>
+{
+    // TODO: review this function
+
+    Ok(match (first, second) {
+           (SubmitAnyBuilder::Empty, b) => b,
+           (a, SubmitAnyBuilder::Empty) => a,
+           (SubmitAnyBuilder::SemaphoresWait(mut a), SubmitAnyBuilder::SemaphoresWait(b)) => {
+               a.merge(b);
+               SubmitAnyBuilder::SemaphoresWait(a)
+           },
+           (SubmitAnyBuilder::SemaphoresWait(a), SubmitAnyBuilder::CommandBuffer(b)) => {
+               b.submit(&second_queue().unwrap())?;
+               SubmitAnyBuilder::SemaphoresWait(a)
+           },
+           (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::SemaphoresWait(b)) => {
+               a.submit(&first_queue().unwrap())?;
+               SubmitAnyBuilder::SemaphoresWait(b)
+           },
+           (SubmitAnyBuilder::SemaphoresWait(a), SubmitAnyBuilder::QueuePresent(b)) => {
+               b.submit(&second_queue().unwrap())?;
+               SubmitAnyBuilder::SemaphoresWait(a)
+           },
+           (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::SemaphoresWait(b)) => {
+               a.submit(&first_queue().unwrap())?;
+               SubmitAnyBuilder::SemaphoresWait(b)
+           },
+           (SubmitAnyBuilder::SemaphoresWait(a), SubmitAnyBuilder::BindSparse(b)) => {
+               b.submit(&second_queue().unwrap())?;
+               SubmitAnyBuilder::SemaphoresWait(a)
+           },
+           (SubmitAnyBuilder::BindSparse(a), SubmitAnyBuilder::SemaphoresWait(b)) => {
+               a.submit(&first_queue().unwrap())?;
+               SubmitAnyBuilder::SemaphoresWait(b)
+           },
+           (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::CommandBuffer(b)) => {
+               // TODO: we may want to add debug asserts here
+               let new = a.merge(b);
+               SubmitAnyBuilder::CommandBuffer(new)
+           },
+           (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::QueuePresent(b)) => {
+               a.submit(&first_queue().unwrap())?;
+               b.submit(&second_queue().unwrap())?;
+               SubmitAnyBuilder::Empty
+           },
+           (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::QueuePresent(b)) => {
+               unimplemented!()
+           },
+           (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::CommandBuffer(b)) => {
+               unimplemented!()
+           },
+           (SubmitAnyBuilder::BindSparse(a), SubmitAnyBuilder::QueuePresent(b)) => {
+               unimplemented!()
+           },
+           (SubmitAnyBuilder::QueuePresent(a), SubmitAnyBuilder::BindSparse(b)) => {
+               unimplemented!()
+           },
+           (SubmitAnyBuilder::BindSparse(a), SubmitAnyBuilder::CommandBuffer(b)) => {
+               unimplemented!()
+           },
+           (SubmitAnyBuilder::CommandBuffer(a), SubmitAnyBuilder::BindSparse(b)) => {
+               unimplemented!()
+           },
+           (SubmitAnyBuilder::BindSparse(mut a), SubmitAnyBuilder::BindSparse(b)) => {
+               match a.merge(b) {
+                   Ok(()) => SubmitAnyBuilder::BindSparse(a),
+                   Err(_) => {
+                       // TODO: this happens if both bind sparse have been given a fence already
+                       //       annoying, but not impossible, to handle
+                       unimplemented!()
+                   },
+               }
+           },
+       })
+}
+
+// Returns the queue of two futures that are joined together.
+fn merge_queues(first: Option<Arc<Queue>>, first_change_allowed: bool,
+                second: Option<Arc<Queue>>, second_change_allowed: bool)
+                -> Option<Arc<Queue>> {
+    match (first, second) {
+        (Some(q1), Some(q2)) => if q1.is_same(&q2) {
+            Some(q1)
+        } else if first_change_allowed {
+            Some(q2)
+        } else if second_change_allowed {
+            Some(q1)
+        } else {
+            None
+        },
+        (Some(q), None) => Some(q),
+        (None, Some(q)) => Some(q),
+        (None, None) => None,
+    }
+}
+
+// Merges the results of the access checks of two futures that are joined together.
+fn merge_accesses(first: Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError>,
+                  second: Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError>,
+                  exclusive: bool)
+                  -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+    debug_assert!(!exclusive || !(first.is_ok() && second.is_ok()),
+                  "Two futures gave exclusive access to the same resource");
+    match (first, second) {
+        (v, Err(AccessCheckError::Unknown)) => v,
+        (Err(AccessCheckError::Unknown), v) => v,
+        (Err(AccessCheckError::Denied(e1)), Err(AccessCheckError::Denied(e2))) =>
+            Err(AccessCheckError::Denied(e1)),        // TODO: which one?
+        (Ok(_), Err(AccessCheckError::Denied(_))) |
+        (Err(AccessCheckError::Denied(_)), Ok(_)) => panic!("Contradictory information \
+                                                             between two futures"),
+        (Ok(None), Ok(None)) => Ok(None),
+        (Ok(Some(a)), Ok(None)) |
+        (Ok(None), Ok(Some(a))) => Ok(Some(a)),
+        (Ok(Some((a1, a2))), Ok(Some((b1, b2)))) => {
+            Ok(Some((a1 | b1, a2 | b2)))
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
+    use sync::GpuFuture;
+    use sync::join_all;
+
+    #[test]
+    fn join_all_executions() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let futures = (0 .. 24)
+            .map(|_| {
+                let cb = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(),
+                                                                           queue.family())
+                    .unwrap()
+                    .build()
+                    .unwrap();
+                cb.execute(queue.clone()).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        join_all(futures)
+            .then_signal_fence_and_flush()
+            .unwrap()
+            .wait(None)
+            .unwrap();
     }
 }
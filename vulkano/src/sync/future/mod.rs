@@ -10,6 +10,7 @@
 use std::error;
 use std::fmt;
 use std::sync::Arc;
+use std::thread;
 
 use OomError;
 use buffer::BufferAccess;
@@ -22,6 +23,7 @@ use command_buffer::submit::SubmitCommandBufferError;
 use command_buffer::submit::SubmitPresentError;
 use device::DeviceOwned;
 use device::Queue;
+use device::WaitIdleError;
 use image::ImageAccess;
 use image::ImageLayout;
 use swapchain;
@@ -31,16 +33,25 @@ use swapchain::Swapchain;
 use sync::AccessFlagBits;
 use sync::FenceWaitError;
 use sync::PipelineStages;
+use sync::Semaphore;
+use sync::TimelineSemaphore;
+use sync::TimelineSemaphoreError;
 
 pub use self::fence_signal::{FenceSignalFuture, FenceSignalFutureBehavior};
-pub use self::join::JoinFuture;
+pub use self::join::{JoinAllFuture, JoinFuture, join_all};
 pub use self::now::{NowFuture, now};
 pub use self::semaphore_signal::SemaphoreSignalFuture;
+pub use self::semaphore_wait::{SemaphoreWaitFuture, semaphore_wait};
+pub use self::timeline_semaphore_signal::TimelineSemaphoreSignalFuture;
+pub use self::timeline_semaphore_wait::{TimelineSemaphoreWaitFuture, timeline_semaphore_wait};
 
 mod now;
 mod fence_signal;
 mod join;
 mod semaphore_signal;
+mod semaphore_wait;
+mod timeline_semaphore_signal;
+mod timeline_semaphore_wait;
 
 /// Represents an event that will happen on the GPU in the future.
 ///
@@ -53,6 +64,9 @@ pub unsafe trait GpuFuture: DeviceOwned {
     /// It is highly recommended to call `cleanup_finished` from time to time. Doing so will
     /// prevent memory usage from increasing over time, and will also destroy the locks on
     /// resources used by the GPU.
+    ///
+    /// Alternatively, a `FenceSignalFuture` registered with `enable_collection` is cleaned up
+    /// whenever `Device::collect_finished` is called.
     fn cleanup_finished(&mut self);
 
     /// Builds a submission that, if submitted, makes sure that the event represented by this
@@ -206,6 +220,60 @@ pub unsafe trait GpuFuture: DeviceOwned {
         Ok(f)
     }
 
+    /// Same as `then_signal_semaphore`, but signals `semaphore` instead of a semaphore taken from
+    /// the pool of the device.
+    ///
+    /// This is typically used with a semaphore created with `Semaphore::alloc_exportable`, whose
+    /// payload can be exported with `semaphore()` once the returned future has been flushed in
+    /// order to be waited upon by another API or another process.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the semaphore doesn't belong to the same device as the future.
+    ///
+    #[inline]
+    fn then_signal_external_semaphore(self, semaphore: Semaphore) -> SemaphoreSignalFuture<Self>
+        where Self: Sized
+    {
+        semaphore_signal::then_signal_external_semaphore(self, semaphore)
+    }
+
+    /// Sets the counter of a timeline semaphore to `value` after this future. Returns another
+    /// future that represents the signal.
+    ///
+    /// The operations chained after the returned future wait until the counter reaches `value`,
+    /// on whichever queue they are submitted to. Other parts of the program can wait for the same
+    /// value with `timeline_semaphore_wait` or `TimelineSemaphore::wait`.
+    ///
+    /// `value` must be greater than the current value of the counter and than the values of the
+    /// other signal operations of the semaphore that are pending, otherwise the behavior is
+    /// undefined.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the semaphore and the future don't belong to the same device.
+    ///
+    // TODO: turn the ordering of the values into a runtime check
+    #[inline]
+    fn then_signal_timeline_semaphore(self, semaphore: Arc<TimelineSemaphore>, value: u64)
+                                      -> TimelineSemaphoreSignalFuture<Self>
+        where Self: Sized
+    {
+        timeline_semaphore_signal::then_signal_timeline_semaphore(self, semaphore, value)
+    }
+
+    /// Same as `then_signal_timeline_semaphore`, but flushes the returned future.
+    #[inline]
+    fn then_signal_timeline_semaphore_and_flush(
+        self, semaphore: Arc<TimelineSemaphore>, value: u64)
+        -> Result<TimelineSemaphoreSignalFuture<Self>, FlushError>
+        where Self: Sized
+    {
+        let f = self.then_signal_timeline_semaphore(semaphore, value);
+        f.flush()?;
+        Ok(f)
+    }
+
     /// Signals a fence after this future. Returns another future that represents the signal.
     ///
     /// > **Note**: More often than not you want to immediately flush the future after calling this
@@ -368,6 +436,20 @@ impl fmt::Display for AccessError {
     }
 }
 
+// Reports an error other than a device loss that happened while a future was waiting for the GPU
+// in its destructor. The GPU may still be using the resources that the destructor is about to
+// release, so the error is a panic. If the thread is already panicking, a second panic would
+// abort the program, so the error is printed instead and the destructor continues.
+pub(crate) fn wait_error_on_drop<E>(err: E)
+    where E: fmt::Debug
+{
+    if thread::panicking() {
+        eprintln!("vulkano: failed to wait for a future while dropping it: {:?}", err);
+    } else {
+        panic!("failed to wait for a future while dropping it: {:?}", err);
+    }
+}
+
 /// Error that can happen when checking whether we have access to a resource.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum AccessCheckError {
@@ -498,6 +580,27 @@ impl From<SubmitBindSparseError> for FlushError {
     }
 }
 
+impl From<TimelineSemaphoreError> for FlushError {
+    #[inline]
+    fn from(err: TimelineSemaphoreError) -> FlushError {
+        match err {
+            TimelineSemaphoreError::OomError(err) => FlushError::OomError(err),
+            TimelineSemaphoreError::Timeout => FlushError::Timeout,
+            TimelineSemaphoreError::DeviceLost => FlushError::DeviceLost,
+        }
+    }
+}
+
+impl From<WaitIdleError> for FlushError {
+    #[inline]
+    fn from(err: WaitIdleError) -> FlushError {
+        match err {
+            WaitIdleError::OomError(err) => FlushError::OomError(err),
+            WaitIdleError::DeviceLost => FlushError::DeviceLost,
+        }
+    }
+}
+
 impl From<FenceWaitError> for FlushError {
     #[inline]
     fn from(err: FenceWaitError) -> FlushError {
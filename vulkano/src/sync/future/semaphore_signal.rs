@@ -12,6 +12,7 @@ use std::sync::Mutex;
 use std::sync::atomic::AtomicBool;
 use std::sync::atomic::Ordering;
 
+use VulkanObject;
 use buffer::BufferAccess;
 use command_buffer::submit::SubmitAnyBuilder;
 use command_buffer::submit::SubmitCommandBufferBuilder;
@@ -27,20 +28,31 @@ use sync::FlushError;
 use sync::GpuFuture;
 use sync::PipelineStages;
 use sync::Semaphore;
+use sync::future::wait_error_on_drop;
 
 /// Builds a new semaphore signal future.
 #[inline]
 pub fn then_signal_semaphore<F>(future: F) -> SemaphoreSignalFuture<F>
     where F: GpuFuture
 {
-    let device = future.device().clone();
+    let semaphore = Semaphore::from_pool(future.device().clone()).unwrap();
+    then_signal_external_semaphore(future, semaphore)
+}
 
+/// Builds a new semaphore signal future that signals `semaphore`.
+#[inline]
+pub fn then_signal_external_semaphore<F>(future: F, semaphore: Semaphore)
+                                         -> SemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
     assert!(future.queue().is_some()); // TODO: document
+    assert_eq!(future.device().internal_object(), semaphore.device().internal_object());
 
     SemaphoreSignalFuture {
         previous: future,
-        semaphore: Semaphore::from_pool(device).unwrap(),
+        semaphore: semaphore,
         wait_submitted: Mutex::new(false),
+        wait_built: AtomicBool::new(false),
         finished: AtomicBool::new(false),
     }
 }
@@ -57,9 +69,24 @@ pub struct SemaphoreSignalFuture<F>
     // If flush is called multiple times, we want to block so that only one flushing is executed.
     // Therefore we use a `Mutex<bool>` and not an `AtomicBool`.
     wait_submitted: Mutex<bool>,
+    // True if a submission that waits on the semaphore has been built by the next future.
+    wait_built: AtomicBool,
     finished: AtomicBool,
 }
 
+impl<F> SemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
+    /// Returns the semaphore that is signaled.
+    ///
+    /// If the semaphore was created with `Semaphore::alloc_exportable`, its payload can be
+    /// exported once the future has been flushed.
+    #[inline]
+    pub fn semaphore(&self) -> &Semaphore {
+        &self.semaphore
+    }
+}
+
 unsafe impl<F> GpuFuture for SemaphoreSignalFuture<F>
     where F: GpuFuture
 {
@@ -75,6 +102,7 @@ unsafe impl<F> GpuFuture for SemaphoreSignalFuture<F>
 
         let mut sem = SubmitSemaphoresWaitBuilder::new();
         sem.add_wait_semaphore(&self.semaphore);
+        self.wait_built.store(true, Ordering::SeqCst);
         Ok(SubmitAnyBuilder::SemaphoresWait(sem))
     }
 
@@ -175,12 +203,23 @@ impl<F> Drop for SemaphoreSignalFuture<F>
     fn drop(&mut self) {
         unsafe {
             if !*self.finished.get_mut() {
-                // TODO: handle errors?
-                self.flush().unwrap();
                 // Block until the queue finished.
-                self.queue().unwrap().wait().unwrap();
+                let result = self.flush()
+                    .and_then(|()| self.queue().unwrap().wait().map_err(From::from));
+                match result {
+                    // If the device is lost, nothing executes anymore and the resources can be
+                    // freed.
+                    Ok(()) | Err(FlushError::DeviceLost) => (),
+                    Err(err) => wait_error_on_drop(err),
+                }
                 self.previous.signal_finished();
             }
+
+            // A semaphore that was signaled but never waited upon stays signaled, and can't be
+            // reused.
+            if *self.wait_submitted.get_mut().unwrap() && !*self.wait_built.get_mut() {
+                self.semaphore.forbid_recycling();
+            }
         }
     }
 }
@@ -0,0 +1,123 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+
+use VulkanObject;
+use buffer::BufferAccess;
+use command_buffer::submit::SubmitAnyBuilder;
+use command_buffer::submit::SubmitSemaphoresWaitBuilder;
+use device::Device;
+use device::DeviceOwned;
+use device::Queue;
+use image::ImageAccess;
+use image::ImageLayout;
+use sync::AccessCheckError;
+use sync::AccessFlagBits;
+use sync::FlushError;
+use sync::GpuFuture;
+use sync::PipelineStages;
+use sync::Semaphore;
+
+/// Builds a future that represents the moment when `semaphore` is signaled by someone else than
+/// vulkano, for example by another API or another process that its payload was imported from.
+///
+/// The operations chained after this future wait on the GPU until the semaphore is signaled,
+/// which unsignals it again.
+///
+/// `queue` is the queue that the operations chained after this future are submitted to, unless
+/// they specify another one.
+///
+/// # Safety
+///
+/// - A signal operation of the semaphore must have been submitted, or be submitted before the
+///   operations chained after this future are.
+/// - The future must be used in only one submission, since waiting on the semaphore unsignals it.
+///
+/// # Panic
+///
+/// - Panics if the semaphore and the queue don't belong to the same device.
+///
+#[inline]
+pub unsafe fn semaphore_wait(queue: Arc<Queue>, semaphore: Semaphore) -> SemaphoreWaitFuture {
+    assert_eq!(queue.device().internal_object(),
+               semaphore.device().internal_object());
+
+    SemaphoreWaitFuture { queue, semaphore }
+}
+
+/// Represents the moment when a semaphore that was signaled outside of vulkano's futures is
+/// signaled.
+///
+/// Contrary to most futures, this future doesn't correspond to anything that is submitted. It
+/// can be dropped at any time without blocking.
+pub struct SemaphoreWaitFuture {
+    queue: Arc<Queue>,
+    semaphore: Semaphore,
+}
+
+impl SemaphoreWaitFuture {
+    /// Returns the semaphore that is waited upon.
+    #[inline]
+    pub fn semaphore(&self) -> &Semaphore {
+        &self.semaphore
+    }
+}
+
+unsafe impl GpuFuture for SemaphoreWaitFuture {
+    #[inline]
+    fn cleanup_finished(&mut self) {
+    }
+
+    #[inline]
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        let mut sem = SubmitSemaphoresWaitBuilder::new();
+        sem.add_wait_semaphore(&self.semaphore);
+        Ok(SubmitAnyBuilder::SemaphoresWait(sem))
+    }
+
+    #[inline]
+    fn flush(&self) -> Result<(), FlushError> {
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn signal_finished(&self) {
+    }
+
+    #[inline]
+    fn queue_change_allowed(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn queue(&self) -> Option<Arc<Queue>> {
+        Some(self.queue.clone())
+    }
+
+    #[inline]
+    fn check_buffer_access(
+        &self, _: &BufferAccess, _: bool, _: &Queue)
+        -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    #[inline]
+    fn check_image_access(&self, _: &ImageAccess, _: ImageLayout, _: bool, _: &Queue)
+                          -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+}
+
+unsafe impl DeviceOwned for SemaphoreWaitFuture {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.semaphore.device()
+    }
+}
@@ -0,0 +1,280 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+
+use VulkanObject;
+use buffer::BufferAccess;
+use command_buffer::submit::SubmitAnyBuilder;
+use command_buffer::submit::SubmitCommandBufferBuilder;
+use device::Device;
+use device::DeviceOwned;
+use device::Queue;
+use image::ImageAccess;
+use image::ImageLayout;
+use sync::AccessCheckError;
+use sync::AccessFlagBits;
+use sync::Fence;
+use sync::FlushError;
+use sync::GpuFuture;
+use sync::PipelineStages;
+use sync::TimelineSemaphore;
+use sync::future::wait_error_on_drop;
+
+/// Builds a new timeline semaphore signal future.
+#[inline]
+pub fn then_signal_timeline_semaphore<F>(future: F, semaphore: Arc<TimelineSemaphore>, value: u64)
+                                         -> TimelineSemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
+    assert!(future.queue().is_some()); // TODO: document
+    assert_eq!(future.device().internal_object(), semaphore.device().internal_object());
+
+    TimelineSemaphoreSignalFuture {
+        previous: future,
+        semaphore,
+        value,
+        wait_submitted: Mutex::new(false),
+        bind_sparse_fence: Mutex::new(None),
+        finished: AtomicBool::new(false),
+    }
+}
+
+/// Represents the counter of a timeline semaphore being set to a value after a previous event.
+///
+/// Other submissions that are chained after this future wait until the counter reaches the value,
+/// even if they are on a different queue.
+#[must_use = "Dropping this object will immediately block the thread until the GPU has finished \
+              processing the submission"]
+pub struct TimelineSemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
+    previous: F,
+    semaphore: Arc<TimelineSemaphore>,
+    value: u64,
+    // True if the signaling command has already been submitted.
+    // If flush is called multiple times, we want to block so that only one flushing is executed.
+    // Therefore we use a `Mutex<bool>` and not an `AtomicBool`.
+    wait_submitted: Mutex<bool>,
+    // Fence signaled by the previous bind sparse submission, if any. It must be kept alive until
+    // the counter reaches the value.
+    bind_sparse_fence: Mutex<Option<Fence>>,
+    finished: AtomicBool,
+}
+
+impl<F> TimelineSemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
+    /// Returns the semaphore that is signaled.
+    #[inline]
+    pub fn semaphore(&self) -> &Arc<TimelineSemaphore> {
+        &self.semaphore
+    }
+
+    /// Returns the value that the counter of the semaphore is set to.
+    #[inline]
+    pub fn value(&self) -> u64 {
+        self.value
+    }
+
+    /// Flushes the future if necessary, then blocks the current thread until the counter reaches
+    /// the value or the timeout has elapsed.
+    pub fn wait(&self, timeout: Option<Duration>) -> Result<(), FlushError> {
+        if self.finished.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        self.flush()?;
+        self.semaphore.wait(self.value, timeout)?;
+
+        unsafe {
+            self.signal_finished();
+        }
+        Ok(())
+    }
+
+    /// Flushes the future if necessary, then returns true if the counter has reached the value,
+    /// without blocking the current thread.
+    pub fn is_signaled(&self) -> Result<bool, FlushError> {
+        if self.finished.load(Ordering::SeqCst) {
+            return Ok(true);
+        }
+
+        self.flush()?;
+        if self.semaphore.value()? < self.value {
+            return Ok(false);
+        }
+
+        unsafe {
+            self.signal_finished();
+        }
+        Ok(true)
+    }
+}
+
+unsafe impl<F> GpuFuture for TimelineSemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
+    fn cleanup_finished(&mut self) {
+        if *self.wait_submitted.get_mut().unwrap() && !*self.finished.get_mut() {
+            let reached = self.semaphore
+                .value()
+                .map(|value| value >= self.value)
+                .unwrap_or(false);
+            if reached {
+                unsafe {
+                    self.signal_finished();
+                }
+            }
+        }
+
+        self.previous.cleanup_finished();
+    }
+
+    #[inline]
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        // Flushing the signaling part, since it must always be submitted before the waiting part.
+        self.flush()?;
+
+        let mut builder = SubmitCommandBufferBuilder::new();
+        builder.add_wait_timeline_semaphore(&self.semaphore,
+                                            self.value,
+                                            PipelineStages {
+                                                // TODO: correct stages ; hard
+                                                all_commands: true,
+                                                ..PipelineStages::none()
+                                            });
+        Ok(SubmitAnyBuilder::CommandBuffer(builder))
+    }
+
+    fn flush(&self) -> Result<(), FlushError> {
+        unsafe {
+            let mut wait_submitted = self.wait_submitted.lock().unwrap();
+
+            if *wait_submitted {
+                return Ok(());
+            }
+
+            let queue = self.previous.queue().unwrap().clone();
+            let mut bind_sparse_fence = self.bind_sparse_fence.lock().unwrap();
+
+            match self.previous.build_submission()? {
+                SubmitAnyBuilder::Empty => {
+                    let mut builder = SubmitCommandBufferBuilder::new();
+                    builder.add_signal_timeline_semaphore(&self.semaphore, self.value);
+                    builder.submit(&queue)?;
+                },
+                SubmitAnyBuilder::SemaphoresWait(sem) => {
+                    let mut builder: SubmitCommandBufferBuilder = sem.into();
+                    builder.add_signal_timeline_semaphore(&self.semaphore, self.value);
+                    builder.submit(&queue)?;
+                },
+                SubmitAnyBuilder::CommandBuffer(mut builder) => {
+                    builder.add_signal_timeline_semaphore(&self.semaphore, self.value);
+                    builder.submit(&queue)?;
+                },
+                SubmitAnyBuilder::BindSparse(mut sparse) => {
+                    // The signal operation of a queue submission only covers the sparse binding
+                    // operations submitted before it if they signal a fence or a semaphore, so
+                    // the bind sparse submission is made to signal a fence.
+                    if !sparse.has_fence() {
+                        let fence = Fence::from_pool(self.device().clone())
+                            .map_err(FlushError::OomError)?;
+                        *bind_sparse_fence = Some(fence);
+                        sparse.set_fence_signal(bind_sparse_fence.as_ref().unwrap());
+                    }
+                    sparse.submit(&queue)?;
+
+                    let mut builder = SubmitCommandBufferBuilder::new();
+                    builder.add_signal_timeline_semaphore(&self.semaphore, self.value);
+                    builder.submit(&queue)?; // FIXME: same problem as with `QueuePresent` below
+                },
+                SubmitAnyBuilder::QueuePresent(present) => {
+                    present.submit(&queue)?;
+                    let mut builder = SubmitCommandBufferBuilder::new();
+                    builder.add_signal_timeline_semaphore(&self.semaphore, self.value);
+                    builder.submit(&queue)?; // FIXME: problematic because if we return an error and flush() is called again, then we'll submit the present twice
+                },
+            };
+
+            // Only write `true` here in order to try again next time if an error occurs.
+            *wait_submitted = true;
+            Ok(())
+        }
+    }
+
+    #[inline]
+    unsafe fn signal_finished(&self) {
+        debug_assert!(*self.wait_submitted.lock().unwrap());
+        self.finished.store(true, Ordering::SeqCst);
+        self.previous.signal_finished();
+    }
+
+    #[inline]
+    fn queue_change_allowed(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn queue(&self) -> Option<Arc<Queue>> {
+        self.previous.queue()
+    }
+
+    #[inline]
+    fn check_buffer_access(
+        &self, buffer: &BufferAccess, exclusive: bool, queue: &Queue)
+        -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        self.previous
+            .check_buffer_access(buffer, exclusive, queue)
+            .map(|_| None)
+    }
+
+    #[inline]
+    fn check_image_access(&self, image: &ImageAccess, layout: ImageLayout, exclusive: bool,
+                          queue: &Queue)
+                          -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        self.previous
+            .check_image_access(image, layout, exclusive, queue)
+            .map(|_| None)
+    }
+}
+
+unsafe impl<F> DeviceOwned for TimelineSemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.semaphore.device()
+    }
+}
+
+impl<F> Drop for TimelineSemaphoreSignalFuture<F>
+    where F: GpuFuture
+{
+    fn drop(&mut self) {
+        unsafe {
+            if !*self.finished.get_mut() {
+                // Block until the counter reaches the value.
+                let result = self.flush()
+                    .and_then(|()| self.semaphore.wait(self.value, None).map_err(From::from));
+                match result {
+                    // If the device is lost, nothing executes anymore and the resources can be
+                    // freed.
+                    Ok(()) | Err(FlushError::DeviceLost) => (),
+                    Err(err) => wait_error_on_drop(err),
+                }
+                self.previous.signal_finished();
+            }
+        }
+    }
+}
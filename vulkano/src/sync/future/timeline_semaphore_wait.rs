@@ -0,0 +1,120 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+
+use VulkanObject;
+use buffer::BufferAccess;
+use command_buffer::submit::SubmitAnyBuilder;
+use command_buffer::submit::SubmitCommandBufferBuilder;
+use device::Device;
+use device::DeviceOwned;
+use device::Queue;
+use image::ImageAccess;
+use image::ImageLayout;
+use sync::AccessCheckError;
+use sync::AccessFlagBits;
+use sync::FlushError;
+use sync::GpuFuture;
+use sync::PipelineStages;
+use sync::TimelineSemaphore;
+
+/// Builds a future that represents the moment when the counter of `semaphore` reaches `value`.
+///
+/// The operations chained after this future wait on the GPU until the counter reaches the value.
+/// The counter can be increased by another submission, for example one that was chained after
+/// `then_signal_timeline_semaphore`, or from the CPU with `TimelineSemaphore::signal`.
+///
+/// `queue` is the queue that the operations chained after this future are submitted to, unless
+/// they specify another one.
+///
+/// # Panic
+///
+/// - Panics if the semaphore and the queue don't belong to the same device.
+///
+#[inline]
+pub fn timeline_semaphore_wait(queue: Arc<Queue>, semaphore: Arc<TimelineSemaphore>, value: u64)
+                               -> TimelineSemaphoreWaitFuture {
+    assert_eq!(queue.device().internal_object(),
+               semaphore.device().internal_object());
+
+    TimelineSemaphoreWaitFuture {
+        queue,
+        semaphore,
+        value,
+    }
+}
+
+/// Represents the moment when the counter of a timeline semaphore reaches a value.
+///
+/// Contrary to most futures, this future doesn't correspond to anything that is submitted. It
+/// can be dropped at any time without blocking.
+pub struct TimelineSemaphoreWaitFuture {
+    queue: Arc<Queue>,
+    semaphore: Arc<TimelineSemaphore>,
+    value: u64,
+}
+
+unsafe impl GpuFuture for TimelineSemaphoreWaitFuture {
+    #[inline]
+    fn cleanup_finished(&mut self) {
+    }
+
+    #[inline]
+    unsafe fn build_submission(&self) -> Result<SubmitAnyBuilder, FlushError> {
+        let mut builder = SubmitCommandBufferBuilder::new();
+        builder.add_wait_timeline_semaphore(&self.semaphore,
+                                            self.value,
+                                            PipelineStages {
+                                                // TODO: correct stages ; hard
+                                                all_commands: true,
+                                                ..PipelineStages::none()
+                                            });
+        Ok(SubmitAnyBuilder::CommandBuffer(builder))
+    }
+
+    #[inline]
+    fn flush(&self) -> Result<(), FlushError> {
+        Ok(())
+    }
+
+    #[inline]
+    unsafe fn signal_finished(&self) {
+    }
+
+    #[inline]
+    fn queue_change_allowed(&self) -> bool {
+        true
+    }
+
+    #[inline]
+    fn queue(&self) -> Option<Arc<Queue>> {
+        Some(self.queue.clone())
+    }
+
+    #[inline]
+    fn check_buffer_access(
+        &self, _: &BufferAccess, _: bool, _: &Queue)
+        -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+
+    #[inline]
+    fn check_image_access(&self, _: &ImageAccess, _: ImageLayout, _: bool, _: &Queue)
+                          -> Result<Option<(PipelineStages, AccessFlagBits)>, AccessCheckError> {
+        Err(AccessCheckError::Unknown)
+    }
+}
+
+unsafe impl DeviceOwned for TimelineSemaphoreWaitFuture {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.semaphore.device()
+    }
+}
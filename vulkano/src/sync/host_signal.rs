@@ -0,0 +1,155 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use device::Device;
+use device::DeviceOwned;
+use device::Queue;
+use sync::TimelineSemaphore;
+use sync::TimelineSemaphoreCreationError;
+use sync::TimelineSemaphoreError;
+use sync::TimelineSemaphoreWaitFuture;
+use sync::timeline_semaphore_wait;
+
+/// Synchronization primitive that is signaled from the CPU and waited upon by the GPU.
+///
+/// A `HostSignal` wraps a timeline semaphore that only the CPU is allowed to signal. This makes
+/// signaling it safe, and is typically used by a worker thread that streams data to the GPU. The
+/// worker signals an increasing value each time a chunk of data is ready, and the main thread
+/// chains the operations that use this chunk after `gpu_wait`. The queue waits for the value on
+/// the GPU, so the main thread never has to block on the worker.
+///
+/// Requires the `timelineSemaphore` feature of the `VK_KHR_timeline_semaphore` extension.
+///
+/// # Example
+///
+/// ```
+/// use std::thread;
+/// use vulkano::sync::GpuFuture;
+/// use vulkano::sync::HostSignal;
+/// # let device: std::sync::Arc<vulkano::device::Device> = return;
+/// # let queue: std::sync::Arc<vulkano::device::Queue> = return;
+///
+/// let ready = HostSignal::new(device.clone()).unwrap();
+///
+/// let streamer = {
+///     let ready = ready.clone();
+///     thread::spawn(move || {
+///         // ... write the first chunk of data to a buffer ...
+///         ready.signal(1).unwrap();
+///     })
+/// };
+///
+/// // Operations chained after this future start on the GPU once the first chunk is ready.
+/// let future = ready.gpu_wait(queue.clone(), 1);
+/// # let _ = future;
+/// # streamer.join().unwrap();
+/// ```
+///
+/// > **Note**: Dropping a `HostSignal` while the GPU is waiting for a value that hasn't been
+/// > signaled blocks the queue forever.
+#[derive(Debug)]
+pub struct HostSignal {
+    semaphore: Arc<TimelineSemaphore>,
+    // Last value that was signaled. Locked while signaling so that the values reach the
+    // semaphore in increasing order.
+    last_value: Mutex<u64>,
+}
+
+impl HostSignal {
+    /// Builds a new `HostSignal` whose value starts at 0.
+    pub fn new(device: Arc<Device>) -> Result<Arc<HostSignal>, TimelineSemaphoreCreationError> {
+        let semaphore = TimelineSemaphore::new(device, 0)?;
+
+        Ok(Arc::new(HostSignal {
+                        semaphore: Arc::new(semaphore),
+                        last_value: Mutex::new(0),
+                    }))
+    }
+
+    /// Returns the last value that was passed to `signal`, or 0 if `signal` was never called.
+    #[inline]
+    pub fn last_signaled(&self) -> u64 {
+        *self.last_value.lock().unwrap()
+    }
+
+    /// Sets the value to `value`. Submissions that wait for a value lower than or equal to
+    /// `value` can then start.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if `value` isn't greater than the last value that was signaled.
+    ///
+    pub fn signal(&self, value: u64) -> Result<(), TimelineSemaphoreError> {
+        let mut last_value = self.last_value.lock().unwrap();
+        assert!(value > *last_value,
+                "Values must be signaled in increasing order ({} after {})",
+                value,
+                *last_value);
+
+        // Safe because the semaphore is never signaled by the GPU and the values are increasing.
+        unsafe {
+            self.semaphore.signal(value)?;
+        }
+
+        *last_value = value;
+        Ok(())
+    }
+
+    /// Blocks the current thread until `value` has been signaled, or at least until the timeout
+    /// duration has elapsed.
+    ///
+    /// If you pass a duration of 0, then the function will return without blocking.
+    #[inline]
+    pub fn wait(&self, value: u64, timeout: Option<Duration>)
+                -> Result<(), TimelineSemaphoreError> {
+        self.semaphore.wait(value, timeout)
+    }
+
+    /// Builds a future that represents the moment when `value` is signaled.
+    ///
+    /// The operations chained after this future wait on the GPU, and not on the CPU, until
+    /// `value` is signaled. `queue` is the queue that they are submitted to, unless they specify
+    /// another one.
+    ///
+    /// # Panic
+    ///
+    /// - Panics if the queue doesn't belong to the same device as this `HostSignal`.
+    ///
+    #[inline]
+    pub fn gpu_wait(&self, queue: Arc<Queue>, value: u64) -> TimelineSemaphoreWaitFuture {
+        timeline_semaphore_wait(queue, self.semaphore.clone(), value)
+    }
+}
+
+unsafe impl DeviceOwned for HostSignal {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        self.semaphore.device()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sync::HostSignal;
+    use sync::TimelineSemaphoreCreationError;
+
+    #[test]
+    fn feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match HostSignal::new(device) {
+            Err(TimelineSemaphoreCreationError::FeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}
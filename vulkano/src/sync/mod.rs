@@ -82,6 +82,38 @@
 //! flush the operation to the first queue as soon as possible. This can easily be done by calling
 //! `then_signal_semaphore_and_flush()` instead of `then_signal_semaphore()`.
 //!
+//! If the two queues belong to different queue families and the resources aren't shared between
+//! them, their ownership must also be transferred. Record `release_buffer_ownership` or
+//! `release_image_ownership` at the end of the first command buffer and the matching
+//! `acquire_buffer_ownership` or `acquire_image_ownership` at the start of the second one, then
+//! chain the two executions with a semaphore as described above:
+//!
+//! ```ignore
+//! let future = upload_cb.execute(transfer_queue.clone())?
+//!     .then_signal_semaphore_and_flush()?
+//!     .then_execute(graphics_queue.clone(), draw_cb)?;
+//! ```
+//!
+//! ## With timeline semaphores
+//!
+//! If the `VK_KHR_timeline_semaphore` extension is enabled, you can instead create a
+//! `TimelineSemaphore` and call `prev_future.then_signal_timeline_semaphore(semaphore, value)`.
+//! Contrary to a regular semaphore, the same value can be waited upon by any number of queues, and
+//! `timeline_semaphore_wait(queue, semaphore, value)` builds a future for a value that is
+//! signaled elsewhere, for example by a previous frame or by the CPU.
+//!
+//! A `HostSignal` wraps a timeline semaphore that only the CPU signals. A thread that streams
+//! data to the GPU can signal it once the data is ready, while the main thread chains the
+//! operations that read this data after `host_signal.gpu_wait(queue, value)` without blocking.
+//!
+//! ## With other APIs and processes
+//!
+//! If the `VK_KHR_external_semaphore_fd` or `VK_KHR_external_semaphore_win32` extension is
+//! enabled, a semaphore created with `Semaphore::alloc_exportable` can be signaled with
+//! `prev_future.then_signal_external_semaphore(semaphore)` and its payload exported to OpenGL,
+//! CUDA or a compositor. In the other direction, a payload imported with `Semaphore::import_fd`
+//! or `Semaphore::import_win32_handle` can be waited upon with `semaphore_wait(queue, semaphore)`.
+//!
 //! ## Between several different GPU queues
 //!
 //! The `then_signal_semaphore()` method is appropriate when you perform an operation in one queue,
@@ -114,19 +146,38 @@ pub use self::future::AccessError;
 pub use self::future::FenceSignalFuture;
 pub use self::future::FlushError;
 pub use self::future::GpuFuture;
+pub use self::future::JoinAllFuture;
 pub use self::future::JoinFuture;
 pub use self::future::NowFuture;
 pub use self::future::SemaphoreSignalFuture;
+pub use self::future::SemaphoreWaitFuture;
+pub use self::future::TimelineSemaphoreSignalFuture;
+pub use self::future::TimelineSemaphoreWaitFuture;
+pub use self::future::join_all;
 pub use self::future::now;
+pub use self::future::semaphore_wait;
+pub use self::future::timeline_semaphore_wait;
+pub(crate) use self::future::wait_error_on_drop;
+pub use self::host_signal::HostSignal;
 pub use self::pipeline::AccessFlagBits;
 pub use self::pipeline::PipelineStages;
+pub(crate) use self::pipeline::access2_to_legacy;
+pub(crate) use self::pipeline::stages2_to_legacy;
+pub use self::semaphore::ExternalSemaphoreError;
+pub use self::semaphore::ExternalSemaphoreHandleType;
+pub use self::semaphore::ExternalSemaphoreHandleTypes;
 pub use self::semaphore::Semaphore;
+pub use self::timeline_semaphore::TimelineSemaphore;
+pub use self::timeline_semaphore::TimelineSemaphoreCreationError;
+pub use self::timeline_semaphore::TimelineSemaphoreError;
 
 mod event;
 mod fence;
 mod future;
+mod host_signal;
 mod pipeline;
 mod semaphore;
+mod timeline_semaphore;
 
 /// Declares in which queue(s) a resource can be used.
 ///
@@ -13,6 +13,7 @@ use vk;
 macro_rules! pipeline_stages {
     ($($elem:ident => $val:expr,)+) => (
         #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[allow(missing_docs)]
         pub struct PipelineStages {
             $(
@@ -31,13 +32,29 @@ macro_rules! pipeline_stages {
             }
 
             #[inline]
-            pub(crate) fn into_vulkan_bits(self) -> vk::PipelineStageFlagBits {
+            pub(crate) fn into_vulkan_bits2(self) -> vk::PipelineStageFlags2KHR {
                 let mut result = 0;
                 $(
                     if self.$elem { result |= $val }
                 )+
                 result
             }
+
+            #[inline]
+            pub(crate) fn into_vulkan_bits(self) -> vk::PipelineStageFlagBits {
+                stages2_to_legacy(self.into_vulkan_bits2())
+            }
+
+            /// Builds a `PipelineStages` from stages of the original API.
+            #[inline]
+            pub(crate) fn from_vulkan_bits(bits: vk::PipelineStageFlagBits) -> PipelineStages {
+                let bits = bits as vk::PipelineStageFlags2KHR;
+                PipelineStages {
+                    $(
+                        $elem: bits & $val != 0,
+                    )+
+                }
+            }
         }
 
         impl ops::BitOr for PipelineStages {
@@ -64,29 +81,74 @@ macro_rules! pipeline_stages {
     );
 }
 
+// The stages from `copy` to `pre_rasterization_shaders` are only available with
+// `VK_KHR_synchronization2`. On other devices they are replaced with the closest stages of the
+// original API.
 pipeline_stages!{
-    top_of_pipe => vk::PIPELINE_STAGE_TOP_OF_PIPE_BIT,
-    draw_indirect => vk::PIPELINE_STAGE_DRAW_INDIRECT_BIT,
-    vertex_input => vk::PIPELINE_STAGE_VERTEX_INPUT_BIT,
-    vertex_shader => vk::PIPELINE_STAGE_VERTEX_SHADER_BIT,
-    tessellation_control_shader => vk::PIPELINE_STAGE_TESSELLATION_CONTROL_SHADER_BIT,
-    tessellation_evaluation_shader => vk::PIPELINE_STAGE_TESSELLATION_EVALUATION_SHADER_BIT,
-    geometry_shader => vk::PIPELINE_STAGE_GEOMETRY_SHADER_BIT,
-    fragment_shader => vk::PIPELINE_STAGE_FRAGMENT_SHADER_BIT,
-    early_fragment_tests => vk::PIPELINE_STAGE_EARLY_FRAGMENT_TESTS_BIT,
-    late_fragment_tests => vk::PIPELINE_STAGE_LATE_FRAGMENT_TESTS_BIT,
-    color_attachment_output => vk::PIPELINE_STAGE_COLOR_ATTACHMENT_OUTPUT_BIT,
-    compute_shader => vk::PIPELINE_STAGE_COMPUTE_SHADER_BIT,
-    transfer => vk::PIPELINE_STAGE_TRANSFER_BIT,
-    bottom_of_pipe => vk::PIPELINE_STAGE_BOTTOM_OF_PIPE_BIT,
-    host => vk::PIPELINE_STAGE_HOST_BIT,
-    all_graphics => vk::PIPELINE_STAGE_ALL_GRAPHICS_BIT,
-    all_commands => vk::PIPELINE_STAGE_ALL_COMMANDS_BIT,
+    top_of_pipe => vk::PIPELINE_STAGE_2_TOP_OF_PIPE_BIT_KHR,
+    draw_indirect => vk::PIPELINE_STAGE_2_DRAW_INDIRECT_BIT_KHR,
+    vertex_input => vk::PIPELINE_STAGE_2_VERTEX_INPUT_BIT_KHR,
+    vertex_shader => vk::PIPELINE_STAGE_2_VERTEX_SHADER_BIT_KHR,
+    tessellation_control_shader => vk::PIPELINE_STAGE_2_TESSELLATION_CONTROL_SHADER_BIT_KHR,
+    tessellation_evaluation_shader => vk::PIPELINE_STAGE_2_TESSELLATION_EVALUATION_SHADER_BIT_KHR,
+    geometry_shader => vk::PIPELINE_STAGE_2_GEOMETRY_SHADER_BIT_KHR,
+    fragment_shader => vk::PIPELINE_STAGE_2_FRAGMENT_SHADER_BIT_KHR,
+    early_fragment_tests => vk::PIPELINE_STAGE_2_EARLY_FRAGMENT_TESTS_BIT_KHR,
+    late_fragment_tests => vk::PIPELINE_STAGE_2_LATE_FRAGMENT_TESTS_BIT_KHR,
+    color_attachment_output => vk::PIPELINE_STAGE_2_COLOR_ATTACHMENT_OUTPUT_BIT_KHR,
+    compute_shader => vk::PIPELINE_STAGE_2_COMPUTE_SHADER_BIT_KHR,
+    transfer => vk::PIPELINE_STAGE_2_ALL_TRANSFER_BIT_KHR,
+    bottom_of_pipe => vk::PIPELINE_STAGE_2_BOTTOM_OF_PIPE_BIT_KHR,
+    host => vk::PIPELINE_STAGE_2_HOST_BIT_KHR,
+    all_graphics => vk::PIPELINE_STAGE_2_ALL_GRAPHICS_BIT_KHR,
+    all_commands => vk::PIPELINE_STAGE_2_ALL_COMMANDS_BIT_KHR,
+    conditional_rendering => vk::PIPELINE_STAGE_2_CONDITIONAL_RENDERING_BIT_EXT,
+    task_shader => vk::PIPELINE_STAGE_2_TASK_SHADER_BIT_EXT,
+    mesh_shader => vk::PIPELINE_STAGE_2_MESH_SHADER_BIT_EXT,
+    ray_tracing_shader => vk::PIPELINE_STAGE_2_RAY_TRACING_SHADER_BIT_KHR,
+    acceleration_structure_build => vk::PIPELINE_STAGE_2_ACCELERATION_STRUCTURE_BUILD_BIT_KHR,
+    transform_feedback => vk::PIPELINE_STAGE_2_TRANSFORM_FEEDBACK_BIT_EXT,
+    copy => vk::PIPELINE_STAGE_2_COPY_BIT_KHR,
+    resolve => vk::PIPELINE_STAGE_2_RESOLVE_BIT_KHR,
+    blit => vk::PIPELINE_STAGE_2_BLIT_BIT_KHR,
+    clear => vk::PIPELINE_STAGE_2_CLEAR_BIT_KHR,
+    index_input => vk::PIPELINE_STAGE_2_INDEX_INPUT_BIT_KHR,
+    vertex_attribute_input => vk::PIPELINE_STAGE_2_VERTEX_ATTRIBUTE_INPUT_BIT_KHR,
+    pre_rasterization_shaders => vk::PIPELINE_STAGE_2_PRE_RASTERIZATION_SHADERS_BIT_KHR,
+}
+
+/// Translates pipeline stages of `VK_KHR_synchronization2` into the stages of the original API.
+///
+/// The stages that only exist in `VK_KHR_synchronization2` are replaced with the stages that
+/// contain them.
+pub(crate) fn stages2_to_legacy(stages: vk::PipelineStageFlags2KHR) -> vk::PipelineStageFlags {
+    let mut result = stages;
+
+    if stages & (vk::PIPELINE_STAGE_2_COPY_BIT_KHR | vk::PIPELINE_STAGE_2_RESOLVE_BIT_KHR |
+                 vk::PIPELINE_STAGE_2_BLIT_BIT_KHR | vk::PIPELINE_STAGE_2_CLEAR_BIT_KHR) != 0
+    {
+        result |= vk::PIPELINE_STAGE_2_ALL_TRANSFER_BIT_KHR;
+    }
+
+    if stages & (vk::PIPELINE_STAGE_2_INDEX_INPUT_BIT_KHR |
+                 vk::PIPELINE_STAGE_2_VERTEX_ATTRIBUTE_INPUT_BIT_KHR) != 0
+    {
+        result |= vk::PIPELINE_STAGE_2_VERTEX_INPUT_BIT_KHR;
+    }
+
+    // The tessellation and geometry stages can't be used if the corresponding features aren't
+    // enabled, so we use all the graphics stages instead.
+    if stages & vk::PIPELINE_STAGE_2_PRE_RASTERIZATION_SHADERS_BIT_KHR != 0 {
+        result |= vk::PIPELINE_STAGE_2_ALL_GRAPHICS_BIT_KHR;
+    }
+
+    (result & 0xffffffff) as vk::PipelineStageFlags
 }
 
 macro_rules! access_flags {
     ($($elem:ident => $val:expr,)+) => (
         #[derive(Debug, Copy, Clone)]
+        #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
         #[allow(missing_docs)]
         pub struct AccessFlagBits {
             $(
@@ -114,13 +176,18 @@ macro_rules! access_flags {
             }
 
             #[inline]
-            pub(crate) fn into_vulkan_bits(self) -> vk::AccessFlagBits {
+            pub(crate) fn into_vulkan_bits2(self) -> vk::AccessFlags2KHR {
                 let mut result = 0;
                 $(
                     if self.$elem { result |= $val }
                 )+
                 result
             }
+
+            #[inline]
+            pub(crate) fn into_vulkan_bits(self) -> vk::AccessFlagBits {
+                access2_to_legacy(self.into_vulkan_bits2())
+            }
         }
 
         impl ops::BitOr for AccessFlagBits {
@@ -147,24 +214,57 @@ macro_rules! access_flags {
     );
 }
 
+// The accesses from `shader_sampled_read` to `shader_storage_write` are only available with
+// `VK_KHR_synchronization2`. On other devices they are replaced with `shader_read` and
+// `shader_write`.
 access_flags!{
-    indirect_command_read => vk::ACCESS_INDIRECT_COMMAND_READ_BIT,
-    index_read => vk::ACCESS_INDEX_READ_BIT,
-    vertex_attribute_read => vk::ACCESS_VERTEX_ATTRIBUTE_READ_BIT,
-    uniform_read => vk::ACCESS_UNIFORM_READ_BIT,
-    input_attachment_read => vk::ACCESS_INPUT_ATTACHMENT_READ_BIT,
-    shader_read => vk::ACCESS_SHADER_READ_BIT,
-    shader_write => vk::ACCESS_SHADER_WRITE_BIT,
-    color_attachment_read => vk::ACCESS_COLOR_ATTACHMENT_READ_BIT,
-    color_attachment_write => vk::ACCESS_COLOR_ATTACHMENT_WRITE_BIT,
-    depth_stencil_attachment_read => vk::ACCESS_DEPTH_STENCIL_ATTACHMENT_READ_BIT,
-    depth_stencil_attachment_write => vk::ACCESS_DEPTH_STENCIL_ATTACHMENT_WRITE_BIT,
-    transfer_read => vk::ACCESS_TRANSFER_READ_BIT,
-    transfer_write => vk::ACCESS_TRANSFER_WRITE_BIT,
-    host_read => vk::ACCESS_HOST_READ_BIT,
-    host_write => vk::ACCESS_HOST_WRITE_BIT,
-    memory_read => vk::ACCESS_MEMORY_READ_BIT,
-    memory_write => vk::ACCESS_MEMORY_WRITE_BIT,
+    indirect_command_read => vk::ACCESS_2_INDIRECT_COMMAND_READ_BIT_KHR,
+    index_read => vk::ACCESS_2_INDEX_READ_BIT_KHR,
+    vertex_attribute_read => vk::ACCESS_2_VERTEX_ATTRIBUTE_READ_BIT_KHR,
+    uniform_read => vk::ACCESS_2_UNIFORM_READ_BIT_KHR,
+    input_attachment_read => vk::ACCESS_2_INPUT_ATTACHMENT_READ_BIT_KHR,
+    shader_read => vk::ACCESS_2_SHADER_READ_BIT_KHR,
+    shader_write => vk::ACCESS_2_SHADER_WRITE_BIT_KHR,
+    color_attachment_read => vk::ACCESS_2_COLOR_ATTACHMENT_READ_BIT_KHR,
+    color_attachment_write => vk::ACCESS_2_COLOR_ATTACHMENT_WRITE_BIT_KHR,
+    depth_stencil_attachment_read => vk::ACCESS_2_DEPTH_STENCIL_ATTACHMENT_READ_BIT_KHR,
+    depth_stencil_attachment_write => vk::ACCESS_2_DEPTH_STENCIL_ATTACHMENT_WRITE_BIT_KHR,
+    transfer_read => vk::ACCESS_2_TRANSFER_READ_BIT_KHR,
+    transfer_write => vk::ACCESS_2_TRANSFER_WRITE_BIT_KHR,
+    host_read => vk::ACCESS_2_HOST_READ_BIT_KHR,
+    host_write => vk::ACCESS_2_HOST_WRITE_BIT_KHR,
+    memory_read => vk::ACCESS_2_MEMORY_READ_BIT_KHR,
+    memory_write => vk::ACCESS_2_MEMORY_WRITE_BIT_KHR,
+    conditional_rendering_read => vk::ACCESS_2_CONDITIONAL_RENDERING_READ_BIT_EXT,
+    acceleration_structure_read => vk::ACCESS_2_ACCELERATION_STRUCTURE_READ_BIT_KHR,
+    acceleration_structure_write => vk::ACCESS_2_ACCELERATION_STRUCTURE_WRITE_BIT_KHR,
+    transform_feedback_write => vk::ACCESS_2_TRANSFORM_FEEDBACK_WRITE_BIT_EXT,
+    transform_feedback_counter_read => vk::ACCESS_2_TRANSFORM_FEEDBACK_COUNTER_READ_BIT_EXT,
+    transform_feedback_counter_write => vk::ACCESS_2_TRANSFORM_FEEDBACK_COUNTER_WRITE_BIT_EXT,
+    shader_sampled_read => vk::ACCESS_2_SHADER_SAMPLED_READ_BIT_KHR,
+    shader_storage_read => vk::ACCESS_2_SHADER_STORAGE_READ_BIT_KHR,
+    shader_storage_write => vk::ACCESS_2_SHADER_STORAGE_WRITE_BIT_KHR,
+}
+
+/// Translates access flags of `VK_KHR_synchronization2` into the access flags of the original
+/// API.
+///
+/// The accesses that only exist in `VK_KHR_synchronization2` are replaced with the accesses that
+/// contain them.
+pub(crate) fn access2_to_legacy(access: vk::AccessFlags2KHR) -> vk::AccessFlags {
+    let mut result = access;
+
+    if access & (vk::ACCESS_2_SHADER_SAMPLED_READ_BIT_KHR |
+                 vk::ACCESS_2_SHADER_STORAGE_READ_BIT_KHR) != 0
+    {
+        result |= vk::ACCESS_2_SHADER_READ_BIT_KHR;
+    }
+
+    if access & vk::ACCESS_2_SHADER_STORAGE_WRITE_BIT_KHR != 0 {
+        result |= vk::ACCESS_2_SHADER_WRITE_BIT_KHR;
+    }
+
+    (result & 0xffffffff) as vk::AccessFlags
 }
 
 impl AccessFlagBits {
@@ -181,21 +281,35 @@ impl AccessFlagBits {
             return false;
         }
 
-        if (self.index_read || self.vertex_attribute_read) && !stages.vertex_input &&
+        if self.index_read && !stages.vertex_input && !stages.index_input &&
             !stages.all_graphics
         {
             return false;
         }
 
-        if (self.uniform_read || self.shader_read || self.shader_write) &&
-            !stages.vertex_shader && !stages.tessellation_control_shader &&
-            !stages.tessellation_evaluation_shader && !stages.geometry_shader &&
-            !stages.fragment_shader &&
-            !stages.compute_shader && !stages.all_graphics
+        if self.vertex_attribute_read && !stages.vertex_input &&
+            !stages.vertex_attribute_input && !stages.all_graphics
         {
             return false;
         }
 
+        let shader_stages = stages.vertex_shader || stages.tessellation_control_shader ||
+            stages.tessellation_evaluation_shader || stages.geometry_shader ||
+            stages.fragment_shader || stages.compute_shader || stages.task_shader ||
+            stages.mesh_shader || stages.ray_tracing_shader || stages.pre_rasterization_shaders ||
+            stages.all_graphics;
+
+        if (self.uniform_read || self.shader_write || self.shader_sampled_read ||
+            self.shader_storage_read || self.shader_storage_write) && !shader_stages
+        {
+            return false;
+        }
+
+        // The inputs of an acceleration structure build are read with the shader read access.
+        if self.shader_read && !shader_stages && !stages.acceleration_structure_build {
+            return false;
+        }
+
         if self.input_attachment_read && !stages.fragment_shader && !stages.all_graphics {
             return false;
         }
@@ -213,7 +327,15 @@ impl AccessFlagBits {
             return false;
         }
 
-        if (self.transfer_read || self.transfer_write) && !stages.transfer {
+        if self.transfer_read && !stages.transfer && !stages.copy && !stages.resolve &&
+            !stages.blit
+        {
+            return false;
+        }
+
+        if self.transfer_write && !stages.transfer && !stages.copy && !stages.resolve &&
+            !stages.blit && !stages.clear
+        {
             return false;
         }
 
@@ -221,6 +343,68 @@ impl AccessFlagBits {
             return false;
         }
 
+        if self.acceleration_structure_read && !stages.ray_tracing_shader &&
+            !stages.acceleration_structure_build && !stages.compute_shader &&
+            !stages.all_graphics
+        {
+            return false;
+        }
+
+        if self.acceleration_structure_write && !stages.acceleration_structure_build {
+            return false;
+        }
+
+        if self.conditional_rendering_read && !stages.conditional_rendering {
+            return false;
+        }
+
+        if (self.transform_feedback_write || self.transform_feedback_counter_write) &&
+            !stages.transform_feedback
+        {
+            return false;
+        }
+
+        // The counter buffer of `draw_indirect_byte_count` is read in the draw indirect stage.
+        if self.transform_feedback_counter_read && !stages.transform_feedback &&
+            !stages.draw_indirect
+        {
+            return false;
+        }
+
         true
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use sync::AccessFlagBits;
+    use sync::PipelineStages;
+    use vk;
+
+    #[test]
+    fn legacy_stages() {
+        let stages = PipelineStages {
+            copy: true,
+            index_input: true,
+            fragment_shader: true,
+            ..PipelineStages::none()
+        };
+
+        assert_eq!(stages.into_vulkan_bits(),
+                   vk::PIPELINE_STAGE_TRANSFER_BIT | vk::PIPELINE_STAGE_VERTEX_INPUT_BIT |
+                       vk::PIPELINE_STAGE_FRAGMENT_SHADER_BIT);
+    }
+
+    #[test]
+    fn legacy_access() {
+        let access = AccessFlagBits {
+            shader_sampled_read: true,
+            shader_storage_write: true,
+            ..AccessFlagBits::none()
+        };
+
+        assert_eq!(access.into_vulkan_bits(),
+                   vk::ACCESS_SHADER_READ_BIT | vk::ACCESS_SHADER_WRITE_BIT);
+        assert!(access.into_vulkan_bits2() > 0xffffffff);
+    }
+}
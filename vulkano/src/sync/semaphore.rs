@@ -7,10 +7,15 @@
 // notice may not be copied, modified, or distributed except
 // according to those terms.
 
+use std::error;
+use std::fmt;
 use std::mem;
+use std::os::raw::c_int;
+use std::os::raw::c_void;
 use std::ptr;
 use std::sync::Arc;
 
+use Error;
 use OomError;
 use SafeDeref;
 use VulkanObject;
@@ -30,6 +35,7 @@ pub struct Semaphore<D = Arc<Device>>
     semaphore: vk::Semaphore,
     device: D,
     must_put_in_pool: bool,
+    export_handle_types: ExternalSemaphoreHandleTypes,
 }
 
 impl<D> Semaphore<D>
@@ -39,6 +45,11 @@ impl<D> Semaphore<D>
     /// If the pool is empty, a new semaphore will be allocated.
     /// Upon `drop`, the semaphore is put back into the pool.
     ///
+    /// The semaphore must be unsignaled when it is dropped, in other words every signal
+    /// operation must have been followed by a wait operation that has completed. The futures of
+    /// vulkano take care of this, and destroy their semaphore instead if it was never waited
+    /// upon. Use `Device::trim_semaphore_pool` to release the semaphores of the pool.
+    ///
     /// For most applications, using the pool should be preferred,
     /// in order to avoid creating new semaphores every frame.
     pub fn from_pool(device: D) -> Result<Semaphore<D>, OomError> {
@@ -49,6 +60,7 @@ impl<D> Semaphore<D>
                        device: device,
                        semaphore: raw_sem,
                        must_put_in_pool: true,
+                       export_handle_types: ExternalSemaphoreHandleTypes::none(),
                    })
             },
             None => {
@@ -77,7 +89,7 @@ impl<D> Semaphore<D>
             let mut output = mem::uninitialized();
             check_errors(vk.CreateSemaphore(device.internal_object(),
                                             &INFOS,
-                                            ptr::null(),
+                                            device.allocator_ptr(),
                                             &mut output))?;
             output
         };
@@ -86,8 +98,265 @@ impl<D> Semaphore<D>
                device: device,
                semaphore: semaphore,
                must_put_in_pool: must_put_in_pool,
+               export_handle_types: ExternalSemaphoreHandleTypes::none(),
            })
     }
+
+    /// Builds a new semaphore whose payload can be exported as the handle types of
+    /// `handle_types`, in order to share it with another API or another process.
+    ///
+    /// Requires the `VK_KHR_external_semaphore` extension.
+    pub fn alloc_exportable(device: D, handle_types: ExternalSemaphoreHandleTypes)
+                            -> Result<Semaphore<D>, ExternalSemaphoreError> {
+        if !device.loaded_extensions().khr_external_semaphore {
+            return Err(ExternalSemaphoreError::ExtensionNotEnabled);
+        }
+
+        let semaphore = unsafe {
+            let export_infos = vk::ExportSemaphoreCreateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_EXPORT_SEMAPHORE_CREATE_INFO_KHR,
+                pNext: ptr::null(),
+                handleTypes: handle_types.into_vulkan_bits(),
+            };
+
+            let infos = vk::SemaphoreCreateInfo {
+                sType: vk::STRUCTURE_TYPE_SEMAPHORE_CREATE_INFO,
+                pNext: &export_infos as *const _ as *const _,
+                flags: 0, // reserved
+            };
+
+            let vk = device.pointers();
+            let mut output = mem::uninitialized();
+            check_errors(vk.CreateSemaphore(device.internal_object(),
+                                            &infos,
+                                            device.allocator_ptr(),
+                                            &mut output))?;
+            output
+        };
+
+        Ok(Semaphore {
+               device: device,
+               semaphore: semaphore,
+               must_put_in_pool: false,
+               export_handle_types: handle_types,
+           })
+    }
+
+    /// Builds a `Semaphore` from a raw semaphore handle, for example one that was created by
+    /// another library.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must be a valid binary semaphore that was created from `device`, with the
+    ///   allocation callbacks of `device` if it has any.
+    /// - The `Semaphore` takes ownership of `handle` and destroys it when it is dropped.
+    ///
+    #[inline]
+    pub unsafe fn from_raw(device: D, handle: u64) -> Semaphore<D> {
+        Semaphore {
+            device: device,
+            semaphore: handle,
+            must_put_in_pool: false,
+            export_handle_types: ExternalSemaphoreHandleTypes::none(),
+        }
+    }
+
+    /// Returns the handle types that the semaphore can be exported as.
+    #[inline]
+    pub fn export_handle_types(&self) -> ExternalSemaphoreHandleTypes {
+        self.export_handle_types
+    }
+
+    /// Exports the payload of the semaphore as a POSIX file descriptor. The caller becomes the
+    /// owner of the file descriptor and is responsible for closing it.
+    ///
+    /// `handle_type` must be `OpaqueFd` or `SyncFd`, and the semaphore must have been created
+    /// with `alloc_exportable` with this handle type.
+    ///
+    /// Requires the `VK_KHR_external_semaphore_fd` extension.
+    ///
+    /// # Safety
+    ///
+    /// - When exporting a `SyncFd`, a signal operation of the semaphore must have been submitted,
+    ///   for example with `GpuFuture::then_signal_external_semaphore`. The export then acts like
+    ///   a wait operation and unsignals the semaphore.
+    ///
+    pub unsafe fn export_fd(&self, handle_type: ExternalSemaphoreHandleType)
+                            -> Result<c_int, ExternalSemaphoreError> {
+        if !self.device.loaded_extensions().khr_external_semaphore_fd {
+            return Err(ExternalSemaphoreError::ExtensionNotEnabled);
+        }
+
+        if !handle_type.is_fd() {
+            return Err(ExternalSemaphoreError::HandleTypeNotSupported);
+        }
+
+        if !self.export_handle_types.contains(handle_type) {
+            return Err(ExternalSemaphoreError::HandleTypeNotExportable);
+        }
+
+        let infos = vk::SemaphoreGetFdInfoKHR {
+            sType: vk::STRUCTURE_TYPE_SEMAPHORE_GET_FD_INFO_KHR,
+            pNext: ptr::null(),
+            semaphore: self.semaphore,
+            handleType: handle_type as u32,
+        };
+
+        let vk = self.device.pointers();
+        let mut output = -1;
+        check_errors(vk.GetSemaphoreFdKHR(self.device.internal_object(), &infos, &mut output))?;
+        Ok(output)
+    }
+
+    /// Imports a payload in the semaphore from a POSIX file descriptor. On success, the
+    /// implementation becomes the owner of the file descriptor.
+    ///
+    /// If `temporary` is true, the semaphore goes back to its own payload after the next wait
+    /// operation on it. Otherwise the imported payload replaces it permanently. A `SyncFd` can
+    /// only be imported temporarily.
+    ///
+    /// Requires the `VK_KHR_external_semaphore_fd` extension.
+    ///
+    /// # Safety
+    ///
+    /// - `fd` must have been exported with `handle_type` from a semaphore of a compatible driver
+    ///   and physical device.
+    /// - The semaphore must not be in use by a pending queue operation.
+    ///
+    pub unsafe fn import_fd(&mut self, handle_type: ExternalSemaphoreHandleType, fd: c_int,
+                            temporary: bool)
+                            -> Result<(), ExternalSemaphoreError> {
+        if !self.device.loaded_extensions().khr_external_semaphore_fd {
+            return Err(ExternalSemaphoreError::ExtensionNotEnabled);
+        }
+
+        if !handle_type.is_fd() {
+            return Err(ExternalSemaphoreError::HandleTypeNotSupported);
+        }
+
+        if handle_type == ExternalSemaphoreHandleType::SyncFd && !temporary {
+            return Err(ExternalSemaphoreError::PermanentSyncFdImport);
+        }
+
+        let infos = vk::ImportSemaphoreFdInfoKHR {
+            sType: vk::STRUCTURE_TYPE_IMPORT_SEMAPHORE_FD_INFO_KHR,
+            pNext: ptr::null(),
+            semaphore: self.semaphore,
+            flags: if temporary {
+                vk::SEMAPHORE_IMPORT_TEMPORARY_BIT_KHR
+            } else {
+                0
+            },
+            handleType: handle_type as u32,
+            fd: fd,
+        };
+
+        let vk = self.device.pointers();
+        check_errors(vk.ImportSemaphoreFdKHR(self.device.internal_object(), &infos))?;
+        self.imported(temporary);
+        Ok(())
+    }
+
+    /// Exports the payload of the semaphore as a Windows handle. If `handle_type` is
+    /// `OpaqueWin32` or `D3D12Fence`, the caller becomes the owner of the handle and is
+    /// responsible for closing it.
+    ///
+    /// `handle_type` must not be a file descriptor type, and the semaphore must have been created
+    /// with `alloc_exportable` with this handle type.
+    ///
+    /// Requires the `VK_KHR_external_semaphore_win32` extension.
+    pub fn export_win32_handle(&self, handle_type: ExternalSemaphoreHandleType)
+                               -> Result<*mut c_void, ExternalSemaphoreError> {
+        if !self.device.loaded_extensions().khr_external_semaphore_win32 {
+            return Err(ExternalSemaphoreError::ExtensionNotEnabled);
+        }
+
+        if handle_type.is_fd() {
+            return Err(ExternalSemaphoreError::HandleTypeNotSupported);
+        }
+
+        if !self.export_handle_types.contains(handle_type) {
+            return Err(ExternalSemaphoreError::HandleTypeNotExportable);
+        }
+
+        unsafe {
+            let infos = vk::SemaphoreGetWin32HandleInfoKHR {
+                sType: vk::STRUCTURE_TYPE_SEMAPHORE_GET_WIN32_HANDLE_INFO_KHR,
+                pNext: ptr::null(),
+                semaphore: self.semaphore,
+                handleType: handle_type as u32,
+            };
+
+            let vk = self.device.pointers();
+            let mut output = ptr::null_mut();
+            check_errors(vk.GetSemaphoreWin32HandleKHR(self.device.internal_object(),
+                                                       &infos,
+                                                       &mut output))?;
+            Ok(output)
+        }
+    }
+
+    /// Imports a payload in the semaphore from a Windows handle. Contrary to file descriptors,
+    /// the caller keeps the ownership of the handle.
+    ///
+    /// If `temporary` is true, the semaphore goes back to its own payload after the next wait
+    /// operation on it. Otherwise the imported payload replaces it permanently.
+    ///
+    /// Requires the `VK_KHR_external_semaphore_win32` extension.
+    ///
+    /// # Safety
+    ///
+    /// - `handle` must have been exported with `handle_type` from a semaphore of a compatible
+    ///   driver and physical device.
+    /// - The semaphore must not be in use by a pending queue operation.
+    ///
+    pub unsafe fn import_win32_handle(&mut self, handle_type: ExternalSemaphoreHandleType,
+                                      handle: *mut c_void, temporary: bool)
+                                      -> Result<(), ExternalSemaphoreError> {
+        if !self.device.loaded_extensions().khr_external_semaphore_win32 {
+            return Err(ExternalSemaphoreError::ExtensionNotEnabled);
+        }
+
+        if handle_type.is_fd() {
+            return Err(ExternalSemaphoreError::HandleTypeNotSupported);
+        }
+
+        let infos = vk::ImportSemaphoreWin32HandleInfoKHR {
+            sType: vk::STRUCTURE_TYPE_IMPORT_SEMAPHORE_WIN32_HANDLE_INFO_KHR,
+            pNext: ptr::null(),
+            semaphore: self.semaphore,
+            flags: if temporary {
+                vk::SEMAPHORE_IMPORT_TEMPORARY_BIT_KHR
+            } else {
+                0
+            },
+            handleType: handle_type as u32,
+            handle: handle,
+            name: ptr::null(),
+        };
+
+        let vk = self.device.pointers();
+        check_errors(vk.ImportSemaphoreWin32HandleKHR(self.device.internal_object(), &infos))?;
+        self.imported(temporary);
+        Ok(())
+    }
+
+    /// Destroys the semaphore when it is dropped, instead of putting it back into the pool.
+    ///
+    /// Called when the semaphore may be left signaled.
+    #[inline]
+    pub(crate) fn forbid_recycling(&mut self) {
+        self.must_put_in_pool = false;
+    }
+
+    // Called after a successful import.
+    #[inline]
+    fn imported(&mut self, temporary: bool) {
+        // A semaphore whose payload was permanently replaced must not be reused by someone else.
+        if !temporary {
+            self.must_put_in_pool = false;
+        }
+    }
 }
 
 unsafe impl DeviceOwned for Semaphore {
@@ -121,15 +390,187 @@ impl<D> Drop for Semaphore<D>
                 self.device.semaphore_pool().lock().unwrap().push(raw_sem);
             } else {
                 let vk = self.device.pointers();
-                vk.DestroySemaphore(self.device.internal_object(), self.semaphore, ptr::null());
+                vk.DestroySemaphore(self.device.internal_object(),
+                                    self.semaphore,
+                                    self.device.allocator_ptr());
             }
         }
     }
 }
 
+/// A type of handle that the payload of a semaphore can be exported as or imported from.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[repr(u32)]
+pub enum ExternalSemaphoreHandleType {
+    /// A POSIX file descriptor that is only meaningful to a driver of the same physical device.
+    OpaqueFd = vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD_BIT_KHR,
+    /// An NT handle that is only meaningful to a driver of the same physical device.
+    OpaqueWin32 = vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR,
+    /// A global share handle that is only meaningful to a driver of the same physical device.
+    OpaqueWin32Kmt = vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR,
+    /// An NT handle to a Direct3D 12 fence.
+    D3D12Fence = vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_D3D12_FENCE_BIT_KHR,
+    /// A Linux sync file descriptor, that can be passed to other graphics APIs and to the kernel.
+    SyncFd = vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD_BIT_KHR,
+}
+
+impl ExternalSemaphoreHandleType {
+    /// Returns true if the handle is a file descriptor.
+    #[inline]
+    pub fn is_fd(&self) -> bool {
+        match *self {
+            ExternalSemaphoreHandleType::OpaqueFd |
+            ExternalSemaphoreHandleType::SyncFd => true,
+            _ => false,
+        }
+    }
+}
+
+/// A set of handle types that the payload of a semaphore can be exported as.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ExternalSemaphoreHandleTypes {
+    /// `True` means that the set contains `ExternalSemaphoreHandleType::OpaqueFd`.
+    pub opaque_fd: bool,
+    /// `True` means that the set contains `ExternalSemaphoreHandleType::OpaqueWin32`.
+    pub opaque_win32: bool,
+    /// `True` means that the set contains `ExternalSemaphoreHandleType::OpaqueWin32Kmt`.
+    pub opaque_win32_kmt: bool,
+    /// `True` means that the set contains `ExternalSemaphoreHandleType::D3D12Fence`.
+    pub d3d12_fence: bool,
+    /// `True` means that the set contains `ExternalSemaphoreHandleType::SyncFd`.
+    pub sync_fd: bool,
+}
+
+impl ExternalSemaphoreHandleTypes {
+    /// Builds an `ExternalSemaphoreHandleTypes` with none of the handle types set.
+    #[inline]
+    pub fn none() -> ExternalSemaphoreHandleTypes {
+        ExternalSemaphoreHandleTypes {
+            opaque_fd: false,
+            opaque_win32: false,
+            opaque_win32_kmt: false,
+            d3d12_fence: false,
+            sync_fd: false,
+        }
+    }
+
+    /// Returns true if `handle_type` is part of the set.
+    #[inline]
+    pub fn contains(&self, handle_type: ExternalSemaphoreHandleType) -> bool {
+        match handle_type {
+            ExternalSemaphoreHandleType::OpaqueFd => self.opaque_fd,
+            ExternalSemaphoreHandleType::OpaqueWin32 => self.opaque_win32,
+            ExternalSemaphoreHandleType::OpaqueWin32Kmt => self.opaque_win32_kmt,
+            ExternalSemaphoreHandleType::D3D12Fence => self.d3d12_fence,
+            ExternalSemaphoreHandleType::SyncFd => self.sync_fd,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn into_vulkan_bits(self) -> vk::ExternalSemaphoreHandleTypeFlagsKHR {
+        let mut result = 0;
+        if self.opaque_fd {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_FD_BIT_KHR;
+        }
+        if self.opaque_win32 {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_BIT_KHR;
+        }
+        if self.opaque_win32_kmt {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_OPAQUE_WIN32_KMT_BIT_KHR;
+        }
+        if self.d3d12_fence {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_D3D12_FENCE_BIT_KHR;
+        }
+        if self.sync_fd {
+            result |= vk::EXTERNAL_SEMAPHORE_HANDLE_TYPE_SYNC_FD_BIT_KHR;
+        }
+        result
+    }
+}
+
+/// Error that can happen when creating an exportable semaphore, or when exporting or importing
+/// the payload of a semaphore.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ExternalSemaphoreError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// Too many handles are opened in the process.
+    TooManyObjects,
+    /// The handle isn't valid for the handle type.
+    InvalidExternalHandle,
+    /// The extension required by the operation isn't enabled.
+    ExtensionNotEnabled,
+    /// The handle type can't be used with this function.
+    HandleTypeNotSupported,
+    /// The semaphore wasn't created with the handle type in its exportable handle types.
+    HandleTypeNotExportable,
+    /// A sync file descriptor can only be imported temporarily.
+    PermanentSyncFdImport,
+}
+
+impl error::Error for ExternalSemaphoreError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            ExternalSemaphoreError::OomError(_) => "not enough memory",
+            ExternalSemaphoreError::TooManyObjects => {
+                "too many handles are opened in the process"
+            },
+            ExternalSemaphoreError::InvalidExternalHandle => {
+                "the handle isn't valid for the handle type"
+            },
+            ExternalSemaphoreError::ExtensionNotEnabled => {
+                "the extension required by the operation isn't enabled"
+            },
+            ExternalSemaphoreError::HandleTypeNotSupported => {
+                "the handle type can't be used with this function"
+            },
+            ExternalSemaphoreError::HandleTypeNotExportable => {
+                "the semaphore wasn't created with the handle type in its exportable handle types"
+            },
+            ExternalSemaphoreError::PermanentSyncFdImport => {
+                "a sync file descriptor can only be imported temporarily"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            ExternalSemaphoreError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ExternalSemaphoreError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<Error> for ExternalSemaphoreError {
+    #[inline]
+    fn from(err: Error) -> ExternalSemaphoreError {
+        match err {
+            err @ Error::OutOfHostMemory => ExternalSemaphoreError::OomError(err.into()),
+            err @ Error::OutOfDeviceMemory => ExternalSemaphoreError::OomError(err.into()),
+            Error::TooManyObjects => ExternalSemaphoreError::TooManyObjects,
+            Error::InvalidExternalHandle => ExternalSemaphoreError::InvalidExternalHandle,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use VulkanObject;
+    use command_buffer::AutoCommandBufferBuilder;
+    use command_buffer::CommandBuffer;
+    use sync::ExternalSemaphoreError;
+    use sync::ExternalSemaphoreHandleTypes;
+    use sync::GpuFuture;
     use sync::Semaphore;
 
     #[test]
@@ -138,6 +579,21 @@ mod tests {
         let _ = Semaphore::alloc(device.clone());
     }
 
+    #[test]
+    fn exportable_extension_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        let handle_types = ExternalSemaphoreHandleTypes {
+            opaque_fd: true,
+            ..ExternalSemaphoreHandleTypes::none()
+        };
+
+        match Semaphore::alloc_exportable(device.clone(), handle_types) {
+            Err(ExternalSemaphoreError::ExtensionNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+
     #[test]
     fn semaphore_pool() {
         let (device, _) = gfx_dev_and_queue!();
@@ -154,4 +610,40 @@ mod tests {
         assert_eq!(device.semaphore_pool().lock().unwrap().len(), 0);
         assert_eq!(sem2.internal_object(), sem1_internal_obj);
     }
+
+    #[test]
+    fn semaphore_pool_trim() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        {
+            let _sem1 = Semaphore::from_pool(device.clone()).unwrap();
+            let _sem2 = Semaphore::from_pool(device.clone()).unwrap();
+        }
+
+        assert_eq!(device.semaphore_pool_len(), 2);
+        device.trim_semaphore_pool(1);
+        assert_eq!(device.semaphore_pool_len(), 1);
+        device.trim_semaphore_pool(4);
+        assert_eq!(device.semaphore_pool_len(), 1);
+    }
+
+    #[test]
+    fn signaled_semaphore_not_recycled() {
+        let (device, queue) = gfx_dev_and_queue!();
+
+        let cb = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family())
+            .unwrap()
+            .build()
+            .unwrap();
+
+        {
+            // The semaphore is signaled but nothing waits on it.
+            let _future = cb.execute(queue)
+                .unwrap()
+                .then_signal_semaphore_and_flush()
+                .unwrap();
+        }
+
+        assert_eq!(device.semaphore_pool_len(), 0);
+    }
 }
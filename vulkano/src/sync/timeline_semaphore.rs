@@ -0,0 +1,301 @@
+// Copyright (c) 2017 The vulkano developers
+// Licensed under the Apache License, Version 2.0
+// <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT
+// license <LICENSE-MIT or http://opensource.org/licenses/MIT>,
+// at your option. All files in the project carrying such
+// notice may not be copied, modified, or distributed except
+// according to those terms.
+
+use std::error;
+use std::fmt;
+use std::mem;
+use std::ptr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use Error;
+use OomError;
+use Success;
+use VulkanObject;
+use check_errors;
+use device::Device;
+use device::DeviceOwned;
+use vk;
+
+/// A semaphore that contains a 64-bits counter instead of a binary state.
+///
+/// Submissions can wait until the counter reaches a value and increase the counter when they end,
+/// and the CPU can read, wait on and increase the counter as well. Contrary to a regular
+/// `Semaphore`, a timeline semaphore can be waited upon by multiple submissions and doesn't need
+/// to be recycled between signals, which makes it convenient to synchronize work between queues
+/// or between frames.
+///
+/// Use `GpuFuture::then_signal_timeline_semaphore` to signal a value after a future, and
+/// `timeline_semaphore_wait` to build a future that starts once a value is reached.
+///
+/// Requires the `timelineSemaphore` feature of the `VK_KHR_timeline_semaphore` extension.
+#[derive(Debug)]
+pub struct TimelineSemaphore {
+    semaphore: vk::Semaphore,
+    device: Arc<Device>,
+}
+
+impl TimelineSemaphore {
+    /// Builds a new timeline semaphore whose counter starts at `initial_value`.
+    pub fn new(device: Arc<Device>, initial_value: u64)
+               -> Result<TimelineSemaphore, TimelineSemaphoreCreationError> {
+        if !device.timeline_semaphore_enabled() {
+            return Err(TimelineSemaphoreCreationError::FeatureNotEnabled);
+        }
+
+        let semaphore = unsafe {
+            let type_info = vk::SemaphoreTypeCreateInfoKHR {
+                sType: vk::STRUCTURE_TYPE_SEMAPHORE_TYPE_CREATE_INFO_KHR,
+                pNext: ptr::null(),
+                semaphoreType: vk::SEMAPHORE_TYPE_TIMELINE_KHR,
+                initialValue: initial_value,
+            };
+
+            let infos = vk::SemaphoreCreateInfo {
+                sType: vk::STRUCTURE_TYPE_SEMAPHORE_CREATE_INFO,
+                pNext: &type_info as *const _ as *const _,
+                flags: 0, // reserved
+            };
+
+            let vk = device.pointers();
+            let mut output = mem::uninitialized();
+            check_errors(vk.CreateSemaphore(device.internal_object(),
+                                            &infos,
+                                            device.allocator_ptr(),
+                                            &mut output))?;
+            output
+        };
+
+        Ok(TimelineSemaphore {
+               semaphore: semaphore,
+               device: device,
+           })
+    }
+
+    /// Returns the current value of the counter.
+    pub fn value(&self) -> Result<u64, TimelineSemaphoreError> {
+        unsafe {
+            let vk = self.device.pointers();
+            let mut value = 0;
+            let result = vk.GetSemaphoreCounterValueKHR(self.device.internal_object(),
+                                                        self.semaphore,
+                                                        &mut value);
+            self.device.check_lost(check_errors(result))?;
+            Ok(value)
+        }
+    }
+
+    /// Waits until the counter is greater than or equal to `value`, or at least until the timeout
+    /// duration has elapsed.
+    ///
+    /// If you pass a duration of 0, then the function will return without blocking.
+    pub fn wait(&self, value: u64, timeout: Option<Duration>)
+                -> Result<(), TimelineSemaphoreError> {
+        unsafe {
+            let timeout_ns = if let Some(timeout) = timeout {
+                timeout
+                    .as_secs()
+                    .saturating_mul(1_000_000_000)
+                    .saturating_add(timeout.subsec_nanos() as u64)
+            } else {
+                u64::max_value()
+            };
+
+            let infos = vk::SemaphoreWaitInfoKHR {
+                sType: vk::STRUCTURE_TYPE_SEMAPHORE_WAIT_INFO_KHR,
+                pNext: ptr::null(),
+                flags: 0,
+                semaphoreCount: 1,
+                pSemaphores: &self.semaphore,
+                pValues: &value,
+            };
+
+            let vk = self.device.pointers();
+            let r = self.device
+                .check_lost(check_errors(vk.WaitSemaphoresKHR(self.device.internal_object(),
+                                                              &infos,
+                                                              timeout_ns)))?;
+
+            match r {
+                Success::Success => Ok(()),
+                Success::Timeout => Err(TimelineSemaphoreError::Timeout),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    /// Sets the counter to `value` from the CPU.
+    ///
+    /// Submissions that wait for a value lower than or equal to `value` can then start.
+    ///
+    /// # Safety
+    ///
+    /// - `value` must be greater than the current value of the counter.
+    /// - `value` must be lower than the values of the signal operations that are pending on the
+    ///   GPU.
+    ///
+    pub unsafe fn signal(&self, value: u64) -> Result<(), TimelineSemaphoreError> {
+        debug_assert!(self.value().map(|current| value > current).unwrap_or(true));
+
+        let infos = vk::SemaphoreSignalInfoKHR {
+            sType: vk::STRUCTURE_TYPE_SEMAPHORE_SIGNAL_INFO_KHR,
+            pNext: ptr::null(),
+            semaphore: self.semaphore,
+            value: value,
+        };
+
+        let vk = self.device.pointers();
+        check_errors(vk.SignalSemaphoreKHR(self.device.internal_object(), &infos))?;
+        Ok(())
+    }
+}
+
+unsafe impl DeviceOwned for TimelineSemaphore {
+    #[inline]
+    fn device(&self) -> &Arc<Device> {
+        &self.device
+    }
+}
+
+unsafe impl VulkanObject for TimelineSemaphore {
+    type Object = vk::Semaphore;
+
+    const TYPE: vk::DebugReportObjectTypeEXT = vk::DEBUG_REPORT_OBJECT_TYPE_SEMAPHORE_EXT;
+
+    #[inline]
+    fn internal_object(&self) -> vk::Semaphore {
+        self.semaphore
+    }
+}
+
+impl Drop for TimelineSemaphore {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            let vk = self.device.pointers();
+            vk.DestroySemaphore(self.device.internal_object(),
+                                self.semaphore,
+                                self.device.allocator_ptr());
+        }
+    }
+}
+
+/// Error that can happen when creating a timeline semaphore.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimelineSemaphoreCreationError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The `timelineSemaphore` feature of the `VK_KHR_timeline_semaphore` extension isn't
+    /// enabled.
+    FeatureNotEnabled,
+}
+
+impl error::Error for TimelineSemaphoreCreationError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            TimelineSemaphoreCreationError::OomError(_) => "not enough memory",
+            TimelineSemaphoreCreationError::FeatureNotEnabled => {
+                "the `timelineSemaphore` feature of the `VK_KHR_timeline_semaphore` extension \
+                 isn't enabled"
+            },
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            TimelineSemaphoreCreationError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TimelineSemaphoreCreationError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<Error> for TimelineSemaphoreCreationError {
+    #[inline]
+    fn from(err: Error) -> TimelineSemaphoreCreationError {
+        match err {
+            err @ Error::OutOfHostMemory => TimelineSemaphoreCreationError::OomError(err.into()),
+            err @ Error::OutOfDeviceMemory => TimelineSemaphoreCreationError::OomError(err.into()),
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+/// Error that can happen when reading, waiting on or signaling a timeline semaphore.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TimelineSemaphoreError {
+    /// Not enough memory.
+    OomError(OomError),
+    /// The specified timeout wasn't long enough.
+    Timeout,
+    /// The device has been lost.
+    DeviceLost,
+}
+
+impl error::Error for TimelineSemaphoreError {
+    #[inline]
+    fn description(&self) -> &str {
+        match *self {
+            TimelineSemaphoreError::OomError(_) => "not enough memory",
+            TimelineSemaphoreError::Timeout => "the timeout has been reached",
+            TimelineSemaphoreError::DeviceLost => "the device was lost",
+        }
+    }
+
+    #[inline]
+    fn cause(&self) -> Option<&error::Error> {
+        match *self {
+            TimelineSemaphoreError::OomError(ref err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for TimelineSemaphoreError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> Result<(), fmt::Error> {
+        write!(fmt, "{}", error::Error::description(self))
+    }
+}
+
+impl From<Error> for TimelineSemaphoreError {
+    #[inline]
+    fn from(err: Error) -> TimelineSemaphoreError {
+        match err {
+            err @ Error::OutOfHostMemory => TimelineSemaphoreError::OomError(err.into()),
+            err @ Error::OutOfDeviceMemory => TimelineSemaphoreError::OomError(err.into()),
+            Error::DeviceLost => TimelineSemaphoreError::DeviceLost,
+            _ => panic!("unexpected error: {:?}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use sync::TimelineSemaphore;
+    use sync::TimelineSemaphoreCreationError;
+
+    #[test]
+    fn feature_not_enabled() {
+        let (device, _) = gfx_dev_and_queue!();
+
+        match TimelineSemaphore::new(device, 0) {
+            Err(TimelineSemaphoreCreationError::FeatureNotEnabled) => (),
+            _ => panic!(),
+        }
+    }
+}